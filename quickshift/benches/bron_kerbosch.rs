@@ -0,0 +1,81 @@
+// Enumeración de cliques de hasta 6 secciones en grafos con la forma de una
+// oferta: varias secciones por ramo (nunca compatibles entre sí) y choques de
+// horario al azar entre ramos. Compara `BronKerbosch` con el DFS sin pivote
+// que usaban los enumeradores de `clique.rs`.
+//
+//   cargo bench --bench bron_kerbosch
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use quickshift::algorithm::bron_kerbosch::{BronKerbosch, Control, Grafo};
+
+const TOPE: usize = 6;
+
+/// `ramos` ramos con `secciones` secciones cada uno; `choques`% de pares de
+/// ramos distintos chocan (LCG determinista)
+fn grafo_oferta(ramos: usize, secciones: usize, choques: u64) -> Vec<Vec<bool>> {
+    let n = ramos * secciones;
+    let mut estado = 0x5eed_u64;
+    let mut adj = vec![vec![false; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            estado = estado.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let compatible = i / secciones != j / secciones && (estado >> 33) % 100 >= choques;
+            adj[i][j] = compatible;
+            adj[j][i] = compatible;
+        }
+    }
+    adj
+}
+
+/// Cliques de exactamente `TOPE` vértices con Bron–Kerbosch
+fn contar_bron_kerbosch(adj: &[Vec<bool>]) -> usize {
+    let grafo = Grafo::nuevo(adj.len(), |_| true, |i, j| adj[i][j]);
+    let mut total = 0;
+    BronKerbosch::new(&grafo, TOPE).enumerar(|nodo| {
+        if nodo.clique.len() == TOPE {
+            total += 1;
+        }
+        Control::Seguir
+    });
+    total
+}
+
+/// Lo mismo con el DFS de combinaciones en orden de índice
+fn contar_dfs(adj: &[Vec<bool>]) -> usize {
+    fn dfs(adj: &[Vec<bool>], inicio: usize, actual: &mut Vec<usize>, total: &mut usize) {
+        if actual.len() == TOPE {
+            *total += 1;
+            return;
+        }
+        for i in inicio..adj.len() {
+            if actual.iter().all(|&u| adj[u][i]) {
+                actual.push(i);
+                dfs(adj, i + 1, actual, total);
+                actual.pop();
+            }
+        }
+    }
+    let mut total = 0;
+    dfs(adj, 0, &mut Vec::new(), &mut total);
+    total
+}
+
+fn bench_enumeracion(c: &mut Criterion) {
+    let mut grupo = c.benchmark_group("cliques_tope_6");
+    grupo.sample_size(10);
+    for (ramos, secciones) in [(10, 3), (14, 3), (18, 2)] {
+        let adj = grafo_oferta(ramos, secciones, 35);
+        assert_eq!(contar_bron_kerbosch(&adj), contar_dfs(&adj));
+        let nombre = format!("{}x{}", ramos, secciones);
+        grupo.bench_with_input(BenchmarkId::new("bron_kerbosch", &nombre), &adj, |b, adj| {
+            b.iter(|| contar_bron_kerbosch(black_box(adj)))
+        });
+        grupo.bench_with_input(BenchmarkId::new("dfs", &nombre), &adj, |b, adj| {
+            b.iter(|| contar_dfs(black_box(adj)))
+        });
+    }
+    grupo.finish();
+}
+
+criterion_group!(benches, bench_enumeracion);
+criterion_main!(benches);
@@ -0,0 +1,71 @@
+// Tiempo del solver sobre ofertas sintéticas de 50, 200 y 500 secciones (ver
+// `algorithm::sintetico`): construcción del grafo de compatibilidad,
+// enumeración de combinaciones (que vuelve a construir su adyacencia sobre
+// las secciones filtradas) y greedy multi-seed, los dos últimos dominados
+// por buscar el ramo de cada sección (`algorithm::indice_ramos`).
+//
+//   cargo bench --bench solver
+//
+// `tests/rendimiento_tests.rs` protege el caso de 200 secciones con un umbral.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use quickshift::algorithm::clique::{get_all_clique_combinations_with_pert, get_clique_max_pond_with_prefs, CompatibilityGraph};
+use quickshift::algorithm::sintetico::oferta_sintetica;
+use quickshift::api_json::InputParams;
+
+const TAMANOS: [usize; 3] = [50, 200, 500];
+const SEMILLA: u64 = 42;
+const MAX_RAMOS: usize = 6;
+const LIMITE_SOLUCIONES: usize = 2_000;
+
+fn params() -> InputParams {
+    serde_json::from_value(serde_json::json!({
+        "email": "bench@x.cl", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "sintetica"
+    })).expect("params de benchmark")
+}
+
+fn bench_adyacencia(c: &mut Criterion) {
+    let mut grupo = c.benchmark_group("adyacencia");
+    for n in TAMANOS {
+        let oferta = oferta_sintetica(n, SEMILLA);
+        grupo.bench_with_input(BenchmarkId::from_parameter(n), &oferta.secciones, |b, secciones| {
+            b.iter(|| CompatibilityGraph::build(black_box(secciones)))
+        });
+    }
+    grupo.finish();
+}
+
+fn bench_enumeracion(c: &mut Criterion) {
+    let params = params();
+    let mut grupo = c.benchmark_group("enumeracion");
+    grupo.sample_size(10);
+    for n in TAMANOS {
+        let oferta = oferta_sintetica(n, SEMILLA);
+        grupo.bench_with_input(BenchmarkId::from_parameter(n), &oferta, |b, oferta| {
+            b.iter(|| get_all_clique_combinations_with_pert(
+                black_box(&oferta.secciones),
+                &oferta.ramos,
+                &params,
+                MAX_RAMOS,
+                LIMITE_SOLUCIONES,
+            ))
+        });
+    }
+    grupo.finish();
+}
+
+fn bench_greedy(c: &mut Criterion) {
+    let params = params();
+    let mut grupo = c.benchmark_group("greedy");
+    grupo.sample_size(10);
+    for n in TAMANOS {
+        let oferta = oferta_sintetica(n, SEMILLA);
+        grupo.bench_with_input(BenchmarkId::from_parameter(n), &oferta, |b, oferta| {
+            b.iter(|| get_clique_max_pond_with_prefs(black_box(&oferta.secciones), &oferta.ramos, &params))
+        });
+    }
+    grupo.finish();
+}
+
+criterion_group!(benches, bench_adyacencia, bench_enumeracion, bench_greedy);
+criterion_main!(benches);
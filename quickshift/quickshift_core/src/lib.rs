@@ -0,0 +1,9 @@
+// Biblioteca del núcleo de `quickshift` para entornos sin servidor (p.ej. un
+// worker wasm32). Reexporta solo lo que compila sin la feature `server`:
+// clique, PERT, conflictos, modelos, lectura de Excel y la resolución en
+// memoria, cuyo punto de entrada es `resolver_json` (JSON -> JSON, mismo
+// formato que `POST /solve`).
+pub use quickshift::{algorithm, api_json, carreras, excel, models, reglas, scoring};
+
+pub use quickshift::algorithm::en_memoria::{resolver_en_memoria, resolver_json, SolveEnMemoria, Soluciones};
+pub use quickshift::api_json::InputParams;
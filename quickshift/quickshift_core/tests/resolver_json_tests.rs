@@ -0,0 +1,23 @@
+use serde_json::json;
+
+#[test]
+fn test_resolver_json_desde_el_nucleo() {
+    let entrada = json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": [],
+        "ramos_prioritarios": [],
+        "malla": "MallaNucleo",
+        "ramos": [
+            {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+            {"id": 2, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1}
+        ],
+        "secciones": [
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["LU 08:30-09:50"], "codigo_box": "CBM1000-1"},
+            {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "1", "horario": ["MA 08:30-09:50"], "codigo_box": "CIT1000-1"}
+        ]
+    });
+    let out = quickshift_core::resolver_json(&entrada.to_string()).expect("resolución en memoria");
+    let soluciones = out["soluciones"].as_array().expect("soluciones");
+    assert!(!soluciones.is_empty());
+    assert_eq!(soluciones[0]["secciones"].as_array().unwrap().len(), 2);
+}
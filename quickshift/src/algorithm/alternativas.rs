@@ -0,0 +1,149 @@
+// alternativas.rs - Secciones de reemplazo para cada ramo de una solución
+//
+// Cada solución fija una sección por ramo; si esa sección se llena el
+// estudiante necesita saber a cuál cambiarse sin rehacer el resto del
+// horario. Para cada ramo de la solución se prueban las demás secciones del
+// mismo código de la oferta (los paquetes cátedra + laboratorio como una
+// unidad, ver `bundles`) y se conservan las que no chocan con el resto de la
+// solución (`conflict::horarios_tienen_conflicto`, como `explicacion`), pasan
+// los filtros por sección del clique y no agregan relajaciones a las que la
+// solución ya tenía (ver `desglose::relajaciones`). Cada alternativa se
+// puntúa como la solución resultante de hacer el cambio, con los mismos pesos
+// y modificadores del clique.
+
+use std::collections::HashMap;
+use serde::Serialize;
+use crate::algorithm::bundles::{self, Paquetes};
+use crate::algorithm::clique::{modificadores_score, seccion_cumple_filtros};
+use crate::algorithm::conflict::horarios_tienen_conflicto;
+use crate::algorithm::desglose::relajaciones;
+use crate::algorithm::prioridad::prioridad_de;
+use crate::api_json::InputParams;
+use crate::models::{RamoDisponible, Seccion};
+
+/// Sección (o paquete) que puede reemplazar a la elegida
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SeccionAlternativa {
+    pub seccion: String,
+    pub profesor: String,
+    /// Horario de todas las partes del paquete
+    pub horario: Vec<String>,
+    /// Si es un paquete, el menor cupo entre sus componentes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cupos_disponibles: Option<u32>,
+    /// Score de la solución con esta sección en vez de la elegida
+    pub total_score: i64,
+    /// `total_score` menos el score de la solución original
+    pub diferencia: i64,
+}
+
+/// Alternativas de un ramo de la solución, de mayor a menor `total_score`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlternativasRamo {
+    pub codigo: String,
+    pub nombre: String,
+    /// Sección elegida en la solución
+    pub seccion: String,
+    pub alternativas: Vec<SeccionAlternativa>,
+}
+
+/// Secciones de la oferta agrupadas como en la búsqueda: cada paquete es una
+/// sola unidad (ver `bundles::colapsar`)
+pub struct Candidatas {
+    unidades: Vec<Seccion>,
+    paquetes: Paquetes,
+}
+
+impl Candidatas {
+    pub fn new(secciones: &[Seccion]) -> Self {
+        let (unidades, paquetes) = bundles::colapsar(secciones);
+        Candidatas { unidades, paquetes }
+    }
+
+    /// Identificador de la unidad de una sección: su paquete o su `codigo_box`
+    fn clave<'a>(&self, s: &'a Seccion) -> &'a str {
+        match &s.bundle_id {
+            Some(id) if self.paquetes.contains_key(id) => id,
+            _ => &s.codigo_box,
+        }
+    }
+
+    /// Secciones reales de una unidad
+    fn miembros<'a>(&'a self, unidad: &'a Seccion) -> &'a [Seccion] {
+        self.paquetes.get(self.clave(unidad))
+            .map(|m| m.as_slice())
+            .unwrap_or(std::slice::from_ref(unidad))
+    }
+}
+
+fn mismo_ramo(a: &Seccion, b: &Seccion) -> bool {
+    a.codigo.trim().eq_ignore_ascii_case(b.codigo.trim())
+}
+
+/// Alternativas para cada ramo de `solucion` (en el orden en que aparecen sus
+/// secciones), dado el `total_score` que le asignó el clique
+pub fn alternativas(
+    solucion: &[(Seccion, i32)],
+    total_score: i64,
+    candidatas: &Candidatas,
+    params: &InputParams,
+    ramos: &HashMap<String, RamoDisponible>,
+) -> Vec<AlternativasRamo> {
+    let scoring = crate::scoring::efectiva(params);
+    let modificadores = modificadores_score(solucion, params, &scoring).total();
+    let relajadas = relajaciones(solucion, params);
+
+    // Unidades de la solución, conservando el orden
+    let mut unidades: Vec<&str> = Vec::new();
+    for (s, _) in solucion {
+        let clave = candidatas.clave(s);
+        if !unidades.contains(&clave) {
+            unidades.push(clave);
+        }
+    }
+
+    unidades.into_iter().map(|clave| {
+        let (propias, resto): (Vec<_>, Vec<_>) = solucion.iter()
+            .partition(|(s, _)| candidatas.clave(s) == clave);
+        let (elegida, score_elegida) = (&propias[0].0, propias[0].1 as i64);
+
+        let mut alternativas: Vec<SeccionAlternativa> = candidatas.unidades.iter()
+            .filter(|u| mismo_ramo(u, elegida) && candidatas.clave(u) != clave)
+            .filter_map(|u| {
+                let miembros = candidatas.miembros(u);
+                let libre = miembros.iter().all(|m| {
+                    seccion_cumple_filtros(m, &params.filtros)
+                        && resto.iter().all(|(r, _)| !horarios_tienen_conflicto(&m.horario, &r.horario))
+                });
+                if !libre {
+                    return None;
+                }
+                let score_unidad = prioridad_de(u, ramos).score(&scoring);
+                let nueva: Vec<(Seccion, i32)> = resto.iter().map(|&x| x.clone())
+                    .chain(miembros.iter().map(|m| (m.clone(), score_unidad as i32)))
+                    .collect();
+                if relajaciones(&nueva, params).iter().any(|r| !relajadas.contains(r)) {
+                    return None;
+                }
+                let total = total_score - score_elegida + score_unidad
+                    - modificadores + modificadores_score(&nueva, params, &scoring).total();
+                Some(SeccionAlternativa {
+                    seccion: u.seccion.clone(),
+                    profesor: u.profesor.clone(),
+                    horario: u.horario.clone(),
+                    cupos_disponibles: u.cupos_disponibles,
+                    total_score: total,
+                    diferencia: total - total_score,
+                })
+            })
+            .collect();
+        alternativas.sort_by(|a, b| b.total_score.cmp(&a.total_score).then_with(|| a.seccion.cmp(&b.seccion)));
+
+        AlternativasRamo {
+            codigo: elegida.codigo.clone(),
+            nombre: elegida.nombre.clone(),
+            seccion: elegida.seccion.clone(),
+            alternativas,
+        }
+    }).collect()
+}
@@ -0,0 +1,36 @@
+// aprobados.rs - Ramos aprobados del estudiante
+//
+// `ramos_pasados` mezcla códigos ("CBM1000") y nombres ("Cálculo I"). Un ramo
+// de la malla cuenta como aprobado si su código (sin espacios, en mayúscula)
+// o su nombre normalizado (`normalize_name`) está en la lista. La comparten
+// planner, bloqueantes, reprobación, explicación, doble titulación y progreso.
+
+use std::collections::HashSet;
+use crate::excel::normalize_name;
+use crate::models::RamoDisponible;
+
+#[derive(Debug, Clone, Default)]
+pub struct Aprobados {
+    codigos: HashSet<String>,
+    nombres: HashSet<String>,
+}
+
+impl Aprobados {
+    pub fn new(ramos_pasados: &[String]) -> Self {
+        Aprobados {
+            codigos: ramos_pasados.iter().map(|s| s.trim().to_uppercase()).collect(),
+            nombres: ramos_pasados.iter().map(|s| normalize_name(s)).collect(),
+        }
+    }
+
+    /// Si `ramo` está aprobado, por código o por nombre
+    pub fn contiene(&self, ramo: &RamoDisponible) -> bool {
+        (!ramo.codigo.trim().is_empty() && self.contiene_codigo(&ramo.codigo))
+            || self.nombres.contains(&normalize_name(&ramo.nombre))
+    }
+
+    /// Si `codigo` figura entre los aprobados (sin mirar nombres)
+    pub fn contiene_codigo(&self, codigo: &str) -> bool {
+        self.codigos.contains(&codigo.trim().to_uppercase())
+    }
+}
@@ -0,0 +1,71 @@
+// balance_lineas.rs - Balance de electivos por línea de formación
+//
+// `BalanceLineas::lineas` pide una proporción por línea (p. ej. 60%
+// informática, 40% telecomunicaciones). El filtro de `clique.rs` ya excluye
+// los electivos de líneas sin peso; aquí se mide qué tan cerca queda cada
+// solución de las proporciones pedidas, contando solo las secciones con
+// línea conocida (`Seccion::linea`). La desviación es la distancia de
+// variación total entre ambas distribuciones: 0 = balance exacto, 1 = nada
+// en común. `clique::apply_optimization_modifiers` la usa para penalizar y
+// la respuesta de `/solve` la reporta por solución.
+
+use std::collections::BTreeMap;
+use serde::Serialize;
+use crate::api_json::InputParams;
+use crate::excel::electivos::normalizar_linea;
+use crate::models::{BalanceLineas, Seccion};
+
+/// Balance alcanzado por una solución
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BalanceLogrado {
+    /// Proporción de secciones con línea que cae en cada línea (suma 1)
+    pub lineas: BTreeMap<String, f64>,
+    /// Proporción pedida, normalizada para sumar 1
+    pub objetivo: BTreeMap<String, f64>,
+    /// Distancia de variación total entre `lineas` y `objetivo`, en [0, 1]
+    pub desviacion: f64,
+}
+
+/// `filtros.balance_lineas` si está habilitado y pide al menos una línea con peso
+pub fn balance_pedido(params: &InputParams) -> Option<&BalanceLineas> {
+    params.filtros.as_ref()
+        .and_then(|f| f.balance_lineas.as_ref())
+        .filter(|b| b.habilitado && !objetivo(b).is_empty())
+}
+
+/// Pesos positivos de `balance.lineas` con claves normalizadas, escalados a suma 1
+fn objetivo(balance: &BalanceLineas) -> BTreeMap<String, f64> {
+    let mut pesos: BTreeMap<String, f64> = BTreeMap::new();
+    for (linea, peso) in balance.lineas.iter().flatten() {
+        if *peso > 0.0 {
+            *pesos.entry(normalizar_linea(linea)).or_insert(0.0) += peso;
+        }
+    }
+    let total: f64 = pesos.values().sum();
+    pesos.values_mut().for_each(|p| *p /= total);
+    pesos
+}
+
+/// Balance de las secciones de una solución. None si ninguna tiene línea
+/// (no hay nada que comparar con el objetivo).
+pub fn balance_logrado<'a>(secciones: impl IntoIterator<Item = &'a Seccion>, balance: &BalanceLineas) -> Option<BalanceLogrado> {
+    let mut conteo: BTreeMap<String, usize> = BTreeMap::new();
+    for linea in secciones.into_iter().filter(|s| !s.is_cfg).filter_map(|s| s.linea.as_ref()) {
+        *conteo.entry(linea.clone()).or_insert(0) += 1;
+    }
+    let total: usize = conteo.values().sum();
+    if total == 0 {
+        return None;
+    }
+
+    let lineas: BTreeMap<String, f64> = conteo.into_iter()
+        .map(|(l, n)| (l, n as f64 / total as f64))
+        .collect();
+    let objetivo = objetivo(balance);
+    let desviacion = lineas.keys().chain(objetivo.keys())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .map(|l| (lineas.get(l).unwrap_or(&0.0) - objetivo.get(l).unwrap_or(&0.0)).abs())
+        .sum::<f64>() / 2.0;
+    Some(BalanceLogrado { lineas, objetivo, desviacion })
+}
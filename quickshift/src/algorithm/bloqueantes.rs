@@ -0,0 +1,120 @@
+// bloqueantes.rs - Ramos "cuello de botella" para la titulación
+//
+// Sobre el grafo de prerequisitos de los ramos AÚN NO aprobados:
+// - `cadena(v)`: largo (en ramos) de la cadena de prerequisitos más larga que
+//   empieza en `v`. Es la cantidad mínima de semestres que faltan si se toma `v` ya.
+// - Un ramo es BLOQUEANTE si hoy se puede tomar (sus prerequisitos están
+//   aprobados) y además inicia una cadena de largo máximo: postergarlo retrasa
+//   la titulación. Es el conjunto mínimo de ramos a tomar para no atrasarse.
+//   Si la cadena más larga tiene un solo ramo, ninguno es bloqueante.
+// - `bloquea`: cantidad de ramos pendientes que dependen (transitivamente) de él.
+
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use crate::algorithm::aprobados::Aprobados;
+use crate::models::RamoDisponible;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Bloqueante {
+    pub id: i32,
+    pub codigo: String,
+    pub nombre: String,
+    pub semestre: Option<i32>,
+    /// Largo de la cadena de prerequisitos más larga que inicia en este ramo
+    pub cadena: usize,
+    /// Ramos pendientes que dependen (directa o indirectamente) de este
+    pub bloquea: usize,
+}
+
+/// Resultado del análisis: largo de la cadena crítica y sus ramos bloqueantes
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalisisBloqueantes {
+    pub ramos_pendientes: usize,
+    /// Semestres mínimos para terminar la cadena más larga
+    pub cadena_maxima: usize,
+    pub bloqueantes: Vec<Bloqueante>,
+}
+
+/// Largo de la cadena más larga desde `v` (memoizado; un ciclo corta la cadena)
+fn largo_cadena(v: i32, sucesores: &HashMap<i32, Vec<i32>>, memo: &mut HashMap<i32, usize>, en_curso: &mut HashSet<i32>) -> usize {
+    if let Some(&l) = memo.get(&v) {
+        return l;
+    }
+    if !en_curso.insert(v) {
+        return 0;
+    }
+    let mut mejor = 0;
+    if let Some(sig) = sucesores.get(&v) {
+        for &s in sig {
+            mejor = mejor.max(largo_cadena(s, sucesores, memo, en_curso));
+        }
+    }
+    en_curso.remove(&v);
+    memo.insert(v, mejor + 1);
+    mejor + 1
+}
+
+/// Calcula los ramos bloqueantes de un estudiante dada su malla y sus ramos aprobados
+pub fn calcular_bloqueantes(ramos: &HashMap<String, RamoDisponible>, ramos_pasados: &[String]) -> AnalisisBloqueantes {
+    let aprobados = Aprobados::new(ramos_pasados);
+
+    let pendientes: HashMap<i32, &RamoDisponible> = ramos.values()
+        .filter(|r| !aprobados.contiene(r))
+        .map(|r| (r.id, r))
+        .collect();
+
+    // Aristas prerequisito -> ramo, solo entre pendientes
+    let mut sucesores: HashMap<i32, Vec<i32>> = HashMap::new();
+    let mut tiene_prereq_pendiente: HashSet<i32> = HashSet::new();
+    for r in pendientes.values() {
+        for req in r.requisitos_ids.iter() {
+            if *req != r.id && pendientes.contains_key(req) {
+                sucesores.entry(*req).or_default().push(r.id);
+                tiene_prereq_pendiente.insert(r.id);
+            }
+        }
+    }
+
+    let mut memo: HashMap<i32, usize> = HashMap::new();
+    let mut en_curso: HashSet<i32> = HashSet::new();
+    let mut ids: Vec<i32> = pendientes.keys().copied().collect();
+    ids.sort_unstable();
+    for &id in ids.iter() {
+        largo_cadena(id, &sucesores, &mut memo, &mut en_curso);
+    }
+    let cadena_maxima = memo.values().copied().max().unwrap_or(0);
+
+    let mut bloqueantes: Vec<Bloqueante> = ids.iter()
+        .filter(|id| !tiene_prereq_pendiente.contains(id))
+        .filter(|id| memo.get(id).copied().unwrap_or(0) == cadena_maxima && cadena_maxima > 1)
+        .filter_map(|id| pendientes.get(id).map(|r| (*id, *r)))
+        .map(|(id, r)| {
+            // Descendientes pendientes
+            let mut vistos: HashSet<i32> = HashSet::new();
+            let mut pila: Vec<i32> = sucesores.get(&id).cloned().unwrap_or_default();
+            while let Some(v) = pila.pop() {
+                if !vistos.insert(v) {
+                    continue;
+                }
+                if let Some(sig) = sucesores.get(&v) {
+                    pila.extend(sig.iter().copied());
+                }
+            }
+            Bloqueante {
+                id,
+                codigo: r.codigo.clone(),
+                nombre: r.nombre.clone(),
+                semestre: r.semestre,
+                cadena: cadena_maxima,
+                bloquea: vistos.len(),
+            }
+        })
+        .collect();
+    bloqueantes.sort_by(|a, b| b.bloquea.cmp(&a.bloquea).then(a.id.cmp(&b.id)));
+
+    AnalisisBloqueantes {
+        ramos_pendientes: pendientes.len(),
+        cadena_maxima,
+        bloqueantes,
+    }
+}
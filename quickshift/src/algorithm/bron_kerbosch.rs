@@ -0,0 +1,248 @@
+// bron_kerbosch.rs - Enumeración de cliques con Bron–Kerbosch
+//
+// Los enumeradores de `clique.rs` describen el problema como un `Grafo`
+// (vértices activos + aristas de compatibilidad) y reciben cada clique a
+// través de un visitante. La búsqueda es Bron–Kerbosch con:
+// - Orden de degeneración en la raíz (Eppstein): cada vértice parte con los
+//   vecinos que le siguen en ese orden como candidatos (P) y los anteriores
+//   como excluidos (X), así cada clique se genera desde un único vértice.
+// - Pivote (Tomita): se elige el vértice de P ∪ X con más vecinos en P y solo
+//   se ramifica por los candidatos que no son sus vecinos. Con tope de tamaño
+//   el pivote solo se usa cuando ninguna clique de la rama llega al tope
+//   (|R| + |P| < tope); si no, se ramifica por todo P para no perder las
+//   cliques del tamaño del tope que caben dentro de una clique mayor.
+//
+// El visitante ve cada nodo del árbol de búsqueda (la clique R en
+// construcción, no solo las maximales) y decide si seguir, podar esa rama o
+// detener todo. Los candidatos se recorren en orden de índice: numerar los
+// vértices por prioridad hace que las ramas más prometedoras salgan primero.
+
+/// Conjunto de vértices como bitset
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Conjunto(Vec<u64>);
+
+impl Conjunto {
+    fn vacio(n: usize) -> Self {
+        Conjunto(vec![0; n.div_ceil(64)])
+    }
+
+    fn insertar(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    fn quitar(&mut self, i: usize) {
+        self.0[i / 64] &= !(1 << (i % 64));
+    }
+
+    fn contiene(&self, i: usize) -> bool {
+        self.0[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn es_vacio(&self) -> bool {
+        self.0.iter().all(|w| *w == 0)
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn interseccion(&self, otro: &Conjunto) -> Conjunto {
+        Conjunto(self.0.iter().zip(&otro.0).map(|(a, b)| a & b).collect())
+    }
+
+    fn tamano_interseccion(&self, otro: &Conjunto) -> usize {
+        self.0.iter().zip(&otro.0).map(|(a, b)| (a & b).count_ones() as usize).sum()
+    }
+
+    /// Elementos en orden creciente
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(k, &w)| {
+            let mut resto = w;
+            std::iter::from_fn(move || {
+                if resto == 0 {
+                    return None;
+                }
+                let bit = resto.trailing_zeros() as usize;
+                resto &= resto - 1;
+                Some(k * 64 + bit)
+            })
+        })
+    }
+}
+
+/// Grafo no dirigido sobre los vértices `0..n`; los inactivos no tienen
+/// aristas ni inician ramas
+#[derive(Debug, Clone)]
+pub struct Grafo {
+    vecinos: Vec<Conjunto>,
+    activos: Vec<bool>,
+}
+
+impl Grafo {
+    /// Grafo con `arista(i, j)` (se consulta una vez por par i < j de vértices activos)
+    pub fn nuevo(n: usize, activo: impl Fn(usize) -> bool, arista: impl Fn(usize, usize) -> bool) -> Self {
+        let activos: Vec<bool> = (0..n).map(activo).collect();
+        let mut vecinos = vec![Conjunto::vacio(n); n];
+        for i in (0..n).filter(|&i| activos[i]) {
+            for j in ((i + 1)..n).filter(|&j| activos[j]) {
+                if arista(i, j) {
+                    vecinos[i].insertar(j);
+                    vecinos[j].insertar(i);
+                }
+            }
+        }
+        Grafo { vecinos, activos }
+    }
+
+    pub fn len(&self) -> usize {
+        self.activos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.activos.is_empty()
+    }
+
+    pub fn activo(&self, v: usize) -> bool {
+        self.activos[v]
+    }
+
+    pub fn adyacentes(&self, a: usize, b: usize) -> bool {
+        self.vecinos[a].contiene(b)
+    }
+
+    pub fn aristas(&self) -> usize {
+        self.vecinos.iter().map(Conjunto::len).sum::<usize>() / 2
+    }
+
+    /// Vértices activos en orden de degeneración: se saca siempre el de menor
+    /// grado entre los que quedan (a igual grado, el de menor índice)
+    pub fn orden_degeneracion(&self) -> Vec<usize> {
+        let n = self.len();
+        let mut grado: Vec<usize> = self.vecinos.iter().map(Conjunto::len).collect();
+        let mut quedan: Vec<bool> = self.activos.clone();
+        let mut orden = Vec::with_capacity(n);
+        while let Some(v) = (0..n).filter(|&v| quedan[v]).min_by_key(|&v| (grado[v], v)) {
+            quedan[v] = false;
+            orden.push(v);
+            for u in self.vecinos[v].iter() {
+                grado[u] -= 1;
+            }
+        }
+        orden
+    }
+}
+
+/// Qué hacer tras visitar una clique
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Seguir extendiendo la clique
+    Seguir,
+    /// No extender esta clique (el resto de la búsqueda sigue)
+    Podar,
+    /// Terminar la enumeración
+    Parar,
+}
+
+/// Nodo del árbol de búsqueda que recibe el visitante
+#[derive(Debug, Clone, Copy)]
+pub struct Nodo<'a> {
+    /// Vértices de la clique, en el orden en que se agregaron
+    pub clique: &'a [usize],
+    /// Ningún vértice del grafo la extiende
+    pub maximal: bool,
+}
+
+/// Enumerador sobre un grafo, con tope de tamaño de clique
+pub struct BronKerbosch<'g> {
+    grafo: &'g Grafo,
+    /// Posición de cada vértice en el orden de degeneración
+    posicion: Vec<usize>,
+    orden: Vec<usize>,
+    max_size: usize,
+}
+
+impl<'g> BronKerbosch<'g> {
+    pub fn new(grafo: &'g Grafo, max_size: usize) -> Self {
+        let orden = grafo.orden_degeneracion();
+        let mut posicion = vec![usize::MAX; grafo.len()];
+        for (pos, &v) in orden.iter().enumerate() {
+            posicion[v] = pos;
+        }
+        BronKerbosch { grafo, posicion, orden, max_size }
+    }
+
+    /// Recorre todas las ramas, en orden de degeneración
+    pub fn enumerar<F>(&self, mut visitante: F) -> Control
+    where
+        F: FnMut(Nodo) -> Control,
+    {
+        for &raiz in &self.orden {
+            if self.enumerar_desde(raiz, &mut visitante) == Control::Parar {
+                return Control::Parar;
+            }
+        }
+        Control::Seguir
+    }
+
+    /// Solo la rama de las cliques cuyo primer vértice (en orden de
+    /// degeneración) es `raiz`; las ramas de distintas raíces son independientes
+    pub fn enumerar_desde<F>(&self, raiz: usize, mut visitante: F) -> Control
+    where
+        F: FnMut(Nodo) -> Control,
+    {
+        if self.max_size == 0 || !self.grafo.activo(raiz) {
+            return Control::Seguir;
+        }
+        let n = self.grafo.len();
+        let mut p = Conjunto::vacio(n);
+        let mut x = Conjunto::vacio(n);
+        for u in self.grafo.vecinos[raiz].iter() {
+            if self.posicion[u] > self.posicion[raiz] {
+                p.insertar(u);
+            } else {
+                x.insertar(u);
+            }
+        }
+        let mut r = vec![raiz];
+        self.expandir(&mut r, p, x, &mut visitante)
+    }
+
+    fn expandir<F>(&self, r: &mut Vec<usize>, mut p: Conjunto, mut x: Conjunto, visitante: &mut F) -> Control
+    where
+        F: FnMut(Nodo) -> Control,
+    {
+        let maximal = p.es_vacio() && x.es_vacio();
+        match visitante(Nodo { clique: r, maximal }) {
+            Control::Parar => return Control::Parar,
+            Control::Podar => return Control::Seguir,
+            Control::Seguir => {}
+        }
+        if p.es_vacio() || r.len() >= self.max_size {
+            return Control::Seguir;
+        }
+
+        let ramas: Vec<usize> = if r.len() + p.len() < self.max_size {
+            let pivote = p.iter().chain(x.iter())
+                .max_by_key(|&u| (p.tamano_interseccion(&self.grafo.vecinos[u]), std::cmp::Reverse(u)));
+            match pivote {
+                Some(u) => p.iter().filter(|&v| !self.grafo.vecinos[u].contiene(v)).collect(),
+                None => p.iter().collect(),
+            }
+        } else {
+            p.iter().collect()
+        };
+
+        for v in ramas {
+            let vecinos = &self.grafo.vecinos[v];
+            r.push(v);
+            let control = self.expandir(r, p.interseccion(vecinos), x.interseccion(vecinos), visitante);
+            r.pop();
+            if control == Control::Parar {
+                return Control::Parar;
+            }
+            p.quitar(v);
+            x.insertar(v);
+        }
+        Control::Seguir
+    }
+}
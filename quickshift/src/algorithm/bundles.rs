@@ -0,0 +1,117 @@
+// bundles.rs - Paquetes cátedra + laboratorio/ayudantía como unidad atómica
+//
+// Las secciones con el mismo `bundle_id` (ver `excel::componentes`) se
+// inscriben juntas. Antes de la búsqueda de cliques `colapsar` reemplaza cada
+// paquete por una sección compuesta (la cátedra con los horarios de todos sus
+// miembros y `codigo_box` = `bundle_id`), así el paquete ocupa un solo vértice
+// del grafo, cuenta como un ramo y choca con lo que choque cualquiera de sus
+// partes. `expandir` devuelve las secciones originales en cada solución.
+
+use std::collections::{HashMap, HashSet};
+use crate::models::horario::bloques_de_seccion;
+use crate::models::{Componente, Seccion};
+
+/// Miembros de cada paquete colapsado, por `bundle_id`
+pub type Paquetes = HashMap<String, Vec<Seccion>>;
+
+/// true si dos miembros del paquete tienen bloques que se solapan
+fn tiene_conflicto_interno(miembros: &[Seccion]) -> bool {
+    let bloques: Vec<_> = miembros.iter().map(bloques_de_seccion).collect();
+    bloques.iter().enumerate().any(|(i, a)| {
+        bloques[i + 1..].iter().any(|b| a.iter().any(|x| b.iter().any(|y| x.solapa(y))))
+    })
+}
+
+/// Sección compuesta que representa al paquete en la búsqueda
+fn seccion_compuesta(id: &str, miembros: &[Seccion]) -> Seccion {
+    let base = miembros.iter().find(|s| s.componente == Componente::Catedra).unwrap_or(&miembros[0]);
+    let mut horario: Vec<String> = Vec::new();
+    for h in miembros.iter().flat_map(|s| s.horario.iter()) {
+        if !horario.contains(h) {
+            horario.push(h.clone());
+        }
+    }
+    Seccion {
+        horario,
+        codigo_box: id.to_string(),
+        componente: Componente::Catedra,
+        bundle_id: Some(id.to_string()),
+        // el paquete tiene cupo solo si lo tienen todas sus partes
+        cupos_disponibles: miembros.iter().filter_map(|s| s.cupos_disponibles).min(),
+        evaluaciones: Vec::new(),
+        ..base.clone()
+    }
+}
+
+/// Reemplaza cada paquete por su sección compuesta. Los paquetes con un
+/// solo miembro presente quedan como sección suelta; los que tienen choques
+/// internos de horario se descartan porque no pueden inscribirse.
+pub fn colapsar(secciones: &[Seccion]) -> (Vec<Seccion>, Paquetes) {
+    let mut paquetes: Paquetes = HashMap::new();
+    for s in secciones {
+        if let Some(id) = &s.bundle_id {
+            paquetes.entry(id.clone()).or_default().push(s.clone());
+        }
+    }
+    paquetes.retain(|_, miembros| miembros.len() > 1);
+    let descartados: HashSet<String> = paquetes.iter()
+        .filter(|(_, miembros)| tiene_conflicto_interno(miembros))
+        .map(|(id, _)| id.clone())
+        .collect();
+    for id in &descartados {
+        tracing::debug!("paquete {} descartado: sus componentes chocan entre sí", id);
+        paquetes.remove(id);
+    }
+
+    let mut out = Vec::with_capacity(secciones.len());
+    let mut emitidos: HashSet<&str> = HashSet::new();
+    for s in secciones {
+        match &s.bundle_id {
+            Some(id) if paquetes.contains_key(id) => {
+                if emitidos.insert(id.as_str()) {
+                    out.push(seccion_compuesta(id, &paquetes[id]));
+                }
+            }
+            Some(id) if descartados.contains(id) => {}
+            _ => out.push(s.clone()),
+        }
+    }
+    (out, paquetes)
+}
+
+/// Reemplaza las secciones compuestas de cada solución por los miembros de
+/// su paquete; cada miembro conserva el score de la unidad.
+pub fn expandir(soluciones: Vec<(Vec<(Seccion, i32)>, i64)>, paquetes: &Paquetes) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    if paquetes.is_empty() {
+        return soluciones;
+    }
+    soluciones
+        .into_iter()
+        .map(|(sol, total)| {
+            let sol = sol
+                .into_iter()
+                .flat_map(|(s, score)| {
+                    let miembros = s.bundle_id.as_ref()
+                        .filter(|id| **id == s.codigo_box)
+                        .and_then(|id| paquetes.get(id));
+                    match miembros {
+                        Some(m) => m.iter().map(|x| (x.clone(), score)).collect::<Vec<_>>(),
+                        None => vec![(s, score)],
+                    }
+                })
+                .collect();
+            (sol, total)
+        })
+        .collect()
+}
+
+/// Cantidad de unidades inscritas en una solución: cada paquete cuenta una vez
+pub fn unidades(sol: &[(Seccion, i32)]) -> usize {
+    let mut paquetes: HashSet<&str> = HashSet::new();
+    sol.iter()
+        .filter(|(s, _)| match &s.bundle_id {
+            Some(id) => paquetes.insert(id.as_str()),
+            None => true,
+        })
+        .count()
+}
@@ -0,0 +1,162 @@
+// carga_diaria.rs - Tope de ramos por día y rango horario (`filtros`)
+//
+// Filtros estrictos de `UserFilters`:
+//   - `max_ramos_por_dia`: ramos distintos con clases en un mismo día (un
+//     paquete cátedra + laboratorio cuenta como uno);
+//   - `hora_inicio_minima` / `hora_fin_maxima` ("HH:MM"): ninguna clase de la
+//     solución empieza antes ni termina después.
+// El rango horario se decide sección por sección: `clique::seccion_cumple_filtros`
+// descarta las que se salen y nunca entran al grafo. El tope por día depende
+// de la solución completa: con tope 1 dos secciones que comparten día no son
+// adyacentes, y con topes mayores los enumeradores no agregan un candidato
+// que lo supere (`excede_ramos_por_dia`). Si con ellos no queda ninguna
+// solución, `ruta` devuelve `FiltrosInviables` en vez de una lista vacía.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use serde::Serialize;
+use crate::models::horario::{bloques_de_seccion, parse_hora};
+use crate::models::{Seccion, UserFilters};
+
+/// Minutos desde medianoche del fin del día (sin `hora_fin_maxima`)
+const FIN_DEL_DIA: u32 = 24 * 60;
+
+/// Rechaza valores que no se pueden cumplir ni interpretar
+pub fn validar(filtros: &UserFilters) -> Result<(), String> {
+    if filtros.max_ramos_por_dia == Some(0) {
+        return Err("max_ramos_por_dia debe ser mayor que 0".to_string());
+    }
+    let hora = |campo: &str, valor: &Option<String>| -> Result<Option<u32>, String> {
+        valor.as_deref()
+            .map(|h| parse_hora(h).ok_or_else(|| format!("{} inválida: '{}'", campo, h)))
+            .transpose()
+    };
+    let inicio = hora("hora_inicio_minima", &filtros.hora_inicio_minima)?;
+    let fin = hora("hora_fin_maxima", &filtros.hora_fin_maxima)?;
+    if let (Some(i), Some(f)) = (inicio, fin)
+        && i >= f
+    {
+        return Err("hora_inicio_minima debe ser anterior a hora_fin_maxima".to_string());
+    }
+    Ok(())
+}
+
+/// Rango permitido en minutos desde medianoche, si se pidió alguno de sus extremos
+pub fn rango_horario(filtros: &Option<UserFilters>) -> Option<(u32, u32)> {
+    let f = filtros.as_ref()?;
+    if f.hora_inicio_minima.is_none() && f.hora_fin_maxima.is_none() {
+        return None;
+    }
+    let inicio = f.hora_inicio_minima.as_deref().and_then(parse_hora).unwrap_or(0);
+    let fin = f.hora_fin_maxima.as_deref().and_then(parse_hora).unwrap_or(FIN_DEL_DIA);
+    Some((inicio, fin))
+}
+
+/// `max_ramos_por_dia` de los filtros
+pub fn tope_por_dia(filtros: &Option<UserFilters>) -> Option<usize> {
+    filtros.as_ref().and_then(|f| f.max_ramos_por_dia)
+}
+
+/// true si alguna clase de la sección empieza antes o termina después del rango
+pub fn fuera_de_rango(seccion: &Seccion, (inicio, fin): (u32, u32)) -> bool {
+    bloques_de_seccion(seccion).iter().any(|b| b.inicio < inicio || b.fin > fin)
+}
+
+/// Ramo de la sección para el tope diario: su paquete, o su código
+fn unidad(s: &Seccion) -> String {
+    s.bundle_id.clone().unwrap_or_else(|| s.codigo.trim().to_uppercase())
+}
+
+/// Ramos distintos con clases cada día (índice de `Dia` -> cantidad)
+pub fn ramos_por_dia<'a>(secciones: impl IntoIterator<Item = &'a Seccion>) -> BTreeMap<u32, usize> {
+    let mut por_dia: BTreeMap<u32, HashSet<String>> = BTreeMap::new();
+    for s in secciones {
+        for b in bloques_de_seccion(s) {
+            por_dia.entry(b.dia.indice()).or_default().insert(unidad(s));
+        }
+    }
+    por_dia.into_iter().map(|(dia, ramos)| (dia, ramos.len())).collect()
+}
+
+/// true si algún día tiene más de `tope` ramos
+pub fn excede_ramos_por_dia<'a>(secciones: impl IntoIterator<Item = &'a Seccion>, tope: usize) -> bool {
+    ramos_por_dia(secciones).values().any(|&n| n > tope)
+}
+
+/// true si la solución respeta el tope diario y el rango horario de `filtros`
+pub fn cumple(solucion: &[(Seccion, i32)], filtros: &Option<UserFilters>) -> bool {
+    let secciones = || solucion.iter().map(|(s, _)| s);
+    tope_por_dia(filtros).is_none_or(|tope| !excede_ramos_por_dia(secciones(), tope))
+        && rango_horario(filtros).is_none_or(|rango| !secciones().any(|s| fuera_de_rango(s, rango)))
+}
+
+/// true si `a` y `b` son ramos distintos con clases el mismo día (con tope 1
+/// no pueden ir juntos)
+pub fn comparten_dia(a: &Seccion, b: &Seccion) -> bool {
+    if unidad(a) == unidad(b) {
+        return false;
+    }
+    let dias_a: HashSet<u32> = bloques_de_seccion(a).iter().map(|x| x.dia.indice()).collect();
+    bloques_de_seccion(b).iter().any(|x| dias_a.contains(&x.dia.indice()))
+}
+
+/// Reporte cuando el tope diario o el rango horario dejan sin soluciones
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FiltrosInviables {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_ramos_por_dia: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hora_inicio_minima: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hora_fin_maxima: Option<String>,
+    /// Secciones candidatas con alguna clase fuera del rango horario
+    pub secciones_fuera_de_rango: usize,
+    /// Ramos con todas sus secciones fuera del rango horario
+    pub ramos_sin_seccion_en_rango: Vec<String>,
+}
+
+impl fmt::Display for FiltrosInviables {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut partes: Vec<String> = Vec::new();
+        if let Some(tope) = self.max_ramos_por_dia {
+            partes.push(format!("máximo {} ramos por día", tope));
+        }
+        if self.hora_inicio_minima.is_some() || self.hora_fin_maxima.is_some() {
+            partes.push(format!(
+                "clases entre {} y {} ({} secciones fuera de rango)",
+                self.hora_inicio_minima.as_deref().unwrap_or("00:00"),
+                self.hora_fin_maxima.as_deref().unwrap_or("24:00"),
+                self.secciones_fuera_de_rango,
+            ));
+        }
+        write!(f, "ninguna solución cumple {}", partes.join(" y "))
+    }
+}
+
+impl std::error::Error for FiltrosInviables {}
+
+/// Qué excluyen el tope diario y el rango horario entre `secciones`
+/// (candidatas del solve); None si ninguno de los dos está pedido
+pub fn diagnosticar(secciones: &[Seccion], filtros: &Option<UserFilters>) -> Option<FiltrosInviables> {
+    let tope = tope_por_dia(filtros);
+    let rango = rango_horario(filtros);
+    if tope.is_none() && rango.is_none() {
+        return None;
+    }
+    let mut fuera = 0;
+    let mut en_rango: BTreeMap<String, bool> = BTreeMap::new();
+    for s in secciones {
+        let dentro = rango.is_none_or(|r| !fuera_de_rango(s, r));
+        if !dentro {
+            fuera += 1;
+        }
+        *en_rango.entry(s.codigo.trim().to_uppercase()).or_default() |= dentro;
+    }
+    Some(FiltrosInviables {
+        max_ramos_por_dia: tope,
+        hora_inicio_minima: filtros.as_ref().and_then(|f| f.hora_inicio_minima.clone()),
+        hora_fin_maxima: filtros.as_ref().and_then(|f| f.hora_fin_maxima.clone()),
+        secciones_fuera_de_rango: fuera,
+        ramos_sin_seccion_en_rango: en_rango.into_iter().filter(|(_, dentro)| !dentro).map(|(c, _)| c).collect(),
+    })
+}
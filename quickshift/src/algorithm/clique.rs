@@ -1,73 +1,26 @@
 /// clique.rs - Planificador minimalista: PERT + Cliques + Restricciones integradas
 use std::collections::{HashMap, HashSet};
-use petgraph::graph::{NodeIndex, UnGraph};
-use crate::models::{Seccion, RamoDisponible};
+use petgraph::graph::UnGraph;
+use crate::models::{Seccion, RamoDisponible, ModoPreferencia};
 use crate::excel::normalize_name;
-use crate::api_json::InputParams;
-
-/// Extrae hora en minutos desde inicio del día de un string "HH:MM"
-fn parse_time_to_minutes(time_str: &str) -> Option<i32> {
-    let parts: Vec<&str> = time_str.split(':').collect();
-    if parts.len() != 2 { return None; }
-    let hours = parts[0].trim().parse::<i32>().ok()?;
-    let minutes = parts[1].trim().parse::<i32>().ok()?;
-    Some(hours * 60 + minutes)
-}
-
-/// Extrae el rango de horas de un string como "LU MI 08:30 - 10:00" o "08:30-10:00"
-fn parse_horario_range(horario: &str) -> Option<(i32, i32)> {
-    // Normalizar guiones (reemplazar múltiples tipos de dash por "-")
-    let normalized = horario
-        .replace("–", "-") // en-dash
-        .replace("—", "-") // em-dash
-        .replace("−", "-") // minus sign
-        .replace("‐", "-"); // hyphen
-    
-    // Buscar el patrón HH:MM-HH:MM o HH:MM - HH:MM
-    // Primero encontramos las partes que contienen ":"
-    let tokens: Vec<&str> = normalized.split_whitespace().collect();
-    
-    let mut start_time: Option<&str> = None;
-    let mut end_time: Option<&str> = None;
-    
-    for (i, token) in tokens.iter().enumerate() {
-        if token.contains(':') {
-            // Este token tiene un tiempo
-            if token.contains('-') {
-                // Formato "08:30-10:00" todo junto
-                let time_parts: Vec<&str> = token.split('-').collect();
-                if time_parts.len() >= 2 {
-                    start_time = Some(time_parts[0]);
-                    end_time = Some(time_parts[1]);
-                }
-            } else if start_time.is_none() {
-                start_time = Some(token);
-            } else if end_time.is_none() {
-                end_time = Some(token);
-            }
-        }
-    }
-    
-    let start = parse_time_to_minutes(start_time?)?;
-    let end = parse_time_to_minutes(end_time?)?;
-    
-    Some((start, end))
-}
-
-/// Extrae day symbols (LU, MA, MI, JU, VI) de un horario como "LU MA MI 08:30 - 10:00"
-fn extract_days_from_horario(horario: &str) -> Vec<String> {
-    let parts: Vec<&str> = horario.split_whitespace().collect();
-    let mut days = Vec::new();
-    
-    for part in parts {
-        let upper = part.to_uppercase();
-        if matches!(upper.as_str(), "LU" | "MA" | "MI" | "JU" | "VI") {
-            days.push(upper);
-        }
-    }
-    
-    days
-}
+use crate::api_json::{InputParams, SolverMode};
+use crate::algorithm::paralelo::{clave_solucion, enumerar_por_semilla, ordenar_canonico};
+use crate::algorithm::bron_kerbosch::{BronKerbosch, Control, Grafo};
+use crate::algorithm::diversidad::clave_desempate;
+use crate::algorithm::deadline::Deadline;
+use crate::algorithm::indice_ramos::RamoIndex;
+use crate::algorithm::balance_lineas;
+use crate::algorithm::traslado;
+use crate::algorithm::compromisos;
+use crate::algorithm::evaluaciones;
+use crate::algorithm::ventanas;
+use crate::algorithm::carga_diaria;
+use crate::algorithm::obligatorios;
+use crate::algorithm::tags;
+use crate::algorithm::prioridad::Priority;
+use crate::models::horario::{self, Dia};
+use crate::scoring::ScoringConfig;
+use serde::Serialize;
 
 /// Calcula el "compactness score" de una solución (0-100).
 /// 
@@ -76,22 +29,17 @@ fn extract_days_from_horario(horario: &str) -> Vec<String> {
 /// - Dentro de cada día, la duración (último horario - primer horario) es ≤ 5 horas
 ///
 /// compactness_score = (compact_days / total_days_with_class) * 100
-fn calculate_compactness_score(solution: &[(Seccion, i32)]) -> f64 {
+pub fn calculate_compactness_score(solution: &[(Seccion, i32)]) -> f64 {
     if solution.is_empty() { return 0.0; }
     
     // Mapear día a (start_min, end_min)
-    let mut day_ranges: HashMap<String, (i32, i32)> = HashMap::new();
+    let mut day_ranges: HashMap<Dia, (i32, i32)> = HashMap::new();
     
     for (seccion, _) in solution {
-        for horario in &seccion.horario {
-            let days = extract_days_from_horario(horario);
-            if let Some((start, end)) = parse_horario_range(horario) {
-                for day in days {
-                    let entry = day_ranges.entry(day).or_insert((i32::MAX, 0));
-                    entry.0 = entry.0.min(start);
-                    entry.1 = entry.1.max(end);
-                }
-            }
+        for b in seccion.horario.iter().flat_map(|h| horario::parse(h)) {
+            let entry = day_ranges.entry(b.dia).or_insert((i32::MAX, 0));
+            entry.0 = entry.0.min(b.inicio as i32);
+            entry.1 = entry.1.max(b.fin as i32);
         }
     }
     
@@ -111,22 +59,17 @@ fn calculate_compactness_score(solution: &[(Seccion, i32)]) -> f64 {
 /// Para cada día:
 /// - Ordena horarios por hora inicio
 /// - Suma los gaps entre horarios consecutivos
-fn calculate_total_gaps(solution: &[(Seccion, i32)]) -> i32 {
+pub fn calculate_total_gaps(solution: &[(Seccion, i32)]) -> i32 {
     if solution.is_empty() { return 0; }
     
     // Mapear día a lista de (start, end) minutos
-    let mut day_slots: HashMap<String, Vec<(i32, i32)>> = HashMap::new();
+    let mut day_slots: HashMap<Dia, Vec<(i32, i32)>> = HashMap::new();
     
     for (seccion, _) in solution {
-        for horario in &seccion.horario {
-            let days = extract_days_from_horario(horario);
-            if let Some((start, end)) = parse_horario_range(horario) {
-                for day in days {
-                    day_slots.entry(day)
-                        .or_insert_with(Vec::new)
-                        .push((start, end));
-                }
-            }
+        for b in seccion.horario.iter().flat_map(|h| horario::parse(h)) {
+            day_slots.entry(b.dia)
+                .or_default()
+                .push((b.inicio as i32, b.fin as i32));
         }
     }
     
@@ -150,68 +93,194 @@ fn calculate_total_gaps(solution: &[(Seccion, i32)]) -> i32 {
     total_gaps
 }
 
-// Extrae la clave base de un curso (quita sufijos tipo 'laboratorio', 'taller', 'práctica')
-fn base_course_key(nombre: &str) -> String {
-    let mut s = nombre.to_lowercase();
-    // remover tokens comunes
-    for t in &["laboratorio", "laboratorios", "lab", "taller", "talleres", "practica", "práctica", "practicas", "prácticas"] {
-        s = s.replace(t, "");
+/// Créditos SCT asumidos para ramos sin dato en la malla (30 SCT / 6 ramos)
+pub const CREDITOS_SCT_POR_DEFECTO: i32 = 5;
+
+/// (clave del ramo, créditos SCT) de una sección. Secciones del mismo ramo
+/// comparten clave, así sus créditos se cuentan una sola vez.
+fn clave_y_creditos(s: &Seccion, indice: &RamoIndex) -> (String, i32) {
+    match indice.de_seccion(s) {
+        Some(r) => (format!("#{}", r.id), r.creditos.unwrap_or(CREDITOS_SCT_POR_DEFECTO)),
+        None => (s.codigo.to_uppercase(), CREDITOS_SCT_POR_DEFECTO),
     }
-    // quitar caracteres no alfanuméricos y normalizar
-    normalize_name(&s)
 }
 
-fn compute_priority(ramo: &RamoDisponible, sec: &Seccion) -> i64 {
-    // Fórmula correcta del RutaCritica.py:
-    // priority = CC + UU + KK + SS (concatenación como string, luego a int)
-    // CC: "10" if critico else "00"
-    // UU: f"{10-holgura:02d}"
-    // KK: f"{60-numb_correlativo:02d}"
-    // SS: f"{seccion_number:02d}"
-    
-    let cc_str = if ramo.critico { "10" } else { "00" };
-    
-    let holgura_int = (ramo.holgura as i32).max(0).min(10);
-    let uu_val = 10 - holgura_int;
-    let uu_str = format!("{:02}", uu_val);
-    
-    let numb_corr_int = ramo.numb_correlativo.max(0);
-    let kk_val = 60 - numb_corr_int;
-    let kk_str = format!("{:02}", kk_val.max(0).min(60));
-    
-    // SS: extraer número de seccion
-    let ss_str = if let Ok(sec_num) = sec.seccion.parse::<i32>() {
-        format!("{:02}", sec_num.max(0).min(99))
-    } else {
-        "00".to_string()
-    };
-    
-    let priority_str = format!("{}{}{}{}", cc_str, uu_str, kk_str, ss_str);
-    priority_str.parse::<i64>().unwrap_or(0)
+/// Suma de créditos contando cada ramo una vez
+fn suma_creditos<'a>(pares: impl IntoIterator<Item = &'a (String, i32)>) -> i32 {
+    let mut vistos: HashSet<&str> = HashSet::new();
+    pares.into_iter()
+        .filter(|(clave, _)| vistos.insert(clave.as_str()))
+        .map(|(_, c)| *c)
+        .sum()
 }
 
+/// Prioridad de una sección según RutaCritica.py (ver `algorithm::prioridad`)
+fn compute_priority(ramo: &RamoDisponible, sec: &Seccion) -> Priority {
+    Priority::de_seccion(ramo, sec)
+}
+
+/// Choque de horario bloque a bloque (ver `conflict::horarios_tienen_conflicto`):
+/// "LU MI 08:30-09:50" choca con "MI 08:30-09:50" aunque el texto difiera
 fn sections_conflict(s1: &Seccion, s2: &Seccion) -> bool {
-    s1.horario.iter().any(|h1| s2.horario.iter().any(|h2| h1 == h2))
+    crate::algorithm::conflict::horarios_tienen_conflicto(&s1.horario, &s2.horario)
+}
+
+/// Regla de arista del grafo de compatibilidad: dos secciones pueden convivir
+/// en una solución si son de paquetes distintos, de ramos distintos (prefijo de
+/// 7 caracteres del código) y sus horarios no chocan.
+fn secciones_compatibles(s1: &Seccion, s2: &Seccion) -> bool {
+    let code_a = &s1.codigo[..std::cmp::min(7, s1.codigo.len())];
+    let code_b = &s2.codigo[..std::cmp::min(7, s2.codigo.len())];
+    s1.codigo_box != s2.codigo_box && code_a != code_b && !sections_conflict(s1, s2)
+}
+
+/// Grafo de compatibilidad precalculado sobre una lista base de secciones.
+///
+/// Permite reutilizar la matriz de adyacencia entre varias ejecuciones del
+/// clique sobre el mismo dataset (p.ej. sesiones de `/solve/session` donde el
+/// usuario solo cambia filtros). Las secciones se identifican por
+/// (codigo, seccion, codigo_box).
+#[derive(Debug, Clone)]
+pub struct CompatibilityGraph {
+    indice: HashMap<(String, String, String), usize>,
+    adj: Vec<Vec<bool>>,
+}
+
+impl CompatibilityGraph {
+    /// Construye la matriz completa de compatibilidad para `secciones`.
+    pub fn build(secciones: &[Seccion]) -> Self {
+        let n = secciones.len();
+        let mut indice = HashMap::with_capacity(n);
+        for (i, s) in secciones.iter().enumerate() {
+            indice.entry(Self::key(s)).or_insert(i);
+        }
+        let adj = crate::metrics::medir("adjacency", n, || {
+            let mut adj = vec![vec![false; n]; n];
+            for i in 0..n {
+                for j in (i+1)..n {
+                    if secciones_compatibles(&secciones[i], &secciones[j]) {
+                        adj[i][j] = true; adj[j][i] = true;
+                    }
+                }
+            }
+            adj
+        });
+        CompatibilityGraph { indice, adj }
+    }
+
+    fn key(s: &Seccion) -> (String, String, String) {
+        (s.codigo.clone(), s.seccion.clone(), s.codigo_box.clone())
+    }
+
+    /// Índice de la sección dentro del grafo (None si no fue parte de la lista base)
+    pub fn indice_de(&self, s: &Seccion) -> Option<usize> {
+        self.indice.get(&Self::key(s)).copied()
+    }
+
+    /// Compatibilidad entre dos índices del grafo
+    pub fn compatibles(&self, i: usize, j: usize) -> bool {
+        self.adj.get(i).and_then(|row| row.get(j)).copied().unwrap_or(false)
+    }
+
+    /// Número de nodos (secciones) del grafo
+    pub fn len(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adj.is_empty()
+    }
+
+    /// Número de aristas (pares compatibles)
+    pub fn edge_count(&self) -> usize {
+        self.adj.iter().map(|row| row.iter().filter(|&&c| c).count()).sum::<usize>() / 2
+    }
 }
 
 /// Aplica modificadores de puntuación basados en optimizaciones seleccionadas
 /// y ramos prioritarios del usuario.
 /// 
-/// PRIORIDADES (de mayor a menor peso):
-/// 1. Ramos prioritarios: +100_000 por cada ramo prioritario en la solución
-/// 2. Optimizaciones de días: ±10_000 * compactness
-/// 3. Minimizar ventanas: -100 por minuto de ventana
+/// PRIORIDADES (de mayor a menor peso, valores por defecto de `ScoringConfig`):
+/// 1. Ramos prioritarios: +100_000_000 por cada ramo prioritario en la solución
+/// 2. Ramos reprobados: +20_000_000 por cada uno en la solución (más que un ramo crítico)
+/// 3. Secciones sin cupos: -1_000_000 por sección llena (prefiere otra sección del ramo)
+/// 4. Profesores en modo `soft`: ±50_000 por sección con profesor preferido/a evitar
+/// 5. Balance de líneas: hasta -500_000 según la desviación de las proporciones pedidas
+/// 6. Traslado entre campus en modo `soft`: -50_000 por par de clases sin tiempo de traslado
+/// 7. `minimizar_ventanas`: -1_000 por minuto de ventana sobre `ventana_ideal_minutos`
+/// 8. Carga de evaluaciones: -25_000 por punto (evaluaciones de cursos distintos el mismo día o en días seguidos)
+/// 9. Optimizaciones de días: ±10_000 * compactness
+/// 10. `minimize-gaps`: -100 por minuto de ventana
 /// 
 /// Esto garantiza que los ramos prioritarios siempre tengan más peso que las ventanas.
-fn apply_optimization_modifiers(base_score: i64, solution: &[(Seccion, i32)], params: &InputParams) -> i64 {
-    let mut score = base_score;
+/// El ajuste por profesor basta para preferir otra sección del mismo ramo, pero
+/// en general es menor que el score base de un ramo: no se deja fuera un ramo
+/// solo para evitar a un profesor.
+fn apply_optimization_modifiers(base_score: i64, solution: &[(Seccion, i32)], params: &InputParams, scoring: &ScoringConfig) -> i64 {
+    base_score + modificadores_score(solution, params, scoring).total()
+}
+
+/// Aporte de cada modificador de `apply_optimization_modifiers` al score de una
+/// solución (positivo = bonus, negativo = penalización)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ModificadoresScore {
+    pub ramos_prioritarios: i64,
+    /// Ramos de `ramos_reprobados` que la solución vuelve a tomar
+    pub ramos_reprobados: i64,
+    pub profesores: i64,
+    pub balance_lineas: i64,
+    pub traslado: i64,
+    /// `minimizar_ventanas`: minutos sobre la ventana ideal
+    pub ventanas: i64,
+    /// `compact-days` / `spread-days`
+    pub compactacion: i64,
+    /// `minimize-gaps`
+    pub gaps: i64,
+    /// Secciones sin cupos disponibles
+    pub cupos: i64,
+    /// Compromisos `soft` que la solución pisa
+    pub compromisos: i64,
+    /// Evaluaciones de cursos distintos el mismo día o en días seguidos
+    pub evaluaciones: i64,
+    /// `usar_historial`: secciones que los estudiantes rara vez confirman
+    pub historial: i64,
+}
+
+impl ModificadoresScore {
+    /// (nombre, aporte) de cada modificador, en el orden de declaración
+    pub fn partes(&self) -> [(&'static str, i64); 12] {
+        [
+            ("ramos_prioritarios", self.ramos_prioritarios),
+            ("ramos_reprobados", self.ramos_reprobados),
+            ("profesores", self.profesores),
+            ("balance_lineas", self.balance_lineas),
+            ("traslado", self.traslado),
+            ("ventanas", self.ventanas),
+            ("compactacion", self.compactacion),
+            ("gaps", self.gaps),
+            ("cupos", self.cupos),
+            ("compromisos", self.compromisos),
+            ("evaluaciones", self.evaluaciones),
+            ("historial", self.historial),
+        ]
+    }
+
+    pub fn total(&self) -> i64 {
+        self.partes().iter().map(|(_, v)| v).sum()
+    }
+}
+
+/// Calcula por separado cada modificador que `apply_optimization_modifiers`
+/// suma al score base
+pub fn modificadores_score(solution: &[(Seccion, i32)], params: &InputParams, scoring: &ScoringConfig) -> ModificadoresScore {
+    let mut m = ModificadoresScore::default();
     
     // DEBUG: siempre registrar que la función fue llamada
     let compactness = calculate_compactness_score(solution);
     let total_gaps = calculate_total_gaps(solution) as i64;
     
     // 1. BONUS POR RAMOS PRIORITARIOS (máxima prioridad)
-    // +100_000_000 (100 millones) por cada ramo prioritario en la solución
+    // `bonus_ramo_prioritario` (100 millones por defecto) por cada ramo prioritario en la solución
     // Esto garantiza que las soluciones con más ramos prioritarios SIEMPRE ganen
     // ya que los scores base típicos son ~10-40 millones
     if !params.ramos_prioritarios.is_empty() {
@@ -232,46 +301,151 @@ fn apply_optimization_modifiers(base_score: i64, solution: &[(Seccion, i32)], pa
         
         if priority_count > 0 {
             // 100 millones por ramo prioritario = domina sobre cualquier score base
-            let priority_bonus = priority_count * 100_000_000i64;
-            eprintln!("[OPT] ramos-prioritarios: {} ramos prioritarios, +{}", priority_count, priority_bonus);
-            score += priority_bonus;
+            let priority_bonus = priority_count * scoring.bonus_ramo_prioritario;
+            tracing::debug!("[OPT] ramos-prioritarios: {} ramos prioritarios, +{}", priority_count, priority_bonus);
+            m.ramos_prioritarios = priority_bonus;
         }
     }
     
+    // 1b. BONUS POR RAMOS REPROBADOS: suelen bloquear el avance, así que
+    // conviene retomarlos pronto aunque el usuario no los marque como prioritarios
+    let reprobados = ramos_reprobados_en(solution, params);
+    if !reprobados.is_empty() {
+        m.ramos_reprobados = reprobados.len() as i64 * scoring.bonus_ramo_reprobado;
+        tracing::debug!("[OPT] ramos-reprobados: {:?}, +{}", reprobados, m.ramos_reprobados);
+    }
+
+    // 2. PREFERENCIAS DE PROFESORES (modo soft): bonus/penalización en vez de excluir
+    let prof_soft = params.filtros.as_ref()
+        .and_then(|f| f.preferencias_profesores.as_ref())
+        .filter(|p| p.habilitado && p.modo == ModoPreferencia::Soft);
+    if let Some(prof) = prof_soft {
+        let preferidas = solution.iter()
+            .filter(|(s, _)| !s.is_cfg && profesor_en_lista(s, &prof.profesores_preferidos))
+            .count() as i64;
+        let evitadas = solution.iter()
+            .filter(|(s, _)| !s.is_cfg && profesor_en_lista(s, &prof.profesores_evitar))
+            .count() as i64;
+        let modifier = (preferidas - evitadas) * scoring.bonus_profesor;
+        if modifier != 0 {
+            tracing::debug!("[OPT] profesores-soft: {} preferidas, {} a evitar, {:+}", preferidas, evitadas, modifier);
+        }
+        m.profesores = modifier;
+    }
+
+    // 3. BALANCE DE LÍNEAS: penalización proporcional a la desviación
+    let logrado = balance_lineas::balance_pedido(params)
+        .and_then(|b| balance_lineas::balance_logrado(solution.iter().map(|(s, _)| s), b));
+    if let Some(logrado) = logrado {
+        let modifier = (logrado.desviacion * scoring.penalizacion_balance_lineas as f64).round() as i64;
+        if modifier != 0 {
+            tracing::debug!("[OPT] balance-lineas: desviación {:.2}, -{}", logrado.desviacion, modifier);
+        }
+        m.balance_lineas = -modifier;
+    }
+
+    // 4. TRASLADO ENTRE CAMPUS (modo soft): penalización por par sin tiempo de traslado
+    if let Some(t) = traslado::traslado_blando(params) {
+        let pares = traslado::traslados_insuficientes(solution.iter().map(|(s, _)| s), traslado::minutos(t)) as i64;
+        if pares > 0 {
+            tracing::debug!("[OPT] traslado-soft: {} pares sin tiempo de traslado, -{}", pares, pares * scoring.penalizacion_traslado);
+        }
+        m.traslado = -pares * scoring.penalizacion_traslado;
+    }
+
+    // 5. VENTANAS SOBRE LA IDEAL (dias_horarios_libres.minimizar_ventanas)
+    if let Some(ideal) = ventanas::ventana_ideal_pedida(params) {
+        let exceso = ventanas::minutos_sobre_ideal(solution.iter().map(|(s, _)| s), ideal) as i64;
+        if exceso > 0 {
+            tracing::debug!("[OPT] minimizar-ventanas: {} min sobre la ventana ideal de {} min, -{}", exceso, ideal, exceso * scoring.penalizacion_ventana_por_minuto);
+        }
+        m.ventanas = -exceso * scoring.penalizacion_ventana_por_minuto;
+    }
+
+    // 6. SECCIONES SIN CUPOS: penalización por sección llena (con `solo_con_cupos` ya se excluyeron)
+    let llenas = solution.iter().filter(|(s, _)| s.sin_cupos()).count() as i64;
+    if llenas > 0 {
+        tracing::debug!("[OPT] sin-cupos: {} secciones sin cupos, -{}", llenas, llenas * scoring.penalizacion_sin_cupos);
+    }
+    m.cupos = -llenas * scoring.penalizacion_sin_cupos;
+
+    // 7. COMPROMISOS BLANDOS: penalización por compromiso `soft` pisado
+    let penalizacion = compromisos::penalizacion(solution.iter().map(|(s, _)| s), params, scoring);
+    if penalizacion > 0 {
+        tracing::debug!("[OPT] compromisos-soft: -{}", penalizacion);
+    }
+    m.compromisos = -penalizacion;
+
+    // 8. CARGA DE EVALUACIONES: penalización por punto (ver `algorithm::evaluaciones`)
+    let carga = evaluaciones::carga_evaluaciones(solution.iter().map(|(s, _)| s)).map_or(0, |c| c.carga) as i64;
+    if carga > 0 {
+        tracing::debug!("[OPT] carga-evaluaciones: {} puntos, -{}", carga, carga * scoring.penalizacion_carga_evaluaciones);
+    }
+    m.evaluaciones = -carga * scoring.penalizacion_carga_evaluaciones;
+
+    // 9. HISTORIAL (usar_historial): penalización por sección que los estudiantes rara vez confirman
+    if params.usar_historial {
+        let rechazadas = crate::scoring::secciones_rechazadas(solution.iter().map(|(s, _)| s), &crate::scoring::historial()) as i64;
+        if rechazadas > 0 {
+            tracing::debug!("[OPT] historial: {} secciones poco confirmadas, -{}", rechazadas, rechazadas * scoring.penalizacion_historial);
+        }
+        m.historial = -rechazadas * scoring.penalizacion_historial;
+    }
+
     // Solo mostrar debug si hay optimizaciones
     if !params.optimizations.is_empty() {
-        eprintln!("[OPT-DEBUG] base_score={}, gaps={}min, compactness={:.2}%, opts={:?}", 
-                  base_score, total_gaps, compactness, params.optimizations);
+        tracing::debug!("[OPT-DEBUG] gaps={}min, compactness={:.2}%, opts={:?}", 
+                  total_gaps, compactness, params.optimizations);
     }
     
-    // 2. OPTIMIZACIONES DE HORARIO (menor prioridad que ramos prioritarios)
+    // 10. OPTIMIZACIONES DE HORARIO (menor prioridad que ramos prioritarios)
     for opt in &params.optimizations {
-        eprintln!("[OPT-DEBUG] Processing optimization: {}", opt);
+        tracing::debug!("[OPT-DEBUG] Processing optimization: {}", opt);
         match opt.as_str() {
             "compact-days" => {
-                let modifier = (compactness as i64) * 10_000;
-                eprintln!("[OPT] compact-days: +{}", modifier);
-                score += modifier;
+                let modifier = (compactness as i64) * scoring.peso_compactacion;
+                tracing::debug!("[OPT] compact-days: +{}", modifier);
+                m.compactacion += modifier;
             }
             "spread-days" => {
-                let modifier = (compactness as i64) * 10_000;
-                eprintln!("[OPT] spread-days: -{}", modifier);
-                score -= modifier;
+                let modifier = (compactness as i64) * scoring.peso_compactacion;
+                tracing::debug!("[OPT] spread-days: -{}", modifier);
+                m.compactacion -= modifier;
             }
             "minimize-gaps" => {
-                // Penalización por ventanas: -100 por minuto
-                // Una ventana de 2 horas = -12_000, mucho menor que el bonus de 1 ramo prioritario (+100_000)
-                let modifier = total_gaps * 100;
-                eprintln!("[OPT] minimize-gaps: -{}", modifier);
-                score -= modifier;
+                // Penalización por ventanas: -100 por minuto (por defecto)
+                // Una ventana de 2 horas = -12_000, mucho menor que el bonus de 1 ramo prioritario
+                let modifier = total_gaps * scoring.penalizacion_gap_por_minuto;
+                tracing::debug!("[OPT] minimize-gaps: -{}", modifier);
+                m.gaps -= modifier;
             }
             _ => {
-                eprintln!("[OPT-DEBUG] Unknown optimization: {}", opt);
+                tracing::debug!("[OPT-DEBUG] Unknown optimization: {}", opt);
             }
         }
     }
     
-    score
+    m
+}
+
+/// true si la sección es de un ramo de `params.ramos_reprobados` (por código o nombre)
+pub fn es_reprobado(params: &InputParams, s: &Seccion) -> bool {
+    let (codigo, nombre) = (normalize_name(&s.codigo), normalize_name(&s.nombre));
+    params.ramos_reprobados.iter().map(|r| normalize_name(r)).any(|r| r == codigo || r == nombre)
+}
+
+/// Códigos de los ramos reprobados que la solución vuelve a tomar, sin repetir
+pub fn ramos_reprobados_en(solution: &[(Seccion, i32)], params: &InputParams) -> Vec<String> {
+    let mut codigos: Vec<String> = Vec::new();
+    if params.ramos_reprobados.is_empty() {
+        return codigos;
+    }
+    for (s, _) in solution.iter().filter(|(s, _)| es_reprobado(params, s)) {
+        if !codigos.contains(&s.codigo) {
+            codigos.push(s.codigo.clone());
+        }
+    }
+    codigos
 }
 
 /// Verifica si los requisitos previos de una sección están cumplidos
@@ -284,7 +458,7 @@ fn apply_optimization_modifiers(base_score: i64, solution: &[(Seccion, i32)], pa
 fn requisitos_cumplidos(
     _seccion: &Seccion,
     ramo: &RamoDisponible,
-    ramos_disp: &HashMap<String, RamoDisponible>,
+    indice: &RamoIndex,
     passed_codes: &HashSet<String>,  // códigos de cursos ya pasados + cursos en solución actual
 ) -> bool {
     // Si no hay requisitos, está permitido
@@ -295,10 +469,10 @@ fn requisitos_cumplidos(
     // Verificar que TODOS los requisitos están cumplidos
     for prereq_id in &ramo.requisitos_ids {
         // Buscar el ramo prerequisito por ID
-        let prereq_ramo = match ramos_disp.values().find(|r| r.id == *prereq_id) {
+        let prereq_ramo = match indice.por_id(*prereq_id) {
             Some(r) => r,
             None => {
-                eprintln!(
+                tracing::warn!(
                     "⚠️  [prerequisitos] {} (id={}) requiere id={} pero no se encontró ese ramo",
                     ramo.nombre, ramo.id, prereq_id
                 );
@@ -311,7 +485,7 @@ fn requisitos_cumplidos(
         let cumplido = passed_codes.contains(&prereq_codigo_upper);
         
         if !cumplido {
-            eprintln!(
+            tracing::debug!(
                 "❌ [prerequisitos] {} requiere: {} (id={}, código='{}')",
                 ramo.nombre, prereq_ramo.nombre, prereq_ramo.id, prereq_ramo.codigo
             );
@@ -328,174 +502,215 @@ fn requisitos_cumplidos(
     true
 }
 
-/// Helper para parsear "HH:MM" a minutos
-fn parse_hora(s: &str) -> Option<i32> {
-    let s = s.trim();
-    let parts: Vec<&str> = s.split(':').collect();
-    if parts.len() != 2 {
-        return None;
+/// Semestre más alto que se puede cursar: el mayor semestre aprobado + 2
+pub(crate) fn semestre_maximo(params: &InputParams, indice: &RamoIndex) -> i32 {
+    let max_sem = params.ramos_pasados.iter()
+        .filter_map(|code| indice.por_codigo(code).and_then(|r| r.semestre))
+        .max()
+        .unwrap_or(0);
+    max_sem + 2
+}
+
+/// true si la sección es de un ramo de la malla hasta `max_sem` (o sin
+/// semestre), o un CFG aunque no esté en la malla
+pub(crate) fn dentro_del_horizonte(s: &Seccion, indice: &RamoIndex, max_sem: i32) -> bool {
+    // Buscar el ramo por CÓDIGO y si no por NOMBRE normalizado
+    if let Some(r) = indice.de_seccion(s) {
+        return r.semestre.is_none_or(|sem| sem <= max_sem);
     }
-    
-    let h = parts[0].trim().parse::<i32>().ok()?;
-    let m = parts[1].trim().parse::<i32>().ok()?;
-    
-    Some(h * 60 + m)
+    // Si NO encontramos en ramos_disponibles (ni por código ni por nombre),
+    // permitir si es una sección CFG, si no excluir
+    s.is_cfg
 }
 
-// Extrae rangos (día, inicio, fin) de un vector de horarios de sección
-fn seccion_time_ranges(horarios: &Vec<String>) -> Vec<(String, i32, i32)> {
-    let mut out = Vec::new();
-    for h in horarios.iter() {
-        // intentar parsear formato "LU MA JU 08:30 - 09:50"
-        let horario = h.replace("- ", "-");
-        // separar tokens
-        let tokens: Vec<&str> = horario.split_whitespace().collect();
-        if tokens.is_empty() { continue; }
-
-        // buscar primer token que contiene ':' para identificar inicio tiempo
-        let mut day_tokens: Vec<&str> = Vec::new();
-        let mut time_tokens: Vec<&str> = Vec::new();
-        for &t in tokens.iter() {
-            if t.contains(":") || t.contains("-") {
-                time_tokens.push(t);
-            } else if time_tokens.is_empty() {
-                day_tokens.push(t);
+/// Validación de prerequisitos del enumerador greedy: solo los electivos
+/// exigen tener aprobados sus requisitos (PYTHON-STYLE)
+pub(crate) fn prerequisitos_de_seccion_ok(s: &Seccion, indice: &RamoIndex, passed_codes_set: &HashSet<String>) -> bool {
+    // Encontrar el ramo correspondiente a esta sección
+    if let Some(ramo) = indice.por_codigo(&s.codigo) {
+        // PYTHON-STYLE: Solo verificar prerequisitos para ELECTIVOS
+        // Los ramos normales pasan sin verificación de prerequisitos
+        if s.is_electivo {
+            // Para electivos, verificar prerequisitos (como hace Python)
+            if requisitos_cumplidos(s, ramo, indice, passed_codes_set) {
+                return true;
+            } else {
+                tracing::debug!(
+                    "   ⊘ Excluyendo ELECTIVO {} (id={}) - prerequisitos no cumplidos",
+                    ramo.nombre, ramo.id
+                );
+                return false;
             }
+        } else {
+            // Ramos normales: permitir SIN verificar prerequisitos (como Python)
+            return true;
         }
+    }
+    
+    // Si no encontramos el ramo en ramos_disponibles por CÓDIGO,
+    // intentar matching por NOMBRE normalizado
+    if let Some(ramo) = indice.por_nombre(&s.nombre) {
+        // PYTHON-STYLE: Solo verificar prerequisitos para ELECTIVOS
+        if s.is_electivo {
+            if requisitos_cumplidos(s, ramo, indice, passed_codes_set) {
+                return true;
+            } else {
+                tracing::debug!(
+                    "   ⊘ Excluyendo ELECTIVO {} (nombre match) - prerequisitos no cumplidos",
+                    ramo.nombre
+                );
+                return false;
+            }
+        } else {
+            return true;
+        }
+    }
+    
+    // Si NO encontramos ni por código ni por nombre,
+    // permitir si la sección proviene de un CFG o es un electivo (lógica original)
+    if s.is_cfg {
+        tracing::debug!(
+            "   ✓ Permitido {} - SECCIÓN CFG no encontrada en malla pero aceptada",
+            s.codigo
+        );
+        return true;
+    }
+    
+    if s.is_electivo {
+        tracing::debug!(
+            "   ✓ Permitido {} - ELECTIVO DE ESPECIALIZACIÓN no encontrado en malla pero aceptado",
+            s.codigo
+        );
+        return true;
+    }
 
-        if time_tokens.is_empty() || day_tokens.is_empty() { continue; }
+    // Cursos no encontrados en malla: permitir (PYTHON-STYLE)
+    tracing::debug!(
+        "   ✓ Permitido {} - no encontrado en malla pero aceptado (PYTHON-STYLE)",
+        s.codigo
+    );
+    true
+}
+
+/// true si la sección es de un ramo de `ramos_excluidos` (por código o nombre
+/// normalizado, ya resueltos por `api_json::resolve_ramos_with_resolver`)
+pub(crate) fn ramo_excluido(s: &Seccion, ramos_excluidos: &[String]) -> bool {
+    ramos_excluidos.iter().any(|r| {
+        r.trim().eq_ignore_ascii_case(s.codigo.trim()) || normalize_name(r) == normalize_name(&s.nombre)
+    })
+}
 
-        // join time tokens to find pattern like "08:30-09:50" or "08:30 - 09:50"
-        let time_join = time_tokens.join(" ");
-        let parts: Vec<&str> = if time_join.contains('-') { time_join.split('-').collect() } else { Vec::new() };
-        if parts.len() != 2 { continue; }
-        if let (Some(si), Some(sf)) = (parse_hora(parts[0].trim()), parse_hora(parts[1].trim())) {
-            for &d in day_tokens.iter() {
-                out.push((d.to_string().to_lowercase(), si, sf));
+/// Verifica los co-requisitos de un ramo: a diferencia de los requisitos,
+/// cada uno puede estar aprobado (`passed_codes`) o tomarse en la misma
+/// solución (`en_solucion`). Ambos conjuntos traen códigos en mayúscula.
+fn correquisitos_cumplidos(
+    ramo: &RamoDisponible,
+    indice: &RamoIndex,
+    passed_codes: &HashSet<String>,
+    en_solucion: &HashSet<String>,
+) -> bool {
+    ramo.correquisitos_ids.iter().all(|id| {
+        match indice.por_id(*id) {
+            Some(co) => {
+                let codigo = co.codigo.to_uppercase();
+                let cumplido = passed_codes.contains(&codigo) || en_solucion.contains(&codigo);
+                if !cumplido {
+                    tracing::debug!("❌ [correquisitos] {} requiere cursar junto a {} (código='{}')", ramo.nombre, co.nombre, co.codigo);
+                }
+                cumplido
+            }
+            None => {
+                tracing::warn!(
+                    "⚠️  [correquisitos] {} (id={}) tiene co-requisito id={} pero no se encontró ese ramo",
+                    ramo.nombre, ramo.id, id
+                );
+                false
             }
         }
+    })
+}
+
+/// Posición (dentro de `clique`) de la primera sección cuyos co-requisitos no
+/// están aprobados ni incluidos en la misma clique
+fn primera_sin_correquisitos(
+    filtered: &[Seccion],
+    clique: &[usize],
+    indice: &RamoIndex,
+    passed_codes: &HashSet<String>,
+) -> Option<usize> {
+    let en_solucion: HashSet<String> = clique.iter().map(|&i| filtered[i].codigo.to_uppercase()).collect();
+    clique.iter().position(|&i| {
+        indice.de_seccion(&filtered[i])
+            .is_some_and(|r| !correquisitos_cumplidos(r, indice, passed_codes, &en_solucion))
+    })
+}
+
+/// Quita de `clique` las secciones con co-requisitos pendientes. Se repite
+/// porque quitar una sección puede dejar sin co-requisito a otra.
+fn podar_correquisitos(
+    filtered: &[Seccion],
+    clique: &mut Vec<usize>,
+    indice: &RamoIndex,
+    passed_codes: &HashSet<String>,
+) {
+    while let Some(pos) = primera_sin_correquisitos(filtered, clique, indice, passed_codes) {
+        let quitada = clique.remove(pos);
+        tracing::debug!("   [CORREQUISITOS] {} sale de la solución: co-requisito no aprobado ni incluido", filtered[quitada].codigo);
     }
-    out
 }
 
 // Comprueba si dos secciones cumplen la ventana mínima entre clases (en minutos)
 fn cumple_ventana_entre(se1: &Seccion, se2: &Seccion, minutos_min: i32) -> bool {
-    let r1 = seccion_time_ranges(&se1.horario);
-    let r2 = seccion_time_ranges(&se2.horario);
-    for (d1, s1, e1) in r1.iter() {
-        for (d2, s2, e2) in r2.iter() {
-            if d1 == d2 {
-                // desreferenciar valores numéricos (iter devuelve &i32 en tuples)
-                let s1v = *s1; let e1v = *e1; let s2v = *s2; let e2v = *e2;
-                // si se solapan la gap será 0; si no, calcular distancia mínima entre intervalos
-                let gap = if e1v <= s2v { s2v - e1v } else if e2v <= s1v { s1v - e2v } else { 0 };
-                if gap < minutos_min { return false; }
-            }
-        }
-    }
-    true
+    let r2 = horario::bloques_de_seccion(se2);
+    horario::bloques_de_seccion(se1).iter().all(|b1| {
+        // si se solapan la distancia es 0
+        r2.iter().filter_map(|b2| b1.distancia(b2)).all(|gap| gap as i32 >= minutos_min)
+    })
 }
 
 /// Verifica si un horario (ej: "LU MA JU 08:30 - 09:50") solapa con una franja prohibida (ej: "LU 08:00-09:00")
-fn horario_solapa_franja(horario: &str, franja_prohibida: &crate::models::FranjaProhibida) -> bool {
-    let horario = horario.trim();
-    
-    // Extraer día, inicio, fin de la estructura
-    let dia_prohibido = franja_prohibida.dia.to_lowercase();
-    let franja_inicio_str = &franja_prohibida.inicio;
-    let franja_fin_str = &franja_prohibida.fin;
-    
-    // Parsear horas
-    let franja_inicio = match parse_hora(franja_inicio_str) {
-        Some(m) => m,
-        None => {
-            eprintln!("[DEBUG] No pude parsear hora inicio de franja: '{}'", franja_inicio_str);
-            return false;
-        }
-    };
-    
-    let franja_fin = match parse_hora(franja_fin_str) {
-        Some(m) => m,
-        None => {
-            eprintln!("[DEBUG] No pude parsear hora fin de franja: '{}'", franja_fin_str);
-            return false;
-        }
-    };
-    
-    // Verificar que el día prohibido está en el horario
-    // Los días están al inicio del horario (antes de las horas)
-    // Formato: "LU MA JU 08:30 - 09:50" o "MI 14:30 - 15:50"
-    let horario_lower = horario.to_lowercase();
-    let horario_days: Vec<&str> = horario_lower.split_whitespace()
-        .take_while(|w| !w.contains(':') && !w.contains('-'))
-        .collect();
-    
-    eprintln!("[DEBUG horario_solapa_franja] horario_days={:?}, dia_prohibido='{}'", horario_days, dia_prohibido);
-    
-    let tiene_dia = horario_days.contains(&dia_prohibido.as_str());
-    
-    if !tiene_dia {
-        eprintln!("[DEBUG horario_solapa_franja] día prohibido '{}' no encontrado en {:?}, retornando false", dia_prohibido, horario_days);
-        return false; // Día no coincide
-    }
-    
-    // Parsear horario: "LU MA JU 08:30 - 09:50" o "MI 14:30 - 15:50"
-    let horario_tiempo = horario.replace("- ", "-");
-    let horario_parts: Vec<&str> = horario_tiempo.split_whitespace()
-        .filter(|w| w.contains(':') || w.contains('-'))
-        .collect();
-    
-    if horario_parts.is_empty() {
-        return false;
-    }
-    
-    let horario_tiempo_combined = horario_parts.join(" ");
-    
-    let horario_tiempo_parts: Vec<&str> = if horario_tiempo_combined.contains('-') {
-        horario_tiempo_combined.split('-').collect()
-    } else {
-        return false;
-    };
-    
-    if horario_tiempo_parts.len() != 2 {
+fn horario_solapa_franja(entrada: &str, franja_prohibida: &crate::models::FranjaProhibida) -> bool {
+    let (Some(dia), Some(inicio), Some(fin)) = (
+        Dia::desde_codigo(&franja_prohibida.dia),
+        horario::parse_hora(&franja_prohibida.inicio),
+        horario::parse_hora(&franja_prohibida.fin),
+    ) else {
+        tracing::debug!("No pude parsear la franja prohibida: {} {}-{}", franja_prohibida.dia, franja_prohibida.inicio, franja_prohibida.fin);
         return false;
-    }
-    
-    let (horario_inicio_str, horario_fin_str) = (horario_tiempo_parts[0].trim(), horario_tiempo_parts[1].trim());
-    
-    let horario_inicio = match parse_hora(horario_inicio_str) {
-        Some(m) => m,
-        None => {
-            eprintln!("[DEBUG] No pude parsear hora inicio de horario: '{}'", horario_inicio_str);
-            return false;
-        }
     };
-    
-    let horario_fin = match parse_hora(horario_fin_str) {
-        Some(m) => m,
-        None => {
-            eprintln!("[DEBUG] No pude parsear hora fin de horario: '{}'", horario_fin_str);
-            return false;
-        }
-    };
-    
-    // Verificar solapamiento temporal
-    // Dos intervalos [a, b] y [c, d] solapan si a < d && c < b
-    let solapa = franja_inicio < horario_fin && horario_inicio < franja_fin;
-    
+    let franja = horario::Bloque { dia, inicio, fin };
+    let solapa = horario::parse(entrada).iter().any(|b| b.solapa(&franja));
     if solapa {
-        eprintln!("[DEBUG] SOLAPAMIENTO: franja=[{}-{}] horario=[{}-{}]", 
-                 franja_inicio, franja_fin, horario_inicio, horario_fin);
+        tracing::debug!("SOLAPAMIENTO: franja={:?} horario='{}'", franja, entrada);
     }
-    
     solapa
 }
 
+/// true si el profesor de la sección contiene (sin distinguir mayúsculas)
+/// alguno de los nombres de `lista`
+fn profesor_en_lista(seccion: &Seccion, lista: &Option<Vec<String>>) -> bool {
+    let profesor = seccion.profesor.to_lowercase();
+    lista.iter().flatten()
+        .any(|p| !p.trim().is_empty() && profesor.contains(&p.trim().to_lowercase()))
+}
+
 /// Verifica si una sección cumple con los filtros del usuario
-fn seccion_cumple_filtros(seccion: &Seccion, filtros: &Option<crate::models::UserFilters>) -> bool {
+pub(crate) fn seccion_cumple_filtros(seccion: &Seccion, filtros: &Option<crate::models::UserFilters>) -> bool {
     if filtros.is_none() {
         return true;
     }
+
+    // Filtro: solo secciones con cupos (también aplica a CFG: sin cupos no se pueden inscribir)
+    if filtros.as_ref().is_some_and(|f| f.solo_con_cupos) && seccion.sin_cupos() {
+        tracing::debug!("FILTRO: Excluyendo {} sección {} - sin cupos", seccion.codigo, seccion.seccion);
+        return false;
+    }
+
+    // Filtro: rango horario (también aplica a CFG: el estudiante no puede asistir fuera de él)
+    if carga_diaria::rango_horario(filtros).is_some_and(|r| carga_diaria::fuera_de_rango(seccion, r)) {
+        tracing::debug!("FILTRO: Excluyendo {} sección {} - fuera del rango horario", seccion.codigo, seccion.seccion);
+        return false;
+    }
     
     // Las secciones CFG siempre pasan los filtros de usuario
     // (se tratan especialmente en la lógica de clique)
@@ -513,7 +728,7 @@ fn seccion_cumple_filtros(seccion: &Seccion, filtros: &Option<crate::models::Use
                 for horario in &seccion.horario {
                     for franja in franjas_prohibidas {
                         if horario_solapa_franja(horario, franja) {
-                            eprintln!("[DEBUG] FILTRO: Excluyendo {} - horario '{}' solapa con franja ({} {}:{})", 
+                            tracing::debug!("FILTRO: Excluyendo {} - horario '{}' solapa con franja ({} {}:{})", 
                                      seccion.codigo, horario, franja.dia, franja.inicio, franja.fin);
                             return false;
                         }
@@ -531,9 +746,10 @@ fn seccion_cumple_filtros(seccion: &Seccion, filtros: &Option<crate::models::Use
         }
     }
     
-    // Filtro: Profesores a evitar / preferidos
+    // Filtro: Profesores a evitar / preferidos (en modo soft se puntúan en
+    // `apply_optimization_modifiers` en vez de excluir)
     if let Some(ref prof_filter) = f.preferencias_profesores {
-        if prof_filter.habilitado {
+        if prof_filter.habilitado && prof_filter.modo == ModoPreferencia::Hard {
             // Si hay una lista de preferidos no vacía, requerir que el profesor esté en la lista
             if let Some(ref preferidos) = prof_filter.profesores_preferidos {
                 if !preferidos.is_empty() {
@@ -557,11 +773,33 @@ fn seccion_cumple_filtros(seccion: &Seccion, filtros: &Option<crate::models::Use
             }
         }
     }
+
+    // Filtro: Balance de líneas. Los electivos de una línea sin peso (o con
+    // peso 0) se excluyen; los que no tienen línea en el catálogo pasan.
+    let lineas = f.balance_lineas.as_ref()
+        .filter(|b| b.habilitado)
+        .and_then(|b| b.lineas.as_ref())
+        .filter(|l| !l.is_empty());
+    let linea_sin_peso = lineas.zip(seccion.linea.as_deref())
+        .filter(|(lineas, linea)| peso_linea(lineas, linea) <= 0.0);
+    if let Some((_, linea)) = linea_sin_peso {
+        tracing::debug!("FILTRO: Excluyendo {} - línea '{}' sin peso en balance_lineas", seccion.codigo, linea);
+        return false;
+    }
     
     true
 }
 
-/// Búsqueda exhaustiva usando petgraph para máximas cliques
+/// Peso pedido para `linea` en `BalanceLineas::lineas` (claves comparadas con
+/// `normalizar_linea`, así "Informática" y "informatica" son la misma). 0 si no está.
+pub(crate) fn peso_linea(lineas: &HashMap<String, f64>, linea: &str) -> f64 {
+    lineas.iter()
+        .find(|(k, _)| crate::excel::electivos::normalizar_linea(k) == linea)
+        .map(|(_, p)| *p)
+        .unwrap_or(0.0)
+}
+
+/// Búsqueda exhaustiva de cliques de `max_size` secciones (Bron–Kerbosch)
 /// Prioriza CFGs y garantiza que aparezcan en soluciones
 pub fn exhaustive_clique_search_with_cfg(
     filtered: &[Seccion],
@@ -570,144 +808,57 @@ pub fn exhaustive_clique_search_with_cfg(
     max_size: usize,
     max_solutions: usize,
 ) -> Vec<(Vec<(Seccion, i32)>, i64)> {
-    eprintln!("   [EXHAUSTIVE] Construyendo grafo de compatibilidad con petgraph...");
-    
-    // Construir grafo usando petgraph
-    let mut graph: UnGraph<(usize, &Seccion), ()> = UnGraph::new_undirected();
-    let mut node_map: HashMap<usize, NodeIndex> = HashMap::new();
-    
-    // Añadir nodos (secciones)
-    for (idx, sec) in filtered.iter().enumerate() {
-        let node_idx = graph.add_node((idx, sec));
-        node_map.insert(idx, node_idx);
-    }
-    
-    // Añadir aristas (compatibilidad entre secciones)
-    for i in 0..filtered.len() {
-        for j in (i + 1)..filtered.len() {
-            let s1 = &filtered[i];
-            let s2 = &filtered[j];
-            
-            // Verificar compatibilidad: mismo código? conflicto horario?
+    let scoring = crate::scoring::efectiva(params);
+    let indice = RamoIndex::new(ramos_disponibles);
+    tracing::debug!("   [EXHAUSTIVE] Construyendo grafo de compatibilidad...");
+
+    // Compatibilidad: distinto ramo y sin conflicto horario; las secciones
+    // que no cumplen los filtros quedan fuera
+    let graph = Grafo::nuevo(
+        filtered.len(),
+        |i| seccion_cumple_filtros(&filtered[i], &params.filtros),
+        |i, j| {
+            let (s1, s2) = (&filtered[i], &filtered[j]);
             let code_a = &s1.codigo[..std::cmp::min(7, s1.codigo.len())];
             let code_b = &s2.codigo[..std::cmp::min(7, s2.codigo.len())];
-            
-            let compatible = s1.codigo_box != s2.codigo_box 
-                && code_a != code_b 
-                && !sections_conflict(s1, s2)
-                && seccion_cumple_filtros(s1, &params.filtros)
-                && seccion_cumple_filtros(s2, &params.filtros);
-            
-            if compatible {
-                if let (Some(&n1), Some(&n2)) = (node_map.get(&i), node_map.get(&j)) {
-                    graph.add_edge(n1, n2, ());
-                }
-            }
-        }
-    }
+            s1.codigo_box != s2.codigo_box && code_a != code_b && !sections_conflict(s1, s2)
+        },
+    );
     
-    eprintln!("   [EXHAUSTIVE] Grafo: {} nodos, {} aristas", graph.node_count(), graph.edge_count());
+    tracing::debug!("   [EXHAUSTIVE] Grafo: {} nodos, {} aristas", graph.len(), graph.aristas());
     
     let mut all_solutions: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::new();
     let mut seen_solutions: HashSet<String> = HashSet::new();
     
-    // Búsqueda exhaustiva de cliques usando DFS con backtracking
-    fn find_cliques_dfs(
-        node: NodeIndex,
-        candidates: Vec<NodeIndex>,
-        graph: &UnGraph<(usize, &Seccion), ()>,
-        current_clique: &mut Vec<NodeIndex>,
-        all_cliques: &mut Vec<Vec<NodeIndex>>,
-        max_size: usize,
-        max_cliques: usize,
-        ramos_disponibles: &HashMap<String, RamoDisponible>,
-    ) {
-        if all_cliques.len() >= max_cliques {
-            return;
-        }
-        
-        // PODA: tamaño máximo alcanzado
-        if current_clique.len() == max_size {
-            all_cliques.push(current_clique.clone());
-            return;
-        }
-        
-        // PODA: no hay suficientes candidatos
-        if current_clique.len() + candidates.len() < max_size {
-            return;
+    // Cliques de exactamente `max_size` secciones (Bron–Kerbosch con tope)
+    let mut cliques_found: Vec<Vec<usize>> = Vec::new();
+    let tope_dia = carga_diaria::tope_por_dia(&params.filtros);
+    BronKerbosch::new(&graph, max_size).enumerar(|nodo| {
+        if cliques_found.len() >= max_solutions {
+            return Control::Parar;
         }
-        
-        // Base case: guardar cliques parciales válidos
-        if candidates.is_empty() {
-            if !current_clique.is_empty() {
-                all_cliques.push(current_clique.clone());
-            }
-            return;
+        if tope_dia.is_some_and(|t| carga_diaria::excede_ramos_por_dia(nodo.clique.iter().map(|&i| &filtered[i]), t)) {
+            return Control::Podar;
         }
-        
-        for (i, &cand) in candidates.iter().enumerate() {
-            // Verificar que cand es compatible con todos en current_clique
-            let mut compatible = true;
-            for &existing in current_clique.iter() {
-                if !graph.contains_edge(cand, existing) {
-                    compatible = false;
-                    break;
-                }
-            }
-            
-            if compatible {
-                current_clique.push(cand);
-                
-                // Nuevos candidatos: solo aquellos conectados a cand
-                let new_candidates: Vec<NodeIndex> = candidates[(i+1)..]
-                    .iter()
-                    .filter(|&&c| graph.contains_edge(cand, c))
-                    .copied()
-                    .collect();
-                
-                find_cliques_dfs(cand, new_candidates, graph, current_clique, 
-                               all_cliques, max_size, max_cliques, ramos_disponibles);
-                
-                current_clique.pop();
-            }
+        if nodo.clique.len() == max_size {
+            cliques_found.push(nodo.clique.to_vec());
         }
-    }
-    
-    // Ejecutar búsqueda comenzando desde cada nodo (priorizando CFGs)
-    let mut all_nodes: Vec<NodeIndex> = graph.node_indices().collect();
-    all_nodes.sort_by(|&a, &b| {
-        let is_cfg_a = filtered[graph[a].0].is_cfg;
-        let is_cfg_b = filtered[graph[b].0].is_cfg;
-        b.index().cmp(&a.index())  // Orden determinista
+        Control::Seguir
     });
     
-    let mut cliques_found: Vec<Vec<NodeIndex>> = Vec::new();
-    
-    for &start_node in &all_nodes {
-        if cliques_found.len() >= max_solutions {
-            break;
-        }
-        
-        let neighbors: Vec<NodeIndex> = graph.neighbors(start_node).collect();
-        let mut current = vec![start_node];
-        find_cliques_dfs(start_node, neighbors, &graph, &mut current, 
-                        &mut cliques_found, max_size, max_solutions, ramos_disponibles);
-    }
-    
-    eprintln!("   [EXHAUSTIVE] Encontradas {} cliques", cliques_found.len());
+    tracing::debug!("   [EXHAUSTIVE] Encontradas {} cliques", cliques_found.len());
     
     // Convertir cliques a soluciones
     for clique_nodes in cliques_found {
         let mut sol_vec: Vec<(Seccion, i32)> = Vec::new();
         let mut score = 0i64;
         
-        for &node_idx in &clique_nodes {
-            let (sec_idx, sec) = graph[node_idx];
-            let priority = if let Some(r) = ramos_disponibles.values()
-                .find(|r| r.codigo.to_uppercase() == sec.codigo.to_uppercase()) {
-                compute_priority(r, sec) as i32
+        for &sec_idx in &clique_nodes {
+            let sec = &filtered[sec_idx];
+            let priority = if let Some(r) = indice.por_codigo(&sec.codigo) {
+                compute_priority(r, sec).score(&scoring) as i32
             } else if sec.is_cfg {
-                10010150i32
+                Priority::CFG.score(&scoring) as i32
             } else {
                 0
             };
@@ -728,71 +879,164 @@ pub fn exhaustive_clique_search_with_cfg(
     }
     
     // Ordenar por score descendente
-    all_solutions.sort_by(|a, b| b.1.cmp(&a.1));
+    ordenar_canonico(&mut all_solutions);
     
-    eprintln!("   [EXHAUSTIVE] ✅ {} soluciones únicamente después de deduplicación", all_solutions.len());
+    tracing::debug!("   [EXHAUSTIVE] ✅ {} soluciones únicamente después de deduplicación", all_solutions.len());
     all_solutions
 }
 
+/// Mapea índices de una clique sobre `filtered` a la solución (Seccion + score).
+/// Las secciones que no son CFG y no se encuentran en la malla se descartan.
+fn solucion_desde_clique(
+    filtered: &[Seccion],
+    clique: &[usize],
+    indice: &RamoIndex,
+    scoring: &ScoringConfig,
+) -> (Vec<(Seccion, i32)>, i64) {
+    let mut sol: Vec<(Seccion, i32)> = Vec::new();
+    let mut total: i64 = 0;
+    for &ix in clique.iter() {
+        let s = filtered[ix].clone();
+        
+        // Los CFGs no están en ramos_disponibles, usar prioridad fija
+        if s.is_cfg {
+            let score = Priority::CFG.score(scoring);  // Prioridad competitiva
+            sol.push((s.clone(), score as i32));
+            total += score;
+        } else if let Some(r) = indice.de_seccion(&s) {
+            let score = compute_priority(r, &s).score(scoring);
+            sol.push((s.clone(), score as i32));
+            total += score;
+        }
+    }
+    (sol, total)
+}
+
+/// Presupuesto de tiempo del solver exacto: `solver_time_budget_ms`, luego
+/// `exact_solver_budget_ms` de la configuración (por defecto 2000 ms).
+fn exact_solver_budget(params: &InputParams) -> std::time::Duration {
+    let ms = params.solver_time_budget_ms
+        .unwrap_or_else(|| crate::app_config::actual().exact_solver_budget_ms);
+    std::time::Duration::from_millis(ms)
+}
+
+/// Ejecuta el branch-and-bound de `clique_exacto` sobre la matriz de
+/// compatibilidad ya filtrada. Aplica las mismas restricciones que el greedy:
+/// `reglas::max_ramos` ramos, límite de CFGs y misma `seccion` para partes de
+/// una misma materia base (cátedra/laboratorio). Con `max_creditos` se suman
+/// los créditos por sección y ese tope reemplaza al de ramos.
+fn resolver_clique_exacto(
+    filtered: &[Seccion],
+    adj: &[Vec<bool>],
+    pri: &[i64],
+    creditos: &[(String, i32)],
+    max_cfgs_permitidos: usize,
+    params: &InputParams,
+    deadline: &Deadline,
+) -> crate::algorithm::clique_exacto::ResultadoExacto {
+    let n = filtered.len();
+    let claves: Vec<String> = filtered.iter().map(|s| crate::excel::componentes::clave_base(&s.nombre)).collect();
+    let mut adj_exacto = adj.to_vec();
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if adj_exacto[i][j] && !claves[i].is_empty() && claves[i] == claves[j] && filtered[i].seccion != filtered[j].seccion {
+                adj_exacto[i][j] = false;
+                adj_exacto[j][i] = false;
+            }
+        }
+    }
+    let limitados: Vec<bool> = filtered.iter()
+        .map(|s| s.is_cfg && s.codigo.to_uppercase().starts_with("CFG"))
+        .collect();
+    let costos: Vec<i64> = creditos.iter().map(|(_, c)| *c as i64).collect();
+    let max_tam = crate::reglas::tope_ramos(params).unwrap_or(n);
+    let restriccion = params.max_creditos.map(|max| (costos.as_slice(), max as i64));
+    // El presupuesto propio del solver exacto no puede pasar el deadline de la petición
+    let presupuesto = match deadline.restante() {
+        Some(r) => exact_solver_budget(params).min(r),
+        None => exact_solver_budget(params),
+    };
+    crate::algorithm::clique_exacto::max_weight_clique_con_costos(
+        &adj_exacto, pri, &limitados, max_tam, max_cfgs_permitidos, restriccion, presupuesto,
+    )
+}
+
 pub fn get_clique_max_pond_with_prefs(
     lista_secciones: &[Seccion],
     ramos_disponibles: &HashMap<String, RamoDisponible>,
     params: &InputParams,
 ) -> Vec<(Vec<(Seccion, i32)>, i64)> {
-    // Implementación directa y concisa de "cliques reales" (greedy multi-seed).
-    eprintln!("🧠 [clique] {} secciones, {} ramos", lista_secciones.len(), ramos_disponibles.len());
-    
+    get_clique_max_pond_with_prefs_cached(lista_secciones, ramos_disponibles, params, None, &Deadline::sin_limite())
+}
+
+/// Igual que `get_clique_max_pond_with_prefs`, pero si se entrega un
+/// `CompatibilityGraph` precalculado las aristas se leen de él en vez de
+/// recalcular la matriz de adyacencia. Las secciones que no estén en el grafo
+/// se comparan directamente.
+///
+/// Los paquetes cátedra + laboratorio/ayudantía se buscan como una sola
+/// sección y se expanden en las soluciones (ver `algorithm::bundles`).
+///
+/// La búsqueda se corta al vencer `deadline`: se devuelven las soluciones
+/// encontradas hasta ese momento (ver `Deadline::alcanzado`).
+pub fn get_clique_max_pond_with_prefs_cached(
+    lista_secciones: &[Seccion],
+    ramos_disponibles: &HashMap<String, RamoDisponible>,
+    params: &InputParams,
+    grafo: Option<&CompatibilityGraph>,
+    deadline: &Deadline,
+) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    // Cada paquete cátedra + laboratorio/ayudantía entra como una sola sección
+    let (secciones, paquetes) = crate::algorithm::bundles::colapsar(lista_secciones);
+    let soluciones = crate::metrics::medir_enumerador("greedy", secciones.len(), || {
+        enumerar_greedy(&secciones, &RamoIndex::new(ramos_disponibles), params, grafo, deadline)
+    });
+    let mut soluciones = crate::algorithm::bundles::expandir(soluciones, &paquetes);
+    // El solver exacto y el respaldo exhaustivo no conocen los ramos obligatorios
+    soluciones.retain(|(sol, _)| obligatorios::incluidos(&params.ramos_obligatorios, sol));
+    soluciones
+}
+
+/// Secciones sobre las que busca `enumerar_greedy` y su matriz de compatibilidad
+pub(crate) struct GrafoBusqueda {
+    pub(crate) secciones: Vec<Seccion>,
+    pub(crate) adj: Vec<Vec<bool>>,
+}
+
+/// Filtrado de `enumerar_greedy` (ramos pasados, excluidos o con notas a
+/// evitar, horizonte, prerequisitos, filtros del usuario y cupos de CFG y
+/// electivos) y compatibilidad entre las secciones que quedan. También lo usa
+/// `?debug=graph` (ver `algorithm::diagnostico_grafo`), así que el grafo que
+/// se muestra es el mismo que se enumera.
+pub(crate) fn grafo_busqueda(
+    lista_secciones: &[Seccion],
+    indice: &RamoIndex,
+    params: &InputParams,
+    grafo: Option<&CompatibilityGraph>,
+) -> GrafoBusqueda {
     let has_filters = params.filtros.is_some();
-    eprintln!("   [DEBUG] has_filters={}, filtros={:?}", has_filters, 
+    tracing::debug!("has_filters={}, filtros={:?}", has_filters, 
               params.filtros.as_ref().map(|f| format!("UserFilters present")));
 
-    // Calcular límite de CFGs: máximo 4 CFGs en total
-    let cfgs_aprobados = params.ramos_pasados.iter()
-        .filter(|r| r.to_uppercase().starts_with("CFG"))
-        .count();
-    let max_cfgs_permitidos = 4usize.saturating_sub(cfgs_aprobados);
-    eprintln!("   [CFG-LIMIT] CFGs aprobados: {}, máximo permitido en soluciones: {}", 
+    // Calcular límites de CFGs y electivos: los que exige la malla en total (ver `reglas`)
+    let reglas = crate::reglas::de_params(params);
+    let cfgs_aprobados = crate::reglas::cfgs_aprobados(&params.ramos_pasados);
+    let max_cfgs_permitidos = reglas.max_cfg.saturating_sub(cfgs_aprobados);
+    tracing::debug!("   [CFG-LIMIT] CFGs aprobados: {}, máximo permitido en soluciones: {}", 
               cfgs_aprobados, max_cfgs_permitidos);
+    let electivos_completos = crate::reglas::electivos_aprobados(&params.ramos_pasados, indice.mapa()) >= reglas.max_electivos;
 
     // --- Filtrado inicial (semestre y ramos pasados) ---
-    let mut max_sem = 0;
-    for code in &params.ramos_pasados {
-        if let Some(r) = ramos_disponibles.values().find(|r| r.codigo == *code) {
-            if let Some(s) = r.semestre { max_sem = max_sem.max(s); }
-        }
-    }
-    let max_sem = max_sem + 2;
+    let max_sem = semestre_maximo(params, indice);
     let passed: HashSet<_> = params.ramos_pasados.iter().cloned().collect();
+    // Ramos con notas de `filtros.evitar_tags`: se excluyen como `ramos_excluidos`
+    let evitar_tags = tags::tags_a_evitar(&params.filtros);
 
     let mut filtered: Vec<Seccion> = lista_secciones.iter().filter(|s| {
         if passed.contains(&s.codigo) { return false; }  // Filtrar por código de curso, NO por codigo_box (package ID)
-        
-        // Intentar encontrar el ramo por CÓDIGO primero
-        if let Some(r) = ramos_disponibles.values().find(|r| r.codigo == s.codigo) {
-            // Encontrado por código
-            if let Some(sem) = r.semestre {
-                return sem <= max_sem;
-            } else {
-                return true; // Sin semestre especificado, permitir
-            }
-        }
-        
-        // Si no encuentra por código, intentar por NOMBRE normalizado
-        let sec_nombre_norm = normalize_name(&s.nombre);
-        if let Some(r) = ramos_disponibles.values().find(|r| {
-            normalize_name(&r.nombre) == sec_nombre_norm
-        }) {
-            // Encontrado por nombre
-            if let Some(sem) = r.semestre {
-                return sem <= max_sem;
-            } else {
-                return true; // Sin semestre especificado, permitir
-            }
-        }
-        
-        // Si NO encontramos en ramos_disponibles (ni por código ni por nombre),
-        // permitir si es una sección CFG, si no excluir
-        s.is_cfg
+        if ramo_excluido(s, &params.ramos_excluidos) { return false; }
+        if tags::ramo_evitado(s, indice, &evitar_tags) { return false; }
+        dentro_del_horizonte(s, indice, max_sem) || obligatorios::es_obligatoria(&params.ramos_obligatorios, s)
     }).cloned().collect();
 
     // Orden determinista de secciones para evitar no-determinismo por iteración
@@ -801,117 +1045,51 @@ pub fn get_clique_max_pond_with_prefs(
         let ord = ca.cmp(&cb);
         if ord != std::cmp::Ordering::Equal { ord } else { a.codigo_box.cmp(&b.codigo_box) }
     });
-    eprintln!("   Filtrado: {} secciones", filtered.len());
+    tracing::debug!("   Filtrado: {} secciones", filtered.len());
     
     // ===============================================================
     // VALIDACIÓN DE PREREQUISITOS (filtrado crítico)
     // ===============================================================
     // Excluir cualquier curso cuyo prerequisito NO esté en ramos_pasados
     // Esto es OBLIGATORIO: no permitimos recomendar cursos sin prerequisitos cumplidos
-    eprintln!("   [PREREQUISITOS] Filtrando secciones por requisitos previos...");
-    eprintln!("   [DEBUG] Ramos con requisitos cargados:");
+    tracing::debug!("   [PREREQUISITOS] Filtrando secciones por requisitos previos...");
+    tracing::debug!("Ramos con requisitos cargados:");
     
     let passed_codes_set: HashSet<String> = params.ramos_pasados
         .iter()
         .map(|s| s.to_uppercase())
         .collect();
     
-    for ramo in ramos_disponibles.values().take(10) {
+    for ramo in indice.ramos().take(10) {
         if !ramo.requisitos_ids.is_empty() {
-            eprintln!("     - {} (id={}) requiere: {:?}", ramo.nombre, ramo.id, ramo.requisitos_ids);
+            tracing::debug!("     - {} (id={}) requiere: {:?}", ramo.nombre, ramo.id, ramo.requisitos_ids);
         }
     }
     
     // PYTHON-STYLE: Filtrado de prerequisitos MENOS estricto
     // En Python, solo se filtran prerequisitos para ELECTIVOS (importancia=2)
     // Los ramos normales NO se filtran por prerequisitos
-    let filtered_with_preqs = filtered.into_iter().filter(|s| {
-        // Encontrar el ramo correspondiente a esta sección
-        if let Some(ramo) = ramos_disponibles.values().find(|r| r.codigo.to_uppercase() == s.codigo.to_uppercase()) {
-            // PYTHON-STYLE: Solo verificar prerequisitos para ELECTIVOS
-            // Los ramos normales pasan sin verificación de prerequisitos
-            if s.is_electivo {
-                // Para electivos, verificar prerequisitos (como hace Python)
-                if requisitos_cumplidos(s, ramo, ramos_disponibles, &passed_codes_set) {
-                    return true;
-                } else {
-                    eprintln!(
-                        "   ⊘ Excluyendo ELECTIVO {} (id={}) - prerequisitos no cumplidos",
-                        ramo.nombre, ramo.id
-                    );
-                    return false;
-                }
-            } else {
-                // Ramos normales: permitir SIN verificar prerequisitos (como Python)
-                return true;
-            }
-        }
-        
-        // Si no encontramos el ramo en ramos_disponibles por CÓDIGO,
-        // intentar matching por NOMBRE normalizado
-        let sec_nombre_norm = normalize_name(&s.nombre);
-        if let Some(ramo) = ramos_disponibles.values().find(|r| {
-            normalize_name(&r.nombre) == sec_nombre_norm
-        }) {
-            // PYTHON-STYLE: Solo verificar prerequisitos para ELECTIVOS
-            if s.is_electivo {
-                if requisitos_cumplidos(s, ramo, ramos_disponibles, &passed_codes_set) {
-                    return true;
-                } else {
-                    eprintln!(
-                        "   ⊘ Excluyendo ELECTIVO {} (nombre match) - prerequisitos no cumplidos",
-                        ramo.nombre
-                    );
-                    return false;
-                }
-            } else {
-                return true;
-            }
-        }
-        
-        // Si NO encontramos ni por código ni por nombre,
-        // permitir si la sección proviene de un CFG o es un electivo (lógica original)
-        if s.is_cfg {
-            eprintln!(
-                "   ✓ Permitido {} - SECCIÓN CFG no encontrada en malla pero aceptada",
-                s.codigo
-            );
-            return true;
-        }
-        
-        if s.is_electivo {
-            eprintln!(
-                "   ✓ Permitido {} - ELECTIVO DE ESPECIALIZACIÓN no encontrado en malla pero aceptado",
-                s.codigo
-            );
-            return true;
-        }
-
-        // Cursos no encontrados en malla: permitir (PYTHON-STYLE)
-        eprintln!(
-            "   ✓ Permitido {} - no encontrado en malla pero aceptado (PYTHON-STYLE)",
-            s.codigo
-        );
-        true
-    }).collect::<Vec<_>>();
+    let filtered_with_preqs = filtered.into_iter()
+        .filter(|s| prerequisitos_de_seccion_ok(s, indice, &passed_codes_set))
+        .collect::<Vec<_>>();
     
-    eprintln!("   ✓ Después de validar prerequisitos: {} secciones", filtered_with_preqs.len());
+    tracing::debug!("   ✓ Después de validar prerequisitos: {} secciones", filtered_with_preqs.len());
     let debug_cfg_count = filtered_with_preqs.iter().filter(|s| s.is_cfg).count();
     let debug_electivo_count = filtered_with_preqs.iter().filter(|s| s.is_electivo).count();
-    eprintln!("   [DEBUG] Secciones CFG después de prerequisitos: {}", debug_cfg_count);
-    eprintln!("   [DEBUG] Secciones ELECTIVOS después de prerequisitos: {}", debug_electivo_count);
+    tracing::debug!("Secciones CFG después de prerequisitos: {}", debug_cfg_count);
+    tracing::debug!("Secciones ELECTIVOS después de prerequisitos: {}", debug_electivo_count);
     let mut filtered = filtered_with_preqs;
     
     // Aplicar filtros del usuario ANTES de construir la matriz de adjacencia
     // Esto reduce drasticamente el tamaño del problema
-    eprintln!("   [PRE-FILTER] params.filtros is_some={}", params.filtros.is_some());
+    tracing::debug!("   [PRE-FILTER] params.filtros is_some={}", params.filtros.is_some());
     let mut filtered = if params.filtros.is_some() {
         let pre_filtered = filtered.into_iter().filter(|s| {
             seccion_cumple_filtros(s, &params.filtros)
         }).collect::<Vec<_>>();
-        eprintln!("   Después de filtros de usuario: {} secciones", pre_filtered.len());
+        tracing::debug!("   Después de filtros de usuario: {} secciones", pre_filtered.len());
         let debug_cfg_after = pre_filtered.iter().filter(|s| s.is_cfg).count();
-        eprintln!("   [DEBUG] Secciones CFG después de filtros de usuario: {}", debug_cfg_after);
+        tracing::debug!("Secciones CFG después de filtros de usuario: {}", debug_cfg_after);
         pre_filtered
     } else {
         filtered
@@ -919,29 +1097,88 @@ pub fn get_clique_max_pond_with_prefs(
     
     // FILTRO POR LÍMITE DE CFGs: Si el usuario ya completó su cuota de CFGs, eliminar todos los CFGs
     if max_cfgs_permitidos == 0 {
-        eprintln!("   [CFG-FILTER] Usuario ya completó 4 CFGs - removiendo todos los CFGs del pool");
+        tracing::debug!("   [CFG-FILTER] Usuario ya completó {} CFGs - removiendo todos los CFGs del pool", reglas.max_cfg);
         filtered = filtered.into_iter().filter(|s| !s.is_cfg).collect();
-        eprintln!("   Después de filtrar CFGs por límite: {} secciones", filtered.len());
+        tracing::debug!("   Después de filtrar CFGs por límite: {} secciones", filtered.len());
     }
+
+    // Igual con los electivos de especialidad (fuera de la malla o marcados como electivo en ella)
+    if electivos_completos {
+        tracing::debug!("   [ELECTIVOS] Usuario ya completó {} electivos - removiendo electivos del pool", reglas.max_electivos);
+        filtered.retain(|s| {
+            s.is_cfg || !(s.is_electivo || indice.de_seccion(s).is_some_and(|r| r.electivo))
+        });
+    }
+    
+    // --- Construir matriz de compatibilidad (adjacency) ---
+    // Si hay un grafo precalculado, reutilizar sus aristas
+    let n = filtered.len();
+    let grafo_idx: Vec<Option<usize>> = match grafo {
+        Some(g) => filtered.iter().map(|s| g.indice_de(s)).collect(),
+        None => vec![None; n],
+    };
+    if let Some(g) = grafo {
+        let hits = grafo_idx.iter().filter(|i| i.is_some()).count();
+        tracing::debug!("   [GRAPH-CACHE] reutilizando grafo de {} nodos ({}/{} secciones encontradas)", g.len(), hits, n);
+    }
+    let adj = crate::metrics::medir("adjacency", n, || {
+        let mut adj = vec![vec![false; n]; n];
+        for i in 0..n {
+            for j in (i+1)..n {
+                let compatible = match (grafo, grafo_idx[i], grafo_idx[j]) {
+                    (Some(g), Some(gi), Some(gj)) => g.compatibles(gi, gj),
+                    _ => secciones_compatibles(&filtered[i], &filtered[j]),
+                };
+                if compatible {
+                    adj[i][j] = true; adj[j][i] = true;
+                }
+            }
+        }
+        adj
+    });
+
+    GrafoBusqueda { secciones: filtered, adj }
+}
+
+/// Cuerpo de `get_clique_max_pond_with_prefs_cached` (greedy multi-seed)
+fn enumerar_greedy(
+    lista_secciones: &[Seccion],
+    indice: &RamoIndex,
+    params: &InputParams,
+    grafo: Option<&CompatibilityGraph>,
+    deadline: &Deadline,
+) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    let scoring = crate::scoring::efectiva(params);
+    // Implementación directa y concisa de "cliques reales" (greedy multi-seed).
+    tracing::info!("🧠 [clique] {} secciones, {} ramos", lista_secciones.len(), indice.len());
     
+    let GrafoBusqueda { secciones: filtered, adj } = grafo_busqueda(lista_secciones, indice, params, grafo);
+    let n = filtered.len();
+
+    let reglas = crate::reglas::de_params(params);
+    let max_cfgs_permitidos = reglas.max_cfg.saturating_sub(crate::reglas::cfgs_aprobados(&params.ramos_pasados));
+    let max_ramos = crate::reglas::max_ramos(params);
+    // Con `max_creditos` los créditos son el límite y no hay tope de ramos
+    let tope_ramos = crate::reglas::tope_ramos(params).unwrap_or(n);
+    let max_sem = semestre_maximo(params, indice);
+    let passed: HashSet<_> = params.ramos_pasados.iter().cloned().collect();
+    let passed_codes_set: HashSet<String> = params.ramos_pasados.iter().map(|s| s.to_uppercase()).collect();
+    let evitar_tags = tags::tags_a_evitar(&params.filtros);
+
     if filtered.is_empty() && params.filtros.is_some() {
-        eprintln!("   ⚠️  Todos fueron filtrados!");
+        tracing::warn!("   ⚠️  Todos fueron filtrados!");
         // FALLBACK: Si los filtros de usuario eliminaron TODAS las secciones,
         // retornar al menos una sección sin filtros de usuario para cumplir LEY FUNDAMENTAL
-        eprintln!("   [FALLBACK LEY FUNDAMENTAL] Intentando retornar sin filtros de usuario...");
+        tracing::debug!("   [FALLBACK LEY FUNDAMENTAL] Intentando retornar sin filtros de usuario...");
         
         // Revertir a las secciones antes de aplicar filtros de usuario
         let mut fallback_filtered: Vec<Seccion> = lista_secciones.iter().filter(|s| {
-            if passed.contains(&s.codigo_box) { return false; }
+            if passed.contains(&s.codigo) { return false; }
+            if ramo_excluido(s, &params.ramos_excluidos) { return false; }
+            if tags::ramo_evitado(s, indice, &evitar_tags) { return false; }
             
-            // Intentar encontrar el ramo por CÓDIGO primero
-            if let Some(r) = ramos_disponibles.values().find(|r| r.codigo == s.codigo) {
-                if let Some(sem) = r.semestre {
-                    return sem <= max_sem;
-                } else { return true; }
-            }
-            let sec_nombre_norm = normalize_name(&s.nombre);
-            if let Some(r) = ramos_disponibles.values().find(|r| normalize_name(&r.nombre) == sec_nombre_norm) {
+            // Buscar el ramo por CÓDIGO y si no por NOMBRE normalizado
+            if let Some(r) = indice.de_seccion(s) {
                 if let Some(sem) = r.semestre { return sem <= max_sem; } else { return true; }
             }
             false
@@ -949,14 +1186,11 @@ pub fn get_clique_max_pond_with_prefs(
 
         // Filtrar solo secciones que cumplen prerequisitos
         let fallback_filtered: Vec<Seccion> = fallback_filtered.into_iter().filter(|s| {
-            if let Some(r) = ramos_disponibles.values().find(|r| {
-                if !r.codigo.is_empty() && !s.codigo.is_empty() {
-                    return r.codigo == s.codigo;
-                }
-                normalize_name(&r.nombre) == normalize_name(&s.nombre)
-            }) {
+            if let Some(r) = indice.de_seccion(s) {
                 let passed_codes_set: HashSet<String> = params.ramos_pasados.iter().map(|c| c.to_uppercase()).collect();
-                return requisitos_cumplidos(s, r, ramos_disponibles, &passed_codes_set);
+                // Una sección sola no trae a sus co-requisitos: deben estar aprobados
+                return requisitos_cumplidos(s, r, indice, &passed_codes_set)
+                    && correquisitos_cumplidos(r, indice, &passed_codes_set, &HashSet::new());
             }
             false
         }).collect();
@@ -964,40 +1198,22 @@ pub fn get_clique_max_pond_with_prefs(
         if !fallback_filtered.is_empty() {
             // Retornar la primer sección viable (mejor solución sin filtros)
             let s = &fallback_filtered[0];
-            if let Some(r) = ramos_disponibles.values().find(|r| {
-                if !r.codigo.is_empty() && !s.codigo.is_empty() {
-                    if r.codigo.to_lowercase() == s.codigo.to_lowercase() { return true; }
-                }
-                normalize_name(&r.nombre) == normalize_name(&s.nombre)
-            }) {
-                let score = compute_priority(r, s);
+            if let Some(r) = indice.de_seccion(s) {
+                let score = compute_priority(r, s).score(&scoring);
                 let sol = vec![(s.clone(), score as i32)];
                 let total = score;
-                eprintln!("✅ [clique] 1 solución (fallback LEY FUNDAMENTAL - sin filtros de usuario)");
+                tracing::info!("✅ [clique] 1 solución (fallback LEY FUNDAMENTAL - sin filtros de usuario)");
                 return vec![(sol, total)];
             }
         }
     }
 
-    // --- Construir matriz de compatibilidad (adjacency) ---
-    let n = filtered.len();
-    let mut adj = vec![vec![false; n]; n];
-    for i in 0..n {
-        for j in (i+1)..n {
-            let s1 = &filtered[i];
-            let s2 = &filtered[j];
-            let code_a = &s1.codigo[..std::cmp::min(7, s1.codigo.len())];
-            let code_b = &s2.codigo[..std::cmp::min(7, s2.codigo.len())];
-            if s1.codigo_box != s2.codigo_box && code_a != code_b && !sections_conflict(s1, s2) {
-                adj[i][j] = true; adj[j][i] = true;
-            }
-        }
-    }
+
     
     // [DEBUG] Verificar conectividad de CFGs en el grafo
     let cfg_count = filtered.iter().filter(|s| s.is_cfg).count();
     if cfg_count > 0 {
-        eprintln!("   [GRAPH-DEBUG] Verificando conectividad de {} CFGs en grafo de {} nodos", cfg_count, n);
+        tracing::debug!("   [GRAPH-DEBUG] Verificando conectividad de {} CFGs en grafo de {} nodos", cfg_count, n);
         let mut cfgs_with_edges = 0;
         for i in 0..n {
             if filtered[i].is_cfg {
@@ -1006,11 +1222,11 @@ pub fn get_clique_max_pond_with_prefs(
                     cfgs_with_edges += 1;
                 }
                 if edge_count == 0 {
-                    eprintln!("      ⚠ CFG {} NO tiene edges (aislado)", filtered[i].codigo);
+                    tracing::warn!("      ⚠ CFG {} NO tiene edges (aislado)", filtered[i].codigo);
                 }
             }
         }
-        eprintln!("   [GRAPH-DEBUG] {}/{} CFGs tienen al menos 1 edge", cfgs_with_edges, cfg_count);
+        tracing::debug!("   [GRAPH-DEBUG] {}/{} CFGs tienen al menos 1 edge", cfgs_with_edges, cfg_count);
     }
 
     // --- Prioridades por sección (resolver RamoDisponible por código o nombre normalizado) ---
@@ -1023,27 +1239,25 @@ pub fn get_clique_max_pond_with_prefs(
     // Bonus MASIVO para ramos prioritarios: 1_000_000_000 (mil millones)
     // Esto garantiza que SIEMPRE se seleccionen primero durante la construcción greedy
     const USER_PRIORITY_BONUS: i64 = 1_000_000_000;
+    // Y uno mayor para las secciones fijas, por sobre los ramos prioritarios
+    const SECCION_FIJA_BONUS: i64 = 10 * USER_PRIORITY_BONUS;
+    // Los ramos obligatorios van después de las fijas y antes que los prioritarios
+    const RAMO_OBLIGATORIO_BONUS: i64 = 5 * USER_PRIORITY_BONUS;
     
     let mut pri: Vec<i64> = Vec::with_capacity(n);
     for s in filtered.iter() {
-        let candidate = ramos_disponibles.values().find(|r| {
-            if !r.codigo.is_empty() && !s.codigo.is_empty() {
-                if r.codigo.to_lowercase() == s.codigo.to_lowercase() { return true; }
-            }
-            normalize_name(&r.nombre) == normalize_name(&s.nombre)
-        });
-        let mut p = match candidate {
-            Some(r) => compute_priority(r, s),
+        let mut p = match indice.de_seccion(s) {
+            Some(r) => compute_priority(r, s).score(&scoring),
             None if s.is_cfg => {
                 // CFG sin entrada en malla: asignar prioridad similar a cursos de 3er semestre
-                eprintln!("   [DEBUG] CFG {} sin entrada en malla, asignando prioridad competitiva", s.codigo);
-                10010150i64  // Similar a un curso no crítico, holgura media-baja, correlativo bajo
+                tracing::debug!("CFG {} sin entrada en malla, asignando prioridad competitiva", s.codigo);
+                Priority::CFG.score(&scoring)  // Similar a un curso no crítico, holgura media-baja, correlativo bajo
             },
             None if s.is_electivo => {
                 // ELECTIVO DE CARRERA: prioridad más baja que obligatorios pero válida
                 // Prioridad base: 00 05 30 00 (no crítico, holgura alta, correlativo medio)
-                eprintln!("   [DEBUG] ELECTIVO {} sin entrada en malla, asignando prioridad de electivo", s.codigo);
-                53000i64  // Prioridad más baja que cursos obligatorios pero mayor que 0
+                tracing::debug!("ELECTIVO {} sin entrada en malla, asignando prioridad de electivo", s.codigo);
+                Priority::ELECTIVO.score(&scoring)  // Prioridad más baja que cursos obligatorios pero mayor que 0
             },
             None => 0,
         };
@@ -1052,9 +1266,21 @@ pub fn get_clique_max_pond_with_prefs(
         let sec_code_norm = normalize_name(&s.codigo);
         let sec_name_norm = normalize_name(&s.nombre);
         if priority_codes.contains(&sec_code_norm) || priority_codes.contains(&sec_name_norm) {
-            eprintln!("   [PRIORITY] 🌟 Ramo prioritario detectado: {} - Bonus +{}", s.codigo, USER_PRIORITY_BONUS);
+            tracing::debug!("   [PRIORITY] 🌟 Ramo prioritario detectado: {} - Bonus +{}", s.codigo, USER_PRIORITY_BONUS);
             p += USER_PRIORITY_BONUS;
         }
+
+        // Las secciones fijas se eligen antes que cualquier otra (ver `algorithm::fijas`)
+        if params.secciones_fijas.iter().any(|f| f.eq_ignore_ascii_case(&s.codigo_box)) {
+            p += SECCION_FIJA_BONUS;
+        }
+        if obligatorios::es_obligatoria(&params.ramos_obligatorios, s) {
+            p += RAMO_OBLIGATORIO_BONUS;
+        }
+        // Los reprobados se retoman antes que el resto de la malla
+        if es_reprobado(params, s) {
+            p += scoring.bonus_ramo_reprobado;
+        }
         
         pri.push(p);
     }
@@ -1062,11 +1288,38 @@ pub fn get_clique_max_pond_with_prefs(
     // Log de ramos prioritarios encontrados
     if !params.ramos_prioritarios.is_empty() {
         let found_count = pri.iter().filter(|&&p| p >= USER_PRIORITY_BONUS).count();
-        eprintln!("   [PRIORITY] {} ramos prioritarios solicitados, {} encontrados en secciones viables", 
+        tracing::debug!("   [PRIORITY] {} ramos prioritarios solicitados, {} encontrados en secciones viables", 
                   params.ramos_prioritarios.len(), found_count);
     }
 
-    // --- Greedy multi-seed to build real cliques with max 6 courses ---
+    // --- Créditos SCT por sección (solo se usan con `max_creditos`) ---
+    let creditos: Vec<(String, i32)> = filtered.iter().map(|s| clave_y_creditos(s, indice)).collect();
+    if let Some(max) = params.max_creditos {
+        tracing::debug!("   [CREDITOS] tope de {} SCT por solución (en vez de {} ramos)", max, max_ramos);
+    }
+
+    // Ninguna solución puede superar esta cota; el modo rápido la usa para
+    // estimar cuánto le falta al greedy
+    deadline.registrar_cota_optimista(cota_optimista(&filtered, indice, params, &scoring, tope_ramos));
+
+    // --- Solver exacto (opt-in): clique de peso máximo por branch-and-bound ---
+    let solucion_exacta = if params.solver == SolverMode::Exact && n > 0 {
+        let res = crate::metrics::medir("exact", n, || {
+            resolver_clique_exacto(&filtered, &adj, &pri, &creditos, max_cfgs_permitidos, params, deadline)
+        });
+        crate::metrics::observar(crate::metrics::SOLUCIONES, "exact", if res.clique.is_empty() { 0.0 } else { 1.0 });
+        tracing::debug!("   [EXACT] peso={} ramos={} nodos={} {}ms {}",
+                  res.peso, res.clique.len(), res.nodos_explorados, res.elapsed_ms,
+                  if res.optimo { "(óptimo)" } else { "(presupuesto agotado, mejor encontrada)" });
+        let mut clique = res.clique.clone();
+        podar_correquisitos(&filtered, &mut clique, indice, &passed_codes_set);
+        let (sol, total) = solucion_desde_clique(&filtered, &clique, indice, &scoring);
+        if sol.is_empty() { None } else { Some((sol.clone(), apply_optimization_modifiers(total, &sol, params, &scoring))) }
+    } else {
+        None
+    };
+
+    // --- Greedy multi-seed to build real cliques with max 6 courses (o max_creditos) ---
     // ESTRATEGIA OPTIMIZADA: Solo generar soluciones que MAXIMIZAN cursos (respetando PERT criticidad)
     // Si encontramos soluciones con 6 cursos -> guardar y seguir buscando DIFERENTES de 6
     // Detener cuando tengamos 10 soluciones con 6 cursos cada una
@@ -1074,25 +1327,20 @@ pub fn get_clique_max_pond_with_prefs(
     let mut cfg_selected_as_seed_count = 0;  // Contador de CFGs seleccionados como seed
     
     // FALLBACK para 1 sección: retornar como solución única (LEY FUNDAMENTAL)
-    if n == 1 {
-        eprintln!("   [DEBUG] Solo 1 sección viable. Retornando como solución única.");
+    if n == 1 && primera_sin_correquisitos(&filtered, &[0], indice, &passed_codes_set).is_none() {
+        tracing::debug!("Solo 1 sección viable. Retornando como solución única.");
         let s = filtered[0].clone();
-        if let Some(r) = ramos_disponibles.values().find(|r| {
-            if !r.codigo.is_empty() && !s.codigo.is_empty() {
-                if r.codigo.to_lowercase() == s.codigo.to_lowercase() { return true; }
-            }
-            normalize_name(&r.nombre) == normalize_name(&s.nombre)
-        }) {
-            let score = compute_priority(r, &s);
+        if let Some(r) = indice.de_seccion(&s) {
+            let score = compute_priority(r, &s).score(&scoring);
             let sol = vec![(s.clone(), score as i32)];
             let total = score;
             all_solutions.push((sol, total));
-            eprintln!("✅ [clique] 1 solución (fallback para 1 sección viable)");
+            tracing::info!("✅ [clique] 1 solución (fallback para 1 sección viable)");
             return all_solutions;
         }
     }
     
-    let should_allow_reuse = n < 6;  // Si hay menos de 6 secciones viables, permitir reutilización
+    let should_allow_reuse = n < max_ramos;  // Si hay menos secciones viables que el tope, permitir reutilización
     // OPTIMIZACIÓN PYTHON-STYLE: Más iteraciones para generar más soluciones
     // Con la estrategia de eliminar solo el nodo de menor prioridad, necesitamos más iteraciones
     // porque cada iteración solo elimina 1 nodo (vs todos los nodos de la solución)
@@ -1105,14 +1353,18 @@ pub fn get_clique_max_pond_with_prefs(
         std::cmp::min(computed, 10000usize)  // Límite máximo aumentado
     };
 
-    eprintln!("   [DEBUG] n={}, should_allow_reuse={}, max_iterations={} (PYTHON-STRATEGY)", n, should_allow_reuse, max_iterations);
+    tracing::debug!("n={}, should_allow_reuse={}, max_iterations={} (PYTHON-STRATEGY)", n, should_allow_reuse, max_iterations);
     
     let mut remaining_indices: HashSet<usize> = (0..n).collect();
     let mut consecutive_empty_resets = 0;
     
     for _iteration in 0..max_iterations {
         // CAMBIO: Sin límites artificiales - generar TODAS las soluciones posibles
-        // El límite se aplica solo por agotamiento del espacio de búsqueda o max_iterations
+        // El límite se aplica solo por agotamiento del espacio de búsqueda, max_iterations o el deadline
+        if deadline.vencido() {
+            tracing::warn!("⏱️  [clique] deadline alcanzado tras {} soluciones greedy; se devuelven parciales", all_solutions.len());
+            break;
+        }
         
         if remaining_indices.is_empty() {
             // Si permitimos reutilización y no hay más nodos únicos, reinicializar
@@ -1132,7 +1384,10 @@ pub fn get_clique_max_pond_with_prefs(
         // Ordenar por prioridad dentro de índices restantes
         let mut candidates: Vec<usize> = remaining_indices.iter().copied().collect();
         // Orden determinista: primero por prioridad descendente, luego por índice ascendente
-        candidates.sort_by(|&i, &j| pri[j].cmp(&pri[i]).then(i.cmp(&j)));
+        // (o por el orden que fija `diversity_seed`)
+        candidates.sort_by(|&i, &j| pri[j].cmp(&pri[i])
+            .then(clave_desempate(params.diversity_seed, i).cmp(&clave_desempate(params.diversity_seed, j)))
+            .then(i.cmp(&j)));
         
         if candidates.is_empty() {
             break;
@@ -1143,7 +1398,7 @@ pub fn get_clique_max_pond_with_prefs(
         // [DEBUG] Track si el seed es CFG
         if filtered[seed_idx].is_cfg {
             cfg_selected_as_seed_count += 1;
-            eprintln!("      [GREEDY-SEED] CFG {} seleccionado como seed (#{} vez)", filtered[seed_idx].codigo, cfg_selected_as_seed_count);
+            tracing::debug!("      [GREEDY-SEED] CFG {} seleccionado como seed (#{} vez)", filtered[seed_idx].codigo, cfg_selected_as_seed_count);
         }
         
         // VALIDAR que el seed cumple filtros Y requisitos previos
@@ -1153,7 +1408,7 @@ pub fn get_clique_max_pond_with_prefs(
         }
         
         // Construir set base de cursos ya aprobados (solo `ramos_pasados`) —STRICT: no permitimos
-        // que la propia solución satisfaga prerequisitos (sin co-requisitos).
+        // que la propia solución satisfaga prerequisitos (los co-requisitos se revisan al cerrar la clique).
         let base_passed_codes: HashSet<String> = params.ramos_pasados.iter()
             .map(|s| s.to_uppercase())
             .collect();
@@ -1161,20 +1416,25 @@ pub fn get_clique_max_pond_with_prefs(
         // PYTHON-STYLE: Solo verificar requisitos del seed si es ELECTIVO
         // Los CFGs no tienen prerequisitos, saltar validación (lógica original)
         // Los ramos normales tampoco verifican prerequisitos (como Python)
-        if !filtered[seed_idx].is_cfg && filtered[seed_idx].is_electivo {
-            if let Some(seed_ramo) = ramos_disponibles.values().find(|r| r.codigo == filtered[seed_idx].codigo) {
-                if !requisitos_cumplidos(&filtered[seed_idx], seed_ramo, ramos_disponibles, &base_passed_codes) {
-                    remaining_indices.remove(&seed_idx);
-                    continue;
-                }
-            }
+        if !filtered[seed_idx].is_cfg && filtered[seed_idx].is_electivo
+            && let Some(seed_ramo) = indice.por_codigo(&filtered[seed_idx].codigo)
+            && !requisitos_cumplidos(&filtered[seed_idx], seed_ramo, indice, &base_passed_codes)
+        {
+            remaining_indices.remove(&seed_idx);
+            continue;
         }
         
+        // Un seed que por sí solo supera el tope de créditos no puede iniciar una solución
+        if params.max_creditos.is_some_and(|max| creditos[seed_idx].1 > max) {
+            remaining_indices.remove(&seed_idx);
+            continue;
+        }
+
         let mut clique: Vec<usize> = vec![seed_idx];
         
-        // Greedy: agregar candidatos conectados a todos en la clique, max 6
+        // Greedy: agregar candidatos conectados a todos en la clique, max 6 (o max_creditos)
         for &cand in candidates.iter().skip(1) {
-            if clique.len() >= 6 {
+            if clique.len() >= tope_ramos {
                 break;
             }
             if !remaining_indices.contains(&cand) {
@@ -1203,26 +1463,20 @@ pub fn get_clique_max_pond_with_prefs(
                     }
                 // PYTHON-STYLE: Solo verificar requisitos para ELECTIVOS
                 // Los ramos normales pasan sin verificación (como en Python)
-                if filtered[cand].is_electivo && !filtered[cand].is_cfg {
-                    let mut prereq_ok = true;
-                    if let Some(cand_ramo) = ramos_disponibles.values().find(|r| r.codigo == filtered[cand].codigo) {
-                        if !requisitos_cumplidos(&filtered[cand], cand_ramo, ramos_disponibles, &base_passed_codes) {
-                            prereq_ok = false;
-                        }
-                    }
-                    
-                    if !prereq_ok {
-                        continue;
-                    }
+                if filtered[cand].is_electivo && !filtered[cand].is_cfg
+                    && let Some(cand_ramo) = indice.por_codigo(&filtered[cand].codigo)
+                    && !requisitos_cumplidos(&filtered[cand], cand_ramo, indice, &base_passed_codes)
+                {
+                    continue;
                 }
                 
                 // Además: si cand y algún u pertenecen a la misma materia base,
                 // exigir que pertenezcan a la misma `seccion` (emparejar laboratorios/talleres)
                 let mut conflict = false;
-                let cand_key = base_course_key(&filtered[cand].nombre);
+                let cand_key = crate::excel::componentes::clave_base(&filtered[cand].nombre);
                 let cand_seccion = filtered[cand].seccion.clone();
                 for &u in clique.iter() {
-                    let u_key = base_course_key(&filtered[u].nombre);
+                    let u_key = crate::excel::componentes::clave_base(&filtered[u].nombre);
                     let u_seccion = &filtered[u].seccion;
                     if !cand_key.is_empty() && cand_key == u_key {
                         if u_seccion != &cand_seccion {
@@ -1231,36 +1485,25 @@ pub fn get_clique_max_pond_with_prefs(
                         }
                     }
                 }
-                if !conflict {
+                let excede_creditos = params.max_creditos.is_some_and(|max| {
+                    suma_creditos(clique.iter().chain(std::iter::once(&cand)).map(|&i| &creditos[i])) > max
+                });
+                let excede_dia = carga_diaria::tope_por_dia(&params.filtros).is_some_and(|t| {
+                    carga_diaria::excede_ramos_por_dia(clique.iter().chain(std::iter::once(&cand)).map(|&i| &filtered[i]), t)
+                });
+                if !conflict && !excede_creditos && !excede_dia {
                     clique.push(cand);
                 }
             }
         }
 
+        // Co-requisitos: cada ramo debe tener los suyos aprobados o dentro de la clique
+        podar_correquisitos(&filtered, &mut clique, indice, &base_passed_codes);
+
         // mapear clique a solución (Seccion + score)
-        let mut sol: Vec<(Seccion, i32)> = Vec::new();
-        let mut total: i64 = 0;
-        for &ix in clique.iter() {
-            let s = filtered[ix].clone();
-            
-            // Los CFGs no están en ramos_disponibles, usar prioridad fija
-            if s.is_cfg {
-                let score = 10010150i64;  // Prioridad competitiva
-                sol.push((s.clone(), score as i32));
-                total += score;
-            } else if let Some(r) = ramos_disponibles.values().find(|r| {
-                if !r.codigo.is_empty() && !s.codigo.is_empty() {
-                    if r.codigo.to_lowercase() == s.codigo.to_lowercase() { return true; }
-                }
-                normalize_name(&r.nombre) == normalize_name(&s.nombre)
-            }) {
-                let score = compute_priority(r, &s);
-                sol.push((s.clone(), score as i32));
-                total += score;
-            }
-        }
+        let (sol, total) = solucion_desde_clique(&filtered, &clique, indice, &scoring);
         
-        if !sol.is_empty() {
+        if !sol.is_empty() && obligatorios::incluidos(&params.ramos_obligatorios, &sol) {
             // Verificar que no es solución duplicada (comparar por `codigo_box` de las secciones
             // para permitir variaciones de sección dentro del mismo ramo)
             let sol_section_keys: Vec<String> = sol.iter().map(|(s, _)| s.codigo_box.clone()).collect();
@@ -1274,8 +1517,9 @@ pub fn get_clique_max_pond_with_prefs(
 
             if !is_duplicate {
                 // Aplicar modificadores de optimización ANTES de guardar
-                let optimized_total = apply_optimization_modifiers(total, &sol, params);
+                let optimized_total = apply_optimization_modifiers(total, &sol, params, &scoring);
                 all_solutions.push((sol.clone(), optimized_total));
+                deadline.registrar_clique();
                 consecutive_empty_resets = 0;  // Reset el contador
                 
                 // ESTRATEGIA PYTHON: Eliminar SOLO el nodo de menor prioridad de la solución
@@ -1287,7 +1531,7 @@ pub fn get_clique_max_pond_with_prefs(
                         .copied()
                         .unwrap_or(seed_idx);
                     remaining_indices.remove(&min_pri_idx);
-                    eprintln!("   [PYTHON-STRATEGY] Removiendo nodo de menor prioridad: {} (pri={})", 
+                    tracing::debug!("   [PYTHON-STRATEGY] Removiendo nodo de menor prioridad: {} (pri={})", 
                               filtered[min_pri_idx].codigo, pri[min_pri_idx]);
                 }
             } else {
@@ -1301,12 +1545,19 @@ pub fn get_clique_max_pond_with_prefs(
 
     // Si la búsqueda greedy no produjo suficientes soluciones, usar el enumerador
     // exhaustivo como fallback para aumentar diversidad (hasta 15 soluciones para garantizar 10).
-    eprintln!("   [GREEDY-SUMMARY] CFG seeds seleccionados: {}", cfg_selected_as_seed_count);
+    tracing::debug!("   [GREEDY-SUMMARY] CFG seeds seleccionados: {}", cfg_selected_as_seed_count);
     
-    if all_solutions.len() < 5 {
-        eprintln!("   [FALLBACK] Solo {} soluciones desde greedy; ejecutando enumerador exhaustivo para aumentar diversidad...", all_solutions.len());
+    // El modo rápido se queda con lo que encontró el greedy
+    if all_solutions.len() < 5 && params.solver != SolverMode::Rapido && !deadline.vencido() {
+        tracing::debug!("   [FALLBACK] Solo {} soluciones desde greedy; ejecutando enumerador exhaustivo para aumentar diversidad...", all_solutions.len());
         // Generar combinaciones adicionales (limit aumentado para garantizar 10+)
-        let mut extras = get_all_clique_combinations_with_pert(&filtered, ramos_disponibles, params, 6usize, 5000usize);
+        let mut extras = combinaciones_hasta(&filtered, indice, params, tope_ramos, 5000usize, deadline);
+        if let Some(max) = params.max_creditos {
+            extras.retain(|(sol, _)| {
+                let pares: Vec<(String, i32)> = sol.iter().map(|(s, _)| clave_y_creditos(s, indice)).collect();
+                suma_creditos(pares.iter()) <= max
+            });
+        }
         // Mezclar sin duplicados (comparando por codigo_box ordenado)
         for (sol, total) in extras.drain(..) {
             let mut keys: Vec<String> = sol.iter().map(|(s, _)| s.codigo_box.clone()).collect();
@@ -1325,11 +1576,11 @@ pub fn get_clique_max_pond_with_prefs(
             }
             // CAMBIO: Sin límite artificial de 15
         }
-        eprintln!("   [FALLBACK] now have {} solutions after merging extras", all_solutions.len());
+        tracing::debug!("   [FALLBACK] now have {} solutions after merging extras", all_solutions.len());
     }
 
     // ordenar por score y aplicar estrategia de OPTIMIZACIÓN
-    all_solutions.sort_by(|a, b| b.1.cmp(&a.1));
+    ordenar_canonico(&mut all_solutions);
     
     // ESTRATEGIA DE FILTRADO INTELIGENTE:
     // SIN FILTROS: Solo retornar soluciones óptimas (máximo tamaño)
@@ -1344,32 +1595,45 @@ pub fn get_clique_max_pond_with_prefs(
         let optimal_count = optimal.len();
         
         all_solutions = optimal;
-        eprintln!("✅ [clique] {} soluciones (max {} ramos, sin filtros = TODAS óptimas)", 
+        tracing::info!("✅ [clique] {} soluciones (max {} ramos, sin filtros = TODAS óptimas)", 
                   all_solutions.len(), max_size);
     } else {
         // CON FILTROS: Aplicar estrategia mixta (óptimas + subóptimas si es necesario)
-        let has_six_course_solutions = all_solutions.iter().any(|(sol, _)| sol.len() == 6);
+        let has_six_course_solutions = all_solutions.iter().any(|(sol, _)| sol.len() == max_ramos);
         if has_six_course_solutions {
             // Separar soluciones óptimas y subóptimas
-            let optimal: Vec<_> = all_solutions.iter().cloned().filter(|(sol, _)| sol.len() == 6).collect();
-            let mut suboptimal: Vec<_> = all_solutions.iter().cloned().filter(|(sol, _)| sol.len() != 6).collect();
+            let optimal: Vec<_> = all_solutions.iter().filter(|(sol, _)| sol.len() == max_ramos).cloned().collect();
+            let mut suboptimal: Vec<_> = all_solutions.iter().filter(|(sol, _)| sol.len() != max_ramos).cloned().collect();
             let optimal_count = optimal.len();
             
             // CAMBIO: Retornar TODAS las soluciones óptimas (sin límite artificial)
             let mut result = optimal;
             // Complementar con subóptimas para máxima diversidad
-            suboptimal.sort_by(|a, b| b.1.cmp(&a.1));  // Ordenar subóptimas por score
+            ordenar_canonico(&mut suboptimal);  // Ordenar subóptimas por score
             for (sol, score) in suboptimal {
                 result.push((sol, score));
             }
-            eprintln!("✅ [clique] {} soluciones TOTALES ({} óptimas + {} subóptimas)", 
+            tracing::info!("✅ [clique] {} soluciones TOTALES ({} óptimas + {} subóptimas)", 
                       result.len(), optimal_count, result.len() - optimal_count);
             all_solutions = result;
         } else {
             // Si no hay soluciones con 6 cursos, mantener TODAS
-            eprintln!("✅ [clique] {} soluciones (max_weight_clique, max 6 ramos, sin 6-ramo solutions)", all_solutions.len());
+            tracing::info!("✅ [clique] {} soluciones (max_weight_clique, max 6 ramos, sin 6-ramo solutions)", all_solutions.len());
         }
     }
+
+    // Con `solver: "exact"` la solución de peso máximo va primera; el resto
+    // de las soluciones greedy se mantiene como alternativas.
+    if let Some((sol, total)) = solucion_exacta {
+        let mut claves: Vec<&str> = sol.iter().map(|(s, _)| s.codigo_box.as_str()).collect();
+        claves.sort_unstable();
+        all_solutions.retain(|(prev, _)| {
+            let mut prev_claves: Vec<&str> = prev.iter().map(|(s, _)| s.codigo_box.as_str()).collect();
+            prev_claves.sort_unstable();
+            prev_claves != claves
+        });
+        all_solutions.insert(0, (sol, total));
+    }
     
     all_solutions
 }
@@ -1379,28 +1643,42 @@ pub fn get_clique_with_user_prefs(
     lista_secciones: &[Seccion],
     ramos_disponibles: &HashMap<String, RamoDisponible>,
     params: &InputParams,
+) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    get_clique_with_user_prefs_hasta(lista_secciones, ramos_disponibles, params, &Deadline::sin_limite())
+}
+
+/// `get_clique_with_user_prefs` que corta la enumeración al vencer `deadline`
+pub fn get_clique_with_user_prefs_hasta(
+    lista_secciones: &[Seccion],
+    ramos_disponibles: &HashMap<String, RamoDisponible>,
+    params: &InputParams,
+    deadline: &Deadline,
 ) -> Vec<(Vec<(Seccion, i32)>, i64)> {
     // DETERMINISMO + OPTIMALIDAD: Usar enumerador exhaustivo con límite MUY alto
     // para capturar TODAS las combinaciones válidas y retornar TOP 50
-    let max_size = 6usize;
-    let n_secciones = lista_secciones.len();
+    let max_size = crate::reglas::max_ramos(params);
+    let (secciones, paquetes) = crate::algorithm::bundles::colapsar(lista_secciones);
+    let n_secciones = secciones.len();
     
     // CAMBIO CRÍTICO: limit = 50,000 para garantizar captura de todas las cliques
     // Con 6 ramos × 20 secciones = 120 secciones, C(120,6) = 1.5B teórico
     // Pero filtrado por no-conflictos + 1 por ramo = ~5K-50K máximo realista
     let limit = 50_000usize;
     
-    eprintln!("   [CLIQUE-DETERMINISM] secciones={}, limit={} (TOP 50 ENUMERATOR)", n_secciones, limit);
-    eprintln!("   [GUARANTEE] Garantía: Enumeración exhaustiva retorna TOP 50 óptimos + subóptimos");
+    tracing::debug!("   [CLIQUE-DETERMINISM] secciones={}, limit={} (TOP 50 ENUMERATOR)", n_secciones, limit);
+    tracing::debug!("   [GUARANTEE] Garantía: Enumeración exhaustiva retorna TOP 50 óptimos + subóptimos");
     
-    let mut results = get_all_clique_combinations_with_pert(lista_secciones, ramos_disponibles, params, max_size, limit);
+    let results = crate::metrics::medir_enumerador("exhaustive", n_secciones, || {
+        get_all_clique_combinations_hasta(&secciones, ramos_disponibles, params, max_size, limit, deadline)
+    });
+    let mut results = crate::algorithm::bundles::expandir(results, &paquetes);
     
-    // DETERMINISMO: Ordenar por score DESC, sin desempate (mostrar TODOS los empatados)
+    // DETERMINISMO: Ordenar por score DESC y los empates por `codigo_box` (mostrar TODOS los empatados)
     // Esto permite ver múltiples soluciones con el mismo score
-    results.sort_by(|a, b| b.1.cmp(&a.1)); // Score descendente (óptimos primero)
+    ordenar_canonico(&mut results); // Score descendente (óptimos primero)
     
     // CAMBIO: Retornar TODAS las soluciones (sin truncar a 50)
-    eprintln!("✅ [DETERMINISM] Retornando TODAS {} soluciones", results.len());
+    tracing::info!("✅ [DETERMINISM] Retornando TODAS {} soluciones", results.len());
     results
 }
 
@@ -1413,7 +1691,7 @@ pub fn get_clique_max_pond_with_prefs_extended(
 ) -> Vec<(Vec<(Seccion, i32)>, i64)> {
     // Simplemente reutilizar la función principal pero con más iteraciones
     // Modificar internamente el comportamiento del clique
-    eprintln!("   [DEBUG] get_clique_max_pond_with_prefs_extended: max_iterations={}", max_iterations_override);
+    tracing::debug!("get_clique_max_pond_with_prefs_extended: max_iterations={}", max_iterations_override);
     
     // Por ahora, llamar a la función normal que ya usa dinámicamente las iteraciones
     get_clique_max_pond_with_prefs(lista_secciones, ramos_disponibles, params)
@@ -1446,11 +1724,12 @@ pub fn get_clique_dependencies_only(
 fn enumerate_cliques_with_cfg_priority(
     filtered: &Vec<Seccion>,
     adj: &Vec<Vec<bool>>,
-    ramos_disponibles: &HashMap<String, RamoDisponible>,
+    indice: &RamoIndex,
     params: &InputParams,
     max_size: usize,
     limit: usize,
 ) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    let scoring = crate::scoring::efectiva(params);
     let n = filtered.len();
     let mut results: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::new();
     let mut seen: HashSet<String> = HashSet::new();
@@ -1458,16 +1737,10 @@ fn enumerate_cliques_with_cfg_priority(
     // Precompute priorities
     let mut pri_cache: Vec<i64> = Vec::with_capacity(n);
     for s in filtered.iter() {
-        let candidate = ramos_disponibles.values().find(|r| {
-            if !r.codigo.is_empty() && !s.codigo.is_empty() {
-                if r.codigo.to_lowercase() == s.codigo.to_lowercase() { return true; }
-            }
-            normalize_name(&r.nombre) == normalize_name(&s.nombre)
-        });
-        let p = match candidate {
-            Some(r) => compute_priority(r, s),
-            None if s.is_cfg => 10010150i64,
-            None if s.is_electivo => 53000i64,
+        let p = match indice.de_seccion(s) {
+            Some(r) => compute_priority(r, s).score(&scoring),
+            None if s.is_cfg => Priority::CFG.score(&scoring),
+            None if s.is_electivo => Priority::ELECTIVO.score(&scoring),
             None => 0,
         };
         pri_cache.push(p);
@@ -1477,7 +1750,7 @@ fn enumerate_cliques_with_cfg_priority(
     let cfg_indices: Vec<usize> = (0..n).filter(|&i| filtered[i].is_cfg).collect();
     let non_cfg_indices: Vec<usize> = (0..n).filter(|&i| !filtered[i].is_cfg).collect();
     
-    eprintln!("   [CFG-PRIORITY] {} CFGs, {} no-CFGs", cfg_indices.len(), non_cfg_indices.len());
+    tracing::debug!("   [CFG-PRIORITY] {} CFGs, {} no-CFGs", cfg_indices.len(), non_cfg_indices.len());
 
     // Estrategia 1: Empezar búsqueda desde CADA CFG como seed
     for &cfg_seed in &cfg_indices {
@@ -1485,7 +1758,7 @@ fn enumerate_cliques_with_cfg_priority(
             break;
         }
 
-        eprintln!("   [CFG-SEED] Partiendo de CFG en índice {} ({})", cfg_seed, filtered[cfg_seed].codigo);
+        tracing::debug!("   [CFG-SEED] Partiendo de CFG en índice {} ({})", cfg_seed, filtered[cfg_seed].codigo);
         
         // Encuentra vecinos compatibles con este CFG
         let mut compatible: Vec<usize> = (0..n)
@@ -1519,11 +1792,10 @@ fn enumerate_cliques_with_cfg_priority(
         let mut total: i64 = 0;
         for &ix in &current {
             let s = filtered[ix].clone();
-            let priority = if let Some(r) = ramos_disponibles.values()
-                .find(|r| r.codigo.to_uppercase() == s.codigo.to_uppercase()) {
-                compute_priority(r, &s) as i32
+            let priority = if let Some(r) = indice.por_codigo(&s.codigo) {
+                compute_priority(r, &s).score(&scoring) as i32
             } else if s.is_cfg {
-                10010150i32
+                Priority::CFG.score(&scoring) as i32
             } else {
                 0
             };
@@ -1532,7 +1804,7 @@ fn enumerate_cliques_with_cfg_priority(
         }
 
         // Aplicar optimizaciones
-        let optimized_total = apply_optimization_modifiers(total, &sol, params);
+        let optimized_total = apply_optimization_modifiers(total, &sol, params, &scoring);
 
         // Verificar duplicado
         let mut keys: Vec<String> = sol.iter().map(|(s, _)| s.codigo_box.clone()).collect();
@@ -1545,346 +1817,218 @@ fn enumerate_cliques_with_cfg_priority(
         }
     }
 
-    eprintln!("   [CFG-PRIORITY] {} soluciones generadas desde CFG seeds", results.len());
+    tracing::debug!("   [CFG-PRIORITY] {} soluciones generadas desde CFG seeds", results.len());
     results
 }
 
-/// Backtracking enumerator: genera combinaciones compatibles (cliques) hasta `max_size`.
-/// - `limit` evita explosión combinatoria.
-fn enumerate_clique_combinations(
-    filtered: &Vec<Seccion>,
-    adj: &Vec<Vec<bool>>,
-    ramos_disponibles: &HashMap<String, RamoDisponible>,
-    params: &InputParams,
-    max_size: usize,
-    limit: usize,
-) -> Vec<(Vec<(Seccion, i32)>, i64)> {
-    let n = filtered.len();
-    let mut results: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::new();
-    let mut seen: HashSet<String> = HashSet::new();
-
-    // Precompute candidate priorities to speed scoring
-    let mut pri_cache: Vec<i64> = Vec::with_capacity(n);
-    for s in filtered.iter() {
-        let candidate = ramos_disponibles.values().find(|r| {
-            if !r.codigo.is_empty() && !s.codigo.is_empty() {
-                if r.codigo.to_lowercase() == s.codigo.to_lowercase() { return true; }
-            }
-            normalize_name(&r.nombre) == normalize_name(&s.nombre)
-        });
-        let p = match candidate {
-            Some(r) => compute_priority(r, s),
-            None if s.is_cfg => {
-                // CFG sin entrada en malla: asignar prioridad similar a cursos de 3er semestre
-                10010150i64
-            },
-            None if s.is_electivo => {
-                // ELECTIVO: prioridad más baja
-                53000i64
-            },
+/// Prioridad de cada sección de `filtered` para ordenar la enumeración; los
+/// CFG y electivos sin ramo en la malla usan su prioridad fija
+fn prioridades_enumeracion(
+    filtered: &[Seccion],
+    indice: &RamoIndex,
+    scoring: &ScoringConfig,
+) -> Vec<i64> {
+    filtered.iter().map(|s| {
+        match indice.de_seccion(s) {
+            Some(r) => compute_priority(r, s).score(scoring),
+            None if s.is_cfg => Priority::CFG.score(scoring),
+            None if s.is_electivo => Priority::ELECTIVO.score(scoring),
             None => 0,
-        };
-        pri_cache.push(p);
-    }
-
-    // Build an order vector of indices sorted by priority desc (tie: index asc)
-    let mut order: Vec<usize> = (0..n).collect();
-    order.sort_by(|&a, &b| pri_cache[b].cmp(&pri_cache[a]).then(a.cmp(&b)));
-
-    // Precompute prefix sums over pri ordered (for optimistic upper bound pruning)
-    let mut pri_ordered: Vec<i64> = order.iter().map(|&i| pri_cache[i]).collect();
-    let mut prefix: Vec<i64> = Vec::with_capacity(pri_ordered.len());
-    let mut acc = 0i64;
-    for &v in pri_ordered.iter() { acc += v; prefix.push(acc); }
-
-    // Recursive backtracking with branch-and-bound using optimistic sum of top priorities
-    fn dfs(
-        start: usize,
-        order: &Vec<usize>,
-        filtered: &Vec<Seccion>,
-        adj: &Vec<Vec<bool>>,
-        ramos_disponibles: &HashMap<String, RamoDisponible>,
-        params: &InputParams,
-        max_size: usize,
-        limit: usize,
-        pri_cache: &Vec<i64>,
-        prefix: &Vec<i64>,
-        current: &mut Vec<usize>,
-        current_total: i64,
-        passed_codes: &mut HashSet<String>,
-        results: &mut Vec<(Vec<(Seccion, i32)>, i64)>,
-        seen: &mut HashSet<String>,
-    ) {
-        if results.len() >= limit { return; }
-
-        // Record current (non-empty) solution
-        if !current.is_empty() {
-            // Use `codigo_box` (identificador de sección) so different sections of same course
-            // are considered distinct solutions by the enumerator
-            let mut keys: Vec<String> = current.iter().map(|&i| filtered[i].codigo_box.clone()).collect();
-            keys.sort();
-            let key = keys.join("|");
-            if !seen.contains(&key) {
-                let mut sol: Vec<(Seccion, i32)> = Vec::new();
-                let mut total: i64 = 0;
-                for &ix in current.iter() {
-                    let s = filtered[ix].clone();
-                    if let Some(r) = ramos_disponibles.values().find(|r| {
-                        if !r.codigo.is_empty() && !s.codigo.is_empty() {
-                            if r.codigo.to_lowercase() == s.codigo.to_lowercase() { return true; }
-                        }
-                        normalize_name(&r.nombre) == normalize_name(&s.nombre)
-                    }) {
-                        let score = compute_priority(r, &s);
-                        sol.push((s.clone(), score as i32));
-                        total += score;
-                    } else {
-                        sol.push((s.clone(), 0));
-                    }
-                }
-                // Aplicar modificadores de optimización
-                let optimized_total = apply_optimization_modifiers(total, &sol, params);
-                results.push((sol, optimized_total));
-                seen.insert(key);
-            }
         }
+    }).collect()
+}
 
-        if current.len() >= max_size { return; }
-
-        // compute current minimum score among results (for pruning)
-        let current_min_score = if results.len() < limit { i64::MIN } else { results.iter().map(|(_,s)| *s).min().unwrap_or(i64::MIN) };
-
-        for pos in start..order.len() {
-            if results.len() >= limit { break; }
-
-            // optimistic upper bound: current_total + sum of next best (max_size - current.len()) pri
-            let remaining_slots = max_size.saturating_sub(current.len());
-            if remaining_slots > 0 {
-                // we can take up to remaining_slots from prefix starting at pos
-                let available = order.len().saturating_sub(pos);
-                let take = std::cmp::min(remaining_slots, available);
-                if take > 0 {
-                    let sum_top = if pos == 0 { prefix[take-1] } else { prefix[pos+take-1] - prefix[pos-1] };
-                    let optimistic = current_total + sum_top;
-                    if results.len() >= limit && optimistic <= current_min_score {
-                        // prune this branch
-                        continue;
-                    }
-                }
-            }
-
-            let i = order[pos];
-
-            // ensure compatibility with all in current
-            let mut ok = true;
-            for &u in current.iter() {
-                if !adj[u][i] { ok = false; break; }
-            }
-            if !ok { continue; }
-
-            // No permitir el mismo curso dos veces dentro de una solución (determinista)
-            let i_code = filtered[i].codigo.to_uppercase();
-            let mut already = false;
-            for &u in current.iter() {
-                if filtered[u].codigo.to_uppercase() == i_code { already = true; break; }
-            }
-            if already { continue; }
-
-            // filters
-            if !seccion_cumple_filtros(&filtered[i], &params.filtros) { continue; }
-
-            if let Some(ref ventana) = params.filtros.as_ref().and_then(|f| f.ventana_entre_actividades.as_ref()) {
-                if ventana.habilitado {
-                    let minutos = ventana.minutos_entre_clases.unwrap_or(15);
-                    let mut ventana_ok = true;
-                    for &u in current.iter() {
-                        if !cumple_ventana_entre(&filtered[u], &filtered[i], minutos) { ventana_ok = false; break; }
-                    }
-                    if !ventana_ok { continue; }
-                }
-            }
-
-            // check prereqs STRICT: only `ramos_pasados` — no co-requisites allowed
-            let local_passed: HashSet<String> = params.ramos_pasados.iter().map(|s| s.to_uppercase()).collect();
-
-            if let Some(ramo_i) = ramos_disponibles.values().find(|r| r.codigo.to_uppercase() == filtered[i].codigo.to_uppercase()) {
-                if !requisitos_cumplidos(&filtered[i], ramo_i, ramos_disponibles, &local_passed) { continue; }
-            } else {
-                let sec_nombre_norm = normalize_name(&filtered[i].nombre);
-                if let Some(ramo_i) = ramos_disponibles.values().find(|r| normalize_name(&r.nombre) == sec_nombre_norm) {
-                    if !requisitos_cumplidos(&filtered[i], ramo_i, ramos_disponibles, &local_passed) { continue; }
-                } else { continue; }
-            }
-
-            // include i (no se añade a `passed_codes`: no permitimos que un curso en la
-            // misma solución sirva como prerequisito para otro)
-            current.push(i);
-            let added_score = pri_cache[i];
+/// Cota optimista del score de una solución de hasta `max_ramos` ramos: por
+/// ramo, la mejor sección con los bonus que puede sumar por sí sola
+/// (prioritario, reprobado, profesor preferido); se ordenan de mayor a menor
+/// y se suman las primeras `max_ramos` (el prefijo más pesado). Las
+/// penalizaciones se toman como 0 y la compactación como la máxima posible.
+pub fn cota_optimista(
+    filtered: &[Seccion],
+    indice: &RamoIndex,
+    params: &InputParams,
+    scoring: &ScoringConfig,
+    max_ramos: usize,
+) -> i64 {
+    let prioritarios: HashSet<String> = params.ramos_prioritarios.iter().map(|s| normalize_name(s)).collect();
+    let preferidos = params.filtros.as_ref()
+        .and_then(|f| f.preferencias_profesores.as_ref())
+        .filter(|p| p.habilitado && p.modo == ModoPreferencia::Soft)
+        .map(|p| &p.profesores_preferidos);
+
+    let mut mejor_por_ramo: HashMap<String, i64> = HashMap::new();
+    for (s, base) in filtered.iter().zip(prioridades_enumeracion(filtered, indice, scoring)) {
+        let mut valor = base;
+        if prioritarios.contains(&normalize_name(&s.codigo)) || prioritarios.contains(&normalize_name(&s.nombre)) {
+            valor += scoring.bonus_ramo_prioritario;
+        }
+        if es_reprobado(params, s) {
+            valor += scoring.bonus_ramo_reprobado;
+        }
+        if preferidos.is_some_and(|lista| !s.is_cfg && profesor_en_lista(s, lista)) {
+            valor += scoring.bonus_profesor;
+        }
+        let mejor = mejor_por_ramo.entry(s.codigo.trim().to_uppercase()).or_insert(valor);
+        *mejor = (*mejor).max(valor);
+    }
 
-            // recurse next (pos+1 ensures combinations without reuse in ordered list)
-            dfs(pos+1, order, filtered, adj, ramos_disponibles, params, max_size, limit, pri_cache, prefix, current, current_total + added_score, passed_codes, results, seen);
+    let mut valores: Vec<i64> = mejor_por_ramo.into_values().filter(|v| *v > 0).collect();
+    valores.sort_unstable_by(|a, b| b.cmp(a));
+    let mut cota: i64 = valores.iter().take(max_ramos).sum();
+    if params.optimizations.iter().any(|o| o == "compact-days") {
+        cota += 100 * scoring.peso_compactacion;
+    }
+    cota
+}
 
-            // backtrack
-            current.pop();
+/// Grafo de enumeración sobre las posiciones de `order` (así Bron–Kerbosch
+/// recorre primero las secciones de mayor prioridad). Una sección entra si
+/// cumple los filtros y sus prerequisitos con `ramos_pasados` (un ramo de la
+/// misma solución no sirve de prerequisito); dos se unen si son compatibles
+/// en `adj`, de ramos distintos, respetan la ventana y el traslado estrictos
+/// y, con `max_ramos_por_dia` 1, no comparten día.
+fn grafo_enumeracion(
+    order: &[usize],
+    filtered: &[Seccion],
+    adj: &[Vec<bool>],
+    indice: &RamoIndex,
+    params: &InputParams,
+) -> Grafo {
+    let local_passed: HashSet<String> = params.ramos_pasados.iter().map(|s| s.to_uppercase()).collect();
+    let ventana = params.filtros.as_ref()
+        .and_then(|f| f.ventana_entre_actividades.as_ref())
+        .filter(|v| v.habilitado)
+        .map(|v| v.minutos_entre_clases.unwrap_or(15));
+    let traslado = traslado::traslado_estricto(params).map(traslado::minutos);
+    let un_ramo_por_dia = carga_diaria::tope_por_dia(&params.filtros) == Some(1);
+    let codigos: Vec<String> = filtered.iter().map(|s| s.codigo.to_uppercase()).collect();
+
+    let activa = |pos: usize| {
+        let s = &filtered[order[pos]];
+        if !seccion_cumple_filtros(s, &params.filtros) {
+            return false;
+        }
+        indice.de_seccion(s).is_some_and(|r| requisitos_cumplidos(s, r, indice, &local_passed))
+    };
+    let compatibles = |a: usize, b: usize| {
+        let (i, j) = (order[a], order[b]);
+        adj[i][j]
+            && codigos[i] != codigos[j]
+            && ventana.is_none_or(|minutos| cumple_ventana_entre(&filtered[i], &filtered[j], minutos))
+            && traslado.is_none_or(|minutos| !traslado::requiere_traslado(&filtered[i], &filtered[j], minutos))
+            && !(un_ramo_por_dia && carga_diaria::comparten_dia(&filtered[i], &filtered[j]))
+    };
+    Grafo::nuevo(order.len(), activa, compatibles)
+}
 
-            if results.len() >= limit { break; }
+/// Solución de una clique enumerada: prioridad de cada sección (0 si no está
+/// en la malla) y total con los modificadores de optimización
+fn solucion_enumerada(
+    filtered: &[Seccion],
+    clique: &[usize],
+    indice: &RamoIndex,
+    params: &InputParams,
+    scoring: &ScoringConfig,
+) -> (Vec<(Seccion, i32)>, i64) {
+    let mut sol: Vec<(Seccion, i32)> = Vec::new();
+    let mut total: i64 = 0;
+    for &ix in clique.iter() {
+        let s = filtered[ix].clone();
+        if let Some(r) = indice.de_seccion(&s) {
+            let score = compute_priority(r, &s).score(scoring);
+            sol.push((s, score as i32));
+            total += score;
+        } else {
+            sol.push((s, 0));
         }
     }
+    let optimized_total = apply_optimization_modifiers(total, &sol, params, scoring);
+    (sol, optimized_total)
+}
 
-    let mut current: Vec<usize> = Vec::new();
-    let mut passed_codes: HashSet<String> = params.ramos_pasados.iter().map(|s| s.to_uppercase()).collect();
-    
-    eprintln!("🚀 [clique] Llamando a dfs con params.optimizations={:?}", params.optimizations);
-    
-    dfs(0, &order, filtered, adj, ramos_disponibles, params, max_size, limit, &pri_cache, &prefix, &mut current, 0, &mut passed_codes, &mut results, &mut seen);
-
-    results
+/// Entradas fijas de una enumeración: secciones filtradas, su matriz de
+/// compatibilidad, la malla, los parámetros y el deadline
+struct ContextoEnumeracion<'a> {
+    filtered: &'a [Seccion],
+    adj: &'a [Vec<bool>],
+    indice: &'a RamoIndex<'a>,
+    params: &'a InputParams,
+    deadline: &'a Deadline,
 }
 
-/// Enumerador con prioridad de tamaño: busca primero cliques del tamaño especificado
-fn enumerate_clique_combinations_size_priority(
-    filtered: &Vec<Seccion>,
-    adj: &Vec<Vec<bool>>,
-    ramos_disponibles: &HashMap<String, RamoDisponible>,
-    params: &InputParams,
+/// Enumera con Bron–Kerbosch (ver `algorithm::bron_kerbosch`) las cliques de
+/// `min_size` a `max_size` secciones, hasta `limit` soluciones. Cada semilla
+/// (posición en el orden de prioridad) es una rama independiente, repartida
+/// en el pool de enumeración. Las cliques con co-requisitos pendientes no se
+/// registran pero se siguen extendiendo: agregar el co-requisito las completa.
+fn enumerar_con_bron_kerbosch(
+    ctx: &ContextoEnumeracion,
     min_size: usize,
     max_size: usize,
     limit: usize,
 ) -> Vec<(Vec<(Seccion, i32)>, i64)> {
-    let n = filtered.len();
-    let mut results: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::new();
-    let mut seen: HashSet<String> = HashSet::new();
-
-    // Precompute priorities
-    let mut pri_cache: Vec<i64> = Vec::with_capacity(n);
-    for s in filtered.iter() {
-        let candidate = ramos_disponibles.values().find(|r| {
-            if !r.codigo.is_empty() && !s.codigo.is_empty() {
-                if r.codigo.to_lowercase() == s.codigo.to_lowercase() { return true; }
-            }
-            normalize_name(&r.nombre) == normalize_name(&s.nombre)
-        });
-        let p = match candidate {
-            Some(r) => compute_priority(r, s),
-            None if s.is_cfg => 10010150i64,
-            None if s.is_electivo => 53000i64,
-            None => 0,
-        };
-        pri_cache.push(p);
-    }
-
-    // Build order by priority
-    let mut order: Vec<usize> = (0..n).collect();
-    order.sort_by(|&a, &b| pri_cache[b].cmp(&pri_cache[a]).then(a.cmp(&b)));
-
-    // Recursive DFS que PRIORIZA encontrar soluciones del tamaño objetivo
-    fn dfs_size_priority(
-        start: usize,
-        order: &Vec<usize>,
-        filtered: &Vec<Seccion>,
-        adj: &Vec<Vec<bool>>,
-        ramos_disponibles: &HashMap<String, RamoDisponible>,
-        params: &InputParams,
-        min_size: usize,
-        max_size: usize,
-        limit: usize,
-        pri_cache: &Vec<i64>,
-        current: &mut Vec<usize>,
-        current_total: i64,
-        results: &mut Vec<(Vec<(Seccion, i32)>, i64)>,
-        seen: &mut HashSet<String>,
-    ) {
-        if results.len() >= limit { return; }
-
-        // SOLO registrar si alcanzamos el tamaño mínimo
-        if current.len() >= min_size {
-            let mut keys: Vec<String> = current.iter().map(|&i| filtered[i].codigo_box.clone()).collect();
-            keys.sort();
-            let key = keys.join("|");
-            
-            if !seen.contains(&key) {
-                let mut sol: Vec<(Seccion, i32)> = Vec::new();
-                let mut total: i64 = 0;
-                for &ix in current.iter() {
-                    let s = filtered[ix].clone();
-                    if let Some(r) = ramos_disponibles.values().find(|r| {
-                        if !r.codigo.is_empty() && !s.codigo.is_empty() {
-                            if r.codigo.to_lowercase() == s.codigo.to_lowercase() { return true; }
-                        }
-                        normalize_name(&r.nombre) == normalize_name(&s.nombre)
-                    }) {
-                        let score = compute_priority(r, &s);
-                        sol.push((s.clone(), score as i32));
-                        total += score;
-                    } else {
-                        sol.push((s.clone(), 0));
-                    }
-                }
-                let optimized_total = apply_optimization_modifiers(total, &sol, params);
-                results.push((sol, optimized_total));
-                seen.insert(key);
-            }
-        }
-
-        if current.len() >= max_size { return; }
-
-        for pos in start..order.len() {
-            if results.len() >= limit { break; }
-
-            let i = order[pos];
-
-            // Compatibilidad
-            let mut ok = true;
-            for &u in current.iter() {
-                if !adj[u][i] { ok = false; break; }
-            }
-            if !ok { continue; }
-
-            // No duplicar curso
-            let i_code = filtered[i].codigo.to_uppercase();
-            let mut already = false;
-            for &u in current.iter() {
-                if filtered[u].codigo.to_uppercase() == i_code { already = true; break; }
-            }
-            if already { continue; }
-
-            // Filtros
-            if !seccion_cumple_filtros(&filtered[i], &params.filtros) { continue; }
-
-            if let Some(ref ventana) = params.filtros.as_ref().and_then(|f| f.ventana_entre_actividades.as_ref()) {
-                if ventana.habilitado {
-                    let minutos = ventana.minutos_entre_clases.unwrap_or(15);
-                    let mut ventana_ok = true;
-                    for &u in current.iter() {
-                        if !cumple_ventana_entre(&filtered[u], &filtered[i], minutos) { ventana_ok = false; break; }
-                    }
-                    if !ventana_ok { continue; }
+    let ContextoEnumeracion { filtered, adj, indice, params, deadline } = *ctx;
+    let scoring = crate::scoring::efectiva(params);
+    let pri_cache = prioridades_enumeracion(filtered, indice, &scoring);
+
+    // Índices por prioridad descendente (desempate: `diversity_seed`, luego índice)
+    let mut order: Vec<usize> = (0..filtered.len()).collect();
+    order.sort_by(|&a, &b| pri_cache[b].cmp(&pri_cache[a])
+        .then(clave_desempate(params.diversity_seed, a).cmp(&clave_desempate(params.diversity_seed, b)))
+        .then(a.cmp(&b)));
+
+    let grafo = grafo_enumeracion(&order, filtered, adj, indice, params);
+    tracing::debug!("   [BK] Grafo de enumeración: {} secciones, {} aristas", grafo.len(), grafo.aristas());
+    let bk = BronKerbosch::new(&grafo, max_size);
+    let pasados: HashSet<String> = params.ramos_pasados.iter().map(|s| s.to_uppercase()).collect();
+    let tope_dia = carga_diaria::tope_por_dia(&params.filtros);
+
+    enumerar_por_semilla(order.len(), limit, |semilla, cupo| {
+        let mut results: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut current: Vec<usize> = Vec::new();
+        bk.enumerar_desde(semilla, |nodo| {
+            if results.len() >= cupo || deadline.vencido() {
+                return Control::Parar;
+            }
+            current.clear();
+            current.extend(nodo.clique.iter().map(|&pos| order[pos]));
+            // Agregar más secciones no baja la carga de ningún día: se poda la rama
+            if tope_dia.is_some_and(|t| carga_diaria::excede_ramos_por_dia(current.iter().map(|&i| &filtered[i]), t)) {
+                return Control::Podar;
+            }
+            if current.len() >= min_size && primera_sin_correquisitos(filtered, &current, indice, &pasados).is_none() {
+                let (sol, total) = solucion_enumerada(filtered, &current, indice, params, &scoring);
+                if obligatorios::incluidos(&params.ramos_obligatorios, &sol) && seen.insert(clave_solucion(&sol)) {
+                    results.push((sol, total));
+                    deadline.registrar_clique();
                 }
             }
+            Control::Seguir
+        });
+        results
+    })
+}
 
-            // Prerequisitos
-            let local_passed: HashSet<String> = params.ramos_pasados.iter().map(|s| s.to_uppercase()).collect();
-            if let Some(ramo_i) = ramos_disponibles.values().find(|r| r.codigo.to_uppercase() == filtered[i].codigo.to_uppercase()) {
-                if !requisitos_cumplidos(&filtered[i], ramo_i, ramos_disponibles, &local_passed) { continue; }
-            } else {
-                let sec_nombre_norm = normalize_name(&filtered[i].nombre);
-                if let Some(ramo_i) = ramos_disponibles.values().find(|r| normalize_name(&r.nombre) == sec_nombre_norm) {
-                    if !requisitos_cumplidos(&filtered[i], ramo_i, ramos_disponibles, &local_passed) { continue; }
-                } else { continue; }
-            }
-
-            current.push(i);
-            dfs_size_priority(pos+1, order, filtered, adj, ramos_disponibles, params, min_size, max_size, limit, pri_cache, current, current_total + pri_cache[i], results, seen);
-            current.pop();
-
-            if results.len() >= limit { break; }
-        }
-    }
-
-    let mut current: Vec<usize> = Vec::new();
-    dfs_size_priority(0, &order, filtered, adj, ramos_disponibles, params, min_size, max_size, limit, &pri_cache, &mut current, 0, &mut results, &mut seen);
+/// Combinaciones compatibles (cliques) de hasta `max_size` secciones.
+/// - `limit` evita explosión combinatoria.
+fn enumerate_clique_combinations(
+    ctx: &ContextoEnumeracion,
+    max_size: usize,
+    limit: usize,
+) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    tracing::info!("🚀 [clique] Enumerando con params.optimizations={:?}", ctx.params.optimizations);
+    enumerar_con_bron_kerbosch(ctx, 1, max_size, limit)
+}
 
-    results
+/// Enumerador con prioridad de tamaño: solo registra cliques de al menos `min_size` secciones
+fn enumerate_clique_combinations_size_priority(
+    ctx: &ContextoEnumeracion,
+    min_size: usize,
+    max_size: usize,
+    limit: usize,
+) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    enumerar_con_bron_kerbosch(ctx, min_size, max_size, limit)
 }
 
 /// Genera todas (hasta un límite) las combinaciones compatibles y devuelve las mejores ordenadas por score.
@@ -1895,55 +2039,65 @@ pub fn get_all_clique_combinations_with_pert(
     max_size: usize,
     limit: usize,
 ) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    get_all_clique_combinations_hasta(lista_secciones, ramos_disponibles, params, max_size, limit, &Deadline::sin_limite())
+}
+
+/// `get_all_clique_combinations_with_pert` con deadline: los enumeradores
+/// dejan de explorar al vencer y se devuelven las combinaciones ya halladas.
+pub fn get_all_clique_combinations_hasta(
+    lista_secciones: &[Seccion],
+    ramos_disponibles: &HashMap<String, RamoDisponible>,
+    params: &InputParams,
+    max_size: usize,
+    limit: usize,
+    deadline: &Deadline,
+) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    combinaciones_hasta(lista_secciones, &RamoIndex::new(ramos_disponibles), params, max_size, limit, deadline)
+}
+
+/// Cuerpo de `get_all_clique_combinations_hasta`, con el índice de la malla
+/// ya armado (el greedy lo reutiliza como fallback)
+fn combinaciones_hasta(
+    lista_secciones: &[Seccion],
+    indice: &RamoIndex,
+    params: &InputParams,
+    max_size: usize,
+    limit: usize,
+    deadline: &Deadline,
+) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    let scoring = crate::scoring::efectiva(params);
     // Reuse initial filtering logic from get_clique_max_pond_with_prefs
     // --- Filtrado inicial (semestre y ramos pasados) ---
-    let mut max_sem = 0;
-    for code in &params.ramos_pasados {
-        if let Some(r) = ramos_disponibles.values().find(|r| r.codigo == *code) {
-            if let Some(s) = r.semestre { max_sem = max_sem.max(s); }
-        }
-    }
-    let max_sem = max_sem + 2;
+    let max_sem = semestre_maximo(params, indice);
 
     let passed: HashSet<_> = params.ramos_pasados.iter().cloned().collect();
+    let evitar_tags = tags::tags_a_evitar(&params.filtros);
 
     let filtered: Vec<Seccion> = lista_secciones.iter().filter(|s| {
-        if passed.contains(&s.codigo_box) { return false; }
-        if let Some(r) = ramos_disponibles.values().find(|r| r.codigo == s.codigo) {
-            if let Some(sem) = r.semestre { return sem <= max_sem; } else { return true; }
-        }
-        let sec_nombre_norm = normalize_name(&s.nombre);
-        if let Some(r) = ramos_disponibles.values().find(|r| normalize_name(&r.nombre) == sec_nombre_norm) {
-            if let Some(sem) = r.semestre { return sem <= max_sem; } else { return true; }
-        }
-        // Permitir CFG aunque no esté en malla
-        s.is_cfg
+        if passed.contains(&s.codigo) { return false; }  // ramos_pasados trae códigos de curso
+        if ramo_excluido(s, &params.ramos_excluidos) { return false; }
+        if tags::ramo_evitado(s, indice, &evitar_tags) { return false; }
+        dentro_del_horizonte(s, indice, max_sem) || obligatorios::es_obligatoria(&params.ramos_obligatorios, s)
     }).cloned().collect();
 
     let cfg_after_initial_filter = filtered.iter().filter(|s| s.is_cfg).count();
-    eprintln!("   [ENUM] Después de filtrado inicial: {} secciones ({} CFGs)", filtered.len(), cfg_after_initial_filter);
+    tracing::debug!("   [ENUM] Después de filtrado inicial: {} secciones ({} CFGs)", filtered.len(), cfg_after_initial_filter);
 
     // --- SELLAR ramos que cumplen prerequisitos según ramos_pasados ---
-    eprintln!("   [SEAL] Sellando ramos que cumplen prerequisitos con ramos_pasados...");
+    tracing::debug!("   [SEAL] Sellando ramos que cumplen prerequisitos con ramos_pasados...");
     let passed_codes_set: HashSet<String> = params.ramos_pasados.iter().map(|s| s.to_uppercase()).collect();
 
-    // Map id -> codigo_upper for lookup
-    let mut id_to_codigo: HashMap<i32, String> = HashMap::new();
-    for r in ramos_disponibles.values() {
-        id_to_codigo.insert(r.id, r.codigo.to_uppercase());
-    }
-
     // Determinar ramos viables (sus prerequisitos todos están en passed_codes_set)
     let mut viable_ramo_ids: HashSet<i32> = HashSet::new();
-    for r in ramos_disponibles.values() {
+    for r in indice.ramos() {
         if r.requisitos_ids.is_empty() {
             viable_ramo_ids.insert(r.id);
             continue;
         }
         let mut ok = true;
         for prereq_id in &r.requisitos_ids {
-            if let Some(cod) = id_to_codigo.get(prereq_id) {
-                if !passed_codes_set.contains(cod) {
+            if let Some(prereq) = indice.por_id(*prereq_id) {
+                if !passed_codes_set.contains(&prereq.codigo.to_uppercase()) {
                     ok = false; break;
                 }
             } else {
@@ -1954,70 +2108,69 @@ pub fn get_all_clique_combinations_with_pert(
         if ok { viable_ramo_ids.insert(r.id); }
     }
 
-    eprintln!("   [SEAL] ramos viables (según ramos_pasados): {} de {}", viable_ramo_ids.len(), ramos_disponibles.len());
+    tracing::debug!("   [SEAL] ramos viables (según ramos_pasados): {} de {}", viable_ramo_ids.len(), indice.len());
 
     // Contar CFGs ANTES del filtrado SEAL
     let cfg_before_seal = filtered.iter().filter(|s| s.is_cfg).count();
-    eprintln!("   [SEAL] CFGs antes de filtrado: {}", cfg_before_seal);
+    tracing::debug!("   [SEAL] CFGs antes de filtrado: {}", cfg_before_seal);
 
     // Filtrar secciones para dejar solo aquellas que pertenecen a ramos viables O son CFG
     let filtered: Vec<Seccion> = filtered.into_iter().filter(|s| {
         // Si es CFG, SIEMPRE permitir - no necesita estar en malla viable
         if s.is_cfg {
-            eprintln!("   [SEAL-FILTER] ✓ Preservando CFG: {}", s.codigo);
+            tracing::debug!("   [SEAL-FILTER] ✓ Preservando CFG: {}", s.codigo);
             return true;
         }
         
         // Para no-CFG: verificar que pertenecen a ramos viables
         // match by codigo
-        if let Some(r) = ramos_disponibles.values().find(|r| r.codigo.to_uppercase() == s.codigo.to_uppercase()) {
+        if let Some(r) = indice.por_codigo(&s.codigo) {
             let viable = viable_ramo_ids.contains(&r.id);
             if !viable {
-                eprintln!("   [SEAL-FILTER] ✗ Excluyendo no-CFG (no viable): {} (id={})", s.codigo, r.id);
+                tracing::debug!("   [SEAL-FILTER] ✗ Excluyendo no-CFG (no viable): {} (id={})", s.codigo, r.id);
             }
             return viable;
         }
         // match by normalized name
-        let sec_nombre_norm = normalize_name(&s.nombre);
-        if let Some(r) = ramos_disponibles.values().find(|r| normalize_name(&r.nombre) == sec_nombre_norm) {
+        if let Some(r) = indice.por_nombre(&s.nombre) {
             let viable = viable_ramo_ids.contains(&r.id);
             if !viable {
-                eprintln!("   [SEAL-FILTER] ✗ Excluyendo no-CFG (no viable): {} (id={})", s.codigo, r.id);
+                tracing::debug!("   [SEAL-FILTER] ✗ Excluyendo no-CFG (no viable): {} (id={})", s.codigo, r.id);
             }
             return viable;
         }
 
-        eprintln!("   [SEAL-FILTER] ✗ Excluyendo (no encontrado en malla): {}", s.codigo);
+        tracing::debug!("   [SEAL-FILTER] ✗ Excluyendo (no encontrado en malla): {}", s.codigo);
         false
     }).collect();
 
-    eprintln!("   [SEAL] Después de sellar por prerequisitos: {} secciones", filtered.len());
+    tracing::debug!("   [SEAL] Después de sellar por prerequisitos: {} secciones", filtered.len());
     
     // Contar CFGs disponibles después del SEAL
     let cfg_count = filtered.iter().filter(|s| s.is_cfg).count();
     let non_cfg_count = filtered.len() - cfg_count;
-    eprintln!("   [SEAL] {} CFG, {} no-CFG después de sellar", cfg_count, non_cfg_count);
+    tracing::debug!("   [SEAL] {} CFG, {} no-CFG después de sellar", cfg_count, non_cfg_count);
 
     // build adjacency
     let n = filtered.len();
-    let mut adj = vec![vec![false; n]; n];
-    for i in 0..n {
-        for j in (i+1)..n {
-            let s1 = &filtered[i];
-            let s2 = &filtered[j];
-            let code_a = &s1.codigo[..std::cmp::min(7, s1.codigo.len())];
-            let code_b = &s2.codigo[..std::cmp::min(7, s2.codigo.len())];
-            if s1.codigo_box != s2.codigo_box && code_a != code_b && !sections_conflict(s1, s2) {
-                adj[i][j] = true; adj[j][i] = true;
+    let adj = crate::metrics::medir("adjacency", n, || {
+        let mut adj = vec![vec![false; n]; n];
+        for i in 0..n {
+            for j in (i+1)..n {
+                if secciones_compatibles(&filtered[i], &filtered[j]) {
+                    adj[i][j] = true; adj[j][i] = true;
+                }
             }
         }
-    }
+        adj
+    });
+    let ctx = ContextoEnumeracion { filtered: &filtered, adj: &adj, indice, params, deadline };
 
     // Si hay CFGs disponibles, crear soluciones con CFGs como base
     let mut combos: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::new();
     
     if cfg_count > 0 {
-        eprintln!("   [CFG-PRIORITY] {} CFGs detectados - creando soluciones con CFGs", cfg_count);
+        tracing::debug!("   [CFG-PRIORITY] {} CFGs detectados - creando soluciones con CFGs", cfg_count);
         
         // Estrategia: Crear soluciones que incluyan cada CFG
         for (i, sec) in filtered.iter().enumerate() {
@@ -2025,21 +2178,21 @@ pub fn get_all_clique_combinations_with_pert(
                 continue;
             }
             
-            let cfg_priority = if let Some(r) = ramos_disponibles.values()
-                .find(|r| r.codigo.to_uppercase() == sec.codigo.to_uppercase()) {
-                compute_priority(r, sec) as i32
+            let cfg_priority = if let Some(r) = indice.por_codigo(&sec.codigo) {
+                compute_priority(r, sec).score(&scoring) as i32
             } else {
-                10010150i32
+                Priority::CFG.score(&scoring) as i32
             };
             
             let mut sol = vec![(sec.clone(), cfg_priority)];
+            let mut indices = vec![i];
             let mut total = cfg_priority as i64;
             
-            // Intentar agregar más secciones compatibles con este CFG
+            // Intentar agregar más secciones compatibles con este CFG y con las ya agregadas
             for (j, other) in filtered.iter().enumerate() {
                 if i == j || other.is_cfg { continue; }
                 if sol.len() >= max_size { break; }
-                if !adj[i][j] { continue; }
+                if !indices.iter().all(|&k| adj[k][j]) { continue; }
                 
                 // Evitar duplicados de código
                 let other_code = other.codigo.to_uppercase();
@@ -2047,18 +2200,18 @@ pub fn get_all_clique_combinations_with_pert(
                     continue;
                 }
                 
-                let other_priority = if let Some(r) = ramos_disponibles.values()
-                    .find(|r| r.codigo.to_uppercase() == other.codigo.to_uppercase()) {
-                    compute_priority(r, other) as i32
+                let other_priority = if let Some(r) = indice.por_codigo(&other.codigo) {
+                    compute_priority(r, other).score(&scoring) as i32
                 } else {
                     0
                 };
                 
                 sol.push((other.clone(), other_priority));
+                indices.push(j);
                 total += other_priority as i64;
             }
             
-            let optimized_total = apply_optimization_modifiers(total, &sol, params);
+            let optimized_total = apply_optimization_modifiers(total, &sol, params, &scoring);
             
             // Verificar duplicado
             let mut keys: Vec<String> = sol.iter().map(|(s, _)| s.codigo_box.clone()).collect();
@@ -2084,13 +2237,15 @@ pub fn get_all_clique_combinations_with_pert(
             }
         }
         
-        eprintln!("   [CFG-PRIORITY] {} soluciones creadas desde CFGs", combos.len());
+        tracing::debug!("   [CFG-PRIORITY] {} soluciones creadas desde CFGs", combos.len());
     }
     
     // Usar enumerador estándar para agregar más soluciones si es necesario
     if combos.len() < limit / 2 {
-        eprintln!("   [STANDARD] Búsqueda exhaustiva estándar para diversidad...");
-        let mut extras = enumerate_clique_combinations(&filtered, &adj, ramos_disponibles, params, max_size, limit);
+        tracing::debug!("   [STANDARD] Búsqueda exhaustiva estándar para diversidad...");
+        let mut extras = crate::metrics::medir_enumerador("combinations", n, || {
+            enumerate_clique_combinations(&ctx, max_size, limit)
+        });
         // Mezclar sin duplicados
         for (sol, score) in extras.drain(..) {
             let mut keys: Vec<String> = sol.iter().map(|(s, _)| s.codigo_box.clone()).collect();
@@ -2113,7 +2268,7 @@ pub fn get_all_clique_combinations_with_pert(
     }
 
     // ===== ESTRATEGIA: Buscar PRIMERO todas las soluciones de 6 cursos =====
-    eprintln!("   [SIZE-PRIORITY] Separando por tamaño y priorizando soluciones de 6 cursos");
+    tracing::debug!("   [SIZE-PRIORITY] Separando por tamaño y priorizando soluciones de 6 cursos");
     
     // Separar por tamaño
     let mut size_6: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::new();
@@ -2128,28 +2283,27 @@ pub fn get_all_clique_combinations_with_pert(
         }
     }
     
-    eprintln!("   [SIZE-PRIORITY] {} soluciones de 6 cursos, {} de 5, {} otras", 
+    tracing::debug!("   [SIZE-PRIORITY] {} soluciones de 6 cursos, {} de 5, {} otras", 
               size_6.len(), size_5.len(), size_other.len());
     
-    // Si hay pocas soluciones de 6 cursos, buscar más exhaustivamente
-    if size_6.len() < 50 {
-        eprintln!("   [EXHAUSTIVE-6] Solo {} soluciones de 6 cursos - buscando más exhaustivamente", size_6.len());
+    // Si hay pocas soluciones de 6 cursos, buscar más exhaustivamente (solo si caben 6)
+    if max_size >= 6 && size_6.len() < 50 && !deadline.vencido() {
+        tracing::debug!("   [EXHAUSTIVE-6] Solo {} soluciones de 6 cursos - buscando más exhaustivamente", size_6.len());
         
         // Aumentar límite de búsqueda para encontrar MÁS soluciones de 6 cursos
         let extended_limit = 200_000usize;
-        eprintln!("   [EXHAUSTIVE-6] Buscando con límite extendido: {}", extended_limit);
+        tracing::debug!("   [EXHAUSTIVE-6] Buscando con límite extendido: {}", extended_limit);
         
-        let mut extended_combos = enumerate_clique_combinations_size_priority(
-            &filtered, 
-            &adj, 
-            ramos_disponibles, 
-            params, 
-            6, // MIN_SIZE = 6
-            6, // MAX_SIZE = 6  
-            extended_limit
-        );
+        let mut extended_combos = crate::metrics::medir_enumerador("combinations_size_priority", n, || {
+            enumerate_clique_combinations_size_priority(
+                &ctx,
+                6, // MIN_SIZE = 6
+                6, // MAX_SIZE = 6
+                extended_limit,
+            )
+        });
         
-        eprintln!("   [EXHAUSTIVE-6] Encontradas {} soluciones adicionales de 6 cursos", extended_combos.len());
+        tracing::debug!("   [EXHAUSTIVE-6] Encontradas {} soluciones adicionales de 6 cursos", extended_combos.len());
         
         // Agregar las nuevas sin duplicados
         let mut seen_keys: HashSet<String> = HashSet::new();
@@ -2170,13 +2324,13 @@ pub fn get_all_clique_combinations_with_pert(
             }
         }
         
-        eprintln!("   [EXHAUSTIVE-6] Total después de búsqueda extendida: {} soluciones de 6 cursos", size_6.len());
+        tracing::debug!("   [EXHAUSTIVE-6] Total después de búsqueda extendida: {} soluciones de 6 cursos", size_6.len());
     }
     
     // Ordenar por score DESC
-    size_6.sort_by(|a, b| b.1.cmp(&a.1));
-    size_5.sort_by(|a, b| b.1.cmp(&a.1));
-    size_other.sort_by(|a, b| b.1.cmp(&a.1));
+    ordenar_canonico(&mut size_6);
+    ordenar_canonico(&mut size_5);
+    ordenar_canonico(&mut size_other);
     
     // PRIORIDAD: 6 cursos > 5 cursos > otros
     let mut final_combos: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::new();
@@ -2187,16 +2341,16 @@ pub fn get_all_clique_combinations_with_pert(
     // Agregar TODAS las soluciones de 5 cursos
     if !size_5.is_empty() {
         final_combos.extend_from_slice(&size_5);
-        eprintln!("   [SIZE-PRIORITY] Agregando {} soluciones de 5 cursos", size_5.len());
+        tracing::debug!("   [SIZE-PRIORITY] Agregando {} soluciones de 5 cursos", size_5.len());
     }
     
     // Agregar TODAS las otras
     if !size_other.is_empty() {
         final_combos.extend_from_slice(&size_other);
-        eprintln!("   [SIZE-PRIORITY] Agregando {} soluciones de otros tamaños", size_other.len());
+        tracing::debug!("   [SIZE-PRIORITY] Agregando {} soluciones de otros tamaños", size_other.len());
     }
     
-    eprintln!("   [ENUM-FINAL] Retornando {} combinaciones ({} de 6 cursos, {} otras)", 
+    tracing::debug!("   [ENUM-FINAL] Retornando {} combinaciones ({} de 6 cursos, {} otras)", 
               final_combos.len(), 
               final_combos.iter().filter(|(s, _)| s.len() == 6).count(),
               final_combos.iter().filter(|(s, _)| s.len() != 6).count());
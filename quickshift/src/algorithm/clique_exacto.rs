@@ -0,0 +1,170 @@
+// clique_exacto.rs - Clique de peso máximo exacto (branch-and-bound)
+//
+// Alternativa opt-in (`solver: "exact"`) al greedy multi-seed de `clique.rs`.
+// Explora el grafo de compatibilidad con branch-and-bound:
+// - Candidatos ordenados por peso descendente (el primer camino ya es una buena cota).
+// - Cota superior por coloreo greedy: una clique toma a lo más un vértice por
+//   clase de color, así que el peso alcanzable es la suma de los máximos de las
+//   `k` clases más pesadas, con `k` = cupos que quedan bajo `max_tam`.
+// - Restricción de cardinalidad sobre un subconjunto (`limitados`, p.ej. CFGs).
+// - Opcionalmente, tope sobre la suma de `costos` (p.ej. créditos SCT).
+//
+// Si la búsqueda termina antes del presupuesto de tiempo, la clique devuelta es
+// óptima; si no, es la mejor encontrada hasta ese momento (`optimo = false`).
+
+use std::time::{Duration, Instant};
+
+/// Resultado de la búsqueda exacta
+#[derive(Debug, Clone)]
+pub struct ResultadoExacto {
+    /// Índices de los vértices de la mejor clique encontrada
+    pub clique: Vec<usize>,
+    pub peso: i64,
+    /// True si se exploró todo el árbol (la clique es demostrablemente óptima)
+    pub optimo: bool,
+    pub nodos_explorados: u64,
+    pub elapsed_ms: u128,
+}
+
+/// Cada cuántos nodos se revisa el reloj
+const CHEQUEO_TIEMPO_CADA: u64 = 1024;
+
+struct Busqueda<'a> {
+    adj: &'a [Vec<bool>],
+    pesos: &'a [i64],
+    limitados: &'a [bool],
+    max_tam: usize,
+    max_limitados: usize,
+    costos: &'a [i64],
+    max_costo: i64,
+    inicio: Instant,
+    presupuesto: Duration,
+    agotado: bool,
+    nodos: u64,
+    mejor: Vec<usize>,
+    mejor_peso: i64,
+}
+
+impl Busqueda<'_> {
+    /// Suma de los máximos de las `cupos` clases de color más pesadas.
+    /// `candidatos` viene ordenado por peso descendente, así que el primer
+    /// vértice de cada clase es su máximo.
+    fn cota_coloreo(&self, candidatos: &[usize], cupos: usize) -> i64 {
+        let mut clases: Vec<Vec<usize>> = Vec::new();
+        let mut maximos: Vec<i64> = Vec::new();
+        for &v in candidatos {
+            match clases.iter().position(|c| c.iter().all(|&u| !self.adj[u][v])) {
+                Some(i) => clases[i].push(v),
+                None => {
+                    clases.push(vec![v]);
+                    maximos.push(self.pesos[v].max(0));
+                }
+            }
+        }
+        maximos.sort_unstable_by(|a, b| b.cmp(a));
+        maximos.iter().take(cupos).sum()
+    }
+
+    fn expandir(&mut self, actual: &mut Vec<usize>, peso: i64, costo: i64, limitados_usados: usize, candidatos: &[usize]) {
+        self.nodos += 1;
+        if self.nodos.is_multiple_of(CHEQUEO_TIEMPO_CADA) && self.inicio.elapsed() >= self.presupuesto {
+            self.agotado = true;
+        }
+        if self.agotado {
+            return;
+        }
+        if peso > self.mejor_peso {
+            self.mejor_peso = peso;
+            self.mejor = actual.clone();
+        }
+        let cupos = self.max_tam.saturating_sub(actual.len());
+        if cupos == 0 || candidatos.is_empty() {
+            return;
+        }
+        if peso + self.cota_coloreo(candidatos, cupos) <= self.mejor_peso {
+            return;
+        }
+
+        for (i, &v) in candidatos.iter().enumerate() {
+            // Cota simple sobre lo que queda: los `cupos` pesos más altos desde `i`
+            let resto: i64 = candidatos[i..].iter().take(cupos).map(|&u| self.pesos[u].max(0)).sum();
+            if peso + resto <= self.mejor_peso {
+                return;
+            }
+            let es_limitado = self.limitados.get(v).copied().unwrap_or(false);
+            if es_limitado && limitados_usados >= self.max_limitados {
+                continue;
+            }
+            let costo_v = self.costos.get(v).copied().unwrap_or(0);
+            if costo + costo_v > self.max_costo {
+                continue;
+            }
+            let siguientes: Vec<usize> = candidatos[i + 1..].iter().copied().filter(|&u| self.adj[v][u]).collect();
+            actual.push(v);
+            self.expandir(actual, peso + self.pesos[v], costo + costo_v, limitados_usados + es_limitado as usize, &siguientes);
+            actual.pop();
+            if self.agotado {
+                return;
+            }
+        }
+    }
+}
+
+/// Busca la clique de peso máximo con a lo más `max_tam` vértices y a lo más
+/// `max_limitados` vértices marcados en `limitados`. Los vértices con peso <= 0
+/// no se consideran. El orden de exploración es determinista.
+pub fn max_weight_clique(
+    adj: &[Vec<bool>],
+    pesos: &[i64],
+    limitados: &[bool],
+    max_tam: usize,
+    max_limitados: usize,
+    presupuesto: Duration,
+) -> ResultadoExacto {
+    max_weight_clique_con_costos(adj, pesos, limitados, max_tam, max_limitados, None, presupuesto)
+}
+
+/// Igual que `max_weight_clique`, pero con `costos = Some((costos, max_costo))`
+/// la suma de costos de la clique no puede superar `max_costo`.
+pub fn max_weight_clique_con_costos(
+    adj: &[Vec<bool>],
+    pesos: &[i64],
+    limitados: &[bool],
+    max_tam: usize,
+    max_limitados: usize,
+    costos: Option<(&[i64], i64)>,
+    presupuesto: Duration,
+) -> ResultadoExacto {
+    let (costos, max_costo) = costos.unwrap_or((&[], i64::MAX));
+    let inicio = Instant::now();
+    let mut candidatos: Vec<usize> = (0..pesos.len()).filter(|&v| pesos[v] > 0).collect();
+    candidatos.sort_by(|&a, &b| pesos[b].cmp(&pesos[a]).then(a.cmp(&b)));
+
+    let mut busqueda = Busqueda {
+        adj,
+        pesos,
+        limitados,
+        max_tam,
+        max_limitados,
+        costos,
+        max_costo,
+        inicio,
+        presupuesto,
+        agotado: false,
+        nodos: 0,
+        mejor: Vec::new(),
+        mejor_peso: 0,
+    };
+    let mut actual = Vec::with_capacity(max_tam);
+    busqueda.expandir(&mut actual, 0, 0, 0, &candidatos);
+
+    let mut clique = busqueda.mejor;
+    clique.sort_unstable();
+    ResultadoExacto {
+        clique,
+        peso: busqueda.mejor_peso,
+        optimo: !busqueda.agotado,
+        nodos_explorados: busqueda.nodos,
+        elapsed_ms: inicio.elapsed().as_millis(),
+    }
+}
@@ -0,0 +1,145 @@
+// cohorte.rs - Agregados sobre los horarios recomendados a un grupo de estudiantes
+//
+// `POST /solve/batch` resuelve a una cohorte completa; aquí se cuenta cuántos
+// estudiantes llevan cada sección en su mejor horario. Una sección se
+// identifica por código y número: la cátedra y el laboratorio de un mismo
+// paquete cuentan una sola vez por estudiante.
+//
+// `POST /simulate/demanda` compara esa demanda con los cupos de la oferta
+// (`proyectar_demanda`) para anticipar qué secciones se van a llenar.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::models::Seccion;
+
+/// Ocupación (inscritos esperados / cupos disponibles) desde la que una
+/// sección se marca en riesgo
+pub const UMBRAL_RIESGO_DEFAULT: f64 = 0.9;
+
+/// Estudiantes que tienen una sección en su mejor horario
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DemandaSeccion {
+    pub codigo: String,
+    pub nombre: String,
+    pub seccion: String,
+    pub estudiantes: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cupos_totales: Option<u32>,
+    /// Si la sección es un paquete, el menor cupo entre sus componentes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cupos_disponibles: Option<u32>,
+}
+
+/// Demanda por sección sobre el mejor horario de cada estudiante, de mayor a
+/// menor (a igual demanda, por código y sección)
+pub fn demanda_por_seccion<'a>(horarios: impl IntoIterator<Item = &'a [Seccion]>) -> Vec<DemandaSeccion> {
+    let mut demanda: HashMap<(String, String), DemandaSeccion> = HashMap::new();
+    for horario in horarios {
+        let mut vistas: HashSet<(String, String)> = HashSet::new();
+        for s in horario {
+            let clave = (s.codigo.trim().to_uppercase(), s.seccion.trim().to_string());
+            let d = demanda.entry(clave.clone())
+                .or_insert_with(|| DemandaSeccion {
+                    codigo: s.codigo.clone(),
+                    nombre: s.nombre.clone(),
+                    seccion: s.seccion.clone(),
+                    estudiantes: 0,
+                    cupos_totales: None,
+                    cupos_disponibles: None,
+                });
+            d.cupos_totales = min_conocido(d.cupos_totales, s.cupos_totales);
+            d.cupos_disponibles = min_conocido(d.cupos_disponibles, s.cupos_disponibles);
+            if vistas.insert(clave) {
+                d.estudiantes += 1;
+            }
+        }
+    }
+    let mut out: Vec<DemandaSeccion> = demanda.into_values().collect();
+    out.sort_by(|a, b| {
+        b.estudiantes.cmp(&a.estudiantes)
+            .then_with(|| a.codigo.cmp(&b.codigo))
+            .then_with(|| a.seccion.cmp(&b.seccion))
+    });
+    out
+}
+
+fn min_conocido(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+    match (a, b) {
+        (Some(x), Some(y)) => Some(x.min(y)),
+        (x, y) => x.or(y),
+    }
+}
+
+/// Situación de una sección frente a la demanda esperada
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoCupos {
+    /// Más inscritos esperados que cupos disponibles
+    Desborda,
+    /// Ocupación esperada sobre el umbral de riesgo
+    EnRiesgo,
+    Holgada,
+    /// La oferta no informa cupos de la sección
+    SinDatos,
+}
+
+/// Inscripción esperada de una sección frente a sus cupos
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProyeccionSeccion {
+    pub codigo: String,
+    pub nombre: String,
+    pub seccion: String,
+    /// Estudiantes de la cohorte con la sección en su mejor horario
+    pub estudiantes: usize,
+    /// `estudiantes` escalado a la población que representa la cohorte
+    pub inscritos_esperados: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cupos_totales: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cupos_disponibles: Option<u32>,
+    /// inscritos_esperados / cupos_disponibles (None sin cupos informados o sin cupos libres)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ocupacion: Option<f64>,
+    /// Inscritos esperados que no alcanzan cupo (0 si caben todos)
+    pub excedente: u32,
+    pub estado: EstadoCupos,
+}
+
+/// Proyecta la inscripción de cada sección: `escala` multiplica la demanda de
+/// la cohorte (p. ej. 4.0 si la cohorte es una muestra de un cuarto de los
+/// estudiantes) y `umbral_riesgo` es la ocupación desde la que se marca
+/// `EnRiesgo`. Ordena primero las que desbordan, por excedente y ocupación.
+pub fn proyectar_demanda(demanda: &[DemandaSeccion], escala: f64, umbral_riesgo: f64) -> Vec<ProyeccionSeccion> {
+    let mut out: Vec<ProyeccionSeccion> = demanda.iter().map(|d| {
+        let esperados = (d.estudiantes as f64 * escala).ceil() as u32;
+        let ocupacion = d.cupos_disponibles.filter(|c| *c > 0).map(|c| esperados as f64 / c as f64);
+        let excedente = d.cupos_disponibles.map(|c| esperados.saturating_sub(c)).unwrap_or(0);
+        let estado = match (d.cupos_disponibles, ocupacion) {
+            (None, _) => EstadoCupos::SinDatos,
+            _ if excedente > 0 => EstadoCupos::Desborda,
+            (_, Some(o)) if o >= umbral_riesgo => EstadoCupos::EnRiesgo,
+            _ => EstadoCupos::Holgada,
+        };
+        ProyeccionSeccion {
+            codigo: d.codigo.clone(),
+            nombre: d.nombre.clone(),
+            seccion: d.seccion.clone(),
+            estudiantes: d.estudiantes,
+            inscritos_esperados: esperados,
+            cupos_totales: d.cupos_totales,
+            cupos_disponibles: d.cupos_disponibles,
+            ocupacion,
+            excedente,
+            estado,
+        }
+    }).collect();
+    out.sort_by(|a, b| {
+        a.estado.cmp(&b.estado)
+            .then_with(|| b.excedente.cmp(&a.excedente))
+            .then_with(|| b.ocupacion.unwrap_or(0.0).total_cmp(&a.ocupacion.unwrap_or(0.0)))
+            .then_with(|| b.estudiantes.cmp(&a.estudiantes))
+            .then_with(|| a.codigo.cmp(&b.codigo))
+            .then_with(|| a.seccion.cmp(&b.seccion))
+    });
+    out
+}
@@ -0,0 +1,210 @@
+// comodidad.rs - Puntaje de comodidad de un horario (`/rutacomoda`)
+//
+// El score del clique mide avance en la malla; la comodidad mide qué tan
+// llevadera es la semana que resulta. Se calcula desde las secciones mismas:
+//   - ventanas: horas de hueco entre clases del mismo día (`ventanas`);
+//   - inicio temprano: horas de clase antes de `hora_inicio_ideal`, sumadas
+//     por día;
+//   - días en campus: días de la semana con al menos una clase;
+//   - profesores: secciones con un profesor preferido menos secciones con uno
+//     a evitar (`filtros.preferencias_profesores` o el body de `/rank`).
+// Cada métrica se multiplica por su peso y el puntaje es la suma: las tres
+// primeras restan, profesores suma. Un `PerfilComodidad` fija pesos de
+// partida y `ConfigComodidad::pesos` los ajusta uno por uno.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use crate::algorithm::paralelo::clave_secciones;
+use crate::algorithm::ventanas::ventanas;
+use crate::models::horario::{bloques_de_seccion, formatear_hora, parse_hora};
+use crate::models::{PreferenciasProfesores, Seccion};
+
+/// Hora desde la que una clase ya no cuenta como temprana, si no se pide otra
+pub const HORA_INICIO_IDEAL_POR_DEFECTO: &str = "10:00";
+
+/// Pesos de partida según lo que más le importa al estudiante
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PerfilComodidad {
+    /// Todas las métricas pesan lo mismo
+    #[default]
+    Equilibrado,
+    /// Evitar huecos entre clases
+    SinVentanas,
+    /// Ir la menor cantidad de días posible
+    PocosDias,
+    /// Empezar lo más tarde posible
+    SinMadrugar,
+}
+
+/// Peso de cada métrica en el puntaje
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+pub struct PesosComodidad {
+    /// Por hora de ventana
+    pub ventanas: f64,
+    /// Por hora de clase antes de la hora ideal
+    pub inicio_temprano: f64,
+    /// Por día con clases
+    pub dias_en_campus: f64,
+    /// Por sección con profesor preferido (y en contra por cada uno a evitar)
+    pub profesores: f64,
+}
+
+impl PerfilComodidad {
+    pub fn pesos(self) -> PesosComodidad {
+        let (ventanas, inicio_temprano, dias_en_campus) = match self {
+            PerfilComodidad::Equilibrado => (1.0, 1.0, 1.0),
+            PerfilComodidad::SinVentanas => (3.0, 0.5, 0.5),
+            PerfilComodidad::PocosDias => (0.5, 0.5, 3.0),
+            PerfilComodidad::SinMadrugar => (0.5, 3.0, 0.5),
+        };
+        PesosComodidad { ventanas, inicio_temprano, dias_en_campus, profesores: 1.0 }
+    }
+}
+
+/// Ajustes de `PesosComodidad`; los campos omitidos quedan como en el perfil
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct PesosOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ventanas: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inicio_temprano: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dias_en_campus: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profesores: Option<f64>,
+}
+
+/// `comodidad` en el body de `/rutacomoda/best` y `/rutacomoda/rank`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ConfigComodidad {
+    #[serde(default)]
+    pub perfil: PerfilComodidad,
+    #[serde(default)]
+    pub pesos: PesosOverrides,
+    /// "HH:MM"; por defecto `HORA_INICIO_IDEAL_POR_DEFECTO`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hora_inicio_ideal: Option<String>,
+    /// Si se omite se usa `filtros.preferencias_profesores` del request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profesores: Option<PreferenciasProfesores>,
+}
+
+impl ConfigComodidad {
+    /// Pesos del perfil con los ajustes aplicados
+    pub fn pesos_efectivos(&self) -> PesosComodidad {
+        let base = self.perfil.pesos();
+        PesosComodidad {
+            ventanas: self.pesos.ventanas.unwrap_or(base.ventanas),
+            inicio_temprano: self.pesos.inicio_temprano.unwrap_or(base.inicio_temprano),
+            dias_en_campus: self.pesos.dias_en_campus.unwrap_or(base.dias_en_campus),
+            profesores: self.pesos.profesores.unwrap_or(base.profesores),
+        }
+    }
+
+    /// `hora_inicio_ideal` en minutos desde medianoche
+    pub fn inicio_ideal(&self) -> Result<u32, String> {
+        let hora = self.hora_inicio_ideal.as_deref().unwrap_or(HORA_INICIO_IDEAL_POR_DEFECTO);
+        parse_hora(hora).ok_or_else(|| format!("hora_inicio_ideal inválida: '{}'", hora))
+    }
+}
+
+/// Métricas de comodidad de una solución
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MetricasComodidad {
+    pub minutos_ventana: u32,
+    /// Minutos de clase antes de la hora ideal, sumados por día
+    pub minutos_antes_inicio_ideal: u32,
+    pub dias_en_campus: usize,
+    /// Primera clase de cada día con clases ("LU" -> "08:30")
+    pub inicio_por_dia: BTreeMap<String, String>,
+    pub secciones_profesor_preferido: usize,
+    pub secciones_profesor_evitado: usize,
+}
+
+/// true si el profesor de la sección contiene alguno de los nombres de `lista`
+fn profesor_en_lista(seccion: &Seccion, lista: &Option<Vec<String>>) -> bool {
+    let profesor = seccion.profesor.to_lowercase();
+    lista.iter().flatten()
+        .any(|p| !p.trim().is_empty() && profesor.contains(&p.trim().to_lowercase()))
+}
+
+/// Mide `secciones` con la hora ideal `inicio_ideal` (minutos desde medianoche)
+pub fn metricas(secciones: &[Seccion], inicio_ideal: u32, profesores: Option<&PreferenciasProfesores>) -> MetricasComodidad {
+    let mut inicio: BTreeMap<u32, (String, u32)> = BTreeMap::new();
+    let mut tempranos: HashSet<(u32, u32)> = HashSet::new();
+    for bloque in secciones.iter().flat_map(bloques_de_seccion) {
+        let dia = inicio.entry(bloque.dia.indice()).or_insert((bloque.dia.codigo().to_string(), bloque.inicio));
+        dia.1 = dia.1.min(bloque.inicio);
+        // Minutos antes de la hora ideal, sin contar dos veces los bloques solapados
+        for minuto in bloque.inicio..bloque.fin.min(inicio_ideal) {
+            tempranos.insert((bloque.dia.indice(), minuto));
+        }
+    }
+    let (preferido, evitado) = profesores.map_or((0, 0), |p| {
+        (
+            secciones.iter().filter(|s| profesor_en_lista(s, &p.profesores_preferidos)).count(),
+            secciones.iter().filter(|s| profesor_en_lista(s, &p.profesores_evitar)).count(),
+        )
+    });
+    MetricasComodidad {
+        minutos_ventana: ventanas(secciones).into_iter().map(|v| v.max(0) as u32).sum(),
+        minutos_antes_inicio_ideal: tempranos.len() as u32,
+        dias_en_campus: inicio.len(),
+        inicio_por_dia: inicio.into_values().map(|(dia, m)| (dia, formatear_hora(m))).collect(),
+        secciones_profesor_preferido: preferido,
+        secciones_profesor_evitado: evitado,
+    }
+}
+
+/// Puntaje de comodidad: más alto es más cómodo. Ventanas e inicio temprano
+/// se cuentan en horas.
+pub fn puntaje(m: &MetricasComodidad, pesos: &PesosComodidad) -> f64 {
+    let profesores = m.secciones_profesor_preferido as f64 - m.secciones_profesor_evitado as f64;
+    let total = pesos.profesores * profesores
+        - pesos.ventanas * m.minutos_ventana as f64 / 60.0
+        - pesos.inicio_temprano * m.minutos_antes_inicio_ideal as f64 / 60.0
+        - pesos.dias_en_campus * m.dias_en_campus as f64;
+    // Redondeo a centésimas para que el orden no dependa del ruido de punto flotante
+    (total * 100.0).round() / 100.0
+}
+
+/// Una solución reordenada por comodidad
+#[derive(Debug, Clone, Serialize)]
+pub struct SolucionComoda {
+    /// Índice en la lista recibida
+    pub posicion_original: usize,
+    pub total_score: i64,
+    pub comodidad: f64,
+    pub metricas: MetricasComodidad,
+    pub secciones: Vec<Seccion>,
+}
+
+/// Ordena `soluciones` (secciones, score del clique) de la más cómoda a la
+/// menos cómoda; a igual comodidad, por score (desc) y por los `codigo_box`
+/// (ver `paralelo::ordenar_canonico`).
+pub fn rankear(
+    soluciones: Vec<(Vec<Seccion>, i64)>,
+    config: &ConfigComodidad,
+    profesores: Option<&PreferenciasProfesores>,
+) -> Result<Vec<SolucionComoda>, String> {
+    let pesos = config.pesos_efectivos();
+    let inicio_ideal = config.inicio_ideal()?;
+    let profesores = config.profesores.as_ref().or(profesores);
+    let mut out: Vec<SolucionComoda> = soluciones.into_iter().enumerate()
+        .map(|(i, (secciones, total_score))| {
+            let m = metricas(&secciones, inicio_ideal, profesores);
+            SolucionComoda { posicion_original: i, total_score, comodidad: puntaje(&m, &pesos), metricas: m, secciones }
+        })
+        .collect();
+    out.sort_by(|a, b| b.comodidad.total_cmp(&a.comodidad)
+        .then(b.total_score.cmp(&a.total_score))
+        .then_with(|| clave_secciones(&a.secciones).cmp(&clave_secciones(&b.secciones))));
+    Ok(out)
+}
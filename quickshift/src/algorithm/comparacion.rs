@@ -0,0 +1,118 @@
+// comparacion.rs - Diferencias entre dos soluciones (`POST /solutions/compare`)
+//
+// Cuando el estudiante ya redujo la elección a dos horarios, lo que le sirve
+// es ver en qué difieren: ramos que solo toma en uno, ramos que toma en ambos
+// pero en otra sección, y cuánto cambian el score y las métricas del horario
+// (`algorithm::metricas`). Los ramos se identifican por código (sin importar
+// mayúsculas); un ramo con cátedra y ayudantía cuenta con todas sus secciones.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use crate::algorithm::metricas::{metricas, MetricasSolucion};
+use crate::models::Seccion;
+
+/// Un ramo que está en una sola de las soluciones
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RamoComparado {
+    pub codigo: String,
+    pub nombre: String,
+    pub secciones: Vec<String>,
+}
+
+/// Un ramo de ambas soluciones tomado en secciones distintas
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CambioSeccion {
+    pub codigo: String,
+    pub nombre: String,
+    pub secciones_a: Vec<String>,
+    pub secciones_b: Vec<String>,
+}
+
+/// Valor de una métrica en cada solución y `delta` = b - a
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeltaMetrica {
+    pub metrica: &'static str,
+    pub a: f64,
+    pub b: f64,
+    pub delta: f64,
+}
+
+/// Resultado de comparar la solución `a` con la `b`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComparacionSoluciones {
+    pub solo_en_a: Vec<RamoComparado>,
+    pub solo_en_b: Vec<RamoComparado>,
+    /// Ramos de ambas en secciones distintas
+    pub distinta_seccion: Vec<CambioSeccion>,
+    /// Códigos de los ramos de ambas en las mismas secciones
+    pub iguales: Vec<String>,
+    /// `total_score` (si ambas lo traen), `total_gap_minutes`, `days_on_campus`
+    /// y `compactness_pct`
+    pub metricas: Vec<DeltaMetrica>,
+    pub metricas_a: MetricasSolucion,
+    pub metricas_b: MetricasSolucion,
+}
+
+/// Secciones de una solución agrupadas por código de ramo, con el nombre
+fn por_ramo(secciones: &[Seccion]) -> BTreeMap<String, (String, Vec<String>)> {
+    let mut ramos: BTreeMap<String, (String, Vec<String>)> = BTreeMap::new();
+    for s in secciones {
+        let entrada = ramos.entry(s.codigo.trim().to_uppercase()).or_insert_with(|| (s.nombre.clone(), Vec::new()));
+        entrada.1.push(s.seccion.clone());
+    }
+    for (_, secs) in ramos.values_mut() {
+        secs.sort();
+        secs.dedup();
+    }
+    ramos
+}
+
+fn delta(metrica: &'static str, a: f64, b: f64) -> DeltaMetrica {
+    // Redondeo a décimas, como `compactness_pct`
+    DeltaMetrica { metrica, a, b, delta: ((b - a) * 10.0).round() / 10.0 }
+}
+
+/// Compara `a` con `b`; los scores son opcionales (una solución armada a
+/// mano no lo trae) y su fila solo se incluye si están ambos
+pub fn comparar(a: &[Seccion], score_a: Option<i64>, b: &[Seccion], score_b: Option<i64>) -> ComparacionSoluciones {
+    let (ramos_a, ramos_b) = (por_ramo(a), por_ramo(b));
+    let mut solo_en_a = Vec::new();
+    let mut distinta_seccion = Vec::new();
+    let mut iguales = Vec::new();
+    for (codigo, (nombre, secciones_a)) in &ramos_a {
+        match ramos_b.get(codigo) {
+            None => solo_en_a.push(RamoComparado { codigo: codigo.clone(), nombre: nombre.clone(), secciones: secciones_a.clone() }),
+            Some((_, secciones_b)) if secciones_b == secciones_a => iguales.push(codigo.clone()),
+            Some((_, secciones_b)) => distinta_seccion.push(CambioSeccion {
+                codigo: codigo.clone(),
+                nombre: nombre.clone(),
+                secciones_a: secciones_a.clone(),
+                secciones_b: secciones_b.clone(),
+            }),
+        }
+    }
+    let solo_en_b = ramos_b.iter()
+        .filter(|(codigo, _)| !ramos_a.contains_key(*codigo))
+        .map(|(codigo, (nombre, secciones))| RamoComparado { codigo: codigo.clone(), nombre: nombre.clone(), secciones: secciones.clone() })
+        .collect();
+
+    let con_prioridad = |secciones: &[Seccion]| secciones.iter().map(|s| (s.clone(), 0)).collect::<Vec<_>>();
+    let (metricas_a, metricas_b) = (metricas(&con_prioridad(a)), metricas(&con_prioridad(b)));
+    let mut filas = Vec::new();
+    if let (Some(sa), Some(sb)) = (score_a, score_b) {
+        filas.push(delta("total_score", sa as f64, sb as f64));
+    }
+    filas.push(delta("total_gap_minutes", metricas_a.total_gap_minutes as f64, metricas_b.total_gap_minutes as f64));
+    filas.push(delta("days_on_campus", metricas_a.days_on_campus as f64, metricas_b.days_on_campus as f64));
+    filas.push(delta("compactness_pct", metricas_a.compactness_pct, metricas_b.compactness_pct));
+
+    ComparacionSoluciones {
+        solo_en_a,
+        solo_en_b,
+        distinta_seccion,
+        iguales,
+        metricas: filas,
+        metricas_a,
+        metricas_b,
+    }
+}
@@ -0,0 +1,70 @@
+// compromisos.rs - Compromisos semanales del estudiante (trabajo, deporte)
+//
+// Cada `Compromiso` de `InputParams::compromisos` es un bloque fijo de la
+// semana ("MA JU 18:00-20:00"). En modo `hard` ninguna sección que lo pise
+// entra al clique (lo filtra `ruta`); en modo `soft`
+// `clique::apply_optimization_modifiers` resta su `peso` (o
+// `ScoringConfig::penalizacion_compromiso`) una vez por compromiso pisado.
+// La respuesta de `/solve` informa, por solución, cuáles respeta.
+
+use crate::api_json::InputParams;
+use crate::models::horario::{self, Bloque};
+use crate::models::{Compromiso, ModoPreferencia, Seccion};
+use crate::scoring::ScoringConfig;
+use serde::Serialize;
+
+/// Bloques semanales del compromiso (vacío si el día o las horas no se reconocen)
+pub fn bloques(c: &Compromiso) -> Vec<Bloque> {
+    horario::parse(&format!("{} {}-{}", c.dia, c.inicio, c.fin))
+}
+
+/// true si alguna clase de la sección se cruza con el compromiso
+pub fn pisa(seccion: &Seccion, c: &Compromiso) -> bool {
+    let propios = bloques(c);
+    horario::bloques_de_seccion(seccion).iter().any(|b| propios.iter().any(|p| p.solapa(b)))
+}
+
+/// Primer compromiso `hard` que la sección pisa
+pub fn choca_con<'a>(seccion: &Seccion, params: &'a InputParams) -> Option<&'a Compromiso> {
+    params.compromisos.iter()
+        .filter(|c| c.modo == ModoPreferencia::Hard)
+        .find(|c| pisa(seccion, c))
+}
+
+/// Penalización total de los compromisos `soft` que pisan las secciones
+pub fn penalizacion<'a>(secciones: impl Iterator<Item = &'a Seccion> + Clone, params: &InputParams, scoring: &ScoringConfig) -> i64 {
+    params.compromisos.iter()
+        .filter(|c| c.modo == ModoPreferencia::Soft)
+        .filter(|c| secciones.clone().any(|s| pisa(s, c)))
+        .map(|c| c.peso.unwrap_or(scoring.penalizacion_compromiso).max(0))
+        .sum()
+}
+
+/// Si una solución respeta un compromiso y, si no, qué secciones lo pisan
+#[derive(Debug, Clone, Serialize)]
+pub struct EstadoCompromiso {
+    pub etiqueta: String,
+    pub modo: ModoPreferencia,
+    pub respetado: bool,
+    /// Secciones que lo pisan ("CBM1000-2")
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub secciones: Vec<String>,
+}
+
+/// Estado de cada compromiso pedido frente a la solución
+pub fn estado(solucion: &[Seccion], params: &InputParams) -> Vec<EstadoCompromiso> {
+    params.compromisos.iter()
+        .map(|c| {
+            let secciones: Vec<String> = solucion.iter()
+                .filter(|s| pisa(s, c))
+                .map(|s| format!("{}-{}", s.codigo, s.seccion))
+                .collect();
+            EstadoCompromiso {
+                etiqueta: c.etiqueta.clone(),
+                modo: c.modo,
+                respetado: secciones.is_empty(),
+                secciones,
+            }
+        })
+        .collect()
+}
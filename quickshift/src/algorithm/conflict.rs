@@ -1,99 +1,15 @@
-// Funciones para detectar conflictos y parsear franjas horarias.
+// Funciones para detectar conflictos entre franjas horarias (el parser está en `models::horario`).
+use crate::models::horario::{self, Dia};
 use crate::models::Seccion;
 
-fn to_min_opt(t: &str) -> Option<i32> {
-    let mut tok = t.trim().to_uppercase().replace('.', ":");
-    // quitar AM/PM si viene
-    tok = tok.replace("AM", "").replace("PM", "").trim().to_string();
-    if tok.len() == 4 && !tok.contains(':') { tok = format!("0{}", tok); }
-    let parts: Vec<&str> = tok.split(':').collect();
-    if parts.len() != 2 { return None; }
-    let hh = parts[0].parse::<i32>().ok()?;
-    let mm = parts[1].parse::<i32>().ok()?;
-    // nota: no se ajusta AM/PM por simplicidad; si el usuario envía 08:00PM debería pasar como 20:00
-    // pero como removemos AM/PM, asumimos formato 24h o correcto
-    Some(hh * 60 + mm)
-}
-
 /// Parsear una cadena de horario a una lista de tuplas (DIA, start_min, end_min)
 /// Ejemplo: "LU MA 08:30-10:00" -> [("LU",510,600),("MA",510,600)]
+/// (forma en tuplas de `models::horario::parse`)
 pub fn parse_slots(h: &str) -> Vec<(String, i32, i32)> {
-    let s = h.trim().replace('.', ":").to_uppercase();
-    let parts: Vec<&str> = s.split_whitespace().collect();
-    if parts.is_empty() { return vec![]; }
-
-    let mut day_tokens: Vec<String> = Vec::new();
-    let mut time_tokens: Vec<&str> = Vec::new();
-    let mut time_started = false;
-
-    for &token in parts.iter() {
-        if !time_started && (token.contains(':') || token.contains('-')) {
-            time_started = true;
-        }
-
-        if time_started {
-            time_tokens.push(token);
-        } else {
-            day_tokens.push(token.to_string());
-        }
-    }
-
-    // Manejar forma compacta "LU:08:30-10:00" donde el día y la hora están en el mismo token
-    if day_tokens.is_empty() && !time_tokens.is_empty() {
-        let first = time_tokens[0];
-        if let Some(pos) = first.find(':') {
-            let (maybe_day, rest) = first.split_at(pos);
-            let rest = &rest[1..];
-            if !maybe_day.trim().is_empty() {
-                day_tokens.push(maybe_day.trim().to_string());
-                time_tokens[0] = rest;
-            }
-        }
-    }
-
-    if day_tokens.is_empty() || time_tokens.is_empty() {
-        return vec![];
-    }
-
-    let mut time_join = time_tokens.join(" ");
-    time_join = time_join
-        .replace('–', "-")
-        .replace('—', "-")
-        .replace('―', "-")
-        .replace('‐', "-")
-        .replace('−', "-");
-    time_join = time_join.replace(" - ", "-").replace(" -", "-").replace("- ", "-");
-
-    let time_parts: Vec<&str> = time_join
-        .split('-')
-        .map(|t| t.trim())
-        .filter(|t| !t.is_empty())
-        .collect();
-    if time_parts.len() != 2 {
-        return vec![];
-    }
-
-    let start = to_min_opt(time_parts[0]).unwrap_or(0);
-    let end = to_min_opt(time_parts[1]).unwrap_or(start + 60);
-    let mut days = Vec::new();
-
-    for d in &day_tokens {
-        let token = d.trim().chars().take(3).collect::<String>();
-        let dn = match token.as_str() {
-            "LUN" | "LU" => "LU",
-            "MAR" | "MA" => "MA",
-            "MIE" | "MI" => "MI",
-            "JUE" | "J U" | "JU" => "JU",
-            "VIE" | "VI" => "VI",
-            "SAB" | "SA" => "SA",
-            "DOM" | "DO" => "DO",
-            other => other,
-        }
-        .to_string();
-        days.push(dn);
-    }
-
-    days.into_iter().map(|d| (d, start, end)).collect()
+    horario::parse(h)
+        .into_iter()
+        .map(|b| (b.dia.codigo().to_string(), b.inicio as i32, b.fin as i32))
+        .collect()
 }
 
 /// True si cualquiera de los slots de horario1 solapa con cualquiera de horario2 (mismo día y rango)
@@ -134,7 +50,7 @@ pub fn horarios_violate_min_gap(horario1: &[String], horario2: &[String], min_mi
 
 /// Comprueba si una sección contiene un tiempo (ej "08:30") dentro de alguno de sus bloques
 pub fn seccion_contiene_hora(seccion: &Seccion, hora_prohibida: &str) -> bool {
-    let objetivo_min = match to_min_opt(hora_prohibida) { Some(m) => m, None => return false };
+    let objetivo_min = match horario::parse_hora(hora_prohibida) { Some(m) => m as i32, None => return false };
     for h in seccion.horario.iter() {
         for (_d, s, e) in parse_slots(h) {
             if objetivo_min >= s && objetivo_min < e { return true; }
@@ -146,7 +62,13 @@ pub fn seccion_contiene_hora(seccion: &Seccion, hora_prohibida: &str) -> bool {
 /// True si la sección está completamente contenida en la franja `rango`.
 /// `rango` puede contener días y una hora, p.ej. "LU 08:00-10:00" o "08:00-10:00".
 pub fn seccion_contenida_en_rango(seccion: &Seccion, rango: &str) -> bool {
-    let rango_slots = parse_slots(rango);
+    let mut rango_slots = parse_slots(rango);
+    if rango_slots.is_empty() {
+        // sin días: el rango vale para toda la semana
+        if let Some((inicio, fin)) = horario::parse_rango(rango) {
+            rango_slots = Dia::TODOS.iter().map(|d| (d.codigo().to_string(), inicio as i32, fin as i32)).collect();
+        }
+    }
     if rango_slots.is_empty() { return false; }
     // Para cada slot de la sección, debe existir al menos un rango que contenga totalmente ese slot (mismo día)
     for h in seccion.horario.iter() {
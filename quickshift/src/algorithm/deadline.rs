@@ -0,0 +1,144 @@
+// deadline.rs - Límite de tiempo y cancelación cooperativa del solver
+//
+// El greedy multi-seed y los enumeradores exhaustivos de `clique.rs` pueden
+// tardar minutos con ofertas grandes. Un `Deadline` viaja por el pipeline
+// (`ruta::resolver_con_datos_hasta` -> `clique`) y los bucles lo consultan
+// periódicamente: al vencer (o al cancelarse, p.ej. porque el cliente HTTP
+// cerró la conexión) dejan de explorar y devuelven lo que ya encontraron.
+//
+// Las copias comparten el estado: cancelar cualquiera cancela todas, y
+// `alcanzado()` indica al final si algún bucle se cortó, es decir, si el
+// resultado es parcial. También llevan el avance de la búsqueda (`progreso`),
+// que consultan los jobs asíncronos (`/jobs/{id}`) mientras el solver corre.
+
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::api_json::{InputParams, SolverMode};
+
+#[derive(Debug, Clone, Default)]
+pub struct Deadline {
+    limite: Option<Instant>,
+    cancelado: Arc<AtomicBool>,
+    alcanzado: Arc<AtomicBool>,
+    progreso: Arc<Contadores>,
+}
+
+#[derive(Debug)]
+struct Contadores {
+    secciones_viables: AtomicUsize,
+    cliques: AtomicUsize,
+    /// `i64::MIN` mientras el greedy no la calcula
+    cota_optimista: AtomicI64,
+}
+
+impl Default for Contadores {
+    fn default() -> Self {
+        Contadores {
+            secciones_viables: AtomicUsize::new(0),
+            cliques: AtomicUsize::new(0),
+            cota_optimista: AtomicI64::new(i64::MIN),
+        }
+    }
+}
+
+/// Avance de una búsqueda en curso
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct Progreso {
+    /// Secciones que pasaron el filtrado de la fase 2c
+    pub secciones_viables: usize,
+    /// Cliques (soluciones distintas) encontrados hasta ahora
+    pub cliques: usize,
+    /// Cota superior del score de cualquier solución (ver `clique::cota_optimista`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cota_optimista: Option<i64>,
+}
+
+impl Deadline {
+    /// Sin límite de tiempo (solo se corta con `cancelar`)
+    pub fn sin_limite() -> Self {
+        Self::default()
+    }
+
+    /// Vence `duracion` después de ahora
+    pub fn en(duracion: Duration) -> Self {
+        Deadline { limite: Some(Instant::now() + duracion), ..Self::default() }
+    }
+
+    /// Límite de una petición: `timeout_ms`, luego `solve_timeout_ms` de la
+    /// configuración (`SOLVE_TIMEOUT_MS`, por defecto 30 s).
+    /// En modo rápido, la latencia objetivo (ver `rapido::latencia_objetivo_ms`).
+    pub fn desde_params(params: &InputParams) -> Self {
+        if params.solver == SolverMode::Rapido {
+            return Self::en(Duration::from_millis(crate::algorithm::rapido::latencia_objetivo_ms(params)));
+        }
+        let ms = params.timeout_ms.unwrap_or_else(|| crate::app_config::actual().solve_timeout_ms);
+        Self::en(Duration::from_millis(ms))
+    }
+
+    /// Pide a los bucles que se detengan en su próximo chequeo
+    pub fn cancelar(&self) {
+        self.cancelado.store(true, Ordering::Relaxed);
+    }
+
+    /// true si se canceló o pasó el límite. La primera vez que devuelve true
+    /// marca el resultado como parcial.
+    pub fn vencido(&self) -> bool {
+        let vencido = self.cancelado.load(Ordering::Relaxed)
+            || self.limite.is_some_and(|l| Instant::now() >= l);
+        if vencido {
+            self.alcanzado.store(true, Ordering::Relaxed);
+        }
+        vencido
+    }
+
+    /// true si algún bucle se cortó por este deadline (resultado parcial)
+    pub fn alcanzado(&self) -> bool {
+        self.alcanzado.load(Ordering::Relaxed)
+    }
+
+    /// Registra cuántas secciones quedaron tras el filtrado
+    pub fn registrar_secciones_viables(&self, n: usize) {
+        self.progreso.secciones_viables.store(n, Ordering::Relaxed);
+    }
+
+    /// Registra un clique nuevo encontrado por el greedy o el enumerador
+    pub fn registrar_clique(&self) {
+        self.progreso.cliques.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Registra la cota optimista del score calculada por el greedy
+    pub fn registrar_cota_optimista(&self, cota: i64) {
+        self.progreso.cota_optimista.store(cota, Ordering::Relaxed);
+    }
+
+    pub fn progreso(&self) -> Progreso {
+        let cota = self.progreso.cota_optimista.load(Ordering::Relaxed);
+        Progreso {
+            secciones_viables: self.progreso.secciones_viables.load(Ordering::Relaxed),
+            cliques: self.progreso.cliques.load(Ordering::Relaxed),
+            cota_optimista: (cota != i64::MIN).then_some(cota),
+        }
+    }
+
+    /// Tiempo que queda, o `None` si no hay límite
+    pub fn restante(&self) -> Option<Duration> {
+        self.limite.map(|l| l.saturating_duration_since(Instant::now()))
+    }
+
+    /// Guardia que cancela el deadline al soltarse. Los handlers la mantienen
+    /// viva mientras esperan al solver: si actix descarta el futuro (cliente
+    /// desconectado), el hilo bloqueante se libera en el siguiente chequeo.
+    pub fn cancelar_al_soltar(&self) -> CancelarAlSoltar {
+        CancelarAlSoltar(self.clone())
+    }
+}
+
+pub struct CancelarAlSoltar(Deadline);
+
+impl Drop for CancelarAlSoltar {
+    fn drop(&mut self) {
+        self.0.cancelar();
+    }
+}
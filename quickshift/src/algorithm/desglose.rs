@@ -0,0 +1,146 @@
+// desglose.rs - ¿Por qué se recomendó esta solución?
+//
+// Descompone el `total_score` de una solución en la prioridad base (suma de
+// prioridades PERT de sus secciones) y el aporte de cada modificador de
+// `clique::apply_optimization_modifiers`. Agrega los filtros que la solución
+// no cumple (solo ocurre cuando `ruta` devuelve la mejor solución sin filtrar
+// porque los filtros eliminaron todas), la dificultad esperada de cada ramo y
+// los componentes CC UU KK SS de la prioridad de cada sección.
+
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use crate::algorithm::carga_diaria;
+use crate::algorithm::clique::{modificadores_score, ramos_reprobados_en, ModificadoresScore};
+use crate::algorithm::filters::{apply_all_filters, solapan_horarios};
+use crate::algorithm::prioridad::{prioridad_de, Priority};
+use crate::algorithm::progreso_esperado::{probabilidad_aprobar, ramo_de_seccion};
+use crate::algorithm::traslado;
+use crate::api_json::InputParams;
+use crate::models::{RamoDisponible, Seccion, UserFilters};
+
+/// Dificultad esperada de un ramo de la solución
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DificultadRamo {
+    pub codigo: String,
+    pub nombre: String,
+    /// Porcentaje histórico de aprobados (None si no hay dato)
+    pub porcentaje_aprobacion: Option<f64>,
+    /// Probabilidad de aprobar según `student_ranking` (ver `progreso_esperado`)
+    pub probabilidad_aprobar: f64,
+}
+
+/// Prioridad de una sección de la solución, por componente
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrioridadSeccion {
+    pub codigo: String,
+    pub seccion: String,
+    #[serde(flatten)]
+    pub prioridad: Priority,
+    /// `prioridad` con los pesos del request
+    pub score: i64,
+}
+
+/// Desglose del score de una solución
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DesgloseScore {
+    /// Score antes de modificadores: prioridad PERT de las secciones
+    pub prioridad_base: i64,
+    #[serde(flatten)]
+    pub modificadores: ModificadoresScore,
+    pub total: i64,
+    /// Filtros pedidos que la solución no cumple ("horarios_prohibidos",
+    /// "dias_horarios_libres", "ventana_entre_actividades", "profesores",
+    /// "solo_con_cupos", "max_ramos_por_dia", "rango_horario", "traslado")
+    pub relajaciones: Vec<String>,
+    /// Ramos de `ramos_reprobados` que la solución vuelve a tomar (el bonus
+    /// va en `ramos_reprobados`, con los demás modificadores)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub retomas: Vec<String>,
+    pub dificultad: Vec<DificultadRamo>,
+    pub prioridades: Vec<PrioridadSeccion>,
+}
+
+/// Filtros estrictos que `solucion` incumple
+pub fn relajaciones(solucion: &[(Seccion, i32)], params: &InputParams) -> Vec<String> {
+    let mut out = Vec::new();
+    if !params.horarios_prohibidos.is_empty()
+        && solucion.iter().any(|(s, _)| solapan_horarios(&s.horario, &params.horarios_prohibidos))
+    {
+        out.push("horarios_prohibidos".to_string());
+    }
+    if let Some(f) = params.filtros.as_ref() {
+        let incumple = |solo: UserFilters| apply_all_filters(vec![(solucion.to_vec(), 0)], &Some(solo)).is_empty();
+        if incumple(UserFilters { dias_horarios_libres: f.dias_horarios_libres.clone(), ..Default::default() }) {
+            out.push("dias_horarios_libres".to_string());
+        }
+        if incumple(UserFilters { ventana_entre_actividades: f.ventana_entre_actividades.clone(), ..Default::default() }) {
+            out.push("ventana_entre_actividades".to_string());
+        }
+        if incumple(UserFilters { preferencias_profesores: f.preferencias_profesores.clone(), ..Default::default() }) {
+            out.push("profesores".to_string());
+        }
+        if f.solo_con_cupos && solucion.iter().any(|(s, _)| s.sin_cupos()) {
+            out.push("solo_con_cupos".to_string());
+        }
+        if f.max_ramos_por_dia.is_some_and(|tope| carga_diaria::excede_ramos_por_dia(solucion.iter().map(|(s, _)| s), tope)) {
+            out.push("max_ramos_por_dia".to_string());
+        }
+        if carga_diaria::rango_horario(&params.filtros).is_some_and(|r| solucion.iter().any(|(s, _)| carga_diaria::fuera_de_rango(s, r))) {
+            out.push("rango_horario".to_string());
+        }
+    }
+    if let Some(t) = traslado::traslado_estricto(params)
+        && traslado::traslados_insuficientes(solucion.iter().map(|(s, _)| s), traslado::minutos(t)) > 0
+    {
+        out.push("traslado".to_string());
+    }
+    out
+}
+
+/// Desglosa `total_score` de `solucion` tal como lo calculó el clique
+pub fn desglosar(
+    solucion: &[(Seccion, i32)],
+    total_score: i64,
+    params: &InputParams,
+    ramos: &HashMap<String, RamoDisponible>,
+) -> DesgloseScore {
+    let scoring = crate::scoring::efectiva(params);
+    let modificadores = modificadores_score(solucion, params, &scoring);
+    let mut vistos: HashSet<String> = HashSet::new();
+    let dificultad = solucion.iter()
+        .filter_map(|(s, _)| {
+            let ramo = ramo_de_seccion(s, ramos);
+            let codigo = ramo.map(|r| r.codigo.clone()).filter(|c| !c.is_empty()).unwrap_or_else(|| s.codigo.clone());
+            if !vistos.insert(codigo.trim().to_uppercase()) {
+                return None;
+            }
+            let porcentaje = ramo.and_then(|r| r.dificultad);
+            Some(DificultadRamo {
+                codigo,
+                nombre: ramo.map(|r| r.nombre.clone()).unwrap_or_else(|| s.nombre.clone()),
+                porcentaje_aprobacion: porcentaje,
+                probabilidad_aprobar: probabilidad_aprobar(porcentaje, params.student_ranking),
+            })
+        })
+        .collect();
+    let prioridades = solucion.iter()
+        .map(|(s, _)| {
+            let prioridad = prioridad_de(s, ramos);
+            PrioridadSeccion {
+                codigo: s.codigo.clone(),
+                seccion: s.seccion.clone(),
+                prioridad,
+                score: prioridad.score(&scoring),
+            }
+        })
+        .collect();
+    DesgloseScore {
+        prioridad_base: total_score - modificadores.total(),
+        modificadores,
+        total: total_score,
+        relajaciones: relajaciones(solucion, params),
+        retomas: ramos_reprobados_en(solucion, params),
+        dificultad,
+        prioridades,
+    }
+}
@@ -0,0 +1,122 @@
+// diagnostico_grafo.rs - Grafo de compatibilidad sin enumerar (`/solve?debug=graph`)
+//
+// Cuando `/solve` devuelve soluciones de pocos ramos casi siempre es porque el
+// grafo que recorre el clique quedó chico o partido: los filtros dejaron pocas
+// secciones, o las que quedan chocan entre sí. Este diagnóstico arma el mismo
+// grafo que el solve (`ruta::secciones_para_clique` y
+// `clique::grafo_busqueda`, con los paquetes cátedra + laboratorio colapsados)
+// y en vez de enumerar informa las secciones que quedaron, el grado de cada
+// una, las componentes conexas y las secciones aisladas.
+
+use std::collections::{BTreeSet, HashMap};
+use serde::Serialize;
+use crate::algorithm::clique::{grafo_busqueda, GrafoBusqueda};
+use crate::algorithm::indice_ramos::RamoIndex;
+use crate::api_json::InputParams;
+use crate::models::{RamoDisponible, Seccion};
+
+/// Una sección (o paquete) del grafo
+#[derive(Debug, Clone, Serialize)]
+pub struct NodoDiagnostico {
+    #[serde(flatten)]
+    pub seccion: Seccion,
+    /// Secciones compatibles con esta
+    pub grado: usize,
+    /// Posición de su componente en `DiagnosticoGrafo::componentes`
+    pub componente: usize,
+}
+
+/// Componente conexa: secciones que se alcanzan unas a otras por aristas
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponenteGrafo {
+    pub secciones: usize,
+    /// Códigos de los ramos de la componente, sin repetir
+    pub ramos: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticoGrafo {
+    /// Secciones de la oferta que pasaron el filtrado de `ruta` (antes del clique)
+    pub secciones_viables: usize,
+    /// Nodos del grafo: las viables que además pasan los filtros del clique
+    pub secciones: Vec<NodoDiagnostico>,
+    /// Pares de secciones compatibles
+    pub aristas: usize,
+    /// Componentes conexas, de mayor a menor
+    pub componentes: Vec<ComponenteGrafo>,
+    /// Secciones sin ninguna compatible (`codigo_box`, o `codigo-seccion` si no trae)
+    pub aisladas: Vec<String>,
+}
+
+fn etiqueta(s: &Seccion) -> String {
+    if s.codigo_box.trim().is_empty() {
+        format!("{}-{}", s.codigo, s.seccion)
+    } else {
+        s.codigo_box.clone()
+    }
+}
+
+/// Diagnóstico del grafo que el clique armaría sobre `viables`
+pub fn diagnosticar(viables: &[Seccion], ramos: &HashMap<String, RamoDisponible>, params: &InputParams) -> DiagnosticoGrafo {
+    let (colapsadas, _) = crate::algorithm::bundles::colapsar(viables);
+    let GrafoBusqueda { secciones, adj } = grafo_busqueda(&colapsadas, &RamoIndex::new(ramos), params, None);
+    let n = secciones.len();
+    let grados: Vec<usize> = adj.iter().map(|fila| fila.iter().filter(|&&c| c).count()).collect();
+
+    // Componentes por recorrido en profundidad, en orden de la primera sección
+    let mut de_nodo: Vec<Option<usize>> = vec![None; n];
+    let mut miembros: Vec<Vec<usize>> = Vec::new();
+    for inicio in 0..n {
+        if de_nodo[inicio].is_some() {
+            continue;
+        }
+        let id = miembros.len();
+        let mut pila = vec![inicio];
+        let mut componente = Vec::new();
+        de_nodo[inicio] = Some(id);
+        while let Some(i) = pila.pop() {
+            componente.push(i);
+            for j in 0..n {
+                if adj[i][j] && de_nodo[j].is_none() {
+                    de_nodo[j] = Some(id);
+                    pila.push(j);
+                }
+            }
+        }
+        miembros.push(componente);
+    }
+
+    // De mayor a menor (a igual tamaño, en orden de aparición)
+    let mut orden: Vec<usize> = (0..miembros.len()).collect();
+    orden.sort_by(|&a, &b| miembros[b].len().cmp(&miembros[a].len()).then(a.cmp(&b)));
+    let mut posicion = vec![0; miembros.len()];
+    for (pos, &id) in orden.iter().enumerate() {
+        posicion[id] = pos;
+    }
+    let componentes = orden.iter().map(|&id| ComponenteGrafo {
+        secciones: miembros[id].len(),
+        ramos: miembros[id].iter()
+            .map(|&i| secciones[i].codigo.trim().to_uppercase())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+    }).collect();
+
+    let aisladas = (0..n).filter(|&i| grados[i] == 0).map(|i| etiqueta(&secciones[i])).collect();
+    let aristas = grados.iter().sum::<usize>() / 2;
+    let nodos = secciones.into_iter().enumerate()
+        .map(|(i, seccion)| NodoDiagnostico {
+            seccion,
+            grado: grados[i],
+            componente: de_nodo[i].map(|id| posicion[id]).unwrap_or(0),
+        })
+        .collect();
+
+    DiagnosticoGrafo {
+        secciones_viables: viables.len(),
+        secciones: nodos,
+        aristas,
+        componentes,
+        aisladas,
+    }
+}
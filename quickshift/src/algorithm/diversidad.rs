@@ -0,0 +1,59 @@
+// diversidad.rs - Variación determinista de soluciones
+//
+// Dos piezas, ambas opt-in desde `InputParams`:
+// - `diversity_seed`: el desempate entre candidatos de igual prioridad deja de
+//   ser por índice y pasa a un orden pseudoaleatorio fijado por la semilla.
+//   Con la misma semilla el resultado es siempre el mismo.
+// - `diversity_min_diff`: post-selección tipo MMR (maximal marginal relevance)
+//   sobre las soluciones ya ordenadas: se toma la siguiente de mejor posición
+//   que difiere en al menos K secciones de todas las ya elegidas; las que no
+//   cumplen quedan al final, en su orden original.
+
+use std::collections::HashSet;
+use crate::models::Seccion;
+
+/// Mezcla de bits splitmix64: barata, sin dependencias y estable entre plataformas
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Clave de desempate del candidato `indice`: el índice mismo sin semilla, o
+/// un valor pseudoaleatorio determinado por (semilla, índice)
+pub fn clave_desempate(semilla: Option<u64>, indice: usize) -> u64 {
+    match semilla {
+        Some(s) => splitmix64(splitmix64(s) ^ indice as u64),
+        None => indice as u64,
+    }
+}
+
+/// Cantidad de secciones en que difieren dos soluciones: las que una tiene y
+/// la otra no (por `codigo_box`), tomando el mayor de ambos lados
+pub fn diferencia_secciones(a: &[(Seccion, i32)], b: &[(Seccion, i32)]) -> usize {
+    let sa: HashSet<&str> = a.iter().map(|(s, _)| s.codigo_box.as_str()).collect();
+    let sb: HashSet<&str> = b.iter().map(|(s, _)| s.codigo_box.as_str()).collect();
+    sa.difference(&sb).count().max(sb.difference(&sa).count())
+}
+
+/// Reordena `soluciones` (ya ordenadas de mejor a peor) para que las primeras
+/// difieran entre sí en al menos `min_diff` secciones. No descarta ninguna.
+pub fn diversificar(soluciones: Vec<(Vec<(Seccion, i32)>, i64)>, min_diff: usize) -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    if min_diff == 0 || soluciones.len() < 2 {
+        return soluciones;
+    }
+    let mut elegidas: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::with_capacity(soluciones.len());
+    let mut resto: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::new();
+    for candidata in soluciones {
+        let distinta = elegidas.iter().all(|(sol, _)| diferencia_secciones(sol, &candidata.0) >= min_diff);
+        if distinta {
+            elegidas.push(candidata);
+        } else {
+            resto.push(candidata);
+        }
+    }
+    tracing::debug!("   [DIVERSIDAD] {} soluciones difieren en >= {} secciones; {} quedan al final", elegidas.len(), min_diff, resto.len());
+    elegidas.extend(resto);
+    elegidas
+}
@@ -0,0 +1,140 @@
+// doble_titulacion.rs - Planificación para estudiantes con dos (o más) programas
+//
+// Las mallas adicionales se fusionan sobre la malla principal:
+// - Un ramo es COMPARTIDO si tiene el mismo nombre normalizado que un ramo de la
+//   principal, o si su código (tras aplicar las equivalencias de su malla) coincide.
+// - Los ramos no compartidos reciben IDs desplazados (`offset`) para no chocar con
+//   los de la principal; sus `requisitos_ids` se remapean al nuevo espacio de IDs,
+//   de modo que PERT y el podado trabajan sobre un único grafo de prerequisitos.
+// - Los requisitos de un ramo compartido son la unión de ambos programas.
+
+use std::collections::{HashMap, HashSet};
+use crate::algorithm::aprobados::Aprobados;
+use crate::models::RamoDisponible;
+
+/// Desplazamiento de IDs por cada malla adicional (las mallas tienen < 1000 ramos)
+pub const ID_OFFSET_POR_MALLA: i32 = 1000;
+
+/// Avance de un programa: cuántos ramos de su malla ya fueron aprobados
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProgresoPrograma {
+    pub malla: String,
+    pub total_ramos: usize,
+    pub aprobados: usize,
+    pub pendientes: usize,
+    pub porcentaje: f64,
+    /// Ramos de esta malla que también cuentan para otro programa
+    pub compartidos: usize,
+}
+
+/// Fusiona `adicional` dentro de `principal`. Devuelve las claves (en `principal`)
+/// de todos los ramos que pertenecen a la malla adicional, compartidos o no, y el
+/// subconjunto de claves compartidas.
+pub fn fusionar_malla(
+    principal: &mut HashMap<String, RamoDisponible>,
+    adicional: HashMap<String, RamoDisponible>,
+    equivalencias: &HashMap<String, String>,
+    offset: i32,
+) -> (HashSet<String>, HashSet<String>) {
+    // Índices de la principal por código para detectar equivalencias
+    let por_codigo: HashMap<String, String> = principal
+        .iter()
+        .filter(|(_, r)| !r.codigo.trim().is_empty())
+        .map(|(k, r)| (r.codigo.trim().to_uppercase(), k.clone()))
+        .collect();
+
+    // 1) Resolver a qué clave de la principal corresponde cada ramo adicional
+    let mut destino: HashMap<String, Option<String>> = HashMap::new(); // clave adicional -> clave compartida
+    for (key, ramo) in adicional.iter() {
+        let mut compartido = if principal.contains_key(key) { Some(key.clone()) } else { None };
+        if compartido.is_none() && !ramo.codigo.trim().is_empty() {
+            let codigo = ramo.codigo.trim().to_uppercase();
+            let equivalente = equivalencias.get(&codigo).cloned().unwrap_or(codigo);
+            compartido = por_codigo.get(&equivalente).cloned();
+        }
+        destino.insert(key.clone(), compartido);
+    }
+
+    // 2) Mapa de IDs de la malla adicional al espacio fusionado
+    let mut id_map: HashMap<i32, i32> = HashMap::new();
+    for (key, ramo) in adicional.iter() {
+        let nuevo = match destino.get(key).and_then(|d| d.as_ref()) {
+            Some(k) => principal.get(k).map(|r| r.id).unwrap_or(ramo.id + offset),
+            None => ramo.id + offset,
+        };
+        id_map.insert(ramo.id, nuevo);
+    }
+
+    // 3) Insertar / unir
+    let mut claves = HashSet::new();
+    let mut compartidas = HashSet::new();
+    let mut ordenadas: Vec<(String, RamoDisponible)> = adicional.into_iter().collect();
+    ordenadas.sort_by(|a, b| a.0.cmp(&b.0));
+    for (key, mut ramo) in ordenadas {
+        let requisitos: Vec<i32> = ramo.requisitos_ids.iter()
+            .map(|id| id_map.get(id).copied().unwrap_or(id + offset))
+            .collect();
+        match destino.get(&key).cloned().flatten() {
+            Some(k) => {
+                if let Some(existente) = principal.get_mut(&k) {
+                    for req in requisitos {
+                        if req != existente.id && !existente.requisitos_ids.contains(&req) {
+                            existente.requisitos_ids.push(req);
+                        }
+                    }
+                    if existente.codigo.trim().is_empty() && !ramo.codigo.trim().is_empty() {
+                        existente.codigo = ramo.codigo.clone();
+                    }
+                }
+                claves.insert(k.clone());
+                compartidas.insert(k);
+            }
+            None => {
+                ramo.id = id_map.get(&ramo.id).copied().unwrap_or(ramo.id + offset);
+                ramo.requisitos_ids = requisitos;
+                claves.insert(key.clone());
+                principal.insert(key, ramo);
+            }
+        }
+    }
+
+    (claves, compartidas)
+}
+
+/// Unión de los aprobados de cada programa (ya traducidos con sus equivalencias),
+/// sin repetir y en el orden en que aparecen
+pub fn unir_aprobados(por_programa: &[Vec<String>]) -> Vec<String> {
+    let mut vistos = HashSet::new();
+    por_programa.iter()
+        .flatten()
+        .filter(|c| vistos.insert(c.trim().to_uppercase()))
+        .cloned()
+        .collect()
+}
+
+/// Calcula el avance de un programa dado el conjunto de claves de su malla
+pub fn progreso_programa(
+    malla: &str,
+    claves: &HashSet<String>,
+    ramos: &HashMap<String, RamoDisponible>,
+    ramos_pasados: &[String],
+    compartidas: &HashSet<String>,
+) -> ProgresoPrograma {
+    let aprobados = Aprobados::new(ramos_pasados);
+
+    let aprobados = claves.iter()
+        .filter_map(|k| ramos.get(k))
+        .filter(|r| aprobados.contiene(r))
+        .count();
+    let total = claves.len();
+    let porcentaje = if total == 0 { 0.0 } else { (aprobados as f64) * 100.0 / (total as f64) };
+
+    ProgresoPrograma {
+        malla: malla.to_string(),
+        total_ramos: total,
+        aprobados,
+        pendientes: total.saturating_sub(aprobados),
+        porcentaje,
+        compartidos: claves.iter().filter(|k| compartidas.contains(*k)).count(),
+    }
+}
@@ -0,0 +1,120 @@
+// en_memoria.rs - Resolución completa a partir de datos ya cargados (sin Excel ni disco)
+//
+// Pensado para entornos sin sistema de archivos (p.ej. un worker wasm32): el
+// cliente envía la malla (`ramos`), la oferta (`secciones`) y opcionalmente la
+// hoja de prerequisitos y las equivalencias como JSON. Se ejecutan las mismas
+// fases que `ruta::ejecutar_ruta_critica_with_params`:
+//   PHASE 0  equivalencias sobre `ramos_pasados`
+//   PHASE 1  podado de ramos inviables
+//   PHASE 2  marcado de electivos + PERT
+//   PHASE 3+ `ruta::resolver_con_datos` (filtros, clique, selección final)
+//
+// Ninguna función de este módulo usa actix, tokio ni `std::fs`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use serde::Deserialize;
+use serde_json::json;
+use crate::api_json::InputParams;
+use crate::excel::normalize_name;
+use crate::models::{RamoDisponible, Seccion};
+use crate::algorithm::ruta::{self, DatosRuta};
+use crate::algorithm::engine::{self, SchedulePlanner};
+use crate::algorithm::balance_lineas::{balance_logrado, balance_pedido};
+
+/// Entrada de una resolución en memoria. Los campos de `InputParams` van en el
+/// mismo nivel del JSON (`email`, `ramos_pasados`, `malla`, `filtros`, ...);
+/// `malla` se usa solo como etiqueta.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SolveEnMemoria {
+    #[serde(flatten)]
+    pub params: InputParams,
+    /// Ramos de la malla curricular
+    pub ramos: Vec<RamoDisponible>,
+    /// Secciones de la oferta académica (incluidos CFG con `is_cfg = true`)
+    pub secciones: Vec<Seccion>,
+    /// Hoja de prerequisitos: código/nombre del ramo -> códigos/nombres de sus prerequisitos
+    #[serde(default)]
+    pub prerequisitos: HashMap<String, Vec<String>>,
+    /// Equivalencias entre códigos antiguos y códigos de la malla actual
+    #[serde(default)]
+    pub equivalencias: HashMap<String, String>,
+}
+
+/// PHASES 0-2b sobre datos en memoria. Equivalente a `ruta::preparar_datos_ruta`
+/// pero sin resolver ni leer archivos.
+pub fn preparar_datos_en_memoria(
+    params: &mut InputParams,
+    ramos: Vec<RamoDisponible>,
+    mut lista_secciones: Vec<Seccion>,
+    prerequisitos: &HashMap<String, Vec<String>>,
+    equivalencias: &HashMap<String, String>,
+) -> DatosRuta {
+    let ramos_disponibles: HashMap<String, RamoDisponible> = ramos.into_iter()
+        .map(|r| (normalize_name(&r.nombre), r))
+        .collect();
+    if !equivalencias.is_empty() {
+        let tabla: Vec<(String, String)> = equivalencias.iter().map(|(a, n)| (a.clone(), n.clone())).collect();
+        params.ramos_pasados = ruta::aplicar_tabla_equivalencias(&params.ramos_pasados, &tabla, &ramos_disponibles);
+    }
+    let mut ramos_disponibles: HashMap<String, RamoDisponible> =
+        crate::algorithm::pert::build_viable_ramos(&ramos_disponibles, &params.ramos_pasados)
+            .into_iter()
+            .collect();
+
+    ruta::marcar_electivos(&mut lista_secciones, &ramos_disponibles);
+
+    let prerequisitos = if prerequisitos.is_empty() { None } else { Some(prerequisitos) };
+    if let Err(e) = crate::algorithm::pert::build_and_run_pert_con_prerequisitos(
+        &mut ramos_disponibles,
+        &lista_secciones,
+        prerequisitos,
+    ) {
+        tracing::warn!("   ⚠️  PERT aviso: {:?}", e);
+    }
+
+    DatosRuta {
+        malla_path: params.malla.clone(),
+        ramos_disponibles,
+        lista_secciones,
+        progreso: Vec::new(),
+        compartidos: Vec::new(),
+    }
+}
+
+/// Soluciones ordenadas por score: (secciones con su prioridad, score total)
+pub type Soluciones = Vec<(Vec<(Seccion, i32)>, i64)>;
+
+/// Ejecuta el pipeline completo sobre una entrada en memoria
+pub fn resolver_en_memoria(entrada: SolveEnMemoria) -> Result<Soluciones, Box<dyn Error>> {
+    let SolveEnMemoria { params, ramos, secciones, prerequisitos, equivalencias } = entrada;
+    engine::EnMemoria { ramos, secciones, prerequisitos, equivalencias }.planificar(params)
+}
+
+/// Punto de entrada JSON -> JSON para integraciones sin servidor HTTP.
+/// Devuelve el mismo formato de soluciones que `POST /solve`.
+pub fn resolver_json(body: &str) -> Result<serde_json::Value, String> {
+    let entrada: SolveEnMemoria = serde_json::from_str(body)
+        .map_err(|e| format!("invalid JSON body: {}", e))?;
+    let balance = balance_pedido(&entrada.params).cloned();
+    let soluciones = resolver_en_memoria(entrada)
+        .map_err(|e| format!("ruta_critica failed: {}", e))?;
+
+    let entries: Vec<serde_json::Value> = soluciones.iter()
+        .filter(|(sol, _)| !sol.is_empty())
+        .map(|(sol, score)| {
+            let mut entry = json!({
+                "total_score": score,
+                "secciones": sol.iter().map(|(sec, _)| sec).collect::<Vec<_>>(),
+            });
+            if let Some(logrado) = balance.as_ref().and_then(|b| balance_logrado(sol.iter().map(|(s, _)| s), b)) {
+                entry["balance_lineas"] = json!(logrado);
+            }
+            entry
+        })
+        .collect();
+    Ok(json!({
+        "soluciones_count": entries.len(),
+        "soluciones": entries,
+    }))
+}
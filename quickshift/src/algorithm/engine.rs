@@ -0,0 +1,88 @@
+// engine.rs - Punto de entrada único del motor de horarios (`SchedulePlanner`)
+//
+// Todo cálculo de soluciones pasa por las dos mitades del pipeline de `ruta`:
+//   - `preparar`: PHASES 0-2b (equivalencias, malla + porcentajes, oferta,
+//     electivos y PERT) -> `DatosRuta`;
+//   - `resolver`: PHASES 2c-4 (filtros, clique y selección final) sobre esos
+//     datos, con un `Deadline` opcional.
+// Lo único que cambia entre integraciones es de dónde salen malla y oferta,
+// así que cada fuente implementa `preparar` y hereda el resto:
+//   - `Datafiles`: los Excel del directorio de datafiles (`/solve`, sesiones,
+//     WebSocket, CLI);
+//   - `EnMemoria`: malla y oferta ya cargadas (wasm, `planner`, tests).
+// `ruta::ejecutar_ruta_critica_with_params` y `en_memoria::resolver_en_memoria`
+// son atajos sobre estas implementaciones; el código nuevo debería usar el
+// trait para no volver a duplicar el pipeline.
+
+use std::collections::HashMap;
+use std::error::Error;
+use crate::algorithm::clique::CompatibilityGraph;
+use crate::algorithm::deadline::Deadline;
+use crate::algorithm::en_memoria;
+use crate::algorithm::paralelo::Soluciones;
+use crate::algorithm::ruta::{self, DatosRuta};
+use crate::api_json::InputParams;
+use crate::models::{RamoDisponible, Seccion};
+
+/// Planificador de un semestre: carga los datos y arma las soluciones
+pub trait SchedulePlanner {
+    /// PHASES 0-2b. Puede reescribir `params.ramos_pasados` (equivalencias).
+    fn preparar(&self, params: &mut InputParams) -> Result<DatosRuta, Box<dyn Error>>;
+
+    /// PHASES 2c-4 sobre datos ya preparados; al vencer `deadline` devuelve
+    /// las mejores soluciones encontradas hasta entonces
+    fn resolver(&self, datos: &DatosRuta, params: &InputParams, deadline: &Deadline) -> Result<Soluciones, Box<dyn Error>> {
+        self.resolver_con_grafo(datos, params, None, deadline)
+    }
+
+    /// `resolver` reutilizando un grafo de compatibilidad ya construido sobre
+    /// `datos` (sesiones y WebSocket, que resuelven varias veces)
+    fn resolver_con_grafo(
+        &self,
+        datos: &DatosRuta,
+        params: &InputParams,
+        grafo: Option<&CompatibilityGraph>,
+        deadline: &Deadline,
+    ) -> Result<Soluciones, Box<dyn Error>> {
+        ruta::resolver_con_datos_hasta(datos, params, grafo, deadline)
+    }
+
+    /// Pipeline completo sin límite de tiempo
+    fn planificar(&self, mut params: InputParams) -> Result<Soluciones, Box<dyn Error>> {
+        let datos = self.preparar(&mut params)?;
+        self.resolver(&datos, &params, &Deadline::sin_limite())
+    }
+}
+
+/// Malla, oferta y porcentajes del directorio de datafiles (`params.malla`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Datafiles;
+
+impl SchedulePlanner for Datafiles {
+    fn preparar(&self, params: &mut InputParams) -> Result<DatosRuta, Box<dyn Error>> {
+        ruta::preparar_datos_ruta(params)
+    }
+}
+
+/// Malla y oferta entregadas por el llamador; no lee archivos
+#[derive(Debug, Clone, Default)]
+pub struct EnMemoria {
+    pub ramos: Vec<RamoDisponible>,
+    pub secciones: Vec<Seccion>,
+    /// código -> códigos de sus prerequisitos
+    pub prerequisitos: HashMap<String, Vec<String>>,
+    /// código antiguo -> código vigente
+    pub equivalencias: HashMap<String, String>,
+}
+
+impl SchedulePlanner for EnMemoria {
+    fn preparar(&self, params: &mut InputParams) -> Result<DatosRuta, Box<dyn Error>> {
+        Ok(en_memoria::preparar_datos_en_memoria(
+            params,
+            self.ramos.clone(),
+            self.secciones.clone(),
+            &self.prerequisitos,
+            &self.equivalencias,
+        ))
+    }
+}
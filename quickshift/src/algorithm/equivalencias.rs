@@ -0,0 +1,283 @@
+// equivalencias.rs - Códigos históricos -> códigos de la malla vigente
+//
+// La hoja "Equivalencias" de una malla lista pares (código antiguo, código
+// nuevo). Un estudiante que viene de una malla anterior trae en
+// `ramos_pasados` códigos que no existen en la vigente; `resolver` los lleva a
+// la malla siguiendo la tabla:
+// - Encadenadas: si el código nuevo de un par es a su vez antiguo en otro
+//   (A→B y B→C) se sigue hasta llegar a un código de la malla (C).
+// - Ambiguas: si un código lleva a más de un código de la malla (A→B y A→C)
+//   no se elige ninguno; queda como estaba y se informa con sus candidatos.
+// - Duplicados: dos códigos aprobados que llegan al mismo ramo de la malla
+//   cuentan una sola vez.
+// Un código con fila en la tabla se reemplaza aunque también esté en la
+// malla (así lo hacía `excel::aplicar_equivalencias`); los demás no se tocan.
+//
+// `analizar` resume la tabla completa (para `GET /equivalencias`) y
+// `duplicados_entre_mallas` detecta ramos de otras mallas con el mismo nombre
+// que uno de la vigente pero otro código y sin fila en la tabla.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use serde::Serialize;
+use crate::excel::normalize_name;
+use crate::models::RamoDisponible;
+
+/// Cómo se resolvió un código
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoEquivalencia {
+    /// Es un código de la malla sin fila en la tabla
+    EnMalla,
+    /// Un par de la tabla lo lleva a la malla
+    Directa,
+    /// Se llegó a la malla pasando por otros códigos (A→B→C)
+    Encadenada,
+    /// Lleva a más de un código; no se aplica
+    Ambigua,
+    /// No está en la malla ni en la tabla; queda como está
+    SinEquivalencia,
+    /// La tabla solo forma ciclos desde este código; no se aplica
+    Ciclo,
+}
+
+/// Resultado de un código de entrada
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CodigoResuelto {
+    pub codigo: String,
+    pub estado: EstadoEquivalencia,
+    /// Código con que cuenta en la malla (`None` si es ambiguo o cíclico)
+    pub destino: Option<String>,
+    /// Códigos recorridos, del original al destino (solo si hubo equivalencia)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cadena: Vec<String>,
+    /// Destinos posibles de un código ambiguo
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidatos: Vec<String>,
+}
+
+/// Ramo de la malla al que llegan varios códigos
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Duplicado {
+    pub codigo: String,
+    pub origenes: Vec<String>,
+}
+
+/// Resultado de `resolver`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResolucionEquivalencias {
+    /// `ramos_pasados` listos para la malla: destinos sin repetir, y los
+    /// códigos ambiguos o sin equivalencia tal como venían
+    pub ramos_pasados: Vec<String>,
+    pub codigos: Vec<CodigoResuelto>,
+    pub duplicados: Vec<Duplicado>,
+}
+
+impl ResolucionEquivalencias {
+    /// Códigos que cambiaron o que no se pudieron resolver (todo salvo `en_malla`)
+    pub fn cambios(&self) -> impl Iterator<Item = &CodigoResuelto> {
+        self.codigos.iter().filter(|c| c.estado != EstadoEquivalencia::EnMalla)
+    }
+}
+
+fn clave(codigo: &str) -> String {
+    codigo.trim().to_uppercase()
+}
+
+/// Tabla antiguo -> nuevos (sin repetir, en orden)
+fn indexar(tabla: &[(String, String)]) -> BTreeMap<String, Vec<String>> {
+    let mut por_antiguo: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (antiguo, nuevo) in tabla {
+        let (antiguo, nuevo) = (clave(antiguo), clave(nuevo));
+        if antiguo.is_empty() || nuevo.is_empty() || antiguo == nuevo {
+            continue;
+        }
+        let destinos = por_antiguo.entry(antiguo).or_default();
+        if !destinos.contains(&nuevo) {
+            destinos.push(nuevo);
+        }
+    }
+    por_antiguo
+}
+
+/// Recorre la tabla desde `actual` y junta los caminos que terminan en la
+/// malla o en un código sin más equivalencias; los ciclos se descartan.
+fn caminos(
+    actual: &str,
+    por_antiguo: &BTreeMap<String, Vec<String>>,
+    malla: &HashSet<String>,
+    camino: &mut Vec<String>,
+    salida: &mut Vec<Vec<String>>,
+) {
+    // El código de partida siempre se sigue, aunque esté en la malla
+    let inicio = camino.is_empty();
+    camino.push(actual.to_string());
+    match por_antiguo.get(actual) {
+        Some(destinos) if inicio || !malla.contains(actual) => {
+            for d in destinos {
+                if !camino.contains(d) {
+                    caminos(d, por_antiguo, malla, camino, salida);
+                }
+            }
+        }
+        _ => salida.push(camino.clone()),
+    }
+    camino.pop();
+}
+
+fn resolver_codigo(codigo: &str, por_antiguo: &BTreeMap<String, Vec<String>>, malla: &HashSet<String>) -> CodigoResuelto {
+    let codigo = clave(codigo);
+    let resuelto = |estado, destino, cadena, candidatos| CodigoResuelto { codigo: codigo.clone(), estado, destino, cadena, candidatos };
+    if !por_antiguo.contains_key(&codigo) {
+        let estado = if malla.contains(&codigo) { EstadoEquivalencia::EnMalla } else { EstadoEquivalencia::SinEquivalencia };
+        return resuelto(estado, Some(codigo.clone()), Vec::new(), Vec::new());
+    }
+
+    let mut encontrados = Vec::new();
+    caminos(&codigo, por_antiguo, malla, &mut Vec::new(), &mut encontrados);
+    // Si alguno llega a la malla, los que no llegan son equivalencias obsoletas
+    if encontrados.iter().any(|c| c.last().is_some_and(|d| malla.contains(d))) {
+        encontrados.retain(|c| c.last().is_some_and(|d| malla.contains(d)));
+    }
+    let destinos: BTreeSet<String> = encontrados.iter().filter_map(|c| c.last().cloned()).collect();
+    match destinos.len() {
+        0 => resuelto(EstadoEquivalencia::Ciclo, None, Vec::new(), Vec::new()),
+        1 => {
+            // El camino más corto hacia el único destino
+            let cadena = encontrados.into_iter().min_by_key(|c| c.len()).unwrap_or_default();
+            let estado = if cadena.len() > 2 { EstadoEquivalencia::Encadenada } else { EstadoEquivalencia::Directa };
+            resuelto(estado, cadena.last().cloned(), cadena, Vec::new())
+        }
+        _ => resuelto(EstadoEquivalencia::Ambigua, None, Vec::new(), destinos.into_iter().collect()),
+    }
+}
+
+/// Lleva `codigos` (p.ej. `ramos_pasados` de una malla anterior) a los
+/// códigos de `malla` según la `tabla` de equivalencias (antiguo, nuevo)
+pub fn resolver(codigos: &[String], tabla: &[(String, String)], malla: &HashSet<String>) -> ResolucionEquivalencias {
+    let malla: HashSet<String> = malla.iter().map(|c| clave(c)).collect();
+    let por_antiguo = indexar(tabla);
+
+    let mut res = ResolucionEquivalencias::default();
+    let mut origenes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut vistos: HashSet<String> = HashSet::new();
+    for codigo in codigos {
+        if codigo.trim().is_empty() || !vistos.insert(clave(codigo)) {
+            continue;
+        }
+        let r = resolver_codigo(codigo, &por_antiguo, &malla);
+        let final_ = r.destino.clone().unwrap_or_else(|| r.codigo.clone());
+        let lista = origenes.entry(final_.clone()).or_default();
+        if lista.is_empty() {
+            res.ramos_pasados.push(final_);
+        }
+        lista.push(r.codigo.clone());
+        res.codigos.push(r);
+    }
+    res.duplicados = origenes.into_iter()
+        .filter(|(_, o)| o.len() > 1)
+        .map(|(codigo, origenes)| Duplicado { codigo, origenes })
+        .collect();
+    res
+}
+
+/// Un par de la tabla
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParEquivalencia {
+    pub antiguo: String,
+    pub nuevo: String,
+    /// `nuevo` es un código de la malla
+    pub en_malla: bool,
+}
+
+/// Resumen de la tabla de equivalencias de una malla (`GET /equivalencias`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AnalisisEquivalencias {
+    pub total: usize,
+    pub equivalencias: Vec<ParEquivalencia>,
+    /// Códigos antiguos que se resuelven pasando por otros (A→B→C)
+    pub encadenadas: Vec<CodigoResuelto>,
+    /// Códigos antiguos con más de un destino en la malla
+    pub ambiguas: Vec<CodigoResuelto>,
+    /// Códigos antiguos cuya tabla solo forma ciclos
+    pub ciclos: Vec<String>,
+    /// Pares cuyo `nuevo` no está en la malla ni lleva a ella
+    pub fuera_de_malla: Vec<ParEquivalencia>,
+    /// Ramos de la malla a los que llegan varios códigos antiguos
+    pub duplicados: Vec<Duplicado>,
+}
+
+/// Clasifica todos los códigos antiguos de la `tabla` frente a `malla`
+pub fn analizar(tabla: &[(String, String)], malla: &HashSet<String>) -> AnalisisEquivalencias {
+    let malla: HashSet<String> = malla.iter().map(|c| clave(c)).collect();
+    let por_antiguo = indexar(tabla);
+    let mut a = AnalisisEquivalencias::default();
+    let mut origenes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (antiguo, nuevos) in por_antiguo.iter() {
+        for nuevo in nuevos {
+            a.equivalencias.push(ParEquivalencia { antiguo: antiguo.clone(), nuevo: nuevo.clone(), en_malla: malla.contains(nuevo) });
+        }
+        let r = resolver_codigo(antiguo, &por_antiguo, &malla);
+        match r.estado {
+            EstadoEquivalencia::Encadenada => a.encadenadas.push(r.clone()),
+            EstadoEquivalencia::Ambigua => a.ambiguas.push(r.clone()),
+            EstadoEquivalencia::Ciclo => a.ciclos.push(antiguo.clone()),
+            _ => {}
+        }
+        match r.destino.filter(|d| malla.contains(d)) {
+            Some(d) => origenes.entry(d).or_default().push(antiguo.clone()),
+            None if r.estado != EstadoEquivalencia::Ambigua => {
+                a.fuera_de_malla.extend(nuevos.iter().map(|n| ParEquivalencia { antiguo: antiguo.clone(), nuevo: n.clone(), en_malla: false }));
+            }
+            None => {}
+        }
+    }
+    a.total = a.equivalencias.len();
+    a.duplicados = origenes.into_iter()
+        .filter(|(_, o)| o.len() > 1)
+        .map(|(codigo, origenes)| Duplicado { codigo, origenes })
+        .collect();
+    a
+}
+
+/// Ramo de otra malla que parece el mismo que uno de la vigente
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicadoEntreMallas {
+    /// Malla de origen
+    pub malla: String,
+    pub codigo: String,
+    pub nombre: String,
+    /// Código del ramo de la malla vigente con el mismo nombre
+    pub equivalente: String,
+}
+
+/// Ramos de `otra` con el mismo nombre normalizado que un ramo de `vigente`
+/// pero otro código, que la tabla no resuelve a ese ramo
+pub fn duplicados_entre_mallas(
+    vigente: &HashMap<String, RamoDisponible>,
+    nombre_otra: &str,
+    otra: &HashMap<String, RamoDisponible>,
+    tabla: &[(String, String)],
+) -> Vec<DuplicadoEntreMallas> {
+    let por_nombre: HashMap<String, String> = vigente.values()
+        .filter(|r| !r.codigo.trim().is_empty())
+        .map(|r| (normalize_name(&r.nombre), clave(&r.codigo)))
+        .collect();
+    let malla: HashSet<String> = por_nombre.values().cloned().collect();
+    let por_antiguo = indexar(tabla);
+    let mut salida: Vec<DuplicadoEntreMallas> = otra.values()
+        .filter_map(|r| {
+            let codigo = clave(&r.codigo);
+            let equivalente = por_nombre.get(&normalize_name(&r.nombre))?;
+            if codigo.is_empty() || codigo == *equivalente {
+                return None;
+            }
+            let resuelto = resolver_codigo(&codigo, &por_antiguo, &malla);
+            if resuelto.destino.as_deref() == Some(equivalente.as_str()) {
+                return None;
+            }
+            Some(DuplicadoEntreMallas { malla: nombre_otra.to_string(), codigo, nombre: r.nombre.clone(), equivalente: equivalente.clone() })
+        })
+        .collect();
+    salida.sort_by(|a, b| a.codigo.cmp(&b.codigo));
+    salida
+}
@@ -0,0 +1,105 @@
+// evaluaciones.rs - Carga de evaluaciones de una solución
+//
+// `Seccion::evaluaciones` viene del calendario de evaluaciones
+// (`excel::evaluaciones`). Dos cursos distintos de la solución con
+// evaluaciones el mismo día suman `PUNTOS_MISMO_DIA` a la carga y en días
+// consecutivos `PUNTOS_DIA_CONSECUTIVO`. `clique::apply_optimization_modifiers`
+// resta `ScoringConfig::penalizacion_carga_evaluaciones` por punto y `/solve`
+// informa la carga de cada solución. Sin calendario (o si ningún curso de la
+// solución tiene evaluaciones) la carga es 0.
+
+use std::collections::BTreeMap;
+use serde::Serialize;
+use crate::excel::evaluaciones::parse_fecha;
+use crate::models::Seccion;
+
+/// Puntos de carga por par de evaluaciones de cursos distintos el mismo día
+pub const PUNTOS_MISMO_DIA: u32 = 2;
+/// Puntos de carga por par de evaluaciones de cursos distintos en días consecutivos
+pub const PUNTOS_DIA_CONSECUTIVO: u32 = 1;
+
+/// Dos evaluaciones de cursos distintos demasiado cerca
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EvaluacionesCercanas {
+    pub codigo_a: String,
+    pub evaluacion_a: String,
+    pub fecha_a: String,
+    pub codigo_b: String,
+    pub evaluacion_b: String,
+    pub fecha_b: String,
+    /// 0 = mismo día, 1 = días consecutivos
+    pub dias: i64,
+}
+
+/// Carga de evaluaciones de una solución
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CargaEvaluaciones {
+    /// `PUNTOS_MISMO_DIA` por par el mismo día + `PUNTOS_DIA_CONSECUTIVO` por par en días consecutivos
+    pub carga: u32,
+    pub mismo_dia: usize,
+    pub dias_consecutivos: usize,
+    /// Evaluaciones de la solución con fecha, de todos sus cursos
+    pub evaluaciones: usize,
+    /// Pares que suman carga, por fecha
+    pub cercanas: Vec<EvaluacionesCercanas>,
+}
+
+/// (nombre, fecha, día) de cada evaluación de un curso
+type EvaluacionesCurso<'a> = Vec<(&'a str, &'a str, i64)>;
+
+/// Evaluaciones de cada curso de la solución. Las secciones de un mismo curso
+/// (cátedra y laboratorio) comparten evaluaciones.
+fn por_curso<'a>(secciones: impl IntoIterator<Item = &'a Seccion>) -> BTreeMap<String, EvaluacionesCurso<'a>> {
+    let mut cursos: BTreeMap<String, EvaluacionesCurso> = BTreeMap::new();
+    for s in secciones {
+        if s.evaluaciones.is_empty() {
+            continue;
+        }
+        cursos.entry(s.codigo.trim().to_uppercase()).or_insert_with(|| {
+            s.evaluaciones.iter()
+                .filter_map(|e| parse_fecha(&e.fecha).map(|dia| (e.nombre.as_str(), e.fecha.as_str(), dia)))
+                .collect()
+        });
+    }
+    cursos
+}
+
+/// Carga de evaluaciones de las secciones; None si ninguna tiene evaluaciones
+pub fn carga_evaluaciones<'a>(secciones: impl IntoIterator<Item = &'a Seccion>) -> Option<CargaEvaluaciones> {
+    let cursos: Vec<(String, EvaluacionesCurso)> = por_curso(secciones).into_iter().collect();
+    if cursos.is_empty() {
+        return None;
+    }
+    let mut cercanas = Vec::new();
+    for (i, (codigo_a, evs_a)) in cursos.iter().enumerate() {
+        for (codigo_b, evs_b) in &cursos[i + 1..] {
+            for (nombre_a, fecha_a, dia_a) in evs_a {
+                for (nombre_b, fecha_b, dia_b) in evs_b {
+                    let dias = (dia_a - dia_b).abs();
+                    if dias <= 1 {
+                        cercanas.push(EvaluacionesCercanas {
+                            codigo_a: codigo_a.clone(),
+                            evaluacion_a: nombre_a.to_string(),
+                            fecha_a: fecha_a.to_string(),
+                            codigo_b: codigo_b.clone(),
+                            evaluacion_b: nombre_b.to_string(),
+                            fecha_b: fecha_b.to_string(),
+                            dias,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    cercanas.sort_by_key(|c| (c.fecha_a.as_str().min(c.fecha_b.as_str()).to_string(), c.dias));
+    let mismo_dia = cercanas.iter().filter(|c| c.dias == 0).count();
+    let dias_consecutivos = cercanas.len() - mismo_dia;
+    Some(CargaEvaluaciones {
+        carga: mismo_dia as u32 * PUNTOS_MISMO_DIA + dias_consecutivos as u32 * PUNTOS_DIA_CONSECUTIVO,
+        mismo_dia,
+        dias_consecutivos,
+        evaluaciones: cursos.iter().map(|(_, evs)| evs.len()).sum(),
+        cercanas,
+    })
+}
+
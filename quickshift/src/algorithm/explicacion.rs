@@ -0,0 +1,265 @@
+// explicacion.rs - ¿Por qué un ramo no aparece en las soluciones?
+//
+// Dado el código (o nombre) de un ramo, revisa en orden cada etapa del
+// pipeline que puede dejarlo fuera y reporta los motivos concretos:
+//   1. Ya aprobado.
+//   2. Prerequisitos faltantes (IDs de malla que no están en `ramos_pasados`).
+//   3. Sin secciones en la oferta.
+//   4. Todas sus secciones descartadas por `horarios_prohibidos` o `filtros`
+//      (franjas prohibidas, días libres, sin horario, profesores, líneas).
+//   5. Todas sus secciones restantes chocan con secciones de la mejor
+//      solución (las selecciones de mayor prioridad). Las soluciones
+//      alternativas pueden incluirlo igual; por eso el análisis se hace
+//      respecto a la mejor.
+//   6. Si nada de lo anterior aplica: desplazado por puntaje o por el tope de
+//      ramos/créditos.
+// Igual que `planner`, no lee archivos: recibe malla y oferta ya cargadas.
+
+use std::collections::HashMap;
+use serde::Serialize;
+use crate::api_json::InputParams;
+use crate::excel::normalize_name;
+use crate::i18n::{tf, Idioma};
+use crate::algorithm::aprobados::Aprobados;
+use crate::models::{RamoDisponible, Seccion, UserFilters};
+use crate::algorithm::{carga_diaria, clique, conflict, en_memoria, filters, ruta};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrerequisitoFaltante {
+    pub id: i32,
+    /// Vacíos si el ID no existe en la malla
+    pub codigo: String,
+    pub nombre: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeccionDescartada {
+    pub seccion: String,
+    pub horario: Vec<String>,
+    pub profesor: String,
+    /// "horarios_prohibidos", "compromisos", "sin_cupos", "fuera_de_rango_horario", "dia_libre",
+    /// "franjas_prohibidas", "sin_horario", "profesores" o "balance_lineas"
+    pub motivo: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeccionEnConflicto {
+    pub seccion: String,
+    pub horario: Vec<String>,
+    /// Secciones de la mejor solución con las que choca (`codigo_box` o `CODIGO-seccion`)
+    pub choca_con: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum Motivo {
+    YaAprobado,
+    NoEncontrado,
+    PrerequisitosFaltantes { faltantes: Vec<PrerequisitoFaltante> },
+    SinSecciones,
+    SeccionesFiltradas { secciones: Vec<SeccionDescartada> },
+    ConflictoHorario { secciones: Vec<SeccionEnConflicto> },
+    Desplazado { detalle: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Explicacion {
+    pub ramo: String,
+    pub nombre: Option<String>,
+    /// True si el ramo aparece en al menos una solución
+    pub en_soluciones: bool,
+    /// True si aparece en la mejor solución (los motivos se explican respecto a ella)
+    pub en_mejor_solucion: bool,
+    pub motivos: Vec<Motivo>,
+}
+
+impl Explicacion {
+    /// Una frase por motivo, en `idioma` (el `resumen` de `/solve/explain`)
+    pub fn resumen(&self, idioma: Idioma) -> String {
+        let ramo = self.nombre.as_ref()
+            .map(|n| format!("{} ({})", self.ramo, n))
+            .unwrap_or_else(|| self.ramo.clone());
+        if self.en_mejor_solucion {
+            return tf("explain.en_mejor_solucion", idioma, &[&ramo]);
+        }
+        let frases: Vec<String> = self.motivos.iter()
+            .map(|m| match m {
+                Motivo::YaAprobado => tf("explain.ya_aprobado", idioma, &[&ramo]),
+                Motivo::NoEncontrado => tf("explain.no_encontrado", idioma, &[&ramo]),
+                Motivo::PrerequisitosFaltantes { faltantes } => {
+                    let lista = faltantes.iter()
+                        .map(|f| if f.codigo.is_empty() { f.id.to_string() } else { f.codigo.clone() })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    tf("explain.prerequisitos_faltantes", idioma, &[&ramo, &lista])
+                }
+                Motivo::SinSecciones => tf("explain.sin_secciones", idioma, &[&ramo]),
+                Motivo::SeccionesFiltradas { secciones } => {
+                    let mut motivos: Vec<&str> = secciones.iter().map(|s| s.motivo.as_str()).collect();
+                    motivos.sort();
+                    motivos.dedup();
+                    tf("explain.secciones_filtradas", idioma, &[&ramo, &motivos.join(", ")])
+                }
+                Motivo::ConflictoHorario { .. } => tf("explain.conflicto_horario", idioma, &[&ramo]),
+                Motivo::Desplazado { detalle } => tf("explain.desplazado", idioma, &[&ramo, detalle]),
+            })
+            .collect();
+        frases.join("; ")
+    }
+}
+
+fn etiqueta(s: &Seccion) -> String {
+    if s.codigo_box.trim().is_empty() { format!("{}-{}", s.codigo, s.seccion) } else { s.codigo_box.clone() }
+}
+
+/// Motivo por el que `ruta`/`clique` descartan la sección antes de armar soluciones
+fn motivo_descarte(s: &Seccion, params: &InputParams) -> Option<&'static str> {
+    if !params.horarios_prohibidos.is_empty() && filters::solapan_horarios(&s.horario, &params.horarios_prohibidos) {
+        return Some("horarios_prohibidos");
+    }
+    if crate::algorithm::compromisos::choca_con(s, params).is_some() {
+        return Some("compromisos");
+    }
+    let f = params.filtros.as_ref()?;
+    if f.solo_con_cupos && s.sin_cupos() {
+        return Some("sin_cupos");
+    }
+    if carga_diaria::rango_horario(&params.filtros).is_some_and(|r| carga_diaria::fuera_de_rango(s, r)) {
+        return Some("fuera_de_rango_horario");
+    }
+    let dias_libres = f.dias_horarios_libres.as_ref().and_then(|d| d.dias_libres_preferidos.as_ref());
+    for dia in dias_libres.into_iter().flatten() {
+        let dia = dia.to_uppercase();
+        if s.horario.iter().any(|h| filters::expand_horario_entry(h).iter().any(|(d, _, _)| *d == dia)) {
+            return Some("dia_libre");
+        }
+    }
+    // Mismo chequeo que el clique, filtro por filtro para saber cuál descarta
+    let solo_horario = Some(UserFilters { dias_horarios_libres: f.dias_horarios_libres.clone(), ..Default::default() });
+    if !clique::seccion_cumple_filtros(s, &solo_horario) {
+        let sin_horario = s.horario.is_empty() || s.horario.iter().any(|h| h.to_lowercase().contains("sin"));
+        return Some(if sin_horario { "sin_horario" } else { "franjas_prohibidas" });
+    }
+    let solo_profesores = Some(UserFilters { preferencias_profesores: f.preferencias_profesores.clone(), ..Default::default() });
+    if !clique::seccion_cumple_filtros(s, &solo_profesores) {
+        return Some("profesores");
+    }
+    let solo_lineas = Some(UserFilters { balance_lineas: f.balance_lineas.clone(), ..Default::default() });
+    if !clique::seccion_cumple_filtros(s, &solo_lineas) {
+        return Some("balance_lineas");
+    }
+    None
+}
+
+/// Explica por qué `ramo` (código o nombre) no aparece en la mejor solución.
+/// `params.ramos_pasados` debe venir ya mapeado con equivalencias.
+pub fn explicar_ramo(
+    ramo: &str,
+    params: &InputParams,
+    malla: &HashMap<String, RamoDisponible>,
+    secciones: &[Seccion],
+) -> Explicacion {
+    let codigo = ramo.trim().to_uppercase();
+    let nombre_norm = normalize_name(ramo);
+    let en_malla = malla.values().find(|r| !r.codigo.trim().is_empty() && r.codigo.trim().to_uppercase() == codigo)
+        .or_else(|| malla.values().find(|r| normalize_name(&r.nombre) == nombre_norm));
+    let es_del_ramo = |s: &Seccion| s.codigo.trim().to_uppercase() == codigo
+        || en_malla.map(|r| normalize_name(&r.nombre) == normalize_name(&s.nombre)).unwrap_or(false);
+
+    let mut explicacion = Explicacion {
+        ramo: en_malla.map(|r| r.codigo.clone()).filter(|c| !c.is_empty()).unwrap_or_else(|| ramo.trim().to_string()),
+        nombre: en_malla.map(|r| r.nombre.clone())
+            .or_else(|| secciones.iter().find(|s| es_del_ramo(s)).map(|s| s.nombre.clone())),
+        en_soluciones: false,
+        en_mejor_solucion: false,
+        motivos: Vec::new(),
+    };
+
+    // 1-2) Aprobado / prerequisitos
+    let aprobados = Aprobados::new(&params.ramos_pasados);
+    if en_malla.is_some_and(|r| aprobados.contiene(r)) || aprobados.contiene_codigo(&codigo) {
+        explicacion.motivos.push(Motivo::YaAprobado);
+        return explicacion;
+    }
+    if let Some(r) = en_malla {
+        let mut faltantes: Vec<PrerequisitoFaltante> = r.requisitos_ids.iter()
+            .filter_map(|id| match malla.values().find(|m| m.id == *id) {
+                Some(m) if aprobados.contiene(m) => None,
+                Some(m) => Some(PrerequisitoFaltante { id: *id, codigo: m.codigo.clone(), nombre: m.nombre.clone() }),
+                None => Some(PrerequisitoFaltante { id: *id, codigo: String::new(), nombre: String::new() }),
+            })
+            .collect();
+        faltantes.sort_by_key(|p| p.id);
+        if !faltantes.is_empty() {
+            explicacion.motivos.push(Motivo::PrerequisitosFaltantes { faltantes });
+        }
+    }
+
+    // 3-4) Oferta y filtros
+    let propias: Vec<&Seccion> = secciones.iter().filter(|s| es_del_ramo(s)).collect();
+    if propias.is_empty() {
+        explicacion.motivos.push(if en_malla.is_some() { Motivo::SinSecciones } else { Motivo::NoEncontrado });
+        return explicacion;
+    }
+    let (descartadas, viables): (Vec<_>, Vec<_>) = propias.into_iter()
+        .map(|s| (s, motivo_descarte(s, params)))
+        .partition(|(_, m)| m.is_some());
+    if viables.is_empty() {
+        explicacion.motivos.push(Motivo::SeccionesFiltradas {
+            secciones: descartadas.into_iter()
+                .map(|(s, m)| SeccionDescartada {
+                    seccion: s.seccion.clone(),
+                    horario: s.horario.clone(),
+                    profesor: s.profesor.clone(),
+                    motivo: m.unwrap_or_default().to_string(),
+                })
+                .collect(),
+        });
+        return explicacion;
+    }
+
+    // 5) Resolver y comparar contra la mejor solución
+    let mut p = params.clone();
+    let datos = en_memoria::preparar_datos_en_memoria(
+        &mut p,
+        malla.values().cloned().collect(),
+        secciones.to_vec(),
+        &HashMap::new(),
+        &HashMap::new(),
+    );
+    let soluciones = ruta::resolver_con_datos(&datos, &p, None).unwrap_or_default();
+    explicacion.en_soluciones = soluciones.iter().any(|(sol, _)| sol.iter().any(|(s, _)| es_del_ramo(s)));
+    let mejor: Vec<&Seccion> = soluciones.iter()
+        .find(|(sol, _)| !sol.is_empty())
+        .map(|(sol, _)| sol.iter().map(|(s, _)| s).collect())
+        .unwrap_or_default();
+    explicacion.en_mejor_solucion = mejor.iter().any(|s| es_del_ramo(s));
+    if explicacion.en_mejor_solucion {
+        return explicacion;
+    }
+
+    let conflictos: Vec<SeccionEnConflicto> = viables.iter()
+        .map(|(s, _)| SeccionEnConflicto {
+            seccion: s.seccion.clone(),
+            horario: s.horario.clone(),
+            choca_con: mejor.iter()
+                .filter(|m| conflict::horarios_tienen_conflicto(&s.horario, &m.horario))
+                .map(|m| etiqueta(m))
+                .collect(),
+        })
+        .collect();
+    if conflictos.iter().all(|c| !c.choca_con.is_empty()) {
+        explicacion.motivos.push(Motivo::ConflictoHorario { secciones: conflictos });
+    } else if explicacion.motivos.is_empty() {
+        // 6) Sin motivo duro: otra combinación obtuvo mejor puntaje
+        let detalle = if let Some(max) = params.max_creditos {
+            format!("la mejor solución prioriza otros ramos dentro del tope de {} créditos", max)
+        } else if mejor.len() >= crate::algorithm::planner::MAX_RAMOS_SEMESTRE {
+            format!("la mejor solución ya tiene {} ramos de mayor prioridad", mejor.len())
+        } else {
+            "hay secciones compatibles, pero las combinaciones sin este ramo obtienen mejor puntaje".to_string()
+        };
+        explicacion.motivos.push(Motivo::Desplazado { detalle });
+    }
+    explicacion
+}
@@ -1,110 +1,142 @@
-// Port literal del script Python `RutaCritica/extract_data.py` a Rust.
+// Lectura de malla + oferta para `POST /rutacritica/run-dependencies-only`.
 //
-// Este módulo implementa las mismas funciones auxiliares que el script
-// original: equivalencia, counters, appendElectivos, secciones_cfg y
-// extract_data. Está escrito de manera relativamente literal para facilitar
-// la revisión y posteriores refactorizaciones.
+// Reemplaza al port literal de `RutaCritica/extract_data.py` (O(n²)) y al
+// selector `extract_controller` que elegía entre ambas versiones: solo queda
+// el lector optimizado sobre MapeoMaestro. `extract_controller` y
+// `extract_optimizado` siguen en `algorithm` como atajos deprecados.
 
 use std::collections::HashMap;
 use std::error::Error;
-
 use crate::models::{Seccion, RamoDisponible};
-use crate::excel; // usamos la API de alto nivel de excel
+use crate::excel;
+
+/// Secciones de la oferta filtradas por la malla, junto a los ramos de la malla
+pub type DatosExtraidos = (Vec<Seccion>, HashMap<String, RamoDisponible>);
 
-/// extract_data simplificado: delega en `excel` para obtener rutas y lectura.
-/// Devuelve (secciones, ramos_disponibles) o error.
-/// 
-/// Flujo:
-/// 1. Resuelve rutas de archivos (malla, oferta, porcentajes)
-/// 2. Lee Malla2020 enriquecida con datos de porcentajes (nombre → código + dificultad)
-/// 3. Lee oferta académica para obtener secciones
+/// Devuelve (secciones, ramos_disponibles) de la malla `nombre_excel_malla`
+///
+/// - Lee la malla con porcentajes (`leer_malla_con_porcentajes_optimizado`,
+///   con la versión antigua como respaldo)
+/// - Lee la oferta más reciente y filtra sus secciones por la malla en una
+///   sola pasada
 pub fn extract_data(
     _ramos_disponibles: HashMap<String, RamoDisponible>,
     nombre_excel_malla: &str,
     _sheet: Option<&str>,
-) -> Result<(Vec<Seccion>, HashMap<String, RamoDisponible>), Box<dyn Error>> {
-    // Resolver rutas de malla, oferta y porcentajes en DATAFILES_DIR
-    let (malla_path, oferta_path, porcent_path) = excel::resolve_datafile_paths(nombre_excel_malla)?;
+) -> Result<DatosExtraidos, Box<dyn Error>> {
+    tracing::info!("🚀 extract_data: Iniciando extracción...");
+
+    // Paso 1: Leer Malla enriquecida con porcentajes (VERSIÓN OPTIMIZADA)
+    tracing::debug!("  📖 Paso 1: Leyendo malla con porcentajes (O(n) optimizado)...");
+    
+    // Usar get_datafiles_dir() para obtener la ruta correcta en runtime
+    let data_dir = excel::get_datafiles_dir();
+    // Si `nombre_excel_malla` es una ruta existente la usamos; si no, la buscamos en data_dir
+    let malla_path = if std::path::Path::new(nombre_excel_malla).exists() {
+        nombre_excel_malla.to_string()
+    } else {
+        data_dir.join(nombre_excel_malla).to_string_lossy().to_string()
+    };
 
-    // 1) Leer Malla2020 enriquecida con porcentajes (Nombre → Código + Dificultad)
-    // Esto reemplaza la lectura manual de malla + enriquecimiento manual
-    let malla_str = malla_path.to_str().ok_or("ruta malla no UTF-8")?;
-    let porcent_str = porcent_path.to_str().ok_or("ruta porcent no UTF-8")?;
+    // Porcentajes (PA) = usar el archivo más reciente que parezca un PA (porcentajes)
+    let porcent_path = if let Some(p) = excel::latest_file_for_keywords(&["porcentaje", "porcentajes", "pa"]) {
+        p.to_string_lossy().to_string()
+    } else {
+        // Fallback conservador
+        data_dir.join("PA2025-1.xlsx").to_string_lossy().to_string()
+    };
     
-    let ramos_disponibles = match excel::leer_malla_con_porcentajes(malla_str, porcent_str) {
+    tracing::debug!("  📁 Rutas resueltas:");
+    tracing::debug!("     - Malla: {}", malla_path);
+    tracing::debug!("     - Porcentajes: {}", porcent_path);
+    
+    let ramos_disponibles = match excel::leer_malla_con_porcentajes_optimizado(
+        &malla_path,
+        &porcent_path,
+    ) {
         Ok(ramos_map) => {
-            eprintln!("DEBUG: Malla2020 enriquecida con porcentajes: {} ramos cargados", ramos_map.len());
+            tracing::debug!(
+                "  ✅ Malla2020 enriquecida (versión optimizada): {} ramos cargados",
+                ramos_map.len()
+            );
             ramos_map
         }
         Err(e) => {
-            eprintln!("WARN: No se pudo enriquecer Malla2020 con porcentajes: {}. Intentando con lectura alternativa...", e);
-            // Fallback: leer malla sin enriquecimiento
-            match excel::leer_malla_excel_with_sheet(malla_str, Some("Malla2020")) {
+            tracing::warn!("  ⚠️  Error en leer_malla_con_porcentajes_optimizado: {}", e);
+            tracing::warn!("  🔄 Intentando con fallback (versión antigua)...");
+            match excel::leer_malla_con_porcentajes(&malla_path, &porcent_path) {
                 Ok(ramos_map) => {
-                    eprintln!("DEBUG: Malla2020 cargada sin porcentajes: {} ramos", ramos_map.len());
+                    tracing::debug!("  ✅ Fallback exitoso: {} ramos cargados", ramos_map.len());
                     ramos_map
                 }
                 Err(e2) => {
-                    return Err(format!("error leyendo malla '{}': {} (fallback también falló: {})", malla_str, e, e2).into());
+                    return Err(
+                        format!("Error en ambas versiones: optimizado ({}) y fallback ({})", e, e2)
+                            .into(),
+                    );
                 }
             }
         }
     };
 
-    // 2) Leer oferta académica -> obtener secciones
-    let oferta_str = oferta_path.to_str().ok_or("ruta oferta no UTF-8")?;
-    let secciones: Vec<Seccion> = match excel::leer_oferta_academica_excel(oferta_str) {
-        Ok(s) => {
-            eprintln!("DEBUG: Oferta académica cargada: {} secciones totales", s.len());
-            s
-        }
-        Err(e) => {
-            // Fallback tolerante: si falla la oferta, usamos un conjunto vacío
-            eprintln!("WARN: no se pudo leer oferta '{}': {}. Usando lista de secciones vacía.", oferta_str, e);
-            Vec::new()
+    // Paso 2: Leer oferta académica -> obtener secciones (UNA SOLA PASADA)
+    tracing::debug!("  📖 Paso 2: Leyendo oferta académica (O(n) una pasada)...");
+    let oferta_path_opt = excel::latest_file_for_keywords(&["oferta", "oa"]);
+    let secciones: Vec<Seccion> = if let Some(opath) = oferta_path_opt {
+        let opath_s = opath.to_string_lossy().to_string();
+        match crate::datafiles::cache::oferta(&opath_s) {
+            Ok(s) => {
+                tracing::debug!("  ✅ Oferta académica cargada: {} secciones totales", s.len());
+                s.as_ref().clone()
+            }
+            Err(e) => {
+                tracing::warn!("  ⚠️  Error al leer oferta ({}) : {}. Usando lista vacía.", opath_s, e);
+                Vec::new()
+            }
         }
+    } else {
+        tracing::warn!("  ⚠️  No se encontró archivo de oferta (OA) reciente. Usando lista vacía.");
+        Vec::new()
     };
 
-    // 2.b) Intentar leer archivo CFG (si existe) y añadir sus secciones
-    let mut secciones = secciones;
-    if let Some(cfg_pathbuf) = crate::excel::latest_file_for_keywords(&["cfg"]) {
-        if let Some(cfg_str) = cfg_pathbuf.to_str() {
-            match crate::excel::leer_oferta_academica_excel(cfg_str) {
-                Ok(cfg_secs) => {
-                    eprintln!("DEBUG: CFG cargado: {} secciones", cfg_secs.len());
-                    for mut s in cfg_secs.into_iter() {
-                        // Regla especial: "Inglés I" pertenece a "Inglés 1" y NO se considera CFG
-                        let name_norm = crate::excel::normalize_name(&s.nombre);
-                        if name_norm == crate::excel::normalize_name("Inglés I") || name_norm == crate::excel::normalize_name("Ingles I") {
-                            s.nombre = "Inglés 1".to_string();
-                            s.is_cfg = false;
-                        } else {
-                            s.is_cfg = true;
-                        }
-                        secciones.push(s);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("WARN: no se pudo leer CFG '{}': {}", cfg_str, e);
-                }
+    // Paso 3: Filtrar secciones por Malla (una sola pasada O(n))
+    tracing::debug!("  📖 Paso 3: Filtrando secciones por Malla2020...");
+    let total_secciones = secciones.len();
+    // Aceptar además laboratorios/talleres/prácticas aunque no aparezcan exacto en la malla
+    let secciones_filtradas: Vec<Seccion> = secciones
+        .into_iter()
+        .filter(|sec| {
+            // 🆕 Usar excel::normalize_name() en lugar de otra función
+            let nombre_norm = crate::excel::normalize_name(&sec.nombre);
+
+            let is_electivo = nombre_norm.contains("electivo");
+            let is_lab = nombre_norm.contains("laboratori") || nombre_norm.contains("pract") || nombre_norm.contains("taller");
+
+            let in_malla = ramos_disponibles.contains_key(&nombre_norm);
+
+            if is_lab && !in_malla {
+                // contaremos laboratorios incluidos por palabra clave
+                // (no alteramos ramos_disponibles, sólo los aceptamos como sección válida)
+                // incrementar contador atómico fuera del closure no es trivial aquí,
+                // así que haremos un hack: marcar con side-effect en un temp variable
+                // usando a mutable static would be overkill; en su lugar, retornamos true
+                // y contaremos luego por inspección si es necesario.
+                return true;
             }
-        }
-    }
 
-    // 3) IMPORTANTE: Filtrar secciones para que solo incluya aquellas que existen en la Malla
-    // Esto es crítico porque OA2024 contiene muchos cursos que no están en Malla2020
-    let total_secciones = secciones.len();
-    let secciones_filtradas: Vec<Seccion> = secciones.into_iter().filter(|sec| {
-        let nombre_norm = crate::excel::normalize_name(&sec.nombre);
-        // Aceptar si existe en ramos_disponibles (de Malla) O si es electivo
-        ramos_disponibles.contains_key(&nombre_norm) || nombre_norm == "electivo profesional"
-    }).collect();
-    
-    eprintln!("DEBUG: Secciones filtradas por Malla2020: {} → {} (quedaron)", 
-              total_secciones, secciones_filtradas.len());
+            in_malla || is_electivo || is_lab
+        })
+        .collect();
+
+    tracing::debug!(
+        "  ✅ Secciones filtradas: {} → {} (quedaron). Cobertura: {:.1}%",
+        total_secciones,
+        secciones_filtradas.len(),
+        (secciones_filtradas.len() as f64 / total_secciones as f64) * 100.0
+    );
 
+    tracing::info!("✅ extract_data completado");
     Ok((secciones_filtradas, ramos_disponibles))
 }
 
-// get_ramo_critico fue movido a `crate::excel::get_ramo_critico` para
-// centralizar todo el acceso a archivos Excel en el módulo `excel`.
+ 
\ No newline at end of file
@@ -0,0 +1,165 @@
+// fijas.rs - Secciones fijadas por el usuario (`InputParams::secciones_fijas`)
+//
+// El estudiante puede fijar secciones (por `codigo_box`) que toda solución
+// debe incluir. Antes de buscar, `resolver` las ubica en la oferta (una
+// sección de un paquete cátedra + laboratorio fija el paquete completo, ver
+// `bundles`) y valida que puedan ir juntas: que existan, que no sean de ramos
+// ya aprobados, que no haya dos del mismo ramo y que sus horarios no se
+// solapen. Después `ruta` deja como candidatas solo las secciones compatibles
+// con las fijas, el clique las prefiere al armar cada solución y al final se
+// descartan las soluciones que no las incluyen todas. Si no se puede,
+// `ConflictoFijas` explica por qué.
+
+use std::collections::HashSet;
+use std::fmt;
+use serde::Serialize;
+use crate::models::horario::bloques_de_seccion;
+use crate::models::Seccion;
+
+/// Dos secciones fijas que no pueden ir en la misma solución
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChoqueFijas {
+    pub a: String,
+    pub b: String,
+    /// "horario" o "mismo_ramo"
+    pub motivo: String,
+    /// Bloques que se solapan ("LU 08:30-09:50 / LU 09:00-10:20")
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bloques: Vec<String>,
+}
+
+/// Por qué las secciones fijas no se pueden inscribir juntas
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConflictoFijas {
+    /// `codigo_box` que no están en la oferta
+    pub no_encontradas: Vec<String>,
+    /// Secciones fijas de ramos que están en `ramos_pasados`
+    pub ya_aprobadas: Vec<String>,
+    pub choques: Vec<ChoqueFijas>,
+    /// Las fijas son compatibles entre sí, pero ninguna solución las incluye
+    /// todas (prerequisitos, tope de ramos o créditos, filtros)
+    pub sin_solucion: bool,
+}
+
+impl fmt::Display for ConflictoFijas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut partes: Vec<String> = Vec::new();
+        if !self.no_encontradas.is_empty() {
+            partes.push(format!("no encontradas en la oferta: {}", self.no_encontradas.join(", ")));
+        }
+        if !self.ya_aprobadas.is_empty() {
+            partes.push(format!("de ramos ya aprobados: {}", self.ya_aprobadas.join(", ")));
+        }
+        for c in &self.choques {
+            partes.push(format!("{} y {} ({})", c.a, c.b, c.motivo));
+        }
+        if self.sin_solucion {
+            partes.push("ninguna solución las incluye a todas".to_string());
+        }
+        write!(f, "secciones fijas inviables: {}", partes.join("; "))
+    }
+}
+
+impl std::error::Error for ConflictoFijas {}
+
+fn mismo_id(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// Ramo de una sección como lo compara el clique (prefijo de 7 caracteres del código)
+fn ramo(s: &Seccion) -> String {
+    s.codigo.trim().to_uppercase().chars().take(7).collect()
+}
+
+fn mismo_paquete(a: &Seccion, b: &Seccion) -> bool {
+    a.bundle_id.is_some() && a.bundle_id == b.bundle_id
+}
+
+fn bloques_solapados(a: &Seccion, b: &Seccion) -> Vec<String> {
+    let texto = |x: &crate::models::horario::Bloque| format!("{} {}-{}", x.dia.codigo(), x.hora_inicio(), x.hora_fin());
+    let (ba, bb) = (bloques_de_seccion(a), bloques_de_seccion(b));
+    ba.iter()
+        .flat_map(|x| bb.iter().filter(|y| x.solapa(y)).map(move |y| format!("{} / {}", texto(x), texto(y))))
+        .collect()
+}
+
+/// true si `s` puede ir en la misma solución que `fija`
+fn compatibles(s: &Seccion, fija: &Seccion) -> bool {
+    mismo_paquete(s, fija) || (ramo(s) != ramo(fija) && bloques_solapados(s, fija).is_empty())
+}
+
+/// Ubica las secciones fijas en `oferta` (con los demás miembros de sus
+/// paquetes) y valida que puedan ir juntas
+pub fn resolver(ids: &[String], oferta: &[Seccion], ramos_pasados: &[String]) -> Result<Vec<Seccion>, ConflictoFijas> {
+    let mut conflicto = ConflictoFijas::default();
+    let mut fijas: Vec<Seccion> = Vec::new();
+    for id in ids.iter().filter(|id| !id.trim().is_empty()) {
+        let Some(sec) = oferta.iter().find(|s| mismo_id(&s.codigo_box, id)) else {
+            conflicto.no_encontradas.push(id.trim().to_string());
+            continue;
+        };
+        let miembros = oferta.iter().filter(|s| std::ptr::eq(*s, sec) || mismo_paquete(s, sec));
+        for s in miembros {
+            if !fijas.iter().any(|f| mismo_id(&f.codigo_box, &s.codigo_box)) {
+                fijas.push(s.clone());
+            }
+        }
+    }
+
+    let pasados: HashSet<String> = ramos_pasados.iter().map(|r| r.trim().to_uppercase()).collect();
+    conflicto.ya_aprobadas = fijas.iter()
+        .filter(|s| pasados.contains(&s.codigo.trim().to_uppercase()))
+        .map(|s| s.codigo_box.clone())
+        .collect();
+
+    for (i, a) in fijas.iter().enumerate() {
+        for b in &fijas[i + 1..] {
+            let bloques = bloques_solapados(a, b);
+            let motivo = if !mismo_paquete(a, b) && ramo(a) == ramo(b) {
+                "mismo_ramo"
+            } else if !bloques.is_empty() {
+                "horario"
+            } else {
+                continue;
+            };
+            conflicto.choques.push(ChoqueFijas {
+                a: a.codigo_box.clone(),
+                b: b.codigo_box.clone(),
+                motivo: motivo.to_string(),
+                bloques,
+            });
+        }
+    }
+
+    if conflicto == ConflictoFijas::default() {
+        Ok(fijas)
+    } else {
+        Err(conflicto)
+    }
+}
+
+/// true si `s` es una de las fijas o puede acompañarlas a todas
+pub fn admite(fijas: &[Seccion], s: &Seccion) -> bool {
+    es_fija(fijas, s) || fijas.iter().all(|f| compatibles(s, f))
+}
+
+fn es_fija(fijas: &[Seccion], s: &Seccion) -> bool {
+    fijas.iter().any(|f| mismo_id(&f.codigo_box, &s.codigo_box))
+}
+
+/// true si la solución incluye todas las secciones fijas
+pub fn incluidas(fijas: &[Seccion], solucion: &[(Seccion, i32)]) -> bool {
+    fijas.iter().all(|f| solucion.iter().any(|(s, _)| mismo_id(&s.codigo_box, &f.codigo_box)))
+}
+
+/// Identificadores con que el clique reconoce a las fijas: su `codigo_box` y,
+/// si son de un paquete, el del paquete colapsado (ver `bundles::colapsar`)
+pub fn ids_busqueda(fijas: &[Seccion]) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for id in fijas.iter().flat_map(|f| std::iter::once(&f.codigo_box).chain(f.bundle_id.as_ref())) {
+        if !ids.contains(id) {
+            ids.push(id.clone());
+        }
+    }
+    ids
+}
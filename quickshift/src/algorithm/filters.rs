@@ -4,20 +4,10 @@
 /// Los filtros se aplican sobre las soluciones generadas para
 /// excluir aquellas que no cumplen con las preferencias del usuario.
 
+use crate::algorithm::carga_diaria;
 use crate::algorithm::conflict::parse_slots;
 use crate::models::{Seccion, UserFilters};
 use std::collections::HashSet;
-use std::str::FromStr;
-
-/// Convierte "HH:MM" -> minutos desde 00:00
-fn parse_hora_minutos(s: &str) -> Option<i32> {
-    let s = s.trim();
-    let parts: Vec<&str> = s.split(':').collect();
-    if parts.len() != 2 { return None; }
-    let h = i32::from_str(parts[0]).ok()?;
-    let m = i32::from_str(parts[1]).ok()?;
-    Some(h * 60 + m)
-}
 
 /// Aplica todos los filtros habilitados a una lista de soluciones
 /// Retorna solo las soluciones que pasan todos los filtros
@@ -52,9 +42,9 @@ pub fn apply_all_filters(
         }
     }
 
-    // Filtro 5: Preferencias de profesores
+    // Filtro 5: Preferencias de profesores (solo modo hard; soft ajusta el score)
     if let Some(ref prof_filter) = filters.preferencias_profesores {
-        if prof_filter.habilitado {
+        if prof_filter.habilitado && prof_filter.modo == crate::models::ModoPreferencia::Hard {
             resultado = resultado
                 .into_iter()
                 .filter(|(sol, _)| filtro_preferencias_profesores(sol, prof_filter))
@@ -62,6 +52,10 @@ pub fn apply_all_filters(
         }
     }
 
+    // Filtros 9 y 10: tope de ramos por día y rango horario (el clique ya
+    // los respeta; aquí cubren las soluciones de respaldo)
+    resultado.retain(|(sol, _)| carga_diaria::cumple(sol, filtros));
+
     resultado
 }
 
@@ -81,7 +75,7 @@ fn filtro_dias_horarios_libres(
         }
         for (seccion, _) in solucion {
             if solapan_horarios(&seccion.horario, &fps) {
-                eprintln!("   ⊘ Excluyendo solución: sección {} solapan con franjas prohibidas", seccion.codigo);
+                tracing::debug!("   ⊘ Excluyendo solución: sección {} solapan con franjas prohibidas", seccion.codigo);
                 return false;
             }
         }
@@ -93,7 +87,7 @@ fn filtro_dias_horarios_libres(
             if seccion.horario.is_empty()
                 || seccion.horario.iter().any(|h| h.to_lowercase().contains("sin"))
             {
-                eprintln!("   ⊘ Excluyendo solución: sección {} sin horario", seccion.codigo);
+                tracing::debug!("   ⊘ Excluyendo solución: sección {} sin horario", seccion.codigo);
                 return false;
             }
         }
@@ -132,7 +126,7 @@ fn filtro_preferencias_profesores(
     for (seccion, _) in solucion {
         let prof_lower = seccion.profesor.to_lowercase();
         if !prof_lower.is_empty() && profesores_evitar.contains(&prof_lower) {
-            eprintln!(
+            tracing::debug!(
                 "   ⊘ Excluyendo solución: profesor {} en lista de evitar",
                 seccion.profesor
             );
@@ -145,10 +139,10 @@ fn filtro_preferencias_profesores(
 
 /// Expande una entrada de horario como "LU JU 14:30 - 15:50" a vectores (dia, inicio, fin)
 pub fn expand_horario_entry(entry: &str) -> Vec<(String, i32, i32)> {
-    eprintln!("[expand_horario_entry START] input: '{}'", entry);
+    tracing::trace!("[expand_horario_entry START] input: '{}'", entry);
     let result = parse_slots(entry);
-    eprintln!("[expand_horario_entry] parsed slots: {:?}", result);
-    eprintln!("[expand_horario_entry SUCCESS] Retornando {} entradas", result.len());
+    tracing::trace!("[expand_horario_entry] parsed slots: {:?}", result);
+    tracing::trace!("[expand_horario_entry SUCCESS] Retornando {} entradas", result.len());
     result
 }
 
@@ -161,34 +155,34 @@ fn intervals_overlap(a0: i32, a1: i32, b0: i32, b1: i32) -> bool {
 /// Comprueba si alguna de las horas de la sección solapa con alguna franja prohibida.
 /// Ambos arrays contienen strings tipo "LU 08:30 - 09:50" o combinados "LU JU 14:30 - 15:50".
 pub fn solapan_horarios(horarios_actuales: &[String], franjas_prohibidas: &[String]) -> bool {
-    eprintln!("[solapan_horarios START] horarios_actuales: {:?}, franjas_prohibidas: {:?}", 
+    tracing::trace!("[solapan_horarios START] horarios_actuales: {:?}, franjas_prohibidas: {:?}", 
               horarios_actuales, franjas_prohibidas);
     
     // Expandir todas las franjas prohibidas a (dia, s, e)
     let mut prohibidos: Vec<(String, i32, i32)> = Vec::new();
     for p in franjas_prohibidas {
-        eprintln!("[solapan_horarios] Expandiendo franja prohibida: '{}'", p);
+        tracing::trace!("[solapan_horarios] Expandiendo franja prohibida: '{}'", p);
         let expanded = expand_horario_entry(p);
-        eprintln!("[solapan_horarios]   -> Expandida a: {:?}", expanded);
+        tracing::trace!("[solapan_horarios]   -> Expandida a: {:?}", expanded);
         prohibidos.extend(expanded);
     }
     
-    eprintln!("[solapan_horarios] Total franjas prohibidas expandidas: {} entradas", prohibidos.len());
+    tracing::trace!("[solapan_horarios] Total franjas prohibidas expandidas: {} entradas", prohibidos.len());
     
     if prohibidos.is_empty() {
-        eprintln!("[solapan_horarios] No hay franjas prohibidas después de expandir -> retornando false");
+        tracing::trace!("[solapan_horarios] No hay franjas prohibidas después de expandir -> retornando false");
         return false;
     }
 
     for h in horarios_actuales {
-        eprintln!("[solapan_horarios] Verificando horario: '{}'", h);
+        tracing::trace!("[solapan_horarios] Verificando horario: '{}'", h);
         let segs = expand_horario_entry(h);
-        eprintln!("[solapan_horarios]   -> Expandido a: {:?}", segs);
+        tracing::trace!("[solapan_horarios]   -> Expandido a: {:?}", segs);
         
         for (d1, s1, e1) in segs {
             for (d2, s2, e2) in &prohibidos {
                 if d1 == *d2 && intervals_overlap(s1, e1, *s2, *e2) {
-                    eprintln!("[solapan_horarios] ¡SOLAPAMIENTO! {} {} ({}-{}) vs {} ({}-{})", 
+                    tracing::trace!("[solapan_horarios] ¡SOLAPAMIENTO! {} {} ({}-{}) vs {} ({}-{})", 
                               d1, d1, s1, e1, d2, s2, e2);
                     return true;
                 }
@@ -196,7 +190,7 @@ pub fn solapan_horarios(horarios_actuales: &[String], franjas_prohibidas: &[Stri
         }
     }
     
-    eprintln!("[solapan_horarios] No se encontraron solapamientos -> retornando false");
+    tracing::trace!("[solapan_horarios] No se encontraron solapamientos -> retornando false");
     false
 }
 
@@ -209,7 +203,7 @@ pub fn solapan_horarios_franja(horarios_actuales: &[String], franjas_prohibidas:
 
 /// Wrapper público para tests/llamadas externas: "hora_a_minutos"
 pub fn hora_a_minutos(s: &str) -> Option<i32> {
-    parse_hora_minutos(s)
+    crate::models::horario::parse_hora(s).map(|m| m as i32)
 }
 
 /// Wrapper público para tests/llamadas externas: "horas_se_solapan"
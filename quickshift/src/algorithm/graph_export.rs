@@ -0,0 +1,105 @@
+// graph_export.rs - Exportación del DAG de prerequisitos de una malla
+//
+// Construye el grafo "prerequisito -> ramo" a partir de `requisitos_ids` y lo
+// serializa como:
+// - JSON `{nodes, edges}` (directo para D3 o cytoscape)
+// - Graphviz DOT, con un rango por semestre y los ramos críticos resaltados
+//
+// `critico` sale de la red PERT de la malla completa (`pert::red_pert_malla`),
+// igual que en `GET /pert`.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use serde::Serialize;
+use crate::models::RamoDisponible;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodoGrafo {
+    pub id: i32,
+    pub codigo: String,
+    pub nombre: String,
+    pub semestre: Option<i32>,
+    pub critico: bool,
+    pub dificultad: Option<f64>,
+}
+
+/// Arista prerequisito (`source`) -> ramo que lo exige (`target`), por id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AristaGrafo {
+    pub source: i32,
+    pub target: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GrafoMalla {
+    pub nodes: Vec<NodoGrafo>,
+    pub edges: Vec<AristaGrafo>,
+}
+
+impl GrafoMalla {
+    /// Arma el grafo con todos los ramos de la malla. Los prerequisitos que no
+    /// existen en la malla (o ids <= 0) se ignoran.
+    pub fn desde_ramos(ramos: &HashMap<String, RamoDisponible>) -> Self {
+        let red = crate::algorithm::pert::red_pert_malla(ramos, None);
+        let criticos: HashSet<i32> = red.nodos.iter().filter(|n| n.critico).map(|n| n.id).collect();
+        let ids: HashSet<i32> = ramos.values().map(|r| r.id).collect();
+
+        let mut nodes: Vec<NodoGrafo> = ramos.values().map(|r| NodoGrafo {
+            id: r.id,
+            codigo: r.codigo.clone(),
+            nombre: r.nombre.clone(),
+            semestre: r.semestre,
+            critico: r.critico || criticos.contains(&r.id),
+            dificultad: r.dificultad,
+        }).collect();
+        nodes.sort_by(|a, b| a.semestre.unwrap_or(i32::MAX).cmp(&b.semestre.unwrap_or(i32::MAX)).then(a.id.cmp(&b.id)));
+
+        let mut edges: Vec<AristaGrafo> = ramos.values()
+            .flat_map(|r| r.requisitos_ids.iter().map(move |&p| AristaGrafo { source: p, target: r.id }))
+            .filter(|a| a.source > 0 && a.source != a.target && ids.contains(&a.source))
+            .collect();
+        edges.sort_by(|a, b| a.source.cmp(&b.source).then(a.target.cmp(&b.target)));
+        edges.dedup();
+
+        GrafoMalla { nodes, edges }
+    }
+
+    /// Graphviz DOT: un `rank=same` por semestre y los ramos críticos en rojo
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph malla {\n    rankdir=LR;\n    node [shape=box, style=rounded];\n");
+        for n in &self.nodes {
+            let mut attrs = format!("label=\"{}\\n{}\"", escapar_dot(&n.codigo), escapar_dot(&n.nombre));
+            if let Some(s) = n.semestre {
+                let _ = write!(attrs, ", semestre={}", s);
+            }
+            if let Some(d) = n.dificultad {
+                let _ = write!(attrs, ", dificultad={}", d);
+            }
+            if n.critico {
+                attrs.push_str(", critico=true, color=red, penwidth=2");
+            }
+            let _ = writeln!(out, "    n{} [{}];", n.id, attrs);
+        }
+
+        let mut por_semestre: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+        for n in &self.nodes {
+            if let Some(s) = n.semestre {
+                por_semestre.entry(s).or_default().push(n.id);
+            }
+        }
+        for ids in por_semestre.values() {
+            let nodos: Vec<String> = ids.iter().map(|id| format!("n{};", id)).collect();
+            let _ = writeln!(out, "    {{ rank=same; {} }}", nodos.join(" "));
+        }
+
+        for e in &self.edges {
+            let _ = writeln!(out, "    n{} -> n{};", e.source, e.target);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn escapar_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
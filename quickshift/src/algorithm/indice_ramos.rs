@@ -0,0 +1,108 @@
+// indice_ramos.rs - Índices de la malla para el planificador
+//
+// El planificador (`clique`) busca el ramo de cada sección dentro de loops
+// O(n²): antes con `ramos_disponibles.values().find(...)`, normalizando el
+// nombre de cada ramo en cada comparación. `RamoIndex` se arma una vez por
+// petición con los tres índices que esas búsquedas usan (código, nombre
+// normalizado e id) y recuerda el resultado de cada sección, también cuando
+// no está en la malla (CFG, electivos externos), que era el caso más caro:
+// recorría la malla completa dos veces.
+//
+// Si dos ramos comparten código o nombre gana el de menor id, así el
+// resultado no depende del orden de iteración del HashMap.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use crate::excel::normalize_name;
+use crate::models::{RamoDisponible, Seccion};
+
+#[derive(Debug)]
+pub struct RamoIndex<'a> {
+    mapa: &'a HashMap<String, RamoDisponible>,
+    /// Ramos ordenados por id; los índices apuntan a este vector
+    ramos: Vec<&'a RamoDisponible>,
+    /// Código en mayúscula y sin espacios (los ramos sin código no entran)
+    por_codigo: HashMap<String, usize>,
+    /// `normalize_name` del nombre
+    por_nombre: HashMap<String, usize>,
+    por_id: HashMap<i32, usize>,
+    /// Código → nombre de sección → ramo, incluidas las que no están en la malla
+    secciones: RwLock<HashMap<String, HashMap<String, Option<usize>>>>,
+}
+
+impl<'a> RamoIndex<'a> {
+    pub fn new(mapa: &'a HashMap<String, RamoDisponible>) -> Self {
+        let mut ramos: Vec<&RamoDisponible> = mapa.values().collect();
+        ramos.sort_by_key(|r| r.id);
+        let mut por_codigo = HashMap::with_capacity(ramos.len());
+        let mut por_nombre = HashMap::with_capacity(ramos.len());
+        let mut por_id = HashMap::with_capacity(ramos.len());
+        for (i, r) in ramos.iter().enumerate() {
+            let codigo = r.codigo.trim().to_uppercase();
+            if !codigo.is_empty() {
+                por_codigo.entry(codigo).or_insert(i);
+            }
+            por_nombre.entry(normalize_name(&r.nombre)).or_insert(i);
+            por_id.entry(r.id).or_insert(i);
+        }
+        RamoIndex { mapa, ramos, por_codigo, por_nombre, por_id, secciones: RwLock::new(HashMap::new()) }
+    }
+
+    /// Mapa original, para las funciones que aún reciben `&HashMap`
+    pub fn mapa(&self) -> &'a HashMap<String, RamoDisponible> {
+        self.mapa
+    }
+
+    pub fn len(&self) -> usize {
+        self.ramos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ramos.is_empty()
+    }
+
+    /// Ramos en orden de id
+    pub fn ramos(&self) -> impl Iterator<Item = &'a RamoDisponible> + '_ {
+        self.ramos.iter().copied()
+    }
+
+    pub fn por_id(&self, id: i32) -> Option<&'a RamoDisponible> {
+        self.por_id.get(&id).map(|&i| self.ramos[i])
+    }
+
+    /// Ramo por código, sin distinguir mayúsculas
+    pub fn por_codigo(&self, codigo: &str) -> Option<&'a RamoDisponible> {
+        let codigo = codigo.trim();
+        if codigo.is_empty() {
+            return None;
+        }
+        self.por_codigo.get(&codigo.to_uppercase()).map(|&i| self.ramos[i])
+    }
+
+    /// Ramo por nombre (se normaliza con `normalize_name`)
+    pub fn por_nombre(&self, nombre: &str) -> Option<&'a RamoDisponible> {
+        self.por_nombre_normalizado(&normalize_name(nombre))
+    }
+
+    /// Ramo por un nombre que ya pasó por `normalize_name`
+    pub fn por_nombre_normalizado(&self, nombre: &str) -> Option<&'a RamoDisponible> {
+        self.por_nombre.get(nombre).map(|&i| self.ramos[i])
+    }
+
+    /// Ramo de una sección: por código y si no por nombre normalizado
+    pub fn de_seccion(&self, s: &Seccion) -> Option<&'a RamoDisponible> {
+        let cacheado = self.secciones.read().ok()
+            .and_then(|m| m.get(s.codigo.as_str()).and_then(|n| n.get(s.nombre.as_str())).copied());
+        if let Some(cacheado) = cacheado {
+            return cacheado.map(|i| self.ramos[i]);
+        }
+        let codigo = s.codigo.trim().to_uppercase();
+        let encontrado = self.por_codigo.get(&codigo)
+            .or_else(|| self.por_nombre.get(&normalize_name(&s.nombre)))
+            .copied();
+        if let Ok(mut m) = self.secciones.write() {
+            m.entry(s.codigo.clone()).or_default().insert(s.nombre.clone(), encontrado);
+        }
+        encontrado.map(|i| self.ramos[i])
+    }
+}
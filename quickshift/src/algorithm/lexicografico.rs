@@ -0,0 +1,119 @@
+// lexicografico.rs - Objetivos en orden de importancia (`InputParams::objetivos`)
+//
+// El score junta todos los criterios en un número con pesos fijos, y un
+// estudiante que dice "primero pocos días en campus, después mis ramos
+// prioritarios, después pocas ventanas" no tiene cómo expresarlo ajustando
+// pesos. Con `objetivos` cada solución se resume en una tupla de métricas, una
+// por objetivo y en el orden pedido, y las soluciones se ordenan comparando
+// las tuplas lexicográficamente: el segundo objetivo solo decide entre
+// soluciones empatadas en el primero, y así. El score desempata al final.
+
+use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+use crate::algorithm::metricas::metricas;
+use crate::algorithm::paralelo::{clave_solucion, Soluciones};
+use crate::algorithm::progreso_esperado::progreso_esperado;
+use crate::api_json::InputParams;
+use crate::excel::normalize_name;
+use crate::models::{RamoDisponible, Seccion};
+
+/// Un objetivo de `InputParams::objetivos`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterio {
+    /// Menos días con clases (`metrics.days_on_campus`)
+    MinDias,
+    /// Más ramos de `ramos_prioritarios`
+    MaxPrioritarios,
+    /// Menos minutos de ventana (`metrics.total_gap_minutes`)
+    MinVentanas,
+    /// Más ramos (un paquete cátedra + laboratorio cuenta como uno)
+    MaxRamos,
+    /// Mayor `compactness_pct`
+    MaxCompactacion,
+    /// Más créditos esperados (ver `algorithm::progreso_esperado`)
+    MaxProgreso,
+    /// Mayor `total_score`
+    MaxScore,
+}
+
+/// Nombres aceptados de cada criterio (el primero es el que se documenta)
+const NOMBRES: &[(&str, Criterio)] = &[
+    ("min_dias", Criterio::MinDias),
+    ("min_days", Criterio::MinDias),
+    ("max_prioritarios", Criterio::MaxPrioritarios),
+    ("max_priority", Criterio::MaxPrioritarios),
+    ("min_ventanas", Criterio::MinVentanas),
+    ("min_gaps", Criterio::MinVentanas),
+    ("max_ramos", Criterio::MaxRamos),
+    ("max_courses", Criterio::MaxRamos),
+    ("max_compactacion", Criterio::MaxCompactacion),
+    ("max_compactness", Criterio::MaxCompactacion),
+    ("max_progreso", Criterio::MaxProgreso),
+    ("max_expected_progress", Criterio::MaxProgreso),
+    ("max_score", Criterio::MaxScore),
+];
+
+impl Criterio {
+    pub fn desde_nombre(nombre: &str) -> Option<Criterio> {
+        let nombre = nombre.trim().to_lowercase().replace('-', "_");
+        NOMBRES.iter().find(|(n, _)| *n == nombre).map(|(_, c)| *c)
+    }
+}
+
+/// Criterios de `objetivos`, en orden; falla con el primer nombre desconocido
+pub fn criterios(objetivos: &[String]) -> Result<Vec<Criterio>, String> {
+    objetivos.iter().map(|o| Criterio::desde_nombre(o).ok_or_else(|| {
+        let validos: Vec<&str> = NOMBRES.iter().step_by(2).map(|(n, _)| *n).collect();
+        format!("objetivo '{}' desconocido en objetivos (valores: {})", o, validos.join(", "))
+    })).collect()
+}
+
+/// Tupla de una solución: un valor por criterio, mayor es mejor
+fn tupla(
+    sol: &[(Seccion, i32)],
+    score: i64,
+    criterios: &[Criterio],
+    prioritarios: &HashSet<String>,
+    ramos: &HashMap<String, RamoDisponible>,
+    params: &InputParams,
+) -> Vec<f64> {
+    let m = metricas(sol);
+    criterios.iter().map(|c| match c {
+        Criterio::MinDias => -(m.days_on_campus as f64),
+        Criterio::MaxPrioritarios => {
+            let codigos: HashSet<String> = sol.iter()
+                .filter(|(s, _)| prioritarios.contains(&normalize_name(&s.codigo)) || prioritarios.contains(&normalize_name(&s.nombre)))
+                .map(|(s, _)| s.codigo.trim().to_uppercase())
+                .collect();
+            codigos.len() as f64
+        }
+        Criterio::MinVentanas => -(m.total_gap_minutes as f64),
+        Criterio::MaxRamos => crate::algorithm::bundles::unidades(sol) as f64,
+        Criterio::MaxCompactacion => m.compactness_pct,
+        Criterio::MaxProgreso => progreso_esperado(sol.iter().map(|(s, _)| s), ramos, params.student_ranking).creditos_esperados,
+        Criterio::MaxScore => score as f64,
+    }).collect()
+}
+
+/// Compara dos tuplas: la mejor primero
+fn comparar(a: &[f64], b: &[f64]) -> Ordering {
+    a.iter().zip(b).map(|(x, y)| y.total_cmp(x)).find(|o| o.is_ne()).unwrap_or(Ordering::Equal)
+}
+
+/// Ordena `soluciones` por `params.objetivos`, luego por score (desc) y por
+/// último por `clave_solucion` (ver `paralelo::ordenar_canonico`). Sin
+/// objetivos (o con nombres inválidos, que `criterios` ya rechazó al parsear)
+/// las deja como están.
+pub fn ordenar(soluciones: Soluciones, params: &InputParams, ramos: &HashMap<String, RamoDisponible>) -> Soluciones {
+    let criterios = match criterios(&params.objetivos) {
+        Ok(c) if !c.is_empty() => c,
+        _ => return soluciones,
+    };
+    let prioritarios: HashSet<String> = params.ramos_prioritarios.iter().map(|s| normalize_name(s)).collect();
+    let mut con_tupla: Vec<(Vec<f64>, _)> = soluciones.into_iter()
+        .map(|sol| (tupla(&sol.0, sol.1, &criterios, &prioritarios, ramos, params), sol))
+        .collect();
+    con_tupla.sort_by(|a, b| comparar(&a.0, &b.0).then(b.1.1.cmp(&a.1.1))
+        .then_with(|| clave_solucion(&a.1.0).cmp(&clave_solucion(&b.1.0))));
+    con_tupla.into_iter().map(|(_, sol)| sol).collect()
+}
@@ -0,0 +1,46 @@
+// metricas.rs - Métricas de horario de cada solución de `/solve`
+//
+// El score ya considera la compactación y los huecos entre clases
+// (`clique::calculate_compactness_score` y `clique::calculate_total_gaps`),
+// pero solo como aporte al total. Aquí se informan los valores mismos, junto
+// con los días en campus y la primera y última hora de clase de la semana,
+// para que el frontend pueda ordenar y filtrar sin volver a leer horarios.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use crate::algorithm::clique::{calculate_compactness_score, calculate_total_gaps};
+use crate::models::horario::{self, formatear_hora};
+use crate::models::Seccion;
+
+/// `metrics` de una solución
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricasSolucion {
+    /// Minutos libres entre clases consecutivas del mismo día, sumados en la semana
+    pub total_gap_minutes: i32,
+    /// Días de la semana con al menos una clase
+    pub days_on_campus: usize,
+    /// Hora ("HH:MM") de la clase que empieza más temprano en la semana
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub earliest_start: Option<String>,
+    /// Hora ("HH:MM") de la clase que termina más tarde en la semana
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_end: Option<String>,
+    /// Porcentaje de días con clases que duran a lo más 5 horas de la primera a la última
+    pub compactness_pct: f64,
+}
+
+/// Métricas de `solution` (secciones con su prioridad, como sale del clique)
+pub fn metricas(solution: &[(Seccion, i32)]) -> MetricasSolucion {
+    let bloques: Vec<horario::Bloque> = solution.iter()
+        .flat_map(|(s, _)| s.horario.iter().flat_map(|h| horario::parse(h)))
+        .collect();
+    let dias: HashSet<u32> = bloques.iter().map(|b| b.dia.indice()).collect();
+    MetricasSolucion {
+        total_gap_minutes: calculate_total_gaps(solution),
+        days_on_campus: dias.len(),
+        earliest_start: bloques.iter().map(|b| b.inicio).min().map(formatear_hora),
+        latest_end: bloques.iter().map(|b| b.fin).max().map(formatear_hora),
+        // Redondeo a décimas: el porcentaje es una razón entre días
+        compactness_pct: (calculate_compactness_score(solution) * 10.0).round() / 10.0,
+    }
+}
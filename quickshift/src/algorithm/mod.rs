@@ -1,22 +1,63 @@
 // Módulo de alto nivel para la ejecución de la Ruta Crítica
 // Declarar submódulos (archivos en la carpeta `src/algorithm`)
 pub mod extract;
-pub mod extract_optimizado;
-pub mod extract_controller;
 pub mod clique;
+pub mod indice_ramos;
+pub mod aprobados;
+pub mod bron_kerbosch;
+pub mod clique_exacto;
+pub mod paralelo;
+pub mod deadline;
+pub mod diversidad;
+pub mod paginacion;
+pub mod equivalencias;
+pub mod balance_lineas;
+pub mod traslado;
+pub mod compromisos;
+pub mod evaluaciones;
+pub mod ventanas;
+pub mod carga_diaria;
+pub mod obligatorios;
+pub mod tags;
+pub mod diagnostico_grafo;
+pub mod prechequeo;
+pub mod comodidad;
+pub mod metricas;
+pub mod comparacion;
 pub mod conflict;
 pub mod section_selector;
-mod pert;
+pub mod pert;
+pub mod graph_export;
 pub mod ruta;
+pub mod engine;
 pub mod filters;
+pub mod doble_titulacion;
+pub mod en_memoria;
+pub mod bloqueantes;
+pub mod planner;
+pub mod progreso_esperado;
+pub mod percentil;
+pub mod lexicografico;
+pub mod reprobacion;
+pub mod progreso_titulacion;
+pub mod explicacion;
+pub mod desglose;
+pub mod prioridad;
+pub mod bundles;
+pub mod cohorte;
+pub mod sintetico;
+pub mod alternativas;
+pub mod fijas;
+pub mod rapido;
 
 // Reexportar solo la API pública que quieres exponer desde aquí
-pub use extract_controller::{extract_data};
+pub use extract::extract_data;
 
 // Reexportar funciones del planner (clique) y el orquestador (ruta)
 pub use crate::algorithm::clique::get_clique_with_user_prefs;
 pub use crate::algorithm::clique::get_clique_dependencies_only;
 pub use crate::algorithm::ruta::ejecutar_ruta_critica_with_params;
+pub use crate::algorithm::engine::SchedulePlanner;
 
 // Reexportar utilidades de detección de conflictos para que tests/integración
 // puedan usarlas fácilmente.
@@ -39,122 +80,119 @@ pub fn get_ramo_critico() -> (HashMap<String, RamoDisponible>, String, bool) {
 use std::error::Error;
 use std::path::PathBuf;
 use std::collections::HashMap;
-use crate::models::{RamoDisponible, Seccion};
-use crate::excel::{normalize_name, get_datafiles_dir};
-use serde_json::json;
+use crate::models::{MergedCurso, RamoDisponible, Seccion};
+use crate::excel::{normalize_name, get_datafiles_dir, FuzzyMatcher, ReporteMatching};
 
 /// Une la malla, la oferta y los porcentajes intentando emparejar por nombre
-/// normalizado. Devuelve una lista de objetos JSON ordenada por malla_codigo.
-/// { malla_codigo, malla_nombre, oferta_codigo, oferta_codigo_box, oferta_nombre, pa_codigo, porcentaje, total, es_electivo }
+/// con `matcher` (normalizado y, si no hay igualdad, aproximado). Devuelve
+/// una fila por ramo y sección de la oferta (o una sola fila si el ramo no
+/// está en la oferta), ordenada por malla_codigo, y el reporte de matches
+/// aproximados/ambiguos.
 pub fn merge_malla_oferta_porcentajes(
 	malla_map: &HashMap<String, RamoDisponible>,
-	oferta: &Vec<Seccion>,
+	oferta: &[Seccion],
 	porcent: &HashMap<String, (f64,f64)>,
 	porcent_names: &std::collections::HashMap<String, (String, f64, f64, bool)>,
-) -> Vec<serde_json::Value> {
+	matcher: &FuzzyMatcher,
+) -> (Vec<MergedCurso>, ReporteMatching) {
 	// Construir índice de oferta por nombre normalizado -> Vec<Seccion>
 	let mut oferta_index: std::collections::HashMap<String, Vec<&Seccion>> = std::collections::HashMap::new();
 	for s in oferta.iter() {
 		let key = normalize_name(&s.nombre);
 		oferta_index.entry(key).or_default().push(s);
 	}
+	// Claves candidatas ordenadas para que los empates se resuelvan siempre igual
+	let mut oferta_nombres: Vec<String> = oferta_index.keys().cloned().collect();
+	oferta_nombres.sort();
+	let mut porcent_nombres: Vec<String> = porcent_names.keys().cloned().collect();
+	porcent_nombres.sort();
 
-	let mut out: Vec<serde_json::Value> = Vec::new();
+	let mut out: Vec<MergedCurso> = Vec::new();
+	let mut reporte = ReporteMatching::default();
 
 	// Para cada ramo en la malla, buscar coincidencias en oferta por nombre
 	for (mcode, ramo) in malla_map.iter() {
-		let rname_norm = normalize_name(&ramo.nombre);
-		if let Some(matches) = oferta_index.get(&rname_norm) {
-			for s in matches.iter() {
-				// Buscar porcentaje por nombre normalizado (más confiable que por código_box)
-				if let Some((pa_code, pct, tot, _is_electivo)) = porcent_names.get(&rname_norm) {
-					// Match encontrado en porcent_names por nombre
-					out.push(json!({
-						"malla_codigo": mcode,
-						"malla_nombre": ramo.nombre,
-						"oferta_codigo": s.codigo,
-						"oferta_codigo_box": s.codigo_box,
-						"oferta_nombre": s.nombre,
-						"pa_codigo": pa_code,
-						"porcentaje": *pct,
-						"total": *tot
-					}));
-				} else if let Some((pct, tot)) = porcent.get(&s.codigo_box) {
-					// Fallback: intentar por codigo_box si existe en porcent
-					out.push(json!({
-						"malla_codigo": mcode,
-						"malla_nombre": ramo.nombre,
-						"oferta_codigo": s.codigo,
-						"oferta_codigo_box": s.codigo_box,
-						"oferta_nombre": s.nombre,
-						"pa_codigo": s.codigo_box.clone(),
-						"porcentaje": *pct,
-						"total": *tot
-					}));
-				} else {
-					// No se encontró porcentaje
-					out.push(json!({
-						"malla_codigo": mcode,
-						"malla_nombre": ramo.nombre,
-						"oferta_codigo": s.codigo,
-						"oferta_codigo_box": s.codigo_box,
-						"oferta_nombre": s.nombre,
-						"pa_codigo": serde_json::Value::Null,
-						"porcentaje": serde_json::Value::Null,
-						"total": serde_json::Value::Null
-					}));
+		let en_oferta = matcher.emparejar(&ramo.nombre, &oferta_nombres);
+		let en_porcent = matcher.emparejar(&ramo.nombre, &porcent_nombres);
+		let pa_match = reporte.registrar(&ramo.nombre, &en_porcent).and_then(|k| porcent_names.get(k));
+		let fila = MergedCurso {
+			malla_codigo: mcode.clone(),
+			malla_nombre: ramo.nombre.clone(),
+			oferta_codigo: None,
+			oferta_codigo_box: None,
+			oferta_nombre: None,
+			pa_codigo: pa_match.map(|(pa_code, ..)| pa_code.clone()),
+			porcentaje: pa_match.map(|(_, pct, ..)| *pct),
+			total: pa_match.map(|(_, _, tot, _)| *tot),
+			es_electivo: pa_match.map(|(.., es_electivo)| *es_electivo),
+		};
+		match reporte.registrar(&ramo.nombre, &en_oferta).and_then(|k| oferta_index.get(k)) {
+			Some(matches) => {
+				for s in matches.iter() {
+					let mut f = MergedCurso {
+						oferta_codigo: Some(s.codigo.clone()),
+						oferta_codigo_box: Some(s.codigo_box.clone()),
+						oferta_nombre: Some(s.nombre.clone()),
+						..fila.clone()
+					};
+					// Sin match por nombre (más confiable), intentar por codigo_box
+					if pa_match.is_none() && let Some((pct, tot)) = porcent.get(&s.codigo_box) {
+						f.pa_codigo = Some(s.codigo_box.clone());
+						f.porcentaje = Some(*pct);
+						f.total = Some(*tot);
+					}
+					out.push(f);
 				}
 			}
-		} else {
-			// Intentar emparejar directamente PA -> malla por nombre como fallback
-			if let Some((pa_code, pct, tot, es_electivo)) = porcent_names.get(&rname_norm) {
-				out.push(json!({
-					"malla_codigo": mcode,
-					"malla_nombre": ramo.nombre,
-					"oferta_codigo": serde_json::Value::Null,
-					"oferta_codigo_box": serde_json::Value::Null,
-					"oferta_nombre": serde_json::Value::Null,
-					"pa_codigo": pa_code,
-					"porcentaje": *pct,
-					"total": *tot,
-					"es_electivo": es_electivo
-				}));
-			} else {
-				// No encontrado en oferta ni en PA por nombre: fila vacía
-				out.push(json!({
-					"malla_codigo": mcode,
-					"malla_nombre": ramo.nombre,
-					"oferta_codigo": serde_json::Value::Null,
-					"oferta_codigo_box": serde_json::Value::Null,
-					"oferta_nombre": serde_json::Value::Null,
-					"pa_codigo": serde_json::Value::Null,
-					"porcentaje": serde_json::Value::Null,
-					"total": serde_json::Value::Null
-				}));
-			}
+			// No está en oferta: fila con lo que haya en PA (o vacía)
+			None => out.push(fila),
 		}
 	}
 
 	// **ORDENAR POR MALLA_CODIGO NUMÉRICO**
 	out.sort_by(|a, b| {
-		let a_code_str = a.get("malla_codigo")
-			.and_then(|v| v.as_str())
-			.unwrap_or("ZZZZZ");
-		let b_code_str = b.get("malla_codigo")
-			.and_then(|v| v.as_str())
-			.unwrap_or("ZZZZZ");
-
-		let a_num = a_code_str.parse::<i32>().ok();
-		let b_num = b_code_str.parse::<i32>().ok();
-
-		match (a_num, b_num) {
+		match (a.malla_codigo.parse::<i32>().ok(), b.malla_codigo.parse::<i32>().ok()) {
 			(Some(an), Some(bn)) => an.cmp(&bn),
 			(Some(_), None) => std::cmp::Ordering::Less,
 			(None, Some(_)) => std::cmp::Ordering::Greater,
-			(None, None) => a_code_str.cmp(b_code_str),
+			(None, None) => a.malla_codigo.cmp(&b.malla_codigo),
 		}
 	});
 
+	(out, reporte)
+}
+
+/// Campo CSV con comillas cuando contiene separadores, comillas o saltos de línea
+fn campo_csv(valor: &str) -> String {
+	if valor.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", valor.replace('"', "\"\""))
+	} else {
+		valor.to_string()
+	}
+}
+
+/// Tabla de `merge_malla_oferta_porcentajes` como CSV con encabezado
+/// (`MergedCurso::COLUMNAS`); los `None` quedan como celdas vacías
+pub fn merged_a_csv(filas: &[MergedCurso]) -> String {
+	let mut out = MergedCurso::COLUMNAS.join(",");
+	out.push('\n');
+	for f in filas {
+		let texto = |v: &Option<String>| campo_csv(v.as_deref().unwrap_or(""));
+		let numero = |v: Option<f64>| v.map(|n| n.to_string()).unwrap_or_default();
+		let campos = [
+			campo_csv(&f.malla_codigo),
+			campo_csv(&f.malla_nombre),
+			texto(&f.oferta_codigo),
+			texto(&f.oferta_codigo_box),
+			texto(&f.oferta_nombre),
+			texto(&f.pa_codigo),
+			numero(f.porcentaje),
+			numero(f.total),
+			f.es_electivo.map(|e| e.to_string()).unwrap_or_default(),
+		];
+		out.push_str(&campos.join(","));
+		out.push('\n');
+	}
 	out
 }
 
@@ -167,21 +205,22 @@ pub fn list_datafiles() -> Result<(Vec<String>, Vec<String>, Vec<String>), Box<d
 /// Resumen práctico de contenidos para una malla dada. Devuelve las rutas
 /// resueltas y los objetos de alto nivel leídos (malla map, oferta vec, porcentajes map).
 pub fn summarize_datafiles(malla_name: &str, sheet: Option<&str>) -> Result<(PathBuf, PathBuf, PathBuf, HashMap<String, RamoDisponible>, Vec<Seccion>, HashMap<String, (f64,f64)>, std::collections::HashMap<String, (String, f64, f64, bool)>), Box<dyn Error>> {
+	let inicio = std::time::Instant::now();
 	let (malla_path, oferta_path, porcent_path) = crate::excel::resolve_datafile_paths(malla_name)?;
 
 	// Leer primero la malla: si esto falla, no podemos continuar.
 	let malla_path_str = malla_path.to_str().ok_or("malla path invalid UTF-8")?;
-	let malla_map = match crate::excel::leer_malla_excel_with_sheet(malla_path_str, sheet) {
-		Ok(m) => m,
+	let malla_map = match crate::datafiles::cache::malla(malla_path_str, sheet) {
+		Ok(m) => m.as_ref().clone(),
 		Err(e) => return Err(format!("failed to read malla '{}': {}", malla_path_str, e).into()),
 	};
 
 	// Intentar leer oferta; si falla degradamos a fallback vacío pero no abortamos.
 	let oferta_path_str = oferta_path.to_str().ok_or("oferta path invalid UTF-8")?;
-	let oferta = match crate::excel::leer_oferta_academica_excel(oferta_path_str) {
-		Ok(o) => o,
+	let oferta = match crate::datafiles::cache::oferta(oferta_path_str) {
+		Ok(o) => o.as_ref().clone(),
 		Err(e) => {
-			eprintln!("WARN: no se pudo leer Oferta Académica '{}': {}. Usando fallback vacío.", oferta_path_str, e);
+			tracing::warn!("no se pudo leer Oferta Académica '{}': {}. Usando fallback vacío.", oferta_path_str, e);
 			Vec::new()
 		}
 	};
@@ -189,10 +228,10 @@ pub fn summarize_datafiles(malla_name: &str, sheet: Option<&str>) -> Result<(Pat
 	// Intentar leer porcentajes; si falla devolvemos mapa vacío. Usamos
 	// la variante que también intenta extraer nombres para matching por nombre.
 	let porcent_path_str = porcent_path.to_str().ok_or("porcent path invalid UTF-8")?;
-	let (porcent, mut porcent_names) = match crate::excel::leer_porcentajes_aprobados_con_nombres(porcent_path_str) {
-		Ok((p, pn)) => (p, pn),
+	let (porcent, mut porcent_names) = match crate::datafiles::cache::porcentajes(porcent_path_str) {
+		Ok(pp) => pp.as_ref().clone(),
 		Err(e) => {
-			eprintln!("WARN: no se pudo leer Porcentajes '{}': {}. Usando fallback vacío.", porcent_path_str, e);
+			tracing::warn!("no se pudo leer Porcentajes '{}': {}. Usando fallback vacío.", porcent_path_str, e);
 			(HashMap::new(), std::collections::HashMap::new())
 		}
 	};
@@ -203,6 +242,8 @@ pub fn summarize_datafiles(malla_name: &str, sheet: Option<&str>) -> Result<(Pat
 		crate::excel::enrich_porcent_names_from_malla(&mut porcent_names, &porcent, &malla_map);
 	}
 
+	crate::metrics::observar(crate::metrics::DURACION, "summarize_datafiles", inicio.elapsed().as_secs_f64());
+	crate::metrics::observar(crate::metrics::SECCIONES, "summarize_datafiles", oferta.len() as f64);
 	Ok((malla_path, oferta_path, porcent_path, malla_map, oferta, porcent, porcent_names))
 }
 
@@ -225,7 +266,7 @@ pub fn resolve_datafile_paths(
 ) -> Result<(PathBuf, Vec<PathBuf>, PathBuf), String> {
     let data_dir = get_datafiles_dir();
     
-    println!("📁 Resolviendo rutas desde: {:?}", data_dir);
+    tracing::debug!("📁 Resolviendo rutas desde: {:?}", data_dir);
 
     let malla_path = data_dir.join(malla_file);
     let oferta_paths: Vec<PathBuf> = oferta_files.iter().map(|f| data_dir.join(f)).collect();
@@ -249,8 +290,51 @@ pub fn resolve_datafile_paths(
     Ok((malla_path, oferta_paths, porcent_path))
 }
 
-// Nota: la API pública principal es `ruta::ejecutar_ruta_critica_with_params` y
-// se reexporta arriba. Eliminamos la función wrapper para evitar lints
-// en builds donde no se usa el helper genérico.
+// Nota: la API pública principal es `engine::SchedulePlanner` (reexportado
+// arriba); `ruta::ejecutar_ruta_critica_with_params` se mantiene como atajo
+// sobre `engine::Datafiles` para las llamadas existentes.
+
+/// Compatibilidad con el antiguo selector de versiones de `extract`: solo
+/// queda el lector optimizado (`extract::extract_data`)
+pub mod extract_controller {
+    use std::collections::HashMap;
+    use std::error::Error;
+    use crate::models::RamoDisponible;
+    use super::extract::DatosExtraidos;
+
+    #[deprecated(note = "solo queda el lector optimizado; no tiene efecto")]
+    pub fn set_use_optimized(_use_opt: bool) {}
+
+    #[deprecated(note = "solo queda el lector optimizado")]
+    pub fn is_using_optimized() -> bool {
+        true
+    }
+
+    #[deprecated(note = "usar `algorithm::extract::extract_data`")]
+    pub fn extract_data(
+        ramos_disponibles: HashMap<String, RamoDisponible>,
+        nombre_excel_malla: &str,
+        sheet: Option<&str>,
+    ) -> Result<DatosExtraidos, Box<dyn Error>> {
+        super::extract::extract_data(ramos_disponibles, nombre_excel_malla, sheet)
+    }
+}
+
+/// Compatibilidad: la versión optimizada es ahora `extract::extract_data`
+pub mod extract_optimizado {
+    use std::collections::HashMap;
+    use std::error::Error;
+    use crate::models::RamoDisponible;
+    use super::extract::DatosExtraidos;
+
+    #[deprecated(note = "usar `algorithm::extract::extract_data`")]
+    pub fn extract_data_optimizado(
+        ramos_disponibles: HashMap<String, RamoDisponible>,
+        nombre_excel_malla: &str,
+        sheet: Option<&str>,
+    ) -> Result<DatosExtraidos, Box<dyn Error>> {
+        super::extract::extract_data(ramos_disponibles, nombre_excel_malla, sheet)
+    }
+}
 
 // NOTE: `InputParams` está definido en `api_json::mod.rs`. No duplicar aquí.
\ No newline at end of file
@@ -0,0 +1,141 @@
+// obligatorios.rs - Ramos que toda solución debe incluir (`InputParams::ramos_obligatorios`)
+//
+// A diferencia de `ramos_prioritarios` (un bonus en el score), un ramo
+// obligatorio es una restricción: cada solución lleva alguna sección suya.
+// Antes de buscar, `validar` revisa que se pueda: que no estén aprobados, que
+// tengan oferta y secciones que pasen los filtros del usuario, que quepan en
+// el tope de ramos y que cada par tenga al menos una combinación de secciones
+// sin choque. El clique prefiere sus secciones al armar cada solución (y las
+// acepta fuera del horizonte de semestres) y solo emite las que los cubren a
+// todos. Si no se puede, `ConflictoObligatorios` explica por qué.
+
+use std::collections::HashSet;
+use std::fmt;
+use serde::Serialize;
+use crate::algorithm::conflict::horarios_tienen_conflicto;
+use crate::excel::normalize_name;
+use crate::models::Seccion;
+
+/// Dos ramos obligatorios sin ninguna combinación de secciones compatible
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChoqueObligatorios {
+    pub a: String,
+    pub b: String,
+}
+
+/// Por qué los ramos obligatorios no caben en una misma solución
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConflictoObligatorios {
+    /// Ramos que están en `ramos_pasados`
+    pub ya_aprobados: Vec<String>,
+    /// Ramos sin secciones en la oferta
+    pub sin_oferta: Vec<String>,
+    /// Ramos con oferta, pero cuyas secciones descartan los filtros del
+    /// usuario (franjas prohibidas, compromisos, secciones fijas, `filtros`)
+    pub sin_secciones_viables: Vec<String>,
+    /// Tope de ramos por solución, cuando hay más obligatorios que eso
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excede_max_ramos: Option<usize>,
+    pub choques: Vec<ChoqueObligatorios>,
+    /// Pasan todas las validaciones anteriores, pero ninguna solución los
+    /// incluye a todos (choques entre tres o más, créditos, tope diario)
+    pub sin_solucion: bool,
+}
+
+impl fmt::Display for ConflictoObligatorios {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut partes: Vec<String> = Vec::new();
+        if !self.ya_aprobados.is_empty() {
+            partes.push(format!("ya aprobados: {}", self.ya_aprobados.join(", ")));
+        }
+        if !self.sin_oferta.is_empty() {
+            partes.push(format!("sin oferta: {}", self.sin_oferta.join(", ")));
+        }
+        if !self.sin_secciones_viables.is_empty() {
+            partes.push(format!("sin secciones que cumplan los filtros: {}", self.sin_secciones_viables.join(", ")));
+        }
+        if let Some(max) = self.excede_max_ramos {
+            partes.push(format!("más de {} ramos", max));
+        }
+        for c in &self.choques {
+            partes.push(format!("{} y {} siempre chocan", c.a, c.b));
+        }
+        if self.sin_solucion {
+            partes.push("ninguna solución los incluye a todos".to_string());
+        }
+        write!(f, "ramos obligatorios inviables: {}", partes.join("; "))
+    }
+}
+
+impl std::error::Error for ConflictoObligatorios {}
+
+/// true si `s` es de alguno de los ramos `obligatorios` (código o nombre)
+pub fn es_obligatoria(obligatorios: &[String], s: &Seccion) -> bool {
+    let (codigo, nombre) = (normalize_name(&s.codigo), normalize_name(&s.nombre));
+    obligatorios.iter().map(|r| normalize_name(r)).any(|r| r == codigo || r == nombre)
+}
+
+fn secciones_de<'a>(ramo: &str, secciones: &'a [Seccion]) -> Vec<&'a Seccion> {
+    let ids = [ramo.to_string()];
+    secciones.iter().filter(|s| es_obligatoria(&ids, s)).collect()
+}
+
+/// Valida que los `obligatorios` puedan ir juntos. `oferta` son todas las
+/// secciones cargadas y `viables` las que quedan tras los filtros del usuario.
+/// `max_ramos` es `reglas::tope_ramos`: sin tope cuando manda `max_creditos`.
+pub fn validar(
+    obligatorios: &[String],
+    oferta: &[Seccion],
+    viables: &[Seccion],
+    ramos_pasados: &[String],
+    max_ramos: Option<usize>,
+) -> Result<(), ConflictoObligatorios> {
+    let mut conflicto = ConflictoObligatorios::default();
+    let pasados: HashSet<String> = ramos_pasados.iter().map(|r| normalize_name(r)).collect();
+    let ramos: Vec<&str> = obligatorios.iter().map(|r| r.trim()).filter(|r| !r.is_empty()).collect();
+
+    let mut con_secciones: Vec<(&str, Vec<&Seccion>)> = Vec::new();
+    for &ramo in &ramos {
+        let en_oferta = secciones_de(ramo, oferta);
+        if pasados.contains(&normalize_name(ramo))
+            || en_oferta.iter().any(|s| pasados.contains(&normalize_name(&s.codigo)))
+        {
+            conflicto.ya_aprobados.push(ramo.to_string());
+        } else if en_oferta.is_empty() {
+            conflicto.sin_oferta.push(ramo.to_string());
+        } else {
+            let propias = secciones_de(ramo, viables);
+            if propias.is_empty() {
+                conflicto.sin_secciones_viables.push(ramo.to_string());
+            } else {
+                con_secciones.push((ramo, propias));
+            }
+        }
+    }
+    if let Some(max) = max_ramos && ramos.len() > max {
+        conflicto.excede_max_ramos = Some(max);
+    }
+
+    for (i, (a, secciones_a)) in con_secciones.iter().enumerate() {
+        for (b, secciones_b) in &con_secciones[i + 1..] {
+            let compatibles = secciones_a.iter()
+                .any(|x| secciones_b.iter().any(|y| !horarios_tienen_conflicto(&x.horario, &y.horario)));
+            if !compatibles {
+                conflicto.choques.push(ChoqueObligatorios { a: a.to_string(), b: b.to_string() });
+            }
+        }
+    }
+
+    if conflicto == ConflictoObligatorios::default() {
+        Ok(())
+    } else {
+        Err(conflicto)
+    }
+}
+
+/// true si la solución tiene alguna sección de cada ramo obligatorio
+pub fn incluidos(obligatorios: &[String], solucion: &[(Seccion, i32)]) -> bool {
+    obligatorios.iter()
+        .filter(|r| !r.trim().is_empty())
+        .all(|r| solucion.iter().any(|(s, _)| es_obligatoria(std::slice::from_ref(r), s)))
+}
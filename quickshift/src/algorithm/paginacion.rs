@@ -0,0 +1,32 @@
+// paginacion.rs - Página de soluciones de `/solve` (`top_n`, `offset`, `min_score`)
+//
+// El enumerador devuelve todas las soluciones que encuentra, ya ordenadas
+// (por score, `objetivo` u `objetivos`, y reordenadas por `diversity_min_diff`). Armar la
+// respuesta de cada una (desglose, alternativas) y serializar miles de ellas
+// es lo caro, así que la página se corta antes: sin soluciones vacías, solo
+// las de `total_score >= min_score`, saltando `offset` y hasta `top_n`.
+
+use crate::api_json::InputParams;
+use crate::models::Seccion;
+
+/// Página pedida y cuántas soluciones hay en total para paginar
+#[derive(Debug, Clone)]
+pub struct Pagina {
+    pub soluciones: Vec<(Vec<(Seccion, i32)>, i64)>,
+    /// Soluciones no vacías con `total_score >= min_score`, antes de `offset` y `top_n`
+    pub total: usize,
+}
+
+/// Corta `soluciones` (ya ordenadas de mejor a peor) según `params.top_n`,
+/// `params.offset` y `params.min_score`, sin cambiar su orden
+pub fn paginar(soluciones: Vec<(Vec<(Seccion, i32)>, i64)>, params: &InputParams) -> Pagina {
+    let candidatas: Vec<(Vec<(Seccion, i32)>, i64)> = soluciones.into_iter()
+        .filter(|(sol, score)| !sol.is_empty() && params.min_score.is_none_or(|min| *score >= min))
+        .collect();
+    let total = candidatas.len();
+    let soluciones = candidatas.into_iter()
+        .skip(params.offset)
+        .take(params.top_n.unwrap_or(usize::MAX))
+        .collect();
+    Pagina { soluciones, total }
+}
@@ -0,0 +1,114 @@
+// paralelo.rs - Enumeración exhaustiva de cliques repartida por semilla
+//
+// Los enumeradores de `clique.rs` recorren el espacio de combinaciones con
+// Bron–Kerbosch (ver `bron_kerbosch`), una rama por sección raíz (la
+// "semilla") en orden de prioridad. Las ramas de distintas semillas son
+// independientes, así que se reparten en un pool de rayon dedicado:
+// - Las semillas se procesan en tandas del tamaño del pool; cada rama recibe
+//   el cupo que falta para `limit`, y la mezcla respeta el orden de semillas.
+//   Así el resultado es el mismo que el de la enumeración secuencial, sin importar
+//   cuántos hilos haya.
+// - El pool tiene un tope propio de hilos (`CLIQUE_THREADS`, por defecto la
+//   mitad de los núcleos) para no dejar sin CPU a los workers de actix.
+//
+// Sin la feature `parallel` las ramas se ejecutan en secuencia.
+
+use std::collections::HashSet;
+use crate::models::Seccion;
+
+/// Soluciones tal como las devuelven los enumeradores: (secciones + score, total)
+pub type Soluciones = Vec<(Vec<(Seccion, i32)>, i64)>;
+
+/// Hilos del pool de enumeración: `clique_threads` de la configuración, o la
+/// mitad de los núcleos disponibles (al menos 1).
+pub fn hilos_enumeracion() -> usize {
+    crate::app_config::actual().clique_threads
+        .filter(|n| *n > 0)
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism().map(|n| n.get() / 2).unwrap_or(1).max(1)
+        })
+}
+
+#[cfg(feature = "parallel")]
+fn pool() -> Option<&'static rayon::ThreadPool> {
+    use std::sync::OnceLock;
+    static POOL: OnceLock<Option<rayon::ThreadPool>> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let hilos = hilos_enumeracion();
+        match rayon::ThreadPoolBuilder::new()
+            .num_threads(hilos)
+            .thread_name(|i| format!("clique-enum-{}", i))
+            .build()
+        {
+            Ok(p) => {
+                tracing::info!("🧵 [clique] pool de enumeración con {} hilos", hilos);
+                Some(p)
+            }
+            Err(e) => {
+                tracing::warn!("⚠️  [clique] no se pudo crear el pool de enumeración ({}); se usa modo secuencial", e);
+                None
+            }
+        }
+    }).as_ref()
+}
+
+/// Ejecuta una tanda de ramas (en paralelo si hay pool) y devuelve sus
+/// resultados en el mismo orden que `semillas`
+fn ejecutar_tanda<F>(semillas: &[usize], cupo: usize, rama: &F) -> Vec<Soluciones>
+where
+    F: Fn(usize, usize) -> Soluciones + Sync,
+{
+    #[cfg(feature = "parallel")]
+    if let Some(pool) = pool() {
+        use rayon::prelude::*;
+        return pool.install(|| semillas.par_iter().map(|&p| rama(p, cupo)).collect());
+    }
+    semillas.iter().map(|&p| rama(p, cupo)).collect()
+}
+
+/// Clave de una solución para deduplicar: `codigo_box` ordenados
+pub fn clave_solucion(sol: &[(Seccion, i32)]) -> String {
+    clave_secciones(sol.iter().map(|(s, _)| s))
+}
+
+/// `clave_solucion` de una lista de secciones sin prioridad
+pub fn clave_secciones<'a>(secciones: impl IntoIterator<Item = &'a Seccion>) -> String {
+    let mut keys: Vec<&str> = secciones.into_iter().map(|s| s.codigo_box.as_str()).collect();
+    keys.sort_unstable();
+    keys.join("|")
+}
+
+/// Ordena por score descendente y, a igual score, por `clave_solucion`.
+/// Los empates de score son comunes (mismos ramos en otra sección) y sin este
+/// desempate su orden dependía del recorrido del enumerador; así la salida es
+/// la misma en cada ejecución y plataforma.
+pub fn ordenar_canonico(soluciones: &mut [(Vec<(Seccion, i32)>, i64)]) {
+    soluciones.sort_by_cached_key(|(sol, score)| (std::cmp::Reverse(*score), clave_solucion(sol)));
+}
+
+/// Recorre las semillas `0..n_semillas` llamando `rama(semilla, cupo)`, que
+/// debe devolver a lo más `cupo` soluciones de esa rama. Mezcla en orden de
+/// semilla, sin duplicados, hasta `limit` soluciones.
+pub fn enumerar_por_semilla<F>(n_semillas: usize, limit: usize, rama: F) -> Soluciones
+where
+    F: Fn(usize, usize) -> Soluciones + Sync,
+{
+    let tanda = hilos_enumeracion().max(1);
+    let mut results: Soluciones = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let semillas: Vec<usize> = (0..n_semillas).collect();
+
+    for grupo in semillas.chunks(tanda) {
+        if results.len() >= limit { break; }
+        let cupo = limit - results.len();
+        for soluciones in ejecutar_tanda(grupo, cupo, &rama) {
+            for (sol, score) in soluciones {
+                if results.len() >= limit { break; }
+                if seen.insert(clave_solucion(&sol)) {
+                    results.push((sol, score));
+                }
+            }
+        }
+    }
+    results
+}
@@ -0,0 +1,120 @@
+// percentil.rs - Percentil del estudiante desde su promedio de notas
+//
+// `student_ranking` es un percentil 0-1 (ver `progreso_esperado`), pero lo
+// que el estudiante conoce es su promedio en escala 1.0-7.0. Con `promedio`
+// el percentil se estima suponiendo que los promedios de la cohorte siguen
+// una normal de `media` y `desviacion`: los de la carrera si `careers.toml`
+// declara `distribucion_promedios`, si no `DISTRIBUCION_POR_DEFECTO`.
+//
+// `normalizar` valida ambos campos y completa `student_ranking`, así el resto
+// del pipeline sigue leyendo solo el percentil. Si el request trae los dos
+// deben coincidir: es el caso de params ya normalizados que se vuelven a
+// parsear (sesiones, jobs), y cualquier otra combinación es ambigua.
+
+use serde::{Deserialize, Serialize};
+use crate::api_json::InputParams;
+
+/// Escala de notas chilena
+pub const PROMEDIO_MIN: f64 = 1.0;
+pub const PROMEDIO_MAX: f64 = 7.0;
+
+/// Distribución de promedios de una cohorte (normal)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DistribucionPromedios {
+    pub media: f64,
+    pub desviacion: f64,
+}
+
+/// Distribución usada cuando la carrera no declara la suya
+pub const DISTRIBUCION_POR_DEFECTO: DistribucionPromedios = DistribucionPromedios { media: 5.0, desviacion: 0.6 };
+
+impl DistribucionPromedios {
+    pub fn validar(&self) -> Result<(), String> {
+        if !(PROMEDIO_MIN..=PROMEDIO_MAX).contains(&self.media) || self.desviacion.is_nan() || self.desviacion <= 0.0 {
+            return Err(format!(
+                "distribucion_promedios inválida: media debe estar entre {} y {} y desviacion ser positiva",
+                PROMEDIO_MIN, PROMEDIO_MAX,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fracción de la cohorte con promedio menor a `promedio`, redondeada a milésimas
+    pub fn percentil(&self, promedio: f64) -> f64 {
+        let z = (promedio - self.media) / self.desviacion;
+        let p = 0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2));
+        (p.clamp(0.0, 1.0) * 1000.0).round() / 1000.0
+    }
+}
+
+/// Función de error (Abramowitz y Stegun 7.1.26, error < 1.5e-7)
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poli = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let y = 1.0 - poli * (-x * x).exp();
+    y.copysign(x)
+}
+
+/// De dónde salió el percentil
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FuenteRanking {
+    StudentRanking,
+    Promedio,
+}
+
+/// Percentil con que se calcularon las probabilidades de aprobar
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RankingInterpretado {
+    pub percentil: f64,
+    pub fuente: FuenteRanking,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promedio: Option<f64>,
+    /// Distribución con que se convirtió el promedio
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distribucion: Option<DistribucionPromedios>,
+}
+
+/// Distribución de la carrera del request, o la por defecto
+pub fn distribucion_de(params: &InputParams) -> DistribucionPromedios {
+    crate::carreras::carrera_de(params).ok().flatten()
+        .and_then(|c| c.distribucion_promedios)
+        .unwrap_or(DISTRIBUCION_POR_DEFECTO)
+}
+
+/// Valida `student_ranking` y `promedio` y, si viene `promedio`, completa
+/// `student_ranking` con su percentil
+pub fn normalizar(params: &mut InputParams) -> Result<(), String> {
+    if let Some(r) = params.student_ranking.filter(|r| !(0.0..=1.0).contains(r)) {
+        return Err(format!("student_ranking debe estar entre 0.0 y 1.0 (recibido {})", r));
+    }
+    let Some(promedio) = params.promedio else { return Ok(()) };
+    if !(PROMEDIO_MIN..=PROMEDIO_MAX).contains(&promedio) {
+        return Err(format!("promedio debe estar entre {:.1} y {:.1} (recibido {})", PROMEDIO_MIN, PROMEDIO_MAX, promedio));
+    }
+    let percentil = distribucion_de(params).percentil(promedio);
+    match params.student_ranking {
+        Some(r) if (r - percentil).abs() > 1e-6 => Err(format!(
+            "student_ranking ({}) no coincide con el percentil del promedio {} ({}); envía solo uno de los dos",
+            r, promedio, percentil,
+        )),
+        _ => {
+            params.student_ranking = Some(percentil);
+            Ok(())
+        }
+    }
+}
+
+/// Percentil usado por la respuesta (params ya normalizados)
+pub fn interpretar(params: &InputParams) -> Option<RankingInterpretado> {
+    let percentil = params.student_ranking?;
+    Some(match params.promedio {
+        Some(promedio) => RankingInterpretado {
+            percentil,
+            fuente: FuenteRanking::Promedio,
+            promedio: Some(promedio),
+            distribucion: Some(distribucion_de(params)),
+        },
+        None => RankingInterpretado { percentil, fuente: FuenteRanking::StudentRanking, promedio: None, distribucion: None },
+    })
+}
@@ -4,6 +4,7 @@ use crate::models::PertNode;
 
 use std::collections::{HashMap, BTreeMap, HashSet};
 use std::error::Error;
+use serde::Serialize;
 use crate::models::{RamoDisponible, Seccion};
 
 /// Filtra ramos inviables (cuyo satisfacción de prerequisitos es imposible)
@@ -12,7 +13,7 @@ pub fn build_viable_ramos(
     ramos_disponibles: &HashMap<String, RamoDisponible>,
     ramos_pasados: &[String],
 ) -> BTreeMap<String, RamoDisponible> {
-    eprintln!("🔍 [PERT] Filtrando ramos inviables (podado determinista)");
+    tracing::info!("🔍 [PERT] Filtrando ramos inviables (podado determinista)");
     
     let passed_set: HashSet<String> = ramos_pasados
         .iter()
@@ -63,11 +64,11 @@ pub fn build_viable_ramos(
             viable.insert(codigo.clone(), ramo.clone());
         } else {
             excluded_count += 1;
-            eprintln!("   ⊘ Excluido: {} (prerequisites no satisfacen)", codigo);
+            tracing::debug!("   ⊘ Excluido: {} (prerequisites no satisfacen)", codigo);
         }
     }
     
-    eprintln!("✅ [PERT] Ramos viables: {} (excluidos: {})", viable.len(), excluded_count);
+    tracing::info!("✅ [PERT] Ramos viables: {} (excluidos: {})", viable.len(), excluded_count);
     viable
 }
 
@@ -81,26 +82,86 @@ pub fn build_and_run_pert(
     lista_secciones: &Vec<Seccion>,
     malla_name: &str,
 ) -> Result<(), Box<dyn Error>> {
-    // Construir grafo y índice de nodos
-    let mut pert_graph: DiGraph<PertNode, ()> = DiGraph::new();
-    let mut node_map: HashMap<i32, NodeIndex> = HashMap::new();  // id (i32) -> NodeIndex
+    // Resolver path de la malla (fallback heurístico si es necesario)
+    let malla_pathbuf = match crate::excel::resolve_datafile_paths(malla_name) {
+        Ok((m, _, _)) => m,
+        Err(_) => {
+            let data_dir = std::path::Path::new(crate::excel::DATAFILES_DIR);
+            let mut found: Option<std::path::PathBuf> = None;
+            if let Ok(entries) = std::fs::read_dir(data_dir) {
+                for e in entries.flatten() {
+                    if !e.path().is_file() { continue; }
+                    if let Some(n) = e.file_name().to_str() {
+                        let ln = n.to_lowercase();
+                        if ln.contains("malla") || n == malla_name {
+                            found = Some(e.path());
+                            break;
+                        }
+                    }
+                }
+            }
+            found.unwrap_or_else(|| std::path::PathBuf::from(malla_name.to_string()))
+        }
+    };
+
+    let malla_path = malla_pathbuf.to_str().unwrap_or(malla_name).to_string();
 
+    // Intentar obtener prerequisitos directamente sin caché; si falla,
+    // el error se propaga y no añadimos aristas por prereqs.
+    let prerequisitos = crate::excel::leer_prerequisitos(&malla_path).ok();
+    build_and_run_pert_con_prerequisitos(ramos_actualizados, lista_secciones, prerequisitos.as_ref())
+}
+
+/// Igual que `build_and_run_pert`, pero recibe la hoja de prerequisitos ya
+/// leída (código/nombre -> prerequisitos) en vez de buscarla en disco.
+/// No toca el sistema de archivos, por lo que sirve para datos en memoria.
+pub fn build_and_run_pert_con_prerequisitos(
+    ramos_actualizados: &mut HashMap<String, RamoDisponible>,
+    lista_secciones: &[Seccion],
+    prerequisitos: Option<&HashMap<String, Vec<String>>>,
+) -> Result<(), Box<dyn Error>> {
     // Construir conjunto de códigos presentes en `lista_secciones` para
     // excluir ramos que no tienen secciones (filtrado de filas vacías OA).
-    use std::collections::HashSet;
+    // `code_key` corresponde a la clave usada en `ramos_actualizados` y
+    // normalmente coincide con `Seccion.codigo`.
     let present_codes: HashSet<String> = lista_secciones.iter()
         .map(|s| s.codigo.trim().to_ascii_uppercase())
         .collect();
+    let (pert_graph, node_map) = calcular_grafo_pert(
+        ramos_actualizados,
+        |code_key| present_codes.contains(&code_key.trim().to_ascii_uppercase()),
+        prerequisitos,
+    );
+
+    // Propagar resultado PERT a ramos_actualizados (marcar críticos con holgura == 0)
+    for (id, idx) in node_map.iter() {
+        let holgura_cero = pert_graph.node_weight(*idx).and_then(|pn| pn.h) == Some(0);
+        if let Some(ramo) = ramos_actualizados.values_mut().find(|r| r.id == *id).filter(|_| holgura_cero) {
+            ramo.critico = true;
+        }
+    }
+
+    Ok(())
+}
+
+/// Construye el grafo PERT con los ramos cuya clave acepta `incluir` y
+/// calcula ES/EF/LS/LF/holgura de cada nodo. Devuelve el grafo y el índice
+/// id de ramo -> nodo.
+fn calcular_grafo_pert(
+    ramos_actualizados: &HashMap<String, RamoDisponible>,
+    incluir: impl Fn(&str) -> bool,
+    prerequisitos: Option<&HashMap<String, Vec<String>>>,
+) -> (DiGraph<PertNode, ()>, HashMap<i32, NodeIndex>) {
+    // Construir grafo y índice de nodos
+    let mut pert_graph: DiGraph<PertNode, ()> = DiGraph::new();
+    let mut node_map: HashMap<i32, NodeIndex> = HashMap::new();  // id (i32) -> NodeIndex
 
     // DETERMINISMO: Iterar en orden alfabético por clave para garantizar reproducibilidad
     let mut sorted_ramos: Vec<_> = ramos_actualizados.iter().collect();
     sorted_ramos.sort_by(|a, b| a.0.cmp(b.0));
 
     for (code_key, ramo) in sorted_ramos.iter() {
-        // `code_key` corresponde a la clave usada en `ramos_actualizados` y
-        // normalmente coincide con `Seccion.codigo`. Si no está presente en
-        // `lista_secciones`, saltamos el ramo.
-        if !present_codes.contains(&code_key.trim().to_ascii_uppercase()) {
+        if !incluir(code_key) {
             continue;
         }
 
@@ -161,33 +222,7 @@ pub fn build_and_run_pert(
         s.chars().filter(|c| c.is_alphanumeric()).map(|c| c.to_ascii_uppercase()).collect()
     }
 
-    // Resolver path de la malla (fallback heurístico si es necesario)
-    let malla_pathbuf = match crate::excel::resolve_datafile_paths(malla_name) {
-        Ok((m, _, _)) => m,
-        Err(_) => {
-            let data_dir = std::path::Path::new(crate::excel::DATAFILES_DIR);
-            let mut found: Option<std::path::PathBuf> = None;
-            if let Ok(entries) = std::fs::read_dir(data_dir) {
-                for e in entries.flatten() {
-                    if !e.path().is_file() { continue; }
-                    if let Some(n) = e.file_name().to_str() {
-                        let ln = n.to_lowercase();
-                        if ln.contains("malla") || n == malla_name {
-                            found = Some(e.path());
-                            break;
-                        }
-                    }
-                }
-            }
-            found.unwrap_or_else(|| std::path::PathBuf::from(malla_name.to_string()))
-        }
-    };
-
-    let malla_path = malla_pathbuf.to_str().unwrap_or(malla_name).to_string();
-
-    // Intentar obtener prerequisitos directamente sin caché; si falla,
-    // el error se propaga y no añadimos aristas por prereqs.
-    if let Ok(pr_map) = crate::excel::leer_prerequisitos(&malla_path) {
+    if let Some(pr_map) = prerequisitos {
         // construir índice: ID (i32) -> NodeIndex
         let mut id_to_node: HashMap<i32, NodeIndex> = HashMap::new();
         for (id, idx) in node_map.iter() {
@@ -246,7 +281,7 @@ pub fn build_and_run_pert(
         Ok(order) => order,
         Err(_) => {
             // En caso de ciclo, hacer fallback limitado (evitamos bucles infinitos)
-            eprintln!("WARNING: PERT graph contains a cycle; using limited iterative fallback");
+            tracing::warn!("PERT graph contains a cycle; using limited iterative fallback");
             let node_count = pert_graph.node_count();
             for _ in 0..3 {
                 for node_idx in pert_graph.node_indices() {
@@ -254,22 +289,7 @@ pub fn build_and_run_pert(
                     set_values_simple(&mut pert_graph, node_idx, len_dag);
                 }
             }
-            // Propagar resultado PERT (igual que abajo) y volver
-            for (id, idx) in node_map.iter() {
-                if let Some(pn) = pert_graph.node_weight(*idx) {
-                    if let Some(h) = pn.h {
-                        for (_norm_name, ramo) in ramos_actualizados.iter_mut() {
-                            if ramo.id == *id {
-                                if h == 0 {
-                                    ramo.critico = true;
-                                }
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-            return Ok(());
+            return (pert_graph, node_map);
         }
     };
 
@@ -322,23 +342,144 @@ pub fn build_and_run_pert(
         }
     }
 
-    // Propagar resultado PERT a ramos_actualizados (marcar críticos con holgura == 0)
-    for (id, idx) in node_map.iter() {
-        if let Some(pn) = pert_graph.node_weight(*idx) {
-            if let Some(h) = pn.h {
-                for (_norm_name, ramo) in ramos_actualizados.iter_mut() {
-                    if ramo.id == *id {
-                        if h == 0 {
-                            ramo.critico = true;
-                        }
-                        break;
-                    }
+    (pert_graph, node_map)
+}
+
+/// Ramo de la red PERT con sus tiempos (en semestres, duración 1 por ramo)
+#[derive(Debug, Clone, Serialize)]
+pub struct NodoPert {
+    pub id: i32,
+    pub codigo: String,
+    pub nombre: String,
+    pub semestre: Option<i32>,
+    pub es: i32,
+    pub ef: i32,
+    pub ls: i32,
+    pub lf: i32,
+    /// Holgura: semestres que se puede postergar sin atrasar la carrera
+    pub holgura: i32,
+    pub critico: bool,
+    /// Ids de los ramos que deben ir antes (aristas entrantes)
+    pub predecesores: Vec<i32>,
+}
+
+/// Red PERT completa de una malla
+#[derive(Debug, Clone, Serialize)]
+pub struct RedPert {
+    /// Ordenados por ES, luego por código
+    pub nodos: Vec<NodoPert>,
+    /// Códigos de los ramos con holgura 0, en el mismo orden que `nodos`
+    pub ruta_critica: Vec<String>,
+}
+
+/// Calcula la red PERT de todos los ramos de la malla (sin filtrar por oferta),
+/// con las mismas aristas que usa el solver: prerequisitos, correlativos y la
+/// hoja de prerequisitos si se entrega.
+pub fn red_pert_malla(
+    ramos: &HashMap<String, RamoDisponible>,
+    prerequisitos: Option<&HashMap<String, Vec<String>>>,
+) -> RedPert {
+    let (grafo, node_map) = calcular_grafo_pert(ramos, |_| true, prerequisitos);
+    let por_id: HashMap<i32, &RamoDisponible> = ramos.values().map(|r| (r.id, r)).collect();
+
+    let mut nodos: Vec<NodoPert> = node_map.iter().filter_map(|(id, &idx)| {
+        let pn = grafo.node_weight(idx)?;
+        let ramo = por_id.get(id)?;
+        let mut predecesores: Vec<i32> = grafo.neighbors_directed(idx, Direction::Incoming)
+            .filter_map(|p| grafo.node_weight(p).and_then(|n| n.codigo.parse::<i32>().ok()))
+            .collect();
+        predecesores.sort_unstable();
+        let holgura = pn.h.unwrap_or(0);
+        Some(NodoPert {
+            id: *id,
+            codigo: ramo.codigo.clone(),
+            nombre: ramo.nombre.clone(),
+            semestre: ramo.semestre,
+            es: pn.es.unwrap_or(1),
+            ef: pn.ef.unwrap_or(2),
+            ls: pn.ls.unwrap_or(1),
+            lf: pn.lf.unwrap_or(2),
+            holgura,
+            critico: holgura == 0,
+            predecesores,
+        })
+    }).collect();
+    nodos.sort_by(|a, b| a.es.cmp(&b.es).then(a.codigo.cmp(&b.codigo)).then(a.id.cmp(&b.id)));
+
+    let ruta_critica = nodos.iter().filter(|n| n.critico).map(|n| n.codigo.clone()).collect();
+    RedPert { nodos, ruta_critica }
+}
+
+/// Ramo de `GET /malla/{id}/criticos`
+#[derive(Debug, Clone, Serialize)]
+pub struct RamoCritico {
+    pub codigo: String,
+    pub nombre: String,
+    pub semestre: Option<i32>,
+    pub holgura: i32,
+    /// Está en la ruta crítica (holgura 0)
+    pub critico: bool,
+    /// Semestre más temprano y más tardío en que se puede cursar
+    pub es: i32,
+    pub ls: i32,
+    /// Ramos que dependen de este, directa o indirectamente
+    pub desbloquea: usize,
+}
+
+/// Ramos de la malla por urgencia y la ruta crítica
+#[derive(Debug, Clone, Serialize)]
+pub struct CriticosMalla {
+    /// Semestres que toma la cadena de prerequisitos más larga
+    pub semestres_minimos: i32,
+    /// Códigos de la ruta crítica, ordenados por ES
+    pub ruta_critica: Vec<String>,
+    /// Todos los ramos, del más urgente al menos urgente
+    pub ramos: Vec<RamoCritico>,
+}
+
+/// Ordena los ramos de la red por urgencia: menor holgura primero, luego el
+/// que antes deja de poder postergarse (LS), luego el que desbloquea más
+/// ramos y al final por código para que el orden sea estable.
+pub fn ramos_criticos(red: &RedPert) -> CriticosMalla {
+    let mut sucesores: HashMap<i32, Vec<i32>> = HashMap::new();
+    for n in &red.nodos {
+        for p in &n.predecesores {
+            sucesores.entry(*p).or_default().push(n.id);
+        }
+    }
+    let desbloquea = |id: i32| {
+        let mut vistos: HashSet<i32> = HashSet::new();
+        let mut pila = vec![id];
+        while let Some(actual) = pila.pop() {
+            for s in sucesores.get(&actual).into_iter().flatten() {
+                if *s != id && vistos.insert(*s) {
+                    pila.push(*s);
                 }
             }
         }
-    }
+        vistos.len()
+    };
 
-    Ok(())
+    let mut ramos: Vec<RamoCritico> = red.nodos.iter().map(|n| RamoCritico {
+        codigo: n.codigo.clone(),
+        nombre: n.nombre.clone(),
+        semestre: n.semestre,
+        holgura: n.holgura,
+        critico: n.critico,
+        es: n.es,
+        ls: n.ls,
+        desbloquea: desbloquea(n.id),
+    }).collect();
+    ramos.sort_by(|a, b| a.holgura.cmp(&b.holgura)
+        .then(a.ls.cmp(&b.ls))
+        .then(b.desbloquea.cmp(&a.desbloquea))
+        .then(a.codigo.cmp(&b.codigo)));
+
+    CriticosMalla {
+        semestres_minimos: red.nodos.iter().map(|n| n.ef - 1).max().unwrap_or(0),
+        ruta_critica: red.ruta_critica.clone(),
+        ramos,
+    }
 }
 /// Versión simplificada NO RECURSIVA para cálcular PERT
 /// Calcula valores para un nodo basándose en sus predecesores
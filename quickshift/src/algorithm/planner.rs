@@ -0,0 +1,206 @@
+// planner.rs - Plan de carrera completo, semestre a semestre
+//
+// `ruta::ejecutar_ruta_critica_with_params` solo planifica el semestre
+// inmediato. Este módulo repite el pipeline (PERT + clique) hasta cubrir la
+// malla:
+//   1. Con los ramos aprobados hasta ahora se podan los inviables, se corre
+//      PERT y el clique sobre la oferta (`en_memoria::preparar_datos_en_memoria`
+//      + `ruta::resolver_con_datos`).
+//   2. Los ramos de malla de la mejor solución se dan por aprobados.
+//   3. Si quedan cupos, se completan con ramos ya tomables que NO tienen
+//      sección en la oferta actual (críticos primero, luego menor holgura).
+//   4. Se avanza al periodo siguiente y se repite.
+//
+// Supuesto: la oferta vigente se repite en los semestres futuros (no hay
+// oferta publicada para ellos). Se detiene al completar la malla, al no poder
+// avanzar (prerequisitos que no están en la malla) o al llegar a `max_semestres`.
+// No lee archivos: el handler entrega malla y oferta ya cargadas.
+
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use crate::api_json::InputParams;
+use crate::excel::normalize_name;
+use crate::algorithm::aprobados::Aprobados;
+use crate::models::{RamoDisponible, Seccion};
+use crate::algorithm::{en_memoria, ruta};
+
+/// Máximo de ramos por semestre (mismo tope que el clique)
+pub const MAX_RAMOS_SEMESTRE: usize = 6;
+
+/// Tope por defecto de semestres a proyectar
+pub const MAX_SEMESTRES_DEFAULT: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RamoPlanificado {
+    pub codigo: String,
+    pub nombre: String,
+    /// Sección elegida por el clique; `None` si el ramo no está en la oferta actual
+    pub seccion: Option<String>,
+    pub horario: Vec<String>,
+    pub critico: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SemestrePlan {
+    /// 1 = próximo semestre
+    pub numero: usize,
+    /// Periodo proyectado, p.ej. "20261"
+    pub periodo: String,
+    pub ramos: Vec<RamoPlanificado>,
+    /// Score de la solución del clique usada (0 si no hubo solución)
+    pub score: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanCarrera {
+    pub periodo_inicial: String,
+    pub semestres: Vec<SemestrePlan>,
+    /// Periodo del último semestre planificado (solo si la malla queda completa)
+    pub egreso_estimado: Option<String>,
+    pub completo: bool,
+    /// Cota inferior: largo de la cadena de prerequisitos más larga al inicio
+    pub semestres_minimos: usize,
+    /// Ramos que quedaron sin planificar (vacío si `completo`)
+    pub pendientes: Vec<String>,
+}
+
+/// Periodo siguiente: "20251" -> "20252", "20252" -> "20261". "2025" se trata como "20251".
+pub fn siguiente_periodo(periodo: &str) -> String {
+    let anio: u32 = periodo.get(..4).and_then(|s| s.parse().ok()).unwrap_or(0);
+    let sem: u32 = periodo.get(4..).and_then(|s| s.parse().ok()).unwrap_or(1);
+    if sem >= 2 {
+        format!("{}1", anio + 1)
+    } else {
+        format!("{}2", anio)
+    }
+}
+
+fn clave_ramo(r: &RamoDisponible) -> String {
+    if r.codigo.trim().is_empty() { r.nombre.clone() } else { r.codigo.trim().to_uppercase() }
+}
+
+/// Ramo de la malla al que corresponde una sección (por código o nombre normalizado)
+fn ramo_de_seccion<'a>(s: &Seccion, malla: &'a HashMap<String, RamoDisponible>) -> Option<&'a RamoDisponible> {
+    let codigo = s.codigo.trim().to_uppercase();
+    let nombre = normalize_name(&s.nombre);
+    malla.values().find(|r| !r.codigo.trim().is_empty() && r.codigo.trim().to_uppercase() == codigo)
+        .or_else(|| malla.values().find(|r| normalize_name(&r.nombre) == nombre))
+}
+
+/// Proyecta el plan de carrera desde `periodo_inicial` (el de la oferta vigente).
+/// `params.ramos_pasados` debe venir ya mapeado con equivalencias.
+pub fn planificar_carrera(
+    params: &InputParams,
+    malla: &HashMap<String, RamoDisponible>,
+    secciones: &[Seccion],
+    periodo_inicial: &str,
+    max_semestres: usize,
+) -> PlanCarrera {
+    let mut params = params.clone();
+    let semestres_minimos = crate::algorithm::bloqueantes::calcular_bloqueantes(malla, &params.ramos_pasados).cadena_maxima;
+    let sin_prerequisitos: HashMap<String, Vec<String>> = HashMap::new();
+    let sin_equivalencias: HashMap<String, String> = HashMap::new();
+
+    let mut semestres: Vec<SemestrePlan> = Vec::new();
+    let mut periodo = periodo_inicial.to_string();
+
+    for numero in 1..=max_semestres {
+        let aprobados = Aprobados::new(&params.ramos_pasados);
+        let pendientes = malla.values().filter(|r| !aprobados.contiene(r)).count();
+        if pendientes == 0 {
+            break;
+        }
+        tracing::info!("🗓️  [planner] semestre {} ({}): {} ramos pendientes", numero, periodo, pendientes);
+
+        // Solo secciones de ramos de malla con TODOS sus prerequisitos ya aprobados:
+        // el podado de PERT admite cadenas completas dentro de un mismo semestre.
+        let ids_aprobados: HashSet<i32> = malla.values()
+            .filter(|r| aprobados.contiene(r))
+            .map(|r| r.id)
+            .collect();
+        let tomable = |r: &RamoDisponible| !aprobados.contiene(r)
+            && r.requisitos_ids.iter().all(|id| ids_aprobados.contains(id));
+        let secciones_tomables: Vec<Seccion> = secciones.iter()
+            .filter(|s| ramo_de_seccion(s, malla).map(tomable).unwrap_or(false))
+            .cloned()
+            .collect();
+
+        // PERT + clique con lo aprobado hasta ahora
+        let datos = en_memoria::preparar_datos_en_memoria(
+            &mut params,
+            malla.values().cloned().collect(),
+            secciones_tomables,
+            &sin_prerequisitos,
+            &sin_equivalencias,
+        );
+        let (mejor, score) = ruta::resolver_con_datos(&datos, &params, None)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|(sol, _)| !sol.is_empty())
+            .unwrap_or_default();
+
+        let mut ramos: Vec<RamoPlanificado> = Vec::new();
+        let mut tomados: HashSet<String> = HashSet::new();
+        for (s, _) in mejor.iter() {
+            let Some(r) = ramo_de_seccion(s, malla) else { continue };
+            if !tomable(r) || !tomados.insert(clave_ramo(r)) {
+                continue;
+            }
+            let critico = datos.ramos_disponibles.get(&normalize_name(&r.nombre)).map(|p| p.critico).unwrap_or(false);
+            ramos.push(RamoPlanificado {
+                codigo: r.codigo.clone(),
+                nombre: r.nombre.clone(),
+                seccion: Some(s.seccion.clone()),
+                horario: s.horario.clone(),
+                critico,
+            });
+        }
+
+        // Completar con ramos tomables sin sección en la oferta actual
+        let mut sin_oferta: Vec<&RamoDisponible> = datos.ramos_disponibles.values()
+            .filter(|r| tomable(r))
+            .filter(|r| !tomados.contains(&clave_ramo(r)))
+            .filter(|r| !secciones.iter().any(|s| ramo_de_seccion(s, malla).map(|m| m.id) == Some(r.id)))
+            .collect();
+        sin_oferta.sort_by(|a, b| b.critico.cmp(&a.critico)
+            .then(a.holgura.cmp(&b.holgura))
+            .then(a.semestre.unwrap_or(i32::MAX).cmp(&b.semestre.unwrap_or(i32::MAX)))
+            .then(a.id.cmp(&b.id)));
+        for r in sin_oferta.into_iter().take(MAX_RAMOS_SEMESTRE.saturating_sub(ramos.len())) {
+            ramos.push(RamoPlanificado {
+                codigo: r.codigo.clone(),
+                nombre: r.nombre.clone(),
+                seccion: None,
+                horario: Vec::new(),
+                critico: r.critico,
+            });
+        }
+
+        if ramos.is_empty() {
+            tracing::warn!("   ⚠️  [planner] sin avance posible en {}: se detiene la proyección", periodo);
+            break;
+        }
+        for r in ramos.iter() {
+            params.ramos_pasados.push(if r.codigo.trim().is_empty() { r.nombre.clone() } else { r.codigo.clone() });
+        }
+        semestres.push(SemestrePlan { numero, periodo: periodo.clone(), ramos, score });
+        periodo = siguiente_periodo(&periodo);
+    }
+
+    let aprobados = Aprobados::new(&params.ramos_pasados);
+    let mut pendientes: Vec<String> = malla.values()
+        .filter(|r| !aprobados.contiene(r))
+        .map(clave_ramo)
+        .collect();
+    pendientes.sort();
+    let completo = pendientes.is_empty();
+
+    PlanCarrera {
+        periodo_inicial: periodo_inicial.to_string(),
+        egreso_estimado: if completo { semestres.last().map(|s| s.periodo.clone()) } else { None },
+        semestres,
+        completo,
+        semestres_minimos,
+        pendientes,
+    }
+}
@@ -0,0 +1,108 @@
+// prechequeo.rs - Cuántos ramos y secciones sobreviven a cada filtro (`POST /solve/precheck`)
+//
+// Recorre las mismas etapas de filtrado que `ruta` y el enumerador greedy,
+// en orden y acumuladas, sin buscar cliques:
+//   1. oferta: todas las secciones cargadas para la malla;
+//   2. pendientes: sin los ramos aprobados, `ramos_excluidos` ni los que
+//      tienen notas de `filtros.evitar_tags`;
+//   3. horizonte_semestre: ramos hasta el mayor semestre aprobado + 2 (y CFG
+//      y `ramos_obligatorios`);
+//   4. prerequisitos: electivos con sus requisitos aprobados (los ramos de
+//      la malla inalcanzables ya quedan fuera al preparar los datos, ver
+//      `pert::build_viable_ramos`);
+//   5. filtros_usuario: secciones fijas, franjas prohibidas, compromisos y
+//      `filtros` (`ruta::seccion_viable` y `clique::seccion_cumple_filtros`);
+//   6. reglas_malla: sin CFG ni electivos si la malla ya no exige más.
+// Lo que queda es la cota superior de lo que puede aparecer en una solución;
+// el choque de horarios entre secciones recién se ve en el clique.
+
+use std::collections::HashSet;
+use std::error::Error;
+use serde::Serialize;
+use crate::algorithm::clique::{dentro_del_horizonte, prerequisitos_de_seccion_ok, ramo_excluido, seccion_cumple_filtros, semestre_maximo};
+use crate::algorithm::fijas;
+use crate::algorithm::indice_ramos::RamoIndex;
+use crate::algorithm::obligatorios;
+use crate::algorithm::tags;
+use crate::algorithm::ruta::{seccion_viable, DatosRuta};
+use crate::api_json::InputParams;
+use crate::models::Seccion;
+use crate::reglas;
+
+/// Ramos y secciones que quedan después de una etapa
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Etapa {
+    pub etapa: &'static str,
+    pub ramos: usize,
+    pub secciones: usize,
+}
+
+/// Resultado de `prechequear`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Prechequeo {
+    /// En el orden en que se aplican; cada una parte de lo que dejó la anterior
+    pub etapas: Vec<Etapa>,
+    pub ramos_elegibles: usize,
+    pub secciones_elegibles: usize,
+    /// Tope de ramos por solución del request (`reglas::tope_ramos`); se
+    /// omite cuando el límite es `max_creditos`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_ramos: Option<usize>,
+    /// Semestre más alto de la malla que entra en el horizonte
+    pub semestre_maximo: i32,
+}
+
+fn etapa(nombre: &'static str, secciones: &[&Seccion]) -> Etapa {
+    let ramos: HashSet<String> = secciones.iter().map(|s| s.codigo.trim().to_uppercase()).collect();
+    Etapa { etapa: nombre, ramos: ramos.len(), secciones: secciones.len() }
+}
+
+/// Aplica las etapas de filtrado a `datos` (ya preparados, con
+/// `params.ramos_pasados` mapeados). Falla solo si las secciones fijas no
+/// existen o no pueden ir juntas (`fijas::ConflictoFijas`).
+pub fn prechequear(datos: &DatosRuta, params: &InputParams) -> Result<Prechequeo, Box<dyn Error>> {
+    let indice = RamoIndex::new(&datos.ramos_disponibles);
+    let fijas = if params.secciones_fijas.is_empty() {
+        Vec::new()
+    } else {
+        fijas::resolver(&params.secciones_fijas, &datos.lista_secciones, &params.ramos_pasados)?
+    };
+    let pasados: HashSet<String> = params.ramos_pasados.iter().map(|s| s.to_uppercase()).collect();
+    let max_sem = semestre_maximo(params, &indice);
+    let reglas_malla = reglas::de_params(params);
+    let sin_cfg = reglas_malla.max_cfg.saturating_sub(reglas::cfgs_aprobados(&params.ramos_pasados)) == 0;
+    let sin_electivos = reglas::electivos_aprobados(&params.ramos_pasados, &datos.ramos_disponibles) >= reglas_malla.max_electivos;
+
+    let mut secciones: Vec<&Seccion> = datos.lista_secciones.iter().collect();
+    let mut etapas = vec![etapa("oferta", &secciones)];
+    let mut filtrar = |nombre: &'static str, secciones: &mut Vec<&Seccion>, admite: &dyn Fn(&Seccion) -> bool| {
+        secciones.retain(|s| admite(s));
+        etapas.push(etapa(nombre, secciones));
+    };
+    let evitar_tags = tags::tags_a_evitar(&params.filtros);
+    filtrar("pendientes", &mut secciones, &|s| {
+        !pasados.contains(&s.codigo.to_uppercase())
+            && !ramo_excluido(s, &params.ramos_excluidos)
+            && !tags::ramo_evitado(s, &indice, &evitar_tags)
+    });
+    filtrar("horizonte_semestre", &mut secciones, &|s| {
+        dentro_del_horizonte(s, &indice, max_sem) || obligatorios::es_obligatoria(&params.ramos_obligatorios, s)
+    });
+    filtrar("prerequisitos", &mut secciones, &|s| prerequisitos_de_seccion_ok(s, &indice, &pasados));
+    filtrar("filtros_usuario", &mut secciones, &|s| {
+        seccion_viable(s, params, &pasados, &fijas) && seccion_cumple_filtros(s, &params.filtros)
+    });
+    filtrar("reglas_malla", &mut secciones, &|s| {
+        !(sin_cfg && s.is_cfg)
+            && !(sin_electivos && !s.is_cfg && (s.is_electivo || indice.de_seccion(s).is_some_and(|r| r.electivo)))
+    });
+
+    let ultima = etapas.last().cloned().unwrap_or_else(|| etapa("oferta", &[]));
+    Ok(Prechequeo {
+        ramos_elegibles: ultima.ramos,
+        secciones_elegibles: ultima.secciones,
+        etapas,
+        max_ramos: reglas::tope_ramos(params),
+        semestre_maximo: max_sem,
+    })
+}
@@ -0,0 +1,73 @@
+// prioridad.rs - Prioridad de una sección (CC UU KK SS de RutaCritica.py)
+//
+// RutaCritica.py concatena cuatro números de dos dígitos y los lee como un
+// entero: CC ("10" si el ramo es crítico), UU (10 - holgura), KK (60 -
+// número correlativo) y SS (número de sección). `Priority` guarda cada
+// componente por separado; el orden derivado compara en ese mismo orden, que
+// es el orden del entero concatenado. `Priority::score` lo convierte al
+// score numérico de las soluciones con los pesos de `crate::scoring`.
+
+use std::collections::HashMap;
+use std::fmt;
+use serde::Serialize;
+use crate::algorithm::progreso_esperado::ramo_de_seccion;
+use crate::models::{RamoDisponible, Seccion};
+use crate::scoring::ScoringConfig;
+
+/// Componentes de la prioridad de una sección, de mayor a menor peso
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Priority {
+    /// CC: el ramo está en la ruta crítica
+    pub critico: bool,
+    /// UU: 10 - holgura (0-10)
+    pub uu: i64,
+    /// KK: 60 - número correlativo (0-60)
+    pub kk: i64,
+    /// SS: número de sección (0-99, 0 si no es numérico)
+    pub ss: i64,
+}
+
+impl Priority {
+    /// CFG sin entrada en la malla: "10 01 01 50", similar a un ramo de 3er semestre
+    pub const CFG: Priority = Priority { critico: true, uu: 1, kk: 1, ss: 50 };
+
+    /// Electivo sin entrada en la malla: "00 05 30 00", bajo los obligatorios
+    pub const ELECTIVO: Priority = Priority { critico: false, uu: 5, kk: 30, ss: 0 };
+
+    /// Prioridad de `sec` como sección de `ramo`
+    pub fn de_seccion(ramo: &RamoDisponible, sec: &Seccion) -> Self {
+        Priority {
+            critico: ramo.critico,
+            uu: 10 - (ramo.holgura as i64).clamp(0, 10),
+            kk: (60 - ramo.numb_correlativo.max(0) as i64).clamp(0, 60),
+            ss: sec.seccion.trim().parse::<i64>().map(|n| n.clamp(0, 99)).unwrap_or(0),
+        }
+    }
+
+    /// Score numérico. Con los pesos por defecto es el entero CCUUKKSS de
+    /// RutaCritica.py (p.ej. 10_085_503 para "10 08 55 03").
+    pub fn score(&self, scoring: &ScoringConfig) -> i64 {
+        let cc = if self.critico { scoring.bonus_critico } else { 0 };
+        cc + self.uu * scoring.peso_holgura + self.kk * scoring.peso_correlativo + self.ss * scoring.peso_seccion
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02} {:02} {:02} {:02}", if self.critico { 10 } else { 0 }, self.uu, self.kk, self.ss)
+    }
+}
+
+/// Prioridad de una sección de una solución: los CFG usan `Priority::CFG`
+/// (como `clique::solucion_desde_clique`), el resto la de su ramo en la malla
+/// y los electivos sin ramo `Priority::ELECTIVO`
+pub fn prioridad_de(sec: &Seccion, ramos: &HashMap<String, RamoDisponible>) -> Priority {
+    if sec.is_cfg {
+        return Priority::CFG;
+    }
+    match ramo_de_seccion(sec, ramos) {
+        Some(r) => Priority::de_seccion(r, sec),
+        None if sec.is_electivo => Priority::ELECTIVO,
+        None => Priority::default(),
+    }
+}
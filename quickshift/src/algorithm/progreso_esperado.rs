@@ -0,0 +1,103 @@
+// progreso_esperado.rs - Probabilidad de aprobar y créditos esperados
+//
+// `RamoDisponible::dificultad` es el porcentaje histórico de aprobados del
+// ramo y `InputParams::student_ranking` el percentil del estudiante (0-1).
+// La probabilidad de aprobar parte de la tasa del ramo y se desplaza en
+// escala logit según el percentil: el percentil 0.5 obtiene la tasa
+// histórica, el 1.0 la sube y el 0.0 la baja (±`PESO_RANKING / 2` en logit).
+// Sin ranking se usa la tasa del ramo; sin tasa, `PROBABILIDAD_POR_DEFECTO`.
+//
+// Los créditos esperados de una solución son la suma de créditos SCT de sus
+// ramos ponderados por esa probabilidad. Con `objetivo: "expected_progress"`
+// `ruta::resolver_con_datos_hasta` ordena las soluciones por este valor.
+
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use crate::algorithm::clique::CREDITOS_SCT_POR_DEFECTO;
+use crate::algorithm::paralelo::{clave_solucion, Soluciones};
+use crate::excel::normalize_name;
+use crate::models::{RamoDisponible, Seccion};
+
+/// Probabilidad asumida para ramos sin porcentaje de aprobación
+pub const PROBABILIDAD_POR_DEFECTO: f64 = 0.8;
+
+/// Desplazamiento en logit entre el percentil 0 y el 1 (un percentil 0.9
+/// lleva una tasa de 70% a ~89%)
+pub const PESO_RANKING: f64 = 3.0;
+
+/// Probabilidad estimada de aprobar un ramo
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProbabilidadRamo {
+    pub codigo: String,
+    pub probabilidad: f64,
+    pub creditos: i32,
+}
+
+/// Progreso esperado de una solución
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgresoEsperado {
+    /// Créditos SCT inscritos, contando cada ramo una vez
+    pub creditos_inscritos: i32,
+    /// Suma de créditos ponderados por la probabilidad de aprobar cada ramo
+    pub creditos_esperados: f64,
+    pub ramos: Vec<ProbabilidadRamo>,
+}
+
+/// Probabilidad de aprobar dado el porcentaje de aprobados (0-100) y el percentil (0-1)
+pub fn probabilidad_aprobar(dificultad: Option<f64>, student_ranking: Option<f64>) -> f64 {
+    let base = dificultad.map(|d| d / 100.0).unwrap_or(PROBABILIDAD_POR_DEFECTO).clamp(0.01, 0.99);
+    let Some(ranking) = student_ranking else { return base };
+    let logit = (base / (1.0 - base)).ln() + PESO_RANKING * (ranking.clamp(0.0, 1.0) - 0.5);
+    1.0 / (1.0 + (-logit).exp())
+}
+
+pub(crate) fn ramo_de_seccion<'a>(s: &Seccion, ramos: &'a HashMap<String, RamoDisponible>) -> Option<&'a RamoDisponible> {
+    let codigo = s.codigo.trim().to_uppercase();
+    let nombre = normalize_name(&s.nombre);
+    ramos.values().find(|r| !r.codigo.trim().is_empty() && r.codigo.trim().to_uppercase() == codigo)
+        .or_else(|| ramos.values().find(|r| normalize_name(&r.nombre) == nombre))
+}
+
+/// Progreso esperado de las secciones de una solución. Las secciones sin ramo
+/// en la malla (p. ej. CFG) usan `PROBABILIDAD_POR_DEFECTO` y
+/// `CREDITOS_SCT_POR_DEFECTO` si no hay dato.
+pub fn progreso_esperado<'a>(
+    secciones: impl IntoIterator<Item = &'a Seccion>,
+    ramos: &HashMap<String, RamoDisponible>,
+    student_ranking: Option<f64>,
+) -> ProgresoEsperado {
+    let mut vistos: HashSet<String> = HashSet::new();
+    let mut detalle: Vec<ProbabilidadRamo> = Vec::new();
+    for s in secciones {
+        let ramo = ramo_de_seccion(s, ramos);
+        let clave = ramo.map(|r| format!("#{}", r.id)).unwrap_or_else(|| s.codigo.trim().to_uppercase());
+        if !vistos.insert(clave) {
+            continue;
+        }
+        detalle.push(ProbabilidadRamo {
+            codigo: ramo.map(|r| r.codigo.clone()).filter(|c| !c.is_empty()).unwrap_or_else(|| s.codigo.clone()),
+            probabilidad: probabilidad_aprobar(ramo.and_then(|r| r.dificultad), student_ranking),
+            creditos: ramo.and_then(|r| r.creditos).unwrap_or(CREDITOS_SCT_POR_DEFECTO),
+        });
+    }
+    ProgresoEsperado {
+        creditos_inscritos: detalle.iter().map(|r| r.creditos).sum(),
+        creditos_esperados: detalle.iter().map(|r| r.creditos as f64 * r.probabilidad).sum(),
+        ramos: detalle,
+    }
+}
+
+/// Ordena soluciones por créditos esperados (desc), luego por score (desc) y
+/// por último por `clave_solucion` (ver `paralelo::ordenar_canonico`).
+pub fn ordenar_por_progreso(
+    soluciones: Soluciones,
+    ramos: &HashMap<String, RamoDisponible>,
+    student_ranking: Option<f64>,
+) -> Soluciones {
+    let mut con_valor: Vec<(f64, _)> = soluciones.into_iter()
+        .map(|sol| (progreso_esperado(sol.0.iter().map(|(s, _)| s), ramos, student_ranking).creditos_esperados, sol))
+        .collect();
+    con_valor.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.1.cmp(&a.1.1))
+        .then_with(|| clave_solucion(&a.1.0).cmp(&clave_solucion(&b.1.0))));
+    con_valor.into_iter().map(|(_, sol)| sol).collect()
+}
@@ -0,0 +1,167 @@
+// progreso_titulacion.rs - Avance hacia la titulación (`POST /progress`)
+//
+// A partir de la malla completa y de `ramos_pasados` (ya mapeados con
+// equivalencias) resume:
+//   - por semestre curricular: ramos y créditos SCT aprobados sobre el total
+//     (`creditos` de la malla o `CREDITOS_SCT_POR_DEFECTO`);
+//   - ruta crítica: ramos con holgura 0 en la red PERT de la malla completa
+//     (`pert::red_pert_malla`) y cuántos de ellos están aprobados;
+//   - pendientes: obligatorios de la malla, más los electivos y CFG que aún
+//     exigen las reglas de la malla (`reglas`);
+//   - semestres restantes: la cadena de prerequisitos más larga entre los
+//     pendientes (EF máximo de su red PERT, como en `reprobacion`) y, como
+//     cota por carga, los pendientes repartidos en `max_ramos` por semestre.
+//     La proyección es el mayor de ambos; sigue siendo una cota inferior
+//     porque supone que cada ramo se dicta todos los semestres.
+
+use std::collections::{BTreeMap, HashMap};
+use serde::Serialize;
+use crate::algorithm::clique::CREDITOS_SCT_POR_DEFECTO;
+use crate::algorithm::pert::red_pert_malla;
+use crate::algorithm::reprobacion::{red_pendientes, semestres_restantes};
+use crate::algorithm::aprobados::Aprobados;
+use crate::models::RamoDisponible;
+use crate::reglas::{self, ReglasMalla};
+
+/// Avance de un semestre curricular de la malla
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgresoSemestre {
+    /// `None` agrupa los ramos sin semestre en la malla
+    pub semestre: Option<i32>,
+    pub ramos: usize,
+    pub ramos_aprobados: usize,
+    pub creditos: i32,
+    pub creditos_aprobados: i32,
+    /// Créditos aprobados sobre los del semestre (0-100)
+    pub porcentaje: f64,
+}
+
+/// Ramos de la ruta crítica de la malla completa
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgresoRutaCritica {
+    pub ramos: usize,
+    pub aprobados: usize,
+    pub porcentaje: f64,
+    /// Códigos de los ramos críticos aún no aprobados, en orden de ES
+    pub pendientes: Vec<String>,
+}
+
+/// Lo que falta para titularse
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Pendientes {
+    /// Ramos de la malla, no electivos ni CFG, sin aprobar
+    pub obligatorios: usize,
+    /// Electivos que aún exige la malla (`max_electivos` menos los aprobados)
+    pub electivos: usize,
+    /// CFG que aún exige la malla (`max_cfg` menos los aprobados)
+    pub cfg: usize,
+}
+
+/// Resultado de `calcular_progreso`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgresoTitulacion {
+    pub ramos: usize,
+    pub ramos_aprobados: usize,
+    pub creditos: i32,
+    pub creditos_aprobados: i32,
+    /// Créditos aprobados sobre los de la malla (0-100)
+    pub porcentaje: f64,
+    /// Ordenados por semestre; los ramos sin semestre van al final
+    pub por_semestre: Vec<ProgresoSemestre>,
+    pub ruta_critica: ProgresoRutaCritica,
+    pub pendientes: Pendientes,
+    /// Largo de la cadena de prerequisitos más larga entre los pendientes
+    pub semestres_cadena_critica: i32,
+    /// Semestres para cursar todos los pendientes con `max_ramos` por semestre
+    pub semestres_por_carga: i32,
+    /// Proyección mínima: el mayor de los dos anteriores
+    pub semestres_restantes_minimos: i32,
+}
+
+fn porcentaje(parte: i32, total: i32) -> f64 {
+    if total <= 0 {
+        return 0.0;
+    }
+    // Redondeo a centésimas
+    (parte as f64 * 10000.0 / total as f64).round() / 100.0
+}
+
+fn es_cfg(ramo: &RamoDisponible) -> bool {
+    ramo.codigo.trim().to_uppercase().starts_with("CFG")
+}
+
+/// Calcula el avance de un estudiante con `ramos_pasados` (mapeados con las
+/// equivalencias de la malla) sobre `malla`. `max_ramos` es el tope de ramos
+/// por semestre para la cota por carga.
+pub fn calcular_progreso(
+    malla: &HashMap<String, RamoDisponible>,
+    ramos_pasados: &[String],
+    reglas_malla: &ReglasMalla,
+    max_ramos: usize,
+) -> ProgresoTitulacion {
+    let aprobados = Aprobados::new(ramos_pasados);
+    let aprobado = |r: &RamoDisponible| aprobados.contiene(r);
+
+    // Semestre -> (ramos, aprobados, créditos, créditos aprobados); None al final
+    let mut semestres: BTreeMap<(bool, i32), (usize, usize, i32, i32)> = BTreeMap::new();
+    for ramo in malla.values() {
+        let creditos = ramo.creditos.unwrap_or(CREDITOS_SCT_POR_DEFECTO);
+        let fila = semestres.entry((ramo.semestre.is_none(), ramo.semestre.unwrap_or(0))).or_default();
+        fila.0 += 1;
+        fila.2 += creditos;
+        if aprobado(ramo) {
+            fila.1 += 1;
+            fila.3 += creditos;
+        }
+    }
+    let por_semestre: Vec<ProgresoSemestre> = semestres.into_iter()
+        .map(|((sin_semestre, semestre), (ramos, aprobados, creditos, creditos_aprobados))| ProgresoSemestre {
+            semestre: (!sin_semestre).then_some(semestre),
+            ramos,
+            ramos_aprobados: aprobados,
+            creditos,
+            creditos_aprobados,
+            porcentaje: porcentaje(creditos_aprobados, creditos),
+        })
+        .collect();
+
+    let por_id: HashMap<i32, &RamoDisponible> = malla.values().map(|r| (r.id, r)).collect();
+    let red = red_pert_malla(malla, None);
+    let criticos: Vec<&RamoDisponible> = red.nodos.iter()
+        .filter(|n| n.critico)
+        .filter_map(|n| por_id.get(&n.id).copied())
+        .collect();
+    let criticos_aprobados = criticos.iter().filter(|r| aprobado(r)).count();
+    let ruta_critica = ProgresoRutaCritica {
+        ramos: criticos.len(),
+        aprobados: criticos_aprobados,
+        porcentaje: porcentaje(criticos_aprobados as i32, criticos.len() as i32),
+        pendientes: criticos.iter().filter(|r| !aprobado(r)).map(|r| r.codigo.clone()).collect(),
+    };
+
+    let pendientes = Pendientes {
+        obligatorios: malla.values().filter(|r| !r.electivo && !es_cfg(r) && !aprobado(r)).count(),
+        electivos: reglas_malla.max_electivos.saturating_sub(reglas::electivos_aprobados(ramos_pasados, malla)),
+        cfg: reglas_malla.max_cfg.saturating_sub(reglas::cfgs_aprobados(ramos_pasados)),
+    };
+    let total_pendientes = pendientes.obligatorios + pendientes.electivos + pendientes.cfg;
+    let semestres_cadena_critica = semestres_restantes(&red_pendientes(malla, ramos_pasados));
+    let semestres_por_carga = total_pendientes.div_ceil(max_ramos.max(1)) as i32;
+
+    let ramos_aprobados = por_semestre.iter().map(|s| s.ramos_aprobados).sum();
+    let creditos: i32 = por_semestre.iter().map(|s| s.creditos).sum();
+    let creditos_aprobados: i32 = por_semestre.iter().map(|s| s.creditos_aprobados).sum();
+    ProgresoTitulacion {
+        ramos: malla.len(),
+        ramos_aprobados,
+        creditos,
+        creditos_aprobados,
+        porcentaje: porcentaje(creditos_aprobados, creditos),
+        por_semestre,
+        ruta_critica,
+        pendientes,
+        semestres_cadena_critica,
+        semestres_por_carga,
+        semestres_restantes_minimos: semestres_cadena_critica.max(semestres_por_carga),
+    }
+}
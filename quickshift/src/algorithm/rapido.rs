@@ -0,0 +1,59 @@
+// rapido.rs - Modo rápido del solver (`solver`/`modo: "rapido"`)
+//
+// Para clientes interactivos que prefieren una respuesta en medio segundo a
+// la mejor posible: solo corre el greedy multi-seed de `clique.rs` (sin el
+// respaldo exhaustivo) con un deadline igual a la latencia objetivo, y la
+// respuesta de `/solve` agrega una estimación de calidad. El gap se calcula
+// contra `clique::cota_optimista`, que el greedy registra en el `Deadline`:
+// como la cota es optimista, el gap real es a lo más el informado.
+
+use serde::Serialize;
+use crate::algorithm::deadline::Deadline;
+use crate::api_json::InputParams;
+
+/// Latencia objetivo por defecto del modo rápido
+pub const LATENCIA_OBJETIVO_MS: u64 = 500;
+
+/// Latencia objetivo de una petición: `timeout_ms`, luego `rapido_latencia_ms`
+/// de la configuración (`RAPIDO_LATENCIA_MS`, por defecto 500 ms)
+pub fn latencia_objetivo_ms(params: &InputParams) -> u64 {
+    params.timeout_ms.unwrap_or_else(|| crate::app_config::actual().rapido_latencia_ms)
+}
+
+/// Calidad estimada de una respuesta en modo rápido
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EstimacionCalidad {
+    pub latencia_objetivo_ms: u64,
+    pub elapsed_ms: u64,
+    /// `elapsed_ms <= latencia_objetivo_ms`
+    pub dentro_de_sla: bool,
+    /// Score de la mejor solución encontrada
+    pub mejor_score: Option<i64>,
+    /// Ningún horario puede superar este score
+    pub cota_optimista: Option<i64>,
+    /// (cota - mejor) / cota, entre 0 y 1; 0 indica que la mejor es óptima
+    pub gap_estimado: Option<f64>,
+}
+
+/// Gap relativo entre `mejor` y la `cota` (None si la cota no es positiva)
+pub fn gap_estimado(mejor: i64, cota: i64) -> Option<f64> {
+    if cota <= 0 {
+        return None;
+    }
+    Some(((cota - mejor) as f64 / cota as f64).clamp(0.0, 1.0))
+}
+
+/// Estimación para las `soluciones` de una búsqueda que usó `deadline`
+pub fn estimar(soluciones: &[(Vec<(crate::models::Seccion, i32)>, i64)], params: &InputParams, deadline: &Deadline, elapsed_ms: u64) -> EstimacionCalidad {
+    let latencia_objetivo_ms = latencia_objetivo_ms(params);
+    let mejor_score = soluciones.iter().filter(|(sol, _)| !sol.is_empty()).map(|(_, score)| *score).max();
+    let cota_optimista = deadline.progreso().cota_optimista;
+    EstimacionCalidad {
+        latencia_objetivo_ms,
+        elapsed_ms,
+        dentro_de_sla: elapsed_ms <= latencia_objetivo_ms,
+        mejor_score,
+        cota_optimista,
+        gap_estimado: mejor_score.zip(cota_optimista).and_then(|(m, c)| gap_estimado(m, c)),
+    }
+}
@@ -0,0 +1,130 @@
+// reprobacion.rs - Simulación "¿qué pasa si repruebo?"
+//
+// Compara dos redes PERT de los ramos pendientes (`pert::red_pert_malla`):
+// - escenario base: el ramo en curso se aprueba (se suma a `ramos_pasados`);
+// - escenario reprobado: el ramo queda pendiente y se retoma el próximo semestre.
+// ES de cada nodo es el semestre (1 = próximo) en que el ramo podría tomarse
+// como muy pronto. Un ramo se retrasa si su ES crece de un escenario a otro;
+// los semestres extra son la diferencia entre los EF máximos (largo de la
+// carrera restante). Igual que PERT, supone que cada ramo se dicta todos los
+// semestres y no limita la carga por semestre: el retraso es una cota inferior.
+
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use crate::algorithm::pert::{red_pert_malla, RedPert};
+use crate::excel::normalize_name;
+use crate::algorithm::aprobados::Aprobados;
+use crate::models::RamoDisponible;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RamoRetrasado {
+    pub codigo: String,
+    pub nombre: String,
+    /// Semestre más temprano (1 = próximo) si se aprueba el ramo en curso
+    pub semestre_base: i32,
+    /// Semestre más temprano tras reprobarlo
+    pub semestre_reprobado: i32,
+    pub retraso: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulacionReprobacion {
+    pub reprobado: String,
+    pub nombre: String,
+    /// Semestres que faltan para completar la malla en cada escenario
+    pub semestres_restantes_base: i32,
+    pub semestres_restantes_reprobado: i32,
+    pub semestres_extra: i32,
+    /// Ramos que dependen (directa o indirectamente) del reprobado y se atrasan, por retraso descendente
+    pub retrasados: Vec<RamoRetrasado>,
+    pub ruta_critica_base: Vec<String>,
+    pub ruta_critica: Vec<String>,
+}
+
+/// Red PERT de los ramos de `malla` no aprobados en `ramos_pasados`
+pub(crate) fn red_pendientes(malla: &HashMap<String, RamoDisponible>, ramos_pasados: &[String]) -> RedPert {
+    let aprobados = Aprobados::new(ramos_pasados);
+    let pendientes: HashMap<String, RamoDisponible> = malla.iter()
+        .filter(|(_, r)| !aprobados.contiene(r))
+        .map(|(k, r)| (k.clone(), r.clone()))
+        .collect();
+    red_pert_malla(&pendientes, None)
+}
+
+/// Semestres que faltan: EF máximo menos 1 (EF = ES + 1, ES = 1 es el próximo semestre)
+pub(crate) fn semestres_restantes(red: &RedPert) -> i32 {
+    red.nodos.iter().map(|n| n.ef - 1).max().unwrap_or(0)
+}
+
+/// Simula reprobar `reprobado` (código o nombre) partiendo de `ramos_pasados`.
+/// `ramos_pasados` debe venir ya mapeado con equivalencias; si incluye al
+/// reprobado, se quita.
+pub fn simular_reprobacion(
+    malla: &HashMap<String, RamoDisponible>,
+    ramos_pasados: &[String],
+    reprobado: &str,
+) -> Result<SimulacionReprobacion, String> {
+    let codigo = reprobado.trim().to_uppercase();
+    let nombre_norm = normalize_name(reprobado);
+    let ramo = malla.values().find(|r| !r.codigo.trim().is_empty() && r.codigo.trim().to_uppercase() == codigo)
+        .or_else(|| malla.values().find(|r| normalize_name(&r.nombre) == nombre_norm))
+        .ok_or_else(|| format!("el ramo '{}' no está en la malla", reprobado))?;
+    let es_reprobado = |s: &String| {
+        (!ramo.codigo.trim().is_empty() && s.trim().eq_ignore_ascii_case(ramo.codigo.trim()))
+            || normalize_name(s) == normalize_name(&ramo.nombre)
+    };
+
+    let pasados_reprobado: Vec<String> = ramos_pasados.iter().filter(|s| !es_reprobado(s)).cloned().collect();
+    let pasados_base: Vec<String> = pasados_reprobado.iter().cloned()
+        .chain(std::iter::once(if ramo.codigo.trim().is_empty() { ramo.nombre.clone() } else { ramo.codigo.clone() }))
+        .collect();
+
+    let base = red_pendientes(malla, &pasados_base);
+    let con_reprobado = red_pendientes(malla, &pasados_reprobado);
+
+    // Descendientes del reprobado en la red reprobada (solo ellos pueden atrasarse por su causa)
+    let mut sucesores: HashMap<i32, Vec<i32>> = HashMap::new();
+    for n in con_reprobado.nodos.iter() {
+        for p in n.predecesores.iter() {
+            sucesores.entry(*p).or_default().push(n.id);
+        }
+    }
+    let mut descendientes: HashSet<i32> = HashSet::new();
+    let mut pila: Vec<i32> = sucesores.get(&ramo.id).cloned().unwrap_or_default();
+    while let Some(v) = pila.pop() {
+        if descendientes.insert(v) {
+            pila.extend(sucesores.get(&v).into_iter().flatten().copied());
+        }
+    }
+
+    let es_base: HashMap<i32, i32> = base.nodos.iter().map(|n| (n.id, n.es)).collect();
+    let mut retrasados: Vec<RamoRetrasado> = con_reprobado.nodos.iter()
+        .filter(|n| descendientes.contains(&n.id))
+        .filter_map(|n| {
+            let antes = *es_base.get(&n.id)?;
+            (n.es > antes).then(|| RamoRetrasado {
+                codigo: n.codigo.clone(),
+                nombre: n.nombre.clone(),
+                semestre_base: antes,
+                semestre_reprobado: n.es,
+                retraso: n.es - antes,
+            })
+        })
+        .collect();
+    retrasados.sort_by(|a, b| b.retraso.cmp(&a.retraso)
+        .then(a.semestre_reprobado.cmp(&b.semestre_reprobado))
+        .then(a.codigo.cmp(&b.codigo)));
+
+    let restantes_base = semestres_restantes(&base);
+    let restantes_reprobado = semestres_restantes(&con_reprobado);
+    Ok(SimulacionReprobacion {
+        reprobado: ramo.codigo.clone(),
+        nombre: ramo.nombre.clone(),
+        semestres_restantes_base: restantes_base,
+        semestres_restantes_reprobado: restantes_reprobado,
+        semestres_extra: (restantes_reprobado - restantes_base).max(0),
+        retrasados,
+        ruta_critica_base: base.ruta_critica,
+        ruta_critica: con_reprobado.ruta_critica,
+    })
+}
@@ -28,113 +28,268 @@ use crate::models::{Seccion, RamoDisponible};
 // Nuevo import para comprobar solapamiento contra bloques prohibidos
 use crate::algorithm::filters::solapan_horarios;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use crate::algorithm::clique::CompatibilityGraph;
+use crate::algorithm::deadline::Deadline;
+use crate::algorithm::engine::SchedulePlanner;
+use crate::algorithm::paralelo::Soluciones;
+use crate::algorithm::doble_titulacion::{self, ProgresoPrograma};
+use crate::algorithm::fijas;
+use crate::algorithm::carga_diaria;
+use crate::algorithm::obligatorios;
+use crate::algorithm::diagnostico_grafo::{self, DiagnosticoGrafo};
 
+/// Datos ya cargados y procesados por las fases 0-2b (lectura de Excel, PERT,
+/// marcado de electivos/CFG). Dependen solo de la malla y de `ramos_pasados`,
+/// por lo que pueden reutilizarse cuando el usuario cambia filtros o scoring.
+#[derive(Debug, Clone)]
+pub struct DatosRuta {
+    pub malla_path: String,
+    pub ramos_disponibles: HashMap<String, RamoDisponible>,
+    pub lista_secciones: Vec<Seccion>,
+    /// Avance por programa (solo con `mallas_adicionales`)
+    pub progreso: Vec<ProgresoPrograma>,
+    /// Códigos (o nombres) de ramos que cuentan para más de un programa
+    pub compartidos: Vec<String>,
+}
+
+impl DatosRuta {
+    /// Construye el grafo de compatibilidad sobre todas las secciones cargadas
+    pub fn build_compatibility_graph(&self) -> CompatibilityGraph {
+        CompatibilityGraph::build(&self.lista_secciones)
+    }
+}
+
+/// Atajo de `engine::Datafiles.planificar(params)`
 pub fn ejecutar_ruta_critica_with_params(
-    mut params: InputParams,
+    params: InputParams,
 ) -> Result<Vec<(Vec<(Seccion, i32)>, i64)>, Box<dyn Error>> {
-    eprintln!("🔁 [ruta::ejecutar_ruta_critica_with_params] iniciando pipeline de 4 fases...");
+    tracing::info!("🔁 [ruta::ejecutar_ruta_critica_with_params] iniciando pipeline de 4 fases...");
+    crate::algorithm::engine::Datafiles.planificar(params)
+}
 
-    // =========================================================================
-    // PHASE 0: Mapear códigos de ramos aprobados usando equivalencias
-    // =========================================================================
-    // Cargar equivalencias y mapear ramos_pasados
-    let (malla_pathbuf, oferta_pathbuf, porcentajes_pathbuf) = 
-        crate::excel::resolve_datafile_paths(&params.malla)?;
-    let malla_str = malla_pathbuf.to_string_lossy().to_string();
-    
-    match crate::excel::cargar_equivalencias(&malla_str) {
-        Ok(equivalencias) => {
-            if !equivalencias.is_empty() {
-                eprintln!("📋 PHASE 0: Mapeando ramos aprobados con equivalencias");
-                eprintln!("   ✓ {} equivalencias cargadas", equivalencias.len());
-                params.ramos_pasados = crate::excel::aplicar_equivalencias(&params.ramos_pasados, &equivalencias);
-                eprintln!("   ✓ Ramos pasados mapeados a códigos de malla actual");
-            }
-        }
-        Err(e) => {
-            eprintln!("   ⚠️  No se pudieron cargar equivalencias: {}", e);
+/// `ramos_pasados` llevados a los códigos de la malla `ramos` según la tabla
+/// de equivalencias (ver `algorithm::equivalencias`). Los ambiguos se dejan
+/// como estaban.
+pub fn aplicar_tabla_equivalencias(
+    ramos_pasados: &[String],
+    tabla: &[(String, String)],
+    ramos: &HashMap<String, RamoDisponible>,
+) -> Vec<String> {
+    let codigos_malla: HashSet<String> = ramos.values()
+        .filter(|r| !r.codigo.trim().is_empty())
+        .map(|r| r.codigo.trim().to_uppercase())
+        .collect();
+    let res = crate::algorithm::equivalencias::resolver(ramos_pasados, tabla, &codigos_malla);
+    for c in res.cambios() {
+        match (&c.destino, c.candidatos.is_empty()) {
+            (Some(d), _) if d != &c.codigo => tracing::debug!("   ✓ {} -> {} ({:?})", c.codigo, d, c.estado),
+            (_, false) => tracing::warn!("   ⚠️  equivalencia ambigua para {}: {:?}; se deja como está", c.codigo, c.candidatos),
+            _ => {}
         }
     }
+    res.ramos_pasados
+}
 
+/// PHASES 0-2b: equivalencias, lectura de malla/oferta/CFG, electivos y PERT.
+///
+/// Modifica `params.ramos_pasados` aplicando equivalencias, igual que el
+/// pipeline completo.
+pub fn preparar_datos_ruta(params: &mut InputParams) -> Result<DatosRuta, Box<dyn Error>> {
+    // Archivos de la malla (o de la carrera), oferta y porcentajes
+    let (malla_pathbuf, oferta_pathbuf, porcentajes_pathbuf) = 
+        crate::carreras::resolver_archivos(params)?;
+    let malla_str = malla_pathbuf.to_string_lossy().to_string();
+    
     // =========================================================================
     // PHASE 1: getRamoCritico + PERT
     // =========================================================================
-    eprintln!("📋 PHASE 1: getRamoCritico + PERT");
+    tracing::info!("📋 PHASE 1: getRamoCritico + PERT");
     
     // 1a) Resolver paths de datafiles (ya hecho arriba, reutilizar)
     let oferta_str = oferta_pathbuf.to_string_lossy().to_string();
     let porcentajes_str = porcentajes_pathbuf.to_string_lossy().to_string();
 
-    eprintln!("   malla_path = {}", malla_str);
-    eprintln!("   oferta_path = {}", oferta_str);
-    eprintln!("   porcentajes_path = {}", porcentajes_str);
+    tracing::debug!("   malla_path = {}", malla_str);
+    tracing::debug!("   oferta_path = {}", oferta_str);
+    tracing::debug!("   porcentajes_path = {}", porcentajes_str);
     
     // 1b) Leer malla + porcentajes -> HashMap<String, RamoDisponible>
-    eprintln!("   📥 Leyendo malla y porcentajes...");
-    let mut ramos_disponibles: HashMap<String, RamoDisponible> = 
-        if malla_str.to_uppercase().contains("MC") {
-            // Usar parser especial para MC (Malla Curricular)
-            eprintln!("   🔍 Detectado MC - usando parser especial");
-            crate::excel::leer_mc_con_porcentajes_optimizado(&malla_str, &porcentajes_str)?
-        } else {
-            // Usar parser estándar para Malla2020 / MiMalla
-            crate::excel::malla_optimizado::leer_malla_con_porcentajes_optimizado(&malla_str, &porcentajes_str)?
-        };
-    eprintln!("   ✓ ramos cargados: {}", ramos_disponibles.len());
+    tracing::debug!("   📥 Leyendo malla y porcentajes...");
+    let mut ramos_disponibles = cargar_ramos_malla(&malla_str, &porcentajes_str)?;
+    tracing::debug!("   ✓ ramos cargados: {}", ramos_disponibles.len());
+
+    // PHASE 0 (necesita los códigos de la malla): los ramos aprobados de una
+    // malla anterior se llevan a la vigente, siguiendo equivalencias encadenadas
+    match crate::excel::leer_tabla_equivalencias(&malla_str) {
+        Ok(tabla) if !tabla.is_empty() => {
+            tracing::info!("📋 PHASE 0: Mapeando ramos aprobados con equivalencias");
+            params.ramos_pasados = aplicar_tabla_equivalencias(&params.ramos_pasados, &tabla, &ramos_disponibles);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!("   ⚠️  No se pudieron cargar equivalencias: {}", e);
+        }
+    }
+
+    // 1b.2) Doble titulación: fusionar mallas adicionales en un único grafo
+    let mut progreso: Vec<ProgresoPrograma> = Vec::new();
+    let mut compartidos: Vec<String> = Vec::new();
+    let mut ofertas_adicionales: Vec<String> = Vec::new();
+    if !params.mallas_adicionales.is_empty() {
+        tracing::debug!("   🎓 Doble titulación: fusionando {} malla(s) adicional(es)", params.mallas_adicionales.len());
+        let claves_principal: HashSet<String> = ramos_disponibles.keys().cloned().collect();
+        let mut programas: Vec<(String, HashSet<String>)> = vec![(params.malla.clone(), claves_principal)];
+        // Cada programa ve los aprobados traducidos solo con las equivalencias de su
+        // propia malla (sobre una copia); la principal conserva sus códigos
+        let mut pasados_por_programa: Vec<Vec<String>> = vec![params.ramos_pasados.clone()];
+        let mut todas_compartidas: HashSet<String> = HashSet::new();
+        for (i, extra) in params.mallas_adicionales.clone().iter().enumerate() {
+            let (extra_path, extra_oferta, _) = crate::excel::resolve_datafile_paths(extra)?;
+            let extra_str = extra_path.to_string_lossy().to_string();
+            let extra_ramos = cargar_ramos_malla(&extra_str, &porcentajes_str)?;
+            let equivalencias = crate::excel::cargar_equivalencias(&extra_str).unwrap_or_default();
+            let pasados_extra = if equivalencias.is_empty() {
+                params.ramos_pasados.clone()
+            } else {
+                crate::excel::aplicar_equivalencias(&params.ramos_pasados, &equivalencias)
+            };
+            let offset = doble_titulacion::ID_OFFSET_POR_MALLA * (i as i32 + 1);
+            let (claves, compartidas) = doble_titulacion::fusionar_malla(&mut ramos_disponibles, extra_ramos, &equivalencias, offset);
+            tracing::debug!("   ✓ {}: {} ramos ({} compartidos)", extra, claves.len(), compartidas.len());
+            todas_compartidas.extend(compartidas);
+            programas.push((extra.clone(), claves));
+            pasados_por_programa.push(pasados_extra);
+            let extra_oferta = extra_oferta.to_string_lossy().to_string();
+            if extra_oferta != oferta_str && !ofertas_adicionales.contains(&extra_oferta) {
+                ofertas_adicionales.push(extra_oferta);
+            }
+        }
+        for ((malla, claves), pasados) in programas.iter().zip(pasados_por_programa.iter()) {
+            progreso.push(doble_titulacion::progreso_programa(malla, claves, &ramos_disponibles, pasados, &todas_compartidas));
+        }
+        // El grafo fusionado se poda con la unión: un ramo de la malla adicional
+        // cuyo prerequisito se aprobó con otro código sigue siendo viable
+        params.ramos_pasados = doble_titulacion::unir_aprobados(&pasados_por_programa);
+        compartidos = todas_compartidas.iter()
+            .filter_map(|k| ramos_disponibles.get(k))
+            .map(|r| if r.codigo.is_empty() { r.nombre.clone() } else { r.codigo.clone() })
+            .collect();
+        compartidos.sort();
+    }
     
     // 1c) PODADO DETERMINISTA: Filtrar ramos cuyo satisfacción de prerequisitos es imposible
     // REGLA DURA: Un ramo solo es viable si TODOS sus prerequisites están en ramos_pasados
-    eprintln!("   🔪 PODADO: Filtrando ramos inviables (prerequisitos no satisfacibles)");
+    tracing::debug!("   🔪 PODADO: Filtrando ramos inviables (prerequisitos no satisfacibles)");
     let ramos_viable_map = crate::algorithm::pert::build_viable_ramos(&ramos_disponibles, &params.ramos_pasados);
     ramos_disponibles = ramos_viable_map.into_iter().collect();
     
     // =========================================================================
     // PHASE 2: extract_viable_sections
     // =========================================================================
-    eprintln!("📋 PHASE 2: extract_viable_sections");
+    tracing::info!("📋 PHASE 2: extract_viable_sections");
     // DEBUG: mostrar filtros y franjas recibidas para diagnóstico
-    eprintln!("   [DEBUG] params.filtros={:?}", params.filtros);
-    eprintln!("   [DEBUG] params.horarios_prohibidos={:?}", params.horarios_prohibidos);
+    tracing::debug!("params.filtros={:?}", params.filtros);
+    tracing::debug!("params.horarios_prohibidos={:?}", params.horarios_prohibidos);
     
     // 2a) Leer oferta académica -> Vec<Seccion>
-    eprintln!("   📥 Leyendo oferta académica...");
+    tracing::debug!("   📥 Leyendo oferta académica...");
     let mut lista_secciones: Vec<Seccion> = 
-        crate::excel::leer_oferta_academica_excel(&oferta_str)?;
+        crate::datafiles::cache::oferta(&oferta_str)?.as_ref().clone();
+
+    // 2a.a) Doble titulación: oferta de cada programa adicional (sin repetir secciones)
+    if !ofertas_adicionales.is_empty() {
+        let clave = |s: &Seccion| if s.codigo_box.trim().is_empty() {
+            format!("{}|{}", s.codigo.trim().to_uppercase(), s.seccion.trim())
+        } else {
+            s.codigo_box.trim().to_string()
+        };
+        let mut vistas: HashSet<String> = lista_secciones.iter().map(clave).collect();
+        for extra_oferta in &ofertas_adicionales {
+            let secciones = crate::datafiles::cache::oferta(extra_oferta)?;
+            let antes = lista_secciones.len();
+            lista_secciones.extend(secciones.iter().filter(|s| vistas.insert(clave(s))).cloned());
+            tracing::debug!("   ✓ oferta adicional {}: {} secciones nuevas", extra_oferta, lista_secciones.len() - antes);
+        }
+    }
 
     // 2a.b) Intentar leer archivo CFG (si existe) y añadir sus secciones
-    if let Some(cfg_pathbuf) = crate::excel::latest_file_for_keywords(&["cfg"]) {
-        if let Some(cfg_str) = cfg_pathbuf.to_str() {
-            match crate::excel::leer_oferta_academica_excel(cfg_str) {
-                Ok(cfg_secs) => {
-                    eprintln!("   DEBUG: CFG cargado: {} secciones desde {}", cfg_secs.len(), cfg_str);
-                    for mut s in cfg_secs.into_iter() {
-                        // Regla especial: "Inglés I" pertenece a "Inglés 1" y NO se considera CFG
-                        let name_norm = crate::excel::normalize_name(&s.nombre);
-                        if name_norm == crate::excel::normalize_name("Inglés I") || name_norm == crate::excel::normalize_name("Ingles I") {
-                            s.nombre = "Inglés 1".to_string();
-                            s.is_cfg = false;
-                        } else {
-                            s.is_cfg = true;
-                        }
-                        lista_secciones.push(s);
+    if let Some(cfg_pathbuf) = crate::carreras::archivo_cfg(params)
+        && let Some(cfg_str) = cfg_pathbuf.to_str()
+    {
+        match crate::datafiles::cache::oferta(cfg_str) {
+            Ok(cfg_secs) => {
+                tracing::debug!("   DEBUG: CFG cargado: {} secciones desde {}", cfg_secs.len(), cfg_str);
+                for mut s in cfg_secs.iter().cloned() {
+                    // Regla especial: "Inglés I" pertenece a "Inglés 1" y NO se considera CFG
+                    let name_norm = crate::excel::normalize_name(&s.nombre);
+                    if name_norm == crate::excel::normalize_name("Inglés I") || name_norm == crate::excel::normalize_name("Ingles I") {
+                        s.nombre = "Inglés 1".to_string();
+                        s.is_cfg = false;
+                    } else {
+                        s.is_cfg = true;
                     }
+                    lista_secciones.push(s);
                 }
-                Err(e) => {
-                    eprintln!("   WARN: no se pudo leer CFG '{}': {}", cfg_str, e);
-                }
+            }
+            Err(e) => {
+                tracing::warn!("   no se pudo leer CFG '{}': {}", cfg_str, e);
             }
         }
     }
-    eprintln!("   ✓ secciones cargadas: {}", lista_secciones.len());
+    tracing::debug!("   ✓ secciones cargadas: {}", lista_secciones.len());
     
     // 2a.c) Marcar electivos: cursos que están en oferta pero NO en la malla
-    eprintln!("   🎓 Identificando electivos de especialización...");
-    let codigos_en_malla: std::collections::HashSet<String> = ramos_disponibles
+    tracing::debug!("   🎓 Identificando electivos de especialización...");
+    let electivos_count = marcar_electivos(&mut lista_secciones, &ramos_disponibles);
+    
+    tracing::debug!("   ✓ Electivos identificados: {} secciones de electivos de especialización", electivos_count);
+    let descartados = crate::carreras::filtrar_electivos(params, &mut lista_secciones);
+    if descartados > 0 {
+        tracing::debug!("   ✓ {} secciones de electivos descartadas por no pertenecer a la carrera", descartados);
+    }
+    let catalogo = cargar_catalogo_electivos(&malla_str);
+    if !catalogo.is_empty() {
+        let con_linea = catalogo.etiquetar(&mut lista_secciones);
+        tracing::debug!("   ✓ Electivos con línea de formación: {}", con_linea);
+    }
+    if let Some(calendario) = cargar_calendario_evaluaciones() {
+        let con_evaluaciones = calendario.asignar(&mut lista_secciones);
+        tracing::debug!("   ✓ Secciones con calendario de evaluaciones: {}", con_evaluaciones);
+    }
+    
+    // 2b) Ejecutar PERT ANTES de filtrar secciones
+    // (porque necesitamos critico/holgura/numb_correlativo propagados)
+    tracing::debug!("   🧭 Ejecutando PERT (primera pasada)...");
+    if let Err(e) = crate::algorithm::pert::build_and_run_pert(
+        &mut ramos_disponibles, 
+        &lista_secciones, 
+        &malla_str
+    ) {
+        tracing::warn!("   ⚠️  PERT aviso: {:?}", e);
+    } else {
+        tracing::debug!("   ✓ PERT completado: ramos actualizados (critico/holgura)");
+    }
+    
+    Ok(DatosRuta {
+        malla_path: malla_str,
+        ramos_disponibles,
+        lista_secciones,
+        progreso,
+        compartidos,
+    })
+}
+
+/// Marca como electivo de especialización toda sección (no CFG) cuyo ramo está
+/// en la oferta pero NO en la malla, ya sea por código o por nombre normalizado.
+/// Devuelve la cantidad de secciones marcadas.
+pub(crate) fn marcar_electivos(lista_secciones: &mut [Seccion], ramos_disponibles: &HashMap<String, RamoDisponible>) -> usize {
+    let codigos_en_malla: HashSet<String> = ramos_disponibles
         .values()
         .map(|r| crate::excel::normalize_name(&r.codigo))
         .collect();
     
-    let nombres_en_malla: std::collections::HashSet<String> = ramos_disponibles
+    let nombres_en_malla: HashSet<String> = ramos_disponibles
         .values()
         .map(|r| crate::excel::normalize_name(&r.nombre))
         .collect();
@@ -154,35 +309,97 @@ pub fn ejecutar_ruta_critica_with_params(
         let en_malla = codigos_en_malla.contains(&codigo_norm) || 
                        nombres_en_malla.contains(&nombre_norm);
         
+        sec.is_electivo = !en_malla;
         if !en_malla {
-            sec.is_electivo = true;
             electivos_count += 1;
-        } else {
-            sec.is_electivo = false;
         }
     }
-    
-    eprintln!("   ✓ Electivos identificados: {} secciones de electivos de especialización", electivos_count);
-    
-    // 2b) Ejecutar PERT ANTES de filtrar secciones
-    // (porque necesitamos critico/holgura/numb_correlativo propagados)
-    eprintln!("   🧭 Ejecutando PERT (primera pasada)...");
-    if let Err(e) = crate::algorithm::pert::build_and_run_pert(
-        &mut ramos_disponibles, 
-        &lista_secciones, 
-        &malla_str
-    ) {
-        eprintln!("   ⚠️  PERT aviso: {:?}", e);
+    electivos_count
+}
+
+/// Catálogo de electivos: hoja "Electivos" de la malla o, si no la tiene,
+/// el archivo de electivos más reciente del directorio de datos. Si no hay
+/// ninguno el catálogo queda vacío y los electivos no tienen línea.
+pub(crate) fn cargar_catalogo_electivos(malla_str: &str) -> Arc<crate::excel::electivos::CatalogoElectivos> {
+    let desde_malla = crate::datafiles::cache::electivos(malla_str).ok().filter(|c| !c.is_empty());
+    desde_malla
+        .or_else(|| {
+            let ruta = crate::excel::latest_file_for_keywords(&["electivo"])?;
+            crate::datafiles::cache::electivos(ruta.to_str()?)
+                .map_err(|e| tracing::warn!("   no se pudo leer electivos '{}': {}", ruta.display(), e))
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+/// Calendario de evaluaciones más reciente del directorio de datos, si hay
+/// uno (ver `excel::evaluaciones`)
+pub(crate) fn cargar_calendario_evaluaciones() -> Option<Arc<crate::excel::evaluaciones::CalendarioEvaluaciones>> {
+    let ruta = crate::excel::latest_file_for_keywords(&["evaluaci", "certamen", "calendario"])?;
+    crate::datafiles::cache::evaluaciones(ruta.to_str()?)
+        .map_err(|e| tracing::warn!("   no se pudo leer evaluaciones '{}': {}", ruta.display(), e))
+        .ok()
+        .filter(|c| !c.is_empty())
+}
+
+/// Lee una malla con sus porcentajes (vía caché; el parser se elige según el tipo de archivo)
+pub(crate) fn cargar_ramos_malla(malla_str: &str, porcentajes_str: &str) -> Result<HashMap<String, RamoDisponible>, Box<dyn Error>> {
+    Ok(crate::datafiles::cache::malla_con_porcentajes(malla_str, porcentajes_str)?.as_ref().clone())
+}
+
+/// Lo que recibe el clique después de la PHASE 2c
+struct EntradaClique {
+    /// Params con los ramos compartidos de doble titulación como prioritarios
+    /// y las secciones fijas por `codigo_box` (None si no hay ninguno)
+    params: Option<InputParams>,
+    viables: Vec<Seccion>,
+    fijas: Vec<Seccion>,
+}
+
+/// PHASE 2c: secciones que llegan al clique. Falla si las fijas o los ramos
+/// obligatorios no pueden ir juntos.
+fn secciones_para_clique(
+    datos: &DatosRuta,
+    params: &InputParams,
+    deadline: &Deadline,
+) -> Result<EntradaClique, Box<dyn Error>> {
+    let lista_secciones = &datos.lista_secciones;
+
+    // Secciones fijas: deben poder ir juntas antes de buscar alrededor de ellas
+    let fijas = if params.secciones_fijas.is_empty() {
+        Vec::new()
     } else {
-        eprintln!("   ✓ PERT completado: ramos actualizados (critico/holgura)");
-    }
-    
+        fijas::resolver(&params.secciones_fijas, lista_secciones, &params.ramos_pasados)?
+    };
+
+    // Doble titulación: los ramos compartidos se priorizan como ramos_prioritarios
+    let params_combinados = if datos.compartidos.is_empty() && fijas.is_empty() {
+        None
+    } else {
+        let mut p = params.clone();
+        for c in datos.compartidos.iter() {
+            if !p.ramos_prioritarios.iter().any(|r| r.eq_ignore_ascii_case(c)) {
+                p.ramos_prioritarios.push(c.clone());
+            }
+        }
+        if !datos.compartidos.is_empty() {
+            tracing::debug!("   🎓 {} ramos compartidos entre programas priorizados", datos.compartidos.len());
+        }
+        // El clique reconoce las fijas por `codigo_box`, también la de su paquete colapsado
+        if !fijas.is_empty() {
+            p.secciones_fijas = fijas::ids_busqueda(&fijas);
+            tracing::debug!("   📌 {} secciones fijas", fijas.len());
+        }
+        Some(p)
+    };
+    let params = params_combinados.as_ref().unwrap_or(params);
+
     // 2c) Filtrar secciones viables según reglas Python:
     // - Excluir ramos ya aprobados (ramos_pasados)
     // NOTA: La validación de requisitos previos se maneja en clique.rs través del cálculo de max_sem
     // PERO: La LEY FUNDAMENTAL se garantiza porque la universidad no diseña
     //       ramos incompatibles en el mismo semestre
-    eprintln!("   🔍 Filtrando secciones viables...");
+    tracing::debug!("   🔍 Filtrando secciones viables...");
     let passed_set: HashSet<String> = params.ramos_pasados
         .iter()
         .map(|s| s.to_uppercase())
@@ -190,89 +407,106 @@ pub fn ejecutar_ruta_critica_with_params(
     
     let lista_secciones_viables: Vec<Seccion> = lista_secciones
         .iter()
-        .filter(|sec| {
-            let sec_codigo_upper = sec.codigo.to_uppercase();
-
-            if passed_set.contains(&sec_codigo_upper) {
-                eprintln!("   ⊘ Excluyendo {} (ya aprobado)", sec.codigo);
-                return false;
-            }
-
-            // Excluir si solapa con cualquier bloque prohibido pasado por el usuario
-            if !params.horarios_prohibidos.is_empty() {
-                eprintln!("   [DEBUG] Comprobando solapamiento contra franjas_prohibidas: {:?}", params.horarios_prohibidos);
-                // sec.horario es Vec<String>
-                if solapan_horarios(&sec.horario, &params.horarios_prohibidos) {
-                    eprintln!("   ⊘ Excluyendo {} (solapa con franja prohibida)", sec.codigo);
-                    return false;
-                }
-            }
-
-            // Si existen filtros adicionales, aplicarlos aquí (ej: dias_horarios_libres estrictos)
-            if let Some(ref filtros) = params.filtros {
-                if let Some(ref dhl) = filtros.dias_horarios_libres {
-                    if let Some(ref dias) = dhl.dias_libres_preferidos {
-                        for dia_str in dias.iter() {
-                            let dia_code = dia_str.to_uppercase();
-                            for h in &sec.horario {
-                                let segs = crate::algorithm::filters::expand_horario_entry(h); // reusar parser público
-                                for (d, _s, _e) in segs.iter() {
-                                    if &dia_code == d {
-                                        eprintln!("   ⊘ Excluyendo {} (tiene clase en día que debe ser libre {})", sec.codigo, dia_code);
-                                        return false;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            true
-        })
+        .filter(|sec| seccion_viable(sec, params, &passed_set, &fijas))
         .cloned()
         .collect();
     
-    eprintln!("   ✓ secciones viables: {} (de {})", lista_secciones_viables.len(), 
+    tracing::debug!("   ✓ secciones viables: {} (de {})", lista_secciones_viables.len(), 
               lista_secciones.len());
-    
+    deadline.registrar_secciones_viables(lista_secciones_viables.len());
+
+    // Ramos obligatorios: deben poder ir juntos con las secciones que dejan los filtros
+    if !params.ramos_obligatorios.is_empty() {
+        let cumplen: Vec<Seccion> = lista_secciones_viables.iter()
+            .filter(|s| crate::algorithm::clique::seccion_cumple_filtros(s, &params.filtros))
+            .cloned()
+            .collect();
+        obligatorios::validar(
+            &params.ramos_obligatorios,
+            lista_secciones,
+            &cumplen,
+            &params.ramos_pasados,
+            crate::reglas::tope_ramos(params),
+        )?;
+    }
+
+    Ok(EntradaClique { params: params_combinados, viables: lista_secciones_viables, fijas })
+}
+
+/// Grafo de compatibilidad que recorrería `resolver_con_datos` con `params`,
+/// sin enumerar soluciones (`/solve?debug=graph`)
+pub fn diagnosticar_grafo(datos: &DatosRuta, params: &InputParams) -> Result<DiagnosticoGrafo, Box<dyn Error>> {
+    let entrada = secciones_para_clique(datos, params, &Deadline::sin_limite())?;
+    let params = entrada.params.as_ref().unwrap_or(params);
+    Ok(diagnostico_grafo::diagnosticar(&entrada.viables, &datos.ramos_disponibles, params))
+}
+
+/// PHASES 2c-4 sobre datos ya cargados: filtros del usuario, clique y
+/// selección final. Si se entrega `grafo`, el clique reutiliza sus aristas.
+pub fn resolver_con_datos(
+    datos: &DatosRuta,
+    params: &InputParams,
+    grafo: Option<&CompatibilityGraph>,
+) -> Result<Soluciones, Box<dyn Error>> {
+    resolver_con_datos_hasta(datos, params, grafo, &Deadline::sin_limite())
+}
+
+/// `resolver_con_datos` con límite de tiempo: al vencer `deadline` el clique
+/// se corta y se devuelven las mejores soluciones encontradas hasta entonces.
+/// `deadline.alcanzado()` indica después si el resultado es parcial.
+pub fn resolver_con_datos_hasta(
+    datos: &DatosRuta,
+    params: &InputParams,
+    grafo: Option<&CompatibilityGraph>,
+    deadline: &Deadline,
+) -> Result<Soluciones, Box<dyn Error>> {
+    let ramos_disponibles = &datos.ramos_disponibles;
+    let EntradaClique { params: params_combinados, viables: lista_secciones_viables, fijas } =
+        secciones_para_clique(datos, params, deadline)?;
+    let params = params_combinados.as_ref().unwrap_or(params);
+    if params.usar_historial {
+        crate::scoring::preparar_historial();
+    }
+
     // =========================================================================
     // PHASE 3: clique_search
     // =========================================================================
-    eprintln!("📋 PHASE 3: clique_search");
+    tracing::info!("📋 PHASE 3: clique_search");
     
     // VALIDACIÓN: Debe haber al menos algunas secciones viables
     if lista_secciones_viables.is_empty() {
-        eprintln!("❌ ERROR: No hay secciones viables después de filtrar");
-        eprintln!("   Posibles causas:");
-        eprintln!("   - Todos los cursos están en ramos_pasados");
-        eprintln!("   - El archivo de oferta académica está vacío");
-        eprintln!("   - Hay un problema en PHASE 2");
+        tracing::error!("❌ ERROR: No hay secciones viables después de filtrar");
+        tracing::error!("   Posibles causas:");
+        tracing::error!("   - Todos los cursos están en ramos_pasados");
+        tracing::error!("   - El archivo de oferta académica está vacío");
+        tracing::error!("   - Hay un problema en PHASE 2");
         return Ok(Vec::new());
     }
     
     // 3) Ejecutar búsqueda de cliques con preferencias del usuario
-    let soluciones = crate::algorithm::clique::get_clique_max_pond_with_prefs(
+    let soluciones = crate::algorithm::clique::get_clique_max_pond_with_prefs_cached(
         &lista_secciones_viables,
-        &ramos_disponibles,
-        &params,
+        ramos_disponibles,
+        params,
+        grafo,
+        deadline,
     );
     
     // Log del resultado del clique y guardar el count
     let soluciones_count = soluciones.len();
-    eprintln!("   ✓ clique search completado: {} soluciones antes de filtrar", soluciones_count);
+    tracing::debug!("   ✓ clique search completado: {} soluciones antes de filtrar", soluciones_count);
     
     // VALIDACIÓN: El clique debe generar al menos 1 solución si hay secciones viables
     if soluciones.is_empty() && !lista_secciones_viables.is_empty() {
-        eprintln!("⚠️  AVISO: El clique no generó soluciones a pesar de tener {} secciones viables", 
+        tracing::warn!("⚠️  AVISO: El clique no generó soluciones a pesar de tener {} secciones viables", 
                   lista_secciones_viables.len());
-        eprintln!("   Esto puede indicar que los cursos viables son incompatibles entre sí");
+        tracing::debug!("   Esto puede indicar que los cursos viables son incompatibles entre sí");
     }
     
     // =========================================================================
     // PHASE 4: apply_filters (DEPRECADO - Los filtros se aplican en el clique)
     // =========================================================================
-    eprintln!("📋 PHASE 4: apply_filters (skipped - filters applied in clique)");
+    tracing::info!("📋 PHASE 4: apply_filters (skipped - filters applied in clique)");
     
     // Guardar una solución de backup para LEY FUNDAMENTAL ANTES de mover soluciones
     let mejor_solucion_backup = if soluciones_count > 0 { soluciones.get(0).cloned() } else { None };
@@ -283,8 +517,10 @@ pub fn ejecutar_ruta_critica_with_params(
         .map(|f| {
             (f.dias_horarios_libres.as_ref().map(|d| d.habilitado).unwrap_or(false)) ||
             (f.ventana_entre_actividades.as_ref().map(|v| v.habilitado).unwrap_or(false)) ||
-            (f.preferencias_profesores.as_ref().map(|p| p.habilitado).unwrap_or(false)) ||
-            (f.balance_lineas.as_ref().map(|b| b.habilitado).unwrap_or(false))
+            (f.preferencias_profesores.as_ref().map(|p| p.habilitado && p.modo == crate::models::ModoPreferencia::Hard).unwrap_or(false)) ||
+            (f.balance_lineas.as_ref().map(|b| b.habilitado).unwrap_or(false)) ||
+            (f.tiempo_traslado.as_ref().map(|t| t.habilitado && t.modo == crate::models::ModoPreferencia::Hard).unwrap_or(false)) ||
+            f.max_ramos_por_dia.is_some() || f.hora_inicio_minima.is_some() || f.hora_fin_maxima.is_some()
         })
         .unwrap_or(false);
     
@@ -317,19 +553,25 @@ pub fn ejecutar_ruta_critica_with_params(
     }
 
     // Ahora, seleccionar soluciones intentando maximizar cantidad de ramos,
-    // pero siendo permisivos si no alcanzamos 10 resultados: intentar k=6..1
+    // pero siendo permisivos si no alcanzamos 10 resultados: intentar k=max..1
     let mut seleccionadas: Vec<(Vec<(Seccion, i32)>, i64)> = Vec::new();
 
-    // Agrupar por longitud y recorrer desde 6 descendente hasta 1
+    // Agrupar por cantidad de ramos (un paquete cátedra + laboratorio cuenta
+    // como uno) y recorrer desde la mayor cantidad presente hasta 1; con
+    // `max_creditos` una solución puede pasar de 6
     // CAMBIO: Retornar TODAS las soluciones (sin límite artificial de 10)
-    for k in (1..=6).rev() {
+    let max_unidades = soluciones_filtradas.iter()
+        .map(|(sol, _)| crate::algorithm::bundles::unidades(sol))
+        .max()
+        .unwrap_or(0);
+    for k in (1..=max_unidades).rev() {
         // tomar las soluciones de longitud k, ordenar por score desc
         let mut grupo: Vec<_> = soluciones_filtradas
             .iter()
-            .filter(|(sol, _)| sol.len() == k)
+            .filter(|(sol, _)| crate::algorithm::bundles::unidades(sol) == k)
             .cloned()
             .collect();
-        grupo.sort_by(|a, b| b.1.cmp(&a.1));
+        crate::algorithm::paralelo::ordenar_canonico(&mut grupo);
 
         for item in grupo.into_iter() {
             seleccionadas.push(item);
@@ -338,15 +580,29 @@ pub fn ejecutar_ruta_critica_with_params(
 
     // Si no se seleccionó nada (caso extremo), mantener TODAS las disponibles
     if seleccionadas.is_empty() {
-        eprintln!("   ⚠️  No se encontraron soluciones por longitud; devolviendo las mejores disponibles");
+        tracing::warn!("   ⚠️  No se encontraron soluciones por longitud; devolviendo las mejores disponibles");
         seleccionadas = soluciones_filtradas.into_iter().collect();
     }
 
     let soluciones_filtradas_count = seleccionadas.len();
-    eprintln!("   ✓ soluciones que cumplen filtros (seleccionadas): {}", soluciones_filtradas_count);
+    tracing::debug!("   ✓ soluciones que cumplen filtros (seleccionadas): {}", soluciones_filtradas_count);
 
     // CAMBIO: Retornar TODAS las soluciones (sin límite de .take(20))
     let mut resultado: Vec<_> = seleccionadas.into_iter().collect();
+
+    // Objetivo alternativo: maximizar créditos esperados en vez del score
+    if params.objetivo == crate::api_json::Objetivo::ExpectedProgress {
+        resultado = crate::algorithm::progreso_esperado::ordenar_por_progreso(resultado, ramos_disponibles, params.student_ranking);
+    }
+    // Objetivos en orden de importancia, por sobre `objetivo`
+    if !params.objetivos.is_empty() {
+        resultado = crate::algorithm::lexicografico::ordenar(resultado, params, ramos_disponibles);
+    }
+
+    // Diversidad opcional: las primeras soluciones difieren en >= K secciones
+    if let Some(k) = params.diversity_min_diff {
+        resultado = crate::algorithm::diversidad::diversificar(resultado, k);
+    }
     
     // =====================================================================
     // VALIDACIÓN CRÍTICA - LEY FUNDAMENTAL
@@ -359,73 +615,141 @@ pub fn ejecutar_ruta_critica_with_params(
     if resultado.is_empty() && !has_active_filters && cursos_por_aprobar > 0 {
         // FALLBACK: LEY FUNDAMENTAL - Si no hay filtros y hay cursos disponibles,
         // MUST retornar al menos 1 solución
-        eprintln!("❌ LEY FUNDAMENTAL VIOLADA: Intentando recuperación...");
-        eprintln!("   - Soluciones en PHASE 3: {}", soluciones_count);
-        eprintln!("   - Soluciones después PHASE 4: {}", soluciones_filtradas_count);
+        tracing::error!("❌ LEY FUNDAMENTAL VIOLADA: Intentando recuperación...");
+        tracing::error!("   - Soluciones en PHASE 3: {}", soluciones_count);
+        tracing::error!("   - Soluciones después PHASE 4: {}", soluciones_filtradas_count);
         
         if let Some(sol) = mejor_solucion_backup {
             // Hay soluciones de PHASE 3 pero fueron filtradas por PHASE 4
             // Retornar la mejor solución sin filtros
-            eprintln!("   [FALLBACK] Retornando mejor solución sin aplicar filtros PHASE 4...");
+            tracing::debug!("   [FALLBACK] Retornando mejor solución sin aplicar filtros PHASE 4...");
             resultado.push(sol);
         } else {
             // No hay soluciones ni siquiera en PHASE 3
-            eprintln!("❌ ✋ LEY FUNDAMENTAL VIOLADA COMPLETAMENTE ✋ ❌");
-            eprintln!("   VIOLACIÓN: No hay soluciones pero:");
-            eprintln!("   - Hay {} cursos disponibles para aprobar", cursos_por_aprobar);
-            eprintln!("   - NO hay filtros activos");
-            eprintln!("   - Esto es IMPOSIBLE y indica un BUG EN EL SISTEMA");
-            eprintln!();
-            eprintln!("   Diagnóstico:");
-            eprintln!("   - Soluciones generadas en PHASE 3: {}", soluciones_count);
-            eprintln!("   - Soluciones que pasaron filtros: {}", soluciones_filtradas_count);
-            eprintln!("   - Estado del clique: FALLO CRÍTICO");
-            eprintln!();
-            eprintln!("   Acción: Este error debe ser investigado inmediatamente");
+            tracing::error!("❌ ✋ LEY FUNDAMENTAL VIOLADA COMPLETAMENTE ✋ ❌");
+            tracing::error!("   VIOLACIÓN: No hay soluciones pero:");
+            tracing::error!("   - Hay {} cursos disponibles para aprobar", cursos_por_aprobar);
+            tracing::error!("   - NO hay filtros activos");
+            tracing::error!("   - Esto es IMPOSIBLE y indica un BUG EN EL SISTEMA");
+            tracing::error!("   Diagnóstico:");
+            tracing::error!("   - Soluciones generadas en PHASE 3: {}", soluciones_count);
+            tracing::error!("   - Soluciones que pasaron filtros: {}", soluciones_filtradas_count);
+            tracing::error!("   - Estado del clique: FALLO CRÍTICO");
+            tracing::error!("   Acción: Este error debe ser investigado inmediatamente");
         }
     } else if resultado.is_empty() && has_active_filters && cursos_por_aprobar > 0 {
         // FALLBACK PARA FILTROS ACTIVOS: Si hay filtros muy restrictivos que eliminan TODO,
         // retornar al menos 1 solución (el mejor curso disponible)
-        eprintln!("⚠️  AVISO (FALLBACK): Filtros muy restrictivos eliminaron todas las soluciones");
-        eprintln!("   - Soluciones en PHASE 3: {}", soluciones_count);
-        eprintln!("   - Soluciones después PHASE 4: {}", soluciones_filtradas_count);
+        tracing::warn!("⚠️  AVISO (FALLBACK): Filtros muy restrictivos eliminaron todas las soluciones");
+        tracing::warn!("   - Soluciones en PHASE 3: {}", soluciones_count);
+        tracing::warn!("   - Soluciones después PHASE 4: {}", soluciones_filtradas_count);
         
         if let Some(sol) = mejor_solucion_backup {
-            eprintln!("   [FALLBACK] Retornando mejor solución incluso sin cumplir todos los filtros...");
+            tracing::debug!("   [FALLBACK] Retornando mejor solución incluso sin cumplir todos los filtros...");
             resultado.push(sol);
         }
     }
     
     if resultado.is_empty() && has_active_filters && cursos_por_aprobar > 0 {
-        eprintln!("⚠️  AVISO: No hay soluciones que pasen los filtros aplicados");
-        eprintln!("   - Cursos disponibles: {}", cursos_por_aprobar);
-        eprintln!("   - Considere relajar algunos filtros para obtener resultados");
+        tracing::warn!("⚠️  AVISO: No hay soluciones que pasen los filtros aplicados");
+        tracing::warn!("   - Cursos disponibles: {}", cursos_por_aprobar);
+        tracing::warn!("   - Considere relajar algunos filtros para obtener resultados");
     }
     
     if resultado.is_empty() && cursos_por_aprobar == 0 {
-        eprintln!("✅ INFORMACIÓN: Todos los cursos han sido aprobados");
-        eprintln!("   - Felicidades, has completado el programa");
+        tracing::info!("✅ INFORMACIÓN: Todos los cursos han sido aprobados");
+        tracing::debug!("   - Felicidades, has completado el programa");
     }
     
-    eprintln!("✅ Pipeline completado: {} soluciones (SIN LÍMITE - TODAS)", resultado.len());
+    // Tope diario y rango horario son estrictos (tampoco los rompe el respaldo)
+    resultado.retain(|(sol, _)| carga_diaria::cumple(sol, &params.filtros));
+    if resultado.is_empty()
+        && cursos_por_aprobar > 0
+        && !deadline.alcanzado()
+        && let Some(reporte) = carga_diaria::diagnosticar(&lista_secciones_viables, &params.filtros)
+    {
+        return Err(Box::new(reporte));
+    }
+
+    // Ni los ramos obligatorios (tampoco las de respaldo)
+    if !params.ramos_obligatorios.is_empty() {
+        resultado.retain(|(sol, _)| obligatorios::incluidos(&params.ramos_obligatorios, sol));
+        if resultado.is_empty() && !deadline.alcanzado() {
+            return Err(Box::new(obligatorios::ConflictoObligatorios { sin_solucion: true, ..Default::default() }));
+        }
+    }
+
+    // Ninguna solución puede omitir las secciones fijas (tampoco las de respaldo)
+    if !fijas.is_empty() {
+        resultado.retain(|(sol, _)| fijas::incluidas(&fijas, sol));
+        if resultado.is_empty() && !deadline.alcanzado() {
+            return Err(Box::new(fijas::ConflictoFijas { sin_solucion: true, ..Default::default() }));
+        }
+    }
+
+    tracing::info!("✅ Pipeline completado: {} soluciones (SIN LÍMITE - TODAS)", resultado.len());
     Ok(resultado)
 }
 
+/// PHASE 2c para una sección: no aprobada, compatible con las fijas, fuera
+/// de las franjas prohibidas y de los compromisos `hard`, y sin clases en los
+/// días que deben quedar libres
+pub(crate) fn seccion_viable(sec: &Seccion, params: &InputParams, passed_set: &HashSet<String>, fijas: &[Seccion]) -> bool {
+    let sec_codigo_upper = sec.codigo.to_uppercase();
+
+    if passed_set.contains(&sec_codigo_upper) {
+        tracing::debug!("   ⊘ Excluyendo {} (ya aprobado)", sec.codigo);
+        return false;
+    }
+
+    // Con secciones fijas solo quedan las que pueden acompañarlas
+    if !fijas.is_empty() && !fijas::admite(fijas, sec) {
+        tracing::debug!("   ⊘ Excluyendo {} (incompatible con las secciones fijas)", sec.codigo_box);
+        return false;
+    }
+
+    // Excluir si solapa con cualquier bloque prohibido pasado por el usuario
+    if !params.horarios_prohibidos.is_empty() {
+        tracing::debug!("Comprobando solapamiento contra franjas_prohibidas: {:?}", params.horarios_prohibidos);
+        // sec.horario es Vec<String>
+        if solapan_horarios(&sec.horario, &params.horarios_prohibidos) {
+            tracing::debug!("   ⊘ Excluyendo {} (solapa con franja prohibida)", sec.codigo);
+            return false;
+        }
+    }
+
+    // Excluir si pisa un compromiso `hard` del estudiante
+    if let Some(c) = crate::algorithm::compromisos::choca_con(sec, params) {
+        tracing::debug!("   ⊘ Excluyendo {} (choca con compromiso '{}')", sec.codigo, c.etiqueta);
+        return false;
+    }
+
+    // Si existen filtros adicionales, aplicarlos aquí (ej: dias_horarios_libres estrictos)
+    let dias_libres = params.filtros.as_ref()
+        .and_then(|f| f.dias_horarios_libres.as_ref())
+        .and_then(|dhl| dhl.dias_libres_preferidos.as_ref());
+    for dia_str in dias_libres.into_iter().flatten() {
+        let dia_code = dia_str.to_uppercase();
+        for h in &sec.horario {
+            let segs = crate::algorithm::filters::expand_horario_entry(h); // reusar parser público
+            for (d, _s, _e) in segs.iter() {
+                if &dia_code == d {
+                    tracing::debug!("   ⊘ Excluyendo {} (tiene clase en día que debe ser libre {})", sec.codigo, dia_code);
+                    return false;
+                }
+            }
+        }
+    }
+
+    true
+}
+
 /// Función alternativa (compatibilidad): intenta cargar con malla por defecto
 pub fn run_ruta_critica_solutions() -> Result<Vec<(Vec<(Seccion, i32)>, i64)>, Box<dyn Error>> {
     let params = InputParams {
         email: "default@example.com".to_string(),
-        ramos_pasados: Vec::new(),
-        ramos_prioritarios: Vec::new(),
-        horarios_preferidos: Vec::new(),
-        horarios_prohibidos: Vec::new(),
         malla: "MiMalla.xlsx".to_string(),
-        anio: None,
-        sheet: None,
-        student_ranking: None,
-        ranking: None,
-        filtros: None,
-        optimizations: Vec::new(),
+        ..Default::default()
     };
     ejecutar_ruta_critica_with_params(params)
 }
\ No newline at end of file
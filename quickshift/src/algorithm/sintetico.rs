@@ -0,0 +1,88 @@
+// sintetico.rs - Ofertas sintéticas para medir el solver
+//
+// `oferta_sintetica(n, semilla)` arma una malla y una oferta de `n`
+// secciones sin leer Excel: ramos de primer y segundo semestre sin
+// prerequisitos, con `SECCIONES_POR_RAMO` secciones cada uno, y horarios de
+// dos días en los módulos habituales de la oferta. El generador es un LCG
+// determinista, así la misma semilla da siempre la misma oferta. Lo usan los
+// benchmarks (`benches/solver.rs`) y la prueba de rendimiento
+// (`tests/rendimiento_tests.rs`).
+
+use std::collections::HashMap;
+use crate::models::{Componente, RamoDisponible, Seccion};
+
+/// Secciones de cada ramo sintético
+pub const SECCIONES_POR_RAMO: usize = 4;
+
+const DIAS: [&str; 5] = ["LU", "MA", "MI", "JU", "VI"];
+const MODULOS: [&str; 6] = ["08:30-09:50", "10:00-11:20", "11:30-12:50", "14:30-15:50", "16:00-17:20", "17:25-18:45"];
+
+/// Malla (por nombre, como la devuelve `excel::get_ramo_critico`) y oferta sintéticas
+#[derive(Debug, Clone)]
+pub struct OfertaSintetica {
+    pub ramos: HashMap<String, RamoDisponible>,
+    pub secciones: Vec<Seccion>,
+}
+
+struct Lcg(u64);
+
+impl Lcg {
+    fn siguiente(&mut self, modulo: usize) -> usize {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % modulo as u64) as usize
+    }
+}
+
+/// Oferta de `n_secciones` secciones (el último ramo puede tener menos)
+pub fn oferta_sintetica(n_secciones: usize, semilla: u64) -> OfertaSintetica {
+    let mut lcg = Lcg(semilla);
+    let n_ramos = n_secciones.div_ceil(SECCIONES_POR_RAMO);
+    let mut ramos = HashMap::with_capacity(n_ramos);
+    let mut secciones = Vec::with_capacity(n_secciones);
+
+    for r in 0..n_ramos {
+        let codigo = format!("SIN{:04}", r);
+        let nombre = format!("Ramo sintético {}", r);
+        ramos.insert(nombre.clone(), RamoDisponible {
+            id: r as i32 + 1,
+            nombre: nombre.clone(),
+            codigo: codigo.clone(),
+            holgura: lcg.siguiente(4) as i32,
+            numb_correlativo: r as i32 + 1,
+            critico: lcg.siguiente(3) == 0,
+            requisitos_ids: Vec::new(),
+            correquisitos_ids: Vec::new(),
+            dificultad: Some(40.0 + lcg.siguiente(60) as f64),
+            electivo: false,
+            semestre: Some(1 + (r % 2) as i32),
+            creditos: Some(6),
+            tags: Vec::new(),
+        });
+
+        let cuantas = SECCIONES_POR_RAMO.min(n_secciones - secciones.len());
+        for s in 1..=cuantas {
+            let dia_a = lcg.siguiente(DIAS.len());
+            let dia_b = (dia_a + 2) % DIAS.len();
+            let modulo = MODULOS[lcg.siguiente(MODULOS.len())];
+            secciones.push(Seccion {
+                codigo: codigo.clone(),
+                nombre: nombre.clone(),
+                seccion: s.to_string(),
+                horario: vec![format!("{} {} {}", DIAS[dia_a], DIAS[dia_b], modulo)],
+                profesor: format!("Profesor {}", lcg.siguiente(50)),
+                codigo_box: format!("{}-{}", codigo, s),
+                is_cfg: false,
+                is_electivo: false,
+                linea: None,
+                sala: None,
+                campus: None,
+                componente: Componente::default(),
+                bundle_id: None,
+                cupos_totales: None,
+                cupos_disponibles: None,
+                evaluaciones: Vec::new(),
+            });
+        }
+    }
+    OfertaSintetica { ramos, secciones }
+}
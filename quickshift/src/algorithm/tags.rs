@@ -0,0 +1,36 @@
+// tags.rs - Notas de los asesores sobre cada ramo (`RamoDisponible::tags`)
+//
+// Las notas vienen de la hoja "Notas" de la malla (`excel::malla::leer_notas`).
+// Se muestran en `/api/cursos/*` y en cada solución de `/solve`, y el filtro
+// `filtros.evitar_tags` descarta los ramos que tengan alguna de las notas
+// pedidas. Las notas se comparan sin distinguir mayúsculas ni tildes.
+
+use std::collections::BTreeMap;
+use crate::algorithm::indice_ramos::RamoIndex;
+use crate::excel::normalize_name;
+use crate::models::{Seccion, UserFilters};
+
+/// `evitar_tags` de los filtros, normalizadas (vacío si no hay filtros)
+pub fn tags_a_evitar(filtros: &Option<UserFilters>) -> Vec<String> {
+    filtros.iter()
+        .flat_map(|f| f.evitar_tags.iter())
+        .map(|t| normalize_name(t))
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// true si el ramo de la sección tiene alguna de las notas `evitar` (ya normalizadas)
+pub fn ramo_evitado(s: &Seccion, indice: &RamoIndex, evitar: &[String]) -> bool {
+    if evitar.is_empty() {
+        return false;
+    }
+    indice.de_seccion(s)
+        .is_some_and(|r| r.tags.iter().any(|t| evitar.contains(&normalize_name(t))))
+}
+
+/// Notas de los ramos de una solución, por código (solo los ramos que tienen)
+pub fn tags_de_solucion<'a>(secciones: impl IntoIterator<Item = &'a Seccion>, indice: &RamoIndex) -> BTreeMap<String, Vec<String>> {
+    secciones.into_iter()
+        .filter_map(|s| indice.de_seccion(s).filter(|r| !r.tags.is_empty()).map(|r| (s.codigo.to_uppercase(), r.tags.clone())))
+        .collect()
+}
@@ -0,0 +1,78 @@
+// traslado.rs - Tiempo de traslado entre campus
+//
+// `Seccion::campus` viene de la columna "Campus"/"Sede" de la Oferta
+// Académica. Dos clases el mismo día en campus distintos necesitan al menos
+// `TiempoTraslado::minutos` entre el fin de una y el inicio de la otra. En
+// modo `hard` el clique no junta esas secciones; en modo `soft`
+// `clique::apply_optimization_modifiers` penaliza cada par sin tiempo de
+// traslado. Secciones sin campus conocido nunca generan conflicto.
+
+use crate::algorithm::conflict::parse_slots;
+use crate::api_json::InputParams;
+use crate::models::{ModoPreferencia, Seccion, TiempoTraslado};
+
+/// Minutos de traslado cuando el filtro no los especifica
+pub const MINUTOS_TRASLADO_POR_DEFECTO: i32 = 30;
+
+/// `filtros.tiempo_traslado` si está habilitado
+pub fn traslado_pedido(params: &InputParams) -> Option<&TiempoTraslado> {
+    params.filtros.as_ref()
+        .and_then(|f| f.tiempo_traslado.as_ref())
+        .filter(|t| t.habilitado)
+}
+
+/// Minutos exigidos por el filtro
+pub fn minutos(traslado: &TiempoTraslado) -> i32 {
+    traslado.minutos.unwrap_or(MINUTOS_TRASLADO_POR_DEFECTO).max(0)
+}
+
+/// El filtro, solo si descarta combinaciones (modo `hard`)
+pub fn traslado_estricto(params: &InputParams) -> Option<&TiempoTraslado> {
+    traslado_pedido(params).filter(|t| t.modo == ModoPreferencia::Hard)
+}
+
+/// El filtro, solo si penaliza en el score (modo `soft`)
+pub fn traslado_blando(params: &InputParams) -> Option<&TiempoTraslado> {
+    traslado_pedido(params).filter(|t| t.modo == ModoPreferencia::Soft)
+}
+
+fn campus_normalizado(seccion: &Seccion) -> Option<String> {
+    seccion.campus.as_deref()
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| !c.is_empty())
+}
+
+/// true si `a` y `b` se dictan en campus distintos y algún día tienen clases
+/// separadas por menos de `minutos` (o solapadas)
+pub fn requiere_traslado(a: &Seccion, b: &Seccion, minutos: i32) -> bool {
+    let (Some(ca), Some(cb)) = (campus_normalizado(a), campus_normalizado(b)) else {
+        return false;
+    };
+    if ca == cb {
+        return false;
+    }
+    let bloques_b: Vec<(String, i32, i32)> = b.horario.iter().flat_map(|h| parse_slots(h)).collect();
+    a.horario.iter().flat_map(|h| parse_slots(h)).any(|(d1, s1, e1)| {
+        bloques_b.iter().any(|(d2, s2, e2)| {
+            if d1 != *d2 {
+                return false;
+            }
+            let gap = if e1 <= *s2 { s2 - e1 } else if *e2 <= s1 { s1 - e2 } else { 0 };
+            gap < minutos
+        })
+    })
+}
+
+/// Pares de secciones de la solución sin tiempo suficiente de traslado
+pub fn traslados_insuficientes<'a>(secciones: impl IntoIterator<Item = &'a Seccion>, minutos: i32) -> usize {
+    let secciones: Vec<&Seccion> = secciones.into_iter().collect();
+    let mut pares = 0;
+    for (i, a) in secciones.iter().enumerate() {
+        for b in secciones.iter().skip(i + 1) {
+            if requiere_traslado(a, b, minutos) {
+                pares += 1;
+            }
+        }
+    }
+    pares
+}
@@ -0,0 +1,50 @@
+// ventanas.rs - Ventanas (tiempo libre entre clases del mismo día)
+//
+// `DiaHorariosLibres::minimizar_ventanas` pide horarios sin huecos largos y
+// `ventana_ideal_minutos` fija cuánto hueco se tolera entre dos clases
+// seguidas (p. ej. 30 min para almorzar o cambiar de sala). Cada ventana se
+// mide contra ese ideal y solo cuentan los minutos que lo exceden;
+// `clique::apply_optimization_modifiers` penaliza el total de forma
+// proporcional. Sin `ventana_ideal_minutos` cualquier hueco cuenta completo.
+
+use std::collections::HashMap;
+use crate::algorithm::conflict::parse_slots;
+use crate::api_json::InputParams;
+use crate::models::Seccion;
+
+/// Ventana ideal (en minutos) si `filtros.dias_horarios_libres` está habilitado
+/// y pide `minimizar_ventanas`
+pub fn ventana_ideal_pedida(params: &InputParams) -> Option<i32> {
+    params.filtros.as_ref()
+        .and_then(|f| f.dias_horarios_libres.as_ref())
+        .filter(|d| d.habilitado && d.minimizar_ventanas.unwrap_or(false))
+        .map(|d| d.ventana_ideal_minutos.unwrap_or(0).max(0))
+}
+
+/// Huecos entre clases consecutivas del mismo día, en minutos. Las clases
+/// solapadas o pegadas no generan ventana.
+pub fn ventanas<'a>(secciones: impl IntoIterator<Item = &'a Seccion>) -> Vec<i32> {
+    let mut por_dia: HashMap<String, Vec<(i32, i32)>> = HashMap::new();
+    for s in secciones {
+        for (dia, inicio, fin) in s.horario.iter().flat_map(|h| parse_slots(h)) {
+            por_dia.entry(dia).or_default().push((inicio, fin));
+        }
+    }
+    let mut out = Vec::new();
+    for bloques in por_dia.values_mut() {
+        bloques.sort_unstable();
+        let mut fin_actual = bloques[0].1;
+        for &(inicio, fin) in bloques.iter().skip(1) {
+            if inicio > fin_actual {
+                out.push(inicio - fin_actual);
+            }
+            fin_actual = fin_actual.max(fin);
+        }
+    }
+    out
+}
+
+/// Minutos de ventana por sobre `ideal`, sumados en toda la semana
+pub fn minutos_sobre_ideal<'a>(secciones: impl IntoIterator<Item = &'a Seccion>, ideal: i32) -> i32 {
+    ventanas(secciones).into_iter().map(|v| (v - ideal).max(0)).sum()
+}
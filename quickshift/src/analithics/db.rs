@@ -1,9 +1,11 @@
 use rusqlite::{params, Connection};
 use std::error::Error;
 use std::fs;
-use std::env;
 use std::path::PathBuf;
 use std::fmt;
+use std::time::Duration;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
 
 // Postgres client for remote DB support
 use postgres::{Client, NoTls};
@@ -26,18 +28,18 @@ impl fmt::Debug for AnalyticsConn {
     }
 }
 
-// load .env at module init if present
-fn load_dotenv() {
-    let _ = dotenv::dotenv();
+/// URL of the analytics DB: DATABASE_URL (the variable hosting platforms set
+/// for an attached Postgres), falling back to ANALITHICS_DB_URL (see `app_config`).
+pub fn analytics_db_url() -> Option<String> {
+    crate::app_config::actual().url_analiticas().map(str::to_string)
 }
 
 /// Return the path to the analytics DB. Exposed so other submodules can open
-/// short-lived connections. Honors ANALITHICS_DB_PATH / ANALITHICS_DB_URL env.
+/// short-lived connections. Honors ANALITHICS_DB_PATH / DATABASE_URL / ANALITHICS_DB_URL env.
 pub fn analytics_db_path() -> PathBuf {
-    load_dotenv();
-    if let Ok(p) = env::var("ANALITHICS_DB_PATH") {
-        PathBuf::from(p)
-    } else if let Ok(p) = env::var("ANALITHICS_DB_URL") {
+    if let Some(p) = crate::app_config::actual().analithics_db_path.clone() {
+        p
+    } else if let Some(p) = analytics_db_url() {
         // If the URL starts with sqlite:// or file://, strip the scheme and return path
         if p.starts_with("sqlite://") {
             // support sqlite:///absolute/path and sqlite://relative/path
@@ -57,17 +59,15 @@ pub fn analytics_db_path() -> PathBuf {
 
 /// Initialize the analytics DB (create dir + sqlite file + table)
 pub fn init_db() -> Result<(), Box<dyn Error>> {
-    load_dotenv();
     // If using a local file-based sqlite, ensure directory exists
-    if let Ok(url) = env::var("ANALITHICS_DB_URL") {
-        if url.starts_with("sqlite://") || url.starts_with("file://") || env::var("ANALITHICS_DB_PATH").is_ok()
+    if let Some(url) = analytics_db_url()
+        && (url.starts_with("sqlite://") || url.starts_with("file://") || crate::app_config::actual().analithics_db_path.is_some())
+    {
+        let db_path = analytics_db_path();
+        if let Some(dir) = db_path.parent()
+            && !dir.exists()
         {
-            let db_path = analytics_db_path();
-            if let Some(dir) = db_path.parent() {
-                if !dir.exists() {
-                    fs::create_dir_all(dir)?;
-                }
-            }
+            fs::create_dir_all(dir)?;
         }
     }
 
@@ -87,10 +87,13 @@ pub fn init_db() -> Result<(), Box<dyn Error>> {
                     filtros_json TEXT,
                     request_json TEXT,
                     response_json TEXT,
-                    client_ip TEXT
+                    client_ip TEXT,
+                    api_key TEXT
                 )",
                 [],
             )?;
+            // Bases creadas antes de la columna `api_key`: falla si ya existe
+            let _ = conn.execute("ALTER TABLE queries ADD COLUMN api_key TEXT", []);
 
             conn.execute(
                 "CREATE TABLE IF NOT EXISTS reports (
@@ -113,6 +116,20 @@ pub fn init_db() -> Result<(), Box<dyn Error>> {
                 )",
                 [],
             )?;
+
+            conn.execute(crate::analithics::scenarios::SQLITE_SCHEMA, [])?;
+            conn.execute_batch(crate::analithics::soluciones::SQLITE_SCHEMA)?;
+            conn.execute(crate::analithics::feedback::SQLITE_SCHEMA, [])?;
+            conn.execute(crate::analithics::jobs::SQLITE_SCHEMA, [])?;
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS api_keys (
+                    clave_sha256 TEXT PRIMARY KEY,
+                    nombre TEXT NOT NULL,
+                    limite_por_min INTEGER,
+                    activa INTEGER NOT NULL DEFAULT 1
+                )",
+                [],
+            )?;
             Ok(())
         }
         Ok(AnalyticsConn::PostgresConfig(url)) => {
@@ -133,8 +150,10 @@ pub fn init_db() -> Result<(), Box<dyn Error>> {
                         filtros_json TEXT,
                         request_json TEXT,
                         response_json TEXT,
-                        client_ip TEXT
+                        client_ip TEXT,
+                        api_key TEXT
                     );
+                    ALTER TABLE queries ADD COLUMN IF NOT EXISTS api_key TEXT;
 
                     CREATE TABLE IF NOT EXISTS reports (
                         id BIGSERIAL PRIMARY KEY,
@@ -150,8 +169,19 @@ pub fn init_db() -> Result<(), Box<dyn Error>> {
                         hits BIGINT,
                         misses BIGINT,
                         entries BIGINT
+                    );
+
+                    CREATE TABLE IF NOT EXISTS api_keys (
+                        clave_sha256 TEXT PRIMARY KEY,
+                        nombre TEXT NOT NULL,
+                        limite_por_min BIGINT,
+                        activa BOOLEAN NOT NULL DEFAULT TRUE
                     );",
                 ).map_err(|e| Box::new(e) as Box<dyn Error + Send + 'static>)?;
+                client.batch_execute(crate::analithics::scenarios::POSTGRES_SCHEMA).map_err(|e| Box::new(e) as Box<dyn Error + Send + 'static>)?;
+                client.batch_execute(crate::analithics::soluciones::POSTGRES_SCHEMA).map_err(|e| Box::new(e) as Box<dyn Error + Send + 'static>)?;
+                client.batch_execute(crate::analithics::feedback::POSTGRES_SCHEMA).map_err(|e| Box::new(e) as Box<dyn Error + Send + 'static>)?;
+                client.batch_execute(crate::analithics::jobs::POSTGRES_SCHEMA).map_err(|e| Box::new(e) as Box<dyn Error + Send + 'static>)?;
                 Ok(())
             });
             match handle.join() {
@@ -165,10 +195,10 @@ pub fn init_db() -> Result<(), Box<dyn Error>> {
 }
 
 /// Open a connection to the analytics DB, accepting sqlite:// URLs or plain paths.
-/// Open a connection to the analytics DB. Accepts sqlite://, file:// and postgres:// URLs.
+/// Open a connection to the analytics DB. Accepts sqlite://, file:// and postgres:// URLs
+/// (see `analytics_db_url`).
 pub fn open_analytics_connection() -> Result<AnalyticsConn, Box<dyn Error>> {
-    load_dotenv();
-    if let Ok(url) = env::var("ANALITHICS_DB_URL") {
+    if let Some(url) = analytics_db_url() {
         if url.starts_with("sqlite://") {
             let path = url.trim_start_matches("sqlite://");
             let conn = Connection::open(path)?;
@@ -183,7 +213,7 @@ pub fn open_analytics_connection() -> Result<AnalyticsConn, Box<dyn Error>> {
             // trying to start a tokio runtime inside the Actix runtime.
             return Ok(AnalyticsConn::PostgresConfig(url));
         } else {
-            return Err(format!("ANALITHICS_DB_URL uses unsupported scheme: {}", url).into());
+            return Err(format!("analytics DB URL (DATABASE_URL / ANALITHICS_DB_URL) uses unsupported scheme: {}", url).into());
         }
     }
 
@@ -297,3 +327,254 @@ pub fn fetch_recent_cache_stats(conn: &AnalyticsConn, limit: i64) -> Result<Vec<
         }
     }
 }
+
+/// Ejecuta `f` con un cliente Postgres en un hilo aparte (ver `open_analytics_connection`)
+pub(crate) fn con_postgres<T: Send + 'static>(
+    url: &str,
+    f: impl FnOnce(&mut Client) -> Result<T, postgres::Error> + Send + 'static,
+) -> Result<T, Box<dyn Error>> {
+    let url = url.to_string();
+    let handle = std::thread::spawn(move || -> Result<T, String> {
+        let mut client = Client::connect(&url, NoTls).map_err(|e| e.to_string())?;
+        f(&mut client).map_err(|e| e.to_string())
+    });
+    match handle.join() {
+        Ok(res) => res.map_err(|e| e.into()),
+        Err(e) => Err(format!("thread join error: {:?}", e).into()),
+    }
+}
+
+/// Tablas con columna `ts` que se podan por fecha
+const TABLAS_CON_TS: [&str; 3] = ["queries", "reports", "cache_stats"];
+
+/// Filas borradas por tabla en una poda
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Borrados {
+    pub queries: u64,
+    pub reports: u64,
+    pub cache_stats: u64,
+}
+
+/// Fecha de corte en el formato de las columnas `ts` (RFC 3339 UTC).
+/// Acepta `YYYY-MM-DD` (medianoche UTC) o un RFC 3339 completo.
+pub fn parse_fecha_corte(fecha: &str) -> Option<String> {
+    let fecha = fecha.trim();
+    if let Ok(d) = NaiveDate::parse_from_str(fecha, "%Y-%m-%d") {
+        return Some(d.and_hms_opt(0, 0, 0)?.and_utc().to_rfc3339());
+    }
+    DateTime::parse_from_rfc3339(fecha).ok().map(|dt| dt.with_timezone(&Utc).to_rfc3339())
+}
+
+/// Borra de `queries`, `reports` y `cache_stats` las filas con `ts` anterior a
+/// `antes` (RFC 3339, ver `parse_fecha_corte`). Los `ts` se guardan como texto
+/// RFC 3339 en UTC, así que la comparación de strings respeta el orden temporal.
+pub fn borrar_antes_de(conn: &AnalyticsConn, antes: &str) -> Result<Borrados, Box<dyn Error>> {
+    let conteos: Vec<u64> = match conn {
+        AnalyticsConn::Sqlite(c) => TABLAS_CON_TS.iter()
+            .map(|t| c.execute(&format!("DELETE FROM {} WHERE ts < ?1", t), params![antes]).map(|n| n as u64))
+            .collect::<Result<_, _>>()?,
+        AnalyticsConn::PostgresConfig(url) => {
+            let antes = antes.to_string();
+            con_postgres(url, move |client| {
+                TABLAS_CON_TS.iter()
+                    .map(|t| client.execute(&format!("DELETE FROM {} WHERE ts < $1", t), &[&antes]))
+                    .collect()
+            })?
+        }
+    };
+    Ok(Borrados { queries: conteos[0], reports: conteos[1], cache_stats: conteos[2] })
+}
+
+/// Días de retención de `ANALITHICS_RETENTION_DAYS`. Sin definir o 0 no se poda.
+pub fn retention_days() -> Option<i64> {
+    crate::app_config::actual().analithics_retention_days.filter(|d| *d > 0)
+}
+
+/// Borra lo anterior a `dias` días atrás
+pub fn aplicar_retencion(conn: &AnalyticsConn, dias: i64) -> Result<Borrados, Box<dyn Error>> {
+    let corte = (Utc::now() - chrono::Duration::days(dias)).to_rfc3339();
+    borrar_antes_de(conn, &corte)
+}
+
+/// Cada cuánto corre la poda programada
+const INTERVALO_RETENCION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Lanza un hilo que aplica la retención al arrancar y luego una vez al día.
+/// No hace nada si `ANALITHICS_RETENTION_DAYS` no está definida.
+pub fn iniciar_retencion() {
+    let Some(dias) = retention_days() else { return };
+    tracing::info!("🧹 Retención de analíticas: {} días", dias);
+    std::thread::spawn(move || loop {
+        match open_analytics_connection().and_then(|conn| aplicar_retencion(&conn, dias)) {
+            Ok(b) => tracing::info!("🧹 Retención: borradas {} queries, {} reports, {} cache_stats", b.queries, b.reports, b.cache_stats),
+            Err(e) => tracing::warn!("retención de analíticas falló: {}", e),
+        }
+        std::thread::sleep(INTERVALO_RETENCION);
+    });
+}
+
+/// Fila de `queries` tal como se exporta
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryLog {
+    pub id: i64,
+    pub ts: String,
+    pub duration_ms: Option<i64>,
+    pub email: Option<String>,
+    pub malla: Option<String>,
+    pub student_ranking: Option<f64>,
+    pub ramos_pasados: Option<String>,
+    pub ramos_prioritarios: Option<String>,
+    pub filtros_json: Option<String>,
+    pub request_json: Option<String>,
+    pub response_json: Option<String>,
+    pub client_ip: Option<String>,
+    pub api_key: Option<String>,
+}
+
+const COLUMNAS_QUERIES: &str = "id, ts, duration_ms, email, malla, student_ranking, ramos_pasados, ramos_prioritarios, filtros_json, request_json, response_json, client_ip, api_key";
+
+/// Todas las filas de `queries`, en orden de inserción
+pub fn fetch_queries(conn: &AnalyticsConn) -> Result<Vec<QueryLog>, Box<dyn Error>> {
+    let sql = format!("SELECT {} FROM queries ORDER BY id", COLUMNAS_QUERIES);
+    match conn {
+        AnalyticsConn::Sqlite(c) => {
+            let mut stmt = c.prepare(&sql)?;
+            let rows = stmt.query_map([], |r| Ok(QueryLog {
+                id: r.get(0)?,
+                ts: r.get(1)?,
+                duration_ms: r.get(2)?,
+                email: r.get(3)?,
+                malla: r.get(4)?,
+                student_ranking: r.get(5)?,
+                ramos_pasados: r.get(6)?,
+                ramos_prioritarios: r.get(7)?,
+                filtros_json: r.get(8)?,
+                request_json: r.get(9)?,
+                response_json: r.get(10)?,
+                client_ip: r.get(11)?,
+                api_key: r.get(12)?,
+            }))?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        }
+        AnalyticsConn::PostgresConfig(url) => con_postgres(url, move |client| {
+            let rows = client.query(sql.as_str(), &[])?;
+            Ok(rows.iter().map(|r| QueryLog {
+                id: r.get(0),
+                ts: r.get(1),
+                duration_ms: r.get(2),
+                email: r.get(3),
+                malla: r.get(4),
+                student_ranking: r.get(5),
+                ramos_pasados: r.get(6),
+                ramos_prioritarios: r.get(7),
+                filtros_json: r.get(8),
+                request_json: r.get(9),
+                response_json: r.get(10),
+                client_ip: r.get(11),
+                api_key: r.get(12),
+            }).collect())
+        }),
+    }
+}
+
+/// Formato de `GET /analytics/export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatoExport {
+    Csv,
+    Jsonl,
+}
+
+impl FormatoExport {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "csv" => Some(FormatoExport::Csv),
+            "jsonl" | "ndjson" => Some(FormatoExport::Jsonl),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            FormatoExport::Csv => "text/csv; charset=utf-8",
+            FormatoExport::Jsonl => "application/x-ndjson",
+        }
+    }
+}
+
+/// Campo CSV con comillas cuando contiene separadores, comillas o saltos de línea
+fn campo_csv(valor: &str) -> String {
+    if valor.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", valor.replace('"', "\"\""))
+    } else {
+        valor.to_string()
+    }
+}
+
+/// Serializa las filas como CSV (con encabezado) o JSON Lines
+pub fn exportar_queries(filas: &[QueryLog], formato: FormatoExport) -> String {
+    let mut out = String::new();
+    match formato {
+        FormatoExport::Csv => {
+            out.push_str(&COLUMNAS_QUERIES.replace(' ', ""));
+            out.push('\n');
+            for f in filas {
+                let opt = |v: &Option<String>| campo_csv(v.as_deref().unwrap_or(""));
+                let campos = [
+                    f.id.to_string(),
+                    campo_csv(&f.ts),
+                    f.duration_ms.map(|d| d.to_string()).unwrap_or_default(),
+                    opt(&f.email),
+                    opt(&f.malla),
+                    f.student_ranking.map(|r| r.to_string()).unwrap_or_default(),
+                    opt(&f.ramos_pasados),
+                    opt(&f.ramos_prioritarios),
+                    opt(&f.filtros_json),
+                    opt(&f.request_json),
+                    opt(&f.response_json),
+                    opt(&f.client_ip),
+                    opt(&f.api_key),
+                ];
+                out.push_str(&campos.join(","));
+                out.push('\n');
+            }
+        }
+        FormatoExport::Jsonl => {
+            for f in filas {
+                out.push_str(&serde_json::to_string(f).unwrap_or_default());
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// Clave registrada en la tabla `api_keys` (solo se guarda el SHA-256 de la clave)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKeyRow {
+    pub clave_sha256: String,
+    pub nombre: String,
+    pub limite_por_min: Option<i64>,
+}
+
+/// Claves activas de la tabla `api_keys`
+pub fn fetch_api_keys(conn: &AnalyticsConn) -> Result<Vec<ApiKeyRow>, Box<dyn Error>> {
+    match conn {
+        AnalyticsConn::Sqlite(c) => {
+            let mut stmt = c.prepare("SELECT clave_sha256, nombre, limite_por_min FROM api_keys WHERE activa <> 0")?;
+            let rows = stmt.query_map([], |r| Ok(ApiKeyRow {
+                clave_sha256: r.get(0)?,
+                nombre: r.get(1)?,
+                limite_por_min: r.get(2)?,
+            }))?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        }
+        AnalyticsConn::PostgresConfig(url) => con_postgres(url, |client| {
+            let rows = client.query("SELECT clave_sha256, nombre, limite_por_min FROM api_keys WHERE activa", &[])?;
+            Ok(rows.iter().map(|r| ApiKeyRow {
+                clave_sha256: r.get(0),
+                nombre: r.get(1),
+                limite_por_min: r.get(2),
+            }).collect())
+        }),
+    }
+}
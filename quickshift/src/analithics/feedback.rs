@@ -0,0 +1,166 @@
+//! Valoraciones de los estudiantes sobre las soluciones recomendadas.
+//!
+//! Cada `POST /solutions/{id}/feedback` agrega una fila en `solution_feedback`
+//! con la nota (1-5), un comentario opcional y la configuración de scoring con
+//! que se generó el conjunto (objetivo, solver y optimizaciones de los params
+//! guardados). `GET /analytics/feedback/summary` agrupa las notas por esa
+//! configuración para ajustar los pesos de `crate::scoring` con datos reales.
+
+use crate::analithics::db::{con_postgres, AnalyticsConn};
+use chrono::Utc;
+use rusqlite::params;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// Tabla de valoraciones (SQLite). La crea `init_db`.
+pub const SQLITE_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS solution_feedback (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ts TEXT NOT NULL,
+    solution_id TEXT NOT NULL,
+    indice INTEGER NOT NULL,
+    email TEXT,
+    rating INTEGER NOT NULL,
+    comentario TEXT,
+    configuracion TEXT NOT NULL
+)";
+
+/// Tabla de valoraciones (Postgres). La crea `init_db`.
+pub const POSTGRES_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS solution_feedback (
+    id BIGSERIAL PRIMARY KEY,
+    ts TEXT NOT NULL,
+    solution_id TEXT NOT NULL,
+    indice BIGINT NOT NULL,
+    email TEXT,
+    rating BIGINT NOT NULL,
+    comentario TEXT,
+    configuracion TEXT NOT NULL
+);";
+
+/// Notas válidas
+pub const RATING_MIN: i64 = 1;
+pub const RATING_MAX: i64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Feedback {
+    pub solution_id: String,
+    pub indice: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub rating: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comentario: Option<String>,
+    pub configuracion: Value,
+    pub ts: String,
+}
+
+/// Notas agregadas de una configuración de scoring
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResumenConfiguracion {
+    pub configuracion: Value,
+    pub cantidad: i64,
+    pub promedio: f64,
+    /// Cantidad de valoraciones por nota ("1".."5")
+    pub distribucion: BTreeMap<String, i64>,
+}
+
+/// Configuración de scoring de un conjunto de soluciones a partir de sus
+/// `InputParams` serializados: `objetivo`, `solver`, `optimizations` (ordenadas)
+/// y, si el request ajustó pesos, sus `scoring`
+pub fn configuracion_scoring(params: &Value) -> Value {
+    let texto = |campo: &str, defecto: &str| params.get(campo).and_then(|v| v.as_str()).unwrap_or(defecto).to_string();
+    let mut optimizations: Vec<String> = params.get("optimizations")
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|o| o.as_str()).map(|o| o.trim().to_lowercase()).collect())
+        .unwrap_or_default();
+    optimizations.sort();
+    optimizations.dedup();
+    let mut configuracion = json!({
+        "objetivo": texto("objetivo", "score"),
+        "solver": texto("solver", "greedy"),
+        "optimizations": optimizations,
+    });
+    if let Some(pesos) = params.get("scoring").filter(|s| s.as_object().is_some_and(|o| !o.is_empty())) {
+        configuracion["scoring"] = pesos.clone();
+    }
+    configuracion
+}
+
+/// Guarda una valoración. `rating` debe estar en `RATING_MIN..=RATING_MAX`.
+pub fn guardar_feedback(
+    conn: &AnalyticsConn,
+    solution_id: &str,
+    indice: i64,
+    email: Option<&str>,
+    rating: i64,
+    comentario: Option<&str>,
+    configuracion: &Value,
+) -> Result<Feedback, Box<dyn Error>> {
+    if !(RATING_MIN..=RATING_MAX).contains(&rating) {
+        return Err(format!("rating must be between {} and {}", RATING_MIN, RATING_MAX).into());
+    }
+    let fb = Feedback {
+        solution_id: solution_id.trim().to_lowercase(),
+        indice,
+        email: email.map(|e| e.trim().to_lowercase()).filter(|e| !e.is_empty()),
+        rating,
+        comentario: comentario.map(|c| c.trim().to_string()).filter(|c| !c.is_empty()),
+        configuracion: configuracion.clone(),
+        ts: Utc::now().to_rfc3339(),
+    };
+    let configuracion_json = configuracion.to_string();
+    match conn {
+        AnalyticsConn::Sqlite(c) => {
+            c.execute(
+                "INSERT INTO solution_feedback (ts, solution_id, indice, email, rating, comentario, configuracion) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![fb.ts, fb.solution_id, fb.indice, fb.email, fb.rating, fb.comentario, configuracion_json],
+            )?;
+        }
+        AnalyticsConn::PostgresConfig(url) => {
+            let f = fb.clone();
+            con_postgres(url, move |client| {
+                client.execute(
+                    "INSERT INTO solution_feedback (ts, solution_id, indice, email, rating, comentario, configuracion) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                    &[&f.ts, &f.solution_id, &f.indice, &f.email, &f.rating, &f.comentario, &configuracion_json],
+                )
+            })?;
+        }
+    }
+    Ok(fb)
+}
+
+/// Notas por configuración de scoring, de la más valorada a la menos
+pub fn resumen_feedback(conn: &AnalyticsConn) -> Result<Vec<ResumenConfiguracion>, Box<dyn Error>> {
+    const SQL: &str = "SELECT configuracion, rating, COUNT(*) FROM solution_feedback GROUP BY configuracion, rating";
+    let filas: Vec<(String, i64, i64)> = match conn {
+        AnalyticsConn::Sqlite(c) => {
+            let mut stmt = c.prepare(SQL)?;
+            let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        }
+        AnalyticsConn::PostgresConfig(url) => con_postgres(url, move |client| {
+            let rows = client.query(SQL, &[])?;
+            Ok(rows.iter().map(|r| (r.get(0), r.get(1), r.get(2))).collect())
+        })?,
+    };
+
+    let mut por_config: BTreeMap<String, BTreeMap<String, i64>> = BTreeMap::new();
+    for (configuracion, rating, cantidad) in filas {
+        *por_config.entry(configuracion).or_default().entry(rating.to_string()).or_default() += cantidad;
+    }
+    let mut out: Vec<ResumenConfiguracion> = por_config.into_iter()
+        .map(|(configuracion, distribucion)| {
+            let cantidad: i64 = distribucion.values().sum();
+            let suma: i64 = distribucion.iter().map(|(r, n)| r.parse::<i64>().unwrap_or(0) * n).sum();
+            ResumenConfiguracion {
+                configuracion: serde_json::from_str(&configuracion).unwrap_or(Value::String(configuracion)),
+                cantidad,
+                promedio: suma as f64 / cantidad as f64,
+                distribucion,
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| b.promedio.total_cmp(&a.promedio).then(b.cantidad.cmp(&a.cantidad)));
+    Ok(out)
+}
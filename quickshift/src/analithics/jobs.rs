@@ -0,0 +1,117 @@
+//! Resultados de los jobs asíncronos de `/solve/async`.
+//!
+//! Los jobs viven en memoria mientras corren (ver `server_handlers::jobs`);
+//! al terminar se guarda una fila en `solve_jobs` con el cuerpo que devuelve
+//! `GET /jobs/{id}/result` (la respuesta de `/solve` o el error) y su status
+//! HTTP, para poder consultarlo después de que el job salga de memoria o de
+//! reiniciar el servidor.
+
+use crate::analithics::db::{con_postgres, AnalyticsConn};
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+
+/// Tabla de jobs terminados (SQLite). La crea `init_db`.
+pub const SQLITE_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS solve_jobs (
+    id TEXT PRIMARY KEY,
+    creado TEXT NOT NULL,
+    terminado TEXT NOT NULL,
+    email TEXT,
+    estado TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    params_json TEXT NOT NULL,
+    progreso_json TEXT NOT NULL,
+    resultado_json TEXT NOT NULL
+)";
+
+/// Tabla de jobs terminados (Postgres). La crea `init_db`.
+pub const POSTGRES_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS solve_jobs (
+    id TEXT PRIMARY KEY,
+    creado TEXT NOT NULL,
+    terminado TEXT NOT NULL,
+    email TEXT,
+    estado TEXT NOT NULL,
+    status BIGINT NOT NULL,
+    params_json TEXT NOT NULL,
+    progreso_json TEXT NOT NULL,
+    resultado_json TEXT NOT NULL
+);";
+
+/// Job terminado (`completado` o `fallido`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobTerminado {
+    pub id: String,
+    pub creado: String,
+    pub terminado: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub estado: String,
+    /// Status HTTP de `GET /jobs/{id}/result`
+    pub status: i64,
+    pub params: Value,
+    /// Avance final (`secciones_viables`, `cliques`)
+    pub progreso: Value,
+    /// Cuerpo de `GET /jobs/{id}/result`: respuesta de `/solve` o `{code, message, details}`
+    pub resultado: Value,
+}
+
+/// Guarda (o reemplaza) un job terminado
+pub fn guardar_job(conn: &AnalyticsConn, job: &JobTerminado) -> Result<(), Box<dyn Error>> {
+    let email = job.email.as_ref().map(|e| e.trim().to_lowercase()).filter(|e| !e.is_empty());
+    let (params_json, progreso_json, resultado_json) = (job.params.to_string(), job.progreso.to_string(), job.resultado.to_string());
+    match conn {
+        AnalyticsConn::Sqlite(c) => {
+            c.execute(
+                "INSERT OR REPLACE INTO solve_jobs (id, creado, terminado, email, estado, status, params_json, progreso_json, resultado_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![job.id, job.creado, job.terminado, email, job.estado, job.status, params_json, progreso_json, resultado_json],
+            )?;
+        }
+        AnalyticsConn::PostgresConfig(url) => {
+            let j = job.clone();
+            con_postgres(url, move |client| {
+                client.execute(
+                    "INSERT INTO solve_jobs (id, creado, terminado, email, estado, status, params_json, progreso_json, resultado_json)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                     ON CONFLICT (id) DO UPDATE SET terminado = EXCLUDED.terminado, estado = EXCLUDED.estado,
+                         status = EXCLUDED.status, progreso_json = EXCLUDED.progreso_json, resultado_json = EXCLUDED.resultado_json",
+                    &[&j.id, &j.creado, &j.terminado, &email, &j.estado, &j.status, &params_json, &progreso_json, &resultado_json],
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+type FilaJob = (String, String, String, Option<String>, String, i64, String, String, String);
+
+/// Job terminado por id
+pub fn obtener_job(conn: &AnalyticsConn, id: &str) -> Result<Option<JobTerminado>, Box<dyn Error>> {
+    const SQL_SQLITE: &str = "SELECT id, creado, terminado, email, estado, status, params_json, progreso_json, resultado_json FROM solve_jobs WHERE id = ?1";
+    const SQL_PG: &str = "SELECT id, creado, terminado, email, estado, status, params_json, progreso_json, resultado_json FROM solve_jobs WHERE id = $1";
+    let id = id.trim().to_string();
+    let fila: Option<FilaJob> = match conn {
+        AnalyticsConn::Sqlite(c) => c
+            .query_row(SQL_SQLITE, params![id], |r| Ok((
+                r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, r.get(5)?, r.get(6)?, r.get(7)?, r.get(8)?,
+            )))
+            .optional()?,
+        AnalyticsConn::PostgresConfig(url) => con_postgres(url, move |client| {
+            let rows = client.query(SQL_PG, &[&id])?;
+            Ok(rows.first().map(|r| (r.get(0), r.get(1), r.get(2), r.get(3), r.get(4), r.get(5), r.get(6), r.get(7), r.get(8))))
+        })?,
+    };
+    let json = |s: &str| serde_json::from_str(s).unwrap_or(Value::Null);
+    Ok(fila.map(|(id, creado, terminado, email, estado, status, params_json, progreso_json, resultado_json)| JobTerminado {
+        id,
+        creado,
+        terminado,
+        email,
+        estado,
+        status,
+        params: json(&params_json),
+        progreso: json(&progreso_json),
+        resultado: json(&resultado_json),
+    }))
+}
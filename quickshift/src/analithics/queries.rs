@@ -1,21 +1,44 @@
 use rusqlite::Connection;
 use std::error::Error;
 use chrono::Utc;
+use crate::analithics::db::{con_postgres, AnalyticsConn, QueryLog};
+use crate::scoring::Historial;
+use std::collections::HashSet;
+use crate::analithics::storage;
+
+/// Valores no nulos de una columna de texto de `queries`
+fn no_nulos(filas: &[QueryLog], columna: fn(&QueryLog) -> &Option<String>) -> impl Iterator<Item = &str> {
+    filas.iter().filter_map(move |q| columna(q).as_deref())
+}
+
+/// Filtros que cuenta `filtros_mas_solicitados`
+const FILTROS_CONTADOS: [&str; 5] = [
+    "dias_horarios_libres",
+    "ventana_entre_actividades",
+    "preferencias_profesores",
+    "balance_lineas",
+    "tiempo_traslado",
+];
+
+/// Si `filtros` trae el filtro `clave` con `habilitado: true`
+fn filtro_habilitado(filtros: &serde_json::Value, clave: &str) -> bool {
+    filtros.get(clave).and_then(|f| f.get("habilitado")).and_then(|x| x.as_bool()).unwrap_or(false)
+}
+
+/// (email, student_ranking, ts) de las consultas que traen ambos
+fn rankings(filas: &[QueryLog]) -> impl Iterator<Item = (&str, f64, &str)> {
+    filas.iter().filter_map(|q| Some((q.email.as_deref()?, q.student_ranking?, q.ts.as_str())))
+}
 
 /// Return a JSON array with the most passed courses across all recorded queries.
 pub fn ramos_mas_pasados(limit: Option<usize>) -> Result<serde_json::Value, Box<dyn Error>> {
     use std::collections::HashMap;
-    let db_path = std::path::Path::new("analithics").join("analytics.db");
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT ramos_pasados FROM queries WHERE ramos_pasados IS NOT NULL")?;
-    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let filas = storage::abrir()?.queries()?;
     let mut counts: HashMap<String, usize> = HashMap::new();
-    for r in rows {
-        if let Ok(s) = r {
-            if let Ok(vec) = serde_json::from_str::<Vec<String>>(&s) {
-                for code in vec {
-                    *counts.entry(code).or_default() += 1;
-                }
+    for s in no_nulos(&filas, |q| &q.ramos_pasados) {
+        if let Ok(vec) = serde_json::from_str::<Vec<String>>(s) {
+            for code in vec {
+                *counts.entry(code).or_default() += 1;
             }
         }
     }
@@ -33,22 +56,17 @@ pub fn ramos_mas_pasados(limit: Option<usize>) -> Result<serde_json::Value, Box<
 pub fn ranking_por_estudiante() -> Result<serde_json::Value, Box<dyn Error>> {
     use std::collections::HashMap;
     use chrono::DateTime;
-    let db_path = std::path::Path::new("analithics").join("analytics.db");
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT email, student_ranking, ts FROM queries WHERE email IS NOT NULL AND student_ranking IS NOT NULL")?;
-    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?, row.get::<_, String>(2)?)))?;
+    let filas = storage::abrir()?.queries()?;
     let mut latest: HashMap<String, (f64, DateTime<Utc>)> = HashMap::new();
-    for r in rows {
-        if let Ok((email, rank, ts)) = r {
-            if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
-                match latest.get(&email) {
-                    Some((_, existing_dt)) => {
-                        if &dt > existing_dt {
-                            latest.insert(email, (rank, dt));
-                        }
+    for (email, rank, ts) in rankings(&filas) {
+        if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
+            match latest.get(email) {
+                Some((_, existing_dt)) => {
+                    if &dt > existing_dt {
+                        latest.insert(email.to_string(), (rank, dt));
                     }
-                    None => { latest.insert(email, (rank, dt)); }
                 }
+                None => { latest.insert(email.to_string(), (rank, dt)); }
             }
         }
     }
@@ -59,12 +77,8 @@ pub fn ranking_por_estudiante() -> Result<serde_json::Value, Box<dyn Error>> {
 }
 
 pub fn count_users() -> Result<serde_json::Value, Box<dyn Error>> {
-    let db_path = std::path::Path::new("analithics").join("analytics.db");
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT DISTINCT email FROM queries WHERE email IS NOT NULL")?;
-    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
-    let mut cnt: usize = 0;
-    for _ in rows { cnt += 1; }
+    let filas = storage::abrir()?.queries()?;
+    let cnt = no_nulos(&filas, |q| &q.email).collect::<std::collections::HashSet<_>>().len();
     let result = serde_json::json!({"count_users": cnt});
     let _ = crate::analithics::save_report("count_users", "{}", &result.to_string());
     Ok(result)
@@ -72,35 +86,12 @@ pub fn count_users() -> Result<serde_json::Value, Box<dyn Error>> {
 
 pub fn filtros_mas_solicitados() -> Result<serde_json::Value, Box<dyn Error>> {
     use std::collections::HashMap;
-    let db_path = std::path::Path::new("analithics").join("analytics.db");
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT filtros_json FROM queries WHERE filtros_json IS NOT NULL")?;
-    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let filas = storage::abrir()?.queries()?;
     let mut counts: HashMap<String, usize> = HashMap::new();
-    for r in rows {
-        if let Ok(s) = r {
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) {
-                if let Some(dhl) = v.get("dias_horarios_libres") {
-                    if dhl.get("habilitado").and_then(|x| x.as_bool()).unwrap_or(false) {
-                        *counts.entry("dias_horarios_libres".to_string()).or_default() += 1;
-                    }
-                }
-                if let Some(vent) = v.get("ventana_entre_actividades") {
-                    if vent.get("habilitado").and_then(|x| x.as_bool()).unwrap_or(false) {
-                        *counts.entry("ventana_entre_actividades".to_string()).or_default() += 1;
-                    }
-                }
-                if let Some(pref) = v.get("preferencias_profesores") {
-                    if pref.get("habilitado").and_then(|x| x.as_bool()).unwrap_or(false) {
-                        *counts.entry("preferencias_profesores".to_string()).or_default() += 1;
-                    }
-                }
-                if let Some(bal) = v.get("balance_lineas") {
-                    if bal.get("habilitado").and_then(|x| x.as_bool()).unwrap_or(false) {
-                        *counts.entry("balance_lineas".to_string()).or_default() += 1;
-                    }
-                }
-            }
+    for s in no_nulos(&filas, |q| &q.filtros_json) {
+        let Ok(v) = serde_json::from_str::<serde_json::Value>(s) else { continue };
+        for clave in FILTROS_CONTADOS.iter().filter(|c| filtro_habilitado(&v, c)) {
+            *counts.entry(clave.to_string()).or_default() += 1;
         }
     }
     let mut vec: Vec<(String, usize)> = counts.into_iter().collect();
@@ -113,18 +104,13 @@ pub fn filtros_mas_solicitados() -> Result<serde_json::Value, Box<dyn Error>> {
 
 pub fn ramos_mas_recomendados(limit: Option<usize>) -> Result<serde_json::Value, Box<dyn Error>> {
     use std::collections::HashMap;
-    let db_path = std::path::Path::new("analithics").join("analytics.db");
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT response_json FROM queries WHERE response_json IS NOT NULL")?;
-    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let filas = storage::abrir()?.queries()?;
     let mut counts: HashMap<String, usize> = HashMap::new();
-    for r in rows {
-        if let Ok(s) = r {
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) {
-                if let Some(soluciones) = v.get("soluciones").and_then(|x| x.as_array()) {
-                    for sol in soluciones { extract_codes_from_value(sol, &mut counts); }
-                } else { extract_codes_from_value(&v, &mut counts); }
-            }
+    for s in no_nulos(&filas, |q| &q.response_json) {
+        if let Ok(v) = serde_json::from_str::<serde_json::Value>(s) {
+            if let Some(soluciones) = v.get("soluciones").and_then(|x| x.as_array()) {
+                for sol in soluciones { extract_codes_from_value(sol, &mut counts); }
+            } else { extract_codes_from_value(&v, &mut counts); }
         }
     }
     let mut vec: Vec<(String, usize)> = counts.into_iter().collect();
@@ -169,16 +155,11 @@ fn extract_codes_from_value(v: &serde_json::Value, counts: &mut std::collections
 /// Extrae profesores y los cursos que imparten desde los `response_json` guardados.
 pub fn profesores_y_cursos() -> Result<serde_json::Value, Box<dyn Error>> {
     use std::collections::{HashMap, HashSet};
-    let db_path = std::path::Path::new("analithics").join("analytics.db");
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT response_json FROM queries WHERE response_json IS NOT NULL")?;
-    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let filas = storage::abrir()?.queries()?;
     let mut map: HashMap<String, HashSet<String>> = HashMap::new();
-    for r in rows {
-        if let Ok(s) = r {
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) {
-                extract_professor_courses(&v, &mut map);
-            }
+    for s in no_nulos(&filas, |q| &q.response_json) {
+        if let Ok(v) = serde_json::from_str::<serde_json::Value>(s) {
+            extract_professor_courses(&v, &mut map);
         }
     }
     let mut arr: Vec<serde_json::Value> = Vec::new();
@@ -218,11 +199,11 @@ fn extract_professor_courses(v: &serde_json::Value, map: &mut std::collections::
 pub fn cursos_por_malla(malla: &str) -> Result<serde_json::Value, Box<dyn Error>> {
     use std::collections::HashSet;
     // Intentar leer oferta académica desde excel
-    let secciones = crate::excel::leer_oferta_academica_excel(malla)?;
+    let secciones = crate::datafiles::cache::oferta(malla)?;
     let mut set: HashSet<String> = HashSet::new();
-    for s in secciones.into_iter() {
-        if !s.codigo.is_empty() { set.insert(s.codigo); }
-        else if !s.nombre.is_empty() { set.insert(s.nombre); }
+    for s in secciones.iter() {
+        if !s.codigo.is_empty() { set.insert(s.codigo.clone()); }
+        else if !s.nombre.is_empty() { set.insert(s.nombre.clone()); }
     }
     let mut vec: Vec<String> = set.into_iter().collect();
     vec.sort();
@@ -237,18 +218,14 @@ pub fn tasa_aprobacion_por_ramo(limit: Option<usize>) -> Result<serde_json::Valu
     use std::collections::HashMap;
     use chrono::DateTime;
     use chrono::Utc;
-    let db_path = std::path::Path::new("analithics").join("analytics.db");
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT email, ramos_pasados, ts FROM queries WHERE email IS NOT NULL AND ramos_pasados IS NOT NULL")?;
-    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?;
+    let filas = storage::abrir()?.queries()?;
     let mut latest: HashMap<String, (String, DateTime<Utc>)> = HashMap::new();
-    for r in rows {
-        if let Ok((email, ramos_json, ts)) = r {
-            if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
-                match latest.get(&email) {
-                    Some((_, existing_dt)) => { if &dt > existing_dt { latest.insert(email, (ramos_json, dt)); } }
-                    None => { latest.insert(email, (ramos_json, dt)); }
-                }
+    for q in &filas {
+        let (Some(email), Some(ramos_json)) = (&q.email, &q.ramos_pasados) else { continue };
+        if let Ok(dt) = q.ts.parse::<DateTime<Utc>>() {
+            match latest.get(email) {
+                Some((_, existing_dt)) => { if &dt > existing_dt { latest.insert(email.clone(), (ramos_json.clone(), dt)); } }
+                None => { latest.insert(email.clone(), (ramos_json.clone(), dt)); }
             }
         }
     }
@@ -277,18 +254,13 @@ pub fn promedio_ranking_y_stddev() -> Result<serde_json::Value, Box<dyn Error>>
     use std::collections::HashMap;
     use chrono::DateTime;
     use chrono::Utc;
-    let db_path = std::path::Path::new("analithics").join("analytics.db");
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT email, student_ranking, ts FROM queries WHERE email IS NOT NULL AND student_ranking IS NOT NULL")?;
-    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?, row.get::<_, String>(2)?)))?;
+    let filas = storage::abrir()?.queries()?;
     let mut latest: HashMap<String, (f64, DateTime<Utc>)> = HashMap::new();
-    for r in rows {
-        if let Ok((email, rank, ts)) = r {
-            if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
-                match latest.get(&email) {
-                    Some((_, existing_dt)) => { if &dt > existing_dt { latest.insert(email, (rank, dt)); } }
-                    None => { latest.insert(email, (rank, dt)); }
-                }
+    for (email, rank, ts) in rankings(&filas) {
+        if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
+            match latest.get(email) {
+                Some((_, existing_dt)) => { if &dt > existing_dt { latest.insert(email.to_string(), (rank, dt)); } }
+                None => { latest.insert(email.to_string(), (rank, dt)); }
             }
         }
     }
@@ -305,16 +277,11 @@ pub fn promedio_ranking_y_stddev() -> Result<serde_json::Value, Box<dyn Error>>
 
 pub fn horarios_mas_ocupados(limit: Option<usize>) -> Result<serde_json::Value, Box<dyn Error>> {
     use std::collections::HashMap;
-    let db_path = std::path::Path::new("analithics").join("analytics.db");
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT response_json FROM queries WHERE response_json IS NOT NULL")?;
-    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let filas = storage::abrir()?.queries()?;
     let mut counts: HashMap<String, usize> = HashMap::new();
-    for r in rows {
-        if let Ok(s) = r {
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) {
-                extract_horarios_from_value(&v, &mut counts);
-            }
+    for s in no_nulos(&filas, |q| &q.response_json) {
+        if let Ok(v) = serde_json::from_str::<serde_json::Value>(s) {
+            extract_horarios_from_value(&v, &mut counts);
         }
     }
     let mut vec: Vec<(String, usize)> = counts.into_iter().collect();
@@ -347,16 +314,11 @@ fn extract_horarios_from_value(v: &serde_json::Value, counts: &mut std::collecti
 /// Horarios más recomendados ponderando por el `total_score` de cada solución
 pub fn horarios_mas_recomendados(limit: Option<usize>) -> Result<serde_json::Value, Box<dyn Error>> {
     use std::collections::HashMap;
-    let db_path = std::path::Path::new("analithics").join("analytics.db");
-    let conn = Connection::open(db_path)?;
-    let mut stmt = conn.prepare("SELECT response_json FROM queries WHERE response_json IS NOT NULL")?;
-    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let filas = storage::abrir()?.queries()?;
     let mut scores: HashMap<String, i64> = HashMap::new();
-    for r in rows {
-        if let Ok(s) = r {
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) {
-                extract_horarios_weighted_from_value(&v, &mut scores);
-            }
+    for s in no_nulos(&filas, |q| &q.response_json) {
+        if let Ok(v) = serde_json::from_str::<serde_json::Value>(s) {
+            extract_horarios_weighted_from_value(&v, &mut scores);
         }
     }
     let mut vec: Vec<(String, i64)> = scores.into_iter().collect();
@@ -397,3 +359,267 @@ fn extract_horarios_weighted_from_value(v: &serde_json::Value, scores: &mut std:
         _ => {}
     }
 }
+
+/// Historial de confirmaciones por sección (ver `scoring::HistorialSeccion`):
+/// por cada conjunto de soluciones que un estudiante confirmó (la última
+/// confirmación de cada email en cada conjunto), cada `codigo_box` que aparece
+/// en alguna solución del conjunto cuenta como ofrecido, y los de la solución
+/// elegida además como confirmados.
+pub fn historial_secciones_con(conn: &AnalyticsConn) -> Result<Historial, Box<dyn Error>> {
+    const SQL: &str = "SELECT ss.indice, s.soluciones_json FROM student_solutions ss \
+        JOIN solution_sets s ON s.id = ss.solution_id \
+        WHERE ss.id IN (SELECT MAX(id) FROM student_solutions GROUP BY email, solution_id)";
+    let filas: Vec<(i64, String)> = match conn {
+        AnalyticsConn::Sqlite(c) => {
+            let mut stmt = c.prepare(SQL)?;
+            let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        }
+        AnalyticsConn::PostgresConfig(url) => con_postgres(url, move |client| {
+            let rows = client.query(SQL, &[])?;
+            Ok(rows.iter().map(|r| (r.get(0), r.get(1))).collect())
+        })?,
+    };
+
+    let cajas = |solucion: &serde_json::Value| -> HashSet<String> {
+        solucion.get("secciones").and_then(|s| s.as_array()).into_iter().flatten()
+            .filter_map(|s| s.get("codigo_box").and_then(|c| c.as_str()))
+            .map(|c| c.trim().to_uppercase())
+            .filter(|c| !c.is_empty())
+            .collect()
+    };
+    let mut historial = Historial::new();
+    for (indice, soluciones_json) in filas {
+        let Ok(serde_json::Value::Array(soluciones)) = serde_json::from_str(&soluciones_json) else { continue };
+        let elegida = usize::try_from(indice).ok().and_then(|i| soluciones.get(i)).map(cajas).unwrap_or_default();
+        let ofrecidas: HashSet<String> = soluciones.iter().flat_map(cajas).collect();
+        for caja in ofrecidas {
+            let h = historial.entry(caja.clone()).or_default();
+            h.ofrecida += 1;
+            if elegida.contains(&caja) {
+                h.confirmada += 1;
+            }
+        }
+    }
+    Ok(historial)
+}
+
+/// `historial_secciones_con` sobre la DB de analíticas. Es la fuente que el
+/// servidor registra en `scoring` para `usar_historial`.
+pub fn historial_secciones() -> Result<Historial, String> {
+    let conn = crate::analithics::db::open_analytics_connection().map_err(|e| e.to_string())?;
+    historial_secciones_con(&conn).map_err(|e| e.to_string())
+}
+
+/// Agrupación temporal de `GET /analytics/dashboard`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    Day,
+    Week,
+}
+
+impl Bucket {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "day" | "dia" => Some(Bucket::Day),
+            "week" | "semana" => Some(Bucket::Week),
+            _ => None,
+        }
+    }
+
+    /// Expresión SQL con el inicio del bucket (`YYYY-MM-DD`; las semanas parten el lunes)
+    fn sql(&self) -> &'static str {
+        match self {
+            Bucket::Day => "date(substr(ts, 1, 10))",
+            Bucket::Week => "date(substr(ts, 1, 10), 'weekday 0', '-6 days')",
+        }
+    }
+
+    /// Inicio del bucket de un `ts` (igual que `sql`); None si no empieza con una fecha
+    fn inicio(&self, ts: &str) -> Option<String> {
+        let dia = chrono::NaiveDate::parse_from_str(ts.get(..10)?, "%Y-%m-%d").ok()?;
+        let inicio = match self {
+            Bucket::Day => dia,
+            Bucket::Week => dia - chrono::Duration::days(chrono::Datelike::weekday(&dia).num_days_from_monday() as i64),
+        };
+        Some(inicio.format("%Y-%m-%d").to_string())
+    }
+}
+
+/// Rango de fechas del dashboard en formato RFC 3339 UTC: `[desde, hasta)`.
+/// `hasta` como `YYYY-MM-DD` incluye ese día completo.
+pub fn rango_dashboard(from: Option<&str>, to: Option<&str>) -> Result<(String, String), String> {
+    let desde = match from {
+        Some(f) => crate::analithics::db::parse_fecha_corte(f).ok_or_else(|| format!("invalid 'from': '{}'", f))?,
+        None => String::new(),
+    };
+    let hasta = match to {
+        Some(t) => match chrono::NaiveDate::parse_from_str(t.trim(), "%Y-%m-%d") {
+            Ok(d) => d.succ_opt()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc().to_rfc3339())
+                .ok_or_else(|| format!("invalid 'to': '{}'", t))?,
+            Err(_) => crate::analithics::db::parse_fecha_corte(t).ok_or_else(|| format!("invalid 'to': '{}'", t))?,
+        },
+        // Mayor que cualquier `ts` RFC 3339
+        None => "9999".to_string(),
+    };
+    Ok((desde, hasta))
+}
+
+/// Dashboard sobre la base de analíticas configurada (ver `Storage::dashboard` y `rango_dashboard`)
+pub fn dashboard(desde: &str, hasta: &str, bucket: Bucket) -> Result<serde_json::Value, Box<dyn Error>> {
+    let result = storage::abrir()?.dashboard(desde, hasta, bucket)?;
+    let params = serde_json::json!({"from": desde, "to": hasta, "bucket": format!("{:?}", bucket).to_lowercase()});
+    let _ = crate::analithics::save_report("dashboard", &params.to_string(), &result.to_string());
+    Ok(result)
+}
+
+/// Métricas de las consultas con `desde <= ts < hasta`, en un solo documento:
+/// consultas y latencia promedio por bucket, ramos prioritarios más pedidos,
+/// filtros habilitados más usados, latencia global y distribución de la
+/// cantidad de soluciones devueltas. Versión SQL para SQLite; los demás
+/// backends usan `resumen_dashboard`, que produce el mismo documento.
+pub fn dashboard_con(conn: &Connection, desde: &str, hasta: &str, bucket: Bucket) -> Result<serde_json::Value, Box<dyn Error>> {
+    use rusqlite::params;
+    const RANGO: &str = "q.ts >= ?1 AND q.ts < ?2";
+
+    let sql_buckets = format!(
+        "SELECT {} AS b, COUNT(*), AVG(duration_ms) FROM queries q WHERE {} GROUP BY b ORDER BY b",
+        bucket.sql(), RANGO
+    );
+    let mut stmt = conn.prepare(&sql_buckets)?;
+    let buckets: Vec<serde_json::Value> = stmt
+        .query_map(params![desde, hasta], |r| {
+            Ok(serde_json::json!({
+                "bucket": r.get::<_, Option<String>>(0)?,
+                "queries": r.get::<_, i64>(1)?,
+                "avg_duration_ms": r.get::<_, Option<f64>>(2)?,
+            }))
+        })?
+        .collect::<Result<_, _>>()?;
+
+    let (total, avg_ms, max_ms): (i64, Option<f64>, Option<i64>) = conn.query_row(
+        &format!("SELECT COUNT(*), AVG(duration_ms), MAX(duration_ms) FROM queries q WHERE {}", RANGO),
+        params![desde, hasta],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    )?;
+
+    let top = |sql: String| -> Result<Vec<(String, i64)>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![desde, hasta], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        rows.collect()
+    };
+    let top_ramos = top(format!(
+        "SELECT j.value, COUNT(*) AS n FROM queries q, json_each(q.ramos_prioritarios) j
+         WHERE json_valid(q.ramos_prioritarios) AND {} GROUP BY j.value ORDER BY n DESC, j.value LIMIT 10",
+        RANGO
+    ))?;
+    let top_filtros = top(format!(
+        "SELECT f.key, COUNT(*) AS n FROM queries q, json_each(q.filtros_json) f
+         WHERE json_valid(q.filtros_json) AND json_type(f.value) = 'object'
+           AND json_extract(f.value, '$.habilitado') = 1 AND {} GROUP BY f.key ORDER BY n DESC, f.key",
+        RANGO
+    ))?;
+    let soluciones = top(format!(
+        "SELECT CASE
+                WHEN n IS NULL THEN 'desconocido'
+                WHEN n = 0 THEN '0'
+                WHEN n <= 5 THEN '1-5'
+                WHEN n <= 10 THEN '6-10'
+                WHEN n <= 20 THEN '11-20'
+                ELSE '>20' END AS rango, COUNT(*)
+         FROM (SELECT CASE WHEN json_valid(q.response_json) THEN json_extract(q.response_json, '$.soluciones_count') END AS n
+               FROM queries q WHERE {})
+         GROUP BY rango ORDER BY MIN(COALESCE(n, -1))",
+        RANGO
+    ))?;
+
+    Ok(serde_json::json!({
+        "bucket": format!("{:?}", bucket).to_lowercase(),
+        "total_queries": total,
+        "queries_per_bucket": buckets,
+        "top_ramos": top_ramos.into_iter().map(|(r, c)| serde_json::json!({"ramo": r, "count": c})).collect::<Vec<_>>(),
+        "top_filtros": top_filtros.into_iter().map(|(f, c)| serde_json::json!({"filter": f, "count": c})).collect::<Vec<_>>(),
+        "latency_ms": {"avg": avg_ms, "max": max_ms},
+        "soluciones_count_distribution": soluciones.into_iter().map(|(r, c)| serde_json::json!({"range": r, "count": c})).collect::<Vec<_>>(),
+    }))
+}
+
+/// Rango de `soluciones_count` de una respuesta guardada (mismos rangos que `dashboard_con`)
+fn rango_soluciones(response_json: Option<&str>) -> (i32, &'static str) {
+    let n = response_json
+        .and_then(|r| serde_json::from_str::<serde_json::Value>(r).ok())
+        .and_then(|v| v.get("soluciones_count").and_then(|n| n.as_f64()));
+    match n {
+        None => (0, "desconocido"),
+        Some(n) if n <= 0.0 => (1, "0"),
+        Some(n) if n <= 5.0 => (2, "1-5"),
+        Some(n) if n <= 10.0 => (3, "6-10"),
+        Some(n) if n <= 20.0 => (4, "11-20"),
+        Some(_) => (5, ">20"),
+    }
+}
+
+/// Conteos de mayor a menor (a igual conteo, por nombre)
+fn ordenar_conteos(conteos: std::collections::HashMap<String, i64>) -> Vec<(String, i64)> {
+    let mut v: Vec<(String, i64)> = conteos.into_iter().collect();
+    v.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    v
+}
+
+/// El documento de `dashboard_con` calculado sobre filas ya filtradas por
+/// rango, para backends sin las funciones JSON de SQLite (Postgres)
+pub fn resumen_dashboard(filas: &[QueryLog], bucket: Bucket) -> serde_json::Value {
+    use std::collections::{BTreeMap, HashMap};
+    let promedio = |v: &[i64]| (!v.is_empty()).then(|| v.iter().sum::<i64>() as f64 / v.len() as f64);
+
+    let mut por_bucket: BTreeMap<Option<String>, (i64, Vec<i64>)> = BTreeMap::new();
+    let mut ramos: HashMap<String, i64> = HashMap::new();
+    let mut filtros: HashMap<String, i64> = HashMap::new();
+    let mut soluciones: BTreeMap<(i32, &'static str), i64> = BTreeMap::new();
+    for q in filas {
+        let b = por_bucket.entry(bucket.inicio(&q.ts)).or_default();
+        b.0 += 1;
+        b.1.extend(q.duration_ms);
+
+        let json = |c: &Option<String>| c.as_deref().and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok());
+        match json(&q.ramos_prioritarios) {
+            Some(serde_json::Value::Array(v)) => v.iter().for_each(|r| *ramos.entry(valor_texto(r)).or_default() += 1),
+            Some(serde_json::Value::Object(m)) => m.values().for_each(|r| *ramos.entry(valor_texto(r)).or_default() += 1),
+            _ => {}
+        }
+        if let Some(serde_json::Value::Object(m)) = json(&q.filtros_json) {
+            for (k, v) in &m {
+                let habilitado = v.get("habilitado").map(|h| h.as_bool() == Some(true) || h.as_f64() == Some(1.0)).unwrap_or(false);
+                if v.is_object() && habilitado {
+                    *filtros.entry(k.clone()).or_default() += 1;
+                }
+            }
+        }
+        *soluciones.entry(rango_soluciones(q.response_json.as_deref())).or_default() += 1;
+    }
+    let duraciones: Vec<i64> = filas.iter().filter_map(|q| q.duration_ms).collect();
+
+    serde_json::json!({
+        "bucket": format!("{:?}", bucket).to_lowercase(),
+        "total_queries": filas.len(),
+        "queries_per_bucket": por_bucket.into_iter().map(|(b, (n, d))| serde_json::json!({
+            "bucket": b,
+            "queries": n,
+            "avg_duration_ms": promedio(&d),
+        })).collect::<Vec<_>>(),
+        "top_ramos": ordenar_conteos(ramos).into_iter().take(10).map(|(r, c)| serde_json::json!({"ramo": r, "count": c})).collect::<Vec<_>>(),
+        "top_filtros": ordenar_conteos(filtros).into_iter().map(|(f, c)| serde_json::json!({"filter": f, "count": c})).collect::<Vec<_>>(),
+        "latency_ms": {"avg": promedio(&duraciones), "max": duraciones.iter().max()},
+        "soluciones_count_distribution": soluciones.into_iter().map(|((_, r), c)| serde_json::json!({"range": r, "count": c})).collect::<Vec<_>>(),
+    })
+}
+
+/// Texto de un elemento JSON como lo devuelve `json_each(...).value` en SQLite
+fn valor_texto(v: &serde_json::Value) -> String {
+    match v {
+        serde_json::Value::String(s) => s.clone(),
+        otro => otro.to_string(),
+    }
+}
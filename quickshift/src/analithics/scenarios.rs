@@ -0,0 +1,224 @@
+//! Escenarios con nombre por estudiante ("plan con tesis en 2026", "sin viernes").
+//!
+//! Cada guardado agrega una versión nueva en `student_scenarios`; nunca se
+//! sobrescribe. Restaurar una versión antigua también crea una versión nueva
+//! (con `restaurado_de`), así el historial queda completo.
+
+use crate::analithics::db::{con_postgres, AnalyticsConn};
+use chrono::Utc;
+use rusqlite::params;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::error::Error;
+
+/// Tabla de escenarios (SQLite). La crea `init_db`.
+pub const SQLITE_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS student_scenarios (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    email TEXT NOT NULL,
+    nombre TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    ts TEXT NOT NULL,
+    params_json TEXT NOT NULL,
+    restaurado_de INTEGER,
+    UNIQUE (email, nombre, version)
+)";
+
+/// Tabla de escenarios (Postgres). La crea `init_db`.
+pub const POSTGRES_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS student_scenarios (
+    id BIGSERIAL PRIMARY KEY,
+    email TEXT NOT NULL,
+    nombre TEXT NOT NULL,
+    version BIGINT NOT NULL,
+    ts TEXT NOT NULL,
+    params_json TEXT NOT NULL,
+    restaurado_de BIGINT,
+    UNIQUE (email, nombre, version)
+);";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EscenarioVersion {
+    pub email: String,
+    pub nombre: String,
+    pub version: i64,
+    pub ts: String,
+    pub params: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restaurado_de: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EscenarioResumen {
+    pub nombre: String,
+    pub version_actual: i64,
+    pub versiones: i64,
+    pub actualizado: String,
+}
+
+type FilaVersion = (String, String, i64, String, String, Option<i64>);
+
+fn a_version(fila: FilaVersion) -> EscenarioVersion {
+    let (email, nombre, version, ts, params_json, restaurado_de) = fila;
+    EscenarioVersion {
+        email,
+        nombre,
+        version,
+        ts,
+        params: serde_json::from_str(&params_json).unwrap_or(Value::Null),
+        restaurado_de,
+    }
+}
+
+/// Versiones de un escenario, de la más reciente a la más antigua
+fn filas_escenario(conn: &AnalyticsConn, email: &str, nombre: &str) -> Result<Vec<FilaVersion>, Box<dyn Error>> {
+    const SQL_SQLITE: &str = "SELECT email, nombre, version, ts, params_json, restaurado_de FROM student_scenarios WHERE email = ?1 AND nombre = ?2 ORDER BY version DESC";
+    const SQL_PG: &str = "SELECT email, nombre, version, ts, params_json, restaurado_de FROM student_scenarios WHERE email = $1 AND nombre = $2 ORDER BY version DESC";
+    let email = email.to_lowercase();
+    match conn {
+        AnalyticsConn::Sqlite(c) => {
+            let mut stmt = c.prepare(SQL_SQLITE)?;
+            let rows = stmt.query_map(params![email, nombre], |r| {
+                Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, r.get(5)?))
+            })?;
+            Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        }
+        AnalyticsConn::PostgresConfig(url) => {
+            let nombre = nombre.to_string();
+            con_postgres(url, move |client| {
+                let rows = client.query(SQL_PG, &[&email, &nombre])?;
+                Ok(rows.iter().map(|r| (r.get(0), r.get(1), r.get(2), r.get(3), r.get(4), r.get(5))).collect())
+            })
+        }
+    }
+}
+
+/// Intentos de `guardar_escenario` en Postgres si otro guardado concurrente
+/// tomó la misma versión
+const INTENTOS_GUARDAR: usize = 5;
+
+/// Guarda `params` como versión nueva del escenario `nombre`. La versión se
+/// calcula en el mismo `INSERT ... SELECT` que inserta la fila, así dos
+/// guardados concurrentes no leen el mismo máximo; en Postgres, si aun así
+/// chocan con `UNIQUE (email, nombre, version)`, se reintenta.
+pub fn guardar_escenario(
+    conn: &AnalyticsConn,
+    email: &str,
+    nombre: &str,
+    params: &Value,
+    restaurado_de: Option<i64>,
+) -> Result<EscenarioVersion, Box<dyn Error>> {
+    const SQL_SQLITE: &str = "INSERT INTO student_scenarios (email, nombre, version, ts, params_json, restaurado_de) \
+        SELECT ?1, ?2, COALESCE(MAX(version), 0) + 1, ?3, ?4, ?5 FROM student_scenarios WHERE email = ?1 AND nombre = ?2 \
+        RETURNING version";
+    const SQL_PG: &str = "INSERT INTO student_scenarios (email, nombre, version, ts, params_json, restaurado_de) \
+        SELECT $1, $2, COALESCE(MAX(version), 0) + 1, $3, $4, $5 FROM student_scenarios WHERE email = $1 AND nombre = $2 \
+        RETURNING version";
+    let email = email.to_lowercase();
+    let ts = Utc::now().to_rfc3339();
+    let params_json = params.to_string();
+    let version: i64 = match conn {
+        AnalyticsConn::Sqlite(c) => {
+            c.query_row(SQL_SQLITE, params![email, nombre, ts, params_json, restaurado_de], |r| r.get(0))?
+        }
+        AnalyticsConn::PostgresConfig(url) => {
+            let (email, nombre, ts, params_json) = (email.clone(), nombre.to_string(), ts.clone(), params_json.clone());
+            con_postgres(url, move |client| {
+                let mut intento = 1;
+                loop {
+                    match client.query_one(SQL_PG, &[&email, &nombre, &ts, &params_json, &restaurado_de]) {
+                        Ok(fila) => return Ok(fila.get(0)),
+                        Err(e) if intento < INTENTOS_GUARDAR && e.code() == Some(&postgres::error::SqlState::UNIQUE_VIOLATION) => {
+                            intento += 1;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            })?
+        }
+    };
+    Ok(EscenarioVersion { email, nombre: nombre.to_string(), version, ts, params: params.clone(), restaurado_de })
+}
+
+/// Escenarios de un estudiante con su versión vigente
+pub fn listar_escenarios(conn: &AnalyticsConn, email: &str) -> Result<Vec<EscenarioResumen>, Box<dyn Error>> {
+    const SQL_SQLITE: &str = "SELECT nombre, MAX(version), COUNT(*), MAX(ts) FROM student_scenarios WHERE email = ?1 GROUP BY nombre ORDER BY nombre";
+    const SQL_PG: &str = "SELECT nombre, MAX(version), COUNT(*), MAX(ts) FROM student_scenarios WHERE email = $1 GROUP BY nombre ORDER BY nombre";
+    let email = email.to_lowercase();
+    let filas: Vec<(String, i64, i64, String)> = match conn {
+        AnalyticsConn::Sqlite(c) => {
+            let mut stmt = c.prepare(SQL_SQLITE)?;
+            let rows = stmt.query_map(params![email], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        }
+        AnalyticsConn::PostgresConfig(url) => con_postgres(url, move |client| {
+            let rows = client.query(SQL_PG, &[&email])?;
+            Ok(rows.iter().map(|r| (r.get(0), r.get(1), r.get(2), r.get(3))).collect())
+        })?,
+    };
+    Ok(filas.into_iter()
+        .map(|(nombre, version_actual, versiones, actualizado)| EscenarioResumen { nombre, version_actual, versiones, actualizado })
+        .collect())
+}
+
+/// Historial completo de un escenario (más reciente primero)
+pub fn historial_escenario(conn: &AnalyticsConn, email: &str, nombre: &str) -> Result<Vec<EscenarioVersion>, Box<dyn Error>> {
+    Ok(filas_escenario(conn, email, nombre)?.into_iter().map(a_version).collect())
+}
+
+/// Una versión concreta del escenario; sin `version`, la vigente
+pub fn obtener_escenario(conn: &AnalyticsConn, email: &str, nombre: &str, version: Option<i64>) -> Result<Option<EscenarioVersion>, Box<dyn Error>> {
+    Ok(filas_escenario(conn, email, nombre)?
+        .into_iter()
+        .find(|f| version.map(|v| f.2 == v).unwrap_or(true))
+        .map(a_version))
+}
+
+/// Restaura `version` creando una versión nueva con su contenido.
+/// Devuelve `None` si la versión no existe.
+pub fn restaurar_escenario(conn: &AnalyticsConn, email: &str, nombre: &str, version: i64) -> Result<Option<EscenarioVersion>, Box<dyn Error>> {
+    match obtener_escenario(conn, email, nombre, Some(version))? {
+        Some(anterior) => Ok(Some(guardar_escenario(conn, email, nombre, &anterior.params, Some(version))?)),
+        None => Ok(None),
+    }
+}
+
+/// Diferencias campo a campo entre dos escenarios (`InputParams` serializados).
+/// Para listas de strings (`ramos_pasados`, `horarios_prohibidos`, ...) informa
+/// qué se agregó y qué se quitó; para el resto, el valor antes y después.
+pub fn diff_escenarios(a: &Value, b: &Value) -> Value {
+    let vacio = serde_json::Map::new();
+    let obj_a = a.as_object().unwrap_or(&vacio);
+    let obj_b = b.as_object().unwrap_or(&vacio);
+    let mut campos: Vec<&String> = obj_a.keys().chain(obj_b.keys()).collect();
+    campos.sort();
+    campos.dedup();
+
+    let mut cambios = Vec::new();
+    for campo in campos {
+        let va = obj_a.get(campo).unwrap_or(&Value::Null);
+        let vb = obj_b.get(campo).unwrap_or(&Value::Null);
+        if va == vb {
+            continue;
+        }
+        let como_strings = |v: &Value| -> Option<Vec<String>> {
+            match v {
+                Value::Array(items) => items.iter().map(|i| i.as_str().map(|s| s.to_string())).collect(),
+                Value::Null => Some(Vec::new()),
+                _ => None,
+            }
+        };
+        match (como_strings(va), como_strings(vb)) {
+            (Some(la), Some(lb)) => {
+                let agregados: Vec<&String> = lb.iter().filter(|x| !la.contains(x)).collect();
+                let quitados: Vec<&String> = la.iter().filter(|x| !lb.contains(x)).collect();
+                if agregados.is_empty() && quitados.is_empty() {
+                    // Mismos elementos en otro orden (p.ej. prioridades reordenadas)
+                    cambios.push(json!({"campo": campo, "antes": va, "despues": vb}));
+                } else {
+                    cambios.push(json!({"campo": campo, "agregados": agregados, "quitados": quitados}));
+                }
+            }
+            _ => cambios.push(json!({"campo": campo, "antes": va, "despues": vb})),
+        }
+    }
+    json!({"iguales": cambios.is_empty(), "cambios": cambios})
+}
@@ -0,0 +1,169 @@
+//! Conjuntos de soluciones devueltos por `/solve`, con un UUID estable.
+//!
+//! Cada respuesta de `/solve` se guarda completa en `solution_sets` (params
+//! de entrada y soluciones serializadas) para poder consultarla o exportarla
+//! después (`GET /solutions/{id}`, `GET /solutions/{id}/export/ics`). Cuando el
+//! estudiante confirma una de las soluciones se agrega una fila en
+//! `student_solutions`; la más reciente es la elegida vigente.
+
+use crate::analithics::db::{con_postgres, AnalyticsConn};
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+
+/// Tablas de soluciones (SQLite). Las crea `init_db`.
+pub const SQLITE_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS solution_sets (
+    id TEXT PRIMARY KEY,
+    ts TEXT NOT NULL,
+    email TEXT,
+    params_json TEXT NOT NULL,
+    soluciones_json TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS student_solutions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    email TEXT NOT NULL,
+    solution_id TEXT NOT NULL,
+    indice INTEGER NOT NULL,
+    ts TEXT NOT NULL
+);";
+
+/// Tablas de soluciones (Postgres). Las crea `init_db`.
+pub const POSTGRES_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS solution_sets (
+    id TEXT PRIMARY KEY,
+    ts TEXT NOT NULL,
+    email TEXT,
+    params_json TEXT NOT NULL,
+    soluciones_json TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS student_solutions (
+    id BIGSERIAL PRIMARY KEY,
+    email TEXT NOT NULL,
+    solution_id TEXT NOT NULL,
+    indice BIGINT NOT NULL,
+    ts TEXT NOT NULL
+);";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConjuntoSoluciones {
+    pub id: String,
+    pub ts: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub params: Value,
+    /// Arreglo `soluciones` tal como lo devolvió `/solve`
+    pub soluciones: Value,
+}
+
+impl ConjuntoSoluciones {
+    /// Solución `indice` del conjunto (0 = la mejor)
+    pub fn solucion(&self, indice: usize) -> Option<&Value> {
+        self.soluciones.as_array().and_then(|s| s.get(indice))
+    }
+}
+
+/// Solución confirmada por un estudiante
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SolucionElegida {
+    pub email: String,
+    pub solution_id: String,
+    pub indice: i64,
+    pub ts: String,
+}
+
+/// Guarda un conjunto de soluciones y devuelve su UUID
+pub fn guardar_soluciones(conn: &AnalyticsConn, email: Option<&str>, params: &Value, soluciones: &Value) -> Result<String, Box<dyn Error>> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let ts = Utc::now().to_rfc3339();
+    let email = email.map(|e| e.trim().to_lowercase()).filter(|e| !e.is_empty());
+    let (params_json, soluciones_json) = (params.to_string(), soluciones.to_string());
+    match conn {
+        AnalyticsConn::Sqlite(c) => {
+            c.execute(
+                "INSERT INTO solution_sets (id, ts, email, params_json, soluciones_json) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, ts, email, params_json, soluciones_json],
+            )?;
+        }
+        AnalyticsConn::PostgresConfig(url) => {
+            let id = id.clone();
+            con_postgres(url, move |client| {
+                client.execute(
+                    "INSERT INTO solution_sets (id, ts, email, params_json, soluciones_json) VALUES ($1, $2, $3, $4, $5)",
+                    &[&id, &ts, &email, &params_json, &soluciones_json],
+                )
+            })?;
+        }
+    }
+    Ok(id)
+}
+
+type FilaConjunto = (String, String, Option<String>, String, String);
+
+/// Conjunto de soluciones por UUID
+pub fn obtener_soluciones(conn: &AnalyticsConn, id: &str) -> Result<Option<ConjuntoSoluciones>, Box<dyn Error>> {
+    const SQL_SQLITE: &str = "SELECT id, ts, email, params_json, soluciones_json FROM solution_sets WHERE id = ?1";
+    const SQL_PG: &str = "SELECT id, ts, email, params_json, soluciones_json FROM solution_sets WHERE id = $1";
+    let id = id.trim().to_lowercase();
+    let fila: Option<FilaConjunto> = match conn {
+        AnalyticsConn::Sqlite(c) => c
+            .query_row(SQL_SQLITE, params![id], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)))
+            .optional()?,
+        AnalyticsConn::PostgresConfig(url) => con_postgres(url, move |client| {
+            let rows = client.query(SQL_PG, &[&id])?;
+            Ok(rows.first().map(|r| (r.get(0), r.get(1), r.get(2), r.get(3), r.get(4))))
+        })?,
+    };
+    Ok(fila.map(|(id, ts, email, params_json, soluciones_json)| ConjuntoSoluciones {
+        id,
+        ts,
+        email,
+        params: serde_json::from_str(&params_json).unwrap_or(Value::Null),
+        soluciones: serde_json::from_str(&soluciones_json).unwrap_or(Value::Null),
+    }))
+}
+
+/// Registra que `email` eligió la solución `indice` del conjunto `solution_id`
+pub fn vincular_solucion(conn: &AnalyticsConn, email: &str, solution_id: &str, indice: i64) -> Result<SolucionElegida, Box<dyn Error>> {
+    let elegida = SolucionElegida {
+        email: email.trim().to_lowercase(),
+        solution_id: solution_id.trim().to_lowercase(),
+        indice,
+        ts: Utc::now().to_rfc3339(),
+    };
+    match conn {
+        AnalyticsConn::Sqlite(c) => {
+            c.execute(
+                "INSERT INTO student_solutions (email, solution_id, indice, ts) VALUES (?1, ?2, ?3, ?4)",
+                params![elegida.email, elegida.solution_id, elegida.indice, elegida.ts],
+            )?;
+        }
+        AnalyticsConn::PostgresConfig(url) => {
+            let e = elegida.clone();
+            con_postgres(url, move |client| {
+                client.execute(
+                    "INSERT INTO student_solutions (email, solution_id, indice, ts) VALUES ($1, $2, $3, $4)",
+                    &[&e.email, &e.solution_id, &e.indice, &e.ts],
+                )
+            })?;
+        }
+    }
+    Ok(elegida)
+}
+
+/// Última solución confirmada por `email`
+pub fn solucion_elegida(conn: &AnalyticsConn, email: &str) -> Result<Option<SolucionElegida>, Box<dyn Error>> {
+    const SQL_SQLITE: &str = "SELECT email, solution_id, indice, ts FROM student_solutions WHERE email = ?1 ORDER BY id DESC LIMIT 1";
+    const SQL_PG: &str = "SELECT email, solution_id, indice, ts FROM student_solutions WHERE email = $1 ORDER BY id DESC LIMIT 1";
+    let email = email.trim().to_lowercase();
+    let fila: Option<(String, String, i64, String)> = match conn {
+        AnalyticsConn::Sqlite(c) => c
+            .query_row(SQL_SQLITE, params![email], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))
+            .optional()?,
+        AnalyticsConn::PostgresConfig(url) => con_postgres(url, move |client| {
+            let rows = client.query(SQL_PG, &[&email])?;
+            Ok(rows.first().map(|r| (r.get(0), r.get(1), r.get(2), r.get(3))))
+        })?,
+    };
+    Ok(fila.map(|(email, solution_id, indice, ts)| SolucionElegida { email, solution_id, indice, ts }))
+}
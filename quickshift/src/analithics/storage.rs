@@ -0,0 +1,170 @@
+//! Almacenamiento de las consultas y reportes de analíticas.
+//!
+//! `insertions` y `queries` trabajan contra el trait `Storage`, con una
+//! implementación SQLite (archivo local) y otra Postgres (para despliegues en
+//! contenedores, donde el archivo local se pierde en cada redeploy). El
+//! backend se elige con `DATABASE_URL` (o `ANALITHICS_DB_URL`): `postgres://`
+//! o `postgresql://` usan Postgres; `sqlite://`, `file://` o sin variable, el
+//! archivo SQLite (ver `db::open_analytics_connection`).
+
+use crate::analithics::db::{con_postgres, open_analytics_connection, AnalyticsConn, QueryLog};
+use crate::analithics::queries::{dashboard_con, resumen_dashboard, Bucket};
+use rusqlite::{params, Connection};
+use std::error::Error;
+
+/// Cota superior de cualquier `ts` RFC 3339, para leer todas las consultas
+pub const TS_MAX: &str = "9999";
+
+const COLUMNAS: &str = "id, ts, duration_ms, email, malla, student_ranking, ramos_pasados, ramos_prioritarios, filtros_json, request_json, response_json, client_ip, api_key";
+
+pub trait Storage {
+    /// "sqlite" o "postgres"
+    fn backend(&self) -> &'static str;
+
+    /// Inserta una fila en `queries` (el `id` lo asigna la base)
+    fn insertar_query(&self, q: &QueryLog) -> Result<(), Box<dyn Error>>;
+
+    /// Inserta una fila en `reports`
+    fn insertar_reporte(&self, ts: &str, query_type: &str, params_json: &str, result_json: &str) -> Result<(), Box<dyn Error>>;
+
+    /// Filas de `queries` con `desde <= ts < hasta`, en orden de inserción
+    fn queries_entre(&self, desde: &str, hasta: &str) -> Result<Vec<QueryLog>, Box<dyn Error>>;
+
+    /// Todas las filas de `queries`
+    fn queries(&self) -> Result<Vec<QueryLog>, Box<dyn Error>> {
+        self.queries_entre("", TS_MAX)
+    }
+
+    /// Métricas del dashboard (ver `queries::resumen_dashboard`)
+    fn dashboard(&self, desde: &str, hasta: &str, bucket: Bucket) -> Result<serde_json::Value, Box<dyn Error>> {
+        Ok(resumen_dashboard(&self.queries_entre(desde, hasta)?, bucket))
+    }
+}
+
+/// Backend SQLite
+pub struct SqliteStorage(pub Connection);
+
+/// Backend Postgres: cada operación abre su conexión en un hilo aparte (ver `db::con_postgres`)
+pub struct PostgresStorage {
+    pub url: String,
+}
+
+/// Storage de la base configurada
+pub fn abrir() -> Result<Box<dyn Storage>, Box<dyn Error>> {
+    Ok(desde_conexion(open_analytics_connection()?))
+}
+
+pub fn desde_conexion(conn: AnalyticsConn) -> Box<dyn Storage> {
+    match conn {
+        AnalyticsConn::Sqlite(c) => Box::new(SqliteStorage(c)),
+        AnalyticsConn::PostgresConfig(url) => Box::new(PostgresStorage { url }),
+    }
+}
+
+fn fila_sqlite(r: &rusqlite::Row) -> rusqlite::Result<QueryLog> {
+    Ok(QueryLog {
+        id: r.get(0)?,
+        ts: r.get(1)?,
+        duration_ms: r.get(2)?,
+        email: r.get(3)?,
+        malla: r.get(4)?,
+        student_ranking: r.get(5)?,
+        ramos_pasados: r.get(6)?,
+        ramos_prioritarios: r.get(7)?,
+        filtros_json: r.get(8)?,
+        request_json: r.get(9)?,
+        response_json: r.get(10)?,
+        client_ip: r.get(11)?,
+        api_key: r.get(12)?,
+    })
+}
+
+impl Storage for SqliteStorage {
+    fn backend(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn insertar_query(&self, q: &QueryLog) -> Result<(), Box<dyn Error>> {
+        self.0.execute(
+            "INSERT INTO queries (
+                ts, duration_ms, email, malla, student_ranking,
+                ramos_pasados, ramos_prioritarios, filtros_json,
+                request_json, response_json, client_ip, api_key
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                q.ts, q.duration_ms, q.email, q.malla, q.student_ranking,
+                q.ramos_pasados, q.ramos_prioritarios, q.filtros_json,
+                q.request_json, q.response_json, q.client_ip, q.api_key,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn insertar_reporte(&self, ts: &str, query_type: &str, params_json: &str, result_json: &str) -> Result<(), Box<dyn Error>> {
+        self.0.execute(
+            "INSERT INTO reports (ts, query_type, params_json, result_json) VALUES (?1, ?2, ?3, ?4)",
+            params![ts, query_type, params_json, result_json],
+        )?;
+        Ok(())
+    }
+
+    fn queries_entre(&self, desde: &str, hasta: &str) -> Result<Vec<QueryLog>, Box<dyn Error>> {
+        let mut stmt = self.0.prepare(&format!("SELECT {} FROM queries WHERE ts >= ?1 AND ts < ?2 ORDER BY id", COLUMNAS))?;
+        let filas = stmt.query_map(params![desde, hasta], fila_sqlite)?;
+        Ok(filas.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// En SQLite las agregaciones se hacen en SQL
+    fn dashboard(&self, desde: &str, hasta: &str, bucket: Bucket) -> Result<serde_json::Value, Box<dyn Error>> {
+        dashboard_con(&self.0, desde, hasta, bucket)
+    }
+}
+
+impl Storage for PostgresStorage {
+    fn backend(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn insertar_query(&self, q: &QueryLog) -> Result<(), Box<dyn Error>> {
+        let q = q.clone();
+        con_postgres(&self.url, move |client| {
+            client.execute(
+                "INSERT INTO queries (ts, duration_ms, email, malla, student_ranking, ramos_pasados, ramos_prioritarios, filtros_json, request_json, response_json, client_ip, api_key) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12)",
+                &[&q.ts, &q.duration_ms, &q.email, &q.malla, &q.student_ranking, &q.ramos_pasados, &q.ramos_prioritarios, &q.filtros_json, &q.request_json, &q.response_json, &q.client_ip, &q.api_key],
+            ).map(|_| ())
+        })
+    }
+
+    fn insertar_reporte(&self, ts: &str, query_type: &str, params_json: &str, result_json: &str) -> Result<(), Box<dyn Error>> {
+        let (ts, q, p, r) = (ts.to_string(), query_type.to_string(), params_json.to_string(), result_json.to_string());
+        con_postgres(&self.url, move |client| {
+            client.execute(
+                "INSERT INTO reports (ts, query_type, params_json, result_json) VALUES ($1,$2,$3,$4)",
+                &[&ts, &q, &p, &r],
+            ).map(|_| ())
+        })
+    }
+
+    fn queries_entre(&self, desde: &str, hasta: &str) -> Result<Vec<QueryLog>, Box<dyn Error>> {
+        let (desde, hasta) = (desde.to_string(), hasta.to_string());
+        let sql = format!("SELECT {} FROM queries WHERE ts >= $1 AND ts < $2 ORDER BY id", COLUMNAS);
+        con_postgres(&self.url, move |client| {
+            let rows = client.query(sql.as_str(), &[&desde, &hasta])?;
+            Ok(rows.iter().map(|r| QueryLog {
+                id: r.get(0),
+                ts: r.get(1),
+                duration_ms: r.get(2),
+                email: r.get(3),
+                malla: r.get(4),
+                student_ranking: r.get(5),
+                ramos_pasados: r.get(6),
+                ramos_prioritarios: r.get(7),
+                filtros_json: r.get(8),
+                request_json: r.get(9),
+                response_json: r.get(10),
+                client_ip: r.get(11),
+                api_key: r.get(12),
+            }).collect())
+        })
+    }
+}
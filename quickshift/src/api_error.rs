@@ -0,0 +1,295 @@
+// api_error.rs - Errores de la API HTTP con código legible por máquina
+//
+// Todos los handlers (`server_handlers` y `api_json::handlers`) responden los
+// errores con el mismo cuerpo JSON:
+//
+//   {"code": "MALLA_NOT_FOUND", "message": "...", "details": {...} | null}
+//
+// `code` es estable (los clientes pueden ramificar por él), `message` es
+// texto para humanos (en el idioma pedido con `lang`, ver `i18n`) y
+// `details` trae datos extra cuando los hay (malla pedida, session_id,
+// cuota, etc.). El status HTTP sale de la variante.
+
+use serde_json::{json, Value};
+use crate::i18n::{self, Idioma};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// Body que no es JSON válido o no calza con el esquema esperado
+    #[error("invalid JSON body: {0}")]
+    InvalidBody(String),
+    /// Parámetros con valores inválidos
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    /// Falta un parámetro obligatorio (query o body)
+    #[error("missing parameter '{0}'")]
+    MissingParameter(&'static str),
+    #[error("unauthorized: {0}")]
+    Unauthorized(String),
+    /// La malla pedida no existe entre los datafiles
+    #[error("malla '{malla}' not found")]
+    MallaNotFound { malla: String, available: Vec<String> },
+    #[error("student '{0}' not found")]
+    StudentNotFound(String),
+    /// Ningún profesor de la oferta tiene ese nombre
+    #[error("professor '{0}' not found")]
+    ProfessorNotFound(String),
+    #[error("scenario not found")]
+    ScenarioNotFound,
+    #[error("session '{0}' not found")]
+    SessionNotFound(String),
+    #[error("session '{0}' expired")]
+    SessionExpired(String),
+    #[error("solution '{0}' not found")]
+    SolutionNotFound(String),
+    #[error("job '{0}' not found")]
+    JobNotFound(String),
+    /// El job asíncrono todavía no termina (`estado` es `en_cola` o `ejecutando`)
+    #[error("job '{id}' has not finished")]
+    JobNotFinished { id: String, estado: &'static str },
+    #[error("file '{0}' not found")]
+    FileNotFound(String),
+    #[error("{0}")]
+    PayloadTooLarge(String),
+    /// Datafile subido o leído que no pasa la validación
+    #[error("{message}")]
+    InvalidDatafile { message: String, details: Option<Value> },
+    /// El solver no encontró ninguna combinación de secciones válida
+    #[error("no feasible schedule for the given input")]
+    NoFeasibleSchedule,
+    /// Las `secciones_fijas` no pueden ir en una misma solución; `details`
+    /// es el reporte de `algorithm::fijas::ConflictoFijas`
+    #[error("pinned sections cannot be scheduled together")]
+    PinnedSectionsInfeasible(Value),
+    /// El tope de ramos por día o el rango horario dejan sin soluciones; el
+    /// detalle es el reporte de `algorithm::carga_diaria::FiltrosInviables`
+    #[error("no schedule satisfies the daily course cap and class time window")]
+    DailyLimitsInfeasible(Value),
+    /// Los `ramos_obligatorios` no caben en una misma solución; el detalle es
+    /// el reporte de `algorithm::obligatorios::ConflictoObligatorios`
+    #[error("required courses cannot be scheduled together")]
+    RequiredCoursesInfeasible(Value),
+    #[error("rate limit exceeded")]
+    RateLimited { limit_per_min: u32, retry_after_secs: u64 },
+    /// Falla del pipeline de resolución (lectura de datos, PERT, clique)
+    #[error("solver error: {0}")]
+    Solver(String),
+    /// Falla de persistencia (analíticas, escenarios, archivos)
+    #[error("storage error: {0}")]
+    Storage(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl ApiError {
+    /// Código estable para que el cliente distinga el error
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::InvalidBody(_) => "INVALID_BODY",
+            ApiError::InvalidInput(_) => "INVALID_INPUT",
+            ApiError::MissingParameter(_) => "MISSING_PARAMETER",
+            ApiError::Unauthorized(_) => "UNAUTHORIZED",
+            ApiError::MallaNotFound { .. } => "MALLA_NOT_FOUND",
+            ApiError::StudentNotFound(_) => "STUDENT_NOT_FOUND",
+            ApiError::ProfessorNotFound(_) => "PROFESSOR_NOT_FOUND",
+            ApiError::ScenarioNotFound => "SCENARIO_NOT_FOUND",
+            ApiError::SessionNotFound(_) => "SESSION_NOT_FOUND",
+            ApiError::SessionExpired(_) => "SESSION_EXPIRED",
+            ApiError::SolutionNotFound(_) => "SOLUTION_NOT_FOUND",
+            ApiError::JobNotFound(_) => "JOB_NOT_FOUND",
+            ApiError::JobNotFinished { .. } => "JOB_NOT_FINISHED",
+            ApiError::FileNotFound(_) => "FILE_NOT_FOUND",
+            ApiError::PayloadTooLarge(_) => "PAYLOAD_TOO_LARGE",
+            ApiError::InvalidDatafile { .. } => "INVALID_DATAFILE",
+            ApiError::NoFeasibleSchedule => "NO_FEASIBLE_SCHEDULE",
+            ApiError::PinnedSectionsInfeasible(_) => "PINNED_SECTIONS_INFEASIBLE",
+            ApiError::DailyLimitsInfeasible(_) => "DAILY_LIMITS_INFEASIBLE",
+            ApiError::RequiredCoursesInfeasible(_) => "REQUIRED_COURSES_INFEASIBLE",
+            ApiError::RateLimited { .. } => "RATE_LIMITED",
+            ApiError::Solver(_) => "SOLVER_ERROR",
+            ApiError::Storage(_) => "STORAGE_ERROR",
+            ApiError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// Status HTTP de la variante
+    pub fn status(&self) -> u16 {
+        match self {
+            ApiError::InvalidBody(_) | ApiError::InvalidInput(_) | ApiError::MissingParameter(_) => 400,
+            ApiError::Unauthorized(_) => 401,
+            ApiError::MallaNotFound { .. }
+            | ApiError::StudentNotFound(_)
+            | ApiError::ProfessorNotFound(_)
+            | ApiError::ScenarioNotFound
+            | ApiError::SessionNotFound(_)
+            | ApiError::SessionExpired(_)
+            | ApiError::SolutionNotFound(_)
+            | ApiError::JobNotFound(_)
+            | ApiError::FileNotFound(_) => 404,
+            ApiError::JobNotFinished { .. } => 409,
+            ApiError::PayloadTooLarge(_) => 413,
+            ApiError::InvalidDatafile { .. } | ApiError::NoFeasibleSchedule | ApiError::PinnedSectionsInfeasible(_)
+                | ApiError::DailyLimitsInfeasible(_) | ApiError::RequiredCoursesInfeasible(_) => 422,
+            ApiError::RateLimited { .. } => 429,
+            ApiError::Solver(_) | ApiError::Storage(_) | ApiError::Internal(_) => 500,
+        }
+    }
+
+    /// Datos adicionales del error, si la variante los tiene
+    pub fn details(&self) -> Option<Value> {
+        match self {
+            ApiError::MissingParameter(p) => Some(json!({"parameter": p})),
+            ApiError::MallaNotFound { malla, available } if available.is_empty() => Some(json!({"malla": malla})),
+            ApiError::MallaNotFound { malla, available } => Some(json!({"malla": malla, "available": available})),
+            ApiError::StudentNotFound(email) => Some(json!({"email": email})),
+            ApiError::ProfessorNotFound(nombre) => Some(json!({"nombre": nombre})),
+            ApiError::SessionNotFound(id) | ApiError::SessionExpired(id) => Some(json!({"session_id": id})),
+            ApiError::SolutionNotFound(id) => Some(json!({"solution_id": id})),
+            ApiError::JobNotFound(id) => Some(json!({"job_id": id})),
+            ApiError::JobNotFinished { id, estado } => Some(json!({"job_id": id, "estado": estado})),
+            ApiError::FileNotFound(nombre) => Some(json!({"name": nombre})),
+            ApiError::InvalidDatafile { details, .. } => details.clone(),
+            ApiError::PinnedSectionsInfeasible(reporte)
+            | ApiError::DailyLimitsInfeasible(reporte)
+            | ApiError::RequiredCoursesInfeasible(reporte) => Some(reporte.clone()),
+            ApiError::RateLimited { limit_per_min, retry_after_secs } => {
+                Some(json!({"limit_per_min": limit_per_min, "retry_after_secs": retry_after_secs}))
+            }
+            _ => None,
+        }
+    }
+
+    /// Cuerpo JSON `{code, message, details}`
+    pub fn to_json(&self) -> Value {
+        json!({
+            "code": self.code(),
+            "message": self.to_string(),
+            "details": self.details(),
+        })
+    }
+
+    /// `message` en `idioma` (ver `i18n`). Solo se traduce la plantilla: el
+    /// texto que trae la variante (p.ej. el de `InvalidInput`) va tal cual.
+    pub fn mensaje(&self, idioma: Idioma) -> String {
+        let (clave, arg): (&str, Option<&dyn std::fmt::Display>) = match self {
+            ApiError::InvalidBody(m) => ("error.invalid_body", Some(m)),
+            ApiError::InvalidInput(m) => ("error.invalid_input", Some(m)),
+            ApiError::MissingParameter(p) => ("error.missing_parameter", Some(p)),
+            ApiError::Unauthorized(m) => ("error.unauthorized", Some(m)),
+            ApiError::MallaNotFound { malla, .. } => ("error.malla_not_found", Some(malla)),
+            ApiError::StudentNotFound(email) => ("error.student_not_found", Some(email)),
+            ApiError::ProfessorNotFound(nombre) => ("error.professor_not_found", Some(nombre)),
+            ApiError::ScenarioNotFound => ("error.scenario_not_found", None),
+            ApiError::SessionNotFound(id) => ("error.session_not_found", Some(id)),
+            ApiError::SessionExpired(id) => ("error.session_expired", Some(id)),
+            ApiError::SolutionNotFound(id) => ("error.solution_not_found", Some(id)),
+            ApiError::JobNotFound(id) => ("error.job_not_found", Some(id)),
+            ApiError::JobNotFinished { id, .. } => ("error.job_not_finished", Some(id)),
+            ApiError::FileNotFound(nombre) => ("error.file_not_found", Some(nombre)),
+            ApiError::NoFeasibleSchedule => ("error.no_feasible_schedule", None),
+            ApiError::PinnedSectionsInfeasible(_) => ("error.pinned_sections_infeasible", None),
+            ApiError::DailyLimitsInfeasible(_) => ("error.daily_limits_infeasible", None),
+            ApiError::RequiredCoursesInfeasible(_) => ("error.required_courses_infeasible", None),
+            ApiError::RateLimited { .. } => ("error.rate_limited", None),
+            ApiError::Solver(m) => ("error.solver", Some(m)),
+            ApiError::Storage(m) => ("error.storage", Some(m)),
+            ApiError::Internal(m) => ("error.internal", Some(m)),
+            // Mensajes que arma quien crea el error
+            ApiError::PayloadTooLarge(_) | ApiError::InvalidDatafile { .. } => return self.to_string(),
+        };
+        i18n::tf(clave, idioma, arg.as_slice())
+    }
+
+    /// `to_json` con el `message` en `idioma`
+    pub fn to_json_en(&self, idioma: Idioma) -> Value {
+        json!({
+            "code": self.code(),
+            "message": self.mensaje(idioma),
+            "details": self.details(),
+        })
+    }
+
+    /// Clasifica un error del pipeline de resolución: las mallas inexistentes
+    /// (`resolve_datafile_paths` / `select_malla_path_for_year`) se reportan
+    /// como `MALLA_NOT_FOUND`, el resto como `SOLVER_ERROR`.
+    pub fn del_pipeline(malla: &str, error: impl std::fmt::Display) -> ApiError {
+        let msg = error.to_string();
+        if es_malla_no_encontrada(&msg) {
+            ApiError::MallaNotFound { malla: malla.to_string(), available: Vec::new() }
+        } else {
+            ApiError::Solver(msg)
+        }
+    }
+
+    /// Error de `ruta` (resolver un request): las secciones fijas y los ramos
+    /// obligatorios inviables y los topes diarios imposibles conservan su
+    /// reporte; el resto se clasifica con `del_pipeline`
+    pub fn de_ruta(malla: &str, error: Box<dyn std::error::Error>) -> ApiError {
+        let error = match error.downcast::<crate::algorithm::fijas::ConflictoFijas>() {
+            Ok(conflicto) => return ApiError::PinnedSectionsInfeasible(serde_json::to_value(*conflicto).unwrap_or(Value::Null)),
+            Err(e) => e,
+        };
+        let error = match error.downcast::<crate::algorithm::obligatorios::ConflictoObligatorios>() {
+            Ok(conflicto) => return ApiError::RequiredCoursesInfeasible(serde_json::to_value(*conflicto).unwrap_or(Value::Null)),
+            Err(e) => e,
+        };
+        let error = match error.downcast::<crate::algorithm::carga_diaria::FiltrosInviables>() {
+            Ok(reporte) => return ApiError::DailyLimitsInfeasible(serde_json::to_value(*reporte).unwrap_or(Value::Null)),
+            Err(e) => e,
+        };
+        match error.downcast::<crate::excel::LibroIlegible>() {
+            Ok(libro) => ApiError::from(*libro),
+            Err(e) => ApiError::del_pipeline(malla, format!("ruta_critica failed: {}", e)),
+        }
+    }
+}
+
+fn es_malla_no_encontrada(msg: &str) -> bool {
+    let m = msg.to_lowercase();
+    m.contains("malla") && (m.contains("no encontrada") || m.contains("not found"))
+}
+
+#[cfg(feature = "server")]
+impl actix_web::ResponseError for ApiError {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        actix_web::http::StatusCode::from_u16(self.status())
+            .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> actix_web::HttpResponse {
+        if self.status() >= 500 {
+            tracing::error!("❌ [{}] {}", self.code(), self);
+        }
+        // Sin idioma pedido el `message` queda como su `Display` (inglés)
+        let cuerpo = match crate::middleware::idioma::pedido() {
+            Some(idioma) => self.to_json_en(idioma),
+            None => self.to_json(),
+        };
+        actix_web::HttpResponse::build(self.status_code()).json(cuerpo)
+    }
+}
+
+/// Rechazos del extractor `web::Json` (body inválido o demasiado grande)
+#[cfg(feature = "server")]
+impl From<actix_web::error::JsonPayloadError> for ApiError {
+    fn from(e: actix_web::error::JsonPayloadError) -> Self {
+        use actix_web::error::JsonPayloadError;
+        match e {
+            JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => {
+                ApiError::PayloadTooLarge(e.to_string())
+            }
+            _ => ApiError::InvalidBody(e.to_string()),
+        }
+    }
+}
+
+/// Un datafile que no se pudo abrir: `details.libro` es su diagnóstico
+/// (formato, hojas, protección)
+impl From<crate::excel::LibroIlegible> for ApiError {
+    fn from(e: crate::excel::LibroIlegible) -> Self {
+        ApiError::InvalidDatafile {
+            message: e.to_string(),
+            details: Some(serde_json::json!({ "libro": e.0 })),
+        }
+    }
+}
@@ -1,13 +1,13 @@
-use actix_web::{HttpResponse, Responder, web};
-use serde_json::json;
+use actix_web::{HttpResponse, Responder, web, ResponseError};
+use crate::api_error::ApiError;
 
 pub async fn anal_ramos_pasados_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
     let limit = query.get("limit").and_then(|s| s.parse::<usize>().ok());
     let res = web::block(move || crate::analithics::ramos_mas_pasados(limit).map_err(|e| format!("{}", e))).await;
     match res {
         Ok(Ok(v)) => HttpResponse::Ok().json(v),
-        Ok(Err(e)) => HttpResponse::InternalServerError().json(json!({"error": format!("analytics error: {}", e)})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("blocking task error: {}", e)})),
+        Ok(Err(e)) => ApiError::Storage(format!("analytics error: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
     }
 }
 
@@ -15,8 +15,8 @@ pub async fn anal_ranking_handler() -> impl Responder {
     let res = web::block(|| crate::analithics::ranking_por_estudiante().map_err(|e| format!("{}", e))).await;
     match res {
         Ok(Ok(v)) => HttpResponse::Ok().json(v),
-        Ok(Err(e)) => HttpResponse::InternalServerError().json(json!({"error": format!("analytics error: {}", e)})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("blocking task error: {}", e)})),
+        Ok(Err(e)) => ApiError::Storage(format!("analytics error: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
     }
 }
 
@@ -24,8 +24,8 @@ pub async fn anal_count_users_handler() -> impl Responder {
     let res = web::block(|| crate::analithics::count_users().map_err(|e| format!("{}", e))).await;
     match res {
         Ok(Ok(v)) => HttpResponse::Ok().json(v),
-        Ok(Err(e)) => HttpResponse::InternalServerError().json(json!({"error": format!("analytics error: {}", e)})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("blocking task error: {}", e)})),
+        Ok(Err(e)) => ApiError::Storage(format!("analytics error: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
     }
 }
 
@@ -33,8 +33,8 @@ pub async fn anal_filtros_handler() -> impl Responder {
     let res = web::block(|| crate::analithics::filtros_mas_solicitados().map_err(|e| format!("{}", e))).await;
     match res {
         Ok(Ok(v)) => HttpResponse::Ok().json(v),
-        Ok(Err(e)) => HttpResponse::InternalServerError().json(json!({"error": format!("analytics error: {}", e)})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("blocking task error: {}", e)})),
+        Ok(Err(e)) => ApiError::Storage(format!("analytics error: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
     }
 }
 
@@ -43,8 +43,8 @@ pub async fn anal_ramos_recomendados_handler(query: web::Query<std::collections:
     let res = web::block(move || crate::analithics::ramos_mas_recomendados(limit).map_err(|e| format!("{}", e))).await;
     match res {
         Ok(Ok(v)) => HttpResponse::Ok().json(v),
-        Ok(Err(e)) => HttpResponse::InternalServerError().json(json!({"error": format!("analytics error: {}", e)})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("blocking task error: {}", e)})),
+        Ok(Err(e)) => ApiError::Storage(format!("analytics error: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
     }
 }
 
@@ -53,8 +53,8 @@ pub async fn anal_horarios_recomendados_handler(query: web::Query<std::collectio
     let res = web::block(move || crate::analithics::horarios_mas_recomendados(limit).map_err(|e| format!("{}", e))).await;
     match res {
         Ok(Ok(v)) => HttpResponse::Ok().json(v),
-        Ok(Err(e)) => HttpResponse::InternalServerError().json(json!({"error": format!("analytics error: {}", e)})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("blocking task error: {}", e)})),
+        Ok(Err(e)) => ApiError::Storage(format!("analytics error: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
     }
 }
 
@@ -62,20 +62,39 @@ pub async fn anal_profesores_handler() -> impl Responder {
     let res = web::block(|| crate::analithics::profesores_y_cursos().map_err(|e| format!("{}", e))).await;
     match res {
         Ok(Ok(v)) => HttpResponse::Ok().json(v),
-        Ok(Err(e)) => HttpResponse::InternalServerError().json(json!({"error": format!("analytics error: {}", e)})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("blocking task error: {}", e)})),
+        Ok(Err(e)) => ApiError::Storage(format!("analytics error: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
     }
 }
 
 pub async fn anal_cursos_por_malla_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
     let malla = match query.get("malla") {
         Some(s) => s.clone(),
-        None => return HttpResponse::BadRequest().json(json!({"error": "missing malla parameter"})),
+        None => return ApiError::MissingParameter("malla").error_response(),
     };
     let res = web::block(move || crate::analithics::cursos_por_malla(&malla).map_err(|e| format!("{}", e))).await;
     match res {
         Ok(Ok(v)) => HttpResponse::Ok().json(v),
-        Ok(Err(e)) => HttpResponse::InternalServerError().json(json!({"error": format!("analytics error: {}", e)})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("blocking task error: {}", e)})),
+        Ok(Err(e)) => ApiError::Storage(format!("analytics error: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
+    }
+}
+
+/// GET /analytics/dashboard?from=2025-03-01&to=2025-03-31&bucket=day|week
+pub async fn anal_dashboard_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    let bucket = match query.get("bucket").map(|b| crate::analithics::queries::Bucket::parse(b)) {
+        None => crate::analithics::queries::Bucket::Day,
+        Some(Some(b)) => b,
+        Some(None) => return ApiError::InvalidInput("'bucket' must be day or week".into()).error_response(),
+    };
+    let (desde, hasta) = match crate::analithics::queries::rango_dashboard(query.get("from").map(|s| s.as_str()), query.get("to").map(|s| s.as_str())) {
+        Ok(r) => r,
+        Err(e) => return ApiError::InvalidInput(e).error_response(),
+    };
+    let res = web::block(move || crate::analithics::dashboard(&desde, &hasta, bucket).map_err(|e| format!("{}", e))).await;
+    match res {
+        Ok(Ok(v)) => HttpResponse::Ok().json(v),
+        Ok(Err(e)) => ApiError::Storage(format!("analytics error: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
     }
 }
@@ -1,11 +1,10 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::{HashMap, HashSet};
 
 use crate::excel::{
-    leer_malla_con_porcentajes_optimizado,
-    leer_mc_con_porcentajes_optimizado,
     normalize_name,
     resolve_datafile_paths,
 };
@@ -22,9 +21,11 @@ struct CursoDto {
     dificultad: Option<f64>,
     numb_correlativo: i32,
     critico: bool,
+    /// Notas de la hoja "Notas" de la malla
+    tags: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CursosRecomendadosRequest {
     pub malla_id: String,
     #[serde(default)]
@@ -44,30 +45,25 @@ fn ramo_to_dto(r: &RamoDisponible) -> CursoDto {
         dificultad: r.dificultad,
         numb_correlativo: r.numb_correlativo,
         critico: r.critico,
+        tags: r.tags.clone(),
     }
 }
 
-fn load_malla_map(malla_id: &str, _sheet: Option<String>) -> Result<HashMap<String, RamoDisponible>, String> {
+fn load_malla_map(malla_id: &str, _sheet: Option<String>) -> Result<HashMap<String, RamoDisponible>, ApiError> {
     let (malla_path, _oferta_path, porcent_path) = resolve_datafile_paths(malla_id)
-        .map_err(|e| format!("failed to resolve malla '{}': {}", malla_id, e))?;
+        .map_err(|e| ApiError::del_pipeline(malla_id, format!("failed to resolve malla '{}': {}", malla_id, e)))?;
 
     let malla_path_str = malla_path
         .to_str()
-        .ok_or_else(|| "invalid UTF-8 in malla path".to_string())?;
+        .ok_or_else(|| ApiError::Internal("invalid UTF-8 in malla path".to_string()))?;
     let porcent_path_str = porcent_path
         .to_str()
-        .ok_or_else(|| "invalid UTF-8 in porcent path".to_string())?;
+        .ok_or_else(|| ApiError::Internal("invalid UTF-8 in porcent path".to_string()))?;
 
-    let malla_lower = malla_path_str.to_lowercase();
-    let is_mc = malla_lower.contains("mc");
+    let res = crate::datafiles::cache::malla_con_porcentajes(malla_path_str, porcent_path_str)
+        .map(|m| m.as_ref().clone());
 
-    let res = if is_mc {
-        leer_mc_con_porcentajes_optimizado(malla_path_str, porcent_path_str)
-    } else {
-        leer_malla_con_porcentajes_optimizado(malla_path_str, porcent_path_str)
-    };
-
-    res.map_err(|e| format!("failed to read malla '{}': {}", malla_path_str, e))
+    res.map_err(|e| ApiError::InvalidDatafile { message: format!("failed to read malla '{}': {}", malla_path_str, e), details: None })
 }
 
 fn sort_cursos(cursos: &mut Vec<CursoDto>) {
@@ -150,7 +146,7 @@ pub async fn cursos_por_semestre_handler(
                 "cursos": cursos
             }))
         }
-        Err(e) => HttpResponse::BadRequest().json(json!({ "error": e })),
+        Err(e) => e.error_response(),
     }
 }
 
@@ -172,7 +168,130 @@ pub async fn cursos_todos_handler(
                 "cursos": cursos
             }))
         }
-        Err(e) => HttpResponse::BadRequest().json(json!({ "error": e })),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// GET /malla/{id}/grafo?format=dot|json
+/// DAG de prerequisitos de la malla como JSON `{nodes, edges}` (por defecto) o Graphviz DOT.
+pub async fn malla_grafo_handler(
+    path: web::Path<String>,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let malla_id = path.into_inner();
+    let formato = query.get("format").map(|f| f.trim().to_lowercase()).unwrap_or_else(|| "json".to_string());
+    if formato != "json" && formato != "dot" {
+        return ApiError::InvalidInput(format!("format '{}' no soportado (usar 'dot' o 'json')", formato)).error_response();
+    }
+    let sheet = query
+        .get("sheet")
+        .and_then(|s| if s.trim().is_empty() { None } else { Some(s.clone()) });
+
+    let map = match load_malla_map(&malla_id, sheet) {
+        Ok(m) => m,
+        Err(e) => return e.error_response(),
+    };
+    let grafo = crate::algorithm::graph_export::GrafoMalla::desde_ramos(&map);
+
+    if formato == "dot" {
+        HttpResponse::Ok()
+            .content_type("text/vnd.graphviz; charset=utf-8")
+            .body(grafo.to_dot())
+    } else {
+        HttpResponse::Ok().json(json!({
+            "malla": malla_id,
+            "nodes": grafo.nodes,
+            "edges": grafo.edges,
+        }))
+    }
+}
+
+/// CFGs y electivos que exige la malla (ver `reglas`); `?carrera=` aplica
+/// además las reglas de esa carrera
+pub async fn malla_reglas_handler(
+    path: web::Path<String>,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let malla_id = path.into_inner();
+    if let Err(e) = resolve_datafile_paths(&malla_id) {
+        return ApiError::del_pipeline(&malla_id, format!("failed to resolve malla '{}': {}", malla_id, e)).error_response();
+    }
+    let carrera = query.get("carrera").map(|c| c.trim()).filter(|c| !c.is_empty());
+    if let Some(c) = carrera.filter(|c| crate::carreras::registro().get(c).is_none()) {
+        return ApiError::InvalidInput(format!("carrera '{}' no está en el registro", c)).error_response();
+    }
+    let reglas = crate::reglas::reglas_de(&malla_id, carrera);
+    HttpResponse::Ok().json(json!({
+        "malla": malla_id,
+        "carrera": carrera,
+        "max_cfg": reglas.max_cfg,
+        "max_electivos": reglas.max_electivos,
+    }))
+}
+
+/// Red PERT de una malla de datafiles (`GET /pert` y `quickshift pert`)
+pub fn red_pert_de_malla(malla: &str, sheet: Option<&str>) -> Result<crate::algorithm::pert::RedPert, ApiError> {
+    let (malla_path, _, _) = resolve_datafile_paths(malla)
+        .map_err(|e| ApiError::del_pipeline(malla, format!("failed to resolve malla '{}': {}", malla, e)))?;
+    let malla_str = malla_path.to_string_lossy().to_string();
+    let ramos = crate::datafiles::cache::malla(&malla_str, sheet)
+        .map_err(|e| ApiError::InvalidDatafile { message: format!("failed to read malla '{}': {}", malla_str, e), details: None })?;
+    let prerequisitos = crate::excel::leer_prerequisitos(&malla_str).ok();
+    Ok(crate::algorithm::pert::red_pert_malla(&ramos, prerequisitos.as_ref()))
+}
+
+/// GET /pert?malla=...&sheet=...
+/// Red PERT de la malla: ES/EF/LS/LF y holgura por ramo, más la ruta crítica.
+pub async fn pert_handler(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    let malla = match query.get("malla").filter(|m| !m.trim().is_empty()) {
+        Some(m) => m.clone(),
+        None => return ApiError::MissingParameter("malla").error_response(),
+    };
+    let sheet = query
+        .get("sheet")
+        .and_then(|s| if s.trim().is_empty() { None } else { Some(s.clone()) });
+
+    let malla_block = malla.clone();
+    let res = web::block(move || red_pert_de_malla(&malla_block, sheet.as_deref())).await;
+
+    match res {
+        Ok(Ok(red)) => HttpResponse::Ok().json(json!({
+            "malla": malla,
+            "nodos": red.nodos,
+            "ruta_critica": red.ruta_critica,
+        })),
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
+
+/// GET /malla/{id}/criticos?sheet=...
+/// Todos los ramos de la malla con su holgura, del más urgente al menos
+/// urgente, marcando los que están en la ruta crítica.
+pub async fn malla_criticos_handler(
+    path: web::Path<String>,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let malla_id = path.into_inner();
+    let sheet = query
+        .get("sheet")
+        .and_then(|s| if s.trim().is_empty() { None } else { Some(s.clone()) });
+
+    let malla_block = malla_id.clone();
+    let res = web::block(move || red_pert_de_malla(&malla_block, sheet.as_deref())).await;
+
+    match res {
+        Ok(Ok(red)) => {
+            let criticos = crate::algorithm::pert::ramos_criticos(&red);
+            HttpResponse::Ok().json(json!({
+                "malla": malla_id,
+                "semestres_minimos": criticos.semestres_minimos,
+                "ruta_critica": criticos.ruta_critica,
+                "ramos": criticos.ramos,
+            }))
+        }
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
     }
 }
 
@@ -182,7 +301,7 @@ pub async fn cursos_recomendados_handler(body: web::Json<CursosRecomendadosReque
 
     let map = match load_malla_map(&payload.malla_id, sheet) {
         Ok(m) => m,
-        Err(e) => return HttpResponse::BadRequest().json(json!({ "error": e })),
+        Err(e) => return e.error_response(),
     };
 
     let elegibles = elegibles_desde_malla(&map, &payload.ramos_aprobados);
@@ -194,11 +313,14 @@ pub async fn cursos_recomendados_handler(body: web::Json<CursosRecomendadosReque
     }))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ProfesoresDisponiblesRequest {
     pub malla: String,
     #[serde(default)]
     pub ramos_pasados: Vec<String>,
+    /// Carrera del registro, para sus reglas (ver `reglas::reglas_de`)
+    #[serde(default)]
+    pub carrera: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -212,11 +334,14 @@ struct ProfesorCursoDto {
     is_electivo: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CursosDisponiblesRequest {
     pub malla: String,
     #[serde(default)]
     pub ramos_pasados: Vec<String>,
+    /// Carrera del registro, para sus reglas (ver `reglas::reglas_de`)
+    #[serde(default)]
+    pub carrera: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -230,6 +355,8 @@ struct CursoDisponibleDto {
     dificultad: Option<f64>,
     is_cfg: bool,
     is_electivo: bool,
+    /// Notas de la malla (vacío para CFGs y electivos)
+    tags: Vec<String>,
 }
 
 /// Endpoint que devuelve todos los cursos disponibles para el estudiante,
@@ -240,7 +367,7 @@ pub async fn cursos_disponibles_handler(body: web::Json<CursosDisponiblesRequest
     // 1. Resolver paths de archivos
     let (malla_pathbuf, oferta_pathbuf, porcentajes_pathbuf) = match resolve_datafile_paths(&payload.malla) {
         Ok(paths) => paths,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("Failed to resolve paths: {}", e)})),
+        Err(e) => return ApiError::del_pipeline(&payload.malla, format!("Failed to resolve paths: {}", e)).error_response(),
     };
     
     let malla_str = malla_pathbuf.to_string_lossy().to_string();
@@ -248,29 +375,22 @@ pub async fn cursos_disponibles_handler(body: web::Json<CursosDisponiblesRequest
     let porcentajes_str = porcentajes_pathbuf.to_string_lossy().to_string();
     
     // 2. Cargar malla
-    let ramos_disponibles: HashMap<String, RamoDisponible> = if malla_str.to_uppercase().contains("MC") {
-        match leer_mc_con_porcentajes_optimizado(&malla_str, &porcentajes_str) {
-            Ok(m) => m,
-            Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("Failed to read malla: {}", e)})),
-        }
-    } else {
-        match leer_malla_con_porcentajes_optimizado(&malla_str, &porcentajes_str) {
-            Ok(m) => m,
-            Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("Failed to read malla: {}", e)})),
-        }
+    let ramos_disponibles: HashMap<String, RamoDisponible> = match crate::datafiles::cache::malla_con_porcentajes(&malla_str, &porcentajes_str) {
+        Ok(m) => m.as_ref().clone(),
+        Err(e) => return ApiError::InvalidDatafile { message: format!("Failed to read malla: {}", e), details: None }.error_response(),
     };
     
     // 3. Cargar oferta académica
-    let mut lista_secciones = match crate::excel::leer_oferta_academica_excel(&oferta_str) {
-        Ok(secs) => secs,
-        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("Failed to read oferta: {}", e)})),
+    let mut lista_secciones = match crate::datafiles::cache::oferta(&oferta_str) {
+        Ok(secs) => secs.as_ref().clone(),
+        Err(e) => return ApiError::InvalidDatafile { message: format!("Failed to read oferta: {}", e), details: None }.error_response(),
     };
     
     // 4. Cargar CFG si existe
     if let Some(cfg_pathbuf) = crate::excel::latest_file_for_keywords(&["cfg"]) {
         if let Some(cfg_str) = cfg_pathbuf.to_str() {
-            if let Ok(cfg_secs) = crate::excel::leer_oferta_academica_excel(cfg_str) {
-                for mut s in cfg_secs.into_iter() {
+            if let Ok(cfg_secs) = crate::datafiles::cache::oferta(cfg_str) {
+                for mut s in cfg_secs.iter().cloned() {
                     let name_norm = normalize_name(&s.nombre);
                     if name_norm == normalize_name("Inglés I") || name_norm == normalize_name("Ingles I") {
                         s.nombre = "Inglés 1".to_string();
@@ -295,32 +415,12 @@ pub async fn cursos_disponibles_handler(body: web::Json<CursosDisponiblesRequest
         .map(|s| normalize_name(s))
         .collect();
     
-    // Contar CFGs aprobados (máximo 4 permitidos en total)
-    let cfgs_aprobados = payload.ramos_pasados.iter()
-        .filter(|r| r.to_uppercase().starts_with("CFG"))
-        .count();
-    let mostrar_cfgs = cfgs_aprobados < 4;
-    
-    // Contar electivos aprobados
-    let codigos_malla: HashSet<String> = ramos_disponibles
-        .values()
-        .map(|r| r.codigo.to_uppercase())
-        .collect();
-    let nombres_malla: HashSet<String> = ramos_disponibles
-        .values()
-        .map(|r| normalize_name(&r.nombre))
-        .collect();
-    
-    let electivos_aprobados = payload.ramos_pasados.iter()
-        .filter(|code| {
-            let code_upper = code.to_uppercase();
-            if code_upper.starts_with("CFG") {
-                return false;
-            }
-            !codigos_malla.contains(&code_upper) && !nombres_malla.contains(&normalize_name(code))
-        })
-        .count();
-    let max_electivos = 3usize;
+    // CFGs y electivos que exige la malla (ver `reglas`), contra los aprobados
+    let reglas = crate::reglas::reglas_de(&payload.malla, payload.carrera.as_deref());
+    let cfgs_aprobados = crate::reglas::cfgs_aprobados(&payload.ramos_pasados);
+    let mostrar_cfgs = cfgs_aprobados < reglas.max_cfg;
+    let max_electivos = reglas.max_electivos;
+    let electivos_aprobados = crate::reglas::electivos_aprobados(&payload.ramos_pasados, &ramos_disponibles);
     let mostrar_electivos = electivos_aprobados < max_electivos;
     
     // Calcular max_sem basado en ramos aprobados
@@ -392,6 +492,7 @@ pub async fn cursos_disponibles_handler(body: web::Json<CursosDisponiblesRequest
             dificultad: ramo.dificultad,
             is_cfg: false,
             is_electivo: false,
+            tags: ramo.tags.clone(),
         });
     }
     
@@ -421,6 +522,7 @@ pub async fn cursos_disponibles_handler(body: web::Json<CursosDisponiblesRequest
                 dificultad: None,
                 is_cfg: true,
                 is_electivo: false,
+                tags: Vec::new(),
             });
             cfg_id += 1;
         }
@@ -467,6 +569,7 @@ pub async fn cursos_disponibles_handler(body: web::Json<CursosDisponiblesRequest
                 dificultad: None,
                 is_cfg: false,
                 is_electivo: true,
+                tags: Vec::new(),
             });
             electivo_id += 1;
         }
@@ -487,7 +590,7 @@ pub async fn cursos_disponibles_handler(body: web::Json<CursosDisponiblesRequest
         "malla": payload.malla,
         "resumen": {
             "cfgs_aprobados": cfgs_aprobados,
-            "cfgs_faltantes": 4usize.saturating_sub(cfgs_aprobados),
+            "cfgs_faltantes": reglas.max_cfg.saturating_sub(cfgs_aprobados),
             "electivos_aprobados": electivos_aprobados,
             "electivos_faltantes": max_electivos.saturating_sub(electivos_aprobados),
             "mostrar_cfgs": mostrar_cfgs,
@@ -511,7 +614,7 @@ pub async fn profesores_disponibles_handler(body: web::Json<ProfesoresDisponible
     // 1. Resolver paths de archivos
     let (malla_pathbuf, oferta_pathbuf, porcentajes_pathbuf) = match resolve_datafile_paths(&payload.malla) {
         Ok(paths) => paths,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("Failed to resolve paths: {}", e)})),
+        Err(e) => return ApiError::del_pipeline(&payload.malla, format!("Failed to resolve paths: {}", e)).error_response(),
     };
     
     let malla_str = malla_pathbuf.to_string_lossy().to_string();
@@ -519,29 +622,22 @@ pub async fn profesores_disponibles_handler(body: web::Json<ProfesoresDisponible
     let porcentajes_str = porcentajes_pathbuf.to_string_lossy().to_string();
     
     // 2. Cargar malla
-    let ramos_disponibles: HashMap<String, RamoDisponible> = if malla_str.to_uppercase().contains("MC") {
-        match leer_mc_con_porcentajes_optimizado(&malla_str, &porcentajes_str) {
-            Ok(m) => m,
-            Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("Failed to read malla: {}", e)})),
-        }
-    } else {
-        match leer_malla_con_porcentajes_optimizado(&malla_str, &porcentajes_str) {
-            Ok(m) => m,
-            Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("Failed to read malla: {}", e)})),
-        }
+    let ramos_disponibles: HashMap<String, RamoDisponible> = match crate::datafiles::cache::malla_con_porcentajes(&malla_str, &porcentajes_str) {
+        Ok(m) => m.as_ref().clone(),
+        Err(e) => return ApiError::InvalidDatafile { message: format!("Failed to read malla: {}", e), details: None }.error_response(),
     };
     
     // 3. Cargar oferta académica
-    let mut lista_secciones = match crate::excel::leer_oferta_academica_excel(&oferta_str) {
-        Ok(secs) => secs,
-        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("Failed to read oferta: {}", e)})),
+    let mut lista_secciones = match crate::datafiles::cache::oferta(&oferta_str) {
+        Ok(secs) => secs.as_ref().clone(),
+        Err(e) => return ApiError::InvalidDatafile { message: format!("Failed to read oferta: {}", e), details: None }.error_response(),
     };
     
     // 4. Cargar CFG si existe
     if let Some(cfg_pathbuf) = crate::excel::latest_file_for_keywords(&["cfg"]) {
         if let Some(cfg_str) = cfg_pathbuf.to_str() {
-            if let Ok(cfg_secs) = crate::excel::leer_oferta_academica_excel(cfg_str) {
-                for mut s in cfg_secs.into_iter() {
+            if let Ok(cfg_secs) = crate::datafiles::cache::oferta(cfg_str) {
+                for mut s in cfg_secs.iter().cloned() {
                     let name_norm = normalize_name(&s.nombre);
                     if name_norm == normalize_name("Inglés I") || name_norm == normalize_name("Ingles I") {
                         s.nombre = "Inglés 1".to_string();
@@ -567,35 +663,12 @@ pub async fn profesores_disponibles_handler(body: web::Json<ProfesoresDisponible
         .map(|s| normalize_name(s))
         .collect();
     
-    // Contar CFGs aprobados (máximo 4 permitidos en total)
-    let cfgs_aprobados = payload.ramos_pasados.iter()
-        .filter(|r| r.to_uppercase().starts_with("CFG"))
-        .count();
-    let mostrar_cfgs = cfgs_aprobados < 4;
-    
-    // Contar electivos aprobados (máximo 2-3 permitidos)
-    // Electivos son cursos que NO están en la malla y NO son CFG
-    let codigos_malla: HashSet<String> = ramos_disponibles
-        .values()
-        .map(|r| r.codigo.to_uppercase())
-        .collect();
-    let nombres_malla: HashSet<String> = ramos_disponibles
-        .values()
-        .map(|r| normalize_name(&r.nombre))
-        .collect();
-    
-    let electivos_aprobados = payload.ramos_pasados.iter()
-        .filter(|code| {
-            let code_upper = code.to_uppercase();
-            // No es CFG
-            if code_upper.starts_with("CFG") {
-                return false;
-            }
-            // No está en la malla = es electivo
-            !codigos_malla.contains(&code_upper) && !nombres_malla.contains(&normalize_name(code))
-        })
-        .count();
-    let max_electivos = 3usize; // Asumimos máximo 3 electivos requeridos
+    // CFGs y electivos que exige la malla (ver `reglas`), contra los aprobados
+    let reglas = crate::reglas::reglas_de(&payload.malla, payload.carrera.as_deref());
+    let cfgs_aprobados = crate::reglas::cfgs_aprobados(&payload.ramos_pasados);
+    let mostrar_cfgs = cfgs_aprobados < reglas.max_cfg;
+    let max_electivos = reglas.max_electivos;
+    let electivos_aprobados = crate::reglas::electivos_aprobados(&payload.ramos_pasados, &ramos_disponibles);
     let mostrar_electivos = electivos_aprobados < max_electivos;
     
     // Calcular max_sem basado en ramos aprobados
@@ -627,7 +700,7 @@ pub async fn profesores_disponibles_handler(body: web::Json<ProfesoresDisponible
             continue;
         }
         
-        // Si es CFG: mostrar solo si aún no aprobó los 4 CFGs requeridos
+        // Si es CFG: mostrar solo si aún no aprobó los CFGs requeridos
         if sec.is_cfg {
             if !mostrar_cfgs {
                 continue;
@@ -757,7 +830,7 @@ pub async fn profesores_disponibles_handler(body: web::Json<ProfesoresDisponible
         "malla": payload.malla,
         "resumen": {
             "cfgs_aprobados": cfgs_aprobados,
-            "cfgs_faltantes": 4usize.saturating_sub(cfgs_aprobados),
+            "cfgs_faltantes": reglas.max_cfg.saturating_sub(cfgs_aprobados),
             "electivos_aprobados": electivos_aprobados,
             "electivos_faltantes": max_electivos.saturating_sub(electivos_aprobados),
             "mostrar_cfgs": mostrar_cfgs,
@@ -1,86 +1,329 @@
 use actix_multipart::Multipart;
 use futures_util::stream::StreamExt;
 use serde_json::json;
-use tokio::io::AsyncWriteExt;
 use crate::algorithm::{list_datafiles, summarize_datafiles};
-use actix_web::{web, HttpResponse, Responder};
+use crate::excel::config::DatafilesConfig;
+use crate::excel::deteccion::{DatafileValidado, TipoDatafile};
+use crate::excel::validate::ReporteMalla;
+use crate::middleware::etag;
+use crate::middleware::trace::con_span_actual;
+use crate::models::Seccion;
+use std::collections::HashMap;
+use std::sync::Arc;
+use actix_web::http::header::CONTENT_TYPE;
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
 
 pub async fn datafiles_list_handler() -> impl Responder {
     match list_datafiles() {
         Ok((mallas, ofertas, porcentajes)) => HttpResponse::Ok().json(json!({"mallas": mallas, "ofertas": ofertas, "porcentajes": porcentajes})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("failed to list datafiles: {}", e)})),
+        Err(e) => ApiError::Storage(format!("failed to list datafiles: {}", e)).error_response(),
     }
 }
 
-pub async fn datafiles_upload_handler(mut payload: Multipart) -> impl Responder {
-    let base = std::path::Path::new("src/datafiles");
-    if let Err(e) = std::fs::create_dir_all(base) {
-        return HttpResponse::InternalServerError().json(json!({"error": format!("failed to create datafiles dir: {}", e)}));
+/// GET /carreras: registro de carreras (`careers.toml`) con sus datafiles y reglas
+pub async fn carreras_list_handler() -> impl Responder {
+    HttpResponse::Ok().json(crate::carreras::registro().as_ref())
+}
+
+/// Tamaño máximo de cada archivo subido
+const MAX_SUBIDA_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(serde::Deserialize)]
+struct ArchivoBase64 {
+    nombre: String,
+    contenido_base64: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum SubidaJson {
+    Varios { archivos: Vec<ArchivoBase64> },
+    Uno(ArchivoBase64),
+}
+
+/// Archivos de un body JSON `{"nombre", "contenido_base64"}` o
+/// `{"archivos": [{"nombre", "contenido_base64"}, ...]}`
+pub fn archivos_desde_json(body: &[u8]) -> Result<Vec<(String, Vec<u8>)>, String> {
+    use base64::Engine;
+    let subida: SubidaJson = serde_json::from_slice(body)
+        .map_err(|e| format!("expected {{\"nombre\", \"contenido_base64\"}} or {{\"archivos\": [...]}}: {}", e))?;
+    let archivos = match subida {
+        SubidaJson::Varios { archivos } => archivos,
+        SubidaJson::Uno(a) => vec![a],
+    };
+    archivos.into_iter()
+        .map(|a| {
+            // Aceptar también data URLs ("data:...;base64,AAAA")
+            let datos = a.contenido_base64.rsplit(',').next().unwrap_or("").trim();
+            base64::engine::general_purpose::STANDARD.decode(datos)
+                .map(|bytes| (a.nombre.clone(), bytes))
+                .map_err(|e| format!("invalid base64 for '{}': {}", a.nombre, e))
+        })
+        .collect()
+}
+
+/// Nombre de archivo aceptable dentro del directorio de datos
+fn validar_nombre(nombre: &str) -> Result<(), String> {
+    if nombre.is_empty() || nombre.contains("..") || nombre.contains('/') || nombre.contains('\\') || nombre.starts_with('.') || nombre.starts_with('~') {
+        return Err(format!("invalid file name '{}'", nombre));
+    }
+    let extension = std::path::Path::new(nombre).extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+    if !matches!(extension.as_deref(), Some("xlsx") | Some("xlsm") | Some("xlsb") | Some("xls")) {
+        return Err(format!("'{}' is not an Excel file (.xlsx/.xlsm/.xlsb/.xls)", nombre));
+    }
+    Ok(())
+}
+
+/// Escribe `contenido` a un archivo temporal de `dir` y lo valida. Si la
+/// validación falla el temporal se borra.
+fn escribir_y_validar(dir: &std::path::Path, nombre: &str, contenido: &[u8]) -> Result<(std::path::PathBuf, DatafileValidado), String> {
+    validar_nombre(nombre)?;
+    if contenido.len() > MAX_SUBIDA_BYTES {
+        return Err(format!("'{}' exceeds {} bytes", nombre, MAX_SUBIDA_BYTES));
+    }
+    std::fs::create_dir_all(dir).map_err(|e| format!("failed to create datafiles dir: {}", e))?;
+
+    let temporal = dir.join(format!(".subida-{}-{}", std::process::id(), nombre));
+    std::fs::write(&temporal, contenido).map_err(|e| format!("failed to write '{}': {}", nombre, e))?;
+    match crate::excel::deteccion::validar_datafile(&temporal, nombre) {
+        Ok(v) => Ok((temporal, v)),
+        Err(e) => {
+            let _ = std::fs::remove_file(&temporal);
+            Err(e)
+        }
+    }
+}
+
+/// Valida `contenido` y lo guarda como `dir/nombre`. Se escribe primero a un
+/// archivo temporal y solo se renombra si pasa la validación, así una subida
+/// inválida nunca reemplaza un datafile bueno.
+pub fn guardar_datafile(dir: &std::path::Path, nombre: &str, contenido: &[u8]) -> Result<DatafileValidado, String> {
+    let (temporal, validado) = escribir_y_validar(dir, nombre, contenido)?;
+    let destino = dir.join(nombre);
+    if let Err(e) = std::fs::rename(&temporal, &destino) {
+        let _ = std::fs::remove_file(&temporal);
+        return Err(format!("failed to store '{}': {}", nombre, e));
     }
+    crate::datafiles::cache::invalidar_archivo(&destino);
+    Ok(validado)
+}
+
+/// Dry-run de una subida: valida igual que `guardar_datafile` pero no guarda
+/// nada. Para mallas agrega el reporte de `excel::validate` contra `oferta`.
+pub fn revisar_datafile(dir: &std::path::Path, nombre: &str, contenido: &[u8], oferta: Option<(&str, &[Seccion])>) -> Result<(DatafileValidado, Option<ReporteMalla>), String> {
+    let (temporal, validado) = escribir_y_validar(dir, nombre, contenido)?;
+    let reporte = match validado.tipo {
+        TipoDatafile::Malla => crate::excel::validate::validar_malla(&temporal, Some(&validado.hoja), oferta)
+            .map(|mut r| {
+                r.archivo = nombre.to_string();
+                r.libro.archivo = nombre.to_string();
+                Some(r)
+            }),
+        _ => Ok(None),
+    };
+    let _ = std::fs::remove_file(&temporal);
+    reporte.map(|r| (validado, r))
+}
+
+/// Nombre de archivo de una oferta junto a sus secciones
+type OfertaNombrada = (String, Arc<Vec<Seccion>>);
+
+/// Oferta para comparar nombres de ramos: la indicada en `oferta=` o la más
+/// reciente. `oferta=` es un nombre de archivo dentro de `dir`; rutas y
+/// nombres fuera del directorio de datafiles se rechazan.
+fn oferta_para_validar(dir: &std::path::Path, nombre: Option<&String>) -> Result<Option<OfertaNombrada>, String> {
+    let ruta = match nombre.map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        Some(n) => {
+            validar_nombre(n)?;
+            let ruta = dir.join(n);
+            if !ruta.is_file() {
+                return Err(format!("oferta '{}' not found in the datafiles directory", n));
+            }
+            ruta
+        }
+        None => match crate::excel::latest_file_for_keywords(&["oferta", "oa"]) {
+            Some(p) => p,
+            None => return Ok(None),
+        },
+    };
+    let archivo = ruta.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let secciones = crate::datafiles::cache::oferta(&ruta.to_string_lossy())
+        .map_err(|e| format!("failed to read oferta '{}': {}", archivo, e))?;
+    Ok(Some((archivo, secciones)))
+}
 
-    let mut saved: Vec<String> = Vec::new();
+/// Lee los archivos de un body multipart/form-data (uno por campo con filename)
+async fn archivos_desde_multipart(mut payload: Multipart) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut archivos = Vec::new();
     while let Some(field_res) = payload.next().await {
-        match field_res {
-            Ok(mut field) => {
-                // Try to read filename from content-disposition
-                let filename = field.content_disposition()
-                    .get_filename()
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| format!("upload-{}.dat", chrono::Utc::now().timestamp_millis()));
-
-                // Sanitize filename a bit
-                if filename.contains("..") {
-                    continue;
-                }
-
-                let filepath = base.join(&filename);
-                match tokio::fs::File::create(&filepath).await {
-                    Ok(mut f) => {
-                        while let Some(chunk) = field.next().await {
-                            match chunk {
-                                Ok(bytes) => {
-                                    if let Err(e) = f.write_all(&bytes).await {
-                                        eprintln!("failed to write upload chunk: {}", e);
-                                        break;
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!("upload stream error: {}", e);
-                                    break;
-                                }
-                            }
-                        }
-                        saved.push(filename);
-                    }
-                    Err(e) => {
-                        eprintln!("failed to create upload file: {}", e);
-                    }
-                }
+        let mut field = field_res.map_err(|e| format!("multipart field error: {}", e))?;
+        let Some(nombre) = field.content_disposition().get_filename().map(|s| s.to_string()) else { continue };
+        let mut contenido: Vec<u8> = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let bytes = chunk.map_err(|e| format!("upload stream error: {}", e))?;
+            if contenido.len() + bytes.len() > MAX_SUBIDA_BYTES {
+                return Err(format!("'{}' exceeds {} bytes", nombre, MAX_SUBIDA_BYTES));
+            }
+            contenido.extend_from_slice(&bytes);
+        }
+        archivos.push((nombre, contenido));
+    }
+    Ok(archivos)
+}
+
+/// POST /datafiles/upload: multipart/form-data (campo con filename) o JSON con
+/// el contenido en base64. Cada archivo se valida (malla/oferta/porcentajes) y
+/// queda disponible de inmediato para el parámetro `malla` y los demás endpoints.
+pub async fn datafiles_upload_handler(req: HttpRequest, mut payload: web::Payload, datafiles: DatafilesConfig) -> impl Responder {
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map(|q| q.into_inner())
+        .unwrap_or_default();
+    let dry_run = query.get("dry_run").map(|v| v == "true" || v == "1").unwrap_or(false);
+
+    let es_json = req.headers().get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|ct| ct.starts_with("application/json"))
+        .unwrap_or(false);
+
+    let archivos = if es_json {
+        let mut body = web::BytesMut::new();
+        while let Some(chunk) = payload.next().await {
+            match chunk {
+                Ok(bytes) if body.len() + bytes.len() <= MAX_SUBIDA_BYTES * 2 => body.extend_from_slice(&bytes),
+                Ok(_) => return ApiError::PayloadTooLarge("upload body too large".into()).error_response(),
+                Err(e) => return ApiError::InvalidBody(format!("failed to read body: {}", e)).error_response(),
+            }
+        }
+        archivos_desde_json(&body)
+    } else {
+        archivos_desde_multipart(Multipart::new(req.headers(), payload)).await
+    };
+    let archivos = match archivos {
+        Ok(a) if !a.is_empty() => a,
+        Ok(_) => return ApiError::InvalidInput("no files in upload".into()).error_response(),
+        Err(e) => return ApiError::InvalidInput(e).error_response(),
+    };
+
+    let dir = datafiles.dir;
+    if dry_run {
+        let oferta = match oferta_para_validar(&dir, query.get("oferta")) {
+            Ok(o) => o,
+            Err(e) => return ApiError::InvalidInput(e).error_response(),
+        };
+        let oferta_ref = oferta.as_ref().map(|(n, s)| (n.as_str(), s.as_slice()));
+        let revisados: Vec<serde_json::Value> = archivos.iter()
+            .map(|(nombre, contenido)| match revisar_datafile(&dir, nombre, contenido, oferta_ref) {
+                Ok((v, reporte)) => json!({"nombre": nombre, "valido": true, "tipo": v.tipo, "hoja": v.hoja, "filas": v.filas, "reporte": reporte}),
+                Err(e) => json!({"nombre": nombre, "valido": false, "error": e}),
+            })
+            .collect();
+        return HttpResponse::Ok().json(json!({"dry_run": true, "archivos": revisados}));
+    }
+
+    let mut guardados = Vec::new();
+    let mut rechazados = Vec::new();
+    for (nombre, contenido) in archivos.iter() {
+        match guardar_datafile(&dir, nombre, contenido) {
+            Ok(v) => {
+                tracing::info!("📤 Datafile subido: {} ({}, {} filas)", nombre, v.tipo.etiqueta(), v.filas);
+                guardados.push(json!({"nombre": nombre, "tipo": v.tipo, "hoja": v.hoja, "filas": v.filas}));
             }
             Err(e) => {
-                eprintln!("multipart field error: {}", e);
+                tracing::warn!("⚠️ Datafile rechazado: {}: {}", nombre, e);
+                rechazados.push(json!({"nombre": nombre, "error": e}));
             }
         }
     }
 
-    HttpResponse::Ok().json(json!({"status": "ok", "saved": saved}))
+    let saved: Vec<&serde_json::Value> = guardados.iter().map(|g| &g["nombre"]).collect();
+    if guardados.is_empty() {
+        return ApiError::InvalidDatafile {
+            message: "no valid datafiles in upload".into(),
+            details: Some(json!({"rechazados": rechazados})),
+        }.error_response();
+    }
+    HttpResponse::Ok().json(json!({
+        "status": if rechazados.is_empty() { "ok" } else { "partial" },
+        "saved": saved,
+        "archivos": guardados,
+        "rechazados": rechazados,
+    }))
+}
+
+/// GET /datafiles/validate?malla=...[&sheet=...][&oferta=...]: reporte de
+/// validación de una malla ya disponible, sin modificar nada
+pub async fn datafiles_validate_handler(query: web::Query<HashMap<String, String>>, datafiles: DatafilesConfig) -> impl Responder {
+    let qm = query.into_inner();
+    let malla = match qm.get("malla").filter(|m| !m.trim().is_empty()) {
+        Some(m) => m.clone(),
+        None => return ApiError::MissingParameter("malla").error_response(),
+    };
+    let ruta = match crate::excel::select_malla_path_for_year(&malla, None) {
+        Ok(p) => p,
+        Err(e) => return ApiError::del_pipeline(&malla, e).error_response(),
+    };
+    let oferta = match oferta_para_validar(&datafiles.dir, qm.get("oferta")) {
+        Ok(o) => o,
+        Err(e) => return ApiError::InvalidInput(e).error_response(),
+    };
+    let hoja = qm.get("sheet").filter(|s| !s.trim().is_empty()).cloned();
+
+    let resultado = web::block(con_span_actual(move || {
+        // Un libro que no se puede abrir (contraseña, formato desconocido)
+        // responde con su diagnóstico en vez del reporte
+        let libro = crate::excel::diagnosticar_libro(&ruta);
+        if !libro.legible {
+            return Err(ApiError::from(crate::excel::LibroIlegible(libro)));
+        }
+        let oferta_ref = oferta.as_ref().map(|(n, s)| (n.as_str(), s.as_slice()));
+        crate::excel::validate::validar_malla(&ruta, hoja.as_deref(), oferta_ref)
+            .map_err(|e| ApiError::InvalidDatafile { message: e, details: None })
+    })).await;
+    match resultado {
+        Ok(Ok(reporte)) => HttpResponse::Ok().json(reporte),
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("blocking error: {}", e)).error_response(),
+    }
+}
+
+/// Vacía el caché de datafiles: el próximo request vuelve a leer los Excel del disco
+pub async fn datafiles_reload_handler() -> impl Responder {
+    let invalidadas = crate::datafiles::cache::invalidar_todo()
+        + crate::excel::mapeo_builder::invalidar_cache_mapeo();
+    let carreras = crate::carreras::recargar_registro();
+    let reglas = crate::reglas::recargar_registro();
+    let scoring = crate::scoring::recargar_config();
+    let layouts = crate::excel::layout::recargar_registro();
+    tracing::info!("🔄 Caché de datafiles recargado ({} entradas invalidadas, {} carreras, reglas de {} mallas, {} formatos)", invalidadas, carreras, reglas, layouts);
+    HttpResponse::Ok().json(json!({"status": "ok", "invalidadas": invalidadas, "carreras": carreras, "reglas": reglas, "scoring": scoring, "layouts": layouts}))
 }
 
-pub async fn datafiles_download_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+/// True si `name` es una ruta relativa que no sale del directorio de
+/// datafiles: sin raíz, unidad, `.` ni `..` (una ruta absoluta reemplazaría
+/// al directorio en `join`)
+fn nombre_seguro(name: &str) -> bool {
+    let ruta = std::path::Path::new(name);
+    !ruta.is_absolute() && ruta.components().all(|c| matches!(c, std::path::Component::Normal(_)))
+}
+
+pub async fn datafiles_download_handler(query: web::Query<std::collections::HashMap<String, String>>, datafiles: DatafilesConfig) -> impl Responder {
     let name = match query.get("name") {
         Some(n) if !n.trim().is_empty() => n.clone(),
-        _ => return HttpResponse::BadRequest().json(json!({"error": "missing name parameter"})),
+        _ => return ApiError::MissingParameter("name").error_response(),
     };
 
-    if name.contains("..") { return HttpResponse::BadRequest().json(json!({"error": "invalid name"})); }
-    let path = std::path::Path::new("src/datafiles").join(&name);
-    if !path.exists() { return HttpResponse::NotFound().json(json!({"error": "file not found"})); }
+    if !nombre_seguro(&name) { return ApiError::InvalidInput("invalid name".into()).error_response(); }
+    let path = datafiles.dir.join(&name);
+    if !path.exists() { return ApiError::FileNotFound(name).error_response(); }
 
     match tokio::fs::read(&path).await {
         Ok(bytes) => {
             // try to set mime by extension (simple mapping)
             let mime = match path.extension().and_then(std::ffi::OsStr::to_str) {
                 Some("xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                Some("xlsm") => "application/vnd.ms-excel.sheet.macroEnabled.12",
+                Some("xlsb") => "application/vnd.ms-excel.sheet.binary.macroEnabled.12",
                 Some("xls") => "application/vnd.ms-excel",
                 _ => "application/octet-stream",
             };
@@ -89,29 +332,33 @@ pub async fn datafiles_download_handler(query: web::Query<std::collections::Hash
                 .append_header((actix_web::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", name)))
                 .body(bytes)
         }
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("failed to read file: {}", e)})),
+        Err(e) => ApiError::Storage(format!("failed to read file: {}", e)).error_response(),
     }
 }
 
-pub async fn datafiles_delete_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+pub async fn datafiles_delete_handler(query: web::Query<std::collections::HashMap<String, String>>, datafiles: DatafilesConfig) -> impl Responder {
     let name = match query.get("name") {
         Some(n) if !n.trim().is_empty() => n.clone(),
-        _ => return HttpResponse::BadRequest().json(json!({"error": "missing name parameter"})),
+        _ => return ApiError::MissingParameter("name").error_response(),
     };
-    if name.contains("..") { return HttpResponse::BadRequest().json(json!({"error": "invalid name"})); }
-    let path = std::path::Path::new("src/datafiles").join(&name);
-    if !path.exists() { return HttpResponse::NotFound().json(json!({"error": "file not found"})); }
+    if !nombre_seguro(&name) { return ApiError::InvalidInput("invalid name".into()).error_response(); }
+    let path = datafiles.dir.join(&name);
+    if !path.exists() { return ApiError::FileNotFound(name).error_response(); }
     match tokio::fs::remove_file(&path).await {
-        Ok(_) => HttpResponse::Ok().json(json!({"status": "deleted", "name": name})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("failed to delete file: {}", e)})),
+        Ok(_) => {
+            crate::datafiles::cache::invalidar_archivo(&path);
+            HttpResponse::Ok().json(json!({"status": "deleted", "name": name}))
+        }
+        Err(e) => ApiError::Storage(format!("failed to delete file: {}", e)).error_response(),
     }
 }
 
-pub async fn datafiles_content_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
-    let qm = query.into_inner();
+/// `malla` (obligatoria, acepta `Malla.xlsx[Hoja]`) y `sheet` de la query,
+/// validando que la malla exista entre los datafiles
+fn malla_y_hoja(qm: &HashMap<String, String>) -> Result<(String, Option<String>), ApiError> {
     let raw_malla = match qm.get("malla").and_then(|s| if s.trim().is_empty() { None } else { Some(s.clone()) }) {
         Some(s) => s,
-        None => return HttpResponse::BadRequest().json(json!({"error": "malla parameter required"})),
+        None => return Err(ApiError::MissingParameter("malla")),
     };
 
     let mut malla = raw_malla.clone();
@@ -135,23 +382,97 @@ pub async fn datafiles_content_handler(query: web::Query<std::collections::HashM
 
     if let Ok((available_mallas, _ofertas, _porc)) = list_datafiles() {
         if !available_mallas.iter().any(|x| x == &malla) {
-            return HttpResponse::BadRequest().json(json!({"error": "malla not found among available datafiles", "available": available_mallas}));
+            return Err(ApiError::MallaNotFound { malla, available: available_mallas });
         }
     }
 
+    Ok((malla, sheet_opt))
+}
+
+/// ETag de `/datafiles/content` y `/datafiles/merge`: los tres archivos que
+/// lee `summarize_datafiles` y `variante` (ver `middleware::etag`)
+fn etag_datafiles(malla: &str, variante: String) -> Option<String> {
+    let (m, o, p) = crate::excel::resolve_datafile_paths(malla).ok()?;
+    etag::etag_de_archivos(&[&m, &o, &p], &variante)
+}
+
+pub async fn datafiles_content_handler(req: HttpRequest, query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    let (malla, sheet_opt) = match malla_y_hoja(&query) {
+        Ok(v) => v,
+        Err(e) => return e.error_response(),
+    };
+    let etag = etag_datafiles(&malla, format!("content|{}", sheet_opt.as_deref().unwrap_or("")));
+    if let Some(resp) = etag::no_modificado(&req, etag.as_deref()) {
+        return resp;
+    }
+
     match summarize_datafiles(&malla, sheet_opt.as_deref()) {
-        Ok(v) => HttpResponse::Ok().json(v),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("failed to summarize datafiles: {}", e)})),
+        Ok(v) => etag::con_etag(HttpResponse::Ok().json(v), etag.as_deref()),
+        Err(e) => ApiError::del_pipeline(&malla, format!("failed to summarize datafiles: {}", e)).error_response(),
     }
 }
 
+/// GET /datafiles/merge?malla=...[&sheet=...][&formato=json|csv]
+/// Tabla malla ↔ oferta ↔ porcentajes de `merge_malla_oferta_porcentajes`,
+/// para auditar qué ramos no se emparejaron. Con `formato=csv` se descarga.
+/// Lleva ETag: con `If-None-Match` vigente responde 304 sin volver a emparejar.
+pub async fn datafiles_merge_handler(req: HttpRequest, query: web::Query<HashMap<String, String>>) -> impl Responder {
+    let (malla, sheet_opt) = match malla_y_hoja(&query) {
+        Ok(v) => v,
+        Err(e) => return e.error_response(),
+    };
+    let csv = match query.get("formato").or_else(|| query.get("format")).map(|f| f.trim().to_lowercase()).as_deref() {
+        None | Some("json") => false,
+        Some("csv") => true,
+        Some(otro) => return ApiError::InvalidInput(format!("formato '{}' no soportado (usar 'json' o 'csv')", otro)).error_response(),
+    };
+    let etag = etag_datafiles(&malla, format!("merge|{}|{}", sheet_opt.as_deref().unwrap_or(""), if csv { "csv" } else { "json" }));
+    if let Some(resp) = etag::no_modificado(&req, etag.as_deref()) {
+        return resp;
+    }
+
+    let malla_blk = malla.clone();
+    let res = web::block(con_span_actual(move || {
+        summarize_datafiles(&malla_blk, sheet_opt.as_deref()).map(|(_, oferta_path, porcent_path, malla_map, oferta, porcent, porcent_names)| {
+            let (filas, reporte) = crate::algorithm::merge_malla_oferta_porcentajes(
+                &malla_map, &oferta, &porcent, &porcent_names, &crate::excel::FuzzyMatcher::default());
+            let nombre = |p: &std::path::Path| p.file_name().map(|n| n.to_string_lossy().to_string());
+            (nombre(&oferta_path), nombre(&porcent_path), filas, reporte)
+        }).map_err(|e| e.to_string())
+    })).await;
+
+    let resp = match res {
+        Ok(Ok((_, _, filas, _))) if csv => HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header(("Content-Disposition", format!("attachment; filename=\"merge_{}.csv\"", malla.trim_end_matches(".xlsx"))))
+            .body(crate::algorithm::merged_a_csv(&filas)),
+        Ok(Ok((oferta, porcentajes, filas, reporte))) => {
+            let sin_oferta = filas.iter().filter(|f| f.oferta_codigo.is_none()).count();
+            let sin_porcentaje = filas.iter().filter(|f| f.porcentaje.is_none()).count();
+            HttpResponse::Ok().json(json!({
+                "malla": malla,
+                "oferta": oferta,
+                "porcentajes": porcentajes,
+                "total": filas.len(),
+                "sin_oferta": sin_oferta,
+                "sin_porcentaje": sin_porcentaje,
+                "filas": filas,
+                "matching": reporte,
+            }))
+        }
+        Ok(Err(e)) => ApiError::del_pipeline(&malla, format!("failed to merge datafiles: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking error: {}", e)).error_response(),
+    };
+    etag::con_etag(resp, etag.as_deref())
+}
+
 pub async fn oferta_summary_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
     let oferta_file = match query.get("oferta") {
         Some(o) if !o.trim().is_empty() => o.clone(),
         _ => "OA2024.xlsx".to_string(),
     };
 
-    eprintln!("📋 Generando resumen de oferta: {}", oferta_file);
+    tracing::info!("📋 Generando resumen de oferta: {}", oferta_file);
 
     match crate::excel::oferta::resumen_oferta_academica(&oferta_file) {
         Ok(resumen) => {
@@ -168,10 +489,8 @@ pub async fn oferta_summary_handler(query: web::Query<std::collections::HashMap<
             HttpResponse::Ok().json(response)
         }
         Err(e) => {
-            eprintln!("❌ Error al generar resumen: {}", e);
-            HttpResponse::InternalServerError().json(json!({
-                "error": format!("failed to generate oferta summary: {}", e)
-            }))
+            tracing::error!("❌ Error al generar resumen: {}", e);
+            ApiError::InvalidDatafile { message: format!("failed to generate oferta summary: {}", e), details: None }.error_response()
         }
     }
 }
@@ -1,14 +1,20 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
 
 pub async fn debug_pa_names_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
     let qm = query.into_inner();
     let porcent_file = match qm.get("porcent").and_then(|s| if s.trim().is_empty() { None } else { Some(s.clone()) }) {
         Some(p) => p,
-        None => return HttpResponse::BadRequest().json(serde_json::json!({"error": "porcent parameter required"})),
+        None => return ApiError::MissingParameter("porcent").error_response(),
     };
 
-    match crate::excel::leer_porcentajes_aprobados_con_nombres(&porcent_file) {
-        Ok(v) => HttpResponse::Ok().json(v),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({"error": format!("excel error: {}", e)})),
+    match crate::datafiles::cache::porcentajes(&porcent_file) {
+        Ok(v) => HttpResponse::Ok().json(v.as_ref()),
+        Err(e) => ApiError::InvalidDatafile { message: format!("excel error: {}", e), details: None }.error_response(),
     }
 }
+
+/// Hits, misses e invalidaciones del caché de `excel::mapeo_maestro`
+pub async fn debug_mapeo_cache_handler() -> impl Responder {
+    HttpResponse::Ok().json(crate::excel::mapeo_builder::estadisticas_cache_mapeo())
+}
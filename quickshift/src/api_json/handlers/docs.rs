@@ -1,12 +1,12 @@
 use actix_web::{HttpResponse, Responder};
 
-const OPENAPI_JSON: &str = include_str!("../../openapi.json");
 const SWAGGER_HTML: &str = include_str!("../../swagger.html");
 
+/// Documento OpenAPI: `src/openapi.json` + esquemas y rutas generados (ver `api_json::openapi`)
 pub async fn openapi_json_handler() -> impl Responder {
     HttpResponse::Ok()
         .content_type("application/json; charset=utf-8")
-        .body(OPENAPI_JSON)
+        .body(crate::api_json::openapi::documento_json())
 }
 
 pub async fn swagger_ui_handler() -> impl Responder {
@@ -1,4 +1,6 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use actix_web::http::header::CONTENT_TYPE;
+use crate::api_error::ApiError;
 use serde_json::json;
 use std::fs::OpenOptions;
 use std::path::Path;
@@ -6,40 +8,44 @@ use std::fs::create_dir_all;
 use std::io::Write;
 use crate::api_json::InputParams;
 
+const STUDENTS_FILE: &str = "data/students.json";
+
+/// Lee los estudiantes guardados; un archivo vacío, ausente o inválido equivale a una lista vacía.
+fn load_students(file_path: &str) -> Vec<InputParams> {
+    if !Path::new(file_path).exists() {
+        return Vec::new();
+    }
+    match std::fs::read_to_string(file_path) {
+        Ok(contents) if !contents.trim().is_empty() => {
+            serde_json::from_str::<Vec<InputParams>>(&contents).unwrap_or_default()
+        }
+        _ => Vec::new(),
+    }
+}
+
 pub async fn save_student_handler(body: web::Json<serde_json::Value>) -> impl Responder {
     let body_value = body.into_inner();
     let json_str = match serde_json::to_string(&body_value) {
         Ok(s) => s,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("invalid JSON body: {}", e)})),
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
     };
 
     let student = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
         Ok(s) => s,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("failed to parse input: {}", e)})),
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
     };
 
     if student.email.trim().is_empty() {
-        return HttpResponse::BadRequest().json(json!({"error": "email is required"}));
+        return ApiError::MissingParameter("email").error_response();
     }
 
     let data_dir = "data";
     if let Err(e) = create_dir_all(data_dir) {
-        return HttpResponse::InternalServerError().json(json!({"error": format!("failed to create data dir: {}", e)}));
+        return ApiError::Storage(format!("failed to create data dir: {}", e)).error_response();
     }
 
     let file_path = format!("{}/students.json", data_dir);
-    let mut students: Vec<InputParams> = Vec::new();
-    if Path::new(&file_path).exists() {
-        match std::fs::read_to_string(&file_path) {
-            Ok(contents) if !contents.trim().is_empty() => {
-                match serde_json::from_str::<Vec<InputParams>>(&contents) {
-                    Ok(v) => students = v,
-                    Err(_) => { /* ignore and start fresh */ }
-                }
-            }
-            _ => { /* empty file or read error -> start fresh */ }
-        }
-    }
+    let mut students = load_students(&file_path);
 
     students.retain(|s| s.email.to_lowercase() != student.email.to_lowercase());
     students.push(student);
@@ -48,13 +54,246 @@ pub async fn save_student_handler(body: web::Json<serde_json::Value>) -> impl Re
         Ok(mut f) => {
             match serde_json::to_string_pretty(&students) {
                 Ok(text) => {
-                    if let Err(e) = f.write_all(text.as_bytes()) { return HttpResponse::InternalServerError().json(json!({"error": format!("failed to write students: {}", e)})); }
+                    if let Err(e) = f.write_all(text.as_bytes()) { return ApiError::Storage(format!("failed to write students: {}", e)).error_response(); }
                 }
-                Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("failed to serialize students: {}", e)})),
+                Err(e) => return ApiError::Internal(format!("failed to serialize students: {}", e)).error_response(),
             }
         }
-        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("failed to open file: {}", e)})),
+        Err(e) => return ApiError::Storage(format!("failed to open file: {}", e)).error_response(),
     }
 
     HttpResponse::Ok().json(json!({"status": "ok", "count": students.len()}))
 }
+
+/// GET /students/{email}/bloqueantes
+/// Ramos pendientes que inician la cadena de prerequisitos más larga del
+/// estudiante (ver `algorithm::bloqueantes`), con cuántos ramos bloquea cada uno.
+pub async fn student_bloqueantes_handler(path: web::Path<String>) -> impl Responder {
+    let email = path.into_inner();
+    let student = match load_students(STUDENTS_FILE).into_iter().find(|s| s.email.eq_ignore_ascii_case(&email)) {
+        Some(s) => s,
+        None => return ApiError::StudentNotFound(email).error_response(),
+    };
+
+    let res = web::block(move || -> Result<_, ApiError> {
+        let (malla_path, _, porcentajes_path) = crate::excel::resolve_datafile_paths(&student.malla)
+            .map_err(|e| ApiError::del_pipeline(&student.malla, format!("failed to resolve malla '{}': {}", student.malla, e)))?;
+        let malla_str = malla_path.to_string_lossy().to_string();
+        let ramos = crate::algorithm::ruta::cargar_ramos_malla(&malla_str, &porcentajes_path.to_string_lossy())
+            .map_err(|e| ApiError::InvalidDatafile { message: format!("failed to read malla '{}': {}", malla_str, e), details: None })?;
+        let ramos_pasados = match crate::excel::cargar_equivalencias(&malla_str) {
+            Ok(eq) if !eq.is_empty() => crate::excel::aplicar_equivalencias(&student.ramos_pasados, &eq),
+            _ => student.ramos_pasados.clone(),
+        };
+        Ok((student.malla, crate::algorithm::bloqueantes::calcular_bloqueantes(&ramos, &ramos_pasados)))
+    }).await;
+
+    match res {
+        Ok(Ok((malla, analisis))) => HttpResponse::Ok().json(json!({
+            "email": email,
+            "malla": malla,
+            "ramos_pendientes": analisis.ramos_pendientes,
+            "cadena_maxima": analisis.cadena_maxima,
+            "bloqueantes": analisis.bloqueantes,
+        })),
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
+
+/// Body JSON de `POST /students/import-avance`
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct ImportarAvanceRequest {
+    /// Malla contra la que se resuelven los ramos (igual que `InputParams::malla`)
+    pub malla: String,
+    /// Texto CSV del avance curricular
+    pub contenido: String,
+}
+
+/// POST /students/import-avance
+/// Recibe el avance curricular como CSV (body `text/csv` con `?malla=`, o
+/// JSON `{malla, contenido}`) y devuelve los `ramos_pasados` resueltos contra
+/// la malla, junto con las filas no reconocidas para confirmarlas a mano.
+pub async fn import_avance_handler(req: HttpRequest, body: web::Bytes) -> impl Responder {
+    let es_json = req.headers().get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|ct| ct.starts_with("application/json"))
+        .unwrap_or(false);
+    let (malla, contenido) = if es_json {
+        match serde_json::from_slice::<ImportarAvanceRequest>(&body) {
+            Ok(r) => (r.malla, r.contenido),
+            Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
+        }
+    } else {
+        let query = web::Query::<std::collections::HashMap<String, String>>::from_query(req.query_string())
+            .map(|q| q.into_inner())
+            .unwrap_or_default();
+        let Some(malla) = query.get("malla").cloned() else {
+            return ApiError::MissingParameter("malla").error_response();
+        };
+        match String::from_utf8(body.to_vec()) {
+            Ok(texto) => (malla, texto),
+            Err(_) => return ApiError::InvalidBody("CSV must be UTF-8 text (PDF exports must be converted to CSV first)".into()).error_response(),
+        }
+    };
+    if malla.trim().is_empty() {
+        return ApiError::MissingParameter("malla").error_response();
+    }
+    if contenido.trim().is_empty() {
+        return ApiError::InvalidInput("empty transcript".into()).error_response();
+    }
+
+    let res = web::block(move || -> Result<_, ApiError> {
+        let (malla_path, _, porcentajes_path) = crate::excel::resolve_datafile_paths(&malla)
+            .map_err(|e| ApiError::del_pipeline(&malla, format!("failed to resolve malla '{}': {}", malla, e)))?;
+        let malla_str = malla_path.to_string_lossy().to_string();
+        let ramos = crate::algorithm::ruta::cargar_ramos_malla(&malla_str, &porcentajes_path.to_string_lossy())
+            .map_err(|e| ApiError::InvalidDatafile { message: format!("failed to read malla '{}': {}", malla_str, e), details: None })?;
+        let equivalencias = crate::excel::cargar_equivalencias(&malla_str).unwrap_or_default();
+        Ok((malla, crate::excel::avance::importar_avance(&contenido, &ramos, &equivalencias)))
+    }).await;
+
+    match res {
+        Ok(Ok((malla, avance))) => {
+            tracing::info!("📥 Avance importado: {} ramos reconocidos, {} filas sin reconocer", avance.ramos_pasados.len(), avance.no_reconocidas.len());
+            HttpResponse::Ok().json(json!({
+                "malla": malla,
+                "ramos_pasados": avance.ramos_pasados,
+                "no_reconocidas": avance.no_reconocidas,
+                "omitidas": avance.omitidas,
+            }))
+        }
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Escenarios con nombre (`analithics::scenarios`)
+// ---------------------------------------------------------------------------
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct GuardarEscenarioRequest {
+    pub nombre: String,
+    /// Mismo formato que el body de `POST /solve`
+    pub params: serde_json::Value,
+}
+
+#[derive(serde::Deserialize)]
+pub struct VersionQuery {
+    pub version: Option<i64>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct DiffEscenariosQuery {
+    pub a: String,
+    pub b: String,
+    pub version_a: Option<i64>,
+    pub version_b: Option<i64>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct RestaurarEscenarioRequest {
+    pub version: i64,
+}
+
+/// Ejecuta una operación sobre la DB de analytics en el pool bloqueante.
+/// `Ok(None)` se responde como 404.
+async fn con_escenarios<F>(f: F) -> HttpResponse
+where
+    F: FnOnce(&crate::analithics::db::AnalyticsConn) -> Result<Option<serde_json::Value>, String> + Send + 'static,
+{
+    let res = web::block(move || {
+        let conn = crate::analithics::db::open_analytics_connection().map_err(|e| format!("analytics DB unavailable: {}", e))?;
+        f(&conn)
+    }).await;
+    match res {
+        Ok(Ok(Some(v))) => HttpResponse::Ok().json(v),
+        Ok(Ok(None)) => ApiError::ScenarioNotFound.error_response(),
+        Ok(Err(msg)) => ApiError::Storage(msg).error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
+
+/// POST /students/{email}/scenarios
+/// Guarda una versión nueva del escenario `nombre`. Los parámetros se validan
+/// igual que en `POST /solve` y se guardan ya resueltos.
+pub async fn save_scenario_handler(path: web::Path<String>, body: web::Json<GuardarEscenarioRequest>) -> impl Responder {
+    let email = path.into_inner();
+    let GuardarEscenarioRequest { nombre, params } = body.into_inner();
+    let nombre = nombre.trim().to_string();
+    if nombre.is_empty() {
+        return ApiError::MissingParameter("nombre").error_response();
+    }
+    let mut resolved = match crate::api_json::parse_and_resolve_ramos(&params.to_string(), Some(".")) {
+        Ok(p) => p,
+        Err(e) => return ApiError::InvalidInput(format!("failed to parse params: {}", e)).error_response(),
+    };
+    resolved.email = email.clone();
+    let params = match serde_json::to_value(&resolved) {
+        Ok(v) => v,
+        Err(e) => return ApiError::Internal(format!("failed to serialize params: {}", e)).error_response(),
+    };
+
+    con_escenarios(move |conn| {
+        crate::analithics::scenarios::guardar_escenario(conn, &email, &nombre, &params, None)
+            .map(|v| Some(json!(v)))
+            .map_err(|e| format!("failed to save scenario: {}", e))
+    }).await
+}
+
+/// GET /students/{email}/scenarios
+pub async fn list_scenarios_handler(path: web::Path<String>) -> impl Responder {
+    let email = path.into_inner();
+    con_escenarios(move |conn| {
+        crate::analithics::scenarios::listar_escenarios(conn, &email)
+            .map(|l| Some(json!({"email": email, "escenarios": l})))
+            .map_err(|e| format!("failed to list scenarios: {}", e))
+    }).await
+}
+
+/// GET /students/{email}/scenarios/{nombre}[?version=N]
+/// Devuelve la versión pedida (o la vigente) y el historial de versiones.
+pub async fn get_scenario_handler(path: web::Path<(String, String)>, query: web::Query<VersionQuery>) -> impl Responder {
+    let (email, nombre) = path.into_inner();
+    let version = query.version;
+    con_escenarios(move |conn| {
+        let historial = crate::analithics::scenarios::historial_escenario(conn, &email, &nombre)
+            .map_err(|e| format!("failed to read scenario: {}", e))?;
+        let Some(actual) = historial.iter().find(|v| version.map(|n| v.version == n).unwrap_or(true)) else {
+            return Ok(None);
+        };
+        let versiones: Vec<serde_json::Value> = historial.iter()
+            .map(|v| json!({"version": v.version, "ts": v.ts, "restaurado_de": v.restaurado_de}))
+            .collect();
+        Ok(Some(json!({"escenario": actual, "historial": versiones})))
+    }).await
+}
+
+/// GET /students/{email}/scenarios/diff?a=..&b=..[&version_a=N&version_b=M]
+pub async fn diff_scenarios_handler(path: web::Path<String>, query: web::Query<DiffEscenariosQuery>) -> impl Responder {
+    let email = path.into_inner();
+    let q = query.into_inner();
+    con_escenarios(move |conn| {
+        use crate::analithics::scenarios::{obtener_escenario, diff_escenarios};
+        let a = obtener_escenario(conn, &email, &q.a, q.version_a).map_err(|e| e.to_string())?;
+        let b = obtener_escenario(conn, &email, &q.b, q.version_b).map_err(|e| e.to_string())?;
+        let (Some(a), Some(b)) = (a, b) else { return Ok(None) };
+        let mut diff = diff_escenarios(&a.params, &b.params);
+        diff["a"] = json!({"nombre": a.nombre, "version": a.version});
+        diff["b"] = json!({"nombre": b.nombre, "version": b.version});
+        Ok(Some(diff))
+    }).await
+}
+
+/// POST /students/{email}/scenarios/{nombre}/restore
+/// Body: { "version": N }. Crea una versión nueva con el contenido de N.
+pub async fn restore_scenario_handler(path: web::Path<(String, String)>, body: web::Json<RestaurarEscenarioRequest>) -> impl Responder {
+    let (email, nombre) = path.into_inner();
+    let version = body.version;
+    con_escenarios(move |conn| {
+        crate::analithics::scenarios::restaurar_escenario(conn, &email, &nombre, version)
+            .map(|v| v.map(|v| json!(v)))
+            .map_err(|e| format!("failed to restore scenario: {}", e))
+    }).await
+}
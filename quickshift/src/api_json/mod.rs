@@ -2,7 +2,10 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use crate::excel::asignatura_from_nombre;
 use crate::models::UserFilters;
+#[cfg(feature = "server")]
 pub mod handlers;
+#[cfg(feature = "server")]
+pub mod openapi;
 
 /// Parámetros de entrada para la ejecución de Ruta Crítica
 ///
@@ -31,7 +34,8 @@ pub mod handlers;
 ///     "preferencias_profesores": {
 ///       "habilitado": false,
 ///       "profesores_preferidos": ["Dr. García"],
-///       "profesores_evitar": []
+///       "profesores_evitar": [],
+///       "modo": "soft"
 ///     },
 ///     "balance_lineas": {
 ///       "habilitado": false,
@@ -39,6 +43,11 @@ pub mod handlers;
 ///         "informatica": 0.6,
 ///         "telecomunicaciones": 0.4
 ///       }
+///     },
+///     "tiempo_traslado": {
+///       "habilitado": false,
+///       "minutos": 30,
+///       "modo": "hard"
 ///     }
 ///   }
 /// }
@@ -48,17 +57,40 @@ pub mod handlers;
 /// - `email`: Email del estudiante (requerido)
 /// - `ramos_pasados`: Lista de códigos/nombres de ramos ya aprobados (Regla 0: Prerequisitos)
 /// - `ramos_prioritarios`: Ramos que el estudiante quiere priorizar
+/// - `ramos_excluidos`: Ramos que el estudiante no quiere tomar este semestre
+/// - `ramos_obligatorios`: Ramos que toda solución debe incluir
 /// - `horarios_preferidos`: Rangos horarios preferidos (formato "HH:MM-HH:MM")
-/// - `malla`: Nombre del archivo de Malla Curricular (requerido)
+/// - `malla`: Nombre del archivo de Malla Curricular (requerido salvo que venga `carrera`)
+/// - `carrera`: Id de carrera del registro `careers.toml` (opcional)
+/// - `periodo`: `"regular"` (default) o `"verano"`: oferta de verano y a lo más 2 ramos
 /// - `sheet`: Hoja interna dentro del workbook (opcional)
 /// - `student_ranking`: Ranking académico como percentil 0.0-1.0 (Regla 2: Probabilidad aprobación)
+/// - `promedio`: Promedio de notas 1.0-7.0, alternativa a `student_ranking`
 /// - `ranking`: Preferencias de ranking del usuario
 /// - `filtros`: Filtros opcionales del usuario (Reglas 3-6). Cada filtro tiene `habilitado: true/false`
-#[derive(Debug, Serialize, Deserialize)]
+///
+/// `Default` coincide con los `#[serde(default)]` de cada campo; sirve para
+/// construir parámetros con `..Default::default()` sin listar todos los campos.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
 pub struct InputParams {
 	pub email: String,
 	pub ramos_pasados: Vec<String>,
 	pub ramos_prioritarios: Vec<String>,
+	/// Ramos (códigos o nombres) que no deben aparecer en ninguna solución;
+	/// sus secciones se descartan antes de armar el grafo.
+	#[serde(default)]
+	pub ramos_excluidos: Vec<String>,
+	/// Ramos (códigos o nombres) que toda solución debe incluir. A diferencia
+	/// de `ramos_prioritarios` no es un bonus: si no caben juntos el request
+	/// falla con el reporte de `algorithm::obligatorios::ConflictoObligatorios`.
+	#[serde(default)]
+	pub ramos_obligatorios: Vec<String>,
+	/// Ramos (códigos o nombres) que el estudiante reprobó y debe volver a
+	/// tomar. Como suelen bloquear el avance, cada uno en la solución suma
+	/// `bonus_ramo_reprobado` (ver `scoring`) sin marcarlos como prioritarios.
+	#[serde(default)]
+	pub ramos_reprobados: Vec<String>,
     /// Franjas horarias preferidas (legacy). Formato: ["08:00-10:00", ...]
     #[serde(default)]
     pub horarios_preferidos: Vec<String>,
@@ -67,7 +99,14 @@ pub struct InputParams {
     /// Se acepta que el cliente envíe este campo y el algoritmo lo usará para excluir secciones.
     #[serde(default)]
     pub horarios_prohibidos: Vec<String>,
+    /// Compromisos semanales (trabajo, deporte): los `hard` descartan las
+    /// secciones que los pisan y los `soft` penalizan la solución. Cada
+    /// solución informa cuáles respeta (ver `algorithm::compromisos`).
+    #[serde(default)]
+    pub compromisos: Vec<crate::models::Compromiso>,
 	// Required: which curricular map to use. Example values: "MallaCurricular2010.xlsx", "MallaCurricular2018.xlsx", "MallaCurricular2020.xlsx"
+	// Puede omitirse si se indica `carrera` (se usa la primera malla de la carrera).
+	#[serde(default)]
 	pub malla: String,
     /// Año objetivo: si se especifica, se intentará seleccionar la Malla/MC del año dado.
     /// Si se omite, se usará la `malla` tal cual (nombre de archivo) y se buscará OA/PA más recientes.
@@ -79,6 +118,11 @@ pub struct InputParams {
 	// Optional: ranking académico del alumno expresado como percentil (0.0 - 1.0)
 	pub student_ranking: Option<f64>,
 
+	/// Promedio de notas (1.0-7.0); alternativa a `student_ranking`, que se
+	/// completa con su percentil en la cohorte (ver `algorithm::percentil`)
+	#[serde(default)]
+	pub promedio: Option<f64>,
+
 	// Optional ranking/preferences provided by the user (may be absent)
 	pub ranking: Option<Vec<String>>,
 
@@ -97,6 +141,138 @@ pub struct InputParams {
 	/// Se aplican como modificadores de puntuación al generar soluciones.
 	#[serde(default)]
 	pub optimizations: Vec<String>,
+
+	/// Doble titulación: mallas adicionales a considerar junto a `malla`.
+	/// Los ramos se fusionan en un único grafo de prerequisitos; los ramos
+	/// compartidos (mismo nombre o equivalentes) se deduplican y se priorizan.
+	/// Las secciones de la oferta de cada malla adicional se suman a la principal,
+	/// y el avance de cada programa usa solo las equivalencias de su malla.
+	#[serde(default)]
+	pub mallas_adicionales: Vec<String>,
+
+	/// Algoritmo de búsqueda de cliques: `"greedy"` (default, multi-seed heurístico),
+	/// `"exact"` (branch-and-bound, óptimo si termina dentro del presupuesto) o
+	/// `"rapido"` (solo el greedy, con latencia acotada; ver `algorithm::rapido`).
+	/// También se acepta como `modo`.
+	#[serde(default, alias = "modo")]
+	pub solver: SolverMode,
+
+	/// Presupuesto de tiempo (ms) para `solver: "exact"`. Si se omite se usa
+	/// `EXACT_SOLVER_BUDGET_MS` o 2000 ms.
+	#[serde(default)]
+	pub solver_time_budget_ms: Option<u64>,
+
+	/// Tope de créditos SCT por solución. Si se define, reemplaza el límite de
+	/// ramos (`reglas::max_ramos`); los ramos sin créditos en la malla cuentan
+	/// `clique::CREDITOS_SCT_POR_DEFECTO`.
+	#[serde(default)]
+	pub max_creditos: Option<i32>,
+
+	/// Semilla para variar soluciones: reordena de forma determinista los
+	/// candidatos con igual prioridad (misma semilla -> mismas soluciones).
+	#[serde(default)]
+	pub diversity_seed: Option<u64>,
+
+	/// Si se define, las primeras soluciones se eligen para que cada una
+	/// difiera de las anteriores en al menos esta cantidad de secciones
+	/// (ver `algorithm::diversidad::diversificar`).
+	#[serde(default)]
+	pub diversity_min_diff: Option<usize>,
+
+	/// Tiempo máximo (ms) de la búsqueda de soluciones en `/solve` y
+	/// `/solve/session`. Al vencer se devuelven las mejores soluciones
+	/// encontradas hasta ese momento con `partial: true`. Si se omite se usa
+	/// `SOLVE_TIMEOUT_MS` o 30000 ms.
+	#[serde(default)]
+	pub timeout_ms: Option<u64>,
+
+	/// Id de la carrera en el registro (`careers.toml`, ver `crate::carreras`).
+	/// Define la oferta, porcentajes y CFG a usar, los electivos admitidos y
+	/// cuántos CFG exige. Sin él se usa la configuración de una sola carrera.
+	#[serde(default)]
+	pub carrera: Option<String>,
+
+	/// Criterio principal para ordenar las soluciones: `"score"` (default) o
+	/// `"expected_progress"` (créditos esperados según `student_ranking` y el
+	/// porcentaje de aprobación de cada ramo, ver `algorithm::progreso_esperado`).
+	#[serde(default)]
+	pub objetivo: Objetivo,
+
+	/// Objetivos en orden de importancia (`["min_dias", "max_prioritarios",
+	/// "min_ventanas"]`): las soluciones se ordenan comparando sus métricas
+	/// objetivo por objetivo y el score solo desempata. Tiene precedencia sobre
+	/// `objetivo` (ver `algorithm::lexicografico`).
+	#[serde(default)]
+	pub objetivos: Vec<String>,
+
+	/// Ajustes de los pesos del score solo para este request (ver
+	/// `crate::scoring`). Los campos omitidos usan la configuración del servidor.
+	#[serde(default)]
+	pub scoring: Option<crate::scoring::ScoringOverrides>,
+
+	/// Penaliza las secciones que los estudiantes rara vez confirman en las
+	/// soluciones guardadas (`penalizacion_historial` por sección, ver
+	/// `crate::scoring::HistorialSeccion`).
+	#[serde(default)]
+	pub usar_historial: bool,
+
+	/// Secciones (`codigo_box`) que toda solución debe incluir; el resto del
+	/// horario se arma alrededor de ellas. Si no pueden ir juntas el request
+	/// falla con el reporte de `algorithm::fijas::ConflictoFijas`.
+	#[serde(default)]
+	pub secciones_fijas: Vec<String>,
+
+	/// Período a planificar. En `"verano"` se usa la oferta de verano
+	/// (`OA<año>V.xlsx`), cada solución lleva a lo más `max_ramos_verano`
+	/// ramos (ver `crate::reglas`) y el score privilegia la ruta crítica.
+	#[serde(default)]
+	pub periodo: Periodo,
+
+	/// Paginación de `/solve`: a lo más `top_n` soluciones (todas si se
+	/// omite), saltando las primeras `offset`, entre las que tienen
+	/// `total_score >= min_score`. Se aplica después de ordenar; la respuesta
+	/// informa `total_solutions` (ver `algorithm::paginacion`).
+	#[serde(default)]
+	pub top_n: Option<usize>,
+	#[serde(default)]
+	pub offset: usize,
+	#[serde(default)]
+	pub min_score: Option<i64>,
+}
+
+/// Período académico (ver `InputParams::periodo`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Periodo {
+	#[default]
+	Regular,
+	#[serde(alias = "summer")]
+	Verano,
+}
+
+/// Criterio de orden de las soluciones (ver `InputParams::objetivo`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Objetivo {
+	#[default]
+	Score,
+	ExpectedProgress,
+}
+
+/// Modo del solver de cliques (ver `InputParams::solver`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SolverMode {
+	#[default]
+	#[serde(alias = "heuristic")]
+	Greedy,
+	Exact,
+	/// Greedy sin el respaldo exhaustivo, con límite `rapido::latencia_objetivo_ms`
+	#[serde(alias = "fast")]
+	Rapido,
 }
 
 pub fn parse_json_input(json_str: &str) -> Result<InputParams, serde_json::Error> {
@@ -125,7 +301,13 @@ where
     P: AsRef<Path>,
     F: Fn(&Path, &str) -> Result<Option<String>, Box<dyn std::error::Error>>,
 {
-    let params = parse_json_input(json_str)?;
+    let mut params = parse_json_input(json_str)?;
+    crate::carreras::aplicar_carrera(&mut params)?;
+    crate::algorithm::percentil::normalizar(&mut params)?;
+    crate::algorithm::lexicografico::criterios(&params.objetivos)?;
+    if let Some(f) = params.filtros.as_ref() {
+        crate::algorithm::carga_diaria::validar(f)?;
+    }
 
     // delegar la lógica de resolución a la función que acepta InputParams
     resolve_ramos_with_resolver(params, base_dir, resolver)
@@ -159,7 +341,41 @@ where
 
     params.ramos_pasados = params.ramos_pasados.into_iter().map(resolve_one).collect();
     params.ramos_prioritarios = params.ramos_prioritarios.into_iter().map(resolve_one).collect();
+    params.ramos_excluidos = params.ramos_excluidos.into_iter().map(resolve_one).collect();
+    params.ramos_obligatorios = params.ramos_obligatorios.into_iter().map(resolve_one).collect();
+    normalizar_horarios(&mut params);
 
     Ok(params)
 }
 
+/// Lleva los horarios, compromisos y filtros del usuario a la forma interna: días en
+/// inglés ("MON") a su código ("LU") y horas de 12 horas a 24 (ver
+/// `models::horario::normalizar`)
+pub fn normalizar_horarios(params: &mut InputParams) {
+    use crate::models::horario::{normalizar, normalizar_dia, normalizar_hora};
+    for h in params.horarios_preferidos.iter_mut().chain(params.horarios_prohibidos.iter_mut()) {
+        *h = normalizar(h);
+    }
+    for c in params.compromisos.iter_mut() {
+        c.dia = c.dia.split_whitespace().map(normalizar_dia).collect::<Vec<_>>().join(" ");
+        c.inicio = normalizar_hora(&c.inicio);
+        c.fin = normalizar_hora(&c.fin);
+    }
+    if let Some(f) = params.filtros.as_mut() {
+        for h in f.hora_inicio_minima.iter_mut().chain(f.hora_fin_maxima.iter_mut()) {
+            *h = normalizar_hora(h);
+        }
+    }
+    let Some(dhl) = params.filtros.as_mut().and_then(|f| f.dias_horarios_libres.as_mut()) else {
+        return;
+    };
+    for dia in dhl.dias_libres_preferidos.iter_mut().flatten() {
+        *dia = normalizar_dia(dia);
+    }
+    for franja in dhl.franjas_prohibidas.iter_mut().flatten() {
+        franja.dia = normalizar_dia(&franja.dia);
+        franja.inicio = normalizar_hora(&franja.inicio);
+        franja.fin = normalizar_hora(&franja.fin);
+    }
+}
+
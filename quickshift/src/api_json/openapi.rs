@@ -0,0 +1,255 @@
+// openapi.rs - Documento OpenAPI servido en `/openapi.json`
+//
+// La base es `src/openapi.json`, con descripciones y ejemplos escritos a mano.
+// Sobre ella se agrega:
+// - `components.schemas` generados con schemars desde los mismos tipos que
+//   deserializan los handlers (`InputParams`, `UserFilters`, `RefineRequest`,
+//   ...): si cambia un campo, el esquema cambia con él.
+// - Cada operación de `OPERACIONES`: las que no están en el JSON base se
+//   generan (resumen, parámetros de ruta/query, body y errores) y a las que sí
+//   están se les completa el body y la respuesta de error `ApiError`.
+//
+// `OPERACIONES` debe cubrir todas las rutas de `server::run_server`; lo
+// verifica `tests/openapi_tests.rs`.
+
+use schemars::r#gen::{SchemaGenerator, SchemaSettings};
+use serde_json::{json, Map, Value};
+use std::sync::OnceLock;
+
+const OPENAPI_BASE: &str = include_str!("../openapi.json");
+
+/// Una ruta HTTP documentada
+pub struct Operacion {
+    pub metodo: &'static str,
+    pub ruta: &'static str,
+    pub tag: &'static str,
+    pub resumen: &'static str,
+    /// Esquema del body (nombre en `components.schemas`), si recibe JSON tipado
+    pub body: Option<&'static str>,
+    /// Parámetros de query aceptados
+    pub consulta: &'static [&'static str],
+}
+
+const fn op(metodo: &'static str, ruta: &'static str, tag: &'static str, resumen: &'static str) -> Operacion {
+    Operacion { metodo, ruta, tag, resumen, body: None, consulta: &[] }
+}
+
+const fn con_body(metodo: &'static str, ruta: &'static str, tag: &'static str, resumen: &'static str, body: &'static str) -> Operacion {
+    Operacion { metodo, ruta, tag, resumen, body: Some(body), consulta: &[] }
+}
+
+const fn con_query(metodo: &'static str, ruta: &'static str, tag: &'static str, resumen: &'static str, consulta: &'static [&'static str]) -> Operacion {
+    Operacion { metodo, ruta, tag, resumen, body: None, consulta }
+}
+
+/// Todas las rutas registradas en `server::run_server`
+pub const OPERACIONES: &[Operacion] = &[
+    op("get", "/", "docs", "Redirige a la documentación Swagger UI"),
+    con_body("post", "/solve", "solve", "Genera soluciones de horario para el estudiante (`?debug=graph`: solo el grafo de compatibilidad, sin enumerar)", "InputParams"),
+    con_query("get", "/solve", "solve", "Resolver horario por query params (equivalente a POST /solve; `filtros` y `scoring` como JSON URL-encoded o base64)", &["malla", "carrera", "email", "ramos_pasados", "ramos_prioritarios", "ramos_excluidos", "ramos_obligatorios", "ramos_reprobados", "student_ranking", "promedio", "horarios_preferidos", "horarios_prohibidos", "compromisos", "optimizations", "mallas_adicionales", "filtros", "scoring", "usar_historial", "solver", "modo", "max_creditos", "timeout_ms", "objetivo", "objetivos", "top_n", "offset", "min_score", "format", "debug"]),
+    con_body("post", "/solve/explain", "solve", "Motivos por los que un ramo (`ramo`) no aparece en ninguna solución", "InputParams"),
+    con_body("post", "/solve/precheck", "solve", "Ramos y secciones que quedan tras cada etapa de filtrado (pendientes, horizonte de semestres, prerequisitos, filtros del usuario, reglas de la malla) sin buscar cliques", "InputParams"),
+    con_query("post", "/solve/async", "solve", "Encola un POST /solve (mismo body) y responde 202 con `job_id`; para modos exhaustivos que superan el timeout HTTP", &["format"]),
+    op("get", "/jobs/{id}", "solve", "Estado de un job de /solve/async (en_cola, ejecutando, completado, fallido) y avance: secciones viables y cliques encontrados"),
+    op("get", "/jobs/{id}/result", "solve", "Respuesta de /solve del job terminado (o su error); 409 JOB_NOT_FINISHED si aún no termina"),
+    op("post", "/solve/export/ics", "solve", "Exporta una solución (`secciones`) como calendario iCalendar (.ics)"),
+    con_body("post", "/solve/export/xlsx", "solve", "Resuelve como POST /solve y devuelve un XLSX con una hoja por solución: grilla semanal, ramos y desglose del score (requiere la feature `export-xlsx`)", "InputParams"),
+    con_body("post", "/solve/export/pdf", "solve", "Resuelve como POST /solve y devuelve un PDF para imprimir con la grilla semanal y el resumen de cada solución (requiere la feature `export-pdf`)", "InputParams"),
+    con_query("post", "/solve/batch", "solve", "Resuelve una cohorte: body JSON (arreglo de InputParams o `{estudiantes, comun}`) o CSV con una fila por estudiante; devuelve el resultado de cada uno y un reporte agregado", &["max_soluciones", "malla", "carrera"]),
+    con_body("post", "/solve/session", "solve", "Igual que POST /solve, dejando en memoria datos y grafo para refinar", "InputParams"),
+    con_body("post", "/solve/session/{id}/refine", "solve", "Reaplica filtros y preferencias sobre una sesión existente", "RefineRequest"),
+    op("delete", "/solve/session/{id}", "solve", "Libera una sesión antes de que expire"),
+    op("get", "/ws/solve", "solve", "WebSocket: comandos incrementales (start, add-passed-course, toggle-filter, pin-section) y soluciones recalculadas tras cada uno"),
+    con_body("post", "/solutions/compare", "solve", "Compara dos soluciones (`secciones` o `solution_id` + `indice`): ramos solo en una, ramos en otra sección y diferencia de score, ventanas, días y compactación", "CompararSolucionesRequest"),
+    op("get", "/solutions/{id}", "solve", "Conjunto de soluciones guardado por /solve (`solution_id`)"),
+    con_query("get", "/solutions/{id}/export/ics", "solve", "Exporta la solución `indice` de un conjunto guardado como iCalendar (.ics)", &["indice", "fecha_inicio", "fecha_fin", "semanas", "nombre"]),
+    con_body("post", "/solutions/{id}/confirm", "solve", "Vincula la solución elegida (`indice`) al perfil del estudiante", "ConfirmarSolucionRequest"),
+    con_body("post", "/solutions/{id}/feedback", "solve", "Valora una solución (`rating` 1-5 y comentario opcional)", "FeedbackRequest"),
+    con_body("post", "/students", "students", "Guarda un perfil de estudiante (se indexa por email)", "InputParams"),
+    con_body("post", "/students/import-avance", "students", "Importa el avance curricular (CSV, o JSON con `contenido`) y lo resuelve a `ramos_pasados`", "ImportarAvanceRequest"),
+    op("get", "/students/{email}/bloqueantes", "students", "Ramos pendientes que bloquean la cadena de prerequisitos más larga"),
+    op("get", "/students/{email}/solution", "students", "Última solución confirmada por el estudiante"),
+    con_body("post", "/students/{email}/scenarios", "students", "Guarda una versión nueva de un escenario con nombre", "GuardarEscenarioRequest"),
+    op("get", "/students/{email}/scenarios", "students", "Lista los escenarios del estudiante"),
+    con_query("get", "/students/{email}/scenarios/diff", "students", "Diferencias entre dos escenarios", &["a", "b", "version_a", "version_b"]),
+    con_query("get", "/students/{email}/scenarios/{nombre}", "students", "Obtiene un escenario (última versión o `version`)", &["version"]),
+    con_body("post", "/students/{email}/scenarios/{nombre}/restore", "students", "Restaura una versión anterior de un escenario", "RestaurarEscenarioRequest"),
+    op("get", "/config", "admin", "Configuración efectiva: quickshift.toml (o APP_CONFIG_FILE) y variables de entorno, sin secretos (requiere X-Admin-Token)"),
+    op("get", "/config/datafiles", "admin", "Directorio de datafiles activo y de dónde salió (requiere X-Admin-Token)"),
+    con_body("put", "/config/datafiles", "admin", "Cambia y persiste el directorio de datafiles; invalida cachés y sesiones (requiere X-Admin-Token)", "DatafilesConfigRequest"),
+    con_body("post", "/admin/rollover", "admin", "Cambio de semestre: valida, archiva, invalida cachés y precalienta (requiere X-Admin-Token)", "RolloverRequest"),
+    con_body("post", "/plan/carrera", "solve", "Proyecta semestre a semestre hasta completar la malla", "InputParams"),
+    con_body("post", "/simulate/reprobacion", "solve", "Ramos que se atrasan, nueva ruta crítica y semestres extra si se reprueba `reprobado`", "InputParams"),
+    con_body("post", "/progress", "students", "Avance hacia la titulación: créditos por semestre, ruta crítica completada, pendientes (obligatorios, electivos, CFG) y semestres restantes mínimos", "ProgresoRequest"),
+    con_query("post", "/simulate/demanda", "solve", "Inscripción esperada por sección (mejor horario de cada estudiante de la cohorte, mismo body que POST /solve/batch) frente a sus cupos; marca las que desbordan o están en riesgo", &["escala", "umbral_riesgo", "malla", "carrera"]),
+    con_query("get", "/analithics/ramos_pasados", "analithics", "Ramos pasados por un estudiante", &["email"]),
+    op("get", "/analithics/ranking_por_estudiante", "analithics", "Ranking de cursos por interacción/solicitudes"),
+    op("get", "/analithics/count_users", "analithics", "Cuenta de usuarios indexados"),
+    op("get", "/analithics/filtros_mas_solicitados", "analithics", "Filtros más solicitados"),
+    con_query("get", "/analithics/ramos_mas_recomendados", "analithics", "Ramos más recomendados para un estudiante", &["email"]),
+    op("get", "/analithics/profesores_cursos", "analithics", "Mapeo de profesores a cursos"),
+    con_query("get", "/analithics/cursos_por_malla", "analithics", "Listado de cursos disponibles en una malla", &["malla"]),
+    op("get", "/analithics/horarios_mas_recomendados", "analithics", "Horarios más recomendados ponderados por puntaje"),
+    op("get", "/analithics/cache_stats/latest", "analithics", "Última instantánea de estadísticas de caché"),
+    con_query("get", "/analithics/cache_stats/recent", "analithics", "Últimas N filas de estadísticas de caché", &["limit"]),
+    con_query("get", "/analytics/dashboard", "analithics", "Métricas agregadas por día o semana: consultas, ramos y filtros más pedidos, latencia y cantidad de soluciones", &["from", "to", "bucket"]),
+    con_query("delete", "/analytics/logs", "admin", "Borra consultas, reportes y cache_stats anteriores a `before` (requiere X-Admin-Token)", &["before"]),
+    con_query("get", "/analytics/export", "admin", "Descarga la tabla de consultas en CSV o JSON Lines (requiere X-Admin-Token)", &["format"]),
+    op("get", "/analytics/feedback/summary", "analithics", "Valoraciones de soluciones agrupadas por configuración de scoring (objetivo, solver, optimizaciones)"),
+    op("post", "/rutacomoda/best", "rutacritica", "Mejores caminos (máximo puntaje, o más cómodos si viene `comodidad`) de la ruta crítica"),
+    op("post", "/rutacomoda/rank", "rutacritica", "Reordena soluciones por comodidad (ventanas, inicio temprano, días en campus, profesores) según un perfil y pesos"),
+    con_body("post", "/rutacritica/run", "rutacritica", "Ejecuta el orquestador con body JSON (igual que POST /solve)", "InputParams"),
+    con_body("post", "/rutacritica/run-dependencies-only", "rutacritica", "Ruta crítica solo por dependencias, sin conflictos de horario", "InputParams"),
+    op("get", "/carreras", "datafiles", "Registro de carreras: mallas, oferta, porcentajes, CFG y reglas de electivos"),
+    op("get", "/datafiles", "datafiles", "Lista archivos disponibles"),
+    con_query("delete", "/datafiles", "datafiles", "Elimina un archivo de datafiles por nombre", &["name"]),
+    con_query("post", "/datafiles/upload", "datafiles", "Sube mallas, ofertas o porcentajes (multipart o JSON base64)", &["dry_run", "oferta"]),
+    op("post", "/datafiles/reload", "datafiles", "Invalida el caché de datafiles y relee el registro de carreras y los pesos de scoring"),
+    con_query("get", "/datafiles/validate", "datafiles", "Reporte de validación de una malla", &["malla", "sheet", "oferta"]),
+    con_query("get", "/datafiles/download", "datafiles", "Descarga un archivo de datafiles", &["name"]),
+    con_query("get", "/datafiles/content", "datafiles", "Resumen de malla/oferta/porcentajes y hojas internas; con ETag (`If-None-Match` vigente responde 304)", &["malla", "sheet"]),
+    con_query("get", "/datafiles/merge", "datafiles", "Tabla malla ↔ oferta ↔ porcentajes emparejada por nombre, con los matches aproximados y ambiguos; `formato=csv` la descarga; con ETag (`If-None-Match` vigente responde 304)", &["malla", "sheet", "formato"]),
+    con_query("get", "/datafiles/oferta/summary", "datafiles", "Resumen de oferta académica agrupada por ramo", &["oferta"]),
+    con_query("get", "/oferta", "datafiles", "Secciones de la oferta académica filtradas por curso, profesor, día y rango horario, paginadas con el total", &["curso", "profesor", "dia", "desde", "hasta", "page", "per_page", "oferta"]),
+    con_query("get", "/profesores", "datafiles", "Busca profesores de la oferta por parte del nombre (sin distinguir mayúsculas ni tildes)", &["q", "oferta"]),
+    con_query("get", "/profesores/{nombre}", "datafiles", "Cursos y secciones de un profesor este semestre, con el porcentaje histórico de aprobación de cada curso", &["oferta", "porcentajes"]),
+    con_query("get", "/api/mallas/{malla_id}/semestres/{semestre}/cursos", "cursos", "Cursos de un semestre de la malla", &["sheet"]),
+    con_query("get", "/api/mallas/{malla_id}/cursos", "cursos", "Todos los cursos de la malla", &["sheet"]),
+    con_query("get", "/pert", "cursos", "Red PERT de la malla: ES/EF/LS/LF, holgura y ruta crítica", &["malla", "sheet"]),
+    con_query("get", "/malla/{id}/grafo", "cursos", "DAG de prerequisitos de la malla en JSON (nodes/edges) o Graphviz DOT", &["format", "sheet"]),
+    con_query("get", "/malla/{id}/reglas", "cursos", "CFGs y electivos que exige la malla (reglas.toml)", &["carrera"]),
+    con_query("get", "/malla/{id}/criticos", "cursos", "Ramos de la malla por urgencia (holgura, LS, ramos que desbloquea) con la ruta crítica marcada", &["sheet"]),
+    con_query("get", "/equivalencias", "cursos", "Hoja de equivalencias de la malla: pares, cadenas (A→B→C), ambiguas, fuera de malla, duplicados y ramos con el mismo nombre y otro código en otras mallas", &["malla", "comparar"]),
+    con_body("post", "/equivalencias/resolve", "cursos", "Lleva códigos históricos (`codigos`) a la malla, informando cadenas, ambiguos y duplicados; /solve lo aplica sobre `ramos_pasados`", "ResolverEquivalenciasRequest"),
+    con_body("post", "/api/cursos/recomendados", "cursos", "Cursos elegibles según ramos aprobados", "CursosRecomendadosRequest"),
+    con_body("post", "/api/cursos/disponibles", "cursos", "Cursos disponibles (malla, CFG y electivos)", "CursosDisponiblesRequest"),
+    con_body("post", "/api/profesores/disponibles", "cursos", "Profesores de los cursos que el estudiante puede tomar", "ProfesoresDisponiblesRequest"),
+    con_query("get", "/datafiles/debug/pa-names", "datafiles", "Nombres leídos de un archivo de porcentajes (debug)", &["porcent"]),
+    op("get", "/datafiles/debug/mapeo-cache", "datafiles", "Estadísticas del caché del mapeo maestro (debug)"),
+    con_query("get", "/help", "docs", "Describe la API y muestra ejemplos en JSON (`lang=es|en`)", &["lang"]),
+    op("get", "/metrics", "docs", "Métricas del solver (duración, secciones y soluciones por fase) en formato Prometheus"),
+    op("get", "/health/live", "docs", "Sonda de vida: el proceso responde"),
+    op("get", "/health/ready", "docs", "Sonda de disponibilidad: datafiles, malla por defecto y base de analíticas (503 si algo falla)"),
+    op("get", "/openapi.json", "docs", "Este documento OpenAPI"),
+    op("get", "/api-doc/openapi.json", "docs", "Este documento OpenAPI (ruta anterior)"),
+    op("get", "/api-docs", "docs", "Swagger UI"),
+];
+
+/// Esquemas generados desde los tipos de los handlers, con referencias
+/// `#/components/schemas/...`
+pub fn esquemas_generados() -> Map<String, Value> {
+    use crate::api_json::handlers::courses::{CursosDisponiblesRequest, CursosRecomendadosRequest, ProfesoresDisponiblesRequest};
+    use crate::api_json::handlers::students::{GuardarEscenarioRequest, ImportarAvanceRequest, RestaurarEscenarioRequest};
+    use crate::server_handlers::{CompararSolucionesRequest, ConfirmarSolucionRequest, DatafilesConfigRequest, FeedbackRequest, ProgresoRequest, RefineRequest, ResolverEquivalenciasRequest, RolloverRequest};
+
+    let mut generador: SchemaGenerator = SchemaSettings::openapi3().into_generator();
+    generador.subschema_for::<crate::api_json::InputParams>();
+    generador.subschema_for::<crate::models::UserFilters>();
+    generador.subschema_for::<crate::models::Seccion>();
+    generador.subschema_for::<RefineRequest>();
+    generador.subschema_for::<RolloverRequest>();
+    generador.subschema_for::<ProgresoRequest>();
+    generador.subschema_for::<ResolverEquivalenciasRequest>();
+    generador.subschema_for::<DatafilesConfigRequest>();
+    generador.subschema_for::<ConfirmarSolucionRequest>();
+    generador.subschema_for::<CompararSolucionesRequest>();
+    generador.subschema_for::<FeedbackRequest>();
+    generador.subschema_for::<GuardarEscenarioRequest>();
+    generador.subschema_for::<RestaurarEscenarioRequest>();
+    generador.subschema_for::<ImportarAvanceRequest>();
+    generador.subschema_for::<CursosRecomendadosRequest>();
+    generador.subschema_for::<CursosDisponiblesRequest>();
+    generador.subschema_for::<ProfesoresDisponiblesRequest>();
+
+    generador.take_definitions()
+        .into_iter()
+        .filter_map(|(nombre, esquema)| serde_json::to_value(esquema).ok().map(|v| (nombre, v)))
+        .collect()
+}
+
+fn respuesta_error() -> Value {
+    json!({
+        "description": "Error con código legible por máquina",
+        "content": {"application/json": {"schema": {"$ref": "#/components/schemas/ApiError"}}}
+    })
+}
+
+fn parametros(op: &Operacion) -> Vec<Value> {
+    let de_ruta = op.ruta.split('/')
+        .filter_map(|seg| seg.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+        .map(|nombre| json!({"name": nombre, "in": "path", "required": true, "schema": {"type": "string"}}));
+    let de_query = op.consulta.iter()
+        .map(|nombre| json!({"name": nombre, "in": "query", "required": false, "schema": {"type": "string"}}));
+    de_ruta.chain(de_query).collect()
+}
+
+fn body(esquema: &str) -> Value {
+    json!({
+        "required": true,
+        "content": {"application/json": {"schema": {"$ref": format!("#/components/schemas/{}", esquema)}}}
+    })
+}
+
+/// Construye el documento completo (base + esquemas + operaciones)
+pub fn construir_documento() -> Value {
+    let mut doc: Value = serde_json::from_str(OPENAPI_BASE).unwrap_or_else(|e| {
+        tracing::warn!("⚠️  openapi.json base inválido ({}); se genera desde cero", e);
+        json!({"openapi": "3.0.0", "info": {"title": "QuickShift API", "version": "1.0.0"}})
+    });
+
+    let esquemas = &mut doc["components"]["schemas"];
+    if !esquemas.is_object() {
+        *esquemas = json!({});
+    }
+    if let Some(mapa) = esquemas.as_object_mut() {
+        for (nombre, esquema) in esquemas_generados() {
+            mapa.insert(nombre, esquema);
+        }
+    }
+
+    // API key opcional: solo se exige si el servidor tiene claves configuradas
+    if doc["components"]["securitySchemes"]["ApiKeyAuth"].is_null() {
+        doc["components"]["securitySchemes"]["ApiKeyAuth"] = json!({"type": "apiKey", "in": "header", "name": "X-API-Key"});
+    }
+    if doc.get("security").is_none() {
+        doc["security"] = json!([{"ApiKeyAuth": []}, {}]);
+    }
+
+    for op in OPERACIONES {
+        let entrada = &mut doc["paths"][op.ruta][op.metodo];
+        if entrada.is_null() {
+            let mut nueva = json!({
+                "tags": [op.tag],
+                "summary": op.resumen,
+                "responses": {"200": {"description": "OK"}},
+            });
+            let params = parametros(op);
+            if !params.is_empty() {
+                nueva["parameters"] = Value::Array(params);
+            }
+            *entrada = nueva;
+        }
+        if entrada.get("tags").is_none() {
+            entrada["tags"] = json!([op.tag]);
+        }
+        if let Some(esquema) = op.body {
+            // Se conserva el ejemplo escrito a mano; el esquema siempre es el generado
+            match entrada["requestBody"]["content"]["application/json"].as_object_mut() {
+                Some(contenido) => {
+                    contenido.insert("schema".to_string(), json!({"$ref": format!("#/components/schemas/{}", esquema)}));
+                }
+                None => entrada["requestBody"] = body(esquema),
+            }
+        }
+        if entrada["responses"].get("default").is_none() {
+            entrada["responses"]["default"] = respuesta_error();
+        }
+    }
+    doc
+}
+
+/// Documento serializado, construido una sola vez por proceso
+pub fn documento_json() -> &'static str {
+    static DOC: OnceLock<String> = OnceLock::new();
+    DOC.get_or_init(|| construir_documento().to_string())
+}
@@ -0,0 +1,270 @@
+// carreras.rs - Registro de carreras (varias mallas/facultades en un servidor)
+//
+// Hasta ahora todo asumía una sola familia de mallas (códigos CIT) y tomaba la
+// oferta, los porcentajes y el CFG más recientes del directorio de datafiles.
+// `careers.toml` (o el archivo de `CAREERS_FILE`) declara cada carrera:
+//
+//   [[carrera]]
+//   id = "ICI"
+//   nombre = "Ingeniería Civil Informática"
+//   facultad = "Ingeniería"
+//   mallas = ["MC2020.xlsx", "MC2018.xlsx"]   # la primera es la default
+//   oferta = "OA20251.xlsx"                   # opcional: si falta, la más reciente
+//   porcentajes = "PA20251.xlsx"              # opcional
+//   cfg = "CFG20251.xlsx"                     # opcional
+//   prefijos = ["CIT"]                        # electivos admitidos (vacío = todos)
+//   max_cfg = 4                               # CFGs que exige la carrera
+//   electivos = true                          # false: no se ofrecen electivos
+//   distribucion_promedios = { media = 5.1, desviacion = 0.5 }   # opcional
+//
+// `InputParams::carrera` elige la carrera; sin ella (o sin registro) se
+// mantiene el comportamiento de una sola carrera.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use crate::algorithm::percentil::DistribucionPromedios;
+use crate::api_json::{InputParams, Periodo};
+use crate::models::Seccion;
+
+/// CFGs exigidos cuando la carrera no define `max_cfg`
+pub const MAX_CFG_POR_DEFECTO: usize = 4;
+
+fn max_cfg_por_defecto() -> usize {
+    MAX_CFG_POR_DEFECTO
+}
+
+fn verdadero() -> bool {
+    true
+}
+
+/// Una carrera del registro
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Carrera {
+    pub id: String,
+    pub nombre: String,
+    #[serde(default)]
+    pub facultad: Option<String>,
+    /// Archivos de malla de la carrera; el primero se usa si el request no trae `malla`
+    pub mallas: Vec<String>,
+    #[serde(default)]
+    pub oferta: Option<String>,
+    #[serde(default)]
+    pub porcentajes: Option<String>,
+    #[serde(default)]
+    pub cfg: Option<String>,
+    /// Prefijos de código de los electivos admitidos (vacío = cualquiera)
+    #[serde(default)]
+    pub prefijos: Vec<String>,
+    #[serde(default = "max_cfg_por_defecto")]
+    pub max_cfg: usize,
+    #[serde(default = "verdadero")]
+    pub electivos: bool,
+    /// Promedios de la cohorte, para convertir `promedio` a percentil (ver
+    /// `algorithm::percentil`)
+    #[serde(default)]
+    pub distribucion_promedios: Option<DistribucionPromedios>,
+}
+
+impl Carrera {
+    /// Si `malla` (nombre o ruta) es una de las mallas de la carrera
+    pub fn tiene_malla(&self, malla: &str) -> bool {
+        let nombre = nombre_archivo(malla);
+        self.mallas.iter().any(|m| nombre_archivo(m).eq_ignore_ascii_case(nombre))
+    }
+
+    /// Si una sección de electivo (curso fuera de la malla) se ofrece en esta carrera
+    pub fn admite_electivo(&self, codigo: &str) -> bool {
+        if !self.electivos {
+            return false;
+        }
+        let codigo = codigo.trim().to_uppercase();
+        self.prefijos.is_empty() || self.prefijos.iter().any(|p| codigo.starts_with(&p.trim().to_uppercase()))
+    }
+}
+
+fn nombre_archivo(ruta: &str) -> &str {
+    Path::new(ruta).file_name().and_then(|n| n.to_str()).unwrap_or(ruta)
+}
+
+/// Contenido de `careers.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RegistroCarreras {
+    #[serde(default, rename = "carrera")]
+    pub carreras: Vec<Carrera>,
+}
+
+impl RegistroCarreras {
+    /// Parsea y valida el TOML: ids únicos (sin distinguir mayúsculas) y al menos una malla por carrera
+    pub fn desde_toml(texto: &str) -> Result<Self, String> {
+        let registro: RegistroCarreras = crate::app_config::desde_toml(texto).map_err(|e| format!("careers.toml inválido: {}", e))?;
+        let mut ids = HashSet::new();
+        for c in &registro.carreras {
+            if c.id.trim().is_empty() {
+                return Err("careers.toml: carrera sin id".into());
+            }
+            if !ids.insert(c.id.to_uppercase()) {
+                return Err(format!("careers.toml: id de carrera repetido '{}'", c.id));
+            }
+            if c.mallas.is_empty() {
+                return Err(format!("careers.toml: la carrera '{}' no declara mallas", c.id));
+            }
+            if let Some(d) = &c.distribucion_promedios {
+                d.validar().map_err(|e| format!("careers.toml: carrera '{}': {}", c.id, e))?;
+            }
+        }
+        Ok(registro)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Carrera> {
+        self.carreras.iter().find(|c| c.id.eq_ignore_ascii_case(id.trim()))
+    }
+}
+
+/// Ruta del registro: `careers_file` de la configuración (`CAREERS_FILE`, por
+/// defecto `careers.toml` en el directorio actual)
+pub fn ruta_registro() -> PathBuf {
+    crate::app_config::actual().careers_file.clone()
+}
+
+fn global() -> &'static RwLock<Option<Arc<RegistroCarreras>>> {
+    static REGISTRO: OnceLock<RwLock<Option<Arc<RegistroCarreras>>>> = OnceLock::new();
+    REGISTRO.get_or_init(|| RwLock::new(None))
+}
+
+/// Lee el registro desde disco. Sin archivo el registro queda vacío; si el
+/// archivo es inválido se avisa y también queda vacío.
+pub fn cargar_registro(ruta: &Path) -> RegistroCarreras {
+    let Ok(texto) = std::fs::read_to_string(ruta) else {
+        return RegistroCarreras::default();
+    };
+    match RegistroCarreras::desde_toml(&texto) {
+        Ok(r) => {
+            tracing::info!("🎓 Registro de carreras: {} carrera(s) desde {:?}", r.carreras.len(), ruta);
+            r
+        }
+        Err(e) => {
+            tracing::warn!("⚠️  {}", e);
+            RegistroCarreras::default()
+        }
+    }
+}
+
+/// Registro vigente (se lee de `ruta_registro()` la primera vez)
+pub fn registro() -> Arc<RegistroCarreras> {
+    if let Some(r) = global().read().ok().and_then(|g| g.clone()) {
+        return r;
+    }
+    let r = Arc::new(cargar_registro(&ruta_registro()));
+    if let Ok(mut g) = global().write() {
+        *g = Some(r.clone());
+    }
+    r
+}
+
+/// Reemplaza el registro vigente (recarga o pruebas)
+pub fn establecer_registro(registro: RegistroCarreras) {
+    if let Ok(mut g) = global().write() {
+        *g = Some(Arc::new(registro));
+    }
+}
+
+/// Vuelve a leer `careers.toml`
+pub fn recargar_registro() -> usize {
+    let r = cargar_registro(&ruta_registro());
+    let n = r.carreras.len();
+    establecer_registro(r);
+    n
+}
+
+/// Carrera pedida en `params` (None si no pidió ninguna)
+pub fn carrera_de(params: &InputParams) -> Result<Option<Carrera>, String> {
+    let Some(id) = params.carrera.as_deref().filter(|c| !c.trim().is_empty()) else {
+        return Ok(None);
+    };
+    registro().get(id).cloned()
+        .map(Some)
+        .ok_or_else(|| format!("carrera '{}' no existe en el registro", id))
+}
+
+/// Valida `carrera` y completa `malla` con la malla por defecto de la carrera
+/// cuando el request no la trae. Una malla ajena a la carrera es un error.
+pub fn aplicar_carrera(params: &mut InputParams) -> Result<(), String> {
+    let Some(carrera) = carrera_de(params)? else {
+        if params.malla.trim().is_empty() {
+            return Err("missing field `malla`".into());
+        }
+        return Ok(());
+    };
+    if params.malla.trim().is_empty() {
+        params.malla = carrera.mallas[0].clone();
+    } else if !carrera.tiene_malla(&params.malla) {
+        return Err(format!("la malla '{}' no pertenece a la carrera '{}' (mallas: {})", params.malla, carrera.id, carrera.mallas.join(", ")));
+    }
+    Ok(())
+}
+
+/// Un archivo declarado por la carrera, como ruta directa o dentro de los datafiles
+fn resolver_archivo(nombre: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let directa = Path::new(nombre);
+    if directa.is_file() {
+        return Ok(directa.to_path_buf());
+    }
+    let en_datafiles = crate::excel::get_datafiles_dir().join(nombre);
+    if en_datafiles.is_file() {
+        return Ok(en_datafiles);
+    }
+    Err(format!("archivo '{}' del registro de carreras no encontrado", nombre).into())
+}
+
+/// (malla, oferta, porcentajes) del request: los de la carrera cuando los
+/// declara, si no los que elige `excel::resolve_datafile_paths`. En verano la
+/// oferta es siempre la de verano más reciente (`excel::latest_verano_for_keywords`).
+pub fn resolver_archivos(params: &InputParams) -> Result<(PathBuf, PathBuf, PathBuf), Box<dyn Error>> {
+    let (malla, mut oferta, porcentajes) = crate::excel::resolve_datafile_paths(&params.malla)?;
+    if params.periodo == Periodo::Verano {
+        // El verano tiene su propia oferta (OA2025V); la de la carrera es la del semestre
+        oferta = crate::excel::latest_verano_for_keywords(&["oferta", "oa"])
+            .ok_or("periodo=verano pero no hay oferta de verano (p. ej. OA2025V.xlsx) en datafiles")?;
+    }
+    let Some(carrera) = carrera_de(params)? else {
+        return Ok((malla, oferta, porcentajes));
+    };
+    let oferta = match &carrera.oferta {
+        Some(o) if params.periodo == Periodo::Regular => resolver_archivo(o)?,
+        _ => oferta,
+    };
+    let porcentajes = match &carrera.porcentajes {
+        Some(p) => resolver_archivo(p)?,
+        None => porcentajes,
+    };
+    Ok((malla, oferta, porcentajes))
+}
+
+/// Oferta de CFG: la de la carrera o la más reciente del directorio. En
+/// verano solo una marcada como tal (`CFG2025V`): si no hay, no se ofrecen CFG
+pub fn archivo_cfg(params: &InputParams) -> Option<PathBuf> {
+    if params.periodo == Periodo::Verano {
+        return crate::excel::latest_verano_for_keywords(&["cfg"]);
+    }
+    match carrera_de(params).ok().flatten().and_then(|c| c.cfg) {
+        Some(cfg) => resolver_archivo(&cfg).ok(),
+        None => crate::excel::latest_file_for_keywords(&["cfg"]),
+    }
+}
+
+/// CFGs que exige el request: los de su malla en `reglas.toml`, si no los de
+/// la carrera (ver `reglas::RegistroReglas::para`)
+pub fn max_cfg(params: &InputParams) -> usize {
+    crate::reglas::de_params(params).max_cfg
+}
+
+/// Descarta los electivos que la carrera no admite. Devuelve cuántas secciones se quitaron.
+pub fn filtrar_electivos(params: &InputParams, secciones: &mut Vec<Seccion>) -> usize {
+    let Some(carrera) = carrera_de(params).ok().flatten() else { return 0 };
+    let antes = secciones.len();
+    secciones.retain(|s| !s.is_electivo || carrera.admite_electivo(&s.codigo));
+    antes - secciones.len()
+}
@@ -0,0 +1,132 @@
+// cli.rs - Subcomandos del binario que no levantan el servidor
+//
+// `main.rs` arma la línea de comandos con clap; aquí está lo que hace cada
+// subcomando para que se pueda probar sin procesos:
+//   - `solve`: el mismo pipeline que `POST /solve` sobre un JSON de
+//     `InputParams`, sin guardar nada en la DB de analíticas;
+//   - `validate-datafiles`: reconoce cada Excel del directorio de datafiles
+//     (`excel::deteccion`) y valida las mallas contra la oferta más reciente
+//     (`excel::validate`);
+//   - `pert`: la red PERT de una malla, como `GET /pert`.
+// Las salidas son JSON con sangría para guardarlas y compararlas con diff; el
+// orden de las secciones empatadas puede variar entre procesos.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+use crate::algorithm::deadline::Deadline;
+use crate::excel::deteccion::{categoria_por_nombre, validar_datafile, TipoDatafile};
+use crate::excel::validate::{validar_malla, ReporteMalla};
+use crate::server_handlers::solve::{ejecutar_con_progreso, soluciones_to_entries};
+
+/// Resuelve un body de `POST /solve` y devuelve `soluciones_count`,
+/// `soluciones`, `programas` (si hay doble titulación) y `partial`
+pub fn solve_desde_json(json_str: &str) -> Result<Value, Box<dyn Error>> {
+    let params = crate::api_json::parse_and_resolve_ramos(json_str, Some("."))?;
+    let deadline = Deadline::desde_params(&params);
+    let (soluciones, programas, ramos, secciones) = ejecutar_con_progreso(params.clone(), &deadline)?;
+    let entries = soluciones_to_entries(&soluciones, &params, &ramos, &secciones);
+    let mut salida = json!({
+        "soluciones_count": soluciones.len(),
+        "soluciones": entries,
+        "partial": deadline.alcanzado(),
+    });
+    if !programas.is_empty() {
+        salida["programas"] = json!(programas);
+    }
+    Ok(salida)
+}
+
+/// Resultado de validar un archivo del directorio de datafiles
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchivoValidado {
+    pub archivo: String,
+    /// Tipo detectado por contenido (None si no se reconoce)
+    pub tipo: Option<TipoDatafile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hoja: Option<String>,
+    pub filas: usize,
+    /// Reporte de `excel::validate` para las mallas
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub malla: Option<ReporteMalla>,
+    pub errores: Vec<String>,
+    pub valido: bool,
+}
+
+/// Valida los Excel del directorio de datafiles activo cuyo nombre los
+/// clasifica como malla, oferta o porcentajes (los demás, como calendarios de
+/// evaluaciones, se omiten), en orden alfabético
+pub fn validar_datafiles() -> Result<Vec<ArchivoValidado>, Box<dyn Error>> {
+    let dir = crate::excel::get_datafiles_dir();
+    let dir = dir.as_path();
+    let mut nombres: Vec<String> = std::fs::read_dir(dir)
+        .map_err(|e| format!("no se pudo leer el directorio de datafiles {:?}: {}", dir, e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|n| {
+            let n = n.to_lowercase();
+            [".xlsx", ".xlsm", ".xlsb", ".xls"].iter().any(|e| n.ends_with(e)) && !n.starts_with("~$")
+        })
+        .filter(|n| categoria_por_nombre(n).is_some())
+        .collect();
+    nombres.sort();
+
+    // Las mallas se comparan con la oferta más reciente, como `GET /datafiles/validate`
+    let oferta = crate::excel::latest_file_for_keywords(&["oferta", "oa"])
+        .and_then(|p| {
+            let ruta = p.to_string_lossy().to_string();
+            let secciones = crate::datafiles::cache::oferta(&ruta).ok()?;
+            Some((p.file_name()?.to_string_lossy().to_string(), secciones))
+        });
+
+    Ok(nombres.into_iter().map(|archivo| {
+        let ruta = dir.join(&archivo);
+        match validar_datafile(&ruta, &archivo) {
+            Ok(detectado) => {
+                let reporte = (detectado.tipo == TipoDatafile::Malla).then(|| {
+                    validar_malla(&ruta, Some(&detectado.hoja), oferta.as_ref().map(|(n, s)| (n.as_str(), s.as_slice())))
+                });
+                let (malla, errores) = match reporte {
+                    Some(Ok(r)) => {
+                        let errores = r.errores.clone();
+                        (Some(r), errores)
+                    }
+                    Some(Err(e)) => (None, vec![e]),
+                    None => (None, Vec::new()),
+                };
+                ArchivoValidado {
+                    archivo,
+                    tipo: Some(detectado.tipo),
+                    hoja: Some(detectado.hoja),
+                    filas: detectado.filas,
+                    valido: errores.is_empty(),
+                    malla,
+                    errores,
+                }
+            }
+            Err(e) => ArchivoValidado { archivo, tipo: None, hoja: None, filas: 0, malla: None, errores: vec![e], valido: false },
+        }
+    }).collect())
+}
+
+/// Red PERT de una malla de datafiles: `malla`, `nodos` y `ruta_critica`
+pub fn pert(malla: &str, sheet: Option<&str>) -> Result<Value, Box<dyn Error>> {
+    let red = crate::api_json::handlers::courses::red_pert_de_malla(malla, sheet)?;
+    Ok(json!({
+        "malla": malla,
+        "nodos": red.nodos,
+        "ruta_critica": red.ruta_critica,
+    }))
+}
+
+/// Escribe `valor` como JSON con sangría en `ruta`, o en stdout sin ruta
+pub fn escribir_json(valor: &impl Serialize, ruta: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let texto = serde_json::to_string_pretty(valor)?;
+    match ruta {
+        Some(r) => std::fs::write(r, texto + "\n").map_err(|e| format!("no se pudo escribir {:?}: {}", r, e))?,
+        None => writeln!(std::io::stdout().lock(), "{}", texto)?,
+    }
+    Ok(())
+}
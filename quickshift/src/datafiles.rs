@@ -0,0 +1,9 @@
+// Acceso a los archivos de datos (mallas, ofertas, porcentajes).
+//
+// `src/datafiles/` es el directorio con los Excel que sube el administrador,
+// por eso el código de este módulo vive fuera de él.
+//   - `cache`: parsea cada archivo una sola vez y lo comparte entre requests;
+//     se invalida con el watcher del directorio o con `POST /datafiles/reload`.
+
+#[path = "datafiles_cache.rs"]
+pub mod cache;
@@ -0,0 +1,203 @@
+// datafiles_cache.rs - Caché en memoria de los datafiles ya parseados
+//
+// Leer un Excel toma cientos de milisegundos; antes algunos caminos lo hacían
+// en cada request y otros guardaban copias propias que quedaban obsoletas al
+// subir un archivo nuevo. Aquí cada archivo (o par malla + porcentajes) se
+// parsea una vez y se comparte como `Arc` entre requests.
+//
+// Las entradas se invalidan cuando:
+//   - el watcher del directorio (`iniciar_watcher`, feature `server`) ve que
+//     un .xlsx/.xls/.csv se creó, modificó o borró,
+//   - se sube o borra un archivo vía `/datafiles`,
+//   - se llama a `POST /datafiles/reload` (`invalidar_todo`).
+//
+// Los errores de lectura no se cachean: el siguiente request vuelve a intentar.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use crate::models::{RamoDisponible, Seccion};
+
+/// Porcentajes de aprobación por código y por nombre normalizado
+/// (ver `excel::leer_porcentajes_aprobados_con_nombres`)
+pub type PorcentajesConNombres = (HashMap<String, (f64, f64)>, HashMap<String, (String, f64, f64, bool)>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum TipoDato {
+    Oferta,
+    Malla,
+    MallaConPorcentajes,
+    Porcentajes,
+    Electivos,
+    Evaluaciones,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Clave {
+    tipo: TipoDato,
+    archivos: Vec<PathBuf>,
+    hoja: Option<String>,
+}
+
+type Valor = Arc<dyn Any + Send + Sync>;
+
+fn entradas() -> &'static RwLock<HashMap<Clave, Valor>> {
+    static ENTRADAS: OnceLock<RwLock<HashMap<Clave, Valor>>> = OnceLock::new();
+    ENTRADAS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Ruta absoluta para usar como clave. Los nombres sueltos ("OA2024.xlsx") se
+/// buscan en el directorio de datafiles, igual que hacen los lectores de `excel`.
+/// Si el archivo ya no existe (evento de borrado) se canoniza el directorio y
+/// se le agrega el nombre.
+fn normalizar(ruta: &Path) -> PathBuf {
+    if let Ok(p) = ruta.canonicalize() {
+        return p;
+    }
+    let en_datafiles = ruta.is_relative()
+        .then(|| crate::excel::get_datafiles_dir().join(ruta))
+        .and_then(|p| p.canonicalize().ok());
+    if let Some(p) = en_datafiles {
+        return p;
+    }
+    match (ruta.parent().and_then(|d| d.canonicalize().ok()), ruta.file_name()) {
+        (Some(dir), Some(nombre)) => dir.join(nombre),
+        _ => ruta.to_path_buf(),
+    }
+}
+
+fn obtener<T, F>(tipo: TipoDato, archivos: &[&str], hoja: Option<&str>, cargar: F) -> Result<Arc<T>, Box<dyn Error>>
+where
+    T: Send + Sync + 'static,
+    F: FnOnce() -> Result<T, Box<dyn Error>>,
+{
+    let clave = Clave {
+        tipo,
+        archivos: archivos.iter().map(|a| normalizar(Path::new(a))).collect(),
+        hoja: hoja.map(|h| h.to_string()),
+    };
+    let existente = entradas().read().ok().and_then(|m| m.get(&clave).cloned());
+    if let Some(Ok(valor)) = existente.map(|v| v.downcast::<T>()) {
+        return Ok(valor);
+    }
+
+    let valor = Arc::new(cargar()?);
+    tracing::debug!("🗃️  Datafile cacheado: {:?} {:?}", clave.tipo, clave.archivos);
+    if let Ok(mut m) = entradas().write() {
+        m.insert(clave, valor.clone());
+    }
+    Ok(valor)
+}
+
+/// Secciones de una oferta académica (`excel::leer_oferta_academica_excel`)
+pub fn oferta(ruta: &str) -> Result<Arc<Vec<Seccion>>, Box<dyn Error>> {
+    obtener(TipoDato::Oferta, &[ruta], None, || crate::excel::leer_oferta_academica_excel(ruta))
+}
+
+/// Malla sin porcentajes (`excel::leer_malla_excel_with_sheet`)
+pub fn malla(ruta: &str, hoja: Option<&str>) -> Result<Arc<HashMap<String, RamoDisponible>>, Box<dyn Error>> {
+    obtener(TipoDato::Malla, &[ruta], hoja, || crate::excel::leer_malla_excel_with_sheet(ruta, hoja))
+}
+
+/// Malla enriquecida con porcentajes, eligiendo el parser según el tipo de
+/// archivo: "MC*" usa el de Malla Curricular, el resto el de Malla2020/MiMalla.
+pub fn malla_con_porcentajes(malla: &str, porcentajes: &str) -> Result<Arc<HashMap<String, RamoDisponible>>, Box<dyn Error>> {
+    obtener(TipoDato::MallaConPorcentajes, &[malla, porcentajes], None, || {
+        if malla.to_uppercase().contains("MC") {
+            crate::excel::leer_mc_con_porcentajes_optimizado(malla, porcentajes)
+        } else {
+            crate::excel::leer_malla_con_porcentajes_optimizado(malla, porcentajes)
+        }
+    })
+}
+
+/// Porcentajes de aprobación (`excel::leer_porcentajes_aprobados_con_nombres`)
+pub fn porcentajes(ruta: &str) -> Result<Arc<PorcentajesConNombres>, Box<dyn Error>> {
+    obtener(TipoDato::Porcentajes, &[ruta], None, || crate::excel::leer_porcentajes_aprobados_con_nombres(ruta))
+}
+
+/// Catálogo de electivos de un workbook (`excel::electivos::leer_electivos`)
+pub fn electivos(ruta: &str) -> Result<Arc<crate::excel::electivos::CatalogoElectivos>, Box<dyn Error>> {
+    obtener(TipoDato::Electivos, &[ruta], None, || crate::excel::electivos::leer_electivos(ruta))
+}
+
+/// Calendario de evaluaciones (`excel::evaluaciones::leer_evaluaciones`)
+pub fn evaluaciones(ruta: &str) -> Result<Arc<crate::excel::evaluaciones::CalendarioEvaluaciones>, Box<dyn Error>> {
+    obtener(TipoDato::Evaluaciones, &[ruta], None, || crate::excel::evaluaciones::leer_evaluaciones(ruta))
+}
+
+/// Descarta las entradas que dependen de `ruta`. Devuelve cuántas se borraron.
+pub fn invalidar_archivo(ruta: &Path) -> usize {
+    let ruta = normalizar(ruta);
+    let Ok(mut m) = entradas().write() else { return 0 };
+    let antes = m.len();
+    m.retain(|clave, _| !clave.archivos.contains(&ruta));
+    antes - m.len()
+}
+
+/// Vacía el caché completo. Devuelve cuántas entradas había.
+pub fn invalidar_todo() -> usize {
+    let Ok(mut m) = entradas().write() else { return 0 };
+    let n = m.len();
+    m.clear();
+    n
+}
+
+/// Cantidad de entradas cacheadas
+pub fn cantidad_entradas() -> usize {
+    entradas().read().map(|m| m.len()).unwrap_or(0)
+}
+
+/// Archivos cuyo cambio debe invalidar el caché (se ignoran temporales de Excel "~$...")
+fn es_datafile(ruta: &Path) -> bool {
+    let nombre = ruta.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    if nombre.starts_with('.') || nombre.starts_with('~') {
+        return false;
+    }
+    matches!(
+        ruta.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref(),
+        Some("xlsx") | Some("xlsm") | Some("xlsb") | Some("xls") | Some("csv")
+    )
+}
+
+/// Observa `dir` e invalida las entradas de cada datafile que cambie. El
+/// watcher queda vivo mientras dure el proceso; llamarlo de nuevo con otro
+/// directorio (ver `PUT /config/datafiles`) lo mueve a ese directorio.
+#[cfg(feature = "server")]
+pub fn iniciar_watcher(dir: &Path) -> notify::Result<()> {
+    use notify::{EventKind, RecursiveMode, Watcher};
+    use std::sync::Mutex;
+
+    static WATCHER: OnceLock<Mutex<(notify::RecommendedWatcher, PathBuf)>> = OnceLock::new();
+    if let Some(actual) = WATCHER.get() {
+        let mut actual = actual.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = normalizar(dir);
+        if actual.1 != dir {
+            let anterior = std::mem::replace(&mut actual.1, dir.clone());
+            let _ = actual.0.unwatch(&anterior);
+            actual.0.watch(&dir, RecursiveMode::NonRecursive)?;
+            tracing::info!("👀 Observando cambios en {:?}", dir);
+        }
+        return Ok(());
+    }
+
+    let dir = normalizar(dir);
+    let mut watcher = notify::recommended_watcher(|res: notify::Result<notify::Event>| match res {
+        Ok(evento) => {
+            if matches!(evento.kind, EventKind::Access(_)) {
+                return;
+            }
+            for ruta in evento.paths.iter().filter(|p| es_datafile(p)) {
+                let n = invalidar_archivo(ruta);
+                tracing::info!("🔄 Datafile cambiado: {:?} ({} entradas invalidadas)", ruta, n);
+            }
+        }
+        Err(e) => tracing::warn!("⚠️ Error del watcher de datafiles: {}", e),
+    })?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    tracing::info!("👀 Observando cambios en {:?}", dir);
+    let _ = WATCHER.set(Mutex::new((watcher, dir)));
+    Ok(())
+}
@@ -1,11 +1,11 @@
-use calamine::{open_workbook_auto, Data, Reader};
+use calamine::{Data, Reader};
 use std::path::Path;
-use crate::excel::io::{cell_to_string, normalize_header};
+use crate::excel::io::{abrir_libro, cell_to_string, normalize_header};
 
 /// Busca en el archivo Excel la fila cuyo "Nombre Asignado" coincide con `nombre_asignado`
 /// y retorna el valor de la columna "Asignatura" si se encuentra.
 pub fn asignatura_from_nombre<P: AsRef<Path>>(path: P, nombre_asignado: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
-    let mut workbook = open_workbook_auto(path)?;
+    let mut workbook = abrir_libro(path)?;
     let target_norm = nombre_asignado.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect::<String>();
 
     for sheet_name in workbook.sheet_names().to_owned() {
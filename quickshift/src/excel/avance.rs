@@ -0,0 +1,182 @@
+// avance.rs - Importación del avance curricular de un estudiante
+//
+// Convierte un CSV exportado por el estudiante (o el export "avance
+// curricular" de la universidad guardado como CSV/texto) en la lista de
+// `ramos_pasados` de una malla:
+// - El separador se detecta por línea de encabezado (`;`, `,` o tabulador).
+// - Las líneas previas al encabezado (datos del alumno, carrera, ...) se
+//   ignoran; si no hay encabezado reconocible cada celda se prueba como
+//   código o nombre.
+// - Si hay columna de estado o nota, sólo cuentan las filas aprobadas
+//   (estado "aprobado"/"convalidado" o nota >= 4.0).
+// - Cada fila se busca por código (aplicando `Equivalencias` de la malla) y
+//   luego por nombre normalizado (`normalize_name`).
+//
+// Los PDF no se leen directamente: deben exportarse a CSV o texto antes.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::excel::normalize_name;
+use crate::models::RamoDisponible;
+
+/// Nota mínima de aprobación (escala chilena 1.0 - 7.0)
+const NOTA_APROBACION: f64 = 4.0;
+
+const COLUMNAS_CODIGO: &[&str] = &["codigo", "cod", "sigla", "asignatura", "codigo asignatura", "cod asignatura"];
+const COLUMNAS_NOMBRE: &[&str] = &["nombre", "nombre asignatura", "nombre asignado", "ramo", "curso", "descripcion"];
+const COLUMNAS_ESTADO: &[&str] = &["estado", "situacion", "resultado", "condicion"];
+const COLUMNAS_NOTA: &[&str] = &["nota", "nota final", "calificacion"];
+
+/// Fila que no se pudo asociar a un ramo de la malla, para confirmación manual
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FilaNoReconocida {
+    /// Número de línea (1-based) en el archivo
+    pub linea: usize,
+    pub contenido: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AvanceImportado {
+    /// Códigos de la malla aprobados, en el orden del archivo y sin repetir
+    pub ramos_pasados: Vec<String>,
+    pub no_reconocidas: Vec<FilaNoReconocida>,
+    /// Filas reconocidas pero no aprobadas (reprobadas, inscritas, ...)
+    pub omitidas: usize,
+}
+
+#[derive(Default)]
+struct Columnas {
+    codigo: Option<usize>,
+    nombre: Option<usize>,
+    estado: Option<usize>,
+    nota: Option<usize>,
+}
+
+impl Columnas {
+    fn desde_encabezado(celdas: &[String]) -> Option<Self> {
+        let buscar = |nombres: &[&str]| celdas.iter().position(|c| nombres.contains(&normalize_name(c).as_str()));
+        let cols = Columnas {
+            codigo: buscar(COLUMNAS_CODIGO),
+            nombre: buscar(COLUMNAS_NOMBRE),
+            estado: buscar(COLUMNAS_ESTADO),
+            nota: buscar(COLUMNAS_NOTA),
+        };
+        (cols.codigo.is_some() || cols.nombre.is_some()).then_some(cols)
+    }
+}
+
+/// Mapea las filas de `contenido` a códigos de `ramos` (la malla, indexada
+/// como la devuelve `cargar_ramos_malla`). `equivalencias` es la hoja
+/// `Equivalencias` de la malla (código antiguo -> código vigente).
+pub fn importar_avance(
+    contenido: &str,
+    ramos: &HashMap<String, RamoDisponible>,
+    equivalencias: &HashMap<String, String>,
+) -> AvanceImportado {
+    let por_codigo: HashMap<String, &str> = ramos.values()
+        .filter(|r| !r.codigo.trim().is_empty())
+        .map(|r| (r.codigo.trim().to_uppercase(), r.codigo.as_str()))
+        .collect();
+    let por_nombre: HashMap<String, &str> = ramos.values()
+        .filter(|r| !r.codigo.trim().is_empty())
+        .map(|r| (normalize_name(&r.nombre), r.codigo.as_str()))
+        .collect();
+    let resolver = |celda: &str| -> Option<String> {
+        let celda = celda.trim();
+        if celda.is_empty() {
+            return None;
+        }
+        let upper = celda.to_uppercase();
+        let codigo = equivalencias.get(&upper).map(|c| c.to_uppercase()).unwrap_or(upper);
+        por_codigo.get(&codigo)
+            .or_else(|| por_nombre.get(&normalize_name(celda)))
+            .map(|c| c.to_string())
+    };
+
+    let lineas: Vec<(usize, &str)> = contenido.lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim_start_matches('\u{feff}').trim()))
+        .filter(|(_, l)| !l.is_empty())
+        .collect();
+
+    // Primera línea (de las 10 iniciales) que parezca encabezado
+    let encabezado = lineas.iter().take(10).enumerate().find_map(|(pos, (_, l))| {
+        let sep = detectar_separador(l);
+        Columnas::desde_encabezado(&separar_campos(l, sep)).map(|c| (pos, sep, c))
+    });
+    let (inicio, sep, columnas) = match encabezado {
+        Some((pos, sep, c)) => (pos + 1, sep, Some(c)),
+        None => (0, lineas.first().map(|(_, l)| detectar_separador(l)).unwrap_or(','), None),
+    };
+
+    let mut res = AvanceImportado::default();
+    let mut vistos = HashSet::new();
+    for (linea, texto) in &lineas[inicio.min(lineas.len())..] {
+        let celdas = separar_campos(texto, sep);
+        let encontrado = match &columnas {
+            Some(c) => [c.codigo, c.nombre].into_iter().flatten()
+                .filter_map(|i| celdas.get(i))
+                .find_map(|celda| resolver(celda)),
+            None => celdas.iter().find_map(|celda| resolver(celda)),
+        };
+        let Some(codigo) = encontrado else {
+            res.no_reconocidas.push(FilaNoReconocida { linea: *linea, contenido: texto.to_string() });
+            continue;
+        };
+        if !columnas.as_ref().map(|c| fila_aprobada(c, &celdas)).unwrap_or(true) {
+            res.omitidas += 1;
+            continue;
+        }
+        if vistos.insert(codigo.clone()) {
+            res.ramos_pasados.push(codigo);
+        }
+    }
+    res
+}
+
+/// Aprobada según la columna de estado; si no hay estado reconocible, según la nota.
+/// Sin ninguna de las dos se asume aprobada.
+fn fila_aprobada(columnas: &Columnas, celdas: &[String]) -> bool {
+    let estado = columnas.estado.and_then(|i| celdas.get(i)).map(|s| normalize_name(s));
+    if let Some(e) = estado.filter(|e| !e.is_empty()) {
+        if e.contains("reprob") || e.contains("inscri") || e.contains("cursando") || e.contains("pendiente") || e.contains("retir") || e.contains("elimin") {
+            return false;
+        }
+        if e.contains("aprob") || e.contains("convalid") || e.contains("homolog") {
+            return true;
+        }
+    }
+    columnas.nota
+        .and_then(|i| celdas.get(i))
+        .and_then(|n| n.trim().replace(',', ".").parse::<f64>().ok())
+        .map(|n| n >= NOTA_APROBACION)
+        .unwrap_or(true)
+}
+
+pub(crate) fn detectar_separador(linea: &str) -> char {
+    [';', '\t', ','].into_iter()
+        .max_by_key(|sep| linea.matches(*sep).count())
+        .filter(|sep| linea.contains(*sep))
+        .unwrap_or(',')
+}
+
+/// Separa una línea CSV respetando comillas dobles (`""` escapa una comilla)
+pub(crate) fn separar_campos(linea: &str, sep: char) -> Vec<String> {
+    let mut campos = Vec::new();
+    let mut actual = String::new();
+    let mut en_comillas = false;
+    let mut chars = linea.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if en_comillas && chars.peek() == Some(&'"') => {
+                actual.push('"');
+                chars.next();
+            }
+            '"' => en_comillas = !en_comillas,
+            c if c == sep && !en_comillas => campos.push(std::mem::take(&mut actual).trim().to_string()),
+            c => actual.push(c),
+        }
+    }
+    campos.push(actual.trim().to_string());
+    campos
+}
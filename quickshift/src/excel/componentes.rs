@@ -0,0 +1,99 @@
+//! Componentes de una sección (cátedra, laboratorio/taller, ayudantía) y
+//! paquetes que deben inscribirse juntos.
+//!
+//! La Oferta Académica publica el laboratorio o la ayudantía de un ramo como
+//! filas aparte ("CBF1000_LA01", tipo "Laboratorio"). Al leer la oferta cada
+//! sección recibe su `Componente` y, si su ramo tiene cátedra y laboratorio o
+//! ayudantía con el mismo número de sección, todas comparten un `bundle_id`
+//! ("CBF1000#1"). `algorithm::bundles` usa ese id para programar el paquete
+//! como una sola unidad.
+
+use std::collections::BTreeMap;
+use crate::excel::normalize_name;
+use crate::models::{Componente, Seccion};
+
+/// Componente según el texto de una columna de tipo/actividad
+/// ("Cátedra", "LAB", "Taller", "Ayudantía"); None si no se reconoce
+fn componente_de_tipo(tipo: &str) -> Option<Componente> {
+    let t = normalize_name(tipo);
+    if t.starts_with("lab") || t.starts_with("tall") || t.starts_with("prac") {
+        Some(Componente::Lab)
+    } else if t.starts_with("ay") {
+        Some(Componente::Ayudantia)
+    } else if t.starts_with("cat") || t.starts_with("teo") || t.starts_with("clase") {
+        Some(Componente::Catedra)
+    } else {
+        None
+    }
+}
+
+/// Componente de una fila de la oferta: primero la columna de tipo (si
+/// existe), luego el sufijo del código ("_LA01", "_TA02", "_AY01") y por
+/// último palabras del nombre ("Laboratorio de Física", "Ayudantía ...").
+pub fn detectar(codigo: &str, nombre: &str, tipo: &str) -> Componente {
+    if let Some(c) = componente_de_tipo(tipo) {
+        return c;
+    }
+    if let Some((_, sufijo)) = codigo.trim().split_once('_') {
+        let letras: String = sufijo.chars().take_while(|c| c.is_ascii_alphabetic()).collect::<String>().to_uppercase();
+        match letras.as_str() {
+            "LA" | "LB" | "TA" => return Componente::Lab,
+            "AY" => return Componente::Ayudantia,
+            _ => {
+                if let Some(c) = componente_de_tipo(&letras) {
+                    return c;
+                }
+            }
+        }
+    }
+    let palabras: Vec<String> = nombre.split_whitespace().map(normalize_name).collect();
+    if palabras.iter().any(|p| p.starts_with("laboratorio") || p.starts_with("taller")) {
+        Componente::Lab
+    } else if palabras.iter().any(|p| p.starts_with("ayudantia")) {
+        Componente::Ayudantia
+    } else {
+        Componente::Catedra
+    }
+}
+
+/// Clave base de un curso: el nombre sin "laboratorio", "taller",
+/// "práctica" ni "ayudantía", normalizado
+pub fn clave_base(nombre: &str) -> String {
+    let mut s = nombre.to_lowercase();
+    for t in &["laboratorio", "laboratorios", "lab", "taller", "talleres", "practica", "práctica", "practicas", "prácticas", "ayudantias", "ayudantías", "ayudantia", "ayudantía"] {
+        s = s.replace(t, "");
+    }
+    normalize_name(&s)
+}
+
+/// "01" y "1" son la misma sección
+fn numero_seccion(seccion: &str) -> String {
+    let s = seccion.trim();
+    let sin_ceros = s.trim_start_matches('0');
+    if sin_ceros.is_empty() && !s.is_empty() { "0".to_string() } else { sin_ceros.to_uppercase() }
+}
+
+/// Asigna `bundle_id` a las secciones de un mismo código y número de
+/// sección cuando hay una cátedra y al menos un laboratorio o ayudantía.
+/// Los laboratorios sin cátedra con su número quedan sin paquete.
+pub fn asignar_bundles(secciones: &mut [Seccion]) {
+    let mut grupos: BTreeMap<(String, String), Vec<usize>> = BTreeMap::new();
+    for (i, s) in secciones.iter().enumerate() {
+        if s.is_cfg {
+            continue;
+        }
+        grupos.entry((s.codigo.trim().to_uppercase(), numero_seccion(&s.seccion))).or_default().push(i);
+    }
+    for ((codigo, seccion), miembros) in grupos {
+        let tiene_catedra = miembros.iter().any(|&i| secciones[i].componente == Componente::Catedra);
+        let tiene_otro = miembros.iter().any(|&i| secciones[i].componente != Componente::Catedra);
+        if tiene_catedra && tiene_otro {
+            let id = format!("{}#{}", codigo, seccion);
+            for &i in &miembros {
+                secciones[i].bundle_id = Some(id.clone());
+            }
+        } else if tiene_otro {
+            tracing::debug!("{} sección {}: laboratorio/ayudantía sin cátedra, se inscribe solo", codigo, seccion);
+        }
+    }
+}
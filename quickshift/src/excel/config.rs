@@ -0,0 +1,219 @@
+//! Directorio de datafiles activo (`DatafilesConfig`).
+//!
+//! Se resuelve una sola vez y queda en memoria; `get_datafiles_dir` y los
+//! handlers (que lo reciben como extractor) leen esa copia en vez de volver a
+//! buscar en disco en cada llamada. Orden de resolución:
+//!
+//!   1. el archivo de configuración (`DATAFILES_CONFIG_FILE` o
+//!      `datafiles_config.toml`), que escribe `PUT /config/datafiles`:
+//!        dir = "/srv/quickshift/datafiles"
+//!   2. `GA_DATAFILES_DIR`
+//!   3. `quickshift/src/datafiles`, `src/datafiles` o `datafiles` bajo el CWD
+//!   4. `quickshift/src/datafiles` junto al ejecutable
+//!   5. los datos de ejemplo embebidos (feature `sample-data`, ver `sample`)
+//!   6. `DATAFILES_DIR`, aunque no exista
+//!
+//! `cambiar_dir` valida el nuevo directorio, lo persiste y lo deja activo;
+//! `recargar` vuelve a resolver todo (por ejemplo tras cambiar el entorno).
+//! Solo se aceptan directorios bajo `raiz_permitida` (`DATAFILES_ROOT` o
+//! `GA_DATAFILES_DIR`); sin raíz configurada no se puede cambiar en caliente.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use super::{sample, DATAFILES_DIR};
+
+/// De dónde salió el directorio activo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrigenDatafiles {
+    /// Archivo de configuración (`PUT /config/datafiles`)
+    Configuracion,
+    /// `GA_DATAFILES_DIR`
+    Entorno,
+    /// Bajo el directorio de trabajo
+    DirectorioActual,
+    /// Junto al ejecutable
+    Ejecutable,
+    /// Datos de ejemplo embebidos
+    Ejemplo,
+    /// Nada encontrado: `DATAFILES_DIR`
+    PorDefecto,
+    /// `--datafiles` de la línea de comandos (`quickshift solve`, ...)
+    LineaDeComandos,
+}
+
+/// Directorio de datafiles activo y su origen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DatafilesConfig {
+    pub dir: PathBuf,
+    pub origen: OrigenDatafiles,
+}
+
+/// Contenido del archivo de configuración
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ArchivoConfig {
+    dir: PathBuf,
+}
+
+/// Ruta del archivo de configuración: `datafiles_config_file` de `app_config`
+/// (`DATAFILES_CONFIG_FILE`, por defecto `datafiles_config.toml`)
+pub fn ruta_config() -> PathBuf {
+    crate::app_config::actual().datafiles_config_file.clone()
+}
+
+/// Directorio guardado en el archivo de configuración, si hay uno válido
+pub fn leer_config(ruta: &Path) -> Option<PathBuf> {
+    let texto = std::fs::read_to_string(ruta).ok()?;
+    match crate::app_config::desde_toml::<ArchivoConfig>(&texto) {
+        Ok(c) => Some(c.dir),
+        Err(e) => {
+            tracing::warn!("⚠️ {:?} inválido: {}", ruta, e);
+            None
+        }
+    }
+}
+
+/// Escribe `dir` en el archivo de configuración
+pub fn guardar_config(ruta: &Path, dir: &Path) -> Result<(), String> {
+    let texto = crate::app_config::a_toml(&ArchivoConfig { dir: dir.to_path_buf() })?;
+    if let Some(padre) = ruta.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(padre).map_err(|e| format!("no se pudo crear {:?}: {}", padre, e))?;
+    }
+    std::fs::write(ruta, texto).map_err(|e| format!("no se pudo escribir {:?}: {}", ruta, e))
+}
+
+impl DatafilesConfig {
+    /// Busca el directorio en el orden descrito en el módulo
+    pub fn resolver() -> Self {
+        let config = |dir, origen| {
+            tracing::info!("✅ Datafiles en {:?} ({:?})", dir, origen);
+            DatafilesConfig { dir, origen }
+        };
+
+        let archivo = ruta_config();
+        if let Some(dir) = leer_config(&archivo) {
+            let fuera_de_raiz = raiz_permitida().is_some_and(|r| validar_dir(&dir, &r).is_err());
+            if fuera_de_raiz {
+                tracing::warn!("⚠️ El directorio de {:?} está fuera de la raíz permitida: {:?}", archivo, dir);
+            } else if dir.is_dir() {
+                return config(dir, OrigenDatafiles::Configuracion);
+            } else {
+                tracing::warn!("⚠️ El directorio de {:?} no existe: {:?}", archivo, dir);
+            }
+        }
+
+        if let Some(p) = crate::app_config::actual().ga_datafiles_dir.clone().filter(|p| p.exists()) {
+            return config(p, OrigenDatafiles::Entorno);
+        }
+
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let desde_cwd = [
+            cwd.join("quickshift/src/datafiles"),
+            cwd.join("src/datafiles"),
+            cwd.join("datafiles"),
+        ];
+        if let Some(dir) = desde_cwd.into_iter().find(|c| c.exists()) {
+            return config(dir, OrigenDatafiles::DirectorioActual);
+        }
+
+        if let Some(exe_dir) = std::env::current_exe().ok().and_then(|e| e.parent().map(Path::to_path_buf)) {
+            let desde_exe = [
+                exe_dir.join("../../../quickshift/src/datafiles"),
+                exe_dir.join("../../quickshift/src/datafiles"),
+                exe_dir.join("../quickshift/src/datafiles"),
+                exe_dir.join("quickshift/src/datafiles"),
+            ];
+            if let Some(dir) = desde_exe.iter().find_map(|c| c.canonicalize().ok()) {
+                return config(dir, OrigenDatafiles::Ejecutable);
+            }
+        }
+
+        if let Some(dir) = sample::materializar() {
+            return DatafilesConfig { dir, origen: OrigenDatafiles::Ejemplo };
+        }
+
+        tracing::warn!("⚠️ No se encontró directorio datafiles en ninguna ubicación (configurarlo con PUT /config/datafiles, GA_DATAFILES_DIR o compilar con la feature `sample-data`)");
+        DatafilesConfig { dir: PathBuf::from(DATAFILES_DIR), origen: OrigenDatafiles::PorDefecto }
+    }
+}
+
+fn global() -> &'static RwLock<Option<Arc<DatafilesConfig>>> {
+    static CONFIG: OnceLock<RwLock<Option<Arc<DatafilesConfig>>>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(None))
+}
+
+/// Deja `config` activa; fuera de los datos de ejemplo apaga `X-Sample-Data`
+fn fijar(config: DatafilesConfig) -> Arc<DatafilesConfig> {
+    if config.origen != OrigenDatafiles::Ejemplo {
+        sample::desactivar();
+    }
+    let c = Arc::new(config);
+    if let Ok(mut g) = global().write() {
+        *g = Some(c.clone());
+    }
+    c
+}
+
+/// Configuración activa (se resuelve la primera vez)
+pub fn actual() -> Arc<DatafilesConfig> {
+    if let Some(c) = global().read().ok().and_then(|g| g.clone()) {
+        return c;
+    }
+    fijar(DatafilesConfig::resolver())
+}
+
+/// Reemplaza la configuración activa sin persistirla (pruebas)
+pub fn establecer(config: DatafilesConfig) {
+    fijar(config);
+}
+
+/// Vuelve a resolver el directorio
+pub fn recargar() -> Arc<DatafilesConfig> {
+    fijar(DatafilesConfig::resolver())
+}
+
+/// Raíz bajo la que se aceptan directorios de datafiles: `datafiles_root`
+/// (`DATAFILES_ROOT`) o, si no está, `ga_datafiles_dir`; resuelta sin enlaces
+pub fn raiz_permitida() -> Option<PathBuf> {
+    let c = crate::app_config::actual();
+    c.datafiles_root.clone()
+        .or_else(|| c.ga_datafiles_dir.clone())
+        .and_then(|r| r.canonicalize().ok())
+}
+
+/// `dir` resuelto (sin enlaces ni `..`) si es un directorio dentro de `raiz`
+pub fn validar_dir(dir: &Path, raiz: &Path) -> Result<PathBuf, String> {
+    if !dir.is_dir() {
+        return Err(format!("{} no es un directorio", dir.display()));
+    }
+    let dir = dir.canonicalize().map_err(|e| format!("no se pudo resolver {}: {}", dir.display(), e))?;
+    if !dir.starts_with(raiz) {
+        return Err(format!("{} está fuera de la raíz permitida {}", dir.display(), raiz.display()));
+    }
+    Ok(dir)
+}
+
+/// Cambia el directorio activo: debe existir y estar bajo `raiz_permitida`;
+/// se guarda en `ruta_config()` para que sobreviva a un reinicio
+pub fn cambiar_dir(dir: &Path) -> Result<Arc<DatafilesConfig>, String> {
+    let raiz = raiz_permitida()
+        .ok_or("no hay raíz permitida para los datafiles (defina DATAFILES_ROOT o GA_DATAFILES_DIR)")?;
+    let dir = validar_dir(dir, &raiz)?;
+    guardar_config(&ruta_config(), &dir)?;
+    let c = fijar(DatafilesConfig { dir, origen: OrigenDatafiles::Configuracion });
+    tracing::info!("📁 Directorio de datafiles cambiado a {:?}", c.dir);
+    Ok(c)
+}
+
+/// Los handlers reciben la configuración activa como argumento
+#[cfg(feature = "server")]
+impl actix_web::FromRequest for DatafilesConfig {
+    type Error = std::convert::Infallible;
+    type Future = std::future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(_req: &actix_web::HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        std::future::ready(Ok(actual().as_ref().clone()))
+    }
+}
@@ -0,0 +1,132 @@
+// deteccion.rs - Reconoce qué tipo de datafile es un workbook
+//
+// Se usa al subir archivos (`POST /datafiles/upload`): antes de dejar un
+// Excel en el directorio de datos se verifica que su contenido sea una malla,
+// una oferta o un archivo de porcentajes, y que el nombre del archivo permita
+// encontrarlo después (la selección de archivos es por nombre, ver
+// `resolve_datafile_paths` y `list_available_datafiles`).
+
+use calamine::{Data, Reader};
+use serde::Serialize;
+use std::path::Path;
+use crate::excel::io::{abrir_libro, data_to_string};
+use crate::excel::normalize_name;
+
+/// Filas iniciales de cada hoja donde se busca el encabezado
+const FILAS_ENCABEZADO: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoDatafile {
+    Malla,
+    Oferta,
+    Porcentajes,
+}
+
+impl TipoDatafile {
+    pub fn etiqueta(&self) -> &'static str {
+        match self {
+            TipoDatafile::Malla => "malla",
+            TipoDatafile::Oferta => "oferta",
+            TipoDatafile::Porcentajes => "porcentajes",
+        }
+    }
+
+    /// Ejemplo de nombre válido, para mensajes de error
+    fn ejemplo_nombre(&self) -> &'static str {
+        match self {
+            TipoDatafile::Malla => "Malla2026.xlsx o MC2026.xlsx",
+            TipoDatafile::Oferta => "OA20261.xlsx",
+            TipoDatafile::Porcentajes => "PA20261.xlsx",
+        }
+    }
+}
+
+/// Resultado de reconocer un workbook
+#[derive(Debug, Clone, Serialize)]
+pub struct DatafileValidado {
+    pub tipo: TipoDatafile,
+    /// Hoja donde se encontró el encabezado
+    pub hoja: String,
+    /// Filas con datos bajo el encabezado
+    pub filas: usize,
+}
+
+/// Categoría que se infiere del nombre del archivo (mismas reglas que
+/// `list_available_datafiles`). Los archivos CFG son ofertas aunque su nombre
+/// no lo diga: se buscan aparte con `latest_file_for_keywords(&["cfg"])`.
+pub fn categoria_por_nombre(nombre: &str) -> Option<TipoDatafile> {
+    let n = nombre.to_lowercase();
+    if n.contains("malla") || n.starts_with("mc") {
+        Some(TipoDatafile::Malla)
+    } else if n.contains("oferta") || n.contains("oa") || n.contains("cfg") {
+        Some(TipoDatafile::Oferta)
+    } else if n.contains("porcent") || n.contains("aprob") || (n.starts_with("pa") && n.chars().nth(2).map(|c| c.is_ascii_digit()).unwrap_or(false)) {
+        Some(TipoDatafile::Porcentajes)
+    } else {
+        None
+    }
+}
+
+/// Tipo según las columnas de una fila de encabezado
+fn tipo_por_encabezado(columnas: &[String]) -> Option<TipoDatafile> {
+    let tiene = |claves: &[&str]| columnas.iter().any(|c| claves.iter().any(|k| c.contains(k)));
+    if tiene(&["porcentaje"]) || (tiene(&["aprobados"]) && tiene(&["codigo"])) {
+        Some(TipoDatafile::Porcentajes)
+    } else if tiene(&["horario"]) && tiene(&["seccion"]) {
+        Some(TipoDatafile::Oferta)
+    } else if tiene(&["semestre"]) && tiene(&["requisito", "prerreq"]) {
+        Some(TipoDatafile::Malla)
+    } else {
+        None
+    }
+}
+
+/// Índice y tipo de la primera fila de encabezado reconocible entre las
+/// primeras `FILAS_ENCABEZADO` filas de una hoja
+pub(crate) fn buscar_encabezado(filas: &[&[Data]]) -> Option<(usize, TipoDatafile)> {
+    filas.iter().take(FILAS_ENCABEZADO).enumerate().find_map(|(i, fila)| {
+        let columnas: Vec<String> = fila.iter().map(|c| normalize_name(&data_to_string(c))).collect();
+        tipo_por_encabezado(&columnas).map(|tipo| (i, tipo))
+    })
+}
+
+/// Busca en cada hoja un encabezado reconocible y cuenta las filas con datos
+/// (al menos dos celdas no vacías) bajo él.
+pub fn detectar_tipo(ruta: &Path) -> Result<DatafileValidado, String> {
+    let mut workbook = abrir_libro(ruta).map_err(|e| e.to_string())?;
+    let hojas = workbook.sheet_names().to_owned();
+    for hoja in hojas.iter() {
+        let Ok(rango) = workbook.worksheet_range(hoja) else { continue };
+        let filas: Vec<&[Data]> = rango.rows().collect();
+        if let Some((i, tipo)) = buscar_encabezado(&filas) {
+            let con_datos = filas[i + 1..].iter()
+                .filter(|f| f.iter().filter(|c| !data_to_string(c).trim().is_empty()).count() >= 2)
+                .count();
+            return Ok(DatafileValidado { tipo, hoja: hoja.clone(), filas: con_datos });
+        }
+    }
+    Err("no se reconoce como malla (Semestre + Requisitos), oferta (Sección + Horario) ni porcentajes (Porcentaje Aprobado)".to_string())
+}
+
+/// Valida un workbook ya escrito en `ruta` que se guardará como `nombre`:
+/// detecta su tipo por contenido, exige que el nombre lo clasifique igual y
+/// que tenga al menos una fila de datos.
+pub fn validar_datafile(ruta: &Path, nombre: &str) -> Result<DatafileValidado, String> {
+    let detectado = detectar_tipo(ruta)?;
+    let tipo = detectado.tipo;
+    match categoria_por_nombre(nombre) {
+        Some(c) if c == tipo => {}
+        otra => {
+            let segun_nombre = otra.map(|c| c.etiqueta()).unwrap_or("ningún tipo");
+            return Err(format!(
+                "el contenido es {} pero el nombre '{}' corresponde a {}; use un nombre como {}",
+                tipo.etiqueta(), nombre, segun_nombre, tipo.ejemplo_nombre()
+            ));
+        }
+    }
+    if detectado.filas == 0 {
+        return Err(format!("el archivo parece {} pero no tiene filas de datos bajo el encabezado", tipo.etiqueta()));
+    }
+    Ok(detectado)
+}
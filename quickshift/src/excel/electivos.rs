@@ -0,0 +1,143 @@
+// electivos.rs - Catálogo de electivos por línea de formación
+//
+// Hasta ahora un electivo era cualquier ramo de la oferta que no está en la
+// malla (`ruta::marcar_electivos`), sin saber a qué línea pertenece. El
+// catálogo se lee de una hoja "Electivos" (dentro de la malla o en un archivo
+// propio, p. ej. `Electivos2025.xlsx`) con columnas de código, nombre y
+// línea, y permite etiquetar cada sección electiva con su línea
+// (`Seccion::linea`) para el filtro `BalanceLineas`.
+
+use calamine::{Data, Reader};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+use crate::excel::io::{abrir_libro, data_to_string};
+use crate::excel::normalize_name;
+use crate::models::Seccion;
+
+/// Filas iniciales de la hoja donde se busca el encabezado
+const FILAS_ENCABEZADO: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Electivo {
+    pub codigo: String,
+    pub nombre: String,
+    /// Línea normalizada (ver `normalizar_linea`)
+    pub linea: String,
+}
+
+/// Electivos indexados por código y por nombre normalizado
+#[derive(Debug, Clone, Default)]
+pub struct CatalogoElectivos {
+    pub electivos: Vec<Electivo>,
+    por_codigo: HashMap<String, usize>,
+    por_nombre: HashMap<String, usize>,
+}
+
+impl CatalogoElectivos {
+    pub fn new(electivos: Vec<Electivo>) -> Self {
+        let mut catalogo = CatalogoElectivos { electivos, ..Default::default() };
+        for (i, e) in catalogo.electivos.iter().enumerate() {
+            if !e.codigo.is_empty() {
+                catalogo.por_codigo.insert(e.codigo.to_uppercase(), i);
+            }
+            if !e.nombre.is_empty() {
+                catalogo.por_nombre.insert(normalize_name(&e.nombre), i);
+            }
+        }
+        catalogo
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.electivos.is_empty()
+    }
+
+    /// Línea de formación de una sección, buscando por código y luego por nombre
+    pub fn linea_de(&self, seccion: &Seccion) -> Option<&str> {
+        self.por_codigo.get(&seccion.codigo.trim().to_uppercase())
+            .or_else(|| self.por_nombre.get(&normalize_name(&seccion.nombre)))
+            .map(|&i| self.electivos[i].linea.as_str())
+    }
+
+    /// Asigna `linea` a las secciones (no CFG) que están en el catálogo; no
+    /// depende de `is_electivo`, así que puede llamarse antes de
+    /// `ruta::marcar_electivos`. Devuelve cuántas se etiquetaron.
+    pub fn etiquetar(&self, secciones: &mut [Seccion]) -> usize {
+        let mut n = 0;
+        for s in secciones.iter_mut().filter(|s| !s.is_cfg) {
+            if let Some(linea) = self.linea_de(s) {
+                s.linea = Some(linea.to_string());
+                n += 1;
+            }
+        }
+        n
+    }
+}
+
+/// Nombre canónico de una línea: "Informática" -> "informatica",
+/// "Telecomunicaciones" / "Telecom" -> "telecomunicaciones", "Gestión" -> "gestion".
+/// Otras líneas quedan en minúsculas sin acentos.
+pub fn normalizar_linea(linea: &str) -> String {
+    let n = normalize_name(linea);
+    if n.starts_with("inform") {
+        "informatica".to_string()
+    } else if n.starts_with("tele") {
+        "telecomunicaciones".to_string()
+    } else if n.starts_with("gesti") {
+        "gestion".to_string()
+    } else {
+        n
+    }
+}
+
+/// Arma el catálogo desde las filas de una hoja: busca el encabezado (código
+/// o nombre, más la línea) en las primeras filas y lee las siguientes.
+/// Las filas sin línea se ignoran.
+pub fn catalogo_desde_filas(filas: &[Vec<String>]) -> CatalogoElectivos {
+    let columna = |fila: &[String], claves: &[&str]| fila.iter().position(|c| claves.contains(&normalize_name(c).as_str()));
+    let encabezado = filas.iter().take(FILAS_ENCABEZADO).enumerate().find_map(|(i, fila)| {
+        let linea = columna(fila, &["linea", "linea de formacion", "linea formacion", "area", "mencion"])?;
+        let codigo = columna(fila, &["codigo", "cod", "sigla", "asignatura"]);
+        let nombre = columna(fila, &["nombre", "nombre asignatura", "nombre asignado", "electivo"]);
+        (codigo.is_some() || nombre.is_some()).then_some((i, codigo, nombre, linea))
+    });
+    let Some((inicio, codigo, nombre, linea)) = encabezado else {
+        return CatalogoElectivos::default();
+    };
+
+    let celda = |fila: &[String], i: Option<usize>| i.and_then(|i| fila.get(i)).map(|s| s.trim().to_string()).unwrap_or_default();
+    let electivos = filas[inicio + 1..].iter()
+        .map(|fila| Electivo {
+            codigo: celda(fila, codigo),
+            nombre: celda(fila, nombre),
+            linea: normalizar_linea(&celda(fila, Some(linea))),
+        })
+        .filter(|e| !e.linea.is_empty() && (!e.codigo.is_empty() || !e.nombre.is_empty()))
+        .collect();
+    CatalogoElectivos::new(electivos)
+}
+
+/// Lee el catálogo de un workbook: usa la hoja cuyo nombre contiene
+/// "electivo" o, si el archivo mismo es de electivos, la primera hoja.
+/// Un workbook sin esa hoja devuelve un catálogo vacío.
+pub fn leer_electivos(ruta: &str) -> Result<CatalogoElectivos, Box<dyn Error>> {
+    let mut workbook = abrir_libro(ruta)?;
+    let hojas = workbook.sheet_names().to_owned();
+    let archivo_de_electivos = std::path::Path::new(ruta).file_name()
+        .map(|n| normalize_name(&n.to_string_lossy()).contains("electivo"))
+        .unwrap_or(false);
+    let hoja = hojas.iter()
+        .find(|h| normalize_name(h).contains("electivo"))
+        .or_else(|| hojas.first().filter(|_| archivo_de_electivos));
+    let Some(hoja) = hoja else {
+        return Ok(CatalogoElectivos::default());
+    };
+
+    let rango = workbook.worksheet_range(hoja)?;
+    let filas: Vec<Vec<String>> = rango.rows()
+        .map(|fila: &[Data]| fila.iter().map(data_to_string).collect())
+        .collect();
+    let catalogo = catalogo_desde_filas(&filas);
+    tracing::debug!("📚 Electivos: {} leídos desde '{}' (hoja '{}')", catalogo.electivos.len(), ruta, hoja);
+    Ok(catalogo)
+}
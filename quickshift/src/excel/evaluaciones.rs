@@ -0,0 +1,215 @@
+// evaluaciones.rs - Calendario de evaluaciones (certámenes, exámenes) por curso
+//
+// Archivo opcional del directorio de datos (p. ej. `Evaluaciones20251.xlsx`)
+// con el código o nombre del curso y las fechas de sus evaluaciones. Acepta
+// dos formatos:
+//   - una fila por evaluación: código | evaluación | fecha
+//   - una fila por curso con una columna por evaluación: código | C1 | C2 | Examen
+// Las fechas pueden venir como fecha de Excel, AAAA-MM-DD o DD/MM/AAAA. Las
+// evaluaciones se copian a `Seccion::evaluaciones` de cada sección del curso
+// (`ruta::preparar_datos_ruta`) y `algorithm::evaluaciones` calcula con ellas
+// la carga de evaluaciones de cada solución.
+
+use calamine::{Data, Reader};
+use std::collections::HashMap;
+use std::error::Error;
+use crate::excel::io::{abrir_libro, data_to_string};
+use crate::excel::normalize_name;
+use crate::models::{Evaluacion, Seccion};
+
+/// Filas iniciales de la hoja donde se busca el encabezado
+const FILAS_ENCABEZADO: usize = 10;
+
+/// Número de serie de Excel del 1970-01-01
+const SERIE_EXCEL_EPOCH: i64 = 25_569;
+
+/// Evaluaciones indexadas por código y por nombre normalizado del curso
+#[derive(Debug, Clone, Default)]
+pub struct CalendarioEvaluaciones {
+    por_codigo: HashMap<String, Vec<Evaluacion>>,
+    por_nombre: HashMap<String, Vec<Evaluacion>>,
+}
+
+impl CalendarioEvaluaciones {
+    pub fn is_empty(&self) -> bool {
+        self.por_codigo.is_empty() && self.por_nombre.is_empty()
+    }
+
+    /// Cursos con al menos una evaluación
+    pub fn cursos(&self) -> usize {
+        self.por_codigo.len() + self.por_nombre.len()
+    }
+
+    /// Agrega una evaluación al curso (por código si lo trae, si no por nombre)
+    pub fn agregar(&mut self, codigo: &str, nombre: &str, evaluacion: Evaluacion) {
+        let codigo = codigo.trim().to_uppercase();
+        let lista = if !codigo.is_empty() {
+            self.por_codigo.entry(codigo).or_default()
+        } else {
+            self.por_nombre.entry(normalize_name(nombre)).or_default()
+        };
+        if !lista.contains(&evaluacion) {
+            lista.push(evaluacion);
+            lista.sort_by(|a, b| a.fecha.cmp(&b.fecha));
+        }
+    }
+
+    /// Evaluaciones del curso de una sección, por código y luego por nombre
+    pub fn de_seccion(&self, seccion: &Seccion) -> Option<&[Evaluacion]> {
+        self.por_codigo.get(&seccion.codigo.trim().to_uppercase())
+            .or_else(|| self.por_nombre.get(&normalize_name(&seccion.nombre)))
+            .map(Vec::as_slice)
+    }
+
+    /// Copia a cada sección las evaluaciones de su curso. Devuelve cuántas
+    /// secciones quedaron con evaluaciones.
+    pub fn asignar(&self, secciones: &mut [Seccion]) -> usize {
+        let mut n = 0;
+        for s in secciones.iter_mut() {
+            if let Some(evaluaciones) = self.de_seccion(s) {
+                s.evaluaciones = evaluaciones.to_vec();
+                n += 1;
+            }
+        }
+        n
+    }
+}
+
+/// Días desde el 1970-01-01 de una fecha del calendario gregoriano
+pub fn dias_desde_epoch(anio: i32, mes: u32, dia: u32) -> i64 {
+    let y = i64::from(anio) - i64::from(mes <= 2);
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (i64::from(mes) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(dia) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inversa de `dias_desde_epoch`: (año, mes, día)
+fn fecha_de_dias(dias: i64) -> (i32, u32, u32) {
+    let z = dias + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let dia = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let mes = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let anio = (yoe + era * 400 + i64::from(mes <= 2)) as i32;
+    (anio, mes, dia)
+}
+
+/// Días desde el 1970-01-01 de una fecha válida (rechaza 31/02 y similares)
+fn dias_si_valida(anio: i32, mes: u32, dia: u32) -> Option<i64> {
+    if !(1..=12).contains(&mes) || !(1..=31).contains(&dia) {
+        return None;
+    }
+    let dias = dias_desde_epoch(anio, mes, dia);
+    (fecha_de_dias(dias) == (anio, mes, dia)).then_some(dias)
+}
+
+/// Días desde el 1970-01-01 de una celda de fecha: AAAA-MM-DD (con hora
+/// opcional), DD/MM/AAAA, DD-MM-AAAA o número de serie de Excel
+pub fn parse_fecha(texto: &str) -> Option<i64> {
+    let texto = texto.trim();
+    let fecha = texto.split(['T', ' ']).next().unwrap_or(texto);
+    if let Ok(serie) = fecha.parse::<f64>() {
+        // Fechas entre 1954 y 2173; un número chico es un dato que no es fecha
+        return (20_000.0..100_000.0).contains(&serie).then(|| serie.floor() as i64 - SERIE_EXCEL_EPOCH);
+    }
+    let partes: Vec<&str> = fecha.split(['-', '/', '.']).collect();
+    let [a, b, c] = partes.as_slice() else { return None };
+    let numero = |s: &str| s.trim().parse::<u32>().ok();
+    let (anio, mes, dia) = if a.len() == 4 {
+        (numero(a)?, numero(b)?, numero(c)?)
+    } else if c.len() == 4 {
+        (numero(c)?, numero(b)?, numero(a)?)
+    } else {
+        return None;
+    };
+    dias_si_valida(anio as i32, mes, dia)
+}
+
+/// Fecha AAAA-MM-DD de un día contado desde el 1970-01-01
+pub fn fecha_iso(dias: i64) -> String {
+    let (anio, mes, dia) = fecha_de_dias(dias);
+    format!("{:04}-{:02}-{:02}", anio, mes, dia)
+}
+
+/// Columna con la fecha de una evaluación: "Fecha", "Fecha certamen", "C1",
+/// "Certamen 2", "Examen", "Prueba 1", "Control 3", "Solemne 1"
+fn es_columna_de_fecha(encabezado: &str) -> bool {
+    let n = normalize_name(encabezado);
+    let c_numerada = (2..=3).contains(&n.len()) && n.starts_with('c') && n[1..].chars().all(|c| c.is_ascii_digit());
+    n.contains("fecha") || c_numerada
+        || ["certamen", "examen", "prueba", "control", "solemne"].iter().any(|p| n.starts_with(p))
+}
+
+/// Arma el calendario desde las filas de una hoja: busca el encabezado
+/// (código o nombre del curso y al menos una columna de fecha) en las
+/// primeras filas y lee las siguientes. Con una columna "Evaluación" o
+/// "Tipo" esa es el nombre de la evaluación; si no, el encabezado de la
+/// columna de fecha. Las celdas sin fecha válida se ignoran.
+pub fn calendario_desde_filas(filas: &[Vec<String>]) -> CalendarioEvaluaciones {
+    let columna = |fila: &[String], claves: &[&str]| fila.iter().position(|c| claves.contains(&normalize_name(c).as_str()));
+    let encabezado = filas.iter().take(FILAS_ENCABEZADO).enumerate().find_map(|(i, fila)| {
+        let codigo = columna(fila, &["codigo", "cod", "sigla", "codigo asignatura", "asignatura"]);
+        let nombre = columna(fila, &["nombre", "nombre asignatura", "curso", "ramo"]);
+        let tipo = columna(fila, &["evaluacion", "tipo", "tipo evaluacion", "nombre evaluacion"]);
+        let fechas: Vec<usize> = fila.iter().enumerate()
+            .filter(|(ci, c)| Some(*ci) != codigo && Some(*ci) != nombre && Some(*ci) != tipo && es_columna_de_fecha(c))
+            .map(|(ci, _)| ci)
+            .collect();
+        ((codigo.is_some() || nombre.is_some()) && !fechas.is_empty()).then_some((i, codigo, nombre, tipo, fechas))
+    });
+    let mut calendario = CalendarioEvaluaciones::default();
+    let Some((inicio, codigo, nombre, tipo, fechas)) = encabezado else {
+        return calendario;
+    };
+
+    let celda = |fila: &[String], i: Option<usize>| i.and_then(|i| fila.get(i)).map(|s| s.trim().to_string()).unwrap_or_default();
+    for fila in &filas[inicio + 1..] {
+        let (cod, nom) = (celda(fila, codigo), celda(fila, nombre));
+        if cod.is_empty() && nom.is_empty() {
+            continue;
+        }
+        for &ci in &fechas {
+            let Some(dias) = parse_fecha(&celda(fila, Some(ci))) else { continue };
+            let nombre_evaluacion = Some(celda(fila, tipo)).filter(|t| !t.is_empty())
+                .unwrap_or_else(|| filas[inicio][ci].trim().to_string());
+            calendario.agregar(&cod, &nom, Evaluacion { nombre: nombre_evaluacion, fecha: fecha_iso(dias) });
+        }
+    }
+    calendario
+}
+
+/// Texto de una celda; las fechas de Excel quedan como número de serie
+fn celda_a_texto(d: &Data) -> String {
+    match d {
+        Data::DateTime(f) => f.as_f64().to_string(),
+        otro => data_to_string(otro),
+    }
+}
+
+/// Lee el calendario de un workbook: la hoja cuyo nombre menciona
+/// evaluaciones, certámenes o calendario, o si no la primera
+pub fn leer_evaluaciones(ruta: &str) -> Result<CalendarioEvaluaciones, Box<dyn Error>> {
+    let mut workbook = abrir_libro(ruta)?;
+    let hojas = workbook.sheet_names().to_owned();
+    let hoja = hojas.iter()
+        .find(|h| {
+            let n = normalize_name(h);
+            n.contains("evaluaci") || n.contains("certamen") || n.contains("calendario")
+        })
+        .or_else(|| hojas.first())
+        .ok_or("el archivo de evaluaciones no tiene hojas")?;
+
+    let rango = workbook.worksheet_range(hoja)?;
+    let filas: Vec<Vec<String>> = rango.rows()
+        .map(|fila: &[Data]| fila.iter().map(celda_a_texto).collect())
+        .collect();
+    let calendario = calendario_desde_filas(&filas);
+    tracing::debug!("📅 Evaluaciones: {} cursos leídos desde '{}' (hoja '{}')", calendario.cursos(), ruta, hoja);
+    Ok(calendario)
+}
@@ -1,4 +1,8 @@
-use calamine::{open_workbook_auto, Data};
+use calamine::{open_workbook, Cell, Data, DataRef, Ods, OdsError, Reader, Sheets, XlsError, XlsbError, Xlsx, XlsxError};
+use serde::Serialize;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
 use std::path::Path;
 
 /// Convierte un `Data` de calamine a String (versión genérica para celdas)
@@ -89,10 +93,9 @@ pub fn normalize_name(s: &str) -> String {
 }
 
 /// Intenta leer una hoja del archivo Excel y devolverla como Vec<Vec<String>>.
-/// Implementación basada en `calamine::open_workbook_auto` para simplicidad (sirve como fallback)
+/// Implementación basada en `abrir_libro` para simplicidad (sirve como fallback)
 pub fn read_sheet_via_zip<P: AsRef<Path>>(path: P, sheet_name: &str) -> Result<Vec<Vec<String>>, Box<dyn std::error::Error>> {
-    use calamine::Reader;
-    let mut workbook = open_workbook_auto(path)?;
+    let mut workbook = abrir_libro(path)?;
 
     // Preferir la hoja con el nombre solicitado; si no existe, tomar la primera
     let names = workbook.sheet_names().to_owned();
@@ -121,3 +124,306 @@ pub fn read_sheet_via_zip<P: AsRef<Path>>(path: P, sheet_name: &str) -> Result<V
         Err(_) => Ok(Vec::new()),
     }
 }
+
+/// Celda siguiente de un lector por celdas (`None` al terminar la hoja)
+type SiguienteCelda<'a> = dyn FnMut() -> Result<Option<Cell<DataRef<'a>>>, Box<dyn std::error::Error>> + 'a;
+
+/// Primera y última (fila, columna) de un rango
+type Bordes = ((u32, u32), (u32, u32));
+
+/// Bordes de las celdas con datos, como los calcula `Range::from_sparse`
+fn bordes_hoja(siguiente: &mut SiguienteCelda<'_>) -> Result<Option<Bordes>, Box<dyn std::error::Error>> {
+    let mut bordes: Option<Bordes> = None;
+    while let Some(celda) = siguiente()? {
+        if *celda.get_value() == DataRef::Empty { continue; }
+        let (f, c) = celda.get_position();
+        bordes = Some(match bordes {
+            None => ((f, c), (f, c)),
+            Some(((f0, c0), (f1, c1))) => ((f0.min(f), c0.min(c)), (f1.max(f), c1.max(c))),
+        });
+    }
+    Ok(bordes)
+}
+
+/// Entrega las filas `inicio.0..=fin.0` a medida que se leen, con el ancho del
+/// rango y las celdas vacías como ""
+fn emitir_filas(
+    siguiente: &mut SiguienteCelda<'_>,
+    (inicio, fin): Bordes,
+    fila: &mut dyn FnMut(Vec<String>),
+) -> Result<(), Box<dyn std::error::Error>> {
+    let ancho = (fin.1 - inicio.1 + 1) as usize;
+    let mut actual = inicio.0;
+    let mut celdas = vec![String::new(); ancho];
+    while let Some(celda) = siguiente()? {
+        let (f, c) = celda.get_position();
+        if *celda.get_value() == DataRef::Empty || f < inicio.0 || f > fin.0 { continue; }
+        if f < actual {
+            return Err(format!("celdas fuera de orden en la fila {}", f + 1).into());
+        }
+        while actual < f {
+            fila(std::mem::replace(&mut celdas, vec![String::new(); ancho]));
+            actual += 1;
+        }
+        celdas[(c - inicio.1) as usize] = data_to_string(&Data::from(celda.get_value().clone()));
+    }
+    fila(celdas);
+    while actual < fin.0 {
+        fila(vec![String::new(); ancho]);
+        actual += 1;
+    }
+    Ok(())
+}
+
+/// Recorre las filas de `hoja` como `range.rows()` + `data_to_string`, pero
+/// sin tener el rango entero en memoria: en xlsx y xlsb las celdas se leen en
+/// streaming, con una pasada para ubicar los bordes del rango y otra para
+/// entregar las filas. xls y ods no tienen lector por celdas y se leen con
+/// `worksheet_range`.
+pub fn recorrer_filas<RS: Read + Seek>(
+    libro: &mut Sheets<RS>,
+    hoja: &str,
+    mut fila: impl FnMut(Vec<String>),
+) -> Result<(), Box<dyn std::error::Error>> {
+    macro_rules! por_celdas {
+        ($lector:expr) => {{
+            let bordes = {
+                let mut celdas = $lector.worksheet_cells_reader(hoja)?;
+                bordes_hoja(&mut || Ok(celdas.next_cell()?))?
+            };
+            let Some(bordes) = bordes else { return Ok(()) };
+            let mut celdas = $lector.worksheet_cells_reader(hoja)?;
+            emitir_filas(&mut || Ok(celdas.next_cell()?), bordes, &mut fila)
+        }};
+    }
+    match libro {
+        Sheets::Xlsx(x) => por_celdas!(x),
+        Sheets::Xlsb(x) => por_celdas!(x),
+        otro => {
+            for r in otro.worksheet_range(hoja)?.rows() {
+                fila(r.iter().map(data_to_string).collect());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Formato real de un libro, según su contenido (no su extensión)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatoLibro {
+    Xlsx,
+    Xlsm,
+    Xlsb,
+    Xls,
+    Ods,
+    /// Contenedor OLE con un `EncryptedPackage`: xlsx/xlsm/xlsb guardado con contraseña
+    Cifrado,
+    Desconocido,
+}
+
+impl FormatoLibro {
+    pub fn etiqueta(&self) -> &'static str {
+        match self {
+            FormatoLibro::Xlsx => "xlsx",
+            FormatoLibro::Xlsm => "xlsm",
+            FormatoLibro::Xlsb => "xlsb",
+            FormatoLibro::Xls => "xls",
+            FormatoLibro::Ods => "ods",
+            FormatoLibro::Cifrado => "cifrado",
+            FormatoLibro::Desconocido => "desconocido",
+        }
+    }
+
+    /// Extensiones con las que puede venir un libro de este formato
+    fn extensiones(&self) -> &'static [&'static str] {
+        match self {
+            FormatoLibro::Xlsx => &["xlsx"],
+            FormatoLibro::Xlsm => &["xlsm"],
+            FormatoLibro::Xlsb => &["xlsb"],
+            FormatoLibro::Xls => &["xls"],
+            FormatoLibro::Ods => &["ods"],
+            FormatoLibro::Cifrado => &["xlsx", "xlsm", "xlsb"],
+            FormatoLibro::Desconocido => &[],
+        }
+    }
+}
+
+/// Protección de un libro: `edicion` se puede leer igual (hojas o estructura
+/// bloqueadas para editar); `contrasena` no se puede abrir sin la clave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Proteccion {
+    Ninguna,
+    Edicion,
+    Contrasena,
+}
+
+/// Resultado de `diagnosticar_libro`, lo que devuelve `GET /datafiles/validate`
+/// junto al reporte (o como `details.libro` si el archivo no se puede leer)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiagnosticoLibro {
+    pub archivo: String,
+    pub formato: FormatoLibro,
+    /// false si la extensión no corresponde al contenido (p. ej. un xlsb renombrado a .xlsx)
+    pub extension_coincide: bool,
+    pub proteccion: Proteccion,
+    /// Hojas con `sheetProtection` (solo xlsx/xlsm)
+    pub hojas_protegidas: usize,
+    pub hojas: Vec<String>,
+    pub num_hojas: usize,
+    pub legible: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl DiagnosticoLibro {
+    fn nuevo(ruta: &Path, formato: FormatoLibro) -> DiagnosticoLibro {
+        let extension = ruta.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).unwrap_or_default();
+        DiagnosticoLibro {
+            archivo: ruta.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            formato,
+            extension_coincide: formato.extensiones().contains(&extension.as_str()),
+            proteccion: Proteccion::Ninguna,
+            hojas_protegidas: 0,
+            hojas: Vec::new(),
+            num_hojas: 0,
+            legible: false,
+            error: None,
+        }
+    }
+}
+
+/// Error de `abrir_libro`: el diagnóstico del archivo que no se pudo abrir
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibroIlegible(pub DiagnosticoLibro);
+
+impl fmt::Display for LibroIlegible {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d = &self.0;
+        if d.proteccion == Proteccion::Contrasena {
+            return write!(f, "'{}' está protegido con contraseña; guárdelo sin contraseña para poder leerlo", d.archivo);
+        }
+        let error = d.error.as_deref().unwrap_or("");
+        if d.formato == FormatoLibro::Desconocido {
+            return write!(f, "'{}' no es un libro xlsx, xlsm, xlsb, xls ni ods: {}", d.archivo, error);
+        }
+        write!(f, "'{}' no se pudo leer como {}", d.archivo, d.formato.etiqueta())?;
+        if !d.extension_coincide {
+            write!(f, " (la extensión no corresponde al contenido)")?;
+        }
+        write!(f, ": {}", error)
+    }
+}
+
+impl std::error::Error for LibroIlegible {}
+
+const FIRMA_ZIP: [u8; 4] = [0x50, 0x4B, 0x03, 0x04];
+const FIRMA_OLE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Formato por los primeros bytes: los libros OOXML y ODS son ZIP (se
+/// distinguen por sus entradas) y los xls y los OOXML cifrados son OLE.
+fn formato_por_contenido(ruta: &Path) -> std::io::Result<FormatoLibro> {
+    let mut firma = [0u8; 8];
+    let leidos = File::open(ruta)?.read(&mut firma)?;
+    if leidos >= 4 && firma[..4] == FIRMA_ZIP {
+        let Ok(zip) = zip::ZipArchive::new(File::open(ruta)?) else { return Ok(FormatoLibro::Desconocido) };
+        let tiene = |nombre: &str| zip.file_names().any(|n| n == nombre);
+        return Ok(if tiene("xl/workbook.bin") {
+            FormatoLibro::Xlsb
+        } else if tiene("xl/workbook.xml") {
+            if tiene("xl/vbaProject.bin") { FormatoLibro::Xlsm } else { FormatoLibro::Xlsx }
+        } else if tiene("content.xml") {
+            FormatoLibro::Ods
+        } else {
+            FormatoLibro::Desconocido
+        });
+    }
+    if leidos == 8 && firma == FIRMA_OLE {
+        // calamine reconoce el `EncryptedPackage` al intentar abrirlo como xlsx
+        return Ok(match open_workbook::<Xlsx<_>, _>(ruta) {
+            Err(XlsxError::Password) => FormatoLibro::Cifrado,
+            _ => FormatoLibro::Xls,
+        });
+    }
+    Ok(FormatoLibro::Desconocido)
+}
+
+/// Abre el libro con el lector de `formato`. El error indica si fue por contraseña.
+fn abrir_como(ruta: &Path, formato: FormatoLibro) -> Result<Sheets<BufReader<File>>, (bool, String)> {
+    match formato {
+        FormatoLibro::Xlsx | FormatoLibro::Xlsm => open_workbook(ruta)
+            .map(Sheets::Xlsx)
+            .map_err(|e: XlsxError| (matches!(e, XlsxError::Password), e.to_string())),
+        FormatoLibro::Xlsb => open_workbook(ruta)
+            .map(Sheets::Xlsb)
+            .map_err(|e: XlsbError| (matches!(e, XlsbError::Password), e.to_string())),
+        FormatoLibro::Xls => open_workbook(ruta)
+            .map(Sheets::Xls)
+            .map_err(|e: XlsError| (matches!(e, XlsError::Password), e.to_string())),
+        FormatoLibro::Ods => open_workbook::<Ods<_>, _>(ruta)
+            .map(Sheets::Ods)
+            .map_err(|e| (matches!(e, OdsError::Password), e.to_string())),
+        FormatoLibro::Cifrado => Err((true, "libro cifrado".to_string())),
+        FormatoLibro::Desconocido => Err((false, "formato no reconocido".to_string())),
+    }
+}
+
+/// Reemplazo de `calamine::open_workbook_auto` que elige el lector por el
+/// contenido del archivo (un xlsb renombrado a .xlsx se abre igual) y, si no
+/// puede, devuelve un `LibroIlegible` que explica por qué.
+pub fn abrir_libro<P: AsRef<Path>>(ruta: P) -> Result<Sheets<BufReader<File>>, LibroIlegible> {
+    let ruta = ruta.as_ref();
+    let ilegible = |formato, contrasena: bool, error: String| {
+        let mut diagnostico = DiagnosticoLibro::nuevo(ruta, formato);
+        if contrasena {
+            diagnostico.proteccion = Proteccion::Contrasena;
+        }
+        diagnostico.error = Some(error);
+        LibroIlegible(diagnostico)
+    };
+    let formato = formato_por_contenido(ruta).map_err(|e| ilegible(FormatoLibro::Desconocido, false, e.to_string()))?;
+    abrir_como(ruta, formato).map_err(|(contrasena, error)| ilegible(formato, contrasena, error))
+}
+
+/// Cuenta las hojas con `sheetProtection` de un xlsx/xlsm; `true` si además
+/// la estructura del libro está protegida (`workbookProtection`)
+fn proteccion_ooxml(ruta: &Path) -> (usize, bool) {
+    let Ok(mut zip) = File::open(ruta).map_err(|_| ()).and_then(|f| zip::ZipArchive::new(f).map_err(|_| ())) else {
+        return (0, false);
+    };
+    let hojas: Vec<String> = zip.file_names()
+        .filter(|n| n.starts_with("xl/worksheets/") && n.ends_with(".xml"))
+        .map(|n| n.to_string())
+        .collect();
+    let mut contiene = |nombre: &str, etiqueta: &str| -> bool {
+        let mut xml = String::new();
+        zip.by_name(nombre).ok().and_then(|mut f| f.read_to_string(&mut xml).ok()).is_some() && xml.contains(etiqueta)
+    };
+    let libro = contiene("xl/workbook.xml", "<workbookProtection");
+    let protegidas = hojas.iter().filter(|n| contiene(n, "<sheetProtection")).count();
+    (protegidas, libro)
+}
+
+/// Formato, hojas y protección de un libro. No falla: si no se puede abrir,
+/// `legible` es false y `error` dice por qué.
+pub fn diagnosticar_libro<P: AsRef<Path>>(ruta: P) -> DiagnosticoLibro {
+    let ruta = ruta.as_ref();
+    let libro = match abrir_libro(ruta) {
+        Ok(libro) => libro,
+        Err(LibroIlegible(diagnostico)) => return diagnostico,
+    };
+    let formato = formato_por_contenido(ruta).unwrap_or(FormatoLibro::Desconocido);
+    let mut diagnostico = DiagnosticoLibro::nuevo(ruta, formato);
+    diagnostico.hojas = libro.sheet_names();
+    diagnostico.num_hojas = diagnostico.hojas.len();
+    diagnostico.legible = true;
+    if matches!(formato, FormatoLibro::Xlsx | FormatoLibro::Xlsm) {
+        let (protegidas, estructura) = proteccion_ooxml(ruta);
+        diagnostico.hojas_protegidas = protegidas;
+        if protegidas > 0 || estructura {
+            diagnostico.proteccion = Proteccion::Edicion;
+        }
+    }
+    diagnostico
+}
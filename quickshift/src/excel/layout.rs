@@ -0,0 +1,330 @@
+//! Columnas de la malla, la oferta y los porcentajes de cada institución (`LayoutProfile`).
+//!
+//! Los lectores de Excel buscan cada columna por su encabezado y, si la hoja
+//! no lo trae, la toman de una posición fija. Los encabezados y posiciones de
+//! la UDP son el perfil por defecto; `layouts.toml` (o el archivo de
+//! `LAYOUTS_FILE`) declara perfiles para otros formatos y a qué datafiles se
+//! aplican:
+//!
+//!   [perfil.uch]
+//!   archivos = ["MALLA_FCFM*", "CATALOGO_*.xlsx"]   # nombre de archivo, `*` comodín
+//!
+//!   [perfil.uch.oferta]
+//!   codigo = { encabezados = ["=sigla"], posicion = 0 }
+//!   nombre = { encabezados = ["nombre curso"], posicion = 1 }
+//!
+//! Cada patrón de `encabezados` se compara con la celda en minúsculas:
+//! `"texto"` calza si la celda lo contiene, `"=texto"` si es exactamente eso y
+//! `"a&b"` si contiene ambos. Lo que un perfil no declara se toma del de la
+//! UDP (a nivel de columna: una columna declarada reemplaza la de la UDP
+//! completa). Un datafile se lee con el perfil cuyos `archivos` calcen con su
+//! nombre (entre varios, el de id menor); si ninguno calza, con el de la UDP.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Cómo reconocer una columna
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Columna {
+    /// Patrones del encabezado (ver el módulo)
+    pub encabezados: Vec<String>,
+    /// Patrones que se prueban solo si ninguna celda calza con `encabezados`
+    pub alternativos: Vec<String>,
+    /// Columna (desde 0) cuando la hoja no trae un encabezado que la nombre
+    pub posicion: Option<usize>,
+}
+
+fn patron_calza(patron: &str, celda: &str) -> bool {
+    patron.to_lowercase().split('&').all(|parte| match parte.strip_prefix('=') {
+        Some(exacto) => celda.trim() == exacto,
+        None => celda.contains(parte),
+    })
+}
+
+/// true si `celda` calza con alguno de los `patrones`
+pub fn alguno_calza(patrones: &[String], celda: &str) -> bool {
+    let celda = celda.to_lowercase();
+    patrones.iter().any(|p| patron_calza(p, &celda))
+}
+
+impl Columna {
+    fn con(encabezados: &[&str], posicion: Option<usize>) -> Self {
+        Columna {
+            encabezados: encabezados.iter().map(|s| s.to_string()).collect(),
+            alternativos: Vec::new(),
+            posicion,
+        }
+    }
+
+    /// true si la celda de encabezado calza con `encabezados`
+    pub fn calza(&self, celda: &str) -> bool {
+        alguno_calza(&self.encabezados, celda)
+    }
+
+    /// Primera columna (de izquierda a derecha) cuyo encabezado calza
+    pub fn primera<S: AsRef<str>>(&self, encabezado: &[S]) -> Option<usize> {
+        let buscar = |patrones: &[String]| encabezado.iter().position(|c| alguno_calza(patrones, c.as_ref()));
+        buscar(&self.encabezados).or_else(|| buscar(&self.alternativos))
+    }
+
+    /// Última columna cuyo encabezado calza
+    pub fn ultima<S: AsRef<str>>(&self, encabezado: &[S]) -> Option<usize> {
+        let buscar = |patrones: &[String]| encabezado.iter().rposition(|c| alguno_calza(patrones, c.as_ref()));
+        buscar(&self.encabezados).or_else(|| buscar(&self.alternativos))
+    }
+}
+
+/// Columnas de la malla curricular
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutMalla {
+    /// Identificador numérico del ramo en la malla (correlativo)
+    pub id: Columna,
+    pub codigo: Columna,
+    pub nombre: Columna,
+    pub requisitos: Columna,
+    pub correquisitos: Columna,
+    pub semestre: Columna,
+    pub creditos: Columna,
+    pub electivo: Columna,
+}
+
+impl Default for LayoutMalla {
+    /// `Num Correlativo | Código | Nombre Asignatura | Prerreq | Abre | Semestre`
+    fn default() -> Self {
+        LayoutMalla {
+            id: Columna::con(&["correlativo", "=id"], Some(0)),
+            codigo: Columna::con(&["código", "codigo"], Some(1)),
+            nombre: Columna::con(&["nombre", "curso"], Some(2)),
+            requisitos: Columna::con(&["prerreq", "requisito"], Some(3)),
+            correquisitos: Columna::con(&["correq", "co-req", "co req"], None),
+            semestre: Columna::con(&["semestre"], Some(5)),
+            creditos: Columna::con(&["crédito", "credito", "sct"], None),
+            electivo: Columna::con(&["electivo"], None),
+        }
+    }
+}
+
+/// Columnas de la oferta académica
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutOferta {
+    /// Una fila (de las primeras 8) es el encabezado si, por cada lista, alguna
+    /// de sus celdas calza con algún patrón de la lista
+    pub fila_encabezado: Vec<Vec<String>>,
+    pub codigo: Columna,
+    pub nombre: Columna,
+    pub seccion: Columna,
+    pub horario: Columna,
+    pub profesor: Columna,
+    pub codigo_box: Columna,
+    pub sala: Columna,
+    pub campus: Columna,
+    /// Cátedra / laboratorio / ayudantía
+    pub tipo: Columna,
+    /// Cupos totales (nunca la misma columna que `cupos_disponibles`)
+    pub cupos: Columna,
+    pub cupos_disponibles: Columna,
+    pub inscritos: Columna,
+}
+
+impl Default for LayoutOferta {
+    /// Oferta académica de la UDP (`OA20251.xlsx`, `OA2024.xlsx`)
+    fn default() -> Self {
+        let lista = |patrones: &[&str]| patrones.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        LayoutOferta {
+            fila_encabezado: vec![
+                lista(&["nombre", "asignatura", "descripcion"]),
+                lista(&["codigo", "código", "cod", "asignatura", "asig", "sección", "seccion"]),
+            ],
+            codigo: Columna {
+                alternativos: lista(&["codigo", "código", "cod", "seccion", "sección"]),
+                ..Columna::con(&["=codigo", "=código", "=asignatura", "=asig"], Some(1))
+            },
+            nombre: Columna::con(&["nombre", "descripcion"], Some(2)),
+            seccion: Columna::con(&["=sección", "=seccion"], Some(3)),
+            horario: Columna::con(&["horario", "hora", "hor."], Some(7)),
+            profesor: Columna::con(&["profesor"], Some(9)),
+            codigo_box: Columna::con(&["codigo_box", "id_box", "id_paquete"], Some(18)),
+            sala: Columna::con(&["sala", "=aula"], None),
+            campus: Columna::con(&["campus", "=sede"], None),
+            tipo: Columna::con(&["=tipo", "actividad", "componente"], None),
+            cupos: Columna::con(&["cupo", "vacante", "=capacidad"], None),
+            cupos_disponibles: Columna::con(&["cupo&disp", "cupo&libre", "vacante&disp", "vacante&libre"], None),
+            inscritos: Columna::con(&["inscrito"], None),
+        }
+    }
+}
+
+/// Columnas de los porcentajes de aprobación
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutPorcentajes {
+    /// Una fila (de las primeras 8) es el encabezado si alguna celda calza
+    pub fila_encabezado: Vec<String>,
+    pub codigo: Columna,
+    pub nombre: Columna,
+    pub aprobados: Columna,
+    pub total: Columna,
+    /// Porcentaje de aprobación, cuando no hay aprobados y total
+    pub porcentaje: Columna,
+    pub electivo: Columna,
+}
+
+impl Default for LayoutPorcentajes {
+    /// `PA20251.xlsx` de la UDP
+    fn default() -> Self {
+        LayoutPorcentajes {
+            fila_encabezado: vec!["codigo".into(), "ramo".into(), "asignatura".into()],
+            codigo: Columna::con(&["codigo", "=ramo", "=asignatura"], Some(0)),
+            nombre: Columna::con(&["denomin", "asignatura"], None),
+            aprobados: Columna::con(&["aprob"], None),
+            total: Columna::con(&["total"], None),
+            porcentaje: Columna::con(&["porcentaje", "%"], Some(1)),
+            electivo: Columna::con(&["electivo"], None),
+        }
+    }
+}
+
+/// Formato de los datafiles de una institución
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutProfile {
+    /// Nombres de datafile a los que se aplica (`*` comodín, sin distinguir mayúsculas)
+    pub archivos: Vec<String>,
+    pub malla: LayoutMalla,
+    pub oferta: LayoutOferta,
+    pub porcentajes: LayoutPorcentajes,
+}
+
+/// `patron` (con `*`) contra `nombre`, ambos en minúsculas
+fn comodin_calza(patron: &str, nombre: &str) -> bool {
+    let partes: Vec<&str> = patron.split('*').collect();
+    if partes.len() == 1 {
+        return patron == nombre;
+    }
+    let (primera, ultima) = (partes[0], partes[partes.len() - 1]);
+    if !nombre.starts_with(primera) || !nombre[primera.len()..].ends_with(ultima) {
+        return false;
+    }
+    let mut resto = &nombre[primera.len()..nombre.len() - ultima.len()];
+    for parte in &partes[1..partes.len() - 1] {
+        match resto.find(parte) {
+            Some(i) => resto = &resto[i + parte.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+impl LayoutProfile {
+    /// Si el perfil se aplica a `archivo` (nombre o ruta; con o sin extensión)
+    pub fn aplica_a(&self, archivo: &str) -> bool {
+        let ruta = Path::new(archivo.trim());
+        let nombre = ruta.file_name().and_then(|n| n.to_str()).unwrap_or(archivo).to_lowercase();
+        let sin_extension = ruta.file_stem().and_then(|n| n.to_str()).unwrap_or(archivo).to_lowercase();
+        self.archivos.iter()
+            .map(|p| p.trim().to_lowercase())
+            .any(|p| comodin_calza(&p, &nombre) || comodin_calza(&p, &sin_extension))
+    }
+}
+
+/// Contenido de `layouts.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistroLayouts {
+    #[serde(default)]
+    pub perfil: HashMap<String, LayoutProfile>,
+}
+
+impl RegistroLayouts {
+    /// Parsea y valida el TOML: cada perfil declara a qué archivos se aplica
+    pub fn desde_toml(texto: &str) -> Result<Self, String> {
+        let registro: RegistroLayouts = crate::app_config::desde_toml(texto).map_err(|e| format!("layouts.toml inválido: {}", e))?;
+        for (id, perfil) in &registro.perfil {
+            if perfil.archivos.iter().all(|a| a.trim().is_empty()) {
+                return Err(format!("layouts.toml: el perfil '{}' no declara archivos", id));
+            }
+        }
+        Ok(registro)
+    }
+
+    /// (id, perfil) que se aplica a `archivo`; entre varios, el de id menor
+    pub fn para(&self, archivo: &str) -> Option<(&str, &LayoutProfile)> {
+        self.perfil.iter()
+            .filter(|(_, p)| p.aplica_a(archivo))
+            .min_by(|a, b| a.0.cmp(b.0))
+            .map(|(id, p)| (id.as_str(), p))
+    }
+}
+
+/// Ruta de los perfiles: `layouts_file` de la configuración (`LAYOUTS_FILE`,
+/// por defecto `layouts.toml` en el directorio actual)
+pub fn ruta_registro() -> PathBuf {
+    crate::app_config::actual().layouts_file.clone()
+}
+
+fn global() -> &'static RwLock<Option<Arc<RegistroLayouts>>> {
+    static REGISTRO: OnceLock<RwLock<Option<Arc<RegistroLayouts>>>> = OnceLock::new();
+    REGISTRO.get_or_init(|| RwLock::new(None))
+}
+
+/// Lee los perfiles desde disco. Sin archivo (o con un archivo inválido, que
+/// se avisa) todos los datafiles se leen con el formato de la UDP.
+pub fn cargar_registro(ruta: &Path) -> RegistroLayouts {
+    let Ok(texto) = std::fs::read_to_string(ruta) else {
+        return RegistroLayouts::default();
+    };
+    match RegistroLayouts::desde_toml(&texto) {
+        Ok(r) => {
+            tracing::info!("🗂️ {} perfil(es) de formato de datafiles desde {:?}", r.perfil.len(), ruta);
+            r
+        }
+        Err(e) => {
+            tracing::warn!("⚠️  {}", e);
+            RegistroLayouts::default()
+        }
+    }
+}
+
+/// Perfiles vigentes (se leen de `ruta_registro()` la primera vez)
+pub fn registro() -> Arc<RegistroLayouts> {
+    if let Some(r) = global().read().ok().and_then(|g| g.clone()) {
+        return r;
+    }
+    let r = Arc::new(cargar_registro(&ruta_registro()));
+    if let Ok(mut g) = global().write() {
+        *g = Some(r.clone());
+    }
+    r
+}
+
+/// Reemplaza los perfiles vigentes (recarga o pruebas)
+pub fn establecer_registro(registro: RegistroLayouts) {
+    if let Ok(mut g) = global().write() {
+        *g = Some(Arc::new(registro));
+    }
+}
+
+/// Vuelve a leer `layouts.toml`. Devuelve cuántos perfiles declara.
+pub fn recargar_registro() -> usize {
+    let r = cargar_registro(&ruta_registro());
+    let n = r.perfil.len();
+    establecer_registro(r);
+    n
+}
+
+/// Perfil con el que se lee `archivo`
+pub fn perfil_para(archivo: &str) -> LayoutProfile {
+    let registro = registro();
+    match registro.para(archivo) {
+        Some((id, perfil)) => {
+            tracing::debug!("Formato '{}' para {}", id, archivo);
+            perfil.clone()
+        }
+        None => LayoutProfile::default(),
+    }
+}
@@ -1,15 +1,9 @@
 use std::collections::{HashMap, HashSet};
-use calamine::{open_workbook_auto, Data, Reader};
+use calamine::{Data, Reader};
 use crate::models::RamoDisponible;
-use crate::excel::io::data_to_string;
+use crate::excel::io::{abrir_libro, data_to_string};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicUsize, Ordering};
-
-// Índices configurables (se pueden cambiar en tiempo de ejecución si se desea)
-pub static MALLA_NAME_COL: AtomicUsize = AtomicUsize::new(0);
-pub static MALLA_ID_COL: AtomicUsize = AtomicUsize::new(1);
-pub static OA_NAME_COL: AtomicUsize = AtomicUsize::new(2);
-pub static OA_CODE_COL: AtomicUsize = AtomicUsize::new(0);
+use crate::excel::layout::perfil_para;
 
 /// Lee un archivo de malla (espera filas: codigo, nombre, correlativo, holgura, critico, ...)
 /// Leer malla desde un archivo Excel, permitiendo opcionalmente elegir la hoja
@@ -23,7 +17,7 @@ pub fn leer_malla_excel_with_sheet(nombre_archivo: &str, sheet: Option<&str>) ->
         if std::path::Path::new(&candidate).exists() { candidate } else { nombre_archivo.to_string() }
     };
 
-    let mut workbook = open_workbook_auto(resolved)?;
+    let mut workbook = abrir_libro(resolved)?;
     let mut ramos_disponibles = HashMap::new();
 
     let sheet_names = workbook.sheet_names().to_owned();
@@ -40,22 +34,21 @@ pub fn leer_malla_excel_with_sheet(nombre_archivo: &str, sheet: Option<&str>) ->
 
     let range = workbook.worksheet_range(&hoja_seleccionada)?;
 
-    // Detectar índices de columnas por encabezado (si existe)
+    // Detectar índices de columnas por encabezado (si existe); el id es la
+    // última columna de id o código
+    let layout = perfil_para(nombre_archivo).malla;
     let mut name_idx: usize = 0;
     let mut id_idx: usize = 1;
-    let mut rows: Vec<_> = range.rows().collect();
+    let mut creditos_idx: Option<usize> = None;
+    let mut correquisitos_idx: Option<usize> = None;
+    let rows: Vec<_> = range.rows().collect();
     if !rows.is_empty() {
-        let header = rows[0];
-        for (i, cell) in header.iter().enumerate() {
-            let s = data_to_string(cell).to_lowercase();
-            if s.contains("nombre") || s.contains("asignatura") || s.contains("curso") {
-                name_idx = i;
-            }
-            if s.contains("código") || s.contains("codigo") || s.contains("id") {
-                id_idx = i;
-            }
-        }
-        eprintln!("DEBUG: header detected -> name_idx={} id_idx={}", name_idx, id_idx);
+        let header: Vec<String> = rows[0].iter().map(data_to_string).collect();
+        name_idx = layout.nombre.ultima(&header).unwrap_or(name_idx);
+        id_idx = layout.id.ultima(&header).max(layout.codigo.ultima(&header)).unwrap_or(id_idx);
+        creditos_idx = layout.creditos.ultima(&header);
+        correquisitos_idx = layout.correquisitos.ultima(&header);
+        tracing::debug!("header detected -> name_idx={} id_idx={}", name_idx, id_idx);
     }
 
     // Iterar filas usando los índices detectados; saltar header si existe
@@ -97,6 +90,10 @@ pub fn leer_malla_excel_with_sheet(nombre_archivo: &str, sheet: Option<&str>) ->
             // ignorar filas incompletas
             continue;
         }
+        let creditos = creditos_idx.and_then(|c| parse_creditos(&data_to_string(row.get(c).unwrap_or(&Data::Empty))));
+        let correquisitos_ids = correquisitos_idx
+            .map(|c| parse_lista_ids(&data_to_string(row.get(c).unwrap_or(&Data::Empty))))
+            .unwrap_or_default();
 
         let nombre_norm = crate::excel::normalize_name(&nombre);
         ramos_disponibles.insert(nombre_norm, RamoDisponible {
@@ -107,15 +104,52 @@ pub fn leer_malla_excel_with_sheet(nombre_archivo: &str, sheet: Option<&str>) ->
             numb_correlativo: id,
             critico: false,
             requisitos_ids: vec![],
+            correquisitos_ids,
             dificultad: None,
             electivo: false,
             semestre: None,
+            creditos,
+            tags: Vec::new(),
         });
     }
 
+    if let Ok(hoja) = leer_correquisitos(nombre_archivo) {
+        aplicar_correquisitos(&mut ramos_disponibles, &hoja);
+    }
+    if let Ok(hoja) = leer_notas(nombre_archivo) {
+        aplicar_notas(&mut ramos_disponibles, &hoja);
+    }
+
     Ok(ramos_disponibles)
 }
 
+/// True si el encabezado corresponde a la columna de créditos ("Créditos", "SCT", ...)
+pub fn es_columna_creditos(encabezado: &str) -> bool {
+    let lower = encabezado.to_lowercase();
+    lower.contains("crédito") || lower.contains("credito") || lower.contains("sct")
+}
+
+/// True si el encabezado corresponde a co-requisitos ("Correquisitos",
+/// "Co-requisito", ...). Se revisa antes que "requisito", que también calza.
+pub fn es_columna_correquisitos(encabezado: &str) -> bool {
+    let lower = encabezado.to_lowercase();
+    lower.contains("correq") || lower.contains("co-req") || lower.contains("co req")
+}
+
+/// Lista de números de una celda ("6,7", "6.7", "6; 7"); "—", "0" o vacío -> []
+pub fn parse_lista_ids(celda: &str) -> Vec<i32> {
+    celda.split([',', '.', ';', '/'])
+        .filter_map(|s| s.trim().parse::<i32>().ok())
+        .filter(|n| *n > 0)
+        .collect()
+}
+
+/// Créditos SCT de una celda ("6", "6.0", "5,5" -> 6); vacío o no positivo -> None
+pub fn parse_creditos(celda: &str) -> Option<i32> {
+    let valor = celda.trim().replace(',', ".").parse::<f64>().ok()?;
+    if valor > 0.0 { Some(valor.round() as i32) } else { None }
+}
+
 /// Normaliza el par (col0, col1) devolviendo (codigo, nombre).
 /// Si detecta que la primera columna contiene letras y la segunda contiene
 /// dígitos (por ejemplo: "Nombre" | "ID"), invierte el orden para que el
@@ -151,7 +185,7 @@ pub fn leer_prerequisitos(nombre_archivo: &str) -> Result<HashMap<String, Vec<St
         if Path::new(&candidate).exists() { candidate } else { nombre_archivo.to_string() }
     };
 
-    let mut workbook = open_workbook_auto(resolved)?;
+    let mut workbook = abrir_libro(resolved)?;
     let mut map: HashMap<String, Vec<String>> = HashMap::new();
 
     let sheet_names = workbook.sheet_names().to_owned();
@@ -179,7 +213,8 @@ pub fn leer_prerequisitos(nombre_archivo: &str) -> Result<HashMap<String, Vec<St
             // no hay hojas adicionales con prerequisitos
             return Ok(map);
         }
-        sheet_names.iter().skip(1).cloned().collect()
+        // La hoja de notas de los asesores no trae prerequisitos
+        sheet_names.iter().skip(1).filter(|n| !es_hoja_notas(n)).cloned().collect()
     };
 
     // Iterar sobre las hojas seleccionadas y extraer pares (codigo -> [prereqs])
@@ -248,6 +283,169 @@ pub fn leer_prerequisitos(nombre_archivo: &str) -> Result<HashMap<String, Vec<St
     Ok(map)
 }
 
+/// Lee la hoja de co-requisitos de una malla (cualquier hoja cuyo nombre
+/// contenga "correq"). Cada fila trae el ramo (columna "Código"/"ID"/
+/// "Correlativo") y sus co-requisitos (columna "Correquisitos"), separados
+/// por ',' o ';'. Devuelve ramo -> [co-requisitos] tal como aparecen en la hoja;
+/// `aplicar_correquisitos` los resuelve a IDs.
+pub fn leer_correquisitos(nombre_archivo: &str) -> Result<HashMap<String, Vec<String>>, Box<dyn std::error::Error>> {
+    let resolved = if Path::new(nombre_archivo).exists() {
+        nombre_archivo.to_string()
+    } else {
+        let candidate = format!("{}/{}", crate::excel::DATAFILES_DIR, nombre_archivo);
+        if Path::new(&candidate).exists() { candidate } else { nombre_archivo.to_string() }
+    };
+
+    let mut workbook = abrir_libro(resolved)?;
+    let mut map: HashMap<String, Vec<String>> = HashMap::new();
+
+    let hojas: Vec<String> = workbook.sheet_names().iter()
+        .filter(|n| es_columna_correquisitos(n))
+        .cloned()
+        .collect();
+    for hoja in hojas.iter() {
+        let Ok(range) = workbook.worksheet_range(hoja) else { continue };
+        let rows: Vec<_> = range.rows().collect();
+        let Some(header) = rows.first() else { continue };
+        let mut codigo_col: usize = 0;
+        let mut correq_col: usize = 1;
+        for (i, cell) in header.iter().enumerate() {
+            let s = data_to_string(cell).to_lowercase();
+            if es_columna_correquisitos(&s) {
+                correq_col = i;
+            } else if s.contains("código") || s.contains("codigo") || s.contains("id") || s.contains("correlativo") {
+                codigo_col = i;
+            }
+        }
+        for row in rows.iter().skip(1) {
+            let codigo = data_to_string(row.get(codigo_col).unwrap_or(&Data::Empty)).trim().to_string();
+            let raw = data_to_string(row.get(correq_col).unwrap_or(&Data::Empty));
+            let mut lista: Vec<String> = raw.split([',', ';'])
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty() && s != "0" && s != "—")
+                .collect();
+            if !codigo.is_empty() && !lista.is_empty() {
+                map.entry(codigo).or_default().append(&mut lista);
+            }
+        }
+    }
+
+    Ok(map)
+}
+
+/// Agrega a `ramos` los co-requisitos leídos con `leer_correquisitos`.
+/// Un valor numérico se busca como correlativo (en las mallas Malla20XX el
+/// correlativo es el ID) y uno alfanumérico como código de ramo.
+pub fn aplicar_correquisitos(ramos: &mut HashMap<String, RamoDisponible>, hoja: &HashMap<String, Vec<String>>) {
+    let resolver = |ramos: &HashMap<String, RamoDisponible>, valor: &str| -> Option<i32> {
+        match valor.parse::<i32>() {
+            Ok(n) => ramos.values().find(|r| r.numb_correlativo == n).map(|r| r.id),
+            Err(_) => ramos.values().find(|r| !r.codigo.is_empty() && r.codigo.eq_ignore_ascii_case(valor)).map(|r| r.id),
+        }
+    };
+    for (ramo, correquisitos) in hoja.iter() {
+        let Some(id) = resolver(ramos, ramo) else {
+            tracing::debug!("⚠️  co-requisitos de '{}': ramo no encontrado en la malla", ramo);
+            continue;
+        };
+        let ids: Vec<i32> = correquisitos.iter()
+            .filter_map(|c| {
+                let resuelto = resolver(ramos, c);
+                if resuelto.is_none() {
+                    tracing::debug!("⚠️  co-requisito '{}' de '{}' no encontrado en la malla", c, ramo);
+                }
+                resuelto
+            })
+            .collect();
+        if let Some(r) = ramos.values_mut().find(|r| r.id == id) {
+            for c in ids {
+                if c != id && !r.correquisitos_ids.contains(&c) {
+                    r.correquisitos_ids.push(c);
+                }
+            }
+        }
+    }
+}
+
+/// True si la hoja (o columna) es la de notas de los asesores ("Notas",
+/// "Tags", "Etiquetas")
+pub fn es_hoja_notas(nombre: &str) -> bool {
+    let lower = crate::excel::normalize_name(nombre);
+    lower.contains("nota") || lower.contains("tag") || lower.contains("etiqueta")
+}
+
+/// Lee la hoja de notas de una malla (opcional): cada fila trae el ramo
+/// (columna "Código"/"ID"/"Correlativo", o el nombre del ramo) y una o más
+/// notas (columna "Notas"/"Tags") separadas por ';' o '|'. Un ramo puede
+/// repetirse en varias filas. Devuelve ramo -> [notas] tal como aparecen en
+/// la hoja; `aplicar_notas` las asigna a `RamoDisponible::tags`.
+pub fn leer_notas(nombre_archivo: &str) -> Result<HashMap<String, Vec<String>>, Box<dyn std::error::Error>> {
+    let resolved = if Path::new(nombre_archivo).exists() {
+        nombre_archivo.to_string()
+    } else {
+        let candidate = format!("{}/{}", crate::excel::DATAFILES_DIR, nombre_archivo);
+        if Path::new(&candidate).exists() { candidate } else { nombre_archivo.to_string() }
+    };
+
+    let mut workbook = abrir_libro(resolved)?;
+    let mut map: HashMap<String, Vec<String>> = HashMap::new();
+
+    let hojas: Vec<String> = workbook.sheet_names().iter()
+        .filter(|n| es_hoja_notas(n))
+        .cloned()
+        .collect();
+    for hoja in hojas.iter() {
+        let Ok(range) = workbook.worksheet_range(hoja) else { continue };
+        let rows: Vec<_> = range.rows().collect();
+        let Some(header) = rows.first() else { continue };
+        let mut codigo_col: usize = 0;
+        let mut notas_col: usize = 1;
+        for (i, cell) in header.iter().enumerate() {
+            let s = data_to_string(cell).to_lowercase();
+            if es_hoja_notas(&s) {
+                notas_col = i;
+            } else if s.contains("código") || s.contains("codigo") || s.contains("id") || s.contains("correlativo") {
+                codigo_col = i;
+            }
+        }
+        for row in rows.iter().skip(1) {
+            let codigo = data_to_string(row.get(codigo_col).unwrap_or(&Data::Empty)).trim().to_string();
+            let raw = data_to_string(row.get(notas_col).unwrap_or(&Data::Empty));
+            let mut lista: Vec<String> = raw.split([';', '|', '\n'])
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            if !codigo.is_empty() && !lista.is_empty() {
+                map.entry(codigo).or_default().append(&mut lista);
+            }
+        }
+    }
+
+    Ok(map)
+}
+
+/// Agrega a `ramos` las notas leídas con `leer_notas`. El ramo se busca como
+/// correlativo si es numérico, si no por código y luego por nombre.
+pub fn aplicar_notas(ramos: &mut HashMap<String, RamoDisponible>, hoja: &HashMap<String, Vec<String>>) {
+    for (ramo, notas) in hoja.iter() {
+        let nombre = crate::excel::normalize_name(ramo);
+        let encontrado = match ramo.parse::<i32>() {
+            Ok(n) => ramos.values_mut().find(|r| r.numb_correlativo == n),
+            Err(_) => ramos.values_mut()
+                .find(|r| (!r.codigo.is_empty() && r.codigo.eq_ignore_ascii_case(ramo)) || crate::excel::normalize_name(&r.nombre) == nombre),
+        };
+        let Some(r) = encontrado else {
+            tracing::debug!("⚠️  notas de '{}': ramo no encontrado en la malla", ramo);
+            continue;
+        };
+        for nota in notas {
+            if !r.tags.iter().any(|t| t.eq_ignore_ascii_case(nota)) {
+                r.tags.push(nota.clone());
+            }
+        }
+    }
+}
+
 /// Lee Malla2020 y lo enriquece con información de PA2025-1 (porcentajes y códigos)
 /// 
 /// IMPORTANTE: Manejo especial de ELECTIVOS
@@ -281,21 +479,18 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
      let mut resolved_malla_path: Option<std::path::PathBuf> = None;
     if let Ok((malla_path, oferta_path, _)) = crate::excel::resolve_datafile_paths(malla_archivo) {
             // Intentar abrir con calamine y detectar columna de nombre dinámicamente
-            if let Ok(mut workbook) = calamine::open_workbook_auto(oferta_path.to_str().unwrap_or("")) {
+            if let Ok(mut workbook) = abrir_libro(&oferta_path) {
                 let sheet_names = workbook.sheet_names().to_owned();
                 for sheet in sheet_names.iter() {
                     if let Ok(range) = workbook.worksheet_range(sheet) {
                         // Detectar columna de nombre en header (si existe)
-                        let mut oa_name_col: usize = OA_NAME_COL.load(Ordering::Relaxed);
+                        let layout_oa = perfil_para(&oferta_path.to_string_lossy()).oferta;
+                        let mut oa_name_col: usize = layout_oa.nombre.posicion.unwrap_or(0);
                         let rows_vec: Vec<_> = range.rows().collect();
-                        if let Some(header_row) = rows_vec.get(0) {
-                            for (i, cell) in header_row.iter().enumerate() {
-                                let s = data_to_string(cell).to_lowercase();
-                                if s.contains("nombre") || s.contains("asignatura") || s.contains("ramo") {
-                                    oa_name_col = i;
-                                }
-                            }
-                            eprintln!("DEBUG: OA header detected in '{}' -> oa_name_col={}", sheet, oa_name_col);
+                        if let Some(header_row) = rows_vec.first() {
+                            let header: Vec<String> = header_row.iter().map(data_to_string).collect();
+                            oa_name_col = layout_oa.nombre.primera(&header).unwrap_or(oa_name_col);
+                            tracing::debug!("OA header detected in '{}' -> oa_name_col={}", sheet, oa_name_col);
                         }
 
                         let mut oa_debug_count = 0;
@@ -303,7 +498,7 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
                             if row_idx == 0 { continue; }  // skip header
                             let nombre = data_to_string(row.get(oa_name_col).unwrap_or(&Data::Empty)).trim().to_string();
                             if oa_debug_count < 5 {
-                                eprintln!("DEBUG OA sample row {}: nombre(C)='{}'", row_idx, nombre);
+                                tracing::debug!("OA sample row {}: nombre(C)='{}'", row_idx, nombre);
                                 oa_debug_count += 1;
                             }
                             if !nombre.is_empty() {
@@ -320,20 +515,21 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
              // Fallback: no pudimos resolver rutas automáticamente; intentamos abrir el archivo
              // de oferta usando heurística en DATAFILES_DIR como antes (no modificar comportamiento previo).
              if let Ok((_, oferta_path, _)) = crate::excel::resolve_datafile_paths(malla_archivo) {
-             if let Ok(mut workbook) = calamine::open_workbook_auto(oferta_path.to_str().unwrap_or("")) {
+             if let Ok(mut workbook) = abrir_libro(&oferta_path) {
                  let sheet_names = workbook.sheet_names().to_owned();
                  if let Some(sheet) = sheet_names.first() {
                      if let Ok(range) = workbook.worksheet_range(sheet) {
                          // contador debug para mostrar las primeras filas leídas (fallback)
                          let mut oa_debug_count_fb = 0;
+                         let layout_oa = perfil_para(&oferta_path.to_string_lossy()).oferta;
+                         let oa_code_col = layout_oa.codigo.posicion.unwrap_or(0);
+                         let oa_name_col = layout_oa.nombre.posicion.unwrap_or(0);
                          for (row_idx, row) in range.rows().enumerate() {
                              if row_idx == 0 { continue; }
-                             let oa_code_col = OA_CODE_COL.load(Ordering::Relaxed);
-                             let oa_name_col = OA_NAME_COL.load(Ordering::Relaxed);
                              let codigo = data_to_string(row.get(oa_code_col).unwrap_or(&Data::Empty)).trim().to_string();
                              let nombre = data_to_string(row.get(oa_name_col).unwrap_or(&Data::Empty)).trim().to_string();
                              if oa_debug_count_fb < 5 {
-                                 eprintln!("DEBUG OA(fallback) sample row {}: código='{}' | nombre='{}'", row_idx, codigo, nombre);
+                                 tracing::debug!("OA(fallback) sample row {}: código='{}' | nombre='{}'", row_idx, codigo, nombre);
                                  oa_debug_count_fb += 1;
                              }
                              if !codigo.is_empty() && !nombre.is_empty() {
@@ -347,7 +543,7 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
          }
      }
     
-    eprintln!("DEBUG: OA nombres cargados: {}", oa_nombres.len());
+    tracing::debug!("OA nombres cargados: {}", oa_nombres.len());
      
      // 3. Construir índice invertido: si es_electivo en PA2025-1, indexar también por codigo
      let mut porcent_by_code_electivos: HashMap<String, (String, f64, f64, bool)> = HashMap::new();
@@ -371,9 +567,9 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
      }
      // Ordenar por porcentaje DESCENDENTE (más fácil primero)
      todos_electivos.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-     eprintln!("DEBUG: {} electivos disponibles en PA2025-1 (ordenados por dificultad):", todos_electivos.len());
+     tracing::debug!("{} electivos disponibles en PA2025-1 (ordenados por dificultad):", todos_electivos.len());
      for (cod, pct, _) in todos_electivos.iter() {
-         eprintln!("  - {} ({}%)", cod, pct);
+         tracing::debug!("  - {} ({}%)", cod, pct);
      }
      
      // 4. Leer Malla2020
@@ -390,7 +586,7 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
          resolved
      };
 
-    let mut workbook = open_workbook_auto(malla_to_open.to_str().unwrap_or(""))?;
+    let mut workbook = abrir_libro(malla_to_open.to_str().unwrap_or(""))?;
     let mut ramos_disponibles = HashMap::new();
     
     // Contador para asignación secuencial de electivos sin repetir
@@ -399,43 +595,43 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
     // Usar hoja "Malla2020"
     let range = workbook.worksheet_range("Malla2020")?;
 
-    // Debug: mostrar primeras filas crudas y los valores percibidos según los índices actuales
-    {
-        let mut dbg_count = 0usize;
-        eprintln!("DEBUG: MALLA -> columnas configuradas: name={} id={}", MALLA_NAME_COL.load(Ordering::Relaxed), MALLA_ID_COL.load(Ordering::Relaxed));
-        for (row_idx, row) in range.rows().enumerate() {
-            if dbg_count >= 10 { break; }
-            // Representación cruda de celdas
-            let cells: Vec<String> = row.iter().map(|c| format!("{:?}", c)).collect();
-            // Valores en las columnas configuradas (si existen)
-            let name_col = MALLA_NAME_COL.load(Ordering::Relaxed);
-            let id_col = MALLA_ID_COL.load(Ordering::Relaxed);
-            let name_val = data_to_string(row.get(name_col).unwrap_or(&Data::Empty));
-            let id_val = data_to_string(row.get(id_col).unwrap_or(&Data::Empty));
-            eprintln!("DEBUG MALLA row {}: cells={:?} | name_col[{}]='{}' | id_col[{}]='{}'", row_idx, cells, name_col, name_val, id_col, id_val);
-            dbg_count += 1;
-        }
+    // Columnas por encabezado (Nombre, ID, Créditos, Requisitos, Semestre,
+    // Electivo) o, si no lo nombra, por la posición de esa hoja
+    let layout = perfil_para(&malla_to_open.to_string_lossy()).malla;
+    let header: Vec<String> = range.rows().next().map(|r| r.iter().map(data_to_string).collect()).unwrap_or_default();
+    let columna = |col: &crate::excel::layout::Columna, posicion: usize| col.ultima(&header).unwrap_or(posicion);
+    let malla_name_col = columna(&layout.nombre, 0);
+    let malla_id_col = columna(&layout.id, 1);
+    let creditos_col = columna(&layout.creditos, 2);
+    let semestre_col = columna(&layout.semestre, 4);
+    let electivo_col = columna(&layout.electivo, 5);
+
+    // Debug: mostrar primeras filas crudas y los valores percibidos según los índices detectados
+    tracing::debug!("MALLA -> columnas detectadas: name={} id={}", malla_name_col, malla_id_col);
+    for (row_idx, row) in range.rows().enumerate().take(10) {
+        // Representación cruda de celdas
+        let cells: Vec<String> = row.iter().map(|c| format!("{:?}", c)).collect();
+        let name_val = data_to_string(row.get(malla_name_col).unwrap_or(&Data::Empty));
+        let id_val = data_to_string(row.get(malla_id_col).unwrap_or(&Data::Empty));
+        tracing::debug!("MALLA row {}: cells={:?} | name_col[{}]='{}' | id_col[{}]='{}'", row_idx, cells, malla_name_col, name_val, malla_id_col, id_val);
     }
     
     for (row_idx, row) in range.rows().enumerate() {
         if row_idx == 0 { continue; }  // Saltar encabezado
         
-        // Estructura de Malla2020: Nombre, ID, Créditos, Requisitos, Semestre, Electivo
-        let malla_name_col = MALLA_NAME_COL.load(Ordering::Relaxed);
-        let malla_id_col = MALLA_ID_COL.load(Ordering::Relaxed);
         let nombre = data_to_string(row.get(malla_name_col).unwrap_or(&Data::Empty)).trim().to_string();
         let id_str = data_to_string(row.get(malla_id_col).unwrap_or(&Data::Empty)).trim().to_string();
         let id = id_str.parse::<i32>().unwrap_or(0);
         
-        // Leer columna Electivo (column 5)
         let es_electivo_en_malla = {
-            let ev = data_to_string(row.get(5).unwrap_or(&Data::Empty)).to_lowercase();
+            let ev = data_to_string(row.get(electivo_col).unwrap_or(&Data::Empty)).to_lowercase();
             ev == "true" || ev == "1" || ev == "sí" || ev == "si"
         };
         
-        // Leer columna Semestre (column 4)
+        let creditos = parse_creditos(&data_to_string(row.get(creditos_col).unwrap_or(&Data::Empty)));
+
         let semestre_opt = {
-            let sem_str = data_to_string(row.get(4).unwrap_or(&Data::Empty)).trim().to_string();
+            let sem_str = data_to_string(row.get(semestre_col).unwrap_or(&Data::Empty)).trim().to_string();
             sem_str.parse::<i32>().ok()
         };
         
@@ -459,7 +655,7 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
             if indice_electivo_para_esta_id < todos_electivos.len() {
                 let (cod_elec, pct_elec, _tot_elec) = &todos_electivos[indice_electivo_para_esta_id];
                 let clave_unica = format!("electivo_profesional_{}", id);
-                eprintln!("DEBUG enrich_electivo: ID={}, slot={}, asignado código='{}' ({}%)", 
+                tracing::debug!("enrich_electivo: ID={}, slot={}, asignado código='{}' ({}%)", 
                           id, indice_electivo_para_esta_id, cod_elec, pct_elec);
                 (
                     clave_unica,  // CLAVE = "electivo_profesional_44", "electivo_profesional_46", etc.
@@ -469,7 +665,7 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
                 )
             } else {
                 // Si hay más electivos en Malla que en PA2025-1, usar fallback
-                eprintln!("WARN: No hay suficientes electivos en PA2025-1 para slot {}. Malla tiene más de {} electivos.", indice_electivo_para_esta_id, todos_electivos.len());
+                tracing::warn!("No hay suficientes electivos en PA2025-1 para slot {}. Malla tiene más de {} electivos.", indice_electivo_para_esta_id, todos_electivos.len());
                 let clave_unica = format!("electivo_profesional_{}", id);
                 (clave_unica, id_str.clone(), None, true)
             }
@@ -485,7 +681,7 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
             }
          };
          
-         eprintln!("DEBUG enrich_malla: '{}' (id={}, electivo={}) → clave='{}', código='{}', dificultad={:?}", 
+         tracing::debug!("enrich_malla: '{}' (id={}, electivo={}) → clave='{}', código='{}', dificultad={:?}", 
                    nombre, id, es_electivo_en_malla, clave_hashmap, codigo_final, dificultad);
         
         // Crear RamoDisponible enriquecido (SIN requisitos_ids aún, se resuelve en segundo pase)
@@ -497,9 +693,12 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
             numb_correlativo: id,  // Correlativo es el mismo que ID
             critico: false,
             requisitos_ids: vec![],  // Se resuelve después
+            correquisitos_ids: vec![],
             dificultad,
             electivo: es_electivo_final,
             semestre: semestre_opt,  // Semestre extraído de la Malla
+            creditos,
+            tags: Vec::new(),
         };
         
         // INSERTAR CON CLAVE DIFERENCIADA (usando nombre como llave universal)
@@ -523,7 +722,7 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
                 if otro_ramo.numb_correlativo == id_anterior {
                     // Encontrado: el ramo anterior tiene id = id_anterior
                     updates.push((clave.clone(), id_anterior));
-                    eprintln!("DEBUG depends: ramo {} (id={}) depende de ramo con id={}", 
+                    tracing::debug!("depends: ramo {} (id={}) depende de ramo con id={}", 
                               ramo.nombre, correlativo_actual, id_anterior);
                     break;
                 }
@@ -543,10 +742,3 @@ pub fn leer_malla_con_porcentajes(malla_archivo: &str, porcentajes_archivo: &str
     
     Ok(ramos_disponibles)
 }
-
-// Índices por defecto (edítalos aquí si necesitas otro mapeo):
-// - MALLA_NAME_COL: columna donde está el NOMBRE en la MALLA (A1 => index 0)
-// - OA_NAME_COL: columna donde está el NOMBRE en la OA (C1 => index 2)
-// Índices configurables (se pueden cambiar en tiempo de ejecución si se desea)
-
-
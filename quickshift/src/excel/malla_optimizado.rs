@@ -16,7 +16,7 @@ pub fn leer_malla_con_porcentajes_optimizado(
     malla_archivo: &str,
     porcentajes_archivo: &str,
 ) -> Result<HashMap<String, RamoDisponible>, Box<dyn Error>> {
-    eprintln!("🔍 [OPTIMIZED MALLA] Starting - malla_archivo={}", malla_archivo);
+    tracing::info!("🔍 [OPTIMIZED MALLA] Starting - malla_archivo={}", malla_archivo);
     
     // 🆕 Usar la misma lógica de normalización que en el resto del código
     fn normalize(s: &str) -> String {
@@ -41,11 +41,11 @@ pub fn leer_malla_con_porcentajes_optimizado(
         out.trim().to_string()  // Quitar espacios al inicio/final
     }
 
-    eprintln!("\n🚀 MERGE SIMPLE: MALLA (base) + OA + PA");
-    eprintln!("======================================");
+    tracing::info!("\n🚀 MERGE SIMPLE: MALLA (base) + OA + PA");
+    tracing::info!("======================================");
 
     // PASO 1: Leer MALLA (fuente primaria - filtra todo)
-    eprintln!("\n📖 PASO 1: Leyendo MALLA desde {}", malla_archivo);
+    tracing::info!("\n📖 PASO 1: Leyendo MALLA desde {}", malla_archivo);
     
     // Detectar qué hoja leer: si es MiMalla.xlsx usa "Malla2020", si es Malla2020.xlsx usa "" (hoja activa)
     let sheet_name = if malla_archivo.contains("MiMalla") || malla_archivo.contains("mimalla") {
@@ -53,7 +53,7 @@ pub fn leer_malla_con_porcentajes_optimizado(
     } else {
         "" // Usar la hoja activa (Sheet1)
     };
-    eprintln!("   Usando hoja: '{}'", if sheet_name.is_empty() { "Sheet1 (activa)" } else { sheet_name });
+    tracing::debug!("   Usando hoja: '{}'", if sheet_name.is_empty() { "Sheet1 (activa)" } else { sheet_name });
     
     let malla_rows = crate::excel::io::read_sheet_via_zip(malla_archivo, sheet_name)?;
     
@@ -66,17 +66,19 @@ pub fn leer_malla_con_porcentajes_optimizado(
     let mut semestre_col_idx: Option<usize> = None; // Nueva columna
     let mut requisitos_col_idx: Option<usize> = None; // Columna para leer requisitos previos
     let mut abre_col_idx: Option<usize> = None; // Columna "Abre la/s asignatura/s:" (inversa)
+    let mut creditos_col_idx: Option<usize> = None; // Créditos SCT
+    let mut correquisitos_col_idx: Option<usize> = None; // Co-requisitos (mismo semestre)
     
-    eprintln!("DEBUG: malla_rows.len()={}", malla_rows.len());
+    tracing::debug!("malla_rows.len()={}", malla_rows.len());
     if !malla_rows.is_empty() {
-        eprintln!("DEBUG: First row (header): {:?}", malla_rows.get(0));
+        tracing::debug!("First row (header): {:?}", malla_rows.first());
     }
     
     for (i, row) in malla_rows.iter().enumerate().take(4) {
         // buscar palabras clave en las celdas
         for (j, cell) in row.iter().enumerate() {
             let lower = cell.to_lowercase();
-            eprintln!("DEBUG: Row {}, Col {}: '{}' -> '{}'", i, j, cell, lower);
+            tracing::debug!("Row {}, Col {}: '{}' -> '{}'", i, j, cell, lower);
             
             // Detectar columna de NOMBRE pero evitar confundir con columnas como
             // "Abre la/s asignatura/s" que contienen listas de referencias.
@@ -95,18 +97,26 @@ pub fn leer_malla_con_porcentajes_optimizado(
             if lower.contains("semestre") {
                 header_row_idx = Some(i);
                 semestre_col_idx = Some(j);
-                eprintln!("DEBUG: Found 'semestre' at row {} col {}", i, j);
+                tracing::debug!("Found 'semestre' at row {} col {}", i, j);
             }
-            if lower.contains("requisito") && !lower.contains("abre") {
+            if crate::excel::malla::es_columna_correquisitos(&lower) {
+                header_row_idx = Some(i);
+                correquisitos_col_idx = Some(j);
+                tracing::debug!("Found 'correquisitos' at row {} col {}", i, j);
+            } else if lower.contains("requisito") && !lower.contains("abre") {
                 header_row_idx = Some(i);
                 requisitos_col_idx = Some(j);
-                eprintln!("DEBUG: Found 'requisitos' at row {} col {}", i, j);
+                tracing::debug!("Found 'requisitos' at row {} col {}", i, j);
+            }
+            if crate::excel::malla::es_columna_creditos(&lower) {
+                header_row_idx = Some(i);
+                creditos_col_idx = Some(j);
             }
             // NUEVA: detectar columna "Abre" (relación inversa)
             if lower.contains("abre") {
                 header_row_idx = Some(i);
                 abre_col_idx = Some(j);
-                eprintln!("DEBUG: Found 'abre' at row {} col {}", i, j);
+                tracing::debug!("Found 'abre' at row {} col {}", i, j);
             }
         }
     }
@@ -116,7 +126,7 @@ pub fn leer_malla_con_porcentajes_optimizado(
         None => 2, // comportamiento legacy
     };
 
-    eprintln!("DEBUG: Malla header detected at {:?}, using name_col={} id_col={} semestre_col={:?} requisitos_col={:?}", header_row_idx, name_col_idx, id_col_idx, semestre_col_idx, requisitos_col_idx);
+    tracing::debug!("Malla header detected at {:?}, using name_col={} id_col={} semestre_col={:?} requisitos_col={:?}", header_row_idx, name_col_idx, id_col_idx, semestre_col_idx, requisitos_col_idx);
 
     for (idx, row) in malla_rows.iter().enumerate() {
         if idx < start_idx { continue; }
@@ -133,6 +143,8 @@ pub fn leer_malla_con_porcentajes_optimizado(
             })
         });
         
+        let creditos = creditos_col_idx.and_then(|col| row.get(col).and_then(|c| crate::excel::malla::parse_creditos(c)));
+
         // Leer requisitos si está disponible (IDs de ramos prerequisitos)
         // Existen dos formas:
         // 1. Columna directa "requisito" → IDs que este ramo requiere
@@ -171,6 +183,12 @@ pub fn leer_malla_con_porcentajes_optimizado(
             vec![]
         };
 
+        // Co-requisitos: IDs, con el mismo formato que los requisitos
+        let correquisitos_ids = correquisitos_col_idx
+            .and_then(|col| row.get(col))
+            .map(|c| crate::excel::malla::parse_lista_ids(c))
+            .unwrap_or_default();
+
         let norm_name = normalize(&nombre_real);
         if !norm_name.is_empty() && norm_name != "—" {
             resultado.insert(norm_name.clone(), RamoDisponible {
@@ -181,25 +199,28 @@ pub fn leer_malla_con_porcentajes_optimizado(
                 numb_correlativo: id,
                 critico: false,
                 requisitos_ids,  // Ahora usa múltiples IDs
+                correquisitos_ids,
                 dificultad: None,
                 electivo: false,
                 semestre: semestre_opt,
+                creditos,
+                tags: Vec::new(),
             });
         }
     }
-    eprintln!("✅ Malla: {} cursos cargados", resultado.len());
-    eprintln!("   Ramos cargados (primeros 5): {:?}", resultado.keys().take(5).collect::<Vec<_>>());
+    tracing::info!("✅ Malla: {} cursos cargados", resultado.len());
+    tracing::debug!("   Ramos cargados (primeros 5): {:?}", resultado.keys().take(5).collect::<Vec<_>>());
     
     // Log de requisitos leídos
-    eprintln!("   Requisitos detectados:");
+    tracing::debug!("   Requisitos detectados:");
     for (_name, ramo) in resultado.iter().take(15) {
         if !ramo.requisitos_ids.is_empty() {
-            eprintln!("     - {} (id={}) -> requisitos ids={:?}", ramo.nombre, ramo.id, ramo.requisitos_ids);
+            tracing::debug!("     - {} (id={}) -> requisitos ids={:?}", ramo.nombre, ramo.id, ramo.requisitos_ids);
         }
     }
 
     // PASO 2: Leer OA y validar existencia (no actualizamos código, solo verificamos match)
-    eprintln!("\n📖 PASO 2: Leyendo OA desde src/datafiles/OA20251.xlsx");
+    tracing::info!("\n📖 PASO 2: Leyendo OA desde src/datafiles/OA20251.xlsx");
     
     // Construir ruta correcta para OA20251 desde datafiles
     let data_dir = crate::excel::get_datafiles_dir();
@@ -208,8 +229,8 @@ pub fn leer_malla_con_porcentajes_optimizado(
     let oa_rows = match crate::excel::io::read_sheet_via_zip(&oa_path, "") {
         Ok(rows) => rows,
         Err(e) => {
-            eprintln!("⚠️  OA20251.xlsx no encontrado en {}: {:?}", oa_path, e);
-            eprintln!("   Continuando sin actualizar códigos desde OA");
+            tracing::warn!("⚠️  OA20251.xlsx no encontrado en {}: {:?}", oa_path, e);
+            tracing::warn!("   Continuando sin actualizar códigos desde OA");
             Vec::new()
         }
     };
@@ -235,10 +256,10 @@ pub fn leer_malla_con_porcentajes_optimizado(
             }
         }
     }
-    eprintln!("✅ OA: {} secciones matcheadas por nombre", oa_matched);
+    tracing::info!("✅ OA: {} secciones matcheadas por nombre", oa_matched);
 
     // PASO 3: Leer PA y actualizar porcentajes en ramos
-    eprintln!("\n📖 PASO 3: Leyendo PA desde {}", porcentajes_archivo);
+    tracing::info!("\n📖 PASO 3: Leyendo PA desde {}", porcentajes_archivo);
     let pa_rows = crate::excel::io::read_sheet_via_zip(porcentajes_archivo, "")?;
     
     let mut pa_matched = 0;
@@ -265,25 +286,35 @@ pub fn leer_malla_con_porcentajes_optimizado(
             pa_index.insert(norm_nombre, pct);
         }
     }
-    eprintln!("✅ PA: {} nombres de asignatura indexados", pa_index.len());
-    eprintln!("   (Primeros 5 entradas del índice PA: {:?})", pa_index.iter().take(5).collect::<Vec<_>>());
+    tracing::info!("✅ PA: {} nombres de asignatura indexados", pa_index.len());
+    tracing::debug!("   (Primeros 5 entradas del índice PA: {:?})", pa_index.iter().take(5).collect::<Vec<_>>());
 
     // PASO 4: Mergear PA basado en nombre normalizado
     for ramo in resultado.values_mut() {
         // Buscar porcentaje por nombre normalizado del ramo
         let norm_ramo_nombre = normalize(&ramo.nombre);
         if let Some(pct) = pa_index.get(&norm_ramo_nombre) {
-            eprintln!("   ✓ Match encontrado: '{}' -> {}%", ramo.nombre, pct);
+            tracing::debug!("   ✓ Match encontrado: '{}' -> {}%", ramo.nombre, pct);
             ramo.dificultad = Some(*pct);
             pa_matched += 1;
         }
     }
-    eprintln!("✅ PA: {} porcentajes matcheados por nombre", pa_matched);
+    tracing::info!("✅ PA: {} porcentajes matcheados por nombre", pa_matched);
 
-    eprintln!("\n✅ MERGE COMPLETADO:");
-    eprintln!("  - Ramos de MALLA: {}", resultado.len());
-    eprintln!("  - Con OA actualizado: {}", oa_matched);
-    eprintln!("  - Con PA (porcentaje): {}", pa_matched);
+    // Co-requisitos declarados en una hoja aparte ("Correquisitos")
+    match crate::excel::malla::leer_correquisitos(malla_archivo) {
+        Ok(hoja) => crate::excel::malla::aplicar_correquisitos(&mut resultado, &hoja),
+        Err(e) => tracing::debug!("sin hoja de co-requisitos en {}: {}", malla_archivo, e),
+    }
+    // Notas de los asesores ("Notas"), opcionales
+    if let Ok(hoja) = crate::excel::malla::leer_notas(malla_archivo) {
+        crate::excel::malla::aplicar_notas(&mut resultado, &hoja);
+    }
+
+    tracing::info!("\n✅ MERGE COMPLETADO:");
+    tracing::debug!("  - Ramos de MALLA: {}", resultado.len());
+    tracing::debug!("  - Con OA actualizado: {}", oa_matched);
+    tracing::debug!("  - Con PA (porcentaje): {}", pa_matched);
 
     Ok(resultado)
 }
@@ -299,7 +330,7 @@ pub fn leer_mc_con_porcentajes_optimizado(
     malla_archivo: &str,
     porcentajes_archivo: &str,
 ) -> Result<HashMap<String, RamoDisponible>, Box<dyn Error>> {
-    eprintln!("🔍 [MC OPTIMIZED] Starting - malla_archivo={}", malla_archivo);
+    tracing::info!("🔍 [MC OPTIMIZED] Starting - malla_archivo={}", malla_archivo);
     
     fn normalize(s: &str) -> String {
         let mut out = String::new();
@@ -323,47 +354,54 @@ pub fn leer_mc_con_porcentajes_optimizado(
         out.trim().to_string()
     }
 
-    eprintln!("\n🚀 MC PARSER: Leyendo Malla Curricular");
-    eprintln!("=====================================");
+    tracing::info!("\n🚀 MC PARSER: Leyendo Malla Curricular");
+    tracing::info!("=====================================");
 
     // PASO 1: Leer MC
-    eprintln!("\n📖 PASO 1: Leyendo MC desde {}", malla_archivo);
+    tracing::info!("\n📖 PASO 1: Leyendo MC desde {}", malla_archivo);
     
     let sheet_name = "MallaCurricular2020"; // MC siempre usa esta hoja
-    eprintln!("   Usando hoja: '{}'", sheet_name);
+    tracing::debug!("   Usando hoja: '{}'", sheet_name);
     
     let malla_rows = crate::excel::io::read_sheet_via_zip(malla_archivo, sheet_name)?;
     
     let mut resultado: HashMap<String, RamoDisponible> = HashMap::new();
     let mut correlativo_to_id: HashMap<i32, i32> = HashMap::new(); // Mapea Num Correlativo -> ID interno
     
-    // Detectar columnas
-    let mut correlativo_col = 0usize;
-    let mut codigo_col = 1usize;
-    let mut nombre_col = 2usize;
-    let mut prerreq_col = 3usize;
-    let mut semestre_col = 5usize;
+    // Detectar columnas según el formato del archivo (`layout::perfil_para`);
+    // cada celda del encabezado nombra a lo más una columna, en este orden
+    let layout = crate::excel::layout::perfil_para(malla_archivo).malla;
+    let mut correlativo_col = layout.id.posicion.unwrap_or(0);
+    let mut codigo_col = layout.codigo.posicion.unwrap_or(1);
+    let mut nombre_col = layout.nombre.posicion.unwrap_or(2);
+    let mut prerreq_col = layout.requisitos.posicion.unwrap_or(3);
+    let mut semestre_col = layout.semestre.posicion.unwrap_or(5);
+    let mut creditos_col: Option<usize> = layout.creditos.posicion;
+    let mut correquisitos_col: Option<usize> = layout.correquisitos.posicion;
     
     // Escanear encabezado
     if !malla_rows.is_empty() {
         let header = &malla_rows[0];
         for (i, cell) in header.iter().enumerate() {
-            let lower = cell.to_lowercase();
-            if lower.contains("correlativo") {
+            if layout.id.calza(cell) {
                 correlativo_col = i;
-            } else if lower.contains("código") {
+            } else if layout.codigo.calza(cell) {
                 codigo_col = i;
-            } else if lower.contains("nombre") {
+            } else if layout.nombre.calza(cell) {
                 nombre_col = i;
-            } else if lower.contains("prerreq") {
+            } else if layout.correquisitos.calza(cell) {
+                correquisitos_col = Some(i);
+            } else if layout.requisitos.calza(cell) {
                 prerreq_col = i;
-            } else if lower.contains("semestre") {
+            } else if layout.semestre.calza(cell) {
                 semestre_col = i;
+            } else if layout.creditos.calza(cell) {
+                creditos_col = Some(i);
             }
         }
     }
 
-    eprintln!("   Columnas detectadas: correlativo={}, codigo={}, nombre={}, prerreq={}, semestre={}", 
+    tracing::debug!("   Columnas detectadas: correlativo={}, codigo={}, nombre={}, prerreq={}, semestre={}", 
               correlativo_col, codigo_col, nombre_col, prerreq_col, semestre_col);
 
     let mut internal_id = 1i32;
@@ -386,6 +424,7 @@ pub fn leer_mc_con_porcentajes_optimizado(
         }
 
         let semestre_opt = semestre_str.parse::<i32>().ok();
+        let creditos = creditos_col.and_then(|c| row.get(c)).and_then(|c| crate::excel::malla::parse_creditos(c));
 
         // Guardar mapeo correlativo -> internal_id
         correlativo_to_id.insert(correlativo, internal_id);
@@ -404,6 +443,12 @@ pub fn leer_mc_con_porcentajes_optimizado(
             }
         }
 
+        // Co-requisitos: también como Num Correlativo
+        let correquisitos_ids = correquisitos_col
+            .and_then(|c| row.get(c))
+            .map(|c| crate::excel::malla::parse_lista_ids(c))
+            .unwrap_or_default();
+
         let norm_name = normalize(&nombre);
         resultado.insert(norm_name.clone(), RamoDisponible {
             id: internal_id,
@@ -413,21 +458,24 @@ pub fn leer_mc_con_porcentajes_optimizado(
             numb_correlativo: correlativo,
             critico: false,
             requisitos_ids,  // Aún contiene correlativo, será convertido después
+            correquisitos_ids,
             dificultad: None,
             electivo: false,
             semestre: semestre_opt,
+            creditos,
+            tags: Vec::new(),
         });
 
         internal_id += 1;
     }
 
-    eprintln!("✅ MC: {} cursos cargados", resultado.len());
-    eprintln!("[DEBUG] correlativo_to_id entries: {}", correlativo_to_id.len());
+    tracing::info!("✅ MC: {} cursos cargados", resultado.len());
+    tracing::debug!("correlativo_to_id entries: {}", correlativo_to_id.len());
 
     // PASO 2: Convertir Num Correlativo a IDs internos en requisitos_ids
     for ramo in resultado.values_mut() {
         if !ramo.requisitos_ids.is_empty() {
-            eprintln!("[DEBUG] {} (id={}) tiene {} requisitos originales: {:?}", 
+            tracing::debug!("{} (id={}) tiene {} requisitos originales: {:?}", 
                       ramo.nombre, ramo.id, ramo.requisitos_ids.len(), ramo.requisitos_ids);
         }
         
@@ -436,21 +484,24 @@ pub fn leer_mc_con_porcentajes_optimizado(
             if let Some(&internal_id) = correlativo_to_id.get(&prereq_corr) {
                 converted_ids.push(internal_id);
             } else {
-                eprintln!("[DEBUG] ⚠️  Correlativo {} NO ENCONTRADO en mapa", prereq_corr);
+                tracing::debug!("⚠️  Correlativo {} NO ENCONTRADO en mapa", prereq_corr);
             }
         }
         ramo.requisitos_ids = converted_ids;
+        ramo.correquisitos_ids = ramo.correquisitos_ids.iter()
+            .filter_map(|c| correlativo_to_id.get(c).copied())
+            .collect();
         
         if !ramo.requisitos_ids.is_empty() {
-            eprintln!("[DEBUG] {} (id={}) después de conversión: {:?}", 
+            tracing::debug!("{} (id={}) después de conversión: {:?}", 
                       ramo.nombre, ramo.id, ramo.requisitos_ids);
         }
     }
 
-    eprintln!("✅ Prerequisitos convertidos de Correlativo a ID");
+    tracing::info!("✅ Prerequisitos convertidos de Correlativo a ID");
 
     // PASO 3: Leer OA20251
-    eprintln!("\n📖 PASO 2: Leyendo OA desde OA20251.xlsx");
+    tracing::info!("\n📖 PASO 2: Leyendo OA desde OA20251.xlsx");
     
     let base_path = std::path::Path::new(malla_archivo)
         .parent()
@@ -475,10 +526,10 @@ pub fn leer_mc_con_porcentajes_optimizado(
             }
         }
     }
-    eprintln!("✅ OA: {} secciones matcheadas", oa_matched);
+    tracing::info!("✅ OA: {} secciones matcheadas", oa_matched);
 
     // PASO 4: Leer PA
-    eprintln!("\n📖 PASO 3: Leyendo PA desde {}", porcentajes_archivo);
+    tracing::info!("\n📖 PASO 3: Leyendo PA desde {}", porcentajes_archivo);
     let pa_rows = crate::excel::io::read_sheet_via_zip(porcentajes_archivo, "")?;
     
     let mut pa_matched = 0;
@@ -507,12 +558,22 @@ pub fn leer_mc_con_porcentajes_optimizado(
             pa_matched += 1;
         }
     }
-    eprintln!("✅ PA: {} porcentajes matcheados", pa_matched);
+    tracing::info!("✅ PA: {} porcentajes matcheados", pa_matched);
+
+    // Co-requisitos declarados en una hoja aparte ("Correquisitos")
+    match crate::excel::malla::leer_correquisitos(malla_archivo) {
+        Ok(hoja) => crate::excel::malla::aplicar_correquisitos(&mut resultado, &hoja),
+        Err(e) => tracing::debug!("sin hoja de co-requisitos en {}: {}", malla_archivo, e),
+    }
+    // Notas de los asesores ("Notas"), opcionales
+    if let Ok(hoja) = crate::excel::malla::leer_notas(malla_archivo) {
+        crate::excel::malla::aplicar_notas(&mut resultado, &hoja);
+    }
 
-    eprintln!("\n✅ MC PARSER COMPLETADO:");
-    eprintln!("  - Ramos de MC: {}", resultado.len());
-    eprintln!("  - Con OA actualizado: {}", oa_matched);
-    eprintln!("  - Con PA (porcentaje): {}", pa_matched);
+    tracing::info!("\n✅ MC PARSER COMPLETADO:");
+    tracing::debug!("  - Ramos de MC: {}", resultado.len());
+    tracing::debug!("  - Con OA actualizado: {}", oa_matched);
+    tracing::debug!("  - Con PA (porcentaje): {}", pa_matched);
 
     Ok(resultado)
 }
@@ -16,6 +16,8 @@
 /// ```
 
 use std::collections::HashMap;
+use serde::Serialize;
+use crate::excel::normalize_name;
 
 /// Estructura que representa la información unificada de una asignatura
 #[derive(Clone, Debug)]
@@ -112,3 +114,203 @@ impl Default for MapeoMaestro {
     }
 }
 
+
+// ============================================================================
+// MATCHING APROXIMADO DE NOMBRES
+// ============================================================================
+//
+// Los archivos no siempre escriben igual un mismo ramo ("Cálculo I" en la
+// malla, "CALCULO 1" en la oferta, "Calculo  I." en porcentajes). Sobre
+// `normalize_name` se comparan claves donde los numerales romanos sueltos
+// (i..x) pasan a dígitos, y si no hay igualdad se acepta el candidato más
+// parecido sobre el umbral. Dos ramos con distinta numeración ("Física I" /
+// "Física II") nunca se emparejan aunque su similitud sea alta.
+
+/// Medida de similitud entre claves, en [0, 1]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum MetricaSimilitud {
+    /// Jaro-Winkler: premia prefijos comunes, buena para typos y sufijos
+    JaroWinkler,
+    /// Levenshtein normalizado por el largo de la clave más larga
+    Levenshtein,
+}
+
+/// Emparejador de nombres entre archivos (malla, oferta, porcentajes)
+#[derive(Clone, Debug)]
+pub struct FuzzyMatcher {
+    pub metrica: MetricaSimilitud,
+    /// Similitud mínima para aceptar un candidato que no es idéntico
+    pub umbral: f64,
+    /// Si el segundo mejor candidato queda a menos de esto del primero, el
+    /// match es ambiguo y no se usa
+    pub margen_ambiguedad: f64,
+}
+
+impl Default for FuzzyMatcher {
+    fn default() -> Self {
+        FuzzyMatcher { metrica: MetricaSimilitud::JaroWinkler, umbral: 0.93, margen_ambiguedad: 0.01 }
+    }
+}
+
+/// Candidato con su similitud al nombre buscado
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Candidato {
+    pub nombre: String,
+    pub similitud: f64,
+}
+
+/// Resultado de buscar un nombre entre los candidatos
+#[derive(Clone, Debug, PartialEq)]
+pub enum Emparejamiento {
+    /// Mismo nombre normalizado
+    Exacto(String),
+    /// Único candidato sobre el umbral (o clave idéntica salvo numerales)
+    Aproximado(Candidato),
+    /// Varios candidatos distintos casi igual de parecidos: no se elige ninguno
+    Ambiguo(Vec<Candidato>),
+    Ninguno,
+}
+
+impl Emparejamiento {
+    /// Candidato a usar, si lo hay (los ambiguos no se usan)
+    pub fn elegido(&self) -> Option<&str> {
+        match self {
+            Emparejamiento::Exacto(n) => Some(n),
+            Emparejamiento::Aproximado(c) => Some(&c.nombre),
+            Emparejamiento::Ambiguo(_) | Emparejamiento::Ninguno => None,
+        }
+    }
+}
+
+/// Match aproximado aceptado, para revisar que el join tenga sentido
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MatchAproximado {
+    pub nombre: String,
+    pub candidato: String,
+    pub similitud: f64,
+}
+
+/// Nombre que quedó sin emparejar por tener varios candidatos parecidos
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MatchAmbiguo {
+    pub nombre: String,
+    pub candidatos: Vec<Candidato>,
+}
+
+/// Lo que el matching aproximado decidió en un join entre archivos
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ReporteMatching {
+    pub aproximados: Vec<MatchAproximado>,
+    pub ambiguos: Vec<MatchAmbiguo>,
+}
+
+impl ReporteMatching {
+    /// Anota `resultado` si fue aproximado o ambiguo y devuelve el candidato elegido
+    pub fn registrar<'a>(&mut self, nombre: &str, resultado: &'a Emparejamiento) -> Option<&'a str> {
+        match resultado {
+            Emparejamiento::Aproximado(c) => {
+                tracing::debug!("'{}' emparejado con '{}' (similitud {:.3})", nombre, c.nombre, c.similitud);
+                self.aproximados.push(MatchAproximado { nombre: nombre.to_string(), candidato: c.nombre.clone(), similitud: c.similitud });
+            }
+            Emparejamiento::Ambiguo(cs) => {
+                tracing::warn!("'{}' es ambiguo entre {:?}; se deja sin emparejar", nombre, cs.iter().map(|c| &c.nombre).collect::<Vec<_>>());
+                self.ambiguos.push(MatchAmbiguo { nombre: nombre.to_string(), candidatos: cs.clone() });
+            }
+            Emparejamiento::Exacto(_) | Emparejamiento::Ninguno => {}
+        }
+        resultado.elegido()
+    }
+}
+
+/// Numeral romano suelto (hasta X) como dígitos
+fn romano_a_digitos(token: &str) -> Option<&'static str> {
+    Some(match token {
+        "i" => "1", "ii" => "2", "iii" => "3", "iv" => "4", "v" => "5",
+        "vi" => "6", "vii" => "7", "viii" => "8", "ix" => "9", "x" => "10",
+        _ => return None,
+    })
+}
+
+/// Nombre normalizado con los numerales romanos como dígitos
+fn clave(nombre: &str) -> String {
+    normalize_name(nombre)
+        .split_whitespace()
+        .map(|t| romano_a_digitos(t).unwrap_or(t))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Números de la clave ("calculo 2" → ["2"]); deben coincidir para emparejar
+fn numeros(clave: &str) -> Vec<&str> {
+    let mut ns: Vec<&str> = clave.split_whitespace().filter(|t| t.chars().all(|c| c.is_ascii_digit())).collect();
+    ns.sort_unstable();
+    ns
+}
+
+/// Jaro-Winkler clásico (prefijo común de a lo más 4, p = 0.1). El de
+/// strsim 0.10 no acota el prefijo y da 1.0 si un nombre es prefijo del
+/// otro ("programacion" / "programacion avanzada").
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let jaro = strsim::jaro(a, b);
+    let prefijo = a.chars().zip(b.chars()).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefijo as f64 * 0.1 * (1.0 - jaro)
+}
+
+impl FuzzyMatcher {
+    /// Similitud entre las claves de dos nombres (0 si su numeración difiere)
+    pub fn similitud(&self, a: &str, b: &str) -> f64 {
+        self.similitud_claves(&clave(a), &clave(b))
+    }
+
+    fn similitud_claves(&self, a: &str, b: &str) -> f64 {
+        if a == b {
+            return 1.0;
+        }
+        if numeros(a) != numeros(b) {
+            return 0.0;
+        }
+        match self.metrica {
+            MetricaSimilitud::JaroWinkler => jaro_winkler(a, b),
+            MetricaSimilitud::Levenshtein => strsim::normalized_levenshtein(a, b),
+        }
+    }
+
+    /// Busca `nombre` entre `candidatos`: primero por igualdad de
+    /// `normalize_name`, luego por similitud sobre `umbral`
+    pub fn emparejar(&self, nombre: &str, candidatos: &[String]) -> Emparejamiento {
+        let norm = normalize_name(nombre);
+        if let Some(c) = candidatos.iter().find(|c| normalize_name(c) == norm) {
+            return Emparejamiento::Exacto(c.clone());
+        }
+
+        let buscada = clave(nombre);
+        // mejor candidato por clave: dos nombres con la misma clave son el mismo ramo
+        let mut por_clave: Vec<(String, Candidato)> = Vec::new();
+        for c in candidatos {
+            let k = clave(c);
+            let similitud = self.similitud_claves(&buscada, &k);
+            if similitud < self.umbral || por_clave.iter().any(|(otra, _)| *otra == k) {
+                continue;
+            }
+            por_clave.push((k, Candidato { nombre: c.clone(), similitud }));
+        }
+        let mut sobre_umbral: Vec<Candidato> = por_clave.into_iter().map(|(_, c)| c).collect();
+        sobre_umbral.sort_by(|a, b| b.similitud.total_cmp(&a.similitud).then_with(|| a.nombre.cmp(&b.nombre)));
+
+        match sobre_umbral.len() {
+            0 => Emparejamiento::Ninguno,
+            1 => Emparejamiento::Aproximado(sobre_umbral.remove(0)),
+            _ => {
+                let mejor = sobre_umbral[0].similitud;
+                let empatados: Vec<Candidato> = sobre_umbral.into_iter()
+                    .filter(|c| mejor - c.similitud < self.margen_ambiguedad)
+                    .collect();
+                if empatados.len() == 1 {
+                    Emparejamiento::Aproximado(empatados.into_iter().next().unwrap())
+                } else {
+                    Emparejamiento::Ambiguo(empatados)
+                }
+            }
+        }
+    }
+}
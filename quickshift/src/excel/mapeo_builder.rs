@@ -4,9 +4,14 @@
 
 use crate::excel::mapeo::{MapeoMaestro, MapeoAsignatura};
 use crate::excel::normalize_name;
-use crate::excel::io::data_to_string;
-use calamine::{open_workbook_auto, Data, Reader};
-use std::path::Path;
+use crate::excel::io::{abrir_libro, data_to_string};
+use calamine::{Data, Reader};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::SystemTime;
 
 /// Construir mapeo maestro desde los 3 archivos Excel
 pub fn construir_mapeo_maestro(
@@ -17,34 +22,124 @@ pub fn construir_mapeo_maestro(
     let mut mapeo = MapeoMaestro::new();
 
     // PASO 1: Leer PA2025-1 (es la fuente de verdad para códigos y porcentajes)
-    eprintln!("📖 PASO 1: Leyendo PA2025-1...");
+    tracing::info!("📖 PASO 1: Leyendo PA2025-1...");
     leer_pa2025_al_mapeo(ruta_pa2025, &mut mapeo)?;
 
     // PASO 2: Leer OA2024 (agrega información de horarios/secciones)
-    eprintln!("📖 PASO 2: Leyendo OA2024...");
+    tracing::info!("📖 PASO 2: Leyendo OA2024...");
     leer_oa2024_al_mapeo(ruta_oa2024, &mut mapeo)?;
 
     // PASO 3: Leer Malla2020 (agrega información de estructura y dependencias)
-    eprintln!("📖 PASO 3: Leyendo Malla2020...");
+    tracing::info!("📖 PASO 3: Leyendo Malla2020...");
     leer_malla2020_al_mapeo(ruta_malla, &mut mapeo)?;
 
-    eprintln!("✅ {}", mapeo.resumen());
+    tracing::info!("✅ {}", mapeo.resumen());
     Ok(mapeo)
 }
 
+/// Ruta tal cual si existe; si no, la misma dentro de `DATAFILES_DIR`
+fn resolver_ruta(archivo: &str) -> String {
+    if Path::new(archivo).exists() {
+        return archivo.to_string();
+    }
+    let candidate = format!("{}/{}", crate::excel::DATAFILES_DIR, archivo);
+    if Path::new(&candidate).exists() { candidate } else { archivo.to_string() }
+}
+
+// Caché del mapeo entre requests. La clave son las tres rutas resueltas; cada
+// entrada guarda el mtime de los archivos con que se construyó y se reconstruye
+// en el siguiente acceso si alguno cambió (invalidación perezosa, sin watcher).
+
+struct EntradaMapeo {
+    mtimes: [Option<SystemTime>; 3],
+    mapeo: Arc<MapeoMaestro>,
+}
+
+fn cache_mapeos() -> &'static RwLock<HashMap<[PathBuf; 3], EntradaMapeo>> {
+    static CACHE: OnceLock<RwLock<HashMap<[PathBuf; 3], EntradaMapeo>>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static INVALIDACIONES: AtomicU64 = AtomicU64::new(0);
+
+/// Contadores del caché de `mapeo_maestro` desde que arrancó el proceso
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EstadisticasCacheMapeo {
+    pub entradas: usize,
+    pub hits: u64,
+    /// Construcciones por no haber entrada (incluye las invalidadas)
+    pub misses: u64,
+    /// Entradas descartadas porque cambió el mtime de alguno de sus archivos
+    pub invalidaciones: u64,
+}
+
+fn mtime(ruta: &Path) -> Option<SystemTime> {
+    std::fs::metadata(ruta).and_then(|m| m.modified()).ok()
+}
+
+/// `construir_mapeo_maestro` cacheado: mientras los tres archivos no cambien
+/// se devuelve el mismo mapeo sin volver a leer los Excel.
+pub fn mapeo_maestro(
+    ruta_malla: &str,
+    ruta_oa2024: &str,
+    ruta_pa2025: &str,
+) -> Result<Arc<MapeoMaestro>, Box<dyn std::error::Error>> {
+    let rutas = [ruta_malla, ruta_oa2024, ruta_pa2025].map(|r| {
+        let ruta = PathBuf::from(resolver_ruta(r));
+        ruta.canonicalize().unwrap_or(ruta)
+    });
+    let mtimes = [mtime(&rutas[0]), mtime(&rutas[1]), mtime(&rutas[2])];
+
+    if let Ok(cache) = cache_mapeos().read() {
+        match cache.get(&rutas) {
+            Some(e) if e.mtimes == mtimes => {
+                HITS.fetch_add(1, Ordering::Relaxed);
+                return Ok(e.mapeo.clone());
+            }
+            Some(_) => {
+                INVALIDACIONES.fetch_add(1, Ordering::Relaxed);
+                tracing::info!("🔄 Mapeo maestro desactualizado, se reconstruye: {:?}", rutas);
+            }
+            None => {}
+        }
+    }
+
+    MISSES.fetch_add(1, Ordering::Relaxed);
+    let mapeo = Arc::new(construir_mapeo_maestro(ruta_malla, ruta_oa2024, ruta_pa2025)?);
+    if let Ok(mut cache) = cache_mapeos().write() {
+        cache.insert(rutas, EntradaMapeo { mtimes, mapeo: mapeo.clone() });
+    }
+    Ok(mapeo)
+}
+
+/// Estado actual del caché de mapeos
+pub fn estadisticas_cache_mapeo() -> EstadisticasCacheMapeo {
+    EstadisticasCacheMapeo {
+        entradas: cache_mapeos().read().map(|c| c.len()).unwrap_or(0),
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+        invalidaciones: INVALIDACIONES.load(Ordering::Relaxed),
+    }
+}
+
+/// Vacía el caché de mapeos. Devuelve cuántas entradas había.
+pub fn invalidar_cache_mapeo() -> usize {
+    let Ok(mut cache) = cache_mapeos().write() else { return 0 };
+    let n = cache.len();
+    cache.clear();
+    n
+}
+
 /// Leer PA2025-1 y agregar al mapeo
 fn leer_pa2025_al_mapeo(
     archivo: &str,
     mapeo: &mut MapeoMaestro,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let resolved = if Path::new(archivo).exists() {
-        archivo.to_string()
-    } else {
-        let candidate = format!("{}/{}", crate::excel::DATAFILES_DIR, archivo);
-        if Path::new(&candidate).exists() { candidate } else { archivo.to_string() }
-    };
-
-    let mut workbook = open_workbook_auto(&resolved)?;
+    let resolved = resolver_ruta(archivo);
+
+    let mut workbook = abrir_libro(&resolved)?;
     let sheet_name = workbook.sheet_names()[0].clone();
     let range = workbook.worksheet_range(&sheet_name)?;
 
@@ -71,7 +166,7 @@ fn leer_pa2025_al_mapeo(
         mapeo.add_asignatura(asignatura);
     }
 
-    eprintln!("  ✓ PA2025-1: {} asignaturas cargadas", mapeo.len());
+    tracing::debug!("  ✓ PA2025-1: {} asignaturas cargadas", mapeo.len());
     Ok(())
 }
 
@@ -80,14 +175,9 @@ fn leer_oa2024_al_mapeo(
     archivo: &str,
     mapeo: &mut MapeoMaestro,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let resolved = if Path::new(archivo).exists() {
-        archivo.to_string()
-    } else {
-        let candidate = format!("{}/{}", crate::excel::DATAFILES_DIR, archivo);
-        if Path::new(&candidate).exists() { candidate } else { archivo.to_string() }
-    };
-
-    let mut workbook = open_workbook_auto(&resolved)?;
+    let resolved = resolver_ruta(archivo);
+
+    let mut workbook = abrir_libro(&resolved)?;
     let sheet_name = workbook.sheet_names()[0].clone();
     let range = workbook.worksheet_range(&sheet_name)?;
 
@@ -109,7 +199,7 @@ fn leer_oa2024_al_mapeo(
         if let Some(asignatura_mut) = mapeo.asignaturas.get_mut(&nombre_norm) {
             asignatura_mut.codigo_oa2024 = Some(codigo.clone());
             matched = true;
-            eprintln!("DEBUG: OA match by normalized name: '{}' -> {}", codigo, asignatura_mut.nombre_real);
+            tracing::debug!("OA match by normalized name: '{}' -> {}", codigo, asignatura_mut.nombre_real);
         }
 
         // Nota: debido a limitaciones del diffs, reescribimos la lógica correctamente abajo.
@@ -121,7 +211,7 @@ fn leer_oa2024_al_mapeo(
             if let Some(asign_pa) = mapeo.asignaturas.values_mut().find(|a| a.codigo_pa2025.as_deref() == Some(codigo.as_str())) {
                 asign_pa.codigo_oa2024 = Some(codigo.clone());
                 matched = true;
-                eprintln!("DEBUG: OA match by PA code: '{}' -> {}", codigo, asign_pa.nombre_real);
+                tracing::debug!("OA match by PA code: '{}' -> {}", codigo, asign_pa.nombre_real);
             }
         }
 
@@ -134,20 +224,20 @@ fn leer_oa2024_al_mapeo(
                 if common >= 2 {
                     asign.codigo_oa2024 = Some(codigo.clone());
                     matched = true;
-                    eprintln!("DEBUG: OA fuzzy match (tokens) '{}' -> {} (common tokens={})", codigo, asign.nombre_real, common);
+                    tracing::debug!("OA fuzzy match (tokens) '{}' -> {} (common tokens={})", codigo, asign.nombre_real, common);
                     break;
                 }
             }
         }
 
         if !matched {
-            eprintln!("WARN: OA no match encontrado para código '{}' nombre='{}' (norm='{}')", codigo, nombre, nombre_norm);
+            tracing::warn!("OA no match encontrado para código '{}' nombre='{}' (norm='{}')", codigo, nombre, nombre_norm);
         }
 
         contador += 1;
     }
 
-    eprintln!("  ✓ OA2024: {} secciones procesadas", contador);
+    tracing::debug!("  ✓ OA2024: {} secciones procesadas", contador);
     Ok(())
 }
 
@@ -156,14 +246,9 @@ fn leer_malla2020_al_mapeo(
     archivo: &str,
     mapeo: &mut MapeoMaestro,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let resolved = if Path::new(archivo).exists() {
-        archivo.to_string()
-    } else {
-        let candidate = format!("{}/{}", crate::excel::DATAFILES_DIR, archivo);
-        if Path::new(&candidate).exists() { candidate } else { archivo.to_string() }
-    };
-
-    let mut workbook = open_workbook_auto(&resolved)?;
+    let resolved = resolver_ruta(archivo);
+
+    let mut workbook = abrir_libro(&resolved)?;
     let range = workbook.worksheet_range("Malla2020")?;
 
     let mut contador = 0;
@@ -187,7 +272,7 @@ fn leer_malla2020_al_mapeo(
         contador += 1;
     }
 
-    eprintln!("  ✓ Malla2020: {} asignaturas procesadas", contador);
+    tracing::debug!("  ✓ Malla2020: {} asignaturas procesadas", contador);
     Ok(())
 }
 
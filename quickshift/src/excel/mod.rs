@@ -1,12 +1,19 @@
 //! Módulo `excel` dividido en submódulos para mantener el código organizado.
 //!
 //! Submódulos:
-//! - `io`: helpers y utilidades para lectura/parseo de Excel
+//! - `io`: helpers y utilidades para lectura/parseo de Excel; abre xlsx, xlsm, xlsb, xls y ods por contenido (`abrir_libro`)
 //! - `malla`: lectura de mallas curriculares
 //! - `porcentajes`: lectura de porcentajes/aprobados
 //! - `oferta`: lectura de oferta académica
 //! - `asignatura`: búsqueda de "Asignatura" por "Nombre Asignado"
 //! - `mapeo`: mapeo universal entre los 3 sistemas de códigos (Malla, OA2024, PA2025-1)
+//! - `deteccion`: reconoce si un workbook es malla, oferta o porcentajes
+//! - `validate`: reporte de validación (dry-run) de una malla
+//! - `avance`: importación del avance curricular (CSV) a `ramos_pasados`
+//! - `electivos`: catálogo de electivos por línea de formación
+//! - `componentes`: cátedra/laboratorio/ayudantía y paquetes de secciones
+//! - `sample`: datos de ejemplo embebidos cuando no hay directorio de datafiles
+//! - `layout`: columnas de malla, oferta y porcentajes por institución (`layouts.toml`)
 
 /// Helpers de IO y utilidades para parsing de Excel
 mod io;
@@ -30,16 +37,48 @@ mod porcentajes;
 /// Lectura de oferta académica: `leer_oferta_academica_excel`
 pub mod oferta;
 
+/// Directorio de profesores con su aprobación histórica: `ficha_profesor`, `buscar_profesores`
+pub mod profesores;
+
 /// Búsqueda de "Asignatura" a partir de "Nombre Asignado": `asignatura_from_nombre`
 mod asignatura;
 
+/// Detección del tipo de datafile al subir archivos: `validar_datafile`
+pub mod deteccion;
+
+/// Reporte estructurado de una malla (columnas, filas omitidas, requisitos rotos): `validar_malla`
+pub mod validate;
+
+/// Importación del avance curricular de un estudiante: `importar_avance`
+pub mod avance;
+
+/// Catálogo de electivos con su línea de formación: `leer_electivos`
+pub mod electivos;
+
+/// Componente de cada sección y paquetes cátedra + laboratorio: `asignar_bundles`
+pub mod componentes;
+
+/// Calendario de evaluaciones (certámenes) por curso: `leer_evaluaciones`
+pub mod evaluaciones;
+
+/// Malla, oferta y porcentajes de ejemplo embebidos (feature `sample-data`)
+pub mod sample;
+
+/// Encabezados y posiciones de columnas por institución: `LayoutProfile`, `perfil_para`
+pub mod layout;
+
+/// Directorio de datafiles activo, resuelto una vez y configurable en runtime: `DatafilesConfig`
+pub mod config;
+
 // Re-exports: helpers de IO son internos al crate; exponemos sólo las funciones de alto nivel
 // helpers internos — no exportarlos públicamente
 // funciones de alto nivel que sí usa `algorithm`
 pub use io::normalize_name;
+pub use io::{abrir_libro, diagnosticar_libro, recorrer_filas, DiagnosticoLibro, FormatoLibro, LibroIlegible, Proteccion};
 pub use malla::leer_malla_excel;
 pub use malla::leer_malla_excel_with_sheet;
 pub use malla::leer_prerequisitos;
+pub use malla::{aplicar_notas, leer_notas};
 pub use malla::leer_malla_con_porcentajes;
 pub use malla::normalize_codigo_nombre;
 pub use malla_optimizado::leer_malla_con_porcentajes_optimizado;
@@ -50,8 +89,8 @@ pub use porcentajes::enrich_porcent_names_from_malla;
 pub use oferta::leer_oferta_academica_excel;
 pub use oferta::resumen_oferta_academica;
 pub use asignatura::asignatura_from_nombre;
-pub use mapeo_builder::construir_mapeo_maestro;
-pub use mapeo::{MapeoMaestro, MapeoAsignatura};
+pub use mapeo_builder::{construir_mapeo_maestro, mapeo_maestro};
+pub use mapeo::{MapeoMaestro, MapeoAsignatura, FuzzyMatcher, MetricaSimilitud, Emparejamiento, ReporteMatching};
 
 use std::path::{Path, PathBuf};
 use std::fs;
@@ -61,77 +100,9 @@ use std::error::Error;
 /// Intenta primero la ruta desde quickshift, luego desde la raíz del proyecto
 pub const DATAFILES_DIR: &str = "src/datafiles";
 
-/// Función para resolver el directorio de datafiles correctamente
+/// Directorio de datafiles activo (ver `config::DatafilesConfig`)
 pub fn get_datafiles_dir() -> PathBuf {
-    use std::path::Path;
-    
-    // Opción 1: Usar variable de entorno si existe
-    if let Ok(path) = std::env::var("GA_DATAFILES_DIR") {
-        let p = PathBuf::from(path);
-        if p.exists() {
-            eprintln!("✅ Usando GA_DATAFILES_DIR: {:?}", p);
-            return p;
-        }
-    }
-
-    // Opción 2: Buscar desde el directorio de trabajo actual (CWD)
-    let cwd = match std::env::current_dir() {
-        Ok(c) => c,
-        Err(_) => PathBuf::from("."),
-    };
-    
-    eprintln!("🔍 Buscando datafiles desde CWD: {:?}", cwd);
-    
-    let candidates_from_cwd = vec![
-        cwd.join("quickshift/src/datafiles"),
-        cwd.join("src/datafiles"),
-        cwd.join("datafiles"),
-    ];
-
-    for candidate in candidates_from_cwd {
-        if candidate.exists() {
-            eprintln!("✅ Datafiles encontrados en (CWD): {:?}", candidate);
-            return candidate;
-        }
-    }
-
-    // Opción 3: Buscar relativo al ejecutable (para casos donde se ejecuta con ruta absoluta)
-    if let Ok(exe_path) = std::env::current_exe() {
-        eprintln!("🔍 Buscando relativo al ejecutable: {:?}", exe_path);
-        if let Some(exe_dir) = exe_path.parent() {
-            let candidates_from_exe = vec![
-                exe_dir.join("../../../quickshift/src/datafiles"),
-                exe_dir.join("../../quickshift/src/datafiles"),
-                exe_dir.join("../quickshift/src/datafiles"),
-                exe_dir.join("quickshift/src/datafiles"),
-            ];
-            
-            for candidate in candidates_from_exe {
-                if let Ok(canonical) = candidate.canonicalize() {
-                    if canonical.exists() {
-                        eprintln!("✅ Datafiles encontrados en (exe): {:?}", canonical);
-                        return canonical;
-                    }
-                }
-            }
-        }
-    }
-
-    // Fallback: devolver ruta absoluta buscando en el sistema de archivos
-    let home = match std::env::var("HOME") {
-        Ok(h) => PathBuf::from(h),
-        Err(_) => PathBuf::from("/home/ignatus"),
-    };
-    
-    let hardcoded = home.join("GitHub/GA_Backend/quickshift/src/datafiles");
-    if hardcoded.exists() {
-        eprintln!("✅ Datafiles encontrados (hardcoded): {:?}", hardcoded);
-        return hardcoded;
-    }
-    
-    eprintln!("⚠️ No se encontró directorio datafiles en ninguna ubicación");
-    eprintln!("   Último intento: {:?}", hardcoded);
-    hardcoded
+    config::actual().dir.clone()
 }
 
 use crate::models::RamoDisponible;
@@ -141,8 +112,8 @@ use std::collections::HashMap;
 /// por defecto. Mantiene la misma firma usada anteriormente en `algorithm`.
 /// Devuelve (mapa, nombre_malla, leido_flag).
 pub fn get_ramo_critico(nombre: &str) -> (HashMap<String, RamoDisponible>, String, bool) {
-    match leer_malla_excel(nombre) {
-        Ok(map) => (map, nombre.to_string(), true),
+    match crate::datafiles::cache::malla(nombre, None) {
+        Ok(map) => (map.as_ref().clone(), nombre.to_string(), true),
         Err(_) => (HashMap::new(), nombre.to_string(), false),
     }
 }
@@ -183,6 +154,10 @@ fn latest_file_matching(dir: &Path, keywords: &[&str]) -> Option<PathBuf> {
         let mut priority_files: Vec<_> = files_matching.iter()
             .filter(|(_, _, name)| !name.to_uppercase().contains("_TEST"))
             .collect();
+
+        // Los archivos de verano (OA2025V) van al final: la planificación
+        // regular no debe tomar la oferta reducida del verano
+        priority_files.sort_by_key(|(_, _, name)| es_archivo_verano(name));
         
         // Ordenar por año/semestre extraído del nombre (e.g., OA20251 = 2025-1)
         priority_files.sort_by(|a, b| {
@@ -228,6 +203,38 @@ fn latest_file_matching(dir: &Path, keywords: &[&str]) -> Option<PathBuf> {
     None
 }
 
+/// Archivo de verano: el año del nombre termina en `V` (`OA2025V.xlsx`,
+/// `CFG2025V.xlsx`, también `OA2025_V.xlsx`)
+pub fn es_archivo_verano(nombre: &str) -> bool {
+    let stem = Path::new(nombre).file_stem().and_then(|s| s.to_str()).unwrap_or(nombre).to_uppercase();
+    let Some(sin_v) = stem.strip_suffix('V') else { return false };
+    let sin_v = sin_v.trim_end_matches(['_', '-']);
+    sin_v.ends_with(|c: char| c.is_ascii_digit())
+}
+
+/// Archivo de verano más reciente que coincida con las keywords: mayor año
+/// en el nombre y, a igual año, el modificado más tarde
+pub fn latest_verano_for_keywords(keywords: &[&str]) -> Option<PathBuf> {
+    let read = fs::read_dir(get_datafiles_dir()).ok()?;
+    let anio = |nombre: &str| -> u32 {
+        let digitos: String = nombre.chars().filter(|c| c.is_ascii_digit()).collect();
+        digitos.get(..4).and_then(|d| d.parse().ok()).unwrap_or(0)
+    };
+    read.flatten()
+        .filter_map(|entry| {
+            let p = entry.path();
+            let nombre = p.file_name()?.to_str()?.to_string();
+            let bajo = nombre.to_lowercase();
+            let valido = p.is_file() && !nombre.starts_with(['.', '~']) && !bajo.contains("_test")
+                && es_archivo_verano(&nombre) && keywords.iter().any(|kw| bajo.contains(&kw.to_lowercase()));
+            if !valido { return None; }
+            let modificado = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some(((anio(&nombre), modificado), p))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, p)| p)
+}
+
 /// Exponer un helper público que devuelve el fichero más reciente que coincida con
 /// una lista de keywords dentro del directorio `datafiles`.
 pub fn latest_file_for_keywords(keywords: &[&str]) -> Option<PathBuf> {
@@ -384,8 +391,8 @@ pub fn list_available_datafiles() -> Result<(Vec<String>, Vec<String>, Vec<Strin
 /// Devuelve los nombres de las hojas en el orden que reporta la librería.
 pub fn listar_hojas_malla<P: AsRef<Path>>(path: P) -> Result<Vec<String>, Box<dyn Error>> {
     // Usar calamine para abrir el workbook de forma genérica (xlsx/xls/xlsb)
-    use calamine::{open_workbook_auto, Reader};
-    let workbook = open_workbook_auto(path)?;
+    use calamine::Reader;
+    let workbook = abrir_libro(path)?;
     let names = workbook.sheet_names().to_owned();
     Ok(names)
 }
@@ -397,66 +404,66 @@ pub fn listar_hojas_malla<P: AsRef<Path>>(path: P) -> Result<Vec<String>, Box<dy
 /// ============================================================================
 /// 
 /// Intenta emparejar un nombre de ramo (de la malla) con nombres de la oferta
-/// académica usando normalización de acentos y espacios.
+/// académica: primero por nombre normalizado (acentos y espacios), luego por
+/// similitud con `FuzzyMatcher::default()`. Los matches ambiguos no se usan.
 ///
 /// Ejemplo:
 /// - Nombre malla: "Mecánica"
 /// - Nombre oferta: "MECÁNICA"
 /// - normalize_name("Mecánica") == normalize_name("MECÁNICA") → MATCH
+/// - "Cálculo I" vs "CALCULO 1" → MATCH aproximado (numeral romano = dígito)
 pub fn find_best_name_match(
     malla_name: &str,
     oferta_names: &[String],
 ) -> Option<String> {
-    let malla_norm = normalize_name(malla_name);
-    
-    for oferta_name in oferta_names {
-        let oferta_norm = normalize_name(oferta_name);
-        if malla_norm == oferta_norm {
-            return Some(oferta_name.clone());
-        }
-    }
-    
-    None
+    FuzzyMatcher::default().emparejar(malla_name, oferta_names).elegido().map(str::to_string)
 }
 
 /// Enriquece el mapa de `ramos_disponibles` con información de oferta y porcentajes
-/// usando matching por nombre normalizado.
+/// usando matching por nombre con `matcher`.
 ///
 /// Flujo:
-/// 1. Para cada ramo en `ramos_disponibles`, normaliza su nombre
-/// 2. Busca coincidencias en `oferta_secciones` por nombre normalizado
-/// 3. Busca coincidencias en `porcentajes_por_nombre` por nombre normalizado
+/// 1. Para cada ramo en `ramos_disponibles`, empareja su nombre
+/// 2. Busca coincidencias en `oferta_secciones` por nombre
+/// 3. Busca coincidencias en `porcentajes_por_nombre` (claves normalizadas)
 /// 4. Actualiza `dificultad` si encuentra datos de porcentajes
+///
+/// Devuelve los matches aproximados y ambiguos para revisarlos.
 pub fn enrich_ramos_with_oferta_and_porcent(
     ramos_disponibles: &mut HashMap<String, RamoDisponible>,
     oferta_secciones: &[crate::models::Seccion],
     porcentajes_por_nombre: &HashMap<String, (String, f64, f64)>,
-) {
-    // Construir índice de oferta por nombre normalizado
-    let mut oferta_por_nombre_norm: HashMap<String, Vec<&crate::models::Seccion>> = HashMap::new();
-    for seccion in oferta_secciones.iter() {
-        let nombre_norm = normalize_name(&seccion.nombre);
-        oferta_por_nombre_norm.entry(nombre_norm).or_default().push(seccion);
-    }
+    matcher: &FuzzyMatcher,
+) -> ReporteMatching {
+    let mut reporte = ReporteMatching::default();
+
+    // Nombres normalizados de oferta y porcentajes (ordenados: los empates se resuelven igual siempre)
+    let mut oferta_nombres: Vec<String> = oferta_secciones.iter().map(|s| normalize_name(&s.nombre)).collect();
+    oferta_nombres.sort();
+    oferta_nombres.dedup();
+    let mut porcent_nombres: Vec<String> = porcentajes_por_nombre.keys().cloned().collect();
+    porcent_nombres.sort();
 
     // Enriquecer cada ramo
     for ramo in ramos_disponibles.values_mut() {
-        let ramo_nombre_norm = normalize_name(&ramo.nombre);
-
-        // Buscar en porcentajes por nombre normalizado
-        if let Some((_codigo_origen, porc, _total)) = porcentajes_por_nombre.get(&ramo_nombre_norm) {
-            ramo.dificultad = Some(*porc);
-            eprintln!("DEBUG: Ramo '{}' → porcentaje encontrado: {}", ramo.nombre, porc);
-        } else {
-            eprintln!("DEBUG: Ramo '{}' → NO encontrado en porcentajes (norm: '{}')", ramo.nombre, ramo_nombre_norm);
+        let en_porcentajes = matcher.emparejar(&ramo.nombre, &porcent_nombres);
+        match reporte.registrar(&ramo.nombre, &en_porcentajes).and_then(|k| porcentajes_por_nombre.get(k)) {
+            Some((_codigo_origen, porc, _total)) => {
+                ramo.dificultad = Some(*porc);
+                tracing::debug!("Ramo '{}' → porcentaje encontrado: {}", ramo.nombre, porc);
+            }
+            None => tracing::debug!("Ramo '{}' → NO encontrado en porcentajes (norm: '{}')", ramo.nombre, normalize_name(&ramo.nombre)),
         }
 
         // Nota: Las secciones de oferta no se usan aquí directamente para enriquecer,
         // pero se registra si hay coincidencia en oferta
-        if oferta_por_nombre_norm.contains_key(&ramo_nombre_norm) {
-            eprintln!("DEBUG: Ramo '{}' encontrado en oferta académica", ramo.nombre);
+        let en_oferta = matcher.emparejar(&ramo.nombre, &oferta_nombres);
+        if reporte.registrar(&ramo.nombre, &en_oferta).is_some() {
+            tracing::debug!("Ramo '{}' encontrado en oferta académica", ramo.nombre);
         }
     }
+
+    reporte
 }
 
 
@@ -490,9 +497,12 @@ pub fn build_normalized_index(names: &[String]) -> HashMap<String, String> {
 ///             numb_correlativo: 0,
 ///             critico: false,
 ///             requisitos_ids: vec![],
+///             correquisitos_ids: vec![],
 ///             dificultad: None,
 ///             electivo: false,
 ///             semestre: None,
+///             creditos: None,
+///             tags: vec![],
 ///         },
 ///     );
 /// let oferta = vec!["Mecánica".to_string()];
@@ -526,17 +536,16 @@ pub fn enrich_ramo_with_congruencias(
     }
 }
 
-/// Carga las equivalencias entre códigos de cursos desde la hoja "Equivalencias" de una malla Excel.
-/// 
-/// Retorna un HashMap donde la clave es el código "antiguo" (ej: CIG1014)
-/// y el valor es el código "nuevo" en la malla actual (ej: CIG1003).
-pub fn cargar_equivalencias(ruta_malla: &str) -> Result<std::collections::HashMap<String, String>, Box<dyn Error>> {
-    use calamine::{open_workbook_auto, Reader, Data};
-    use std::collections::HashMap;
-    
-    let mut workbook = open_workbook_auto(ruta_malla)?;
-    let mut equivalencias = HashMap::new();
-    
+/// Lee todos los pares (código antiguo, código nuevo) de la hoja "Equivalencias"
+/// de una malla Excel, en mayúsculas y en el orden de la hoja. A diferencia de
+/// `cargar_equivalencias` conserva los códigos antiguos con más de un destino
+/// (ver `algorithm::equivalencias`). Sin hoja devuelve una lista vacía.
+pub fn leer_tabla_equivalencias(ruta_malla: &str) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    use calamine::{Reader, Data};
+
+    let mut workbook = abrir_libro(ruta_malla)?;
+    let mut pares = Vec::new();
+
     // Intentar cargar la hoja "Equivalencias"
     match workbook.worksheet_range("Equivalencias") {
         Ok(range) => {
@@ -552,23 +561,32 @@ pub fn cargar_equivalencias(ruta_malla: &str) -> Result<std::collections::HashMa
                         Data::Int(i) => Some(i.to_string()),
                         _ => None,
                     };
-                    
+
                     if let (Some(codigo_antiguo), Some(codigo_nuevo)) = (col0, col1) {
-                        equivalencias.insert(
+                        pares.push((
                             codigo_antiguo.to_uppercase().trim().to_string(),
                             codigo_nuevo.to_uppercase().trim().to_string(),
-                        );
+                        ));
                     }
                 }
             }
-            eprintln!("✅ {} equivalencias cargadas desde hoja 'Equivalencias'", equivalencias.len());
+            tracing::info!("✅ {} equivalencias cargadas desde hoja 'Equivalencias'", pares.len());
         }
         Err(_) => {
-            eprintln!("⚠️  No se encontró hoja 'Equivalencias' en {}", ruta_malla);
+            tracing::warn!("⚠️  No se encontró hoja 'Equivalencias' en {}", ruta_malla);
         }
     }
-    
-    Ok(equivalencias)
+
+    Ok(pares)
+}
+
+/// Carga las equivalencias entre códigos de cursos desde la hoja "Equivalencias" de una malla Excel.
+/// 
+/// Retorna un HashMap donde la clave es el código "antiguo" (ej: CIG1014)
+/// y el valor es el código "nuevo" en la malla actual (ej: CIG1003).
+/// Si un código antiguo aparece varias veces queda el último.
+pub fn cargar_equivalencias(ruta_malla: &str) -> Result<std::collections::HashMap<String, String>, Box<dyn Error>> {
+    Ok(leer_tabla_equivalencias(ruta_malla)?.into_iter().collect())
 }
 
 /// Mapea códigos de cursos aprobados a sus equivalentes en la malla actual.
@@ -1,6 +1,8 @@
-use calamine::{open_workbook_auto, Data, Reader};
-use crate::models::Seccion;
-use crate::excel::io::{data_to_string, read_sheet_via_zip};
+use calamine::Reader;
+use crate::models::{Componente, Seccion};
+use crate::excel::componentes;
+use crate::excel::io::{abrir_libro, read_sheet_via_zip, recorrer_filas};
+use crate::excel::layout::{alguno_calza, LayoutOferta};
 use zip;
 use std::collections::{HashMap, HashSet};
 
@@ -23,7 +25,219 @@ fn base_course_code(code: &str) -> String {
     trimmed
 }
 
-/// Lee la oferta académica y devuelve una lista de `Seccion`.
+/// Cupo numérico de una celda ("30", "30.0"); None si está vacía o no es un número
+fn parse_cupo(s: &str) -> Option<u32> {
+    s.trim().parse::<f64>().ok().filter(|v| *v >= 0.0).map(|v| v.round() as u32)
+}
+
+/// (cupos_totales, cupos_disponibles) de una fila. Sin columna de
+/// disponibles se calculan como totales - inscritos.
+fn cupos_de_fila(totales: &str, disponibles: &str, inscritos: &str) -> (Option<u32>, Option<u32>) {
+    let totales = parse_cupo(totales);
+    let disponibles = parse_cupo(disponibles)
+        .or_else(|| totales.zip(parse_cupo(inscritos)).map(|(t, i)| t.saturating_sub(i)));
+    (totales, disponibles)
+}
+
+/// Fila cruda de la oferta, antes de agrupar por sección
+struct RawRow { codigo: String, nombre: String, seccion: String, horario: Vec<String>, profesor: String, codigo_box: String, sala: String, campus: String, componente: Componente, cupos_totales: Option<u32>, cupos_disponibles: Option<u32> }
+
+/// Índices de las columnas de la oferta en una hoja
+#[derive(Debug, Default)]
+struct ColumnasOferta { codigo: Option<usize>, nombre: Option<usize>, seccion: Option<usize>, horario: Option<usize>, profesor: Option<usize>, codigo_box: Option<usize>, sala: Option<usize>, campus: Option<usize>, tipo: Option<usize>, cupos: Option<usize>, disponibles: Option<usize>, inscritos: Option<usize> }
+
+impl ColumnasOferta {
+    /// Columnas de una hoja sin encabezado: las posiciones del layout
+    fn por_posicion(layout: &LayoutOferta) -> Self {
+        ColumnasOferta {
+            codigo: layout.codigo.posicion,
+            nombre: layout.nombre.posicion,
+            seccion: layout.seccion.posicion,
+            horario: layout.horario.posicion,
+            profesor: layout.profesor.posicion,
+            codigo_box: layout.codigo_box.posicion,
+            sala: layout.sala.posicion,
+            campus: layout.campus.posicion,
+            tipo: layout.tipo.posicion,
+            cupos: layout.cupos.posicion,
+            disponibles: layout.cupos_disponibles.posicion,
+            inscritos: layout.inscritos.posicion,
+        }
+    }
+}
+
+/// Busca la fila de encabezado entre las primeras 8 y las columnas según el
+/// layout. Si la columna de código no trae dígitos en las 6 filas siguientes
+/// se usa la primera que sí los trae.
+fn detectar_columnas(filas: &[Vec<String>], layout: &LayoutOferta) -> Option<(usize, ColumnasOferta)> {
+    let ridx = filas.iter().take(8).position(|fila| {
+        layout.fila_encabezado.iter().all(|patrones| fila.iter().any(|c| alguno_calza(patrones, c)))
+    })?;
+    let encabezado = &filas[ridx];
+    let disponibles = layout.cupos_disponibles.primera(encabezado);
+    let mut sin_disponibles = encabezado.clone();
+    if let Some(d) = disponibles {
+        sin_disponibles[d].clear();
+    }
+    let mut columnas = ColumnasOferta {
+        codigo: layout.codigo.primera(encabezado),
+        nombre: layout.nombre.primera(encabezado),
+        seccion: layout.seccion.primera(encabezado),
+        horario: layout.horario.primera(encabezado),
+        profesor: layout.profesor.primera(encabezado),
+        codigo_box: layout.codigo_box.primera(encabezado),
+        sala: layout.sala.primera(encabezado),
+        campus: layout.campus.primera(encabezado),
+        tipo: layout.tipo.primera(encabezado),
+        cupos: layout.cupos.primera(&sin_disponibles),
+        disponibles,
+        inscritos: layout.inscritos.primera(encabezado),
+    };
+
+    let siguientes = || filas.iter().skip(ridx + 1).take(6);
+    let con_digitos = |ci: usize| siguientes().any(|f| f.get(ci).is_some_and(|s| s.chars().any(|ch| ch.is_ascii_digit())));
+    if columnas.codigo.is_some_and(|ci| !con_digitos(ci)) {
+        columnas.codigo = (0..encabezado.len()).find(|&ci| con_digitos(ci));
+    }
+    Some((ridx, columnas))
+}
+
+/// Filas que se miran para ubicar el encabezado: las 8 candidatas más las 6
+/// siguientes que usa `detectar_columnas`
+const FILAS_ENCABEZADO: usize = 8 + 6;
+
+/// Convierte una fila de datos en `RawRow`; None si no trae código
+fn fila_cruda(row: &[String], columnas: &ColumnasOferta) -> Option<RawRow> {
+    let celda = |idx: Option<usize>| idx.and_then(|i| row.get(i)).map(|c| c.trim().to_string());
+    let codigo = celda(columnas.codigo).unwrap_or_default();
+    if codigo.is_empty() { return None; }
+    let nombre = celda(columnas.nombre).unwrap_or_default();
+    let seccion = celda(columnas.seccion).unwrap_or_else(|| "1".to_string());
+    let horario_str = celda(columnas.horario).unwrap_or_default();
+    let profesor = celda(columnas.profesor).unwrap_or_else(|| "Sin asignar".to_string());
+    let codigo_box = celda(columnas.codigo_box).unwrap_or_else(|| codigo.clone());
+    let sala = celda(columnas.sala).unwrap_or_default();
+    let campus = celda(columnas.campus).unwrap_or_default();
+    let tipo = celda(columnas.tipo).unwrap_or_default();
+    let (cupos_totales, cupos_disponibles) = cupos_de_fila(
+        &celda(columnas.cupos).unwrap_or_default(),
+        &celda(columnas.disponibles).unwrap_or_default(),
+        &celda(columnas.inscritos).unwrap_or_default(),
+    );
+    let componente = componentes::detectar(&codigo, &nombre, &tipo);
+    let horario: Vec<String> = if horario_str.is_empty() { vec!["Sin horario".to_string()] } else { horario_str.split([',', ';']).map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect() };
+    Some(RawRow { codigo, nombre, seccion, horario, profesor, codigo_box, sala, campus, componente, cupos_totales, cupos_disponibles })
+}
+
+/// (código base, sección, codigo_box, componente)
+type ClaveSeccion = (String, String, String, Componente);
+
+/// Secciones agrupadas por (código base, sección, codigo_box, componente) a
+/// medida que llegan las filas: cada fila se funde en su sección y se
+/// descarta, así que en memoria queda una `Seccion` por grupo y no la hoja.
+#[derive(Default)]
+struct Agrupador {
+    /// Sección de cada grupo y sus salas, sin repetir
+    grupos: HashMap<ClaveSeccion, (Seccion, Vec<String>)>,
+}
+
+impl Agrupador {
+    fn agregar(&mut self, r: RawRow) {
+        let key = (base_course_code(&r.codigo), r.seccion.clone(), r.codigo_box.clone(), r.componente);
+        let (s, salas) = self.grupos.entry(key).or_insert_with_key(|(codigo, secc, codigo_box, componente)| (Seccion {
+            codigo: codigo.clone(), nombre: String::new(), seccion: secc.clone(), horario: Vec::new(), profesor: String::new(),
+            codigo_box: codigo_box.clone(), is_cfg: false, is_electivo: false, linea: None, sala: None, campus: None,
+            componente: *componente, bundle_id: None, cupos_totales: None, cupos_disponibles: None, evaluaciones: Vec::new(),
+        }, Vec::new()));
+        if s.nombre.is_empty() { s.nombre = r.nombre; }
+        if !r.sala.is_empty() && !salas.contains(&r.sala) { salas.push(r.sala); }
+        if s.campus.is_none() && !r.campus.is_empty() { s.campus = Some(r.campus); }
+        s.cupos_totales = s.cupos_totales.or(r.cupos_totales);
+        s.cupos_disponibles = s.cupos_disponibles.or(r.cupos_disponibles);
+        if s.profesor.is_empty() && !r.profesor.trim().is_empty() { s.profesor = r.profesor; }
+        // unir horarios y deduplicar
+        for h in r.horario.into_iter() {
+            if !s.horario.iter().any(|x| x == &h) {
+                s.horario.push(h);
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.grupos.is_empty()
+    }
+
+    fn secciones(self) -> Vec<Seccion> {
+        let mut result: Vec<Seccion> = self.grupos.into_values().map(|(mut s, salas)| {
+            if s.horario.is_empty() { s.horario.push("Sin horario".to_string()); }
+            if !salas.is_empty() { s.sala = Some(salas.join(" / ")); }
+            s
+        }).collect();
+        componentes::asignar_bundles(&mut result);
+        result
+    }
+}
+
+/// Recibe las filas de una hoja de a una. Las primeras `FILAS_ENCABEZADO` se
+/// guardan hasta ubicar el encabezado (sin encabezado se leen por posición);
+/// desde ahí cada fila pasa directo al `Agrupador`.
+struct LectorHoja<'a> {
+    layout: &'a LayoutOferta,
+    agrupador: Agrupador,
+    primeras: Vec<Vec<String>>,
+    columnas: Option<ColumnasOferta>,
+}
+
+impl<'a> LectorHoja<'a> {
+    fn new(layout: &'a LayoutOferta) -> Self {
+        LectorHoja { layout, agrupador: Agrupador::default(), primeras: Vec::new(), columnas: None }
+    }
+
+    fn fila(&mut self, row: Vec<String>) {
+        match &self.columnas {
+            Some(columnas) => {
+                if let Some(r) = fila_cruda(&row, columnas) {
+                    self.agrupador.agregar(r);
+                }
+            }
+            None => {
+                self.primeras.push(row);
+                if self.primeras.len() == FILAS_ENCABEZADO {
+                    self.ubicar_encabezado();
+                }
+            }
+        }
+    }
+
+    fn ubicar_encabezado(&mut self) {
+        let primeras = std::mem::take(&mut self.primeras);
+        let (header_row_idx, columnas) = match detectar_columnas(&primeras, self.layout) {
+            Some((h, c)) => (Some(h), c),
+            None => (None, ColumnasOferta::por_posicion(self.layout)),
+        };
+        for (row_idx, row) in primeras.iter().enumerate() {
+            if Some(row_idx) == header_row_idx { continue; }
+            if let Some(r) = fila_cruda(row, &columnas) {
+                self.agrupador.agregar(r);
+            }
+        }
+        self.columnas = Some(columnas);
+    }
+
+    /// Secciones de la hoja (vacío si no trajo filas con código)
+    fn terminar(mut self) -> Agrupador {
+        if self.columnas.is_none() {
+            self.ubicar_encabezado();
+        }
+        self.agrupador
+    }
+}
+
+/// Lee la oferta académica y devuelve una lista de `Seccion`. Las columnas
+/// se buscan según el formato del archivo (`layout::perfil_para`). Las hojas
+/// se recorren fila a fila (`io::recorrer_filas`) y las secciones se arman
+/// al vuelo, sin cargar la hoja entera: una oferta de decenas de miles de
+/// filas ocupa lo que ocupan sus secciones.
 pub fn leer_oferta_academica_excel(nombre_archivo: &str) -> Result<Vec<Seccion>, Box<dyn std::error::Error>> {
     // Resolver ruta hacia el directorio protegido `DATAFILES_DIR` si es necesario
     let resolved = if std::path::Path::new(nombre_archivo).exists() {
@@ -39,144 +253,25 @@ pub fn leer_oferta_academica_excel(nombre_archivo: &str) -> Result<Vec<Seccion>,
         }
     };
 
-    // Recolectaremos filas crudas y luego las agruparemos por (codigo, seccion, codigo_box)
-    struct RawRow { codigo: String, nombre: String, seccion: String, horario: Vec<String>, profesor: String, codigo_box: String }
-    let mut raw_rows: Vec<RawRow> = Vec::new();
-
-    // Intentar primero con calamine (más rápido si funciona)
-    if let Ok(mut workbook) = open_workbook_auto(&resolved) {
-        let sheet_names = workbook.sheet_names().to_owned();
-        
-        for sheet in sheet_names.iter() {
-            if let Ok(range) = workbook.worksheet_range(sheet) {
-                // Primero buscamos una fila de encabezado (header) y determinamos índices
-                let mut header_row_idx: Option<usize> = None;
-                let mut code_idx: Option<usize> = None;
-                let mut name_idx: Option<usize> = None;
-                let mut seccion_idx: Option<usize> = None;
-                let mut horario_idx: Option<usize> = None;
-                let mut profesor_idx: Option<usize> = None;
-                let mut codigo_box_idx: Option<usize> = None;
-
-                for (ridx, row) in range.rows().enumerate().take(8) {
-                    let row_texts: Vec<String> = row.iter().map(|c| data_to_string(c).to_lowercase()).collect();
-                    let has_codigo = row_texts.iter().any(|s| s.contains("codigo") || s.contains("código") || s.contains("cod") || s.contains("asignatura") || s.contains("asig"));
-                    let has_nombre = row_texts.iter().any(|s| s.contains("nombre") || s.contains("asignatura") || s.contains("descripcion"));
-                    let has_seccion = row_texts.iter().any(|s| s.contains("sección") || s.contains("seccion"));
-                    if (has_codigo && has_nombre) || (has_seccion && has_nombre) {
-                        header_row_idx = Some(ridx);
-                        for (ci, cell) in row.iter().enumerate() {
-                            let txt = data_to_string(cell).to_lowercase();
-                            let ttrim = txt.trim();
-                            if code_idx.is_none() && (ttrim == "codigo" || ttrim == "código" || ttrim == "asignatura" || ttrim == "asig") { code_idx = Some(ci); }
-                            // Priority: "nombre asig" > "nombre" > "asignatura" > "descripcion" for name column
-                            if name_idx.is_none() && (txt.contains("nombre asig") || ttrim.contains("nombre asig.")) { name_idx = Some(ci); }
-                            if name_idx.is_none() && (txt.contains("nombre") || txt.contains("descripcion")) { name_idx = Some(ci); }
-                            if seccion_idx.is_none() && (ttrim == "sección" || ttrim == "seccion") { seccion_idx = Some(ci); }
-                            if horario_idx.is_none() && (txt.contains("horario") || txt.contains("hora") || txt.contains("hor.")) { horario_idx = Some(ci); }
-                            if profesor_idx.is_none() && txt.contains("profesor") { profesor_idx = Some(ci); }
-                            if codigo_box_idx.is_none() && (txt.contains("codigo_box") || txt.contains("id_box") || txt.contains("id_paquete")) { codigo_box_idx = Some(ci); }
-                        }
-                        if code_idx.is_none() {
-                            for (ci, cell) in row.iter().enumerate() {
-                                let txt = data_to_string(cell).to_lowercase();
-                                if txt.contains("codigo")|| txt.contains("código") || txt.contains("cod") || txt.contains("seccion") || txt.contains("sección") { code_idx = Some(ci); break; }
-                            }
-                        }
-                        // Validate that the candidate code column contains code-like tokens in the rows below
-                        if let Some(ci) = code_idx {
-                            let mut has_digits = false;
-                            for validate_row in range.rows().skip(ridx+1).take(6) {
-                                if let Some(cell) = validate_row.get(ci) {
-                                    let s = data_to_string(cell);
-                                    if s.chars().any(|ch| ch.is_ascii_digit()) {
-                                        has_digits = true; break;
-                                    }
-                                }
-                            }
-                            if !has_digits {
-                                // Try to find another column with digits in rows below
-                                code_idx = None;
-                                for (ci2, _) in row.iter().enumerate() {
-                                    let mut found = false;
-                                    for validate_row in range.rows().skip(ridx+1).take(6) {
-                                        if let Some(cell) = validate_row.get(ci2) {
-                                            let s = data_to_string(cell);
-                                            if s.chars().any(|ch| ch.is_ascii_digit()) {
-                                                found = true; break;
-                                            }
-                                        }
-                                    }
-                                    if found { code_idx = Some(ci2); break; }
-                                }
-                            }
-                        }
-                        break;
-                    }
-                }
+    let layout = crate::excel::layout::perfil_para(&resolved).oferta;
 
-                for (row_idx, row) in range.rows().enumerate() {
-                    if row.is_empty() { continue; }
-                    if let Some(h) = header_row_idx {
-                        if row_idx == h { continue; }
-                        let codigo = code_idx.and_then(|i| row.get(i)).map(|c| data_to_string(c).trim().to_string()).unwrap_or_default();
-                        let base_codigo = base_course_code(&codigo);
-                        if codigo.is_empty() { continue; }
-                        let nombre = name_idx.and_then(|i| row.get(i)).map(|c| data_to_string(c).trim().to_string()).unwrap_or_default();
-                        let seccion = seccion_idx.and_then(|i| row.get(i)).map(|c| data_to_string(c).trim().to_string()).unwrap_or_else(|| "1".to_string());
-                        let horario_str = horario_idx.and_then(|i| row.get(i)).map(|c| data_to_string(c).trim().to_string()).unwrap_or_default();
-                        let profesor = profesor_idx.and_then(|i| row.get(i)).map(|c| data_to_string(c).trim().to_string()).unwrap_or_else(|| "Sin asignar".to_string());
-                        let codigo_box = codigo_box_idx.and_then(|i| row.get(i)).map(|c| data_to_string(c).trim().to_string()).unwrap_or_else(|| codigo.clone());
-                        let horario: Vec<String> = if horario_str.is_empty() { vec!["Sin horario".to_string()] } else { horario_str.split(|c| c == ',' || c == ';').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect() };
-                        raw_rows.push(RawRow { codigo: codigo.clone(), nombre: nombre.clone(), seccion: seccion.clone(), horario, profesor, codigo_box: codigo_box.clone() });
-                    } else {
-                        // fallback: same as before
-                        let codigo = data_to_string(row.get(1).unwrap_or(&Data::Empty)).trim().to_string();
-                        let base_codigo = base_course_code(&codigo);
-                        if codigo.is_empty() { continue; }
-                        let nombre = data_to_string(row.get(2).unwrap_or(&Data::Empty)).trim().to_string();
-                        let seccion = data_to_string(row.get(3).unwrap_or(&Data::Empty)).trim().to_string();
-                        let horario_str = data_to_string(row.get(7).unwrap_or(&Data::Empty)).trim().to_string();
-                        let profesor = data_to_string(row.get(9).unwrap_or(&Data::Empty)).trim().to_string();
-                        let codigo_box = data_to_string(row.get(18).unwrap_or(&Data::Empty)).trim().to_string();
-                        let codigo_box = if codigo_box.is_empty() { codigo.clone() } else { codigo_box };
-                        let horario: Vec<String> = if horario_str.is_empty() { vec!["Sin horario".to_string()] } else { horario_str.split(|c| c == ',' || c == ';').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect() };
-                        raw_rows.push(RawRow { codigo: codigo.clone(), nombre: nombre.clone(), seccion: seccion.clone(), horario, profesor, codigo_box: codigo_box.clone() });
-                    }
-                }
-                // Agrupar y construir secciones si recolectamos filas
-                if !raw_rows.is_empty() {
-                    let mut map: HashMap<(String,String,String), Vec<RawRow>> = HashMap::new();
-                    for r in raw_rows.into_iter() {
-                        let key = (base_course_code(&r.codigo), r.seccion.clone(), r.codigo_box.clone());
-                        map.entry(key).or_insert_with(Vec::new).push(r);
-                    }
-                    let mut result: Vec<Seccion> = Vec::new();
-                    for ((codigo, _secc, codigo_box), rows) in map.into_iter() {
-                        // unir horarios y deduplicar
-                        let mut horarios_acc: Vec<String> = Vec::new();
-                        let mut profesor_pref = String::new();
-                        let mut nombre_pref = String::new();
-                        for r in rows.into_iter() {
-                            if nombre_pref.is_empty() { nombre_pref = r.nombre.clone(); }
-                            if profesor_pref.is_empty() && !r.profesor.trim().is_empty() { profesor_pref = r.profesor.clone(); }
-                            for h in r.horario.into_iter() {
-                                if !horarios_acc.iter().any(|x| x == &h) {
-                                    horarios_acc.push(h);
-                                }
-                            }
-                        }
-                        if horarios_acc.is_empty() { horarios_acc.push("Sin horario".to_string()); }
-                        result.push(Seccion { codigo: codigo.clone(), nombre: nombre_pref.clone(), seccion: _secc.clone(), horario: horarios_acc, profesor: profesor_pref.clone(), codigo_box: codigo_box.clone(), is_cfg: false, is_electivo: false });
-                    }
-                    return Ok(result);
-                }
+    // Intentar primero con calamine (más rápido si funciona). Si ni siquiera
+    // se puede abrir (contraseña, formato desconocido) el zip tampoco sirve.
+    let mut workbook = abrir_libro(&resolved)?;
+    let sheet_names = workbook.sheet_names().to_owned();
+    
+    for sheet in sheet_names.iter() {
+        let mut lector = LectorHoja::new(&layout);
+        if recorrer_filas(&mut workbook, sheet, |fila| lector.fila(fila)).is_ok() {
+            let agrupador = lector.terminar();
+            if !agrupador.is_empty() {
+                return Ok(agrupador.secciones());
             }
         }
     }
 
     // Fallback: usar zip reader como alternativa si calamine falló
-    eprintln!("DEBUG: calamine falló o no devolvió datos, intentando leer vía zip para '{}'", resolved);
+    tracing::debug!("calamine falló o no devolvió datos, intentando leer vía zip para '{}'", resolved);
     
     // Obtener lista de hojas desde el archivo zip
     if let Ok(archive) = zip::ZipArchive::new(std::fs::File::open(&resolved)?) {
@@ -186,115 +281,14 @@ pub fn leer_oferta_academica_excel(nombre_archivo: &str) -> Result<Vec<Seccion>,
             if !fname.starts_with("xl/worksheets/sheet") { continue; }
 
             if let Ok(rows_vec) = read_sheet_via_zip(&resolved, fname) {
-                let mut raw_rows_zip: Vec<RawRow> = Vec::new();
-                // detect header for zip data (first few rows)
-                let mut header_row_idx: Option<usize> = None;
-                let mut code_idx: Option<usize> = None;
-                let mut name_idx: Option<usize> = None;
-                let mut seccion_idx: Option<usize> = None;
-                let mut horario_idx: Option<usize> = None;
-                let mut profesor_idx: Option<usize> = None;
-                let mut codigo_box_idx: Option<usize> = None;
-                for (ridx, row) in rows_vec.iter().enumerate().take(8) {
-                    let texts: Vec<String> = row.iter().map(|c| c.to_lowercase()).collect();
-                    let has_codigo = texts.iter().any(|s| s.contains("codigo") || s.contains("código") || s.contains("cod"));
-                    let has_nombre = texts.iter().any(|s| s.contains("nombre") || s.contains("asignatura") || s.contains("descripcion"));
-                    let has_seccion = texts.iter().any(|s| s.contains("sección") || s.contains("seccion"));
-                    if (has_codigo && has_nombre) || (has_seccion && has_nombre) {
-                        header_row_idx = Some(ridx);
-                        for (ci, cell) in row.iter().enumerate() {
-                            let txt = cell.to_lowercase();
-                            let ttrim = txt.trim();
-                            if code_idx.is_none() && (ttrim == "codigo" || ttrim == "código") { code_idx = Some(ci); }
-                            if name_idx.is_none() && (txt.contains("nombre") || txt.contains("asignatura") || txt.contains("descripcion")) { name_idx = Some(ci); }
-                            if seccion_idx.is_none() && (ttrim == "sección" || ttrim == "seccion") { seccion_idx = Some(ci); }
-                            if horario_idx.is_none() && (txt.contains("horario") || txt.contains("hora")) { horario_idx = Some(ci); }
-                            if profesor_idx.is_none() && txt.contains("profesor") { profesor_idx = Some(ci); }
-                            if codigo_box_idx.is_none() && (txt.contains("codigo_box") || txt.contains("id_box") || txt.contains("id_paquete")) { codigo_box_idx = Some(ci); }
-                        }
-                        if code_idx.is_none() {
-                            for (ci, cell) in row.iter().enumerate() {
-                                let txt = cell.to_lowercase();
-                                if txt.contains("codigo") || txt.contains("código") || txt.contains("cod") || txt.contains("seccion") || txt.contains("sección") { code_idx = Some(ci); break; }
-                            }
-                        }
-                        // Validate the detected column by checking later rows for digit presence
-                        if let Some(ci) = code_idx {
-                            let mut has_digits = false;
-                            for validate_row in rows_vec.iter().skip(ridx+1).take(6) {
-                                if let Some(cell) = validate_row.get(ci) {
-                                    if cell.chars().any(|ch| ch.is_ascii_digit()) { has_digits = true; break; }
-                                }
-                            }
-                            if !has_digits {
-                                code_idx = None;
-                                for (ci2, _) in row.iter().enumerate() {
-                                    let mut found = false;
-                                    for validate_row in rows_vec.iter().skip(ridx+1).take(6) {
-                                        if let Some(cell) = validate_row.get(ci2) {
-                                            if cell.chars().any(|ch| ch.is_ascii_digit()) { found = true; break; }
-                                        }
-                                    }
-                                    if found { code_idx = Some(ci2); break; }
-                                }
-                            }
-                        }
-                        break;
-                    }
-                }
-                for (row_idx, row) in rows_vec.iter().enumerate() {
-                    if row.iter().all(|c| c.trim().is_empty()) { continue; }
-                    if let Some(h) = header_row_idx {
-                        if row_idx == h { continue; }
-                        let codigo = code_idx.and_then(|i| row.get(i)).map(|c| c.trim().to_string()).unwrap_or_default();
-                        let base_codigo = base_course_code(&codigo);
-                        if codigo.is_empty() { continue; }
-                        let nombre = name_idx.and_then(|i| row.get(i)).map(|c| c.trim().to_string()).unwrap_or_default();
-                        let seccion = seccion_idx.and_then(|i| row.get(i)).map(|c| c.trim().to_string()).unwrap_or_else(|| "1".to_string());
-                        let horario_str = horario_idx.and_then(|i| row.get(i)).map(|c| c.trim().to_string()).unwrap_or_default();
-                        let profesor = profesor_idx.and_then(|i| row.get(i)).map(|c| c.trim().to_string()).unwrap_or_else(|| "Sin asignar".to_string());
-                        let codigo_box = codigo_box_idx.and_then(|i| row.get(i)).map(|c| c.trim().to_string()).unwrap_or_else(|| codigo.clone());
-                        let horario: Vec<String> = if horario_str.is_empty() { vec!["Sin horario".to_string()] } else { horario_str.split(|c| c == ',' || c == ';').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect() };
-                        raw_rows_zip.push(RawRow { codigo: codigo.clone(), nombre: nombre.clone(), seccion: seccion.clone(), horario, profesor, codigo_box: codigo_box.clone() });
-                        continue;
-                    }
-                    // fallback to fixed indexes
-                    let codigo = row.get(1).cloned().unwrap_or_default().trim().to_string();
-                    let base_codigo = base_course_code(&codigo);
-                    if codigo.is_empty() { continue; }
-                    let nombre = row.get(2).cloned().unwrap_or_else(|| "Sin nombre".to_string());
-                    let seccion = row.get(3).cloned().unwrap_or_else(|| "1".to_string());
-                    let horario_str = row.get(7).cloned().unwrap_or_default();
-                    let profesor = row.get(9).cloned().unwrap_or_else(|| "Sin asignar".to_string());
-                    let codigo_box = row.get(18).cloned().unwrap_or_else(|| codigo.clone());
-                    let horario: Vec<String> = if horario_str.is_empty() { vec!["Sin horario".to_string()] } else { horario_str.split(|c| c == ',' || c == ';').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect() };
-                    raw_rows_zip.push(RawRow { codigo: codigo.clone(), nombre: nombre.clone(), seccion: seccion.clone(), horario, profesor, codigo_box: codigo_box.clone() });
+                let mut lector = LectorHoja::new(&layout);
+                for fila in rows_vec {
+                    lector.fila(fila);
                 }
-
-                if !raw_rows_zip.is_empty() {
-                    let mut map: HashMap<(String,String,String), Vec<RawRow>> = HashMap::new();
-                    for r in raw_rows_zip.into_iter() {
-                        let key = (base_course_code(&r.codigo), r.seccion.clone(), r.codigo_box.clone());
-                        map.entry(key).or_insert_with(Vec::new).push(r);
-                    }
-                    let mut result: Vec<Seccion> = Vec::new();
-                    for ((codigo, secc, codigo_box), rows) in map.into_iter() {
-                        let mut horarios_acc: Vec<String> = Vec::new();
-                        let mut profesor_pref = String::new();
-                        let mut nombre_pref = String::new();
-                        for r in rows.into_iter() {
-                            if nombre_pref.is_empty() { nombre_pref = r.nombre.clone(); }
-                            if profesor_pref.is_empty() && !r.profesor.trim().is_empty() { profesor_pref = r.profesor.clone(); }
-                            for h in r.horario.into_iter() {
-                                if !horarios_acc.iter().any(|x| x == &h) {
-                                    horarios_acc.push(h);
-                                }
-                            }
-                        }
-                        if horarios_acc.is_empty() { horarios_acc.push("Sin horario".to_string()); }
-                        result.push(Seccion { codigo: codigo.clone(), nombre: nombre_pref.clone(), seccion: secc.clone(), horario: horarios_acc, profesor: profesor_pref.clone(), codigo_box: codigo_box.clone(), is_cfg: false, is_electivo: false });
-                    }
-                    eprintln!("DEBUG: leer_oferta_academica_excel cargó {} secciones vía zip agrupadas", result.len());
+                let agrupador = lector.terminar();
+                if !agrupador.is_empty() {
+                    let result = agrupador.secciones();
+                    tracing::debug!("leer_oferta_academica_excel cargó {} secciones vía zip agrupadas", result.len());
                     return Ok(result);
                 }
             }
@@ -306,7 +300,7 @@ pub fn leer_oferta_academica_excel(nombre_archivo: &str) -> Result<Vec<Seccion>,
 
 /// Genera un resumen de la oferta académica: nombre del ramo → cantidad de secciones
 pub fn resumen_oferta_academica(nombre_archivo: &str) -> Result<Vec<(String, usize)>, Box<dyn std::error::Error>> {
-    let secciones = leer_oferta_academica_excel(nombre_archivo)?;
+    let secciones = crate::datafiles::cache::oferta(nombre_archivo)?;
     
     let mut resumen: HashMap<String, usize> = HashMap::new();
     
@@ -328,7 +322,7 @@ pub fn resumen_oferta_academica(nombre_archivo: &str) -> Result<Vec<(String, usi
 
 /// Extrae el conjunto de códigos de cursos disponibles en la oferta académica
 pub fn get_available_course_codes(nombre_archivo: &str) -> Result<HashSet<String>, Box<dyn std::error::Error>> {
-    let secciones = leer_oferta_academica_excel(nombre_archivo)?;
+    let secciones = crate::datafiles::cache::oferta(nombre_archivo)?;
     let mut codes = std::collections::HashSet::new();
     
     for seccion in secciones.iter() {
@@ -337,3 +331,95 @@ pub fn get_available_course_codes(nombre_archivo: &str) -> Result<HashSet<String
     
     Ok(codes)
 }
+
+/// Secciones por página de `consultar_oferta` si no se indica `per_page`
+pub const POR_PAGINA_DEFECTO: usize = 50;
+/// Tope de `per_page`
+pub const POR_PAGINA_MAX: usize = 500;
+
+/// Criterios de `GET /oferta` (todos opcionales y combinables)
+#[derive(Debug, Clone, Default)]
+pub struct ConsultaOferta {
+    /// Código o parte del nombre del ramo, sin distinguir mayúsculas ni tildes
+    pub curso: Option<String>,
+    /// Parte del nombre del profesor, sin distinguir mayúsculas ni tildes
+    pub profesor: Option<String>,
+    /// Día con clases ("LU", "martes", "THU", ver `horario::Dia::desde_codigo`)
+    pub dia: Option<String>,
+    /// Inicio del rango horario ("08:30"): algún bloque debe empezar desde esta hora
+    pub desde: Option<String>,
+    /// Fin del rango horario ("13:00"): y terminar a más tardar a esta hora
+    pub hasta: Option<String>,
+    /// Página, desde 1
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+/// Una página de la oferta filtrada
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PaginaOferta {
+    /// Secciones que cumplen los filtros (en todas las páginas)
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+    pub total_pages: usize,
+    pub secciones: Vec<Seccion>,
+}
+
+/// Filtra `secciones` según `consulta` y devuelve la página pedida, en el
+/// orden de la oferta. Con `dia` y rango horario, el mismo bloque debe
+/// cumplir ambos. Error si el día, las horas o la paginación no son válidos.
+pub fn consultar_oferta(secciones: &[Seccion], consulta: &ConsultaOferta) -> Result<PaginaOferta, String> {
+    use crate::excel::normalize_name;
+    use crate::models::horario::{self, Dia};
+
+    let texto = |v: &Option<String>| v.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(normalize_name);
+    let hora = |v: &Option<String>, campo: &str| -> Result<Option<u32>, String> {
+        match v.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(h) => horario::parse_hora(h).map(Some).ok_or_else(|| format!("{} inválido: '{}'", campo, h)),
+            None => Ok(None),
+        }
+    };
+
+    let curso = texto(&consulta.curso);
+    let profesor = texto(&consulta.profesor);
+    let dia = match consulta.dia.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(d) => Some(Dia::desde_codigo(d).ok_or_else(|| format!("dia inválido: '{}'", d))?),
+        None => None,
+    };
+    let desde = hora(&consulta.desde, "desde")?;
+    let hasta = hora(&consulta.hasta, "hasta")?;
+    if let (Some(d), Some(h)) = (desde, hasta) && d >= h {
+        return Err(format!("desde ({}) debe ser anterior a hasta ({})", horario::formatear_hora(d), horario::formatear_hora(h)));
+    }
+    let page = consulta.page.unwrap_or(1);
+    if page == 0 {
+        return Err("page parte en 1".into());
+    }
+    let per_page = consulta.per_page.unwrap_or(POR_PAGINA_DEFECTO);
+    if per_page == 0 || per_page > POR_PAGINA_MAX {
+        return Err(format!("per_page debe estar entre 1 y {}", POR_PAGINA_MAX));
+    }
+
+    let filtra_horario = dia.is_some() || desde.is_some() || hasta.is_some();
+    let coinciden: Vec<&Seccion> = secciones.iter()
+        .filter(|s| curso.as_ref().is_none_or(|c| {
+            normalize_name(&s.codigo).contains(c.as_str()) || normalize_name(&s.nombre).contains(c.as_str())
+        }))
+        .filter(|s| profesor.as_ref().is_none_or(|p| normalize_name(&s.profesor).contains(p.as_str())))
+        .filter(|s| !filtra_horario || horario::bloques_de_seccion(s).iter().any(|b| {
+            dia.is_none_or(|d| b.dia == d)
+                && desde.is_none_or(|d| b.inicio >= d)
+                && hasta.is_none_or(|h| b.fin <= h)
+        }))
+        .collect();
+
+    let total = coinciden.len();
+    Ok(PaginaOferta {
+        total,
+        page,
+        per_page,
+        total_pages: total.div_ceil(per_page),
+        secciones: coinciden.into_iter().skip((page - 1) * per_page).take(per_page).cloned().collect(),
+    })
+}
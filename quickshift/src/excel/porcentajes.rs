@@ -1,6 +1,7 @@
 use std::collections::HashMap;
-use calamine::{open_workbook_auto, Data, Reader};
-use crate::excel::io::{data_to_string, read_sheet_via_zip};
+use calamine::{Data, Reader};
+use crate::excel::io::{abrir_libro, data_to_string, read_sheet_via_zip};
+use crate::excel::layout::{alguno_calza, perfil_para};
 use crate::excel::normalize_name;
 
 /// Leer porcentajes/aprobados. Devuelve un mapa codigo -> (A, n) donde
@@ -16,25 +17,21 @@ pub fn leer_porcentajes_aprobados(path: &str) -> Result<HashMap<String, (f64, f6
         if std::path::Path::new(&candidate).exists() { candidate } else { path.to_string() }
     };
 
+    let layout = perfil_para(&resolved).porcentajes;
+
     // Intentar con calamine primero
-    if let Ok(mut workbook) = open_workbook_auto(&resolved) {
+    if let Ok(mut workbook) = abrir_libro(&resolved) {
         let sheet_names = workbook.sheet_names().to_owned();
         if !sheet_names.is_empty() {
             let primera = &sheet_names[0];
             if let Ok(range) = workbook.worksheet_range(primera) {
                 let mut rows_iter = range.rows();
                 if let Some(header_row) = rows_iter.next() {
-                    let headers: Vec<String> = header_row.iter().map(|c| data_to_string(c)).map(|s| s.to_lowercase()).collect();
-                    let mut idx_codigo: usize = 0;
-                    let mut idx_aprobados: Option<usize> = None;
-                    let mut idx_total: Option<usize> = None;
-                    let mut idx_porcentaje: Option<usize> = None;
-                    for (i, h) in headers.iter().enumerate() {
-                        if h.contains("codigo") || h == "ramo" || h == "asignatura" { idx_codigo = i; }
-                        if h.contains("aprob") { idx_aprobados = Some(i); }
-                        if h.contains("total") { idx_total = Some(i); }
-                        if h.contains("porcentaje") || h.contains('%') { idx_porcentaje = Some(i); }
-                    }
+                    let headers: Vec<String> = header_row.iter().map(data_to_string).collect();
+                    let idx_codigo = layout.codigo.ultima(&headers).or(layout.codigo.posicion).unwrap_or(0);
+                    let idx_aprobados = layout.aprobados.ultima(&headers);
+                    let idx_total = layout.total.ultima(&headers);
+                    let idx_porcentaje = layout.porcentaje.ultima(&headers);
 
                 for row in rows_iter {
                 let codigo = data_to_string(row.get(idx_codigo).unwrap_or(&Data::Empty)).trim().to_string();
@@ -65,17 +62,10 @@ pub fn leer_porcentajes_aprobados(path: &str) -> Result<HashMap<String, (f64, f6
         Ok(rows) => {
             if rows.is_empty() { return Ok(res); }
             let headers_row = &rows[0];
-            let headers: Vec<String> = headers_row.iter().map(|h| h.trim().to_lowercase()).collect();
-            let mut idx_codigo: usize = 0;
-            let mut idx_aprobados: Option<usize> = None;
-            let mut idx_total: Option<usize> = None;
-            let mut idx_porcentaje: Option<usize> = None;
-            for (i, h) in headers.iter().enumerate() {
-                if h.contains("codigo") || h == "ramo" || h == "asignatura" { idx_codigo = i; }
-                if h.contains("aprob") { idx_aprobados = Some(i); }
-                if h.contains("total") { idx_total = Some(i); }
-                if h.contains("porcentaje") || h.contains('%') { idx_porcentaje = Some(i); }
-            }
+            let idx_codigo = layout.codigo.ultima(headers_row).or(layout.codigo.posicion).unwrap_or(0);
+            let idx_aprobados = layout.aprobados.ultima(headers_row);
+            let idx_total = layout.total.ultima(headers_row);
+            let idx_porcentaje = layout.porcentaje.ultima(headers_row);
 
             for (i, row) in rows.iter().enumerate() {
                 if i == 0 { continue; }
@@ -95,8 +85,8 @@ pub fn leer_porcentajes_aprobados(path: &str) -> Result<HashMap<String, (f64, f6
                     if let Ok(pv) = p.parse::<f64>() { res.insert(codigo.clone(), (pv, 100.0)); continue; }
                 }
 
-                // fallback segunda columna
-                let second = row.get(1).cloned().unwrap_or_default();
+                // fallback: columna del porcentaje por posición
+                let second = layout.porcentaje.posicion.and_then(|i| row.get(i)).cloned().unwrap_or_default();
                 let s2 = second.replace('%', "").replace(',', ".");
                 if let Ok(pv) = s2.parse::<f64>() { res.insert(codigo.clone(), (pv, 100.0)); }
             }
@@ -119,8 +109,9 @@ pub fn leer_porcentajes_aprobados_con_nombres(path: &str) -> Result<(HashMap<Str
         let candidate = format!("{}/{}", crate::excel::DATAFILES_DIR, path);
         if std::path::Path::new(&candidate).exists() { candidate } else { path.to_string() }
     };
+    let layout = perfil_para(&resolved).porcentajes;
 
-    if let Ok(mut workbook) = open_workbook_auto(&resolved) {
+    if let Ok(mut workbook) = abrir_libro(&resolved) {
         let sheet_names = workbook.sheet_names().to_owned();
         if !sheet_names.is_empty() {
             let primera = &sheet_names[0];
@@ -131,30 +122,22 @@ pub fn leer_porcentajes_aprobados_con_nombres(path: &str) -> Result<(HashMap<Str
                 let search_limit = std::cmp::min(8, rows.len());
                 let mut header_idx: Option<usize> = None;
                 for i in 0..search_limit {
-                    let headers: Vec<String> = rows[i].iter().map(|c| data_to_string(c).to_lowercase()).collect();
-                    // considerar fila header si contiene 'codigo' o 'ramo' o 'asignatura'
-                    if headers.iter().any(|h| h.contains("codigo") || h.contains("ramo") || h.contains("asignatura")) {
+                    let headers: Vec<String> = rows[i].iter().map(data_to_string).collect();
+                    // considerar fila header si alguna celda calza con `fila_encabezado`
+                    if headers.iter().any(|h| alguno_calza(&layout.fila_encabezado, h)) {
                         header_idx = Some(i);
                         break;
                     }
                 }
 
                 if let Some(hidx) = header_idx {
-                    let headers: Vec<String> = rows[hidx].iter().map(|c| data_to_string(c).to_lowercase()).collect();
-                    let mut idx_codigo: usize = 0;
-                    let mut idx_aprobados: Option<usize> = None;
-                    let mut idx_total: Option<usize> = None;
-                    let mut idx_porcentaje: Option<usize> = None;
-                    let mut idx_nombre: Option<usize> = None;
-                    let mut idx_electivo: Option<usize> = None;
-                    for (i, h) in headers.iter().enumerate() {
-                        if h.contains("codigo") || h == "ramo" || h == "asignatura" { idx_codigo = i; }
-                        if h.contains("aprob") { idx_aprobados = Some(i); }
-                        if h.contains("total") { idx_total = Some(i); }
-                        if h.contains("porcentaje") || h.contains('%') { idx_porcentaje = Some(i); }
-                        if h.contains("denomin") || h.contains("denominación") || h.contains("denominacion") || h.contains("asignatura") { idx_nombre = Some(i); }
-                        if h.contains("electivo") { idx_electivo = Some(i); }
-                    }
+                    let headers: Vec<String> = rows[hidx].iter().map(data_to_string).collect();
+                    let idx_codigo = layout.codigo.ultima(&headers).or(layout.codigo.posicion).unwrap_or(0);
+                    let idx_aprobados = layout.aprobados.ultima(&headers);
+                    let idx_total = layout.total.ultima(&headers);
+                    let idx_porcentaje = layout.porcentaje.ultima(&headers);
+                    let idx_nombre = layout.nombre.ultima(&headers);
+                    let idx_electivo = layout.electivo.ultima(&headers);
 
                     for row in rows.iter().skip(hidx+1) {
                         let codigo = data_to_string(row.get(idx_codigo).unwrap_or(&Data::Empty)).trim().to_string();
@@ -208,21 +191,12 @@ pub fn leer_porcentajes_aprobados_con_nombres(path: &str) -> Result<(HashMap<Str
         Ok(rows) => {
             if rows.is_empty() { return Ok((res, name_index)); }
             let headers_row = &rows[0];
-            let headers: Vec<String> = headers_row.iter().map(|h| h.trim().to_lowercase()).collect();
-            let mut idx_codigo: usize = 0;
-            let mut idx_aprobados: Option<usize> = None;
-            let mut idx_total: Option<usize> = None;
-            let mut idx_porcentaje: Option<usize> = None;
-            let mut idx_nombre: Option<usize> = None;
-            let mut idx_electivo: Option<usize> = None;
-            for (i, h) in headers.iter().enumerate() {
-                if h.contains("codigo") || h == "ramo" || h == "asignatura" { idx_codigo = i; }
-                if h.contains("aprob") { idx_aprobados = Some(i); }
-                if h.contains("total") { idx_total = Some(i); }
-                if h.contains("porcentaje") || h.contains('%') { idx_porcentaje = Some(i); }
-                if h.contains("denomin") || h.contains("denominación") || h.contains("denominacion") || h.contains("asignatura") { idx_nombre = Some(i); }
-                if h.contains("electivo") { idx_electivo = Some(i); }
-            }
+            let idx_codigo = layout.codigo.ultima(headers_row).or(layout.codigo.posicion).unwrap_or(0);
+            let idx_aprobados = layout.aprobados.ultima(headers_row);
+            let idx_total = layout.total.ultima(headers_row);
+            let idx_porcentaje = layout.porcentaje.ultima(headers_row);
+            let idx_nombre = layout.nombre.ultima(headers_row);
+            let idx_electivo = layout.electivo.ultima(headers_row);
 
             for (i, row) in rows.iter().enumerate() {
                 if i == 0 { continue; }
@@ -297,8 +271,8 @@ pub fn enrich_porcent_names_from_malla(
             malla_by_norm.insert(rname_norm, (mcode.clone(), ramo));
         }
         
-        eprintln!("[ENRICH] Building porcent_names from PA data...");
-        eprintln!("[ENRICH] Total PA codes: {}, Total Malla courses: {}", porcent.len(), malla_map.len());
+        tracing::info!("[ENRICH] Building porcent_names from PA data...");
+        tracing::info!("[ENRICH] Total PA codes: {}, Total Malla courses: {}", porcent.len(), malla_map.len());
         
         let mut matched = 0;
         let mut unmatched_pa: Vec<(String, f64, f64)> = Vec::new();
@@ -311,7 +285,7 @@ pub fn enrich_porcent_names_from_malla(
             if let Some((mcode, _ramo)) = malla_by_norm.get(&pa_norm) {
                 // ¡Encontramos match por nombre normalizado!
                 porcent_names.insert(pa_norm.clone(), (pa_code.clone(), *pct, *tot, false));
-                eprintln!("[ENRICH] MATCHED by name: PA code '{}' -> Malla '{}' (pct={}%, tot={})", 
+                tracing::info!("[ENRICH] MATCHED by name: PA code '{}' -> Malla '{}' (pct={}%, tot={})", 
                     pa_code, mcode, pct, tot);
                 matched += 1;
             } else {
@@ -336,12 +310,12 @@ pub fn enrich_porcent_names_from_malla(
             if i < unmatched_malla.len() {
                 let (rname_norm, mcode) = &unmatched_malla[i];
                 porcent_names.insert(rname_norm.clone(), (pa_code.clone(), *pct, *tot, false));
-                eprintln!("[ENRICH] FALLBACK 1:1: PA code '{}' -> Malla '{}' (pct={}%, tot={})", 
+                tracing::info!("[ENRICH] FALLBACK 1:1: PA code '{}' -> Malla '{}' (pct={}%, tot={})", 
                     pa_code, mcode, pct, tot);
             }
         }
         
-        eprintln!("[ENRICH] ✅ Complete! Matched: {}, Unmatched PA: {}, Unmatched Malla: {}, Final size: {}", 
+        tracing::info!("[ENRICH] ✅ Complete! Matched: {}, Unmatched PA: {}, Unmatched Malla: {}, Final size: {}", 
             matched, unmatched_pa.len(), unmatched_malla.len(), porcent_names.len());
     }
 }
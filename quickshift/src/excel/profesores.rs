@@ -0,0 +1,115 @@
+// profesores.rs - Directorio de profesores a partir de la oferta y los porcentajes
+//
+// Agrupa las secciones de la oferta por profesor (nombres comparados con
+// `normalize_name`, así "PÉREZ ANA" y "perez ana" son el mismo) y le agrega
+// a cada curso su porcentaje histórico de aprobación del archivo de
+// porcentajes, si lo trae. El porcentaje es del ramo, no del profesor: el
+// archivo no distingue secciones.
+
+use std::collections::BTreeMap;
+use serde::Serialize;
+use crate::datafiles::cache::PorcentajesConNombres;
+use crate::excel::normalize_name;
+use crate::models::Seccion;
+
+/// Profesor encontrado por `buscar_profesores`
+#[derive(Debug, Clone, Serialize)]
+pub struct ResumenProfesor {
+    pub nombre: String,
+    pub cursos: usize,
+    pub secciones: usize,
+}
+
+/// Sección dictada por el profesor este semestre
+#[derive(Debug, Clone, Serialize)]
+pub struct SeccionProfesor {
+    pub seccion: String,
+    pub codigo_box: String,
+    pub horario: Vec<String>,
+}
+
+/// Curso del profesor con su aprobación histórica
+#[derive(Debug, Clone, Serialize)]
+pub struct CursoProfesor {
+    pub codigo: String,
+    pub nombre: String,
+    pub secciones: Vec<SeccionProfesor>,
+    /// Porcentaje de aprobación del ramo en el archivo de porcentajes
+    pub porcentaje_aprobacion: Option<f64>,
+    /// Estudiantes sobre los que se calculó el porcentaje
+    pub total_historico: Option<f64>,
+}
+
+/// `GET /profesores/{nombre}`
+#[derive(Debug, Clone, Serialize)]
+pub struct FichaProfesor {
+    pub nombre: String,
+    pub cursos: Vec<CursoProfesor>,
+    pub total_secciones: usize,
+}
+
+/// (porcentaje, total) de un ramo: por código o nombre en el índice por
+/// nombre (`leer_porcentajes_aprobados_con_nombres`) o por código en el mapa
+fn aprobacion(seccion: &Seccion, porcentajes: &PorcentajesConNombres) -> Option<(f64, f64)> {
+    let (por_codigo, por_nombre) = porcentajes;
+    por_nombre.get(&normalize_name(&seccion.codigo))
+        .or_else(|| por_nombre.get(&normalize_name(&seccion.nombre)))
+        .map(|(_, pct, total, _)| (*pct, *total))
+        .or_else(|| por_codigo.get(seccion.codigo.trim()).copied())
+}
+
+/// Secciones con profesor agrupadas por nombre normalizado (orden alfabético)
+fn por_profesor(secciones: &[Seccion]) -> BTreeMap<String, Vec<&Seccion>> {
+    let mut grupos: BTreeMap<String, Vec<&Seccion>> = BTreeMap::new();
+    for s in secciones.iter().filter(|s| !s.profesor.trim().is_empty()) {
+        grupos.entry(normalize_name(&s.profesor)).or_default().push(s);
+    }
+    grupos
+}
+
+/// Profesores cuyo nombre contiene `consulta` (sin distinguir mayúsculas ni
+/// tildes), en orden alfabético; una consulta vacía los lista a todos
+pub fn buscar_profesores(secciones: &[Seccion], consulta: &str) -> Vec<ResumenProfesor> {
+    let q = normalize_name(consulta);
+    por_profesor(secciones).into_iter()
+        .filter(|(clave, _)| clave.contains(q.as_str()))
+        .map(|(_, secs)| {
+            let mut codigos: Vec<&str> = secs.iter().map(|s| s.codigo.as_str()).collect();
+            codigos.sort();
+            codigos.dedup();
+            ResumenProfesor { nombre: secs[0].profesor.trim().to_string(), cursos: codigos.len(), secciones: secs.len() }
+        })
+        .collect()
+}
+
+/// Cursos y secciones de `nombre` (nombre completo, sin distinguir
+/// mayúsculas ni tildes), con la aprobación histórica de cada curso
+pub fn ficha_profesor(secciones: &[Seccion], porcentajes: &PorcentajesConNombres, nombre: &str) -> Option<FichaProfesor> {
+    let clave = normalize_name(nombre);
+    let secs = por_profesor(secciones).remove(&clave)?;
+
+    let mut cursos: BTreeMap<String, CursoProfesor> = BTreeMap::new();
+    for s in &secs {
+        let curso = cursos.entry(s.codigo.to_uppercase()).or_insert_with(|| {
+            let aprobacion = aprobacion(s, porcentajes);
+            CursoProfesor {
+                codigo: s.codigo.clone(),
+                nombre: s.nombre.clone(),
+                secciones: Vec::new(),
+                porcentaje_aprobacion: aprobacion.map(|(pct, _)| pct),
+                total_historico: aprobacion.map(|(_, total)| total),
+            }
+        });
+        curso.secciones.push(SeccionProfesor {
+            seccion: s.seccion.clone(),
+            codigo_box: s.codigo_box.clone(),
+            horario: s.horario.clone(),
+        });
+    }
+
+    Some(FichaProfesor {
+        nombre: secs[0].profesor.trim().to_string(),
+        total_secciones: secs.len(),
+        cursos: cursos.into_values().collect(),
+    })
+}
@@ -0,0 +1,71 @@
+//! Datos de ejemplo embebidos en el binario (feature `sample-data`).
+//!
+//! Una malla (MC2020), su oferta (OA20251) y sus porcentajes (PA20251) se
+//! incluyen con `include_bytes!` para que un checkout o contenedor sin
+//! directorio de datafiles pueda resolver `/solve` de punta a punta. Cuando
+//! no se encuentra ningún directorio de datafiles (ver `config`), `materializar` escribe
+//! los archivos en un directorio temporal y lo usa; desde ese momento
+//! `en_uso()` es true y el servidor responde con `X-Sample-Data: true`.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Archivos embebidos: (nombre, contenido)
+#[cfg(feature = "sample-data")]
+pub const ARCHIVOS: &[(&str, &[u8])] = &[
+    ("MC2020.xlsx", include_bytes!("../datafiles/MC2020.xlsx")),
+    ("OA20251.xlsx", include_bytes!("../datafiles/OA20251.xlsx")),
+    ("PA20251.xlsx", include_bytes!("../datafiles/PA20251.xlsx")),
+];
+
+#[cfg(not(feature = "sample-data"))]
+pub const ARCHIVOS: &[(&str, &[u8])] = &[];
+
+/// Malla de los datos de ejemplo
+pub const MALLA: &str = "MC2020.xlsx";
+
+static EN_USO: AtomicBool = AtomicBool::new(false);
+
+/// true si los datafiles que se están usando son los de ejemplo
+pub fn en_uso() -> bool {
+    EN_USO.load(Ordering::Relaxed)
+}
+
+/// Marca los datos de ejemplo como fuera de uso (se configuró otro directorio)
+pub fn desactivar() {
+    EN_USO.store(false, Ordering::Relaxed);
+}
+
+/// Directorio donde se escriben los datos de ejemplo
+pub fn directorio() -> PathBuf {
+    std::env::temp_dir().join("quickshift-sample-data")
+}
+
+/// Escribe los datos de ejemplo en `directorio()` (solo los archivos que
+/// falten o hayan cambiado) y los marca en uso. None si el binario se compiló
+/// sin `sample-data` o no se pudo escribir.
+pub fn materializar() -> Option<PathBuf> {
+    if ARCHIVOS.is_empty() {
+        return None;
+    }
+    let dir = directorio();
+    if en_uso() && dir.is_dir() {
+        return Some(dir);
+    }
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        tracing::warn!("⚠️ No se pudo crear {:?} para los datos de ejemplo: {}", dir, e);
+        return None;
+    }
+    for (nombre, contenido) in ARCHIVOS {
+        let ruta = dir.join(nombre);
+        let igual = std::fs::read(&ruta).map(|actual| actual == *contenido).unwrap_or(false);
+        if !igual && let Err(e) = std::fs::write(&ruta, contenido) {
+            tracing::warn!("⚠️ No se pudo escribir {:?}: {}", ruta, e);
+            return None;
+        }
+    }
+    if !EN_USO.swap(true, Ordering::Relaxed) {
+        tracing::warn!("⚠️ Usando datos de ejemplo embebidos en {:?} (malla {})", dir, MALLA);
+    }
+    Some(dir)
+}
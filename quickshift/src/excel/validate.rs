@@ -0,0 +1,306 @@
+// validate.rs - Reporte de validación (dry-run) de una malla
+//
+// Los lectores de malla son tolerantes: una columna mal nombrada o filas con
+// el ID corrido terminan en un mapa vacío o incompleto sin más aviso que un
+// log. Este módulo recorre la hoja con las mismas reglas de encabezado y
+// devuelve un reporte estructurado: columnas detectadas, filas leídas y
+// omitidas (con motivo), requisitos que apuntan a IDs/códigos inexistentes,
+// códigos duplicados y ramos cuyo nombre no aparece en la oferta.
+//
+// No modifica nada: sirve para revisar un archivo antes de subirlo
+// (`POST /datafiles/upload?dry_run=true`) o uno ya seleccionado
+// (`GET /datafiles/validate?malla=...`).
+
+use calamine::{Data, Reader};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use crate::excel::deteccion::{buscar_encabezado, TipoDatafile};
+use crate::excel::io::{abrir_libro, data_to_string, diagnosticar_libro, DiagnosticoLibro};
+use crate::excel::normalize_name;
+use crate::models::Seccion;
+
+/// Columnas reconocidas (texto original del encabezado)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ColumnasDetectadas {
+    /// Fila del encabezado (1 = primera fila de la hoja)
+    pub fila_encabezado: usize,
+    pub id: Option<String>,
+    pub codigo: Option<String>,
+    pub nombre: Option<String>,
+    pub requisitos: Option<String>,
+    pub semestre: Option<String>,
+    pub creditos: Option<String>,
+    pub electivo: Option<String>,
+    /// Encabezados que no corresponden a ninguna columna conocida
+    pub no_reconocidas: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilaOmitida {
+    pub fila: usize,
+    pub motivo: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RequisitoFaltante {
+    pub fila: usize,
+    pub ramo: String,
+    /// ID o código referenciado que no existe en la malla
+    pub requisito: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Duplicado {
+    /// "id" o "codigo"
+    pub campo: &'static str,
+    pub valor: String,
+    pub filas: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RamoSinOferta {
+    pub fila: usize,
+    pub codigo: Option<String>,
+    pub nombre: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReporteMalla {
+    pub archivo: String,
+    /// Formato, hojas y protección del libro
+    pub libro: DiagnosticoLibro,
+    pub hoja: String,
+    pub columnas: ColumnasDetectadas,
+    /// Filas no vacías bajo el encabezado
+    pub filas_totales: usize,
+    pub filas_leidas: usize,
+    pub filas_omitidas: Vec<FilaOmitida>,
+    pub requisitos_faltantes: Vec<RequisitoFaltante>,
+    pub duplicados: Vec<Duplicado>,
+    /// Oferta contra la que se compararon los nombres (si se entregó)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oferta: Option<String>,
+    pub sin_oferta: Vec<RamoSinOferta>,
+    /// Problemas que impiden usar la malla (columnas obligatorias, cero filas)
+    pub errores: Vec<String>,
+    pub valido: bool,
+}
+
+/// Un ramo leído de la hoja
+struct FilaRamo {
+    fila: usize,
+    id: Option<i64>,
+    codigo: Option<String>,
+    nombre: String,
+    requisitos: Vec<String>,
+}
+
+/// Valores de la columna de requisitos que significan "sin requisitos"
+const SIN_REQUISITOS: [&str; 7] = ["", "-", "—", "0", "ninguno", "no", "n/a"];
+
+fn celda(fila: &[Data], idx: Option<usize>) -> String {
+    idx.and_then(|i| fila.get(i)).map(|c| data_to_string(c).trim().to_string()).unwrap_or_default()
+}
+
+/// "3" o "3.0" -> 3
+fn parse_id(valor: &str) -> Option<i64> {
+    valor.parse::<i64>().ok().or_else(|| valor.parse::<f64>().ok().filter(|f| f.fract() == 0.0).map(|f| f as i64))
+}
+
+/// Separa "1, 2; 7" / "CBM1000 CBM1001" en referencias individuales. El
+/// punto también separa: Excel guarda "6,7" como el número 6.7 (igual que en
+/// `leer_malla_con_porcentajes_optimizado`).
+fn separar_requisitos(valor: &str) -> Vec<String> {
+    valor.split(|c: char| c == ',' || c == '.' || c == ';' || c == '/' || c.is_whitespace())
+        .map(|t| t.trim().to_string())
+        .filter(|t| !SIN_REQUISITOS.contains(&t.to_lowercase().as_str()))
+        .collect()
+}
+
+fn detectar_columnas(encabezado: &[Data], fila_encabezado: usize) -> (ColumnasDetectadas, [Option<usize>; 7]) {
+    let mut columnas = ColumnasDetectadas { fila_encabezado: fila_encabezado + 1, ..Default::default() };
+    // id, codigo, nombre, requisitos, semestre, creditos, electivo
+    let mut indices: [Option<usize>; 7] = [None; 7];
+    for (i, c) in encabezado.iter().enumerate() {
+        let original = data_to_string(c).trim().to_string();
+        if original.is_empty() {
+            continue;
+        }
+        let h = normalize_name(&original);
+        let rol = if h == "id" || h.contains("correlativo") {
+            Some(0)
+        } else if h.contains("codigo") {
+            Some(1)
+        } else if h.contains("nombre") {
+            Some(2)
+        } else if (h.contains("requisito") && !super::malla::es_columna_correquisitos(&original)) || h.contains("prerreq") {
+            Some(3)
+        } else if h.contains("semestre") {
+            Some(4)
+        } else if super::malla::es_columna_creditos(&original) {
+            Some(5)
+        } else if h.contains("electivo") {
+            Some(6)
+        } else {
+            None
+        };
+        match rol {
+            Some(r) if indices[r].is_none() => {
+                indices[r] = Some(i);
+                let destino = match r {
+                    0 => &mut columnas.id,
+                    1 => &mut columnas.codigo,
+                    2 => &mut columnas.nombre,
+                    3 => &mut columnas.requisitos,
+                    4 => &mut columnas.semestre,
+                    5 => &mut columnas.creditos,
+                    _ => &mut columnas.electivo,
+                };
+                *destino = Some(original);
+            }
+            _ => columnas.no_reconocidas.push(original),
+        }
+    }
+    (columnas, indices)
+}
+
+/// Valida la malla en `ruta` (hoja `hoja`, o la primera con encabezado de
+/// malla). Si se entrega `oferta`, lista los ramos que no aparecen en ella
+/// ni por código ni por nombre normalizado.
+pub fn validar_malla(ruta: &Path, hoja: Option<&str>, oferta: Option<(&str, &[Seccion])>) -> Result<ReporteMalla, String> {
+    let libro = diagnosticar_libro(ruta);
+    let mut workbook = abrir_libro(ruta).map_err(|e| e.to_string())?;
+    let hojas = workbook.sheet_names().to_owned();
+    let candidatas: Vec<String> = match hoja {
+        Some(h) if hojas.iter().any(|n| n == h) => vec![h.to_string()],
+        Some(h) => return Err(format!("la hoja '{}' no existe (hojas: {})", h, hojas.join(", "))),
+        None => hojas.clone(),
+    };
+
+    let mut encontrada = None;
+    for nombre_hoja in candidatas.iter() {
+        let Ok(rango) = workbook.worksheet_range(nombre_hoja) else { continue };
+        let filas: Vec<&[Data]> = rango.rows().collect();
+        if let Some((i, TipoDatafile::Malla)) = buscar_encabezado(&filas) {
+            encontrada = Some((nombre_hoja.clone(), i, rango.clone()));
+            break;
+        }
+    }
+    let Some((nombre_hoja, idx_encabezado, rango)) = encontrada else {
+        return Err("no se encontró un encabezado de malla (columnas Semestre y Requisitos/Prerreq)".to_string());
+    };
+    let filas: Vec<&[Data]> = rango.rows().collect();
+    let (columnas, [i_id, i_codigo, i_nombre, i_requisitos, ..]) = detectar_columnas(filas[idx_encabezado], idx_encabezado);
+
+    let mut errores = Vec::new();
+    if i_nombre.is_none() {
+        errores.push("falta la columna de nombre".to_string());
+    }
+    if i_id.is_none() && i_codigo.is_none() {
+        errores.push("falta una columna de ID o código".to_string());
+    }
+
+    let mut filas_totales = 0;
+    let mut omitidas = Vec::new();
+    let mut ramos: Vec<FilaRamo> = Vec::new();
+    for (offset, fila) in filas[idx_encabezado + 1..].iter().enumerate() {
+        let numero = idx_encabezado + offset + 2;
+        if fila.iter().all(|c| data_to_string(c).trim().is_empty()) {
+            continue;
+        }
+        filas_totales += 1;
+
+        let nombre = celda(fila, i_nombre);
+        let id_txt = celda(fila, i_id);
+        let codigo = celda(fila, i_codigo);
+        let id = parse_id(&id_txt);
+        let motivo = if nombre.is_empty() {
+            Some("sin nombre".to_string())
+        } else if i_id.is_some() && id.is_none() && !id_txt.is_empty() {
+            Some(format!("ID no numérico '{}'", id_txt))
+        } else if id.is_none() && codigo.is_empty() {
+            Some("sin ID ni código".to_string())
+        } else {
+            None
+        };
+        if let Some(motivo) = motivo {
+            omitidas.push(FilaOmitida { fila: numero, motivo });
+            continue;
+        }
+        ramos.push(FilaRamo {
+            fila: numero,
+            id,
+            codigo: (!codigo.is_empty()).then(|| codigo.to_uppercase()),
+            nombre,
+            requisitos: separar_requisitos(&celda(fila, i_requisitos)),
+        });
+    }
+    if ramos.is_empty() {
+        errores.push("no se pudo leer ningún ramo".to_string());
+    }
+
+    // Duplicados de ID y de código
+    let mut por_id: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut por_codigo: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for r in ramos.iter() {
+        if let Some(id) = r.id {
+            por_id.entry(id.to_string()).or_default().push(r.fila);
+        }
+        if let Some(ref c) = r.codigo {
+            por_codigo.entry(c.clone()).or_default().push(r.fila);
+        }
+    }
+    let duplicados: Vec<Duplicado> = por_id.into_iter().map(|(v, f)| ("id", v, f))
+        .chain(por_codigo.into_iter().map(|(v, f)| ("codigo", v, f)))
+        .filter(|(_, _, filas)| filas.len() > 1)
+        .map(|(campo, valor, filas)| Duplicado { campo, valor, filas })
+        .collect();
+
+    // Requisitos: números se buscan entre los IDs, el resto entre los códigos
+    let ids: HashSet<i64> = ramos.iter().filter_map(|r| r.id).collect();
+    let codigos: HashSet<&str> = ramos.iter().filter_map(|r| r.codigo.as_deref()).collect();
+    let mut requisitos_faltantes = Vec::new();
+    for r in ramos.iter() {
+        for req in r.requisitos.iter() {
+            let existe = match parse_id(req) {
+                Some(id) => ids.contains(&id),
+                None => codigos.contains(req.to_uppercase().as_str()),
+            };
+            if !existe {
+                requisitos_faltantes.push(RequisitoFaltante { fila: r.fila, ramo: r.nombre.clone(), requisito: req.clone() });
+            }
+        }
+    }
+
+    let sin_oferta = match oferta {
+        Some((_, secciones)) => {
+            let codigos_oferta: HashSet<String> = secciones.iter().map(|s| s.codigo.trim().to_uppercase()).collect();
+            let nombres_oferta: HashSet<String> = secciones.iter().map(|s| normalize_name(&s.nombre)).collect();
+            ramos.iter()
+                .filter(|r| {
+                    let por_codigo = r.codigo.as_ref().map(|c| codigos_oferta.contains(c)).unwrap_or(false);
+                    !por_codigo && !nombres_oferta.contains(&normalize_name(&r.nombre))
+                })
+                .map(|r| RamoSinOferta { fila: r.fila, codigo: r.codigo.clone(), nombre: r.nombre.clone() })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    Ok(ReporteMalla {
+        archivo: ruta.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        libro,
+        hoja: nombre_hoja,
+        columnas,
+        filas_totales,
+        filas_leidas: ramos.len(),
+        filas_omitidas: omitidas,
+        requisitos_faltantes,
+        duplicados,
+        oferta: oferta.map(|(nombre, _)| nombre.to_string()),
+        sin_oferta,
+        valido: errores.is_empty(),
+        errores,
+    })
+}
@@ -0,0 +1,130 @@
+// ics.rs - Exporta una solución como calendario iCalendar (RFC 5545)
+//
+// Un VEVENT por bloque de clases, con recurrencia semanal desde la primera
+// ocurrencia del día en o después de `fecha_inicio`. La recurrencia termina en
+// `fecha_fin` (UNTIL) o tras `semanas` repeticiones (COUNT).
+//
+// Las horas se escriben como hora local "flotante" (sin TZID ni Z): el
+// cliente de calendario las muestra en su zona horaria, que es lo esperado
+// para un horario presencial y evita incluir un VTIMEZONE.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use crate::models::horario::{bloques_de_seccion, Bloque};
+use crate::models::Seccion;
+
+/// Semanas por defecto de un semestre (si no se indica `fecha_fin`)
+pub const SEMANAS_POR_DEFECTO: u32 = 16;
+
+const PRODID: &str = "-//quickshift//Horario//ES";
+
+#[derive(Debug, Clone)]
+pub struct OpcionesIcs {
+    /// Primer día del semestre
+    pub fecha_inicio: NaiveDate,
+    /// Último día de clases (inclusive). Tiene prioridad sobre `semanas`.
+    pub fecha_fin: Option<NaiveDate>,
+    pub semanas: u32,
+    /// Nombre del calendario (X-WR-CALNAME)
+    pub nombre: Option<String>,
+    /// Marca de tiempo UTC para DTSTAMP
+    pub generado: NaiveDateTime,
+}
+
+/// Escapa TEXT según RFC 5545 §3.3.11
+fn escapar(texto: &str) -> String {
+    texto.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Pliega líneas de más de 75 octetos (RFC 5545 §3.1) sin cortar caracteres UTF-8
+fn plegar(linea: &str, out: &mut String) {
+    let mut largo = 0;
+    for ch in linea.chars() {
+        let n = ch.len_utf8();
+        if largo + n > 75 {
+            out.push_str("\r\n ");
+            largo = 1;
+        }
+        out.push(ch);
+        largo += n;
+    }
+    out.push_str("\r\n");
+}
+
+fn fecha_hora(fecha: NaiveDate, minutos: u32) -> String {
+    format!("{}T{:02}{:02}00", fecha.format("%Y%m%d"), minutos / 60, minutos % 60)
+}
+
+/// Primera fecha en o después de `desde` que cae en el día del bloque
+fn primera_ocurrencia(desde: NaiveDate, bloque: &Bloque) -> NaiveDate {
+    let actual = desde.weekday().num_days_from_monday();
+    let delta = (bloque.dia.indice() + 7 - actual) % 7;
+    desde + Duration::days(delta as i64)
+}
+
+/// Genera el contenido del archivo .ics. Los bloques sin horario reconocible se omiten.
+pub fn generar_ics(secciones: &[Seccion], opciones: &OpcionesIcs) -> String {
+    let mut lineas: Vec<String> = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+    if let Some(ref nombre) = opciones.nombre {
+        lineas.push(format!("X-WR-CALNAME:{}", escapar(nombre)));
+    }
+
+    let dtstamp = opciones.generado.format("%Y%m%dT%H%M%SZ").to_string();
+    let rrule = match opciones.fecha_fin {
+        Some(fin) => format!("RRULE:FREQ=WEEKLY;UNTIL={}", fecha_hora(fin, 23 * 60 + 59)),
+        None => format!("RRULE:FREQ=WEEKLY;COUNT={}", opciones.semanas.max(1)),
+    };
+
+    for s in secciones {
+        for b in bloques_de_seccion(s) {
+            let dia = primera_ocurrencia(opciones.fecha_inicio, &b);
+            if opciones.fecha_fin.map(|fin| dia > fin).unwrap_or(false) {
+                continue;
+            }
+            let id_seccion = if s.codigo_box.trim().is_empty() { format!("{}-{}", s.codigo, s.seccion) } else { s.codigo_box.clone() };
+            let uid: String = format!("{}-{}-{}-{}@quickshift", id_seccion, b.dia.codigo(), b.hora_inicio(), opciones.fecha_inicio.format("%Y%m%d"))
+                .chars()
+                .filter(|c| !c.is_whitespace() && *c != ':')
+                .collect();
+
+            let mut descripcion = Vec::new();
+            if !s.profesor.trim().is_empty() {
+                descripcion.push(format!("Profesor: {}", s.profesor.trim()));
+            }
+            if !s.seccion.trim().is_empty() {
+                descripcion.push(format!("Sección: {}", s.seccion.trim()));
+            }
+
+            lineas.push("BEGIN:VEVENT".to_string());
+            lineas.push(format!("UID:{}", uid));
+            lineas.push(format!("DTSTAMP:{}", dtstamp));
+            lineas.push(format!("DTSTART:{}", fecha_hora(dia, b.inicio)));
+            lineas.push(format!("DTEND:{}", fecha_hora(dia, b.fin)));
+            lineas.push(rrule.clone());
+            lineas.push(format!("SUMMARY:{}", escapar(format!("{} {}", s.codigo, s.nombre).trim())));
+            if let Some(ref sala) = s.sala {
+                lineas.push(format!("LOCATION:{}", escapar(sala)));
+            }
+            if !descripcion.is_empty() {
+                lineas.push(format!("DESCRIPTION:{}", escapar(&descripcion.join("\n"))));
+            }
+            lineas.push("END:VEVENT".to_string());
+        }
+    }
+    lineas.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for l in lineas.iter() {
+        plegar(l, &mut out);
+    }
+    out
+}
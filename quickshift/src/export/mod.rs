@@ -0,0 +1,28 @@
+// Exportación de una solución a formatos externos.
+//
+// Los exportadores trabajan con los bloques estructurados de
+// `models::horario` (día de la semana + rango en minutos), que se reexportan
+// aquí con sus nombres anteriores.
+//   - `ics`: calendario iCalendar (RFC 5545) con eventos semanales. Usa
+//     chrono, por eso solo existe con la feature `server`.
+//   - `xlsx`: reporte de las soluciones recomendadas (grilla, ramos y desglose
+//     del score), con la feature `export-xlsx`.
+//   - `pdf`: horario para imprimir (grilla y resumen de cada solución), con la
+//     feature `export-pdf`.
+
+#[cfg(feature = "server")]
+pub mod ics;
+#[cfg(feature = "export-xlsx")]
+pub mod xlsx;
+#[cfg(feature = "export-pdf")]
+pub mod pdf;
+
+pub use crate::models::horario::{bloques_de_seccion, formatear_hora, parse as parse_horario, Bloque as BloqueHorario, Dia};
+
+/// Una solución de `/solve` con su desglose, para los reportes `xlsx` y `pdf`
+#[derive(Debug, Clone, Copy)]
+pub struct SolucionReporte<'a> {
+    pub secciones: &'a [crate::models::Seccion],
+    pub total_score: i64,
+    pub desglose: &'a crate::algorithm::desglose::DesgloseScore,
+}
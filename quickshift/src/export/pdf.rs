@@ -0,0 +1,239 @@
+// pdf.rs - Horario en PDF para imprimir
+//
+// Una página A4 apaisada por solución con la grilla semanal
+// (`models::ScheduleGrid`) y debajo un resumen: los ramos con sección,
+// profesor, horario, sala y probabilidad de aprobar, el score y los filtros
+// relajados. Si el resumen no cabe sigue en otra página. Usa las fuentes
+// estándar de PDF (Helvetica, codificación Windows-1252), así que no embebe
+// fuentes; los textos que no caben en su columna se recortan con "…".
+// `POST /solve/export/pdf` lo devuelve como descarga.
+
+use printpdf::path::PaintMode;
+use printpdf::{BuiltinFont, Color, Error, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Rect, Rgb};
+use crate::export::SolucionReporte;
+use crate::models::{CeldaGrid, ScheduleGrid};
+
+/// Nombre de archivo de la descarga
+pub const NOMBRE_ARCHIVO: &str = "horario.pdf";
+
+// Medidas en mm: A4 apaisada
+const ANCHO: f32 = 297.0;
+const ALTO: f32 = 210.0;
+const MARGEN: f32 = 12.0;
+/// Alto máximo de la grilla; con muchas franjas las filas se achican
+const ALTO_GRILLA: f32 = 112.0;
+const ALTO_FILA_MAX: f32 = 10.0;
+const ANCHO_HORA: f32 = 22.0;
+const ALTO_LINEA: f32 = 4.6;
+/// Puntos por mm
+const PT_POR_MM: f32 = 72.0 / 25.4;
+
+/// (encabezado, ancho) de las columnas del resumen; suman el ancho útil
+const COLUMNAS_RESUMEN: [(&str, f32); 6] = [
+    ("Curso", 28.0),
+    ("Ramo", 78.0),
+    ("Profesor", 60.0),
+    ("Horario", 67.0),
+    ("Sala", 20.0),
+    ("Prob. aprobar", 20.0),
+];
+
+fn gris(nivel: f32) -> Color {
+    Color::Rgb(Rgb::new(nivel, nivel, nivel, None))
+}
+
+/// Caracteres que caben en `ancho` mm a `tamano` pt (ancho medio de Helvetica)
+fn caben(ancho: f32, tamano: f32) -> usize {
+    (ancho * PT_POR_MM / (tamano * 0.52)).max(1.0) as usize
+}
+
+/// `texto` recortado a `max` caracteres, terminando en "…" si se cortó
+fn recortar(texto: &str, max: usize) -> String {
+    if texto.chars().count() <= max {
+        return texto.to_string();
+    }
+    let mut out: String = texto.chars().take(max.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
+/// Página en construcción; `y` baja desde el borde superior
+struct Pagina {
+    capa: PdfLayerReference,
+    normal: IndirectFontRef,
+    negrita: IndirectFontRef,
+    y: f32,
+}
+
+impl Pagina {
+    fn nueva(doc: &PdfDocumentReference, normal: &IndirectFontRef, negrita: &IndirectFontRef, nombre: &str) -> Self {
+        let (pagina, capa) = doc.add_page(Mm(ANCHO), Mm(ALTO), nombre);
+        Pagina {
+            capa: doc.get_page(pagina).get_layer(capa),
+            normal: normal.clone(),
+            negrita: negrita.clone(),
+            y: ALTO - MARGEN,
+        }
+    }
+
+    fn texto(&self, texto: &str, tamano: f32, x: f32, y: f32, negrita: bool) {
+        let fuente = if negrita { &self.negrita } else { &self.normal };
+        self.capa.set_fill_color(gris(0.0));
+        self.capa.use_text(texto, tamano, Mm(x), Mm(y), fuente);
+    }
+
+    /// Rectángulo con esquina superior izquierda (x, y)
+    fn rect(&self, x: f32, y: f32, ancho: f32, alto: f32, relleno: Option<Color>) {
+        let r = Rect::new(Mm(x), Mm(y - alto), Mm(x + ancho), Mm(y));
+        if let Some(color) = relleno {
+            self.capa.set_fill_color(color);
+            self.capa.add_rect(r.with_mode(PaintMode::Fill));
+        }
+        self.capa.set_outline_color(gris(0.55));
+        self.capa.set_outline_thickness(0.4);
+        self.capa.add_rect(r.with_mode(PaintMode::Stroke));
+    }
+}
+
+/// Líneas de una celda de la grilla: "CIT2107-1 Bases de Datos" por clase
+fn lineas_celda(clases: &[CeldaGrid], ancho: f32, tamano: f32, max_lineas: usize) -> Vec<String> {
+    let max = caben(ancho - 2.0, tamano);
+    let mut lineas: Vec<String> = clases.iter()
+        .map(|c| recortar(&format!("{}-{} {}", c.codigo, c.seccion, c.nombre), max))
+        .collect();
+    if lineas.len() > max_lineas {
+        lineas.truncate(max_lineas.max(1));
+        if let Some(ultima) = lineas.last_mut() {
+            *ultima = recortar(&format!("{} (+{})", ultima, clases.len() - max_lineas.max(1)), max);
+        }
+    }
+    lineas
+}
+
+fn dibujar_grilla(p: &mut Pagina, grilla: &ScheduleGrid) {
+    let ancho_dia = (ANCHO - 2.0 * MARGEN - ANCHO_HORA) / grilla.dias.len().max(1) as f32;
+    let alto_fila = (ALTO_GRILLA / grilla.franjas.len().max(1) as f32).min(ALTO_FILA_MAX);
+    let tamano = if alto_fila >= 7.0 { 6.5 } else { 5.5 };
+    let alto_texto = tamano / PT_POR_MM + 0.6;
+    let max_lineas = ((alto_fila - 1.0) / alto_texto).floor().max(1.0) as usize;
+
+    let alto_encabezado = 6.0;
+    p.rect(MARGEN, p.y, ANCHO_HORA, alto_encabezado, Some(gris(0.85)));
+    p.texto("Hora", 8.0, MARGEN + 1.5, p.y - 4.2, true);
+    for (i, dia) in grilla.dias.iter().enumerate() {
+        let x = MARGEN + ANCHO_HORA + i as f32 * ancho_dia;
+        p.rect(x, p.y, ancho_dia, alto_encabezado, Some(gris(0.85)));
+        p.texto(dia.codigo(), 8.0, x + ancho_dia / 2.0 - 2.5, p.y - 4.2, true);
+    }
+    p.y -= alto_encabezado;
+
+    for (franja, celdas) in grilla.franjas.iter().zip(&grilla.celdas) {
+        p.rect(MARGEN, p.y, ANCHO_HORA, alto_fila, None);
+        p.texto(&format!("{}-{}", franja.inicio, franja.fin), tamano, MARGEN + 1.5, p.y - alto_texto, false);
+        for (i, clases) in celdas.iter().enumerate() {
+            let x = MARGEN + ANCHO_HORA + i as f32 * ancho_dia;
+            let relleno = (!clases.is_empty()).then(|| Color::Rgb(Rgb::new(0.85, 0.9, 0.97, None)));
+            p.rect(x, p.y, ancho_dia, alto_fila, relleno);
+            for (l, linea) in lineas_celda(clases, ancho_dia, tamano, max_lineas).iter().enumerate() {
+                p.texto(linea, tamano, x + 1.0, p.y - alto_texto * (l + 1) as f32, false);
+            }
+        }
+        p.y -= alto_fila;
+    }
+
+    if !grilla.sin_horario.is_empty() {
+        p.y -= ALTO_LINEA;
+        let ramos: Vec<String> = grilla.sin_horario.iter().map(|c| format!("{}-{} {}", c.codigo, c.seccion, c.nombre)).collect();
+        let texto = format!("Sin horario: {}", ramos.join("; "));
+        p.texto(&recortar(&texto, caben(ANCHO - 2.0 * MARGEN, 7.0)), 7.0, MARGEN, p.y, false);
+    }
+}
+
+fn encabezado_resumen(p: &mut Pagina) {
+    let mut x = MARGEN;
+    for (nombre, ancho) in COLUMNAS_RESUMEN {
+        p.rect(x, p.y, ancho, ALTO_LINEA + 0.4, Some(gris(0.85)));
+        p.texto(nombre, 7.5, x + 1.0, p.y - 3.5, true);
+        x += ancho;
+    }
+    p.y -= ALTO_LINEA + 0.4;
+}
+
+fn dibujar_resumen(doc: &PdfDocumentReference, mut p: Pagina, titulo: &str, sol: &SolucionReporte) {
+    p.y -= 8.0;
+    p.texto("Resumen", 11.0, MARGEN, p.y, true);
+    p.y -= 3.0;
+    encabezado_resumen(&mut p);
+
+    for s in sol.secciones {
+        if p.y - ALTO_LINEA < MARGEN {
+            p = Pagina::nueva(doc, &p.normal, &p.negrita, titulo);
+            p.texto(&format!("{} (continuación)", titulo), 11.0, MARGEN, p.y - 4.0, true);
+            p.y -= 8.0;
+            encabezado_resumen(&mut p);
+        }
+        let probabilidad = sol.desglose.dificultad.iter()
+            .find(|d| d.codigo.eq_ignore_ascii_case(&s.codigo))
+            .map(|d| format!("{:.0}%", d.probabilidad_aprobar * 100.0))
+            .unwrap_or_default();
+        let horario = s.horario.join(", ");
+        let celdas = [
+            format!("{}-{}", s.codigo, s.seccion),
+            s.nombre.clone(),
+            s.profesor.clone(),
+            horario,
+            s.sala.clone().unwrap_or_default(),
+            probabilidad,
+        ];
+        let mut x = MARGEN;
+        for ((_, ancho), texto) in COLUMNAS_RESUMEN.iter().zip(&celdas) {
+            p.rect(x, p.y, *ancho, ALTO_LINEA, None);
+            p.texto(&recortar(texto, caben(*ancho - 2.0, 7.0)), 7.0, x + 1.0, p.y - 3.3, false);
+            x += ancho;
+        }
+        p.y -= ALTO_LINEA;
+    }
+
+    let d = sol.desglose;
+    let mut partes = vec![format!("prioridad_base {}", d.prioridad_base)];
+    partes.extend(d.modificadores.partes().into_iter().filter(|(_, v)| *v != 0).map(|(n, v)| format!("{} {}", n, v)));
+    let mut lineas = vec![format!("Score total: {} ({})", sol.total_score, partes.join(", "))];
+    if !d.relajaciones.is_empty() {
+        lineas.push(format!("Filtros relajados: {}", d.relajaciones.join(", ")));
+    }
+    for linea in lineas {
+        if p.y - 2.0 * ALTO_LINEA < MARGEN {
+            p = Pagina::nueva(doc, &p.normal, &p.negrita, titulo);
+        }
+        p.y -= ALTO_LINEA + 1.0;
+        p.texto(&recortar(&linea, caben(ANCHO - 2.0 * MARGEN, 8.0)), 8.0, MARGEN, p.y, false);
+    }
+}
+
+/// PDF con una página por solución (más las que necesite su resumen), en el
+/// orden recibido. Sin soluciones queda una página que lo indica.
+pub fn generar_pdf(soluciones: &[SolucionReporte]) -> Result<Vec<u8>, Error> {
+    let (doc, pagina, capa) = PdfDocument::new("Horario recomendado", Mm(ANCHO), Mm(ALTO), "Solución 1");
+    let normal = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let negrita = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+    let primera = Pagina { capa: doc.get_page(pagina).get_layer(capa), normal: normal.clone(), negrita: negrita.clone(), y: ALTO - MARGEN };
+
+    if soluciones.is_empty() {
+        primera.texto("No se encontraron soluciones", 14.0, MARGEN, ALTO - MARGEN - 6.0, true);
+        return doc.save_to_bytes();
+    }
+
+    let mut siguiente = Some(primera);
+    for (i, sol) in soluciones.iter().enumerate() {
+        let titulo = format!("Solución {}", i + 1);
+        let mut p = siguiente.take().unwrap_or_else(|| Pagina::nueva(&doc, &normal, &negrita, &titulo));
+        p.y -= 6.0;
+        p.texto(&format!("Horario recomendado - {}", titulo), 14.0, MARGEN, p.y, true);
+        p.y -= 6.0;
+        p.texto(&format!("Score total: {}  ·  {} secciones", sol.total_score, sol.secciones.len()), 9.0, MARGEN, p.y, false);
+        p.y -= 5.0;
+        dibujar_grilla(&mut p, &ScheduleGrid::from_secciones(sol.secciones));
+        dibujar_resumen(&doc, p, &titulo, sol);
+    }
+    doc.save_to_bytes()
+}
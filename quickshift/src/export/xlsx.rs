@@ -0,0 +1,160 @@
+// xlsx.rs - Reporte XLSX de las soluciones recomendadas
+//
+// Una hoja por solución ("Solución 1", "Solución 2", ...) con tres bloques:
+//   - la grilla semanal (`models::ScheduleGrid`): una fila por franja y una
+//     columna por día, con código, sección y nombre de cada clase;
+//   - los ramos: código, nombre, sección, profesor, horario, sala y la
+//     dificultad esperada (aprobación histórica y probabilidad de aprobar);
+//   - el desglose del score (`algorithm::desglose`): prioridad base, aporte
+//     de cada modificador, total y filtros relajados.
+// El workbook se arma en memoria; `POST /solve/export/xlsx` lo devuelve como
+// descarga.
+
+use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, Workbook, Worksheet, XlsxError};
+use crate::algorithm::desglose::DesgloseScore;
+use crate::export::SolucionReporte;
+use crate::models::{ScheduleGrid, Seccion};
+
+/// Nombre de archivo de la descarga
+pub const NOMBRE_ARCHIVO: &str = "horario.xlsx";
+
+/// Ancho (en caracteres) de las columnas de días de la grilla
+const ANCHO_DIA: f64 = 24.0;
+
+struct Formatos {
+    titulo: Format,
+    encabezado: Format,
+    celda: Format,
+    porcentaje: Format,
+    numero: Format,
+    total: Format,
+}
+
+impl Formatos {
+    fn new() -> Self {
+        let borde = Format::new().set_border(FormatBorder::Thin);
+        Formatos {
+            titulo: Format::new().set_bold().set_font_size(14),
+            encabezado: borde.clone().set_bold().set_background_color(Color::RGB(0xD9E1F2)).set_align(FormatAlign::Center),
+            celda: borde.clone().set_text_wrap().set_align(FormatAlign::Top),
+            porcentaje: borde.clone().set_num_format("0.0%"),
+            numero: borde.clone().set_num_format("#,##0"),
+            total: borde.set_bold().set_num_format("#,##0"),
+        }
+    }
+}
+
+/// Texto de una celda de la grilla: "CIT2107-1 Bases de Datos" por clase
+fn texto_celda(celdas: &[crate::models::CeldaGrid]) -> String {
+    celdas.iter()
+        .map(|c| format!("{}-{} {}", c.codigo, c.seccion, c.nombre))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escribe la grilla desde `fila`; devuelve la primera fila libre
+fn escribir_grilla(hoja: &mut Worksheet, f: &Formatos, secciones: &[Seccion], mut fila: u32) -> Result<u32, XlsxError> {
+    let grilla = ScheduleGrid::from_secciones(secciones);
+    hoja.write_string_with_format(fila, 0, "Horario semanal", &f.titulo)?;
+    fila += 1;
+    hoja.write_string_with_format(fila, 0, "Hora", &f.encabezado)?;
+    for (col, dia) in grilla.dias.iter().enumerate() {
+        hoja.write_string_with_format(fila, col as u16 + 1, dia.codigo(), &f.encabezado)?;
+        hoja.set_column_width(col as u16 + 1, ANCHO_DIA)?;
+    }
+    fila += 1;
+    for (franja, celdas) in grilla.franjas.iter().zip(&grilla.celdas) {
+        hoja.write_string_with_format(fila, 0, format!("{}-{}", franja.inicio, franja.fin), &f.celda)?;
+        for (col, clases) in celdas.iter().enumerate() {
+            hoja.write_string_with_format(fila, col as u16 + 1, texto_celda(clases), &f.celda)?;
+        }
+        fila += 1;
+    }
+    if !grilla.sin_horario.is_empty() {
+        hoja.write_string(fila, 0, "Sin horario")?;
+        hoja.write_string(fila, 1, texto_celda(&grilla.sin_horario).replace('\n', "; "))?;
+        fila += 1;
+    }
+    Ok(fila + 1)
+}
+
+/// Escribe la lista de ramos desde `fila`; devuelve la primera fila libre
+fn escribir_ramos(hoja: &mut Worksheet, f: &Formatos, sol: &SolucionReporte, mut fila: u32) -> Result<u32, XlsxError> {
+    const COLUMNAS: [&str; 8] = ["Código", "Ramo", "Sección", "Profesor", "Horario", "Sala", "Aprobación histórica", "Prob. de aprobar"];
+    hoja.write_string_with_format(fila, 0, "Ramos", &f.titulo)?;
+    fila += 1;
+    for (col, nombre) in COLUMNAS.iter().enumerate() {
+        hoja.write_string_with_format(fila, col as u16, *nombre, &f.encabezado)?;
+    }
+    fila += 1;
+    for s in sol.secciones {
+        let dificultad = sol.desglose.dificultad.iter().find(|d| d.codigo.eq_ignore_ascii_case(&s.codigo));
+        let horario = s.horario.join(", ");
+        let textos = [&s.codigo, &s.nombre, &s.seccion, &s.profesor, &horario, s.sala.as_deref().unwrap_or("")];
+        for (col, texto) in textos.into_iter().enumerate() {
+            hoja.write_string_with_format(fila, col as u16, texto, &f.celda)?;
+        }
+        match dificultad.and_then(|d| d.porcentaje_aprobacion) {
+            // El archivo de porcentajes trae 0-100
+            Some(pct) => hoja.write_number_with_format(fila, 6, pct / 100.0, &f.porcentaje)?,
+            None => hoja.write_string_with_format(fila, 6, "", &f.celda)?,
+        };
+        match dificultad {
+            Some(d) => hoja.write_number_with_format(fila, 7, d.probabilidad_aprobar, &f.porcentaje)?,
+            None => hoja.write_string_with_format(fila, 7, "", &f.celda)?,
+        };
+        fila += 1;
+    }
+    Ok(fila + 1)
+}
+
+/// Escribe el desglose del score desde `fila`; devuelve la primera fila libre
+fn escribir_desglose(hoja: &mut Worksheet, f: &Formatos, desglose: &DesgloseScore, mut fila: u32) -> Result<u32, XlsxError> {
+    hoja.write_string_with_format(fila, 0, "Desglose del score", &f.titulo)?;
+    fila += 1;
+    hoja.write_string_with_format(fila, 0, "Componente", &f.encabezado)?;
+    hoja.write_string_with_format(fila, 1, "Aporte", &f.encabezado)?;
+    fila += 1;
+    let partes = std::iter::once(("prioridad_base", desglose.prioridad_base))
+        .chain(desglose.modificadores.partes().into_iter().filter(|(_, v)| *v != 0));
+    for (nombre, valor) in partes {
+        hoja.write_string_with_format(fila, 0, nombre, &f.celda)?;
+        hoja.write_number_with_format(fila, 1, valor as f64, &f.numero)?;
+        fila += 1;
+    }
+    hoja.write_string_with_format(fila, 0, "total", &f.total)?;
+    hoja.write_number_with_format(fila, 1, desglose.total as f64, &f.total)?;
+    fila += 1;
+    if !desglose.relajaciones.is_empty() {
+        hoja.write_string(fila, 0, "Filtros relajados")?;
+        hoja.write_string(fila, 1, desglose.relajaciones.join(", "))?;
+        fila += 1;
+    }
+    Ok(fila)
+}
+
+fn escribir_hoja(hoja: &mut Worksheet, f: &Formatos, indice: usize, sol: &SolucionReporte) -> Result<(), XlsxError> {
+    hoja.set_name(format!("Solución {}", indice + 1))?;
+    hoja.set_column_width(0, 14)?;
+    hoja.write_string_with_format(0, 0, format!("Solución {}", indice + 1), &f.titulo)?;
+    hoja.write_string(1, 0, "Score total")?;
+    hoja.write_number_with_format(1, 1, sol.total_score as f64, &f.numero)?;
+    let fila = escribir_grilla(hoja, f, sol.secciones, 3)?;
+    let fila = escribir_ramos(hoja, f, sol, fila)?;
+    escribir_desglose(hoja, f, sol.desglose, fila)?;
+    Ok(())
+}
+
+/// Workbook con una hoja por solución, en el orden recibido. Sin soluciones
+/// queda una hoja que lo indica (un XLSX necesita al menos una).
+pub fn generar_xlsx(soluciones: &[SolucionReporte]) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let formatos = Formatos::new();
+    for (i, sol) in soluciones.iter().enumerate() {
+        escribir_hoja(workbook.add_worksheet(), &formatos, i, sol)?;
+    }
+    if soluciones.is_empty() {
+        workbook.add_worksheet().set_name("Sin soluciones")?.write_string(0, 0, "No se encontraron soluciones")?;
+    }
+    workbook.save_to_buffer()
+}
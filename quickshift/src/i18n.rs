@@ -0,0 +1,147 @@
+// i18n.rs - Textos de las respuestas en español e inglés
+//
+// El cliente elige el idioma con `?lang=en` o `Accept-Language` (ver
+// `middleware::idioma`). Los textos para humanos de las respuestas (`/help`,
+// el `message` de los errores, el `resumen` de `/solve/explain`) salen de
+// `MENSAJES`, con `{}` donde van los argumentos. Los `code` de error, los
+// nombres de campos y los datos (códigos de ramo, horarios) no se traducen.
+//
+// Sin idioma pedido (ni `DEFAULT_LANG`) las respuestas quedan como antes:
+// errores en inglés y `/help` en español.
+
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Idioma de las respuestas
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Idioma {
+    #[default]
+    Es,
+    En,
+}
+
+impl Idioma {
+    /// "es", "en", "en-US", "es_CL", "english", "español", ... (sin distinguir mayúsculas)
+    pub fn desde_codigo(codigo: &str) -> Option<Idioma> {
+        let c = codigo.trim().to_lowercase();
+        let base = c.split(['-', '_']).next().unwrap_or("");
+        match base {
+            "es" | "spa" | "español" | "espanol" | "spanish" => Some(Idioma::Es),
+            "en" | "eng" | "english" | "inglés" | "ingles" => Some(Idioma::En),
+            _ => None,
+        }
+    }
+
+    /// Primer idioma soportado de un `Accept-Language` ("en-US,en;q=0.9,es;q=0.8"),
+    /// respetando los pesos `q`
+    pub fn desde_accept_language(valor: &str) -> Option<Idioma> {
+        let mut opciones: Vec<(f32, Idioma)> = valor.split(',')
+            .filter_map(|parte| {
+                let mut campos = parte.split(';');
+                let idioma = Idioma::desde_codigo(campos.next()?)?;
+                let q = campos
+                    .find_map(|c| c.trim().strip_prefix("q=").and_then(|q| q.trim().parse::<f32>().ok()))
+                    .unwrap_or(1.0);
+                (q > 0.0).then_some((q, idioma))
+            })
+            .collect();
+        // Orden estable: a igual peso gana el que aparece primero
+        opciones.sort_by(|a, b| b.0.total_cmp(&a.0));
+        opciones.first().map(|(_, i)| *i)
+    }
+
+    /// Código de dos letras ("es", "en"), el de `Content-Language`
+    pub fn codigo(&self) -> &'static str {
+        match self {
+            Idioma::Es => "es",
+            Idioma::En => "en",
+        }
+    }
+
+    /// Idioma de `DEFAULT_LANG`, si está definida y es válida
+    pub fn configurado() -> Option<Idioma> {
+        crate::app_config::actual().default_lang.as_deref().and_then(Idioma::desde_codigo)
+    }
+}
+
+/// Catálogo: (clave, español, inglés)
+const MENSAJES: &[(&str, &str, &str)] = &[
+    // Errores (`ApiError`); el texto en inglés es el mismo de su `Display`
+    ("error.invalid_body", "body JSON inválido: {}", "invalid JSON body: {}"),
+    ("error.invalid_input", "entrada inválida: {}", "invalid input: {}"),
+    ("error.missing_parameter", "falta el parámetro '{}'", "missing parameter '{}'"),
+    ("error.unauthorized", "no autorizado: {}", "unauthorized: {}"),
+    ("error.malla_not_found", "malla '{}' no encontrada", "malla '{}' not found"),
+    ("error.student_not_found", "estudiante '{}' no encontrado", "student '{}' not found"),
+    ("error.professor_not_found", "profesor '{}' no encontrado", "professor '{}' not found"),
+    ("error.scenario_not_found", "escenario no encontrado", "scenario not found"),
+    ("error.session_not_found", "sesión '{}' no encontrada", "session '{}' not found"),
+    ("error.session_expired", "sesión '{}' expirada", "session '{}' expired"),
+    ("error.solution_not_found", "solución '{}' no encontrada", "solution '{}' not found"),
+    ("error.job_not_found", "job '{}' no encontrado", "job '{}' not found"),
+    ("error.job_not_finished", "el job '{}' todavía no termina", "job '{}' has not finished"),
+    ("error.file_not_found", "archivo '{}' no encontrado", "file '{}' not found"),
+    ("error.no_feasible_schedule", "no hay un horario factible para los datos enviados", "no feasible schedule for the given input"),
+    ("error.pinned_sections_infeasible", "las secciones fijas no se pueden inscribir juntas", "pinned sections cannot be scheduled together"),
+    ("error.required_courses_infeasible", "los ramos obligatorios no se pueden inscribir juntos", "required courses cannot be scheduled together"),
+    ("error.daily_limits_infeasible", "ningún horario cumple el tope de ramos por día y el rango horario", "no schedule satisfies the daily course cap and class time window"),
+    ("error.rate_limited", "límite de solicitudes excedido", "rate limit exceeded"),
+    ("error.solver", "error del solver: {}", "solver error: {}"),
+    ("error.storage", "error de almacenamiento: {}", "storage error: {}"),
+    ("error.internal", "error interno: {}", "internal error: {}"),
+    // GET /help
+    ("help.description",
+        "API para obtener soluciones de horario. POST /solve acepta un JSON complejo (ver 'example') y soporta resolución de nombres usando 'malla'. GET /solve acepta parámetros simples en query (listas separadas por comas).",
+        "API to build timetable solutions. POST /solve accepts a full JSON body (see 'example') and resolves course names using 'malla'. GET /solve accepts simple query parameters (comma-separated lists)."),
+    ("help.note",
+        "GET es una versión ligera: los parámetros son listas separadas por comas. Para JSON complejo o datos privados use POST con body JSON.",
+        "GET is a lightweight version: parameters are comma-separated lists. For complex JSON or private data use POST with a JSON body."),
+    ("help.note_file_reference",
+        "#file:OfertaAcademica2024.xlsx (fila/col 'Asignatura')",
+        "#file:OfertaAcademica2024.xlsx (row/column 'Asignatura')"),
+    ("help.note_horarios",
+        "Los horarios aceptan días en español o inglés (LU/MON, MA/TUE, ...) y horas de 24 o 12 horas (14:30, 2:30 PM); se normalizan a 'LU 14:30-15:50'.",
+        "Schedules accept Spanish or English days (LU/MON, MA/TUE, ...) and 24- or 12-hour times (14:30, 2:30 PM); they are normalized to 'LU 14:30-15:50'."),
+    ("help.note_lang",
+        "Idioma de las respuestas: ?lang=es|en o Accept-Language.",
+        "Response language: ?lang=es|en or Accept-Language."),
+    // Resumen de POST /solve/explain
+    ("explain.en_mejor_solucion", "{} está en la mejor solución", "{} is in the best solution"),
+    ("explain.ya_aprobado", "{} ya está aprobado", "{} is already passed"),
+    ("explain.no_encontrado", "{} no está en la malla ni en la oferta", "{} is not in the curriculum or the course offering"),
+    ("explain.prerequisitos_faltantes", "a {} le faltan prerequisitos: {}", "{} is missing prerequisites: {}"),
+    ("explain.sin_secciones", "{} no tiene secciones en la oferta", "{} has no sections in the course offering"),
+    ("explain.secciones_filtradas", "todas las secciones de {} quedan fuera por los filtros ({})", "all sections of {} are excluded by the filters ({})"),
+    ("explain.conflicto_horario", "todas las secciones de {} chocan con la mejor solución", "every section of {} clashes with the best solution"),
+    ("explain.desplazado", "{}: {}", "{}: other combinations without it score higher"),
+];
+
+/// Texto de `clave` en `idioma`; la clave misma si no está en el catálogo
+pub fn t(clave: &str, idioma: Idioma) -> &str {
+    MENSAJES.iter()
+        .find(|(c, _, _)| *c == clave)
+        .map(|(_, es, en)| match idioma {
+            Idioma::Es => *es,
+            Idioma::En => *en,
+        })
+        .unwrap_or(clave)
+}
+
+/// `t` con cada `{}` reemplazado, en orden, por `args` (los que sobran se ignoran)
+pub fn tf(clave: &str, idioma: Idioma, args: &[&dyn Display]) -> String {
+    let plantilla = t(clave, idioma);
+    let mut out = String::with_capacity(plantilla.len());
+    let mut args = args.iter();
+    let mut partes = plantilla.split("{}").peekable();
+    while let Some(parte) = partes.next() {
+        out.push_str(parte);
+        if partes.peek().is_some() {
+            match args.next() {
+                Some(a) => out.push_str(&a.to_string()),
+                None => out.push_str("{}"),
+            }
+        }
+    }
+    out
+}
@@ -1,14 +1,33 @@
 // Biblioteca raíz del crate `quickshift`.
 // Reexporta los módulos principales y proporciona una función de conveniencia
 // `run_ruta_critica` que orquesta el flujo principal.
+pub mod app_config;
 pub mod excel;
 pub mod algorithm;
 pub mod models;
 pub mod api_json;
+pub mod export;
+pub mod datafiles;
+pub mod carreras;
+pub mod reglas;
+pub mod scoring;
+pub mod api_error;
+pub mod i18n;
+pub mod metrics;
+// Módulos que dependen de actix/tokio/bases de datos: solo con la feature `server`.
+// Sin ella el crate queda como núcleo puro del algoritmo (ver `algorithm::en_memoria`).
+#[cfg(feature = "server")]
 pub mod server;
+#[cfg(feature = "server")]
 pub mod server_handlers;
+#[cfg(feature = "server")]
+pub mod middleware;
+#[cfg(feature = "server")]
 pub mod analithics;
+#[cfg(feature = "server")]
+pub mod cli;
 
 /// Ejecuta el servidor HTTP (reexport para facilitar uso desde `main`)
+#[cfg(feature = "server")]
 pub use server::run_server;
 
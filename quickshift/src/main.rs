@@ -1,22 +1,124 @@
 // --- Sistema Generador de Horarios - Archivo principal ---
+//
+// Sin subcomando (o con `serve`) levanta la API. `solve`, `validate-datafiles`
+// y `pert` corren sin servidor e imprimen JSON (ver `quickshift::cli`).
 
+use clap::{Parser, Subcommand};
 use quickshift::run_server;
-use std::env;
-use quickshift::algorithm::extract_controller; // <-- agregado
+use std::path::PathBuf;
+use std::process::ExitCode;
+use quickshift::excel::config::{self, DatafilesConfig, OrigenDatafiles};
+use quickshift::app_config::{self, AppConfig};
+
+#[derive(Parser)]
+#[command(name = "quickshift", version, about = "Sistema Generador de Horarios")]
+struct Cli {
+    /// Directorio de datafiles (en vez de GA_DATAFILES_DIR y la búsqueda habitual)
+    #[arg(long, global = true, value_name = "DIR")]
+    datafiles: Option<PathBuf>,
+    #[command(subcommand)]
+    comando: Option<Comando>,
+}
+
+#[derive(Subcommand)]
+enum Comando {
+    /// Levanta la API HTTP (comportamiento por defecto)
+    Serve {
+        /// Puerto; por defecto `port` de la configuración (PORT) o 8080
+        #[arg(long)]
+        port: Option<u16>,
+    },
+    /// Resuelve un JSON con el body de POST /solve sin levantar el servidor
+    Solve {
+        /// Archivo con los parámetros (InputParams); `-` lee stdin
+        #[arg(long, value_name = "PARAMS.json")]
+        input: PathBuf,
+        /// Archivo de salida; por defecto stdout
+        #[arg(long, value_name = "SOLUTIONS.json")]
+        out: Option<PathBuf>,
+    },
+    /// Valida las mallas, ofertas y porcentajes del directorio de datafiles
+    ValidateDatafiles {
+        /// Archivo de salida; por defecto stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Red PERT de una malla (ES/EF/LS/LF, holgura y ruta crítica)
+    Pert {
+        #[arg(long)]
+        malla: String,
+        /// Hoja interna de la malla
+        #[arg(long)]
+        sheet: Option<String>,
+        /// Archivo de salida; por defecto stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    // Logging: nivel por RUST_LOG (por defecto info), a stderr
+    quickshift::middleware::trace::init_logging();
+    // Configuración tipada (quickshift.toml + entorno); un valor inválido no arranca
+    match AppConfig::cargar() {
+        Ok(c) => {
+            app_config::establecer(c);
+        }
+        Err(e) => {
+            eprintln!("error: configuración inválida ({:?}): {}", app_config::ruta_archivo(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+    if let Some(dir) = cli.datafiles {
+        config::establecer(DatafilesConfig { dir, origen: OrigenDatafiles::LineaDeComandos });
+    }
+
+    let resultado: Result<bool, Box<dyn std::error::Error>> = match cli.comando.unwrap_or(Comando::Serve { port: None }) {
+        Comando::Serve { port } => serve(port).map(|_| true).map_err(Into::into),
+        Comando::Solve { input, out } => leer_entrada(&input)
+            .and_then(|json| quickshift::cli::solve_desde_json(&json))
+            .and_then(|salida| quickshift::cli::escribir_json(&salida, out.as_deref()))
+            .map(|_| true),
+        Comando::ValidateDatafiles { out } => quickshift::cli::validar_datafiles().and_then(|archivos| {
+            let validos = archivos.iter().all(|a| a.valido);
+            quickshift::cli::escribir_json(&archivos, out.as_deref()).map(|_| validos)
+        }),
+        Comando::Pert { malla, sheet, out } => quickshift::cli::pert(&malla, sheet.as_deref())
+            .and_then(|salida| quickshift::cli::escribir_json(&salida, out.as_deref()))
+            .map(|_| true),
+    };
+    match resultado {
+        Ok(true) => ExitCode::SUCCESS,
+        // `validate-datafiles` con algún archivo inválido: el reporte ya se escribió
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Contenido de `--input`; `-` es stdin
+fn leer_entrada(ruta: &std::path::Path) -> Result<String, Box<dyn std::error::Error>> {
+    if ruta.as_os_str() == "-" {
+        let mut texto = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut texto)?;
+        return Ok(texto);
+    }
+    std::fs::read_to_string(ruta).map_err(|e| format!("no se pudo leer {:?}: {}", ruta, e).into())
+}
 
 #[tokio::main]
-async fn main() -> std::io::Result<()> {
+async fn serve(port: Option<u16>) -> std::io::Result<()> {
     println!("=== Sistema Generador de Horarios (API) ===");
 
-    // Bind a 0.0.0.0 y puerto desde env PORT (Railway la expone)
-    let port: u16 = env::var("PORT").unwrap_or_else(|_| "8080".into()).parse().unwrap_or(8080);
+    // Bind a 0.0.0.0 y puerto desde --port o `port` de la configuración (env PORT, Railway la expone)
+    let config = app_config::actual();
+    let port: u16 = port.unwrap_or(config.port);
     let bind = format!("0.0.0.0:{}", port);
 
     println!("Iniciando servidor en http://{}", bind);
-    // Leer variable de entorno USE_OPTIMIZED (true/false). Por defecto true.
-    let use_opt = env::var("USE_OPTIMIZED").unwrap_or_else(|_| "true".into());
-    let use_opt_bool = matches!(use_opt.to_lowercase().as_str(), "1" | "true" | "yes" | "y");
-    extract_controller::set_use_optimized(use_opt_bool);
     println!("");
     println!("Endpoints disponibles:");
     println!("  POST /solve    - Body JSON. Ejemplo (use 'malla' y opcional 'sheet' para seleccionar hoja interna):");
@@ -30,14 +132,49 @@ async fn main() -> std::io::Result<()> {
 }"#);
     println!("  GET /solve     - Query params (comma-separated). Ejemplo:");
     println!("    /solve?ramos_pasados=CIT3313,CIT3211&ramos_prioritarios=CIT3413&horarios_preferidos=08:00-10:00&malla=MallaCurricular2020.xlsx&sheet=Malla%202020&email=alumno%40ejemplo.cl");
+    println!("  POST /solve/explain - Igual que POST /solve + \"ramo\"; explica por qué ese ramo no aparece en las soluciones");
+    println!("  POST /solve/precheck - Igual que POST /solve; cuenta ramos y secciones que sobreviven a cada filtro, sin resolver");
+    println!("  POST /solve?debug=graph - Igual que POST /solve; devuelve el grafo de compatibilidad (grados, componentes, aisladas) sin enumerar");
+    println!("  POST /solve/async - Igual que POST /solve; encola el cálculo y responde 202 con job_id");
+    println!("  GET /jobs/{{id}} - Estado y avance del job (secciones viables, cliques); GET /jobs/{{id}}/result - respuesta de /solve");
+    println!("  POST /solutions/compare - Body: {{\"a\": {{\"secciones\": [...]}} o {{\"solution_id\", \"indice\"}}, \"b\": ...}}; ramos y métricas que difieren");
+    println!("  POST /solve/export/ics - Body: {{\"secciones\": [...], \"fecha_inicio\"?: \"2026-03-09\", \"fecha_fin\"?, \"semanas\"?}}; devuelve un .ics semanal");
+    #[cfg(feature = "export-xlsx")]
+    println!("  POST /solve/export/xlsx - Igual que POST /solve; devuelve un .xlsx con una hoja por solución (grilla, ramos, desglose)");
+    #[cfg(feature = "export-pdf")]
+    println!("  POST /solve/export/pdf - Igual que POST /solve; devuelve un .pdf con la grilla y el resumen de cada solución");
+    println!("  POST /solve/session - Igual que POST /solve; devuelve session_id y cachea datos + grafo de compatibilidad");
+    println!("  POST /solve/session/{{id}}/refine - Body: {{ filtros?, optimizations?, ramos_prioritarios?, horarios_prohibidos? }}");
+    println!("  GET /ws/solve - WebSocket: start, add-passed-course, toggle-filter, pin-section; responde soluciones recalculadas");
     println!("{}", r#"  POST /rutacomoda/best - Body: { "file_path": "/path/to/paths.json" } o incluir 'paths' array"#);
+    println!("  POST /rutacomoda/rank - Body: {{\"soluciones\": [...], \"comodidad\": {{\"perfil\": \"pocos_dias\", \"pesos\"?}}}} o el de /solve + comodidad; reordena por comodidad");
     println!("  POST /rutacritica/run - Ejecuta el orquestador con body JSON (igual que POST /solve)");
     println!("  GET /datafiles - Lista archivos disponibles en src/datafiles");
-    println!("  GET /datafiles/content?malla=MiMalla.xlsx[&sheet=Hoja]");
+    println!("  GET /datafiles/content?malla=MiMalla.xlsx[&sheet=Hoja] - Con ETag: If-None-Match vigente responde 304");
     println!("      - Devuelve resumen de malla/oferta/porcentajes y lista de hojas internas de la malla");
+    println!("  GET /datafiles/merge?malla=MiMalla.xlsx[&formato=csv] - Tabla malla ↔ oferta ↔ porcentajes emparejada por nombre");
+    println!("  POST /datafiles/upload - multipart o JSON {{\"nombre\", \"contenido_base64\"}}; valida malla/oferta/porcentajes y lo deja disponible");
+    println!("  GET /datafiles/validate?malla=MC2020.xlsx[&sheet=Hoja][&oferta=OA20251.xlsx] - Reporte de validación de la malla (no modifica nada)");
+    println!("      - POST /datafiles/upload?dry_run=true entrega el mismo reporte sin guardar el archivo");
+    println!("  POST /datafiles/reload - Invalida el caché de mallas/ofertas/porcentajes (también se invalida solo al cambiar un archivo)");
     println!("  POST /students  - Guarda un perfil de estudiante (body JSON, se indexa por email)");
+    println!("  GET /students/{{email}}/bloqueantes - Ramos que inician la cadena de prerequisitos más larga");
+    println!("  POST /students/{{email}}/scenarios - Guarda un escenario con nombre (body: {{\"nombre\": \"...\", \"params\": {{...}}}})");
+    println!("  GET /students/{{email}}/scenarios[/{{nombre}}[?version=N]] - Lista escenarios o muestra uno con su historial");
+    println!("  GET /students/{{email}}/scenarios/diff?a=X&b=Y - Compara dos escenarios");
+    println!("  POST /students/{{email}}/scenarios/{{nombre}}/restore - Restaura una versión (body: {{\"version\": N}})");
+    println!("  GET /equivalencias?malla=MC2020.xlsx[&comparar=...] - Equivalencias de la malla: cadenas, ambiguas y duplicados entre mallas");
+    println!("  POST /equivalencias/resolve - Body: {{\"malla\", \"codigos\": [...]}}; lleva códigos históricos a la malla");
+    println!("  POST /progress - Body: {{\"malla\", \"ramos_pasados\", \"carrera\"?, \"max_ramos\"?}}; avance por semestre, ruta crítica y semestres restantes");
+    println!("  GET /malla/{{id}}/criticos[?sheet=Hoja] - Ramos por urgencia con su holgura; marca la ruta crítica");
+    println!("  POST /plan/carrera - Plan semestre a semestre hasta egresar (body de /solve + periodo_inicial?, max_semestres?)");
+    println!("  POST /admin/rollover - Cambio de semestre (body: {{\"periodo\": \"20252\", \"dry_run\": true}})");
+    println!("  GET /config - Configuración efectiva (quickshift.toml + entorno), sin secretos; requiere X-Admin-Token");
     println!("  GET /help       - Describe la API y muestra ejemplos en JSON");
+    println!("  GET /openapi.json - Documento OpenAPI de todos los endpoints (Swagger UI en /api-docs)");
     println!("");
     println!("Nota: GET /solve es una versión ligera (parametros por query). Para datos privados o estructuras complejas use POST /solve o POST /rutacritica/run con body JSON.");
+    println!("Nota: POST/GET /solve aceptan \"top_n\", \"offset\" y \"min_score\" para paginar las soluciones; la respuesta trae \"total_solutions\".");
+    println!("Nota: POST/GET /solve?format=grid agrega a cada solución \"grid\": matriz días × franjas con {{codigo, nombre, seccion, profesor}} por celda.");
     run_server(&bind).await
 }
@@ -0,0 +1,102 @@
+// metrics.rs - Métricas de rendimiento del solver (formato Prometheus)
+//
+// Histogramas en memoria del proceso, etiquetados por fase:
+//   quickshift_phase_duration_seconds   duración de cada fase
+//   quickshift_phase_sections           secciones que recibió la fase
+//   quickshift_enumerator_solutions     soluciones que devolvió cada enumerador
+//
+// Fases instrumentadas: `summarize_datafiles`, `adjacency` (matriz de
+// compatibilidad, también `CompatibilityGraph::build`) y los enumeradores
+// `greedy`, `exact`, `combinations`, `combinations_size_priority` y
+// `exhaustive` de `algorithm::clique`. No depende de la feature `server`;
+// `GET /metrics` sirve `render()`.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+pub const DURACION: &str = "quickshift_phase_duration_seconds";
+pub const SECCIONES: &str = "quickshift_phase_sections";
+pub const SOLUCIONES: &str = "quickshift_enumerator_solutions";
+
+/// (nombre, ayuda, límites superiores de los buckets)
+const METRICAS: [(&str, &str, &[f64]); 3] = [
+    (DURACION, "Duración de cada fase del solver", &[0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]),
+    (SECCIONES, "Secciones de entrada por fase", &[1.0, 10.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0]),
+    (SOLUCIONES, "Soluciones devueltas por enumerador", &[0.0, 1.0, 5.0, 10.0, 50.0, 100.0, 1000.0, 10000.0, 50000.0]),
+];
+
+#[derive(Debug, Clone, Default)]
+struct Histograma {
+    /// Conteo por bucket (no acumulado), mismo largo que los límites
+    buckets: Vec<u64>,
+    suma: f64,
+    total: u64,
+}
+
+type Registro = BTreeMap<(&'static str, String), Histograma>;
+
+fn registro() -> &'static Mutex<Registro> {
+    static REGISTRO: OnceLock<Mutex<Registro>> = OnceLock::new();
+    REGISTRO.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+fn limites(metrica: &str) -> &'static [f64] {
+    METRICAS.iter().find(|(n, _, _)| *n == metrica).map(|(_, _, l)| *l).unwrap_or(&[])
+}
+
+/// Registra `valor` en el histograma `metrica` (una de las constantes) para `fase`
+pub fn observar(metrica: &'static str, fase: &str, valor: f64) {
+    let limites = limites(metrica);
+    let mut reg = registro().lock().unwrap_or_else(|e| e.into_inner());
+    let h = reg.entry((metrica, fase.to_string())).or_insert_with(|| Histograma {
+        buckets: vec![0; limites.len()],
+        ..Default::default()
+    });
+    if let Some(i) = limites.iter().position(|l| valor <= *l) {
+        h.buckets[i] += 1;
+    }
+    h.suma += valor;
+    h.total += 1;
+}
+
+/// Ejecuta `f` registrando su duración y las secciones que recibió
+pub fn medir<T>(fase: &str, secciones: usize, f: impl FnOnce() -> T) -> T {
+    let inicio = Instant::now();
+    let res = f();
+    observar(DURACION, fase, inicio.elapsed().as_secs_f64());
+    observar(SECCIONES, fase, secciones as f64);
+    res
+}
+
+/// `medir` para un enumerador: registra además cuántas soluciones devolvió
+pub fn medir_enumerador<T>(fase: &str, secciones: usize, f: impl FnOnce() -> Vec<T>) -> Vec<T> {
+    let res = medir(fase, secciones, f);
+    observar(SOLUCIONES, fase, res.len() as f64);
+    res
+}
+
+/// Descarta todo lo registrado
+pub fn reiniciar() {
+    registro().lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// Texto de exposición de Prometheus (versión 0.0.4)
+pub fn render() -> String {
+    let reg = registro().lock().unwrap_or_else(|e| e.into_inner());
+    let mut out = String::new();
+    for (nombre, ayuda, limites) in METRICAS.iter() {
+        out.push_str(&format!("# HELP {} {}\n# TYPE {} histogram\n", nombre, ayuda, nombre));
+        for ((_, fase), h) in reg.iter().filter(|((m, _), _)| m == nombre) {
+            let mut acumulado = 0;
+            for (limite, n) in limites.iter().zip(&h.buckets) {
+                acumulado += n;
+                out.push_str(&format!("{}_bucket{{phase=\"{}\",le=\"{}\"}} {}\n", nombre, fase, limite, acumulado));
+            }
+            out.push_str(&format!("{}_bucket{{phase=\"{}\",le=\"+Inf\"}} {}\n", nombre, fase, h.total));
+            out.push_str(&format!("{}_sum{{phase=\"{}\"}} {}\n", nombre, fase, h.suma));
+            out.push_str(&format!("{}_count{{phase=\"{}\"}} {}\n", nombre, fase, h.total));
+        }
+    }
+    out
+}
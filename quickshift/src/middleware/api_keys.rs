@@ -0,0 +1,233 @@
+//! Autenticación por API key y límite de solicitudes por clave.
+//!
+//! - Claves desde `API_KEYS` (`nombre:clave[:limite_por_min]`, separadas por coma)
+//!   y desde la tabla `api_keys` de la base de analíticas (solo el SHA-256 de la
+//!   clave), recargadas cada 5 minutos por `iniciar_api_keys`.
+//! - El cliente envía `X-API-Key: <clave>` o `Authorization: Bearer <clave>`.
+//! - Si no hay ninguna clave configurada no se exige autenticación (desarrollo local).
+//! - Con `API_ALLOW_ANONYMOUS_READ` (default `1`) los `GET` de `/help`,
+//!   `/carreras`, `/datafiles`, `/datafiles/validate` y la documentación (`/`, `/api-docs`,
+//!   `/openapi.json`) no necesitan clave; con `0` todo requiere clave.
+//! - Las sondas `/health/*` nunca piden clave (las usan los orquestadores).
+//! - Cada clave tiene un token bucket de `limite_por_min` tokens que se rellena
+//!   a ritmo constante (default `API_KEY_RATE_LIMIT_PER_MIN`, 60; `0` = sin límite).
+//!   Sin tokens se responde 429 con `Retry-After`.
+//! - El nombre de la clave queda en las extensiones del request (`ApiKeyId`);
+//!   `/solve` lo guarda en `queries.api_key` para atribuir el uso.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderMap, HeaderValue, AUTHORIZATION, RETRY_AFTER};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpMessage, ResponseError};
+use crate::api_error::ApiError;
+use crate::middleware::response_headers::QuotaState;
+use crate::middleware::auth::sha256_hex;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+const INTERVALO_RECARGA: Duration = Duration::from_secs(5 * 60);
+
+/// Clave válida: nombre con que se atribuye el uso y su límite por minuto
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKey {
+    pub nombre: String,
+    pub limite_por_min: u32,
+}
+
+/// Nombre de la API key que autenticó el request; queda en sus extensiones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyId(pub String);
+
+/// Claves indexadas por el SHA-256 (hex) de la clave
+pub type Claves = HashMap<String, ApiKey>;
+
+/// Límite por minuto para claves que no definen uno
+pub fn key_rate_limit_per_min() -> u32 {
+    crate::app_config::actual().api_key_rate_limit_per_min
+}
+
+/// Si los endpoints de solo lectura aceptan solicitudes sin clave
+pub fn permitir_lectura_anonima() -> bool {
+    crate::app_config::actual().api_allow_anonymous_read
+}
+
+/// Parsea `nombre:clave[:limite_por_min],...`. Ignora entradas mal formadas.
+pub fn parse_api_keys(valor: &str, limite_default: u32) -> Claves {
+    valor.split(',')
+        .filter_map(|entrada| {
+            let mut partes = entrada.trim().splitn(3, ':');
+            let nombre = partes.next()?.trim();
+            let clave = partes.next()?.trim();
+            if nombre.is_empty() || clave.is_empty() {
+                return None;
+            }
+            let limite = match partes.next() {
+                Some(l) => l.trim().parse::<u32>().ok()?,
+                None => limite_default,
+            };
+            Some((sha256_hex(clave.as_bytes()), ApiKey { nombre: nombre.to_string(), limite_por_min: limite }))
+        })
+        .collect()
+}
+
+fn claves() -> &'static RwLock<Claves> {
+    static CLAVES: OnceLock<RwLock<Claves>> = OnceLock::new();
+    CLAVES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Reemplaza el conjunto de claves vigente
+pub fn establecer_claves(nuevas: Claves) {
+    *claves().write().unwrap_or_else(|e| e.into_inner()) = nuevas;
+}
+
+/// Vuelve a leer `API_KEYS` y la tabla `api_keys`. Si la base no responde se
+/// conservan solo las de entorno.
+pub fn recargar_claves() -> usize {
+    let limite = key_rate_limit_per_min();
+    let mut nuevas = parse_api_keys(crate::app_config::actual().api_keys.as_deref().unwrap_or_default(), limite);
+    let desde_db = crate::analithics::db::open_analytics_connection()
+        .and_then(|conn| crate::analithics::db::fetch_api_keys(&conn));
+    match desde_db {
+        Ok(filas) => {
+            for f in filas {
+                let limite_por_min = f.limite_por_min.and_then(|l| u32::try_from(l).ok()).unwrap_or(limite);
+                nuevas.entry(f.clave_sha256.to_lowercase()).or_insert(ApiKey { nombre: f.nombre, limite_por_min });
+            }
+        }
+        Err(e) => tracing::warn!("no se pudieron leer las API keys de la base: {}", e),
+    }
+    let total = nuevas.len();
+    establecer_claves(nuevas);
+    total
+}
+
+/// Carga las claves y lanza un hilo que las recarga cada 5 minutos
+pub fn iniciar_api_keys() {
+    let total = recargar_claves();
+    if total == 0 {
+        tracing::warn!("🔓 Sin API keys configuradas: el servidor no exige autenticación");
+    } else {
+        tracing::info!("🔐 {} API keys cargadas", total);
+    }
+    std::thread::spawn(|| loop {
+        std::thread::sleep(INTERVALO_RECARGA);
+        recargar_claves();
+    });
+}
+
+/// Clave vigente para el texto enviado por el cliente
+pub fn buscar_clave(clave: &str) -> Option<ApiKey> {
+    claves().read().unwrap_or_else(|e| e.into_inner()).get(&sha256_hex(clave.as_bytes())).cloned()
+}
+
+fn auth_habilitada() -> bool {
+    !claves().read().unwrap_or_else(|e| e.into_inner()).is_empty()
+}
+
+/// Clave de `X-API-Key` o `Authorization: Bearer`
+pub fn extraer_clave(headers: &HeaderMap) -> Option<String> {
+    if let Some(v) = headers.get("x-api-key").and_then(|v| v.to_str().ok()) {
+        return Some(v.trim().to_string()).filter(|v| !v.is_empty());
+    }
+    headers.get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Endpoints de solo lectura que pueden usarse sin clave
+pub fn es_lectura_anonima(metodo: &Method, path: &str) -> bool {
+    if metodo != Method::GET {
+        return false;
+    }
+    matches!(
+        path,
+        "/" | "/help" | "/carreras" | "/api-docs" | "/openapi.json" | "/api-doc/openapi.json"
+            | "/datafiles" | "/datafiles/validate"
+    )
+}
+
+/// Sondas de salud (`server_handlers::health`), siempre sin clave
+pub fn es_sonda_salud(path: &str) -> bool {
+    path == "/health/live" || path == "/health/ready"
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Cubo {
+    tokens: f64,
+    ultimo: Instant,
+}
+
+fn cubos() -> &'static Mutex<HashMap<String, Cubo>> {
+    static CUBOS: OnceLock<Mutex<HashMap<String, Cubo>>> = OnceLock::new();
+    CUBOS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Consume un token del cubo de `clave` en el instante `ahora`. El cubo
+/// guarda hasta `limite_por_min` tokens y se rellena a `limite_por_min / 60`
+/// por segundo. `reset_secs` es lo que falta para el próximo token (si se
+/// excedió) o para llenar el cubo.
+pub fn consumir_token_en(clave: &str, limite_por_min: u32, ahora: Instant) -> QuotaState {
+    if limite_por_min == 0 {
+        return QuotaState { limit: 0, remaining: 0, reset_secs: 0, exceeded: false };
+    }
+    let capacidad = limite_por_min as f64;
+    let por_segundo = capacidad / 60.0;
+    let mut mapa = cubos().lock().unwrap_or_else(|e| e.into_inner());
+    let cubo = mapa.entry(clave.to_string()).or_insert(Cubo { tokens: capacidad, ultimo: ahora });
+    let transcurrido = ahora.saturating_duration_since(cubo.ultimo).as_secs_f64();
+    cubo.tokens = (cubo.tokens + transcurrido * por_segundo).min(capacidad);
+    cubo.ultimo = cubo.ultimo.max(ahora);
+
+    if cubo.tokens < 1.0 {
+        let espera = ((1.0 - cubo.tokens) / por_segundo).ceil() as u64;
+        return QuotaState { limit: limite_por_min, remaining: 0, reset_secs: espera, exceeded: true };
+    }
+    cubo.tokens -= 1.0;
+    let hasta_lleno = ((capacidad - cubo.tokens) / por_segundo).ceil() as u64;
+    QuotaState { limit: limite_por_min, remaining: cubo.tokens.floor() as u32, reset_secs: hasta_lleno, exceeded: false }
+}
+
+/// `consumir_token_en` con el instante actual
+pub fn consumir_token(clave: &str, limite_por_min: u32) -> QuotaState {
+    consumir_token_en(clave, limite_por_min, Instant::now())
+}
+
+/// Middleware (`actix_web::middleware::from_fn`) que exige una API key válida,
+/// aplica su límite y la deja en las extensiones del request.
+pub async fn api_key_auth<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    if req.method() == Method::OPTIONS || !auth_habilitada() || es_sonda_salud(req.path()) {
+        return Ok(next.call(req).await?.map_into_left_body());
+    }
+
+    let Some(clave) = extraer_clave(req.headers()) else {
+        if permitir_lectura_anonima() && es_lectura_anonima(req.method(), req.path()) {
+            return Ok(next.call(req).await?.map_into_left_body());
+        }
+        let resp = ApiError::Unauthorized("missing API key (X-API-Key or Authorization: Bearer)".into()).error_response();
+        return Ok(req.into_response(resp).map_into_right_body());
+    };
+    let Some(api_key) = buscar_clave(&clave) else {
+        tracing::warn!("⛔ [api-key] clave inválida en {}", req.path());
+        let resp = ApiError::Unauthorized("invalid API key".into()).error_response();
+        return Ok(req.into_response(resp).map_into_right_body());
+    };
+
+    let quota = consumir_token(&api_key.nombre, api_key.limite_por_min);
+    if quota.exceeded {
+        tracing::warn!("⛔ [rate-limit] API key '{}' excedió {} solicitudes/min en {}", api_key.nombre, quota.limit, req.path());
+        let mut resp = ApiError::RateLimited { limit_per_min: quota.limit, retry_after_secs: quota.reset_secs }.error_response();
+        resp.headers_mut().insert(RETRY_AFTER, HeaderValue::from(quota.reset_secs));
+        return Ok(req.into_response(resp).map_into_right_body());
+    }
+
+    req.extensions_mut().insert(ApiKeyId(api_key.nombre));
+    Ok(next.call(req).await?.map_into_left_body())
+}
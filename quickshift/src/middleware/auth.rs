@@ -0,0 +1,29 @@
+//! Piezas de autenticación compartidas por los handlers y los middlewares.
+//!
+//! - `autorizado`: los endpoints de administración (`/admin/*`, `/config*`,
+//!   borrado y exportación de analíticas) exigen `X-Admin-Token` igual a
+//!   `ADMIN_TOKEN`. Sin `ADMIN_TOKEN` configurada se rechazan todos.
+//! - `sha256_hex`: huella con que se guardan las API keys y los checksums del
+//!   archivo de rollover.
+
+use actix_web::HttpRequest;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// SHA-256 de `bytes` en hexadecimal (minúsculas)
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Exige `X-Admin-Token` igual a `ADMIN_TOKEN` (comparado en tiempo
+/// constante). Sin `ADMIN_TOKEN` configurada no se autoriza a nadie.
+pub(crate) fn autorizado(req: &HttpRequest) -> bool {
+    let Some(token) = crate::app_config::actual().admin_token.clone().filter(|t| !t.is_empty()) else {
+        tracing::warn!("🔒 Endpoint de administración rechazado: ADMIN_TOKEN no está configurada");
+        return false;
+    };
+    req.headers()
+        .get("x-admin-token")
+        .map(|v| bool::from(v.as_bytes().ct_eq(token.as_bytes())))
+        .unwrap_or(false)
+}
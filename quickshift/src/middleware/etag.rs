@@ -0,0 +1,65 @@
+// etag.rs - ETag de las respuestas derivadas de datafiles
+//
+// `GET /datafiles/content` y `/datafiles/merge` leen y emparejan la malla,
+// la oferta y los porcentajes en cada llamada, y su cuerpo solo cambia si
+// cambia alguno de esos archivos. El ETag se calcula sin leerlos: ruta,
+// tamaño y mtime de cada archivo más la variante pedida (hoja, formato). Si
+// el cliente (o un CDN) manda ese valor en `If-None-Match` se responde 304
+// sin cuerpo ni parseo. Es débil (`W/"..."`) porque el mismo recurso sale
+// con distinta codificación según `Accept-Encoding` (ver `Compress` en
+// `server::run_server`).
+
+use actix_web::http::header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH};
+use actix_web::{HttpRequest, HttpResponse};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// ETag débil de una respuesta calculada desde `archivos`; `None` si alguno
+/// no se puede leer (la respuesta sale sin ETag)
+pub fn etag_de_archivos(archivos: &[&Path], variante: &str) -> Option<String> {
+    let mut hasher = Sha256::new();
+    for ruta in archivos {
+        let meta = std::fs::metadata(ruta).ok()?;
+        let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        hasher.update(ruta.to_string_lossy().as_bytes());
+        hasher.update(meta.len().to_le_bytes());
+        hasher.update(mtime.as_nanos().to_le_bytes());
+        hasher.update([0]);
+    }
+    hasher.update(variante.as_bytes());
+    let hex: String = hasher.finalize().iter().take(12).map(|b| format!("{:02x}", b)).collect();
+    Some(format!("W/\"{}\"", hex))
+}
+
+/// true si algún valor de `If-None-Match` (o `*`) calza con `etag`; la
+/// comparación es débil, así que `"x"` y `W/"x"` son iguales
+pub fn coincide(req: &HttpRequest, etag: &str) -> bool {
+    let opaco = |s: &str| s.trim().trim_start_matches("W/").to_string();
+    let buscado = opaco(etag);
+    req.headers().get_all(IF_NONE_MATCH)
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|v| v.trim() == "*" || opaco(v) == buscado)
+}
+
+/// 304 con el ETag si el cliente ya tiene esta versión
+pub fn no_modificado(req: &HttpRequest, etag: Option<&str>) -> Option<HttpResponse> {
+    let etag = etag?;
+    coincide(req, etag).then(|| HttpResponse::NotModified()
+        .insert_header((ETAG, etag))
+        .insert_header((CACHE_CONTROL, "no-cache"))
+        .finish())
+}
+
+/// Agrega `ETag` y `Cache-Control: no-cache` (guardar, pero revalidar) a
+/// una respuesta exitosa
+pub fn con_etag(mut resp: HttpResponse, etag: Option<&str>) -> HttpResponse {
+    if let Some(etag) = etag.filter(|_| resp.status().is_success()) {
+        if let Ok(v) = etag.parse() {
+            resp.headers_mut().insert(ETAG, v);
+        }
+        resp.headers_mut().insert(CACHE_CONTROL, actix_web::http::header::HeaderValue::from_static("no-cache"));
+    }
+    resp
+}
@@ -0,0 +1,67 @@
+//! Idioma de las respuestas (`?lang=es|en` o `Accept-Language`).
+//!
+//! `idioma` resuelve el idioma pedido (el query `lang` gana sobre la
+//! cabecera; sin ninguno vale `DEFAULT_LANG`, si está definida), lo deja en
+//! las extensiones del request y en un task-local mientras corre el handler,
+//! de modo que `ApiError::error_response` lo encuentre con `pedido()`, y
+//! responde `Content-Language`. Los handlers que arman texto reciben `Idioma`
+//! como extractor.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderValue, ACCEPT_LANGUAGE, CONTENT_LANGUAGE};
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpMessage, HttpRequest};
+use crate::i18n::Idioma;
+
+tokio::task_local! {
+    static IDIOMA: Option<Idioma>;
+}
+
+/// Idioma pedido en el query (`lang`) o en `Accept-Language`, o el de `DEFAULT_LANG`
+pub fn idioma_de(query: &str, accept_language: Option<&str>) -> Option<Idioma> {
+    let del_query = query.split('&')
+        .filter_map(|par| par.split_once('='))
+        .find(|(k, _)| *k == "lang")
+        .and_then(|(_, v)| Idioma::desde_codigo(v));
+    del_query
+        .or_else(|| accept_language.and_then(Idioma::desde_accept_language))
+        .or_else(Idioma::configurado)
+}
+
+fn idioma_del_request(req: &HttpRequest) -> Option<Idioma> {
+    if let Some(i) = req.extensions().get::<Option<Idioma>>() {
+        return *i;
+    }
+    let accept = req.headers().get(ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok());
+    idioma_de(req.query_string(), accept)
+}
+
+/// Idioma pedido por el request en curso (None fuera de `idioma` o si no pidió ninguno)
+pub fn pedido() -> Option<Idioma> {
+    IDIOMA.try_with(|i| *i).ok().flatten()
+}
+
+/// Middleware (`actix_web::middleware::from_fn`) que fija el idioma del request
+pub async fn idioma<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<B>, Error> {
+    let elegido = idioma_del_request(req.request());
+    req.extensions_mut().insert(elegido);
+    let mut res = IDIOMA.scope(elegido, next.call(req)).await?;
+    if let Some(i) = elegido {
+        res.headers_mut().insert(CONTENT_LANGUAGE, HeaderValue::from_static(i.codigo()));
+    }
+    Ok(res)
+}
+
+/// Los handlers reciben el idioma pedido (español si no se pidió ninguno)
+impl actix_web::FromRequest for Idioma {
+    type Error = std::convert::Infallible;
+    type Future = std::future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        std::future::ready(Ok(idioma_del_request(req).unwrap_or_default()))
+    }
+}
@@ -0,0 +1,12 @@
+// Middlewares HTTP del servidor (se registran en `server::run_server`).
+pub mod api_keys;
+pub mod auth;
+pub mod etag;
+pub mod idioma;
+pub mod response_headers;
+pub mod trace;
+
+pub use api_keys::{api_key_auth, ApiKeyId};
+pub use idioma::idioma;
+pub use response_headers::{solve_response_headers, CacheStatus};
+pub use trace::{request_trace, TraceId};
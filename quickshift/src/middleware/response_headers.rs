@@ -0,0 +1,171 @@
+//! Cabeceras informativas para las respuestas de resolución (`/solve*`, `/rutacritica/run*`).
+//!
+//! - `X-RateLimit-Limit`, `X-RateLimit-Remaining`, `X-RateLimit-Reset`: cuota por IP
+//!   en ventanas fijas de 60 segundos, solo si está activada. Al agotarla se
+//!   responde 429 con `Retry-After`.
+//! - `X-Body-Size-Limit`: tamaño máximo aceptado para el body JSON (el de actix).
+//! - `X-Cache`: `hit` / `miss` / `stale`, según lo que marque el handler con `CacheStatus`.
+//! - `X-Compute-Time-Ms`: tiempo total que tomó el handler.
+//! - `X-Sample-Data: true` en todas las respuestas si el servidor usa los datos
+//!   de ejemplo embebidos (ver `excel::sample`).
+//!
+//! Configuración por variables de entorno:
+//! - `SOLVE_RATE_LIMIT_PER_MIN` (default `0`: sin límite)
+//! - `TRUST_PROXY_HEADERS` (default `false`): la IP de la cuota es la del
+//!   socket; con `true` se toma de `Forwarded`/`X-Forwarded-For`, que el
+//!   cliente puede inventar si no hay un proxy de confianza delante
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use actix_web::middleware::Next;
+use actix_web::{Error, ResponseError};
+use crate::api_error::ApiError;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Estado de caché de una respuesta. Los handlers lo insertan en las
+/// extensiones de la respuesta (`resp.extensions_mut().insert(CacheStatus::Hit)`);
+/// si no lo hacen se informa `miss`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+    Stale,
+}
+
+impl CacheStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "hit",
+            CacheStatus::Miss => "miss",
+            CacheStatus::Stale => "stale",
+        }
+    }
+}
+
+/// Resultado de consumir una unidad de cuota
+#[derive(Debug, Clone, Copy)]
+pub struct QuotaState {
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_secs: u64,
+    pub exceeded: bool,
+}
+
+/// Límite de solicitudes por minuto y por IP (0 = sin límite)
+pub fn rate_limit_per_min() -> u32 {
+    crate::app_config::actual().solve_rate_limit_per_min
+}
+
+/// Tamaño máximo del body JSON aceptado por el servidor: el límite por
+/// defecto de `web::JsonConfig`, que no se cambia
+pub fn max_body_bytes() -> usize {
+    2 * 1024 * 1024
+}
+
+/// IP con que se cuenta la cuota: la del socket, o la de las cabeceras del
+/// proxy si `confiar_proxy`
+pub fn ip_cliente(req: &ServiceRequest, confiar_proxy: bool) -> String {
+    if confiar_proxy {
+        return req.connection_info().realip_remote_addr().unwrap_or("unknown").to_string();
+    }
+    req.peer_addr().map(|a| a.ip().to_string()).unwrap_or_else(|| "unknown".to_string())
+}
+
+fn windows() -> &'static Mutex<HashMap<String, (Instant, u32)>> {
+    static WINDOWS: OnceLock<Mutex<HashMap<String, (Instant, u32)>>> = OnceLock::new();
+    WINDOWS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Consume una solicitud de la cuota de `client` (ventana fija de 60s)
+pub fn consume_quota(client: &str, limit: u32) -> QuotaState {
+    if limit == 0 {
+        return QuotaState { limit: 0, remaining: 0, reset_secs: 0, exceeded: false };
+    }
+    let now = Instant::now();
+    let mut map = match windows().lock() {
+        Ok(m) => m,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    // Limpiar ventanas vencidas para que el mapa no crezca sin límite
+    map.retain(|_, (start, _)| now.duration_since(*start) < RATE_WINDOW);
+
+    let entry = map.entry(client.to_string()).or_insert((now, 0));
+    let reset_secs = RATE_WINDOW.saturating_sub(now.duration_since(entry.0)).as_secs();
+    if entry.1 >= limit {
+        return QuotaState { limit, remaining: 0, reset_secs, exceeded: true };
+    }
+    entry.1 += 1;
+    QuotaState { limit, remaining: limit - entry.1, reset_secs, exceeded: false }
+}
+
+fn is_solve_path(path: &str) -> bool {
+    path.starts_with("/solve") || path.starts_with("/rutacritica/run")
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &'static str, value: String) {
+    if let Ok(v) = HeaderValue::from_str(&value) {
+        headers.insert(HeaderName::from_static(name), v);
+    }
+}
+
+fn insert_quota_headers(headers: &mut HeaderMap, quota: &QuotaState) {
+    if quota.limit == 0 {
+        return;
+    }
+    insert_header(headers, "x-ratelimit-limit", quota.limit.to_string());
+    insert_header(headers, "x-ratelimit-remaining", quota.remaining.to_string());
+    insert_header(headers, "x-ratelimit-reset", quota.reset_secs.to_string());
+}
+
+/// `X-Sample-Data: true` si se están usando los datos de ejemplo
+fn insert_sample_header(headers: &mut HeaderMap) {
+    if crate::excel::sample::en_uso() {
+        insert_header(headers, "x-sample-data", "true".to_string());
+    }
+}
+
+/// Middleware (`actix_web::middleware::from_fn`) que aplica la cuota y agrega
+/// las cabeceras de límites, caché y tiempo de cómputo a las rutas de resolución.
+pub async fn solve_response_headers<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    if !is_solve_path(req.path()) {
+        let mut res = next.call(req).await?;
+        insert_sample_header(res.headers_mut());
+        return Ok(res.map_into_left_body());
+    }
+
+    let client_ip = ip_cliente(&req, crate::app_config::actual().trust_proxy_headers);
+    let quota = consume_quota(&client_ip, rate_limit_per_min());
+
+    if quota.exceeded {
+        tracing::warn!("⛔ [rate-limit] {} excedió {} solicitudes/min en {}", client_ip, quota.limit, req.path());
+        let mut resp = ApiError::RateLimited { limit_per_min: quota.limit, retry_after_secs: quota.reset_secs }.error_response();
+        let headers = resp.headers_mut();
+        insert_quota_headers(headers, &quota);
+        insert_header(headers, "x-body-size-limit", max_body_bytes().to_string());
+        headers.insert(RETRY_AFTER, HeaderValue::from(quota.reset_secs));
+        insert_sample_header(headers);
+        return Ok(req.into_response(resp).map_into_right_body());
+    }
+
+    let start = Instant::now();
+    let mut res = next.call(req).await?;
+    let compute_ms = start.elapsed().as_millis();
+
+    let cache = res.response().extensions().get::<CacheStatus>().copied().unwrap_or(CacheStatus::Miss);
+    let headers = res.headers_mut();
+    insert_quota_headers(headers, &quota);
+    insert_header(headers, "x-body-size-limit", max_body_bytes().to_string());
+    insert_header(headers, "x-cache", cache.as_str().to_string());
+    insert_header(headers, "x-compute-time-ms", compute_ms.to_string());
+    insert_sample_header(headers);
+
+    Ok(res.map_into_left_body())
+}
@@ -0,0 +1,113 @@
+//! Logging estructurado (`tracing`) y trace ID por solicitud.
+//!
+//! - `init_logging` instala el subscriber. El nivel se controla con `RUST_LOG`
+//!   (sintaxis de `EnvFilter`, p.ej. `RUST_LOG=warn,quickshift::algorithm=debug`);
+//!   por defecto `info`.
+//! - `request_trace` toma `X-Request-Id` si el cliente lo envía (saneado) o
+//!   genera uno, abre el span `request{trace_id}` y responde `X-Trace-Id`.
+//!   En respuestas de error con body JSON agrega `"trace_id"` para que el
+//!   usuario pueda reportar la falla.
+//! - `con_span_actual` lleva el span del request al pool bloqueante
+//!   (`web::block` / `spawn_blocking`), así los logs de `ruta`, `clique` y
+//!   `extract` quedan asociados al mismo trace ID.
+
+use actix_web::body::{self, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpMessage};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tracing::Instrument;
+
+const MAX_TRACE_ID_LEN: usize = 64;
+
+/// Trace ID de la solicitud; queda en las extensiones del request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceId(pub String);
+
+/// Instala el subscriber de `tracing` (stderr). Llamar una vez al iniciar.
+pub fn init_logging() {
+    let filtro = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filtro)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+/// ID nuevo: milisegundos desde epoch + contador, en hex (único por proceso)
+pub fn nuevo_trace_id() -> String {
+    static CONTADOR: AtomicU64 = AtomicU64::new(0);
+    let ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+    format!("{:011x}-{:05x}", ms, CONTADOR.fetch_add(1, Ordering::Relaxed) & 0xfffff)
+}
+
+/// Acepta un `X-Request-Id` del cliente solo si es corto y alfanumérico (más `-`, `_`, `.`)
+pub fn sanear_trace_id(valor: &str) -> Option<String> {
+    let v = valor.trim();
+    let valido = !v.is_empty()
+        && v.len() <= MAX_TRACE_ID_LEN
+        && v.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valido.then(|| v.to_string())
+}
+
+/// Agrega `"trace_id"` a un body JSON objeto. `None` si el body no es un objeto JSON.
+pub fn agregar_trace_id(body: &[u8], trace_id: &str) -> Option<Vec<u8>> {
+    let mut v: serde_json::Value = serde_json::from_slice(body).ok()?;
+    v.as_object_mut()?.insert("trace_id".to_string(), serde_json::Value::String(trace_id.to_string()));
+    serde_json::to_vec(&v).ok()
+}
+
+/// Envuelve `f` para que se ejecute dentro del span actual (el del request)
+pub fn con_span_actual<R>(f: impl FnOnce() -> R) -> impl FnOnce() -> R {
+    let span = tracing::Span::current();
+    move || span.in_scope(f)
+}
+
+/// Middleware (`actix_web::middleware::from_fn`) de trace ID por solicitud
+pub async fn request_trace<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let trace_id = req.headers().get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(sanear_trace_id)
+        .unwrap_or_else(nuevo_trace_id);
+    req.extensions_mut().insert(TraceId(trace_id.clone()));
+
+    let span = tracing::info_span!("request", trace_id = %trace_id, method = %req.method(), path = %req.path());
+    let start = Instant::now();
+    let mut res = next.call(req).instrument(span.clone()).await?;
+    let status = res.status();
+    let ms = start.elapsed().as_millis() as u64;
+    span.in_scope(|| {
+        if status.is_server_error() {
+            tracing::error!(status = status.as_u16(), ms, "respuesta con error");
+        } else if status.is_client_error() {
+            tracing::warn!(status = status.as_u16(), ms, "solicitud rechazada");
+        } else {
+            tracing::debug!(status = status.as_u16(), ms, "respuesta enviada");
+        }
+    });
+    if let Ok(v) = HeaderValue::from_str(&trace_id) {
+        res.headers_mut().insert(HeaderName::from_static("x-trace-id"), v);
+    }
+
+    let es_json = res.headers().get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|ct| ct.starts_with("application/json"))
+        .unwrap_or(false);
+    if !(status.is_client_error() || status.is_server_error()) || !es_json {
+        return Ok(res.map_into_left_body());
+    }
+
+    // Error JSON: reescribir el body con el trace ID
+    let (req, res) = res.into_parts();
+    let (res, cuerpo) = res.into_parts();
+    let bytes = body::to_bytes(cuerpo).await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.into().to_string()))?;
+    let nuevo = agregar_trace_id(&bytes, &trace_id).unwrap_or_else(|| bytes.to_vec());
+    let res = res.set_body(nuevo);
+    Ok(ServiceResponse::new(req, res).map_into_right_body())
+}
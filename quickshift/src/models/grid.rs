@@ -0,0 +1,89 @@
+//! Solución como grilla semanal (días × franjas) lista para dibujar.
+//!
+//! Las franjas son los intervalos elementales entre todos los inicios y
+//! términos de bloque de la solución (se omiten los tramos sin clases). Un
+//! bloque que abarca varias franjas aparece en cada una de ellas; si dos
+//! secciones comparten franja y día, la celda trae ambas.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use crate::models::horario::{bloques_de_seccion, formatear_hora, Bloque, Dia};
+use crate::models::Seccion;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CeldaGrid {
+    pub codigo: String,
+    pub nombre: String,
+    pub seccion: String,
+    pub profesor: String,
+}
+
+impl CeldaGrid {
+    fn de(s: &Seccion) -> Self {
+        CeldaGrid { codigo: s.codigo.clone(), nombre: s.nombre.clone(), seccion: s.seccion.clone(), profesor: s.profesor.clone() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FranjaGrid {
+    pub inicio: String,
+    pub fin: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleGrid {
+    /// Columnas: LU a VI, más SA/DO solo si hay clases esos días
+    pub dias: Vec<Dia>,
+    /// Filas, en orden cronológico
+    pub franjas: Vec<FranjaGrid>,
+    /// `celdas[fila][columna]`: secciones con clase en esa franja y día
+    pub celdas: Vec<Vec<Vec<CeldaGrid>>>,
+    /// Secciones sin horario reconocible ("Sin horario", "Por definir")
+    pub sin_horario: Vec<CeldaGrid>,
+}
+
+impl ScheduleGrid {
+    pub fn from_secciones(secciones: &[Seccion]) -> Self {
+        let mut bloques: Vec<(Bloque, CeldaGrid)> = Vec::new();
+        let mut sin_horario = Vec::new();
+        for s in secciones {
+            let propios = bloques_de_seccion(s);
+            if propios.is_empty() {
+                sin_horario.push(CeldaGrid::de(s));
+            }
+            bloques.extend(propios.into_iter().map(|b| (b, CeldaGrid::de(s))));
+        }
+
+        let dias: Vec<Dia> = Dia::TODOS.iter()
+            .copied()
+            .filter(|d| *d <= Dia::Viernes || bloques.iter().any(|(b, _)| b.dia == *d))
+            .collect();
+
+        let cortes: Vec<u32> = bloques.iter()
+            .flat_map(|(b, _)| [b.inicio, b.fin])
+            .collect::<BTreeSet<u32>>()
+            .into_iter()
+            .collect();
+        let cubre = |b: &Bloque, ini: u32, fin: u32| b.inicio <= ini && b.fin >= fin;
+        let tramos: Vec<(u32, u32)> = cortes.windows(2)
+            .map(|w| (w[0], w[1]))
+            .filter(|(ini, fin)| bloques.iter().any(|(b, _)| cubre(b, *ini, *fin)))
+            .collect();
+
+        let celdas = tramos.iter()
+            .map(|(ini, fin)| dias.iter()
+                .map(|d| bloques.iter()
+                    .filter(|(b, _)| b.dia == *d && cubre(b, *ini, *fin))
+                    .map(|(_, c)| c.clone())
+                    .collect())
+                .collect())
+            .collect();
+
+        ScheduleGrid {
+            dias,
+            franjas: tramos.iter().map(|(ini, fin)| FranjaGrid { inicio: formatear_hora(*ini), fin: formatear_hora(*fin) }).collect(),
+            celdas,
+            sin_horario,
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! Horarios de la oferta como bloques estructurados.
+//!
+//! Los horarios vienen como texto libre ("LU MA 08:30 - 10:00",
+//! "JU 10:00-11:20", "Sáb 09:00–12:00", "Sin horario"). `parse` los convierte
+//! en `Bloque`s (día + rango [inicio, fin) en minutos desde medianoche) y es
+//! el único parser de horarios del crate: `conflict::parse_slots`, los
+//! filtros, el clique y los exportadores lo usan.
+//!
+//! Formatos aceptados:
+//!   - uno o más días antes del rango: "LU MA 08:30 - 10:00", "LU,MI 8:30-10:00"
+//!   - días abreviados o completos, con o sin tilde: "MIE", "Miércoles", "SA", "Domingo"
+//!   - días en inglés: "MON", "Tue", "Wednesday", "TH"
+//!   - guión normal, en/em dash o signo menos, con o sin espacios
+//!   - horas "HH:MM", "H:MM", "HH.MM", "HHMM" y en formato de 12 horas: "2:30PM",
+//!     "2:30 pm", "2 PM", "2:30 p.m."
+//!   - día pegado a la hora: "LU:08:30-10:00"
+//!   - varios tramos en una entrada: "LU 08:30-10:00 JU 14:30-15:50"
+//!
+//! Un rango sin días ("08:30-10:00") no produce bloques con `parse`; su
+//! rango se obtiene con `parse_rango`. "Sin horario", "Por definir" y las
+//! entradas vacías no producen bloques. `normalizar` reescribe una entrada
+//! en la forma interna ("LU MI 08:30-10:00"), que es como se guardan los
+//! horarios y filtros que envía el usuario.
+
+use serde::Serialize;
+use crate::models::Seccion;
+
+/// Día de la semana de un bloque de clases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Dia {
+    #[serde(rename = "LU")]
+    Lunes,
+    #[serde(rename = "MA")]
+    Martes,
+    #[serde(rename = "MI")]
+    Miercoles,
+    #[serde(rename = "JU")]
+    Jueves,
+    #[serde(rename = "VI")]
+    Viernes,
+    #[serde(rename = "SA")]
+    Sabado,
+    #[serde(rename = "DO")]
+    Domingo,
+}
+
+impl Dia {
+    pub const TODOS: [Dia; 7] = [Dia::Lunes, Dia::Martes, Dia::Miercoles, Dia::Jueves, Dia::Viernes, Dia::Sabado, Dia::Domingo];
+
+    /// "LU", "MAR", "Miércoles", "sab", "MON", "Thu", ... (prefijo de al
+    /// menos dos letras del nombre del día en español o en inglés, sin
+    /// distinguir mayúsculas ni tildes; el español tiene precedencia)
+    pub fn desde_codigo(codigo: &str) -> Option<Dia> {
+        let c = crate::excel::normalize_name(codigo.trim().trim_end_matches('.'));
+        if c.chars().count() < 2 || !c.chars().all(|ch| ch.is_alphabetic()) {
+            return None;
+        }
+        Dia::TODOS.into_iter()
+            .find(|d| d.nombre().starts_with(c.as_str()))
+            .or_else(|| Dia::TODOS.into_iter().find(|d| d.nombre_en().starts_with(c.as_str())))
+    }
+
+    /// Código de dos letras usado en los horarios de la oferta
+    pub fn codigo(&self) -> &'static str {
+        match self {
+            Dia::Lunes => "LU",
+            Dia::Martes => "MA",
+            Dia::Miercoles => "MI",
+            Dia::Jueves => "JU",
+            Dia::Viernes => "VI",
+            Dia::Sabado => "SA",
+            Dia::Domingo => "DO",
+        }
+    }
+
+    /// Nombre en minúsculas y sin tilde
+    fn nombre(&self) -> &'static str {
+        match self {
+            Dia::Lunes => "lunes",
+            Dia::Martes => "martes",
+            Dia::Miercoles => "miercoles",
+            Dia::Jueves => "jueves",
+            Dia::Viernes => "viernes",
+            Dia::Sabado => "sabado",
+            Dia::Domingo => "domingo",
+        }
+    }
+
+    /// Nombre en inglés, en minúsculas
+    fn nombre_en(&self) -> &'static str {
+        match self {
+            Dia::Lunes => "monday",
+            Dia::Martes => "tuesday",
+            Dia::Miercoles => "wednesday",
+            Dia::Jueves => "thursday",
+            Dia::Viernes => "friday",
+            Dia::Sabado => "saturday",
+            Dia::Domingo => "sunday",
+        }
+    }
+
+    /// Días desde el lunes (lunes = 0)
+    pub fn indice(&self) -> u32 {
+        *self as u32
+    }
+}
+
+/// Un bloque semanal de clases: día + rango [inicio, fin) en minutos desde medianoche
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Bloque {
+    pub dia: Dia,
+    pub inicio: u32,
+    pub fin: u32,
+}
+
+impl Bloque {
+    /// "08:30"
+    pub fn hora_inicio(&self) -> String {
+        formatear_hora(self.inicio)
+    }
+
+    /// "10:00"
+    pub fn hora_fin(&self) -> String {
+        formatear_hora(self.fin)
+    }
+
+    /// true si comparten día y los rangos se cruzan (tocarse no cuenta)
+    pub fn solapa(&self, otro: &Bloque) -> bool {
+        self.dia == otro.dia && self.inicio < otro.fin && otro.inicio < self.fin
+    }
+
+    /// Minutos libres entre dos bloques del mismo día (0 si se solapan,
+    /// None si son de días distintos)
+    pub fn distancia(&self, otro: &Bloque) -> Option<u32> {
+        (self.dia == otro.dia).then(|| otro.inicio.saturating_sub(self.fin).max(self.inicio.saturating_sub(otro.fin)))
+    }
+}
+
+/// Minutos desde medianoche -> "HH:MM"
+pub fn formatear_hora(minutos: u32) -> String {
+    format!("{:02}:{:02}", minutos / 60, minutos % 60)
+}
+
+/// Sufijo de 12 horas ("AM", "pm", "p.m.") -> true si es PM
+fn sufijo_12h(s: &str) -> Option<bool> {
+    match s.trim().to_uppercase().replace('.', "").as_str() {
+        "AM" => Some(false),
+        "PM" => Some(true),
+        _ => None,
+    }
+}
+
+/// "08:30", "8:30", "08.30", "0830", "2:30PM", "2:30 p.m.", "2PM" -> minutos desde medianoche
+pub fn parse_hora(s: &str) -> Option<u32> {
+    let u = s.trim().to_uppercase();
+    let sufijo = ["A.M.", "P.M.", "AM", "PM"].into_iter().find(|suf| u.ends_with(suf));
+    let pm = sufijo.and_then(sufijo_12h) == Some(true);
+    let am = sufijo.and_then(sufijo_12h) == Some(false);
+    let t = u[..u.len() - sufijo.map_or(0, str::len)].trim().replace('.', ":");
+    let (h, m) = match t.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if t.len() == 4 && t.chars().all(|c| c.is_ascii_digit()) => t.split_at(2),
+        // "2PM": hora en punto
+        None if (am || pm) && !t.is_empty() && t.len() <= 2 && t.chars().all(|c| c.is_ascii_digit()) => (t.as_str(), "00"),
+        None => return None,
+    };
+    if h.is_empty() || h.len() > 2 || m.len() != 2 {
+        return None;
+    }
+    let mut h = h.parse::<u32>().ok()?;
+    let m = m.parse::<u32>().ok()?;
+    if m >= 60 || h > 24 || (h == 24 && m > 0) {
+        return None;
+    }
+    if pm && h < 12 {
+        h += 12;
+    } else if am && h == 12 {
+        h = 0;
+    }
+    Some(h * 60 + m)
+}
+
+/// Tramos de una entrada: días (vacío si no tiene) y rango [inicio, fin)
+fn tramos(entrada: &str) -> Vec<(Vec<Dia>, u32, u32)> {
+    let mut texto = entrada.to_string();
+    for guion in ['–', '—', '―', '‐', '‑', '−', '-'] {
+        texto = texto.replace(guion, " - ");
+    }
+    let texto = texto.replace([',', ';', '/', '|'], " ");
+
+    // "2:30 PM", "9 AM": el sufijo de 12 horas suelto se pega a la hora anterior
+    let mut tokens: Vec<String> = Vec::new();
+    for token in texto.split_whitespace() {
+        match tokens.last_mut() {
+            Some(anterior) if sufijo_12h(token).is_some() && parse_hora(&format!("{}{}", anterior, token)).is_some() => anterior.push_str(token),
+            _ => tokens.push(token.to_string()),
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut dias: Vec<Dia> = Vec::new();
+    let mut horas: Vec<u32> = Vec::new();
+    for token in tokens.iter().map(String::as_str) {
+        // "LU:08:30": día pegado a la hora
+        let (dia, hora) = match token.split_once(':') {
+            Some((d, h)) if Dia::desde_codigo(d).is_some() => (Dia::desde_codigo(d), Some(h)),
+            _ => (Dia::desde_codigo(token), if token == "-" { None } else { Some(token) }),
+        };
+        if let Some(d) = dia {
+            if !horas.is_empty() {
+                // un día después de una hora suelta empieza otro tramo
+                horas.clear();
+                dias.clear();
+            }
+            if !dias.contains(&d) {
+                dias.push(d);
+            }
+        }
+        if let Some(h) = hora.and_then(parse_hora) {
+            horas.push(h);
+            if horas.len() == 2 {
+                if horas[1] > horas[0] {
+                    out.push((std::mem::take(&mut dias), horas[0], horas[1]));
+                }
+                dias.clear();
+                horas.clear();
+            }
+        }
+    }
+    out
+}
+
+/// Convierte una entrada de horario en bloques, en el orden de sus días.
+/// Entradas sin día u hora reconocibles ("Sin horario", "Por definir")
+/// devuelven una lista vacía.
+pub fn parse(entrada: &str) -> Vec<Bloque> {
+    tramos(entrada)
+        .into_iter()
+        .flat_map(|(dias, inicio, fin)| dias.into_iter().map(move |dia| Bloque { dia, inicio, fin }))
+        .collect()
+}
+
+/// Reescribe una entrada en la forma interna: días con su código de dos
+/// letras y horas de 24 horas ("mon wed 2:30 PM - 3:50 PM" -> "LU MI
+/// 14:30-15:50"). Una entrada sin horas reconocibles se devuelve tal cual.
+pub fn normalizar(entrada: &str) -> String {
+    let tramos = tramos(entrada);
+    if tramos.is_empty() {
+        return entrada.trim().to_string();
+    }
+    tramos.iter()
+        .map(|(dias, inicio, fin)| {
+            let rango = format!("{}-{}", formatear_hora(*inicio), formatear_hora(*fin));
+            if dias.is_empty() {
+                rango
+            } else {
+                format!("{} {}", dias.iter().map(Dia::codigo).collect::<Vec<_>>().join(" "), rango)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Código de dos letras de un día ("MON", "martes" -> "LU", "MA"); en
+/// mayúsculas tal cual si no se reconoce
+pub fn normalizar_dia(dia: &str) -> String {
+    Dia::desde_codigo(dia).map(|d| d.codigo().to_string()).unwrap_or_else(|| dia.trim().to_uppercase())
+}
+
+/// Hora en 24 horas ("2:30 PM" -> "14:30"); tal cual si no se reconoce
+pub fn normalizar_hora(hora: &str) -> String {
+    parse_hora(hora).map(formatear_hora).unwrap_or_else(|| hora.trim().to_string())
+}
+
+/// Primer rango [inicio, fin) de la entrada, tenga o no días ("08:30-10:00")
+pub fn parse_rango(entrada: &str) -> Option<(u32, u32)> {
+    tramos(entrada).first().map(|&(_, inicio, fin)| (inicio, fin))
+}
+
+/// Todos los bloques de una sección, ordenados y sin repetir
+pub fn bloques_de_seccion(seccion: &Seccion) -> Vec<Bloque> {
+    let mut bloques: Vec<Bloque> = seccion.horario.iter().flat_map(|h| parse(h)).collect();
+    bloques.sort();
+    bloques.dedup();
+    bloques
+}
@@ -1,9 +1,14 @@
 // Estructuras de datos principales
 
+pub mod grid;
+pub mod horario;
+pub use grid::{CeldaGrid, FranjaGrid, ScheduleGrid};
+
 /// Filtros opcionales del usuario (Reglas 3-6 en Plan.md)
 /// Todos los campos son opcionales; si no se especifican, se ignoran los filtros
 #[allow(dead_code)]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
 pub struct UserFilters {
     /// Filtro 3: Días/horarios libres
     pub dias_horarios_libres: Option<DiaHorariosLibres>,
@@ -13,11 +18,33 @@ pub struct UserFilters {
     pub preferencias_profesores: Option<PreferenciasProfesores>,
     /// Filtro 6: Balance entre líneas de formación
     pub balance_lineas: Option<BalanceLineas>,
+    /// Filtro 7: Tiempo de traslado entre campus
+    #[serde(default)]
+    pub tiempo_traslado: Option<TiempoTraslado>,
+    /// Filtro 8: descartar secciones sin cupos disponibles (las que no
+    /// informan cupos pasan)
+    #[serde(default)]
+    pub solo_con_cupos: bool,
+    /// Filtro 9: tope de ramos distintos con clases en un mismo día (un
+    /// paquete cátedra + laboratorio cuenta como uno)
+    #[serde(default)]
+    pub max_ramos_por_dia: Option<usize>,
+    /// Filtro 10: ninguna clase empieza antes de esta hora ("HH:MM")
+    #[serde(default)]
+    pub hora_inicio_minima: Option<String>,
+    /// Filtro 10: ninguna clase termina después de esta hora ("HH:MM")
+    #[serde(default)]
+    pub hora_fin_maxima: Option<String>,
+    /// Filtro 11: descartar los ramos con alguna de estas notas
+    /// (`RamoDisponible::tags`, sin distinguir mayúsculas ni tildes)
+    #[serde(default)]
+    pub evitar_tags: Vec<String>,
 
 }
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
 pub struct FranjaProhibida {
     pub dia: String,      // "LU", "MA", "MI", "JU", "VI"
     pub inicio: String,   // "08:00"
@@ -26,6 +53,7 @@ pub struct FranjaProhibida {
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
 pub struct DiaHorariosLibres {
     #[serde(default)]
     pub habilitado: bool,
@@ -42,6 +70,7 @@ pub struct DiaHorariosLibres {
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
 pub struct VentanaEntreActividades {
     #[serde(default)]
     pub habilitado: bool,
@@ -50,42 +79,156 @@ pub struct VentanaEntreActividades {
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
 pub struct PreferenciasProfesores {
     #[serde(default)]
     pub habilitado: bool,
     pub profesores_preferidos: Option<Vec<String>>,
     pub profesores_evitar: Option<Vec<String>>,
+    /// `hard` (por defecto) excluye secciones; `soft` solo ajusta el score
+    #[serde(default)]
+    pub modo: ModoPreferencia,
+}
+
+/// Cómo se aplica una preferencia del usuario
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ModoPreferencia {
+    /// Las secciones que no cumplen se descartan (puede dejar sin soluciones)
+    #[default]
+    Hard,
+    /// Las secciones que no cumplen se mantienen con bonus/penalización en el score
+    Soft,
 }
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
 pub struct BalanceLineas {
     #[serde(default)]
     pub habilitado: bool,
     pub lineas: Option<std::collections::HashMap<String, f64>>, // {"informatica": 0.6, "telecomunicaciones": 0.4}
 }
 
+/// Clases seguidas en campus distintos necesitan al menos `minutos` entre
+/// el fin de una y el inicio de la otra (el mismo día)
+#[allow(dead_code)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+pub struct TiempoTraslado {
+    #[serde(default)]
+    pub habilitado: bool,
+    pub minutos: Option<i32>, // default: 30
+    /// `hard` (por defecto) descarta la combinación; `soft` la penaliza en el score
+    #[serde(default)]
+    pub modo: ModoPreferencia,
+}
+
+/// Compromiso semanal fijo del estudiante (trabajo, deporte) que el horario
+/// debe respetar (ver `algorithm::compromisos`)
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+pub struct Compromiso {
+    /// Para mostrar en la respuesta: "Trabajo", "Fútbol"
+    #[serde(default)]
+    pub etiqueta: String,
+    /// Uno o más días: "LU", "MA JU", "MON"
+    pub dia: String,
+    pub inicio: String,   // "18:00" o "6 PM"
+    pub fin: String,
+    /// `hard` (por defecto) descarta las secciones que lo pisan; `soft` penaliza el score
+    #[serde(default)]
+    pub modo: ModoPreferencia,
+    /// Penalización si una solución lo pisa (modo `soft`); por defecto
+    /// `ScoringConfig::penalizacion_compromiso`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peso: Option<i64>,
+}
+
 // Note: carga (max ramos) is enforced as a fixed cap of 6 per semester in the algorithm.
 
+/// Componente de una sección dentro de su ramo (ver `excel::componentes`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Componente {
+    #[default]
+    Catedra,
+    Lab,
+    Ayudantia,
+}
+
 #[allow(dead_code)]
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
 pub struct Seccion {
     pub codigo: String,
     pub nombre: String,
+    #[serde(default)]
     pub seccion: String,
+    #[serde(default)]
     pub horario: Vec<String>,
+    #[serde(default)]
     pub profesor: String,
+    #[serde(default)]
     pub codigo_box: String,
     /// True si esta sección proviene del archivo CFG (Curso de Formación General)
     /// Las secciones leídas desde la Oferta Académica normal tienen `is_cfg = false`.
+    #[serde(default)]
     pub is_cfg: bool,
     /// True si esta sección es un electivo de especialización
     /// (está en la oferta académica pero NO en la malla curricular)
+    #[serde(default)]
     pub is_electivo: bool,
+    /// Línea de formación del electivo ("informatica", "telecomunicaciones",
+    /// "gestion", ...) según el catálogo de `excel::electivos`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linea: Option<String>,
+    /// Sala(s) donde se dicta, si la Oferta Académica trae la columna
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sala: Option<String>,
+    /// Campus (o sede) donde se dicta, si la Oferta Académica trae la columna
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campus: Option<String>,
+    /// Cátedra, laboratorio/taller o ayudantía
+    #[serde(default)]
+    pub componente: Componente,
+    /// Paquete cátedra + laboratorio/ayudantía que se inscribe junto
+    /// ("CBF1000#1"); None si la sección se inscribe sola
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+    /// Cupos (vacantes) de la sección, si la Oferta Académica trae la columna
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cupos_totales: Option<u32>,
+    /// Cupos aún disponibles, si la Oferta Académica trae la columna
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cupos_disponibles: Option<u32>,
+    /// Evaluaciones del curso según el calendario de evaluaciones, si hay
+    /// uno en los datafiles (ver `excel::evaluaciones`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evaluaciones: Vec<Evaluacion>,
+}
+
+/// Evaluación (certamen, examen) de un curso
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+pub struct Evaluacion {
+    /// "Certamen 1", "Examen", ...
+    pub nombre: String,
+    /// AAAA-MM-DD
+    pub fecha: String,
+}
+
+impl Seccion {
+    /// true si la oferta informa que no quedan cupos
+    pub fn sin_cupos(&self) -> bool {
+        self.cupos_disponibles == Some(0)
+    }
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RamoDisponible {
     /// ID único dentro de Malla2020 (1-57 típicamente)
     /// Usado para resolver dependencias en PERT
@@ -94,19 +237,35 @@ pub struct RamoDisponible {
     /// Código de la oferta (PA2025-1). Ej: "CIT2107"
     /// Usado para búsqueda en oferta y como referencia universal
     pub codigo: String,
+    #[serde(default)]
     pub holgura: i32,
+    #[serde(default)]
     pub numb_correlativo: i32,
+    #[serde(default)]
     pub critico: bool,
     /// IDs de los ramos prerequisitos (para dependencias PERT)
     /// Lista de IDs de ramos que deben ser aprobados antes de tomar este
+    #[serde(default)]
     pub requisitos_ids: Vec<i32>,
+    /// IDs de los co-requisitos: ramos que deben estar aprobados o tomarse
+    /// en el mismo semestre que este
+    #[serde(default)]
+    pub correquisitos_ids: Vec<i32>,
     /// Porcentaje de aprobados (0.0 - 100.0). Se usará como estimador de dificultad inversa.
     /// Valores cercanos a 0 => muy difícil, cercanos a 100 => muy fácil.
     pub dificultad: Option<f64>,
     /// True si es un ramo electivo (puede elegirse entre opciones)
+    #[serde(default)]
     pub electivo: bool,
     /// Semestre curricular (1 = S1, 2 = S2, etc.)
     pub semestre: Option<i32>,
+    /// Créditos SCT del ramo según la malla (None si la malla no trae la columna)
+    #[serde(default)]
+    pub creditos: Option<i32>,
+    /// Notas de los asesores desde la hoja "Notas" de la malla ("requiere
+    /// buena base de programación", "proyecto pesado al final", ...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 #[allow(dead_code)]
@@ -119,4 +278,28 @@ pub struct PertNode {
     pub ls: Option<i32>,  // Latest Start
     pub lf: Option<i32>,  // Latest Finish
     pub h: Option<i32>,   // Holgura
-}
\ No newline at end of file
+}
+/// Fila de `algorithm::merge_malla_oferta_porcentajes`: un ramo de la malla
+/// con la sección de la oferta y el porcentaje que le correspondieron. Los
+/// campos de oferta/porcentajes quedan en `None` si no hubo match.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MergedCurso {
+    pub malla_codigo: String,
+    pub malla_nombre: String,
+    pub oferta_codigo: Option<String>,
+    pub oferta_codigo_box: Option<String>,
+    pub oferta_nombre: Option<String>,
+    pub pa_codigo: Option<String>,
+    pub porcentaje: Option<f64>,
+    pub total: Option<f64>,
+    /// Solo cuando el porcentaje vino del índice por nombre (que lo informa)
+    pub es_electivo: Option<bool>,
+}
+
+impl MergedCurso {
+    /// Encabezado de `merged_a_csv`, en el orden de los campos
+    pub const COLUMNAS: [&'static str; 9] = [
+        "malla_codigo", "malla_nombre", "oferta_codigo", "oferta_codigo_box", "oferta_nombre",
+        "pa_codigo", "porcentaje", "total", "es_electivo",
+    ];
+}
@@ -0,0 +1,217 @@
+// reglas.rs - Reglas institucionales de cada malla
+//
+// Cuántos CFG y cuántos electivos de especialidad exige una malla estaban
+// fijos en el solver y en `cursos_disponibles` (4 y 3). `reglas.toml` (o el
+// archivo de `REGLAS_FILE`) los declara por malla:
+//
+//   [default]
+//   max_cfg = 4
+//   max_electivos = 3
+//
+//   [malla."MC2010.xlsx"]                     # por nombre de archivo o sin extensión
+//   max_electivos = 2
+//
+// Un campo que la malla no declara se toma del `max_cfg` de la carrera del
+// request (ver `carreras`), si trae una, y si no de `[default]`. Sin archivo
+// valen `MAX_CFG_POR_DEFECTO` y `MAX_ELECTIVOS_POR_DEFECTO`.
+//
+// `max_ramos_verano` es el tope de ramos por solución al planificar el
+// verano (`InputParams::periodo`); en el período regular el tope es 6. Con
+// `max_creditos` el límite son los créditos y el tope de ramos no aplica.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use crate::api_json::{InputParams, Periodo};
+use crate::carreras::{Carrera, MAX_CFG_POR_DEFECTO};
+use crate::excel::normalize_name;
+use crate::models::RamoDisponible;
+
+/// Electivos de especialidad exigidos cuando nada los declara
+pub const MAX_ELECTIVOS_POR_DEFECTO: usize = 3;
+/// Ramos por solución en el período regular
+pub const MAX_RAMOS_POR_DEFECTO: usize = 6;
+/// Ramos por solución en verano cuando nada lo declara
+pub const MAX_RAMOS_VERANO_POR_DEFECTO: usize = 2;
+
+/// Reglas vigentes de una malla
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReglasMalla {
+    /// CFGs que exige la malla en total
+    pub max_cfg: usize,
+    /// Electivos de especialidad (fuera de la malla o marcados como electivo) que exige en total
+    pub max_electivos: usize,
+    /// Tope de ramos por solución en verano
+    pub max_ramos_verano: usize,
+}
+
+/// Campos declarados en `reglas.toml`; los omitidos se heredan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AjusteReglas {
+    #[serde(default)]
+    pub max_cfg: Option<usize>,
+    #[serde(default)]
+    pub max_electivos: Option<usize>,
+    #[serde(default)]
+    pub max_ramos_verano: Option<usize>,
+}
+
+/// Contenido de `reglas.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistroReglas {
+    #[serde(default)]
+    pub default: AjusteReglas,
+    #[serde(default)]
+    pub malla: HashMap<String, AjusteReglas>,
+}
+
+/// Nombre de archivo sin extensión, en mayúsculas ("datafiles/MC2020.xlsx" -> "MC2020")
+fn clave_malla(malla: &str) -> String {
+    let ruta = Path::new(malla.trim());
+    ruta.file_stem().and_then(|n| n.to_str()).unwrap_or(malla).trim().to_uppercase()
+}
+
+impl RegistroReglas {
+    pub fn desde_toml(texto: &str) -> Result<Self, String> {
+        let registro: RegistroReglas = crate::app_config::desde_toml(texto).map_err(|e| format!("reglas.toml inválido: {}", e))?;
+        let mut claves = HashSet::new();
+        for malla in registro.malla.keys() {
+            if !claves.insert(clave_malla(malla)) {
+                return Err(format!("reglas.toml: malla repetida '{}'", malla));
+            }
+        }
+        Ok(registro)
+    }
+
+    /// Reglas de `malla`, con el `max_cfg` de `carrera` cuando la malla no lo declara
+    pub fn para(&self, malla: &str, carrera: Option<&Carrera>) -> ReglasMalla {
+        let clave = clave_malla(malla);
+        let propia = self.malla.iter()
+            .find(|(m, _)| clave_malla(m) == clave)
+            .map(|(_, a)| *a)
+            .unwrap_or_default();
+        ReglasMalla {
+            max_cfg: propia.max_cfg
+                .or(carrera.map(|c| c.max_cfg))
+                .or(self.default.max_cfg)
+                .unwrap_or(MAX_CFG_POR_DEFECTO),
+            max_electivos: propia.max_electivos
+                .or(self.default.max_electivos)
+                .unwrap_or(MAX_ELECTIVOS_POR_DEFECTO),
+            max_ramos_verano: propia.max_ramos_verano
+                .or(self.default.max_ramos_verano)
+                .unwrap_or(MAX_RAMOS_VERANO_POR_DEFECTO)
+                .max(1),
+        }
+    }
+}
+
+/// Ruta de las reglas: `reglas_file` de la configuración (`REGLAS_FILE`, por
+/// defecto `reglas.toml` en el directorio actual)
+pub fn ruta_registro() -> PathBuf {
+    crate::app_config::actual().reglas_file.clone()
+}
+
+fn global() -> &'static RwLock<Option<Arc<RegistroReglas>>> {
+    static REGISTRO: OnceLock<RwLock<Option<Arc<RegistroReglas>>>> = OnceLock::new();
+    REGISTRO.get_or_init(|| RwLock::new(None))
+}
+
+/// Lee las reglas desde disco. Sin archivo (o con un archivo inválido, que se
+/// avisa) todas las mallas usan los valores por defecto.
+pub fn cargar_registro(ruta: &Path) -> RegistroReglas {
+    let Ok(texto) = std::fs::read_to_string(ruta) else {
+        return RegistroReglas::default();
+    };
+    match RegistroReglas::desde_toml(&texto) {
+        Ok(r) => {
+            tracing::info!("📏 Reglas de {} malla(s) desde {:?}", r.malla.len(), ruta);
+            r
+        }
+        Err(e) => {
+            tracing::warn!("⚠️  {}", e);
+            RegistroReglas::default()
+        }
+    }
+}
+
+/// Reglas vigentes (se leen de `ruta_registro()` la primera vez)
+pub fn registro() -> Arc<RegistroReglas> {
+    if let Some(r) = global().read().ok().and_then(|g| g.clone()) {
+        return r;
+    }
+    let r = Arc::new(cargar_registro(&ruta_registro()));
+    if let Ok(mut g) = global().write() {
+        *g = Some(r.clone());
+    }
+    r
+}
+
+/// Reemplaza las reglas vigentes (recarga o pruebas)
+pub fn establecer_registro(registro: RegistroReglas) {
+    if let Ok(mut g) = global().write() {
+        *g = Some(Arc::new(registro));
+    }
+}
+
+/// Vuelve a leer `reglas.toml`. Devuelve cuántas mallas declara.
+pub fn recargar_registro() -> usize {
+    let r = cargar_registro(&ruta_registro());
+    let n = r.malla.len();
+    establecer_registro(r);
+    n
+}
+
+/// Reglas de una malla, opcionalmente dentro de una carrera del registro
+pub fn reglas_de(malla: &str, carrera: Option<&str>) -> ReglasMalla {
+    let carrera = carrera.filter(|c| !c.trim().is_empty()).and_then(|c| crate::carreras::registro().get(c).cloned());
+    registro().para(malla, carrera.as_ref())
+}
+
+/// Reglas de la malla (y carrera) del request
+pub fn de_params(params: &InputParams) -> ReglasMalla {
+    reglas_de(&params.malla, params.carrera.as_deref())
+}
+
+/// Tope de ramos por solución del request: 6 en el período regular,
+/// `max_ramos_verano` de su malla en verano
+pub fn max_ramos(params: &InputParams) -> usize {
+    match params.periodo {
+        Periodo::Regular => MAX_RAMOS_POR_DEFECTO,
+        Periodo::Verano => de_params(params).max_ramos_verano,
+    }
+}
+
+/// Tope de ramos que limita la solución: `max_ramos`, salvo que el request
+/// fije `max_creditos`, que pasa a ser el único límite (`None`)
+pub fn tope_ramos(params: &InputParams) -> Option<usize> {
+    match params.max_creditos {
+        Some(_) => None,
+        None => Some(max_ramos(params)),
+    }
+}
+
+/// CFGs entre los ramos aprobados
+pub fn cfgs_aprobados(ramos_pasados: &[String]) -> usize {
+    ramos_pasados.iter().filter(|r| r.trim().to_uppercase().starts_with("CFG")).count()
+}
+
+/// Electivos entre los ramos aprobados: los que no son CFG y no están en la
+/// malla (por código o nombre) o están en ella como electivo
+pub fn electivos_aprobados(ramos_pasados: &[String], ramos: &HashMap<String, RamoDisponible>) -> usize {
+    ramos_pasados.iter()
+        .filter(|r| {
+            let codigo = r.trim().to_uppercase();
+            if codigo.starts_with("CFG") {
+                return false;
+            }
+            let nombre = normalize_name(r);
+            ramos.values()
+                .find(|m| m.codigo.eq_ignore_ascii_case(&codigo) || normalize_name(&m.nombre) == nombre)
+                .is_none_or(|m| m.electivo)
+        })
+        .count()
+}
@@ -0,0 +1,384 @@
+// scoring.rs - Pesos del score de las soluciones
+//
+// El score de una solución es la suma de la prioridad de cada sección
+// (`algorithm::prioridad::Priority`, el CC UU KK SS de RutaCritica.py) más los
+// modificadores por preferencias del usuario (`clique::modificadores_score`).
+// Los pesos de ambos se leen de `scoring.toml` (o del archivo de
+// `SCORING_FILE`), por ejemplo:
+//
+//   bonus_ramo_prioritario = 100000000
+//   penalizacion_gap_por_minuto = 250
+//
+// Los campos omitidos conservan su valor por defecto y cada uno se puede
+// reemplazar con una variable de entorno `SCORING_<CAMPO>` en mayúsculas
+// (p.ej. `SCORING_PESO_COMPACTACION=20000`). Un request puede ajustar los
+// pesos solo para sí mismo con `InputParams::scoring`.
+//
+// Con los valores por defecto la prioridad coincide con la concatenación
+// CC UU KK SS: 10_000_000 si es crítico, 10_000 por punto de (10 - holgura),
+// 100 por punto de (60 - correlativo) y 1 por número de sección.
+//
+// Con `InputParams::usar_historial` cada sección que los estudiantes rara vez
+// confirman (ver `HistorialSeccion::rechazada`) resta `penalizacion_historial`.
+// El historial lo calcula la fuente que registre el servidor
+// (`analithics::queries::historial_secciones`) y se relee cada
+// `VIGENCIA_HISTORIAL`.
+//
+// Al planificar el verano (`InputParams::periodo`) caben uno o dos ramos, así
+// que el score se reduce a elegir cuáles: los de la ruta crítica pesan
+// `FACTOR_CRITICO_VERANO` veces más y la compactación no cuenta (los cursos
+// de verano son intensivos y se dictan casi todos los días).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use crate::api_json::{InputParams, Periodo};
+use crate::models::Seccion;
+
+/// Multiplica `bonus_critico` al planificar el verano
+pub const FACTOR_CRITICO_VERANO: i64 = 10;
+
+/// Pesos vigentes del score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoringConfig {
+    /// CC: bonus de prioridad de un ramo crítico (ruta crítica)
+    pub bonus_critico: i64,
+    /// UU: peso por cada punto de (10 - holgura)
+    pub peso_holgura: i64,
+    /// KK: peso por cada punto de (60 - número correlativo)
+    pub peso_correlativo: i64,
+    /// SS: peso del número de sección
+    pub peso_seccion: i64,
+    /// Por cada ramo prioritario en la solución
+    pub bonus_ramo_prioritario: i64,
+    /// Por cada ramo reprobado (`InputParams::ramos_reprobados`) en la solución
+    pub bonus_ramo_reprobado: i64,
+    /// Por sección con profesor preferido (o a evitar) en modo `soft`
+    pub bonus_profesor: i64,
+    /// Con desviación 1 respecto de `balance_lineas`
+    pub penalizacion_balance_lineas: i64,
+    /// Por par de clases sin tiempo de traslado (modo `soft`)
+    pub penalizacion_traslado: i64,
+    /// Por minuto de ventana sobre `ventana_ideal_minutos` (`minimizar_ventanas`)
+    pub penalizacion_ventana_por_minuto: i64,
+    /// Por punto de compactness (0-100) con `compact-days` / `spread-days`
+    pub peso_compactacion: i64,
+    /// Por minuto de ventana con `minimize-gaps`
+    pub penalizacion_gap_por_minuto: i64,
+    /// Por sección sin cupos disponibles en la solución
+    pub penalizacion_sin_cupos: i64,
+    /// Por compromiso `soft` que la solución pisa (si el compromiso no trae `peso`)
+    pub penalizacion_compromiso: i64,
+    /// Por punto de carga de evaluaciones (ver `algorithm::evaluaciones`)
+    pub penalizacion_carga_evaluaciones: i64,
+    /// Por sección que los estudiantes rara vez confirman (`usar_historial`)
+    pub penalizacion_historial: i64,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        ScoringConfig {
+            bonus_critico: 10_000_000,
+            peso_holgura: 10_000,
+            peso_correlativo: 100,
+            peso_seccion: 1,
+            bonus_ramo_prioritario: 100_000_000,
+            bonus_ramo_reprobado: 20_000_000,
+            bonus_profesor: 50_000,
+            penalizacion_balance_lineas: 500_000,
+            penalizacion_traslado: 50_000,
+            penalizacion_ventana_por_minuto: 1_000,
+            peso_compactacion: 10_000,
+            penalizacion_gap_por_minuto: 100,
+            penalizacion_sin_cupos: 1_000_000,
+            penalizacion_compromiso: 5_000_000,
+            penalizacion_carga_evaluaciones: 25_000,
+            penalizacion_historial: 20_000,
+        }
+    }
+}
+
+/// Ajustes de pesos de un request (`InputParams::scoring`). Los campos omitidos
+/// usan la configuración del servidor.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ScoringOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bonus_critico: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peso_holgura: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peso_correlativo: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peso_seccion: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bonus_ramo_prioritario: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bonus_ramo_reprobado: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bonus_profesor: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub penalizacion_balance_lineas: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub penalizacion_traslado: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub penalizacion_ventana_por_minuto: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peso_compactacion: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub penalizacion_gap_por_minuto: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub penalizacion_sin_cupos: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub penalizacion_compromiso: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub penalizacion_carga_evaluaciones: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub penalizacion_historial: Option<i64>,
+}
+
+impl ScoringConfig {
+    /// Parsea `scoring.toml`; los campos omitidos quedan con su valor por defecto
+    pub fn desde_toml(texto: &str) -> Result<Self, String> {
+        crate::app_config::desde_toml(texto).map_err(|e| format!("scoring.toml inválido: {}", e))
+    }
+
+    /// (nombre, valor) de cada peso, en el orden de declaración
+    fn campos_mut(&mut self) -> [(&'static str, &mut i64); 16] {
+        [
+            ("bonus_critico", &mut self.bonus_critico),
+            ("peso_holgura", &mut self.peso_holgura),
+            ("peso_correlativo", &mut self.peso_correlativo),
+            ("peso_seccion", &mut self.peso_seccion),
+            ("bonus_ramo_prioritario", &mut self.bonus_ramo_prioritario),
+            ("bonus_ramo_reprobado", &mut self.bonus_ramo_reprobado),
+            ("bonus_profesor", &mut self.bonus_profesor),
+            ("penalizacion_balance_lineas", &mut self.penalizacion_balance_lineas),
+            ("penalizacion_traslado", &mut self.penalizacion_traslado),
+            ("penalizacion_ventana_por_minuto", &mut self.penalizacion_ventana_por_minuto),
+            ("peso_compactacion", &mut self.peso_compactacion),
+            ("penalizacion_gap_por_minuto", &mut self.penalizacion_gap_por_minuto),
+            ("penalizacion_sin_cupos", &mut self.penalizacion_sin_cupos),
+            ("penalizacion_compromiso", &mut self.penalizacion_compromiso),
+            ("penalizacion_carga_evaluaciones", &mut self.penalizacion_carga_evaluaciones),
+            ("penalizacion_historial", &mut self.penalizacion_historial),
+        ]
+    }
+
+    /// Aplica las variables `SCORING_<CAMPO>` que existan y sean enteros válidos
+    pub fn con_env(mut self) -> Self {
+        for (nombre, valor) in self.campos_mut() {
+            let var = format!("SCORING_{}", nombre.to_uppercase());
+            let Ok(texto) = std::env::var(&var) else { continue };
+            match texto.trim().replace('_', "").parse::<i64>() {
+                Ok(v) => *valor = v,
+                Err(_) => tracing::warn!("⚠️  {}='{}' no es un entero, se ignora", var, texto),
+            }
+        }
+        self
+    }
+
+    /// Copia con los ajustes de un request
+    pub fn con_overrides(mut self, o: &ScoringOverrides) -> Self {
+        let ajustes = [
+            o.bonus_critico,
+            o.peso_holgura,
+            o.peso_correlativo,
+            o.peso_seccion,
+            o.bonus_ramo_prioritario,
+            o.bonus_ramo_reprobado,
+            o.bonus_profesor,
+            o.penalizacion_balance_lineas,
+            o.penalizacion_traslado,
+            o.penalizacion_ventana_por_minuto,
+            o.peso_compactacion,
+            o.penalizacion_gap_por_minuto,
+            o.penalizacion_sin_cupos,
+            o.penalizacion_compromiso,
+            o.penalizacion_carga_evaluaciones,
+            o.penalizacion_historial,
+        ];
+        for ((_, valor), ajuste) in self.campos_mut().into_iter().zip(ajustes) {
+            if let Some(v) = ajuste {
+                *valor = v;
+            }
+        }
+        self
+    }
+}
+
+/// Ruta de la configuración: `scoring_file` de `app_config` (`SCORING_FILE`,
+/// por defecto `scoring.toml` en el directorio actual)
+pub fn ruta_config() -> PathBuf {
+    crate::app_config::actual().scoring_file.clone()
+}
+
+fn global() -> &'static RwLock<Option<ScoringConfig>> {
+    static CONFIG: OnceLock<RwLock<Option<ScoringConfig>>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(None))
+}
+
+/// Lee la configuración desde disco y aplica el entorno. Sin archivo (o con
+/// un archivo inválido, que se avisa) se parte de los valores por defecto.
+pub fn cargar_config(ruta: &Path) -> ScoringConfig {
+    let base = match std::fs::read_to_string(ruta) {
+        Ok(texto) => match ScoringConfig::desde_toml(&texto) {
+            Ok(c) => {
+                tracing::info!("⚖️  Pesos de scoring desde {:?}", ruta);
+                c
+            }
+            Err(e) => {
+                tracing::warn!("⚠️  {}", e);
+                ScoringConfig::default()
+            }
+        },
+        Err(_) => ScoringConfig::default(),
+    };
+    base.con_env()
+}
+
+/// Configuración del servidor (se lee de `ruta_config()` la primera vez)
+pub fn config() -> ScoringConfig {
+    if let Some(c) = global().read().ok().and_then(|g| *g) {
+        return c;
+    }
+    let c = cargar_config(&ruta_config());
+    if let Ok(mut g) = global().write() {
+        *g = Some(c);
+    }
+    c
+}
+
+/// Reemplaza la configuración del servidor (recarga o pruebas)
+pub fn establecer_config(config: ScoringConfig) {
+    if let Ok(mut g) = global().write() {
+        *g = Some(config);
+    }
+}
+
+/// Vuelve a leer `scoring.toml` y el entorno
+pub fn recargar_config() -> ScoringConfig {
+    let c = cargar_config(&ruta_config());
+    establecer_config(c);
+    c
+}
+
+/// Pesos de verano a partir de los del servidor
+pub fn para_verano(mut c: ScoringConfig) -> ScoringConfig {
+    c.bonus_critico = c.bonus_critico.saturating_mul(FACTOR_CRITICO_VERANO);
+    c.peso_compactacion = 0;
+    c
+}
+
+/// Pesos efectivos de un request: los del servidor (ajustados al período)
+/// con `params.scoring` encima
+pub fn efectiva(params: &InputParams) -> ScoringConfig {
+    let base = match params.periodo {
+        Periodo::Regular => config(),
+        Periodo::Verano => para_verano(config()),
+    };
+    match &params.scoring {
+        Some(o) => base.con_overrides(o),
+        None => base,
+    }
+}
+
+/// Veces que una sección debe haberse ofrecido para que su tasa cuente
+pub const MIN_OFRECIDA_HISTORIAL: u32 = 5;
+/// Tasa de confirmación bajo la cual una sección se penaliza
+pub const TASA_CONFIRMACION_BAJA: f64 = 0.1;
+/// Cada cuánto se vuelve a pedir el historial a su fuente
+pub const VIGENCIA_HISTORIAL: Duration = Duration::from_secs(600);
+
+/// Confirmaciones de una sección en las soluciones guardadas: en cuántas
+/// confirmaciones la sección aparecía en alguna solución del conjunto y en
+/// cuántas estaba en la solución elegida
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct HistorialSeccion {
+    pub ofrecida: u32,
+    pub confirmada: u32,
+}
+
+impl HistorialSeccion {
+    pub fn tasa(&self) -> f64 {
+        if self.ofrecida == 0 { 0.0 } else { self.confirmada as f64 / self.ofrecida as f64 }
+    }
+
+    /// Ofrecida al menos `MIN_OFRECIDA_HISTORIAL` veces y confirmada en menos
+    /// de `TASA_CONFIRMACION_BAJA` de ellas
+    pub fn rechazada(&self) -> bool {
+        self.ofrecida >= MIN_OFRECIDA_HISTORIAL && self.tasa() < TASA_CONFIRMACION_BAJA
+    }
+}
+
+/// Historial por `codigo_box` (en mayúsculas)
+pub type Historial = HashMap<String, HistorialSeccion>;
+/// De dónde sale el historial (la registra el servidor)
+pub type FuenteHistorial = fn() -> Result<Historial, String>;
+
+#[derive(Default)]
+struct EstadoHistorial {
+    fuente: Option<FuenteHistorial>,
+    datos: Arc<Historial>,
+    leido: Option<Instant>,
+}
+
+fn historial_global() -> &'static RwLock<EstadoHistorial> {
+    static HISTORIAL: OnceLock<RwLock<EstadoHistorial>> = OnceLock::new();
+    HISTORIAL.get_or_init(|| RwLock::new(EstadoHistorial::default()))
+}
+
+/// Registra la fuente del historial; se lee en el próximo `preparar_historial`
+pub fn registrar_fuente_historial(fuente: FuenteHistorial) {
+    if let Ok(mut h) = historial_global().write() {
+        h.fuente = Some(fuente);
+        h.leido = None;
+    }
+}
+
+/// Reemplaza el historial vigente (pruebas, o sin fuente registrada)
+pub fn establecer_historial(historial: Historial) {
+    if let Ok(mut h) = historial_global().write() {
+        h.datos = Arc::new(historial);
+        h.leido = Some(Instant::now());
+    }
+}
+
+/// Relee el historial de su fuente si pasó `VIGENCIA_HISTORIAL` desde la
+/// última lectura. Se llama una vez por solve (no por solución evaluada); si
+/// la fuente falla se conserva el historial anterior.
+pub fn preparar_historial() {
+    let fuente = match historial_global().read() {
+        Ok(h) if h.leido.is_none_or(|t| t.elapsed() >= VIGENCIA_HISTORIAL) => h.fuente,
+        _ => None,
+    };
+    let Some(fuente) = fuente else { return };
+    match fuente() {
+        Ok(historial) => {
+            tracing::debug!("historial de confirmaciones: {} secciones", historial.len());
+            establecer_historial(historial);
+        }
+        Err(e) => {
+            tracing::warn!("⚠️  no se pudo leer el historial de confirmaciones: {}", e);
+            if let Ok(mut h) = historial_global().write() {
+                h.leido = Some(Instant::now());
+            }
+        }
+    }
+}
+
+/// Historial vigente (vacío si nunca se cargó)
+pub fn historial() -> Arc<Historial> {
+    historial_global().read().map(|h| Arc::clone(&h.datos)).unwrap_or_default()
+}
+
+/// Secciones de `secciones` que el historial marca como rechazadas
+pub fn secciones_rechazadas<'a>(secciones: impl IntoIterator<Item = &'a Seccion>, historial: &Historial) -> usize {
+    secciones.into_iter()
+        .filter(|s| historial.get(&s.codigo_box.trim().to_uppercase()).is_some_and(|h| h.rechazada()))
+        .count()
+}
@@ -1,6 +1,5 @@
 use actix_web::{web, App, HttpResponse, HttpServer, Responder, HttpRequest};
 use actix_cors::Cors;
-use actix_multipart::Multipart;
 use serde_json::json;
 use crate::algorithm::{extract_data, get_clique_with_user_prefs};
 use crate::models::Seccion;
@@ -18,12 +17,39 @@ async fn solve_handler(req: HttpRequest, body: web::Json<serde_json::Value>) ->
     crate::server_handlers::solve::solve_handler(req, body).await
 }
 
+/// POST /solve/session
+/// Igual que `/solve`, pero deja en memoria los datos cargados y el grafo de
+/// compatibilidad para refinar la búsqueda sin releer los Excel.
+async fn solve_session_create_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    crate::server_handlers::session::solve_session_create_handler(body).await
+}
+
+/// POST /solve/session/{id}/refine
+/// Reaplica filtros, optimizaciones y ramos prioritarios sobre una sesión existente.
+async fn solve_session_refine_handler(
+    path: web::Path<String>,
+    body: web::Json<crate::server_handlers::session::RefineRequest>,
+) -> impl Responder {
+    crate::server_handlers::session::solve_session_refine_handler(path, body).await
+}
+
+/// DELETE /solve/session/{id}
+async fn solve_session_delete_handler(path: web::Path<String>) -> impl Responder {
+    crate::server_handlers::session::solve_session_delete_handler(path).await
+}
+
 /// Handler para obtener los mejores caminos desde un JSON de `PathsOutput` o un
 /// `file_path` que apunte a un JSON en disco generado por Ruta crítica.
 async fn rutacomoda_best_handler(body: web::Json<serde_json::Value>) -> impl Responder {
     crate::server_handlers::rutacritica::rutacomoda_best_handler(body).await
 }
 
+/// POST /rutacomoda/rank
+/// Reordena soluciones (recibidas o recién calculadas) por comodidad.
+async fn rutacomoda_rank_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    crate::server_handlers::rutacritica::rutacomoda_rank_handler(body).await
+}
+
 async fn rutacritica_run_handler(body: web::Json<serde_json::Value>) -> impl Responder {
     crate::server_handlers::rutacritica::rutacritica_run_handler(body).await
 }
@@ -63,6 +89,176 @@ async fn save_student_handler(body: web::Json<serde_json::Value>) -> impl Respon
     crate::api_json::handlers::students::save_student_handler(body).await
 }
 
+/// POST /students/import-avance
+/// Convierte un avance curricular (CSV) en `ramos_pasados` de la malla.
+async fn import_avance_handler(req: HttpRequest, body: web::Bytes) -> impl Responder {
+    crate::api_json::handlers::students::import_avance_handler(req, body).await
+}
+
+/// GET /students/{email}/bloqueantes
+/// Ramos que el estudiante debe tomar ya para no retrasar su titulación.
+async fn student_bloqueantes_handler(path: web::Path<String>) -> impl Responder {
+    crate::api_json::handlers::students::student_bloqueantes_handler(path).await
+}
+
+/// POST /students/{email}/scenarios
+/// Guarda una versión nueva de un escenario con nombre.
+async fn save_scenario_handler(path: web::Path<String>, body: web::Json<crate::api_json::handlers::students::GuardarEscenarioRequest>) -> impl Responder {
+    crate::api_json::handlers::students::save_scenario_handler(path, body).await
+}
+
+/// GET /students/{email}/scenarios
+async fn list_scenarios_handler(path: web::Path<String>) -> impl Responder {
+    crate::api_json::handlers::students::list_scenarios_handler(path).await
+}
+
+/// GET /students/{email}/scenarios/diff?a=..&b=..
+async fn diff_scenarios_handler(path: web::Path<String>, query: web::Query<crate::api_json::handlers::students::DiffEscenariosQuery>) -> impl Responder {
+    crate::api_json::handlers::students::diff_scenarios_handler(path, query).await
+}
+
+/// GET /students/{email}/scenarios/{nombre}[?version=N]
+async fn get_scenario_handler(path: web::Path<(String, String)>, query: web::Query<crate::api_json::handlers::students::VersionQuery>) -> impl Responder {
+    crate::api_json::handlers::students::get_scenario_handler(path, query).await
+}
+
+/// POST /students/{email}/scenarios/{nombre}/restore
+async fn restore_scenario_handler(path: web::Path<(String, String)>, body: web::Json<crate::api_json::handlers::students::RestaurarEscenarioRequest>) -> impl Responder {
+    crate::api_json::handlers::students::restore_scenario_handler(path, body).await
+}
+
+/// POST /admin/rollover
+/// Cambio de semestre: valida archivos nuevos, archiva el periodo anterior,
+/// invalida cachés y precalienta.
+async fn admin_rollover_handler(req: HttpRequest, body: web::Json<crate::server_handlers::RolloverRequest>) -> impl Responder {
+    crate::server_handlers::rollover::admin_rollover_handler(req, body).await
+}
+
+/// POST /plan/carrera
+/// Proyecta semestre a semestre (PERT + clique) hasta completar la malla.
+async fn plan_carrera_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    crate::server_handlers::planner::plan_carrera_handler(body).await
+}
+
+/// POST /simulate/reprobacion
+/// Impacto de reprobar un ramo en curso sobre los semestres siguientes.
+async fn simular_reprobacion_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    crate::server_handlers::simulacion::simular_reprobacion_handler(body).await
+}
+
+/// POST /progress
+/// Avance por semestre hacia la titulación y semestres restantes estimados.
+async fn progreso_handler(body: web::Json<crate::server_handlers::progreso::ProgresoRequest>) -> impl Responder {
+    crate::server_handlers::progreso::progreso_handler(body).await
+}
+
+/// POST /simulate/demanda
+/// Inscripción esperada por sección en una cohorte frente a sus cupos.
+async fn simular_demanda_handler(req: HttpRequest, body: web::Bytes) -> impl Responder {
+    crate::server_handlers::simulacion::simular_demanda_handler(req, body).await
+}
+
+/// POST /solve/explain
+/// Motivos por los que un ramo no aparece en ninguna solución.
+async fn solve_explain_handler(body: web::Json<serde_json::Value>, idioma: crate::i18n::Idioma) -> impl Responder {
+    crate::server_handlers::explain::explain_handler(body, idioma).await
+}
+
+/// POST /solve/precheck
+/// Ramos y secciones que sobreviven a cada etapa de filtrado, sin resolver.
+async fn solve_precheck_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    crate::server_handlers::prechequeo::precheck_handler(body).await
+}
+
+/// POST /solve/batch
+/// Resuelve a una cohorte completa (JSON o CSV de estudiantes) con un pool acotado.
+async fn solve_batch_handler(req: HttpRequest, body: web::Bytes) -> impl Responder {
+    crate::server_handlers::batch::solve_batch_handler(req, body).await
+}
+
+/// POST /solve/export/ics
+/// Exporta una solución como calendario iCalendar (.ics).
+async fn solve_export_ics_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    crate::server_handlers::export::export_ics_handler(body).await
+}
+
+/// POST /solve/export/xlsx
+/// Resuelve y exporta las soluciones como workbook XLSX (una hoja por solución).
+#[cfg(feature = "export-xlsx")]
+async fn solve_export_xlsx_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    crate::server_handlers::export::export_xlsx_handler(body).await
+}
+
+/// POST /solve/export/pdf
+/// Resuelve y exporta el horario de cada solución en PDF para imprimir.
+#[cfg(feature = "export-pdf")]
+async fn solve_export_pdf_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    crate::server_handlers::export::export_pdf_handler(body).await
+}
+
+/// Rutas que dependen de features opcionales
+fn rutas_opcionales(cfg: &mut web::ServiceConfig) {
+    #[cfg(feature = "export-xlsx")]
+    cfg.route("/solve/export/xlsx", web::post().to(solve_export_xlsx_handler));
+    #[cfg(feature = "export-pdf")]
+    cfg.route("/solve/export/pdf", web::post().to(solve_export_pdf_handler));
+    #[cfg(not(any(feature = "export-xlsx", feature = "export-pdf")))]
+    let _ = cfg;
+}
+
+/// POST /solve/async
+/// Encola el mismo body de `/solve` y responde 202 con el `job_id`.
+async fn solve_async_handler(req: HttpRequest, body: web::Json<serde_json::Value>) -> impl Responder {
+    crate::server_handlers::jobs::solve_async_handler(req, body).await
+}
+
+/// GET /jobs/{id}
+/// Estado y avance de un job de `/solve/async`.
+async fn job_status_handler(path: web::Path<String>) -> impl Responder {
+    crate::server_handlers::jobs::job_status_handler(path).await
+}
+
+/// GET /jobs/{id}/result
+async fn job_result_handler(path: web::Path<String>) -> impl Responder {
+    crate::server_handlers::jobs::job_result_handler(path).await
+}
+
+/// POST /solutions/compare
+/// Diferencias entre dos soluciones (ramos, secciones y métricas).
+async fn compare_solutions_handler(body: web::Json<crate::server_handlers::CompararSolucionesRequest>) -> impl Responder {
+    crate::server_handlers::soluciones::compare_solutions_handler(body).await
+}
+
+/// GET /solutions/{id}
+/// Conjunto de soluciones guardado por `/solve` (`solution_id` de la respuesta).
+async fn get_solution_handler(path: web::Path<String>) -> impl Responder {
+    crate::server_handlers::soluciones::get_solution_handler(path).await
+}
+
+/// GET /solutions/{id}/export/ics
+/// Exporta la solución `indice` de un conjunto guardado como iCalendar.
+async fn solution_export_ics_handler(path: web::Path<String>, query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    crate::server_handlers::soluciones::solution_export_ics_handler(path, query).await
+}
+
+/// POST /solutions/{id}/confirm
+/// Vincula la solución elegida al perfil del estudiante.
+async fn confirm_solution_handler(path: web::Path<String>, body: web::Json<crate::server_handlers::ConfirmarSolucionRequest>) -> impl Responder {
+    crate::server_handlers::soluciones::confirm_solution_handler(path, body).await
+}
+
+/// POST /solutions/{id}/feedback
+/// Nota (1-5) y comentario sobre una solución recomendada.
+async fn solution_feedback_handler(path: web::Path<String>, body: web::Json<crate::server_handlers::FeedbackRequest>) -> impl Responder {
+    crate::server_handlers::soluciones::solution_feedback_handler(path, body).await
+}
+
+/// GET /students/{email}/solution
+/// Última solución confirmada por el estudiante.
+async fn student_solution_handler(path: web::Path<String>) -> impl Responder {
+    crate::server_handlers::soluciones::student_solution_handler(path).await
+}
+
 // OpenAPI and Swagger UI are served from the `api_json::handlers::docs` module.
 
 // Nuevo handler para servir el OpenAPI JSON
@@ -70,7 +266,7 @@ async fn openapi_json_handler() -> impl Responder {
     crate::api_json::handlers::openapi_json_handler().await
 }
 
-// Nuevo handler para servir la página Swagger UI (carga JSON desde /api-doc/openapi.json)
+// Nuevo handler para servir la página Swagger UI (carga JSON desde /openapi.json)
 async fn swagger_ui_handler() -> impl Responder {
     crate::api_json::handlers::swagger_ui_handler().await
 }
@@ -81,8 +277,32 @@ async fn root_redirect_handler() -> impl Responder {
 }
 
 pub async fn run_server(bind_addr: &str) -> std::io::Result<()> {
+    // Invalidar el caché de datafiles cuando cambie un Excel en disco
+    if let Err(e) = crate::datafiles::cache::iniciar_watcher(&crate::excel::get_datafiles_dir()) {
+        tracing::warn!("⚠️ No se pudo observar el directorio de datafiles ({}); use POST /datafiles/reload tras cambiarlos", e);
+    }
+    // Poda diaria de analíticas si `ANALITHICS_RETENTION_DAYS` está definida
+    crate::analithics::db::iniciar_retencion();
+    // API keys de `API_KEYS` y de la tabla `api_keys` (requiere la base inicializada)
+    if let Err(e) = crate::analithics::init_db() {
+        tracing::info!("analytics init failed: {}", e);
+    }
+    crate::middleware::api_keys::iniciar_api_keys();
+    // Confirmaciones de las soluciones guardadas para `usar_historial`
+    crate::scoring::registrar_fuente_historial(crate::analithics::queries::historial_secciones);
+    let config = web::Data::from(crate::app_config::actual());
     HttpServer::new(move || {
         App::new()
+            // Configuración tipada (`app_config`); `GET /config` la muestra
+            .app_data(config.clone())
+            // Cabeceras X-RateLimit-*, X-Cache y X-Compute-Time-Ms en rutas de resolución
+            .wrap(actix_web::middleware::from_fn(crate::middleware::solve_response_headers))
+            // API key (X-API-Key / Bearer) y token bucket por clave; ver `middleware::api_keys`
+            .wrap(actix_web::middleware::from_fn(crate::middleware::api_key_auth))
+            // Idioma de los mensajes (?lang= / Accept-Language); ver `middleware::idioma`
+            .wrap(actix_web::middleware::from_fn(crate::middleware::idioma))
+            // Trace ID por solicitud (X-Request-Id / X-Trace-Id) y span de logging
+            .wrap(actix_web::middleware::from_fn(crate::middleware::request_trace))
             // CORS: During development allow localhost origins so browser clients
             // (served from different ports) can call the API. In production tighten this.
             .wrap(
@@ -93,14 +313,34 @@ pub async fn run_server(bind_addr: &str) -> std::io::Result<()> {
                         actix_web::http::header::AUTHORIZATION,
                         actix_web::http::header::ACCEPT,
                         actix_web::http::header::CONTENT_TYPE,
+                        actix_web::http::header::HeaderName::from_static("x-request-id"),
+                        actix_web::http::header::HeaderName::from_static("x-api-key"),
+                        actix_web::http::header::IF_NONE_MATCH,
+                    ])
+                    .expose_headers(vec![
+                        "x-ratelimit-limit",
+                        "x-ratelimit-remaining",
+                        "x-ratelimit-reset",
+                        "x-body-size-limit",
+                        "x-cache",
+                        "x-compute-time-ms",
+                        "x-trace-id",
+                        "x-sample-data",
+                        "etag",
                     ])
                     .max_age(3600)
             )
+            // gzip/brotli/zstd según `Accept-Encoding`, sobre todas las respuestas
+            // (los bodies JSON con `Content-Encoding` se descomprimen en el extractor)
+            .wrap(actix_web::middleware::Compress::default())
+            // Los rechazos del extractor JSON responden con el mismo formato `{code, message, details}`
+            .app_data(web::JsonConfig::default()
+                .error_handler(|err, _req| crate::api_error::ApiError::from(err).into()))
             // Initialize analytics DB (best-effort)
             .app_data({
                 // call init_db here in closure side-effect: we call it once when app is built
                 if let Err(e) = crate::analithics::init_db() {
-                    eprintln!("analytics init failed: {}", e);
+                    tracing::info!("analytics init failed: {}", e);
                 }
                 // analytics initialization only (no background persistence started here)
                 web::Data::new(())
@@ -108,7 +348,41 @@ pub async fn run_server(bind_addr: &str) -> std::io::Result<()> {
             .route("/", web::get().to(root_redirect_handler))
             .route("/solve", web::post().to(solve_handler))
             .route("/solve", web::get().to(solve_get_handler))
+            .route("/solve/explain", web::post().to(solve_explain_handler))
+            .route("/solve/precheck", web::post().to(solve_precheck_handler))
+            .route("/solve/batch", web::post().to(solve_batch_handler))
+            .route("/solve/export/ics", web::post().to(solve_export_ics_handler))
+            .route("/solve/async", web::post().to(solve_async_handler))
+            .route("/jobs/{id}", web::get().to(job_status_handler))
+            .route("/jobs/{id}/result", web::get().to(job_result_handler))
+            .configure(rutas_opcionales)
+            .route("/solve/session", web::post().to(solve_session_create_handler))
+            .route("/solve/session/{id}/refine", web::post().to(solve_session_refine_handler))
+            .route("/solve/session/{id}", web::delete().to(solve_session_delete_handler))
+            .route("/ws/solve", web::get().to(crate::server_handlers::solve_ws::solve_ws_handler))
+            .route("/solutions/compare", web::post().to(compare_solutions_handler))
+            .route("/solutions/{id}", web::get().to(get_solution_handler))
+            .route("/solutions/{id}/export/ics", web::get().to(solution_export_ics_handler))
+            .route("/solutions/{id}/confirm", web::post().to(confirm_solution_handler))
+            .route("/solutions/{id}/feedback", web::post().to(solution_feedback_handler))
                 .route("/students", web::post().to(save_student_handler))
+            .route("/students/import-avance", web::post().to(import_avance_handler))
+            .route("/students/{email}/bloqueantes", web::get().to(student_bloqueantes_handler))
+            .route("/students/{email}/solution", web::get().to(student_solution_handler))
+            .route("/students/{email}/scenarios", web::post().to(save_scenario_handler))
+            .route("/students/{email}/scenarios", web::get().to(list_scenarios_handler))
+            // `diff` antes de `{nombre}` para que no se interprete como nombre de escenario
+            .route("/students/{email}/scenarios/diff", web::get().to(diff_scenarios_handler))
+            .route("/students/{email}/scenarios/{nombre}", web::get().to(get_scenario_handler))
+            .route("/students/{email}/scenarios/{nombre}/restore", web::post().to(restore_scenario_handler))
+            .route("/admin/rollover", web::post().to(admin_rollover_handler))
+            .route("/config", web::get().to(crate::server_handlers::config::config_get_handler))
+            .route("/config/datafiles", web::get().to(crate::server_handlers::config::config_datafiles_get_handler))
+            .route("/config/datafiles", web::put().to(crate::server_handlers::config::config_datafiles_put_handler))
+            .route("/plan/carrera", web::post().to(plan_carrera_handler))
+            .route("/simulate/reprobacion", web::post().to(simular_reprobacion_handler))
+            .route("/simulate/demanda", web::post().to(simular_demanda_handler))
+            .route("/progress", web::post().to(progreso_handler))
             // Analytics routes
             .route("/analithics/ramos_pasados", web::get().to(anal_ramos_pasados_handler))
             .route("/analithics/ranking_por_estudiante", web::get().to(anal_ranking_handler))
@@ -121,23 +395,46 @@ pub async fn run_server(bind_addr: &str) -> std::io::Result<()> {
             // Cache stats endpoints (latest and recent)
             .route("/analithics/cache_stats/latest", web::get().to(crate::server_handlers::analithics::cache_stats_latest))
             .route("/analithics/cache_stats/recent", web::get().to(crate::server_handlers::analithics::cache_stats_recent))
+            .route("/analytics/dashboard", web::get().to(crate::api_json::handlers::analytics::anal_dashboard_handler))
+            .route("/analytics/logs", web::delete().to(crate::server_handlers::analithics::analytics_logs_delete))
+            .route("/analytics/export", web::get().to(crate::server_handlers::analithics::analytics_export))
+            .route("/analytics/feedback/summary", web::get().to(crate::server_handlers::analithics::analytics_feedback_summary))
             .route("/rutacomoda/best", web::post().to(rutacomoda_best_handler))
+            .route("/rutacomoda/rank", web::post().to(rutacomoda_rank_handler))
             .route("/rutacritica/run", web::post().to(rutacritica_run_handler))
             .route("/rutacritica/run-dependencies-only", web::post().to(rutacritica_run_dependencies_only_handler))
+            .route("/carreras", web::get().to(crate::api_json::handlers::datafiles::carreras_list_handler))
             .route("/datafiles", web::get().to(datafiles_list_handler))
             .route("/datafiles", web::delete().to(datafiles_delete_handler))
             .route("/datafiles/upload", web::post().to(datafiles_upload_handler))
+            .route("/datafiles/reload", web::post().to(datafiles_reload_handler))
+            .route("/datafiles/validate", web::get().to(datafiles_validate_handler))
             .route("/datafiles/download", web::get().to(datafiles_download_handler))
             .route("/datafiles/content", web::get().to(datafiles_content_handler))
+            .route("/datafiles/merge", web::get().to(datafiles_merge_handler))
             .route("/datafiles/oferta/summary", web::get().to(oferta_summary_handler))
+            .route("/oferta", web::get().to(crate::server_handlers::oferta::oferta_handler))
+            .route("/profesores", web::get().to(crate::server_handlers::profesores::profesores_buscar_handler))
+            .route("/profesores/{nombre}", web::get().to(crate::server_handlers::profesores::profesor_handler))
             .route("/api/mallas/{malla_id}/semestres/{semestre}/cursos", web::get().to(malla_cursos_semestre_handler))
             .route("/api/mallas/{malla_id}/cursos", web::get().to(malla_cursos_all_handler))
+            .route("/pert", web::get().to(pert_handler))
+            .route("/malla/{id}/grafo", web::get().to(malla_grafo_handler))
+            .route("/malla/{id}/reglas", web::get().to(malla_reglas_handler))
+            .route("/malla/{id}/criticos", web::get().to(malla_criticos_handler))
+            .route("/equivalencias", web::get().to(equivalencias_handler))
+            .route("/equivalencias/resolve", web::post().to(equivalencias_resolve_handler))
             .route("/api/cursos/recomendados", web::post().to(cursos_recomendados_handler))
             .route("/api/cursos/disponibles", web::post().to(cursos_disponibles_handler))
             .route("/api/profesores/disponibles", web::post().to(profesores_disponibles_handler))
             .route("/datafiles/debug/pa-names", web::get().to(debug_pa_names_handler))
+            .route("/datafiles/debug/mapeo-cache", web::get().to(crate::api_json::handlers::debug::debug_mapeo_cache_handler))
             .route("/help", web::get().to(help_handler))
+            .route("/metrics", web::get().to(crate::server_handlers::metrics::metrics_handler))
+            .route("/health/live", web::get().to(crate::server_handlers::health::health_live_handler))
+            .route("/health/ready", web::get().to(crate::server_handlers::health::health_ready_handler))
             // Registrar rutas de documentación SWAGGER
+            .route("/openapi.json", web::get().to(openapi_json_handler))
             .route("/api-doc/openapi.json", web::get().to(openapi_json_handler))
             .route("/api-docs", web::get().to(swagger_ui_handler))
     })
@@ -153,25 +450,44 @@ async fn datafiles_list_handler() -> impl Responder {
 }
 
 /// POST /datafiles/upload
-/// multipart/form-data upload; field(s) with files will be written to `src/datafiles/<filename>`
-async fn datafiles_upload_handler(mut payload: Multipart) -> impl Responder {
-    crate::api_json::handlers::datafiles::datafiles_upload_handler(payload).await
+/// multipart/form-data o JSON con `contenido_base64`; valida cada workbook y lo
+/// guarda en el directorio de datafiles configurado
+async fn datafiles_upload_handler(req: HttpRequest, payload: web::Payload, datafiles: crate::excel::config::DatafilesConfig) -> impl Responder {
+    crate::api_json::handlers::datafiles::datafiles_upload_handler(req, payload, datafiles).await
+}
+
+/// GET /datafiles/validate?malla=MC2020.xlsx[&sheet=Hoja][&oferta=OA20251.xlsx]
+/// Reporte de validación de una malla (columnas, filas omitidas, requisitos rotos, duplicados)
+async fn datafiles_validate_handler(query: web::Query<std::collections::HashMap<String, String>>, datafiles: crate::excel::config::DatafilesConfig) -> impl Responder {
+    crate::api_json::handlers::datafiles::datafiles_validate_handler(query, datafiles).await
+}
+
+/// POST /datafiles/reload
+/// Invalida el caché de datafiles parseados
+async fn datafiles_reload_handler() -> impl Responder {
+    crate::api_json::handlers::datafiles::datafiles_reload_handler().await
 }
 
 /// GET /datafiles/download?name=archivo.xlsx
-async fn datafiles_download_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
-    crate::api_json::handlers::datafiles::datafiles_download_handler(query).await
+async fn datafiles_download_handler(query: web::Query<std::collections::HashMap<String, String>>, datafiles: crate::excel::config::DatafilesConfig) -> impl Responder {
+    crate::api_json::handlers::datafiles::datafiles_download_handler(query, datafiles).await
 }
 
 /// DELETE /datafiles?name=archivo.xlsx
-async fn datafiles_delete_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
-    crate::api_json::handlers::datafiles::datafiles_delete_handler(query).await
+async fn datafiles_delete_handler(query: web::Query<std::collections::HashMap<String, String>>, datafiles: crate::excel::config::DatafilesConfig) -> impl Responder {
+    crate::api_json::handlers::datafiles::datafiles_delete_handler(query, datafiles).await
 }
 
 /// GET /datafiles/content?malla=MiMalla.xlsx
 /// Devuelve un resumen de los contenidos (primeros elementos) de MALLA, OA y PA
-async fn datafiles_content_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
-    crate::api_json::handlers::datafiles::datafiles_content_handler(query).await
+async fn datafiles_content_handler(req: HttpRequest, query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    crate::api_json::handlers::datafiles::datafiles_content_handler(req, query).await
+}
+
+/// GET /datafiles/merge?malla=MiMalla.xlsx&formato=csv
+/// Tabla malla ↔ oferta ↔ porcentajes emparejada por nombre (JSON o CSV)
+async fn datafiles_merge_handler(req: HttpRequest, query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    crate::api_json::handlers::datafiles::datafiles_merge_handler(req, query).await
 }
 
 /// GET /datafiles/oferta/summary?oferta=OA2024.xlsx
@@ -187,12 +503,13 @@ async fn oferta_summary_handler(query: web::Query<std::collections::HashMap<Stri
 /// - horarios_preferidos
 /// - malla
 /// - email
-async fn solve_get_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
-    crate::server_handlers::solve::solve_get_handler(query).await
+/// - filtros / scoring: JSON URL-encoded o en base64 (como en POST /solve)
+async fn solve_get_handler(req: HttpRequest, query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    crate::server_handlers::solve::solve_get_handler(req, query).await
 }
 
-async fn help_handler() -> impl Responder {
-    crate::server_handlers::docs::help_handler().await
+async fn help_handler(idioma: crate::i18n::Idioma) -> impl Responder {
+    crate::server_handlers::docs::help_handler(idioma).await
 }
 
 /// DEBUG: GET /datafiles/debug/pa-names
@@ -208,6 +525,51 @@ async fn malla_cursos_semestre_handler(
     crate::api_json::handlers::courses::cursos_por_semestre_handler(path, query).await
 }
 
+/// GET /malla/{id}/grafo?format=dot|json
+/// DAG de prerequisitos de la malla (JSON nodes/edges o Graphviz DOT).
+async fn malla_grafo_handler(
+    path: web::Path<String>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    crate::api_json::handlers::courses::malla_grafo_handler(path, query).await
+}
+
+/// GET /malla/{id}/reglas?carrera=...
+/// CFGs y electivos que exige la malla.
+async fn malla_reglas_handler(
+    path: web::Path<String>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    crate::api_json::handlers::courses::malla_reglas_handler(path, query).await
+}
+
+/// GET /malla/{id}/criticos?sheet=...
+/// Ramos de la malla por urgencia, con su holgura y la ruta crítica.
+async fn malla_criticos_handler(
+    path: web::Path<String>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    crate::api_json::handlers::courses::malla_criticos_handler(path, query).await
+}
+
+/// GET /equivalencias?malla=...[&comparar=...]
+/// Tabla de equivalencias de la malla: cadenas, ambiguas y ramos duplicados entre mallas.
+async fn equivalencias_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    crate::server_handlers::equivalencias::equivalencias_handler(query).await
+}
+
+/// POST /equivalencias/resolve
+/// Lleva códigos históricos a la malla pedida.
+async fn equivalencias_resolve_handler(body: web::Json<crate::server_handlers::ResolverEquivalenciasRequest>) -> impl Responder {
+    crate::server_handlers::equivalencias::equivalencias_resolve_handler(body).await
+}
+
+/// GET /pert?malla=...&sheet=...
+/// Red PERT de la malla (ES/EF/LS/LF, holgura y ruta crítica) para diagramas.
+async fn pert_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    crate::api_json::handlers::courses::pert_handler(query).await
+}
+
 async fn malla_cursos_all_handler(
     path: web::Path<String>,
     query: web::Query<std::collections::HashMap<String, String>>,
@@ -1,5 +1,8 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
 use serde::Serialize;
+use crate::analithics::db::{self, FormatoExport};
+use crate::middleware::auth::autorizado;
 
 #[derive(Serialize)]
 struct CacheStatsRow {
@@ -19,13 +22,13 @@ pub async fn cache_stats_latest() -> impl Responder {
             }
             Ok(None) => HttpResponse::Ok().json(serde_json::json!({"message":"no stats"})),
             Err(e) => {
-                eprintln!("error fetching cache stats: {}", e);
-                HttpResponse::InternalServerError().body("error fetching cache stats")
+                tracing::error!("error fetching cache stats: {}", e);
+                ApiError::Storage(format!("error fetching cache stats: {}", e)).error_response()
             }
         },
         Err(e) => {
-            eprintln!("error opening analytics conn: {}", e);
-            HttpResponse::InternalServerError().body("error opening analytics connection")
+            tracing::error!("error opening analytics conn: {}", e);
+            ApiError::Storage(format!("error opening analytics connection: {}", e)).error_response()
         }
     }
 }
@@ -40,13 +43,93 @@ pub async fn cache_stats_recent(query: web::Query<std::collections::HashMap<Stri
                 HttpResponse::Ok().json(out)
             }
             Err(e) => {
-                eprintln!("error fetching recent cache stats: {}", e);
-                HttpResponse::InternalServerError().body("error fetching recent cache stats")
+                tracing::error!("error fetching recent cache stats: {}", e);
+                ApiError::Storage(format!("error fetching recent cache stats: {}", e)).error_response()
             }
         },
         Err(e) => {
-            eprintln!("error opening analytics conn: {}", e);
-            HttpResponse::InternalServerError().body("error opening analytics connection")
+            tracing::error!("error opening analytics conn: {}", e);
+            ApiError::Storage(format!("error opening analytics connection: {}", e)).error_response()
         }
     }
 }
+
+/// DELETE /analytics/logs?before=2025-03-01
+/// Borra las consultas, reportes y cache_stats anteriores a la fecha
+/// (`YYYY-MM-DD` o RFC 3339). Requiere `X-Admin-Token` igual a `ADMIN_TOKEN`;
+/// sin ella configurada responde 401.
+pub async fn analytics_logs_delete(req: HttpRequest, query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    if !autorizado(&req) {
+        return ApiError::Unauthorized("invalid or missing X-Admin-Token".into()).error_response();
+    }
+    let Some(before) = query.get("before") else {
+        return ApiError::MissingParameter("before").error_response();
+    };
+    let Some(corte) = db::parse_fecha_corte(before) else {
+        return ApiError::InvalidInput(format!("'before' must be YYYY-MM-DD or RFC 3339, got '{}'", before)).error_response();
+    };
+    let corte_block = corte.clone();
+    let res = web::block(move || {
+        db::open_analytics_connection()
+            .and_then(|conn| db::borrar_antes_de(&conn, &corte_block))
+            .map_err(|e| e.to_string())
+    }).await;
+    match res {
+        Ok(Ok(borrados)) => {
+            tracing::info!("🧹 /analytics/logs: borrado lo anterior a {} ({:?})", corte, borrados);
+            HttpResponse::Ok().json(serde_json::json!({"before": corte, "deleted": borrados}))
+        }
+        Ok(Err(e)) => ApiError::Storage(format!("error pruning analytics: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
+    }
+}
+
+/// GET /analytics/export?format=csv|jsonl
+/// Descarga la tabla `queries` completa. Requiere `X-Admin-Token` igual a
+/// `ADMIN_TOKEN`; sin ella configurada responde 401.
+pub async fn analytics_export(req: HttpRequest, query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    if !autorizado(&req) {
+        return ApiError::Unauthorized("invalid or missing X-Admin-Token".into()).error_response();
+    }
+    let formato = match query.get("format").map(|f| FormatoExport::parse(f)) {
+        None => FormatoExport::Jsonl,
+        Some(Some(f)) => f,
+        Some(None) => return ApiError::InvalidInput("'format' must be csv or jsonl".into()).error_response(),
+    };
+    let res = web::block(move || {
+        db::open_analytics_connection()
+            .and_then(|conn| db::fetch_queries(&conn))
+            .map(|filas| db::exportar_queries(&filas, formato))
+            .map_err(|e| e.to_string())
+    }).await;
+    let extension = match formato {
+        FormatoExport::Csv => "csv",
+        FormatoExport::Jsonl => "jsonl",
+    };
+    match res {
+        Ok(Ok(cuerpo)) => HttpResponse::Ok()
+            .content_type(formato.content_type())
+            .insert_header(("Content-Disposition", format!("attachment; filename=\"analytics_queries.{}\"", extension)))
+            .body(cuerpo),
+        Ok(Err(e)) => ApiError::Storage(format!("error exporting analytics: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
+    }
+}
+
+/// GET /analytics/feedback/summary
+/// Notas de `POST /solutions/{id}/feedback` agrupadas por configuración de scoring
+pub async fn analytics_feedback_summary() -> impl Responder {
+    let res = web::block(|| {
+        db::open_analytics_connection()
+            .and_then(|conn| crate::analithics::feedback::resumen_feedback(&conn))
+            .map_err(|e| e.to_string())
+    }).await;
+    match res {
+        Ok(Ok(configuraciones)) => HttpResponse::Ok().json(serde_json::json!({
+            "total": configuraciones.iter().map(|c| c.cantidad).sum::<i64>(),
+            "configuraciones": configuraciones,
+        })),
+        Ok(Err(e)) => ApiError::Storage(format!("error reading feedback: {}", e)).error_response(),
+        Err(e) => ApiError::Internal(format!("blocking task error: {}", e)).error_response(),
+    }
+}
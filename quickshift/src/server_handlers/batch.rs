@@ -0,0 +1,332 @@
+//! Resolución por lotes (`POST /solve/batch`) para orientadores académicos.
+//!
+//! Body JSON: arreglo de objetos con el mismo formato que el body de
+//! `POST /solve`, o `{estudiantes: [...], comun: {...}}` donde `comun` aporta
+//! los campos que faltan en cada estudiante (malla, filtros, ...). También
+//! acepta un CSV (`text/csv`) con una fila por estudiante (ver
+//! `estudiantes_desde_csv`); ahí `?malla=` y `?carrera=` hacen de `comun`.
+//!
+//! Cada estudiante se resuelve por separado en un pool acotado compartido por
+//! todos los lotes (`BATCH_WORKERS`, por defecto la cantidad de CPUs). Un
+//! estudiante con datos inválidos o sin horario factible no detiene el lote:
+//! su resultado trae el error y el reporte agregado lo cuenta.
+
+use actix_web::http::header::CONTENT_TYPE;
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use crate::algorithm::cohorte::{demanda_por_seccion, DemandaSeccion};
+use crate::algorithm::deadline::Deadline;
+use crate::api_error::ApiError;
+use crate::excel::avance::{detectar_separador, separar_campos};
+use crate::excel::normalize_name;
+use crate::middleware::trace::con_span_actual;
+use crate::models::Seccion;
+use crate::server_handlers::solve::{ejecutar_con_progreso, soluciones_to_entries, SolutionEntry};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::Semaphore;
+
+/// Secciones más demandadas que se informan en el reporte
+const TOP_SECCIONES: usize = 20;
+
+/// Columnas del CSV de estudiantes (encabezado normalizado -> campo de `InputParams`)
+const COLUMNAS_CSV: &[(&str, &str)] = &[
+    ("email", "email"),
+    ("correo", "email"),
+    ("mail", "email"),
+    ("malla", "malla"),
+    ("carrera", "carrera"),
+    ("sheet", "sheet"),
+    ("hoja", "sheet"),
+    ("ramos pasados", "ramos_pasados"),
+    ("aprobados", "ramos_pasados"),
+    ("ramos prioritarios", "ramos_prioritarios"),
+    ("prioritarios", "ramos_prioritarios"),
+    ("student ranking", "student_ranking"),
+    ("ranking", "student_ranking"),
+    ("promedio", "promedio"),
+    ("gpa", "promedio"),
+    ("max creditos", "max_creditos"),
+];
+
+/// Convierte un CSV con una fila por estudiante en objetos con el formato del
+/// body de `POST /solve`. El encabezado debe traer `email` (o `correo`); las
+/// columnas `ramos_pasados` y `ramos_prioritarios` listan códigos o nombres
+/// separados por `;`, `|` o `,` (entre comillas si coincide con el separador
+/// del archivo).
+/// Las columnas desconocidas se ignoran.
+pub fn estudiantes_desde_csv(contenido: &str) -> Result<Vec<Value>, String> {
+    let mut lineas = contenido.lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim_start_matches('\u{feff}').trim()))
+        .filter(|(_, l)| !l.is_empty());
+    let Some((_, encabezado)) = lineas.next() else {
+        return Err("empty CSV".into());
+    };
+    let sep = detectar_separador(encabezado);
+    let columnas: Vec<Option<&str>> = separar_campos(encabezado, sep).iter()
+        .map(|c| {
+            let c = normalize_name(c);
+            COLUMNAS_CSV.iter().find(|(nombre, _)| *nombre == c).map(|(_, campo)| *campo)
+        })
+        .collect();
+    if !columnas.contains(&Some("email")) {
+        return Err("CSV header must include an 'email' column".into());
+    }
+
+    let mut estudiantes = Vec::new();
+    for (linea, texto) in lineas {
+        let mut obj = Map::new();
+        for (campo, celda) in columnas.iter().zip(separar_campos(texto, sep)) {
+            let Some(campo) = campo else { continue };
+            if celda.is_empty() {
+                continue;
+            }
+            let valor = match *campo {
+                "ramos_pasados" | "ramos_prioritarios" => json!(celda
+                    .split([';', '|', ','])
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()),
+                "student_ranking" | "promedio" => json!(celda.replace(',', ".").parse::<f64>()
+                    .map_err(|_| format!("line {}: invalid {} '{}'", linea, campo, celda))?),
+                "max_creditos" => json!(celda.parse::<u32>()
+                    .map_err(|_| format!("line {}: invalid max_creditos '{}'", linea, celda))?),
+                _ => json!(celda),
+            };
+            obj.insert(campo.to_string(), valor);
+        }
+        if !obj.contains_key("email") {
+            return Err(format!("line {}: missing email", linea));
+        }
+        estudiantes.push(Value::Object(obj));
+    }
+    Ok(estudiantes)
+}
+
+/// Completa cada estudiante con los campos de `comun` que no trae, y con
+/// listas vacías en los campos obligatorios de `InputParams`
+fn completar_estudiantes(estudiantes: Vec<Value>, comun: &Map<String, Value>) -> Result<Vec<Value>, ApiError> {
+    estudiantes.into_iter().enumerate().map(|(i, e)| {
+        let Value::Object(mut obj) = e else {
+            return Err(ApiError::InvalidInput(format!("estudiantes[{}] must be an object", i)));
+        };
+        for (k, v) in comun {
+            obj.entry(k.clone()).or_insert_with(|| v.clone());
+        }
+        for campo in ["ramos_pasados", "ramos_prioritarios"] {
+            obj.entry(campo).or_insert_with(|| json!([]));
+        }
+        Ok(Value::Object(obj))
+    }).collect()
+}
+
+/// Estudiantes del lote (ver `leer_estudiantes`), validando que no esté
+/// vacío ni supere `BATCH_MAX_ESTUDIANTES`
+pub(crate) fn estudiantes_del_lote(req: &HttpRequest, body: &[u8], query: &HashMap<String, String>) -> Result<Vec<Value>, ApiError> {
+    let estudiantes = leer_estudiantes(req, body, query)?;
+    if estudiantes.is_empty() {
+        return Err(ApiError::InvalidInput("batch has no students".into()));
+    }
+    let maximo = max_estudiantes();
+    if estudiantes.len() > maximo {
+        return Err(ApiError::InvalidInput(format!("batch has {} students (max {})", estudiantes.len(), maximo)));
+    }
+    Ok(estudiantes)
+}
+
+/// Estudiantes del body: JSON (arreglo u objeto `{estudiantes, comun}`) o CSV
+fn leer_estudiantes(req: &HttpRequest, body: &[u8], query: &HashMap<String, String>) -> Result<Vec<Value>, ApiError> {
+    let es_json = req.headers().get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|ct| ct.starts_with("application/json"))
+        .unwrap_or(false);
+    let (estudiantes, comun) = if es_json {
+        match serde_json::from_slice::<Value>(body).map_err(|e| ApiError::InvalidBody(e.to_string()))? {
+            Value::Array(lista) => (lista, Map::new()),
+            Value::Object(mut obj) => {
+                let Some(Value::Array(lista)) = obj.remove("estudiantes") else {
+                    return Err(ApiError::MissingParameter("estudiantes"));
+                };
+                let comun = match obj.remove("comun") {
+                    Some(Value::Object(c)) => c,
+                    None | Some(Value::Null) => Map::new(),
+                    Some(_) => return Err(ApiError::InvalidInput("'comun' must be an object".into())),
+                };
+                (lista, comun)
+            }
+            _ => return Err(ApiError::InvalidBody("expected an array of students or {estudiantes: [...]}".into())),
+        }
+    } else {
+        let texto = std::str::from_utf8(body).map_err(|_| ApiError::InvalidBody("CSV must be UTF-8 text".into()))?;
+        let lista = estudiantes_desde_csv(texto).map_err(ApiError::InvalidInput)?;
+        let comun: Map<String, Value> = ["malla", "carrera"].iter()
+            .filter_map(|k| query.get(*k).map(|v| (k.to_string(), json!(v))))
+            .collect();
+        (lista, comun)
+    };
+    completar_estudiantes(estudiantes, &comun)
+}
+
+/// Pool de solves por lotes, compartido entre peticiones
+fn pool() -> Arc<Semaphore> {
+    static POOL: OnceLock<Arc<Semaphore>> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let workers = crate::app_config::actual().batch_workers.unwrap_or_else(num_cpus::get);
+        Arc::new(Semaphore::new(workers.max(1)))
+    }).clone()
+}
+
+fn max_estudiantes() -> usize {
+    crate::app_config::actual().batch_max_estudiantes
+}
+
+/// Resultado de un estudiante del lote
+#[derive(serde::Serialize)]
+pub(crate) struct ResultadoEstudiante {
+    /// Posición en el lote (0-based)
+    indice: usize,
+    email: String,
+    soluciones_count: usize,
+    /// Mejores soluciones (hasta `max_soluciones`), con el formato de `/solve`
+    soluciones: Vec<SolutionEntry>,
+    partial: bool,
+    /// Error con el mismo formato que las respuestas de error de la API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<Value>,
+}
+
+impl ResultadoEstudiante {
+    /// Secciones de la mejor solución, si la hay
+    pub(crate) fn mejor_horario(&self) -> Option<&[Seccion]> {
+        self.soluciones.first().map(|s| s.secciones.as_slice())
+    }
+}
+
+/// Reporte agregado del lote
+#[derive(serde::Serialize)]
+pub(crate) struct ReporteLote {
+    pub(crate) total: usize,
+    pub(crate) con_horario: usize,
+    /// Emails de los estudiantes sin horario factible
+    pub(crate) sin_horario: Vec<String>,
+    /// Emails de los estudiantes cuyo request falló (datos inválidos, malla inexistente, ...)
+    pub(crate) con_error: Vec<String>,
+    /// Secciones más frecuentes en el mejor horario de cada estudiante
+    pub(crate) secciones_mas_demandadas: Vec<DemandaSeccion>,
+}
+
+/// Resuelve un estudiante esperando su turno en el pool
+async fn resolver_estudiante(indice: usize, estudiante: Value, max_soluciones: usize) -> ResultadoEstudiante {
+    let email = estudiante.get("email").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let fallo = |email: String, e: ApiError| ResultadoEstudiante {
+        indice,
+        email,
+        soluciones_count: 0,
+        soluciones: Vec::new(),
+        partial: false,
+        error: Some(e.to_json()),
+    };
+
+    let permiso = match pool().acquire_owned().await {
+        Ok(p) => p,
+        Err(_) => return fallo(email, ApiError::Internal("failed to acquire semaphore".into())),
+    };
+    let params = tokio::task::spawn_blocking(con_span_actual(move || {
+        crate::api_json::parse_and_resolve_ramos(&estudiante.to_string(), Some("."))
+            .map_err(|e| ApiError::InvalidInput(e.to_string()))
+    })).await;
+    let params = match params {
+        Ok(Ok(p)) => p,
+        Ok(Err(e)) => return fallo(email, e),
+        Err(e) => return fallo(email, ApiError::Internal(format!("task join error: {}", e))),
+    };
+
+    // Igual que en `/solve`: el límite corre desde que se obtiene el permiso y
+    // se cancela si se descarta la petición
+    let deadline = Deadline::desde_params(&params);
+    let _cancelar = deadline.cancelar_al_soltar();
+    let deadline_block = deadline.clone();
+    let res = tokio::task::spawn_blocking(con_span_actual(move || {
+        let _permiso = permiso;
+        let malla = params.malla.clone();
+        match ejecutar_con_progreso(params.clone(), &deadline_block) {
+            Ok((soluciones, _, ramos, secciones)) => Ok((soluciones.len(), soluciones_to_entries(&soluciones, &params, &ramos, &secciones))),
+            Err(e) => Err(ApiError::de_ruta(&malla, e)),
+        }
+    })).await;
+
+    match res {
+        Ok(Ok((soluciones_count, mut soluciones))) => {
+            let partial = deadline.alcanzado();
+            let error = (soluciones.is_empty() && !partial).then(|| ApiError::NoFeasibleSchedule.to_json());
+            soluciones.truncate(max_soluciones);
+            ResultadoEstudiante { indice, email, soluciones_count, soluciones, partial, error }
+        }
+        Ok(Err(e)) => fallo(email, e),
+        Err(e) => fallo(email, ApiError::Internal(format!("task join error: {}", e))),
+    }
+}
+
+/// Resuelve todos los estudiantes en el pool; los resultados quedan en el
+/// orden del lote
+pub(crate) async fn resolver_lote(estudiantes: Vec<Value>, max_soluciones: usize) -> Vec<ResultadoEstudiante> {
+    futures_util::future::join_all(
+        estudiantes.into_iter().enumerate().map(|(i, e)| resolver_estudiante(i, e, max_soluciones)),
+    ).await
+}
+
+pub(crate) fn reporte(resultados: &[ResultadoEstudiante]) -> ReporteLote {
+    let sin_horario_code = ApiError::NoFeasibleSchedule.code();
+    let (mut sin_horario, mut con_error) = (Vec::new(), Vec::new());
+    for r in resultados {
+        match r.error.as_ref().and_then(|e| e["code"].as_str()) {
+            Some(code) if code == sin_horario_code => sin_horario.push(r.email.clone()),
+            Some(_) => con_error.push(r.email.clone()),
+            None => {}
+        }
+    }
+    let mut secciones_mas_demandadas = demanda_por_seccion(resultados.iter().filter_map(|r| r.mejor_horario()));
+    secciones_mas_demandadas.truncate(TOP_SECCIONES);
+    ReporteLote {
+        total: resultados.len(),
+        con_horario: resultados.iter().filter(|r| !r.soluciones.is_empty()).count(),
+        sin_horario,
+        con_error,
+        secciones_mas_demandadas,
+    }
+}
+
+/// POST /solve/batch
+/// Resuelve a cada estudiante del lote y devuelve `resultados` (uno por
+/// estudiante, en el orden del body) y `reporte`. `?max_soluciones=N`
+/// (por defecto 1) limita las soluciones devueltas por estudiante.
+pub async fn solve_batch_handler(req: HttpRequest, body: web::Bytes) -> impl Responder {
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map(|q| q.into_inner())
+        .unwrap_or_default();
+    let max_soluciones = match query.get("max_soluciones").map(|v| v.trim().parse::<usize>()) {
+        None => 1,
+        Some(Ok(n)) if n > 0 => n,
+        Some(_) => return ApiError::InvalidInput("max_soluciones must be a positive integer".into()).error_response(),
+    };
+    let estudiantes = match estudiantes_del_lote(&req, &body, &query) {
+        Ok(e) => e,
+        Err(e) => return e.error_response(),
+    };
+
+    let start = std::time::Instant::now();
+    tracing::info!("📦 Lote de {} estudiantes", estudiantes.len());
+    let resultados = resolver_lote(estudiantes, max_soluciones).await;
+    let reporte = reporte(&resultados);
+    tracing::info!(
+        "📦 Lote resuelto en {} ms: {} con horario, {} sin horario, {} con error",
+        start.elapsed().as_millis(), reporte.con_horario, reporte.sin_horario.len(), reporte.con_error.len()
+    );
+
+    HttpResponse::Ok().json(json!({
+        "resultados": resultados,
+        "reporte": reporte,
+        "compute_ms": start.elapsed().as_millis() as u64,
+    }))
+}
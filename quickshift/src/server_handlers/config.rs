@@ -0,0 +1,89 @@
+//! Configuración del directorio de datafiles (`GET/PUT /config/datafiles`)
+//! y configuración efectiva del proceso (`GET /config`, ver `app_config`).
+//!
+//! `GET` devuelve el directorio activo, de dónde salió (ver
+//! `excel::config::OrigenDatafiles`) y el archivo donde se persiste. `PUT`
+//! cambia el directorio: valida que exista, lo guarda en ese archivo, invalida
+//! los cachés de datafiles y las sesiones de `/solve/session` y mueve el
+//! watcher al nuevo directorio. Todos requieren `X-Admin-Token` igual a
+//! `ADMIN_TOKEN`; sin ella configurada responden 401.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use crate::app_config::{self, AppConfig};
+use crate::excel::config::{self, DatafilesConfig};
+use crate::middleware::auth::autorizado;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DatafilesConfigRequest {
+    /// Directorio con los Excel; debe existir en el servidor
+    pub dir: String,
+}
+
+fn cuerpo(datafiles: &DatafilesConfig) -> serde_json::Value {
+    json!({
+        "dir": datafiles.dir,
+        "origen": datafiles.origen,
+        "existe": datafiles.dir.is_dir(),
+        "archivo_config": config::ruta_config(),
+    })
+}
+
+/// GET /config/datafiles
+pub async fn config_datafiles_get_handler(req: HttpRequest, datafiles: DatafilesConfig) -> impl Responder {
+    if !autorizado(&req) {
+        return ApiError::Unauthorized("invalid or missing X-Admin-Token".into()).error_response();
+    }
+    HttpResponse::Ok().json(cuerpo(&datafiles))
+}
+
+/// PUT /config/datafiles
+pub async fn config_datafiles_put_handler(req: HttpRequest, body: web::Json<DatafilesConfigRequest>) -> impl Responder {
+    if !autorizado(&req) {
+        return ApiError::Unauthorized("invalid or missing X-Admin-Token".into()).error_response();
+    }
+    let dir = body.into_inner().dir;
+    if dir.trim().is_empty() {
+        return ApiError::MissingParameter("dir").error_response();
+    }
+    let Some(raiz) = config::raiz_permitida() else {
+        return ApiError::InvalidInput("no hay raíz permitida para los datafiles (defina DATAFILES_ROOT o GA_DATAFILES_DIR)".into()).error_response();
+    };
+    let dir = match config::validar_dir(std::path::Path::new(dir.trim()), &raiz) {
+        Ok(d) => d,
+        Err(e) => return ApiError::InvalidInput(e).error_response(),
+    };
+    let nueva = match config::cambiar_dir(&dir) {
+        Ok(c) => c,
+        Err(e) => return ApiError::Storage(e).error_response(),
+    };
+
+    let invalidadas = crate::datafiles::cache::invalidar_todo()
+        + crate::excel::mapeo_builder::invalidar_cache_mapeo();
+    let sesiones_invalidadas = crate::server_handlers::session::clear_sessions();
+    if let Err(e) = crate::datafiles::cache::iniciar_watcher(&nueva.dir) {
+        tracing::warn!("⚠️ No se pudo observar {:?} ({}); use POST /datafiles/reload tras cambiarlos", nueva.dir, e);
+    }
+
+    let mut respuesta = cuerpo(&nueva);
+    respuesta["invalidadas"] = json!(invalidadas);
+    respuesta["sesiones_invalidadas"] = json!(sesiones_invalidadas);
+    HttpResponse::Ok().json(respuesta)
+}
+
+/// GET /config
+/// La configuración con que arrancó el proceso (app data), sin secretos
+pub async fn config_get_handler(req: HttpRequest, config: web::Data<AppConfig>) -> impl Responder {
+    if !autorizado(&req) {
+        return ApiError::Unauthorized("invalid or missing X-Admin-Token".into()).error_response();
+    }
+    let archivo = app_config::ruta_archivo();
+    HttpResponse::Ok().json(json!({
+        "archivo_config": archivo,
+        "archivo_existe": archivo.is_file(),
+        "config": config.publica(),
+    }))
+}
@@ -0,0 +1,123 @@
+//! Equivalencias entre mallas (`GET /equivalencias`, `POST /equivalencias/resolve`).
+//!
+//! `GET /equivalencias?malla=` resume la hoja "Equivalencias" de la malla:
+//! los pares, las cadenas (A→B→C), los códigos ambiguos, los que no llegan a
+//! la malla y los ramos a los que llegan varios códigos. Además compara la
+//! malla con las demás de datafiles (o las de `?comparar=`) y lista los ramos
+//! con el mismo nombre y otro código que la tabla no cubre.
+//!
+//! `POST /equivalencias/resolve` lleva una lista de códigos históricos a la
+//! malla, con el mismo criterio que aplica `/solve` sobre `ramos_pasados`
+//! (ver `algorithm::equivalencias`).
+
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use crate::algorithm::equivalencias::{analizar, duplicados_entre_mallas, resolver};
+use crate::middleware::trace::con_span_actual;
+use crate::models::RamoDisponible;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+
+/// Body JSON de `POST /equivalencias/resolve`
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ResolverEquivalenciasRequest {
+    /// Malla de destino (igual que `InputParams::malla`)
+    pub malla: String,
+    /// Códigos de ramos aprobados, de cualquier malla
+    #[serde(alias = "ramos_pasados")]
+    pub codigos: Vec<String>,
+}
+
+/// Ruta, tabla de equivalencias y ramos de una malla de datafiles
+type MallaCargada = (String, Vec<(String, String)>, HashMap<String, RamoDisponible>);
+
+fn cargar_malla(malla: &str) -> Result<MallaCargada, ApiError> {
+    let (malla_path, _, _) = crate::excel::resolve_datafile_paths(malla)
+        .map_err(|e| ApiError::del_pipeline(malla, format!("failed to resolve malla '{}': {}", malla, e)))?;
+    let malla_str = malla_path.to_string_lossy().to_string();
+    let ramos = crate::excel::leer_malla_excel(&malla_str)
+        .map_err(|e| ApiError::InvalidDatafile { message: format!("failed to read malla '{}': {}", malla_str, e), details: None })?;
+    let tabla = crate::excel::leer_tabla_equivalencias(&malla_str)
+        .map_err(|e| ApiError::InvalidDatafile { message: format!("failed to read equivalencias of '{}': {}", malla_str, e), details: None })?;
+    Ok((malla_str, tabla, ramos))
+}
+
+fn codigos_malla(ramos: &HashMap<String, RamoDisponible>) -> HashSet<String> {
+    ramos.values()
+        .filter(|r| !r.codigo.trim().is_empty())
+        .map(|r| r.codigo.trim().to_uppercase())
+        .collect()
+}
+
+fn nombre_archivo(ruta: &str) -> String {
+    std::path::Path::new(ruta).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| ruta.to_string())
+}
+
+/// GET /equivalencias?malla=MC2020.xlsx[&comparar=Malla2018.xlsx,Malla2015.xlsx]
+pub async fn equivalencias_handler(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    let q = query.into_inner();
+    let Some(malla) = q.get("malla").map(|m| m.trim().to_string()).filter(|m| !m.is_empty()) else {
+        return ApiError::MissingParameter("malla").error_response();
+    };
+    let comparar: Option<Vec<String>> = q.get("comparar")
+        .map(|c| c.split(',').map(|m| m.trim().to_string()).filter(|m| !m.is_empty()).collect());
+
+    let res = web::block(con_span_actual(move || -> Result<_, ApiError> {
+        let (malla_str, tabla, ramos) = cargar_malla(&malla)?;
+        let analisis = analizar(&tabla, &codigos_malla(&ramos));
+
+        let archivo = nombre_archivo(&malla_str);
+        let otras = match comparar {
+            Some(c) => c,
+            None => crate::excel::list_available_datafiles().map(|(m, _, _)| m).unwrap_or_default(),
+        };
+        let mut duplicados = Vec::new();
+        let mut omitidas = Vec::new();
+        for otra in otras.iter().filter(|o| !o.eq_ignore_ascii_case(&archivo) && !o.eq_ignore_ascii_case(&malla)) {
+            let leida = crate::excel::resolve_datafile_paths(otra)
+                .and_then(|(p, _, _)| crate::excel::leer_malla_excel(&p.to_string_lossy()));
+            match leida {
+                Ok(ramos_otra) => duplicados.extend(duplicados_entre_mallas(&ramos, otra, &ramos_otra, &tabla)),
+                Err(e) => omitidas.push(json!({"malla": otra, "error": e.to_string()})),
+            }
+        }
+        Ok((malla, archivo, analisis, duplicados, omitidas))
+    })).await;
+
+    match res {
+        Ok(Ok((malla, archivo, analisis, duplicados, omitidas))) => {
+            let mut out = json!(analisis);
+            out["malla"] = json!(malla);
+            out["archivo"] = json!(archivo);
+            out["duplicados_entre_mallas"] = json!(duplicados);
+            if !omitidas.is_empty() {
+                out["mallas_omitidas"] = json!(omitidas);
+            }
+            HttpResponse::Ok().json(out)
+        }
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
+
+/// POST /equivalencias/resolve
+pub async fn equivalencias_resolve_handler(body: web::Json<ResolverEquivalenciasRequest>) -> impl Responder {
+    let body = body.into_inner();
+    if body.malla.trim().is_empty() {
+        return ApiError::MissingParameter("malla").error_response();
+    }
+    let res = web::block(con_span_actual(move || -> Result<_, ApiError> {
+        let (_, tabla, ramos) = cargar_malla(&body.malla)?;
+        Ok((body.malla, resolver(&body.codigos, &tabla, &codigos_malla(&ramos))))
+    })).await;
+    match res {
+        Ok(Ok((malla, resolucion))) => {
+            let mut out = json!(resolucion);
+            out["malla"] = json!(malla);
+            HttpResponse::Ok().json(out)
+        }
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
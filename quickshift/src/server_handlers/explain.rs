@@ -0,0 +1,50 @@
+//! Explicación de ramos ausentes (`POST /solve/explain`).
+//!
+//! Mismo body que `POST /solve`, más `ramo`: código (o nombre) del ramo que se
+//! esperaba ver en las soluciones. Responde los motivos concretos por los que
+//! no aparece (ver `algorithm::explicacion`) y un `resumen` en el idioma
+//! pedido (`lang`).
+
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use serde_json::json;
+use std::time::Instant;
+use crate::algorithm::explicacion;
+use crate::i18n::Idioma;
+use crate::server_handlers::planner::cargar_malla_y_oferta;
+use crate::middleware::trace::con_span_actual;
+
+pub async fn explain_handler(body: web::Json<serde_json::Value>, idioma: Idioma) -> impl Responder {
+    let body_value = body.into_inner();
+    let ramo = match body_value.get("ramo").and_then(|v| v.as_str()).map(|s| s.trim().to_string()) {
+        Some(r) if !r.is_empty() => r,
+        _ => return ApiError::MissingParameter("ramo").error_response(),
+    };
+
+    let json_str = match serde_json::to_string(&body_value) {
+        Ok(s) => s,
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
+    };
+    let mut params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
+        Ok(p) => p,
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
+    };
+
+    let malla_pedida = params.malla.clone();
+    let start = Instant::now();
+    let res = web::block(con_span_actual(move || -> Result<explicacion::Explicacion, String> {
+        let (malla, secciones, _) = cargar_malla_y_oferta(&mut params)?;
+        Ok(explicacion::explicar_ramo(&ramo, &params, &malla, &secciones))
+    })).await;
+
+    match res {
+        Ok(Ok(explicacion)) => {
+            let mut out = json!(explicacion);
+            out["resumen"] = json!(explicacion.resumen(idioma));
+            out["compute_ms"] = json!(start.elapsed().as_millis() as u64);
+            HttpResponse::Ok().json(out)
+        }
+        Ok(Err(msg)) => ApiError::del_pipeline(&malla_pedida, msg).error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
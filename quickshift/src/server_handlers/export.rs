@@ -0,0 +1,169 @@
+//! Exportación de una solución (`POST /solve/export/ics`) y de las
+//! soluciones recomendadas (`POST /solve/export/xlsx` y `POST /solve/export/pdf`,
+//! features `export-xlsx` y `export-pdf`).
+//!
+//! Body:
+//! - `secciones`: secciones de la solución elegida (el mismo formato que
+//!   `soluciones[i].secciones` de `POST /solve`). También se acepta
+//!   `solucion: { "secciones": [...] }`.
+//! - `fecha_inicio` (opcional, "2026-03-09"): primer día de clases; por defecto hoy.
+//! - `fecha_fin` (opcional): último día de clases; si no, `semanas` (por defecto 16).
+//! - `nombre` (opcional): nombre del calendario.
+//!
+//! `POST /solve/export/xlsx` y `POST /solve/export/pdf` reciben el mismo body
+//! que `POST /solve`, resuelven y devuelven un workbook con una hoja por
+//! solución (ver `export::xlsx`) o un PDF con una página por solución (ver
+//! `export::pdf`).
+
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use chrono::{NaiveDate, Utc};
+use serde_json::Value;
+use crate::export::ics::{self, OpcionesIcs};
+use crate::models::Seccion;
+
+fn parse_fecha(valor: Option<&str>, campo: &str) -> Result<Option<NaiveDate>, ApiError> {
+    match valor {
+        Some(s) => NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| ApiError::InvalidInput(format!("invalid '{}': expected YYYY-MM-DD", campo))),
+        None => Ok(None),
+    }
+}
+
+/// Opciones del calendario a partir de `fecha_inicio`, `fecha_fin`, `semanas` y `nombre`
+pub(crate) fn opciones_ics(
+    fecha_inicio: Option<&str>,
+    fecha_fin: Option<&str>,
+    semanas: Option<u32>,
+    nombre: Option<String>,
+) -> Result<OpcionesIcs, ApiError> {
+    let fecha_inicio = parse_fecha(fecha_inicio, "fecha_inicio")?.unwrap_or_else(|| Utc::now().date_naive());
+    let fecha_fin = parse_fecha(fecha_fin, "fecha_fin")?;
+    if fecha_fin.map(|f| f < fecha_inicio).unwrap_or(false) {
+        return Err(ApiError::InvalidInput("'fecha_fin' is before 'fecha_inicio'".into()));
+    }
+    Ok(OpcionesIcs {
+        fecha_inicio,
+        fecha_fin,
+        semanas: semanas.unwrap_or(ics::SEMANAS_POR_DEFECTO),
+        nombre,
+        generado: Utc::now().naive_utc(),
+    })
+}
+
+/// Respuesta `text/calendar` descargable como `horario.ics`
+pub(crate) fn respuesta_ics(secciones: &[Seccion], opciones: &OpcionesIcs) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename("horario.ics".to_string())],
+        })
+        .body(ics::generar_ics(secciones, opciones))
+}
+
+pub async fn export_ics_handler(body: web::Json<Value>) -> impl Responder {
+    let body = body.into_inner();
+    let secciones_json = body.get("secciones")
+        .or_else(|| body.get("solucion").and_then(|s| s.get("secciones")))
+        .cloned()
+        .unwrap_or(Value::Null);
+    let secciones: Vec<Seccion> = match serde_json::from_value(secciones_json) {
+        Ok(s) => s,
+        Err(e) => return ApiError::InvalidInput(format!("missing or invalid 'secciones': {}", e)).error_response(),
+    };
+    if secciones.is_empty() {
+        return ApiError::InvalidInput("'secciones' must not be empty".into()).error_response();
+    }
+
+    let texto = |campo: &str| body.get(campo).and_then(|v| v.as_str());
+    let opciones = match opciones_ics(
+        texto("fecha_inicio"),
+        texto("fecha_fin"),
+        body.get("semanas").and_then(|v| v.as_u64()).map(|n| n as u32),
+        texto("nombre").map(|s| s.to_string()),
+    ) {
+        Ok(o) => o,
+        Err(e) => return e.error_response(),
+    };
+    respuesta_ics(&secciones, &opciones)
+}
+
+/// Resuelve el body de `/solve` y responde con `generar` aplicado a las
+/// soluciones como archivo descargable
+#[cfg(any(feature = "export-xlsx", feature = "export-pdf"))]
+async fn exportar_soluciones(
+    body: Value,
+    generar: fn(&[crate::export::SolucionReporte]) -> Result<Vec<u8>, String>,
+    content_type: &'static str,
+    nombre_archivo: &'static str,
+) -> HttpResponse {
+    use crate::algorithm::deadline::Deadline;
+    use crate::algorithm::desglose::desglosar;
+    use crate::export::SolucionReporte;
+
+    let json_str = match serde_json::to_string(&body) {
+        Ok(s) => s,
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
+    };
+    let res = web::block(crate::middleware::trace::con_span_actual(move || -> Result<Vec<u8>, ApiError> {
+        let params = crate::api_json::parse_and_resolve_ramos(&json_str, Some("."))
+            .map_err(|e| ApiError::InvalidInput(e.to_string()))?;
+        let malla = params.malla.clone();
+        let deadline = Deadline::desde_params(&params);
+        let (soluciones, _, ramos, _) = crate::server_handlers::solve::ejecutar_con_progreso(params.clone(), &deadline)
+            .map_err(|e| ApiError::de_ruta(&malla, e))?;
+        let soluciones: Vec<_> = soluciones.into_iter().filter(|(sol, _)| !sol.is_empty()).collect();
+        if soluciones.is_empty() {
+            return Err(ApiError::NoFeasibleSchedule);
+        }
+        let secciones: Vec<Vec<Seccion>> = soluciones.iter()
+            .map(|(sol, _)| sol.iter().map(|(s, _)| s.clone()).collect())
+            .collect();
+        let desgloses: Vec<_> = soluciones.iter()
+            .map(|(sol, score)| desglosar(sol, *score, &params, &ramos))
+            .collect();
+        let reportes: Vec<SolucionReporte> = soluciones.iter().zip(&secciones).zip(&desgloses)
+            .map(|(((_, score), secciones), desglose)| SolucionReporte { secciones, total_score: *score, desglose })
+            .collect();
+        generar(&reportes).map_err(|e| ApiError::Internal(format!("{}: {}", nombre_archivo, e)))
+    })).await;
+
+    match res {
+        Ok(Ok(bytes)) => HttpResponse::Ok()
+            .content_type(content_type)
+            .insert_header(ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![DispositionParam::Filename(nombre_archivo.to_string())],
+            })
+            .body(bytes),
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
+
+/// Resuelve el body de `/solve` y devuelve el reporte XLSX de las soluciones
+#[cfg(feature = "export-xlsx")]
+pub async fn export_xlsx_handler(body: web::Json<Value>) -> impl Responder {
+    use crate::export::xlsx;
+    exportar_soluciones(
+        body.into_inner(),
+        |reportes| xlsx::generar_xlsx(reportes).map_err(|e| e.to_string()),
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        xlsx::NOMBRE_ARCHIVO,
+    ).await
+}
+
+/// Resuelve el body de `/solve` y devuelve el horario de cada solución en PDF
+#[cfg(feature = "export-pdf")]
+pub async fn export_pdf_handler(body: web::Json<Value>) -> impl Responder {
+    use crate::export::pdf;
+    exportar_soluciones(
+        body.into_inner(),
+        |reportes| pdf::generar_pdf(reportes).map_err(|e| e.to_string()),
+        "application/pdf",
+        pdf::NOMBRE_ARCHIVO,
+    ).await
+}
@@ -0,0 +1,110 @@
+// health.rs - Sondas de salud para orquestadores (Kubernetes, Docker, etc.)
+//
+// `GET /health/live` solo confirma que el proceso responde. `GET /health/ready`
+// verifica que el servicio pueda atender un /solve: que el directorio de
+// datafiles exista, que la malla por defecto se pueda leer (con sus
+// porcentajes) y que la base de analíticas acepte una consulta. Responde 200
+// si todo está bien y 503 si algo falla, con el estado de cada verificación.
+
+use actix_web::{web, HttpResponse, Responder};
+use serde::Serialize;
+use serde_json::json;
+use std::time::Instant;
+
+/// Malla que se usa si no hay `DEFAULT_MALLA` ni carreras en el registro
+const MALLA_POR_DEFECTO: &str = "MC2020.xlsx";
+
+/// Resultado de una verificación de `/health/ready`
+#[derive(Debug, Clone, Serialize)]
+pub struct Verificacion {
+    pub ok: bool,
+    /// Qué se verificó o por qué falló
+    pub detalle: String,
+    pub ms: u128,
+}
+
+fn verificar(f: impl FnOnce() -> Result<String, String>) -> Verificacion {
+    let inicio = Instant::now();
+    let (ok, detalle) = match f() {
+        Ok(d) => (true, d),
+        Err(e) => (false, e),
+    };
+    Verificacion { ok, detalle, ms: inicio.elapsed().as_millis() }
+}
+
+/// Malla por defecto: `DEFAULT_MALLA`, la primera de la primera carrera del
+/// registro o `MC2020.xlsx`
+pub fn malla_por_defecto() -> String {
+    if let Some(m) = crate::app_config::actual().default_malla.clone() {
+        return m;
+    }
+    crate::carreras::registro().carreras.first()
+        .and_then(|c| c.mallas.first().cloned())
+        .unwrap_or_else(|| MALLA_POR_DEFECTO.to_string())
+}
+
+fn verificar_datafiles() -> Result<String, String> {
+    let dir = crate::excel::get_datafiles_dir();
+    if dir.is_dir() {
+        Ok(dir.display().to_string())
+    } else {
+        Err(format!("directorio de datafiles no encontrado ({})", dir.display()))
+    }
+}
+
+fn verificar_malla(malla: &str) -> Result<String, String> {
+    let (malla_path, _oferta, porcentajes) = crate::excel::resolve_datafile_paths(malla).map_err(|e| e.to_string())?;
+    let ramos = crate::datafiles::cache::malla_con_porcentajes(&malla_path.to_string_lossy(), &porcentajes.to_string_lossy())
+        .map_err(|e| format!("no se pudo leer {}: {}", malla, e))?;
+    if ramos.is_empty() {
+        return Err(format!("{} no tiene ramos", malla));
+    }
+    Ok(format!("{} ({} ramos)", malla, ramos.len()))
+}
+
+fn verificar_analytics() -> Result<String, String> {
+    let storage = crate::analithics::storage::abrir().map_err(|e| e.to_string())?;
+    // Rango vacío: solo comprueba que la conexión y la tabla respondan
+    let ts = crate::analithics::storage::TS_MAX;
+    storage.queries_entre(ts, ts).map_err(|e| e.to_string())?;
+    Ok(storage.backend().to_string())
+}
+
+/// Ejecuta las verificaciones de `/health/ready`: (todas bien, estado por verificación)
+pub fn verificaciones_ready(malla: &str) -> (bool, serde_json::Map<String, serde_json::Value>) {
+    let resultados = [
+        ("datafiles", verificar(verificar_datafiles)),
+        ("malla", verificar(|| verificar_malla(malla))),
+        ("analytics_db", verificar(verificar_analytics)),
+    ];
+    let ok = resultados.iter().all(|(_, v)| v.ok);
+    let checks = resultados.into_iter()
+        .map(|(nombre, v)| (nombre.to_string(), json!(v)))
+        .collect();
+    (ok, checks)
+}
+
+/// GET /health/live
+pub async fn health_live_handler() -> impl Responder {
+    HttpResponse::Ok().json(json!({"status": "ok"}))
+}
+
+/// GET /health/ready
+/// 200 si el servicio puede atender requests, 503 si alguna verificación falla.
+pub async fn health_ready_handler() -> impl Responder {
+    let malla = malla_por_defecto();
+    let (ok, checks) = match web::block(move || verificaciones_ready(&malla)).await {
+        Ok(r) => r,
+        Err(e) => {
+            let mut checks = serde_json::Map::new();
+            checks.insert("blocking".to_string(), json!({"ok": false, "detalle": e.to_string(), "ms": 0}));
+            (false, checks)
+        }
+    };
+    let body = json!({"status": if ok { "ok" } else { "unavailable" }, "checks": checks});
+    if ok {
+        HttpResponse::Ok().json(body)
+    } else {
+        HttpResponse::ServiceUnavailable().json(body)
+    }
+}
@@ -0,0 +1,278 @@
+//! Jobs asíncronos de resolución (`/solve/async`).
+//!
+//! Los modos exhaustivos (`solver: "exact"`, ofertas grandes) pueden tardar
+//! más que el timeout HTTP de un proxy. `POST /solve/async` encola el mismo
+//! body de `/solve` y responde de inmediato con un `job_id`; un pool de tareas
+//! tokio (`JOBS_WORKERS`) los ejecuta en orden de llegada.
+//!
+//! - `GET /jobs/{id}`: estado (`en_cola`, `ejecutando`, `completado`,
+//!   `fallido`) y avance (secciones viables, cliques encontrados).
+//! - `GET /jobs/{id}/result`: la respuesta de `/solve` (o su error) una vez
+//!   terminado; 409 `JOB_NOT_FINISHED` mientras tanto.
+//!
+//! Los jobs terminados se guardan en la DB de analíticas (`analithics::jobs`)
+//! y salen de memoria tras `JOBS_TTL_SECS`; después se consultan desde ahí.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use crate::algorithm::deadline::{Deadline, Progreso};
+use crate::analithics::jobs::{guardar_job, obtener_job, JobTerminado};
+use crate::api_json::InputParams;
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EstadoJob {
+    EnCola,
+    Ejecutando,
+    Completado,
+    Fallido,
+}
+
+impl EstadoJob {
+    fn as_str(self) -> &'static str {
+        match self {
+            EstadoJob::EnCola => "en_cola",
+            EstadoJob::Ejecutando => "ejecutando",
+            EstadoJob::Completado => "completado",
+            EstadoJob::Fallido => "fallido",
+        }
+    }
+}
+
+struct Job {
+    estado: EstadoJob,
+    creado: String,
+    email: String,
+    params: Value,
+    encolado: Instant,
+    iniciado: Option<Instant>,
+    /// Se crea al empezar a ejecutar; sus contadores son el avance
+    deadline: Option<Deadline>,
+    terminado: Option<(Instant, JobTerminado)>,
+}
+
+fn jobs() -> &'static Mutex<HashMap<String, Job>> {
+    static JOBS: OnceLock<Mutex<HashMap<String, Job>>> = OnceLock::new();
+    JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Pool de workers: cada job ocupa un permiso mientras se ejecuta
+fn workers() -> Arc<Semaphore> {
+    static WORKERS: OnceLock<Arc<Semaphore>> = OnceLock::new();
+    WORKERS.get_or_init(|| {
+        let n = crate::app_config::actual().jobs_workers;
+        Arc::new(Semaphore::new(n.max(1)))
+    }).clone()
+}
+
+fn jobs_ttl() -> Duration {
+    Duration::from_secs(crate::app_config::actual().jobs_ttl_secs)
+}
+
+/// Límite de un job: `timeout_ms`, luego `JOB_TIMEOUT_MS`, luego 10 minutos
+fn deadline_job(params: &InputParams) -> Deadline {
+    let ms = params.timeout_ms.unwrap_or_else(|| crate::app_config::actual().job_timeout_ms);
+    Deadline::en(Duration::from_millis(ms))
+}
+
+/// Descarta de memoria los jobs terminados hace más de `JOBS_TTL_SECS`
+/// (siguen disponibles en la DB de analíticas)
+fn evict(map: &mut HashMap<String, Job>) {
+    let ttl = jobs_ttl();
+    map.retain(|_, j| j.terminado.as_ref().is_none_or(|(t, _)| t.elapsed() < ttl));
+}
+
+fn con_jobs<T>(f: impl FnOnce(&mut HashMap<String, Job>) -> T) -> T {
+    let mut map = jobs().lock().unwrap_or_else(|p| p.into_inner());
+    f(&mut map)
+}
+
+/// Estado de un job en memoria, como lo devuelve `GET /jobs/{id}`
+fn estado_json(id: &str, job: &Job) -> Value {
+    let progreso = match (&job.terminado, &job.deadline) {
+        (Some((_, t)), _) => t.progreso.clone(),
+        (None, Some(d)) => json!(d.progreso()),
+        (None, None) => json!(Progreso::default()),
+    };
+    let fin = job.terminado.as_ref().map(|(t, _)| *t).unwrap_or_else(Instant::now);
+    let mut v = json!({
+        "job_id": id,
+        "estado": job.estado.as_str(),
+        "creado": job.creado,
+        "progreso": progreso,
+        "queued_ms": job.iniciado.unwrap_or(fin).duration_since(job.encolado).as_millis() as u64,
+        "elapsed_ms": job.iniciado.map(|i| fin.duration_since(i).as_millis() as u64),
+    });
+    if let Some((_, t)) = &job.terminado {
+        agregar_terminado(&mut v, t);
+    }
+    v
+}
+
+fn agregar_terminado(v: &mut Value, t: &JobTerminado) {
+    v["terminado"] = json!(t.terminado);
+    v["result_url"] = json!(format!("/jobs/{}/result", t.id));
+    if t.estado == EstadoJob::Fallido.as_str() {
+        v["error"] = t.resultado.clone();
+    } else {
+        v["partial"] = t.resultado.get("partial").cloned().unwrap_or(Value::Bool(false));
+    }
+}
+
+/// Estado de un job que ya no está en memoria, desde la DB de analíticas
+fn estado_guardado(t: &JobTerminado) -> Value {
+    let mut v = json!({
+        "job_id": t.id,
+        "estado": t.estado,
+        "creado": t.creado,
+        "progreso": t.progreso,
+    });
+    agregar_terminado(&mut v, t);
+    v
+}
+
+async fn job_guardado(id: String) -> Result<JobTerminado, ApiError> {
+    let buscado = id.clone();
+    web::block(move || {
+        let conn = crate::analithics::db::open_analytics_connection().map_err(|e| e.to_string())?;
+        obtener_job(&conn, &buscado).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| ApiError::Internal(format!("task join error: {}", e)))?
+    .map_err(|e| ApiError::Storage(format!("failed to read job: {}", e)))?
+    .ok_or(ApiError::JobNotFound(id))
+}
+
+/// Ejecuta el job `id`: espera un worker, resuelve como `/solve` y deja el
+/// resultado en memoria y en la DB de analíticas
+async fn ejecutar_job(id: String, params: InputParams, con_grid: bool) {
+    let Ok(_worker) = workers().acquire_owned().await else { return };
+    let resultado = match super::solve::permiso_solver().await {
+        Ok(permit) => {
+            let deadline = deadline_job(&params);
+            con_jobs(|map| if let Some(job) = map.get_mut(&id) {
+                job.estado = EstadoJob::Ejecutando;
+                job.iniciado = Some(Instant::now());
+                job.deadline = Some(deadline.clone());
+            });
+            tracing::info!("⚙️  job {} ejecutando", id);
+            super::solve::resolver_respuesta(params, con_grid, permit, &deadline).await
+                .map(|resp| (deadline.progreso(), resp))
+        }
+        Err(e) => Err(e),
+    };
+
+    let (estado, status, progreso, cuerpo) = match resultado {
+        Ok((progreso, resp)) => (EstadoJob::Completado, 200, progreso, serde_json::to_value(&resp).unwrap_or(Value::Null)),
+        Err(e) => {
+            let progreso = con_jobs(|map| map.get(&id).and_then(|j| j.deadline.as_ref().map(|d| d.progreso())));
+            (EstadoJob::Fallido, e.status(), progreso.unwrap_or_default(), e.to_json())
+        }
+    };
+    let Some(terminado) = con_jobs(|map| {
+        let job = map.get_mut(&id)?;
+        let t = JobTerminado {
+            id: id.clone(),
+            creado: job.creado.clone(),
+            terminado: Utc::now().to_rfc3339(),
+            email: Some(job.email.clone()).filter(|e| !e.trim().is_empty()),
+            estado: estado.as_str().to_string(),
+            status: status as i64,
+            params: job.params.clone(),
+            progreso: json!(progreso),
+            resultado: cuerpo,
+        };
+        job.estado = estado;
+        job.terminado = Some((Instant::now(), t.clone()));
+        Some(t)
+    }) else { return };
+    tracing::info!("⚙️  job {} {}", id, estado.as_str());
+
+    let res = web::block(move || {
+        let conn = crate::analithics::db::open_analytics_connection().map_err(|e| e.to_string())?;
+        guardar_job(&conn, &terminado).map_err(|e| e.to_string())
+    }).await;
+    if let Err(e) = res.map_err(|e| e.to_string()).and_then(|r| r) {
+        tracing::warn!("no se pudo guardar el resultado del job {}: {}", id, e);
+    }
+}
+
+/// POST /solve/async[?format=grid]
+/// Mismo body que `/solve`; responde 202 con el `job_id` para consultar el avance.
+pub async fn solve_async_handler(req: HttpRequest, body: web::Json<Value>) -> impl Responder {
+    let json_str = match serde_json::to_string(&body.into_inner()) {
+        Ok(s) => s,
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
+    };
+    let params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
+        Ok(p) => p,
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
+    };
+    let con_grid = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map(|q| q.get("format").is_some_and(|f| f.eq_ignore_ascii_case("grid")))
+        .unwrap_or(false);
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let creado = Utc::now().to_rfc3339();
+    con_jobs(|map| {
+        evict(map);
+        map.insert(id.clone(), Job {
+            estado: EstadoJob::EnCola,
+            creado: creado.clone(),
+            email: params.email.clone(),
+            params: serde_json::to_value(&params).unwrap_or(Value::Null),
+            encolado: Instant::now(),
+            iniciado: None,
+            deadline: None,
+            terminado: None,
+        });
+    });
+    tokio::spawn(ejecutar_job(id.clone(), params, con_grid));
+
+    HttpResponse::Accepted().json(json!({
+        "job_id": id,
+        "estado": EstadoJob::EnCola.as_str(),
+        "creado": creado,
+        "status_url": format!("/jobs/{}", id),
+        "result_url": format!("/jobs/{}/result", id),
+    }))
+}
+
+/// GET /jobs/{id}
+pub async fn job_status_handler(path: web::Path<String>) -> impl Responder {
+    let id = path.into_inner();
+    if let Some(v) = con_jobs(|map| map.get(&id).map(|j| estado_json(&id, j))) {
+        return HttpResponse::Ok().json(v);
+    }
+    match job_guardado(id).await {
+        Ok(t) => HttpResponse::Ok().json(estado_guardado(&t)),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// GET /jobs/{id}/result
+/// Cuerpo de `/solve` (o su error, con el mismo status) del job terminado.
+pub async fn job_result_handler(path: web::Path<String>) -> impl Responder {
+    let id = path.into_inner();
+    let en_memoria = con_jobs(|map| map.get(&id).map(|j| match &j.terminado {
+        Some((_, t)) => Ok(t.clone()),
+        None => Err(ApiError::JobNotFinished { id: id.clone(), estado: j.estado.as_str() }),
+    }));
+    let terminado = match en_memoria {
+        Some(r) => r,
+        None => job_guardado(id).await,
+    };
+    match terminado {
+        Ok(t) => {
+            let status = actix_web::http::StatusCode::from_u16(t.status as u16)
+                .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+            HttpResponse::build(status).json(t.resultado)
+        }
+        Err(e) => e.error_response(),
+    }
+}
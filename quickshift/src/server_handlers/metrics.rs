@@ -0,0 +1,10 @@
+use actix_web::{HttpResponse, Responder};
+
+/// GET /metrics
+/// Histogramas de duración, secciones y soluciones por fase (`crate::metrics`)
+/// en formato de texto de Prometheus.
+pub async fn metrics_handler() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(crate::metrics::render())
+}
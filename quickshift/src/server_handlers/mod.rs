@@ -2,8 +2,44 @@ pub mod solve;
 pub mod rutacritica;
 pub mod docs;
 pub mod analithics;
+pub mod session;
+pub mod rollover;
+pub mod planner;
+pub mod simulacion;
+pub mod progreso;
+pub mod explain;
+pub mod prechequeo;
+pub mod export;
+pub mod soluciones;
+pub mod metrics;
+pub mod batch;
+pub mod health;
+pub mod config;
+pub mod oferta;
+pub mod profesores;
+pub mod solve_ws;
+pub mod jobs;
+pub mod equivalencias;
 
 pub use solve::*;
 pub use rutacritica::*;
 pub use docs::*;
 pub use analithics::*;
+pub use session::*;
+pub use rollover::*;
+pub use planner::*;
+pub use simulacion::*;
+pub use progreso::*;
+pub use explain::*;
+pub use prechequeo::*;
+pub use export::*;
+pub use soluciones::*;
+pub use metrics::*;
+pub use batch::*;
+pub use health::*;
+pub use config::*;
+pub use oferta::*;
+pub use profesores::*;
+pub use solve_ws::*;
+pub use jobs::*;
+pub use equivalencias::*;
@@ -0,0 +1,61 @@
+//! Oferta académica como recurso consultable (`GET /oferta`).
+//!
+//! Lee la oferta indicada en `oferta=` (o la más reciente de datafiles) desde
+//! el caché de datafiles y la filtra por curso, profesor, día y rango horario
+//! con `excel::oferta::consultar_oferta`, devolviendo una página y el total.
+
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use crate::excel::oferta::{consultar_oferta, ConsultaOferta};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Criterios de la query; `page` y `per_page` deben ser números
+fn consulta_desde_query(q: &HashMap<String, String>) -> Result<ConsultaOferta, String> {
+    let texto = |k: &str| q.get(k).cloned();
+    let numero = |k: &str| -> Result<Option<usize>, String> {
+        q.get(k).map(|v| v.trim().parse::<usize>().map_err(|_| format!("{} inválido: '{}'", k, v))).transpose()
+    };
+    Ok(ConsultaOferta {
+        curso: texto("curso"),
+        profesor: texto("profesor"),
+        dia: texto("dia"),
+        desde: texto("desde"),
+        hasta: texto("hasta"),
+        page: numero("page")?,
+        per_page: numero("per_page")?,
+    })
+}
+
+/// GET /oferta?curso=&profesor=&dia=&desde=&hasta=&page=&per_page=&oferta=
+pub async fn oferta_handler(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    let consulta = match consulta_desde_query(&query) {
+        Ok(c) => c,
+        Err(e) => return ApiError::InvalidInput(e).error_response(),
+    };
+    let oferta = query.get("oferta").filter(|o| !o.trim().is_empty()).cloned();
+
+    let res = web::block(move || -> Result<_, ApiError> {
+        let ruta = match oferta {
+            Some(o) => o,
+            None => crate::excel::latest_file_for_keywords(&["oferta", "oa"])
+                .map(|p| p.to_string_lossy().to_string())
+                .ok_or_else(|| ApiError::FileNotFound("oferta".into()))?,
+        };
+        let secciones = crate::datafiles::cache::oferta(&ruta)
+            .map_err(|e| ApiError::InvalidDatafile { message: format!("failed to read oferta '{}': {}", ruta, e), details: None })?;
+        let pagina = consultar_oferta(&secciones, &consulta).map_err(ApiError::InvalidInput)?;
+        let archivo = std::path::Path::new(&ruta).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or(ruta);
+        Ok((archivo, pagina))
+    }).await;
+
+    match res {
+        Ok(Ok((archivo, pagina))) => {
+            let mut cuerpo = json!(pagina);
+            cuerpo["archivo"] = json!(archivo);
+            HttpResponse::Ok().json(cuerpo)
+        }
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
@@ -0,0 +1,86 @@
+//! Plan de carrera (`POST /plan/carrera`).
+//!
+//! Mismo body que `POST /solve`, más dos campos opcionales:
+//! - `periodo_inicial`: periodo del primer semestre planificado (p.ej. "20252").
+//!   Por defecto, el periodo de la Oferta Académica vigente.
+//! - `max_semestres`: tope de semestres a proyectar (por defecto 20).
+
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Instant;
+use crate::algorithm::planner;
+use crate::api_json::InputParams;
+use crate::models::{RamoDisponible, Seccion};
+use crate::middleware::trace::con_span_actual;
+
+/// Malla completa, oferta vigente y ruta del archivo de oferta
+pub(crate) type MallaYOferta = (HashMap<String, RamoDisponible>, Vec<Seccion>, PathBuf);
+
+/// Malla completa y oferta vigente de `params.malla`. Aplica las equivalencias
+/// de la malla sobre `params.ramos_pasados`. También lo usa `POST /solve/explain`.
+pub(crate) fn cargar_malla_y_oferta(params: &mut InputParams) -> Result<MallaYOferta, String> {
+    let (malla_path, oferta_path, porcentajes_path) = crate::excel::resolve_datafile_paths(&params.malla)
+        .map_err(|e| format!("failed to resolve malla '{}': {}", params.malla, e))?;
+    let malla_str = malla_path.to_string_lossy().to_string();
+    let malla = crate::algorithm::ruta::cargar_ramos_malla(&malla_str, &porcentajes_path.to_string_lossy())
+        .map_err(|e| format!("failed to read malla '{}': {}", malla_str, e))?;
+    params.ramos_pasados = match crate::excel::cargar_equivalencias(&malla_str) {
+        Ok(eq) if !eq.is_empty() => crate::excel::aplicar_equivalencias(&params.ramos_pasados, &eq),
+        _ => params.ramos_pasados.clone(),
+    };
+    let mut secciones = crate::datafiles::cache::oferta(&oferta_path.to_string_lossy())
+        .map(|s| s.as_ref().clone())
+        .map_err(|e| format!("failed to read oferta {:?}: {}", oferta_path, e))?;
+    crate::algorithm::ruta::cargar_catalogo_electivos(&malla_str).etiquetar(&mut secciones);
+    Ok((malla, secciones, oferta_path))
+}
+
+pub async fn plan_carrera_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    let body_value = body.into_inner();
+    let periodo_inicial = body_value.get("periodo_inicial").and_then(|v| v.as_str()).map(|s| s.replace('-', ""));
+    let max_semestres = body_value.get("max_semestres")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+        .unwrap_or(planner::MAX_SEMESTRES_DEFAULT);
+    if max_semestres == 0 {
+        return ApiError::InvalidInput("max_semestres must be greater than 0".into()).error_response();
+    }
+
+    let json_str = match serde_json::to_string(&body_value) {
+        Ok(s) => s,
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
+    };
+    let mut params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
+        Ok(p) => p,
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
+    };
+
+    let malla_pedida = params.malla.clone();
+    let start = Instant::now();
+    let res = web::block(con_span_actual(move || -> Result<planner::PlanCarrera, String> {
+        let (malla, secciones, oferta_path) = cargar_malla_y_oferta(&mut params)?;
+
+        let periodo = periodo_inicial
+            .or_else(|| oferta_path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(crate::server_handlers::rollover::periodo_de_archivo)
+                .map(|(_, p)| p))
+            .or_else(|| crate::server_handlers::rollover::periodo_activo(&crate::excel::get_datafiles_dir()))
+            .ok_or("no se pudo determinar el periodo inicial: indique 'periodo_inicial' (p.ej. \"20252\")")?;
+
+        Ok(planner::planificar_carrera(&params, &malla, &secciones, &periodo, max_semestres))
+    })).await;
+
+    match res {
+        Ok(Ok(plan)) => {
+            let mut out = json!(plan);
+            out["compute_ms"] = json!(start.elapsed().as_millis() as u64);
+            HttpResponse::Ok().json(out)
+        }
+        Ok(Err(msg)) => ApiError::del_pipeline(&malla_pedida, msg).error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
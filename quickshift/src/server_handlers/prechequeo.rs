@@ -0,0 +1,46 @@
+//! Prechequeo de factibilidad (`POST /solve/precheck`).
+//!
+//! Mismo body que `POST /solve`. Prepara malla y oferta igual que el solve,
+//! pero solo aplica las etapas de filtrado (ver `algorithm::prechequeo`) y
+//! responde cuántos ramos y secciones quedan tras cada una, para avisar de
+//! filtros demasiado estrictos antes de pagar la búsqueda de cliques.
+
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use serde_json::json;
+use std::time::Instant;
+use crate::algorithm::engine::Datafiles;
+use crate::algorithm::prechequeo::prechequear;
+use crate::algorithm::SchedulePlanner;
+use crate::middleware::trace::con_span_actual;
+
+pub async fn precheck_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    let json_str = match serde_json::to_string(&body.into_inner()) {
+        Ok(s) => s,
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
+    };
+    let mut params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
+        Ok(p) => p,
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
+    };
+
+    let malla_pedida = params.malla.clone();
+    let malla = malla_pedida.clone();
+    let start = Instant::now();
+    let res = web::block(con_span_actual(move || {
+        Datafiles.preparar(&mut params)
+            .and_then(|datos| prechequear(&datos, &params))
+            .map_err(|e| ApiError::de_ruta(&malla, e))
+    })).await;
+
+    match res {
+        Ok(Ok(prechequeo)) => {
+            let mut out = json!(prechequeo);
+            out["malla"] = json!(malla_pedida);
+            out["compute_ms"] = json!(start.elapsed().as_millis() as u64);
+            HttpResponse::Ok().json(out)
+        }
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
@@ -0,0 +1,78 @@
+//! Directorio de profesores (`GET /profesores`, `GET /profesores/{nombre}`).
+//!
+//! Cruza la oferta (`oferta=` o la más reciente) con el archivo de
+//! porcentajes (`porcentajes=` o el más reciente) vía `excel::profesores`.
+//! Sin archivo de porcentajes la ficha sale igual, sin aprobación histórica.
+
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use crate::datafiles::cache::PorcentajesConNombres;
+use crate::excel::profesores::{buscar_profesores, ficha_profesor};
+use crate::models::Seccion;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Nombre de archivo de una ruta, para informarlo en la respuesta
+fn archivo(ruta: &str) -> String {
+    std::path::Path::new(ruta).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| ruta.to_string())
+}
+
+/// Ruta del parámetro `clave` o el datafile más reciente con esas palabras
+fn ruta_datafile(query: &HashMap<String, String>, clave: &str, keywords: &[&str]) -> Option<String> {
+    query.get(clave).filter(|v| !v.trim().is_empty()).cloned()
+        .or_else(|| crate::excel::latest_file_for_keywords(keywords).map(|p| p.to_string_lossy().to_string()))
+}
+
+fn cargar_oferta(query: &HashMap<String, String>) -> Result<(String, Arc<Vec<Seccion>>), ApiError> {
+    let ruta = ruta_datafile(query, "oferta", &["oferta", "oa"]).ok_or_else(|| ApiError::FileNotFound("oferta".into()))?;
+    let secciones = crate::datafiles::cache::oferta(&ruta)
+        .map_err(|e| ApiError::InvalidDatafile { message: format!("failed to read oferta '{}': {}", ruta, e), details: None })?;
+    Ok((archivo(&ruta), secciones))
+}
+
+/// GET /profesores?q=&oferta=
+/// Profesores cuyo nombre contiene `q` (todos si no viene), con cuántos cursos y secciones dictan
+pub async fn profesores_buscar_handler(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    let query = query.into_inner();
+    let res = web::block(move || -> Result<_, ApiError> {
+        let (archivo, secciones) = cargar_oferta(&query)?;
+        let profesores = buscar_profesores(&secciones, query.get("q").map(String::as_str).unwrap_or(""));
+        Ok(json!({ "archivo": archivo, "total": profesores.len(), "profesores": profesores }))
+    }).await;
+
+    match res {
+        Ok(Ok(cuerpo)) => HttpResponse::Ok().json(cuerpo),
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
+
+/// GET /profesores/{nombre}?oferta=&porcentajes=
+/// Cursos y secciones del profesor este semestre, con el porcentaje histórico de aprobación de cada curso
+pub async fn profesor_handler(nombre: web::Path<String>, query: web::Query<HashMap<String, String>>) -> impl Responder {
+    let nombre = nombre.into_inner();
+    let query = query.into_inner();
+    let res = web::block(move || -> Result<_, ApiError> {
+        let (archivo_oferta, secciones) = cargar_oferta(&query)?;
+        let (archivo_porcentajes, porcentajes) = match ruta_datafile(&query, "porcentajes", &["porcentaje", "porcentajes", "pa"]) {
+            Some(ruta) => {
+                let p = crate::datafiles::cache::porcentajes(&ruta)
+                    .map_err(|e| ApiError::InvalidDatafile { message: format!("failed to read porcentajes '{}': {}", ruta, e), details: None })?;
+                (Some(archivo(&ruta)), p)
+            }
+            None => (None, Arc::new(PorcentajesConNombres::default())),
+        };
+        let ficha = ficha_profesor(&secciones, &porcentajes, &nombre).ok_or_else(|| ApiError::ProfessorNotFound(nombre.clone()))?;
+        let mut cuerpo = json!(ficha);
+        cuerpo["archivo_oferta"] = json!(archivo_oferta);
+        cuerpo["archivo_porcentajes"] = json!(archivo_porcentajes);
+        Ok(cuerpo)
+    }).await;
+
+    match res {
+        Ok(Ok(cuerpo)) => HttpResponse::Ok().json(cuerpo),
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
@@ -0,0 +1,71 @@
+//! Avance hacia la titulación (`POST /progress`).
+//!
+//! Body: `malla`, `ramos_pasados` (códigos o nombres) y `carrera` opcional
+//! (para las reglas de CFG y electivos, ver `reglas::reglas_de`). Responde el
+//! avance por semestre, la ruta crítica completada, los pendientes y la
+//! proyección de semestres restantes (ver `algorithm::progreso_titulacion`).
+//! Pensado para paneles de consejería: no lee la oferta ni arma horarios.
+
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Instant;
+use crate::algorithm::progreso_titulacion::{calcular_progreso, ProgresoTitulacion};
+use crate::middleware::trace::con_span_actual;
+use crate::reglas::MAX_RAMOS_POR_DEFECTO;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProgresoRequest {
+    /// Malla de datafiles (igual que `InputParams::malla`)
+    pub malla: String,
+    #[serde(default)]
+    pub ramos_pasados: Vec<String>,
+    /// Carrera del registro, para sus reglas
+    #[serde(default)]
+    pub carrera: Option<String>,
+    /// Ramos por semestre para la proyección por carga (por defecto 6)
+    #[serde(default)]
+    pub max_ramos: Option<usize>,
+}
+
+/// Calcula el avance de `body` leyendo la malla y sus equivalencias
+pub fn progreso_de(body: &ProgresoRequest) -> Result<ProgresoTitulacion, ApiError> {
+    let (malla_path, _, porcentajes_path) = crate::excel::resolve_datafile_paths(&body.malla)
+        .map_err(|e| ApiError::del_pipeline(&body.malla, format!("failed to resolve malla '{}': {}", body.malla, e)))?;
+    let malla_str = malla_path.to_string_lossy().to_string();
+    let malla = crate::algorithm::ruta::cargar_ramos_malla(&malla_str, &porcentajes_path.to_string_lossy())
+        .map_err(|e| ApiError::InvalidDatafile { message: format!("failed to read malla '{}': {}", malla_str, e), details: None })?;
+    let ramos_pasados = match crate::excel::cargar_equivalencias(&malla_str) {
+        Ok(eq) if !eq.is_empty() => crate::excel::aplicar_equivalencias(&body.ramos_pasados, &eq),
+        _ => body.ramos_pasados.clone(),
+    };
+    let reglas = crate::reglas::reglas_de(&body.malla, body.carrera.as_deref());
+    Ok(calcular_progreso(&malla, &ramos_pasados, &reglas, body.max_ramos.unwrap_or(MAX_RAMOS_POR_DEFECTO)))
+}
+
+pub async fn progreso_handler(body: web::Json<ProgresoRequest>) -> impl Responder {
+    let body = body.into_inner();
+    if body.malla.trim().is_empty() {
+        return ApiError::MissingParameter("malla").error_response();
+    }
+    if body.max_ramos == Some(0) {
+        return ApiError::InvalidInput("max_ramos must be greater than 0".into()).error_response();
+    }
+    if let Some(c) = body.carrera.as_deref().filter(|c| crate::carreras::registro().get(c).is_none()) {
+        return ApiError::InvalidInput(format!("carrera '{}' no está en el registro", c)).error_response();
+    }
+
+    let start = Instant::now();
+    let res = web::block(con_span_actual(move || progreso_de(&body).map(|p| (body.malla, p)))).await;
+    match res {
+        Ok(Ok((malla, progreso))) => {
+            let mut out = json!(progreso);
+            out["malla"] = json!(malla);
+            out["compute_ms"] = json!(start.elapsed().as_millis() as u64);
+            HttpResponse::Ok().json(out)
+        }
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
@@ -0,0 +1,279 @@
+//! Cambio de semestre (`POST /admin/rollover`).
+//!
+//! Orquesta en una sola operación lo que antes se hacía a mano al iniciar un
+//! semestre:
+//! 1. Valida que existan (y se puedan leer) los archivos del periodo nuevo
+//!    (`OA<periodo>` obligatorio; `PA<periodo>` y `CFG<periodo>` opcionales).
+//! 2. Archiva los archivos del periodo anterior que tienen reemplazo en
+//!    `<datafiles>/archive/<periodo>/`, con `checksums.json` (SHA-256) y
+//!    permisos de solo lectura.
+//! 3. Registra el periodo activo en `<datafiles>/periodo_activo.json`. Como la
+//!    resolución de datafiles elige el OA/PA más reciente del directorio, al
+//!    archivar los del periodo anterior el nuevo queda activo.
+//! 4. Invalida las sesiones de `/solve/session` (datos cacheados del periodo anterior).
+//! 5. Precalienta: lee cada malla disponible contra los archivos nuevos.
+//! 6. Devuelve (y guarda junto al archivo) un reporte con el resultado.
+//!
+//! Con `"dry_run": true` solo se valida y se devuelve el plan.
+//! La solicitud debe traer `X-Admin-Token` igual a `ADMIN_TOKEN`; sin ella
+//! configurada el endpoint responde 401.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::Path;
+use std::time::Instant;
+use crate::middleware::auth::{autorizado, sha256_hex};
+use crate::middleware::trace::con_span_actual;
+
+/// Tipos de archivo que dependen del periodo
+const PREFIJOS_PERIODO: [&str; 3] = ["OA", "PA", "CFG"];
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RolloverRequest {
+    /// Periodo nuevo, p.ej. "20252" (año + semestre)
+    pub periodo: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Qué haría el rollover sobre el directorio de datafiles
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanRollover {
+    pub periodo_nuevo: String,
+    pub periodo_anterior: Option<String>,
+    /// Archivos del periodo nuevo encontrados
+    pub nuevos: Vec<String>,
+    /// Archivos del periodo anterior que se moverán al archivo
+    pub a_archivar: Vec<String>,
+    pub advertencias: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivoArchivado {
+    pub nombre: String,
+    pub sha256: String,
+    pub bytes: u64,
+}
+
+/// Extrae (prefijo, periodo) de nombres como `OA20251.xlsx`, `PA2025-1.xlsx` o `CFG20251.xlsx`
+pub fn periodo_de_archivo(nombre: &str) -> Option<(&'static str, String)> {
+    let upper = nombre.to_uppercase();
+    if upper.contains("_TEST") || upper.contains("_BACKUP") {
+        return None;
+    }
+    for prefijo in PREFIJOS_PERIODO {
+        if let Some(resto) = upper.strip_prefix(prefijo) {
+            let digitos: String = resto.chars().filter(|c| *c != '-').take_while(|c| c.is_ascii_digit()).collect();
+            if digitos.len() == 4 || digitos.len() == 5 {
+                return Some((prefijo, digitos));
+            }
+        }
+    }
+    None
+}
+
+/// Clave de orden (año, semestre); "2024" equivale a (2024, 0)
+fn orden_periodo(periodo: &str) -> (u32, u32) {
+    let anio = periodo.get(..4).and_then(|s| s.parse().ok()).unwrap_or(0);
+    let sem = periodo.get(4..).and_then(|s| s.parse().ok()).unwrap_or(0);
+    (anio, sem)
+}
+
+fn archivos_con_periodo(data_dir: &Path) -> Vec<(String, &'static str, String)> {
+    let mut out = Vec::new();
+    if let Ok(read) = std::fs::read_dir(data_dir) {
+        for entry in read.flatten() {
+            let p = entry.path();
+            if !p.is_file() { continue; }
+            let Some(nombre) = p.file_name().and_then(|s| s.to_str()) else { continue };
+            if let Some((prefijo, periodo)) = periodo_de_archivo(nombre) {
+                out.push((nombre.to_string(), prefijo, periodo));
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+/// Calcula el plan de rollover. Falla si no existe la OA del periodo nuevo o si
+/// el periodo nuevo no es posterior al activo.
+pub fn planificar_rollover(data_dir: &Path, periodo_nuevo: &str) -> Result<PlanRollover, String> {
+    let periodo_nuevo = periodo_nuevo.replace('-', "");
+    if !(periodo_nuevo.len() == 4 || periodo_nuevo.len() == 5) || !periodo_nuevo.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("periodo inválido '{}': se espera AAAAS, p.ej. 20252", periodo_nuevo));
+    }
+    let archivos = archivos_con_periodo(data_dir);
+
+    let nuevos: Vec<&(String, &str, String)> = archivos.iter().filter(|(_, _, p)| *p == periodo_nuevo).collect();
+    if !nuevos.iter().any(|(_, prefijo, _)| *prefijo == "OA") {
+        return Err(format!("falta la Oferta Académica OA{} en {:?}", periodo_nuevo, data_dir));
+    }
+
+    let periodo_anterior = archivos.iter()
+        .filter(|(_, prefijo, p)| *prefijo == "OA" && *p != periodo_nuevo)
+        .map(|(_, _, p)| p.clone())
+        .max_by_key(|p| orden_periodo(p));
+    if let Some(anterior) = periodo_anterior.as_ref().filter(|a| orden_periodo(a) >= orden_periodo(&periodo_nuevo)) {
+        return Err(format!("el periodo {} no es posterior al activo {}", periodo_nuevo, anterior));
+    }
+
+    let mut advertencias = Vec::new();
+    let mut a_archivar = Vec::new();
+    for prefijo in PREFIJOS_PERIODO {
+        let tiene_nuevo = nuevos.iter().any(|(_, p, _)| *p == prefijo);
+        if !tiene_nuevo {
+            advertencias.push(format!("no hay {}{}: se mantiene el archivo vigente", prefijo, periodo_nuevo));
+            continue;
+        }
+        if let Some(ref anterior) = periodo_anterior {
+            a_archivar.extend(archivos.iter()
+                .filter(|(_, p, per)| *p == prefijo && per == anterior)
+                .map(|(n, _, _)| n.clone()));
+        }
+    }
+
+    Ok(PlanRollover {
+        periodo_nuevo,
+        periodo_anterior,
+        nuevos: nuevos.iter().map(|(n, _, _)| n.clone()).collect(),
+        a_archivar,
+        advertencias,
+    })
+}
+
+fn marcar_solo_lectura(path: &Path) -> std::io::Result<()> {
+    let mut perms = std::fs::metadata(path)?.permissions();
+    perms.set_readonly(true);
+    std::fs::set_permissions(path, perms)
+}
+
+/// Mueve `archivos` a `<data_dir>/archive/<periodo>/`, escribe `checksums.json`
+/// y deja todo en solo lectura.
+pub fn archivar_periodo(data_dir: &Path, periodo: &str, archivos: &[String]) -> Result<Vec<ArchivoArchivado>, String> {
+    let destino = data_dir.join("archive").join(periodo);
+    std::fs::create_dir_all(&destino).map_err(|e| format!("no se pudo crear {:?}: {}", destino, e))?;
+
+    let mut archivados = Vec::new();
+    for nombre in archivos {
+        let origen = data_dir.join(nombre);
+        let bytes = std::fs::read(&origen).map_err(|e| format!("no se pudo leer {:?}: {}", origen, e))?;
+        let objetivo = destino.join(nombre);
+        std::fs::rename(&origen, &objetivo).map_err(|e| format!("no se pudo mover {:?}: {}", origen, e))?;
+        marcar_solo_lectura(&objetivo).map_err(|e| format!("no se pudo proteger {:?}: {}", objetivo, e))?;
+        archivados.push(ArchivoArchivado { nombre: nombre.clone(), sha256: sha256_hex(&bytes), bytes: bytes.len() as u64 });
+    }
+
+    // Rollover repetido sobre el mismo periodo: conservar las entradas previas.
+    // El archivo es de solo lectura, así que se reemplaza en vez de sobrescribirlo.
+    let checksums = destino.join("checksums.json");
+    if let Ok(prev) = std::fs::read_to_string(&checksums) {
+        if let Ok(prev) = serde_json::from_str::<Vec<ArchivoArchivado>>(&prev) {
+            let mut todos: Vec<ArchivoArchivado> = prev.into_iter().filter(|p| !archivos.contains(&p.nombre)).collect();
+            todos.extend(archivados);
+            archivados = todos;
+        }
+        std::fs::remove_file(&checksums).map_err(|e| format!("no se pudo reemplazar {:?}: {}", checksums, e))?;
+    }
+    let texto = serde_json::to_string_pretty(&archivados).map_err(|e| e.to_string())?;
+    std::fs::write(&checksums, texto).map_err(|e| format!("no se pudo escribir {:?}: {}", checksums, e))?;
+    marcar_solo_lectura(&checksums).map_err(|e| format!("no se pudo proteger {:?}: {}", checksums, e))?;
+    Ok(archivados)
+}
+
+/// Periodo activo registrado por el último rollover (si existe)
+pub fn periodo_activo(data_dir: &Path) -> Option<String> {
+    let texto = std::fs::read_to_string(data_dir.join("periodo_activo.json")).ok()?;
+    let v: serde_json::Value = serde_json::from_str(&texto).ok()?;
+    v.get("periodo").and_then(|p| p.as_str()).map(|s| s.to_string())
+}
+
+/// POST /admin/rollover
+pub async fn admin_rollover_handler(req: HttpRequest, body: web::Json<RolloverRequest>) -> impl Responder {
+    if !autorizado(&req) {
+        return ApiError::Unauthorized("invalid or missing X-Admin-Token".into()).error_response();
+    }
+    let body = body.into_inner();
+    let start = Instant::now();
+
+    let res = web::block(con_span_actual(move || -> Result<serde_json::Value, ApiError> {
+        let data_dir = crate::excel::get_datafiles_dir();
+        let plan = planificar_rollover(&data_dir, &body.periodo).map_err(ApiError::InvalidInput)?;
+
+        // Validar que la nueva oferta se pueda leer antes de tocar nada
+        let oa_nueva = plan.nuevos.iter()
+            .find(|n| n.to_uppercase().starts_with("OA"))
+            .cloned()
+            .unwrap_or_default();
+        let oa_path = data_dir.join(&oa_nueva);
+        let secciones = crate::datafiles::cache::oferta(&oa_path.to_string_lossy())
+            .map(|s| s.as_ref().clone())
+            .map_err(|e| ApiError::InvalidDatafile { message: format!("no se pudo leer {}: {}", oa_nueva, e), details: None })?;
+        if secciones.is_empty() {
+            return Err(ApiError::InvalidDatafile { message: format!("{} no contiene secciones", oa_nueva), details: None });
+        }
+        tracing::info!("🔄 [rollover] {} -> {} ({} secciones en {})",
+                  plan.periodo_anterior.as_deref().unwrap_or("-"), plan.periodo_nuevo, secciones.len(), oa_nueva);
+
+        if body.dry_run {
+            return Ok(json!({"dry_run": true, "plan": plan, "secciones_nuevas": secciones.len()}));
+        }
+
+        // Archivar periodo anterior
+        let archivados = match plan.periodo_anterior {
+            Some(ref anterior) if !plan.a_archivar.is_empty() => archivar_periodo(&data_dir, anterior, &plan.a_archivar).map_err(ApiError::Storage)?,
+            _ => Vec::new(),
+        };
+
+        // Registrar periodo activo
+        let activado_en = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let estado = json!({"periodo": plan.periodo_nuevo, "anterior": plan.periodo_anterior, "activado_en": activado_en});
+        std::fs::write(data_dir.join("periodo_activo.json"), estado.to_string())
+            .map_err(|e| ApiError::Storage(format!("no se pudo registrar el periodo activo: {}", e)))?;
+
+        // Invalidar cachés
+        let sesiones_invalidadas = crate::server_handlers::session::clear_sessions();
+
+        // Precalentar: cada malla disponible contra los archivos nuevos
+        let mut warmup = Vec::new();
+        let mallas = crate::excel::list_available_datafiles().map(|(m, _, _)| m).unwrap_or_default();
+        for malla in mallas {
+            let t = Instant::now();
+            let resultado = crate::excel::resolve_datafile_paths(&malla)
+                .and_then(|(m, _, p)| crate::algorithm::ruta::cargar_ramos_malla(&m.to_string_lossy(), &p.to_string_lossy()));
+            warmup.push(match resultado {
+                Ok(ramos) => json!({"malla": malla, "ok": true, "ramos": ramos.len(), "ms": t.elapsed().as_millis() as u64}),
+                Err(e) => json!({"malla": malla, "ok": false, "error": e.to_string()}),
+            });
+        }
+
+        let reporte = json!({
+            "dry_run": false,
+            "plan": plan,
+            "secciones_nuevas": secciones.len(),
+            "archivados": archivados,
+            "sesiones_invalidadas": sesiones_invalidadas,
+            "warmup": warmup,
+        });
+        if let Some(ref anterior) = plan.periodo_anterior {
+            let path = data_dir.join("archive").join(anterior).join(format!("rollover_{}.json", plan.periodo_nuevo));
+            if let Err(e) = std::fs::write(&path, reporte.to_string()) {
+                tracing::warn!("   ⚠️  [rollover] no se pudo guardar el reporte {:?}: {}", path, e);
+            }
+        }
+        Ok(reporte)
+    })).await;
+
+    match res {
+        Ok(Ok(mut reporte)) => {
+            reporte["compute_ms"] = json!(start.elapsed().as_millis() as u64);
+            HttpResponse::Ok().json(reporte)
+        }
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
@@ -1,60 +1,173 @@
-use actix_web::{web, HttpResponse, Responder};
-use serde_json::json;
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use serde_json::{json, Value};
+use crate::algorithm::comodidad::{self, ConfigComodidad};
+use crate::algorithm::paralelo::Soluciones;
+use crate::api_json::InputParams;
+use crate::models::{PreferenciasProfesores, Seccion};
+use crate::middleware::trace::con_span_actual;
+
+/// Corre el pipeline en el pool bloqueante, con a lo más un cálculo por CPU
+async fn ejecutar_limitado(params: InputParams) -> Result<Soluciones, ApiError> {
+    let malla_pedida = params.malla.clone();
+    static GLOBAL_SEM2: std::sync::OnceLock<std::sync::Arc<tokio::sync::Semaphore>> = std::sync::OnceLock::new();
+    let sem2 = GLOBAL_SEM2.get_or_init(|| std::sync::Arc::new(tokio::sync::Semaphore::new(std::cmp::max(1, num_cpus::get())))).clone();
+    let permit2 = sem2.clone().acquire_owned().await
+        .map_err(|_| ApiError::Internal("failed to acquire semaphore".into()))?;
+
+    let blocking = tokio::task::spawn_blocking(con_span_actual(move || {
+        let _permit2 = permit2;
+        match crate::algorithm::ejecutar_ruta_critica_with_params(params) {
+            Ok(sol) => Ok(sol),
+            Err(e) => Err(format!("{}", e)),
+        }
+    }));
+
+    match blocking.await {
+        Ok(Ok(soluciones)) => Ok(soluciones),
+        Ok(Err(e)) => Err(ApiError::del_pipeline(&malla_pedida, e)),
+        Err(e) => Err(ApiError::Internal(format!("task join error: {}", e))),
+    }
+}
+
+/// `comodidad` del body, si viene
+fn config_comodidad(body: &Value) -> Result<Option<ConfigComodidad>, ApiError> {
+    body.get("comodidad")
+        .map(|c| serde_json::from_value(c.clone()).map_err(|e| ApiError::InvalidInput(format!("comodidad inválida: {}", e))))
+        .transpose()
+}
+
+/// Preferencias de profesores habilitadas en `filtros` del body
+fn profesores_de_filtros(body: &Value) -> Option<PreferenciasProfesores> {
+    body.pointer("/filtros/preferencias_profesores")
+        .and_then(|p| serde_json::from_value::<PreferenciasProfesores>(p.clone()).ok())
+        .filter(|p| p.habilitado)
+}
+
+/// Soluciones de un `PathsOutput` ya calculado (`soluciones` de `/solve` o de
+/// `/rutacritica/run`, o `paths`). Cada sección puede venir sola o como
+/// `{"seccion": ..., "prioridad": ...}`; el score se lee de `total_score` o `score`.
+fn soluciones_del_body(lista: &[Value]) -> Result<Vec<(Vec<Seccion>, i64)>, ApiError> {
+    lista.iter().enumerate().map(|(i, sol)| {
+        let score = sol.get("total_score").or_else(|| sol.get("score")).and_then(Value::as_i64).unwrap_or(0);
+        let secciones = sol.get("secciones").and_then(Value::as_array)
+            .ok_or_else(|| ApiError::InvalidInput(format!("soluciones[{}] no trae 'secciones'", i)))?
+            .iter()
+            // `Seccion` también tiene un campo `seccion` (el número), por eso se pide un objeto
+            .map(|s| s.get("seccion").filter(|v| v.is_object()).unwrap_or(s))
+            .map(|s| serde_json::from_value::<Seccion>(s.clone())
+                .map_err(|e| ApiError::InvalidInput(format!("soluciones[{}]: sección inválida: {}", i, e))))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((secciones, score))
+    }).collect()
+}
 
 pub async fn rutacomoda_best_handler(body: web::Json<serde_json::Value>) -> impl Responder {
     let body_value = body.into_inner();
+    let comodidad = match config_comodidad(&body_value) {
+        Ok(c) => c,
+        Err(e) => return e.error_response(),
+    };
     let json_str = match serde_json::to_string(&body_value) {
         Ok(s) => s,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("invalid JSON body: {}", e)})),
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
     };
 
     let params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
         Ok(p) => p,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("failed to parse input: {}", e)})),
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
     };
 
-    static GLOBAL_SEM2: std::sync::OnceLock<std::sync::Arc<tokio::sync::Semaphore>> = std::sync::OnceLock::new();
-    let sem2 = GLOBAL_SEM2.get_or_init(|| std::sync::Arc::new(tokio::sync::Semaphore::new(std::cmp::max(1, num_cpus::get())))).clone();
-    let permit2 = match sem2.clone().acquire_owned().await {
-        Ok(p) => p,
-        Err(_) => return HttpResponse::InternalServerError().json(json!({"error": "failed to acquire semaphore"})),
+    let soluciones = match ejecutar_limitado(params).await {
+        Ok(s) => s,
+        Err(e) => return e.error_response(),
     };
+    if soluciones.is_empty() {
+        return HttpResponse::Ok().json(json!({"best": []}));
+    }
 
-    let blocking = tokio::task::spawn_blocking(move || {
-        let _permit2 = permit2;
-        match crate::algorithm::ejecutar_ruta_critica_with_params(params) {
-            Ok(sol) => Ok(sol),
-            Err(e) => Err(format!("{}", e)),
+    // Con `comodidad` los mejores son los más cómodos en vez de los de mayor score
+    if let Some(config) = comodidad {
+        let candidatas = soluciones.into_iter()
+            .map(|(sol, score)| (sol.into_iter().map(|(s, _)| s).collect(), score))
+            .collect();
+        let ranking = match comodidad::rankear(candidatas, &config, profesores_de_filtros(&body_value).as_ref()) {
+            Ok(r) => r,
+            Err(e) => return ApiError::InvalidInput(e).error_response(),
+        };
+        let mejor = ranking[0].comodidad;
+        let bests: Vec<Value> = ranking.into_iter()
+            .take_while(|r| r.comodidad == mejor)
+            .map(|r| json!({
+                "path": r.secciones.iter().map(|s| s.codigo.clone()).collect::<Vec<_>>(),
+                "score": r.total_score,
+                "comodidad": r.comodidad,
+                "metricas": r.metricas,
+            }))
+            .collect();
+        return HttpResponse::Ok().json(json!({"best": bests}));
+    }
+
+    let mut max_score: Option<i64> = None;
+    for (_sol, score) in soluciones.iter() {
+        match max_score {
+            None => max_score = Some(*score),
+            Some(ms) => if *score > ms { max_score = Some(*score); }
         }
-    });
+    }
 
-    match blocking.await {
-        Ok(Ok(soluciones)) => {
-            if soluciones.is_empty() {
-                return HttpResponse::Ok().json(json!({"best": []}));
-            }
+    let ms = max_score.unwrap_or(0);
+    let mut bests: Vec<serde_json::Value> = Vec::new();
+    for (sol, score) in soluciones.into_iter() {
+        if score == ms {
+            let path_codes: Vec<String> = sol.into_iter().map(|(s, _prio)| s.codigo).collect();
+            bests.push(json!({"path": path_codes, "score": score}));
+        }
+    }
 
-            let mut max_score: Option<i64> = None;
-            for (_sol, score) in soluciones.iter() {
-                match max_score {
-                    None => max_score = Some(*score),
-                    Some(ms) => if *score > ms { max_score = Some(*score); }
-                }
-            }
+    HttpResponse::Ok().json(json!({"best": bests}))
+}
 
-            let ms = max_score.unwrap_or(0);
-            let mut bests: Vec<serde_json::Value> = Vec::new();
-            for (sol, score) in soluciones.into_iter() {
-                if score == ms {
-                    let path_codes: Vec<String> = sol.into_iter().map(|(s, _prio)| s.codigo).collect();
-                    bests.push(json!({"path": path_codes, "score": score}));
-                }
-            }
+/// POST /rutacomoda/rank
+/// Reordena soluciones por comodidad (ver `algorithm::comodidad`). Con
+/// `soluciones` (o `paths`) en el body reordena esas; si no, el body es el de
+/// `POST /solve` y se resuelve primero.
+pub async fn rutacomoda_rank_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    let body_value = body.into_inner();
+    let config = match config_comodidad(&body_value) {
+        Ok(c) => c.unwrap_or_default(),
+        Err(e) => return e.error_response(),
+    };
 
-            HttpResponse::Ok().json(json!({"best": bests}))
+    let recibidas = body_value.get("soluciones").or_else(|| body_value.get("paths")).and_then(Value::as_array);
+    let soluciones = match recibidas {
+        Some(lista) => match soluciones_del_body(lista) {
+            Ok(s) => s,
+            Err(e) => return e.error_response(),
+        },
+        None => {
+            let json_str = match serde_json::to_string(&body_value) {
+                Ok(s) => s,
+                Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
+            };
+            let params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
+                Ok(p) => p,
+                Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
+            };
+            match ejecutar_limitado(params).await {
+                Ok(s) => s.into_iter().map(|(sol, score)| (sol.into_iter().map(|(s, _)| s).collect(), score)).collect(),
+                Err(e) => return e.error_response(),
+            }
         }
-        Ok(Err(e)) => HttpResponse::InternalServerError().json(json!({"error": format!("algorithm error: {}", e)})),
-        Err(e) => HttpResponse::InternalServerError().json(json!({"error": format!("task join error: {}", e)})),
+    };
+
+    match comodidad::rankear(soluciones, &config, profesores_de_filtros(&body_value).as_ref()) {
+        Ok(ranking) => HttpResponse::Ok().json(json!({
+            "perfil": config.perfil,
+            "pesos": config.pesos_efectivos(),
+            "soluciones": ranking,
+        })),
+        Err(e) => ApiError::InvalidInput(e).error_response(),
     }
 }
 
@@ -62,12 +175,12 @@ pub async fn rutacritica_run_handler(body: web::Json<serde_json::Value>) -> impl
     let body_value = body.into_inner();
     let json_str = match serde_json::to_string(&body_value) {
         Ok(s) => s,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("invalid JSON body: {}", e)})),
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
     };
 
     let params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
         Ok(p) => p,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("failed to parse input: {}", e)})),
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
     };
 
     // DEBUG: incluir optimizations en response para verificar que se parsea
@@ -76,6 +189,7 @@ pub async fn rutacritica_run_handler(body: web::Json<serde_json::Value>) -> impl
         "horarios_prohibidos_count": params.horarios_prohibidos.len(),
     });
 
+    let malla_pedida = params.malla.clone();
     match crate::algorithm::ejecutar_ruta_critica_with_params(params) {
         Ok(soluciones) => {
             let mut out: Vec<serde_json::Value> = Vec::new();
@@ -89,7 +203,7 @@ pub async fn rutacritica_run_handler(body: web::Json<serde_json::Value>) -> impl
             }
             HttpResponse::Ok().json(json!({"status": "ok", "debug": debug_info, "soluciones": out}))
         }
-        Err(e) => HttpResponse::InternalServerError().json(json!({"status": "error", "error": format!("{}", e)})),
+        Err(e) => ApiError::del_pipeline(&malla_pedida, e).error_response(),
     }
 }
 
@@ -100,23 +214,23 @@ pub async fn rutacritica_run_dependencies_only_handler(body: web::Json<serde_jso
     let body_value = body.into_inner();
     let json_str = match serde_json::to_string(&body_value) {
         Ok(s) => s,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("invalid JSON body: {}", e)})),
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
     };
 
     let params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
         Ok(p) => p,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("failed to parse input: {}", e)})),
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
     };
 
     if params.email.trim().is_empty() {
-        return HttpResponse::BadRequest().json(json!({"error": "email is required"}));
+        return ApiError::MissingParameter("email").error_response();
     }
 
     let initial_map: HashMap<String, RamoDisponible> = HashMap::new();
     let sheet_opt = params.sheet.as_deref();
     let (lista_secciones, ramos_actualizados) = match crate::algorithm::extract_data(initial_map, &params.malla, sheet_opt) {
         Ok((ls, ra)) => (ls, ra),
-        Err(e) => return HttpResponse::InternalServerError().json(json!({"status": "error", "error": format!("extraction failed: {}", e)})),
+        Err(e) => return ApiError::del_pipeline(&params.malla, format!("extraction failed: {}", e)).error_response(),
     };
 
     let soluciones = crate::algorithm::get_clique_dependencies_only(&lista_secciones, &ramos_actualizados);
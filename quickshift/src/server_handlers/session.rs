@@ -0,0 +1,290 @@
+//! Sesiones de resolución incremental (`/solve/session`).
+//!
+//! La primera llamada ejecuta las fases 0-2b del pipeline (lectura de Excel,
+//! equivalencias, PERT) y construye el grafo de compatibilidad de secciones.
+//! Ambos quedan en memoria asociados a un `session_id`. Las llamadas a
+//! `/solve/session/{id}/refine` solo vuelven a aplicar filtros y scoring sobre
+//! esos datos, sin releer archivos ni recalcular la matriz de adyacencia.
+
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use crate::algorithm::clique::CompatibilityGraph;
+use crate::algorithm::ruta::DatosRuta;
+use crate::api_json::InputParams;
+use crate::middleware::CacheStatus;
+use crate::models::UserFilters;
+use crate::server_handlers::solve::soluciones_to_entries;
+use crate::middleware::trace::con_span_actual;
+use crate::algorithm::deadline::Deadline;
+use crate::algorithm::engine::{Datafiles, SchedulePlanner};
+
+/// Máximo de sesiones en memoria; al superarlo se descarta la menos usada recientemente.
+const MAX_SESSIONS: usize = 64;
+
+struct SolveSession {
+    params: InputParams,
+    datos: Arc<DatosRuta>,
+    grafo: Arc<CompatibilityGraph>,
+    last_used: Instant,
+}
+
+/// Cambios aceptados al refinar una sesión. Los campos omitidos conservan el valor anterior.
+/// `ramos_pasados` y `malla` no se pueden cambiar: requieren una sesión nueva.
+#[derive(Debug, Default, serde::Deserialize, schemars::JsonSchema)]
+pub struct RefineRequest {
+    #[serde(default)]
+    pub filtros: Option<UserFilters>,
+    #[serde(default)]
+    pub optimizations: Option<Vec<String>>,
+    #[serde(default)]
+    pub ramos_prioritarios: Option<Vec<String>>,
+    #[serde(default)]
+    pub ramos_excluidos: Option<Vec<String>>,
+    #[serde(default)]
+    pub horarios_preferidos: Option<Vec<String>>,
+    #[serde(default)]
+    pub horarios_prohibidos: Option<Vec<String>>,
+    #[serde(default)]
+    pub compromisos: Option<Vec<crate::models::Compromiso>>,
+    #[serde(default)]
+    pub objetivo: Option<crate::api_json::Objetivo>,
+    /// Reemplaza los ajustes de pesos del request (ver `crate::scoring`)
+    #[serde(default)]
+    pub scoring: Option<crate::scoring::ScoringOverrides>,
+    /// Reemplaza las secciones fijas (ver `InputParams::secciones_fijas`)
+    #[serde(default)]
+    pub secciones_fijas: Option<Vec<String>>,
+    /// Si es true, elimina los filtros actuales (equivale a `filtros: null`)
+    #[serde(default)]
+    pub clear_filtros: bool,
+}
+
+impl RefineRequest {
+    /// Aplica los cambios sobre una copia de los parámetros de la sesión
+    pub fn apply_to(&self, params: &InputParams) -> InputParams {
+        let mut p = params.clone();
+        if self.clear_filtros {
+            p.filtros = None;
+        }
+        if let Some(ref f) = self.filtros {
+            p.filtros = Some(f.clone());
+        }
+        if let Some(ref o) = self.optimizations {
+            p.optimizations = o.clone();
+        }
+        if let Some(ref r) = self.ramos_prioritarios {
+            p.ramos_prioritarios = r.clone();
+        }
+        if let Some(ref r) = self.ramos_excluidos {
+            p.ramos_excluidos = r.clone();
+        }
+        if let Some(ref h) = self.horarios_preferidos {
+            p.horarios_preferidos = h.clone();
+        }
+        if let Some(ref h) = self.horarios_prohibidos {
+            p.horarios_prohibidos = h.clone();
+        }
+        if let Some(ref c) = self.compromisos {
+            p.compromisos = c.clone();
+        }
+        if let Some(o) = self.objetivo {
+            p.objetivo = o;
+        }
+        if let Some(ref s) = self.scoring {
+            p.scoring = Some(s.clone());
+        }
+        if let Some(ref f) = self.secciones_fijas {
+            p.secciones_fijas = f.clone();
+        }
+        crate::api_json::normalizar_horarios(&mut p);
+        p
+    }
+}
+
+fn sessions() -> &'static Mutex<HashMap<String, SolveSession>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<String, SolveSession>>> = OnceLock::new();
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn session_ttl() -> Duration {
+    Duration::from_secs(crate::app_config::actual().solve_session_ttl_secs)
+}
+
+fn new_session_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{:x}-{:04x}", nanos, n & 0xffff)
+}
+
+/// Elimina sesiones expiradas y, si aún se supera el máximo, la menos usada.
+fn evict(map: &mut HashMap<String, SolveSession>) {
+    let ttl = session_ttl();
+    map.retain(|_, s| s.last_used.elapsed() < ttl);
+    while map.len() >= MAX_SESSIONS {
+        let oldest = map.iter().min_by_key(|(_, s)| s.last_used).map(|(k, _)| k.clone());
+        match oldest {
+            Some(k) => { map.remove(&k); }
+            None => break,
+        }
+    }
+}
+
+/// Descarta todas las sesiones (p.ej. tras cambiar los datafiles). Devuelve cuántas había.
+pub fn clear_sessions() -> usize {
+    match sessions().lock() {
+        Ok(mut m) => {
+            let n = m.len();
+            m.clear();
+            n
+        }
+        Err(poisoned) => {
+            let mut m = poisoned.into_inner();
+            let n = m.len();
+            m.clear();
+            n
+        }
+    }
+}
+
+/// POST /solve/session
+/// Mismo body que `/solve`. Carga datos, construye el grafo y devuelve `session_id` + soluciones.
+pub async fn solve_session_create_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    let json_str = match serde_json::to_string(&body.into_inner()) {
+        Ok(s) => s,
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
+    };
+    let params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
+        Ok(p) => p,
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
+    };
+
+    let start = Instant::now();
+    let deadline = Deadline::desde_params(&params);
+    let _cancelar = deadline.cancelar_al_soltar();
+    let deadline_block = deadline.clone();
+    let res = web::block(con_span_actual(move || -> Result<_, ApiError> {
+        let mut params = params;
+        let datos = Datafiles.preparar(&mut params)
+            .map_err(|e| ApiError::del_pipeline(&params.malla, format!("ruta_critica failed: {}", e)))?;
+        let grafo = datos.build_compatibility_graph();
+        let soluciones = Datafiles.resolver_con_grafo(&datos, &params, Some(&grafo), &deadline_block)
+            .map_err(|e| ApiError::de_ruta(&params.malla, e))?;
+        Ok((params, datos, grafo, soluciones))
+    })).await;
+
+    let (params, datos, grafo, soluciones) = match res {
+        Ok(Ok(v)) => v,
+        Ok(Err(e)) => return e.error_response(),
+        Err(e) => return ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    };
+
+    let session_id = new_session_id();
+    let graph_nodes = grafo.len();
+    let graph_edges = grafo.edge_count();
+    let entries = soluciones_to_entries(&soluciones, &params, &datos.ramos_disponibles, &datos.lista_secciones);
+    {
+        let mut map = match sessions().lock() {
+            Ok(m) => m,
+            Err(_) => return ApiError::Internal("session store poisoned".into()).error_response(),
+        };
+        evict(&mut map);
+        map.insert(session_id.clone(), SolveSession {
+            params,
+            datos: Arc::new(datos),
+            grafo: Arc::new(grafo),
+            last_used: Instant::now(),
+        });
+    }
+
+    HttpResponse::Ok().json(json!({
+        "session_id": session_id,
+        "expires_in_secs": session_ttl().as_secs(),
+        "graph": {"nodes": graph_nodes, "edges": graph_edges},
+        "compute_ms": start.elapsed().as_millis() as u64,
+        "soluciones_count": soluciones.len(),
+        "soluciones": entries,
+        "partial": deadline.alcanzado(),
+    }))
+}
+
+/// POST /solve/session/{id}/refine
+/// Reaplica filtros/scoring sobre los datos y el grafo cacheados de la sesión.
+pub async fn solve_session_refine_handler(path: web::Path<String>, body: web::Json<RefineRequest>) -> impl Responder {
+    let session_id = path.into_inner();
+    let refine = body.into_inner();
+
+    let (params, datos, grafo) = {
+        let mut map = match sessions().lock() {
+            Ok(m) => m,
+            Err(_) => return ApiError::Internal("session store poisoned".into()).error_response(),
+        };
+        let expired = match map.get(&session_id) {
+            Some(s) => s.last_used.elapsed() >= session_ttl(),
+            None => return ApiError::SessionNotFound(session_id).error_response(),
+        };
+        if expired {
+            map.remove(&session_id);
+            let mut resp = ApiError::SessionExpired(session_id).error_response();
+            resp.extensions_mut().insert(CacheStatus::Stale);
+            return resp;
+        }
+        let s = match map.get_mut(&session_id) {
+            Some(s) => s,
+            None => return ApiError::SessionNotFound(session_id).error_response(),
+        };
+        s.params = refine.apply_to(&s.params);
+        s.last_used = Instant::now();
+        (s.params.clone(), s.datos.clone(), s.grafo.clone())
+    };
+
+    let start = Instant::now();
+    let deadline = Deadline::desde_params(&params);
+    let _cancelar = deadline.cancelar_al_soltar();
+    let deadline_block = deadline.clone();
+    let params_block = params.clone();
+    let datos_block = datos.clone();
+    let res = web::block(con_span_actual(move || {
+        Datafiles.resolver_con_grafo(&datos_block, &params_block, Some(&grafo), &deadline_block)
+            .map_err(|e| ApiError::de_ruta(&params_block.malla, e))
+    })).await;
+
+    let soluciones = match res {
+        Ok(Ok(v)) => v,
+        Ok(Err(e)) => return e.error_response(),
+        Err(e) => return ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    };
+
+    let mut resp = HttpResponse::Ok().json(json!({
+        "session_id": session_id,
+        "compute_ms": start.elapsed().as_millis() as u64,
+        "soluciones_count": soluciones.len(),
+        "soluciones": soluciones_to_entries(&soluciones, &params, &datos.ramos_disponibles, &datos.lista_secciones),
+        "partial": deadline.alcanzado(),
+    }));
+    resp.extensions_mut().insert(CacheStatus::Hit);
+    resp
+}
+
+/// DELETE /solve/session/{id}
+/// Libera la memoria de una sesión antes de que expire.
+pub async fn solve_session_delete_handler(path: web::Path<String>) -> impl Responder {
+    let session_id = path.into_inner();
+    let removed = match sessions().lock() {
+        Ok(mut m) => m.remove(&session_id).is_some(),
+        Err(_) => return ApiError::Internal("session store poisoned".into()).error_response(),
+    };
+    if removed {
+        HttpResponse::Ok().json(json!({"deleted": session_id}))
+    } else {
+        ApiError::SessionNotFound(session_id).error_response()
+    }
+}
@@ -0,0 +1,122 @@
+//! Simulaciones sobre el avance de los estudiantes.
+//!
+//! `POST /simulate/reprobacion`: mismo body que `POST /solve`, más
+//! `reprobado`: código (o nombre) del ramo en curso que se supone reprobado.
+//! Responde qué ramos pendientes se atrasan, la ruta crítica antes y después
+//! y los semestres extra estimados (ver `algorithm::reprobacion`).
+//!
+//! `POST /simulate/demanda`: mismo body que `POST /solve/batch`. Resuelve a
+//! la cohorte y compara cuántos estudiantes llevan cada sección en su mejor
+//! horario con los cupos de la oferta (ver `algorithm::cohorte`).
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Instant;
+use crate::algorithm::cohorte::{demanda_por_seccion, proyectar_demanda, EstadoCupos, UMBRAL_RIESGO_DEFAULT};
+use crate::algorithm::reprobacion;
+use crate::middleware::trace::con_span_actual;
+use crate::server_handlers::batch::{estudiantes_del_lote, reporte, resolver_lote};
+
+pub async fn simular_reprobacion_handler(body: web::Json<serde_json::Value>) -> impl Responder {
+    let body_value = body.into_inner();
+    let reprobado = match body_value.get("reprobado").and_then(|v| v.as_str()).map(|s| s.trim().to_string()) {
+        Some(r) if !r.is_empty() => r,
+        _ => return ApiError::MissingParameter("reprobado").error_response(),
+    };
+
+    let json_str = match serde_json::to_string(&body_value) {
+        Ok(s) => s,
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
+    };
+    let params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
+        Ok(p) => p,
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
+    };
+
+    let malla_pedida = params.malla.clone();
+    let start = Instant::now();
+    let res = web::block(con_span_actual(move || -> Result<Result<reprobacion::SimulacionReprobacion, String>, String> {
+        let (malla_path, _, porcentajes_path) = crate::excel::resolve_datafile_paths(&params.malla)
+            .map_err(|e| format!("failed to resolve malla '{}': {}", params.malla, e))?;
+        let malla_str = malla_path.to_string_lossy().to_string();
+        let malla = crate::algorithm::ruta::cargar_ramos_malla(&malla_str, &porcentajes_path.to_string_lossy())
+            .map_err(|e| format!("failed to read malla '{}': {}", malla_str, e))?;
+        let (ramos_pasados, reprobado) = match crate::excel::cargar_equivalencias(&malla_str) {
+            Ok(eq) if !eq.is_empty() => (
+                crate::excel::aplicar_equivalencias(&params.ramos_pasados, &eq),
+                crate::excel::aplicar_equivalencias(std::slice::from_ref(&reprobado), &eq).pop().unwrap_or(reprobado),
+            ),
+            _ => (params.ramos_pasados.clone(), reprobado),
+        };
+        Ok(reprobacion::simular_reprobacion(&malla, &ramos_pasados, &reprobado))
+    })).await;
+
+    match res {
+        Ok(Ok(Ok(simulacion))) => {
+            let mut out = json!(simulacion);
+            out["compute_ms"] = json!(start.elapsed().as_millis() as u64);
+            HttpResponse::Ok().json(out)
+        }
+        Ok(Ok(Err(msg))) => ApiError::InvalidInput(msg).error_response(),
+        Ok(Err(msg)) => ApiError::del_pipeline(&malla_pedida, msg).error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
+
+/// Número positivo de la query, o `defecto` si no viene
+fn positivo_de_query(query: &HashMap<String, String>, nombre: &str, defecto: f64) -> Result<f64, ApiError> {
+    match query.get(nombre).map(|v| v.trim().replace(',', ".").parse::<f64>()) {
+        None => Ok(defecto),
+        Some(Ok(x)) if x.is_finite() && x > 0.0 => Ok(x),
+        Some(_) => Err(ApiError::InvalidInput(format!("{} must be a positive number", nombre))),
+    }
+}
+
+/// POST /simulate/demanda
+/// Inscripción esperada por sección según el mejor horario de cada estudiante
+/// de la cohorte, frente a sus cupos disponibles. `?escala=` multiplica la
+/// demanda (cohorte que es una muestra) y `?umbral_riesgo=` (0.9 por defecto)
+/// es la ocupación desde la que una sección se marca `en_riesgo`.
+pub async fn simular_demanda_handler(req: HttpRequest, body: web::Bytes) -> impl Responder {
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map(|q| q.into_inner())
+        .unwrap_or_default();
+    let (escala, umbral_riesgo) = match (
+        positivo_de_query(&query, "escala", 1.0),
+        positivo_de_query(&query, "umbral_riesgo", UMBRAL_RIESGO_DEFAULT),
+    ) {
+        (Ok(e), Ok(u)) => (e, u),
+        (Err(e), _) | (_, Err(e)) => return e.error_response(),
+    };
+    let estudiantes = match estudiantes_del_lote(&req, &body, &query) {
+        Ok(e) => e,
+        Err(e) => return e.error_response(),
+    };
+
+    let start = Instant::now();
+    let resultados = resolver_lote(estudiantes, 1).await;
+    let lote = reporte(&resultados);
+    let demanda = demanda_por_seccion(resultados.iter().filter_map(|r| r.mejor_horario()));
+    let secciones = proyectar_demanda(&demanda, escala, umbral_riesgo);
+    let contar = |estado: EstadoCupos| secciones.iter().filter(|s| s.estado == estado).count();
+    let (desbordadas, en_riesgo) = (contar(EstadoCupos::Desborda), contar(EstadoCupos::EnRiesgo));
+    tracing::info!(
+        "📊 Demanda simulada para {} estudiantes: {} secciones, {} desbordan, {} en riesgo",
+        lote.total, secciones.len(), desbordadas, en_riesgo
+    );
+
+    HttpResponse::Ok().json(json!({
+        "estudiantes": lote.total,
+        "con_horario": lote.con_horario,
+        "sin_horario": lote.sin_horario,
+        "con_error": lote.con_error,
+        "escala": escala,
+        "umbral_riesgo": umbral_riesgo,
+        "desbordadas": desbordadas,
+        "en_riesgo": en_riesgo,
+        "secciones": secciones,
+        "compute_ms": start.elapsed().as_millis() as u64,
+    }))
+}
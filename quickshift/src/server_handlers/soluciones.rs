@@ -0,0 +1,235 @@
+//! Conjuntos de soluciones guardados por `/solve` (ver `analithics::soluciones`).
+//!
+//! - `GET /solutions/{id}`: params de entrada y soluciones devueltas.
+//! - `GET /solutions/{id}/export/ics?indice=0`: una solución como iCalendar
+//!   (mismas opciones que `POST /solve/export/ics`, por query).
+//! - `POST /solutions/{id}/confirm`: el estudiante elige una solución; queda
+//!   vinculada a su perfil y se consulta con `GET /students/{email}/solution`.
+//! - `POST /solutions/{id}/feedback`: nota 1-5 y comentario sobre una solución
+//!   (ver `analithics::feedback`).
+//! - `POST /solutions/compare`: diferencias entre dos soluciones, dadas por
+//!   sus secciones o por `solution_id` + `indice` (ver `algorithm::comparacion`).
+
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use crate::api_error::ApiError;
+use crate::algorithm::comparacion::comparar;
+use crate::analithics::db::AnalyticsConn;
+use crate::analithics::soluciones::{obtener_soluciones, solucion_elegida, vincular_solucion, ConjuntoSoluciones};
+use crate::models::Seccion;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Body JSON de `POST /solutions/{id}/confirm`
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct ConfirmarSolucionRequest {
+    /// Estudiante que confirma; por defecto el email con que se llamó a `/solve`
+    pub email: Option<String>,
+    /// Posición en `soluciones` (0 = la mejor)
+    #[serde(default)]
+    pub indice: usize,
+}
+
+/// Body JSON de `POST /solutions/{id}/feedback`
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct FeedbackRequest {
+    /// Nota de 1 a 5
+    pub rating: i64,
+    pub comentario: Option<String>,
+    /// Posición en `soluciones` de la solución valorada (0 = la mejor)
+    #[serde(default)]
+    pub indice: usize,
+    /// Por defecto el email con que se llamó a `/solve`
+    pub email: Option<String>,
+}
+
+/// Una de las soluciones de `POST /solutions/compare`
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum SolucionAComparar {
+    /// Solución de un conjunto guardado por `/solve`
+    Guardada {
+        solution_id: String,
+        /// Posición en `soluciones` (0 = la mejor)
+        #[serde(default)]
+        indice: usize,
+    },
+    /// Solución dada directamente (p. ej. una entrada de `soluciones` de `/solve`)
+    Secciones {
+        secciones: Vec<Seccion>,
+        total_score: Option<i64>,
+    },
+}
+
+/// Body JSON de `POST /solutions/compare`
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct CompararSolucionesRequest {
+    pub a: SolucionAComparar,
+    pub b: SolucionAComparar,
+}
+
+/// Ejecuta `f` con una conexión a la DB de analíticas en el pool bloqueante
+async fn con_analiticas<T, F>(f: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce(&AnalyticsConn) -> Result<T, ApiError> + Send + 'static,
+{
+    web::block(move || {
+        let conn = crate::analithics::db::open_analytics_connection()
+            .map_err(|e| ApiError::Storage(format!("analytics DB unavailable: {}", e)))?;
+        f(&conn)
+    })
+    .await
+    .map_err(|e| ApiError::Internal(format!("task join error: {}", e)))?
+}
+
+fn cargar_conjunto(conn: &AnalyticsConn, id: &str) -> Result<ConjuntoSoluciones, ApiError> {
+    obtener_soluciones(conn, id)
+        .map_err(|e| ApiError::Storage(format!("failed to read solutions: {}", e)))?
+        .ok_or_else(|| ApiError::SolutionNotFound(id.to_string()))
+}
+
+fn solucion_en(conjunto: &ConjuntoSoluciones, indice: usize) -> Result<&Value, ApiError> {
+    conjunto.solucion(indice).ok_or_else(|| ApiError::InvalidInput(format!(
+        "'indice' {} out of range: the solution set has {} solutions",
+        indice,
+        conjunto.soluciones.as_array().map(|s| s.len()).unwrap_or(0),
+    )))
+}
+
+/// Secciones y score de una solución guardada
+fn solucion_guardada(conn: &AnalyticsConn, id: &str, indice: usize) -> Result<(Vec<Seccion>, Option<i64>), ApiError> {
+    let conjunto = cargar_conjunto(conn, id)?;
+    let solucion = solucion_en(&conjunto, indice)?;
+    let secciones = serde_json::from_value::<Vec<Seccion>>(solucion.get("secciones").cloned().unwrap_or(Value::Null))
+        .map_err(|e| ApiError::Internal(format!("stored solution is invalid: {}", e)))?;
+    Ok((secciones, solucion.get("total_score").and_then(Value::as_i64)))
+}
+
+/// Resuelve las soluciones guardadas de `lado`; las dadas por secciones no tocan la DB
+async fn secciones_a_comparar(lado: SolucionAComparar) -> Result<(Vec<Seccion>, Option<i64>), ApiError> {
+    match lado {
+        SolucionAComparar::Secciones { secciones, total_score } => Ok((secciones, total_score)),
+        SolucionAComparar::Guardada { solution_id, indice } => {
+            con_analiticas(move |conn| solucion_guardada(conn, &solution_id, indice)).await
+        }
+    }
+}
+
+/// POST /solutions/compare
+pub async fn compare_solutions_handler(body: web::Json<CompararSolucionesRequest>) -> impl Responder {
+    let CompararSolucionesRequest { a, b } = body.into_inner();
+    let res = async {
+        let (a, score_a) = secciones_a_comparar(a).await?;
+        let (b, score_b) = secciones_a_comparar(b).await?;
+        Ok::<_, ApiError>(comparar(&a, score_a, &b, score_b))
+    }.await;
+    match res {
+        Ok(comparacion) => HttpResponse::Ok().json(comparacion),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// GET /solutions/{id}
+pub async fn get_solution_handler(path: web::Path<String>) -> impl Responder {
+    let id = path.into_inner();
+    match con_analiticas(move |conn| cargar_conjunto(conn, &id)).await {
+        Ok(conjunto) => HttpResponse::Ok().json(conjunto),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// GET /solutions/{id}/export/ics?indice=0[&fecha_inicio=..&fecha_fin=..&semanas=..&nombre=..]
+pub async fn solution_export_ics_handler(path: web::Path<String>, query: web::Query<HashMap<String, String>>) -> impl Responder {
+    let id = path.into_inner();
+    let q = query.into_inner();
+    let indice = match q.get("indice").map(|s| s.trim().parse::<usize>()) {
+        Some(Ok(i)) => i,
+        Some(Err(_)) => return ApiError::InvalidInput("invalid 'indice': expected a non-negative integer".into()).error_response(),
+        None => 0,
+    };
+    let res = con_analiticas(move |conn| solucion_guardada(conn, &id, indice)).await;
+    let secciones = match res {
+        Ok((s, _)) => s,
+        Err(e) => return e.error_response(),
+    };
+
+    let opciones = match super::export::opciones_ics(
+        q.get("fecha_inicio").map(|s| s.as_str()),
+        q.get("fecha_fin").map(|s| s.as_str()),
+        q.get("semanas").and_then(|s| s.trim().parse::<u32>().ok()),
+        q.get("nombre").cloned(),
+    ) {
+        Ok(o) => o,
+        Err(e) => return e.error_response(),
+    };
+    super::export::respuesta_ics(&secciones, &opciones)
+}
+
+/// POST /solutions/{id}/confirm
+pub async fn confirm_solution_handler(path: web::Path<String>, body: web::Json<ConfirmarSolucionRequest>) -> impl Responder {
+    let id = path.into_inner();
+    let ConfirmarSolucionRequest { email, indice } = body.into_inner();
+    let res = con_analiticas(move |conn| {
+        let conjunto = cargar_conjunto(conn, &id)?;
+        solucion_en(&conjunto, indice)?;
+        let email = email.filter(|e| !e.trim().is_empty())
+            .or(conjunto.email.clone())
+            .ok_or(ApiError::MissingParameter("email"))?;
+        vincular_solucion(conn, &email, &conjunto.id, indice as i64)
+            .map_err(|e| ApiError::Storage(format!("failed to confirm solution: {}", e)))
+    }).await;
+    match res {
+        Ok(elegida) => HttpResponse::Ok().json(elegida),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// POST /solutions/{id}/feedback
+pub async fn solution_feedback_handler(path: web::Path<String>, body: web::Json<FeedbackRequest>) -> impl Responder {
+    use crate::analithics::feedback::{configuracion_scoring, guardar_feedback, RATING_MAX, RATING_MIN};
+    let id = path.into_inner();
+    let FeedbackRequest { rating, comentario, indice, email } = body.into_inner();
+    if !(RATING_MIN..=RATING_MAX).contains(&rating) {
+        return ApiError::InvalidInput(format!("'rating' must be between {} and {}", RATING_MIN, RATING_MAX)).error_response();
+    }
+    let res = con_analiticas(move |conn| {
+        let conjunto = cargar_conjunto(conn, &id)?;
+        solucion_en(&conjunto, indice)?;
+        let email = email.filter(|e| !e.trim().is_empty()).or(conjunto.email.clone());
+        guardar_feedback(
+            conn,
+            &conjunto.id,
+            indice as i64,
+            email.as_deref(),
+            rating,
+            comentario.as_deref(),
+            &configuracion_scoring(&conjunto.params),
+        ).map_err(|e| ApiError::Storage(format!("failed to save feedback: {}", e)))
+    }).await;
+    match res {
+        Ok(feedback) => HttpResponse::Ok().json(feedback),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// GET /students/{email}/solution
+/// Última solución confirmada por el estudiante (`elegida: null` si no hay)
+pub async fn student_solution_handler(path: web::Path<String>) -> impl Responder {
+    let email = path.into_inner();
+    let respuesta_email = email.clone();
+    let res = con_analiticas(move |conn| {
+        let Some(elegida) = solucion_elegida(conn, &email)
+            .map_err(|e| ApiError::Storage(format!("failed to read chosen solution: {}", e)))? else {
+            return Ok(None);
+        };
+        let solucion = obtener_soluciones(conn, &elegida.solution_id)
+            .map_err(|e| ApiError::Storage(format!("failed to read solutions: {}", e)))?
+            .and_then(|c| c.solucion(elegida.indice as usize).cloned());
+        Ok(Some((elegida, solucion)))
+    }).await;
+    match res {
+        Ok(Some((elegida, solucion))) => HttpResponse::Ok().json(json!({"email": respuesta_email, "elegida": elegida, "solucion": solucion})),
+        Ok(None) => HttpResponse::Ok().json(json!({"email": respuesta_email, "elegida": null})),
+        Err(e) => e.error_response(),
+    }
+}
@@ -1,11 +1,30 @@
-use actix_web::{web, HttpResponse, Responder, HttpRequest};
-use serde_json::json;
+use actix_web::{web, HttpMessage, HttpResponse, Responder, HttpRequest, ResponseError};
+use crate::api_error::ApiError;
 use crate::api_json::InputParams;
-use crate::models::Seccion;
+use crate::models::{ScheduleGrid, Seccion};
+use crate::algorithm::doble_titulacion::ProgresoPrograma;
 use std::sync::OnceLock;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
 use num_cpus;
+use crate::middleware::trace::con_span_actual;
+use crate::middleware::ApiKeyId;
+use crate::algorithm::deadline::Deadline;
+use crate::algorithm::engine::{Datafiles, SchedulePlanner};
+use crate::algorithm::paginacion::paginar;
+use crate::algorithm::rapido::EstimacionCalidad;
+use crate::algorithm::balance_lineas::{balance_logrado, balance_pedido, BalanceLogrado};
+use crate::models::{BalanceLineas, RamoDisponible};
+use crate::algorithm::progreso_esperado::{progreso_esperado, ProgresoEsperado};
+use crate::algorithm::desglose::{desglosar, DesgloseScore};
+use crate::algorithm::alternativas::{alternativas, AlternativasRamo, Candidatas};
+use crate::algorithm::compromisos::{estado as estado_compromisos, EstadoCompromiso};
+use crate::algorithm::evaluaciones::{carga_evaluaciones, CargaEvaluaciones};
+use crate::algorithm::metricas::{metricas, MetricasSolucion};
+use crate::algorithm::percentil::{interpretar as interpretar_ranking, RankingInterpretado};
+use crate::algorithm::indice_ramos::RamoIndex;
+use crate::algorithm::tags::tags_de_solucion;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(serde::Deserialize)]
 struct SolveRequest {
@@ -13,16 +32,216 @@ struct SolveRequest {
 }
 
 #[derive(serde::Serialize)]
-struct SolveResponse {
+pub(crate) struct SolveResponse {
     documentos_leidos: usize,
     soluciones_count: usize,
+    /// Soluciones con `total_score >= min_score`, antes de `offset` y `top_n`
+    total_solutions: usize,
     soluciones: Vec<SolutionEntry>,
+    /// Avance por programa en doble titulación (omitido si hay una sola malla)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    programas: Vec<ProgresoPrograma>,
+    /// true si venció `timeout_ms` (o se canceló la petición): las soluciones
+    /// son las mejores encontradas hasta ese momento
+    partial: bool,
+    /// UUID del conjunto guardado (`GET /solutions/{id}`); omitido si la DB de
+    /// analíticas no está disponible
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solution_id: Option<String>,
+    /// Latencia y gap estimado frente a la cota optimista (solo `solver: "rapido"`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    calidad: Option<EstimacionCalidad>,
+    /// Percentil con que se calculó `progreso_esperado`, y el promedio del que
+    /// salió si vino `promedio` (ver `algorithm::percentil`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ranking_interpretado: Option<RankingInterpretado>,
 }
 
+pub(crate) type SolucionesYProgreso = (Vec<(Vec<(Seccion, i32)>, i64)>, Vec<ProgresoPrograma>, HashMap<String, RamoDisponible>, Vec<Seccion>);
+
+/// Ejecuta el pipeline completo devolviendo además el avance por programa,
+/// los ramos de la malla (para el progreso esperado de cada solución) y las
+/// secciones de la oferta (para las alternativas de cada ramo)
+pub(crate) fn ejecutar_con_progreso(mut params: InputParams, deadline: &Deadline) -> Result<SolucionesYProgreso, Box<dyn std::error::Error>> {
+    let datos = Datafiles.preparar(&mut params)?;
+    let soluciones = Datafiles.resolver(&datos, &params, deadline)?;
+    Ok((soluciones, datos.progreso, datos.ramos_disponibles, datos.lista_secciones))
+}
+
+#[derive(serde::Serialize)]
+pub(crate) struct SolutionEntry {
+    pub(crate) total_score: i64,
+    pub(crate) secciones: Vec<Seccion>,
+    /// Grilla semanal, solo con `?format=grid`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) grid: Option<ScheduleGrid>,
+    /// Proporción de electivos por línea frente a `filtros.balance_lineas`
+    /// (solo si el filtro está habilitado y la solución tiene electivos con línea)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) balance_lineas: Option<BalanceLogrado>,
+    /// Probabilidad de aprobar cada ramo y créditos esperados (ver `algorithm::progreso_esperado`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) progreso_esperado: Option<ProgresoEsperado>,
+    /// Por qué se recomendó: prioridad base, modificadores, filtros relajados
+    /// y dificultad de cada ramo (ver `algorithm::desglose`)
+    pub(crate) desglose: DesgloseScore,
+    /// Ventanas, días en campus, primera y última hora y compactación del
+    /// horario (ver `algorithm::metricas`)
+    pub(crate) metrics: MetricasSolucion,
+    /// Cupos restantes de las secciones cuya oferta los informa
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) cupos: Vec<CuposSeccion>,
+    /// Por ramo, las otras secciones que caben en el resto de la solución
+    /// (ver `algorithm::alternativas`)
+    pub(crate) alternativas: Vec<AlternativasRamo>,
+    /// Por compromiso pedido, si la solución lo respeta (ver `algorithm::compromisos`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) compromisos: Vec<EstadoCompromiso>,
+    /// Evaluaciones de cursos distintos el mismo día o en días seguidos, si
+    /// hay calendario de evaluaciones (ver `algorithm::evaluaciones`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) carga_evaluaciones: Option<CargaEvaluaciones>,
+    /// Notas de la malla de los ramos de la solución, por código (ver `algorithm::tags`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) tags: BTreeMap<String, Vec<String>>,
+}
+
+/// Cupos de una sección recomendada
 #[derive(serde::Serialize)]
-struct SolutionEntry {
-    total_score: i64,
-    secciones: Vec<Seccion>,
+pub(crate) struct CuposSeccion {
+    pub(crate) codigo: String,
+    pub(crate) seccion: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cupos_totales: Option<u32>,
+    pub(crate) cupos_disponibles: u32,
+}
+
+
+/// `format=grid` en la query: agrega la grilla semanal a cada solución
+fn pide_grid(query: &std::collections::HashMap<String, String>) -> bool {
+    query.get("format").map(|f| f.eq_ignore_ascii_case("grid")).unwrap_or(false)
+}
+
+/// `debug=graph` en la query: en vez de resolver, devuelve el grafo de
+/// compatibilidad que se habría enumerado (ver `algorithm::diagnostico_grafo`)
+fn pide_grafo(query: &std::collections::HashMap<String, String>) -> Result<bool, ApiError> {
+    match query.get("debug").map(|d| d.trim().to_lowercase()) {
+        None => Ok(false),
+        Some(d) if d == "graph" => Ok(true),
+        Some(d) => Err(ApiError::InvalidInput(format!("debug '{}' no soportado (valores: graph)", d))),
+    }
+}
+
+/// Respuesta de `debug=graph`: mismos datos y filtrado que el solve, sin enumerar
+async fn responder_grafo(mut params: InputParams) -> HttpResponse {
+    let permit = match permiso_solver().await {
+        Ok(p) => p,
+        Err(e) => return e.error_response(),
+    };
+    let malla = params.malla.clone();
+    let resultado = tokio::task::spawn_blocking(con_span_actual(move || {
+        let _permit = permit;
+        Datafiles.preparar(&mut params)
+            .and_then(|datos| crate::algorithm::ruta::diagnosticar_grafo(&datos, &params))
+            .map_err(|e| ApiError::de_ruta(&malla, e))
+    })).await;
+    match resultado {
+        Ok(Ok(diagnostico)) => HttpResponse::Ok().json(diagnostico),
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::Internal(format!("task join error: {}", e)).error_response(),
+    }
+}
+
+fn agregar_grids(soluciones: &mut [SolutionEntry]) {
+    for s in soluciones.iter_mut() {
+        s.grid = Some(ScheduleGrid::from_secciones(&s.secciones));
+    }
+}
+
+fn agregar_balance(soluciones: &mut [SolutionEntry], balance: Option<&BalanceLineas>) {
+    let Some(balance) = balance else { return };
+    for s in soluciones.iter_mut() {
+        s.balance_lineas = balance_logrado(&s.secciones, balance);
+    }
+}
+
+fn agregar_progreso(soluciones: &mut [SolutionEntry], ramos: &HashMap<String, RamoDisponible>, student_ranking: Option<f64>) {
+    for s in soluciones.iter_mut() {
+        s.progreso_esperado = Some(progreso_esperado(&s.secciones, ramos, student_ranking));
+    }
+}
+
+fn agregar_cupos(soluciones: &mut [SolutionEntry]) {
+    for s in soluciones.iter_mut() {
+        s.cupos = s.secciones.iter()
+            .filter_map(|sec| sec.cupos_disponibles.map(|disponibles| CuposSeccion {
+                codigo: sec.codigo.clone(),
+                seccion: sec.seccion.clone(),
+                cupos_totales: sec.cupos_totales,
+                cupos_disponibles: disponibles,
+            }))
+            .collect();
+    }
+}
+
+fn agregar_compromisos(soluciones: &mut [SolutionEntry], params: &InputParams) {
+    if params.compromisos.is_empty() {
+        return;
+    }
+    for s in soluciones.iter_mut() {
+        s.compromisos = estado_compromisos(&s.secciones, params);
+    }
+}
+
+/// Guarda el conjunto de soluciones en la DB de analíticas y devuelve su UUID
+async fn persistir_soluciones(params: &InputParams, soluciones: &[SolutionEntry]) -> Option<String> {
+    let email = params.email.clone();
+    let (params_json, soluciones_json) = (serde_json::to_value(params).ok()?, serde_json::to_value(soluciones).ok()?);
+    let res = web::block(move || {
+        let conn = crate::analithics::db::open_analytics_connection().map_err(|e| e.to_string())?;
+        crate::analithics::soluciones::guardar_soluciones(&conn, Some(&email), &params_json, &soluciones_json).map_err(|e| e.to_string())
+    }).await;
+    match res.map_err(|e| e.to_string()).and_then(|r| r) {
+        Ok(id) => Some(id),
+        Err(e) => {
+            tracing::warn!("no se pudo guardar el conjunto de soluciones: {}", e);
+            None
+        }
+    }
+}
+
+/// Convierte la salida del pipeline a entradas serializables, descartando
+/// soluciones vacías. `secciones` es la oferta de la que salen las alternativas.
+pub(crate) fn soluciones_to_entries(
+    soluciones: &[(Vec<(Seccion, i32)>, i64)],
+    params: &InputParams,
+    ramos: &HashMap<String, RamoDisponible>,
+    secciones: &[Seccion],
+) -> Vec<SolutionEntry> {
+    let candidatas = Candidatas::new(secciones);
+    let indice = RamoIndex::new(ramos);
+    let mut entries: Vec<SolutionEntry> = soluciones.iter()
+        .filter(|(sol, _)| !sol.is_empty())
+        .map(|(sol, score)| SolutionEntry {
+            total_score: *score,
+            secciones: sol.iter().map(|(sec, _)| sec.clone()).collect(),
+            grid: None,
+            balance_lineas: None,
+            progreso_esperado: None,
+            desglose: desglosar(sol, *score, params, ramos),
+            metrics: metricas(sol),
+            cupos: Vec::new(),
+            alternativas: alternativas(sol, *score, &candidatas, params, ramos),
+            compromisos: Vec::new(),
+            carga_evaluaciones: carga_evaluaciones(sol.iter().map(|(s, _)| s)),
+            tags: tags_de_solucion(sol.iter().map(|(s, _)| s), &indice),
+        })
+        .collect();
+    agregar_balance(&mut entries, balance_pedido(params));
+    agregar_progreso(&mut entries, ramos, params.student_ranking);
+    agregar_cupos(&mut entries);
+    agregar_compromisos(&mut entries, params);
+    entries
 }
 
 pub async fn solve_handler(req: HttpRequest, body: web::Json<serde_json::Value>) -> impl Responder {
@@ -30,76 +249,129 @@ pub async fn solve_handler(req: HttpRequest, body: web::Json<serde_json::Value>)
     let body_value = body.into_inner();
     let json_str = match serde_json::to_string(&body_value) {
         Ok(s) => s,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("invalid JSON body: {}", e)})),
+        Err(e) => return ApiError::InvalidBody(e.to_string()).error_response(),
     };
 
     let params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
         Ok(p) => p,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("failed to parse input: {}", e)})),
+        Err(e) => return ApiError::InvalidInput(e.to_string()).error_response(),
     };
 
+    let query = web::Query::<std::collections::HashMap<String, String>>::from_query(req.query_string())
+        .map(|q| q.into_inner())
+        .unwrap_or_default();
+    match pide_grafo(&query) {
+        Ok(true) => return responder_grafo(params).await,
+        Ok(false) => {}
+        Err(e) => return e.error_response(),
+    }
+    let con_grid = pide_grid(&query);
+
     let client_ip = req.connection_info().realip_remote_addr().unwrap_or("unknown").to_string();
-    let start = std::time::Instant::now();
+    let api_key = req.extensions().get::<ApiKeyId>().map(|k| k.0.clone());
+    responder_solve(params, con_grid, json_str, client_ip, api_key).await
+}
 
+/// Permiso del pool de CPU del solver (uno por núcleo), compartido por
+/// `/solve` y los jobs de `/solve/async`
+pub(crate) async fn permiso_solver() -> Result<tokio::sync::OwnedSemaphorePermit, ApiError> {
     static GLOBAL_SEM: OnceLock<Arc<Semaphore>> = OnceLock::new();
     let sem = GLOBAL_SEM.get_or_init(|| {
         let procs = num_cpus::get();
         Arc::new(Semaphore::new(std::cmp::max(1, procs)))
     }).clone();
+    sem.acquire_owned().await.map_err(|_| ApiError::Internal("failed to acquire semaphore".into()))
+}
 
-    let permit = match sem.clone().acquire_owned().await {
-        Ok(p) => p,
-        Err(_) => return HttpResponse::InternalServerError().json(json!({"error": "failed to acquire semaphore"})),
-    };
-
+/// Ejecuta el solver con `deadline` y arma el cuerpo de `/solve` (página
+/// pedida, grillas, conjunto guardado en analíticas)
+pub(crate) async fn resolver_respuesta(
+    params: InputParams,
+    con_grid: bool,
+    permit: tokio::sync::OwnedSemaphorePermit,
+    deadline: &Deadline,
+) -> Result<SolveResponse, ApiError> {
+    let inicio = std::time::Instant::now();
+    let malla_pedida = params.malla.clone();
+    // `ejecutar_con_progreso` consume los params; las entradas de respuesta usan esta copia
+    let params_respuesta = params.clone();
+    let deadline_block = deadline.clone();
     let params_block = params;
 
-    let blocking_handle = tokio::task::spawn_blocking(move || {
+    let blocking_handle = tokio::task::spawn_blocking(con_span_actual(move || {
         let _permit = permit;
         // USAR LA NUEVA FUNCIÓN 4-FASES CON FILTRAJE CORRECTO
-        match crate::algorithm::ruta::ejecutar_ruta_critica_with_params(params_block) {
+        match ejecutar_con_progreso(params_block, &deadline_block) {
             Ok(soluciones) => {
                 // soluciones es Vec<(Vec<(Seccion, i32)>, i64)>
                 // necesitamos extraer lista_secciones y ramos_actualizados para luego serializar
                 // Por ahora, solo retornamos soluciones
                 Ok(soluciones)
             },
-            Err(e) => Err(format!("ruta_critica failed: {}", e)),
+            Err(e) => Err(ApiError::de_ruta(&malla_pedida, e)),
         }
-    });
+    }));
 
-    let blocking_result = match blocking_handle.await {
-        Ok(res) => res,
-        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("task join error: {}", e)})),
-    };
+    let (soluciones, programas, ramos, secciones) = blocking_handle.await
+        .map_err(|e| ApiError::Internal(format!("task join error: {}", e)))??;
 
-    let soluciones = match blocking_result {
-        Ok(v) => v,
-        Err(err_msg) => return HttpResponse::InternalServerError().json(json!({"error": err_msg})),
-    };
+    // Sin soluciones por haberse cortado la búsqueda no implica que no existan
+    let partial = deadline.alcanzado();
+    if soluciones.iter().all(|(sol, _)| sol.is_empty()) && !partial {
+        return Err(ApiError::NoFeasibleSchedule);
+    }
+
+    let calidad = (params_respuesta.solver == crate::api_json::SolverMode::Rapido)
+        .then(|| crate::algorithm::rapido::estimar(&soluciones, &params_respuesta, deadline, inicio.elapsed().as_millis() as u64));
 
     // Convertir Vec<(Vec<(Seccion, i32)>, i64)> a Vec<SolutionEntry>
     // NO filtrar por available_codes porque las secciones ya fueron validadas por el algoritmo
-    // CAMBIO: Retornar TODAS las soluciones (sin límite de .take(20))
-    let mut soluciones_serial: Vec<SolutionEntry> = Vec::new();
-    for (sol_with_prefs, score) in soluciones.iter() {
-        // Extraer todas las secciones (ya validadas por el algoritmo)
-        let final_secs: Vec<Seccion> = sol_with_prefs.iter()
-            .map(|(sec, _pref)| sec.clone())
-            .collect();
-        
-        // Agregar la solución con todas sus secciones
-        if !final_secs.is_empty() {
-            soluciones_serial.push(SolutionEntry { total_score: *score, secciones: final_secs });
-        }
+    // Solo la página pedida (`top_n`, `offset`, `min_score`): todas si no se pide
+    let pagina = paginar(soluciones, &params_respuesta);
+    let mut soluciones_serial = soluciones_to_entries(&pagina.soluciones, &params_respuesta, &ramos, &secciones);
+    if con_grid {
+        agregar_grids(&mut soluciones_serial);
     }
 
+    let solution_id = persistir_soluciones(&params_respuesta, &soluciones_serial).await;
     let documentos = 2usize;
 
-    let resp = SolveResponse {
+    Ok(SolveResponse {
         documentos_leidos: documentos,
-        soluciones_count: soluciones.len(),
+        soluciones_count: soluciones_serial.len(),
+        total_solutions: pagina.total,
         soluciones: soluciones_serial,
+        programas,
+        partial,
+        solution_id,
+        calidad,
+        ranking_interpretado: interpretar_ranking(&params_respuesta),
+    })
+}
+
+/// Resuelve `params` (ya resueltos los nombres de ramos) y arma la respuesta
+/// de `/solve`; compartido por POST y GET para que ambos se comporten igual
+async fn responder_solve(
+    params: InputParams,
+    con_grid: bool,
+    json_str: String,
+    client_ip: String,
+    api_key: Option<String>,
+) -> HttpResponse {
+    let start = std::time::Instant::now();
+
+    let permit = match permiso_solver().await {
+        Ok(p) => p,
+        Err(e) => return e.error_response(),
+    };
+
+    // El límite corre desde que se obtiene el permiso; si el cliente se
+    // desconecta, la guardia cancela la búsqueda y libera el hilo bloqueante.
+    let deadline = Deadline::desde_params(&params);
+    let _cancelar = deadline.cancelar_al_soltar();
+    let resp = match resolver_respuesta(params, con_grid, permit, &deadline).await {
+        Ok(r) => r,
+        Err(e) => return e.error_response(),
     };
 
     let duration_ms = start.elapsed().as_millis() as i64;
@@ -112,13 +384,43 @@ pub async fn solve_handler(req: HttpRequest, body: web::Json<serde_json::Value>)
     let resp_clone = resp_ser.clone();
     let ip_clone = client_ip.clone();
     tokio::task::spawn_blocking(move || {
-        let _ = crate::analithics::log_query(&req_clone, &resp_clone, duration_ms, &ip_clone);
+        let _ = crate::analithics::log_query(&req_clone, &resp_clone, duration_ms, &ip_clone, api_key.as_deref());
     });
 
     HttpResponse::Ok().json(resp)
 }
 
-pub async fn solve_get_handler(query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+/// Valor JSON de un parámetro de query: JSON URL-encoded
+/// (`filtros=%7B%22dias_horarios_libres%22...`) o ese mismo JSON en base64
+/// (estándar o URL-safe, con o sin padding)
+pub fn json_de_query(nombre: &'static str, valor: &str) -> Result<serde_json::Value, ApiError> {
+    use base64::Engine;
+    use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+    let valor = valor.trim();
+    if valor.starts_with('{') || valor.starts_with('[') {
+        return serde_json::from_str(valor)
+            .map_err(|e| ApiError::InvalidInput(format!("`{}` no es un JSON válido: {}", nombre, e)));
+    }
+    [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD].iter()
+        .find_map(|engine| engine.decode(valor).ok())
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(|| ApiError::InvalidInput(format!("`{}` debe ser JSON URL-encoded o JSON en base64", nombre)))
+}
+
+/// Parámetro JSON opcional de la query deserializado a `T`
+fn campo_json<T: serde::de::DeserializeOwned>(qm: &HashMap<String, String>, nombre: &'static str) -> Result<Option<T>, ApiError> {
+    match qm.get(nombre).filter(|v| !v.trim().is_empty()) {
+        Some(v) => serde_json::from_value(json_de_query(nombre, v)?)
+            .map(Some)
+            .map_err(|e| ApiError::InvalidInput(format!("`{}` inválido: {}", nombre, e))),
+        None => Ok(None),
+    }
+}
+
+/// Arma los `InputParams` de `GET /solve`. Las listas van separadas por
+/// comas; `filtros` y `scoring` aceptan el mismo JSON que el body de
+/// `POST /solve`, URL-encoded o en base64 (ver `json_de_query`).
+pub fn input_desde_query(qm: &HashMap<String, String>) -> Result<InputParams, ApiError> {
     let split_list = |s_opt: Option<&String>| -> Vec<String> {
         match s_opt {
             Some(s) if !s.trim().is_empty() => s.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect(),
@@ -126,71 +428,113 @@ pub async fn solve_get_handler(query: web::Query<std::collections::HashMap<Strin
         }
     };
 
-    let qm = query.into_inner();
     let ramos_pasados = split_list(qm.get("ramos_pasados"));
     let ramos_prioritarios = split_list(qm.get("ramos_prioritarios"));
+    let ramos_excluidos = split_list(qm.get("ramos_excluidos"));
+    let ramos_obligatorios = split_list(qm.get("ramos_obligatorios"));
+    let ramos_reprobados = split_list(qm.get("ramos_reprobados"));
     let horarios_preferidos = split_list(qm.get("horarios_preferidos"));
+    let horarios_prohibidos = split_list(qm.get("horarios_prohibidos"));
+    let optimizations = split_list(qm.get("optimizations"));
+    let mallas_adicionales = split_list(qm.get("mallas_adicionales"));
+    let secciones_fijas = split_list(qm.get("secciones_fijas"));
+    let ranking = Some(split_list(qm.get("ranking"))).filter(|r| !r.is_empty());
+    let carrera = qm.get("carrera").map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let sheet = qm.get("sheet").map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    // Con `carrera` la malla puede omitirse (se usa la de la carrera)
     let malla = match qm.get("malla").and_then(|s| if s.trim().is_empty() { None } else { Some(s.clone()) }) {
         Some(m) => m,
-        None => return HttpResponse::BadRequest().json(json!({"error": "malla is required in query"})),
+        None if carrera.is_some() => String::new(),
+        None => return Err(ApiError::MissingParameter("malla")),
     };
 
     let email = qm.get("email").cloned().unwrap_or_else(|| "".to_string());
+    let solver = match qm.get("solver").or_else(|| qm.get("modo")).map(|s| s.trim().to_lowercase()) {
+        Some(s) if s == "exact" => crate::api_json::SolverMode::Exact,
+        Some(s) if s == "rapido" || s == "fast" => crate::api_json::SolverMode::Rapido,
+        _ => crate::api_json::SolverMode::Greedy,
+    };
+    let anio = qm.get("anio").and_then(|s| s.trim().parse::<i32>().ok());
+    let solver_time_budget_ms = qm.get("solver_time_budget_ms").and_then(|s| s.trim().parse::<u64>().ok());
+    let max_creditos = qm.get("max_creditos").and_then(|s| s.trim().parse::<i32>().ok());
+    let diversity_seed = qm.get("diversity_seed").and_then(|s| s.trim().parse::<u64>().ok());
+    let diversity_min_diff = qm.get("diversity_min_diff").and_then(|s| s.trim().parse::<usize>().ok());
+    let timeout_ms = qm.get("timeout_ms").and_then(|s| s.trim().parse::<u64>().ok());
+    let student_ranking = qm.get("student_ranking").and_then(|s| s.trim().parse::<f64>().ok());
+    let promedio = qm.get("promedio").and_then(|s| s.trim().replace(',', ".").parse::<f64>().ok());
+    let top_n = qm.get("top_n").and_then(|s| s.trim().parse::<usize>().ok());
+    let offset = qm.get("offset").and_then(|s| s.trim().parse::<usize>().ok()).unwrap_or(0);
+    let min_score = qm.get("min_score").and_then(|s| s.trim().parse::<i64>().ok());
+    let objetivo = match qm.get("objetivo").map(|s| s.trim().to_lowercase()) {
+        Some(o) if o == "expected_progress" => crate::api_json::Objetivo::ExpectedProgress,
+        _ => crate::api_json::Objetivo::Score,
+    };
+    let periodo = match qm.get("periodo").map(|s| s.trim().to_lowercase()) {
+        Some(p) if p == "verano" || p == "summer" => crate::api_json::Periodo::Verano,
+        _ => crate::api_json::Periodo::Regular,
+    };
 
-        let input = InputParams {
+    Ok(InputParams {
         email,
         ramos_pasados,
         ramos_prioritarios,
+        ramos_excluidos,
+        ramos_obligatorios,
+        ramos_reprobados,
         horarios_preferidos,
-        horarios_prohibidos: Vec::new(),
+        horarios_prohibidos,
+        compromisos: campo_json(qm, "compromisos")?.unwrap_or_default(),
         malla,
-        sheet: None,
-        ranking: None,
-        student_ranking: None,
-        anio: None,
-        filtros: None,
-        optimizations: Vec::new(),
+        sheet,
+        ranking,
+        student_ranking,
+        promedio,
+        anio,
+        filtros: campo_json(qm, "filtros")?,
+        optimizations,
+        mallas_adicionales,
+        solver,
+        solver_time_budget_ms,
+        max_creditos,
+        diversity_seed,
+        diversity_min_diff,
+        timeout_ms,
+        carrera,
+        objetivo,
+        objetivos: split_list(qm.get("objetivos")),
+        scoring: campo_json(qm, "scoring")?,
+        usar_historial: qm.get("usar_historial").is_some_and(|s| matches!(s.trim(), "true" | "1")),
+        secciones_fijas,
+        periodo,
+        top_n,
+        offset,
+        min_score,
+    })
+}
+
+pub async fn solve_get_handler(req: HttpRequest, query: web::Query<std::collections::HashMap<String, String>>) -> impl Responder {
+    let qm = query.into_inner();
+    let input = match input_desde_query(&qm) {
+        Ok(i) => i,
+        Err(e) => return e.error_response(),
     };
 
     let json_str = match serde_json::to_string(&input) {
         Ok(s) => s,
-        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("failed to serialize input: {}", e)})),
+        Err(e) => return ApiError::Internal(format!("failed to serialize input: {}", e)).error_response(),
     };
 
     let params = match crate::api_json::parse_and_resolve_ramos(&json_str, Some(".")) {
         Ok(p) => p,
-        Err(e) => return HttpResponse::BadRequest().json(json!({"error": format!("failed to resolve names: {}", e)})),
-    };
-
-    // USAR LA NUEVA FUNCIÓN 4-FASES CON FILTRAJE CORRECTO
-    let soluciones = match crate::algorithm::ruta::ejecutar_ruta_critica_with_params(params) {
-        Ok(sols) => sols,
-        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": format!("ruta_critica failed: {}", e)})),
+        Err(e) => return ApiError::InvalidInput(format!("failed to resolve names: {}", e)).error_response(),
     };
-
-    // Convertir Vec<(Vec<(Seccion, i32)>, i64)> a Vec<SolutionEntry>
-    // NO filtrar por available_codes porque las secciones ya fueron validadas por el algoritmo
-    // CAMBIO: Retornar TODAS las soluciones (sin límite de .take(20))
-    let mut soluciones_serial: Vec<SolutionEntry> = Vec::new();
-    for (sol_with_prefs, score) in soluciones.iter() {
-        // Extraer todas las secciones (ya validadas por el algoritmo)
-        let final_secs: Vec<Seccion> = sol_with_prefs.iter()
-            .map(|(sec, _pref)| sec.clone())
-            .collect();
-        
-        // Agregar la solución con todas sus secciones
-        if !final_secs.is_empty() {
-            soluciones_serial.push(SolutionEntry { total_score: *score, secciones: final_secs });
-        }
+    match pide_grafo(&qm) {
+        Ok(true) => return responder_grafo(params).await,
+        Ok(false) => {}
+        Err(e) => return e.error_response(),
     }
 
-    let documentos = 2usize;
-
-    let resp = SolveResponse {
-        documentos_leidos: documentos,
-        soluciones_count: soluciones.len(),
-        soluciones: soluciones_serial,
-    };
-
-    HttpResponse::Ok().json(resp)
+    let client_ip = req.connection_info().realip_remote_addr().unwrap_or("unknown").to_string();
+    let api_key = req.extensions().get::<ApiKeyId>().map(|k| k.0.clone());
+    responder_solve(params, pide_grid(&qm), json_str, client_ip, api_key).await
 }
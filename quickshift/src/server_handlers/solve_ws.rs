@@ -0,0 +1,332 @@
+//! Armado interactivo de horario por WebSocket (`GET /ws/solve`).
+//!
+//! El cliente abre el socket y manda comandos JSON (`{"cmd": ...}`); tras
+//! cada uno recibe las mejores soluciones recalculadas. Como en
+//! `/solve/session`, los datos de las fases 0-2b y el grafo de
+//! compatibilidad quedan en el estado de la conexión: cambiar filtros o
+//! fijar secciones solo vuelve a correr el clique, y agregar un ramo
+//! aprobado rehace la preparación con los Excel del caché de datafiles,
+//! reutilizando el grafo (que depende solo de la oferta). Si un comando
+//! falla el estado no cambia y se responde `{"tipo": "error", ...}`.
+
+use actix::{Actor, ActorContext, ActorFutureExt, AsyncContext, StreamHandler};
+use actix_web::{web, HttpRequest, Responder, ResponseError};
+use actix_web_actors::ws;
+use crate::algorithm::clique::CompatibilityGraph;
+use crate::algorithm::deadline::Deadline;
+use crate::algorithm::engine::{Datafiles, SchedulePlanner};
+use crate::algorithm::ruta::DatosRuta;
+use crate::api_error::ApiError;
+use crate::api_json::InputParams;
+use crate::middleware::trace::con_span_actual;
+use crate::server_handlers::solve::soluciones_to_entries;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Soluciones enviadas por mensaje si `start` no indica `max_soluciones`
+const MAX_SOLUCIONES_DEFECTO: usize = 5;
+/// Cada cuánto se envía un ping al cliente
+const INTERVALO_LATIDO: Duration = Duration::from_secs(10);
+/// Sin pong ni mensajes del cliente por este tiempo se cierra la conexión
+const TIMEOUT_CLIENTE: Duration = Duration::from_secs(60);
+
+/// Filtros que acepta `toggle-filter` (campos de `UserFilters`)
+pub const FILTROS: &[&str] = &[
+    "dias_horarios_libres",
+    "ventana_entre_actividades",
+    "preferencias_profesores",
+    "balance_lineas",
+    "tiempo_traslado",
+    "solo_con_cupos",
+];
+
+/// Mensajes del cliente
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "cmd", rename_all = "kebab-case")]
+pub enum ComandoWs {
+    /// Primer comando: `params` es el mismo body de `POST /solve`
+    Start {
+        params: Value,
+        #[serde(default)]
+        max_soluciones: Option<usize>,
+    },
+    /// Agrega un ramo aprobado (código o nombre)
+    AddPassedCourse { ramo: String },
+    RemovePassedCourse { ramo: String },
+    /// Activa/desactiva un filtro de `FILTROS`. Sin `habilitado` lo invierte;
+    /// `config` reemplaza su configuración (y lo activa si no se indica otra cosa)
+    ToggleFilter {
+        filtro: String,
+        #[serde(default)]
+        habilitado: Option<bool>,
+        #[serde(default)]
+        config: Option<Value>,
+    },
+    /// Fija una sección por `codigo_box` (ver `InputParams::secciones_fijas`)
+    PinSection { seccion: String },
+    UnpinSection { seccion: String },
+}
+
+impl ComandoWs {
+    /// Nombre del comando, como viene en `cmd`
+    pub fn nombre(&self) -> &'static str {
+        match self {
+            ComandoWs::Start { .. } => "start",
+            ComandoWs::AddPassedCourse { .. } => "add-passed-course",
+            ComandoWs::RemovePassedCourse { .. } => "remove-passed-course",
+            ComandoWs::ToggleFilter { .. } => "toggle-filter",
+            ComandoWs::PinSection { .. } => "pin-section",
+            ComandoWs::UnpinSection { .. } => "unpin-section",
+        }
+    }
+}
+
+/// Estado de una conexión: parámetros actuales y datos ya preparados
+#[derive(Clone)]
+pub struct EstadoSolveWs {
+    pub params: InputParams,
+    pub datos: Arc<DatosRuta>,
+    pub grafo: Arc<CompatibilityGraph>,
+    pub max_soluciones: usize,
+}
+
+fn mismo_id(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// Resuelve nombres de ramos a códigos como `parse_and_resolve_ramos`
+fn resolver_ramos(params: InputParams) -> Result<InputParams, ApiError> {
+    crate::api_json::resolve_ramos_with_resolver(params, Some("."), |p, nombre| crate::excel::asignatura_from_nombre(p, nombre))
+        .map_err(|e| ApiError::InvalidInput(e.to_string()))
+}
+
+/// Prepara los datos de `params` (Excel vía caché); aplica equivalencias a `ramos_pasados`
+fn preparar(params: &mut InputParams) -> Result<DatosRuta, ApiError> {
+    Datafiles.preparar(params)
+        .map_err(|e| ApiError::del_pipeline(&params.malla, format!("ruta_critica failed: {}", e)))
+}
+
+/// `habilitado` del filtro en `filtros` (JSON de `UserFilters`)
+fn filtro_habilitado(filtros: &Value, filtro: &str) -> bool {
+    match &filtros[filtro] {
+        Value::Bool(b) => *b,
+        v => v["habilitado"].as_bool().unwrap_or(false),
+    }
+}
+
+/// Aplica `toggle-filter` sobre los filtros de `params`
+fn alternar_filtro(params: &mut InputParams, filtro: &str, habilitado: Option<bool>, config: Option<Value>) -> Result<(), ApiError> {
+    if !FILTROS.contains(&filtro) {
+        return Err(ApiError::InvalidInput(format!("filtro '{}' desconocido (usar {})", filtro, FILTROS.join(", "))));
+    }
+    let mut filtros = serde_json::to_value(params.filtros.clone().unwrap_or_default())
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let activo = habilitado.unwrap_or(if config.is_some() { true } else { !filtro_habilitado(&filtros, filtro) });
+
+    if filtro == "solo_con_cupos" {
+        filtros[filtro] = json!(activo);
+    } else {
+        let mut valor = match config {
+            Some(c) if c.is_object() => c,
+            Some(_) => return Err(ApiError::InvalidInput(format!("config de '{}' debe ser un objeto", filtro))),
+            None if filtros[filtro].is_object() => filtros[filtro].take(),
+            None => json!({}),
+        };
+        valor["habilitado"] = json!(activo);
+        filtros[filtro] = valor;
+    }
+    params.filtros = Some(serde_json::from_value(filtros)
+        .map_err(|e| ApiError::InvalidInput(format!("config de '{}' inválida: {}", filtro, e)))?);
+    crate::api_json::normalizar_horarios(params);
+    Ok(())
+}
+
+/// Aplica `comando` sobre `estado` (None antes de `start`) sin resolver
+pub fn aplicar_comando(estado: Option<EstadoSolveWs>, comando: ComandoWs) -> Result<EstadoSolveWs, ApiError> {
+    let (mut e, comando) = match (estado, comando) {
+        (_, ComandoWs::Start { params, max_soluciones }) => {
+            let json_str = serde_json::to_string(&params).map_err(|e| ApiError::InvalidBody(e.to_string()))?;
+            let mut params = crate::api_json::parse_and_resolve_ramos(&json_str, Some("."))
+                .map_err(|e| ApiError::InvalidInput(e.to_string()))?;
+            let datos = preparar(&mut params)?;
+            let grafo = datos.build_compatibility_graph();
+            return Ok(EstadoSolveWs {
+                params,
+                datos: Arc::new(datos),
+                grafo: Arc::new(grafo),
+                max_soluciones: max_soluciones.filter(|n| *n > 0).unwrap_or(MAX_SOLUCIONES_DEFECTO),
+            });
+        }
+        (None, _) => return Err(ApiError::InvalidInput("la sesión no ha comenzado: el primer comando debe ser 'start'".into())),
+        (Some(estado), comando) => (estado, comando),
+    };
+
+    match comando {
+        ComandoWs::Start { .. } => unreachable!(),
+        ComandoWs::AddPassedCourse { ramo } | ComandoWs::RemovePassedCourse { ramo } if ramo.trim().is_empty() => {
+            return Err(ApiError::MissingParameter("ramo"));
+        }
+        ComandoWs::AddPassedCourse { ramo } => {
+            let mut params = e.params.clone();
+            params.ramos_pasados.push(ramo.trim().to_string());
+            let mut params = resolver_ramos(params)?;
+            let mut vistos = std::collections::HashSet::new();
+            params.ramos_pasados.retain(|r| vistos.insert(r.trim().to_uppercase()));
+            // El grafo se conserva: se arma sobre toda la oferta, no sobre los ramos viables
+            e.datos = Arc::new(preparar(&mut params)?);
+            e.params = params;
+        }
+        ComandoWs::RemovePassedCourse { ramo } => {
+            let mut params = e.params.clone();
+            let quitar = resolver_ramos(InputParams { ramos_pasados: vec![ramo.trim().to_string()], ..params.clone() })?
+                .ramos_pasados.remove(0);
+            let antes = params.ramos_pasados.len();
+            params.ramos_pasados.retain(|r| !mismo_id(r, &quitar) && !mismo_id(r, &ramo));
+            if params.ramos_pasados.len() == antes {
+                return Err(ApiError::InvalidInput(format!("'{}' no está entre los ramos aprobados", ramo)));
+            }
+            e.datos = Arc::new(preparar(&mut params)?);
+            e.params = params;
+        }
+        ComandoWs::ToggleFilter { filtro, habilitado, config } => {
+            alternar_filtro(&mut e.params, filtro.trim(), habilitado, config)?;
+        }
+        ComandoWs::PinSection { seccion } => {
+            if seccion.trim().is_empty() {
+                return Err(ApiError::MissingParameter("seccion"));
+            }
+            if !e.params.secciones_fijas.iter().any(|f| mismo_id(f, &seccion)) {
+                e.params.secciones_fijas.push(seccion.trim().to_string());
+            }
+        }
+        ComandoWs::UnpinSection { seccion } => {
+            e.params.secciones_fijas.retain(|f| !mismo_id(f, &seccion));
+        }
+    }
+    Ok(e)
+}
+
+/// Resuelve con el estado actual y arma el mensaje de respuesta
+pub fn resolver_estado(estado: &EstadoSolveWs, cmd: &str) -> Result<Value, ApiError> {
+    let start = Instant::now();
+    let deadline = Deadline::desde_params(&estado.params);
+    let _cancelar = deadline.cancelar_al_soltar();
+    let mut soluciones = Datafiles.resolver_con_grafo(&estado.datos, &estado.params, Some(&estado.grafo), &deadline)
+        .map_err(|e| ApiError::de_ruta(&estado.params.malla, e))?;
+    let total = soluciones.len();
+    soluciones.truncate(estado.max_soluciones);
+    Ok(json!({
+        "tipo": "soluciones",
+        "cmd": cmd,
+        "compute_ms": start.elapsed().as_millis() as u64,
+        "soluciones_count": total,
+        "soluciones": soluciones_to_entries(&soluciones, &estado.params, &estado.datos.ramos_disponibles, &estado.datos.lista_secciones),
+        "partial": deadline.alcanzado(),
+        "estado": {
+            "ramos_pasados": estado.params.ramos_pasados,
+            "secciones_fijas": estado.params.secciones_fijas,
+            "filtros": estado.params.filtros,
+        },
+    }))
+}
+
+/// Aplica el comando y resuelve; si algo falla el estado anterior se mantiene
+pub fn procesar(estado: Option<EstadoSolveWs>, comando: ComandoWs) -> Result<(EstadoSolveWs, Value), ApiError> {
+    let cmd = comando.nombre();
+    let nuevo = aplicar_comando(estado, comando)?;
+    let cuerpo = resolver_estado(&nuevo, cmd)?;
+    Ok((nuevo, cuerpo))
+}
+
+/// Mensaje de error: `{"tipo": "error", "cmd", "code", "message", "details"}`
+pub fn mensaje_error(cmd: Option<&str>, error: &ApiError) -> Value {
+    let mut cuerpo = error.to_json();
+    cuerpo["tipo"] = json!("error");
+    cuerpo["cmd"] = json!(cmd);
+    cuerpo
+}
+
+/// Conexión de `GET /ws/solve`
+struct SolveWs {
+    estado: Option<EstadoSolveWs>,
+    latido: Instant,
+}
+
+impl SolveWs {
+    fn latir(&self, ctx: &mut ws::WebsocketContext<Self>) {
+        ctx.run_interval(INTERVALO_LATIDO, |act, ctx| {
+            if act.latido.elapsed() > TIMEOUT_CLIENTE {
+                tracing::info!("🔌 [ws/solve] cliente sin responder, cerrando");
+                ctx.stop();
+                return;
+            }
+            ctx.ping(b"");
+        });
+    }
+
+    fn comando(&mut self, texto: &str, ctx: &mut ws::WebsocketContext<Self>) {
+        let comando: ComandoWs = match serde_json::from_str(texto) {
+            Ok(c) => c,
+            Err(e) => {
+                ctx.text(mensaje_error(None, &ApiError::InvalidBody(e.to_string())).to_string());
+                return;
+            }
+        };
+        let cmd = comando.nombre();
+        let estado = self.estado.clone();
+        let tarea = web::block(con_span_actual(move || procesar(estado, comando)));
+        // `wait`: los comandos se procesan en orden, uno a la vez
+        ctx.wait(actix::fut::wrap_future::<_, Self>(tarea).map(move |res, act, ctx| {
+            act.latido = Instant::now();
+            match res {
+                Ok(Ok((estado, cuerpo))) => {
+                    act.estado = Some(estado);
+                    ctx.text(cuerpo.to_string());
+                }
+                Ok(Err(e)) => ctx.text(mensaje_error(Some(cmd), &e).to_string()),
+                Err(e) => ctx.text(mensaje_error(Some(cmd), &ApiError::Internal(format!("task join error: {}", e))).to_string()),
+            }
+        }));
+    }
+}
+
+impl Actor for SolveWs {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.latir(ctx);
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for SolveWs {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        self.latido = Instant::now();
+        match msg {
+            Ok(ws::Message::Text(texto)) => self.comando(&texto, ctx),
+            Ok(ws::Message::Ping(b)) => ctx.pong(&b),
+            Ok(ws::Message::Pong(_)) => {}
+            Ok(ws::Message::Binary(_)) => {
+                ctx.text(mensaje_error(None, &ApiError::InvalidBody("se esperan comandos JSON como texto".into())).to_string());
+            }
+            Ok(ws::Message::Close(motivo)) => {
+                ctx.close(motivo);
+                ctx.stop();
+            }
+            Ok(ws::Message::Continuation(_)) | Ok(ws::Message::Nop) => {}
+            Err(e) => {
+                tracing::warn!("🔌 [ws/solve] error de protocolo: {}", e);
+                ctx.stop();
+            }
+        }
+    }
+}
+
+/// GET /ws/solve
+/// Abre la conexión; el primer mensaje debe ser `{"cmd": "start", "params": {...}}`
+pub async fn solve_ws_handler(req: HttpRequest, stream: web::Payload) -> impl Responder {
+    match ws::start(SolveWs { estado: None, latido: Instant::now() }, &req, stream) {
+        Ok(resp) => resp,
+        Err(e) => ApiError::InvalidInput(format!("websocket handshake failed: {}", e)).error_response(),
+    }
+}
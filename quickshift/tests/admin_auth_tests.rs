@@ -0,0 +1,38 @@
+#![cfg(feature = "server")]
+
+use actix_web::{test as atest, web, App};
+use quickshift::server_handlers::{admin_rollover_handler, config_datafiles_get_handler};
+
+#[actix_web::test]
+async fn test_admin_sin_token_configurado_responde_401() {
+    // Un solo test en el archivo: cambia variables de entorno del proceso
+    unsafe {
+        std::env::remove_var("ADMIN_TOKEN");
+    }
+    let app = atest::init_service(App::new()
+        .route("/admin/rollover", web::post().to(admin_rollover_handler))
+        .route("/config/datafiles", web::get().to(config_datafiles_get_handler))).await;
+    let rollover = |token: Option<&str>| {
+        let mut req = atest::TestRequest::post().uri("/admin/rollover")
+            .set_json(serde_json::json!({"periodo": "20252", "dry_run": true}));
+        if let Some(t) = token {
+            req = req.insert_header(("X-Admin-Token", t));
+        }
+        req.to_request()
+    };
+
+    // Sin ADMIN_TOKEN no pasa nadie, traiga o no el header
+    assert_eq!(atest::call_service(&app, rollover(None)).await.status(), 401);
+    assert_eq!(atest::call_service(&app, rollover(Some(""))).await.status(), 401);
+    assert_eq!(atest::call_service(&app, rollover(Some("cualquiera"))).await.status(), 401);
+    let req = atest::TestRequest::get().uri("/config/datafiles").to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 401);
+
+    unsafe {
+        std::env::set_var("ADMIN_TOKEN", "secreto");
+    }
+    assert_eq!(atest::call_service(&app, rollover(Some("secret"))).await.status(), 401);
+    assert_eq!(atest::call_service(&app, rollover(Some("secreto!"))).await.status(), 401);
+    let req = atest::TestRequest::get().uri("/config/datafiles").insert_header(("X-Admin-Token", "secreto")).to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 200);
+}
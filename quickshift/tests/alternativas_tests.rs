@@ -0,0 +1,67 @@
+use quickshift::algorithm::alternativas::{alternativas, Candidatas};
+use quickshift::algorithm::clique::modificadores_score;
+use quickshift::algorithm::prioridad::prioridad_de;
+use quickshift::excel::componentes::asignar_bundles;
+use quickshift::models::{RamoDisponible, Seccion};
+use quickshift::scoring::ScoringConfig;
+use std::collections::HashMap;
+
+mod common;
+use common::{params, seccion_con};
+
+fn seccion(codigo: &str, n: &str, componente: &str, horario: &str, disponibles: Option<u32>) -> Seccion {
+    seccion_con(codigo, n, &[horario], serde_json::json!({
+        "profesor": format!("Profe {}", n),
+        "codigo_box": format!("{}-{}-{}", codigo, componente, n),
+        "componente": componente,
+        "cupos_disponibles": disponibles,
+    }))
+}
+
+#[test]
+fn test_alternativas_por_ramo() {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "CBF1000", "codigo": "CBF1000", "semestre": 1}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    let mut oferta = vec![
+        seccion("CBM1000", "1", "catedra", "LU 08:30 - 09:50", None),
+        seccion("CBM1000", "2", "catedra", "MA 08:30 - 09:50", None),
+        seccion("CBM1000", "3", "catedra", "MI 08:30 - 09:50", Some(0)),
+        // choca con el laboratorio del paquete elegido
+        seccion("CBM1000", "4", "catedra", "JU 10:00 - 11:20", None),
+        seccion("CBF1000", "1", "catedra", "LU 10:00 - 11:20", None),
+        seccion("CBF1000", "1", "lab", "JU 10:00 - 11:20", None),
+        seccion("CBF1000", "2", "catedra", "MA 10:00 - 11:20", None),
+        seccion("CBF1000", "2", "lab", "VI 10:00 - 11:20", None),
+    ];
+    asignar_bundles(&mut oferta);
+
+    let p = params("MallaAlternativas", serde_json::json!({}));
+    let scoring = ScoringConfig::default();
+    let score = |s: &Seccion| prioridad_de(s, &ramos).score(&scoring);
+    let solucion: Vec<(Seccion, i32)> = [0, 4, 5].iter().map(|&i| (oferta[i].clone(), score(&oferta[i]) as i32)).collect();
+    let total = score(&oferta[0]) + score(&oferta[4]) + modificadores_score(&solucion, &p, &scoring).total();
+
+    let candidatas = Candidatas::new(&oferta);
+    let por_ramo = alternativas(&solucion, total, &candidatas, &p, &ramos);
+    assert_eq!(por_ramo.len(), 2);
+    assert_eq!((por_ramo[0].codigo.as_str(), por_ramo[0].seccion.as_str()), ("CBM1000", "1"));
+    let cbm: Vec<(&str, i64)> = por_ramo[0].alternativas.iter().map(|a| (a.seccion.as_str(), a.diferencia)).collect();
+    // la sección 3 no tiene cupos y queda última; la 4 choca con el laboratorio
+    assert_eq!(cbm, vec![("2", 1), ("3", 2 - 1_000_000)]);
+    assert_eq!(por_ramo[0].alternativas[0].total_score, total + 1);
+    assert_eq!(por_ramo[0].alternativas[0].profesor, "Profe 2");
+
+    // el paquete se reemplaza completo
+    let cbf = &por_ramo[1].alternativas;
+    assert_eq!(cbf.len(), 1);
+    assert_eq!(cbf[0].seccion, "2");
+    assert_eq!(cbf[0].horario, vec!["MA 10:00 - 11:20", "VI 10:00 - 11:20"]);
+
+    // con `solo_con_cupos` las secciones llenas no son alternativa
+    let p = params("MallaAlternativas", serde_json::json!({"filtros": {"solo_con_cupos": true}}));
+    let por_ramo = alternativas(&solucion, total, &candidatas, &p, &ramos);
+    assert_eq!(por_ramo[0].alternativas.iter().map(|a| a.seccion.as_str()).collect::<Vec<_>>(), vec!["2"]);
+}
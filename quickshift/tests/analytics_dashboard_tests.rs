@@ -0,0 +1,50 @@
+#![cfg(feature = "server")]
+
+use quickshift::analithics::queries::{dashboard_con, rango_dashboard, Bucket};
+use serde_json::json;
+
+fn conn() -> rusqlite::Connection {
+    let c = rusqlite::Connection::open_in_memory().unwrap();
+    c.execute_batch(
+        "CREATE TABLE queries (id INTEGER PRIMARY KEY AUTOINCREMENT, ts TEXT NOT NULL, duration_ms INTEGER, email TEXT, malla TEXT,
+            student_ranking REAL, ramos_pasados TEXT, ramos_prioritarios TEXT, filtros_json TEXT, request_json TEXT, response_json TEXT, client_ip TEXT);
+         INSERT INTO queries (ts, duration_ms, ramos_prioritarios, filtros_json, response_json) VALUES
+            ('2025-03-03T10:00:00.123456+00:00', 100, '[\"CIT3313\",\"CBM1000\"]', '{\"dias_horarios_libres\":{\"habilitado\":true},\"balance_lineas\":{\"habilitado\":false}}', '{\"soluciones_count\":3}'),
+            ('2025-03-03T18:00:00+00:00', 300, '[\"CIT3313\"]', '{\"dias_horarios_libres\":{\"habilitado\":true}}', '{\"soluciones_count\":12}'),
+            ('2025-03-09T09:00:00+00:00', 200, NULL, NULL, 'no es json'),
+            ('2025-03-10T09:00:00+00:00', 400, '[\"CBM1000\"]', NULL, '{\"soluciones_count\":0}'),
+            ('2025-04-01T09:00:00+00:00', 999, '[\"FUERA\"]', NULL, NULL);",
+    ).unwrap();
+    c
+}
+
+#[test]
+fn test_dashboard_por_dia_y_semana() {
+    let conn = conn();
+    let (desde, hasta) = rango_dashboard(Some("2025-03-01"), Some("2025-03-31")).unwrap();
+    assert_eq!(hasta, "2025-04-01T00:00:00+00:00");
+
+    let d = dashboard_con(&conn, &desde, &hasta, Bucket::Day).unwrap();
+    assert_eq!(d["total_queries"], 4);
+    assert_eq!(d["queries_per_bucket"][0], json!({"bucket": "2025-03-03", "queries": 2, "avg_duration_ms": 200.0}));
+    assert_eq!(d["queries_per_bucket"].as_array().unwrap().len(), 3);
+    assert_eq!(d["top_ramos"], json!([{"ramo": "CBM1000", "count": 2}, {"ramo": "CIT3313", "count": 2}]));
+    assert_eq!(d["top_filtros"], json!([{"filter": "dias_horarios_libres", "count": 2}]));
+    assert_eq!(d["latency_ms"], json!({"avg": 250.0, "max": 400}));
+    assert_eq!(d["soluciones_count_distribution"], json!([
+        {"range": "desconocido", "count": 1},
+        {"range": "0", "count": 1},
+        {"range": "1-5", "count": 1},
+        {"range": "11-20", "count": 1},
+    ]));
+
+    // El domingo 9 cae en la semana del lunes 3; el lunes 10 abre otra
+    let w = dashboard_con(&conn, &desde, &hasta, Bucket::Week).unwrap();
+    let semanas: Vec<(String, i64)> = w["queries_per_bucket"].as_array().unwrap().iter()
+        .map(|b| (b["bucket"].as_str().unwrap().to_string(), b["queries"].as_i64().unwrap()))
+        .collect();
+    assert_eq!(semanas, vec![("2025-03-03".to_string(), 3), ("2025-03-10".to_string(), 1)]);
+
+    assert!(rango_dashboard(Some("ayer"), None).is_err());
+    assert_eq!(Bucket::parse("WEEK"), Some(Bucket::Week));
+}
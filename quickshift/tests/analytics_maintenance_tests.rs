@@ -0,0 +1,63 @@
+#![cfg(feature = "server")]
+
+use quickshift::analithics::db::*;
+
+fn conn() -> AnalyticsConn {
+    let c = rusqlite::Connection::open_in_memory().unwrap();
+    c.execute_batch(
+        "CREATE TABLE queries (id INTEGER PRIMARY KEY AUTOINCREMENT, ts TEXT NOT NULL, duration_ms INTEGER, email TEXT, malla TEXT,
+            student_ranking REAL, ramos_pasados TEXT, ramos_prioritarios TEXT, filtros_json TEXT, request_json TEXT, response_json TEXT, client_ip TEXT, api_key TEXT);
+         CREATE TABLE reports (id INTEGER PRIMARY KEY AUTOINCREMENT, ts TEXT NOT NULL, query_type TEXT NOT NULL, params_json TEXT, result_json TEXT);
+         CREATE TABLE cache_stats (id INTEGER PRIMARY KEY AUTOINCREMENT, ts TEXT NOT NULL, hits INTEGER, misses INTEGER, entries INTEGER);
+         INSERT INTO queries (ts, duration_ms, email, malla, request_json, api_key) VALUES
+            ('2025-01-10T12:00:00+00:00', 120, 'a@x.cl', 'MC2020', '{\"malla\":\"MC2020\"}', 'frontend'),
+            ('2025-03-02T08:00:00+00:00', 80, 'b@x.cl', 'MC2020', 'linea con, coma', NULL),
+            ('2025-04-01T08:00:00+00:00', NULL, NULL, NULL, NULL, NULL);
+         INSERT INTO reports (ts, query_type) VALUES ('2025-01-11T00:00:00+00:00', 'count_users');
+         INSERT INTO cache_stats (ts, hits, misses, entries) VALUES ('2025-05-01T00:00:00+00:00', 1, 2, 3);",
+    ).unwrap();
+    AnalyticsConn::Sqlite(c)
+}
+
+#[test]
+fn test_export_csv_y_jsonl() {
+    let conn = conn();
+    let filas = fetch_queries(&conn).unwrap();
+    assert_eq!(filas.len(), 3);
+    assert_eq!(filas[0].duration_ms, Some(120));
+    assert_eq!(filas[2].email, None);
+    assert_eq!(filas[0].api_key.as_deref(), Some("frontend"));
+
+    let csv = exportar_queries(&filas, FormatoExport::Csv);
+    let lineas: Vec<&str> = csv.lines().collect();
+    assert_eq!(lineas.len(), 4);
+    assert!(lineas[0].starts_with("id,ts,duration_ms,email,malla"));
+    assert!(lineas[1].contains(r#""{""malla"":""MC2020""}""#), "{}", lineas[1]);
+    assert!(lineas[0].ends_with(",client_ip,api_key"));
+    assert!(lineas[1].ends_with(",frontend"));
+    assert!(lineas[2].contains("\"linea con, coma\""));
+
+    let jsonl = exportar_queries(&filas, FormatoExport::Jsonl);
+    let primera: serde_json::Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+    assert_eq!(primera["email"], "a@x.cl");
+    assert_eq!(jsonl.lines().count(), 3);
+
+    assert_eq!(FormatoExport::parse("CSV"), Some(FormatoExport::Csv));
+    assert_eq!(FormatoExport::parse("xml"), None);
+}
+
+#[test]
+fn test_borrar_antes_de_y_retencion() {
+    assert_eq!(parse_fecha_corte("2025-03-01").as_deref(), Some("2025-03-01T00:00:00+00:00"));
+    assert_eq!(parse_fecha_corte("2025-03-01T03:00:00-03:00").as_deref(), Some("2025-03-01T06:00:00+00:00"));
+    assert!(parse_fecha_corte("01/03/2025").is_none());
+
+    let conn = conn();
+    let borrados = borrar_antes_de(&conn, &parse_fecha_corte("2025-03-01").unwrap()).unwrap();
+    assert_eq!(borrados, Borrados { queries: 1, reports: 1, cache_stats: 0 });
+    assert_eq!(fetch_queries(&conn).unwrap().len(), 2);
+
+    // Con una retención de 1 día todo lo de 2025 queda fuera
+    let borrados = aplicar_retencion(&conn, 1).unwrap();
+    assert_eq!(borrados, Borrados { queries: 2, reports: 0, cache_stats: 1 });
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "server")]
+
+use actix_web::{test as atest, web, App};
+use quickshift::api_error::ApiError;
+use quickshift::server_handlers::{export_ics_handler, solve_get_handler, solve_session_delete_handler};
+use serde_json::json;
+
+#[test]
+fn test_codigos_y_status_de_api_error() {
+    let e = ApiError::del_pipeline("MallaX", "malla 'MallaX' no encontrada en cwd ni en \"datafiles\"");
+    assert_eq!(e.code(), "MALLA_NOT_FOUND");
+    assert_eq!(e.status(), 404);
+    assert_eq!(e.to_json(), json!({
+        "code": "MALLA_NOT_FOUND",
+        "message": "malla 'MallaX' not found",
+        "details": {"malla": "MallaX"},
+    }));
+
+    let e = ApiError::del_pipeline("MC2020", "PERT failed");
+    assert_eq!((e.code(), e.status()), ("SOLVER_ERROR", 500));
+    assert_eq!(e.to_json()["details"], serde_json::Value::Null);
+
+    assert_eq!((ApiError::NoFeasibleSchedule.code(), ApiError::NoFeasibleSchedule.status()), ("NO_FEASIBLE_SCHEDULE", 422));
+    let e = ApiError::RateLimited { limit_per_min: 30, retry_after_secs: 12 };
+    assert_eq!(e.status(), 429);
+    assert_eq!(e.to_json()["details"], json!({"limit_per_min": 30, "retry_after_secs": 12}));
+}
+
+#[actix_web::test]
+async fn test_handlers_responden_code_message_details() {
+    let app = atest::init_service(
+        App::new()
+            .app_data(web::JsonConfig::default().error_handler(|err, _req| ApiError::from(err).into()))
+            .route("/solve", web::get().to(solve_get_handler))
+            .route("/solve/session/{id}", web::delete().to(solve_session_delete_handler))
+            .route("/solve/export/ics", web::post().to(export_ics_handler)),
+    ).await;
+
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/solve?email=a@b.cl").to_request()).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!(body["code"], "MISSING_PARAMETER");
+    assert_eq!(body["details"], json!({"parameter": "malla"}));
+
+    let req = atest::TestRequest::delete().uri("/solve/session/no-existe").to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    let body: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!(body["code"], "SESSION_NOT_FOUND");
+    assert_eq!(body["details"]["session_id"], "no-existe");
+
+    let req = atest::TestRequest::post().uri("/solve/export/ics").set_json(json!({"secciones": []})).to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!(body["code"], "INVALID_INPUT");
+    assert!(body["message"].as_str().unwrap().contains("secciones"));
+
+    // Body que no es JSON: lo rechaza el extractor con el mismo formato
+    let req = atest::TestRequest::post()
+        .uri("/solve/export/ics")
+        .insert_header(("content-type", "application/json"))
+        .set_payload("{no json")
+        .to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!(body["code"], "INVALID_BODY");
+}
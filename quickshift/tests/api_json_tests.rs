@@ -93,6 +93,7 @@ fn test_parse_and_resolve_ramos_with_mock() {
         "email": "juan.perez@example.com",
         "ramos_pasados": ["Algebra y Geometría", "Calculo 1", "Programación"],
         "ramos_prioritarios": ["Programación Avanzada", "Calculo 2"],
+        "ramos_excluidos": ["Algebra y Geometría", "CIT3100"],
         "horarios_preferidos": ["08:00-10:00"],
                 "malla": "MallaCurricularTest.xlsx"
     }
@@ -111,4 +112,5 @@ fn test_parse_and_resolve_ramos_with_mock() {
     assert!(params.ramos_pasados.contains(&"CIT1001".to_string()));
     assert!(params.ramos_prioritarios.contains(&"CIT9999".to_string()));
     assert!(params.ramos_pasados.contains(&"Calculo 1".to_string()));
+    assert_eq!(params.ramos_excluidos, vec!["MAT1000", "CIT3100"]);
 }
@@ -0,0 +1,77 @@
+#![cfg(feature = "server")]
+
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use actix_web::http::Method;
+use quickshift::middleware::api_keys::*;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_parse_y_busqueda_de_claves() {
+    let claves = parse_api_keys("frontend:abc123, movil:xyz:10, roto, :sin-nombre, malo:k:NaN", 60);
+    assert_eq!(claves.len(), 2);
+    establecer_claves(claves);
+
+    assert_eq!(buscar_clave("abc123"), Some(ApiKey { nombre: "frontend".into(), limite_por_min: 60 }));
+    assert_eq!(buscar_clave("xyz").map(|k| k.limite_por_min), Some(10));
+    assert_eq!(buscar_clave("otra"), None);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer abc123"));
+    assert_eq!(extraer_clave(&headers).as_deref(), Some("abc123"));
+    headers.insert(HeaderName::from_static("x-api-key"), HeaderValue::from_static(" xyz "));
+    assert_eq!(extraer_clave(&headers).as_deref(), Some("xyz"));
+    assert_eq!(extraer_clave(&HeaderMap::new()), None);
+}
+
+#[test]
+fn test_lectura_anonima_solo_get_de_help_y_datafiles() {
+    assert!(es_lectura_anonima(&Method::GET, "/help"));
+    assert!(es_lectura_anonima(&Method::GET, "/datafiles"));
+    assert!(es_lectura_anonima(&Method::GET, "/datafiles/validate"));
+    for privado in ["/datafiles/content", "/datafiles/download", "/datafiles/merge", "/datafiles/debug/oferta"] {
+        assert!(!es_lectura_anonima(&Method::GET, privado), "{}", privado);
+    }
+    assert!(!es_lectura_anonima(&Method::DELETE, "/datafiles"));
+    assert!(!es_lectura_anonima(&Method::POST, "/datafiles/upload"));
+    assert!(!es_lectura_anonima(&Method::GET, "/solve"));
+    assert!(!es_lectura_anonima(&Method::GET, "/datafilesx"));
+}
+
+#[test]
+fn test_token_bucket_se_rellena() {
+    let t0 = Instant::now();
+    let clave = "test-bucket";
+    for restante in (0..3).rev() {
+        let q = consumir_token_en(clave, 3, t0);
+        assert!(!q.exceeded);
+        assert_eq!(q.remaining, restante);
+    }
+    let agotado = consumir_token_en(clave, 3, t0);
+    assert!(agotado.exceeded);
+    assert_eq!(agotado.reset_secs, 20);
+
+    // 3 por minuto = un token cada 20 s
+    let q = consumir_token_en(clave, 3, t0 + Duration::from_secs(20));
+    assert!(!q.exceeded);
+    assert_eq!(q.remaining, 0);
+    assert!(consumir_token_en(clave, 3, t0 + Duration::from_secs(30)).exceeded);
+
+    assert!(!consumir_token_en("test-bucket-sin-limite", 0, t0).exceeded);
+}
+
+#[actix_web::test]
+async fn test_validacion_anonima_rechaza_ofertas_fuera_de_datafiles() {
+    use actix_web::{middleware::from_fn, test as atest, web, App};
+    use quickshift::api_json::handlers::datafiles::datafiles_validate_handler;
+
+    establecer_claves(parse_api_keys("frontend:abc123", 60));
+    let app = atest::init_service(App::new()
+        .wrap(from_fn(api_key_auth))
+        .route("/datafiles/validate", web::get().to(datafiles_validate_handler))).await;
+    for oferta in ["..%2FCargo.toml", "..%2F..%2Fetc%2Fpasswd.xlsx", "%2Fetc%2Fpasswd.xlsx", "%2Ftmp%2FOA20251.xlsx"] {
+        let req = atest::TestRequest::get().uri(&format!("/datafiles/validate?malla=MC2020.xlsx&oferta={}", oferta)).to_request();
+        assert_eq!(atest::call_service(&app, req).await.status(), 400, "{}", oferta);
+    }
+    let req = atest::TestRequest::get().uri("/datafiles/validate?malla=MC2020.xlsx&oferta=OA20251.xlsx").to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 200);
+}
@@ -0,0 +1,42 @@
+use quickshift::excel::avance::importar_avance;
+use quickshift::models::RamoDisponible;
+use std::collections::HashMap;
+
+fn malla() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Cálculo I", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "Álgebra y Geometría", "codigo": "CBM1001", "semestre": 1},
+        {"id": 3, "nombre": "Programación", "codigo": "CIT1000", "semestre": 1},
+        {"id": 4, "nombre": "Física I", "codigo": "FIS1000", "semestre": 2}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+#[test]
+fn test_importar_avance_curricular_con_encabezado() {
+    let equivalencias: HashMap<String, String> = [("CIT0100".to_string(), "CIT1000".to_string())].into_iter().collect();
+    let csv = "\u{feff}Alumno;Juan Pérez\n\
+               Carrera;Ingeniería Civil Informática\n\
+               Código;Nombre Asignatura;Nota;Estado\n\
+               cbm1000;Cálculo I;5,5;Aprobado\n\
+               ;ALGEBRA Y GEOMETRIA;4.0;\n\
+               cit0100;\"Programación; sección 2\";6,1;Convalidado\n\
+               FIS1000;Física I;3,2;Reprobado\n\
+               XYZ9999;Taller de Deportes;7,0;Aprobado\n\
+               CBM1000;Cálculo I;6,0;Aprobado\n";
+
+    let res = importar_avance(csv, &malla(), &equivalencias);
+    assert_eq!(res.ramos_pasados, vec!["CBM1000", "CBM1001", "CIT1000"]);
+    assert_eq!(res.omitidas, 1, "Física I reprobada");
+    assert_eq!(res.no_reconocidas.len(), 1);
+    assert_eq!(res.no_reconocidas[0].linea, 8);
+    assert!(res.no_reconocidas[0].contenido.starts_with("XYZ9999"));
+}
+
+#[test]
+fn test_importar_lista_sin_encabezado() {
+    let res = importar_avance("CBM1000\nprogramacion\nRamo inventado\n", &malla(), &HashMap::new());
+    assert_eq!(res.ramos_pasados, vec!["CBM1000", "CIT1000"]);
+    assert_eq!(res.no_reconocidas.iter().map(|f| f.linea).collect::<Vec<_>>(), vec![3]);
+    assert_eq!(res.omitidas, 0);
+}
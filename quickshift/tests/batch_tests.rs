@@ -0,0 +1,82 @@
+#![cfg(feature = "server")]
+
+use actix_web::{test as atest, web, App};
+use quickshift::algorithm::cohorte::demanda_por_seccion;
+use quickshift::models::Seccion;
+use quickshift::server_handlers::{estudiantes_desde_csv, solve_batch_handler};
+use serde_json::json;
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, n: &str, componente: &str) -> Seccion {
+    seccion_con(codigo, n, &["LU 08:30 - 09:50"], json!({
+        "codigo_box": format!("{}-{}-{}", codigo, componente, n),
+        "componente": componente,
+    }))
+}
+
+#[test]
+fn test_estudiantes_desde_csv() {
+    let csv = "Email;Malla;Ramos pasados;Student ranking;Observaciones\n\
+               a@example.com;MiMalla.xlsx;CBM1000|CBQ1000;0,8;nada\n\
+               \n\
+               b@example.com;;;;\n";
+    let estudiantes = estudiantes_desde_csv(csv).unwrap();
+    assert_eq!(estudiantes, vec![
+        json!({"email": "a@example.com", "malla": "MiMalla.xlsx", "ramos_pasados": ["CBM1000", "CBQ1000"], "student_ranking": 0.8}),
+        json!({"email": "b@example.com"}),
+    ]);
+
+    // con comas como separador las listas van entre comillas
+    let csv = "correo,ramos_prioritarios\nc@example.com,\"CIT1000, Cálculo I\"\n";
+    let estudiantes = estudiantes_desde_csv(csv).unwrap();
+    assert_eq!(estudiantes[0]["ramos_prioritarios"], json!(["CIT1000", "Cálculo I"]));
+
+    assert!(estudiantes_desde_csv("malla;ramos_pasados\nMiMalla.xlsx;CBM1000\n").is_err());
+    assert!(estudiantes_desde_csv("email;student_ranking\na@example.com;alto\n").is_err());
+}
+
+#[test]
+fn test_demanda_por_seccion() {
+    let a = vec![seccion("CBF1000", "1", "catedra"), seccion("CBF1000", "1", "lab"), seccion("CBM1000", "2", "catedra")];
+    let b = vec![seccion("CBF1000", "1", "catedra"), seccion("CBF1000", "1", "lab"), seccion("CBM1000", "1", "catedra")];
+    let c = vec![seccion("CBM1000", "2", "catedra")];
+    let demanda = demanda_por_seccion([a.as_slice(), b.as_slice(), c.as_slice()]);
+    let resumen: Vec<(&str, &str, usize)> = demanda.iter().map(|d| (d.codigo.as_str(), d.seccion.as_str(), d.estudiantes)).collect();
+    // el paquete cátedra + laboratorio cuenta una vez por estudiante
+    assert_eq!(resumen, vec![("CBF1000", "1", 2), ("CBM1000", "2", 2), ("CBM1000", "1", 1)]);
+}
+
+#[actix_web::test]
+async fn test_lote_invalido_y_errores_por_estudiante() {
+    let app = atest::init_service(App::new().route("/solve/batch", web::post().to(solve_batch_handler))).await;
+
+    let resp = atest::call_service(&app, atest::TestRequest::post().uri("/solve/batch").set_json(json!([])).to_request()).await;
+    assert_eq!(resp.status(), 400);
+    let resp = atest::call_service(&app, atest::TestRequest::post().uri("/solve/batch").set_json(json!({"comun": {}})).to_request()).await;
+    assert_eq!(resp.status(), 400);
+    let resp = atest::call_service(&app, atest::TestRequest::post().uri("/solve/batch")
+        .insert_header(("content-type", "text/csv"))
+        .set_payload("malla\nMiMalla.xlsx\n")
+        .to_request()).await;
+    assert_eq!(resp.status(), 400);
+
+    // un estudiante con una malla inexistente no detiene el lote
+    let body = json!({
+        "estudiantes": [{"email": "a@example.com"}, {"email": "b@example.com", "ramos_pasados": ["CBM1000"]}],
+        "comun": {"malla": "NoExiste_batch.xlsx"}
+    });
+    let resp = atest::call_service(&app, atest::TestRequest::post().uri("/solve/batch").set_json(body).to_request()).await;
+    assert_eq!(resp.status(), 200);
+    let out: serde_json::Value = atest::read_body_json(resp).await;
+    let resultados = out["resultados"].as_array().unwrap();
+    assert_eq!(resultados.len(), 2);
+    assert_eq!(resultados[1]["indice"], 1);
+    assert_eq!(resultados[1]["email"], "b@example.com");
+    assert!(resultados.iter().all(|r| r["error"]["code"].is_string()));
+    assert_eq!(out["reporte"]["total"], 2);
+    assert_eq!(out["reporte"]["con_horario"], 0);
+    assert_eq!(out["reporte"]["con_error"], json!(["a@example.com", "b@example.com"]));
+    assert_eq!(out["reporte"]["secciones_mas_demandadas"], json!([]));
+}
@@ -1,13 +1,13 @@
-/// Benchmark comparativo: Sistema Rust vs Sistema Python original
-/// 
-/// Este test compara el sistema quickshift (Rust) con el sistema antiguo en Python
-/// para demostrar objetivamente cuál genera mejores soluciones.
-/// 
-/// Criterios de comparación:
-/// 1. Cantidad de soluciones generadas
-/// 2. Tamaño promedio de soluciones (cursos por solución)
-/// 3. Diversidad de soluciones (secciones únicas)
-/// 4. Tiempo de ejecución
+//! Benchmark comparativo: Sistema Rust vs Sistema Python original
+//! 
+//! Este test compara el sistema quickshift (Rust) con el sistema antiguo en Python
+//! para demostrar objetivamente cuál genera mejores soluciones.
+//! 
+//! Criterios de comparación:
+//! 1. Cantidad de soluciones generadas
+//! 2. Tamaño promedio de soluciones (cursos por solución)
+//! 3. Diversidad de soluciones (secciones únicas)
+//! 4. Tiempo de ejecución
 
 use quickshift::algorithm::ruta::ejecutar_ruta_critica_with_params;
 use quickshift::excel::{leer_mc_con_porcentajes_optimizado, resolve_datafile_paths};
@@ -21,8 +21,7 @@ fn benchmark_rust_vs_python() {
     eprintln!("╚════════════════════════════════════════════════════════════╝\n");
 
     // Cargar malla para obtener información
-    let current_dir = std::env::current_dir().expect("No se pudo obtener directorio actual");
-    let (malla_path, _, porcentajes_path) = resolve_datafile_paths(&current_dir)
+    let (malla_path, _, porcentajes_path) = resolve_datafile_paths("MC2020moded.xlsx")
         .expect("No se pudieron resolver datafiles");
     
     let malla_path_str = malla_path.to_str().unwrap();
@@ -72,14 +71,8 @@ fn benchmark_rust_vs_python() {
         let params = InputParams {
             malla: "MC2020moded.xlsx".to_string(),
             anio: Some(2025),
-            periodo: Some(1),
             ramos_pasados: ramos_pasados.iter().map(|s| s.to_string()).collect(),
-            numero_cfgs_aprobados: cfgs_aprobados,
-            filtros: None,
-            horarios_prohibidos: vec![],
-            optimizations: vec![],
-            ramos_prioritarios: vec![],
-            email: None,
+            ..Default::default()
         };
 
         let resultado = ejecutar_ruta_critica_with_params(params);
@@ -206,7 +199,4 @@ fn benchmark_rust_vs_python() {
     eprintln!("   2. Implementar búsqueda exhaustiva vs greedy");
     eprintln!("   3. Integrar PERT para optimización de ruta crítica");
     eprintln!("   4. Lograr mejor rendimiento y seguridad de tipos\"\n");
-
-    // Test siempre pasa - es informativo
-    assert!(true, "Benchmark completado exitosamente");
 }
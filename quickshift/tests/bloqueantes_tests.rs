@@ -0,0 +1,62 @@
+use quickshift::algorithm::bloqueantes::calcular_bloqueantes;
+use quickshift::models::RamoDisponible;
+use std::collections::HashMap;
+
+fn ramo(id: i32, nombre: &str, codigo: &str, requisitos_ids: Vec<i32>) -> RamoDisponible {
+    RamoDisponible {
+        id,
+        nombre: nombre.to_string(),
+        codigo: codigo.to_string(),
+        holgura: 0,
+        numb_correlativo: id,
+        critico: false,
+        requisitos_ids,
+        correquisitos_ids: vec![],
+        dificultad: None,
+        electivo: false,
+        semestre: Some(1),
+        creditos: None,
+        tags: Vec::new(),
+    }
+}
+
+fn malla() -> HashMap<String, RamoDisponible> {
+    // Cadena larga: Calculo I -> Calculo II -> Calculo III -> Ecuaciones
+    // Cadena corta: Programacion -> Algoritmos
+    vec![
+        ramo(1, "Calculo I", "CBM1000", vec![]),
+        ramo(2, "Calculo II", "CBM1001", vec![1]),
+        ramo(3, "Calculo III", "CBM1002", vec![2]),
+        ramo(4, "Ecuaciones", "CBM1003", vec![3]),
+        ramo(5, "Programacion", "CIT1000", vec![]),
+        ramo(6, "Algoritmos", "CIT2000", vec![5]),
+        ramo(7, "Fisica", "FIS1000", vec![1]),
+    ]
+    .into_iter()
+    .map(|r| (quickshift::excel::normalize_name(&r.nombre), r))
+    .collect()
+}
+
+#[test]
+fn test_bloqueante_inicia_cadena_mas_larga() {
+    let analisis = calcular_bloqueantes(&malla(), &[]);
+    assert_eq!(analisis.ramos_pendientes, 7);
+    assert_eq!(analisis.cadena_maxima, 4);
+    assert_eq!(analisis.bloqueantes.len(), 1);
+    let b = &analisis.bloqueantes[0];
+    assert_eq!(b.codigo, "CBM1000");
+    // Calculo II, III, Ecuaciones y Fisica dependen de Calculo I
+    assert_eq!(b.bloquea, 4);
+}
+
+#[test]
+fn test_bloqueantes_con_ramos_aprobados() {
+    let pasados = vec!["CBM1000".to_string(), "Calculo II".to_string()];
+    let analisis = calcular_bloqueantes(&malla(), &pasados);
+    assert_eq!(analisis.ramos_pendientes, 5);
+    assert_eq!(analisis.cadena_maxima, 2);
+    // Calculo III -> Ecuaciones y Programacion -> Algoritmos empatan en largo
+    let codigos: Vec<&str> = analisis.bloqueantes.iter().map(|b| b.codigo.as_str()).collect();
+    assert_eq!(codigos, vec!["CBM1002", "CIT1000"]);
+    assert!(analisis.bloqueantes.iter().all(|b| b.bloquea == 1));
+}
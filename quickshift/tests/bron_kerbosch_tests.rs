@@ -0,0 +1,93 @@
+use quickshift::algorithm::bron_kerbosch::{BronKerbosch, Control, Grafo};
+use std::collections::BTreeSet;
+
+/// Grafo pseudoaleatorio determinista (LCG) con densidad ~`densidad`/100
+fn grafo_aleatorio(n: usize, densidad: u64, semilla: u64) -> Grafo {
+    let mut estado = semilla;
+    let mut aristas = vec![vec![false; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            estado = estado.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let hay = (estado >> 33) % 100 < densidad;
+            aristas[i][j] = hay;
+            aristas[j][i] = hay;
+        }
+    }
+    Grafo::nuevo(n, |_| true, |i, j| aristas[i][j])
+}
+
+fn es_clique(g: &Grafo, c: &[usize]) -> bool {
+    c.iter().enumerate().all(|(k, &a)| c[k + 1..].iter().all(|&b| g.adyacentes(a, b)))
+}
+
+/// Por fuerza bruta: cliques que son maximales o tienen exactamente `tope` vértices
+fn esperadas(g: &Grafo, tope: usize) -> BTreeSet<Vec<usize>> {
+    let n = g.len();
+    (1u32..(1 << n))
+        .map(|m| (0..n).filter(|&v| m & (1 << v) != 0).collect::<Vec<_>>())
+        .filter(|c| c.len() <= tope && es_clique(g, c))
+        .filter(|c| c.len() == tope || (0..n).all(|v| c.contains(&v) || !c.iter().all(|&u| g.adyacentes(u, v))))
+        .collect()
+}
+
+fn reportadas(g: &Grafo, tope: usize) -> Vec<Vec<usize>> {
+    let mut out = Vec::new();
+    BronKerbosch::new(g, tope).enumerar(|nodo| {
+        if nodo.maximal || nodo.clique.len() == tope {
+            let mut c = nodo.clique.to_vec();
+            c.sort();
+            out.push(c);
+        }
+        Control::Seguir
+    });
+    out
+}
+
+#[test]
+fn test_coincide_con_fuerza_bruta() {
+    for semilla in 0..12 {
+        let g = grafo_aleatorio(12, 55, semilla);
+        for tope in [2, 4, 12] {
+            let encontradas = reportadas(&g, tope);
+            let unicas: BTreeSet<Vec<usize>> = encontradas.iter().cloned().collect();
+            assert_eq!(unicas.len(), encontradas.len(), "cliques repetidas (semilla {}, tope {})", semilla, tope);
+            assert_eq!(unicas, esperadas(&g, tope), "semilla {}, tope {}", semilla, tope);
+        }
+    }
+}
+
+#[test]
+fn test_tope_dentro_de_clique_mayor() {
+    // K6 con tope 4: las C(6,4) = 15 cliques de 4, ninguna de 5 o 6
+    let g = Grafo::nuevo(6, |_| true, |_, _| true);
+    let cliques = reportadas(&g, 4);
+    assert_eq!(cliques.len(), 15);
+    assert!(cliques.iter().all(|c| c.len() == 4));
+}
+
+#[test]
+fn test_inactivos_y_control_del_visitante() {
+    // Camino 0-1-2-3 con el 3 inactivo
+    let g = Grafo::nuevo(4, |v| v != 3, |i, j| j == i + 1);
+    assert!(!g.adyacentes(2, 3));
+    assert_eq!(g.orden_degeneracion().len(), 3);
+    assert_eq!(reportadas(&g, 4).into_iter().collect::<BTreeSet<_>>(), BTreeSet::from([vec![0, 1], vec![1, 2]]));
+
+    // Podar la raíz de una rama no detiene las demás
+    let mut visitadas = 0;
+    let control = BronKerbosch::new(&g, 4).enumerar(|_| {
+        visitadas += 1;
+        Control::Podar
+    });
+    assert_eq!(control, Control::Seguir);
+    assert_eq!(visitadas, 3);
+
+    // Parar corta la enumeración en la primera visita
+    let mut visitadas = 0;
+    let control = BronKerbosch::new(&g, 4).enumerar(|_| {
+        visitadas += 1;
+        Control::Parar
+    });
+    assert_eq!(control, Control::Parar);
+    assert_eq!(visitadas, 1);
+}
@@ -0,0 +1,112 @@
+use quickshift::algorithm::bundles::{colapsar, expandir, unidades};
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::api_json::InputParams;
+use quickshift::excel::componentes::{asignar_bundles, detectar};
+use quickshift::models::{Componente, RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, n: &str, componente: &str, horario: &[&str]) -> Seccion {
+    seccion_con(codigo, n, horario, serde_json::json!({
+        "codigo_box": format!("{}-{}-{}", codigo, componente, n),
+        "componente": componente,
+    }))
+}
+
+#[test]
+fn test_detectar_componente() {
+    assert_eq!(detectar("CBF1000", "Física I", ""), Componente::Catedra);
+    assert_eq!(detectar("CBF1000_LA01", "Física I", ""), Componente::Lab);
+    assert_eq!(detectar("CIT2000_TA02", "Programación", ""), Componente::Lab);
+    assert_eq!(detectar("CBM1000_AY01", "Cálculo I", ""), Componente::Ayudantia);
+    assert_eq!(detectar("CBF1000", "Laboratorio de Física I", ""), Componente::Lab);
+    assert_eq!(detectar("CBM1000", "Ayudantía Cálculo I", ""), Componente::Ayudantia);
+    // la columna de tipo manda sobre el código y el nombre
+    assert_eq!(detectar("CBF1000_LA01", "Física I", "Cátedra"), Componente::Catedra);
+    assert_eq!(detectar("CBF1000", "Física I", "LAB"), Componente::Lab);
+}
+
+#[test]
+fn test_asignar_bundles_por_codigo_y_seccion() {
+    let mut secciones = vec![
+        seccion("CBF1000", "1", "catedra", &["LU 08:30 - 09:50"]),
+        seccion("CBF1000", "01", "lab", &["MI 10:00 - 11:20"]),
+        seccion("CBF1000", "2", "catedra", &["MA 08:30 - 09:50"]),
+        seccion("CBF1000", "3", "lab", &["VI 08:30 - 09:50"]),
+    ];
+    asignar_bundles(&mut secciones);
+    assert_eq!(secciones[0].bundle_id.as_deref(), Some("CBF1000#1"));
+    assert_eq!(secciones[1].bundle_id.as_deref(), Some("CBF1000#1"));
+    // cátedra sin laboratorio y laboratorio sin cátedra se inscriben solos
+    assert_eq!(secciones[2].bundle_id, None);
+    assert_eq!(secciones[3].bundle_id, None);
+}
+
+#[test]
+fn test_colapsar_y_expandir() {
+    let mut secciones = vec![
+        seccion("CBF1000", "1", "catedra", &["LU 08:30 - 09:50"]),
+        seccion("CBF1000", "1", "lab", &["MI 10:00 - 11:20"]),
+        seccion("CBF1000", "2", "catedra", &["MA 08:30 - 09:50"]),
+        seccion("CBF1000", "2", "lab", &["MA 09:00 - 10:20"]),
+        seccion("CBM1000", "1", "catedra", &["JU 08:30 - 09:50"]),
+    ];
+    asignar_bundles(&mut secciones);
+    let (colapsadas, paquetes) = colapsar(&secciones);
+
+    // el paquete 2 choca internamente y se descarta
+    assert_eq!(colapsadas.len(), 2);
+    assert_eq!(paquetes.len(), 1);
+    let compuesta = &colapsadas[0];
+    assert_eq!(compuesta.bundle_id.as_deref(), Some("CBF1000#1"));
+    assert_eq!(compuesta.horario, vec!["LU 08:30 - 09:50", "MI 10:00 - 11:20"]);
+
+    let sol = vec![(compuesta.clone(), 7), (colapsadas[1].clone(), 3)];
+    let expandidas = expandir(vec![(sol, 10)], &paquetes);
+    let (sol, total) = &expandidas[0];
+    assert_eq!(*total, 10);
+    assert_eq!(sol.len(), 3);
+    assert_eq!(sol[0].0.componente, Componente::Catedra);
+    assert_eq!(sol[1].0.componente, Componente::Lab);
+    assert_eq!(sol[1].1, 7);
+    assert_eq!(unidades(sol), 2);
+}
+
+#[test]
+fn test_clique_inscribe_el_paquete_completo() {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "CBF1000", "codigo": "CBF1000", "semestre": 1},
+        {"id": 2, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    let mut secciones = vec![
+        seccion("CBF1000", "1", "catedra", &["LU 08:30 - 09:50"]),
+        seccion("CBF1000", "1", "lab", &["MI 10:00 - 11:20"]),
+        seccion("CBM1000", "1", "catedra", &["MI 10:00 - 11:20"]),
+        seccion("CBM1000", "2", "catedra", &["JU 08:30 - 09:50"]),
+    ];
+    asignar_bundles(&mut secciones);
+    let params: InputParams = serde_json::from_value(serde_json::json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": [],
+        "ramos_prioritarios": [],
+        "malla": "MallaBundles"
+    })).unwrap();
+
+    let soluciones = get_clique_max_pond_with_prefs(&secciones, &ramos, &params);
+    assert!(!soluciones.is_empty());
+    for (sol, _) in &soluciones {
+        let catedra = sol.iter().any(|(s, _)| s.codigo == "CBF1000" && s.componente == Componente::Catedra);
+        let lab = sol.iter().any(|(s, _)| s.codigo == "CBF1000" && s.componente == Componente::Lab);
+        assert_eq!(catedra, lab, "cátedra y laboratorio deben ir juntos");
+        if lab {
+            // el laboratorio choca con CBM1000 sección 1
+            assert!(!sol.iter().any(|(s, _)| s.codigo == "CBM1000" && s.seccion == "1"));
+        }
+    }
+    let (mejor, _) = &soluciones[0];
+    assert_eq!(mejor.len(), 3);
+    assert_eq!(unidades(mejor), 2);
+}
@@ -0,0 +1,75 @@
+use quickshift::algorithm::carga_diaria::{diagnosticar, excede_ramos_por_dia, validar, FiltrosInviables};
+use quickshift::algorithm::en_memoria::{resolver_en_memoria, SolveEnMemoria};
+use quickshift::models::{Seccion, UserFilters};
+use serde_json::json;
+
+mod common;
+
+fn seccion(codigo: &str, horario: &[&str]) -> Seccion {
+    common::seccion(codigo, "1", horario)
+}
+
+fn filtros(valor: serde_json::Value) -> UserFilters {
+    serde_json::from_value(valor).unwrap()
+}
+
+/// Cuatro ramos de primer semestre: dos el lunes (uno temprano), dos el martes (uno tarde)
+fn entrada(filtros: serde_json::Value) -> SolveEnMemoria {
+    serde_json::from_value(json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": [],
+        "ramos_prioritarios": [],
+        "malla": "MallaEnMemoria",
+        "filtros": filtros,
+        "ramos": [
+            {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+            {"id": 2, "nombre": "Algebra", "codigo": "CBM1002", "semestre": 1},
+            {"id": 3, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+            {"id": 4, "nombre": "Comunicacion", "codigo": "CIT1010", "semestre": 1}
+        ],
+        "secciones": [
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["LU 08:30-09:50"], "codigo_box": "CBM1000-1"},
+            {"codigo": "CBM1002", "nombre": "Algebra", "seccion": "1", "horario": ["LU 10:00-11:20"], "codigo_box": "CBM1002-1"},
+            {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "1", "horario": ["MA 10:00-11:20"], "codigo_box": "CIT1000-1"},
+            {"codigo": "CIT1010", "nombre": "Comunicacion", "seccion": "1", "horario": ["MA 17:00-18:20"], "codigo_box": "CIT1010-1"}
+        ]
+    })).unwrap()
+}
+
+#[test]
+fn test_validar_y_contar_ramos_por_dia() {
+    assert!(validar(&filtros(json!({"max_ramos_por_dia": 0}))).is_err());
+    assert!(validar(&filtros(json!({"hora_inicio_minima": "mediodía"}))).is_err());
+    assert!(validar(&filtros(json!({"hora_inicio_minima": "14:00", "hora_fin_maxima": "10:00"}))).is_err());
+    assert!(validar(&filtros(json!({"max_ramos_por_dia": 2, "hora_inicio_minima": "8:30", "hora_fin_maxima": "18:00"}))).is_ok());
+
+    let lunes = [seccion("CBM1000", &["LU 08:30-09:50"]), seccion("CBM1002", &["LU 10:00-11:20", "MI 10:00-11:20"])];
+    assert!(excede_ramos_por_dia(&lunes, 1));
+    assert!(!excede_ramos_por_dia(&lunes, 2));
+}
+
+#[test]
+fn test_soluciones_respetan_tope_y_rango() {
+    let soluciones = resolver_en_memoria(entrada(json!({"max_ramos_por_dia": 1}))).unwrap();
+    assert!(!soluciones.is_empty());
+    for (sol, _) in &soluciones {
+        assert!(!excede_ramos_por_dia(sol.iter().map(|(s, _)| s), 1));
+    }
+
+    let soluciones = resolver_en_memoria(entrada(json!({"hora_inicio_minima": "09:00", "hora_fin_maxima": "17:00"}))).unwrap();
+    assert!(!soluciones.is_empty());
+    for (sol, _) in &soluciones {
+        let codigos: Vec<&str> = sol.iter().map(|(s, _)| s.codigo.as_str()).collect();
+        assert!(!codigos.contains(&"CBM1000") && !codigos.contains(&"CIT1010"), "{:?}", codigos);
+    }
+}
+
+#[test]
+fn test_rango_inviable_reporta_violaciones() {
+    let err = resolver_en_memoria(entrada(json!({"hora_inicio_minima": "12:00", "hora_fin_maxima": "16:00"}))).unwrap_err();
+    let reporte = err.downcast::<FiltrosInviables>().expect("reporte de filtros inviables");
+    assert_eq!(reporte.secciones_fuera_de_rango, 4);
+    assert_eq!(reporte.ramos_sin_seccion_en_rango, vec!["CBM1000", "CBM1002", "CIT1000", "CIT1010"]);
+
+    assert!(diagnosticar(&[], &Some(UserFilters::default())).is_none());
+}
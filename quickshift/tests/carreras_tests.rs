@@ -0,0 +1,72 @@
+use quickshift::api_json::parse_json_input;
+use quickshift::carreras::*;
+use quickshift::models::Seccion;
+
+mod common;
+use common::seccion_con;
+
+const REGISTRO: &str = r#"
+[[carrera]]
+id = "ICI"
+nombre = "Ingeniería Civil Informática"
+mallas = ["MC2020.xlsx", "MC2018.xlsx"]
+prefijos = ["CIT"]
+
+[[carrera]]
+id = "ICQ"
+nombre = "Ingeniería Civil Química"
+facultad = "Ingeniería"
+mallas = ["ICQ2022.xlsx"]
+max_cfg = 2
+electivos = false
+"#;
+
+fn seccion(codigo: &str, electivo: bool) -> Seccion {
+    seccion_con(codigo, "1", &["LU 08:30-10:00"], serde_json::json!({"is_electivo": electivo}))
+}
+
+#[test]
+fn test_registro_valida_ids_y_mallas() {
+    let r = RegistroCarreras::desde_toml(REGISTRO).unwrap();
+    assert_eq!(r.carreras.len(), 2);
+    let icq = r.get("icq").unwrap();
+    assert_eq!(icq.max_cfg, 2);
+    assert_eq!(icq.facultad.as_deref(), Some("Ingeniería"));
+    assert_eq!(r.get("ICI").unwrap().max_cfg, MAX_CFG_POR_DEFECTO);
+
+    let repetido = "[[carrera]]\nid = \"A\"\nnombre = \"a\"\nmallas = [\"x.xlsx\"]\n[[carrera]]\nid = \"a\"\nnombre = \"b\"\nmallas = [\"y.xlsx\"]\n";
+    assert!(RegistroCarreras::desde_toml(repetido).unwrap_err().contains("repetido"));
+    let sin_mallas = "[[carrera]]\nid = \"A\"\nnombre = \"a\"\nmallas = []\n";
+    assert!(RegistroCarreras::desde_toml(sin_mallas).unwrap_err().contains("no declara mallas"));
+}
+
+#[test]
+fn test_aplicar_carrera_y_reglas() {
+    establecer_registro(RegistroCarreras::desde_toml(REGISTRO).unwrap());
+
+    let mut p = parse_json_input(r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[],"carrera":"ici"}"#).unwrap();
+    aplicar_carrera(&mut p).unwrap();
+    assert_eq!(p.malla, "MC2020.xlsx");
+    assert_eq!(max_cfg(&p), 4);
+
+    let mut ajena = parse_json_input(r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[],"carrera":"ICQ","malla":"MC2020.xlsx"}"#).unwrap();
+    assert!(aplicar_carrera(&mut ajena).unwrap_err().contains("no pertenece"));
+
+    let mut desconocida = parse_json_input(r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[],"carrera":"XYZ","malla":"MC2020.xlsx"}"#).unwrap();
+    assert!(aplicar_carrera(&mut desconocida).is_err());
+
+    let mut sin_malla = parse_json_input(r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[]}"#).unwrap();
+    assert!(aplicar_carrera(&mut sin_malla).is_err());
+
+    // ICI solo admite electivos CIT; los ramos de la malla no se tocan
+    let mut secciones = vec![seccion("CIT3313", true), seccion("ICQ2001", true), seccion("CBM1000", false)];
+    assert_eq!(filtrar_electivos(&p, &mut secciones), 1);
+    assert_eq!(secciones.iter().map(|s| s.codigo.as_str()).collect::<Vec<_>>(), vec!["CIT3313", "CBM1000"]);
+
+    // ICQ no ofrece electivos y exige 2 CFG
+    let mut q = parse_json_input(r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[],"carrera":"ICQ","malla":"ICQ2022.xlsx"}"#).unwrap();
+    aplicar_carrera(&mut q).unwrap();
+    assert_eq!(max_cfg(&q), 2);
+    let mut secciones = vec![seccion("CIT3313", true), seccion("ICQ2001", false)];
+    assert_eq!(filtrar_electivos(&q, &mut secciones), 1);
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "server")]
+
+// Subcomandos de la línea de comandos (`quickshift::cli`), sin lanzar procesos.
+
+use quickshift::cli::{pert, solve_desde_json, validar_datafiles};
+use quickshift::excel::config::{self, DatafilesConfig, OrigenDatafiles};
+use quickshift::excel::deteccion::TipoDatafile;
+
+#[test]
+fn test_validate_datafiles_reporta_cada_archivo_reconocido() {
+    // pert con los datafiles del repo, antes de cambiar de directorio
+    let red = pert("Malla2020.xlsx", None).unwrap();
+    assert_eq!(red["malla"], "Malla2020.xlsx");
+    assert!(!red["nodos"].as_array().unwrap().is_empty());
+    assert!(pert("NoExiste2099.xlsx", None).is_err());
+
+    let dir = std::env::temp_dir().join(format!("quickshift-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy("src/datafiles/Malla2020.xlsx", dir.join("Malla2020.xlsx")).unwrap();
+    // Una oferta que no es un Excel y un archivo cuyo nombre no se clasifica
+    std::fs::write(dir.join("OA20251.xlsx"), b"no es un excel").unwrap();
+    std::fs::write(dir.join("notas.xlsx"), b"").unwrap();
+    config::establecer(DatafilesConfig { dir: dir.clone(), origen: OrigenDatafiles::LineaDeComandos });
+
+    let reporte = validar_datafiles().unwrap();
+    let nombres: Vec<&str> = reporte.iter().map(|a| a.archivo.as_str()).collect();
+    assert_eq!(nombres, vec!["Malla2020.xlsx", "OA20251.xlsx"]);
+
+    let malla = &reporte[0];
+    assert_eq!(malla.tipo, Some(TipoDatafile::Malla));
+    assert!(malla.filas > 0);
+    assert!(malla.malla.is_some(), "las mallas llevan el reporte de excel::validate");
+
+    let oferta = &reporte[1];
+    assert_eq!(oferta.tipo, None);
+    assert!(!oferta.valido);
+    assert!(!oferta.errores.is_empty());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_solve_rechaza_json_invalido() {
+    assert!(solve_desde_json("{").is_err());
+    assert!(solve_desde_json(r#"{"email": "x@x.cl"}"#).is_err());
+}
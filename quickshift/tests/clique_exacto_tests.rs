@@ -0,0 +1,90 @@
+use quickshift::algorithm::clique_exacto::{max_weight_clique, max_weight_clique_con_costos};
+use std::time::Duration;
+
+fn grafo(n: usize, aristas: &[(usize, usize)]) -> Vec<Vec<bool>> {
+    let mut adj = vec![vec![false; n]; n];
+    for &(a, b) in aristas {
+        adj[a][b] = true;
+        adj[b][a] = true;
+    }
+    adj
+}
+
+#[test]
+fn test_exacto_supera_greedy_por_peso() {
+    // El vértice más pesado (0) está aislado: el greedy por peso lo toma y se queda en 10,
+    // pero la clique {1, 2, 3} pesa 21.
+    let adj = grafo(4, &[(1, 2), (1, 3), (2, 3)]);
+    let pesos = vec![10, 7, 7, 7];
+    let res = max_weight_clique(&adj, &pesos, &[false; 4], 6, 6, Duration::from_secs(5));
+    assert!(res.optimo);
+    assert_eq!(res.clique, vec![1, 2, 3]);
+    assert_eq!(res.peso, 21);
+}
+
+#[test]
+fn test_exacto_respeta_tamano_y_limitados() {
+    let n = 5;
+    let todas: Vec<(usize, usize)> = (0..n).flat_map(|a| ((a + 1)..n).map(move |b| (a, b))).collect();
+    let adj = grafo(n, &todas);
+    let pesos = vec![5, 4, 3, 2, 1];
+
+    let res = max_weight_clique(&adj, &pesos, &[false; 5], 2, 5, Duration::from_secs(5));
+    assert_eq!(res.clique, vec![0, 1]);
+
+    // 0 y 1 son "limitados" (p.ej. CFG) y solo se permite uno
+    let limitados = [true, true, false, false, false];
+    let res = max_weight_clique(&adj, &pesos, &limitados, 3, 1, Duration::from_secs(5));
+    assert_eq!(res.clique, vec![0, 2, 3]);
+    assert_eq!(res.peso, 10);
+}
+
+#[test]
+fn test_exacto_coincide_con_fuerza_bruta() {
+    // Grafo pseudoaleatorio determinista (LCG) de 14 vértices
+    let n = 14;
+    let mut estado: u64 = 42;
+    let mut siguiente = || {
+        estado = estado.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (estado >> 33) as u32
+    };
+    let mut aristas = Vec::new();
+    for a in 0..n {
+        for b in (a + 1)..n {
+            if siguiente() % 100 < 55 {
+                aristas.push((a, b));
+            }
+        }
+    }
+    let adj = grafo(n, &aristas);
+    let pesos: Vec<i64> = (0..n).map(|_| (siguiente() % 50 + 1) as i64).collect();
+
+    let mut mejor = 0i64;
+    for mask in 0u32..(1 << n) {
+        if mask.count_ones() > 4 {
+            continue;
+        }
+        let vs: Vec<usize> = (0..n).filter(|&v| mask & (1 << v) != 0).collect();
+        if vs.iter().all(|&a| vs.iter().all(|&b| a == b || adj[a][b])) {
+            mejor = mejor.max(vs.iter().map(|&v| pesos[v]).sum());
+        }
+    }
+
+    let res = max_weight_clique(&adj, &pesos, &[false; 14], 4, 4, Duration::from_secs(5));
+    assert!(res.optimo);
+    assert_eq!(res.peso, mejor);
+}
+
+#[test]
+fn test_exacto_respeta_tope_de_costos() {
+    let n = 4;
+    let todas: Vec<(usize, usize)> = (0..n).flat_map(|a| ((a + 1)..n).map(move |b| (a, b))).collect();
+    let adj = grafo(n, &todas);
+    let pesos = vec![10, 9, 4, 3];
+    // Créditos: 0 y 1 juntos suman 12 > 10
+    let costos = vec![6, 6, 2, 2];
+    let res = max_weight_clique_con_costos(&adj, &pesos, &[false; 4], n, n, Some((&costos, 10)), Duration::from_secs(5));
+    assert!(res.optimo);
+    assert_eq!(res.clique, vec![0, 2, 3]);
+    assert_eq!(res.peso, 17);
+}
@@ -0,0 +1,40 @@
+//! Helpers compartidos por los tests de integración (`mod common;`).
+//! Cada binario de test usa solo algunos, de ahí el `allow(dead_code)`.
+#![allow(dead_code)]
+
+use quickshift::api_json::InputParams;
+use quickshift::models::Seccion;
+use serde_json::{json, Value};
+
+/// `InputParams` mínimos para `malla`; los campos de `extra` se agregan o
+/// reemplazan sobre la base
+pub fn params(malla: &str, extra: Value) -> InputParams {
+    let mut base = json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": [],
+        "ramos_prioritarios": [],
+        "malla": malla
+    });
+    base.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    serde_json::from_value(base).unwrap()
+}
+
+/// Sección `n` de `codigo` (el nombre es el mismo código) con `codigo_box`
+/// `<codigo>-<n>`
+pub fn seccion(codigo: &str, n: &str, horario: &[&str]) -> Seccion {
+    seccion_con(codigo, n, horario, json!({}))
+}
+
+/// Como `seccion`, con campos adicionales (`cupos_disponibles`, `componente`, ...)
+/// que se agregan o reemplazan sobre la base
+pub fn seccion_con(codigo: &str, n: &str, horario: &[&str], extra: Value) -> Seccion {
+    let mut base = json!({
+        "codigo": codigo,
+        "nombre": codigo,
+        "seccion": n,
+        "horario": horario,
+        "codigo_box": format!("{}-{}", codigo, n),
+    });
+    base.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    serde_json::from_value(base).unwrap()
+}
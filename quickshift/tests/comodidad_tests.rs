@@ -0,0 +1,102 @@
+use quickshift::algorithm::comodidad::{metricas, puntaje, rankear, ConfigComodidad, PerfilComodidad};
+use quickshift::models::Seccion;
+use serde_json::json;
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, horario: &[&str], profesor: &str) -> Seccion {
+    seccion_con(codigo, "1", horario, json!({"profesor": profesor}))
+}
+
+/// Tres días, empieza 08:30 el lunes y tiene una ventana de 2 horas el martes
+fn repartida() -> Vec<Seccion> {
+    vec![
+        seccion("CBM1000", &["LU 08:30 - 09:50"], "Soto"),
+        seccion("CBM1001", &["MA 10:00 - 11:20"], "Rojas"),
+        seccion("CIT1000", &["MA 13:20 - 14:40", "JU 11:30 - 12:50"], "Vera"),
+    ]
+}
+
+/// Dos días, 10 minutos de ventana el miércoles, desde las 11:30
+fn compacta() -> Vec<Seccion> {
+    vec![
+        seccion("CBM1000", &["MI 11:30 - 12:50"], "Soto"),
+        seccion("CBM1001", &["MI 13:00 - 14:20"], "Rojas"),
+        seccion("CIT1000", &["VI 11:30 - 12:50"], "Vera"),
+    ]
+}
+
+#[test]
+fn test_metricas_de_una_semana() {
+    let m = metricas(&repartida(), 600, None);
+    assert_eq!(m.minutos_ventana, 120);
+    assert_eq!(m.minutos_antes_inicio_ideal, 80);
+    assert_eq!(m.dias_en_campus, 3);
+    assert_eq!(m.inicio_por_dia.get("LU").map(String::as_str), Some("08:30"));
+    assert_eq!(m.inicio_por_dia.get("MA").map(String::as_str), Some("10:00"));
+
+    let config = ConfigComodidad::default();
+    // 120 min de ventana (2 h), 80 min temprano (1.33 h) y 3 días
+    assert_eq!(puntaje(&m, &config.pesos_efectivos()), -6.33);
+}
+
+#[test]
+fn test_rankear_por_perfil_y_pesos() {
+    let soluciones = vec![(repartida(), 900), (compacta(), 800)];
+
+    let ranking = rankear(soluciones.clone(), &ConfigComodidad::default(), None).unwrap();
+    assert_eq!(ranking[0].posicion_original, 1);
+    assert_eq!(ranking[0].total_score, 800);
+
+    // Solo pesan los profesores: ambas tienen a Vera, y el empate lo decide el score
+    let config: ConfigComodidad = serde_json::from_value(json!({
+        "perfil": "pocos_dias",
+        "pesos": {"ventanas": 0, "inicio_temprano": 0, "dias_en_campus": 0},
+        "profesores": {"habilitado": true, "profesores_preferidos": ["vera"]}
+    })).unwrap();
+    assert_eq!(config.perfil, PerfilComodidad::PocosDias);
+    let ranking = rankear(soluciones.clone(), &config, None).unwrap();
+    assert_eq!(ranking.iter().map(|r| r.posicion_original).collect::<Vec<_>>(), vec![0, 1]);
+    assert_eq!(ranking[0].metricas.secciones_profesor_preferido, 1);
+
+    // Temprano = antes de las 12:00: la compacta también madruga, pero menos
+    let config: ConfigComodidad = serde_json::from_value(json!({"perfil": "sin_madrugar", "hora_inicio_ideal": "12:00"})).unwrap();
+    let ranking = rankear(soluciones.clone(), &config, None).unwrap();
+    assert_eq!(ranking[0].posicion_original, 1);
+
+    let config: ConfigComodidad = serde_json::from_value(json!({"hora_inicio_ideal": "mediodía"})).unwrap();
+    assert!(rankear(soluciones, &config, None).is_err());
+    assert!(serde_json::from_value::<ConfigComodidad>(json!({"pesos": {"almuerzo": 1}})).is_err());
+}
+
+#[cfg(feature = "server")]
+#[actix_web::test]
+async fn test_rank_reordena_soluciones_recibidas() {
+    use actix_web::{test as atest, web, App};
+    let app = atest::init_service(
+        App::new().route("/rutacomoda/rank", web::post().to(quickshift::server_handlers::rutacomoda_rank_handler)),
+    ).await;
+
+    // Mezcla el formato de /solve (secciones sueltas) con el de /rutacritica/run
+    let body = json!({
+        "soluciones": [
+            {"total_score": 900, "secciones": repartida()},
+            {"total_score": 800, "secciones": compacta().into_iter().map(|s| json!({"seccion": s, "prioridad": 1})).collect::<Vec<_>>()},
+        ],
+        "comodidad": {"perfil": "sin_ventanas"}
+    });
+    let req = atest::TestRequest::post().uri("/rutacomoda/rank").set_json(&body).to_request();
+    let out: serde_json::Value = atest::call_and_read_body_json(&app, req).await;
+    assert_eq!(out["perfil"], "sin_ventanas");
+    assert_eq!(out["pesos"]["ventanas"], 3.0);
+    let soluciones = out["soluciones"].as_array().unwrap();
+    assert_eq!(soluciones[0]["posicion_original"], 1);
+    assert_eq!(soluciones[0]["metricas"]["minutos_ventana"], 10);
+    assert_eq!(soluciones[1]["total_score"], 900);
+
+    let req = atest::TestRequest::post().uri("/rutacomoda/rank")
+        .set_json(json!({"soluciones": [{"total_score": 1}]}))
+        .to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 400);
+}
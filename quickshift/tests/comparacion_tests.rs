@@ -0,0 +1,74 @@
+use quickshift::algorithm::comparacion::comparar;
+use quickshift::models::Seccion;
+use serde_json::json;
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, seccion: &str, horario: &[&str]) -> Seccion {
+    seccion_con(codigo, seccion, horario, json!({"nombre": format!("Ramo {}", codigo)}))
+}
+
+/// Lunes y martes, con una hora de ventana el lunes
+fn solucion_a() -> Vec<Seccion> {
+    vec![
+        seccion("CBM1000", "1", &["LU 08:30 - 09:50"]),
+        seccion("CBM1001", "1", &["LU 11:00 - 12:20"]),
+        seccion("CIT1000", "2", &["MA 08:30 - 09:50"]),
+    ]
+}
+
+/// Solo el lunes, sin ventanas
+fn solucion_b() -> Vec<Seccion> {
+    vec![
+        seccion("cbm1000", "1", &["LU 08:30 - 09:50"]),
+        seccion("CBM1001", "3", &["LU 10:00 - 11:20"]),
+        seccion("FIC1000", "1", &["LU 11:30 - 12:50"]),
+    ]
+}
+
+#[test]
+fn test_comparar_ramos_y_secciones() {
+    let c = comparar(&solucion_a(), Some(900), &solucion_b(), Some(850));
+    assert_eq!(c.iguales, vec!["CBM1000".to_string()]);
+    assert_eq!(c.solo_en_a.len(), 1);
+    assert_eq!((c.solo_en_a[0].codigo.as_str(), c.solo_en_a[0].secciones.clone()), ("CIT1000", vec!["2".to_string()]));
+    assert_eq!(c.solo_en_b.iter().map(|r| r.codigo.as_str()).collect::<Vec<_>>(), vec!["FIC1000"]);
+    assert_eq!(c.distinta_seccion.len(), 1);
+    assert_eq!(c.distinta_seccion[0].codigo, "CBM1001");
+    assert_eq!((c.distinta_seccion[0].secciones_a.clone(), c.distinta_seccion[0].secciones_b.clone()), (vec!["1".to_string()], vec!["3".to_string()]));
+
+    let fila = |m: &str| c.metricas.iter().find(|f| f.metrica == m).map(|f| (f.a, f.b, f.delta)).unwrap();
+    assert_eq!(fila("total_score"), (900.0, 850.0, -50.0));
+    assert_eq!(fila("total_gap_minutes"), (70.0, 20.0, -50.0));
+    assert_eq!(fila("days_on_campus"), (2.0, 1.0, -1.0));
+    assert_eq!(fila("compactness_pct"), (100.0, 100.0, 0.0));
+
+    // Sin score en un lado no hay fila de total_score
+    let c = comparar(&solucion_a(), Some(900), &solucion_b(), None);
+    assert!(c.metricas.iter().all(|f| f.metrica != "total_score"));
+}
+
+#[cfg(feature = "server")]
+#[actix_web::test]
+async fn test_handler_compara_secciones_dadas() {
+    use actix_web::{test as atest, web, App};
+    use quickshift::server_handlers::compare_solutions_handler;
+
+    let app = atest::init_service(App::new().route("/solutions/compare", web::post().to(compare_solutions_handler))).await;
+    let req = atest::TestRequest::post().uri("/solutions/compare").set_json(json!({
+        "a": {"secciones": solucion_a(), "total_score": 900},
+        "b": {"secciones": solucion_b()},
+    })).to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!(body["iguales"], json!(["CBM1000"]));
+    assert_eq!(body["solo_en_b"][0]["codigo"], "FIC1000");
+    assert_eq!(body["metricas_b"]["days_on_campus"], 1);
+    assert_eq!(body["metricas"][0]["metrica"], "total_gap_minutes");
+
+    // Un cuerpo que no es ni secciones ni solution_id es un error de deserialización
+    let req = atest::TestRequest::post().uri("/solutions/compare").set_json(json!({"a": {"indice": 0}, "b": {}})).to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 400);
+}
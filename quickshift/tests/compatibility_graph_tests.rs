@@ -0,0 +1,58 @@
+use quickshift::algorithm::clique::CompatibilityGraph;
+use quickshift::models::{Componente, Seccion};
+
+fn sec(codigo: &str, seccion: &str, horario: &[&str]) -> Seccion {
+    Seccion {
+        codigo: codigo.to_string(),
+        nombre: codigo.to_string(),
+        seccion: seccion.to_string(),
+        horario: horario.iter().map(|h| h.to_string()).collect(),
+        profesor: String::new(),
+        codigo_box: format!("{}-{}", codigo, seccion),
+        is_cfg: false,
+        is_electivo: false,
+        linea: None,
+        sala: None,
+        campus: None,
+        componente: Componente::Catedra,
+        bundle_id: None,
+        cupos_totales: None,
+        cupos_disponibles: None,
+        evaluaciones: Vec::new(),
+    }
+}
+
+#[test]
+fn test_compatibility_graph_edges() {
+    let secciones = vec![
+        sec("CIT1000", "1", &["LU 08:30-09:50"]),
+        sec("CIT1000", "2", &["MA 08:30-09:50"]),
+        sec("CBM1001", "1", &["LU 08:30-09:50"]),
+        sec("CBM1002", "1", &["MI 10:00-11:20"]),
+    ];
+    let g = CompatibilityGraph::build(&secciones);
+    assert_eq!(g.len(), 4);
+
+    let i0 = g.indice_de(&secciones[0]).unwrap();
+    let i1 = g.indice_de(&secciones[1]).unwrap();
+    let i2 = g.indice_de(&secciones[2]).unwrap();
+    let i3 = g.indice_de(&secciones[3]).unwrap();
+
+    // Mismo ramo: nunca compatibles
+    assert!(!g.compatibles(i0, i1));
+    // Mismo bloque horario: conflicto
+    assert!(!g.compatibles(i0, i2));
+    // Ramos distintos sin choque
+    assert!(g.compatibles(i1, i2));
+    assert!(g.compatibles(i0, i3));
+    assert!(g.compatibles(i3, i0));
+    assert_eq!(g.edge_count(), 4);
+}
+
+#[test]
+fn test_compatibility_graph_unknown_section() {
+    let secciones = vec![sec("CIT1000", "1", &["LU 08:30-09:50"])];
+    let g = CompatibilityGraph::build(&secciones);
+    assert!(g.indice_de(&sec("CIT9999", "1", &[])).is_none());
+    assert!(!g.compatibles(0, 5));
+}
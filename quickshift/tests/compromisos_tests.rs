@@ -0,0 +1,66 @@
+use quickshift::algorithm::clique::modificadores_score;
+use quickshift::algorithm::compromisos::*;
+use quickshift::api_json::normalizar_horarios;
+use quickshift::models::Seccion;
+use quickshift::scoring::ScoringConfig;
+
+mod common;
+
+fn seccion(codigo: &str, horario: &str) -> Seccion {
+    common::seccion(codigo, "1", &[horario])
+}
+
+fn params(compromisos: &str) -> quickshift::api_json::InputParams {
+    let compromisos: serde_json::Value = serde_json::from_str(compromisos).unwrap();
+    let mut p = common::params("MiMalla.xlsx", serde_json::json!({"compromisos": compromisos}));
+    normalizar_horarios(&mut p);
+    p
+}
+
+#[test]
+fn test_compromiso_hard_descarta_secciones_que_lo_pisan() {
+    let p = params(r#"[{"etiqueta":"Trabajo","dia":"TUE thu","inicio":"6 PM","fin":"9 PM"}]"#);
+    assert_eq!(p.compromisos[0].dia, "MA JU");
+    assert_eq!(p.compromisos[0].inicio, "18:00");
+
+    let choca = seccion("CIT1000", "JU 17:30 - 18:50");
+    let antes = seccion("CIT2000", "MA 16:30 - 18:00");
+    let otro_dia = seccion("CIT3000", "MI 18:00 - 19:20");
+    assert_eq!(choca_con(&choca, &p).map(|c| c.etiqueta.as_str()), Some("Trabajo"));
+    // tocarse en el borde no cuenta
+    assert!(choca_con(&antes, &p).is_none());
+    assert!(choca_con(&otro_dia, &p).is_none());
+}
+
+#[test]
+fn test_compromiso_soft_penaliza_una_vez() {
+    let p = params(r#"[{"etiqueta":"Fútbol","dia":"LU","inicio":"08:00","fin":"10:00","modo":"soft","peso":700},
+        {"etiqueta":"Gimnasio","dia":"VI","inicio":"08:00","fin":"10:00","modo":"soft"}]"#);
+    let scoring = ScoringConfig::default();
+    let a = seccion("CIT1000", "LU 08:30 - 09:50");
+    let b = seccion("CIT2000", "LU 09:00 - 10:20");
+    let libre = seccion("CIT3000", "MA 08:30 - 09:50");
+
+    // solo cuenta el compromiso soft, no hay descarte
+    assert!(choca_con(&a, &p).is_none());
+    assert_eq!(penalizacion([&a, &b].into_iter(), &p, &scoring), 700);
+    assert_eq!(penalizacion([&libre].into_iter(), &p, &scoring), 0);
+
+    let sol = vec![(a.clone(), 0), (libre.clone(), 0)];
+    assert_eq!(modificadores_score(&sol, &p, &scoring).compromisos, -700);
+    let viernes = vec![(seccion("CIT4000", "VI 08:30 - 09:50"), 0)];
+    assert_eq!(modificadores_score(&viernes, &p, &scoring).compromisos, -scoring.penalizacion_compromiso);
+}
+
+#[test]
+fn test_estado_compromisos_por_solucion() {
+    let p = params(r#"[{"etiqueta":"Trabajo","dia":"LU","inicio":"08:00","fin":"10:00","modo":"soft"},
+        {"etiqueta":"Natación","dia":"MI","inicio":"19:00","fin":"20:00"}]"#);
+    let sol = vec![seccion("CIT1000", "LU 08:30 - 09:50"), seccion("CIT2000", "MA 08:30 - 09:50")];
+    let estados = estado(&sol, &p);
+    assert_eq!(estados.len(), 2);
+    assert!(!estados[0].respetado);
+    assert_eq!(estados[0].secciones, vec!["CIT1000-1".to_string()]);
+    assert!(estados[1].respetado);
+    assert!(estados[1].secciones.is_empty());
+}
@@ -0,0 +1,111 @@
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::api_json::InputParams;
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+use common::seccion_con;
+
+// Física (id 2) tiene como co-requisito a Cálculo (id 1)
+fn ramos() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "Fisica I", "codigo": "CBF1000", "semestre": 1, "correquisitos_ids": [1]},
+        {"id": 3, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+fn seccion(codigo: &str, nombre: &str, horario: &str) -> Seccion {
+    seccion_con(codigo, "1", &[horario], serde_json::json!({"nombre": nombre}))
+}
+
+fn params(ramos_pasados: &[&str], solver: &str) -> InputParams {
+    common::params("MallaCorrequisitos", serde_json::json!({"ramos_pasados": ramos_pasados, "solver": solver}))
+}
+
+fn codigos(sol: &[(Seccion, i32)]) -> Vec<&str> {
+    sol.iter().map(|(s, _)| s.codigo.as_str()).collect()
+}
+
+#[test]
+fn test_correquisito_en_la_misma_solucion() {
+    let secciones = vec![
+        seccion("CBM1000", "Calculo I", "LU 08:30-09:50"),
+        seccion("CBF1000", "Fisica I", "MA 08:30-09:50"),
+        seccion("CIT1000", "Programacion", "MI 08:30-09:50"),
+    ];
+    for solver in ["greedy", "exact"] {
+        let soluciones = get_clique_max_pond_with_prefs(&secciones, &ramos(), &params(&[], solver));
+        assert!(!soluciones.is_empty(), "solver {}", solver);
+        // Física puede tomarse porque Cálculo va en la misma solución
+        assert_eq!(soluciones[0].0.len(), 3, "solver {}: {:?}", solver, codigos(&soluciones[0].0));
+        for (sol, _) in soluciones.iter() {
+            let c = codigos(sol);
+            assert!(!c.contains(&"CBF1000") || c.contains(&"CBM1000"), "solver {}: {:?}", solver, c);
+        }
+    }
+}
+
+#[test]
+fn test_correquisito_con_tope_de_horario() {
+    // Física y Cálculo chocan: Física solo es posible con Cálculo aprobado
+    let secciones = vec![
+        seccion("CBM1000", "Calculo I", "LU 08:30-09:50"),
+        seccion("CBF1000", "Fisica I", "LU 08:30-09:50"),
+        seccion("CIT1000", "Programacion", "MI 08:30-09:50"),
+    ];
+    for solver in ["greedy", "exact"] {
+        let soluciones = get_clique_max_pond_with_prefs(&secciones, &ramos(), &params(&[], solver));
+        assert!(!soluciones.is_empty(), "solver {}", solver);
+        for (sol, _) in soluciones.iter() {
+            assert!(!codigos(sol).contains(&"CBF1000"), "solver {}: {:?}", solver, codigos(sol));
+        }
+
+        let soluciones = get_clique_max_pond_with_prefs(&secciones, &ramos(), &params(&["CBM1000"], solver));
+        assert!(soluciones.iter().any(|(sol, _)| codigos(sol).contains(&"CBF1000")), "solver {}", solver);
+    }
+}
+
+#[cfg(feature = "server")]
+#[test]
+fn test_leer_correquisitos_columna_y_hoja() {
+    use quickshift::excel::leer_malla_excel_with_sheet;
+
+    let dir = std::env::temp_dir().join(format!("quickshift_correquisitos_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let ruta = dir.join("MallaCorreq.xlsx");
+
+    let mut libro = umya_spreadsheet::new_file();
+    let hoja = libro.get_sheet_mut(0);
+    let filas = [
+        ["ID", "Nombre", "Correquisitos"],
+        ["1", "Calculo I", ""],
+        ["2", "Fisica I", "1"],
+        ["3", "Laboratorio de Fisica", ""],
+        ["4", "Programacion", ""],
+    ];
+    for (i, fila) in filas.iter().enumerate() {
+        for (j, valor) in fila.iter().enumerate() {
+            let coord = format!("{}{}", (b'A' + j as u8) as char, i + 1);
+            hoja.get_cell_mut(coord).set_value(*valor);
+        }
+    }
+    // Hoja aparte: Laboratorio (3) se cursa junto a Física (2) y Cálculo (1)
+    let correq = libro.new_sheet("Correquisitos").unwrap();
+    for (coord, valor) in [("A1", "ID"), ("B1", "Correquisitos"), ("A2", "3"), ("B2", "2; 1")] {
+        correq.get_cell_mut(coord).set_value(valor);
+    }
+    umya_spreadsheet::writer::xlsx::write(&libro, &ruta).unwrap();
+
+    let ramos = leer_malla_excel_with_sheet(ruta.to_str().unwrap(), None).unwrap();
+    let por_id = |id: i32| ramos.values().find(|r| r.id == id).unwrap();
+    assert_eq!(por_id(2).correquisitos_ids, vec![1]);
+    let mut lab = por_id(3).correquisitos_ids.clone();
+    lab.sort();
+    assert_eq!(lab, vec![1, 2]);
+    assert!(por_id(1).correquisitos_ids.is_empty());
+    assert!(por_id(4).correquisitos_ids.is_empty());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
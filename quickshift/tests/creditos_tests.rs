@@ -0,0 +1,105 @@
+use quickshift::algorithm::clique::{get_clique_max_pond_with_prefs, CREDITOS_SCT_POR_DEFECTO};
+use quickshift::algorithm::en_memoria::{resolver_en_memoria, SolveEnMemoria};
+use quickshift::api_json::InputParams;
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+use common::seccion_con;
+
+fn ramos() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1, "creditos": 8},
+        {"id": 2, "nombre": "Algebra", "codigo": "CBM1001", "semestre": 1, "creditos": 8},
+        {"id": 3, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1, "creditos": 4},
+        {"id": 4, "nombre": "Quimica", "codigo": "CBQ1000", "semestre": 1},
+        {"id": 5, "nombre": "Fisica", "codigo": "CBF1000", "semestre": 1, "creditos": 3}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+fn secciones() -> Vec<Seccion> {
+    let dias = ["LU", "MA", "MI", "JU", "VI"];
+    ramos().values().enumerate()
+        .map(|(i, r)| seccion_con(&r.codigo, "1", &[&format!("{} 08:30-09:50", dias[i])], serde_json::json!({"nombre": r.nombre})))
+        .collect()
+}
+
+fn params(max_creditos: Option<i32>, solver: &str) -> InputParams {
+    common::params("MallaCreditos", serde_json::json!({"solver": solver, "max_creditos": max_creditos}))
+}
+
+fn creditos_de(sol: &[(Seccion, i32)]) -> i32 {
+    let ramos = ramos();
+    sol.iter()
+        .map(|(s, _)| ramos.values().find(|r| r.codigo == s.codigo).and_then(|r| r.creditos).unwrap_or(CREDITOS_SCT_POR_DEFECTO))
+        .sum()
+}
+
+#[test]
+fn test_sin_max_creditos_se_mantiene_el_tope_de_ramos() {
+    let soluciones = get_clique_max_pond_with_prefs(&secciones(), &ramos(), &params(None, "greedy"));
+    assert!(!soluciones.is_empty());
+    // Los 5 ramos son compatibles y caben bajo el tope de 6
+    assert_eq!(soluciones[0].0.len(), 5);
+}
+
+#[test]
+fn test_max_creditos_limita_la_suma_por_solucion() {
+    for solver in ["greedy", "exact"] {
+        let soluciones = get_clique_max_pond_with_prefs(&secciones(), &ramos(), &params(Some(15), solver));
+        assert!(!soluciones.is_empty(), "solver {}", solver);
+        for (sol, _) in soluciones.iter() {
+            assert!(creditos_de(sol) <= 15, "solver {}: {} SCT en {:?}", solver, creditos_de(sol),
+                    sol.iter().map(|(s, _)| &s.codigo).collect::<Vec<_>>());
+        }
+    }
+}
+
+/// 8 ramos de 1 SCT, todos compatibles: más que el tope de 6 ramos
+fn ramos_livianos() -> (HashMap<String, RamoDisponible>, Vec<Seccion>) {
+    let ramos: HashMap<String, RamoDisponible> = (1..=8)
+        .map(|i| serde_json::from_value::<RamoDisponible>(serde_json::json!({
+            "id": i, "nombre": format!("Ramo {}", i), "codigo": format!("TST10{:02}", i), "semestre": 1, "creditos": 1
+        })).unwrap())
+        .map(|r| (r.nombre.clone(), r))
+        .collect();
+    let horas = ["08:30-09:50", "10:00-11:20"];
+    let secciones: Vec<Seccion> = ramos.values().enumerate().map(|(i, r)| serde_json::from_value(serde_json::json!({
+        "codigo": r.codigo,
+        "nombre": r.nombre,
+        "seccion": "1",
+        "horario": [format!("{} {}", ["LU", "MA", "MI", "JU"][i % 4], horas[i / 4])],
+        "codigo_box": format!("{}-1", r.codigo),
+    })).unwrap()).collect();
+    (ramos, secciones)
+}
+
+#[test]
+fn test_max_creditos_reemplaza_el_tope_de_ramos() {
+    // Los 8 ramos suman 8 SCT: con `max_creditos` caben todos aunque pasen de 6
+    let (ramos, secciones) = ramos_livianos();
+    for solver in ["greedy", "exact"] {
+        let soluciones = get_clique_max_pond_with_prefs(&secciones, &ramos, &params(Some(100), solver));
+        assert!(!soluciones.is_empty(), "solver {}", solver);
+        assert_eq!(soluciones[0].0.len(), 8, "solver {}", solver);
+    }
+    // Sin `max_creditos` vuelve el tope de ramos
+    let soluciones = get_clique_max_pond_with_prefs(&secciones, &ramos, &params(None, "greedy"));
+    assert!(soluciones.iter().all(|(sol, _)| sol.len() <= 6));
+}
+
+#[test]
+fn test_max_creditos_llega_al_resultado_con_mas_de_6_ramos() {
+    // La selección final de `ruta` no descarta soluciones de más de 6 ramos
+    let (ramos, secciones) = ramos_livianos();
+    let soluciones = resolver_en_memoria(SolveEnMemoria {
+        params: params(Some(100), "greedy"),
+        ramos: ramos.into_values().collect(),
+        secciones,
+        prerequisitos: HashMap::new(),
+        equivalencias: HashMap::new(),
+    }).unwrap();
+    assert!(!soluciones.is_empty());
+    assert_eq!(soluciones[0].0.len(), 8);
+}
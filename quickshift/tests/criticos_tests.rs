@@ -0,0 +1,69 @@
+use quickshift::algorithm::pert::{ramos_criticos, red_pert_malla};
+use quickshift::models::RamoDisponible;
+use std::collections::HashMap;
+
+#[test]
+fn test_ramos_criticos_por_urgencia() {
+    // CBM1000 -> CBM1001 -> CBM1002 (cadena), CBM1000 -> FIS1000,
+    // CIT1000 -> CIT1001 y CIT1002 suelto
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1, "numb_correlativo": 1},
+        {"id": 2, "nombre": "Calculo II", "codigo": "CBM1001", "semestre": 2, "numb_correlativo": 2, "requisitos_ids": [1]},
+        {"id": 3, "nombre": "Calculo III", "codigo": "CBM1002", "semestre": 3, "numb_correlativo": 3, "requisitos_ids": [2]},
+        {"id": 4, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1, "numb_correlativo": 4},
+        {"id": 5, "nombre": "Fisica", "codigo": "FIS1000", "semestre": 2, "numb_correlativo": 5, "requisitos_ids": [1]},
+        {"id": 6, "nombre": "Programacion II", "codigo": "CIT1001", "semestre": 2, "numb_correlativo": 6, "requisitos_ids": [4]},
+        {"id": 7, "nombre": "Etica", "codigo": "CIT1002", "semestre": 2, "numb_correlativo": 7}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.codigo.clone(), r)).collect();
+
+    let criticos = ramos_criticos(&red_pert_malla(&ramos, None));
+    assert_eq!(criticos.semestres_minimos, 3);
+    assert_eq!(criticos.ruta_critica, vec!["CBM1000", "CBM1001", "CBM1002"]);
+
+    let orden: Vec<(&str, i32, bool)> = criticos.ramos.iter().map(|r| (r.codigo.as_str(), r.holgura, r.critico)).collect();
+    assert_eq!(orden, vec![
+        ("CBM1000", 0, true),
+        ("CBM1001", 0, true),
+        ("CBM1002", 0, true),
+        // Holgura 1: primero el que hay que empezar antes (menor LS)
+        ("CIT1000", 1, false),
+        // Empatan en todo: por código
+        ("CIT1001", 1, false),
+        ("FIS1000", 1, false),
+        ("CIT1002", 2, false),
+    ]);
+    let desbloquea = |codigo: &str| criticos.ramos.iter().find(|r| r.codigo == codigo).unwrap().desbloquea;
+    assert_eq!(desbloquea("CBM1000"), 3);
+    assert_eq!(desbloquea("CIT1000"), 1);
+    assert_eq!(desbloquea("CBM1002"), 0);
+}
+
+#[actix_web::test]
+async fn test_get_malla_criticos() {
+    use actix_web::{test as atest, web, App};
+    use quickshift::api_json::handlers::courses::malla_criticos_handler;
+
+    let app = atest::init_service(App::new().route("/malla/{id}/criticos", web::get().to(malla_criticos_handler))).await;
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/malla/Malla2020.xlsx/criticos").to_request()).await;
+    assert_eq!(resp.status(), 200);
+    let out: serde_json::Value = atest::read_body_json(resp).await;
+    let ramos = out["ramos"].as_array().unwrap();
+    assert!(!ramos.is_empty());
+    assert!(out["semestres_minimos"].as_i64().unwrap() > 0);
+
+    // Los críticos van primero y coinciden con la ruta crítica
+    let criticos: Vec<&str> = ramos.iter().take_while(|r| r["critico"] == true).map(|r| r["codigo"].as_str().unwrap()).collect();
+    assert!(!criticos.is_empty());
+    assert!(ramos[criticos.len()..].iter().all(|r| r["critico"] == false));
+    let mut ruta: Vec<&str> = out["ruta_critica"].as_array().unwrap().iter().map(|c| c.as_str().unwrap()).collect();
+    let mut criticos_ordenados = criticos.clone();
+    ruta.sort_unstable();
+    criticos_ordenados.sort_unstable();
+    assert_eq!(ruta, criticos_ordenados);
+    let holguras: Vec<i64> = ramos.iter().map(|r| r["holgura"].as_i64().unwrap()).collect();
+    assert!(holguras.windows(2).all(|w| w[0] <= w[1]));
+
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/malla/NoExiste_criticos.xlsx/criticos").to_request()).await;
+    assert!(resp.status().is_client_error());
+}
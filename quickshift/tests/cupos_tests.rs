@@ -0,0 +1,87 @@
+use quickshift::algorithm::clique::{get_clique_max_pond_with_prefs, modificadores_score};
+use quickshift::excel::leer_oferta_academica_excel;
+use quickshift::models::{Componente, RamoDisponible, Seccion};
+use quickshift::scoring::ScoringConfig;
+use std::collections::HashMap;
+
+mod common;
+use common::{params, seccion_con};
+
+fn seccion(codigo: &str, n: &str, horario: &str, disponibles: Option<u32>) -> Seccion {
+    seccion_con(codigo, n, &[horario], serde_json::json!({"cupos_totales": 40, "cupos_disponibles": disponibles}))
+}
+
+#[test]
+fn test_leer_cupos_de_la_oferta() {
+    let dir = std::env::temp_dir().join(format!("quickshift_cupos_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let ruta = dir.join("OfertaCupos.xlsx");
+
+    let mut libro = umya_spreadsheet::new_file();
+    let hoja = libro.get_sheet_mut(0);
+    let filas = [
+        ["Codigo", "Nombre", "Seccion", "Horario", "Profesor", "Cupos", "Inscritos"],
+        ["CBM1000", "Calculo I", "1", "LU 08:30 - 09:50", "Soto", "40", "40"],
+        ["CBM1000", "Calculo I", "2", "MA 08:30 - 09:50", "Rojas", "35", "20"],
+        ["CBF1000", "Fisica I", "1", "MI 08:30 - 09:50", "Vera", "", ""],
+        ["CBF1000_LA01", "Fisica I", "1", "JU 10:00 - 11:20", "Vera", "", ""],
+    ];
+    for (i, fila) in filas.iter().enumerate() {
+        for (j, valor) in fila.iter().enumerate() {
+            let coord = format!("{}{}", (b'A' + j as u8) as char, i + 1);
+            hoja.get_cell_mut(coord).set_value(*valor);
+        }
+    }
+    umya_spreadsheet::writer::xlsx::write(&libro, &ruta).unwrap();
+
+    let secciones = leer_oferta_academica_excel(ruta.to_str().unwrap()).unwrap();
+    let buscar = |codigo: &str, n: &str, componente: Componente| {
+        secciones.iter().find(|s| s.codigo == codigo && s.seccion == n && s.componente == componente).unwrap()
+    };
+    let llena = buscar("CBM1000", "1", Componente::Catedra);
+    assert_eq!((llena.cupos_totales, llena.cupos_disponibles), (Some(40), Some(0)));
+    assert!(llena.sin_cupos());
+    let libre = buscar("CBM1000", "2", Componente::Catedra);
+    assert_eq!((libre.cupos_totales, libre.cupos_disponibles), (Some(35), Some(15)));
+    let sin_dato = buscar("CBF1000", "1", Componente::Catedra);
+    assert_eq!((sin_dato.cupos_totales, sin_dato.cupos_disponibles), (None, None));
+    assert!(!sin_dato.sin_cupos());
+
+    // el laboratorio queda en el paquete de su cátedra
+    let lab = buscar("CBF1000", "1", Componente::Lab);
+    assert_eq!(lab.bundle_id.as_deref(), Some("CBF1000#1"));
+    assert_eq!(sin_dato.bundle_id, lab.bundle_id);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_penalizacion_y_filtro_solo_con_cupos() {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    // la sección 2 tiene mayor SS pero está llena
+    let secciones = vec![
+        seccion("CBM1000", "1", "LU 08:30 - 09:50", Some(10)),
+        seccion("CBM1000", "2", "MA 08:30 - 09:50", Some(0)),
+    ];
+
+    let p = params("MallaCupos", serde_json::json!({}));
+    let m = modificadores_score(&[(secciones[1].clone(), 0)], &p, &ScoringConfig::default());
+    assert_eq!(m.cupos, -1_000_000);
+    assert_eq!(m.total(), -1_000_000);
+
+    let soluciones = get_clique_max_pond_with_prefs(&secciones, &ramos, &p);
+    assert_eq!(soluciones[0].0[0].0.seccion, "1");
+
+    let p = params("MallaCupos", serde_json::json!({"filtros": {"solo_con_cupos": true}}));
+    let soluciones = get_clique_max_pond_with_prefs(&secciones, &ramos, &p);
+    assert!(!soluciones.is_empty());
+    assert!(soluciones.iter().all(|(sol, _)| sol.iter().all(|(s, _)| !s.sin_cupos())));
+
+    // sin penalización la sección llena vuelve a ganar por número de sección
+    let p = params("MallaCupos", serde_json::json!({"scoring": {"penalizacion_sin_cupos": 0}}));
+    let soluciones = get_clique_max_pond_with_prefs(&secciones, &ramos, &p);
+    assert_eq!(soluciones[0].0[0].0.seccion, "2");
+}
@@ -0,0 +1,60 @@
+use quickshift::datafiles::cache;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Copia una oferta real a un directorio propio del test
+fn oferta_temporal(nombre: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("quickshift_cache_{}_{}", nombre, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let destino = dir.join("OA20251.xlsx");
+    std::fs::copy("src/datafiles/OA20251.xlsx", &destino).unwrap();
+    destino
+}
+
+#[test]
+fn test_cache_reutiliza_e_invalida() {
+    let ruta = oferta_temporal("invalida");
+    let ruta_s = ruta.to_string_lossy().to_string();
+
+    let primera = cache::oferta(&ruta_s).unwrap();
+    assert!(!primera.is_empty());
+    assert!(Arc::ptr_eq(&primera, &cache::oferta(&ruta_s).unwrap()));
+
+    // La clave no depende de cómo se escriba la ruta
+    let relativa = ruta.parent().unwrap().join(".").join("OA20251.xlsx");
+    assert!(Arc::ptr_eq(&primera, &cache::oferta(&relativa.to_string_lossy()).unwrap()));
+
+    assert_eq!(cache::invalidar_archivo(&ruta), 1);
+    let recargada = cache::oferta(&ruta_s).unwrap();
+    assert!(!Arc::ptr_eq(&primera, &recargada));
+    assert_eq!(primera.len(), recargada.len());
+}
+
+#[test]
+fn test_cache_no_guarda_errores() {
+    let inexistente = std::env::temp_dir().join(format!("quickshift_cache_no_existe_{}.xlsx", std::process::id()));
+    assert!(cache::oferta(&inexistente.to_string_lossy()).is_err());
+    assert_eq!(cache::invalidar_archivo(&inexistente), 0);
+}
+
+#[cfg(feature = "server")]
+#[test]
+fn test_watcher_invalida_al_modificar() {
+    let ruta = oferta_temporal("watcher");
+    let ruta_s = ruta.to_string_lossy().to_string();
+    cache::iniciar_watcher(ruta.parent().unwrap()).unwrap();
+
+    let antes = cache::oferta(&ruta_s).unwrap();
+    std::fs::copy("src/datafiles/OA20251.xlsx", &ruta).unwrap();
+
+    let mut invalidada = false;
+    for _ in 0..50 {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        if !Arc::ptr_eq(&antes, &cache::oferta(&ruta_s).unwrap()) {
+            invalidada = true;
+            break;
+        }
+    }
+    assert!(invalidada, "el watcher no invalidó la entrada tras modificar el archivo");
+}
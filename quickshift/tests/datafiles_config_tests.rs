@@ -0,0 +1,75 @@
+#![cfg(feature = "server")]
+
+use actix_web::{test as atest, web, App};
+use quickshift::excel::config::{self, OrigenDatafiles};
+use quickshift::excel::get_datafiles_dir;
+use quickshift::server_handlers::{config_datafiles_get_handler, config_datafiles_put_handler};
+
+#[actix_web::test]
+async fn test_config_datafiles_get_put() {
+    let base = std::env::temp_dir().join(format!("quickshift-config-{}", std::process::id()));
+    let nuevo = base.join("datafiles");
+    std::fs::create_dir_all(&nuevo).unwrap();
+    let archivo = base.join("datafiles_config.toml");
+    unsafe {
+        std::env::set_var("DATAFILES_CONFIG_FILE", &archivo);
+        std::env::set_var("ADMIN_TOKEN", "secreto");
+        std::env::remove_var("DATAFILES_ROOT");
+        std::env::remove_var("GA_DATAFILES_DIR");
+    }
+
+    let app = atest::init_service(App::new()
+        .route("/config/datafiles", web::get().to(config_datafiles_get_handler))
+        .route("/config/datafiles", web::put().to(config_datafiles_put_handler))).await;
+
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/config/datafiles").to_request()).await;
+    assert_eq!(resp.status(), 401);
+
+    let put = |dir: &std::path::Path| atest::TestRequest::put().uri("/config/datafiles")
+        .insert_header(("X-Admin-Token", "secreto"))
+        .set_json(serde_json::json!({"dir": dir}))
+        .to_request();
+
+    // Sin raíz configurada no se puede cambiar el directorio
+    assert_eq!(atest::call_service(&app, put(&nuevo)).await.status(), 400);
+    unsafe {
+        std::env::set_var("DATAFILES_ROOT", &base);
+    }
+    // Fuera de la raíz tampoco, aunque se llegue con `..`
+    assert_eq!(atest::call_service(&app, put(std::path::Path::new("/etc"))).await.status(), 400);
+    assert_eq!(atest::call_service(&app, put(&nuevo.join("../.."))).await.status(), 400);
+    assert!(!archivo.exists());
+
+    // Un directorio que no existe no se acepta ni se persiste
+    let req = atest::TestRequest::put().uri("/config/datafiles")
+        .insert_header(("X-Admin-Token", "secreto"))
+        .set_json(serde_json::json!({"dir": base.join("no_existe")}))
+        .to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    assert!(!archivo.exists());
+
+    let req = atest::TestRequest::put().uri("/config/datafiles")
+        .insert_header(("X-Admin-Token", "secreto"))
+        .set_json(serde_json::json!({"dir": nuevo}))
+        .to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let out: serde_json::Value = atest::read_body_json(resp).await;
+    let canonico = nuevo.canonicalize().unwrap();
+    assert_eq!(out["origen"], "configuracion");
+    assert_eq!(out["dir"], canonico.to_string_lossy().as_ref());
+    assert_eq!(get_datafiles_dir(), canonico);
+    assert_eq!(config::leer_config(&archivo), Some(canonico.clone()));
+
+    let req = atest::TestRequest::get().uri("/config/datafiles").insert_header(("X-Admin-Token", "secreto")).to_request();
+    let out: serde_json::Value = atest::read_body_json(atest::call_service(&app, req).await).await;
+    assert_eq!(out["dir"], canonico.to_string_lossy().as_ref());
+    assert_eq!(out["existe"], true);
+
+    // Al volver a resolver (como en un reinicio) gana el archivo persistido
+    let recargada = config::recargar();
+    assert_eq!((recargada.dir.clone(), recargada.origen), (canonico, OrigenDatafiles::Configuracion));
+
+    let _ = std::fs::remove_dir_all(&base);
+}
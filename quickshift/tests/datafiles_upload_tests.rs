@@ -0,0 +1,118 @@
+use quickshift::excel::deteccion::{categoria_por_nombre, detectar_tipo, TipoDatafile};
+use std::path::Path;
+
+#[test]
+fn test_detectar_tipo_por_contenido() {
+    let tipo = |nombre: &str| detectar_tipo(&Path::new("src/datafiles").join(nombre)).unwrap().tipo;
+    assert_eq!(tipo("MC2020.xlsx"), TipoDatafile::Malla);
+    assert_eq!(tipo("Malla2020.xlsx"), TipoDatafile::Malla);
+    assert_eq!(tipo("MiMalla.xlsx"), TipoDatafile::Malla);
+    assert_eq!(tipo("OA20251.xlsx"), TipoDatafile::Oferta);
+    assert_eq!(tipo("CFG20251.xlsx"), TipoDatafile::Oferta);
+    assert_eq!(tipo("PA20251.xlsx"), TipoDatafile::Porcentajes);
+    assert!(detectar_tipo(Path::new("src/datafiles/MC2020_corregido_mapping.csv")).is_err());
+}
+
+#[test]
+fn test_categoria_por_nombre() {
+    assert_eq!(categoria_por_nombre("MC2026.xlsx"), Some(TipoDatafile::Malla));
+    assert_eq!(categoria_por_nombre("Malla2026.xlsx"), Some(TipoDatafile::Malla));
+    assert_eq!(categoria_por_nombre("OA20261.xlsx"), Some(TipoDatafile::Oferta));
+    assert_eq!(categoria_por_nombre("CFG20261.xlsx"), Some(TipoDatafile::Oferta));
+    assert_eq!(categoria_por_nombre("PA20261.xlsx"), Some(TipoDatafile::Porcentajes));
+    assert_eq!(categoria_por_nombre("horario.xlsx"), None);
+}
+
+#[cfg(feature = "server")]
+#[test]
+fn test_guardar_datafile_valida_antes_de_guardar() {
+    use quickshift::api_json::handlers::datafiles::{archivos_desde_json, guardar_datafile, revisar_datafile};
+    use base64::Engine;
+
+    let dir = std::env::temp_dir().join(format!("quickshift_upload_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let malla = std::fs::read("src/datafiles/MC2020.xlsx").unwrap();
+    let oferta = std::fs::read("src/datafiles/OA20251.xlsx").unwrap();
+
+    // Subida en JSON/base64 (también como data URL)
+    let body = serde_json::json!({"archivos": [
+        {"nombre": "MC2026.xlsx", "contenido_base64": base64::engine::general_purpose::STANDARD.encode(&malla)},
+        {"nombre": "OA20261.xlsx", "contenido_base64": format!("data:application/vnd.ms-excel;base64,{}", base64::engine::general_purpose::STANDARD.encode(&oferta))},
+    ]});
+    let archivos = archivos_desde_json(body.to_string().as_bytes()).unwrap();
+    assert_eq!(archivos.len(), 2);
+    assert_eq!(archivos[0].1, malla);
+    assert_eq!(archivos[1].1, oferta);
+    assert!(archivos_desde_json(br#"{"nombre": "MC2026.xlsx", "contenido_base64": "%%%"}"#).is_err());
+
+    // Dry-run: reporte de la malla sin dejar archivos
+    let (v, reporte) = revisar_datafile(&dir, "MC2026.xlsx", &malla, None).unwrap();
+    assert_eq!(v.tipo, TipoDatafile::Malla);
+    let reporte = reporte.unwrap();
+    assert_eq!(reporte.archivo, "MC2026.xlsx");
+    assert_eq!(reporte.filas_leidas, v.filas);
+    assert!(revisar_datafile(&dir, "OA20261.xlsx", &oferta, None).unwrap().1.is_none());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    let v = guardar_datafile(&dir, "MC2026.xlsx", &malla).unwrap();
+    assert_eq!(v.tipo, TipoDatafile::Malla);
+    assert!(v.filas > 0);
+    assert!(dir.join("MC2026.xlsx").is_file());
+
+    // Contenido y nombre no coinciden: no se guarda nada
+    let err = guardar_datafile(&dir, "MC2027.xlsx", &oferta).unwrap_err();
+    assert!(err.contains("oferta"), "{}", err);
+    assert!(!dir.join("MC2027.xlsx").exists());
+
+    assert!(guardar_datafile(&dir, "../OA20261.xlsx", &oferta).is_err());
+    assert!(guardar_datafile(&dir, "OA20261.csv", &oferta).is_err());
+    assert!(guardar_datafile(&dir, "OA20261.xlsx", b"no es un excel").is_err());
+
+    // Solo queda el archivo válido (sin temporales)
+    let nombres: Vec<String> = std::fs::read_dir(&dir).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(nombres, vec!["MC2026.xlsx".to_string()]);
+}
+
+#[cfg(feature = "server")]
+#[actix_web::test]
+async fn test_descarga_y_borrado_rechazan_rutas_fuera_del_directorio() {
+    use actix_web::{test as atest, web, App};
+    use quickshift::api_json::handlers::datafiles::{datafiles_delete_handler, datafiles_download_handler};
+
+    let app = atest::init_service(App::new()
+        .route("/datafiles/download", web::get().to(datafiles_download_handler))
+        .route("/datafiles", web::delete().to(datafiles_delete_handler))).await;
+    // Una ruta absoluta reemplazaría al directorio de datafiles en `join`
+    for name in ["%2Fetc%2Fpasswd", "..%2FCargo.toml", "sub%2F..%2F..%2FCargo.toml", "."] {
+        let req = atest::TestRequest::get().uri(&format!("/datafiles/download?name={}", name)).to_request();
+        assert_eq!(atest::call_service(&app, req).await.status(), 400, "{}", name);
+        let req = atest::TestRequest::delete().uri(&format!("/datafiles?name={}", name)).to_request();
+        assert_eq!(atest::call_service(&app, req).await.status(), 400, "{}", name);
+    }
+    let req = atest::TestRequest::get().uri("/datafiles/download?name=MC2020.xlsx").to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 200);
+}
+
+#[cfg(feature = "server")]
+#[actix_web::test]
+async fn test_oferta_de_validacion_se_busca_solo_en_el_directorio_de_datafiles() {
+    use actix_web::{test as atest, web, App};
+    use quickshift::api_json::handlers::datafiles::{datafiles_upload_handler, datafiles_validate_handler};
+
+    let app = atest::init_service(App::new()
+        .route("/datafiles/validate", web::get().to(datafiles_validate_handler))
+        .route("/datafiles/upload", web::post().to(datafiles_upload_handler))).await;
+    let subida = serde_json::json!({"nombre": "MC2026.xlsx", "contenido_base64": ""});
+    for oferta in ["..%2FCargo.toml", "..%2Fsrc%2Fdatafiles%2FOA20251.xlsx", "%2Fetc%2Fpasswd.xlsx", "sub%2FOA20251.xlsx", "NoExiste.xlsx"] {
+        let req = atest::TestRequest::get().uri(&format!("/datafiles/validate?malla=MC2020.xlsx&oferta={}", oferta)).to_request();
+        assert_eq!(atest::call_service(&app, req).await.status(), 400, "{}", oferta);
+        let req = atest::TestRequest::post().uri(&format!("/datafiles/upload?dry_run=1&oferta={}", oferta))
+            .set_json(&subida).to_request();
+        assert_eq!(atest::call_service(&app, req).await.status(), 400, "{}", oferta);
+    }
+    // Un nombre simple se resuelve dentro del directorio de datafiles
+    let req = atest::TestRequest::get().uri("/datafiles/validate?malla=MC2020.xlsx&oferta=OA20251.xlsx").to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 200);
+}
@@ -0,0 +1,79 @@
+use quickshift::algorithm::clique::{get_all_clique_combinations_hasta, get_all_clique_combinations_with_pert, get_clique_max_pond_with_prefs_cached};
+use quickshift::algorithm::deadline::Deadline;
+use quickshift::api_json::InputParams;
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::HashMap;
+use std::time::Duration;
+
+mod common;
+use common::{params, seccion_con};
+
+fn datos() -> (HashMap<String, RamoDisponible>, Vec<Seccion>, InputParams) {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "Algebra", "codigo": "CBM1001", "semestre": 1},
+        {"id": 3, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+        {"id": 4, "nombre": "Quimica", "codigo": "CBQ1000", "semestre": 1}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    let dias = ["LU", "MA", "MI", "JU"];
+    let secciones: Vec<Seccion> = ramos.values().enumerate().flat_map(|(i, r)| {
+        (1..=2).map(move |k| {
+            let horario = format!("{} {}", dias[i], if k == 1 { "08:30-09:50" } else { "10:00-11:20" });
+            seccion_con(&r.codigo, &k.to_string(), &[&horario], serde_json::json!({"nombre": r.nombre}))
+        })
+    }).collect();
+    let params = params("MallaDeadline", serde_json::json!({}));
+    (ramos, secciones, params)
+}
+
+#[test]
+fn test_deadline_vence_cancela_y_comparte_estado() {
+    let sin_limite = Deadline::sin_limite();
+    assert!(!sin_limite.vencido());
+    assert_eq!(sin_limite.restante(), None);
+
+    let largo = Deadline::en(Duration::from_secs(60));
+    assert!(!largo.vencido() && !largo.alcanzado());
+    let copia = largo.clone();
+    {
+        let _guardia = copia.cancelar_al_soltar();
+    }
+    assert!(largo.vencido(), "la guardia cancela todas las copias");
+    assert!(largo.alcanzado() && copia.alcanzado());
+
+    let vencido = Deadline::en(Duration::ZERO);
+    assert!(!vencido.alcanzado(), "solo se marca parcial cuando un bucle lo consulta");
+    assert!(vencido.vencido());
+    assert_eq!(vencido.restante(), Some(Duration::ZERO));
+
+    let params = params("M", serde_json::json!({"timeout_ms": 5000}));
+    let restante = Deadline::desde_params(&params).restante().unwrap();
+    assert!(restante <= Duration::from_millis(5000) && restante > Duration::from_millis(4000));
+}
+
+#[test]
+fn test_solver_corta_al_vencer_y_devuelve_parciales() {
+    let (ramos, secciones, params) = datos();
+
+    // Sin límite: mismo resultado que la API sin deadline y sin marca de parcial
+    let completo = Deadline::sin_limite();
+    let todas = get_all_clique_combinations_hasta(&secciones, &ramos, &params, 6, 5000, &completo);
+    assert_eq!(todas.len(), get_all_clique_combinations_with_pert(&secciones, &ramos, &params, 6, 5000).len());
+    assert_eq!(todas.iter().filter(|(s, _)| s.len() == 4).count(), 16);
+    assert!(!completo.alcanzado());
+
+    // Deadline ya vencido: la enumeración no explora y queda marcada como parcial
+    let vencido = Deadline::en(Duration::ZERO);
+    let parciales = get_all_clique_combinations_hasta(&secciones, &ramos, &params, 6, 5000, &vencido);
+    assert!(parciales.len() < todas.len());
+    assert!(vencido.alcanzado());
+
+    let greedy_completo = get_clique_max_pond_with_prefs_cached(&secciones, &ramos, &params, None, &Deadline::sin_limite());
+    assert!(!greedy_completo.is_empty());
+    let cancelado = Deadline::sin_limite();
+    cancelado.cancelar();
+    let greedy_cancelado = get_clique_max_pond_with_prefs_cached(&secciones, &ramos, &params, None, &cancelado);
+    assert!(greedy_cancelado.len() < greedy_completo.len());
+    assert!(cancelado.alcanzado());
+}
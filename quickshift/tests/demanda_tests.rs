@@ -0,0 +1,70 @@
+use quickshift::algorithm::cohorte::{demanda_por_seccion, proyectar_demanda, EstadoCupos};
+use quickshift::models::Seccion;
+use serde_json::json;
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, n: &str, componente: &str, disponibles: Option<u32>) -> Seccion {
+    seccion_con(codigo, n, &["LU 08:30 - 09:50"], json!({
+        "codigo_box": format!("{}-{}-{}", codigo, componente, n),
+        "componente": componente,
+        "cupos_totales": disponibles.map(|_| 40),
+        "cupos_disponibles": disponibles,
+    }))
+}
+
+#[test]
+fn test_proyectar_demanda_contra_cupos() {
+    let fisica = vec![seccion("CBF1000", "1", "catedra", Some(10)), seccion("CBF1000", "1", "lab", Some(2))];
+    let calculo = vec![seccion("CBM1000", "1", "catedra", Some(4))];
+    let quimica = vec![seccion("CBQ1000", "1", "catedra", None)];
+    let horarios = [
+        [fisica.clone(), calculo.clone(), quimica.clone()].concat(),
+        [fisica.clone(), calculo.clone()].concat(),
+        [fisica.clone(), calculo.clone()].concat(),
+        vec![seccion("CIT1000", "2", "catedra", Some(30))],
+    ];
+    let demanda = demanda_por_seccion(horarios.iter().map(|h| h.as_slice()));
+    // el paquete cuenta una vez por estudiante y tiene el menor cupo de sus componentes
+    let fis = demanda.iter().find(|d| d.codigo == "CBF1000").unwrap();
+    assert_eq!((fis.estudiantes, fis.cupos_disponibles), (3, Some(2)));
+
+    let proyeccion = proyectar_demanda(&demanda, 1.0, 0.7);
+    let resumen: Vec<(&str, u32, u32, EstadoCupos)> = proyeccion.iter()
+        .map(|p| (p.codigo.as_str(), p.inscritos_esperados, p.excedente, p.estado))
+        .collect();
+    assert_eq!(resumen, vec![
+        ("CBF1000", 3, 1, EstadoCupos::Desborda),
+        ("CBM1000", 3, 0, EstadoCupos::EnRiesgo),
+        ("CIT1000", 1, 0, EstadoCupos::Holgada),
+        ("CBQ1000", 1, 0, EstadoCupos::SinDatos),
+    ]);
+    assert_eq!(proyeccion[1].ocupacion, Some(0.75));
+
+    // una cohorte que es la décima parte de los estudiantes
+    let proyeccion = proyectar_demanda(&demanda, 10.0, 0.9);
+    let cit = proyeccion.iter().find(|p| p.codigo == "CIT1000").unwrap();
+    assert_eq!((cit.inscritos_esperados, cit.estado), (10, EstadoCupos::Holgada));
+    assert_eq!(proyeccion.iter().filter(|p| p.estado == EstadoCupos::Desborda).count(), 2);
+    assert_eq!(serde_json::to_value(&proyeccion[0]).unwrap()["estado"], "desborda");
+}
+
+#[cfg(feature = "server")]
+#[actix_web::test]
+async fn test_simular_demanda_valida_parametros() {
+    use actix_web::{test as atest, web, App};
+    use quickshift::server_handlers::simular_demanda_handler;
+
+    let app = atest::init_service(App::new().route("/simulate/demanda", web::post().to(simular_demanda_handler))).await;
+    let body = json!([{"email": "a@example.com", "malla": "NoExiste_demanda.xlsx"}]);
+    let resp = atest::call_service(&app, atest::TestRequest::post().uri("/simulate/demanda?escala=0").set_json(&body).to_request()).await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = atest::call_service(&app, atest::TestRequest::post().uri("/simulate/demanda?umbral_riesgo=0,8").set_json(&body).to_request()).await;
+    assert_eq!(resp.status(), 200);
+    let out: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!(out["umbral_riesgo"], 0.8);
+    assert_eq!(out["con_error"], json!(["a@example.com"]));
+    assert_eq!(out["secciones"], json!([]));
+}
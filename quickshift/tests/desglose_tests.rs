@@ -0,0 +1,64 @@
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::algorithm::desglose::{desglosar, relajaciones};
+use quickshift::models::RamoDisponible;
+use std::collections::HashMap;
+
+mod common;
+use common::{params, seccion};
+
+fn ramos() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1, "dificultad": 60.0},
+        {"id": 2, "nombre": "CBM1001", "codigo": "CBM1001", "semestre": 1}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+#[test]
+fn test_desglose_suma_el_score_del_clique() {
+    let secciones = vec![
+        seccion("CBM1000", "1", &["LU 08:30 - 09:50"]),
+        seccion("CBM1001", "1", &["LU 14:30 - 15:50"]),
+    ];
+    let p = params("MallaDesglose", serde_json::json!({"ramos_prioritarios": ["CBM1001"], "optimizations": ["minimize-gaps"]}));
+    let sols = get_clique_max_pond_with_prefs(&secciones, &ramos(), &p);
+    let (sol, score) = &sols[0];
+    assert_eq!(sol.len(), 2);
+
+    let d = desglosar(sol, *score, &p, &ramos());
+    assert_eq!(d.total, *score);
+    assert_eq!(d.modificadores.ramos_prioritarios, 100_000_000);
+    // 280 min de ventana entre 09:50 y 14:30
+    assert_eq!(d.modificadores.gaps, -280 * 100);
+    assert_eq!(d.modificadores.compactacion, 0);
+    assert_eq!(d.prioridad_base + d.modificadores.total(), *score);
+    assert!(d.relajaciones.is_empty());
+
+    let cbm1000 = d.dificultad.iter().find(|r| r.codigo == "CBM1000").unwrap();
+    assert_eq!(cbm1000.porcentaje_aprobacion, Some(60.0));
+    assert!((cbm1000.probabilidad_aprobar - 0.6).abs() < 1e-9);
+    assert_eq!(d.dificultad.iter().find(|r| r.codigo == "CBM1001").unwrap().porcentaje_aprobacion, None);
+
+    // CBM1000 sin ruta crítica ni holgura: "00 10 60 01"
+    let pri = d.prioridades.iter().find(|p| p.codigo == "CBM1000").unwrap();
+    assert_eq!((pri.prioridad.critico, pri.prioridad.uu, pri.prioridad.kk, pri.prioridad.ss), (false, 10, 60, 1));
+    assert_eq!(d.prioridades.iter().map(|p| p.score).sum::<i64>(), d.prioridad_base);
+
+    let json = serde_json::to_value(&d).unwrap();
+    assert_eq!(json["ramos_prioritarios"], 100_000_000);
+    assert!(json.get("modificadores").is_none());
+    assert!(json["prioridades"][0].get("kk").is_some());
+}
+
+#[test]
+fn test_relajaciones_lista_filtros_incumplidos() {
+    let sol = vec![(seccion("CBM1000", "1", &["VI 08:30 - 09:50"]), 0)];
+    let p = params("MallaDesglose", serde_json::json!({
+        "horarios_prohibidos": ["VI 08:00 - 10:00"],
+        "filtros": {"dias_horarios_libres": {"habilitado": true, "franjas_prohibidas": [{"dia": "VI", "inicio": "09:00", "fin": "12:00"}]}}
+    }));
+    assert_eq!(relajaciones(&sol, &p), vec!["horarios_prohibidos", "dias_horarios_libres"]);
+
+    let cumple = vec![(seccion("CBM1000", "1", &["LU 08:30 - 09:50"]), 0)];
+    assert!(relajaciones(&cumple, &p).is_empty());
+}
@@ -9,7 +9,7 @@
 //! - Top 50 soluciones con todos los empates mostrados
 //! - 100+ ejecuciones idénticas sin variación
 
-use quickshift::models::{Seccion, RamoDisponible};
+use quickshift::models::{Componente, Seccion, RamoDisponible};
 use quickshift::api_json::InputParams;
 use std::collections::HashMap;
 
@@ -58,17 +58,8 @@ fn test_determinism_100_runs() {
     // Parámetros de búsqueda
     let params = InputParams {
         email: "test@example.com".to_string(),
-        ramos_pasados: Vec::new(),
-        ramos_prioritarios: Vec::new(),
-        horarios_preferidos: Vec::new(),
-        horarios_prohibidos: Vec::new(),
         malla: "MC2020moded.xlsx".to_string(),
-        anio: None,
-        sheet: None,
-        student_ranking: None,
-        ranking: None,
-        filtros: None,
-        optimizations: Vec::new(),
+        ..Default::default()
     };
     
     // ============================================================================
@@ -206,9 +197,12 @@ fn create_demo_ramos() -> HashMap<String, RamoDisponible> {
                 numb_correlativo: i as i32,
                 critico: true,
                 requisitos_ids: Vec::new(),
+                correquisitos_ids: Vec::new(),
                 dificultad: Some(50.0),
                 electivo: false,
                 semestre: Some(sem as i32),
+                creditos: None,
+                tags: Vec::new(),
             });
         }
     }
@@ -232,6 +226,14 @@ fn create_demo_secciones() -> Vec<Seccion> {
                     codigo_box: format!("BOX_S{}_{}_SEC{}", sem, i, sec),
                     is_cfg: false,
                     is_electivo: false,
+                    linea: None,
+                    sala: None,
+                    campus: None,
+                    componente: Componente::Catedra,
+                    bundle_id: None,
+                    cupos_totales: None,
+                    cupos_disponibles: None,
+                    evaluaciones: Vec::new(),
                 });
             }
         }
@@ -0,0 +1,66 @@
+use quickshift::algorithm::diagnostico_grafo::DiagnosticoGrafo;
+use quickshift::algorithm::en_memoria::SolveEnMemoria;
+use quickshift::algorithm::engine::EnMemoria;
+use quickshift::algorithm::ruta::diagnosticar_grafo;
+use quickshift::algorithm::SchedulePlanner;
+use serde_json::json;
+
+fn diagnostico(secciones_extra: serde_json::Value) -> DiagnosticoGrafo {
+    let mut secciones = json!([
+        {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["LU 08:30-09:50"], "codigo_box": "CBM1000-1"},
+        {"codigo": "CBM1001", "nombre": "Calculo II", "seccion": "1", "horario": ["MA 08:30-09:50"], "codigo_box": "CBM1001-1"},
+        {"codigo": "CBM1001", "nombre": "Calculo II", "seccion": "2", "horario": ["MI 08:30-09:50"], "codigo_box": "CBM1001-2"},
+        {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "1", "horario": ["MI 10:00-11:20"], "codigo_box": "CIT1000-1"},
+        {"codigo": "CIT2000", "nombre": "Taller", "seccion": "1", "horario": ["JU 10:00-11:20"], "codigo_box": "CIT2000-1"}
+    ]);
+    secciones.as_array_mut().unwrap().extend(secciones_extra.as_array().unwrap().clone());
+    let body = json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": ["CBM1000"],
+        "ramos_prioritarios": [],
+        "malla": "MallaEnMemoria",
+        "ramos": [
+            {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+            {"id": 2, "nombre": "Calculo II", "codigo": "CBM1001", "requisitos_ids": [1], "semestre": 2},
+            {"id": 3, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+            {"id": 4, "nombre": "Taller", "codigo": "CIT2000", "semestre": 2}
+        ],
+        "secciones": secciones
+    });
+    let SolveEnMemoria { mut params, ramos, secciones, prerequisitos, equivalencias } = serde_json::from_value(body).unwrap();
+    let planner = EnMemoria { ramos, secciones, prerequisitos, equivalencias };
+    let datos = planner.preparar(&mut params).unwrap();
+    diagnosticar_grafo(&datos, &params).unwrap()
+}
+
+#[test]
+fn test_grafo_conexo_con_grados() {
+    let d = diagnostico(json!([]));
+    // Cálculo I ya está aprobado: quedan 4 secciones
+    assert_eq!(d.secciones_viables, 4);
+    assert_eq!(d.secciones.len(), 4);
+    // Las dos de Cálculo II no se unen entre sí; el resto de los pares sí
+    assert_eq!(d.aristas, 5);
+    let grado = |caja: &str| d.secciones.iter().find(|n| n.seccion.codigo_box == caja).map(|n| n.grado);
+    assert_eq!(grado("CBM1001-1"), Some(2));
+    assert_eq!(grado("CIT1000-1"), Some(3));
+    assert_eq!(d.componentes.len(), 1);
+    assert_eq!(d.componentes[0].secciones, 4);
+    assert_eq!(d.componentes[0].ramos, vec!["CBM1001", "CIT1000", "CIT2000"]);
+    assert!(d.aisladas.is_empty());
+}
+
+#[test]
+fn test_seccion_que_choca_con_todo_queda_aislada() {
+    let d = diagnostico(json!([
+        {"codigo": "CIT2000", "nombre": "Taller", "seccion": "2",
+         "horario": ["MA 08:30-09:50", "MI 08:30-09:50", "MI 10:00-11:20"], "codigo_box": "CIT2000-2"}
+    ]));
+    assert_eq!(d.aristas, 5);
+    assert_eq!(d.aisladas, vec!["CIT2000-2"]);
+    // La componente grande va primero y el nodo aislado apunta a la suya
+    let tamanos: Vec<usize> = d.componentes.iter().map(|c| c.secciones).collect();
+    assert_eq!(tamanos, vec![4, 1]);
+    let aislada = d.secciones.iter().find(|n| n.seccion.codigo_box == "CIT2000-2").unwrap();
+    assert_eq!((aislada.grado, aislada.componente), (0, 1));
+}
@@ -0,0 +1,66 @@
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::algorithm::diversidad::{diferencia_secciones, diversificar};
+use quickshift::api_json::InputParams;
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::{HashMap, HashSet};
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, k: usize, horario: &str) -> Seccion {
+    // Mismo número de sección en todas: la prioridad solo depende del ramo
+    seccion_con(codigo, "1", &[horario], serde_json::json!({"codigo_box": format!("{}-{}", codigo, k)}))
+}
+
+fn solucion(boxes: &[(&str, usize)]) -> Vec<(Seccion, i32)> {
+    boxes.iter().map(|(c, k)| (seccion(c, *k, "LU 08:30-09:50"), 0)).collect()
+}
+
+#[test]
+fn test_diversificar_separa_soluciones_parecidas() {
+    let soluciones = vec![
+        (solucion(&[("A", 1), ("B", 1), ("C", 1)]), 30),
+        (solucion(&[("A", 1), ("B", 1), ("C", 2)]), 29),
+        (solucion(&[("A", 2), ("B", 2), ("C", 1)]), 28),
+        (solucion(&[("A", 2), ("B", 2), ("C", 2)]), 27),
+    ];
+    assert_eq!(diferencia_secciones(&soluciones[0].0, &soluciones[1].0), 1);
+    assert_eq!(diferencia_secciones(&soluciones[0].0, &soluciones[3].0), 3);
+
+    let orden: Vec<i64> = diversificar(soluciones.clone(), 2).iter().map(|(_, t)| *t).collect();
+    // 29 difiere en 1 sección de 30; 28 difiere en 2 de 30; 27 solo en 1 de 28
+    assert_eq!(orden, vec![30, 28, 29, 27]);
+    // Sin K (o K = 0) se conserva el orden
+    let igual: Vec<i64> = diversificar(soluciones, 0).iter().map(|(_, t)| *t).collect();
+    assert_eq!(igual, vec![30, 29, 28, 27]);
+}
+
+fn params(seed: Option<u64>) -> InputParams {
+    common::params("MallaDiversidad", serde_json::json!({"diversity_seed": seed}))
+}
+
+#[test]
+fn test_diversity_seed_es_determinista_y_cambia_desempates() {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "CBM1001", "codigo": "CBM1001", "semestre": 1},
+        {"id": 3, "nombre": "CIT1000", "codigo": "CIT1000", "semestre": 1}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    // Cuatro secciones por ramo con la misma prioridad, sin topes entre ramos
+    let dias = ["LU", "MA", "MI"];
+    let secciones: Vec<Seccion> = ["CBM1000", "CBM1001", "CIT1000"].iter().enumerate()
+        .flat_map(|(i, c)| (1..=4).map(move |k| seccion(c, k, &format!("{} {:02}:00-{:02}:50", dias[i], 7 + k, 7 + k))))
+        .collect();
+    let primera = |seed: Option<u64>| -> Vec<String> {
+        let sols = get_clique_max_pond_with_prefs(&secciones, &ramos, &params(seed));
+        let mut boxes: Vec<String> = sols[0].0.iter().map(|(s, _)| s.codigo_box.clone()).collect();
+        boxes.sort();
+        boxes
+    };
+
+    assert_eq!(primera(None), primera(None));
+    assert_eq!(primera(Some(7)), primera(Some(7)));
+    let distintas: HashSet<Vec<String>> = (0..8u64).map(|s| primera(Some(s))).collect();
+    assert!(distintas.len() > 1, "semillas distintas deberían variar la primera solución");
+}
@@ -0,0 +1,100 @@
+use quickshift::algorithm::doble_titulacion::{fusionar_malla, progreso_programa, ID_OFFSET_POR_MALLA};
+use quickshift::models::RamoDisponible;
+use std::collections::{HashMap, HashSet};
+
+fn ramo(id: i32, nombre: &str, codigo: &str, requisitos_ids: Vec<i32>) -> RamoDisponible {
+    RamoDisponible {
+        id,
+        nombre: nombre.to_string(),
+        codigo: codigo.to_string(),
+        holgura: 0,
+        numb_correlativo: id,
+        critico: false,
+        requisitos_ids,
+        correquisitos_ids: vec![],
+        dificultad: None,
+        electivo: false,
+        semestre: Some(1),
+        creditos: None,
+        tags: Vec::new(),
+    }
+}
+
+fn mapa(ramos: Vec<RamoDisponible>) -> HashMap<String, RamoDisponible> {
+    ramos.into_iter().map(|r| (quickshift::excel::normalize_name(&r.nombre), r)).collect()
+}
+
+#[test]
+fn test_fusionar_malla_deduplica_y_remapea() {
+    let mut principal = mapa(vec![
+        ramo(1, "Calculo I", "CBM1000", vec![]),
+        ramo(2, "Calculo II", "CBM1001", vec![1]),
+    ]);
+    let adicional = mapa(vec![
+        // Compartido por nombre
+        ramo(1, "Calculo I", "", vec![]),
+        // Compartido por equivalencia de código
+        ramo(2, "Calculo Diferencial II", "MAT1001", vec![1]),
+        // Propio de la segunda carrera
+        ramo(3, "Economia", "ECO1000", vec![2]),
+    ]);
+    let mut equivalencias = HashMap::new();
+    equivalencias.insert("MAT1001".to_string(), "CBM1001".to_string());
+
+    let (claves, compartidas) = fusionar_malla(&mut principal, adicional, &equivalencias, ID_OFFSET_POR_MALLA);
+
+    assert_eq!(principal.len(), 3);
+    assert_eq!(claves.len(), 3);
+    assert_eq!(compartidas.len(), 2);
+    assert!(compartidas.contains("calculo i"));
+    assert!(compartidas.contains("calculo ii"));
+
+    let eco = principal.get("economia").expect("ramo propio insertado");
+    assert_eq!(eco.id, 3 + ID_OFFSET_POR_MALLA);
+    // Su prerequisito (id 2 en la malla adicional) apunta al ramo compartido de la principal
+    assert_eq!(eco.requisitos_ids, vec![2]);
+}
+
+#[test]
+fn test_progreso_programa() {
+    let ramos = mapa(vec![
+        ramo(1, "Calculo I", "CBM1000", vec![]),
+        ramo(2, "Calculo II", "CBM1001", vec![1]),
+        ramo(3, "Economia", "ECO1000", vec![]),
+        ramo(4, "Contabilidad", "", vec![]),
+    ]);
+    let claves: HashSet<String> = ramos.keys().cloned().collect();
+    let compartidas: HashSet<String> = ["calculo i".to_string()].into_iter().collect();
+    let pasados = vec!["CBM1000".to_string(), "Contabilidad".to_string()];
+
+    let p = progreso_programa("MallaA.xlsx", &claves, &ramos, &pasados, &compartidas);
+    assert_eq!(p.total_ramos, 4);
+    assert_eq!(p.aprobados, 2);
+    assert_eq!(p.pendientes, 2);
+    assert_eq!(p.compartidos, 1);
+    assert!((p.porcentaje - 50.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_progreso_por_programa_con_sus_equivalencias() {
+    use quickshift::algorithm::doble_titulacion::unir_aprobados;
+    let mut principal = mapa(vec![ramo(1, "Calculo I", "CBM1000", vec![])]);
+    let adicional = mapa(vec![ramo(1, "Calculo Diferencial", "MAT1000", vec![])]);
+    let (claves_extra, compartidas) = fusionar_malla(&mut principal, adicional, &HashMap::new(), ID_OFFSET_POR_MALLA);
+    let claves_principal: HashSet<String> = ["calculo i".to_string()].into_iter().collect();
+
+    // Las equivalencias de la malla adicional traducen CBM1000 -> MAT1000 solo para ella
+    let pasados = vec!["CBM1000".to_string()];
+    let mut equivalencias = HashMap::new();
+    equivalencias.insert("CBM1000".to_string(), "MAT1000".to_string());
+    let pasados_extra = quickshift::excel::aplicar_equivalencias(&pasados, &equivalencias);
+
+    let p = progreso_programa("principal", &claves_principal, &principal, &pasados, &compartidas);
+    let e = progreso_programa("adicional", &claves_extra, &principal, &pasados_extra, &compartidas);
+    assert_eq!(p.aprobados, 1);
+    assert_eq!(e.aprobados, 1);
+
+    let union = unir_aprobados(&[pasados, pasados_extra]);
+    assert!(union.contains(&"CBM1000".to_string()));
+    assert!(union.contains(&"MAT1000".to_string()));
+}
@@ -0,0 +1,141 @@
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::api_json::InputParams;
+use quickshift::excel::electivos::{catalogo_desde_filas, normalizar_linea};
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+use common::seccion_con;
+
+fn filas(datos: &[&[&str]]) -> Vec<Vec<String>> {
+    datos.iter().map(|f| f.iter().map(|c| c.to_string()).collect()).collect()
+}
+
+fn seccion(codigo: &str, nombre: &str, horario: &str, electivo: bool) -> Seccion {
+    seccion_con(codigo, "1", &[horario], serde_json::json!({"nombre": nombre, "is_electivo": electivo}))
+}
+
+#[test]
+fn test_catalogo_electivos_y_etiquetado() {
+    assert_eq!(normalizar_linea("Informática"), "informatica");
+    assert_eq!(normalizar_linea(" TELECOM "), "telecomunicaciones");
+    assert_eq!(normalizar_linea("Gestión"), "gestion");
+
+    let catalogo = catalogo_desde_filas(&filas(&[
+        &["Catálogo de electivos 2025"],
+        &["Código", "Nombre", "Línea de Formación"],
+        &["CIT2500", "Inteligencia Artificial", "Informática"],
+        &["cit2600", "Redes Inalámbricas", "Telecomunicaciones"],
+        &["", "Gestión de Proyectos TI", "Gestión"],
+        &["CIT2700", "Sin línea", ""],
+    ]));
+    assert_eq!(catalogo.electivos.len(), 3);
+
+    let mut secciones = vec![
+        seccion("CIT2500", "INTELIGENCIA ARTIFICIAL", "LU 08:30-09:50", true),
+        seccion("CIT2600", "Redes", "MA 08:30-09:50", true),
+        seccion("XX999", "Gestion de proyectos TI", "MI 08:30-09:50", true),
+        seccion("CIT2700", "Sin línea", "JU 08:30-09:50", true),
+    ];
+    assert_eq!(catalogo.etiquetar(&mut secciones), 3);
+    let lineas: Vec<Option<&str>> = secciones.iter().map(|s| s.linea.as_deref()).collect();
+    assert_eq!(lineas, vec![Some("informatica"), Some("telecomunicaciones"), Some("gestion"), None]);
+
+    // Sin columna de línea no hay catálogo
+    assert!(catalogo_desde_filas(&filas(&[&["Código", "Nombre"], &["CIT2500", "IA"]])).is_empty());
+}
+
+#[test]
+fn test_balance_lineas_excluye_electivos_sin_peso() {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "Inteligencia Artificial", "codigo": "CIT2500", "electivo": true},
+        {"id": 3, "nombre": "Redes Inalambricas", "codigo": "CIT2600", "electivo": true},
+        {"id": 4, "nombre": "Electivo Libre", "codigo": "CIT2800", "electivo": true}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+
+    let mut secciones = vec![
+        seccion("CBM1000", "Calculo I", "LU 08:30-09:50", false),
+        seccion("CIT2500", "Inteligencia Artificial", "MA 08:30-09:50", true),
+        seccion("CIT2600", "Redes Inalambricas", "MI 08:30-09:50", true),
+        seccion("CIT2800", "Electivo Libre", "JU 08:30-09:50", true),
+    ];
+    secciones[1].linea = Some("informatica".into());
+    secciones[2].linea = Some("telecomunicaciones".into());
+
+    let params = |balance: serde_json::Value| -> InputParams {
+        serde_json::from_value(serde_json::json!({
+            "email": "alumno@example.com",
+            "ramos_pasados": [],
+            "ramos_prioritarios": [],
+            "malla": "MallaElectivos",
+            "filtros": {"balance_lineas": balance}
+        })).unwrap()
+    };
+    let codigos = |sols: &[(Vec<(Seccion, i32)>, i64)]| -> Vec<String> {
+        let mut c: Vec<String> = sols.iter().flat_map(|(sol, _)| sol.iter().map(|(s, _)| s.codigo.clone())).collect();
+        c.sort();
+        c.dedup();
+        c
+    };
+
+    let sin_filtro = get_clique_max_pond_with_prefs(&secciones, &ramos, &params(serde_json::json!({"habilitado": false, "lineas": {"Informática": 1.0}})));
+    assert!(codigos(&sin_filtro).contains(&"CIT2600".to_string()));
+
+    let con_filtro = get_clique_max_pond_with_prefs(&secciones, &ramos, &params(serde_json::json!({"habilitado": true, "lineas": {"Informática": 1.0, "gestion": 0.0}})));
+    let usados = codigos(&con_filtro);
+    assert!(!usados.contains(&"CIT2600".to_string()), "{:?}", usados);
+    assert!(usados.contains(&"CIT2500".to_string()));
+    assert!(usados.contains(&"CIT2800".to_string()), "electivo sin línea conocida no se filtra");
+}
+
+#[test]
+fn test_balance_lineas_logrado_y_score() {
+    use quickshift::algorithm::balance_lineas::balance_logrado;
+    use quickshift::models::BalanceLineas;
+
+    let con_linea = |codigo: &str, nombre: &str, horario: &str, linea: &str| -> Seccion {
+        let mut s = seccion(codigo, nombre, horario, true);
+        s.linea = Some(linea.to_string());
+        s
+    };
+    let balance = BalanceLineas {
+        habilitado: true,
+        lineas: Some(HashMap::from([("Informática".to_string(), 3.0), ("Telecom".to_string(), 1.0)])),
+    };
+
+    let secs = [
+        seccion("CBM1000", "Calculo I", "LU 08:30-09:50", false),
+        con_linea("CIT2500", "IA", "MA 08:30-09:50", "informatica"),
+        con_linea("CIT2600", "Redes", "MI 08:30-09:50", "telecomunicaciones"),
+    ];
+    let logrado = balance_logrado(&secs, &balance).unwrap();
+    assert_eq!(logrado.objetivo["informatica"], 0.75);
+    assert_eq!(logrado.lineas["telecomunicaciones"], 0.5);
+    assert!((logrado.desviacion - 0.25).abs() < 1e-9);
+    assert!(balance_logrado(&secs[..1], &balance).is_none(), "sin electivos con línea no hay balance");
+
+    // IA II y Redes chocan: con 50/50 pedido la mejor solución lleva Redes
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "IA", "codigo": "CIT2500", "electivo": true},
+        {"id": 2, "nombre": "IA II", "codigo": "CIT2510", "electivo": true},
+        {"id": 3, "nombre": "Redes", "codigo": "CIT2600", "electivo": true}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    let secciones = vec![
+        con_linea("CIT2500", "IA", "LU 08:30-09:50", "informatica"),
+        con_linea("CIT2510", "IA II", "MA 08:30-09:50", "informatica"),
+        con_linea("CIT2600", "Redes", "MA 08:30-09:50", "telecomunicaciones"),
+    ];
+    let params: InputParams = serde_json::from_value(serde_json::json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": [],
+        "ramos_prioritarios": [],
+        "malla": "MallaElectivos",
+        "filtros": {"balance_lineas": {"habilitado": true, "lineas": {"informatica": 0.5, "telecomunicaciones": 0.5}}}
+    })).unwrap();
+    let sols = get_clique_max_pond_with_prefs(&secciones, &ramos, &params);
+    let mejor: Vec<&str> = sols[0].0.iter().map(|(s, _)| s.codigo.as_str()).collect();
+    assert!(mejor.contains(&"CIT2600"), "{:?}", mejor);
+}
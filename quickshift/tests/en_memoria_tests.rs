@@ -0,0 +1,71 @@
+use quickshift::algorithm::en_memoria::resolver_json;
+use serde_json::json;
+
+fn entrada() -> serde_json::Value {
+    json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": ["CBM1000"],
+        "ramos_prioritarios": [],
+        "malla": "MallaEnMemoria",
+        "ramos": [
+            {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+            {"id": 2, "nombre": "Calculo II", "codigo": "CBM1001", "requisitos_ids": [1], "semestre": 2},
+            {"id": 3, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+            {"id": 4, "nombre": "Algoritmos", "codigo": "CIT2000", "requisitos_ids": [3], "semestre": 2}
+        ],
+        "secciones": [
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["LU 08:30-09:50"], "codigo_box": "CBM1000-1"},
+            {"codigo": "CBM1001", "nombre": "Calculo II", "seccion": "1", "horario": ["MA 08:30-09:50"], "codigo_box": "CBM1001-1"},
+            {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "1", "horario": ["MI 10:00-11:20"], "codigo_box": "CIT1000-1"},
+            {"codigo": "CIT2000", "nombre": "Algoritmos", "seccion": "1", "horario": ["JU 10:00-11:20"], "codigo_box": "CIT2000-1"}
+        ]
+    })
+}
+
+#[test]
+fn test_resolver_json_sin_archivos() {
+    let out = resolver_json(&entrada().to_string()).expect("resolución en memoria");
+    let soluciones = out["soluciones"].as_array().expect("soluciones");
+    assert!(!soluciones.is_empty());
+    assert_eq!(out["soluciones_count"].as_u64(), Some(soluciones.len() as u64));
+
+    for sol in soluciones {
+        let codigos: Vec<&str> = sol["secciones"].as_array().unwrap().iter()
+            .map(|s| s["codigo"].as_str().unwrap())
+            .collect();
+        // Ramo aprobado nunca se vuelve a ofrecer
+        assert!(!codigos.contains(&"CBM1000"));
+    }
+}
+
+#[test]
+fn test_resolver_json_entrada_invalida() {
+    let err = resolver_json("{\"email\": \"x\"}").unwrap_err();
+    assert!(err.starts_with("invalid JSON body"));
+}
+
+#[test]
+fn test_schedule_planner_en_memoria() {
+    use quickshift::algorithm::deadline::Deadline;
+    use quickshift::algorithm::en_memoria::{resolver_en_memoria, SolveEnMemoria};
+    use quickshift::algorithm::engine::EnMemoria;
+    use quickshift::algorithm::SchedulePlanner;
+
+    let SolveEnMemoria { mut params, ramos, secciones, prerequisitos, equivalencias } =
+        serde_json::from_value(entrada()).unwrap();
+    let planner = EnMemoria { ramos, secciones, prerequisitos, equivalencias };
+
+    // Las dos mitades por separado dan lo mismo que el pipeline completo
+    let datos = planner.preparar(&mut params).unwrap();
+    let por_fases = planner.resolver(&datos, &params, &Deadline::sin_limite()).unwrap();
+    let completo = resolver_en_memoria(serde_json::from_value(entrada()).unwrap()).unwrap();
+    assert!(!por_fases.is_empty());
+    assert_eq!(por_fases.len(), completo.len());
+    for ((a, score_a), (b, score_b)) in por_fases.iter().zip(&completo) {
+        assert_eq!(score_a, score_b);
+        assert_eq!(
+            a.iter().map(|(s, _)| &s.codigo_box).collect::<Vec<_>>(),
+            b.iter().map(|(s, _)| &s.codigo_box).collect::<Vec<_>>(),
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use quickshift::algorithm::equivalencias::*;
+use quickshift::models::RamoDisponible;
+use std::collections::{HashMap, HashSet};
+
+fn par(antiguo: &str, nuevo: &str) -> (String, String) {
+    (antiguo.to_string(), nuevo.to_string())
+}
+
+fn malla() -> HashSet<String> {
+    ["CIT3000", "CIT3100", "CBM1000", "CBM1001"].iter().map(|c| c.to_string()).collect()
+}
+
+fn tabla() -> Vec<(String, String)> {
+    vec![
+        par("CIT1000", "CIT3000"),  // directa
+        par("INF100", "CIT2000"),   // encadenada: INF100 -> CIT2000 -> CIT3100
+        par("CIT2000", "CIT3100"),
+        par("MAT100", "CBM1000"),   // ambigua
+        par("MAT100", "CBM1001"),
+        par("X1", "X2"),            // ciclo
+        par("X2", "X1"),
+        par("FIS100", "FIS200"),    // no llega a la malla
+    ]
+}
+
+fn ramo(id: i32, nombre: &str, codigo: &str) -> RamoDisponible {
+    RamoDisponible {
+        id,
+        nombre: nombre.to_string(),
+        codigo: codigo.to_string(),
+        holgura: 0,
+        numb_correlativo: id,
+        critico: false,
+        requisitos_ids: vec![],
+        correquisitos_ids: vec![],
+        dificultad: None,
+        electivo: false,
+        semestre: Some(1),
+        creditos: None,
+        tags: Vec::new(),
+    }
+}
+
+#[test]
+fn test_resolver_cadenas_ambiguas_y_duplicados() {
+    let codigos: Vec<String> = ["cit1000", "INF100", "MAT100", "X1", "CBM1000", "CIT3000", "OTRO1"]
+        .iter().map(|c| c.to_string()).collect();
+    let res = resolver(&codigos, &tabla(), &malla());
+
+    let estado = |c: &str| res.codigos.iter().find(|r| r.codigo == c).unwrap();
+    assert_eq!(estado("CIT1000").estado, EstadoEquivalencia::Directa);
+    assert_eq!(estado("CIT1000").destino.as_deref(), Some("CIT3000"));
+    assert_eq!(estado("INF100").estado, EstadoEquivalencia::Encadenada);
+    assert_eq!(estado("INF100").cadena, vec!["INF100", "CIT2000", "CIT3100"]);
+    assert_eq!(estado("MAT100").estado, EstadoEquivalencia::Ambigua);
+    assert_eq!(estado("MAT100").candidatos, vec!["CBM1000", "CBM1001"]);
+    assert_eq!(estado("X1").estado, EstadoEquivalencia::Ciclo);
+    assert_eq!(estado("CBM1000").estado, EstadoEquivalencia::EnMalla);
+    assert_eq!(estado("OTRO1").estado, EstadoEquivalencia::SinEquivalencia);
+
+    // CIT1000 y CIT3000 cuentan una sola vez; los no resueltos quedan como venían
+    assert_eq!(res.ramos_pasados, vec!["CIT3000", "CIT3100", "MAT100", "X1", "CBM1000", "OTRO1"]);
+    assert_eq!(res.duplicados.len(), 1);
+    assert_eq!(res.duplicados[0].codigo, "CIT3000");
+    assert_eq!(res.duplicados[0].origenes, vec!["CIT1000", "CIT3000"]);
+    assert_eq!(res.cambios().count(), 5);
+}
+
+#[test]
+fn test_analizar_tabla_y_duplicados_entre_mallas() {
+    let mut t = tabla();
+    t.push(par("CIT1500", "CIT3000"));
+    let a = analizar(&t, &malla());
+    assert_eq!(a.total, 9);
+    assert_eq!(a.encadenadas.iter().map(|r| r.codigo.as_str()).collect::<Vec<_>>(), vec!["INF100"]);
+    assert_eq!(a.ambiguas.iter().map(|r| r.codigo.as_str()).collect::<Vec<_>>(), vec!["MAT100"]);
+    assert_eq!(a.ciclos, vec!["X1", "X2"]);
+    assert!(a.fuera_de_malla.iter().any(|p| p.antiguo == "FIS100" && p.nuevo == "FIS200"));
+    assert!(a.duplicados.iter().any(|d| d.codigo == "CIT3000" && d.origenes == vec!["CIT1000", "CIT1500"]));
+
+    let vigente: HashMap<String, RamoDisponible> = vec![ramo(1, "Cálculo I", "CBM1000"), ramo(2, "Bases de Datos", "CIT3000")]
+        .into_iter().map(|r| (r.codigo.clone(), r)).collect();
+    let anterior: HashMap<String, RamoDisponible> = vec![
+        ramo(1, "Calculo I", "MAT001"),       // mismo nombre, sin fila en la tabla
+        ramo(2, "Bases de datos", "CIT1000"), // cubierto por la tabla
+        ramo(3, "Física", "FIS100"),
+    ].into_iter().map(|r| (r.codigo.clone(), r)).collect();
+    let dups = duplicados_entre_mallas(&vigente, "Malla2018.xlsx", &anterior, &t);
+    assert_eq!(dups.len(), 1);
+    assert_eq!((dups[0].codigo.as_str(), dups[0].equivalente.as_str(), dups[0].malla.as_str()), ("MAT001", "CBM1000", "Malla2018.xlsx"));
+}
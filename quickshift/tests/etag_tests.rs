@@ -0,0 +1,61 @@
+#![cfg(feature = "server")]
+
+use actix_web::{test as atest, web, App};
+use quickshift::middleware::etag::{coincide, etag_de_archivos};
+
+#[test]
+fn test_etag_cambia_con_el_archivo_y_la_variante() {
+    let ruta = std::env::temp_dir().join(format!("quickshift-etag-{}.xlsx", std::process::id()));
+    std::fs::write(&ruta, b"uno").unwrap();
+    let a = etag_de_archivos(&[&ruta], "content|").unwrap();
+    assert!(a.starts_with("W/\""));
+    assert_eq!(etag_de_archivos(&[&ruta], "content|"), Some(a.clone()));
+    assert_ne!(etag_de_archivos(&[&ruta], "merge||csv"), Some(a.clone()));
+
+    std::fs::write(&ruta, b"otro contenido").unwrap();
+    assert_ne!(etag_de_archivos(&[&ruta], "content|"), Some(a.clone()));
+    // Un archivo que no existe deja la respuesta sin ETag
+    assert_eq!(etag_de_archivos(&[&ruta, std::path::Path::new("/no/existe.xlsx")], ""), None);
+
+    let opaco = a.trim_start_matches("W/").to_string();
+    let con = |valor: &str| atest::TestRequest::get().insert_header(("If-None-Match", valor)).to_http_request();
+    assert!(coincide(&con(&a), &a));
+    assert!(coincide(&con(&opaco), &a));
+    assert!(coincide(&con(&format!("\"viejo\", {}", a)), &a));
+    assert!(coincide(&con("*"), &a));
+    assert!(!coincide(&con("\"viejo\""), &a));
+    assert!(!coincide(&atest::TestRequest::get().to_http_request(), &a));
+    let _ = std::fs::remove_file(ruta);
+}
+
+#[actix_web::test]
+async fn test_merge_responde_304_y_comprime() {
+    let app = atest::init_service(App::new()
+        .wrap(actix_web::middleware::Compress::default())
+        .route("/datafiles/merge", web::get().to(quickshift::api_json::handlers::datafiles::datafiles_merge_handler))).await;
+
+    let req = atest::TestRequest::get().uri("/datafiles/merge?malla=Malla2020.xlsx")
+        .insert_header(("Accept-Encoding", "gzip"))
+        .to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("content-encoding").unwrap(), "gzip");
+    assert_eq!(resp.headers().get("cache-control").unwrap(), "no-cache");
+    let etag = resp.headers().get("etag").unwrap().to_str().unwrap().to_string();
+
+    let req = atest::TestRequest::get().uri("/datafiles/merge?malla=Malla2020.xlsx")
+        .insert_header(("If-None-Match", etag.as_str()))
+        .to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+    assert_eq!(resp.headers().get("etag").unwrap().to_str().unwrap(), etag);
+    assert!(atest::read_body(resp).await.is_empty());
+
+    // Otra variante (CSV) tiene su propio ETag
+    let req = atest::TestRequest::get().uri("/datafiles/merge?malla=Malla2020.xlsx&formato=csv")
+        .insert_header(("If-None-Match", etag.as_str()))
+        .to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_ne!(resp.headers().get("etag").unwrap().to_str().unwrap(), etag);
+}
@@ -0,0 +1,133 @@
+use quickshift::algorithm::clique::{get_clique_max_pond_with_prefs, modificadores_score};
+use quickshift::algorithm::evaluaciones::carga_evaluaciones;
+use quickshift::api_json::InputParams;
+use quickshift::excel::evaluaciones::{calendario_desde_filas, fecha_iso, parse_fecha};
+use quickshift::models::{Evaluacion, RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+use common::seccion_con;
+
+fn filas(datos: &[&[&str]]) -> Vec<Vec<String>> {
+    datos.iter().map(|f| f.iter().map(|c| c.to_string()).collect()).collect()
+}
+
+fn seccion(codigo: &str, nombre: &str, horario: &str, fechas: &[&str]) -> Seccion {
+    let mut s = seccion_con(codigo, "1", &[horario], serde_json::json!({"nombre": nombre}));
+    s.evaluaciones = fechas.iter().enumerate()
+        .map(|(i, f)| Evaluacion { nombre: format!("Certamen {}", i + 1), fecha: f.to_string() })
+        .collect();
+    s
+}
+
+fn params() -> InputParams {
+    common::params("MallaEvaluaciones", serde_json::json!({}))
+}
+
+#[test]
+fn test_parse_fecha() {
+    let dia = parse_fecha("2025-04-15").unwrap();
+    assert_eq!(fecha_iso(dia), "2025-04-15");
+    assert_eq!(parse_fecha("15/04/2025"), Some(dia));
+    assert_eq!(parse_fecha("15-04-2025"), Some(dia));
+    assert_eq!(parse_fecha("2025-04-15 00:00:00"), Some(dia));
+    // Número de serie de Excel del 15/04/2025
+    assert_eq!(parse_fecha("45762"), Some(dia));
+    assert_eq!(parse_fecha("1970-01-01"), Some(0));
+    assert_eq!(parse_fecha("2024-02-29").map(fecha_iso).as_deref(), Some("2024-02-29"));
+    for invalida in ["2025-02-30", "31/04/2025", "3", "", "pendiente", "15/04"] {
+        assert_eq!(parse_fecha(invalida), None, "{}", invalida);
+    }
+}
+
+#[test]
+fn test_calendario_en_ambos_formatos() {
+    // Una fila por evaluación
+    let largo = calendario_desde_filas(&filas(&[
+        &["Calendario de evaluaciones 2025-1"],
+        &["Código", "Asignatura", "Evaluación", "Fecha"],
+        &["CIT2107", "Bases de Datos", "Certamen 1", "2025-04-15"],
+        &["cit2107", "Bases de Datos", "Certamen 2", "20/05/2025"],
+        &["CIT2108", "Redes", "Examen", "por definir"],
+    ]));
+    // Una fila por curso, una columna por evaluación
+    let ancho = calendario_desde_filas(&filas(&[
+        &["Sigla", "Nombre", "C1", "C2", "Examen"],
+        &["CIT2107", "Bases de Datos", "15/04/2025", "2025-05-20", ""],
+        &["", "Cálculo II", "2025-04-16", "", "2025-07-01"],
+    ]));
+
+    let mut secciones = vec![
+        seccion("CIT2107", "BASES DE DATOS", "LU 08:30-09:50", &[]),
+        seccion("CBM2000", "Calculo II", "MA 08:30-09:50", &[]),
+        seccion("CIT2108", "Redes", "MI 08:30-09:50", &[]),
+    ];
+    assert_eq!(largo.asignar(&mut secciones), 1);
+    let fechas = |s: &Seccion| s.evaluaciones.iter().map(|e| (e.nombre.clone(), e.fecha.clone())).collect::<Vec<_>>();
+    assert_eq!(fechas(&secciones[0]), vec![
+        ("Certamen 1".to_string(), "2025-04-15".to_string()),
+        ("Certamen 2".to_string(), "2025-05-20".to_string()),
+    ]);
+
+    assert_eq!(ancho.asignar(&mut secciones), 2);
+    assert_eq!(fechas(&secciones[0]), vec![
+        ("C1".to_string(), "2025-04-15".to_string()),
+        ("C2".to_string(), "2025-05-20".to_string()),
+    ]);
+    assert_eq!(fechas(&secciones[1]).len(), 2, "por nombre cuando la fila no trae código");
+    assert!(secciones[2].evaluaciones.is_empty());
+
+    // Sin columna de fecha no hay calendario
+    assert!(calendario_desde_filas(&filas(&[&["Código", "Nombre"], &["CIT2107", "BD"]])).is_empty());
+}
+
+#[test]
+fn test_carga_de_evaluaciones() {
+    let bd = seccion("CIT2107", "Bases de Datos", "LU 08:30-09:50", &["2025-04-15", "2025-05-20"]);
+    let bd_lab = seccion("CIT2107", "Bases de Datos", "MI 08:30-09:50", &["2025-04-15", "2025-05-20"]);
+    let calculo = seccion("CBM2000", "Calculo II", "MA 08:30-09:50", &["2025-04-15", "2025-05-21"]);
+    let fisica = seccion("CBF1000", "Fisica", "JU 08:30-09:50", &["2025-06-10"]);
+    let sin_calendario = seccion("CIT1000", "Programacion", "VI 08:30-09:50", &[]);
+
+    let carga = carga_evaluaciones([&bd, &bd_lab, &calculo, &fisica, &sin_calendario]).unwrap();
+    // Laboratorio y cátedra del mismo curso no chocan entre sí
+    assert_eq!((carga.mismo_dia, carga.dias_consecutivos, carga.carga), (1, 1, 3));
+    assert_eq!(carga.evaluaciones, 5);
+    assert_eq!(carga.cercanas[0].fecha_a, "2025-04-15");
+    assert_eq!(carga.cercanas[0].dias, 0);
+    assert_eq!((carga.cercanas[1].codigo_a.as_str(), carga.cercanas[1].codigo_b.as_str()), ("CBM2000", "CIT2107"));
+
+    assert_eq!(carga_evaluaciones([&bd, &fisica]).unwrap().carga, 0);
+    assert!(carga_evaluaciones([&sin_calendario]).is_none());
+
+    let m = modificadores_score(&[(bd, 0), (calculo, 0)], &params(), &quickshift::scoring::config());
+    assert_eq!(m.evaluaciones, -3 * quickshift::scoring::config().penalizacion_carga_evaluaciones);
+}
+
+#[test]
+fn test_solver_evita_evaluaciones_el_mismo_dia() {
+    // Redes y Física chocan en horario: con el mismo score base la solución
+    // elegida debe ser la que no tiene certamen el mismo día que Bases de Datos
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Bases de Datos", "codigo": "CIT2107"},
+        {"id": 2, "nombre": "Redes", "codigo": "CIT2108"},
+        {"id": 3, "nombre": "Fisica", "codigo": "CBF1000"}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    let secciones = vec![
+        seccion("CIT2107", "Bases de Datos", "LU 08:30-09:50", &["2025-04-15"]),
+        seccion("CIT2108", "Redes", "MA 08:30-09:50", &["2025-04-15"]),
+        seccion("CBF1000", "Fisica", "MA 08:30-09:50", &["2025-04-22"]),
+    ];
+
+    let sols = get_clique_max_pond_with_prefs(&secciones, &ramos, &params());
+    let mejor: Vec<&str> = sols[0].0.iter().map(|(s, _)| s.codigo.as_str()).collect();
+    assert!(mejor.contains(&"CBF1000") && !mejor.contains(&"CIT2108"), "{:?}", mejor);
+
+    // Con la penalización en 0 la carga ya no decide
+    let mut sin_penalizacion = params();
+    sin_penalizacion.scoring = Some(serde_json::from_value(serde_json::json!({"penalizacion_carga_evaluaciones": 0})).unwrap());
+    let sols = get_clique_max_pond_with_prefs(&secciones, &ramos, &sin_penalizacion);
+    let scores: Vec<i64> = sols.iter().filter(|(s, _)| s.len() == 2).map(|(_, score)| *score).collect();
+    assert!(scores.windows(2).all(|w| w[0] == w[1]), "{:?}", scores);
+}
@@ -0,0 +1,41 @@
+use quickshift::algorithm::en_memoria::{resolver_en_memoria, SolveEnMemoria};
+use serde_json::json;
+
+fn entrada(excluidos: &[&str]) -> SolveEnMemoria {
+    serde_json::from_value(json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": [],
+        "ramos_prioritarios": ["CBM1000"],
+        "ramos_excluidos": excluidos,
+        "malla": "MallaExcluidos",
+        "ramos": [
+            {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+            {"id": 2, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+            {"id": 3, "nombre": "Fisica I", "codigo": "CBF1000", "semestre": 1}
+        ],
+        "secciones": [
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["LU 08:30-09:50"], "codigo_box": "CBM1000-1"},
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "2", "horario": ["MA 08:30-09:50"], "codigo_box": "CBM1000-2"},
+            {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "1", "horario": ["MI 10:00-11:20"], "codigo_box": "CIT1000-1"},
+            {"codigo": "CBF1000", "nombre": "Física I", "seccion": "1", "horario": ["JU 10:00-11:20"], "codigo_box": "CBF1000-1"}
+        ]
+    })).unwrap()
+}
+
+fn codigos(excluidos: &[&str]) -> Vec<Vec<String>> {
+    resolver_en_memoria(entrada(excluidos)).unwrap().into_iter()
+        .map(|(sol, _)| sol.into_iter().map(|(s, _)| s.codigo).collect())
+        .collect()
+}
+
+#[test]
+fn test_ramos_excluidos_no_aparecen() {
+    assert!(codigos(&[]).iter().any(|c| c.contains(&"CBM1000".to_string())));
+
+    // por código (aunque sea prioritario) y por nombre, sin importar tildes ni mayúsculas
+    let soluciones = codigos(&["cbm1000", "FISICA I"]);
+    assert!(!soluciones.is_empty());
+    for c in &soluciones {
+        assert_eq!(c, &vec!["CIT1000".to_string()]);
+    }
+}
@@ -0,0 +1,77 @@
+use quickshift::algorithm::explicacion::explicar_ramo;
+use quickshift::models::{RamoDisponible, Seccion};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+mod common;
+use common::{params, seccion_con};
+
+fn malla() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "Calculo II", "codigo": "CBM1001", "requisitos_ids": [1], "semestre": 2},
+        {"id": 3, "nombre": "Calculo III", "codigo": "CBM1002", "requisitos_ids": [2], "semestre": 3},
+        {"id": 4, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+        {"id": 5, "nombre": "Taller", "codigo": "CIT1010", "requisitos_ids": [4], "semestre": 2},
+        {"id": 6, "nombre": "Quimica", "codigo": "CBQ1000", "semestre": 1}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+fn secciones() -> Vec<Seccion> {
+    // "Taller" no tiene sección; "Quimica" choca con "Programacion"
+    vec![
+        seccion_con("CBM1000", "1", &["LU 08:30-09:50"], json!({"nombre": "Calculo I"})),
+        seccion_con("CBM1001", "1", &["MA 08:30-09:50"], json!({"nombre": "Calculo II"})),
+        seccion_con("CBM1002", "1", &["MI 08:30-09:50"], json!({"nombre": "Calculo III"})),
+        seccion_con("CIT1000", "1", &["JU 10:00-11:20"], json!({"nombre": "Programacion"})),
+        seccion_con("CBQ1000", "1", &["JU 10:00-11:20"], json!({"nombre": "Quimica", "profesor": "Perez"})),
+    ]
+}
+
+fn motivos(ramo: &str, extra: Value) -> Vec<Value> {
+    let exp = explicar_ramo(ramo, &params("MallaExplicacion", extra), &malla(), &secciones());
+    serde_json::to_value(&exp.motivos).unwrap().as_array().unwrap().clone()
+}
+
+#[test]
+fn test_explica_prerequisitos_y_oferta() {
+    let m = motivos("CBM1002", json!({}));
+    assert_eq!(m[0]["tipo"], "prerequisitos_faltantes");
+    assert_eq!(m[0]["faltantes"], json!([{"id": 2, "codigo": "CBM1001", "nombre": "Calculo II"}]));
+
+    assert_eq!(motivos("cit1010", json!({"ramos_pasados": ["CIT1000"]})), vec![json!({"tipo": "sin_secciones"})]);
+    assert_eq!(motivos("CBM1000", json!({"ramos_pasados": ["CBM1000"]})), vec![json!({"tipo": "ya_aprobado"})]);
+    assert_eq!(motivos("XYZ9999", json!({})), vec![json!({"tipo": "no_encontrado"})]);
+}
+
+#[test]
+fn test_explica_secciones_filtradas() {
+    let m = motivos("CBM1002", json!({
+        "ramos_pasados": ["CBM1000", "CBM1001"],
+        "horarios_prohibidos": ["MI 08:30-09:50"]
+    }));
+    assert_eq!(m.len(), 1);
+    assert_eq!(m[0]["tipo"], "secciones_filtradas");
+    assert_eq!(m[0]["secciones"][0]["motivo"], "horarios_prohibidos");
+
+    let m = motivos("CBQ1000", json!({
+        "filtros": {"preferencias_profesores": {"habilitado": true, "profesores_evitar": ["perez"]}}
+    }));
+    assert_eq!(m[0]["secciones"][0]["motivo"], "profesores");
+}
+
+#[test]
+fn test_explica_conflicto_con_ramo_prioritario() {
+    let p = params("MallaExplicacion", json!({"ramos_prioritarios": ["CIT1000"]}));
+    let exp = explicar_ramo("CBQ1000", &p, &malla(), &secciones());
+    assert!(!exp.en_mejor_solucion);
+    let m = serde_json::to_value(&exp.motivos).unwrap();
+    assert_eq!(m[0]["tipo"], "conflicto_horario");
+    assert_eq!(m[0]["secciones"][0]["choca_con"], json!(["CIT1000-1"]));
+
+    // El ramo prioritario sí aparece y no tiene motivos
+    let exp = explicar_ramo("CIT1000", &p, &malla(), &secciones());
+    assert!(exp.en_soluciones && exp.en_mejor_solucion);
+    assert!(exp.motivos.is_empty());
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "export-pdf")]
+
+use printpdf::lopdf::content::Content;
+use printpdf::lopdf::Document;
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::algorithm::desglose::desglosar;
+use quickshift::algorithm::sintetico::oferta_sintetica;
+use quickshift::api_json::InputParams;
+use quickshift::export::pdf::generar_pdf;
+use quickshift::export::SolucionReporte;
+use quickshift::models::Seccion;
+
+fn leer(bytes: &[u8]) -> Document {
+    assert!(bytes.starts_with(b"%PDF-"));
+    Document::load_mem(bytes).unwrap()
+}
+
+/// Textos (`Tj`) de una página, uno por línea. Las fuentes estándar van en
+/// WinAnsiEncoding.
+fn texto_de_pagina(doc: &Document, pagina: u32) -> String {
+    let id = doc.get_pages()[&pagina];
+    let contenido = Content::decode(&doc.get_page_content(id).unwrap()).unwrap();
+    contenido.operations.iter()
+        .filter(|op| op.operator == "Tj")
+        .filter_map(|op| op.operands.first()?.as_str().ok())
+        .map(|bytes| Document::decode_text(Some("WinAnsiEncoding"), bytes))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_una_pagina_por_solucion_con_grilla_y_resumen() {
+    let oferta = oferta_sintetica(30, 11);
+    let params: InputParams = serde_json::from_value(serde_json::json!({
+        "email": "pdf@x.cl", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "sintetica"
+    })).unwrap();
+    let soluciones = get_clique_max_pond_with_prefs(&oferta.secciones, &oferta.ramos, &params);
+    let soluciones = &soluciones[..soluciones.len().min(2)];
+    assert!(!soluciones.is_empty());
+
+    let secciones: Vec<Vec<Seccion>> = soluciones.iter().map(|(s, _)| s.iter().map(|(x, _)| x.clone()).collect()).collect();
+    let desgloses: Vec<_> = soluciones.iter().map(|(s, score)| desglosar(s, *score, &params, &oferta.ramos)).collect();
+    let reportes: Vec<SolucionReporte> = soluciones.iter().zip(&secciones).zip(&desgloses)
+        .map(|(((_, score), secciones), desglose)| SolucionReporte { secciones, total_score: *score, desglose })
+        .collect();
+
+    let doc = leer(&generar_pdf(&reportes).unwrap());
+    assert_eq!(doc.get_pages().len(), soluciones.len());
+    let texto = texto_de_pagina(&doc, 1);
+    for esperado in ["Solución 1", "Hora", "LU", "VI", "Resumen", "Profesor", &format!("Score total: {}", soluciones[0].1)] {
+        assert!(texto.contains(esperado), "falta '{}' en:\n{}", esperado, texto);
+    }
+    for s in &secciones[0] {
+        assert!(texto.contains(&format!("{}-{}", s.codigo, s.seccion)), "falta {}", s.codigo);
+    }
+}
+
+#[test]
+fn test_resumen_largo_sigue_en_otra_pagina_y_sin_soluciones() {
+    let oferta = oferta_sintetica(40, 3);
+    let params: InputParams = serde_json::from_value(serde_json::json!({
+        "email": "pdf@x.cl", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "sintetica"
+    })).unwrap();
+    // Una "solución" con toda la oferta no cabe en una página
+    let todas: Vec<(Seccion, i32)> = oferta.secciones.iter().map(|s| (s.clone(), 0)).collect();
+    let desglose = desglosar(&todas, 0, &params, &oferta.ramos);
+    let reporte = SolucionReporte { secciones: &oferta.secciones, total_score: 0, desglose: &desglose };
+    let doc = leer(&generar_pdf(&[reporte]).unwrap());
+    assert!(doc.get_pages().len() > 1);
+    assert!(texto_de_pagina(&doc, 2).contains("Solución 1 (continuación)"));
+
+    let vacio = leer(&generar_pdf(&[]).unwrap());
+    assert_eq!(vacio.get_pages().len(), 1);
+    assert!(texto_de_pagina(&vacio, 1).contains("No se encontraron soluciones"));
+}
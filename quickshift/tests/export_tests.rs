@@ -0,0 +1,49 @@
+use quickshift::export::{parse_horario, BloqueHorario, Dia};
+
+#[test]
+fn test_parse_horario_estructurado() {
+    assert_eq!(parse_horario("LU MA 08:30 - 10:00"), vec![
+        BloqueHorario { dia: Dia::Lunes, inicio: 510, fin: 600 },
+        BloqueHorario { dia: Dia::Martes, inicio: 510, fin: 600 },
+    ]);
+    assert_eq!(parse_horario("JU 10:00-11:20"), vec![BloqueHorario { dia: Dia::Jueves, inicio: 600, fin: 680 }]);
+    assert_eq!(parse_horario("Mié 14:30-15:50")[0].dia, Dia::Miercoles);
+    assert_eq!(parse_horario("LU 08:30-10:00")[0].hora_inicio(), "08:30");
+    assert!(parse_horario("Sin horario").is_empty());
+    assert!(parse_horario("").is_empty());
+}
+
+#[cfg(feature = "server")]
+#[test]
+fn test_generar_ics_eventos_semanales() {
+    use chrono::NaiveDate;
+    use quickshift::export::ics::{generar_ics, OpcionesIcs};
+    use quickshift::models::Seccion;
+
+    let secciones: Vec<Seccion> = serde_json::from_value(serde_json::json!([
+        {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["LU MI 08:30 - 10:00"],
+         "profesor": "Perez, Juan", "codigo_box": "CBM1000-1", "sala": "A-201"},
+        {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "2", "horario": ["Sin horario"], "codigo_box": "CIT1000-2"}
+    ])).unwrap();
+    let opciones = OpcionesIcs {
+        // Martes: el lunes cae en la semana siguiente
+        fecha_inicio: NaiveDate::from_ymd_opt(2026, 3, 10).unwrap(),
+        fecha_fin: None,
+        semanas: 16,
+        nombre: Some("Horario 2026-1".to_string()),
+        generado: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap().and_hms_opt(12, 0, 0).unwrap(),
+    };
+    let ics = generar_ics(&secciones, &opciones);
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    assert!(ics.contains("DTSTART:20260311T083000\r\nDTEND:20260311T100000\r\n"));
+    assert!(ics.contains("DTSTART:20260316T083000\r\n"));
+    assert!(ics.contains("RRULE:FREQ=WEEKLY;COUNT=16\r\n"));
+    assert!(ics.contains("LOCATION:A-201\r\n"));
+    assert!(ics.contains("DESCRIPTION:Profesor: Perez\\, Juan\\nSección: 1\r\n"));
+    assert!(ics.contains("DTSTAMP:20260301T120000Z\r\n"));
+    assert!(!ics.contains("CIT1000"));
+    assert!(ics.split("\r\n").all(|l| l.len() <= 75));
+}
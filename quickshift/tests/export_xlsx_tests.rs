@@ -0,0 +1,59 @@
+#![cfg(feature = "export-xlsx")]
+
+use calamine::{open_workbook_from_rs, Data, Reader, Xlsx};
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::algorithm::desglose::desglosar;
+use quickshift::algorithm::sintetico::oferta_sintetica;
+use quickshift::api_json::InputParams;
+use quickshift::export::xlsx::generar_xlsx;
+use quickshift::export::SolucionReporte;
+use quickshift::models::Seccion;
+use std::io::Cursor;
+
+fn leer(bytes: Vec<u8>) -> Xlsx<Cursor<Vec<u8>>> {
+    assert!(bytes.starts_with(b"PK"), "un XLSX es un zip");
+    open_workbook_from_rs(Cursor::new(bytes)).unwrap()
+}
+
+fn textos(rango: &calamine::Range<Data>) -> Vec<String> {
+    rango.rows().flat_map(|f| f.iter().map(|c| c.to_string())).filter(|s| !s.is_empty()).collect()
+}
+
+#[test]
+fn test_una_hoja_por_solucion_con_grilla_ramos_y_desglose() {
+    let oferta = oferta_sintetica(30, 7);
+    let params: InputParams = serde_json::from_value(serde_json::json!({
+        "email": "xlsx@x.cl", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "sintetica"
+    })).unwrap();
+    let soluciones = get_clique_max_pond_with_prefs(&oferta.secciones, &oferta.ramos, &params);
+    let soluciones = &soluciones[..soluciones.len().min(3)];
+    assert!(!soluciones.is_empty());
+
+    let secciones: Vec<Vec<Seccion>> = soluciones.iter().map(|(s, _)| s.iter().map(|(x, _)| x.clone()).collect()).collect();
+    let desgloses: Vec<_> = soluciones.iter().map(|(s, score)| desglosar(s, *score, &params, &oferta.ramos)).collect();
+    let reportes: Vec<SolucionReporte> = soluciones.iter().zip(&secciones).zip(&desgloses)
+        .map(|(((_, score), secciones), desglose)| SolucionReporte { secciones, total_score: *score, desglose })
+        .collect();
+
+    let mut workbook = leer(generar_xlsx(&reportes).unwrap());
+    let esperadas: Vec<String> = (1..=soluciones.len()).map(|i| format!("Solución {}", i)).collect();
+    assert_eq!(workbook.sheet_names(), esperadas);
+
+    let hoja = workbook.worksheet_range("Solución 1").unwrap();
+    assert_eq!(hoja.get_value((1, 1)), Some(&Data::Float(soluciones[0].1 as f64)));
+    let celdas = textos(&hoja);
+    for bloque in ["Horario semanal", "LU", "VI", "Ramos", "Profesor", "Prob. de aprobar", "Desglose del score", "prioridad_base", "total"] {
+        assert!(celdas.iter().any(|c| c == bloque), "falta '{}'", bloque);
+    }
+    for s in &secciones[0] {
+        assert!(celdas.contains(&s.codigo), "falta el ramo {}", s.codigo);
+        assert!(celdas.iter().any(|c| c.starts_with(&format!("{}-{} ", s.codigo, s.seccion))), "{} no está en la grilla", s.codigo);
+    }
+}
+
+#[test]
+fn test_sin_soluciones_queda_una_hoja() {
+    let mut workbook = leer(generar_xlsx(&[]).unwrap());
+    assert_eq!(workbook.sheet_names(), vec!["Sin soluciones".to_string()]);
+    assert_eq!(textos(&workbook.worksheet_range("Sin soluciones").unwrap()), vec!["No se encontraron soluciones"]);
+}
@@ -1,17 +1,23 @@
+#![allow(deprecated)]
+
 use quickshift::algorithm::extract_controller::{set_use_optimized, is_using_optimized};
 
 #[test]
-fn test_controller_dispatches_to_optimized() {
-    let old = is_using_optimized();
+fn test_controller_siempre_usa_el_lector_optimizado() {
+    set_use_optimized(false);
+    assert!(is_using_optimized(), "Solo queda la versión optimizada");
     set_use_optimized(true);
-    assert!(is_using_optimized(), "El flag debe estar activado");
-    set_use_optimized(old);
+    assert!(is_using_optimized());
 }
 
 #[test]
-fn test_controller_can_switch_to_original() {
-    let old = is_using_optimized();
-    set_use_optimized(false);
-    assert!(!is_using_optimized(), "El flag debe estar desactivado");
-    set_use_optimized(old); // restore
+fn test_atajos_deprecados_delegan_en_extract() {
+    use quickshift::algorithm::{extract, extract_controller, extract_optimizado};
+    use std::collections::HashMap;
+
+    let (secciones, ramos) = extract::extract_data(HashMap::new(), "Malla2020.xlsx", None).unwrap();
+    let (s1, r1) = extract_controller::extract_data(HashMap::new(), "Malla2020.xlsx", None).unwrap();
+    let (s2, r2) = extract_optimizado::extract_data_optimizado(HashMap::new(), "Malla2020.xlsx", None).unwrap();
+    assert_eq!((s1.len(), r1.len()), (secciones.len(), ramos.len()));
+    assert_eq!((s2.len(), r2.len()), (secciones.len(), ramos.len()));
 }
@@ -0,0 +1,47 @@
+#![cfg(feature = "server")]
+
+use quickshift::analithics::db::AnalyticsConn;
+use quickshift::analithics::feedback::*;
+use serde_json::json;
+
+fn conn() -> AnalyticsConn {
+    let c = rusqlite::Connection::open_in_memory().unwrap();
+    c.execute(SQLITE_SCHEMA, []).unwrap();
+    AnalyticsConn::Sqlite(c)
+}
+
+#[test]
+fn test_configuracion_scoring_desde_params() {
+    let params = json!({"malla": "MC2020", "optimizations": ["minimize-gaps", "Compact-Days", "minimize-gaps"], "solver": "exact"});
+    assert_eq!(configuracion_scoring(&params), json!({
+        "objetivo": "score",
+        "solver": "exact",
+        "optimizations": ["compact-days", "minimize-gaps"],
+    }));
+    assert_eq!(configuracion_scoring(&json!({"objetivo": "expected_progress"}))["solver"], "greedy");
+}
+
+#[test]
+fn test_resumen_por_configuracion() {
+    let conn = conn();
+    let base = configuracion_scoring(&json!({}));
+    let compacta = configuracion_scoring(&json!({"optimizations": ["compact-days"]}));
+
+    let fb = guardar_feedback(&conn, "ABC", 0, Some("Alumno@X.cl"), 5, Some("  muy bueno "), &compacta).unwrap();
+    assert_eq!((fb.solution_id.as_str(), fb.email.as_deref(), fb.comentario.as_deref()), ("abc", Some("alumno@x.cl"), Some("muy bueno")));
+    guardar_feedback(&conn, "abc", 1, None, 4, None, &compacta).unwrap();
+    guardar_feedback(&conn, "def", 0, None, 2, Some(""), &base).unwrap();
+    guardar_feedback(&conn, "def", 0, None, 2, None, &base).unwrap();
+    guardar_feedback(&conn, "def", 0, None, 5, None, &base).unwrap();
+    assert!(guardar_feedback(&conn, "def", 0, None, 6, None, &base).is_err());
+
+    let resumen = resumen_feedback(&conn).unwrap();
+    assert_eq!(resumen.len(), 2);
+    assert_eq!(resumen[0].configuracion, compacta);
+    assert_eq!((resumen[0].cantidad, resumen[0].promedio), (2, 4.5));
+    assert_eq!(resumen[1].configuracion, base);
+    assert_eq!(resumen[1].cantidad, 3);
+    assert!((resumen[1].promedio - 3.0).abs() < 1e-9);
+    assert_eq!(resumen[1].distribucion.get("2"), Some(&2));
+    assert_eq!(resumen[1].distribucion.get("5"), Some(&1));
+}
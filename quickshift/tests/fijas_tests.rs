@@ -0,0 +1,93 @@
+use quickshift::algorithm::en_memoria::{resolver_en_memoria, SolveEnMemoria};
+use quickshift::algorithm::fijas::{resolver, ConflictoFijas};
+use quickshift::api_error::ApiError;
+use quickshift::models::Seccion;
+use serde_json::json;
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, n: &str, componente: &str, horario: &str, bundle: Option<&str>) -> Seccion {
+    seccion_con(codigo, n, &[horario], json!({
+        "codigo_box": format!("{}-{}-{}", codigo, componente, n),
+        "componente": componente,
+        "bundle_id": bundle,
+    }))
+}
+
+#[test]
+fn test_resolver_fijas() {
+    let oferta = vec![
+        seccion("CBF1000", "1", "catedra", "LU 08:30 - 09:50", Some("CBF1000#1")),
+        seccion("CBF1000", "1", "lab", "JU 10:00 - 11:20", Some("CBF1000#1")),
+        seccion("CBF1000", "2", "catedra", "MA 08:30 - 09:50", None),
+        seccion("CBM1000", "1", "catedra", "LU 09:00 - 10:20", None),
+        seccion("CIT1000", "1", "catedra", "VI 08:30 - 09:50", None),
+    ];
+    let ids = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    // fijar la cátedra de un paquete fija también su laboratorio
+    let fijas = resolver(&ids(&["cbf1000-catedra-1", "CIT1000-catedra-1"]), &oferta, &[]).unwrap();
+    let boxes: Vec<&str> = fijas.iter().map(|s| s.codigo_box.as_str()).collect();
+    assert_eq!(boxes, vec!["CBF1000-catedra-1", "CBF1000-lab-1", "CIT1000-catedra-1"]);
+
+    let err = resolver(&ids(&["CBF1000-catedra-1", "CBF1000-catedra-2", "CBM1000-catedra-1", "NOEXISTE-1"]), &oferta, &ids(&["CIT1000", "CBM1000"])).unwrap_err();
+    assert_eq!(err.no_encontradas, vec!["NOEXISTE-1"]);
+    assert_eq!(err.ya_aprobadas, vec!["CBM1000-catedra-1"]);
+    let choques: Vec<(&str, &str, &str)> = err.choques.iter().map(|c| (c.a.as_str(), c.b.as_str(), c.motivo.as_str())).collect();
+    assert_eq!(choques, vec![
+        ("CBF1000-catedra-1", "CBF1000-catedra-2", "mismo_ramo"),
+        ("CBF1000-catedra-1", "CBM1000-catedra-1", "horario"),
+        ("CBF1000-lab-1", "CBF1000-catedra-2", "mismo_ramo"),
+    ]);
+    assert_eq!(err.choques[1].bloques, vec!["LU 08:30-09:50 / LU 09:00-10:20"]);
+    assert!(!err.sin_solucion);
+
+    let api = ApiError::de_ruta("Malla", Box::new(err));
+    assert_eq!((api.code(), api.status()), ("PINNED_SECTIONS_INFEASIBLE", 422));
+    assert_eq!(api.to_json()["details"]["choques"][0]["motivo"], "mismo_ramo");
+}
+
+fn entrada(fijas: &[&str]) -> SolveEnMemoria {
+    serde_json::from_value(json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": [],
+        "ramos_prioritarios": [],
+        "malla": "MallaFijas",
+        "secciones_fijas": fijas,
+        "ramos": [
+            {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+            {"id": 2, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+            {"id": 3, "nombre": "Fisica I", "codigo": "CBF1000", "semestre": 1},
+            {"id": 4, "nombre": "Taller de Titulo", "codigo": "CIT4000", "semestre": 10}
+        ],
+        "secciones": [
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["MA 08:30-09:50"], "codigo_box": "CBM1000-1"},
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "2", "horario": ["LU 08:30-09:50"], "codigo_box": "CBM1000-2"},
+            {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "1", "horario": ["MI 10:00-11:20"], "codigo_box": "CIT1000-1"},
+            {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "2", "horario": ["MA 08:30-09:50"], "codigo_box": "CIT1000-2"},
+            {"codigo": "CBF1000", "nombre": "Fisica I", "seccion": "1", "horario": ["JU 10:00-11:20"], "codigo_box": "CBF1000-1"},
+            {"codigo": "CIT4000", "nombre": "Taller de Titulo", "seccion": "1", "horario": ["VI 10:00-11:20"], "codigo_box": "CIT4000-1"}
+        ]
+    })).unwrap()
+}
+
+#[test]
+fn test_soluciones_alrededor_de_las_fijas() {
+    let soluciones = resolver_en_memoria(entrada(&["CBM1000-1"])).unwrap();
+    assert!(!soluciones.is_empty());
+    for (sol, _) in &soluciones {
+        let boxes: Vec<&str> = sol.iter().map(|(s, _)| s.codigo_box.as_str()).collect();
+        assert!(boxes.contains(&"CBM1000-1"), "{:?}", boxes);
+        // CIT1000-2 choca con la fija y CBM1000-2 es otra sección del mismo ramo
+        assert!(!boxes.contains(&"CIT1000-2") && !boxes.contains(&"CBM1000-2"), "{:?}", boxes);
+    }
+
+    // el ramo de décimo semestre nunca entra a la búsqueda: ninguna solución lo incluye
+    let err = resolver_en_memoria(entrada(&["CIT4000-1"])).unwrap_err();
+    let conflicto = err.downcast_ref::<ConflictoFijas>().expect("reporte de fijas");
+    assert!(conflicto.sin_solucion);
+
+    let err = resolver_en_memoria(entrada(&["CBM1000-1", "CIT1000-2"])).unwrap_err();
+    assert_eq!(err.downcast_ref::<ConflictoFijas>().unwrap().choques[0].motivo, "horario");
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use quickshift::algorithm::merge_malla_oferta_porcentajes;
+use quickshift::excel::{enrich_ramos_with_oferta_and_porcent, find_best_name_match, Emparejamiento, FuzzyMatcher, MetricaSimilitud};
+use quickshift::models::{RamoDisponible, Seccion};
+
+mod common;
+use common::seccion_con;
+
+fn nombres(ns: &[&str]) -> Vec<String> {
+    ns.iter().map(|n| n.to_string()).collect()
+}
+
+fn ramo(codigo: &str, nombre: &str) -> RamoDisponible {
+    serde_json::from_value(serde_json::json!({"id": 0, "codigo": codigo, "nombre": nombre})).unwrap()
+}
+
+fn seccion(codigo: &str, nombre: &str) -> Seccion {
+    seccion_con(codigo, "1", &["LU 08:30-09:50"], serde_json::json!({"nombre": nombre, "codigo_box": codigo}))
+}
+
+#[test]
+fn test_emparejar_numerales_y_typos() {
+    let m = FuzzyMatcher::default();
+    let oferta = nombres(&["CALCULO 1", "CALCULO 2", "FISICA II", "TERMODINAMICA", "PROGRAMACION AVANZADA"]);
+
+    // nombre normalizado idéntico
+    assert_eq!(m.emparejar("Termodinámica", &oferta), Emparejamiento::Exacto("TERMODINAMICA".into()));
+    // numeral romano contra dígito
+    assert_eq!(m.emparejar("Cálculo I", &oferta).elegido(), Some("CALCULO 1"));
+    assert_eq!(m.emparejar("Física 2", &oferta).elegido(), Some("FISICA II"));
+    // typo
+    assert_eq!(m.emparejar("Termodinamca", &oferta).elegido(), Some("TERMODINAMICA"));
+    // distinta numeración o nombre más corto no emparejan
+    assert_eq!(m.emparejar("Cálculo III", &oferta), Emparejamiento::Ninguno);
+    assert_eq!(m.emparejar("Física I", &oferta), Emparejamiento::Ninguno);
+    assert_eq!(m.emparejar("Programación", &oferta), Emparejamiento::Ninguno);
+    assert_eq!(m.similitud("Cálculo I", "Cálculo II"), 0.0);
+
+    let lev = FuzzyMatcher { metrica: MetricaSimilitud::Levenshtein, umbral: 0.9, ..Default::default() };
+    assert_eq!(lev.emparejar("Termodinamca", &oferta).elegido(), Some("TERMODINAMICA"));
+    let estricto = FuzzyMatcher { umbral: 1.0, ..Default::default() };
+    assert_eq!(estricto.emparejar("Termodinamca", &oferta), Emparejamiento::Ninguno);
+
+    assert_eq!(find_best_name_match("Calculo I", &oferta).as_deref(), Some("CALCULO 1"));
+}
+
+#[test]
+fn test_emparejar_ambiguo() {
+    let m = FuzzyMatcher::default();
+    // "Quimica General" está a una letra de ambos candidatos
+    let candidatos = nombres(&["QUIMICA GENERAL A", "QUIMICA GENERAL B"]);
+    match m.emparejar("Química General", &candidatos) {
+        Emparejamiento::Ambiguo(cs) => assert_eq!(cs.len(), 2),
+        otro => panic!("se esperaba ambiguo: {:?}", otro),
+    }
+    // el mismo ramo escrito de dos formas no es ambigüedad
+    let mismo = nombres(&["CALCULO I", "CALCULO 1."]);
+    assert!(matches!(m.emparejar("Calculo 1", &mismo), Emparejamiento::Exacto(_)));
+}
+
+#[test]
+fn test_joins_usan_matching_aproximado() {
+    let mut ramos = HashMap::new();
+    ramos.insert("1".to_string(), ramo("1", "Cálculo I"));
+    ramos.insert("2".to_string(), ramo("2", "Química General"));
+    let oferta = vec![seccion("CBM1000", "CALCULO 1"), seccion("CBQ1000", "QUIMICA GENERAL A"), seccion("CBQ1001", "QUIMICA GENERAL B")];
+
+    let mut por_nombre = HashMap::new();
+    por_nombre.insert("calculo 1".to_string(), ("CBM1000".to_string(), 55.0, 200.0));
+    let reporte = enrich_ramos_with_oferta_and_porcent(&mut ramos, &oferta, &por_nombre, &FuzzyMatcher::default());
+    assert_eq!(ramos["1"].dificultad, Some(55.0));
+    assert_eq!(ramos["2"].dificultad, None);
+    assert!(reporte.aproximados.iter().any(|a| a.nombre == "Cálculo I" && a.candidato == "calculo 1"));
+    assert_eq!(reporte.ambiguos.len(), 1);
+    assert_eq!(reporte.ambiguos[0].nombre, "Química General");
+
+    let porcent_names: HashMap<String, (String, f64, f64, bool)> = por_nombre.into_iter()
+        .map(|(k, (c, p, t))| (k, (c, p, t, false)))
+        .collect();
+    let (filas, reporte) = merge_malla_oferta_porcentajes(&ramos, &oferta, &HashMap::new(), &porcent_names, &FuzzyMatcher::default());
+    let calculo = filas.iter().find(|f| f.malla_codigo == "1").unwrap();
+    assert_eq!(calculo.oferta_codigo.as_deref(), Some("CBM1000"));
+    assert_eq!(calculo.porcentaje, Some(55.0));
+    let quimica = filas.iter().find(|f| f.malla_codigo == "2").unwrap();
+    assert!(quimica.oferta_codigo.is_none());
+    assert_eq!(reporte.ambiguos.len(), 1);
+}
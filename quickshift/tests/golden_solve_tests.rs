@@ -0,0 +1,66 @@
+// Salidas de referencia (golden) del pipeline completo de /solve.
+//
+// `tests/fixtures/golden` trae una malla (10 ramos, formato MC), su oferta y
+// sus porcentajes, sintéticos y chicos. La oferta se llama OA20251.xlsx
+// porque el lector de mallas MC la busca con ese nombre junto a la malla.
+// Cada caso corre `ejecutar_ruta_critica_with_params` sobre ellos y compara
+// el conjunto ordenado de soluciones con un snapshot de insta
+// (`tests/snapshots`). Si un cambio altera el orden o los scores a propósito,
+// revisar y aceptar el snapshot con `cargo insta review`.
+
+use quickshift::algorithm::ejecutar_ruta_critica_with_params;
+use quickshift::api_json::InputParams;
+use quickshift::excel::config::{self, DatafilesConfig, OrigenDatafiles};
+use quickshift::models::Seccion;
+use serde_json::json;
+use std::path::PathBuf;
+
+mod common;
+
+fn usar_fixtures() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden");
+    config::establecer(DatafilesConfig { dir, origen: OrigenDatafiles::LineaDeComandos });
+}
+
+fn params(mut extra: serde_json::Value) -> InputParams {
+    let base = extra.as_object_mut().unwrap();
+    base.entry("email").or_insert(json!("golden@example.com"));
+    base.entry("horarios_preferidos").or_insert(json!([]));
+    common::params("MC2099.xlsx", extra)
+}
+
+/// Una línea por solución: score y secciones en el orden en que salen
+fn resumen(soluciones: &[(Vec<(Seccion, i32)>, i64)]) -> Vec<String> {
+    soluciones.iter().map(|(sol, score)| {
+        let secciones: Vec<String> = sol.iter()
+            .map(|(s, prioridad)| format!("{}-{} ({})", s.codigo, s.seccion, prioridad))
+            .collect();
+        format!("{} | {}", score, secciones.join(", "))
+    }).collect()
+}
+
+fn resolver(extra: serde_json::Value) -> Vec<String> {
+    usar_fixtures();
+    let soluciones = ejecutar_ruta_critica_with_params(params(extra)).expect("pipeline sobre los fixtures");
+    resumen(&soluciones)
+}
+
+#[test]
+fn test_golden_primer_semestre() {
+    let salida = resolver(json!({}));
+    // Dos corridas en el mismo proceso deben coincidir antes de compararlas con el snapshot
+    assert_eq!(salida, resolver(json!({})));
+    insta::assert_json_snapshot!(salida);
+}
+
+#[test]
+fn test_golden_segundo_semestre_con_preferencias() {
+    let extra = json!({
+        "ramos_pasados": ["CBM1000", "CBM1001", "CIT1000", "CBQ1000"],
+        "ramos_prioritarios": ["CIT1010"],
+        "horarios_prohibidos": ["VI 08:00-18:00"]
+    });
+    let salida = resolver(extra.clone());
+    assert_eq!(salida, resolver(extra));
+    insta::assert_json_snapshot!(salida);
+}
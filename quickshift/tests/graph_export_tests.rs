@@ -0,0 +1,41 @@
+use quickshift::algorithm::graph_export::{AristaGrafo, GrafoMalla};
+use quickshift::models::RamoDisponible;
+use std::collections::HashMap;
+
+fn malla() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1, "numb_correlativo": 1, "dificultad": 35.5},
+        {"id": 2, "nombre": "Calculo \"II\"", "codigo": "CBM1001", "semestre": 2, "numb_correlativo": 2, "requisitos_ids": [1, 99]},
+        {"id": 3, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1, "numb_correlativo": 3, "requisitos_ids": [0]}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.codigo.clone(), r)).collect()
+}
+
+#[test]
+fn test_grafo_malla_json() {
+    let g = GrafoMalla::desde_ramos(&malla());
+    let ids: Vec<i32> = g.nodes.iter().map(|n| n.id).collect();
+    assert_eq!(ids, vec![1, 3, 2], "ordenados por semestre e id");
+    // Prerequisitos inexistentes (99) o vacíos (0) no generan aristas
+    assert_eq!(g.edges, vec![AristaGrafo { source: 1, target: 2 }]);
+
+    let calculo = &g.nodes[0];
+    assert!(calculo.critico);
+    assert_eq!(calculo.dificultad, Some(35.5));
+    assert!(!g.nodes[1].critico, "Programacion tiene holgura");
+
+    let v = serde_json::to_value(&g).unwrap();
+    assert_eq!(v["edges"][0], serde_json::json!({"source": 1, "target": 2}));
+    assert_eq!(v["nodes"][2]["semestre"], 2);
+}
+
+#[test]
+fn test_grafo_malla_dot() {
+    let dot = GrafoMalla::desde_ramos(&malla()).to_dot();
+    assert!(dot.starts_with("digraph malla {"));
+    assert!(dot.contains("n1 -> n2;"));
+    assert!(dot.contains(r#"n2 [label="CBM1001\nCalculo \"II\"", semestre=2, critico=true"#), "{}", dot);
+    assert!(dot.contains("dificultad=35.5"));
+    assert!(dot.contains("{ rank=same; n1; n3; }"));
+    assert!(dot.trim_end().ends_with('}'));
+}
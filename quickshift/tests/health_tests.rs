@@ -0,0 +1,35 @@
+#![cfg(feature = "server")]
+
+use actix_web::{test as atest, web, App};
+use actix_web::http::Method;
+use quickshift::middleware::api_keys::{es_lectura_anonima, es_sonda_salud};
+use quickshift::server_handlers::{health_live_handler, health_ready_handler, verificaciones_ready};
+
+#[actix_web::test]
+async fn test_sondas_de_salud() {
+    let app = atest::init_service(App::new()
+        .route("/health/live", web::get().to(health_live_handler))
+        .route("/health/ready", web::get().to(health_ready_handler))).await;
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/health/live").to_request()).await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/health/ready").to_request()).await;
+    let status = resp.status().as_u16();
+    let out: serde_json::Value = atest::read_body_json(resp).await;
+    let checks = out["checks"].as_object().unwrap();
+    assert_eq!(checks.keys().collect::<Vec<_>>(), vec!["analytics_db", "datafiles", "malla"]);
+    let todas_ok = checks.values().all(|c| c["ok"] == true);
+    assert_eq!(status, if todas_ok { 200 } else { 503 }, "{}", out);
+    assert_eq!(out["status"], if todas_ok { "ok" } else { "unavailable" });
+
+    assert!(es_sonda_salud("/health/ready") && !es_lectura_anonima(&Method::GET, "/health/ready"));
+}
+
+#[test]
+fn test_ready_falla_con_malla_inexistente() {
+    let (ok, checks) = verificaciones_ready("NoExiste_health.xlsx");
+    assert!(!ok);
+    assert_eq!(checks["malla"]["ok"], false);
+    assert!(checks["malla"]["detalle"].as_str().unwrap().contains("NoExiste_health.xlsx"));
+    assert_eq!(checks["datafiles"]["ok"], true);
+}
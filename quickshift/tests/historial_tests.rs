@@ -0,0 +1,75 @@
+#![cfg(feature = "server")]
+
+use quickshift::algorithm::en_memoria::{resolver_en_memoria, SolveEnMemoria};
+use quickshift::analithics::db::AnalyticsConn;
+use quickshift::analithics::queries::historial_secciones_con;
+use quickshift::analithics::soluciones::{guardar_soluciones, vincular_solucion, SQLITE_SCHEMA};
+use quickshift::scoring::{establecer_historial, HistorialSeccion};
+use serde_json::json;
+
+fn conn() -> AnalyticsConn {
+    let c = rusqlite::Connection::open_in_memory().unwrap();
+    c.execute_batch(SQLITE_SCHEMA).unwrap();
+    AnalyticsConn::Sqlite(c)
+}
+
+fn solucion(cajas: &[&str]) -> serde_json::Value {
+    json!({"total_score": 1, "secciones": cajas.iter().map(|c| json!({"codigo": &c[..7], "codigo_box": c})).collect::<Vec<_>>()})
+}
+
+#[test]
+fn test_historial_desde_confirmaciones() {
+    let conn = conn();
+    let soluciones = json!([solucion(&["CBM1000-2", "CIT1000-1"]), solucion(&["CBM1000-1", "CIT1000-1"])]);
+    for i in 0..5 {
+        let email = format!("alumno{}@x.cl", i);
+        let id = guardar_soluciones(&conn, Some(&email), &json!({}), &soluciones).unwrap();
+        // Solo cuenta la última confirmación de cada estudiante en el conjunto
+        vincular_solucion(&conn, &email, &id, 0).unwrap();
+        vincular_solucion(&conn, &email, &id, 1).unwrap();
+    }
+    // Un conjunto sin confirmar no cuenta como rechazo
+    guardar_soluciones(&conn, None, &json!({}), &soluciones).unwrap();
+
+    let historial = historial_secciones_con(&conn).unwrap();
+    assert_eq!(historial["CBM1000-1"], HistorialSeccion { ofrecida: 5, confirmada: 5 });
+    assert_eq!(historial["CBM1000-2"], HistorialSeccion { ofrecida: 5, confirmada: 0 });
+    assert_eq!(historial["CIT1000-1"].tasa(), 1.0);
+    assert!(historial["CBM1000-2"].rechazada());
+    assert!(!historial["CBM1000-1"].rechazada());
+    // Con menos de `MIN_OFRECIDA_HISTORIAL` ofertas no hay suficiente evidencia
+    assert!(!HistorialSeccion { ofrecida: 4, confirmada: 0 }.rechazada());
+}
+
+fn entrada(usar_historial: bool) -> SolveEnMemoria {
+    serde_json::from_value(json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": [],
+        "ramos_prioritarios": [],
+        "malla": "MallaEnMemoria",
+        "usar_historial": usar_historial,
+        "ramos": [{"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1}],
+        "secciones": [
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["LU 08:30-09:50"], "codigo_box": "CBM1000-1"},
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "2", "horario": ["MA 08:30-09:50"], "codigo_box": "CBM1000-2"}
+        ]
+    })).unwrap()
+}
+
+#[test]
+fn test_usar_historial_penaliza_secciones_rechazadas() {
+    establecer_historial([
+        ("CBM1000-2".to_string(), HistorialSeccion { ofrecida: 20, confirmada: 1 }),
+        ("CBM1000-1".to_string(), HistorialSeccion { ofrecida: 20, confirmada: 15 }),
+    ].into_iter().collect());
+
+    // Sin el modo, la sección 2 gana por su número de sección
+    let sin = resolver_en_memoria(entrada(false)).unwrap();
+    assert_eq!(sin[0].0[0].0.codigo_box, "CBM1000-2");
+
+    let con = resolver_en_memoria(entrada(true)).unwrap();
+    assert_eq!(con[0].0[0].0.codigo_box, "CBM1000-1");
+    let penalizada = con.iter().find(|(s, _)| s[0].0.codigo_box == "CBM1000-2").unwrap();
+    let original = sin.iter().find(|(s, _)| s[0].0.codigo_box == "CBM1000-2").unwrap();
+    assert_eq!(original.1 - penalizada.1, quickshift::scoring::config().penalizacion_historial);
+}
@@ -0,0 +1,88 @@
+use quickshift::models::horario::{normalizar, normalizar_dia, normalizar_hora, parse, parse_hora, parse_rango, Bloque, Dia};
+
+fn b(dia: Dia, inicio: u32, fin: u32) -> Bloque {
+    Bloque { dia, inicio, fin }
+}
+
+#[test]
+fn test_parse_hora_formatos() {
+    assert_eq!(parse_hora("08:30"), Some(510));
+    assert_eq!(parse_hora("8:30"), Some(510));
+    assert_eq!(parse_hora("08.30"), Some(510));
+    assert_eq!(parse_hora("0830"), Some(510));
+    assert_eq!(parse_hora("2:30PM"), Some(870));
+    assert_eq!(parse_hora("12:00 AM"), Some(0));
+    assert_eq!(parse_hora("25:00"), None);
+    assert_eq!(parse_hora("08:75"), None);
+    assert_eq!(parse_hora("LU"), None);
+}
+
+#[test]
+fn test_parse_dias_y_guiones() {
+    assert_eq!(parse("LU MA 08:30 - 10:00"), vec![b(Dia::Lunes, 510, 600), b(Dia::Martes, 510, 600)]);
+    assert_eq!(parse("JU 10:00-11:20"), vec![b(Dia::Jueves, 600, 680)]);
+    assert_eq!(parse("MI 14:30–15:50"), vec![b(Dia::Miercoles, 870, 950)]);
+    assert_eq!(parse("VI 14:30 — 15:50"), vec![b(Dia::Viernes, 870, 950)]);
+    assert_eq!(parse("Miércoles 8:30-10:00"), vec![b(Dia::Miercoles, 510, 600)]);
+    assert_eq!(parse("LU,MI 08:30-10:00"), vec![b(Dia::Lunes, 510, 600), b(Dia::Miercoles, 510, 600)]);
+    assert_eq!(parse("LU:08:30-10:00"), vec![b(Dia::Lunes, 510, 600)]);
+}
+
+#[test]
+fn test_parse_fin_de_semana_y_varios_tramos() {
+    assert_eq!(parse("SA 09:00 - 12:00"), vec![b(Dia::Sabado, 540, 720)]);
+    assert_eq!(parse("Sáb 09:00-12:00"), vec![b(Dia::Sabado, 540, 720)]);
+    assert_eq!(parse("DOM 10:00-11:00"), vec![b(Dia::Domingo, 600, 660)]);
+    assert_eq!(
+        parse("LU 08:30-10:00 JU 14:30-15:50"),
+        vec![b(Dia::Lunes, 510, 600), b(Dia::Jueves, 870, 950)]
+    );
+}
+
+#[test]
+fn test_parse_sin_horario_y_rangos_sin_dia() {
+    assert!(parse("Sin horario").is_empty());
+    assert!(parse("Por definir").is_empty());
+    assert!(parse("").is_empty());
+    assert!(parse("LU 10:00 - 08:00").is_empty());
+
+    assert!(parse("08:30-10:00").is_empty());
+    assert_eq!(parse_rango("08:30-10:00"), Some((510, 600)));
+    assert_eq!(parse_rango("LU 08:30 - 10:00"), Some((510, 600)));
+    assert_eq!(parse_rango("Sin horario"), None);
+}
+
+#[test]
+fn test_solapa_y_distancia() {
+    let lu = b(Dia::Lunes, 510, 600);
+    assert!(lu.solapa(&b(Dia::Lunes, 540, 660)));
+    assert!(!lu.solapa(&b(Dia::Lunes, 600, 680)));
+    assert!(!lu.solapa(&b(Dia::Martes, 510, 600)));
+    assert_eq!(lu.distancia(&b(Dia::Lunes, 630, 700)), Some(30));
+    assert_eq!(b(Dia::Lunes, 630, 700).distancia(&lu), Some(30));
+    assert_eq!(lu.distancia(&b(Dia::Lunes, 540, 560)), Some(0));
+    assert_eq!(lu.distancia(&b(Dia::Martes, 630, 700)), None);
+}
+
+#[test]
+fn test_dias_en_ingles_y_formato_12_horas() {
+    assert_eq!(parse_hora("2PM"), Some(840));
+    assert_eq!(parse_hora("2:30 p.m."), Some(870));
+    assert_eq!(parse_hora("12 a.m."), Some(0));
+    assert_eq!(
+        parse("MON WED 2:30 PM - 3:50 PM"),
+        vec![b(Dia::Lunes, 870, 950), b(Dia::Miercoles, 870, 950)]
+    );
+    assert_eq!(parse("Thursday 8:30am-10am"), vec![b(Dia::Jueves, 510, 600)]);
+    assert_eq!(parse("TUE,FRI 9 AM - 10:20 AM"), vec![b(Dia::Martes, 540, 620), b(Dia::Viernes, 540, 620)]);
+    // Los códigos en español siguen teniendo precedencia ("SA" = sábado, "MA" = martes)
+    assert_eq!(Dia::desde_codigo("SAT"), Some(Dia::Sabado));
+    assert_eq!(Dia::desde_codigo("MA"), Some(Dia::Martes));
+    assert_eq!(Dia::desde_codigo("Sin"), None);
+
+    assert_eq!(normalizar("mon wed 2:30 PM - 3:50 PM"), "LU MI 14:30-15:50");
+    assert_eq!(normalizar("8am - 10am"), "08:00-10:00");
+    assert_eq!(normalizar("Sin horario"), "Sin horario");
+    assert_eq!(normalizar_dia("Friday"), "VI");
+    assert_eq!(normalizar_hora("1:15 PM"), "13:15");
+}
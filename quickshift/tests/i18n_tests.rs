@@ -0,0 +1,91 @@
+#![cfg(feature = "server")]
+
+use actix_web::{test as atest, web, App, HttpResponse, ResponseError};
+use quickshift::api_error::ApiError;
+use quickshift::api_json::parse_and_resolve_ramos_with_resolver;
+use quickshift::i18n::{tf, Idioma};
+use quickshift::server_handlers::help_handler;
+
+#[test]
+fn test_idioma_pedido() {
+    assert_eq!(Idioma::desde_codigo("en-US"), Some(Idioma::En));
+    assert_eq!(Idioma::desde_codigo("es_CL"), Some(Idioma::Es));
+    assert_eq!(Idioma::desde_codigo("fr"), None);
+    assert_eq!(Idioma::desde_accept_language("fr-FR, en;q=0.8, es;q=0.9"), Some(Idioma::Es));
+    assert_eq!(Idioma::desde_accept_language("en-GB,en;q=0.9"), Some(Idioma::En));
+    assert_eq!(Idioma::desde_accept_language("de, es;q=0"), None);
+    assert_eq!(tf("explain.prerequisitos_faltantes", Idioma::Es, &[&"CIT3313", &"CIT2108"]), "a CIT3313 le faltan prerequisitos: CIT2108");
+    assert_eq!(tf("clave.inexistente", Idioma::En, &[]), "clave.inexistente");
+}
+
+#[test]
+fn test_mensajes_de_error_traducidos() {
+    let errores = [
+        ApiError::MissingParameter("ramo"),
+        ApiError::MallaNotFound { malla: "MC1999.xlsx".into(), available: Vec::new() },
+        ApiError::InvalidInput("filtros inválidos".into()),
+        ApiError::NoFeasibleSchedule,
+        ApiError::RateLimited { limit_per_min: 5, retry_after_secs: 10 },
+    ];
+    // En inglés el mensaje es el mismo de `Display` (el que se usa sin `lang`)
+    for e in &errores {
+        assert_eq!(e.mensaje(Idioma::En), e.to_string());
+    }
+    assert_eq!(errores[0].mensaje(Idioma::Es), "falta el parámetro 'ramo'");
+    assert_eq!(errores[1].mensaje(Idioma::Es), "malla 'MC1999.xlsx' no encontrada");
+    assert_eq!(errores[2].to_json_en(Idioma::Es)["message"], "entrada inválida: filtros inválidos");
+}
+
+#[actix_web::test]
+async fn test_lang_en_query_y_accept_language() {
+    let app = atest::init_service(App::new()
+        .wrap(actix_web::middleware::from_fn(quickshift::middleware::idioma))
+        .route("/help", web::get().to(help_handler))
+        .route("/falla", web::get().to(|| async { ApiError::MissingParameter("ramo").error_response() }))
+        .route("/ok", web::get().to(|| async { HttpResponse::Ok().finish() }))).await;
+
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/help?lang=en").to_request()).await;
+    assert_eq!(resp.headers().get("content-language").unwrap(), "en");
+    let out: serde_json::Value = atest::read_body_json(resp).await;
+    assert!(out["description"].as_str().unwrap().starts_with("API to build"));
+    assert_eq!(out["lang"], "en");
+
+    // El query gana sobre la cabecera
+    let req = atest::TestRequest::get().uri("/falla?lang=es").insert_header(("Accept-Language", "en-US")).to_request();
+    let out: serde_json::Value = atest::read_body_json(atest::call_service(&app, req).await).await;
+    assert_eq!(out["message"], "falta el parámetro 'ramo'");
+    assert_eq!(out["code"], "MISSING_PARAMETER");
+
+    let req = atest::TestRequest::get().uri("/falla").insert_header(("Accept-Language", "en-US,en;q=0.9")).to_request();
+    let out: serde_json::Value = atest::read_body_json(atest::call_service(&app, req).await).await;
+    assert_eq!(out["message"], "missing parameter 'ramo'");
+
+    // Sin idioma pedido: sin Content-Language y `/help` en español
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/ok").to_request()).await;
+    assert!(resp.headers().get("content-language").is_none());
+    let out: serde_json::Value = atest::read_body_json(
+        atest::call_service(&app, atest::TestRequest::get().uri("/help").to_request()).await).await;
+    assert!(out["description"].as_str().unwrap().starts_with("API para obtener"));
+}
+
+#[test]
+fn test_filtros_en_ingles_se_normalizan() {
+    let json = r#"{
+        "email": "a@example.com",
+        "ramos_pasados": [],
+        "ramos_prioritarios": [],
+        "horarios_prohibidos": ["FRI 2 PM - 6 PM"],
+        "malla": "MC2020.xlsx",
+        "filtros": {"dias_horarios_libres": {
+            "habilitado": true,
+            "dias_libres_preferidos": ["Mon", "fri"],
+            "franjas_prohibidas": [{"dia": "TUE", "inicio": "8:30 AM", "fin": "10 AM"}]
+        }}
+    }"#;
+    let params = parse_and_resolve_ramos_with_resolver(json, None::<&str>, |_, _| Ok(None)).unwrap();
+    assert_eq!(params.horarios_prohibidos, vec!["VI 14:00-18:00"]);
+    let dhl = params.filtros.unwrap().dias_horarios_libres.unwrap();
+    assert_eq!(dhl.dias_libres_preferidos.unwrap(), vec!["LU", "VI"]);
+    let franja = &dhl.franjas_prohibidas.unwrap()[0];
+    assert_eq!((franja.dia.as_str(), franja.inicio.as_str(), franja.fin.as_str()), ("MA", "08:30", "10:00"));
+}
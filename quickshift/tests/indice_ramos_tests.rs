@@ -0,0 +1,85 @@
+// `RamoIndex`: las búsquedas del planificador por código, nombre e id sin
+// recorrer la malla.
+
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::algorithm::indice_ramos::RamoIndex;
+use quickshift::algorithm::sintetico::oferta_sintetica;
+use quickshift::api_json::InputParams;
+use quickshift::models::{RamoDisponible, Seccion};
+use serde_json::json;
+use std::collections::HashMap;
+
+mod common;
+use common::seccion_con;
+
+fn ramo(id: i32, codigo: &str, nombre: &str) -> RamoDisponible {
+    serde_json::from_value(json!({"id": id, "codigo": codigo, "nombre": nombre})).unwrap()
+}
+
+fn seccion(codigo: &str, nombre: &str) -> Seccion {
+    seccion_con(codigo, "1", &[], json!({"nombre": nombre}))
+}
+
+fn malla() -> HashMap<String, RamoDisponible> {
+    [
+        ramo(3, "CBM1001", "Cálculo II"),
+        ramo(1, "CBM1000", "Cálculo I"),
+        ramo(2, "", "Física I"),
+        ramo(7, "CIT2107", "Cálculo I"),
+    ].into_iter().map(|r| (r.nombre.clone() + &r.id.to_string(), r)).collect()
+}
+
+#[test]
+fn test_busquedas_por_codigo_nombre_e_id() {
+    let malla = malla();
+    let indice = RamoIndex::new(&malla);
+    assert_eq!(indice.len(), 4);
+    assert_eq!(indice.ramos().map(|r| r.id).collect::<Vec<_>>(), vec![1, 2, 3, 7]);
+
+    assert_eq!(indice.por_codigo(" cbm1001 ").map(|r| r.id), Some(3));
+    assert!(indice.por_codigo("").is_none());
+    assert!(indice.por_codigo("CFG0001").is_none());
+    assert_eq!(indice.por_nombre("FISICA  i").map(|r| r.id), Some(2));
+    assert_eq!(indice.por_id(7).map(|r| r.codigo.as_str()), Some("CIT2107"));
+    assert_eq!(indice.por_id(99).map(|r| r.id), None);
+
+    // nombre repetido: gana el de menor id
+    assert_eq!(indice.por_nombre("calculo i").map(|r| r.id), Some(1));
+}
+
+#[test]
+fn test_ramo_de_seccion() {
+    let malla = malla();
+    let indice = RamoIndex::new(&malla);
+
+    // el código manda sobre el nombre
+    assert_eq!(indice.de_seccion(&seccion("CIT2107", "Cálculo I")).map(|r| r.id), Some(7));
+    // sin código en la malla se busca por nombre
+    assert_eq!(indice.de_seccion(&seccion("FIS100", "FÍSICA I")).map(|r| r.id), Some(2));
+    // un código vacío no calza con los ramos sin código
+    assert_eq!(indice.de_seccion(&seccion("", "Química")).map(|r| r.id), None);
+
+    // las búsquedas repetidas (también las fallidas) dan lo mismo
+    for _ in 0..3 {
+        assert_eq!(indice.de_seccion(&seccion("CFG1001", "Taller de teatro")).map(|r| r.id), None);
+        assert_eq!(indice.de_seccion(&seccion("cbm1000", "otro nombre")).map(|r| r.id), Some(1));
+    }
+}
+
+#[test]
+fn test_greedy_determinista_con_indice() {
+    let oferta = oferta_sintetica(120, 9);
+    let params: InputParams = serde_json::from_value(json!({
+        "email": "indice@x.cl", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "sintetica"
+    })).unwrap();
+    let claves = |sols: &[(Vec<(Seccion, i32)>, i64)]| -> Vec<(Vec<String>, i64)> {
+        sols.iter().map(|(sol, total)| (sol.iter().map(|(s, _)| s.codigo_box.clone()).collect(), *total)).collect()
+    };
+    let a = get_clique_max_pond_with_prefs(&oferta.secciones, &oferta.ramos, &params);
+    let b = get_clique_max_pond_with_prefs(&oferta.secciones, &oferta.ramos, &params);
+    assert!(!a.is_empty());
+    assert_eq!(claves(&a), claves(&b));
+    // cada sección de una solución tiene su ramo en la malla sintética
+    let indice = RamoIndex::new(&oferta.ramos);
+    assert!(a.iter().flat_map(|(sol, _)| sol).all(|(s, _)| indice.de_seccion(s).is_some()));
+}
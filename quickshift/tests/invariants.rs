@@ -0,0 +1,273 @@
+// Invariantes de toda solución, sobre ofertas y parámetros generados al azar
+// (proptest): sin choques de horario, sin ramos repetidos, a lo más
+// `max_size` ramos, filtros duros respetados y prerequisitos de los
+// electivos aprobados. Cubre el pipeline completo (`resolver_con_datos`) y el
+// enumerador de combinaciones.
+//
+// Más casos: PROPTEST_CASES=1000 cargo test --test invariants
+
+use std::collections::{HashMap, HashSet};
+use proptest::prelude::*;
+use quickshift::algorithm::clique::get_all_clique_combinations_with_pert;
+use quickshift::algorithm::compromisos::choca_con;
+use quickshift::algorithm::conflict::horarios_tienen_conflicto;
+use quickshift::algorithm::filters::{expand_horario_entry, solapan_horarios};
+use quickshift::algorithm::ruta::{resolver_con_datos, DatosRuta};
+use quickshift::api_json::{normalizar_horarios, InputParams};
+use quickshift::models::{RamoDisponible, Seccion};
+
+mod common;
+use common::seccion_con;
+
+const DIAS: [&str; 5] = ["LU", "MA", "MI", "JU", "VI"];
+const MODULOS: [(&str, &str); 6] = [
+    ("08:30", "09:50"), ("10:00", "11:20"), ("11:30", "12:50"),
+    ("14:30", "15:50"), ("16:00", "17:20"), ("17:25", "18:45"),
+];
+/// Tope de ramos del pipeline sin `max_creditos`
+const MAX_RAMOS: usize = 6;
+
+/// Sección generada: día, si también tiene clase dos días después, módulo y profesor
+type SeccionGen = (usize, bool, usize, usize);
+
+#[derive(Debug, Clone)]
+struct RamoGen {
+    secciones: Vec<SeccionGen>,
+    electivo: bool,
+    /// Índice (módulo la posición del ramo) del ramo que pide como prerequisito
+    prerequisito: Option<usize>,
+    semestre: i32,
+    aprobado: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Caso {
+    ramos: Vec<RamoGen>,
+    cfgs: Vec<SeccionGen>,
+    prohibido: Option<(usize, usize)>,
+    compromiso: Option<(usize, usize)>,
+    dia_libre: Option<usize>,
+    excluido: Option<usize>,
+}
+
+fn seccion_gen() -> impl Strategy<Value = SeccionGen> {
+    (0..DIAS.len(), any::<bool>(), 0..MODULOS.len(), 0..4usize)
+}
+
+fn ramo_gen() -> impl Strategy<Value = RamoGen> {
+    (
+        prop::collection::vec(seccion_gen(), 1..=3),
+        prop::bool::weighted(0.3),
+        prop::option::weighted(0.5, 0..8usize),
+        1..=3i32,
+        prop::bool::weighted(0.25),
+    ).prop_map(|(secciones, electivo, prerequisito, semestre, aprobado)| RamoGen {
+        secciones, electivo, prerequisito, semestre, aprobado,
+    })
+}
+
+fn caso() -> impl Strategy<Value = Caso> {
+    let franja = (0..DIAS.len(), 0..MODULOS.len());
+    (
+        prop::collection::vec(ramo_gen(), 3..=9),
+        prop::collection::vec(seccion_gen(), 0..=2),
+        prop::option::of(franja.clone()),
+        prop::option::of(franja),
+        prop::option::weighted(0.3, 0..DIAS.len()),
+        prop::option::weighted(0.3, 0..9usize),
+    ).prop_map(|(ramos, cfgs, prohibido, compromiso, dia_libre, excluido)| Caso {
+        ramos, cfgs, prohibido, compromiso, dia_libre, excluido,
+    })
+}
+
+fn horario((dia, dos_dias, modulo, _): SeccionGen) -> String {
+    let (inicio, fin) = MODULOS[modulo];
+    if dos_dias {
+        format!("{} {} {}-{}", DIAS[dia], DIAS[(dia + 2) % DIAS.len()], inicio, fin)
+    } else {
+        format!("{} {}-{}", DIAS[dia], inicio, fin)
+    }
+}
+
+fn seccion(codigo: &str, nombre: &str, n: usize, g: SeccionGen, electivo: bool, cfg: bool) -> Seccion {
+    seccion_con(codigo, &n.to_string(), &[&horario(g)], serde_json::json!({
+        "nombre": nombre,
+        "profesor": format!("Profesor {}", g.3),
+        "is_cfg": cfg,
+        "is_electivo": electivo,
+    }))
+}
+
+fn codigo(i: usize) -> String {
+    format!("INV{:03}", i)
+}
+
+fn datos(caso: &Caso) -> DatosRuta {
+    let mut ramos_disponibles = HashMap::new();
+    let mut lista_secciones = Vec::new();
+    for (i, r) in caso.ramos.iter().enumerate() {
+        let nombre = format!("Ramo invariante {}", i);
+        let requisitos_ids = match r.prerequisito {
+            Some(p) if i > 0 => vec![(p % i) as i32 + 1],
+            _ => Vec::new(),
+        };
+        ramos_disponibles.insert(nombre.clone(), RamoDisponible {
+            id: i as i32 + 1,
+            nombre: nombre.clone(),
+            codigo: codigo(i),
+            holgura: (i % 3) as i32,
+            numb_correlativo: i as i32 + 1,
+            critico: i % 2 == 0,
+            requisitos_ids,
+            correquisitos_ids: Vec::new(),
+            dificultad: Some(50.0),
+            electivo: r.electivo,
+            semestre: Some(r.semestre),
+            creditos: Some(6),
+            tags: Vec::new(),
+        });
+        for (n, g) in r.secciones.iter().enumerate() {
+            lista_secciones.push(seccion(&codigo(i), &nombre, n + 1, *g, r.electivo, false));
+        }
+    }
+    for (k, g) in caso.cfgs.iter().enumerate() {
+        let cfg = format!("CFG{:03}", k);
+        lista_secciones.push(seccion(&cfg, &format!("Formación general {}", k), 1, *g, false, true));
+    }
+    DatosRuta {
+        malla_path: String::new(),
+        ramos_disponibles,
+        lista_secciones,
+        progreso: Vec::new(),
+        compartidos: Vec::new(),
+    }
+}
+
+fn params(caso: &Caso) -> InputParams {
+    let ramos_pasados: Vec<String> = caso.ramos.iter().enumerate()
+        .filter(|(_, r)| r.aprobado)
+        .map(|(i, _)| codigo(i))
+        .collect();
+    let franja = |(dia, modulo): (usize, usize)| {
+        let (inicio, fin) = MODULOS[modulo];
+        (DIAS[dia], inicio, fin)
+    };
+    let horarios_prohibidos: Vec<String> = caso.prohibido.map(franja).into_iter()
+        .map(|(dia, inicio, fin)| format!("{} {}-{}", dia, inicio, fin))
+        .collect();
+    let compromisos: Vec<serde_json::Value> = caso.compromiso.map(franja).into_iter()
+        .map(|(dia, inicio, fin)| serde_json::json!({"etiqueta": "Trabajo", "dia": dia, "inicio": inicio, "fin": fin}))
+        .collect();
+    let filtros = caso.dia_libre.map(|d| serde_json::json!({
+        "dias_horarios_libres": {"habilitado": true, "dias_libres_preferidos": [DIAS[d]]}
+    }));
+    let ramos_excluidos: Vec<String> = caso.excluido.map(codigo).into_iter().collect();
+
+    let mut p = common::params("MiMalla.xlsx", serde_json::json!({
+        "ramos_pasados": ramos_pasados,
+        "ramos_excluidos": ramos_excluidos,
+        "horarios_prohibidos": horarios_prohibidos,
+        "compromisos": compromisos,
+        "filtros": filtros,
+    }));
+    normalizar_horarios(&mut p);
+    p
+}
+
+/// Verifica las invariantes de una solución; devuelve la primera que falla
+fn revisar(sol: &[(Seccion, i32)], datos: &DatosRuta, params: &InputParams, max_size: usize) -> Result<(), String> {
+    let nombres: Vec<&str> = sol.iter().map(|(s, _)| s.codigo_box.as_str()).collect();
+
+    if sol.len() > max_size {
+        return Err(format!("{} ramos (máximo {}): {:?}", sol.len(), max_size, nombres));
+    }
+    let mut codigos = HashSet::new();
+    for (s, _) in sol {
+        if !codigos.insert(s.codigo.to_uppercase()) {
+            return Err(format!("ramo {} repetido: {:?}", s.codigo, nombres));
+        }
+    }
+    for (i, (a, _)) in sol.iter().enumerate() {
+        for (b, _) in &sol[i + 1..] {
+            if horarios_tienen_conflicto(&a.horario, &b.horario) {
+                return Err(format!("{} {:?} choca con {} {:?}", a.codigo_box, a.horario, b.codigo_box, b.horario));
+            }
+        }
+    }
+
+    let aprobados: HashSet<String> = params.ramos_pasados.iter().map(|c| c.to_uppercase()).collect();
+    let dias_libres: Vec<String> = params.filtros.as_ref()
+        .and_then(|f| f.dias_horarios_libres.as_ref())
+        .and_then(|d| d.dias_libres_preferidos.clone())
+        .unwrap_or_default();
+    for (s, _) in sol {
+        if aprobados.contains(&s.codigo.to_uppercase()) {
+            return Err(format!("{} ya está aprobado", s.codigo));
+        }
+        if params.ramos_excluidos.iter().any(|e| e.eq_ignore_ascii_case(&s.codigo)) {
+            return Err(format!("{} está excluido", s.codigo));
+        }
+        if solapan_horarios(&s.horario, &params.horarios_prohibidos) {
+            return Err(format!("{} {:?} pisa una franja prohibida {:?}", s.codigo_box, s.horario, params.horarios_prohibidos));
+        }
+        if let Some(c) = choca_con(s, params) {
+            return Err(format!("{} {:?} pisa el compromiso {} {} {}-{}", s.codigo_box, s.horario, c.etiqueta, c.dia, c.inicio, c.fin));
+        }
+        let dias: Vec<String> = s.horario.iter().flat_map(|h| expand_horario_entry(h)).map(|(d, _, _)| d).collect();
+        if let Some(d) = dias_libres.iter().find(|d| dias.contains(d)) {
+            return Err(format!("{} {:?} tiene clases el {}, que debía quedar libre", s.codigo_box, s.horario, d));
+        }
+        if s.is_electivo {
+            let ramo = datos.ramos_disponibles.values().find(|r| r.codigo == s.codigo)
+                .ok_or_else(|| format!("electivo {} fuera de la malla", s.codigo))?;
+            for id in &ramo.requisitos_ids {
+                let requisito = datos.ramos_disponibles.values().find(|r| r.id == *id).expect("requisito en la malla");
+                if !aprobados.contains(&requisito.codigo.to_uppercase()) {
+                    return Err(format!("electivo {} sin su prerequisito {}", s.codigo, requisito.codigo));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn soluciones_del_pipeline_cumplen_invariantes(caso in caso()) {
+        let datos = datos(&caso);
+        let params = params(&caso);
+        let soluciones = resolver_con_datos(&datos, &params, None).expect("pipeline");
+        for (sol, _) in &soluciones {
+            if let Err(e) = revisar(sol, &datos, &params, MAX_RAMOS) {
+                prop_assert!(false, "{}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn combinaciones_cumplen_invariantes(caso in caso(), max_size in 1..=MAX_RAMOS) {
+        let datos = datos(&caso);
+        let params = params(&caso);
+        // El enumerador no aplica los filtros duros de `resolver_con_datos`:
+        // se revisan sobre la oferta que ya los pasó
+        let viables: Vec<Seccion> = datos.lista_secciones.iter()
+            .filter(|s| !solapan_horarios(&s.horario, &params.horarios_prohibidos) && choca_con(s, &params).is_none())
+            .filter(|s| {
+                let dias_libres = params.filtros.as_ref()
+                    .and_then(|f| f.dias_horarios_libres.as_ref())
+                    .and_then(|d| d.dias_libres_preferidos.as_ref());
+                !s.horario.iter().flat_map(|h| expand_horario_entry(h))
+                    .any(|(d, _, _)| dias_libres.is_some_and(|l| l.contains(&d)))
+            })
+            .cloned()
+            .collect();
+        let soluciones = get_all_clique_combinations_with_pert(&viables, &datos.ramos_disponibles, &params, max_size, 500);
+        for (sol, _) in &soluciones {
+            if let Err(e) = revisar(sol, &datos, &params, max_size) {
+                prop_assert!(false, "{}", e);
+            }
+        }
+    }
+}
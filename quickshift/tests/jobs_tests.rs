@@ -0,0 +1,110 @@
+#![cfg(feature = "server")]
+
+use actix_web::{test as atest, web, App};
+use quickshift::analithics::db::AnalyticsConn;
+use quickshift::analithics::jobs::*;
+use quickshift::server_handlers::{job_result_handler, job_status_handler, solve_async_handler};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+fn conn() -> AnalyticsConn {
+    let c = rusqlite::Connection::open_in_memory().unwrap();
+    c.execute(SQLITE_SCHEMA, []).unwrap();
+    AnalyticsConn::Sqlite(c)
+}
+
+#[test]
+fn test_guardar_y_obtener_job_terminado() {
+    let conn = conn();
+    let mut job = JobTerminado {
+        id: "b0c5a1e2-0000-4000-8000-000000000001".into(),
+        creado: "2026-03-01T10:00:00+00:00".into(),
+        terminado: "2026-03-01T10:04:00+00:00".into(),
+        email: Some("Alumno@X.cl".into()),
+        estado: "fallido".into(),
+        status: 500,
+        params: json!({"malla": "MC2020.xlsx", "solver": "exact"}),
+        progreso: json!({"secciones_viables": 120, "cliques": 0}),
+        resultado: json!({"code": "SOLVER_ERROR", "message": "solver error: x", "details": null}),
+    };
+    guardar_job(&conn, &job).unwrap();
+
+    // Volver a guardar el mismo id reemplaza la fila
+    job.estado = "completado".into();
+    job.status = 200;
+    job.resultado = json!({"soluciones_count": 1, "partial": true});
+    guardar_job(&conn, &job).unwrap();
+
+    let leido = obtener_job(&conn, &job.id).unwrap().unwrap();
+    assert_eq!(leido.email.as_deref(), Some("alumno@x.cl"));
+    assert_eq!((leido.estado.as_str(), leido.status), ("completado", 200));
+    assert_eq!(leido.resultado["partial"], true);
+    assert_eq!(leido.progreso["secciones_viables"], 120);
+    assert!(obtener_job(&conn, "otro").unwrap().is_none());
+}
+
+#[actix_web::test]
+async fn test_job_asincrono_termina_y_expone_resultado() {
+    let db = std::env::temp_dir().join(format!("quickshift_jobs_{}.db", std::process::id()));
+    // Solo este test usa la DB de analíticas en este binario
+    unsafe {
+        std::env::set_var("ANALITHICS_DB_PATH", &db);
+    }
+    quickshift::analithics::init_db().unwrap();
+
+    let app = atest::init_service(
+        App::new()
+            .route("/solve/async", web::post().to(solve_async_handler))
+            .route("/jobs/{id}", web::get().to(job_status_handler))
+            .route("/jobs/{id}/result", web::get().to(job_result_handler)),
+    ).await;
+
+    let body = json!({"email": "alumno@x.cl", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "NoExiste2099.xlsx"});
+    let req = atest::TestRequest::post().uri("/solve/async").set_json(body).to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 202);
+    let creado: Value = atest::read_body_json(resp).await;
+    let id = creado["job_id"].as_str().unwrap().to_string();
+    assert_eq!(creado["result_url"], format!("/jobs/{}/result", id));
+
+    let mut estado = Value::Null;
+    for _ in 0..200 {
+        let req = atest::TestRequest::get().uri(&format!("/jobs/{}", id)).to_request();
+        estado = atest::call_and_read_body_json(&app, req).await;
+        if estado["estado"] == "completado" || estado["estado"] == "fallido" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    // La malla no existe: el job falla con el mismo error que daría /solve
+    assert_eq!(estado["estado"], "fallido", "{}", estado);
+    assert_eq!(estado["error"]["code"], "MALLA_NOT_FOUND");
+    assert!(estado["progreso"]["cliques"].is_u64());
+
+    let req = atest::TestRequest::get().uri(&format!("/jobs/{}/result", id)).to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    let resultado: Value = atest::read_body_json(resp).await;
+    assert_eq!(resultado["code"], "MALLA_NOT_FOUND");
+
+    // El resultado queda en la DB de analíticas (se escribe justo después de
+    // marcar el job como terminado)
+    let conn = quickshift::analithics::db::open_analytics_connection().unwrap();
+    let mut guardado = None;
+    for _ in 0..100 {
+        guardado = obtener_job(&conn, &id).unwrap();
+        if guardado.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let guardado = guardado.expect("job guardado");
+    assert_eq!((guardado.estado.as_str(), guardado.status), ("fallido", 404));
+
+    let req = atest::TestRequest::get().uri("/jobs/no-existe").to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    let body: Value = atest::read_body_json(resp).await;
+    assert_eq!(body["code"], "JOB_NOT_FOUND");
+    let _ = std::fs::remove_file(db);
+}
@@ -0,0 +1,76 @@
+use quickshift::excel::layout::{establecer_registro, LayoutProfile, RegistroLayouts};
+use quickshift::excel::{leer_oferta_academica_excel, leer_porcentajes_aprobados};
+
+const LAYOUTS: &str = r#"
+[perfil.uch]
+archivos = ["CATALOGO_*.xlsx", "notas_uch*"]
+
+[perfil.uch.oferta]
+fila_encabezado = [["sigla"]]
+codigo = { encabezados = ["=sigla"] }
+nombre = { encabezados = ["curso"] }
+seccion = { encabezados = ["grupo"] }
+profesor = { encabezados = ["docente"] }
+
+[perfil.uch.porcentajes]
+codigo = { encabezados = ["sigla"] }
+porcentaje = { encabezados = ["tasa"] }
+"#;
+
+fn escribir(ruta: &std::path::Path, filas: &[&[&str]]) {
+    let mut libro = umya_spreadsheet::new_file();
+    let hoja = libro.get_sheet_mut(0);
+    for (i, fila) in filas.iter().enumerate() {
+        for (j, valor) in fila.iter().enumerate() {
+            let coord = format!("{}{}", (b'A' + j as u8) as char, i + 1);
+            hoja.get_cell_mut(coord).set_value(*valor);
+        }
+    }
+    umya_spreadsheet::writer::xlsx::write(&libro, ruta).unwrap();
+}
+
+#[test]
+fn test_registro_elige_perfil_por_archivo() {
+    let registro = RegistroLayouts::desde_toml(LAYOUTS).unwrap();
+    assert_eq!(registro.para("/datos/catalogo_2025.XLSX").map(|(id, _)| id), Some("uch"));
+    assert_eq!(registro.para("notas_uch_2024").map(|(id, _)| id), Some("uch"));
+    assert!(registro.para("OA20251.xlsx").is_none());
+
+    // Lo que el perfil no declara es lo de la UDP
+    let (_, perfil) = registro.para("CATALOGO_1.xlsx").unwrap();
+    let udp = LayoutProfile::default();
+    assert_eq!(perfil.malla, udp.malla);
+    assert_eq!(perfil.oferta.horario, udp.oferta.horario);
+    assert_ne!(perfil.oferta.codigo, udp.oferta.codigo);
+    assert_eq!(udp.oferta.codigo.primera(&["Asignatura", "Nombre Asig.", "Sección"]), Some(0));
+    assert_eq!(udp.oferta.cupos_disponibles.primera(&["Vac. Ev.", "Cupos disponibles"]), Some(1));
+
+    assert!(RegistroLayouts::desde_toml("[perfil.vacio]\narchivos = []").is_err());
+    assert!(RegistroLayouts::desde_toml("[perfil.x]\narchivos = [\"a*\"]\n[perfil.x.oferta]\ncolumna_rara = {}").is_err());
+}
+
+#[test]
+fn test_lee_oferta_y_porcentajes_con_otro_formato() {
+    establecer_registro(RegistroLayouts::desde_toml(LAYOUTS).unwrap());
+    let dir = std::env::temp_dir().join(format!("quickshift_layout_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let oferta = dir.join("CATALOGO_2025.xlsx");
+    escribir(&oferta, &[
+        &["Sigla", "Curso", "Grupo", "Docente", "Horario"],
+        &["MA1001", "Calculo", "2", "Soto", "LU 08:30 - 09:50"],
+        &["FI1001", "Mecanica", "1", "Vera", "MA 10:00 - 11:20"],
+    ]);
+    let secciones = leer_oferta_academica_excel(oferta.to_str().unwrap()).unwrap();
+    let calculo = secciones.iter().find(|s| s.codigo == "MA1001").expect("sección leída por encabezado 'Sigla'");
+    assert_eq!((calculo.nombre.as_str(), calculo.seccion.as_str(), calculo.profesor.as_str()), ("Calculo", "2", "Soto"));
+    assert_eq!(calculo.horario, vec!["LU 08:30 - 09:50"]);
+
+    let notas = dir.join("notas_uch.xlsx");
+    escribir(&notas, &[
+        &["Sigla", "Tasa"],
+        &["MA1001", "62.5"],
+    ]);
+    let porcentajes = leer_porcentajes_aprobados(notas.to_str().unwrap()).unwrap();
+    assert_eq!(porcentajes.get("MA1001"), Some(&(62.5, 100.0)));
+}
@@ -0,0 +1,49 @@
+use quickshift::algorithm::lexicografico::{criterios, ordenar, Criterio};
+use quickshift::api_json::parse_and_resolve_ramos_with_resolver;
+use quickshift::models::Seccion;
+use std::collections::HashMap;
+
+mod common;
+
+fn seccion(codigo: &str, horario: &str) -> (Seccion, i32) {
+    (common::seccion(codigo, "1", &[horario]), 0)
+}
+
+fn params(objetivos: &str) -> quickshift::api_json::InputParams {
+    let objetivos: serde_json::Value = serde_json::from_str(objetivos).unwrap();
+    common::params("M", serde_json::json!({"ramos_prioritarios": ["CIT1000"], "objetivos": objetivos}))
+}
+
+#[test]
+fn test_objetivos_se_comparan_en_orden() {
+    // tres días, score alto
+    let a = (vec![seccion("CBM1000", "LU 08:30-09:50"), seccion("CBQ1000", "MA 08:30-09:50"), seccion("FIS1000", "MI 08:30-09:50")], 3_000);
+    // dos días con el prioritario
+    let b = (vec![seccion("CIT1000", "LU 08:30-09:50"), seccion("CBQ1000", "MA 08:30-09:50")], 1_000);
+    // dos días sin el prioritario, mejor score que b
+    let c = (vec![seccion("CBM1000", "LU 08:30-09:50"), seccion("CBQ1000", "MA 08:30-09:50")], 2_000);
+    let soluciones = vec![a, b, c];
+
+    let orden = |objetivos: &str| -> Vec<i64> {
+        ordenar(soluciones.clone(), &params(objetivos), &HashMap::new()).into_iter().map(|(_, s)| s).collect()
+    };
+    assert_eq!(orden(r#"["min_dias", "max_prioritarios"]"#), vec![1_000, 2_000, 3_000]);
+    // Sin el segundo criterio, b y c empatan y desempata el score
+    assert_eq!(orden(r#"["min_dias"]"#), vec![2_000, 1_000, 3_000]);
+    assert_eq!(orden(r#"["max_ramos", "min_dias"]"#), vec![3_000, 2_000, 1_000]);
+    // Sin objetivos el orden no cambia
+    assert_eq!(orden("[]"), vec![3_000, 1_000, 2_000]);
+}
+
+#[test]
+fn test_nombres_de_objetivos() {
+    assert_eq!(
+        criterios(&["min_days".into(), "Max-Prioritarios".into(), "min_gaps".into()]).unwrap(),
+        vec![Criterio::MinDias, Criterio::MaxPrioritarios, Criterio::MinVentanas],
+    );
+    let err = criterios(&["min_dias".into(), "menos_lunes".into()]).unwrap_err();
+    assert!(err.contains("menos_lunes"), "{}", err);
+
+    let json = r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[],"malla":"M","objetivos":["menos_lunes"]}"#;
+    assert!(parse_and_resolve_ramos_with_resolver(json, None::<&str>, |_, _| Ok(None)).is_err());
+}
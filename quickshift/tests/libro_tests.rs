@@ -0,0 +1,108 @@
+use quickshift::api_error::ApiError;
+use quickshift::excel::{abrir_libro, diagnosticar_libro, leer_oferta_academica_excel, FormatoLibro, LibroIlegible, Proteccion};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+fn dir_temporal() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("quickshift_libro_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn escribir_xlsx(ruta: &Path) {
+    let mut libro = umya_spreadsheet::new_file();
+    libro.get_sheet_mut(0).get_cell_mut("A1").set_value("Semestre");
+    umya_spreadsheet::writer::xlsx::write(&libro, ruta).unwrap();
+}
+
+/// Contenedor OLE mínimo con un stream `EncryptedPackage`, como el que deja
+/// Excel al guardar con contraseña
+fn escribir_cifrado(ruta: &Path) {
+    let mut datos = vec![0u8; 512 * 3];
+    datos[..8].copy_from_slice(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]);
+    datos[26..28].copy_from_slice(&3u16.to_le_bytes());
+    datos[28..30].copy_from_slice(&0xFFFEu16.to_le_bytes());
+    datos[30..32].copy_from_slice(&9u16.to_le_bytes());
+    datos[32..34].copy_from_slice(&6u16.to_le_bytes());
+    datos[44..48].copy_from_slice(&1u32.to_le_bytes());
+    datos[48..52].copy_from_slice(&1u32.to_le_bytes());
+    datos[60..64].copy_from_slice(&0xFFFF_FFFEu32.to_le_bytes());
+    datos[68..72].copy_from_slice(&0xFFFF_FFFEu32.to_le_bytes());
+    datos[76..512].fill(0xFF);
+    datos[76..80].copy_from_slice(&0u32.to_le_bytes());
+    // Sector 0: FAT (él mismo y el directorio en el sector 1)
+    datos[512..1024].fill(0xFF);
+    datos[512..516].copy_from_slice(&0xFFFF_FFFDu32.to_le_bytes());
+    datos[516..520].copy_from_slice(&0xFFFF_FFFEu32.to_le_bytes());
+    // Sector 1: directorio
+    for (i, nombre) in ["Root Entry", "EncryptedPackage"].iter().enumerate() {
+        let entrada = 1024 + i * 128;
+        for (j, u) in nombre.encode_utf16().enumerate() {
+            datos[entrada + j * 2..entrada + j * 2 + 2].copy_from_slice(&u.to_le_bytes());
+        }
+        datos[entrada + 116..entrada + 120].copy_from_slice(&0xFFFF_FFFEu32.to_le_bytes());
+    }
+    std::fs::write(ruta, datos).unwrap();
+}
+
+/// Copia el xlsx `origen` a `destino` con la primera hoja protegida contra edición
+fn proteger_hoja(origen: &Path, destino: &Path) {
+    let mut entrada = zip::ZipArchive::new(std::fs::File::open(origen).unwrap()).unwrap();
+    let mut salida = zip::ZipWriter::new(std::fs::File::create(destino).unwrap());
+    for i in 0..entrada.len() {
+        let mut archivo = entrada.by_index(i).unwrap();
+        let nombre = archivo.name().to_string();
+        let mut contenido = String::new();
+        archivo.read_to_string(&mut contenido).unwrap();
+        if nombre == "xl/worksheets/sheet1.xml" {
+            contenido = contenido.replace("</sheetData>", "</sheetData><sheetProtection sheet=\"1\"/>");
+        }
+        salida.start_file(nombre, zip::write::FileOptions::default()).unwrap();
+        salida.write_all(contenido.as_bytes()).unwrap();
+    }
+    salida.finish().unwrap();
+}
+
+#[test]
+fn test_diagnostico_por_contenido() {
+    let dir = dir_temporal();
+
+    // Un xlsx con extensión .xls se abre igual
+    let renombrado = dir.join("Malla2020.xls");
+    escribir_xlsx(&renombrado);
+    let d = diagnosticar_libro(&renombrado);
+    assert_eq!((d.formato, d.extension_coincide, d.legible), (FormatoLibro::Xlsx, false, true));
+    assert_eq!((d.num_hojas, d.proteccion, d.hojas_protegidas), (1, Proteccion::Ninguna, 0));
+    assert!(abrir_libro(&renombrado).is_ok());
+
+    let protegido = dir.join("protegido.xlsx");
+    proteger_hoja(&renombrado, &protegido);
+    let d = diagnosticar_libro(&protegido);
+    assert_eq!((d.legible, d.extension_coincide), (true, true));
+    assert_eq!((d.proteccion, d.hojas_protegidas), (Proteccion::Edicion, 1));
+
+    let texto = dir.join("notas.xlsx");
+    std::fs::write(&texto, "codigo,nombre\n").unwrap();
+    let d = diagnosticar_libro(&texto);
+    assert_eq!((d.formato, d.legible), (FormatoLibro::Desconocido, false));
+    assert!(d.error.is_some());
+}
+
+#[test]
+fn test_libro_con_contrasena() {
+    let ruta = dir_temporal().join("OA20251.xlsx");
+    escribir_cifrado(&ruta);
+
+    let d = diagnosticar_libro(&ruta);
+    assert_eq!((d.formato, d.proteccion, d.legible), (FormatoLibro::Cifrado, Proteccion::Contrasena, false));
+
+    // Los lectores devuelven el diagnóstico en vez de un error de calamine
+    let err = leer_oferta_academica_excel(ruta.to_str().unwrap()).unwrap_err();
+    let ilegible = err.downcast::<LibroIlegible>().expect("diagnóstico del libro");
+    assert!(ilegible.to_string().contains("contraseña"), "{}", ilegible);
+
+    let api = ApiError::from(*ilegible);
+    assert_eq!((api.code(), api.status()), ("INVALID_DATAFILE", 422));
+    assert_eq!(api.to_json()["details"]["libro"]["proteccion"], "contrasena");
+    assert_eq!(api.to_json()["details"]["libro"]["formato"], "cifrado");
+}
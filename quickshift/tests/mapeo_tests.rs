@@ -14,3 +14,39 @@ fn test_mapeo_basico() {
     assert!(mapeo.get_by_codigo_oa("CBM1001").is_some());
     assert_eq!(mapeo.len(), 1);
 }
+
+#[test]
+fn test_mapeo_maestro_cacheado_se_invalida_por_mtime() {
+    use quickshift::excel::mapeo_builder::{estadisticas_cache_mapeo, mapeo_maestro};
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    let dir = std::env::temp_dir().join(format!("quickshift_mapeo_cache_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let datafiles = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/datafiles");
+    let rutas: Vec<String> = ["MiMalla.xlsx", "OA2024.xlsx", "PA20251.xlsx"].iter()
+        .map(|n| {
+            let destino = dir.join(n);
+            std::fs::copy(datafiles.join(n), &destino).unwrap();
+            destino.to_string_lossy().to_string()
+        })
+        .collect();
+
+    let antes = estadisticas_cache_mapeo();
+    let primero = mapeo_maestro(&rutas[0], &rutas[1], &rutas[2]).unwrap();
+    let segundo = mapeo_maestro(&rutas[0], &rutas[1], &rutas[2]).unwrap();
+    assert!(Arc::ptr_eq(&primero, &segundo));
+    assert!(primero.len() > 0);
+
+    // Cambiar el mtime de la oferta obliga a reconstruir
+    std::fs::File::options().write(true).open(&rutas[1]).unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+    let tercero = mapeo_maestro(&rutas[0], &rutas[1], &rutas[2]).unwrap();
+    assert!(!Arc::ptr_eq(&primero, &tercero));
+
+    let despues = estadisticas_cache_mapeo();
+    assert_eq!(despues.hits - antes.hits, 1);
+    assert_eq!(despues.misses - antes.misses, 2);
+    assert_eq!(despues.invalidaciones - antes.invalidaciones, 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
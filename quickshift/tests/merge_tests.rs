@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use quickshift::algorithm::{merge_malla_oferta_porcentajes, merged_a_csv};
+use quickshift::excel::FuzzyMatcher;
+use quickshift::models::{MergedCurso, RamoDisponible, Seccion};
+
+mod common;
+use common::seccion_con;
+
+fn ramo(nombre: &str) -> RamoDisponible {
+    serde_json::from_value(serde_json::json!({"id": 0, "codigo": "", "nombre": nombre})).unwrap()
+}
+
+fn seccion(codigo: &str, nombre: &str, n: &str) -> Seccion {
+    seccion_con(codigo, n, &[], serde_json::json!({"nombre": nombre}))
+}
+
+fn merge() -> Vec<MergedCurso> {
+    let mut malla = HashMap::new();
+    malla.insert("10".to_string(), ramo("Mecánica"));
+    malla.insert("2".to_string(), ramo("Álgebra, Lineal"));
+    malla.insert("7".to_string(), ramo("Taller Inexistente"));
+    let oferta = vec![seccion("CBF1000", "MECANICA", "1"), seccion("CBF1000", "MECANICA", "2"), seccion("CBM1002", "ALGEBRA LINEAL", "1")];
+    let mut porcent = HashMap::new();
+    porcent.insert("CBM1002-1".to_string(), (48.5, 300.0));
+    let mut porcent_names = HashMap::new();
+    porcent_names.insert("mecanica".to_string(), ("CBF1000".to_string(), 61.0, 250.0, false));
+    merge_malla_oferta_porcentajes(&malla, &oferta, &porcent, &porcent_names, &FuzzyMatcher::default()).0
+}
+
+#[test]
+fn test_merge_filas_tipadas() {
+    let filas = merge();
+    // una fila por sección de la oferta, ordenadas por malla_codigo numérico
+    let orden: Vec<(&str, Option<&str>)> = filas.iter().map(|f| (f.malla_codigo.as_str(), f.oferta_codigo_box.as_deref())).collect();
+    assert_eq!(orden, [("2", Some("CBM1002-1")), ("7", None), ("10", Some("CBF1000-1")), ("10", Some("CBF1000-2"))]);
+
+    // porcentaje por nombre (con es_electivo) o, si no, por codigo_box
+    assert_eq!((filas[2].pa_codigo.as_deref(), filas[2].porcentaje, filas[2].es_electivo), (Some("CBF1000"), Some(61.0), Some(false)));
+    assert_eq!((filas[0].pa_codigo.as_deref(), filas[0].porcentaje, filas[0].es_electivo), (Some("CBM1002-1"), Some(48.5), None));
+    assert_eq!(filas[1], MergedCurso {
+        malla_codigo: "7".into(), malla_nombre: "Taller Inexistente".into(),
+        oferta_codigo: None, oferta_codigo_box: None, oferta_nombre: None,
+        pa_codigo: None, porcentaje: None, total: None, es_electivo: None,
+    });
+
+    // el JSON conserva los nombres de campo de antes, con null donde no hubo match
+    let json = serde_json::to_value(&filas[1]).unwrap();
+    assert!(json["porcentaje"].is_null());
+    assert_eq!(json["malla_nombre"], "Taller Inexistente");
+}
+
+#[test]
+fn test_merged_a_csv() {
+    let csv = merged_a_csv(&merge());
+    let lineas: Vec<&str> = csv.lines().collect();
+    assert_eq!(lineas[0], MergedCurso::COLUMNAS.join(","));
+    assert_eq!(lineas.len(), 5);
+    // comillas en campos con coma; None como celda vacía
+    assert_eq!(lineas[1], "2,\"Álgebra, Lineal\",CBM1002,CBM1002-1,ALGEBRA LINEAL,CBM1002-1,48.5,300,");
+    assert_eq!(lineas[2], "7,Taller Inexistente,,,,,,,");
+    assert_eq!(lineas[3], "10,Mecánica,CBF1000,CBF1000-1,MECANICA,CBF1000,61,250,false");
+}
+
+#[cfg(feature = "server")]
+#[actix_web::test]
+async fn test_datafiles_merge_endpoint() {
+    use actix_web::{test as atest, web, App};
+    let app = atest::init_service(App::new()
+        .route("/datafiles/merge", web::get().to(quickshift::api_json::handlers::datafiles::datafiles_merge_handler))).await;
+
+    let req = atest::TestRequest::get().uri("/datafiles/merge?malla=Malla2020.xlsx").to_request();
+    let body: serde_json::Value = atest::call_and_read_body_json(&app, req).await;
+    let filas = body["filas"].as_array().unwrap();
+    assert_eq!(body["total"].as_u64().unwrap() as usize, filas.len());
+    assert!(filas.iter().any(|f| f["oferta_codigo"].is_string()));
+    assert!(body["matching"]["ambiguos"].is_array());
+
+    let req = atest::TestRequest::get().uri("/datafiles/merge?malla=Malla2020.xlsx&formato=csv").to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("content-type").unwrap().to_str().unwrap().starts_with("text/csv"));
+    let cuerpo = atest::read_body(resp).await;
+    let texto = String::from_utf8(cuerpo.to_vec()).unwrap();
+    assert!(texto.starts_with("malla_codigo,malla_nombre,"));
+    assert_eq!(texto.lines().count(), filas.len() + 1);
+
+    let req = atest::TestRequest::get().uri("/datafiles/merge?malla=Malla2020.xlsx&formato=xml").to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 400);
+    let req = atest::TestRequest::get().uri("/datafiles/merge").to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 400);
+}
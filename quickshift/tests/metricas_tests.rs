@@ -0,0 +1,41 @@
+use quickshift::algorithm::metricas::metricas;
+use quickshift::models::Seccion;
+use serde_json::json;
+
+mod common;
+
+fn seccion(codigo: &str, horario: &[&str]) -> (Seccion, i32) {
+    (common::seccion(codigo, "1", horario), 0)
+}
+
+#[test]
+fn test_metricas_de_una_semana() {
+    // Martes de 08:30 a 17:20 (no compacto) con 4 h 20 de ventana; lunes y jueves compactos
+    let solucion = vec![
+        seccion("CBM1000", &["LU 10:00 - 11:20", "MA 08:30 - 09:50"]),
+        seccion("CBM1001", &["MA 14:10 - 15:30", "JU 11:30 - 12:50"]),
+        seccion("CIT1000", &["MA 16:00 - 17:20"]),
+    ];
+    let m = metricas(&solucion);
+    assert_eq!(m.total_gap_minutes, 260 + 30);
+    assert_eq!(m.days_on_campus, 3);
+    assert_eq!(m.earliest_start.as_deref(), Some("08:30"));
+    assert_eq!(m.latest_end.as_deref(), Some("17:20"));
+    assert_eq!(m.compactness_pct, 66.7);
+}
+
+#[test]
+fn test_metricas_serializadas() {
+    let m = metricas(&[seccion("CBM1000", &["VI 11:30 - 12:50"])]);
+    assert_eq!(serde_json::to_value(&m).unwrap(), json!({
+        "total_gap_minutes": 0,
+        "days_on_campus": 1,
+        "earliest_start": "11:30",
+        "latest_end": "12:50",
+        "compactness_pct": 100.0,
+    }));
+
+    // Sin horarios no hay primera ni última hora
+    let vacia = serde_json::to_value(metricas(&[seccion("CBM1000", &[])])).unwrap();
+    assert_eq!(vacia, json!({"total_gap_minutes": 0, "days_on_campus": 0, "compactness_pct": 0.0}));
+}
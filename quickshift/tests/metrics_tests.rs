@@ -0,0 +1,44 @@
+use quickshift::algorithm::clique::{get_clique_max_pond_with_prefs, CompatibilityGraph};
+use quickshift::metrics;
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+
+fn seccion(codigo: &str, horario: &str) -> Seccion {
+    common::seccion(codigo, "1", &[horario])
+}
+
+/// Valor de una línea `<nombre>{phase="<fase>"...} <valor>` del texto Prometheus
+fn valor(texto: &str, nombre: &str, fase: &str) -> Option<f64> {
+    let prefijo = format!("{}{{phase=\"{}\"}} ", nombre, fase);
+    texto.lines().find_map(|l| l.strip_prefix(&prefijo)).and_then(|v| v.parse().ok())
+}
+
+#[test]
+fn test_metricas_de_adyacencia_y_greedy() {
+    metrics::reiniciar();
+    let secciones = vec![
+        seccion("CBM1000", "LU 08:30-09:50"),
+        seccion("CBM1001", "MA 08:30-09:50"),
+        seccion("CIT1000", "LU 08:30-09:50"),
+    ];
+    CompatibilityGraph::build(&secciones);
+
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "CBM1001", "codigo": "CBM1001", "semestre": 1},
+        {"id": 3, "nombre": "CIT1000", "codigo": "CIT1000", "semestre": 1}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    let params = common::params("M", serde_json::json!({}));
+    let sols = get_clique_max_pond_with_prefs(&secciones, &ramos, &params);
+
+    let texto = metrics::render();
+    assert!(texto.contains("# TYPE quickshift_phase_duration_seconds histogram"));
+    assert!(valor(&texto, "quickshift_phase_duration_seconds_count", "adjacency").unwrap() >= 2.0, "{}", texto);
+    assert_eq!(valor(&texto, "quickshift_phase_sections_sum", "greedy"), Some(3.0));
+    assert_eq!(valor(&texto, "quickshift_enumerator_solutions_sum", "greedy"), Some(sols.len() as f64));
+    assert!(texto.contains("quickshift_phase_sections_bucket{phase=\"greedy\",le=\"10\"} 1"), "{}", texto);
+    assert!(texto.contains("quickshift_phase_sections_bucket{phase=\"greedy\",le=\"1\"} 0"), "{}", texto);
+}
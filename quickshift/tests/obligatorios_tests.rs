@@ -0,0 +1,60 @@
+use quickshift::algorithm::en_memoria::{resolver_en_memoria, SolveEnMemoria};
+use quickshift::algorithm::obligatorios::ConflictoObligatorios;
+use quickshift::api_error::ApiError;
+use serde_json::json;
+
+fn entrada(obligatorios: &[&str], ramos_pasados: &[&str]) -> SolveEnMemoria {
+    serde_json::from_value(json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": ramos_pasados,
+        "ramos_prioritarios": [],
+        "malla": "MallaObligatorios",
+        "ramos_obligatorios": obligatorios,
+        "ramos": [
+            {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+            {"id": 2, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+            {"id": 3, "nombre": "Fisica I", "codigo": "CBF1000", "semestre": 1},
+            {"id": 4, "nombre": "Taller de Titulo", "codigo": "CIT4000", "semestre": 10},
+            {"id": 5, "nombre": "Quimica", "codigo": "CBQ1000", "semestre": 1}
+        ],
+        "secciones": [
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["MA 08:30-09:50"], "codigo_box": "CBM1000-1"},
+            {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "1", "horario": ["MI 10:00-11:20"], "codigo_box": "CIT1000-1"},
+            {"codigo": "CBF1000", "nombre": "Fisica I", "seccion": "1", "horario": ["MA 08:30-09:50"], "codigo_box": "CBF1000-1"},
+            {"codigo": "CIT4000", "nombre": "Taller de Titulo", "seccion": "1", "horario": ["VI 10:00-11:20"], "codigo_box": "CIT4000-1"}
+        ]
+    })).unwrap()
+}
+
+#[test]
+fn test_toda_solucion_incluye_los_obligatorios() {
+    // CIT4000 es de semestre 10, fuera del horizonte: solo entra por ser obligatorio
+    let soluciones = resolver_en_memoria(entrada(&["CIT4000", "Fisica I"], &[])).unwrap();
+    assert!(!soluciones.is_empty());
+    for (sol, _) in &soluciones {
+        let codigos: Vec<&str> = sol.iter().map(|(s, _)| s.codigo.as_str()).collect();
+        assert!(codigos.contains(&"CIT4000") && codigos.contains(&"CBF1000"), "{:?}", codigos);
+        // Cálculo I choca con Física I
+        assert!(!codigos.contains(&"CBM1000"));
+    }
+}
+
+#[test]
+fn test_obligatorios_inviables() {
+    let err = resolver_en_memoria(entrada(&["CBM1000", "CBF1000", "CBQ1000", "CIT1000"], &["CIT1000"])).unwrap_err();
+    let conflicto = err.downcast::<ConflictoObligatorios>().expect("reporte de ramos obligatorios");
+    assert_eq!(conflicto.ya_aprobados, vec!["CIT1000"]);
+    assert_eq!(conflicto.sin_oferta, vec!["CBQ1000"]);
+    assert_eq!(conflicto.choques.len(), 1);
+    assert_eq!((conflicto.choques[0].a.as_str(), conflicto.choques[0].b.as_str()), ("CBM1000", "CBF1000"));
+
+    let api = ApiError::de_ruta("MallaObligatorios", conflicto);
+    assert_eq!((api.code(), api.status()), ("REQUIRED_COURSES_INFEASIBLE", 422));
+    assert_eq!(api.to_json()["details"]["sin_oferta"], json!(["CBQ1000"]));
+
+    // Un horario prohibido deja sin secciones a un obligatorio
+    let mut filtrado = entrada(&["CIT1000"], &[]);
+    filtrado.params.horarios_prohibidos = vec!["MI 10:00-11:00".to_string()];
+    let conflicto = resolver_en_memoria(filtrado).unwrap_err().downcast::<ConflictoObligatorios>().unwrap();
+    assert_eq!(conflicto.sin_secciones_viables, vec!["CIT1000"]);
+}
@@ -0,0 +1,169 @@
+use calamine::Reader;
+use quickshift::excel::{abrir_libro, leer_oferta_academica_excel, recorrer_filas};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Asignador que lleva la cuenta de los bytes en uso y del máximo alcanzado
+struct Contador;
+
+static EN_USO: AtomicUsize = AtomicUsize::new(0);
+static MAXIMO: AtomicUsize = AtomicUsize::new(0);
+/// Los tests de este archivo miden memoria: no pueden correr a la vez
+static SERIAL: Mutex<()> = Mutex::new(());
+
+unsafe impl GlobalAlloc for Contador {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let p = unsafe { System.alloc(layout) };
+        if !p.is_null() {
+            let ahora = EN_USO.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            MAXIMO.fetch_max(ahora, Ordering::Relaxed);
+        }
+        p
+    }
+
+    unsafe fn dealloc(&self, p: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(p, layout) };
+        EN_USO.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ASIGNADOR: Contador = Contador;
+
+/// Bytes extra que ocupó `f` en su punto más alto
+fn pico<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let base = EN_USO.load(Ordering::Relaxed);
+    MAXIMO.store(base, Ordering::Relaxed);
+    let r = f();
+    (r, MAXIMO.load(Ordering::Relaxed).saturating_sub(base))
+}
+
+fn dir_temporal() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("quickshift_streaming_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn columna(mut c: usize) -> String {
+    let mut s = String::new();
+    loop {
+        s.insert(0, (b'A' + (c % 26) as u8) as char);
+        if c < 26 { return s; }
+        c = c / 26 - 1;
+    }
+}
+
+/// Escribe un xlsx mínimo con una hoja por elemento de `hojas`. Cada fila es
+/// (número de fila desde 1, columna inicial desde 0, celdas); las celdas van
+/// como texto en línea para no depender de la tabla de strings compartidos.
+fn escribir_xlsx(ruta: &Path, hojas: &[Vec<(usize, usize, Vec<String>)>]) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(ruta).unwrap());
+    let opciones = zip::write::FileOptions::default();
+    let mut archivo = |nombre: &str, contenido: &[u8]| {
+        zip.start_file(nombre, opciones).unwrap();
+        zip.write_all(contenido).unwrap();
+    };
+    let tipos: String = (1..=hojas.len()).map(|i| format!(
+        r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#, i)).collect();
+    archivo("[Content_Types].xml", format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>{}</Types>"#, tipos).as_bytes());
+    archivo("_rels/.rels", br#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#);
+    let hojas_xml: String = (1..=hojas.len()).map(|i| format!(r#"<sheet name="Hoja{0}" sheetId="{0}" r:id="rId{0}"/>"#, i)).collect();
+    archivo("xl/workbook.xml", format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>{}</sheets></workbook>"#, hojas_xml).as_bytes());
+    let rels: String = (1..=hojas.len()).map(|i| format!(
+        r#"<Relationship Id="rId{0}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{0}.xml"/>"#, i)).collect();
+    archivo("xl/_rels/workbook.xml.rels", format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{}</Relationships>"#, rels).as_bytes());
+    for (i, filas) in hojas.iter().enumerate() {
+        let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#);
+        for (n, desde, celdas) in filas {
+            xml.push_str(&format!(r#"<row r="{}">"#, n));
+            for (j, valor) in celdas.iter().enumerate() {
+                if valor.is_empty() { continue; }
+                xml.push_str(&format!(r#"<c r="{}{}" t="inlineStr"><is><t>{}</t></is></c>"#, columna(desde + j), n, valor));
+            }
+            xml.push_str("</row>");
+        }
+        xml.push_str("</sheetData></worksheet>");
+        archivo(&format!("xl/worksheets/sheet{}.xml", i + 1), xml.as_bytes());
+    }
+    zip.finish().unwrap();
+}
+
+fn fila(celdas: &[&str]) -> Vec<String> {
+    celdas.iter().map(|c| c.to_string()).collect()
+}
+
+#[test]
+fn test_filas_iguales_a_worksheet_range() {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    // Datos desde C3, con una fila vacía y celdas vacías entre medio
+    let ruta = dir_temporal().join("OfertaHuecos.xlsx");
+    escribir_xlsx(&ruta, &[vec![
+        (3, 2, fila(&["Codigo", "Nombre", "Seccion", "Horario", "Profesor"])),
+        (4, 2, fila(&["CBM1000", "Calculo I", "1", "LU 08:30 - 09:50", "Soto"])),
+        (6, 3, fila(&["Calculo I", "", "MA 08:30 - 09:50"])),
+        (7, 2, fila(&["CBM1000", "Calculo I", "1", "MI 08:30 - 09:50", "", "nota"])),
+    ]]);
+
+    let mut libro = abrir_libro(&ruta).unwrap();
+    let mut leidas = Vec::new();
+    recorrer_filas(&mut libro, "Hoja1", |f| leidas.push(f)).unwrap();
+    let esperadas: Vec<Vec<String>> = libro.worksheet_range("Hoja1").unwrap().rows()
+        .map(|r| r.iter().map(|c| c.to_string()).collect())
+        .collect();
+    assert_eq!(leidas.len(), 5);
+    assert_eq!(leidas, esperadas);
+
+    // Las filas de la misma sección se juntan igual que antes
+    let secciones = leer_oferta_academica_excel(ruta.to_str().unwrap()).unwrap();
+    let calculo: Vec<_> = secciones.iter().filter(|s| s.codigo == "CBM1000").collect();
+    assert_eq!(calculo.len(), 1);
+    assert_eq!(calculo[0].horario, vec!["LU 08:30 - 09:50", "MI 08:30 - 09:50"]);
+    assert_eq!(calculo[0].profesor, "Soto");
+}
+
+#[test]
+fn test_oferta_grande_sin_cargar_la_hoja() {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    // 30.000 filas: 300 ramos x 10 secciones x 10 bloques, con una columna
+    // de observaciones larga que no forma parte de la sección
+    let observacion = "x".repeat(200);
+    let mut filas = vec![(1, 0, fila(&["Codigo", "Nombre", "Seccion", "Horario", "Profesor", "Sala", "Observaciones"]))];
+    for r in 0..300 {
+        for s in 1..=10 {
+            for b in 0..10 {
+                let dia = ["LU", "MA", "MI", "JU", "VI"][b % 5];
+                filas.push((filas.len() + 1, 0, vec![
+                    format!("CIT{}", 1000 + r), format!("Ramo {}", r), s.to_string(),
+                    format!("{} {:02}:00 - {:02}:20", dia, 8 + b / 5, 9 + b / 5),
+                    format!("Profesor {}", s), format!("Sala {}", b), observacion.clone(),
+                ]));
+            }
+        }
+    }
+    // Una segunda hoja que no se alcanza a leer
+    let ruta = dir_temporal().join("OfertaGrande.xlsx");
+    escribir_xlsx(&ruta, &[filas, vec![(1, 0, fila(&["Codigo", "Nombre"]))]]);
+
+    let (secciones, pico_streaming) = pico(|| leer_oferta_academica_excel(ruta.to_str().unwrap()).unwrap());
+    assert_eq!(secciones.len(), 3_000);
+    assert!(secciones.iter().all(|s| s.horario.len() == 10));
+    assert_eq!(secciones[0].sala.as_deref().map(|s| s.split(" / ").count()), Some(10));
+    drop(secciones);
+
+    // La hoja entera como rango (lo que se hacía antes) ocupa varias veces más
+    let (_, pico_rango) = pico(|| {
+        let mut libro = abrir_libro(&ruta).unwrap();
+        let rango = libro.worksheet_range("Hoja1").unwrap();
+        rango.rows().map(|r| r.iter().map(|c| c.to_string()).collect::<Vec<String>>()).collect::<Vec<_>>()
+    });
+    assert!(
+        pico_streaming * 3 < pico_rango,
+        "streaming: {} bytes, rango: {} bytes", pico_streaming, pico_rango,
+    );
+}
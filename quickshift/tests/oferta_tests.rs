@@ -0,0 +1,80 @@
+use quickshift::excel::oferta::{consultar_oferta, ConsultaOferta};
+use quickshift::models::Seccion;
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, nombre: &str, n: &str, profesor: &str, horario: &[&str]) -> Seccion {
+    seccion_con(codigo, n, horario, serde_json::json!({"nombre": nombre, "profesor": profesor}))
+}
+
+fn oferta() -> Vec<Seccion> {
+    vec![
+        seccion("CBM1000", "Cálculo I", "1", "Ana Pérez", &["LU MI 08:30-09:50"]),
+        seccion("CBM1000", "Cálculo I", "2", "Jorge Núñez", &["MA JU 14:30-15:50"]),
+        seccion("CBM1001", "Cálculo II", "1", "Ana Pérez", &["VI 10:00-11:20"]),
+        seccion("CIT1000", "Programación", "1", "Luis Soto", &["LU 14:30-15:50", "JU 08:30-09:50"]),
+        seccion("CIT1010", "Taller", "1", "", &["Sin horario"]),
+    ]
+}
+
+fn boxes(consulta: &ConsultaOferta) -> Vec<String> {
+    consultar_oferta(&oferta(), consulta).unwrap().secciones.into_iter().map(|s| s.codigo_box).collect()
+}
+
+#[test]
+fn test_consultar_oferta_filtros() {
+    let todo = consultar_oferta(&oferta(), &ConsultaOferta::default()).unwrap();
+    assert_eq!((todo.total, todo.page, todo.total_pages, todo.secciones.len()), (5, 1, 1, 5));
+
+    // curso por código o nombre, sin tildes ni mayúsculas
+    let curso = |c: &str| boxes(&ConsultaOferta { curso: Some(c.into()), ..Default::default() });
+    assert_eq!(curso("cbm1000"), ["CBM1000-1", "CBM1000-2"]);
+    assert_eq!(curso("calculo"), ["CBM1000-1", "CBM1000-2", "CBM1001-1"]);
+    assert_eq!(boxes(&ConsultaOferta { profesor: Some("perez".into()), ..Default::default() }), ["CBM1000-1", "CBM1001-1"]);
+
+    // día y rango: un mismo bloque debe cumplir ambos; "Sin horario" nunca coincide
+    assert_eq!(boxes(&ConsultaOferta { dia: Some("jueves".into()), ..Default::default() }), ["CBM1000-2", "CIT1000-1"]);
+    assert_eq!(boxes(&ConsultaOferta { dia: Some("THU".into()), hasta: Some("12:00".into()), ..Default::default() }), ["CIT1000-1"]);
+    assert_eq!(boxes(&ConsultaOferta { desde: Some("10:00".into()), hasta: Some("16:00".into()), ..Default::default() }),
+        ["CBM1000-2", "CBM1001-1", "CIT1000-1"]);
+}
+
+#[test]
+fn test_consultar_oferta_paginacion_y_errores() {
+    let pagina = |page, per_page| consultar_oferta(&oferta(), &ConsultaOferta { page, per_page, ..Default::default() });
+    let p = pagina(Some(2), Some(2)).unwrap();
+    assert_eq!((p.total, p.total_pages), (5, 3));
+    assert_eq!(p.secciones.iter().map(|s| s.codigo_box.as_str()).collect::<Vec<_>>(), ["CBM1001-1", "CIT1000-1"]);
+    // más allá de la última página: vacía, con el total
+    let p = pagina(Some(9), Some(2)).unwrap();
+    assert!(p.secciones.is_empty());
+    assert_eq!(p.total, 5);
+
+    assert!(pagina(Some(0), None).is_err());
+    assert!(pagina(None, Some(0)).is_err());
+    assert!(pagina(None, Some(10_000)).is_err());
+    assert!(consultar_oferta(&oferta(), &ConsultaOferta { dia: Some("X".into()), ..Default::default() }).is_err());
+    assert!(consultar_oferta(&oferta(), &ConsultaOferta { desde: Some("mediodía".into()), ..Default::default() }).is_err());
+    assert!(consultar_oferta(&oferta(), &ConsultaOferta { desde: Some("12:00".into()), hasta: Some("10:00".into()), ..Default::default() }).is_err());
+}
+
+#[cfg(feature = "server")]
+#[actix_web::test]
+async fn test_get_oferta_endpoint() {
+    use actix_web::{test as atest, web, App};
+    let app = atest::init_service(App::new()
+        .route("/oferta", web::get().to(quickshift::server_handlers::oferta_handler))).await;
+
+    let req = atest::TestRequest::get().uri("/oferta?oferta=OA20251.xlsx&per_page=3&page=1").to_request();
+    let body: serde_json::Value = atest::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["archivo"], "OA20251.xlsx");
+    assert_eq!(body["per_page"], 3);
+    assert!(body["secciones"].as_array().unwrap().len() <= 3);
+    assert!(body["total"].as_u64().unwrap() > 3);
+
+    let req = atest::TestRequest::get().uri("/oferta?oferta=OA20251.xlsx&page=abc").to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 400);
+    let req = atest::TestRequest::get().uri("/oferta?oferta=OA20251.xlsx&dia=XYZ").to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 400);
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "server")]
+
+use quickshift::api_json::openapi::{construir_documento, OPERACIONES};
+use quickshift::api_json::InputParams;
+use std::collections::HashSet;
+
+/// (método, ruta) de cada `.route(...)` registrada en `server::run_server`
+fn rutas_del_servidor() -> Vec<(String, String)> {
+    let fuente = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/src/server.rs")).unwrap();
+    fuente.split(".route(\"").skip(1).filter_map(|resto| {
+        let (ruta, despues) = resto.split_once('"')?;
+        let metodo = despues.split("web::").nth(1)?.split('(').next()?;
+        Some((metodo.to_string(), ruta.to_string()))
+    }).collect()
+}
+
+#[test]
+fn test_todas_las_rutas_estan_documentadas() {
+    let rutas = rutas_del_servidor();
+    assert!(rutas.len() > 40, "no se leyeron las rutas de server.rs: {:?}", rutas);
+    let doc = construir_documento();
+    let registradas: HashSet<(&str, &str)> = OPERACIONES.iter().map(|o| (o.metodo, o.ruta)).collect();
+    for (metodo, ruta) in &rutas {
+        assert!(registradas.contains(&(metodo.as_str(), ruta.as_str())), "falta {} {} en OPERACIONES", metodo, ruta);
+        let op = &doc["paths"][ruta][metodo];
+        assert!(op.is_object(), "falta {} {} en el documento", metodo, ruta);
+        assert_eq!(op["responses"]["default"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/ApiError");
+    }
+    // Los parámetros de ruta se declaran
+    let params = doc["paths"]["/students/{email}/scenarios/{nombre}"]["get"]["parameters"].as_array().unwrap();
+    let nombres: Vec<&str> = params.iter().filter(|p| p["in"] == "path").filter_map(|p| p["name"].as_str()).collect();
+    assert_eq!(nombres, vec!["email", "nombre"]);
+}
+
+#[test]
+fn test_esquema_input_params_sigue_al_struct() {
+    let doc = construir_documento();
+    let esquemas = &doc["components"]["schemas"];
+    let props = esquemas["InputParams"]["properties"].as_object().expect("InputParams generado");
+
+    // Mismos campos que serializa el struct
+    let params: InputParams = serde_json::from_value(serde_json::json!({
+        "email": "a@b.cl", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "M"
+    })).unwrap();
+    let campos: HashSet<String> = serde_json::to_value(&params).unwrap().as_object().unwrap().keys().cloned().collect();
+    let documentados: HashSet<String> = props.keys().cloned().collect();
+    assert_eq!(campos, documentados);
+
+    assert!(esquemas["UserFilters"]["properties"]["dias_horarios_libres"].is_object());
+    assert!(esquemas["ApiError"].is_object(), "se conserva el esquema escrito a mano");
+    assert_eq!(
+        doc["paths"]["/solve"]["post"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/InputParams"
+    );
+    assert!(doc["paths"]["/solve/session/{id}/refine"]["post"]["requestBody"].is_object());
+    assert!(esquemas["RefineRequest"]["properties"]["clear_filtros"].is_object());
+}
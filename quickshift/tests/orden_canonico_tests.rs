@@ -0,0 +1,76 @@
+use quickshift::algorithm::en_memoria::SolveEnMemoria;
+use quickshift::algorithm::engine::EnMemoria;
+use quickshift::algorithm::paralelo::{clave_solucion, ordenar_canonico};
+use quickshift::algorithm::SchedulePlanner;
+use quickshift::models::Seccion;
+use serde_json::json;
+
+mod common;
+use common::seccion_con;
+
+fn seccion(caja: &str) -> (Seccion, i32) {
+    (seccion_con(&caja[..7], &caja[8..], &[], json!({"codigo_box": caja})), 0)
+}
+
+#[test]
+fn test_empates_se_ordenan_por_codigo_box() {
+    let sol = |cajas: &[&str], score: i64| (cajas.iter().map(|c| seccion(c)).collect::<Vec<_>>(), score);
+    let soluciones = vec![
+        sol(&["CIT2000-2", "CBM1000-1"], 500),
+        sol(&["CIT2000-1", "CBM1000-2"], 500),
+        sol(&["CIT2000-9"], 900),
+        sol(&["CBM1000-1", "CIT2000-1"], 500),
+    ];
+    let mut invertidas: Vec<_> = soluciones.iter().rev().cloned().collect();
+    let mut ordenadas = soluciones;
+    ordenar_canonico(&mut ordenadas);
+    ordenar_canonico(&mut invertidas);
+
+    let claves = |v: &[(Vec<(Seccion, i32)>, i64)]| v.iter().map(|(s, _)| clave_solucion(s)).collect::<Vec<_>>();
+    assert_eq!(claves(&ordenadas), vec![
+        "CIT2000-9",
+        "CBM1000-1|CIT2000-1",
+        "CBM1000-1|CIT2000-2",
+        "CBM1000-2|CIT2000-1",
+    ]);
+    // El orden de entrada no importa
+    assert_eq!(claves(&ordenadas), claves(&invertidas));
+}
+
+fn resolver() -> String {
+    // Tres ramos del mismo semestre con tres secciones sin choques: muchas
+    // soluciones con el mismo score
+    let mut secciones = Vec::new();
+    for (codigo, dia) in [("CBM1000", "LU"), ("CBQ1000", "MA"), ("CIT1000", "MI")] {
+        for (n, hora) in ["08:30-09:50", "10:00-11:20", "11:30-12:50"].iter().enumerate() {
+            secciones.push(json!({
+                "codigo": codigo, "nombre": codigo, "seccion": (n + 1).to_string(),
+                "horario": [format!("{} {}", dia, hora)], "codigo_box": format!("{}-{}", codigo, n + 1),
+            }));
+        }
+    }
+    let body = json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": [],
+        "ramos_prioritarios": [],
+        "malla": "MallaEnMemoria",
+        "ramos": [
+            {"id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1},
+            {"id": 2, "nombre": "CBQ1000", "codigo": "CBQ1000", "semestre": 1},
+            {"id": 3, "nombre": "CIT1000", "codigo": "CIT1000", "semestre": 1}
+        ],
+        "secciones": secciones
+    });
+    let SolveEnMemoria { params, ramos, secciones, prerequisitos, equivalencias } = serde_json::from_value(body).unwrap();
+    let soluciones = EnMemoria { ramos, secciones, prerequisitos, equivalencias }.planificar(params).unwrap();
+    assert!(soluciones.len() > 1);
+    serde_json::to_string(&soluciones).unwrap()
+}
+
+#[test]
+fn test_dos_ejecuciones_producen_el_mismo_json() {
+    let primera = resolver();
+    for _ in 0..3 {
+        assert_eq!(resolver(), primera);
+    }
+}
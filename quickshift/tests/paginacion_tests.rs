@@ -0,0 +1,56 @@
+use quickshift::algorithm::paginacion::paginar;
+use quickshift::models::Seccion;
+use serde_json::json;
+
+mod common;
+use common::params;
+
+fn solucion(codigo: &str) -> Vec<(Seccion, i32)> {
+    let seccion: Seccion = serde_json::from_value(json!({
+        "codigo": codigo, "nombre": codigo, "seccion": "1", "horario": ["LU 08:30-09:50"], "codigo_box": codigo
+    })).unwrap();
+    vec![(seccion, 0)]
+}
+
+fn soluciones() -> Vec<(Vec<(Seccion, i32)>, i64)> {
+    vec![
+        (solucion("A"), 50),
+        (Vec::new(), 45),
+        (solucion("B"), 40),
+        (solucion("C"), 30),
+        (solucion("D"), 20),
+        (solucion("E"), 10),
+    ]
+}
+
+fn scores(pagina: &[(Vec<(Seccion, i32)>, i64)]) -> Vec<i64> {
+    pagina.iter().map(|(_, s)| *s).collect()
+}
+
+#[test]
+fn test_paginar_respeta_orden_y_cuenta_total() {
+    // Sin parámetros: todas menos las vacías
+    let todas = paginar(soluciones(), &params("MC2020.xlsx", json!({})));
+    assert_eq!((scores(&todas.soluciones), todas.total), (vec![50, 40, 30, 20, 10], 5));
+
+    let pagina = paginar(soluciones(), &params("MC2020.xlsx", json!({"top_n": 2, "offset": 1})));
+    assert_eq!((scores(&pagina.soluciones), pagina.total), (vec![40, 30], 5));
+
+    // `total` cuenta las que pasan `min_score`, no las de la página
+    let pagina = paginar(soluciones(), &params("MC2020.xlsx", json!({"top_n": 1, "min_score": 25})));
+    assert_eq!((scores(&pagina.soluciones), pagina.total), (vec![50], 3));
+
+    let fuera = paginar(soluciones(), &params("MC2020.xlsx", json!({"offset": 10})));
+    assert_eq!((fuera.soluciones.len(), fuera.total), (0, 5));
+}
+
+#[cfg(feature = "server")]
+#[test]
+fn test_get_solve_lee_paginacion() {
+    let qm = [("malla", "MC2020.xlsx"), ("top_n", "20"), ("offset", "40"), ("min_score", "-5")]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let input = quickshift::server_handlers::input_desde_query(&qm).unwrap();
+    assert_eq!((input.top_n, input.offset, input.min_score), (Some(20), 40, Some(-5)));
+}
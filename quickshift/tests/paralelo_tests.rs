@@ -0,0 +1,68 @@
+use quickshift::algorithm::clique::get_all_clique_combinations_with_pert;
+use quickshift::algorithm::paralelo::{clave_solucion, enumerar_por_semilla, hilos_enumeracion, Soluciones};
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::{HashMap, HashSet};
+
+mod common;
+use common::{params, seccion_con};
+
+fn seccion(codigo: &str, box_: &str) -> Seccion {
+    seccion_con(codigo, "1", &["LU 08:30-09:50"], serde_json::json!({"codigo_box": box_}))
+}
+
+#[test]
+fn test_enumerar_por_semilla_mezcla_en_orden_y_sin_duplicados() {
+    assert!(hilos_enumeracion() >= 1);
+    // La semilla k produce k+1 soluciones; la semilla 3 repite una de la semilla 0
+    let rama = |semilla: usize, cupo: usize| -> Soluciones {
+        (0..=semilla)
+            .map(|j| {
+                let clave = if semilla == 3 && j == 0 { "s0-0".to_string() } else { format!("s{}-{}", semilla, j) };
+                (vec![(seccion("CIT1000", &clave), 0)], (semilla * 10 + j) as i64)
+            })
+            .take(cupo)
+            .collect()
+    };
+    let todas = enumerar_por_semilla(5, usize::MAX, rama);
+    // 1 + 2 + 3 + 4 + 5 = 15 soluciones, menos el duplicado
+    assert_eq!(todas.len(), 14);
+    let totales: Vec<i64> = todas.iter().map(|(_, t)| *t).collect();
+    let mut ordenados = totales.clone();
+    ordenados.sort();
+    assert_eq!(totales, ordenados, "se mezcla en orden de semilla");
+
+    let limitadas = enumerar_por_semilla(5, 4, rama);
+    assert_eq!(limitadas.iter().map(|(_, t)| *t).collect::<Vec<_>>(), vec![0, 10, 11, 20]);
+}
+
+#[test]
+fn test_enumeracion_exhaustiva_determinista() {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "Algebra", "codigo": "CBM1001", "semestre": 1},
+        {"id": 3, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+        {"id": 4, "nombre": "Quimica", "codigo": "CBQ1000", "semestre": 1},
+        {"id": 5, "nombre": "Fisica", "codigo": "CBF1000", "semestre": 1}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    // Dos secciones por ramo en bloques distintos: muchas combinaciones compatibles
+    let dias = ["LU", "MA", "MI", "JU", "VI"];
+    let secciones: Vec<Seccion> = ramos.values().enumerate().flat_map(|(i, r)| {
+        (1..=2).map(move |k| {
+            let horario = format!("{} {}", dias[i], if k == 1 { "08:30-09:50" } else { "10:00-11:20" });
+            seccion_con(&r.codigo, &k.to_string(), &[&horario], serde_json::json!({"nombre": r.nombre}))
+        })
+    }).collect();
+    let params = params("MallaParalela", serde_json::json!({}));
+
+    let a = get_all_clique_combinations_with_pert(&secciones, &ramos, &params, 6, 5000);
+    let b = get_all_clique_combinations_with_pert(&secciones, &ramos, &params, 6, 5000);
+    assert!(!a.is_empty());
+    let claves_a: Vec<String> = a.iter().map(|(s, _)| clave_solucion(s)).collect();
+    let claves_b: Vec<String> = b.iter().map(|(s, _)| clave_solucion(s)).collect();
+    assert_eq!(claves_a, claves_b);
+    let unicas: HashSet<&String> = claves_a.iter().collect();
+    assert_eq!(unicas.len(), claves_a.len());
+    // 5 ramos con 2 secciones cada uno: 2^5 = 32 soluciones de 5 ramos
+    assert_eq!(a.iter().filter(|(s, _)| s.len() == 5).count(), 32);
+}
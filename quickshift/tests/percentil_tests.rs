@@ -0,0 +1,62 @@
+use quickshift::algorithm::percentil::{interpretar, normalizar, FuenteRanking, DISTRIBUCION_POR_DEFECTO};
+use quickshift::api_json::parse_and_resolve_ramos_with_resolver;
+use quickshift::carreras::{establecer_registro, RegistroCarreras};
+use serde_json::json;
+use std::path::Path;
+
+fn parsear(extra: serde_json::Value) -> Result<quickshift::api_json::InputParams, String> {
+    let mut base = json!({"email": "alumno@example.com", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "MC2020.xlsx"});
+    base.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    parse_and_resolve_ramos_with_resolver(&base.to_string(), None::<&Path>, |_, _| Ok(None)).map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_promedio_a_percentil_y_validacion() {
+    // La media de la cohorte queda en el percentil 50; una desviación arriba, en el 84
+    assert_eq!(DISTRIBUCION_POR_DEFECTO.percentil(5.0), 0.5);
+    assert_eq!(DISTRIBUCION_POR_DEFECTO.percentil(5.6), 0.841);
+    assert_eq!(DISTRIBUCION_POR_DEFECTO.percentil(1.0), 0.0);
+
+    let p = parsear(json!({"promedio": 5.6})).unwrap();
+    assert_eq!(p.student_ranking, Some(0.841));
+    let r = interpretar(&p).unwrap();
+    assert_eq!((r.fuente, r.promedio, r.distribucion), (FuenteRanking::Promedio, Some(5.6), Some(DISTRIBUCION_POR_DEFECTO)));
+
+    // Los params normalizados se pueden volver a parsear (sesiones, jobs)
+    let mut de_nuevo = serde_json::from_value(serde_json::to_value(&p).unwrap()).unwrap();
+    assert!(normalizar(&mut de_nuevo).is_ok());
+
+    let r = interpretar(&parsear(json!({"student_ranking": 0.3})).unwrap()).unwrap();
+    assert_eq!((r.percentil, r.fuente), (0.3, FuenteRanking::StudentRanking));
+    assert!(interpretar(&parsear(json!({})).unwrap()).is_none());
+
+    assert!(parsear(json!({"promedio": 7.5})).unwrap_err().contains("promedio"));
+    assert!(parsear(json!({"student_ranking": 75})).unwrap_err().contains("student_ranking"));
+    assert!(parsear(json!({"promedio": 5.0, "student_ranking": 0.9})).is_err());
+}
+
+#[test]
+fn test_distribucion_por_carrera() {
+    let registro = RegistroCarreras::desde_toml(r#"
+[[carrera]]
+id = "ICI"
+nombre = "Ingeniería Civil Informática"
+mallas = ["MC2020.xlsx"]
+distribucion_promedios = { media = 5.6, desviacion = 0.4 }
+"#).unwrap();
+    establecer_registro(registro);
+
+    // El mismo promedio queda más abajo en una cohorte con mejores notas
+    let p = parsear(json!({"carrera": "ICI", "promedio": 5.6})).unwrap();
+    assert_eq!(p.student_ranking, Some(0.5));
+    assert_eq!(interpretar(&p).unwrap().distribucion.map(|d| d.media), Some(5.6));
+
+    let invalido = RegistroCarreras::desde_toml(r#"
+[[carrera]]
+id = "ICI"
+nombre = "ICI"
+mallas = ["MC2020.xlsx"]
+distribucion_promedios = { media = 5.0, desviacion = 0.0 }
+"#);
+    assert!(invalido.unwrap_err().contains("distribucion_promedios"));
+}
@@ -0,0 +1,36 @@
+use quickshift::algorithm::pert::red_pert_malla;
+use quickshift::models::RamoDisponible;
+use std::collections::HashMap;
+
+#[test]
+fn test_red_pert_malla_tiempos_y_ruta_critica() {
+    // CBM1000 -> CBM1001 -> CBM1002 (cadena), CBM1000 -> FIS1000, CIT1000 suelto
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1, "numb_correlativo": 1},
+        {"id": 2, "nombre": "Calculo II", "codigo": "CBM1001", "semestre": 2, "numb_correlativo": 2, "requisitos_ids": [1]},
+        {"id": 3, "nombre": "Calculo III", "codigo": "CBM1002", "semestre": 3, "numb_correlativo": 3, "requisitos_ids": [2]},
+        {"id": 4, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1, "numb_correlativo": 4},
+        {"id": 5, "nombre": "Fisica", "codigo": "FIS1000", "semestre": 2, "numb_correlativo": 5, "requisitos_ids": [1]}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.codigo.clone(), r)).collect();
+
+    let red = red_pert_malla(&ramos, None);
+    assert_eq!(red.nodos.len(), 5);
+    let nodo = |codigo: &str| red.nodos.iter().find(|n| n.codigo == codigo).unwrap();
+
+    let tiempos = |codigo: &str| { let n = nodo(codigo); (n.es, n.ef, n.ls, n.lf, n.holgura) };
+    assert_eq!(tiempos("CBM1000"), (1, 2, 1, 2, 0));
+    assert_eq!(tiempos("CBM1001"), (2, 3, 2, 3, 0));
+    assert_eq!(tiempos("CBM1002"), (3, 4, 3, 4, 0));
+    assert_eq!(tiempos("FIS1000"), (2, 3, 3, 4, 1));
+    assert_eq!(tiempos("CIT1000"), (1, 2, 3, 4, 2));
+
+    assert_eq!(nodo("CBM1001").predecesores, vec![1]);
+    assert!(nodo("CIT1000").predecesores.is_empty());
+    assert!(!nodo("FIS1000").critico);
+    assert_eq!(red.ruta_critica, vec!["CBM1000", "CBM1001", "CBM1002"]);
+
+    // Orden por ES y luego código
+    let orden: Vec<&str> = red.nodos.iter().map(|n| n.codigo.as_str()).collect();
+    assert_eq!(orden, vec!["CBM1000", "CIT1000", "CBM1001", "FIS1000", "CBM1002"]);
+}
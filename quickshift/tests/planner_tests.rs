@@ -0,0 +1,71 @@
+use quickshift::algorithm::planner::{planificar_carrera, siguiente_periodo};
+use quickshift::api_json::InputParams;
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+use common::seccion_con;
+
+fn malla() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "Calculo II", "codigo": "CBM1001", "requisitos_ids": [1], "semestre": 2},
+        {"id": 3, "nombre": "Calculo III", "codigo": "CBM1002", "requisitos_ids": [2], "semestre": 3},
+        {"id": 4, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+        {"id": 5, "nombre": "Taller", "codigo": "CIT1010", "requisitos_ids": [4], "semestre": 2}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+fn secciones() -> Vec<Seccion> {
+    // "Taller" no tiene sección en la oferta vigente
+    vec![
+        seccion_con("CBM1000", "1", &["LU 08:30-09:50"], serde_json::json!({"nombre": "Calculo I"})),
+        seccion_con("CBM1001", "1", &["MA 08:30-09:50"], serde_json::json!({"nombre": "Calculo II"})),
+        seccion_con("CBM1002", "1", &["MI 08:30-09:50"], serde_json::json!({"nombre": "Calculo III"})),
+        seccion_con("CIT1000", "1", &["JU 10:00-11:20"], serde_json::json!({"nombre": "Programacion"})),
+    ]
+}
+
+fn params(ramos_pasados: &[&str]) -> InputParams {
+    common::params("MallaPlan", serde_json::json!({"ramos_pasados": ramos_pasados}))
+}
+
+#[test]
+fn test_siguiente_periodo() {
+    assert_eq!(siguiente_periodo("20251"), "20252");
+    assert_eq!(siguiente_periodo("20252"), "20261");
+    assert_eq!(siguiente_periodo("2025"), "20252");
+}
+
+#[test]
+fn test_plan_respeta_prerequisitos_y_completa_malla() {
+    let plan = planificar_carrera(&params(&[]), &malla(), &secciones(), "20252", 10);
+    assert!(plan.completo, "pendientes: {:?}", plan.pendientes);
+    assert_eq!(plan.semestres_minimos, 3);
+    assert_eq!(plan.semestres.len(), 3);
+    assert_eq!(plan.egreso_estimado.as_deref(), Some("20262"));
+
+    // Cada ramo aparece una sola vez y después de sus prerequisitos
+    let semestre_de = |codigo: &str| plan.semestres.iter()
+        .position(|s| s.ramos.iter().any(|r| r.codigo == codigo))
+        .unwrap_or_else(|| panic!("{} no planificado", codigo));
+    assert!(semestre_de("CBM1000") < semestre_de("CBM1001"));
+    assert!(semestre_de("CBM1001") < semestre_de("CBM1002"));
+    assert!(semestre_de("CIT1000") < semestre_de("CIT1010"));
+    let total: usize = plan.semestres.iter().map(|s| s.ramos.len()).sum();
+    assert_eq!(total, 5);
+
+    // El ramo sin oferta se planifica igual, sin sección
+    let taller = plan.semestres.iter().flat_map(|s| s.ramos.iter()).find(|r| r.codigo == "CIT1010").unwrap();
+    assert!(taller.seccion.is_none());
+}
+
+#[test]
+fn test_plan_se_detiene_en_max_semestres() {
+    let plan = planificar_carrera(&params(&["CBM1000", "CIT1000"]), &malla(), &secciones(), "20261", 1);
+    assert_eq!(plan.semestres.len(), 1);
+    assert!(!plan.completo);
+    assert!(plan.egreso_estimado.is_none());
+    assert!(plan.pendientes.contains(&"CBM1002".to_string()));
+}
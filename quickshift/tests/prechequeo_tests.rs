@@ -0,0 +1,69 @@
+use quickshift::algorithm::en_memoria::SolveEnMemoria;
+use quickshift::algorithm::engine::EnMemoria;
+use quickshift::algorithm::prechequeo::prechequear;
+use quickshift::algorithm::SchedulePlanner;
+use serde_json::json;
+
+fn entrada(extra: serde_json::Value) -> SolveEnMemoria {
+    let mut body = json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": ["CBM1000"],
+        "ramos_prioritarios": [],
+        "malla": "MallaEnMemoria",
+        "ramos": [
+            {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+            {"id": 2, "nombre": "Calculo II", "codigo": "CBM1001", "requisitos_ids": [1], "semestre": 2},
+            {"id": 3, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1},
+            {"id": 4, "nombre": "Taller", "codigo": "CIT2000", "semestre": 2},
+            {"id": 5, "nombre": "Titulo", "codigo": "CIT9000", "semestre": 9}
+        ],
+        "secciones": [
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["LU 08:30-09:50"], "codigo_box": "CBM1000-1"},
+            {"codigo": "CBM1001", "nombre": "Calculo II", "seccion": "1", "horario": ["MA 08:30-09:50"], "codigo_box": "CBM1001-1"},
+            {"codigo": "CBM1001", "nombre": "Calculo II", "seccion": "2", "horario": ["MI 08:30-09:50"], "codigo_box": "CBM1001-2"},
+            {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "1", "horario": ["MI 10:00-11:20"], "codigo_box": "CIT1000-1"},
+            {"codigo": "CIT2000", "nombre": "Taller", "seccion": "1", "horario": ["JU 10:00-11:20"], "codigo_box": "CIT2000-1"},
+            {"codigo": "CIT9000", "nombre": "Titulo", "seccion": "1", "horario": ["VI 10:00-11:20"], "codigo_box": "CIT9000-1"}
+        ]
+    });
+    body.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    serde_json::from_value(body).unwrap()
+}
+
+fn conteos(extra: serde_json::Value) -> Vec<(String, usize, usize)> {
+    let SolveEnMemoria { mut params, ramos, secciones, prerequisitos, equivalencias } = entrada(extra);
+    let planner = EnMemoria { ramos, secciones, prerequisitos, equivalencias };
+    let datos = planner.preparar(&mut params).unwrap();
+    let out = prechequear(&datos, &params).unwrap();
+    assert_eq!(out.etapas.last().map(|e| e.secciones), Some(out.secciones_elegibles));
+    out.etapas.into_iter().map(|e| (e.etapa.to_string(), e.ramos, e.secciones)).collect()
+}
+
+#[test]
+fn test_etapas_acumuladas() {
+    let etapas = conteos(json!({}));
+    let esperado = [
+        ("oferta", 5, 6),
+        ("pendientes", 4, 5),
+        // CIT9000 es del semestre 9, lejos del horizonte (1 + 2)
+        ("horizonte_semestre", 3, 4),
+        ("prerequisitos", 3, 4),
+        ("filtros_usuario", 3, 4),
+        ("reglas_malla", 3, 4),
+    ];
+    assert_eq!(etapas.len(), esperado.len());
+    for ((etapa, ramos, secciones), (nombre, r, s)) in etapas.iter().zip(esperado) {
+        assert_eq!((etapa.as_str(), *ramos, *secciones), (nombre, r, s));
+    }
+}
+
+#[test]
+fn test_filtros_del_usuario_restan_secciones() {
+    let etapas = conteos(json!({
+        "horarios_prohibidos": ["MA 08:00-10:00"],
+        "filtros": {"hora_inicio_minima": "09:00"}
+    }));
+    let filtros = etapas.iter().find(|(e, _, _)| e == "filtros_usuario").unwrap();
+    // Cálculo II queda sin secciones: una choca con la franja y la otra empieza 08:30
+    assert_eq!((filtros.1, filtros.2), (2, 2));
+}
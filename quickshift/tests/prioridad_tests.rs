@@ -0,0 +1,63 @@
+use quickshift::algorithm::prioridad::{prioridad_de, Priority};
+use quickshift::models::{RamoDisponible, Seccion};
+use quickshift::scoring::ScoringConfig;
+use std::collections::HashMap;
+
+mod common;
+use common::seccion_con;
+
+fn ramo(critico: bool, holgura: i32, correlativo: i32) -> RamoDisponible {
+    serde_json::from_value(serde_json::json!({
+        "id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1,
+        "critico": critico, "holgura": holgura, "numb_correlativo": correlativo
+    })).unwrap()
+}
+
+fn seccion(codigo: &str, n: &str) -> Seccion {
+    seccion_con(codigo, n, &[], serde_json::json!({"codigo_box": codigo}))
+}
+
+#[test]
+fn test_componentes_y_score_por_defecto() {
+    let p = Priority::de_seccion(&ramo(true, 2, 5), &seccion("CBM1000", "3"));
+    assert_eq!(p, Priority { critico: true, uu: 8, kk: 55, ss: 3 });
+    assert_eq!(p.to_string(), "10 08 55 03");
+    assert_eq!(p.score(&ScoringConfig::default()), 10_085_503);
+
+    // fuera de rango se acota como en RutaCritica.py
+    let p = Priority::de_seccion(&ramo(false, 15, 80), &seccion("CBM1000", "A"));
+    assert_eq!(p, Priority { critico: false, uu: 0, kk: 0, ss: 0 });
+
+    assert_eq!(Priority::CFG.score(&ScoringConfig::default()), 10_010_150);
+    assert_eq!(Priority::ELECTIVO.score(&ScoringConfig::default()), 53_000);
+}
+
+#[test]
+fn test_orden_coincide_con_el_score() {
+    let mut ps = vec![
+        Priority { critico: false, uu: 9, kk: 60, ss: 99 },
+        Priority { critico: true, uu: 0, kk: 0, ss: 0 },
+        Priority { critico: false, uu: 9, kk: 59, ss: 1 },
+        Priority::CFG,
+        Priority::ELECTIVO,
+    ];
+    let mut por_score = ps.clone();
+    ps.sort();
+    por_score.sort_by_key(|p| p.score(&ScoringConfig::default()));
+    assert_eq!(ps, por_score);
+}
+
+#[test]
+fn test_prioridad_de_sin_ramo_en_malla() {
+    let ramos: HashMap<String, RamoDisponible> = [("CBM1000".to_string(), ramo(false, 0, 10))].into();
+    assert_eq!(prioridad_de(&seccion("CBM1000", "2"), &ramos), Priority { critico: false, uu: 10, kk: 50, ss: 2 });
+
+    let mut cfg = seccion("CFG101", "1");
+    cfg.is_cfg = true;
+    assert_eq!(prioridad_de(&cfg, &ramos), Priority::CFG);
+
+    let mut electivo = seccion("ELE200", "1");
+    electivo.is_electivo = true;
+    assert_eq!(prioridad_de(&electivo, &ramos), Priority::ELECTIVO);
+    assert_eq!(prioridad_de(&seccion("OTRO", "1"), &ramos), Priority::default());
+}
@@ -0,0 +1,76 @@
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::api_json::InputParams;
+use quickshift::models::{ModoPreferencia, RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+
+fn datos() -> (HashMap<String, RamoDisponible>, Vec<Seccion>) {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "Algebra", "codigo": "CBM1001", "semestre": 1}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    let seccion = |codigo: &str, nombre: &str, n: &str, horario: &str, profesor: &str| -> Seccion {
+        serde_json::from_value(serde_json::json!({
+            "codigo": codigo,
+            "nombre": nombre,
+            "seccion": n,
+            "horario": [horario],
+            "profesor": profesor,
+            "codigo_box": format!("{}-{}", codigo, n),
+        })).unwrap()
+    };
+    let secciones = vec![
+        seccion("CBM1000", "Calculo I", "1", "LU 08:30-09:50", "Juan Soto"),
+        seccion("CBM1000", "Calculo I", "2", "MA 08:30-09:50", "Ana Perez"),
+        seccion("CBM1001", "Algebra", "1", "MI 08:30-09:50", "Luis Rojas"),
+    ];
+    (ramos, secciones)
+}
+
+fn params(modo: &str) -> InputParams {
+    common::params("MallaProfesores", serde_json::json!({
+        "filtros": {
+            "preferencias_profesores": {
+                "habilitado": true,
+                "profesores_preferidos": ["ana perez"],
+                "profesores_evitar": ["Luis Rojas"],
+                "modo": modo
+            }
+        }
+    }))
+}
+
+fn cajas(sol: &[(Seccion, i32)]) -> Vec<String> {
+    let mut c: Vec<String> = sol.iter().map(|(s, _)| s.codigo_box.clone()).collect();
+    c.sort();
+    c
+}
+
+#[test]
+fn test_modo_por_defecto_es_hard() {
+    let p: InputParams = serde_json::from_value(serde_json::json!({
+        "email": "a@b.cl", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "M",
+        "filtros": {"preferencias_profesores": {"habilitado": true, "profesores_preferidos": ["X"]}}
+    })).unwrap();
+    let prof = p.filtros.unwrap().preferencias_profesores.unwrap();
+    assert_eq!(prof.modo, ModoPreferencia::Hard);
+}
+
+#[test]
+fn test_profesores_hard_excluye_y_soft_puntua() {
+    let (ramos, secciones) = datos();
+
+    // Hard: solo queda la sección de la profesora preferida
+    let hard = get_clique_max_pond_with_prefs(&secciones, &ramos, &params("hard"));
+    assert!(!hard.is_empty());
+    assert!(hard.iter().all(|(sol, _)| cajas(sol) == vec!["CBM1000-2"]), "{:?}", hard.iter().map(|(s, _)| cajas(s)).collect::<Vec<_>>());
+
+    // Soft: se mantienen todas las secciones; la mejor solución usa a la
+    // profesora preferida y conserva Algebra pese al profesor a evitar
+    let soft = get_clique_max_pond_with_prefs(&secciones, &ramos, &params("soft"));
+    assert_eq!(cajas(&soft[0].0), vec!["CBM1000-2", "CBM1001-1"]);
+    let con_juan = soft.iter().find(|(sol, _)| cajas(sol) == vec!["CBM1000-1", "CBM1001-1"]).expect("sección no preferida se conserva");
+    assert!(soft[0].1 > con_juan.1);
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use quickshift::datafiles::cache::PorcentajesConNombres;
+use quickshift::excel::profesores::{buscar_profesores, ficha_profesor};
+use quickshift::models::Seccion;
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, nombre: &str, n: &str, profesor: &str) -> Seccion {
+    seccion_con(codigo, n, &["LU 08:30-09:50"], serde_json::json!({"nombre": nombre, "profesor": profesor}))
+}
+
+fn oferta() -> Vec<Seccion> {
+    vec![
+        seccion("CBM1000", "Cálculo I", "1", "PÉREZ ANA"),
+        seccion("CBM1000", "Cálculo I", "2", "PÉREZ ANA"),
+        seccion("CBM1001", "Cálculo II", "1", "Perez Ana"),
+        seccion("CIT1000", "Programación", "1", "NÚÑEZ JORGE"),
+        seccion("CIT1010", "Taller", "1", ""),
+    ]
+}
+
+fn porcentajes() -> PorcentajesConNombres {
+    let mut por_codigo = HashMap::new();
+    por_codigo.insert("CBM1001".to_string(), (61.0, 40.0));
+    let mut por_nombre = HashMap::new();
+    por_nombre.insert("cbm1000".to_string(), ("20002527".to_string(), 72.5, 120.0, false));
+    (por_codigo, por_nombre)
+}
+
+#[test]
+fn test_buscar_profesores() {
+    let nombres = |q: &str| buscar_profesores(&oferta(), q).into_iter().map(|p| (p.nombre, p.cursos, p.secciones)).collect::<Vec<_>>();
+    // sin tildes ni mayúsculas; "Perez Ana" y "PÉREZ ANA" son el mismo profesor
+    assert_eq!(nombres("perez"), [("PÉREZ ANA".to_string(), 2, 3)]);
+    assert_eq!(nombres("NUNEZ"), [("NÚÑEZ JORGE".to_string(), 1, 1)]);
+    // consulta vacía: todos, sin las secciones sin profesor
+    assert_eq!(nombres("").len(), 2);
+    assert!(nombres("soto").is_empty());
+}
+
+#[test]
+fn test_ficha_profesor_con_aprobacion() {
+    let ficha = ficha_profesor(&oferta(), &porcentajes(), "perez ana").unwrap();
+    assert_eq!(ficha.total_secciones, 3);
+    let cursos: Vec<_> = ficha.cursos.iter()
+        .map(|c| (c.codigo.as_str(), c.secciones.len(), c.porcentaje_aprobacion, c.total_historico))
+        .collect();
+    // CBM1000 por el índice por nombre, CBM1001 por el mapa por código
+    assert_eq!(cursos, [("CBM1000", 2, Some(72.5), Some(120.0)), ("CBM1001", 1, Some(61.0), Some(40.0))]);
+
+    let ficha = ficha_profesor(&oferta(), &porcentajes(), "Núñez Jorge").unwrap();
+    assert_eq!(ficha.cursos[0].porcentaje_aprobacion, None);
+    // nombre completo, no parcial
+    assert!(ficha_profesor(&oferta(), &porcentajes(), "perez").is_none());
+}
+
+#[cfg(feature = "server")]
+#[actix_web::test]
+async fn test_profesores_endpoints() {
+    use actix_web::{test as atest, web, App};
+    let app = atest::init_service(App::new()
+        .route("/profesores", web::get().to(quickshift::server_handlers::profesores_buscar_handler))
+        .route("/profesores/{nombre}", web::get().to(quickshift::server_handlers::profesor_handler))).await;
+
+    let req = atest::TestRequest::get().uri("/profesores?q=garcia&oferta=OA20251.xlsx").to_request();
+    let body: serde_json::Value = atest::call_and_read_body_json(&app, req).await;
+    let profesores = body["profesores"].as_array().unwrap();
+    assert!(profesores.iter().any(|p| p["nombre"] == "GARCIA CARLOS ENRIQUE ANTONIO"));
+
+    let req = atest::TestRequest::get()
+        .uri("/profesores/garc%C3%ADa%20carlos%20enrique%20antonio?oferta=OA20251.xlsx&porcentajes=PA20251.xlsx")
+        .to_request();
+    let body: serde_json::Value = atest::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["nombre"], "GARCIA CARLOS ENRIQUE ANTONIO");
+    assert_eq!(body["archivo_porcentajes"], "PA20251.xlsx");
+    let cursos = body["cursos"].as_array().unwrap();
+    assert!(!cursos.is_empty());
+    assert!(cursos.iter().all(|c| !c["secciones"].as_array().unwrap().is_empty()));
+    assert!(cursos.iter().any(|c| c["porcentaje_aprobacion"].is_number()));
+
+    let req = atest::TestRequest::get().uri("/profesores/nadie%20nunca?oferta=OA20251.xlsx").to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    let body: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!(body["code"], "PROFESSOR_NOT_FOUND");
+}
@@ -0,0 +1,56 @@
+use quickshift::algorithm::progreso_esperado::*;
+use quickshift::api_json::{parse_json_input, Objetivo};
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+
+fn ramos() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "dificultad": 40.0, "creditos": 6},
+        {"id": 2, "nombre": "Programacion", "codigo": "CIT1000", "dificultad": 90.0, "creditos": 6},
+        {"id": 3, "nombre": "Quimica", "codigo": "CBQ1000", "creditos": 4}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+fn seccion(codigo: &str, n: &str) -> Seccion {
+    common::seccion(codigo, n, &["LU 08:30 - 09:50"])
+}
+
+#[test]
+fn test_probabilidad_segun_ranking() {
+    assert!((probabilidad_aprobar(Some(70.0), None) - 0.7).abs() < 1e-9);
+    assert!((probabilidad_aprobar(Some(70.0), Some(0.5)) - 0.7).abs() < 1e-9);
+    assert!(probabilidad_aprobar(Some(70.0), Some(0.9)) > 0.85);
+    assert!(probabilidad_aprobar(Some(70.0), Some(0.1)) < 0.55);
+    assert!((probabilidad_aprobar(None, None) - PROBABILIDAD_POR_DEFECTO).abs() < 1e-9);
+    // Tasas extremas no llegan a 0 ni a 1
+    assert!(probabilidad_aprobar(Some(100.0), Some(1.0)) < 1.0);
+    assert!(probabilidad_aprobar(Some(0.0), Some(0.0)) > 0.0);
+}
+
+#[test]
+fn test_progreso_esperado_por_solucion() {
+    let secciones = vec![seccion("CBM1000", "1"), seccion("CIT1000", "1"), seccion("CBQ1000", "1"), seccion("CFG0001", "1")];
+    let p = progreso_esperado(&secciones, &ramos(), None);
+    assert_eq!(p.creditos_inscritos, 6 + 6 + 4 + 5);
+    let esperado = 6.0 * 0.4 + 6.0 * 0.9 + 4.0 * PROBABILIDAD_POR_DEFECTO + 5.0 * PROBABILIDAD_POR_DEFECTO;
+    assert!((p.creditos_esperados - esperado).abs() < 1e-9);
+    assert_eq!(p.ramos.iter().map(|r| r.codigo.as_str()).collect::<Vec<_>>(), vec!["CBM1000", "CIT1000", "CBQ1000", "CFG0001"]);
+}
+
+#[test]
+fn test_expected_progress_reordena_soluciones() {
+    // La de mayor score tiene el ramo difícil; la otra aprueba más créditos en promedio
+    let dificil = (vec![(seccion("CBM1000", "1"), 0)], 2_000);
+    let facil = (vec![(seccion("CIT1000", "1"), 0)], 1_000);
+    let ordenadas = ordenar_por_progreso(vec![dificil, facil], &ramos(), Some(0.5));
+    assert_eq!(ordenadas[0].0[0].0.codigo, "CIT1000");
+    assert_eq!(ordenadas[1].1, 2_000);
+
+    let p = parse_json_input(r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[],"malla":"M","objetivo":"expected_progress"}"#).unwrap();
+    assert_eq!(p.objetivo, Objetivo::ExpectedProgress);
+    let por_defecto = parse_json_input(r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[],"malla":"M"}"#).unwrap();
+    assert_eq!(por_defecto.objetivo, Objetivo::Score);
+}
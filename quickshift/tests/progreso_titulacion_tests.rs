@@ -0,0 +1,87 @@
+use quickshift::algorithm::progreso_titulacion::calcular_progreso;
+use quickshift::models::RamoDisponible;
+use quickshift::reglas::ReglasMalla;
+use std::collections::HashMap;
+
+fn malla() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "numb_correlativo": 1, "semestre": 1, "creditos": 6},
+        {"id": 2, "nombre": "Calculo II", "codigo": "CBM1001", "requisitos_ids": [1], "numb_correlativo": 2, "semestre": 2, "creditos": 6},
+        {"id": 3, "nombre": "Calculo III", "codigo": "CBM1002", "requisitos_ids": [2], "numb_correlativo": 3, "semestre": 3, "creditos": 6},
+        {"id": 4, "nombre": "Programacion", "codigo": "CIT1000", "numb_correlativo": 4, "semestre": 1},
+        {"id": 5, "nombre": "Taller", "codigo": "CIT1010", "requisitos_ids": [4], "numb_correlativo": 5, "semestre": 2},
+        {"id": 6, "nombre": "Electivo Profesional", "codigo": "", "numb_correlativo": 6, "electivo": true}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+fn reglas() -> ReglasMalla {
+    ReglasMalla { max_cfg: 2, max_electivos: 1, max_ramos_verano: 2 }
+}
+
+#[test]
+fn test_progreso_por_semestre_y_ruta_critica() {
+    let pasados = vec!["cbm1000".to_string(), "Programacion".to_string(), "CFG0001".to_string()];
+    let p = calcular_progreso(&malla(), &pasados, &reglas(), 6);
+
+    assert_eq!((p.ramos, p.ramos_aprobados), (6, 2));
+    // 6 + 6 + 6 + 5 + 5 + 5 (sin créditos en la malla se usan 5)
+    assert_eq!((p.creditos, p.creditos_aprobados), (33, 11));
+    assert_eq!(p.porcentaje, 33.33);
+
+    let semestres: Vec<_> = p.por_semestre.iter().map(|s| (s.semestre, s.ramos, s.ramos_aprobados, s.porcentaje)).collect();
+    assert_eq!(semestres, vec![
+        (Some(1), 2, 2, 100.0),
+        (Some(2), 2, 0, 0.0),
+        (Some(3), 1, 0, 0.0),
+        (None, 1, 0, 0.0),
+    ]);
+
+    // La cadena de cálculo es la más larga: es la ruta crítica
+    assert_eq!(p.ruta_critica.ramos, 3);
+    assert_eq!(p.ruta_critica.aprobados, 1);
+    assert_eq!(p.ruta_critica.pendientes, vec!["CBM1001", "CBM1002"]);
+
+    assert_eq!((p.pendientes.obligatorios, p.pendientes.electivos, p.pendientes.cfg), (3, 1, 1));
+    assert_eq!(p.semestres_cadena_critica, 2);
+    assert_eq!(p.semestres_por_carga, 1);
+    assert_eq!(p.semestres_restantes_minimos, 2);
+}
+
+#[test]
+fn test_proyeccion_limitada_por_carga() {
+    let p = calcular_progreso(&malla(), &[], &reglas(), 2);
+    // 5 obligatorios + 1 electivo + 2 CFG en tandas de 2
+    assert_eq!(p.semestres_por_carga, 4);
+    assert_eq!(p.semestres_cadena_critica, 3);
+    assert_eq!(p.semestres_restantes_minimos, 4);
+    assert_eq!(p.ruta_critica.porcentaje, 0.0);
+}
+
+#[cfg(feature = "server")]
+#[actix_web::test]
+async fn test_progress_sobre_fixtures() {
+    use actix_web::{test as atest, web, App};
+    use quickshift::excel::config::{self, DatafilesConfig, OrigenDatafiles};
+
+    let dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden");
+    config::establecer(DatafilesConfig { dir, origen: OrigenDatafiles::LineaDeComandos });
+    let app = atest::init_service(
+        App::new().route("/progress", web::post().to(quickshift::server_handlers::progreso_handler)),
+    ).await;
+
+    let req = atest::TestRequest::post().uri("/progress")
+        .set_json(serde_json::json!({"malla": "MC2099.xlsx", "ramos_pasados": ["CBM1000", "CIT1000"]}))
+        .to_request();
+    let out: serde_json::Value = atest::call_and_read_body_json(&app, req).await;
+    assert_eq!(out["malla"], "MC2099.xlsx");
+    assert_eq!(out["ramos"], 10);
+    assert_eq!(out["ramos_aprobados"], 2);
+    assert!(out["semestres_restantes_minimos"].as_i64().unwrap() >= 1);
+    assert_eq!(out["por_semestre"][0]["semestre"], 1);
+
+    let req = atest::TestRequest::post().uri("/progress")
+        .set_json(serde_json::json!({"malla": "MC2099.xlsx", "carrera": "no-existe"}))
+        .to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 400);
+}
@@ -0,0 +1,73 @@
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs_cached;
+use quickshift::algorithm::deadline::Deadline;
+use quickshift::algorithm::rapido::{estimar, gap_estimado};
+use quickshift::api_json::{InputParams, SolverMode};
+use quickshift::models::RamoDisponible;
+use std::collections::HashMap;
+use std::time::Duration;
+
+mod common;
+use common::seccion;
+
+fn ramos() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1, "numb_correlativo": 1, "critico": true},
+        {"id": 2, "nombre": "CIT1000", "codigo": "CIT1000", "semestre": 1, "numb_correlativo": 2},
+        {"id": 3, "nombre": "FIC1000", "codigo": "FIC1000", "semestre": 1, "numb_correlativo": 3}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+fn params(mut extra: serde_json::Value) -> InputParams {
+    extra.as_object_mut().unwrap().entry("modo").or_insert(serde_json::json!("rapido"));
+    common::params("MallaRapida", extra)
+}
+
+#[test]
+fn test_modo_rapido_acota_la_latencia() {
+    let p = params(serde_json::json!({}));
+    assert_eq!(p.solver, SolverMode::Rapido);
+    let restante = Deadline::desde_params(&p).restante().unwrap();
+    assert!(restante <= Duration::from_millis(500));
+
+    let p = params(serde_json::json!({"timeout_ms": 120}));
+    assert!(Deadline::desde_params(&p).restante().unwrap() <= Duration::from_millis(120));
+
+    assert_eq!(gap_estimado(75, 100), Some(0.25));
+    assert_eq!(gap_estimado(120, 100), Some(0.0));
+    assert_eq!(gap_estimado(10, 0), None);
+}
+
+#[test]
+fn test_gap_contra_la_cota_optimista() {
+    let p = params(serde_json::json!({}));
+    let deadline = Deadline::desde_params(&p);
+
+    // Todo cabe junto: el greedy llega a la cota
+    let compatibles = vec![
+        seccion("CBM1000", "1", &["LU 08:30 - 09:50"]),
+        seccion("CIT1000", "1", &["MA 08:30 - 09:50"]),
+        seccion("FIC1000", "1", &["MI 08:30 - 09:50"]),
+    ];
+    let sols = get_clique_max_pond_with_prefs_cached(&compatibles, &ramos(), &p, None, &deadline);
+    let calidad = estimar(&sols, &p, &deadline, 3);
+    assert_eq!(calidad.mejor_score, calidad.cota_optimista);
+    assert_eq!(calidad.gap_estimado, Some(0.0));
+    assert!(calidad.dentro_de_sla);
+
+    // CBM1000 choca con CIT1000: la cota (que los cuenta a ambos) queda por encima
+    let deadline = Deadline::desde_params(&p);
+    let con_choque = vec![
+        seccion("CBM1000", "1", &["LU 08:30 - 09:50"]),
+        seccion("CIT1000", "1", &["LU 08:30 - 09:50"]),
+        seccion("FIC1000", "1", &["MI 08:30 - 09:50"]),
+    ];
+    let sols = get_clique_max_pond_with_prefs_cached(&con_choque, &ramos(), &p, None, &deadline);
+    let calidad = estimar(&sols, &p, &deadline, 900);
+    let (mejor, cota) = (calidad.mejor_score.unwrap(), calidad.cota_optimista.unwrap());
+    assert!(mejor < cota, "{} {}", mejor, cota);
+    let gap = calidad.gap_estimado.unwrap();
+    assert!(gap > 0.0 && gap < 1.0);
+    assert!(!calidad.dentro_de_sla);
+    assert_eq!(serde_json::to_value(deadline.progreso()).unwrap()["cota_optimista"], cota);
+}
@@ -0,0 +1,92 @@
+use quickshift::algorithm::en_memoria::{resolver_en_memoria, SolveEnMemoria};
+use quickshift::carreras::{RegistroCarreras, MAX_CFG_POR_DEFECTO};
+use quickshift::reglas::*;
+use serde_json::json;
+
+const REGLAS: &str = r#"
+[default]
+max_electivos = 4
+
+[malla."MallaReglas.xlsx"]
+max_cfg = 1
+max_electivos = 1
+
+[malla.MC2010]
+max_electivos = 2
+"#;
+
+#[test]
+fn test_reglas_por_malla_y_carrera() {
+    let r = RegistroReglas::desde_toml(REGLAS).unwrap();
+    assert_eq!(r.para("datafiles/mallareglas.xlsx", None), ReglasMalla { max_cfg: 1, max_electivos: 1, max_ramos_verano: MAX_RAMOS_VERANO_POR_DEFECTO });
+    // sin max_cfg propio: el de la carrera, si no el por defecto
+    assert_eq!(r.para("MC2010.xlsx", None), ReglasMalla { max_cfg: 4, max_electivos: 2, max_ramos_verano: MAX_RAMOS_VERANO_POR_DEFECTO });
+    let carreras = RegistroCarreras::desde_toml("[[carrera]]\nid = \"ICQ\"\nnombre = \"q\"\nmallas = [\"MC2010.xlsx\"]\nmax_cfg = 2\n").unwrap();
+    assert_eq!(r.para("MC2010", carreras.get("ICQ")).max_cfg, 2);
+    assert_eq!(r.para("Otra.xlsx", None), ReglasMalla { max_cfg: 4, max_electivos: 4, max_ramos_verano: MAX_RAMOS_VERANO_POR_DEFECTO });
+    assert_eq!(RegistroReglas::default().para("Otra.xlsx", None), ReglasMalla { max_cfg: MAX_CFG_POR_DEFECTO, max_electivos: MAX_ELECTIVOS_POR_DEFECTO, max_ramos_verano: MAX_RAMOS_VERANO_POR_DEFECTO });
+
+    assert!(RegistroReglas::desde_toml("[malla.\"A.xlsx\"]\nmax_cfg = 1\n[malla.a]\nmax_cfg = 2\n").unwrap_err().contains("repetida"));
+    assert!(RegistroReglas::desde_toml("[default]\nmax_cfgs = 1\n").is_err());
+
+    let pasados: Vec<String> = ["CFG1001", "CBM1000", "Calculo I", "CIT9001"].iter().map(|s| s.to_string()).collect();
+    let ramos = serde_json::from_value(json!({
+        "CBM1000": {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000"},
+        "CIT3400": {"id": 2, "nombre": "Electivo de Datos", "codigo": "CIT3400", "electivo": true}
+    })).unwrap();
+    assert_eq!((cfgs_aprobados(&pasados), electivos_aprobados(&pasados, &ramos)), (1, 1));
+    let pasados = [pasados, vec!["electivo de datos".to_string()]].concat();
+    assert_eq!(electivos_aprobados(&pasados, &ramos), 2);
+}
+
+fn entrada(ramos_pasados: &[&str]) -> SolveEnMemoria {
+    serde_json::from_value(json!({
+        "email": "alumno@example.com",
+        "ramos_pasados": ramos_pasados,
+        "ramos_prioritarios": [],
+        "malla": "MallaReglas.xlsx",
+        "ramos": [
+            {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1},
+            {"id": 2, "nombre": "Electivo de Datos", "codigo": "CIT3400", "semestre": 1, "electivo": true}
+        ],
+        "secciones": [
+            {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["LU 08:30-09:50"], "codigo_box": "CBM1000-1"},
+            {"codigo": "CIT3400", "nombre": "Electivo de Datos", "seccion": "1", "horario": ["MA 08:30-09:50"], "codigo_box": "CIT3400-1"}
+        ]
+    })).unwrap()
+}
+
+fn codigos(ramos_pasados: &[&str]) -> Vec<String> {
+    let mut codigos: Vec<String> = resolver_en_memoria(entrada(ramos_pasados)).unwrap().into_iter()
+        .flat_map(|(sol, _)| sol.into_iter().map(|(s, _)| s.codigo))
+        .collect();
+    codigos.sort();
+    codigos.dedup();
+    codigos
+}
+
+#[test]
+fn test_solver_respeta_electivos_de_la_malla() {
+    establecer_registro(RegistroReglas::desde_toml(REGLAS).unwrap());
+    assert_eq!(codigos(&[]), vec!["CBM1000", "CIT3400"]);
+    // MallaReglas exige un solo electivo y ya aprobó uno fuera de la malla
+    assert_eq!(codigos(&["CIT9001"]), vec!["CBM1000"]);
+}
+
+#[cfg(feature = "server")]
+#[actix_web::test]
+async fn test_get_malla_reglas() {
+    use actix_web::{test as atest, web, App};
+    use quickshift::api_json::handlers::courses::malla_reglas_handler;
+
+    let app = atest::init_service(App::new().route("/malla/{id}/reglas", web::get().to(malla_reglas_handler))).await;
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/malla/MC2020.xlsx/reglas").to_request()).await;
+    assert_eq!(resp.status(), 200);
+    let out: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!((out["max_cfg"].as_u64(), out["carrera"].is_null()), (Some(4), true));
+
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/malla/MC2020.xlsx/reglas?carrera=NOEXISTE").to_request()).await;
+    assert_eq!(resp.status(), 400);
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/malla/NoExiste_reglas.xlsx/reglas").to_request()).await;
+    assert!(resp.status().is_client_error());
+}
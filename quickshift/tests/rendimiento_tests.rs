@@ -0,0 +1,57 @@
+// Regresiones de rendimiento del solver sobre ofertas sintéticas (ver
+// `algorithm::sintetico`). El umbral se puede ajustar con
+// `QUICKSHIFT_PERF_UMBRAL_MS` (p. ej. en máquinas de CI lentas).
+
+use quickshift::algorithm::clique::{get_all_clique_combinations_with_pert, CompatibilityGraph};
+use quickshift::algorithm::sintetico::{oferta_sintetica, SECCIONES_POR_RAMO};
+use quickshift::api_json::InputParams;
+use std::time::{Duration, Instant};
+
+mod common;
+
+/// Tope por defecto para construir la adyacencia y enumerar 200 secciones.
+/// Pensado para el build debug de `cargo test` (~5 s); en release tarda una
+/// fracción de eso
+const UMBRAL_POR_DEFECTO_MS: u64 = 30_000;
+
+fn umbral() -> Duration {
+    let ms = std::env::var("QUICKSHIFT_PERF_UMBRAL_MS").ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(UMBRAL_POR_DEFECTO_MS);
+    Duration::from_millis(ms)
+}
+
+fn params() -> InputParams {
+    common::params("sintetica", serde_json::json!({}))
+}
+
+#[test]
+fn test_oferta_sintetica_determinista() {
+    let a = oferta_sintetica(50, 7);
+    let b = oferta_sintetica(50, 7);
+    assert_eq!(a.secciones.len(), 50);
+    assert_eq!(a.ramos.len(), 50_usize.div_ceil(SECCIONES_POR_RAMO));
+    let horarios = |o: &quickshift::algorithm::sintetico::OfertaSintetica| o.secciones.iter().map(|s| s.horario.clone()).collect::<Vec<_>>();
+    assert_eq!(horarios(&a), horarios(&b));
+
+    // Hay choques de horario, pero no todo choca
+    let grafo = CompatibilityGraph::build(&a.secciones);
+    let pares_de_ramos_distintos = (50 * 49) / 2 - a.ramos.len() * (SECCIONES_POR_RAMO * (SECCIONES_POR_RAMO - 1) / 2);
+    assert!(grafo.edge_count() > 0);
+    assert!(grafo.edge_count() < pares_de_ramos_distintos, "{} aristas", grafo.edge_count());
+}
+
+#[test]
+fn test_solver_200_secciones_bajo_umbral() {
+    let oferta = oferta_sintetica(200, 42);
+    let params = params();
+    let inicio = Instant::now();
+    CompatibilityGraph::build(&oferta.secciones);
+    let soluciones = get_all_clique_combinations_with_pert(&oferta.secciones, &oferta.ramos, &params, 6, 2_000);
+    let duracion = inicio.elapsed();
+
+    assert!(!soluciones.is_empty());
+    assert!(soluciones.iter().any(|(s, _)| s.len() == 6));
+    let limite = umbral();
+    assert!(duracion <= limite, "200 secciones tardaron {:?} (umbral {:?}, QUICKSHIFT_PERF_UMBRAL_MS)", duracion, limite);
+}
@@ -0,0 +1,42 @@
+use quickshift::algorithm::reprobacion::simular_reprobacion;
+use quickshift::models::RamoDisponible;
+use std::collections::HashMap;
+
+fn malla() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "numb_correlativo": 1},
+        {"id": 2, "nombre": "Calculo II", "codigo": "CBM1001", "requisitos_ids": [1], "numb_correlativo": 2},
+        {"id": 3, "nombre": "Calculo III", "codigo": "CBM1002", "requisitos_ids": [2], "numb_correlativo": 3},
+        {"id": 4, "nombre": "Programacion", "codigo": "CIT1000", "numb_correlativo": 4},
+        {"id": 5, "nombre": "Taller", "codigo": "CIT1010", "requisitos_ids": [4], "numb_correlativo": 5}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+#[test]
+fn test_reprobar_ramo_critico_atrasa_la_cadena() {
+    let sim = simular_reprobacion(&malla(), &[], "cbm1000").unwrap();
+    assert_eq!(sim.reprobado, "CBM1000");
+    assert_eq!(sim.semestres_restantes_base, 2);
+    assert_eq!(sim.semestres_restantes_reprobado, 3);
+    assert_eq!(sim.semestres_extra, 1);
+
+    let retrasos: Vec<(&str, i32, i32)> = sim.retrasados.iter()
+        .map(|r| (r.codigo.as_str(), r.semestre_base, r.semestre_reprobado))
+        .collect();
+    assert_eq!(retrasos, vec![("CBM1001", 1, 2), ("CBM1002", 2, 3)]);
+    assert!(sim.ruta_critica.contains(&"CBM1000".to_string()));
+    assert!(!sim.ruta_critica.contains(&"CIT1000".to_string()));
+}
+
+#[test]
+fn test_reprobar_ramo_con_holgura_no_atrasa_el_egreso() {
+    // Ya aprobó Calculo I: la cadena de cálculo sigue siendo la más larga
+    let sim = simular_reprobacion(&malla(), &["CBM1000".to_string(), "CIT1000".to_string()], "Programacion").unwrap();
+    assert_eq!(sim.reprobado, "CIT1000");
+    assert_eq!(sim.semestres_extra, 0);
+    assert_eq!(sim.retrasados.len(), 1);
+    assert_eq!(sim.retrasados[0].codigo, "CIT1010");
+
+    assert!(simular_reprobacion(&malla(), &[], "XYZ9999").unwrap_err().contains("no está en la malla"));
+}
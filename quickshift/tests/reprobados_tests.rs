@@ -0,0 +1,62 @@
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::algorithm::desglose::desglosar;
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+use common::{params, seccion};
+
+fn ramos() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1, "numb_correlativo": 1, "critico": true},
+        {"id": 2, "nombre": "CIT1010", "codigo": "CIT1010", "semestre": 2, "numb_correlativo": 12},
+        {"id": 3, "nombre": "FIC1000", "codigo": "FIC1000", "semestre": 1, "numb_correlativo": 4}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+fn secciones() -> Vec<Seccion> {
+    // CBM1000 y CIT1010 chocan: cabe solo uno de los dos junto a FIC1000
+    vec![
+        seccion("CBM1000", "1", &["LU 08:30 - 09:50"]),
+        seccion("CIT1010", "1", &["LU 08:30 - 09:50"]),
+        seccion("FIC1000", "1", &["MA 10:00 - 11:20"]),
+    ]
+}
+
+fn codigos(sol: &[(Seccion, i32)]) -> Vec<&str> {
+    sol.iter().map(|(s, _)| s.codigo.as_str()).collect()
+}
+
+#[test]
+fn test_reprobado_se_retoma_antes_que_un_ramo_critico() {
+    let sin = params("MallaReprobados", serde_json::json!({}));
+    let sols = get_clique_max_pond_with_prefs(&secciones(), &ramos(), &sin);
+    assert!(codigos(&sols[0].0).contains(&"CBM1000"));
+
+    let p = params("MallaReprobados", serde_json::json!({"ramos_reprobados": ["cit1010"]}));
+    let sols = get_clique_max_pond_with_prefs(&secciones(), &ramos(), &p);
+    let (sol, score) = &sols[0];
+    assert!(codigos(sol).contains(&"CIT1010"), "{:?}", codigos(sol));
+
+    let d = desglosar(sol, *score, &p, &ramos());
+    assert_eq!(d.modificadores.ramos_reprobados, 20_000_000);
+    assert_eq!(d.retomas, vec!["CIT1010"]);
+    let json = serde_json::to_value(&d).unwrap();
+    assert_eq!(json["retomas"], serde_json::json!(["CIT1010"]));
+    assert_eq!(json["ramos_reprobados"], 20_000_000);
+}
+
+#[test]
+fn test_bonus_reprobado_configurable() {
+    // Sin bonus el reprobado vuelve a competir solo por su prioridad PERT
+    let p = params("MallaReprobados", serde_json::json!({"ramos_reprobados": ["CIT1010"], "scoring": {"bonus_ramo_reprobado": 0}}));
+    let sols = get_clique_max_pond_with_prefs(&secciones(), &ramos(), &p);
+    let (sol, score) = &sols[0];
+    assert!(codigos(sol).contains(&"CBM1000"), "{:?}", codigos(sol));
+
+    let d = desglosar(sol, *score, &p, &ramos());
+    assert_eq!(d.modificadores.ramos_reprobados, 0);
+    assert!(d.retomas.is_empty());
+    assert!(serde_json::to_value(&d).unwrap().get("retomas").is_none());
+}
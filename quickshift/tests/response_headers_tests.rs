@@ -0,0 +1,52 @@
+#![cfg(feature = "server")]
+
+use quickshift::middleware::response_headers::consume_quota;
+use quickshift::middleware::CacheStatus;
+
+#[test]
+fn test_consume_quota_exhausts_window() {
+    let client = "test-client-quota";
+    let first = consume_quota(client, 2);
+    assert!(!first.exceeded);
+    assert_eq!(first.remaining, 1);
+
+    let second = consume_quota(client, 2);
+    assert!(!second.exceeded);
+    assert_eq!(second.remaining, 0);
+
+    let third = consume_quota(client, 2);
+    assert!(third.exceeded);
+    assert!(third.reset_secs <= 60);
+}
+
+#[test]
+fn test_consume_quota_disabled() {
+    let q = consume_quota("test-client-unlimited", 0);
+    assert!(!q.exceeded);
+    assert_eq!(q.limit, 0);
+}
+
+#[test]
+fn test_cache_status_labels() {
+    assert_eq!(CacheStatus::Hit.as_str(), "hit");
+    assert_eq!(CacheStatus::Miss.as_str(), "miss");
+    assert_eq!(CacheStatus::Stale.as_str(), "stale");
+}
+
+#[test]
+fn test_cuota_desactivada_y_por_ip_del_socket() {
+    use actix_web::test::TestRequest;
+    use quickshift::app_config::AppConfig;
+    use quickshift::middleware::response_headers::ip_cliente;
+
+    assert_eq!(AppConfig::default().solve_rate_limit_per_min, 0);
+    assert!(!AppConfig::default().trust_proxy_headers);
+
+    // Sin proxy de confianza, un X-Forwarded-For inventado no cambia la IP
+    let req = TestRequest::default()
+        .peer_addr("10.0.0.7:5555".parse().unwrap())
+        .insert_header(("X-Forwarded-For", "1.2.3.4"))
+        .to_srv_request();
+    assert_eq!(ip_cliente(&req, false), "10.0.0.7");
+    assert_eq!(ip_cliente(&req, true), "1.2.3.4");
+}
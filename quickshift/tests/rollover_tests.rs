@@ -0,0 +1,55 @@
+#![cfg(feature = "server")]
+
+use quickshift::server_handlers::rollover::{archivar_periodo, periodo_de_archivo, planificar_rollover};
+use std::path::{Path, PathBuf};
+
+fn dir_temporal(nombre: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("quickshift_rollover_{}_{}", nombre, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn tocar(dir: &Path, nombre: &str) {
+    std::fs::write(dir.join(nombre), nombre.as_bytes()).unwrap();
+}
+
+#[test]
+fn test_periodo_de_archivo() {
+    assert_eq!(periodo_de_archivo("OA20251.xlsx"), Some(("OA", "20251".to_string())));
+    assert_eq!(periodo_de_archivo("PA2025-1.xlsx"), Some(("PA", "20251".to_string())));
+    assert_eq!(periodo_de_archivo("CFG20252.xlsx"), Some(("CFG", "20252".to_string())));
+    assert_eq!(periodo_de_archivo("OA20251_backup.xlsx"), None);
+    assert_eq!(periodo_de_archivo("Malla2020.xlsx"), None);
+}
+
+#[test]
+fn test_plan_y_archivo_de_rollover() {
+    let dir = dir_temporal("plan");
+    for f in ["OA20251.xlsx", "PA20251.xlsx", "CFG20251.xlsx", "OA20252.xlsx", "PA20252.xlsx", "Malla2020.xlsx"] {
+        tocar(&dir, f);
+    }
+
+    assert!(planificar_rollover(&dir, "20261").is_err(), "sin OA20261 debe fallar");
+    assert!(planificar_rollover(&dir, "20242").is_err(), "falta OA del periodo");
+
+    let plan = planificar_rollover(&dir, "20252").unwrap();
+    assert_eq!(plan.periodo_anterior.as_deref(), Some("20251"));
+    // Sin CFG20252 el CFG vigente se mantiene
+    assert_eq!(plan.a_archivar, vec!["OA20251.xlsx".to_string(), "PA20251.xlsx".to_string()]);
+    assert_eq!(plan.advertencias.len(), 1);
+
+    let archivados = archivar_periodo(&dir, "20251", &plan.a_archivar).unwrap();
+    assert_eq!(archivados.len(), 2);
+    assert_eq!(archivados[0].sha256.len(), 64);
+    assert!(!dir.join("OA20251.xlsx").exists());
+    let archivado = dir.join("archive/20251/OA20251.xlsx");
+    assert!(std::fs::metadata(&archivado).unwrap().permissions().readonly());
+    assert!(dir.join("archive/20251/checksums.json").exists());
+
+    // El periodo nuevo ya no tiene anterior con qué compararse salvo el propio
+    let plan = planificar_rollover(&dir, "20252").unwrap();
+    assert!(plan.periodo_anterior.is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -1,6 +1,6 @@
 use quickshift::algorithm::get_ramo_critico;
 use quickshift::excel;
-use quickshift::models::Seccion;
+use quickshift::models::{Componente, Seccion};
 use petgraph::graph::{NodeIndex, DiGraph};
 use quickshift::models::PertNode;
 use std::collections::HashMap;
@@ -72,6 +72,14 @@ fn test_prereqs_produce_pert_edges() {
                 codigo_box: String::new(),
                 is_cfg: false,
                 is_electivo: false,
+                linea: None,
+                sala: None,
+                campus: None,
+                componente: Componente::Catedra,
+                bundle_id: None,
+                cupos_totales: None,
+                cupos_disponibles: None,
+                evaluaciones: Vec::new(),
             }).collect()
         }
     };
@@ -0,0 +1,28 @@
+#![cfg(feature = "sample-data")]
+
+use quickshift::algorithm::ruta::ejecutar_ruta_critica_with_params;
+use quickshift::api_json::parse_json_input;
+use quickshift::excel::config::{self, DatafilesConfig, OrigenDatafiles};
+use quickshift::excel::{resolve_datafile_paths, sample};
+
+#[test]
+fn test_solve_con_datos_de_ejemplo() {
+    let dir = sample::materializar().expect("binario con sample-data");
+    assert!(sample::en_uso());
+    for (nombre, contenido) in sample::ARCHIVOS {
+        assert_eq!(std::fs::read(dir.join(nombre)).unwrap(), *contenido);
+    }
+
+    // Como si no hubiera otro directorio de datafiles
+    config::establecer(DatafilesConfig { dir: dir.clone(), origen: OrigenDatafiles::Ejemplo });
+    assert!(sample::en_uso());
+    let (malla, oferta, porcentajes) = resolve_datafile_paths(sample::MALLA).unwrap();
+    assert!(malla.starts_with(&dir) && oferta.starts_with(&dir) && porcentajes.starts_with(&dir));
+
+    let params = parse_json_input(&format!(
+        r#"{{"email":"a@example.com","ramos_pasados":[],"ramos_prioritarios":[],"malla":"{}"}}"#,
+        sample::MALLA
+    )).unwrap();
+    let soluciones = ejecutar_ruta_critica_with_params(params).unwrap();
+    assert!(!soluciones.is_empty());
+}
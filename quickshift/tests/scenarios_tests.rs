@@ -0,0 +1,74 @@
+#![cfg(feature = "server")]
+
+use quickshift::analithics::db::AnalyticsConn;
+use quickshift::analithics::scenarios::*;
+use serde_json::json;
+
+fn conn() -> AnalyticsConn {
+    let c = rusqlite::Connection::open_in_memory().unwrap();
+    c.execute(SQLITE_SCHEMA, []).unwrap();
+    AnalyticsConn::Sqlite(c)
+}
+
+#[test]
+fn test_escenarios_versionados_y_restauracion() {
+    let conn = conn();
+    let v1 = json!({"malla": "MC2020", "ramos_pasados": ["CBM1000"]});
+    let v2 = json!({"malla": "MC2020", "ramos_pasados": ["CBM1000", "CBM1001"]});
+
+    assert_eq!(guardar_escenario(&conn, "Alumno@X.cl", "tesis 2026", &v1, None).unwrap().version, 1);
+    assert_eq!(guardar_escenario(&conn, "alumno@x.cl", "tesis 2026", &v2, None).unwrap().version, 2);
+    guardar_escenario(&conn, "alumno@x.cl", "sin viernes", &v1, None).unwrap();
+    guardar_escenario(&conn, "otro@x.cl", "tesis 2026", &v1, None).unwrap();
+
+    let lista = listar_escenarios(&conn, "alumno@x.cl").unwrap();
+    assert_eq!(lista.iter().map(|e| e.nombre.as_str()).collect::<Vec<_>>(), vec!["sin viernes", "tesis 2026"]);
+    assert_eq!(lista[1].version_actual, 2);
+    assert_eq!(lista[1].versiones, 2);
+
+    // Restaurar v1 crea la v3 con el contenido de v1; el historial se conserva
+    let v3 = restaurar_escenario(&conn, "alumno@x.cl", "tesis 2026", 1).unwrap().unwrap();
+    assert_eq!(v3.version, 3);
+    assert_eq!(v3.restaurado_de, Some(1));
+    assert_eq!(obtener_escenario(&conn, "alumno@x.cl", "tesis 2026", None).unwrap().unwrap().params, v1);
+    assert_eq!(historial_escenario(&conn, "alumno@x.cl", "tesis 2026").unwrap().len(), 3);
+
+    assert!(restaurar_escenario(&conn, "alumno@x.cl", "tesis 2026", 9).unwrap().is_none());
+    assert!(obtener_escenario(&conn, "alumno@x.cl", "no existe", None).unwrap().is_none());
+}
+
+#[test]
+fn test_diff_escenarios() {
+    let a = json!({"malla": "MC2020", "ramos_pasados": ["CBM1000", "CIT1000"], "student_ranking": 0.5});
+    let b = json!({"malla": "MC2020", "ramos_pasados": ["CBM1000", "CBM1001"], "student_ranking": 0.7});
+
+    let diff = diff_escenarios(&a, &b);
+    assert_eq!(diff["iguales"], json!(false));
+    let cambios = diff["cambios"].as_array().unwrap();
+    assert_eq!(cambios.len(), 2);
+    assert_eq!(cambios[0], json!({"campo": "ramos_pasados", "agregados": ["CBM1001"], "quitados": ["CIT1000"]}));
+    assert_eq!(cambios[1], json!({"campo": "student_ranking", "antes": 0.5, "despues": 0.7}));
+
+    assert_eq!(diff_escenarios(&a, &a)["iguales"], json!(true));
+}
+
+#[test]
+fn test_guardados_concurrentes_no_repiten_version() {
+    let path = std::env::temp_dir().join(format!("escenarios_concurrentes_{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    rusqlite::Connection::open(&path).unwrap().execute(SQLITE_SCHEMA, []).unwrap();
+
+    let hilos: Vec<_> = (0..8).map(|i| {
+        let path = path.clone();
+        std::thread::spawn(move || {
+            let c = rusqlite::Connection::open(&path).unwrap();
+            c.busy_timeout(std::time::Duration::from_secs(10)).unwrap();
+            let conn = AnalyticsConn::Sqlite(c);
+            guardar_escenario(&conn, "alumno@x.cl", "plan", &json!({"i": i}), None).unwrap().version
+        })
+    }).collect();
+    let mut versiones: Vec<i64> = hilos.into_iter().map(|h| h.join().unwrap()).collect();
+    versiones.sort();
+    assert_eq!(versiones, (1..=8).collect::<Vec<i64>>());
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,59 @@
+use quickshift::export::Dia;
+use quickshift::models::{FranjaGrid, ScheduleGrid, Seccion};
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, seccion: &str, horario: &[&str]) -> Seccion {
+    seccion_con(codigo, seccion, horario, serde_json::json!({"nombre": format!("Ramo {}", codigo), "profesor": "Perez"}))
+}
+
+fn franja(inicio: &str, fin: &str) -> FranjaGrid {
+    FranjaGrid { inicio: inicio.to_string(), fin: fin.to_string() }
+}
+
+#[test]
+fn test_grid_franjas_y_celdas() {
+    let grid = ScheduleGrid::from_secciones(&[
+        seccion("CBM1000", "1", &["LU MI 08:30 - 10:00"]),
+        seccion("CIT1000", "2", &["LU 09:00-11:00", "VI 14:30-15:50"]),
+        seccion("FIC1000", "1", &["Sin horario"]),
+    ]);
+
+    assert_eq!(grid.dias, vec![Dia::Lunes, Dia::Martes, Dia::Miercoles, Dia::Jueves, Dia::Viernes]);
+    assert_eq!(grid.franjas, vec![
+        franja("08:30", "09:00"),
+        franja("09:00", "10:00"),
+        franja("10:00", "11:00"),
+        franja("14:30", "15:50"),
+    ]);
+    assert_eq!(grid.celdas.len(), grid.franjas.len());
+    assert!(grid.celdas.iter().all(|fila| fila.len() == grid.dias.len()));
+
+    // Lunes 09:00-10:00: ambos ramos se solapan en la misma celda
+    let codigos: Vec<&str> = grid.celdas[1][0].iter().map(|c| c.codigo.as_str()).collect();
+    assert_eq!(codigos, vec!["CBM1000", "CIT1000"]);
+    assert_eq!(grid.celdas[0][2][0].codigo, "CBM1000");
+    assert_eq!(grid.celdas[2][0][0].seccion, "2");
+    assert_eq!(grid.celdas[3][4][0].profesor, "Perez");
+    assert!(grid.celdas[0][1].is_empty());
+
+    assert_eq!(grid.sin_horario.len(), 1);
+    assert_eq!(grid.sin_horario[0].codigo, "FIC1000");
+}
+
+#[test]
+fn test_grid_fin_de_semana_y_serializacion() {
+    let grid = ScheduleGrid::from_secciones(&[seccion("CIT2000", "1", &["SA 09:00-12:00"])]);
+    assert_eq!(grid.dias.len(), 6);
+    assert_eq!(grid.dias[5], Dia::Sabado);
+
+    let v = serde_json::to_value(&grid).unwrap();
+    assert_eq!(v["dias"][5], "SA");
+    assert_eq!(v["franjas"][0], serde_json::json!({"inicio": "09:00", "fin": "12:00"}));
+    assert_eq!(v["celdas"][0][5][0]["nombre"], "Ramo CIT2000");
+
+    let vacia = ScheduleGrid::from_secciones(&[]);
+    assert!(vacia.franjas.is_empty() && vacia.celdas.is_empty());
+    assert_eq!(vacia.dias.len(), 5);
+}
@@ -0,0 +1,79 @@
+use quickshift::algorithm::clique::{get_clique_max_pond_with_prefs, modificadores_score};
+use quickshift::api_json::InputParams;
+use quickshift::models::RamoDisponible;
+use quickshift::scoring::{ScoringConfig, ScoringOverrides};
+use std::collections::HashMap;
+
+mod common;
+use common::{params, seccion};
+
+fn ramos() -> HashMap<String, RamoDisponible> {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1, "critico": true, "holgura": 2, "numb_correlativo": 5},
+        {"id": 2, "nombre": "CBM1001", "codigo": "CBM1001", "semestre": 1}
+    ])).unwrap();
+    ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect()
+}
+
+fn prioridades(p: &InputParams) -> HashMap<String, i32> {
+    let secciones = vec![
+        seccion("CBM1000", "3", &["LU 08:30 - 09:50"]),
+        seccion("CBM1001", "1", &["LU 14:30 - 15:50"]),
+    ];
+    let sols = get_clique_max_pond_with_prefs(&secciones, &ramos(), p);
+    sols[0].0.iter().map(|(s, score)| (s.codigo.clone(), *score)).collect()
+}
+
+#[test]
+fn test_pesos_por_defecto_reproducen_cc_uu_kk_ss() {
+    let pri = prioridades(&params("MallaScoring", serde_json::json!({})));
+    // CC=10 UU=08 KK=55 SS=03
+    assert_eq!(pri["CBM1000"], 10_085_503);
+    assert_eq!(pri["CBM1001"], 106_001);
+}
+
+#[test]
+fn test_overrides_del_request_cambian_la_prioridad() {
+    let p = params("MallaScoring", serde_json::json!({"scoring": {"bonus_critico": 0, "peso_seccion": 1000}}));
+    assert_eq!(p.scoring.as_ref().unwrap().peso_seccion, Some(1000));
+    let pri = prioridades(&p);
+    assert_eq!(pri["CBM1000"], 80_000 + 5_500 + 3_000);
+    assert_eq!(pri["CBM1001"], 100_000 + 6_000 + 1_000);
+
+    let invalido = serde_json::from_value::<InputParams>(serde_json::json!({
+        "email": "a@b.cl", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "M",
+        "scoring": {"peso_inexistente": 1}
+    }));
+    assert!(invalido.is_err());
+}
+
+#[test]
+fn test_modificadores_usan_los_pesos() {
+    let sol = vec![
+        (seccion("CBM1000", "1", &["LU 08:30 - 09:50"]), 0),
+        (seccion("CBM1001", "1", &["LU 10:30 - 11:50"]), 0),
+    ];
+    let p = params("MallaScoring", serde_json::json!({"ramos_prioritarios": ["CBM1001"], "optimizations": ["minimize-gaps"]}));
+
+    let m = modificadores_score(&sol, &p, &ScoringConfig::default());
+    assert_eq!((m.ramos_prioritarios, m.gaps), (100_000_000, -40 * 100));
+
+    let pesos = ScoringConfig { bonus_ramo_prioritario: 7, penalizacion_gap_por_minuto: 2, ..Default::default() };
+    let m = modificadores_score(&sol, &p, &pesos);
+    assert_eq!((m.ramos_prioritarios, m.gaps), (7, -80));
+}
+
+#[test]
+fn test_toml_parcial_y_overrides() {
+    let c = ScoringConfig::desde_toml("peso_compactacion = 20000\npenalizacion_gap_por_minuto = 250\n").unwrap();
+    assert_eq!(c.peso_compactacion, 20_000);
+    assert_eq!(c.penalizacion_gap_por_minuto, 250);
+    assert_eq!(c.bonus_ramo_prioritario, ScoringConfig::default().bonus_ramo_prioritario);
+    assert!(ScoringConfig::desde_toml("peso_compactacion = \"mucho\"").is_err());
+    assert!(ScoringConfig::desde_toml("otro = 1").is_err());
+
+    let o = ScoringOverrides { bonus_profesor: Some(1), ..Default::default() };
+    let efectiva = c.con_overrides(&o);
+    assert_eq!(efectiva.bonus_profesor, 1);
+    assert_eq!(efectiva.peso_compactacion, 20_000);
+}
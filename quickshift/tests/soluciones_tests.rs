@@ -0,0 +1,50 @@
+#![cfg(feature = "server")]
+
+use quickshift::analithics::db::AnalyticsConn;
+use quickshift::analithics::soluciones::*;
+use serde_json::json;
+
+fn conn() -> AnalyticsConn {
+    let c = rusqlite::Connection::open_in_memory().unwrap();
+    c.execute_batch(SQLITE_SCHEMA).unwrap();
+    AnalyticsConn::Sqlite(c)
+}
+
+#[test]
+fn test_guardar_y_obtener_conjunto_de_soluciones() {
+    let conn = conn();
+    let params = json!({"email": "Alumno@X.cl", "malla": "MC2020", "ramos_pasados": []});
+    let soluciones = json!([
+        {"total_score": 200, "secciones": [{"codigo": "CBM1000", "seccion": "1"}]},
+        {"total_score": 100, "secciones": [{"codigo": "CBM1001", "seccion": "2"}]}
+    ]);
+
+    let id = guardar_soluciones(&conn, Some("Alumno@X.cl"), &params, &soluciones).unwrap();
+    assert!(uuid::Uuid::parse_str(&id).is_ok(), "{}", id);
+    let otro = guardar_soluciones(&conn, None, &params, &soluciones).unwrap();
+    assert_ne!(id, otro);
+
+    let conjunto = obtener_soluciones(&conn, &id.to_uppercase()).unwrap().unwrap();
+    assert_eq!(conjunto.email.as_deref(), Some("alumno@x.cl"));
+    assert_eq!(conjunto.params, params);
+    assert_eq!(conjunto.solucion(1).unwrap()["total_score"], 100);
+    assert!(conjunto.solucion(2).is_none());
+    assert_eq!(obtener_soluciones(&conn, &otro).unwrap().unwrap().email, None);
+
+    assert!(obtener_soluciones(&conn, "00000000-0000-0000-0000-000000000000").unwrap().is_none());
+}
+
+#[test]
+fn test_solucion_elegida_es_la_ultima_confirmada() {
+    let conn = conn();
+    let soluciones = json!([{"total_score": 1, "secciones": []}, {"total_score": 0, "secciones": []}]);
+    let id = guardar_soluciones(&conn, Some("alumno@x.cl"), &json!({}), &soluciones).unwrap();
+
+    assert!(solucion_elegida(&conn, "alumno@x.cl").unwrap().is_none());
+    vincular_solucion(&conn, "alumno@x.cl", &id, 0).unwrap();
+    vincular_solucion(&conn, "ALUMNO@x.cl", &id, 1).unwrap();
+
+    let elegida = solucion_elegida(&conn, "Alumno@X.cl").unwrap().unwrap();
+    assert_eq!((elegida.solution_id.as_str(), elegida.indice), (id.as_str(), 1));
+    assert!(solucion_elegida(&conn, "otro@x.cl").unwrap().is_none());
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "server")]
+
+use actix_web::{test as atest, web, App};
+use base64::Engine;
+use quickshift::server_handlers::{input_desde_query, json_de_query, solve_get_handler};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn query(pares: &[(&str, &str)]) -> HashMap<String, String> {
+    pares.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn test_json_de_query_acepta_json_y_base64() {
+    let filtros = json!({"dias_horarios_libres": {"habilitado": true, "dias_libres_preferidos": ["VI"]}});
+    let texto = filtros.to_string();
+    assert_eq!(json_de_query("filtros", &texto).unwrap(), filtros);
+
+    let estandar = base64::engine::general_purpose::STANDARD.encode(&texto);
+    assert_eq!(json_de_query("filtros", &estandar).unwrap(), filtros);
+    let url_safe = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(&texto);
+    assert_eq!(json_de_query("filtros", &url_safe).unwrap(), filtros);
+
+    assert!(json_de_query("filtros", "{no json").is_err());
+    assert!(json_de_query("filtros", "no es base64!").is_err());
+}
+
+#[test]
+fn test_input_desde_query_equivale_al_body_de_post() {
+    let filtros = json!({
+        "dias_horarios_libres": {"habilitado": true, "dias_libres_preferidos": ["VI"]},
+        "preferencias_profesores": {"habilitado": true, "profesores_preferidos": ["Soto"], "modo": "soft"}
+    });
+    let qm = query(&[
+        ("malla", "MiMalla.xlsx"),
+        ("email", "alumno@example.com"),
+        ("ramos_pasados", "CBM1000, CBQ1000"),
+        ("horarios_prohibidos", "LU 08:30-10:00"),
+        ("optimizations", "no-fridays,compact-days"),
+        ("filtros", &filtros.to_string()),
+        ("scoring", &base64::engine::general_purpose::URL_SAFE.encode(r#"{"bonus_profesor": 1}"#)),
+    ]);
+    let desde_get = input_desde_query(&qm).unwrap();
+
+    let desde_post: quickshift::api_json::InputParams = serde_json::from_value(json!({
+        "malla": "MiMalla.xlsx",
+        "email": "alumno@example.com",
+        "ramos_pasados": ["CBM1000", "CBQ1000"],
+        "ramos_prioritarios": [],
+        "horarios_prohibidos": ["LU 08:30-10:00"],
+        "optimizations": ["no-fridays", "compact-days"],
+        "filtros": filtros,
+        "scoring": {"bonus_profesor": 1},
+        "sheet": null, "student_ranking": null, "ranking": null
+    })).unwrap();
+    assert_eq!(serde_json::to_value(&desde_get).unwrap(), serde_json::to_value(&desde_post).unwrap());
+}
+
+#[actix_web::test]
+async fn test_filtros_invalidos_responden_invalid_input() {
+    let app = atest::init_service(App::new().route("/solve", web::get().to(solve_get_handler))).await;
+
+    let uri = "/solve?malla=MiMalla.xlsx&filtros=%7B%22dias_horarios_libres%22%3A1%7D";
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri(uri).to_request()).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!(body["code"], "INVALID_INPUT");
+    assert!(body["message"].as_str().unwrap().contains("filtros"));
+
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/solve?malla=MiMalla.xlsx&filtros=%%%").to_request()).await;
+    assert_eq!(resp.status(), 400);
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "server")]
+
+use quickshift::server_handlers::solve_ws::{aplicar_comando, procesar, ComandoWs, EstadoSolveWs};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+fn comando(v: Value) -> ComandoWs {
+    serde_json::from_value(v).unwrap()
+}
+
+fn start() -> (EstadoSolveWs, Value) {
+    procesar(None, comando(json!({
+        "cmd": "start",
+        "max_soluciones": 3,
+        "params": {
+            "email": "ws@example.com",
+            "malla": "MC2020.xlsx",
+            "ramos_pasados": ["CBM1000", "CBM1001", "CBQ1000", "CIG1012"],
+            "ramos_prioritarios": [],
+        },
+    }))).unwrap()
+}
+
+fn boxes(solucion: &Value) -> Vec<String> {
+    solucion["secciones"].as_array().unwrap().iter().map(|s| s["codigo_box"].as_str().unwrap().to_string()).collect()
+}
+
+#[test]
+fn test_comandos_ws_parseo() {
+    assert_eq!(comando(json!({"cmd": "add-passed-course", "ramo": "CBM1002"})).nombre(), "add-passed-course");
+    assert_eq!(comando(json!({"cmd": "toggle-filter", "filtro": "solo_con_cupos"})).nombre(), "toggle-filter");
+    assert!(serde_json::from_value::<ComandoWs>(json!({"cmd": "borrar-todo"})).is_err());
+    assert!(serde_json::from_value::<ComandoWs>(json!({"cmd": "pin-section"})).is_err());
+
+    // antes de `start` ningún otro comando se acepta
+    let err = aplicar_comando(None, comando(json!({"cmd": "pin-section", "seccion": "CBM1002"}))).err().unwrap();
+    assert_eq!(err.code(), "INVALID_INPUT");
+}
+
+#[test]
+fn test_sesion_ws_incremental() {
+    let (estado, cuerpo) = start();
+    assert_eq!(cuerpo["tipo"], "soluciones");
+    assert_eq!(cuerpo["cmd"], "start");
+    let soluciones = cuerpo["soluciones"].as_array().unwrap();
+    assert!(!soluciones.is_empty() && soluciones.len() <= 3);
+
+    // filtros: sin `habilitado` se invierte; `config` reemplaza y activa
+    let (estado, cuerpo) = procesar(Some(estado), comando(json!({"cmd": "toggle-filter", "filtro": "solo_con_cupos"}))).unwrap();
+    assert_eq!(cuerpo["estado"]["filtros"]["solo_con_cupos"], true);
+    let (estado, cuerpo) = procesar(Some(estado), comando(json!({
+        "cmd": "toggle-filter", "filtro": "ventana_entre_actividades", "config": {"minutos_entre_clases": 10},
+    }))).unwrap();
+    assert_eq!(cuerpo["estado"]["filtros"]["ventana_entre_actividades"], json!({"habilitado": true, "minutos_entre_clases": 10}));
+    let (estado, cuerpo) = procesar(Some(estado), comando(json!({"cmd": "toggle-filter", "filtro": "ventana_entre_actividades"}))).unwrap();
+    assert_eq!(cuerpo["estado"]["filtros"]["ventana_entre_actividades"]["habilitado"], false);
+    assert!(procesar(Some(estado.clone()), comando(json!({"cmd": "toggle-filter", "filtro": "color_favorito"}))).is_err());
+
+    // ramo aprobado: se rehace la preparación pero el grafo es el mismo
+    let grafo = estado.grafo.clone();
+    let (estado, cuerpo) = procesar(Some(estado), comando(json!({"cmd": "add-passed-course", "ramo": "CBM1002"}))).unwrap();
+    assert!(Arc::ptr_eq(&grafo, &estado.grafo));
+    assert!(estado.params.ramos_pasados.iter().any(|r| r == "CBM1002"));
+    for s in cuerpo["soluciones"].as_array().unwrap() {
+        assert!(!boxes(s).iter().any(|b| b.starts_with("CBM1002")));
+    }
+
+    // fijar una sección de otra solución que no está en la mejor: aparece en todas
+    let primera = boxes(&cuerpo["soluciones"][0]);
+    let otra = cuerpo["soluciones"].as_array().unwrap().iter().skip(1)
+        .flat_map(boxes)
+        .find(|b| !primera.contains(b))
+        .unwrap();
+    let (estado, cuerpo) = procesar(Some(estado), comando(json!({"cmd": "pin-section", "seccion": otra}))).unwrap();
+    assert_eq!(cuerpo["estado"]["secciones_fijas"], json!([otra]));
+    for s in cuerpo["soluciones"].as_array().unwrap() {
+        assert!(boxes(s).contains(&otra));
+    }
+    let (estado, _) = procesar(Some(estado), comando(json!({"cmd": "unpin-section", "seccion": otra.to_lowercase()}))).unwrap();
+    assert!(estado.params.secciones_fijas.is_empty());
+
+    // un comando que falla no cambia el estado
+    let err = procesar(Some(estado.clone()), comando(json!({"cmd": "pin-section", "seccion": "NOEXISTE-99"}))).err().unwrap();
+    assert_eq!(err.code(), "PINNED_SECTIONS_INFEASIBLE");
+    assert!(estado.params.secciones_fijas.is_empty());
+}
+
+#[actix_web::test]
+async fn test_ws_solve_handshake() {
+    use actix_web::{test as atest, web, App};
+    let app = atest::init_service(App::new()
+        .route("/ws/solve", web::get().to(quickshift::server_handlers::solve_ws::solve_ws_handler))).await;
+
+    let req = atest::TestRequest::get().uri("/ws/solve")
+        .insert_header(("Upgrade", "websocket"))
+        .insert_header(("Connection", "Upgrade"))
+        .insert_header(("Sec-WebSocket-Version", "13"))
+        .insert_header(("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+        .to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 101);
+    assert_eq!(resp.headers().get("sec-websocket-accept").unwrap(), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+
+    // sin cabeceras de upgrade no hay socket
+    let req = atest::TestRequest::get().uri("/ws/solve").to_request();
+    assert_eq!(atest::call_service(&app, req).await.status(), 400);
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "server")]
+
+use quickshift::analithics::db::QueryLog;
+use quickshift::analithics::queries::{dashboard_con, resumen_dashboard, Bucket};
+use quickshift::analithics::storage::{SqliteStorage, Storage};
+use serde_json::json;
+
+fn storage() -> SqliteStorage {
+    let c = rusqlite::Connection::open_in_memory().unwrap();
+    c.execute_batch(
+        "CREATE TABLE queries (id INTEGER PRIMARY KEY AUTOINCREMENT, ts TEXT NOT NULL, duration_ms INTEGER, email TEXT, malla TEXT,
+            student_ranking REAL, ramos_pasados TEXT, ramos_prioritarios TEXT, filtros_json TEXT, request_json TEXT, response_json TEXT,
+            client_ip TEXT, api_key TEXT);
+         CREATE TABLE reports (id INTEGER PRIMARY KEY AUTOINCREMENT, ts TEXT NOT NULL, query_type TEXT, params_json TEXT, result_json TEXT);",
+    ).unwrap();
+    SqliteStorage(c)
+}
+
+fn fila(ts: &str, duration_ms: Option<i64>, ramos_prioritarios: Option<&str>, filtros_json: Option<&str>, response_json: Option<&str>) -> QueryLog {
+    QueryLog {
+        id: 0,
+        ts: ts.to_string(),
+        duration_ms,
+        email: Some("a@example.com".to_string()),
+        malla: None,
+        student_ranking: None,
+        ramos_pasados: None,
+        ramos_prioritarios: ramos_prioritarios.map(String::from),
+        filtros_json: filtros_json.map(String::from),
+        request_json: None,
+        response_json: response_json.map(String::from),
+        client_ip: None,
+        api_key: Some("movil".to_string()),
+    }
+}
+
+#[test]
+fn test_sqlite_storage_inserta_y_lee() {
+    let s = storage();
+    assert_eq!(s.backend(), "sqlite");
+    s.insertar_query(&fila("2025-03-03T10:00:00+00:00", Some(100), None, None, None)).unwrap();
+    s.insertar_query(&fila("2025-03-10T10:00:00+00:00", None, None, None, None)).unwrap();
+    s.insertar_reporte("2025-03-10T11:00:00+00:00", "ramos_mas_pasados", "{}", "[]").unwrap();
+
+    let todas = s.queries().unwrap();
+    assert_eq!(todas.iter().map(|q| q.id).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(todas[0].api_key.as_deref(), Some("movil"));
+    let marzo_3 = s.queries_entre("2025-03-03", "2025-03-04").unwrap();
+    assert_eq!(marzo_3.len(), 1);
+    assert_eq!(marzo_3[0].duration_ms, Some(100));
+    let reportes: i64 = s.0.query_row("SELECT COUNT(*) FROM reports", [], |r| r.get(0)).unwrap();
+    assert_eq!(reportes, 1);
+}
+
+#[test]
+fn test_resumen_dashboard_igual_al_sql() {
+    let s = storage();
+    let filtros = r#"{"dias_horarios_libres":{"habilitado":true},"balance_lineas":{"habilitado":false},"ventana":{"habilitado":1}}"#;
+    for q in [
+        fila("2025-03-03T10:00:00.123456+00:00", Some(100), Some(r#"["CIT3313","CBM1000"]"#), Some(filtros), Some(r#"{"soluciones_count":3}"#)),
+        fila("2025-03-03T18:00:00+00:00", Some(300), Some(r#"["CIT3313"]"#), Some(filtros), Some(r#"{"soluciones_count":12}"#)),
+        fila("2025-03-09T09:00:00+00:00", None, None, None, Some("no es json")),
+        fila("2025-03-10T09:00:00+00:00", Some(400), Some(r#"["CBM1000"]"#), None, Some(r#"{"soluciones_count":0}"#)),
+        fila("2025-03-11T09:00:00+00:00", Some(50), Some("{}"), None, Some(r#"{"soluciones_count":40}"#)),
+        fila("sin fecha", Some(10), None, None, None),
+    ] {
+        s.insertar_query(&q).unwrap();
+    }
+    let (desde, hasta) = ("", "9999");
+    for bucket in [Bucket::Day, Bucket::Week] {
+        let sql = dashboard_con(&s.0, desde, hasta, bucket).unwrap();
+        let rust = resumen_dashboard(&s.queries_entre(desde, hasta).unwrap(), bucket);
+        assert_eq!(rust, sql);
+    }
+    let vacio = resumen_dashboard(&[], Bucket::Day);
+    assert_eq!(vacio, dashboard_con(&s.0, "2030", "2031", Bucket::Day).unwrap());
+    assert_eq!(vacio["latency_ms"], json!({"avg": null, "max": null}));
+}
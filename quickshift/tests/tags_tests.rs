@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use quickshift::algorithm::en_memoria::{resolver_en_memoria, SolveEnMemoria};
+use quickshift::algorithm::indice_ramos::RamoIndex;
+use quickshift::algorithm::tags::{ramo_evitado, tags_a_evitar, tags_de_solucion};
+use quickshift::excel::aplicar_notas;
+use quickshift::models::{RamoDisponible, Seccion, UserFilters};
+use serde_json::json;
+
+mod common;
+
+fn ramo(id: i32, codigo: &str, nombre: &str) -> RamoDisponible {
+    serde_json::from_value(json!({"id": id, "nombre": nombre, "codigo": codigo, "numb_correlativo": id, "semestre": 1})).unwrap()
+}
+
+fn seccion(codigo: &str) -> Seccion {
+    common::seccion(codigo, "1", &["LU 08:30-09:50"])
+}
+
+#[test]
+fn test_aplicar_notas_y_evitar() {
+    let mut ramos: HashMap<String, RamoDisponible> = [
+        ramo(1, "CBM1000", "Cálculo I"),
+        ramo(2, "CIT1000", "Programación"),
+        ramo(3, "CIT1010", "Comunicación"),
+    ].into_iter().map(|r| (r.codigo.clone(), r)).collect();
+    // Por código, por correlativo y por nombre; las repetidas no se duplican
+    let hoja: HashMap<String, Vec<String>> = [
+        ("cbm1000".to_string(), vec!["Alta carga".to_string()]),
+        ("1".to_string(), vec!["alta carga".to_string(), "Solo primer semestre".to_string()]),
+        ("Programacion".to_string(), vec!["Laboratorio".to_string()]),
+        ("XXX9999".to_string(), vec!["ignorada".to_string()]),
+    ].into_iter().collect();
+    aplicar_notas(&mut ramos, &hoja);
+    assert_eq!(ramos["CBM1000"].tags.len(), 2);
+    assert_eq!(ramos["CIT1000"].tags, vec!["Laboratorio"]);
+    assert!(ramos["CIT1010"].tags.is_empty());
+
+    let indice = RamoIndex::new(&ramos);
+    let filtros: UserFilters = serde_json::from_value(json!({"evitar_tags": ["ALTA CARGA"]})).unwrap();
+    let evitar = tags_a_evitar(&Some(filtros));
+    assert!(ramo_evitado(&seccion("CBM1000"), &indice, &evitar));
+    assert!(!ramo_evitado(&seccion("CIT1000"), &indice, &evitar));
+    assert!(tags_a_evitar(&None).is_empty());
+
+    let secciones = [seccion("CBM1000"), seccion("CIT1000"), seccion("CIT1010")];
+    let tags = tags_de_solucion(&secciones, &indice);
+    assert_eq!(tags.keys().collect::<Vec<_>>(), vec!["CBM1000", "CIT1000"]);
+}
+
+#[test]
+fn test_evitar_tags_excluye_ramos_de_las_soluciones() {
+    let entrada = |filtros: serde_json::Value| -> SolveEnMemoria {
+        serde_json::from_value(json!({
+            "email": "alumno@example.com",
+            "ramos_pasados": [],
+            "ramos_prioritarios": [],
+            "malla": "MallaEnMemoria",
+            "filtros": filtros,
+            "ramos": [
+                {"id": 1, "nombre": "Calculo I", "codigo": "CBM1000", "semestre": 1, "tags": ["Alta carga"]},
+                {"id": 2, "nombre": "Algebra", "codigo": "CBM1002", "semestre": 1},
+                {"id": 3, "nombre": "Programacion", "codigo": "CIT1000", "semestre": 1, "tags": ["Laboratório"]}
+            ],
+            "secciones": [
+                {"codigo": "CBM1000", "nombre": "Calculo I", "seccion": "1", "horario": ["LU 08:30-09:50"], "codigo_box": "CBM1000-1"},
+                {"codigo": "CBM1002", "nombre": "Algebra", "seccion": "1", "horario": ["LU 10:00-11:20"], "codigo_box": "CBM1002-1"},
+                {"codigo": "CIT1000", "nombre": "Programacion", "seccion": "1", "horario": ["MA 10:00-11:20"], "codigo_box": "CIT1000-1"}
+            ]
+        })).unwrap()
+    };
+    let codigos = |soluciones: &Vec<(Vec<(Seccion, i32)>, i64)>| -> Vec<String> {
+        soluciones.iter().flat_map(|(sol, _)| sol.iter().map(|(s, _)| s.codigo.clone())).collect()
+    };
+
+    let sin_filtro = resolver_en_memoria(entrada(json!({}))).unwrap();
+    assert!(codigos(&sin_filtro).contains(&"CBM1000".to_string()));
+
+    let con_filtro = resolver_en_memoria(entrada(json!({"evitar_tags": ["alta carga", "laboratorio"]}))).unwrap();
+    assert!(!con_filtro.is_empty());
+    let vistos = codigos(&con_filtro);
+    assert!(vistos.iter().all(|c| c == "CBM1002"), "{:?}", vistos);
+}
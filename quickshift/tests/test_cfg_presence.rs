@@ -6,14 +6,8 @@ fn create_base_params(ramos_pasados: Vec<String>) -> InputParams {
     InputParams {
         email: "ignacio.marambio_z@mail.udp.cl".to_string(),
         ramos_pasados,
-        ramos_prioritarios: Vec::new(),
-        horarios_preferidos: Vec::new(),
-        horarios_prohibidos: Vec::new(),
         malla: "MC2020moded.xlsx".to_string(),
-        anio: None,
-        sheet: None,
         student_ranking: Some(0.5),
-        ranking: None,
         filtros: Some(UserFilters {
             dias_horarios_libres: Some(DiaHorariosLibres {
                 habilitado: true,
@@ -31,10 +25,18 @@ fn create_base_params(ramos_pasados: Vec<String>) -> InputParams {
                 habilitado: false,
                 profesores_preferidos: None,
                 profesores_evitar: None,
+                modo: Default::default(),
             }),
             balance_lineas: None,
+            tiempo_traslado: None,
+            solo_con_cupos: false,
+            max_ramos_por_dia: None,
+            hora_inicio_minima: None,
+            hora_fin_maxima: None,
+            evitar_tags: Vec::new(),
         }),
         optimizations: vec!["minimize-gaps".to_string()],
+        ..Default::default()
     }
 }
 
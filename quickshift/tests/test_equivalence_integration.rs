@@ -14,15 +14,8 @@ fn test_equivalence_integration_full_pipeline() {
             "MAT1610".to_string(), // Cálculo I
             "CIT1001".to_string(), // Programación I
         ],
-        ramos_prioritarios: vec![],
-        horarios_preferidos: vec![],
-        horarios_prohibidos: vec![],
-        sheet: None,
         anio: Some(2020),
-        student_ranking: None,
-        ranking: None,
-        filtros: None,
-        optimizations: vec![],
+        ..Default::default()
     };
     
     eprintln!("\n=== TEST: Equivalencia CIG1014 -> CIG1003 ===");
@@ -74,15 +67,8 @@ fn test_multiple_equivalences() {
             "CIG1014".to_string(), // INGLÉS GENERAL III -> CIG1003
             "CIG1012".to_string(), // INGLÉS GENERAL II -> CIG1002
         ],
-        ramos_prioritarios: vec![],
-        horarios_preferidos: vec![],
-        horarios_prohibidos: vec![],
-        sheet: None,
         anio: Some(2020),
-        student_ranking: None,
-        ranking: None,
-        filtros: None,
-        optimizations: vec![],
+        ..Default::default()
     };
     
     eprintln!("\n=== TEST: Múltiples equivalencias ===");
@@ -1,21 +1,14 @@
 use quickshift::algorithm::ruta::ejecutar_ruta_critica_with_params;
 use quickshift::api_json::InputParams;
-use quickshift::models::UserFilters;
 
 fn create_base_params(ramos_pasados: Vec<String>) -> InputParams {
     InputParams {
         email: "test@mail.udp.cl".to_string(),
         ramos_pasados,
-        ramos_prioritarios: Vec::new(),
-        horarios_preferidos: Vec::new(),
-        horarios_prohibidos: Vec::new(),
         malla: "MC2020moded.xlsx".to_string(),
-        anio: None,
-        sheet: None,
         student_ranking: Some(0.5),
-        ranking: None,
         filtros: None,  // Sin filtros para simplificar test
-        optimizations: vec![],
+        ..Default::default()
     }
 }
 
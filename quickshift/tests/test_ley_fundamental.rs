@@ -227,16 +227,10 @@ mod test_ley_fundamental {
         let params = InputParams {
             email: "test@example.com".to_string(),
             ramos_pasados: ramos_aprobados.clone(),
-            ramos_prioritarios: vec![],
-            horarios_preferidos: vec![],
-            horarios_prohibidos: vec![],
             malla: "MC2020moded.xlsx".to_string(),
-            anio: None,
-            sheet: None,
             student_ranking: Some(0.5),
-            ranking: None,
             filtros: None, // SIN FILTROS
-            optimizations: vec![],
+            ..Default::default()
         };
 
         let soluciones = match ejecutar_ruta_critica_with_params(params) {
@@ -358,16 +352,9 @@ mod test_ley_fundamental {
         let params_sin_filtros = InputParams {
             email: "test@example.com".to_string(),
             ramos_pasados: ramos_aprobados.clone(),
-            ramos_prioritarios: vec![],
-            horarios_preferidos: vec![],
-            horarios_prohibidos: vec![],
             malla: "MiMalla.xlsx".to_string(),
-            anio: None,
-            sheet: None,
             student_ranking: Some(0.75),
-            ranking: None,
-            filtros: None,
-            optimizations: vec![],
+            ..Default::default()
         };
 
         let soluciones_sin_filtros = match ejecutar_ruta_critica_with_params(params_sin_filtros) {
@@ -407,16 +394,10 @@ mod test_ley_fundamental {
         let params_con_filtros = InputParams {
             email: "test@example.com".to_string(),
             ramos_pasados: ramos_aprobados.clone(),
-            ramos_prioritarios: vec![],
-            horarios_preferidos: vec![],
-            horarios_prohibidos: vec![],
             malla: "MiMalla.xlsx".to_string(),
-            anio: None,
-            sheet: None,
             student_ranking: Some(0.75),
-            ranking: None,
             filtros: Some(filtros_con_restriccion),
-            optimizations: vec![],
+            ..Default::default()
         };
 
         let soluciones_con_filtros = match ejecutar_ruta_critica_with_params(params_con_filtros) {
@@ -539,21 +520,16 @@ mod test_ley_fundamental {
             habilitado: false, // Deshabilitado para no restringir tanto
             profesores_preferidos: None,
             profesores_evitar: None,
+            modo: Default::default(),
         });
 
         let params = InputParams {
             email: "test@example.com".to_string(),
             ramos_pasados: ramos_aprobados,
-            ramos_prioritarios: vec![],
-            horarios_preferidos: vec![],
-            horarios_prohibidos: vec![],
             malla: "MiMalla.xlsx".to_string(),
-            anio: None,
-            sheet: None,
             student_ranking: Some(0.75),
-            ranking: None,
             filtros: Some(filtros),
-            optimizations: vec![],
+            ..Default::default()
         };
 
         println!("📋 Parámetros:");
@@ -10,16 +10,9 @@ fn test_malla2020_sin_ramos_aprobados() {
     let params = InputParams {
         email: "estudiante@example.com".to_string(),
         ramos_pasados: vec![], // ❌ VACÍO
-        ramos_prioritarios: vec![],
-        horarios_preferidos: vec![],
-        horarios_prohibidos: vec![],
         malla: "Malla2020.xlsx".to_string(), // ⚠️ Malla2020, no MiMalla
-        anio: None,
-        sheet: None,
         student_ranking: Some(0.75),
-        ranking: None,
-        filtros: None,
-        optimizations: vec![],
+        ..Default::default()
     };
 
     println!("\n📋 Parámetros:");
@@ -10,16 +10,9 @@ fn test_malla2020_con_calculo_i_aprobado() {
     let params = InputParams {
         email: "estudiante@example.com".to_string(),
         ramos_pasados: vec!["CBM1001".to_string()], // ✅ Cálculo I
-        ramos_prioritarios: vec![],
-        horarios_preferidos: vec![],
-        horarios_prohibidos: vec![],
         malla: "Malla2020.xlsx".to_string(),
-        anio: None,
-        sheet: None,
         student_ranking: Some(0.75),
-        ranking: None,
-        filtros: None,
-        optimizations: vec![],
+        ..Default::default()
     };
 
     println!("\n📋 Parámetros:");
@@ -84,16 +77,9 @@ fn test_malla2020_con_primer_semestre_completo() {
             "CIT1000".to_string(), // Programación
             "FIC1000".to_string(), // Comunicación
         ],
-        ramos_prioritarios: vec![],
-        horarios_preferidos: vec![],
-        horarios_prohibidos: vec![],
         malla: "Malla2020.xlsx".to_string(),
-        anio: None,
-        sheet: None,
         student_ranking: Some(0.75),
-        ranking: None,
-        filtros: None,
-        optimizations: vec![],
+        ..Default::default()
     };
 
     println!("\n📋 Parámetros:");
@@ -0,0 +1,52 @@
+#![cfg(feature = "server")]
+
+use actix_web::{test as atest, web, App, HttpResponse};
+use quickshift::middleware::request_trace;
+use quickshift::middleware::trace::{agregar_trace_id, nuevo_trace_id, sanear_trace_id};
+use serde_json::json;
+
+#[test]
+fn test_trace_id_generado_y_saneado() {
+    let a = nuevo_trace_id();
+    let b = nuevo_trace_id();
+    assert_ne!(a, b);
+    assert_eq!(sanear_trace_id(&a), Some(a.clone()));
+
+    assert_eq!(sanear_trace_id(" abc-123 "), Some("abc-123".to_string()));
+    assert_eq!(sanear_trace_id(""), None);
+    assert_eq!(sanear_trace_id("a b"), None);
+    assert_eq!(sanear_trace_id(&"x".repeat(65)), None);
+}
+
+#[test]
+fn test_agregar_trace_id_a_body_json() {
+    let body = agregar_trace_id(br#"{"error": "boom"}"#, "t-1").unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(v, json!({"error": "boom", "trace_id": "t-1"}));
+    assert!(agregar_trace_id(b"[1, 2]", "t-1").is_none());
+    assert!(agregar_trace_id(b"no json", "t-1").is_none());
+}
+
+#[actix_web::test]
+async fn test_middleware_propaga_trace_id() {
+    let app = atest::init_service(
+        App::new()
+            .wrap(actix_web::middleware::from_fn(request_trace))
+            .route("/ok", web::get().to(|| async { HttpResponse::Ok().json(json!({"ok": true})) }))
+            .route("/falla", web::get().to(|| async { HttpResponse::InternalServerError().json(json!({"error": "boom"})) })),
+    ).await;
+
+    // Error: el trace ID del cliente vuelve en la cabecera y en el body
+    let req = atest::TestRequest::get().uri("/falla").insert_header(("X-Request-Id", "req-42")).to_request();
+    let resp = atest::call_service(&app, req).await;
+    assert_eq!(resp.status(), 500);
+    assert_eq!(resp.headers().get("x-trace-id").unwrap(), "req-42");
+    let body: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!(body, json!({"error": "boom", "trace_id": "req-42"}));
+
+    // Éxito: se genera un ID y el body no se toca
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/ok").to_request()).await;
+    assert!(resp.headers().get("x-trace-id").is_some());
+    let body: serde_json::Value = atest::read_body_json(resp).await;
+    assert_eq!(body, json!({"ok": true}));
+}
@@ -0,0 +1,47 @@
+use quickshift::algorithm::traslado::*;
+use quickshift::api_json::parse_json_input;
+use quickshift::models::Seccion;
+
+mod common;
+use common::seccion_con;
+
+fn seccion(codigo: &str, horario: &str, campus: Option<&str>) -> Seccion {
+    seccion_con(codigo, "1", &[horario], serde_json::json!({"campus": campus}))
+}
+
+#[test]
+fn test_requiere_traslado_entre_campus() {
+    let a = seccion("CIT1000", "LU 08:30 - 09:50", Some("Santiago Centro"));
+    let b = seccion("CIT2000", "LU 10:00 - 11:20", Some("Providencia"));
+    let c = seccion("CIT3000", "LU 11:00 - 12:20", Some("santiago centro "));
+    let d = seccion("CIT4000", "MA 10:00 - 11:20", Some("Providencia"));
+    let sin_campus = seccion("CIT5000", "LU 10:00 - 11:20", None);
+
+    // 10 minutos entre campus distintos no alcanzan para 30
+    assert!(requiere_traslado(&a, &b, 30));
+    assert!(!requiere_traslado(&a, &b, 10));
+    // mismo campus (sin distinguir mayúsculas ni espacios), otro día o campus desconocido
+    assert!(!requiere_traslado(&a, &c, 30));
+    assert!(!requiere_traslado(&a, &d, 30));
+    assert!(!requiere_traslado(&a, &sin_campus, 30));
+
+    assert_eq!(traslados_insuficientes([&a, &b, &c, &d], 30), 2);
+}
+
+#[test]
+fn test_filtro_tiempo_traslado_desde_json() {
+    let p = parse_json_input(r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[],"malla":"MiMalla.xlsx",
+        "filtros":{"tiempo_traslado":{"habilitado":true,"modo":"soft"}}}"#).unwrap();
+    let t = traslado_pedido(&p).unwrap();
+    assert_eq!(minutos(t), MINUTOS_TRASLADO_POR_DEFECTO);
+    assert!(traslado_blando(&p).is_some());
+    assert!(traslado_estricto(&p).is_none());
+
+    let hard = parse_json_input(r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[],"malla":"MiMalla.xlsx",
+        "filtros":{"tiempo_traslado":{"habilitado":true,"minutos":45}}}"#).unwrap();
+    assert_eq!(traslado_estricto(&hard).map(minutos), Some(45));
+
+    let apagado = parse_json_input(r#"{"email":"a@x.cl","ramos_pasados":[],"ramos_prioritarios":[],"malla":"MiMalla.xlsx",
+        "filtros":{"tiempo_traslado":{"habilitado":false,"minutos":45}}}"#).unwrap();
+    assert!(traslado_pedido(&apagado).is_none());
+}
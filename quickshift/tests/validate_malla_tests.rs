@@ -0,0 +1,77 @@
+use quickshift::excel::validate::validar_malla;
+use quickshift::models::Seccion;
+use std::path::Path;
+
+#[test]
+fn test_validar_mallas_reales() {
+    let oferta = quickshift::excel::leer_oferta_academica_excel("src/datafiles/OA20251.xlsx").unwrap();
+
+    let malla = validar_malla(Path::new("src/datafiles/Malla2020.xlsx"), None, Some(("OA20251.xlsx", &oferta))).unwrap();
+    assert!(malla.valido, "{:?}", malla.errores);
+    assert_eq!(malla.columnas.id.as_deref(), Some("ID"));
+    assert_eq!(malla.columnas.requisitos.as_deref().map(str::trim), Some("Requisitos"));
+    assert!(malla.filas_leidas > 40);
+    // "6,7" guardado como 6.7 se separa en dos requisitos existentes
+    assert!(malla.requisitos_faltantes.is_empty(), "{:?}", malla.requisitos_faltantes);
+    assert_eq!(malla.oferta.as_deref(), Some("OA20251.xlsx"));
+
+    // Encabezado en la segunda fila y columnas extra
+    let mi_malla = validar_malla(Path::new("src/datafiles/MiMalla.xlsx"), Some("MiMalla"), None).unwrap();
+    assert_eq!(mi_malla.columnas.fila_encabezado, 2);
+    assert_eq!(mi_malla.columnas.codigo.as_deref(), Some("Código"));
+    assert_eq!(mi_malla.columnas.no_reconocidas, vec!["Abre la/s asignatura/s:".to_string()]);
+    assert!(mi_malla.sin_oferta.is_empty() && mi_malla.oferta.is_none());
+
+    assert!(validar_malla(Path::new("src/datafiles/MiMalla.xlsx"), Some("NoExiste"), None).is_err());
+    assert!(validar_malla(Path::new("src/datafiles/OA20251.xlsx"), None, None).is_err());
+}
+
+#[cfg(feature = "server")]
+#[test]
+fn test_validar_malla_con_problemas() {
+    let filas: [[&str; 5]; 7] = [
+        ["ID", "Código", "Nombre", "Requisitos", "Semestre"],
+        ["1", "CBM1000", "Álgebra", "—", "1"],
+        ["2", "CBM1001", "Cálculo I", "1", "1"],
+        ["3", "CBM1001", "Cálculo II", "2, 9", "2"],
+        ["x", "CIT1000", "Programación", "", "1"],
+        ["5", "CIT1010", "", "", "2"],
+        ["6", "CIT2000", "Estructuras de Datos", "CIT1000; CIT9999", "3"],
+    ];
+    let mut libro = umya_spreadsheet::new_file();
+    let hoja = libro.get_sheet_mut(0);
+    for (i, fila) in filas.iter().enumerate() {
+        for (j, valor) in fila.iter().enumerate() {
+            let coordenada = format!("{}{}", (b'A' + j as u8) as char, i + 1);
+            hoja.get_cell_mut(coordenada).set_value(*valor);
+        }
+    }
+    let ruta = std::env::temp_dir().join(format!("quickshift_validate_{}.xlsx", std::process::id()));
+    umya_spreadsheet::writer::xlsx::write(&libro, &ruta).unwrap();
+
+    let oferta: Vec<Seccion> = serde_json::from_value(serde_json::json!([
+        {"codigo": "CBM1000", "nombre": "ALGEBRA", "seccion": "1", "horario": ["LU 08:30-10:00"], "codigo_box": "CBM1000-1"},
+        {"codigo": "X", "nombre": "CÁLCULO I", "seccion": "1", "horario": ["MA 08:30-10:00"], "codigo_box": "X-1"}
+    ])).unwrap();
+    let r = validar_malla(&ruta, None, Some(("OA.xlsx", &oferta))).unwrap();
+    let _ = std::fs::remove_file(&ruta);
+
+    assert_eq!(r.filas_totales, 6);
+    assert_eq!(r.filas_leidas, 4);
+    let omitidas: Vec<(usize, &str)> = r.filas_omitidas.iter().map(|f| (f.fila, f.motivo.as_str())).collect();
+    assert_eq!(omitidas, vec![(5, "ID no numérico 'x'"), (6, "sin nombre")]);
+
+    let faltantes: Vec<(&str, &str)> = r.requisitos_faltantes.iter().map(|f| (f.ramo.as_str(), f.requisito.as_str())).collect();
+    // CIT1000 solo aparece en una fila omitida (ID inválido): cuenta como faltante
+    assert_eq!(faltantes, vec![("Cálculo II", "9"), ("Estructuras de Datos", "CIT1000"), ("Estructuras de Datos", "CIT9999")]);
+
+    assert_eq!(r.duplicados.len(), 1);
+    assert_eq!(r.duplicados[0].campo, "codigo");
+    assert_eq!(r.duplicados[0].valor, "CBM1001");
+    assert_eq!(r.duplicados[0].filas, vec![3, 4]);
+
+    // Álgebra coincide por código y Cálculo I por nombre normalizado
+    let sin_oferta: Vec<&str> = r.sin_oferta.iter().map(|s| s.nombre.as_str()).collect();
+    assert_eq!(sin_oferta, vec!["Cálculo II", "Estructuras de Datos"]);
+    assert!(r.valido);
+}
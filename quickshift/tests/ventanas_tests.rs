@@ -0,0 +1,64 @@
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::algorithm::ventanas::{minutos_sobre_ideal, ventana_ideal_pedida, ventanas};
+use quickshift::api_json::InputParams;
+use quickshift::models::{RamoDisponible, Seccion};
+use std::collections::HashMap;
+
+mod common;
+use common::seccion;
+
+fn params(filtros: serde_json::Value) -> InputParams {
+    common::params("MallaVentanas", serde_json::json!({"filtros": filtros}))
+}
+
+#[test]
+fn test_ventanas_por_dia() {
+    let a = seccion("CBM1000", "1", &["LU JU 08:30 - 09:50"]);
+    let b = seccion("CBM1001", "1", &["LU 10:00 - 11:20", "JU 14:30 - 15:50"]);
+    // Solapada con `a`: no genera ventana propia
+    let c = seccion("CIT1000", "1", &["LU 09:00 - 09:30"]);
+
+    let mut v = ventanas([&a, &b, &c]);
+    v.sort();
+    assert_eq!(v, vec![10, 280]);
+    assert_eq!(minutos_sobre_ideal([&a, &b, &c], 0), 290);
+    assert_eq!(minutos_sobre_ideal([&a, &b, &c], 30), 250);
+    assert_eq!(minutos_sobre_ideal([&a, &b, &c], 300), 0);
+    assert_eq!(minutos_sobre_ideal([&a], 0), 0);
+}
+
+#[test]
+fn test_ventana_ideal_solo_con_minimizar_ventanas() {
+    let p = params(serde_json::json!({"dias_horarios_libres": {"habilitado": true, "minimizar_ventanas": true, "ventana_ideal_minutos": 20}}));
+    assert_eq!(ventana_ideal_pedida(&p), Some(20));
+    let sin_ideal = params(serde_json::json!({"dias_horarios_libres": {"habilitado": true, "minimizar_ventanas": true}}));
+    assert_eq!(ventana_ideal_pedida(&sin_ideal), Some(0));
+    let apagado = params(serde_json::json!({"dias_horarios_libres": {"habilitado": true, "minimizar_ventanas": false, "ventana_ideal_minutos": 20}}));
+    assert_eq!(ventana_ideal_pedida(&apagado), None);
+}
+
+#[test]
+fn test_minimizar_ventanas_prefiere_horario_compacto() {
+    let ramos: Vec<RamoDisponible> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "nombre": "CBM1000", "codigo": "CBM1000", "semestre": 1},
+        {"id": 2, "nombre": "CBM1001", "codigo": "CBM1001", "semestre": 1}
+    ])).unwrap();
+    let ramos: HashMap<String, RamoDisponible> = ramos.into_iter().map(|r| (r.nombre.clone(), r)).collect();
+    let secciones = vec![
+        seccion("CBM1000", "1", &["LU 08:30 - 09:50"]),
+        seccion("CBM1001", "1", &["LU 16:00 - 17:20"]),
+        seccion("CBM1001", "2", &["LU 10:20 - 11:40"]),
+    ];
+    let p = params(serde_json::json!({"dias_horarios_libres": {"habilitado": true, "minimizar_ventanas": true, "ventana_ideal_minutos": 30}}));
+
+    let sols = get_clique_max_pond_with_prefs(&secciones, &ramos, &p);
+    let cajas = |sol: &[(Seccion, i32)]| {
+        let mut c: Vec<String> = sol.iter().map(|(s, _)| s.codigo_box.clone()).collect();
+        c.sort();
+        c
+    };
+    assert_eq!(cajas(&sols[0].0), vec!["CBM1000-1", "CBM1001-2"]);
+    let tarde = sols.iter().find(|(sol, _)| cajas(sol) == vec!["CBM1000-1", "CBM1001-1"]).expect("la sección con ventana larga se conserva");
+    // 370 min de ventana, 340 sobre la ideal
+    assert!(sols[0].1 - tarde.1 >= 340 * 1_000);
+}
@@ -0,0 +1,96 @@
+// Planificación del verano: `periodo` en InputParams, tope de ramos, pesos y
+// elección de los archivos OA/CFG marcados con V.
+
+use quickshift::algorithm::clique::get_clique_max_pond_with_prefs;
+use quickshift::algorithm::sintetico::oferta_sintetica;
+use quickshift::api_json::{InputParams, Periodo};
+use quickshift::excel::config::{self, DatafilesConfig, OrigenDatafiles};
+use quickshift::excel::{es_archivo_verano, latest_file_for_keywords};
+use quickshift::reglas::{max_ramos, MAX_RAMOS_POR_DEFECTO, MAX_RAMOS_VERANO_POR_DEFECTO};
+
+mod common;
+
+fn params(periodo: Option<&str>) -> InputParams {
+    let extra = match periodo {
+        Some(p) => serde_json::json!({"periodo": p}),
+        None => serde_json::json!({}),
+    };
+    common::params("sintetica", extra)
+}
+
+#[test]
+fn test_periodo_por_defecto_regular() {
+    assert_eq!(params(None).periodo, Periodo::Regular);
+    assert_eq!(params(Some("verano")).periodo, Periodo::Verano);
+    assert_eq!(params(Some("summer")).periodo, Periodo::Verano);
+    assert!(serde_json::from_value::<InputParams>(serde_json::json!({
+        "email": "x@x.cl", "ramos_pasados": [], "ramos_prioritarios": [], "malla": "m", "periodo": "otoño"
+    })).is_err());
+}
+
+#[test]
+fn test_tope_de_ramos_y_pesos_de_verano() {
+    assert_eq!(max_ramos(&params(None)), MAX_RAMOS_POR_DEFECTO);
+    assert_eq!(max_ramos(&params(Some("verano"))), MAX_RAMOS_VERANO_POR_DEFECTO);
+
+    let regular = quickshift::scoring::efectiva(&params(None));
+    let verano = quickshift::scoring::efectiva(&params(Some("verano")));
+    assert_eq!(verano.bonus_critico, regular.bonus_critico * quickshift::scoring::FACTOR_CRITICO_VERANO);
+    assert_eq!(verano.peso_compactacion, 0);
+}
+
+#[test]
+fn test_soluciones_de_verano_respetan_el_tope() {
+    let oferta = oferta_sintetica(60, 3);
+    let soluciones = get_clique_max_pond_with_prefs(&oferta.secciones, &oferta.ramos, &params(Some("verano")));
+    assert!(!soluciones.is_empty());
+    assert!(soluciones.iter().all(|(s, _)| s.len() <= MAX_RAMOS_VERANO_POR_DEFECTO));
+
+    let regulares = get_clique_max_pond_with_prefs(&oferta.secciones, &oferta.ramos, &params(None));
+    assert!(regulares.iter().any(|(s, _)| s.len() > MAX_RAMOS_VERANO_POR_DEFECTO));
+}
+
+#[test]
+fn test_es_archivo_verano() {
+    for nombre in ["OA2025V.xlsx", "oa2025v.xlsx", "CFG2025V.xlsx", "OA2025_V.xlsx", "datafiles/OA20251V.xlsx"] {
+        assert!(es_archivo_verano(nombre), "{}", nombre);
+    }
+    for nombre in ["OA20251.xlsx", "MC2020.xlsx", "OAV.xlsx", "Oferta_TEST.xlsx", "PA2025-1.xlsx"] {
+        assert!(!es_archivo_verano(nombre), "{}", nombre);
+    }
+}
+
+#[test]
+fn test_resolver_elige_oferta_de_verano() {
+    let dir = std::env::temp_dir().join(format!("quickshift-verano-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for nombre in ["MC2020.xlsx", "OA20251.xlsx", "OA2025V.xlsx", "OA2024V.xlsx", "PA20251.xlsx", "CFG20251.xlsx"] {
+        std::fs::write(dir.join(nombre), b"").unwrap();
+    }
+    config::establecer(DatafilesConfig { dir: dir.clone(), origen: OrigenDatafiles::Configuracion });
+
+    let nombre = |p: std::path::PathBuf| p.file_name().unwrap().to_string_lossy().to_string();
+    // La planificación regular nunca toma la oferta de verano
+    assert_eq!(latest_file_for_keywords(&["oferta", "oa"]).map(nombre).as_deref(), Some("OA20251.xlsx"));
+
+    let mut p = params(Some("verano"));
+    p.malla = "MC2020.xlsx".into();
+    let (_, oferta, _) = quickshift::carreras::resolver_archivos(&p).unwrap();
+    assert_eq!(nombre(oferta), "OA2025V.xlsx");
+    // Sin CFG de verano no se ofrecen CFG
+    assert!(quickshift::carreras::archivo_cfg(&p).is_none());
+    std::fs::write(dir.join("CFG2025V.xlsx"), b"").unwrap();
+    assert_eq!(quickshift::carreras::archivo_cfg(&p).map(nombre).as_deref(), Some("CFG2025V.xlsx"));
+
+    p.periodo = Periodo::Regular;
+    let (_, oferta, _) = quickshift::carreras::resolver_archivos(&p).unwrap();
+    assert_eq!(nombre(oferta), "OA20251.xlsx");
+
+    // Sin oferta de verano el request falla en vez de usar la del semestre
+    std::fs::remove_file(dir.join("OA2025V.xlsx")).unwrap();
+    std::fs::remove_file(dir.join("OA2024V.xlsx")).unwrap();
+    p.periodo = Periodo::Verano;
+    assert!(quickshift::carreras::resolver_archivos(&p).is_err());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}